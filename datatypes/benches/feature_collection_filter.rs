@@ -0,0 +1,123 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollectionInfos, FeatureCollectionModifications,
+    GeoFeatureCollectionRowBuilder, IntoGeometryIterator, MultiPointCollection,
+    MultiPolygonCollection,
+};
+use geoengine_datatypes::primitives::{
+    Coordinate2D, FeatureData, FeatureDataType, FeatureDataValue, MultiPoint, MultiPointAccess,
+    MultiPolygon, TimeInterval,
+};
+
+const NUMBER_OF_FEATURES: usize = 100_000;
+
+fn points(n: usize) -> (MultiPointCollection, Vec<f64>) {
+    let numbers: Vec<f64> = (0..n).map(|i| i as f64).collect();
+
+    let collection = MultiPointCollection::from_data(
+        (0..n)
+            .map(|i| MultiPoint::new(vec![Coordinate2D::new(i as f64, i as f64)]).unwrap())
+            .collect(),
+        (0..n as i64)
+            .map(|i| TimeInterval::new_unchecked(i, i + 1))
+            .collect(),
+        [("number".to_string(), FeatureData::Float(numbers.clone()))]
+            .iter()
+            .cloned()
+            .collect(),
+    )
+    .unwrap();
+
+    (collection, numbers)
+}
+
+fn polygons(n: usize) -> MultiPolygonCollection {
+    MultiPolygonCollection::from_data(
+        (0..n)
+            .map(|i| {
+                let (x, y) = (i as f64, i as f64);
+                MultiPolygon::new(vec![vec![vec![
+                    Coordinate2D::new(x, y),
+                    Coordinate2D::new(x + 1., y),
+                    Coordinate2D::new(x + 1., y + 1.),
+                    Coordinate2D::new(x, y + 1.),
+                    Coordinate2D::new(x, y),
+                ]]])
+                .unwrap()
+            })
+            .collect(),
+        vec![TimeInterval::default(); n],
+        Default::default(),
+    )
+    .unwrap()
+}
+
+/// Filters a collection by rebuilding it feature by feature, which is what `filter` did before it used Arrow's kernels
+fn rebuild_filtered(
+    collection: &MultiPointCollection,
+    numbers: &[f64],
+    mask: &[bool],
+) -> MultiPointCollection {
+    let mut builder = MultiPointCollection::builder();
+    builder
+        .add_column("number".into(), FeatureDataType::Float)
+        .unwrap();
+    let mut builder = builder.finish_header();
+
+    for (((geometry, time), number), &selected) in collection
+        .geometries()
+        .zip(collection.time_intervals())
+        .zip(numbers)
+        .zip(mask)
+    {
+        if !selected {
+            continue;
+        }
+
+        builder
+            .push_geometry(MultiPoint::new(geometry.points().to_vec()).unwrap())
+            .unwrap();
+        builder.push_time_interval(*time).unwrap();
+        builder
+            .push_data("number", FeatureDataValue::Float(*number))
+            .unwrap();
+        builder.finish_row();
+    }
+
+    builder.build().unwrap()
+}
+
+fn feature_collection_filter_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("FeatureCollectionFilter");
+
+    let (points, numbers) = points(NUMBER_OF_FEATURES);
+    let polygons = polygons(NUMBER_OF_FEATURES);
+
+    for (name, selectivity) in [("1%", 100), ("50%", 2)] {
+        let mask: Vec<bool> = (0..NUMBER_OF_FEATURES)
+            .map(|i| i % selectivity == 0)
+            .collect();
+
+        group.bench_function(format!("MultiPoint Rebuild {}", name), |b| {
+            b.iter(|| black_box(rebuild_filtered(&points, &numbers, &mask)));
+        });
+
+        group.bench_function(format!("MultiPoint Filter {}", name), |b| {
+            b.iter(|| black_box(points.filter(mask.clone()).unwrap()));
+        });
+
+        group.bench_function(format!("MultiPolygon Filter {}", name), |b| {
+            b.iter(|| black_box(polygons.filter(mask.clone()).unwrap()));
+        });
+    }
+
+    group.bench_function("MultiPoint Filter 100%", |b| {
+        let mask = vec![true; points.len()];
+        b.iter(|| black_box(points.filter(mask.clone()).unwrap()));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, feature_collection_filter_benchmarks);
+criterion_main!(benches);
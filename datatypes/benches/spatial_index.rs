@@ -0,0 +1,80 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use geoengine_datatypes::collections::{
+    GeometryCollection, MultiPointCollection, SpatialIndex, ToSpatialIndex,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, MultiPoint, MultiPointAccess, TimeInterval,
+};
+
+fn points(n: usize) -> MultiPointCollection {
+    let coordinates: Vec<(f64, f64)> = (0..n)
+        .map(|i| ((i * 7919 % 1000) as f64, (i * 104_729 % 1000) as f64))
+        .collect();
+
+    MultiPointCollection::from_data(
+        MultiPoint::many(coordinates).unwrap(),
+        vec![TimeInterval::default(); n],
+        Default::default(),
+    )
+    .unwrap()
+}
+
+fn linear_nearest(collection: &MultiPointCollection, coordinate: Coordinate2D) -> Option<usize> {
+    collection
+        .geometries()
+        .map(|geometry| {
+            let point = geometry.points()[0];
+            let (dx, dy) = (point.x - coordinate.x, point.y - coordinate.y);
+            dx * dx + dy * dy
+        })
+        .enumerate()
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+        .map(|(feature, _)| feature)
+}
+
+fn spatial_index_benchmarks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("SpatialIndex");
+
+    let collection = points(10_000);
+    let index: SpatialIndex = collection.spatial_index();
+    let queries: Vec<Coordinate2D> = (0..100)
+        .map(|i| Coordinate2D::new((i * 13 % 1000) as f64, (i * 31 % 1000) as f64))
+        .collect();
+
+    group.bench_function("Build 10000", |b| {
+        b.iter(|| black_box(collection.spatial_index()))
+    });
+
+    group.bench_function("Nearest 100 of 10000", |b| {
+        b.iter(|| {
+            for coordinate in &queries {
+                black_box(index.nearest_features(*coordinate).next());
+            }
+        })
+    });
+
+    group.bench_function("Linear Nearest 100 of 10000", |b| {
+        b.iter(|| {
+            for coordinate in &queries {
+                black_box(linear_nearest(&collection, *coordinate));
+            }
+        })
+    });
+
+    group.bench_function("BBox 100 of 10000", |b| {
+        b.iter(|| {
+            for coordinate in &queries {
+                let bbox = BoundingBox2D::new_unchecked(
+                    *coordinate,
+                    Coordinate2D::new(coordinate.x + 10., coordinate.y + 10.),
+                );
+                black_box(index.features_intersecting_bbox(&bbox).count());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, spatial_index_benchmarks);
+criterion_main!(benches);
@@ -0,0 +1,431 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::collections::{error, FeatureCollectionError};
+use crate::primitives::{
+    FeatureData, FeatureDataRef, FeatureDataType, FeatureDataValue, TimeInstance,
+};
+use crate::util::Result;
+
+/// Determines how a column cast handles values that cannot be represented in the target type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CastErrorPolicy {
+    /// Fails on the first value that cannot be cast
+    Fail,
+    /// Sets values that cannot be cast to null
+    Null,
+    /// Clamps numbers and time instances that exceed the range of the target type to its minimum or maximum.
+    /// Fails on values that cannot be cast at all, e.g., texts that are no numbers.
+    Clamp,
+}
+
+/// Whether a column of type `from` can be cast to type `to`
+pub fn is_castable(from: FeatureDataType, to: FeatureDataType) -> bool {
+    match to {
+        FeatureDataType::Int | FeatureDataType::Float | FeatureDataType::Text => true,
+        FeatureDataType::DateTime => from != FeatureDataType::Bool,
+        FeatureDataType::Category | FeatureDataType::Bool => from == to,
+    }
+}
+
+/// Casts the values of `column` to the type `to`.
+///
+/// Nulls stay nulls, as do empty texts, which is how CSV files usually encode missing values.
+/// Texts are trimmed before they are parsed.
+///
+/// Floats are rounded to the nearest integer when cast to ints.
+/// Ints are interpreted as milliseconds since the Unix epoch when cast to date times and vice versa.
+/// Texts are parsed as ISO 8601 date times.
+pub(super) fn cast_column_data(
+    column: &str,
+    data: &FeatureDataRef,
+    to: FeatureDataType,
+    policy: CastErrorPolicy,
+) -> Result<FeatureData> {
+    let from = FeatureDataType::from(data);
+
+    ensure!(
+        is_castable(from, to),
+        error::UnsupportedColumnCast { column, from, to }
+    );
+
+    let values = (0..data.len())
+        .map(|i| {
+            let value = match Scalar::from_value(data.get_unchecked(i)) {
+                Some(value) => value,
+                None => return Ok(None),
+            };
+
+            match (value.cast(to), policy) {
+                (Ok(value), _) => Ok(value),
+                (Err(CastFailure::OutOfRange { clamped }), CastErrorPolicy::Clamp) => {
+                    Ok(Some(clamped))
+                }
+                (Err(_), CastErrorPolicy::Null) => Ok(None),
+                (Err(_), CastErrorPolicy::Fail | CastErrorPolicy::Clamp) => {
+                    Err(FeatureCollectionError::InvalidColumnCastValue {
+                        column: column.to_string(),
+                        value: value.to_string(),
+                        to,
+                    })
+                }
+            }
+        })
+        .collect::<Result<Vec<_>, FeatureCollectionError>>()?;
+
+    Ok(FeatureData::nullable_from_values(to, values))
+}
+
+/// A non-null value of a column
+#[derive(Debug, Clone, PartialEq)]
+enum Scalar {
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bool(bool),
+    DateTime(TimeInstance),
+}
+
+/// The reason why a [`Scalar`] cannot be cast
+#[derive(Debug, Clone, PartialEq)]
+enum CastFailure {
+    /// the value is out of the range of the target type
+    OutOfRange { clamped: FeatureDataValue },
+    /// the value has no representation in the target type
+    Invalid,
+}
+
+type CastResult = std::result::Result<Option<FeatureDataValue>, CastFailure>;
+
+impl Scalar {
+    /// Unwraps a value, returns `None` for nulls
+    fn from_value(value: FeatureDataValue) -> Option<Self> {
+        Some(match value {
+            FeatureDataValue::Category(v) | FeatureDataValue::NullableCategory(Some(v)) => {
+                Self::Text(v)
+            }
+            FeatureDataValue::Int(v) | FeatureDataValue::NullableInt(Some(v)) => Self::Int(v),
+            FeatureDataValue::Float(v) | FeatureDataValue::NullableFloat(Some(v)) => Self::Float(v),
+            FeatureDataValue::Text(v) | FeatureDataValue::NullableText(Some(v)) => Self::Text(v),
+            FeatureDataValue::Bool(v) | FeatureDataValue::NullableBool(Some(v)) => Self::Bool(v),
+            FeatureDataValue::DateTime(v) | FeatureDataValue::NullableDateTime(Some(v)) => {
+                Self::DateTime(v)
+            }
+            FeatureDataValue::NullableCategory(None)
+            | FeatureDataValue::NullableInt(None)
+            | FeatureDataValue::NullableFloat(None)
+            | FeatureDataValue::NullableText(None)
+            | FeatureDataValue::NullableBool(None)
+            | FeatureDataValue::NullableDateTime(None) => return None,
+        })
+    }
+
+    fn cast(&self, to: FeatureDataType) -> CastResult {
+        match to {
+            FeatureDataType::Int => self.to_int(),
+            FeatureDataType::Float => self.to_float(),
+            FeatureDataType::Text => Ok(Some(FeatureDataValue::Text(self.to_text()))),
+            FeatureDataType::DateTime => self.to_date_time(),
+            FeatureDataType::Category | FeatureDataType::Bool => Err(CastFailure::Invalid),
+        }
+    }
+
+    fn to_int(&self) -> CastResult {
+        match self {
+            Scalar::Int(v) => Ok(Some(FeatureDataValue::Int(*v))),
+            Scalar::Float(v) => float_to_int(*v).map(|v| Some(FeatureDataValue::Int(v))),
+            Scalar::Text(v) => match v.trim() {
+                "" => Ok(None),
+                v => match i64::from_str(v) {
+                    Ok(v) => Ok(Some(FeatureDataValue::Int(v))),
+                    Err(_) => Scalar::Float(parse_float(v)?).to_int(),
+                },
+            },
+            Scalar::Bool(v) => Ok(Some(FeatureDataValue::Int(i64::from(*v)))),
+            Scalar::DateTime(v) => Ok(Some(FeatureDataValue::Int(v.inner()))),
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn to_float(&self) -> CastResult {
+        let value = match self {
+            Scalar::Int(v) => *v as f64,
+            Scalar::Float(v) => *v,
+            Scalar::Text(v) => match v.trim() {
+                "" => return Ok(None),
+                v => parse_float(v)?,
+            },
+            Scalar::Bool(v) => f64::from(u8::from(*v)),
+            Scalar::DateTime(v) => v.inner() as f64,
+        };
+
+        Ok(Some(FeatureDataValue::Float(value)))
+    }
+
+    fn to_text(&self) -> String {
+        match self {
+            Scalar::Int(v) => v.to_string(),
+            Scalar::Float(v) => v.to_string(),
+            Scalar::Text(v) => v.clone(),
+            Scalar::Bool(v) => v.to_string(),
+            Scalar::DateTime(v) => v.as_rfc3339(),
+        }
+    }
+
+    fn to_date_time(&self) -> CastResult {
+        let millis = match self {
+            Scalar::Int(v) => *v,
+            Scalar::Float(v) => float_to_int(*v).map_err(|failure| match failure {
+                CastFailure::OutOfRange { .. } => out_of_time_range(*v),
+                CastFailure::Invalid => CastFailure::Invalid,
+            })?,
+            Scalar::Text(v) => {
+                return match v.trim() {
+                    "" => Ok(None),
+                    v => TimeInstance::from_str(v)
+                        .map(|v| Some(FeatureDataValue::DateTime(v)))
+                        .map_err(|_| CastFailure::Invalid),
+                }
+            }
+            Scalar::Bool(_) => return Err(CastFailure::Invalid),
+            Scalar::DateTime(v) => v.inner(),
+        };
+
+        TimeInstance::from_millis(millis)
+            .map(|v| Some(FeatureDataValue::DateTime(v)))
+            .map_err(|_| out_of_time_range(millis))
+    }
+}
+
+impl std::fmt::Display for Scalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scalar::Text(v) => write!(f, "{:?}", v),
+            _ => write!(f, "{}", self.to_text()),
+        }
+    }
+}
+
+fn parse_float(value: &str) -> std::result::Result<f64, CastFailure> {
+    f64::from_str(value).map_err(|_| CastFailure::Invalid)
+}
+
+/// Rounds a float to the nearest int
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn float_to_int(value: f64) -> std::result::Result<i64, CastFailure> {
+    if value.is_nan() {
+        return Err(CastFailure::Invalid);
+    }
+
+    let value = value.round();
+
+    // `i64::MAX` is not representable as `f64` and is rounded up to 2^63
+    if value >= i64::MAX as f64 {
+        Err(CastFailure::OutOfRange {
+            clamped: FeatureDataValue::Int(i64::MAX),
+        })
+    } else if value < i64::MIN as f64 {
+        Err(CastFailure::OutOfRange {
+            clamped: FeatureDataValue::Int(i64::MIN),
+        })
+    } else {
+        Ok(value as i64)
+    }
+}
+
+fn out_of_time_range<T: PartialOrd + Default>(value: T) -> CastFailure {
+    CastFailure::OutOfRange {
+        clamped: FeatureDataValue::DateTime(if value < T::default() {
+            TimeInstance::MIN
+        } else {
+            TimeInstance::MAX
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::{
+        DataCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+    };
+    use crate::error::Error;
+    use crate::primitives::{NoGeometry, TimeInterval};
+
+    fn collection(data: FeatureData) -> DataCollection {
+        DataCollection::from_data(
+            vec![NoGeometry; data.len()],
+            vec![TimeInterval::default(); data.len()],
+            [("column".to_string(), data)].into_iter().collect(),
+        )
+        .unwrap()
+    }
+
+    fn cast(
+        data: FeatureData,
+        to: FeatureDataType,
+        policy: CastErrorPolicy,
+    ) -> Result<Vec<Option<Scalar>>> {
+        let collection = collection(data).cast_column("column", to, policy)?;
+
+        assert_eq!(collection.column_type("column")?, to);
+
+        let data = collection.data("column")?;
+        Ok((0..data.len())
+            .map(|i| Scalar::from_value(data.get_unchecked(i)))
+            .collect())
+    }
+
+    fn texts(values: &[&str]) -> FeatureData {
+        FeatureData::Text(values.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn text_to_numbers() {
+        assert_eq!(
+            cast(
+                texts(&[" 42 ", "1.6", "", "-7"]),
+                FeatureDataType::Int,
+                CastErrorPolicy::Fail
+            )
+            .unwrap(),
+            vec![
+                Some(Scalar::Int(42)),
+                Some(Scalar::Int(2)),
+                None,
+                Some(Scalar::Int(-7)),
+            ]
+        );
+
+        assert_eq!(
+            cast(
+                texts(&["1.5", "1e3"]),
+                FeatureDataType::Float,
+                CastErrorPolicy::Fail
+            )
+            .unwrap(),
+            vec![Some(Scalar::Float(1.5)), Some(Scalar::Float(1000.))]
+        );
+    }
+
+    #[test]
+    fn error_policies() {
+        let data = || texts(&["1", "one", "1e100"]);
+
+        assert!(matches!(
+            cast(data(), FeatureDataType::Int, CastErrorPolicy::Fail),
+            Err(Error::FeatureCollection {
+                source: FeatureCollectionError::InvalidColumnCastValue { value, .. }
+            }) if value == "\"one\""
+        ));
+
+        assert_eq!(
+            cast(data(), FeatureDataType::Int, CastErrorPolicy::Null).unwrap(),
+            vec![Some(Scalar::Int(1)), None, None,]
+        );
+
+        // unparsable texts cannot be clamped
+        assert!(cast(data(), FeatureDataType::Int, CastErrorPolicy::Clamp).is_err());
+
+        assert_eq!(
+            cast(
+                FeatureData::Float(vec![-1e100, 2.4, 1e100]),
+                FeatureDataType::Int,
+                CastErrorPolicy::Clamp
+            )
+            .unwrap(),
+            vec![
+                Some(Scalar::Int(i64::MIN)),
+                Some(Scalar::Int(2)),
+                Some(Scalar::Int(i64::MAX)),
+            ]
+        );
+    }
+
+    #[test]
+    fn date_times() {
+        assert_eq!(
+            cast(
+                texts(&["2014-04-01T12:00:00Z", "2014-04-01"]),
+                FeatureDataType::DateTime,
+                CastErrorPolicy::Fail
+            )
+            .unwrap(),
+            vec![
+                Some(Scalar::DateTime(
+                    TimeInstance::from_millis(1_396_353_600_000).unwrap()
+                )),
+                Some(Scalar::DateTime(
+                    TimeInstance::from_millis(1_396_310_400_000).unwrap()
+                )),
+            ]
+        );
+
+        assert_eq!(
+            cast(
+                FeatureData::NullableInt(vec![Some(1_396_353_600_000), None, Some(i64::MAX)]),
+                FeatureDataType::DateTime,
+                CastErrorPolicy::Clamp
+            )
+            .unwrap(),
+            vec![
+                Some(Scalar::DateTime(
+                    TimeInstance::from_millis(1_396_353_600_000).unwrap()
+                )),
+                None,
+                Some(Scalar::DateTime(TimeInstance::MAX)),
+            ]
+        );
+
+        assert_eq!(
+            cast(
+                FeatureData::DateTime(vec![TimeInstance::from_millis(1_396_353_600_000).unwrap()]),
+                FeatureDataType::Text,
+                CastErrorPolicy::Fail
+            )
+            .unwrap(),
+            vec![Some(Scalar::Text("2014-04-01T12:00:00+00:00".to_string()))]
+        );
+    }
+
+    #[test]
+    fn numbers_to_text() {
+        assert_eq!(
+            cast(
+                FeatureData::NullableFloat(vec![Some(1.5), None]),
+                FeatureDataType::Text,
+                CastErrorPolicy::Fail
+            )
+            .unwrap(),
+            vec![Some(Scalar::Text("1.5".to_string())), None,]
+        );
+    }
+
+    #[test]
+    fn unsupported_casts() {
+        assert!(matches!(
+            cast(
+                FeatureData::Bool(vec![true]),
+                FeatureDataType::DateTime,
+                CastErrorPolicy::Null
+            ),
+            Err(Error::FeatureCollection {
+                source: FeatureCollectionError::UnsupportedColumnCast { .. }
+            })
+        ));
+
+        assert!(matches!(
+            cast(
+                FeatureData::Int(vec![1]),
+                FeatureDataType::Category,
+                CastErrorPolicy::Null
+            ),
+            Err(Error::FeatureCollection {
+                source: FeatureCollectionError::UnsupportedColumnCast { .. }
+            })
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error;
+use chrono::FixedOffset;
 use gdal::vector::OGRwkbGeometryType;
 use std::collections::hash_map::Keys;
 use std::collections::HashMap;
@@ -8,9 +9,9 @@ use std::ops::RangeBounds;
 use serde::{Deserialize, Serialize};
 
 use crate::collections::{
-    DataCollection, FeatureCollectionError, FeatureCollectionInfos, FeatureCollectionModifications,
-    FilterArray, FilteredColumnNameIter, GeometryCollection, MultiLineStringCollection,
-    MultiPointCollection, MultiPolygonCollection, ToGeoJson,
+    CastErrorPolicy, DataCollection, FeatureCollectionError, FeatureCollectionInfos,
+    FeatureCollectionModifications, FilterArray, FilteredColumnNameIter, GeometryCollection,
+    MultiLineStringCollection, MultiPointCollection, MultiPolygonCollection, ToGeoJson,
 };
 use crate::error::Error;
 use crate::primitives::{
@@ -357,11 +358,11 @@ impl<'c> FeatureCollectionInfos for TypedFeatureCollectionRef<'c> {
 }
 
 impl ToGeoJson<'_> for TypedFeatureCollection {
-    impl_function_by_forwarding_ref!(fn to_geo_json(&self) -> String);
+    impl_function_by_forwarding_ref!(fn to_geo_json_features(&self, time_zone: &FixedOffset) -> Vec<geojson::Feature>);
 }
 
 impl<'c> ToGeoJson<'_> for TypedFeatureCollectionRef<'c> {
-    impl_function_by_forwarding_ref2!(fn to_geo_json(&self) -> String);
+    impl_function_by_forwarding_ref2!(fn to_geo_json_features(&self, time_zone: &FixedOffset) -> Vec<geojson::Feature>);
 }
 
 /// Implements a function by forwarding its output
@@ -429,6 +430,8 @@ impl FeatureCollectionModifications for TypedFeatureCollection {
     impl_mod_function_by_forwarding_ref!(fn column_range_filter<R>(&self, column: &str, ranges: &[R], keep_nulls: bool) -> Result<Self::Output>
                                          where R: RangeBounds<FeatureDataValue>);
 
+    impl_mod_function_by_forwarding_ref!(fn column_set_filter(&self, column: &str, values: &[FeatureDataValue], keep_nulls: bool) -> Result<Self::Output>);
+
     fn append(&self, other: &Self) -> Result<Self::Output> {
         Ok(match (self, other) {
             (TypedFeatureCollection::Data(c1), TypedFeatureCollection::Data(c2)) => {
@@ -452,6 +455,8 @@ impl FeatureCollectionModifications for TypedFeatureCollection {
     impl_mod_function_by_forwarding_ref!(fn sort_by_time_asc(&self) -> Result<Self::Output>);
 
     impl_mod_function_by_forwarding_ref!(fn replace_time(&self, time_intervals: &[TimeInterval]) -> Result<Self::Output>);
+
+    impl_mod_function_by_forwarding_ref!(fn cast_column(&self, column_name: &str, data_type: FeatureDataType, policy: CastErrorPolicy) -> Result<Self::Output>);
 }
 
 impl<'c> FeatureCollectionModifications for TypedFeatureCollectionRef<'c> {
@@ -469,6 +474,8 @@ impl<'c> FeatureCollectionModifications for TypedFeatureCollectionRef<'c> {
     impl_mod_function_by_forwarding_ref2!(fn column_range_filter<R>(&self, column: &str, ranges: &[R], keep_nulls: bool) -> Result<Self::Output>
                                           where R: RangeBounds<FeatureDataValue>);
 
+    impl_mod_function_by_forwarding_ref2!(fn column_set_filter(&self, column: &str, values: &[FeatureDataValue], keep_nulls: bool) -> Result<Self::Output>);
+
     fn append(&self, other: &Self) -> Result<Self::Output> {
         Ok(match (self, other) {
             (TypedFeatureCollectionRef::Data(c1), TypedFeatureCollectionRef::Data(c2)) => {
@@ -493,6 +500,8 @@ impl<'c> FeatureCollectionModifications for TypedFeatureCollectionRef<'c> {
     impl_mod_function_by_forwarding_ref2!(fn sort_by_time_asc(&self) -> Result<Self::Output>);
 
     impl_mod_function_by_forwarding_ref2!(fn replace_time(&self, time_intervals: &[TimeInterval]) -> Result<Self::Output>);
+
+    impl_mod_function_by_forwarding_ref2!(fn cast_column(&self, column_name: &str, data_type: FeatureDataType, policy: CastErrorPolicy) -> Result<Self::Output>);
 }
 
 #[cfg(test)]
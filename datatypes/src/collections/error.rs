@@ -2,7 +2,7 @@ use arrow::error::ArrowError;
 use snafu::prelude::*;
 
 use crate::error::Error;
-use crate::primitives::PrimitivesError;
+use crate::primitives::{FeatureDataType, PrimitivesError};
 
 #[derive(Debug, Snafu)]
 #[snafu(visibility(pub(crate)))]
@@ -51,6 +51,79 @@ pub enum FeatureCollectionError {
 
     MissingTime,
     MissingGeo,
+
+    #[snafu(display("Invalid GeoJSON: {}", source))]
+    GeoJsonParsing {
+        source: serde_json::Error,
+    },
+
+    #[snafu(display("Expected a GeoJSON FeatureCollection, but found {}", found))]
+    NotAGeoJsonFeatureCollection {
+        found: String,
+    },
+
+    #[snafu(display("Invalid geometry of GeoJSON feature {}: {}", feature, source))]
+    InvalidGeoJsonGeometry {
+        feature: usize,
+        source: Box<Error>,
+    },
+
+    #[snafu(display("GeoJSON feature {} has no geometry", feature))]
+    MissingGeoJsonGeometry {
+        feature: usize,
+    },
+
+    #[snafu(display("Invalid time of GeoJSON feature {}: {}", feature, reason))]
+    InvalidGeoJsonTime {
+        feature: usize,
+        reason: String,
+    },
+
+    #[snafu(display(
+        "Property `{}` of GeoJSON feature {} is not part of the schema",
+        property,
+        feature
+    ))]
+    UnknownGeoJsonProperty {
+        feature: usize,
+        property: String,
+    },
+
+    #[snafu(display(
+        "Property `{}` of GeoJSON feature {} must be of type {:?}, but is {}",
+        property,
+        feature,
+        expected,
+        found
+    ))]
+    InvalidGeoJsonPropertyType {
+        feature: usize,
+        property: String,
+        expected: FeatureDataType,
+        found: String,
+    },
+
+    #[snafu(display(
+        "Cannot infer the type of GeoJSON property `{}` since its values are of different or nested types",
+        property
+    ))]
+    MixedGeoJsonPropertyTypes {
+        property: String,
+    },
+
+    #[snafu(display("Cannot cast column `{}` from {:?} to {:?}", column, from, to))]
+    UnsupportedColumnCast {
+        column: String,
+        from: FeatureDataType,
+        to: FeatureDataType,
+    },
+
+    #[snafu(display("Cannot cast the value {} of column `{}` to {:?}", value, column, to))]
+    InvalidColumnCastValue {
+        column: String,
+        value: String,
+        to: FeatureDataType,
+    },
 }
 
 impl From<FeatureCollectionError> for Error {
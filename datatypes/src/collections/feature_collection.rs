@@ -6,10 +6,11 @@ use arrow::{
 use arrow::{
     array::{
         as_boolean_array, as_primitive_array, as_string_array, Array, ArrayData, ArrayRef,
-        BooleanArray, ListArray, StructArray,
+        BooleanArray, DictionaryArray, ListArray, StructArray,
     },
     buffer::Buffer,
 };
+use chrono::FixedOffset;
 use serde::{Deserialize, Serialize};
 use serde_json::Map;
 use snafu::ensure;
@@ -23,12 +24,13 @@ use std::rc::Rc;
 use std::sync::Arc;
 use std::{mem, slice};
 
+use crate::collections::column_cast::{cast_column_data, CastErrorPolicy};
 use crate::primitives::{BoolDataRef, Coordinate2D, DateTimeDataRef, TimeInstance};
 use crate::primitives::{
-    CategoryDataRef, FeatureData, FeatureDataRef, FeatureDataType, FeatureDataValue, FloatDataRef,
-    Geometry, IntDataRef, TextDataRef, TimeInterval,
+    CategoryDataRef, CategoryKeyType, FeatureData, FeatureDataRef, FeatureDataType,
+    FeatureDataValue, FloatDataRef, Geometry, IntDataRef, TextDataRef, TimeInterval,
 };
-use crate::util::arrow::{downcast_array, ArrowTyped};
+use crate::util::arrow::{downcast_array, selected_indices, ArrowTyped};
 use crate::util::helpers::SomeIter;
 use crate::util::Result;
 use crate::{
@@ -89,7 +91,10 @@ impl<CollectionType> AsRef<FeatureCollection<CollectionType>>
 pub trait FeatureCollectionModifications {
     type Output;
 
-    /// Filters the feature collection by copying the data into a new feature collection
+    /// Filters the feature collection by copying the selected features into a new feature collection.
+    ///
+    /// The columns are filtered with Arrow's `filter` and `take` kernels instead of rebuilding the
+    /// collection feature by feature. If all features are selected, the data is shared instead of copied.
     ///
     /// # Errors
     ///
@@ -146,6 +151,17 @@ pub trait FeatureCollectionModifications {
     where
         R: RangeBounds<FeatureDataValue>;
 
+    /// Filter a column by a set of values, i.e., keep all features whose value equals one of `values`.
+    /// If `keep_nulls` is false, then all nulls will be discarded.
+    ///
+    /// For category columns, the values are only compared to the distinct categories.
+    fn column_set_filter(
+        &self,
+        column: &str,
+        values: &[FeatureDataValue],
+        keep_nulls: bool,
+    ) -> Result<Self::Output>;
+
     /// Appends a collection to another one
     ///
     /// # Errors
@@ -170,6 +186,22 @@ pub trait FeatureCollectionModifications {
 
     /// Replaces the current time intervals and returns an updated collection.
     fn replace_time(&self, time_intervals: &[TimeInterval]) -> Result<Self::Output>;
+
+    /// Converts the values of a column to `data_type`, e.g., texts of a CSV import to numbers.
+    ///
+    /// The `policy` decides about values that cannot be represented in the new type.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the column does not exist, the types are not convertible or a value
+    /// cannot be cast and the `policy` does not allow it
+    ///
+    fn cast_column(
+        &self,
+        column_name: &str,
+        data_type: FeatureDataType,
+        policy: CastErrorPolicy,
+    ) -> Result<Self::Output>;
 }
 
 impl<CollectionType> FeatureCollectionModifications for FeatureCollection<CollectionType>
@@ -192,7 +224,18 @@ where
 
         let filter_array: arrow::array::BooleanArray = mask.into();
 
-        // TODO: use filter directly on struct array when it is implemented
+        let predicate = arrow::compute::FilterBuilder::new(&filter_array)
+            .optimize()
+            .build();
+
+        // the geometries and time intervals are nested lists that are not supported by the
+        // `filter` kernel, so they are gathered with the `take` kernel using the selected indices
+        let indices = selected_indices(&predicate, self.table.len())?;
+
+        if indices.len() == self.table.len() {
+            // all features are selected, so the arrays can be shared
+            return Ok(self.clone());
+        }
 
         let table_data = self.table.data();
         let columns = if let arrow::datatypes::DataType::Struct(columns) = table_data.data_type() {
@@ -208,14 +251,10 @@ where
             filtered_data.push((
                 column.clone(),
                 match column.name().as_str() {
-                    Self::GEOMETRY_COLUMN_NAME => Arc::new(CollectionType::filter(
-                        downcast_array(array),
-                        &filter_array,
-                    )?),
-                    Self::TIME_COLUMN_NAME => {
-                        Arc::new(TimeInterval::filter(downcast_array(array), &filter_array)?)
+                    Self::GEOMETRY_COLUMN_NAME | Self::TIME_COLUMN_NAME => {
+                        arrow::compute::take(array.as_ref(), &indices, None)?
                     }
-                    _ => arrow::compute::filter(array.as_ref(), &filter_array)?,
+                    _ => predicate.filter(array.as_ref())?,
                 },
             ));
         }
@@ -489,7 +528,23 @@ where
                 )?;
             }
             FeatureDataType::Category => {
-                return Err(error::FeatureCollectionError::WrongDataType.into());
+                let dictionary: &DictionaryArray<CategoryKeyType> = downcast_array(column);
+
+                // compare the distinct categories instead of every feature's value
+                let mut category_filter = None;
+                apply_filters(
+                    as_string_array(dictionary.values()),
+                    &mut category_filter,
+                    ranges,
+                    arrow::compute::gt_eq_utf8_scalar,
+                    arrow::compute::gt_utf8_scalar,
+                    arrow::compute::lt_eq_utf8_scalar,
+                    arrow::compute::lt_utf8_scalar,
+                )?;
+
+                filter_array = category_filter
+                    .map(|category_filter| filter_by_categories(dictionary, &category_filter))
+                    .transpose()?;
             }
         }
 
@@ -504,6 +559,57 @@ where
         self.filter(filter_array)
     }
 
+    fn column_set_filter(
+        &self,
+        column: &str,
+        values: &[FeatureDataValue],
+        keep_nulls: bool,
+    ) -> Result<Self::Output> {
+        let column_type = self.types.get(column);
+        ensure!(
+            column_type.is_some(),
+            error::ColumnDoesNotExist {
+                name: column.to_string()
+            }
+        );
+
+        if column_type != Some(&FeatureDataType::Category) {
+            let ranges: Vec<_> = values
+                .iter()
+                .map(|value| value.clone()..=value.clone())
+                .collect();
+
+            return self.column_range_filter(column, &ranges, keep_nulls);
+        }
+
+        ensure!(!values.is_empty(), error::EmptyPredicate);
+
+        let values = values
+            .iter()
+            .map(<&str>::try_from)
+            .collect::<Result<HashSet<&str>, _>>()?;
+
+        let column = self
+            .table
+            .column_by_name(column)
+            .expect("checked by ensure");
+        let dictionary: &DictionaryArray<CategoryKeyType> = downcast_array(column);
+
+        let category_filter: BooleanArray = as_string_array(dictionary.values())
+            .iter()
+            .map(|category| Some(category.map_or(false, |category| values.contains(category))))
+            .collect();
+
+        let mut filter_array = filter_by_categories(dictionary, &category_filter)?;
+
+        if keep_nulls && column.null_count() > 0 {
+            let null_flags = arrow::compute::is_null(column.as_ref())?;
+            filter_array = arrow::compute::or_kleene(&filter_array, &null_flags)?;
+        }
+
+        self.filter(filter_array)
+    }
+
     fn append(&self, other: &Self) -> Result<Self::Output> {
         ensure!(
             self.types == other.types,
@@ -739,6 +845,24 @@ where
             self.types.clone(),
         ))
     }
+
+    fn cast_column(
+        &self,
+        column_name: &str,
+        data_type: FeatureDataType,
+        policy: CastErrorPolicy,
+    ) -> Result<Self::Output> {
+        let data = self.data(column_name)?;
+
+        if FeatureDataType::from(&data) == data_type {
+            return Ok(self.clone());
+        }
+
+        let casted_data = cast_column_data(column_name, &data, data_type, policy)?;
+
+        self.remove_column(column_name)?
+            .add_column(column_name, casted_data)
+    }
 }
 
 /// A trait for common feature collection information
@@ -894,7 +1018,24 @@ where
 /// Transform an object to the `GeoJson` format
 pub trait ToGeoJson<'i> {
     /// Serialize the feature collection to a geo json string
-    fn to_geo_json(&'i self) -> String;
+    fn to_geo_json(&'i self) -> String {
+        self.to_geo_json_with_time_zone(&FixedOffset::east(0))
+    }
+
+    /// Serialize the feature collection to a geo json string with times in a display time zone
+    fn to_geo_json_with_time_zone(&'i self, time_zone: &FixedOffset) -> String {
+        let feature_collection = geojson::FeatureCollection {
+            bbox: None,
+            features: self.to_geo_json_features(time_zone),
+            foreign_members: None,
+        };
+
+        feature_collection.to_string()
+    }
+
+    /// Transform the features of the collection to geo json features with times in a display
+    /// time zone, e.g., for serializing multiple collections as one feature collection
+    fn to_geo_json_features(&'i self, time_zone: &FixedOffset) -> Vec<geojson::Feature>;
 }
 
 impl<'i, CollectionType> ToGeoJson<'i> for FeatureCollection<CollectionType>
@@ -902,7 +1043,7 @@ where
     CollectionType: Geometry + ArrowTyped,
     Self: IntoGeometryOptionsIterator<'i>,
 {
-    fn to_geo_json(&'i self) -> String {
+    fn to_geo_json_features(&'i self, time_zone: &FixedOffset) -> Vec<geojson::Feature> {
         let mut property_maps = (0..self.len())
             .map(|_| serde_json::Map::with_capacity(self.types.len()))
             .collect::<Vec<_>>();
@@ -918,8 +1059,7 @@ where
             }
         }
 
-        let features = self
-            .geometry_options()
+        self.geometry_options()
             .zip(self.time_intervals())
             .zip(property_maps)
             .map(
@@ -930,19 +1070,11 @@ where
                     properties: Some(properties),
                     foreign_members: Some(Map::from_iter([(
                         "when".to_string(),
-                        time_interval.as_geo_json_event(),
+                        time_interval.as_geo_json_event_with_offset(time_zone),
                     )])),
                 },
             )
-            .collect();
-
-        let feature_collection = geojson::FeatureCollection {
-            bbox: None,
-            features,
-            foreign_members: None,
-        };
-
-        feature_collection.to_string()
+            .collect()
     }
 }
 
@@ -1025,8 +1157,13 @@ where
                     IntDataRef::new(array.values(), array.data_ref().null_bitmap()).into()
                 }
                 FeatureDataType::Category => {
-                    let array: &arrow::array::UInt8Array = downcast_array(column);
-                    CategoryDataRef::new(array.values(), array.data_ref().null_bitmap()).into()
+                    let array: &DictionaryArray<CategoryKeyType> = downcast_array(column);
+                    CategoryDataRef::new(
+                        array.keys().values(),
+                        downcast_array(array.values()),
+                        array.data_ref().null_bitmap(),
+                    )
+                    .into()
                 }
                 FeatureDataType::Bool => {
                     let array: &arrow::array::BooleanArray = downcast_array(column);
@@ -1255,6 +1392,28 @@ where
         )
     }
 
+    /// Returns the Arrow array of a data column, e.g., for applying Arrow's compute kernels
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the column does not exist or is a reserved column
+    ///
+    pub fn column_array(&self, column_name: &str) -> Result<&ArrayRef> {
+        ensure!(
+            !Self::is_reserved_name(column_name),
+            error::CannotAccessReservedColumn {
+                name: column_name.to_string(),
+            }
+        );
+
+        self.table.column_by_name(column_name).ok_or_else(|| {
+            FeatureCollectionError::ColumnDoesNotExist {
+                name: column_name.to_string(),
+            }
+            .into()
+        })
+    }
+
     /// Checks for name conflicts with reserved names
     pub(super) fn is_reserved_name(name: &str) -> bool {
         name == Self::GEOMETRY_COLUMN_NAME || name == Self::TIME_COLUMN_NAME
@@ -1431,8 +1590,20 @@ where
     Ok(())
 }
 
+/// Maps a filter on the distinct categories of a dictionary to a filter on its features.
+/// Null features are `null` in the resulting filter.
+fn filter_by_categories(
+    dictionary: &DictionaryArray<CategoryKeyType>,
+    category_filter: &BooleanArray,
+) -> Result<BooleanArray> {
+    let filter_array = arrow::compute::take(category_filter, dictionary.keys(), None)?;
+
+    Ok(BooleanArray::from(filter_array.data().clone()))
+}
+
 /// Custom serializer for Arrow's `StructArray`
 mod struct_serde {
+    use arrow::datatypes::Schema;
     use arrow::record_batch::RecordBatch;
     use serde::de::{SeqAccess, Visitor};
     use serde::ser::Error;
@@ -1449,6 +1620,27 @@ mod struct_serde {
     {
         let batch = RecordBatch::from(struct_array);
 
+        // every dictionary-encoded column needs its own dictionary id
+        let schema = Schema::new(
+            batch
+                .schema()
+                .fields()
+                .iter()
+                .enumerate()
+                .map(|(dict_id, field)| {
+                    Field::new_dict(
+                        field.name(),
+                        field.data_type().clone(),
+                        field.is_nullable(),
+                        dict_id as i64,
+                        false,
+                    )
+                })
+                .collect(),
+        );
+        let batch = RecordBatch::try_new(Arc::new(schema), batch.columns().to_vec())
+            .map_err(|error| S::Error::custom(error.to_string()))?;
+
         let mut serialized_struct = Vec::<u8>::new();
 
         let mut writer = arrow::ipc::writer::FileWriter::try_new(
@@ -1681,4 +1873,26 @@ mod tests {
             .rename_columns(&[("foo", "baz"), ("bar", "baz")])
             .is_err());
     }
+
+    #[test]
+    fn column_array() {
+        let collection = DataCollection::from_data(
+            vec![],
+            vec![TimeInterval::new(0, 1).unwrap(); 2],
+            [("foo".to_string(), FeatureData::Int(vec![1, 2]))]
+                .iter()
+                .cloned()
+                .collect(),
+        )
+        .unwrap();
+
+        let array = collection.column_array("foo").unwrap();
+        assert_eq!(array.data_type(), &DataType::Int64);
+        assert_eq!(array.len(), 2);
+
+        assert!(collection.column_array("bar").is_err());
+        assert!(collection
+            .column_array(DataCollection::TIME_COLUMN_NAME)
+            .is_err());
+    }
 }
@@ -1,11 +1,13 @@
 use crate::collections::batch_builder::RawFeatureCollectionBuilder;
 use crate::collections::{error, FeatureCollection, FeatureCollectionError};
-use crate::primitives::{FeatureDataType, FeatureDataValue, Geometry, TimeInstance, TimeInterval};
+use crate::primitives::{
+    CategoryKeyType, FeatureDataType, FeatureDataValue, Geometry, TimeInstance, TimeInterval,
+};
 use crate::util::arrow::{downcast_mut_array, ArrowTyped};
 use crate::util::Result;
 use arrow::array::{
     ArrayBuilder, BooleanBuilder, Date64Builder, Float64Builder, Int64Builder, StringBuilder,
-    StructBuilder, UInt8Builder,
+    StringDictionaryBuilder, StructBuilder,
 };
 use arrow::datatypes::Field;
 use snafu::ensure;
@@ -216,12 +218,18 @@ where
                 int_builder.append_option(value)?;
             }
             FeatureDataValue::Category(value) => {
-                let category_builder: &mut UInt8Builder = downcast_mut_array(data_builder.as_mut());
-                category_builder.append_value(value)?;
+                let category_builder: &mut StringDictionaryBuilder<CategoryKeyType> =
+                    downcast_mut_array(data_builder.as_mut());
+                category_builder.append(value)?;
             }
             FeatureDataValue::NullableCategory(value) => {
-                let category_builder: &mut UInt8Builder = downcast_mut_array(data_builder.as_mut());
-                category_builder.append_option(value)?;
+                let category_builder: &mut StringDictionaryBuilder<CategoryKeyType> =
+                    downcast_mut_array(data_builder.as_mut());
+                if let Some(v) = value {
+                    category_builder.append(v)?;
+                } else {
+                    category_builder.append_null()?;
+                }
             }
             FeatureDataValue::Bool(value) => {
                 let bool_builder: &mut BooleanBuilder = downcast_mut_array(data_builder.as_mut());
@@ -258,7 +266,8 @@ where
 
         match self.types.get(column).expect("checked before") {
             FeatureDataType::Category => {
-                let category_builder: &mut UInt8Builder = downcast_mut_array(data_builder.as_mut());
+                let category_builder: &mut StringDictionaryBuilder<CategoryKeyType> =
+                    downcast_mut_array(data_builder.as_mut());
                 category_builder.append_null()?;
             }
             FeatureDataType::Int => {
@@ -320,8 +329,12 @@ where
                     builder.len() * std::mem::size_of::<f64>()
                 } else if builder.as_any().is::<Int64Builder>() {
                     builder.len() * std::mem::size_of::<i64>()
-                } else if builder.as_any().is::<UInt8Builder>() {
-                    builder.len() * std::mem::size_of::<u8>()
+                } else if builder
+                    .as_any()
+                    .is::<StringDictionaryBuilder<CategoryKeyType>>()
+                {
+                    // the distinct categories are negligible compared to the keys
+                    builder.len() * std::mem::size_of::<i32>()
                 } else if builder.as_any().is::<StringBuilder>() {
                     0 // TODO: how to get this dynamic value
                 } else if builder.as_any().is::<BooleanBuilder>() {
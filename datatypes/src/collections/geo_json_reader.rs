@@ -0,0 +1,690 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::Read;
+
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use snafu::ResultExt;
+
+use crate::collections::{error, FeatureCollection, FeatureCollectionError};
+use crate::error::Error;
+use crate::primitives::{FeatureData, FeatureDataType, Geometry, TimeInstance, TimeInterval};
+use crate::util::arrow::ArrowTyped;
+use crate::util::Result;
+
+/// Determines how a [`GeoJsonReader`] handles features that do not fit into the collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoJsonReadMode {
+    /// Fails on the first feature with an invalid geometry, time or property
+    Strict,
+    /// Skips features with missing or invalid geometries, uses the default time for invalid times,
+    /// ignores properties that are not part of the schema and sets property values of the wrong type to null
+    Lenient,
+}
+
+/// Reads a `GeoJSON` `FeatureCollection` into a [`FeatureCollection`].
+///
+/// The input is parsed feature by feature, s.t. neither the text nor the `GeoJSON` objects of the
+/// whole collection are kept in memory.
+/// The column types are either given by a schema or inferred from the property values, where
+/// integers become `Int`, numbers `Float`, strings `Text` and booleans `Bool` columns.
+/// The time interval of a feature is read from its `when` member as written by
+/// [`ToGeoJson`](crate::collections::ToGeoJson) and defaults to [`TimeInterval::default`].
+///
+/// # Example
+///
+/// ```rust
+/// use geoengine_datatypes::collections::{FeatureCollectionInfos, GeoJsonReader, MultiPointCollection};
+///
+/// let collection: MultiPointCollection = GeoJsonReader::default()
+///     .read_str(r#"{
+///         "type": "FeatureCollection",
+///         "features": [{
+///             "type": "Feature",
+///             "geometry": { "type": "Point", "coordinates": [1.0, 2.0] },
+///             "properties": { "name": "foo" }
+///         }]
+///     }"#)
+///     .unwrap();
+///
+/// assert_eq!(collection.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GeoJsonReader {
+    schema: Option<HashMap<String, FeatureDataType>>,
+    mode: GeoJsonReadMode,
+}
+
+impl Default for GeoJsonReader {
+    fn default() -> Self {
+        Self::new(GeoJsonReadMode::Strict)
+    }
+}
+
+impl GeoJsonReader {
+    pub fn new(mode: GeoJsonReadMode) -> Self {
+        Self { schema: None, mode }
+    }
+
+    /// Uses the given column types instead of inferring them from the property values
+    #[must_use]
+    pub fn with_schema(mut self, schema: HashMap<String, FeatureDataType>) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    /// Reads a feature collection from a byte stream.
+    ///
+    /// The `reader` should be buffered since it is read in small portions.
+    pub fn read<G, R>(&self, reader: R) -> Result<FeatureCollection<G>>
+    where
+        G: Geometry + ArrowTyped + TryFrom<geojson::Value, Error = Error>,
+        R: Read,
+    {
+        let mut accumulator = FeatureAccumulator::new(self);
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+
+        let result = FeatureCollectionSeed {
+            accumulator: &mut accumulator,
+        }
+        .deserialize(&mut deserializer)
+        .and_then(|_| deserializer.end());
+
+        // errors of the features are reported to the deserializer as plain messages
+        if let Some(error) = accumulator.error.take() {
+            return Err(error);
+        }
+        result.context(error::GeoJsonParsing)?;
+
+        accumulator.finish()
+    }
+
+    pub fn read_str<G>(&self, geo_json: &str) -> Result<FeatureCollection<G>>
+    where
+        G: Geometry + ArrowTyped + TryFrom<geojson::Value, Error = Error>,
+    {
+        self.read(geo_json.as_bytes())
+    }
+}
+
+/// Collects the features column by column until the column types are known
+struct FeatureAccumulator<'r, G> {
+    reader: &'r GeoJsonReader,
+    geometries: Vec<G>,
+    time_intervals: Vec<TimeInterval>,
+    columns: HashMap<String, Vec<Value>>,
+    /// The number of features that were read, including the skipped ones
+    number_of_features: usize,
+    error: Option<Error>,
+}
+
+impl<'r, G> FeatureAccumulator<'r, G>
+where
+    G: Geometry + ArrowTyped + TryFrom<geojson::Value, Error = Error>,
+{
+    fn new(reader: &'r GeoJsonReader) -> Self {
+        Self {
+            reader,
+            geometries: Vec::new(),
+            time_intervals: Vec::new(),
+            columns: reader
+                .schema
+                .iter()
+                .flat_map(HashMap::keys)
+                .map(|column| (column.clone(), Vec::new()))
+                .collect(),
+            number_of_features: 0,
+            error: None,
+        }
+    }
+
+    fn is_strict(&self) -> bool {
+        self.reader.mode == GeoJsonReadMode::Strict
+    }
+
+    fn push(&mut self, feature: geojson::Feature) -> Result<()> {
+        let index = self.number_of_features;
+        self.number_of_features += 1;
+
+        let geometry = match self.geometry(index, feature.geometry) {
+            Ok(geometry) => geometry,
+            Err(_) if !self.is_strict() => return Ok(()),
+            Err(error) => return Err(error),
+        };
+
+        let time_interval = match time_interval(feature.foreign_members.as_ref()) {
+            Ok(time_interval) => time_interval,
+            Err(_) if !self.is_strict() => TimeInterval::default(),
+            Err(reason) => {
+                return Err(FeatureCollectionError::InvalidGeoJsonTime {
+                    feature: index,
+                    reason,
+                }
+                .into())
+            }
+        };
+
+        let row = self.time_intervals.len();
+
+        for (property, value) in feature.properties.unwrap_or_default() {
+            let value = match self.validate_property(index, &property, value)? {
+                Some(value) => value,
+                None => continue,
+            };
+
+            self.columns
+                .entry(property)
+                .or_insert_with(|| vec![Value::Null; row])
+                .push(value);
+        }
+
+        for column in self.columns.values_mut() {
+            if column.len() == row {
+                column.push(Value::Null);
+            }
+        }
+
+        if let Some(geometry) = geometry {
+            self.geometries.push(geometry);
+        }
+        self.time_intervals.push(time_interval);
+
+        Ok(())
+    }
+
+    /// Converts the geometry of a feature, which is `None` for collections without geometries
+    fn geometry(&self, index: usize, geometry: Option<geojson::Geometry>) -> Result<Option<G>> {
+        if !G::IS_GEOMETRY {
+            return Ok(None);
+        }
+
+        let geometry =
+            geometry.ok_or(FeatureCollectionError::MissingGeoJsonGeometry { feature: index })?;
+
+        G::try_from(geometry.value).map(Some).map_err(|source| {
+            FeatureCollectionError::InvalidGeoJsonGeometry {
+                feature: index,
+                source: Box::new(source),
+            }
+            .into()
+        })
+    }
+
+    /// Checks a property value against the schema and returns `None` if the property is dropped
+    fn validate_property(
+        &self,
+        index: usize,
+        property: &str,
+        value: Value,
+    ) -> Result<Option<Value>> {
+        let schema = match &self.reader.schema {
+            Some(schema) => schema,
+            None => return Ok(Some(value)),
+        };
+
+        let data_type = match schema.get(property) {
+            Some(data_type) => *data_type,
+            None if self.is_strict() => {
+                return Err(FeatureCollectionError::UnknownGeoJsonProperty {
+                    feature: index,
+                    property: property.to_string(),
+                }
+                .into())
+            }
+            None => return Ok(None),
+        };
+
+        if is_of_type(&value, data_type) {
+            Ok(Some(value))
+        } else if self.is_strict() {
+            Err(FeatureCollectionError::InvalidGeoJsonPropertyType {
+                feature: index,
+                property: property.to_string(),
+                expected: data_type,
+                found: value.to_string(),
+            }
+            .into())
+        } else {
+            Ok(Some(Value::Null))
+        }
+    }
+
+    fn finish(self) -> Result<FeatureCollection<G>> {
+        let mut data = HashMap::with_capacity(self.columns.len());
+
+        for (column, values) in self.columns {
+            let data_type = match &self.reader.schema {
+                Some(schema) => schema.get(&column).copied(),
+                None => infer_data_type(&values),
+            };
+
+            let feature_data = match data_type {
+                Some(data_type) => feature_data(data_type, values),
+                None if self.reader.mode == GeoJsonReadMode::Lenient => text_feature_data(values),
+                None => {
+                    return Err(FeatureCollectionError::MixedGeoJsonPropertyTypes {
+                        property: column,
+                    }
+                    .into())
+                }
+            };
+
+            data.insert(column, feature_data);
+        }
+
+        FeatureCollection::from_data(self.geometries, self.time_intervals, data)
+    }
+}
+
+/// Reads the time interval from the `when` member of a feature
+fn time_interval(foreign_members: Option<&Map<String, Value>>) -> Result<TimeInterval, String> {
+    let when = match foreign_members.and_then(|members| members.get("when")) {
+        Some(when) => when,
+        None => return Ok(TimeInterval::default()),
+    };
+
+    let time_instance = |bound: &str| {
+        let value = when
+            .get(bound)
+            .ok_or_else(|| format!("`when` has no `{}`", bound))?;
+        TimeInstance::deserialize(value).map_err(|error| error.to_string())
+    };
+
+    TimeInterval::new(time_instance("start")?, time_instance("end")?)
+        .map_err(|error| error.to_string())
+}
+
+fn is_of_type(value: &Value, data_type: FeatureDataType) -> bool {
+    match (value, data_type) {
+        (Value::Null, _) | (Value::Bool(_), FeatureDataType::Bool) => true,
+        (Value::Number(number), FeatureDataType::Int) => number.is_i64(),
+        (Value::Number(_), FeatureDataType::Float)
+        | (Value::String(_), FeatureDataType::Text | FeatureDataType::Category) => true,
+        (_, FeatureDataType::DateTime) => TimeInstance::deserialize(value).is_ok(),
+        _ => false,
+    }
+}
+
+/// Infers the column type from the values, which is `None` if the values are of different or nested types
+fn infer_data_type(values: &[Value]) -> Option<FeatureDataType> {
+    let mut data_type = None;
+
+    for value in values {
+        let value_type = match value {
+            Value::Null => continue,
+            Value::Bool(_) => FeatureDataType::Bool,
+            Value::Number(number) if number.is_i64() => FeatureDataType::Int,
+            Value::Number(_) => FeatureDataType::Float,
+            Value::String(_) => FeatureDataType::Text,
+            Value::Array(_) | Value::Object(_) => return None,
+        };
+
+        data_type = match (data_type, value_type) {
+            (None, value_type) => Some(value_type),
+            (Some(FeatureDataType::Int), FeatureDataType::Float)
+            | (Some(FeatureDataType::Float), FeatureDataType::Int) => Some(FeatureDataType::Float),
+            (Some(data_type), value_type) if data_type == value_type => Some(data_type),
+            _ => return None,
+        };
+    }
+
+    // columns with only null values are text columns
+    Some(data_type.unwrap_or(FeatureDataType::Text))
+}
+
+/// Creates the column data from values that are either null or of the `data_type`
+fn feature_data(data_type: FeatureDataType, values: Vec<Value>) -> FeatureData {
+    match data_type {
+        FeatureDataType::Category => {
+            FeatureData::NullableCategory(values.into_iter().map(into_string).collect())
+        }
+        FeatureDataType::Int => {
+            FeatureData::NullableInt(values.iter().map(Value::as_i64).collect())
+        }
+        FeatureDataType::Float => {
+            FeatureData::NullableFloat(values.iter().map(Value::as_f64).collect())
+        }
+        FeatureDataType::Text => {
+            FeatureData::NullableText(values.into_iter().map(into_string).collect())
+        }
+        FeatureDataType::Bool => {
+            FeatureData::NullableBool(values.iter().map(Value::as_bool).collect())
+        }
+        FeatureDataType::DateTime => FeatureData::NullableDateTime(
+            values
+                .iter()
+                .map(|value| TimeInstance::deserialize(value).ok())
+                .collect(),
+        ),
+    }
+}
+
+/// Creates a text column from values of any type, where non-string values are written as JSON
+fn text_feature_data(values: Vec<Value>) -> FeatureData {
+    FeatureData::NullableText(
+        values
+            .into_iter()
+            .map(|value| match value {
+                Value::Null => None,
+                Value::String(string) => Some(string),
+                value => Some(value.to_string()),
+            })
+            .collect(),
+    )
+}
+
+fn into_string(value: Value) -> Option<String> {
+    match value {
+        Value::String(string) => Some(string),
+        _ => None,
+    }
+}
+
+/// Visits the members of the `FeatureCollection` object and passes its features to the accumulator
+struct FeatureCollectionSeed<'a, 'r, G> {
+    accumulator: &'a mut FeatureAccumulator<'r, G>,
+}
+
+impl<'de, 'a, 'r, G> DeserializeSeed<'de> for FeatureCollectionSeed<'a, 'r, G>
+where
+    G: Geometry + ArrowTyped + TryFrom<geojson::Value, Error = Error>,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a, 'r, G> Visitor<'de> for FeatureCollectionSeed<'a, 'r, G>
+where
+    G: Geometry + ArrowTyped + TryFrom<geojson::Value, Error = Error>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a GeoJSON FeatureCollection")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let accumulator = self.accumulator;
+        let mut geo_json_type = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "type" => geo_json_type = Some(map.next_value::<String>()?),
+                "features" => map.next_value_seed(FeaturesSeed {
+                    accumulator: &mut *accumulator,
+                })?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        match geo_json_type.as_deref() {
+            Some("FeatureCollection") => Ok(()),
+            found => fail(
+                accumulator,
+                FeatureCollectionError::NotAGeoJsonFeatureCollection {
+                    found: found.unwrap_or("an object without type").to_string(),
+                }
+                .into(),
+            ),
+        }
+    }
+}
+
+/// Visits the `features` array and passes each feature to the accumulator
+struct FeaturesSeed<'a, 'r, G> {
+    accumulator: &'a mut FeatureAccumulator<'r, G>,
+}
+
+impl<'de, 'a, 'r, G> DeserializeSeed<'de> for FeaturesSeed<'a, 'r, G>
+where
+    G: Geometry + ArrowTyped + TryFrom<geojson::Value, Error = Error>,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'a, 'r, G> Visitor<'de> for FeaturesSeed<'a, 'r, G>
+where
+    G: Geometry + ArrowTyped + TryFrom<geojson::Value, Error = Error>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of GeoJSON features")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        while let Some(feature) = seq.next_element::<geojson::Feature>()? {
+            if let Err(error) = self.accumulator.push(feature) {
+                return fail(self.accumulator, error);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Keeps the error for the reader and aborts the deserialization
+fn fail<G, E: serde::de::Error>(
+    accumulator: &mut FeatureAccumulator<G>,
+    error: Error,
+) -> Result<(), E> {
+    let message = error.to_string();
+    accumulator.error = Some(error);
+    Err(E::custom(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::{
+        DataCollection, FeatureCollectionInfos, MultiPointCollection, MultiPolygonCollection,
+        ToGeoJson,
+    };
+    use crate::primitives::{FeatureDataValue, MultiPoint, MultiPolygon, NoGeometry};
+
+    const POINTS: &str = r#"{
+        "type": "FeatureCollection",
+        "bbox": [0.0, 0.0, 2.0, 2.0],
+        "features": [{
+            "type": "Feature",
+            "geometry": { "type": "Point", "coordinates": [0.0, 0.0] },
+            "properties": { "name": "foo", "count": 1, "value": 1.5, "valid": true },
+            "when": { "start": "1970-01-01T00:00:00+00:00", "end": "1970-01-01T00:00:00.001+00:00", "type": "Interval" }
+        }, {
+            "type": "Feature",
+            "geometry": { "type": "MultiPoint", "coordinates": [[1.0, 1.0, 10.0], [2.0, 2.0, 20.0]] },
+            "properties": { "name": "bar", "count": 2, "value": 2 }
+        }]
+    }"#;
+
+    #[test]
+    fn it_infers_the_schema() {
+        let collection: MultiPointCollection = GeoJsonReader::default().read_str(POINTS).unwrap();
+
+        assert_eq!(
+            collection,
+            MultiPointCollection::from_data(
+                vec![
+                    MultiPoint::new(vec![(0.0, 0.0).into()]).unwrap(),
+                    MultiPoint::new(vec![(1.0, 1.0).into(), (2.0, 2.0).into()]).unwrap(),
+                ],
+                vec![TimeInterval::new_unchecked(0, 1), TimeInterval::default()],
+                [
+                    (
+                        "name".to_string(),
+                        FeatureData::NullableText(vec![Some("foo".into()), Some("bar".into())])
+                    ),
+                    (
+                        "count".to_string(),
+                        FeatureData::NullableInt(vec![Some(1), Some(2)])
+                    ),
+                    (
+                        "value".to_string(),
+                        FeatureData::NullableFloat(vec![Some(1.5), Some(2.)])
+                    ),
+                    (
+                        "valid".to_string(),
+                        FeatureData::NullableBool(vec![Some(true), None])
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_round_trips() {
+        let collection: MultiPointCollection = GeoJsonReader::default().read_str(POINTS).unwrap();
+
+        let round_tripped: MultiPointCollection = GeoJsonReader::default()
+            .read_str(&collection.to_geo_json())
+            .unwrap();
+
+        assert_eq!(collection, round_tripped);
+    }
+
+    #[test]
+    fn it_validates_the_schema() {
+        let schema: HashMap<String, FeatureDataType> = [
+            ("name".to_string(), FeatureDataType::Category),
+            ("count".to_string(), FeatureDataType::Int),
+            ("value".to_string(), FeatureDataType::Int),
+        ]
+        .into_iter()
+        .collect();
+
+        let strict = GeoJsonReader::new(GeoJsonReadMode::Strict).with_schema(schema.clone());
+
+        assert!(matches!(
+            strict.read_str::<MultiPoint>(POINTS),
+            Err(Error::FeatureCollection {
+                source: FeatureCollectionError::InvalidGeoJsonPropertyType { feature: 0, .. }
+                    | FeatureCollectionError::UnknownGeoJsonProperty { feature: 0, .. }
+            })
+        ));
+
+        let lenient: MultiPointCollection = GeoJsonReader::new(GeoJsonReadMode::Lenient)
+            .with_schema(schema)
+            .read_str(POINTS)
+            .unwrap();
+
+        assert_eq!(lenient.len(), 2);
+        assert!(lenient.data("valid").is_err());
+        assert_eq!(
+            lenient.data("value").unwrap().get_unchecked(0),
+            FeatureDataValue::NullableInt(None)
+        );
+        assert_eq!(
+            lenient.data("value").unwrap().get_unchecked(1),
+            FeatureDataValue::NullableInt(Some(2))
+        );
+    }
+
+    #[test]
+    fn it_handles_invalid_geometries() {
+        let geo_json = r#"{
+            "type": "FeatureCollection",
+            "features": [
+                { "type": "Feature", "geometry": null, "properties": { "a": 1 } },
+                {
+                    "type": "Feature",
+                    "geometry": { "type": "Polygon", "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]] },
+                    "properties": { "a": 2 }
+                },
+                {
+                    "type": "Feature",
+                    "geometry": { "type": "Point", "coordinates": [0.0, 0.0] },
+                    "properties": { "a": 3 }
+                }
+            ]
+        }"#;
+
+        assert!(matches!(
+            GeoJsonReader::default().read_str::<MultiPolygon>(geo_json),
+            Err(Error::FeatureCollection {
+                source: FeatureCollectionError::MissingGeoJsonGeometry { feature: 0 }
+            })
+        ));
+
+        let polygons: MultiPolygonCollection = GeoJsonReader::new(GeoJsonReadMode::Lenient)
+            .read_str(geo_json)
+            .unwrap();
+
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(
+            polygons.data("a").unwrap().get_unchecked(0),
+            FeatureDataValue::Int(2)
+        );
+
+        let data: DataCollection = GeoJsonReader::default().read_str(geo_json).unwrap();
+        assert_eq!(data.len(), 3);
+    }
+
+    #[test]
+    fn it_rejects_other_geo_json() {
+        assert!(matches!(
+            GeoJsonReader::default().read_str::<NoGeometry>(
+                r#"{ "type": "Feature", "geometry": null, "properties": {} }"#
+            ),
+            Err(Error::FeatureCollection {
+                source: FeatureCollectionError::NotAGeoJsonFeatureCollection { .. }
+            })
+        ));
+
+        assert!(matches!(
+            GeoJsonReader::default()
+                .read_str::<NoGeometry>(r#"{ "type": "FeatureCollection", "features": [ "#),
+            Err(Error::FeatureCollection {
+                source: FeatureCollectionError::GeoJsonParsing { .. }
+            })
+        ));
+
+        let mixed = r#"{
+            "type": "FeatureCollection",
+            "features": [
+                { "type": "Feature", "geometry": null, "properties": { "a": 1 } },
+                { "type": "Feature", "geometry": null, "properties": { "a": "b" } }
+            ]
+        }"#;
+
+        assert!(matches!(
+            GeoJsonReader::default().read_str::<NoGeometry>(mixed),
+            Err(Error::FeatureCollection {
+                source: FeatureCollectionError::MixedGeoJsonPropertyTypes { .. }
+            })
+        ));
+
+        let data: DataCollection = GeoJsonReader::new(GeoJsonReadMode::Lenient)
+            .read_str(mixed)
+            .unwrap();
+        assert_eq!(
+            data.data("a").unwrap().get_unchecked(0),
+            FeatureDataValue::Text("1".into())
+        );
+    }
+}
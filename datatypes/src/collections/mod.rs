@@ -1,4 +1,5 @@
 mod batch_builder;
+mod column_cast;
 mod data_types;
 pub(self) mod error;
 mod feature_collection;
@@ -6,12 +7,15 @@ mod feature_collection;
 mod feature_collection_builder;
 #[macro_use]
 mod geo_feature_collection;
+mod geo_json_reader;
 
 mod data_collection;
 mod multi_line_string_collection;
 mod multi_point_collection;
 mod multi_polygon_collection;
+mod spatial_index;
 
+pub use column_cast::{is_castable, CastErrorPolicy};
 pub(crate) use error::FeatureCollectionError;
 pub(self) use feature_collection::FilterArray;
 pub use feature_collection::{
@@ -25,6 +29,7 @@ pub use feature_collection_builder::{
 pub use geo_feature_collection::{
     GeometryCollection, GeometryRandomAccess, IntoGeometryIterator, IntoGeometryOptionsIterator,
 };
+pub use geo_json_reader::{GeoJsonReadMode, GeoJsonReader};
 
 pub use data_collection::DataCollection;
 pub use data_types::{
@@ -33,6 +38,7 @@ pub use data_types::{
 pub use multi_line_string_collection::MultiLineStringCollection;
 pub use multi_point_collection::MultiPointCollection;
 pub use multi_polygon_collection::MultiPolygonCollection;
+pub use spatial_index::{SpatialIndex, ToSpatialIndex};
 
 pub use batch_builder::RawFeatureCollectionBuilder;
 
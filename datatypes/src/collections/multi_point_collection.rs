@@ -425,6 +425,79 @@ mod tests {
         assert_eq!(filtered.len(), 1);
     }
 
+    #[test]
+    fn filter_with_attributes() {
+        let pc = MultiPointCollection::from_data(
+            MultiPoint::many(vec![
+                vec![(0., 0.)],
+                vec![(1., 1.), (1.5, 1.5)],
+                vec![(2., 2.)],
+                vec![(3., 3.)],
+            ])
+            .unwrap(),
+            vec![
+                TimeInterval::new_unchecked(0, 1),
+                TimeInterval::new_unchecked(1, 2),
+                TimeInterval::new_unchecked(2, 3),
+                TimeInterval::new_unchecked(3, 4),
+            ],
+            [
+                ("foo".to_string(), FeatureData::Int(vec![1, 2, 3, 4])),
+                (
+                    "bar".to_string(),
+                    FeatureData::NullableText(vec![
+                        Some("a".to_string()),
+                        None,
+                        Some("c".to_string()),
+                        Some("d".to_string()),
+                    ]),
+                ),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        let filtered = pc.filter(vec![false, true, false, true]).unwrap();
+
+        assert_eq!(
+            filtered,
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![vec![(1., 1.), (1.5, 1.5)], vec![(3., 3.)]]).unwrap(),
+                vec![
+                    TimeInterval::new_unchecked(1, 2),
+                    TimeInterval::new_unchecked(3, 4),
+                ],
+                [
+                    ("foo".to_string(), FeatureData::Int(vec![2, 4])),
+                    (
+                        "bar".to_string(),
+                        FeatureData::NullableText(vec![None, Some("d".to_string())]),
+                    ),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            )
+            .unwrap()
+        );
+
+        // the filtered geometries are compact
+        assert_eq!(
+            filtered.coordinates(),
+            &[
+                Coordinate2D::new(1., 1.),
+                Coordinate2D::new(1.5, 1.5),
+                Coordinate2D::new(3., 3.)
+            ]
+        );
+        assert_eq!(filtered.feature_offsets(), &[0, 2, 3]);
+
+        assert_eq!(pc.filter(vec![true; 4]).unwrap(), pc);
+        assert!(pc.filter(vec![false; 4]).unwrap().is_empty());
+    }
+
     #[test]
     fn append() {
         let collection_a = MultiPointCollection::from_data(
@@ -985,6 +1058,223 @@ mod tests {
         );
     }
 
+    #[test]
+    fn range_filter_category() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![
+                (0.0, 0.1),
+                (1.0, 1.1),
+                (2.0, 3.1),
+                (3.0, 3.1),
+                (4.0, 4.1),
+            ])
+            .unwrap(),
+            vec![TimeInterval::new_unchecked(0, 1); 5],
+            [(
+                "foo".to_string(),
+                FeatureData::NullableCategory(vec![
+                    Some("forest".to_string()),
+                    Some("water".to_string()),
+                    None,
+                    Some("field".to_string()),
+                    Some("forest".to_string()),
+                ]),
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            collection
+                .column_range_filter(
+                    "foo",
+                    &[FeatureDataValue::Category("forest".into())..],
+                    false
+                )
+                .unwrap(),
+            collection
+                .filter(vec![true, true, false, false, true])
+                .unwrap()
+        );
+
+        assert_eq!(
+            collection
+                .column_range_filter("foo", &[..FeatureDataValue::Text("g".into())], true)
+                .unwrap(),
+            collection
+                .filter(vec![true, false, true, true, true])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn set_filter() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![
+                (0.0, 0.1),
+                (1.0, 1.1),
+                (2.0, 3.1),
+                (3.0, 3.1),
+                (4.0, 4.1),
+            ])
+            .unwrap(),
+            vec![TimeInterval::new_unchecked(0, 1); 5],
+            [
+                (
+                    "category".to_string(),
+                    FeatureData::NullableCategory(vec![
+                        Some("forest".to_string()),
+                        Some("water".to_string()),
+                        None,
+                        Some("field".to_string()),
+                        Some("forest".to_string()),
+                    ]),
+                ),
+                ("int".to_string(), FeatureData::Int(vec![1, 2, 3, 4, 5])),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            collection
+                .column_set_filter(
+                    "category",
+                    &[
+                        FeatureDataValue::Category("forest".into()),
+                        FeatureDataValue::Category("field".into()),
+                        FeatureDataValue::Category("desert".into()),
+                    ],
+                    false
+                )
+                .unwrap(),
+            collection
+                .filter(vec![true, false, false, true, true])
+                .unwrap()
+        );
+
+        assert_eq!(
+            collection
+                .column_set_filter("category", &[FeatureDataValue::Text("water".into())], true)
+                .unwrap(),
+            collection
+                .filter(vec![false, true, true, false, false])
+                .unwrap()
+        );
+
+        assert_eq!(
+            collection
+                .column_set_filter(
+                    "int",
+                    &[FeatureDataValue::Int(2), FeatureDataValue::Int(5)],
+                    false
+                )
+                .unwrap(),
+            collection
+                .filter(vec![false, true, false, false, true])
+                .unwrap()
+        );
+
+        assert!(collection
+            .column_set_filter("category", &[FeatureDataValue::Int(2)], false)
+            .is_err());
+    }
+
+    #[test]
+    fn categories() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1), (2.0, 3.1)]).unwrap(),
+            vec![TimeInterval::new_unchecked(0, 1); 3],
+            [(
+                "foo".to_string(),
+                FeatureData::NullableCategory(vec![
+                    Some("forest".to_string()),
+                    None,
+                    Some("forest".to_string()),
+                ]),
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        let other = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(3.0, 3.1)]).unwrap(),
+            vec![TimeInterval::new_unchecked(0, 1)],
+            [(
+                "foo".to_string(),
+                FeatureData::Category(vec!["water".to_string()]),
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        let collection = collection.append(&other).unwrap();
+
+        let data = collection.data("foo").unwrap();
+        let data = if let FeatureDataRef::Category(data) = data {
+            data
+        } else {
+            unreachable!()
+        };
+
+        assert_eq!(
+            (0..4).map(|i| data.category_at(i)).collect::<Vec<_>>(),
+            vec![Some("forest"), None, Some("forest"), Some("water")]
+        );
+        assert_eq!(
+            data.get_unchecked(3),
+            FeatureDataValue::NullableCategory(Some("water".to_string()))
+        );
+        assert_eq!(
+            data.json_values().collect::<Vec<_>>(),
+            vec![
+                json!("forest"),
+                serde_json::Value::Null,
+                json!("forest"),
+                json!("water")
+            ]
+        );
+
+        let mut builder = MultiPointCollection::builder();
+        builder
+            .add_column("foo".to_string(), FeatureDataType::Category)
+            .unwrap();
+        let mut builder = builder.finish_header();
+        for category in [Some("forest"), None, Some("forest"), Some("water")] {
+            builder
+                .push_geometry(Coordinate2D::new(0., 0.).into())
+                .unwrap();
+            builder
+                .push_time_interval(TimeInterval::new_unchecked(0, 1))
+                .unwrap();
+            builder
+                .push_data(
+                    "foo",
+                    FeatureDataValue::NullableCategory(category.map(ToString::to_string)),
+                )
+                .unwrap();
+            builder.finish_row();
+        }
+        let built = builder.build().unwrap();
+
+        assert_eq!(
+            built
+                .data("foo")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["forest", "", "forest", "water"]
+        );
+    }
+
     #[test]
     fn range_filter_null() {
         let collection = MultiPointCollection::from_data(
@@ -1048,6 +1338,14 @@ mod tests {
                     "bar".to_string(),
                     FeatureData::Text(vec!["a".into(), "b".into(), "c".into()]),
                 ),
+                (
+                    "baz".to_string(),
+                    FeatureData::NullableCategory(vec![Some("a".into()), None, Some("a".into())]),
+                ),
+                (
+                    "qux".to_string(),
+                    FeatureData::Category(vec!["b".into(), "c".into(), "b".into()]),
+                ),
             ]
             .iter()
             .cloned()
@@ -0,0 +1,292 @@
+use geo::algorithm::bounding_rect::BoundingRect;
+use geo::algorithm::closest_point::ClosestPoint;
+use geo::algorithm::euclidean_distance::EuclideanDistance;
+use rstar::{PointDistance, RTree, RTreeObject, AABB};
+
+use crate::collections::{
+    GeometryCollection, MultiLineStringCollection, MultiPointCollection, MultiPolygonCollection,
+};
+use crate::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, Coordinate2D, MultiLineString, MultiPointAccess,
+    MultiPolygon,
+};
+
+/// An R-tree over the geometries of a feature collection.
+///
+/// The index refers to features by their position in the collection.
+/// It is built once and can then be queried for many features of another collection.
+#[derive(Debug)]
+pub struct SpatialIndex {
+    tree: RTree<IndexedGeometry>,
+}
+
+#[derive(Debug)]
+struct IndexedGeometry {
+    feature: usize,
+    geometry: geo::Geometry<f64>,
+    envelope: AABB<[f64; 2]>,
+}
+
+impl RTreeObject for IndexedGeometry {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+impl PointDistance for IndexedGeometry {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        let point = geo::Point::new(point[0], point[1]);
+
+        let distance = match &self.geometry {
+            geo::Geometry::Point(geometry) => point.euclidean_distance(geometry),
+            geo::Geometry::MultiPoint(geometry) => point.euclidean_distance(geometry),
+            geo::Geometry::LineString(geometry) => point.euclidean_distance(geometry),
+            geo::Geometry::MultiLineString(geometry) => point.euclidean_distance(geometry),
+            geo::Geometry::Polygon(geometry) => point.euclidean_distance(geometry),
+            geo::Geometry::MultiPolygon(geometry) => point.euclidean_distance(geometry),
+            _ => f64::INFINITY,
+        };
+
+        distance * distance
+    }
+}
+
+impl SpatialIndex {
+    /// Builds an index where the `i`-th geometry refers to feature `i`.
+    ///
+    /// Empty geometries are not indexed.
+    pub fn from_geometries<I>(geometries: I) -> Self
+    where
+        I: IntoIterator<Item = geo::Geometry<f64>>,
+    {
+        let indexed_geometries = geometries
+            .into_iter()
+            .enumerate()
+            .filter_map(|(feature, geometry)| {
+                let rect = geometry.bounding_rect()?;
+
+                Some(IndexedGeometry {
+                    feature,
+                    geometry,
+                    envelope: AABB::from_corners(
+                        [rect.min().x, rect.min().y],
+                        [rect.max().x, rect.max().y],
+                    ),
+                })
+            })
+            .collect();
+
+        Self {
+            tree: RTree::bulk_load(indexed_geometries),
+        }
+    }
+
+    /// The number of indexed features
+    pub fn len(&self) -> usize {
+        self.tree.size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the features whose bounding boxes intersect the `bbox`.
+    ///
+    /// This is a filter step, so the geometries themselves may not intersect the `bbox`.
+    pub fn features_intersecting_bbox<'i>(
+        &'i self,
+        bbox: &BoundingBox2D,
+    ) -> impl Iterator<Item = usize> + 'i {
+        let lower_left = bbox.lower_left();
+        let upper_right = bbox.upper_right();
+
+        self.tree
+            .locate_in_envelope_intersecting(&AABB::from_corners(
+                [lower_left.x, lower_left.y],
+                [upper_right.x, upper_right.y],
+            ))
+            .map(|indexed| indexed.feature)
+    }
+
+    /// Returns the features whose geometries intersect the `coordinate`, e.g. the polygons containing it.
+    pub fn features_intersecting_coordinate<'i>(
+        &'i self,
+        coordinate: Coordinate2D,
+    ) -> impl Iterator<Item = usize> + 'i {
+        // a coordinate is located at a geometry if its distance is zero
+        self.tree
+            .locate_all_at_point(&[coordinate.x, coordinate.y])
+            .map(|indexed| indexed.feature)
+    }
+
+    /// Returns all features ordered by their distance to the `coordinate`, together with the distance.
+    ///
+    /// The features are computed lazily, so taking only the first ones is cheap.
+    pub fn nearest_features<'i>(
+        &'i self,
+        coordinate: Coordinate2D,
+    ) -> impl Iterator<Item = (usize, f64)> + 'i {
+        self.tree
+            .nearest_neighbor_iter_with_distance_2(&[coordinate.x, coordinate.y])
+            .map(|(indexed, distance_2)| (indexed.feature, distance_2.sqrt()))
+    }
+
+    /// Returns all features ordered by their distance to the `coordinate`, together with the
+    /// coordinate of their geometry that is closest to the `coordinate`.
+    ///
+    /// This allows measuring the distance differently, e.g., geodesically.
+    pub fn nearest_coordinates<'i>(
+        &'i self,
+        coordinate: Coordinate2D,
+    ) -> impl Iterator<Item = (usize, Coordinate2D)> + 'i {
+        let point = geo::Point::from(geo::Coordinate::from(coordinate));
+
+        self.tree
+            .nearest_neighbor_iter(&[coordinate.x, coordinate.y])
+            .filter_map(
+                move |indexed| match indexed.geometry.closest_point(&point) {
+                    geo::Closest::Intersection(closest) | geo::Closest::SinglePoint(closest) => {
+                        Some((indexed.feature, Coordinate2D::from(closest)))
+                    }
+                    geo::Closest::Indeterminate => None,
+                },
+            )
+    }
+}
+
+/// Builds a `SpatialIndex` on the geometries of a collection
+pub trait ToSpatialIndex {
+    fn spatial_index(&self) -> SpatialIndex;
+}
+
+impl ToSpatialIndex for MultiPointCollection {
+    fn spatial_index(&self) -> SpatialIndex {
+        SpatialIndex::from_geometries(self.geometries().map(|geometry| {
+            geo::Geometry::MultiPoint(geo::MultiPoint(
+                geometry
+                    .points()
+                    .iter()
+                    .map(|coordinate| geo::Point::from(geo::Coordinate::from(coordinate)))
+                    .collect(),
+            ))
+        }))
+    }
+}
+
+impl ToSpatialIndex for MultiLineStringCollection {
+    fn spatial_index(&self) -> SpatialIndex {
+        SpatialIndex::from_geometries(self.geometries().map(|geometry| {
+            geo::Geometry::MultiLineString((&MultiLineString::from(geometry)).into())
+        }))
+    }
+}
+
+impl ToSpatialIndex for MultiPolygonCollection {
+    fn spatial_index(&self) -> SpatialIndex {
+        SpatialIndex::from_geometries(
+            self.geometries().map(|geometry| {
+                geo::Geometry::MultiPolygon((&MultiPolygon::from(geometry)).into())
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{MultiPoint, TimeInterval};
+
+    fn square(x: f64, y: f64) -> MultiPolygon {
+        MultiPolygon::new(vec![vec![vec![
+            (x, y).into(),
+            (x + 1., y).into(),
+            (x + 1., y + 1.).into(),
+            (x, y + 1.).into(),
+            (x, y).into(),
+        ]]])
+        .unwrap()
+    }
+
+    fn squares() -> MultiPolygonCollection {
+        MultiPolygonCollection::from_data(
+            vec![square(0., 0.), square(5., 5.), square(0.5, 0.5)],
+            vec![TimeInterval::default(); 3],
+            Default::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn bbox_query() {
+        let index = squares().spatial_index();
+
+        assert_eq!(index.len(), 3);
+
+        let mut features: Vec<usize> = index
+            .features_intersecting_bbox(
+                &BoundingBox2D::new((0.9, 0.9).into(), (2., 2.).into()).unwrap(),
+            )
+            .collect();
+        features.sort_unstable();
+
+        assert_eq!(features, vec![0, 2]);
+    }
+
+    #[test]
+    fn coordinate_query() {
+        let index = squares().spatial_index();
+
+        let mut features: Vec<usize> = index
+            .features_intersecting_coordinate((0.25, 0.25).into())
+            .collect();
+        features.sort_unstable();
+        assert_eq!(features, vec![0]);
+
+        let mut features: Vec<usize> = index
+            .features_intersecting_coordinate((0.75, 0.75).into())
+            .collect();
+        features.sort_unstable();
+        assert_eq!(features, vec![0, 2]);
+
+        assert_eq!(
+            index
+                .features_intersecting_coordinate((3., 3.).into())
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn nearest_query() {
+        let points = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0., 0.), (10., 0.), (3., 4.)]).unwrap(),
+            vec![TimeInterval::default(); 3],
+            Default::default(),
+        )
+        .unwrap();
+
+        let index = points.spatial_index();
+
+        let nearest: Vec<(usize, f64)> = index.nearest_features((6., 8.).into()).take(2).collect();
+
+        assert_eq!(nearest.len(), 2);
+        assert_eq!(nearest[0].0, 2);
+        assert!((nearest[0].1 - 5.).abs() < 1e-10);
+        assert_eq!(nearest[1].0, 1);
+        assert!((nearest[1].1 - 80_f64.sqrt()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn nearest_coordinates_query() {
+        let index = squares().spatial_index();
+
+        let nearest: Vec<(usize, Coordinate2D)> = index
+            .nearest_coordinates((3., 0.5).into())
+            .take(2)
+            .collect();
+
+        assert_eq!(nearest, vec![(2, (1.5, 0.5).into()), (0, (1., 0.5).into())]);
+    }
+}
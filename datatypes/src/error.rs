@@ -3,7 +3,7 @@ use std::convert::Infallible;
 
 use crate::{
     collections::FeatureCollectionError,
-    primitives::{BoundingBox2D, TimeInstance},
+    primitives::{BoundingBox2D, TimeInstance, TimeStep},
     spatial_reference::SpatialReference,
 };
 use crate::{
@@ -144,6 +144,11 @@ pub enum Error {
         details: String,
     },
 
+    #[snafu(display("Invalid raster tile in Arrow IPC format: {}", details))]
+    InvalidRasterTileIpc {
+        details: String,
+    },
+
     #[snafu(display("Colorizer exception: {}", details))]
     Colorizer {
         details: String,
@@ -196,6 +201,16 @@ pub enum Error {
         day: u32,
     },
 
+    #[snafu(display(
+        "Adding {:?} to {} is outside of the valid time range",
+        time_step,
+        time_instance
+    ))]
+    TimeStepOutOfBounds {
+        time_instance: TimeInstance,
+        time_step: TimeStep,
+    },
+
     #[snafu(display(
         "The supplied spatial bounds are empty: {} {}",
         lower_left_coordinate,
@@ -246,6 +261,25 @@ pub enum Error {
     MissingRasterProperty {
         property: String,
     },
+
+    #[snafu(display("Cannot stack raster tiles as levels: {}", reason))]
+    InvalidRasterLevels {
+        reason: String,
+    },
+
+    #[snafu(display("Unknown unit: {}", unit))]
+    UnknownUnit {
+        unit: String,
+    },
+
+    #[snafu(display("Cannot convert values from unit {} to unit {}", from, to))]
+    IncompatibleUnits {
+        from: String,
+        to: String,
+    },
+
+    #[snafu(display("The measurement does not have a unit"))]
+    MeasurementWithoutUnit,
 }
 
 impl From<arrow::error::ArrowError> for Error {
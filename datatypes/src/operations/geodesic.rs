@@ -0,0 +1,357 @@
+use geo::algorithm::area::Area;
+use geo::algorithm::euclidean_length::EuclideanLength;
+use geographiclib_rs::{DirectGeodesic, Geodesic, InverseGeodesic, PolygonArea, Winding};
+
+use crate::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, Coordinate2D, MultiLineString, MultiLineStringAccess,
+    MultiPolygon, MultiPolygonAccess,
+};
+use crate::spatial_reference::SpatialReferenceOption;
+use crate::util::Result;
+
+/// The smallest length of one degree of latitude on the WGS 84 ellipsoid (at the equator) in meters
+const MIN_METERS_PER_DEGREE_LATITUDE: f64 = 110_574.;
+
+/// The length of one degree of longitude on the WGS 84 equator in meters
+const METERS_PER_DEGREE_LONGITUDE_AT_EQUATOR: f64 = 111_320.;
+
+/// Measures distances, lengths and areas of coordinates in a spatial reference.
+///
+/// Coordinates of geographic spatial references are in degrees, so planar math on them is wrong,
+/// especially at high latitudes. Here, the measurements are computed on the WGS 84 ellipsoid
+/// using the geodesic algorithms of Karney (2013) and are returned in meters.
+/// For projected (and unreferenced) coordinates, the measurements are planar in the units of
+/// the spatial reference.
+#[derive(Debug, Clone)]
+pub enum Measurement {
+    Geodesic(Box<Geodesic>),
+    Planar,
+}
+
+impl Measurement {
+    pub fn geodesic() -> Self {
+        Self::Geodesic(Box::new(Geodesic::wgs84()))
+    }
+
+    /// Selects geodesic measurements for geographic spatial references and planar ones otherwise
+    pub fn for_spatial_reference(spatial_reference: SpatialReferenceOption) -> Result<Self> {
+        Ok(match spatial_reference {
+            SpatialReferenceOption::SpatialReference(spatial_reference)
+                if spatial_reference.is_geographic()? =>
+            {
+                Self::geodesic()
+            }
+            _ => Self::Planar,
+        })
+    }
+
+    pub fn is_geodesic(&self) -> bool {
+        matches!(self, Self::Geodesic(_))
+    }
+
+    /// The distance between two coordinates
+    pub fn distance(&self, a: Coordinate2D, b: Coordinate2D) -> f64 {
+        match self {
+            Self::Geodesic(geodesic) => geodesic.inverse(a.y, a.x, b.y, b.x),
+            Self::Planar => {
+                let (dx, dy) = (b.x - a.x, b.y - a.y);
+                (dx * dx + dy * dy).sqrt()
+            }
+        }
+    }
+
+    /// The length of all lines of a `MultiLineString`
+    pub fn length(&self, multi_line_string: &MultiLineString) -> f64 {
+        match self {
+            Self::Geodesic(_) => multi_line_string
+                .lines()
+                .iter()
+                .flat_map(|line| line.windows(2))
+                .map(|segment| self.distance(segment[0], segment[1]))
+                .sum(),
+            Self::Planar => geo::MultiLineString::from(multi_line_string).euclidean_length(),
+        }
+    }
+
+    /// The area of a `MultiPolygon` where the areas of holes are subtracted
+    pub fn area(&self, multi_polygon: &MultiPolygon) -> f64 {
+        match self {
+            Self::Geodesic(geodesic) => multi_polygon
+                .polygons()
+                .iter()
+                .map(|polygon| {
+                    let mut rings = polygon.iter();
+
+                    let exterior = rings
+                        .next()
+                        .map_or(0., |ring| geodesic_ring_area(geodesic, ring));
+                    let interiors: f64 = rings.map(|ring| geodesic_ring_area(geodesic, ring)).sum();
+
+                    (exterior - interiors).max(0.)
+                })
+                .sum(),
+            Self::Planar => geo::MultiPolygon::from(multi_polygon).unsigned_area(),
+        }
+    }
+
+    /// The coordinate that is reached by moving `distance` from `origin` in the direction of
+    /// `bearing`, given in degrees clockwise from north.
+    pub fn destination(&self, origin: Coordinate2D, bearing: f64, distance: f64) -> Coordinate2D {
+        match self {
+            Self::Geodesic(geodesic) => {
+                let (lat, lon): (f64, f64) = geodesic.direct(origin.y, origin.x, bearing, distance);
+                Coordinate2D::new(lon, lat)
+            }
+            Self::Planar => {
+                let (sin, cos) = bearing.to_radians().sin_cos();
+                Coordinate2D::new(origin.x + distance * sin, origin.y + distance * cos)
+            }
+        }
+    }
+
+    /// A polygon that approximates the circle of `radius` around `center` with `number_of_vertices`.
+    ///
+    /// This buffers a point by a distance in meters for geographic coordinates.
+    pub fn circle(
+        &self,
+        center: Coordinate2D,
+        radius: f64,
+        number_of_vertices: usize,
+    ) -> Result<MultiPolygon> {
+        let number_of_vertices = number_of_vertices.max(3);
+
+        // counter-clockwise, starting and ending in the east
+        let mut ring: Vec<Coordinate2D> = (0..number_of_vertices)
+            .map(|i| {
+                let bearing = 90. - 360. * (i as f64) / (number_of_vertices as f64);
+                self.destination(center, bearing, radius)
+            })
+            .collect();
+        ring.push(ring[0]);
+
+        MultiPolygon::new(vec![vec![ring]])
+    }
+
+    /// Enlarges a bounding box such that it contains all coordinates within `distance` of it.
+    ///
+    /// For geodesic measurements, the result is a conservative estimate in degrees that is
+    /// clamped to the valid range of latitudes and longitudes.
+    pub fn enlarge_bbox(&self, bbox: BoundingBox2D, distance: f64) -> BoundingBox2D {
+        let lower_left = bbox.lower_left();
+        let upper_right = bbox.upper_right();
+
+        match self {
+            Self::Geodesic(_) => {
+                let delta_latitude = distance / MIN_METERS_PER_DEGREE_LATITUDE;
+
+                let min_y = (lower_left.y - delta_latitude).max(-90.);
+                let max_y = (upper_right.y + delta_latitude).min(90.);
+
+                // the length of a degree of longitude shrinks towards the poles
+                let max_abs_latitude = min_y.abs().max(max_y.abs());
+                let meters_per_degree_longitude =
+                    METERS_PER_DEGREE_LONGITUDE_AT_EQUATOR * max_abs_latitude.to_radians().cos();
+                let delta_longitude = distance / meters_per_degree_longitude;
+
+                let (min_x, max_x) = if delta_longitude.is_finite() && delta_longitude < 180. {
+                    (
+                        (lower_left.x - delta_longitude).max(-180.),
+                        (upper_right.x + delta_longitude).min(180.),
+                    )
+                } else {
+                    (-180., 180.)
+                };
+
+                BoundingBox2D::new_unchecked(
+                    Coordinate2D::new(min_x, min_y),
+                    Coordinate2D::new(max_x, max_y),
+                )
+            }
+            Self::Planar => BoundingBox2D::new_unchecked(
+                Coordinate2D::new(lower_left.x - distance, lower_left.y - distance),
+                Coordinate2D::new(upper_right.x + distance, upper_right.y + distance),
+            ),
+        }
+    }
+}
+
+/// The unsigned area of a ring on the ellipsoid
+fn geodesic_ring_area(geodesic: &Geodesic, ring: &[Coordinate2D]) -> f64 {
+    let mut polygon_area = PolygonArea::new(geodesic, Winding::CounterClockwise);
+
+    // the ring is closed implicitly
+    let open_ring = match ring.split_last() {
+        Some((last, rest)) if rest.first() == Some(last) => rest,
+        _ => ring,
+    };
+
+    for coordinate in open_ring {
+        polygon_area.add_point(coordinate.y, coordinate.x);
+    }
+
+    // allow a negative sign for clockwise rings to not mistake them for the complement
+    let (_perimeter, area, _count) = polygon_area.compute(true);
+
+    area.abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spatial_reference::{SpatialReference, SpatialReferenceAuthority};
+    use float_cmp::approx_eq;
+
+    #[test]
+    fn selects_by_spatial_reference() {
+        assert!(
+            Measurement::for_spatial_reference(SpatialReference::epsg_4326().into())
+                .unwrap()
+                .is_geodesic()
+        );
+        assert!(!Measurement::for_spatial_reference(
+            SpatialReference::new(SpatialReferenceAuthority::Epsg, 3857).into()
+        )
+        .unwrap()
+        .is_geodesic());
+        assert!(
+            !Measurement::for_spatial_reference(SpatialReferenceOption::Unreferenced)
+                .unwrap()
+                .is_geodesic()
+        );
+    }
+
+    #[test]
+    fn geodesic_distance() {
+        let measurement = Measurement::geodesic();
+
+        // New York City to London
+        let distance = measurement.distance((-74.006, 40.7128).into(), (-0.1278, 51.5074).into());
+        assert!(approx_eq!(f64, distance.round(), 5_585_234.));
+
+        // one degree of longitude shrinks with increasing latitude
+        let at_equator = measurement.distance((0., 0.).into(), (1., 0.).into());
+        let at_70_degrees = measurement.distance((0., 70.).into(), (1., 70.).into());
+        assert!(approx_eq!(f64, at_equator.round(), 111_319.));
+        assert!(at_70_degrees < at_equator / 2.);
+    }
+
+    #[test]
+    fn planar_distance() {
+        let distance = Measurement::Planar.distance((0., 0.).into(), (3., 4.).into());
+        assert!(approx_eq!(f64, distance, 5.));
+    }
+
+    #[test]
+    fn geodesic_length() {
+        let measurement = Measurement::geodesic();
+
+        let line = MultiLineString::new(vec![vec![
+            (0., 0.).into(),
+            (1., 0.).into(),
+            (2., 0.).into(),
+        ]])
+        .unwrap();
+
+        assert!(approx_eq!(
+            f64,
+            measurement.length(&line),
+            measurement.distance((0., 0.).into(), (2., 0.).into()),
+            epsilon = 1e-6
+        ));
+    }
+
+    #[test]
+    fn geodesic_area() {
+        let measurement = Measurement::geodesic();
+
+        let square = |x: f64, y: f64, size: f64| {
+            vec![
+                Coordinate2D::new(x, y),
+                Coordinate2D::new(x + size, y),
+                Coordinate2D::new(x + size, y + size),
+                Coordinate2D::new(x, y + size),
+                Coordinate2D::new(x, y),
+            ]
+        };
+
+        let polygon = MultiPolygon::new(vec![vec![square(0., 0., 1.)]]).unwrap();
+        assert!(approx_eq!(
+            f64,
+            measurement.area(&polygon).round(),
+            12_308_778_361.
+        ));
+
+        // the orientation of the ring does not matter
+        let mut clockwise = square(0., 0., 1.);
+        clockwise.reverse();
+        let polygon = MultiPolygon::new(vec![vec![clockwise]]).unwrap();
+        assert!(approx_eq!(
+            f64,
+            measurement.area(&polygon).round(),
+            12_308_778_361.
+        ));
+
+        let with_hole =
+            MultiPolygon::new(vec![vec![square(0., 0., 1.), square(0.25, 0.25, 0.5)]]).unwrap();
+        let hole = MultiPolygon::new(vec![vec![square(0.25, 0.25, 0.5)]]).unwrap();
+        assert!(approx_eq!(
+            f64,
+            measurement.area(&with_hole),
+            12_308_778_361.469_452 - measurement.area(&hole),
+            epsilon = 1.
+        ));
+    }
+
+    #[test]
+    fn planar_area() {
+        let polygon = MultiPolygon::new(vec![vec![vec![
+            (0., 0.).into(),
+            (2., 0.).into(),
+            (2., 2.).into(),
+            (0., 2.).into(),
+            (0., 0.).into(),
+        ]]])
+        .unwrap();
+
+        assert!(approx_eq!(f64, Measurement::Planar.area(&polygon), 4.));
+    }
+
+    #[test]
+    fn geodesic_circle() {
+        let measurement = Measurement::geodesic();
+        let center = Coordinate2D::new(10., 60.);
+
+        let circle = measurement.circle(center, 1000., 32).unwrap();
+
+        for coordinate in &circle.polygons()[0][0] {
+            assert!(approx_eq!(
+                f64,
+                measurement.distance(center, *coordinate),
+                1000.,
+                epsilon = 1e-6
+            ));
+        }
+    }
+
+    #[test]
+    fn enlarge_bbox() {
+        let measurement = Measurement::geodesic();
+        let bbox = BoundingBox2D::new((10., 60.).into(), (11., 61.).into()).unwrap();
+
+        let enlarged = measurement.enlarge_bbox(bbox, 10_000.);
+
+        for coordinate in [
+            measurement.destination(bbox.lower_left(), 225., 10_000.),
+            measurement.destination(bbox.upper_right(), 45., 10_000.),
+            measurement.destination(Coordinate2D::new(10., 61.), 270., 10_000.),
+        ] {
+            assert!(enlarged.contains_coordinate(&coordinate));
+        }
+
+        let near_pole = BoundingBox2D::new((10., 89.).into(), (11., 89.5).into()).unwrap();
+        let enlarged = measurement.enlarge_bbox(near_pole, 100_000.);
+        assert!(approx_eq!(f64, enlarged.lower_left().x, -180.));
+        assert!(approx_eq!(f64, enlarged.upper_right().x, 180.));
+        assert!(approx_eq!(f64, enlarged.upper_right().y, 90.));
+    }
+}
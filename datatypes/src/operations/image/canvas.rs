@@ -0,0 +1,485 @@
+use std::ops::Range;
+
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use super::to_png::encode_image;
+use super::{ImageEncoding, RgbaColor};
+use crate::primitives::{AxisAlignedRectangle, BoundingBox2D, Coordinate2D};
+use crate::util::Result;
+
+/// A transparent RGBA image for drawing vector geometries, e.g., for rendering feature collections
+/// as map images.
+///
+/// Geometries are given in the coordinates of the canvas' `bounds`, whereas radii and stroke widths
+/// are given in pixels. A pixel is covered by a shape if its center is covered.
+pub struct Canvas {
+    image: RgbaImage,
+    bounds: BoundingBox2D,
+    x_resolution: f64,
+    y_resolution: f64,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32, bounds: BoundingBox2D) -> Self {
+        Self {
+            image: RgbaImage::new(width, height),
+            bounds,
+            x_resolution: bounds.size_x() / f64::from(width),
+            y_resolution: bounds.size_y() / f64::from(height),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.image.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.image.height()
+    }
+
+    /// Draws a point as a circle with a `radius` and an outline of `stroke_width`
+    pub fn draw_point(
+        &mut self,
+        point: Coordinate2D,
+        radius: f64,
+        fill_color: RgbaColor,
+        stroke_width: f64,
+        stroke_color: RgbaColor,
+    ) {
+        let center = self.to_pixel(point);
+        // a point is always visible, even if its radius is smaller than a pixel
+        let radius = radius.max(0.5);
+        let half_stroke_width = stroke_width / 2.;
+
+        let mut fill = self.mask(&[center], radius);
+        fill.cover_where(|pixel| distance(pixel, center) <= radius);
+        self.blend(&fill, fill_color);
+
+        if stroke_width > 0. {
+            let mut stroke = self.mask(&[center], radius + half_stroke_width);
+            stroke
+                .cover_where(|pixel| (distance(pixel, center) - radius).abs() <= half_stroke_width);
+            self.blend(&stroke, stroke_color);
+        }
+    }
+
+    /// Draws the segments of a line string with a `stroke_width`
+    pub fn draw_line_string(
+        &mut self,
+        line_string: &[Coordinate2D],
+        stroke_width: f64,
+        stroke_color: RgbaColor,
+    ) {
+        let line_string = self.to_pixels(line_string);
+        let half_stroke_width = stroke_width.max(1.) / 2.;
+
+        let mut stroke = self.mask(&line_string, half_stroke_width);
+        stroke.cover_segments(segments(&line_string, false), half_stroke_width);
+        self.blend(&stroke, stroke_color);
+    }
+
+    /// Draws a polygon that consists of an exterior ring and holes using the even-odd rule
+    pub fn draw_polygon<R: AsRef<[Coordinate2D]>>(
+        &mut self,
+        rings: &[R],
+        fill_color: RgbaColor,
+        stroke_width: f64,
+        stroke_color: RgbaColor,
+    ) {
+        let rings: Vec<Vec<Coordinate2D>> = rings
+            .iter()
+            .map(|ring| self.to_pixels(ring.as_ref()))
+            .collect();
+        let pixels: Vec<Coordinate2D> = rings.iter().flatten().copied().collect();
+
+        let mut fill = self.mask(&pixels, 0.);
+        fill.cover_scanlines(rings.iter().flat_map(|ring| segments(ring, true)));
+        self.blend(&fill, fill_color);
+
+        if stroke_width > 0. {
+            let half_stroke_width = stroke_width / 2.;
+
+            let mut stroke = self.mask(&pixels, half_stroke_width);
+            stroke.cover_segments(
+                rings.iter().flat_map(|ring| segments(ring, true)),
+                half_stroke_width,
+            );
+            self.blend(&stroke, stroke_color);
+        }
+    }
+
+    /// Outputs the bytes of the canvas encoded as `encoding`
+    pub fn to_image_bytes(&self, encoding: ImageEncoding) -> Result<Vec<u8>> {
+        encode_image(DynamicImage::ImageRgba8(self.image.clone()), encoding)
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> RgbaColor {
+        let Rgba([red, green, blue, alpha]) = *self.image.get_pixel(x, y);
+        RgbaColor::new(red, green, blue, alpha)
+    }
+
+    fn to_pixel(&self, coordinate: Coordinate2D) -> Coordinate2D {
+        Coordinate2D::new(
+            (coordinate.x - self.bounds.upper_left().x) / self.x_resolution,
+            (self.bounds.upper_left().y - coordinate.y) / self.y_resolution,
+        )
+    }
+
+    fn to_pixels(&self, coordinates: &[Coordinate2D]) -> Vec<Coordinate2D> {
+        coordinates.iter().map(|c| self.to_pixel(*c)).collect()
+    }
+
+    /// Creates a mask for all pixels within the bounds of `pixels` extended by `buffer`
+    fn mask(&self, pixels: &[Coordinate2D], buffer: f64) -> Mask {
+        let (min, max) = pixels.iter().fold(
+            (
+                Coordinate2D::new(f64::MAX, f64::MAX),
+                Coordinate2D::new(f64::MIN, f64::MIN),
+            ),
+            |(min, max), pixel| (min.min_elements(*pixel), max.max_elements(*pixel)),
+        );
+
+        Mask::new(
+            pixel_range(min.x - buffer, max.x + buffer, self.width()),
+            pixel_range(min.y - buffer, max.y + buffer, self.height()),
+        )
+    }
+
+    /// Draws `color` over all covered pixels of the `mask`
+    fn blend(&mut self, mask: &Mask, color: RgbaColor) {
+        let [red, green, blue, alpha] = color.channels();
+        let alpha = alpha / 255.;
+
+        if alpha <= 0. {
+            return;
+        }
+
+        for (x, y) in mask.covered_pixels() {
+            let pixel = self.image.get_pixel_mut(x, y);
+            let Rgba([dst_red, dst_green, dst_blue, dst_alpha]) = *pixel;
+            let dst_alpha = f64::from(dst_alpha) / 255.;
+
+            let out_alpha = alpha + dst_alpha * (1. - alpha);
+            let channel = |src: f64, dst: u8| {
+                to_u8((src * alpha + f64::from(dst) * dst_alpha * (1. - alpha)) / out_alpha)
+            };
+
+            *pixel = Rgba([
+                channel(red, dst_red),
+                channel(green, dst_green),
+                channel(blue, dst_blue),
+                to_u8(out_alpha * 255.),
+            ]);
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn to_u8(value: f64) -> u8 {
+    value.round().clamp(0., 255.) as u8
+}
+
+/// The pixels whose centers lie within `[min, max]`, clamped to `[0, size)`
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn pixel_range(min: f64, max: f64, size: u32) -> Range<u32> {
+    // also catches `NaN`s, e.g., of a canvas without an extent
+    if min.is_nan() || max.is_nan() || min > max {
+        return 0..0;
+    }
+
+    let size = f64::from(size);
+
+    let start = (min - 0.5).ceil().clamp(0., size);
+    let end = ((max - 0.5).floor() + 1.).clamp(start, size);
+
+    (start as u32)..(end as u32)
+}
+
+fn pixel_center(x: u32, y: u32) -> Coordinate2D {
+    Coordinate2D::new(f64::from(x) + 0.5, f64::from(y) + 0.5)
+}
+
+fn distance(a: Coordinate2D, b: Coordinate2D) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+fn distance_to_segment(pixel: Coordinate2D, (a, b): (Coordinate2D, Coordinate2D)) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_squared = dx * dx + dy * dy;
+
+    if length_squared < f64::EPSILON {
+        return distance(pixel, a);
+    }
+
+    let t = (((pixel.x - a.x) * dx + (pixel.y - a.y) * dy) / length_squared).clamp(0., 1.);
+
+    distance(pixel, Coordinate2D::new(a.x + t * dx, a.y + t * dy))
+}
+
+/// The segments of a line string or, if `closed`, of a ring
+fn segments(
+    coordinates: &[Coordinate2D],
+    closed: bool,
+) -> impl Iterator<Item = (Coordinate2D, Coordinate2D)> + '_ {
+    let closing_segment = match (closed, coordinates.first(), coordinates.last()) {
+        (true, Some(first), Some(last)) if first != last => Some((*last, *first)),
+        _ => None,
+    };
+    // a single coordinate is drawn as a degenerated segment
+    let single_coordinate = match coordinates {
+        [coordinate] => Some((*coordinate, *coordinate)),
+        _ => None,
+    };
+
+    coordinates
+        .windows(2)
+        .map(|segment| (segment[0], segment[1]))
+        .chain(closing_segment)
+        .chain(single_coordinate)
+}
+
+/// The pixels of a rectangular section of the canvas that are covered by a shape
+struct Mask {
+    columns: Range<u32>,
+    rows: Range<u32>,
+    covered: Vec<bool>,
+}
+
+impl Mask {
+    fn new(columns: Range<u32>, rows: Range<u32>) -> Self {
+        let size = (columns.end - columns.start) as usize * (rows.end - rows.start) as usize;
+
+        Self {
+            columns,
+            rows,
+            covered: vec![false; size],
+        }
+    }
+
+    fn cover(&mut self, x: u32, y: u32) {
+        let width = (self.columns.end - self.columns.start) as usize;
+        let index = (y - self.rows.start) as usize * width + (x - self.columns.start) as usize;
+        self.covered[index] = true;
+    }
+
+    fn cover_where(&mut self, predicate: impl Fn(Coordinate2D) -> bool) {
+        for y in self.rows.clone() {
+            for x in self.columns.clone() {
+                if predicate(pixel_center(x, y)) {
+                    self.cover(x, y);
+                }
+            }
+        }
+    }
+
+    /// Covers all pixels within `half_width` of any of the `segments`
+    fn cover_segments(
+        &mut self,
+        segments: impl Iterator<Item = (Coordinate2D, Coordinate2D)>,
+        half_width: f64,
+    ) {
+        for (a, b) in segments {
+            let columns = intersect(
+                &self.columns,
+                pixel_range(
+                    a.x.min(b.x) - half_width,
+                    a.x.max(b.x) + half_width,
+                    u32::MAX,
+                ),
+            );
+            let rows = intersect(
+                &self.rows,
+                pixel_range(
+                    a.y.min(b.y) - half_width,
+                    a.y.max(b.y) + half_width,
+                    u32::MAX,
+                ),
+            );
+
+            for y in rows {
+                for x in columns.clone() {
+                    if distance_to_segment(pixel_center(x, y), (a, b)) <= half_width {
+                        self.cover(x, y);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Covers all pixels inside the area enclosed by the `segments` using the even-odd rule
+    fn cover_scanlines(&mut self, segments: impl Iterator<Item = (Coordinate2D, Coordinate2D)>) {
+        let segments: Vec<_> = segments.collect();
+        let mut intersections = Vec::new();
+
+        for y in self.rows.clone() {
+            let center_y = f64::from(y) + 0.5;
+
+            intersections.clear();
+            intersections.extend(
+                segments
+                    .iter()
+                    .filter(|(a, b)| (a.y <= center_y) != (b.y <= center_y))
+                    .map(|(a, b)| a.x + (center_y - a.y) * (b.x - a.x) / (b.y - a.y)),
+            );
+            intersections.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+            for span in intersections.chunks_exact(2) {
+                // the right edge is exclusive
+                let columns = intersect(
+                    &self.columns,
+                    pixel_range(span[0], span[1] - f64::EPSILON, u32::MAX),
+                );
+
+                for x in columns {
+                    self.cover(x, y);
+                }
+            }
+        }
+    }
+
+    fn covered_pixels(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        let columns = self.columns.clone();
+        let rows = self.rows.clone();
+
+        rows.flat_map(move |y| columns.clone().map(move |x| (x, y)))
+            .zip(&self.covered)
+            .filter_map(|(pixel, covered)| covered.then(|| pixel))
+    }
+}
+
+fn intersect(a: &Range<u32>, b: Range<u32>) -> Range<u32> {
+    let start = a.start.max(b.start);
+    start..a.end.min(b.end).max(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas() -> Canvas {
+        Canvas::new(
+            10,
+            10,
+            BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
+        )
+    }
+
+    fn covered(canvas: &Canvas) -> Vec<(u32, u32)> {
+        (0..canvas.height())
+            .flat_map(|y| (0..canvas.width()).map(move |x| (x, y)))
+            .filter(|(x, y)| canvas.pixel(*x, *y) != RgbaColor::transparent())
+            .collect()
+    }
+
+    #[test]
+    fn point() {
+        let mut canvas = canvas();
+
+        canvas.draw_point(
+            (5., 5.).into(),
+            1.,
+            RgbaColor::white(),
+            0.,
+            RgbaColor::black(),
+        );
+
+        assert_eq!(covered(&canvas), vec![(4, 4), (5, 4), (4, 5), (5, 5)]);
+        assert_eq!(canvas.pixel(4, 4), RgbaColor::white());
+    }
+
+    #[test]
+    fn line_string() {
+        let mut canvas = canvas();
+
+        canvas.draw_line_string(
+            &[(0.5, 9.5).into(), (3.5, 9.5).into(), (3.5, 7.5).into()],
+            1.,
+            RgbaColor::black(),
+        );
+
+        assert_eq!(
+            covered(&canvas),
+            vec![(0, 0), (1, 0), (2, 0), (3, 0), (3, 1), (3, 2)]
+        );
+    }
+
+    #[test]
+    fn polygon_with_hole() {
+        let mut canvas = canvas();
+
+        canvas.draw_polygon(
+            &[
+                vec![
+                    (0., 7.).into(),
+                    (3., 7.).into(),
+                    (3., 10.).into(),
+                    (0., 10.).into(),
+                    (0., 7.).into(),
+                ],
+                vec![
+                    (1., 8.).into(),
+                    (2., 8.).into(),
+                    (2., 9.).into(),
+                    (1., 9.).into(),
+                    (1., 8.).into(),
+                ],
+            ],
+            RgbaColor::white(),
+            0.,
+            RgbaColor::black(),
+        );
+
+        assert_eq!(
+            covered(&canvas),
+            vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (0, 1),
+                (2, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn blending() {
+        let mut canvas = canvas();
+
+        canvas.draw_point(
+            (0.5, 9.5).into(),
+            0.5,
+            RgbaColor::white(),
+            0.,
+            RgbaColor::black(),
+        );
+        canvas.draw_point(
+            (0.5, 9.5).into(),
+            0.5,
+            RgbaColor::black().with_alpha(128),
+            0.,
+            RgbaColor::black(),
+        );
+
+        assert_eq!(canvas.pixel(0, 0), RgbaColor::new(127, 127, 127, 255));
+    }
+
+    #[test]
+    fn encode() {
+        let mut canvas = canvas();
+
+        canvas.draw_point(
+            (5., 5.).into(),
+            2.,
+            RgbaColor::white(),
+            1.,
+            RgbaColor::black(),
+        );
+
+        let bytes = canvas.to_image_bytes(ImageEncoding::Png).unwrap();
+
+        assert_eq!(&bytes[1..4], b"PNG");
+    }
+}
@@ -250,12 +250,12 @@ impl Colorizer {
 
         match self {
             Self::LinearGradient {
-                breakpoints: _,
+                breakpoints,
                 no_data_color,
                 default_color,
             }
             | Self::LogarithmicGradient {
-                breakpoints: _,
+                breakpoints,
                 no_data_color,
                 default_color,
             } => {
@@ -263,6 +263,8 @@ impl Colorizer {
 
                 ColorMapper::ColorTable {
                     color_table,
+                    breakpoints,
+                    logarithmic: matches!(self, Self::LogarithmicGradient { .. }),
                     min_value,
                     max_value,
                     no_data_color: *no_data_color,
@@ -351,6 +353,8 @@ impl Colorizer {
 pub enum ColorMapper<'c> {
     ColorTable {
         color_table: Vec<RgbaColor>,
+        breakpoints: &'c Breakpoints,
+        logarithmic: bool,
         min_value: f64,
         max_value: f64,
         no_data_color: RgbaColor,
@@ -378,6 +382,7 @@ impl<'c> ColorMapper<'c> {
                 max_value,
                 no_data_color,
                 default_color,
+                ..
             } => {
                 let value: f64 = value.as_();
                 if f64::is_nan(value) {
@@ -406,6 +411,70 @@ impl<'c> ColorMapper<'c> {
             ColorMapper::Rgba => value.transmute_to_rgba(),
         }
     }
+
+    /// Map a raster value to a color whose channels in [0, 255] are not rounded to integers.
+    ///
+    /// Gradients are interpolated between their breakpoints instead of using the color table.
+    /// This allows rendering subtle differences of continuous values, e.g., with 16 bits per
+    /// channel or with dithering.
+    pub fn call_continuous<T>(&self, value: T) -> [f64; 4]
+    where
+        T: Pixel + RgbaTransmutable,
+    {
+        match self {
+            ColorMapper::ColorTable {
+                breakpoints,
+                logarithmic,
+                min_value,
+                max_value,
+                no_data_color,
+                default_color,
+                ..
+            } => {
+                let value: f64 = value.as_();
+                if f64::is_nan(value) {
+                    no_data_color.channels()
+                } else if value < *min_value || value > *max_value {
+                    default_color.channels()
+                } else {
+                    interpolate_breakpoints(breakpoints, *logarithmic, value)
+                }
+            }
+            ColorMapper::ColorMap { .. } | ColorMapper::Rgba => self.call(value).channels(),
+        }
+    }
+}
+
+/// Interpolates the color of a `value` between the enclosing breakpoints
+fn interpolate_breakpoints(breakpoints: &[Breakpoint], logarithmic: bool, value: f64) -> [f64; 4] {
+    let next_index = breakpoints
+        .iter()
+        .position(|breakpoint| value <= *breakpoint.value)
+        .unwrap_or(breakpoints.len() - 1)
+        .max(1);
+
+    let prev = &breakpoints[next_index - 1];
+    let next = &breakpoints[next_index];
+
+    let fraction = if logarithmic {
+        (f64::log10(value) - f64::log10(*prev.value))
+            / (f64::log10(*next.value) - f64::log10(*prev.value))
+    } else {
+        (value - *prev.value) / (*next.value - *prev.value)
+    }
+    .clamp(0., 1.);
+
+    let prev_channels = prev.color.channels();
+    let next_channels = next.color.channels();
+
+    let mut channels = [0.; 4];
+    for (channel, (prev, next)) in channels
+        .iter_mut()
+        .zip(prev_channels.iter().zip(next_channels.iter()))
+    {
+        *channel = (1. - fraction) * prev + fraction * next;
+    }
+    channels
 }
 
 /// A container type for breakpoints that specify a value to color mapping
@@ -514,6 +583,18 @@ impl RgbaColor {
         RgbaColor::new(255, 0, 255, 255)
     }
 
+    /// Returns a copy of this color with the given `alpha` value
+    #[must_use]
+    pub fn with_alpha(self, alpha: u8) -> Self {
+        let [r, g, b, _] = self.0;
+        RgbaColor([r, g, b, alpha])
+    }
+
+    /// The red, green, blue and alpha channels as floating point values in [0, 255]
+    pub fn channels(self) -> [f64; 4] {
+        self.0.map(f64::from)
+    }
+
     /// Adds another color with a factor in [0, 1] to this color.
     /// The current color remains in (1 - factor)
     ///
@@ -612,6 +693,41 @@ mod tests {
         assert_eq!(color_table[4], RgbaColor::white());
     }
 
+    #[test]
+    fn continuous_colors() {
+        let colorizer = Colorizer::linear_gradient(
+            vec![
+                (0.0, RgbaColor::black()).try_into().unwrap(),
+                (10.0, RgbaColor::new(100, 100, 100, 255))
+                    .try_into()
+                    .unwrap(),
+                (20.0, RgbaColor::white()).try_into().unwrap(),
+            ],
+            RgbaColor::transparent(),
+            RgbaColor::pink(),
+        )
+        .unwrap();
+
+        let color_mapper = colorizer.create_color_mapper();
+
+        assert_eq!(color_mapper.call_continuous(0.), [0., 0., 0., 255.]);
+        assert_eq!(color_mapper.call_continuous(1.), [10., 10., 10., 255.]);
+        assert_eq!(color_mapper.call_continuous(10.), [100., 100., 100., 255.]);
+        assert_eq!(
+            color_mapper.call_continuous(15.),
+            [177.5, 177.5, 177.5, 255.]
+        );
+        assert_eq!(color_mapper.call_continuous(20.), [255., 255., 255., 255.]);
+        assert_eq!(
+            color_mapper.call_continuous(21.),
+            RgbaColor::pink().channels()
+        );
+        assert_eq!(
+            color_mapper.call_continuous(f64::NAN),
+            RgbaColor::transparent().channels()
+        );
+    }
+
     #[test]
     fn serialized_palette() {
         let colorizer = Colorizer::palette(
@@ -1,9 +1,11 @@
+mod canvas;
 mod colorizer;
 mod into_lossy;
 mod rgba_transmutable;
 mod to_png;
 
+pub use canvas::Canvas;
 pub use colorizer::{Breakpoints, Colorizer, RgbaColor};
 pub use into_lossy::LossyInto;
 pub use rgba_transmutable::RgbaTransmutable;
-pub use to_png::ToPng;
+pub use to_png::{ImageEncoding, PngBitDepth, PngOptions, ToPng};
@@ -6,25 +6,121 @@ use crate::raster::{
 use crate::util::Result;
 use crate::{error, raster::EmptyGrid2D};
 use crate::{
-    operations::image::{Colorizer, RgbaTransmutable},
+    operations::image::{Colorizer, RgbaColor, RgbaTransmutable},
     raster::GridOrEmpty,
 };
-use image::{DynamicImage, ImageBuffer, ImageFormat, RgbaImage};
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::{WebPEncoder, WebPQuality};
+use image::{ColorType, DynamicImage, ImageBuffer, ImageFormat, RgbaImage};
 
 pub trait ToPng {
     /// Outputs png bytes of an image of size width x height
-    fn to_png(&self, width: u32, height: u32, colorizer: &Colorizer) -> Result<Vec<u8>>;
+    fn to_png(&self, width: u32, height: u32, colorizer: &Colorizer) -> Result<Vec<u8>> {
+        self.to_png_with_options(width, height, colorizer, PngOptions::default())
+    }
+
+    /// Outputs png bytes of an image of size width x height that is rendered according to `options`
+    fn to_png_with_options(
+        &self,
+        width: u32,
+        height: u32,
+        colorizer: &Colorizer,
+        options: PngOptions,
+    ) -> Result<Vec<u8>> {
+        self.to_image_bytes(width, height, colorizer, options, ImageEncoding::Png)
+    }
+
+    /// Outputs the bytes of an image of size width x height that is rendered according to `options`
+    /// and encoded as `encoding`
+    fn to_image_bytes(
+        &self,
+        width: u32,
+        height: u32,
+        colorizer: &Colorizer,
+        options: PngOptions,
+        encoding: ImageEncoding,
+    ) -> Result<Vec<u8>>;
+}
+
+/// The encoding of a rendered image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageEncoding {
+    Png,
+    /// Lossy JPEG with a quality in [1, 100], drops the alpha channel
+    Jpeg {
+        quality: u8,
+    },
+    /// Lossy WebP with a quality in [0, 100]
+    WebP {
+        quality: u8,
+    },
+}
+
+impl ImageEncoding {
+    fn name(self) -> &'static str {
+        match self {
+            ImageEncoding::Png => "PNG",
+            ImageEncoding::Jpeg { .. } => "JPEG",
+            ImageEncoding::WebP { .. } => "WebP",
+        }
+    }
+}
+
+/// Options for rendering a raster to a PNG image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PngOptions {
+    pub bit_depth: PngBitDepth,
+    /// Overrides the alpha value of the colorizer's no data color, e.g., `0` for transparent no data
+    pub no_data_alpha: Option<u8>,
+    /// Dithers continuous colors instead of rounding them to 8 bits per channel.
+    /// This has no effect for 16 bits per channel.
+    pub dither: bool,
+}
+
+/// The number of bits per channel of a PNG image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PngBitDepth {
+    Eight,
+    Sixteen,
+}
+
+impl Default for PngBitDepth {
+    fn default() -> Self {
+        Self::Eight
+    }
 }
 
-fn image_buffer_to_png_bytes(
-    image_buffer: ImageBuffer<image::Rgba<u8>, Vec<u8>>,
-) -> Result<Vec<u8>> {
+pub(super) fn encode_image(image: DynamicImage, encoding: ImageEncoding) -> Result<Vec<u8>> {
     let mut buffer = Cursor::new(Vec::new());
-    DynamicImage::ImageRgba8(image_buffer)
-        .write_to(&mut buffer, ImageFormat::Png)
-        .map_err(|error| error::Error::Colorizer {
-            details: format!("encoding PNG failed: {}", error),
-        })?;
+
+    let result = match encoding {
+        ImageEncoding::Png => image.write_to(&mut buffer, ImageFormat::Png),
+        ImageEncoding::Jpeg { quality } => {
+            let image = image.to_rgb8();
+            JpegEncoder::new_with_quality(&mut buffer, quality).encode(
+                image.as_raw(),
+                image.width(),
+                image.height(),
+                ColorType::Rgb8,
+            )
+        }
+        ImageEncoding::WebP { quality } => {
+            let image = image.to_rgba8();
+            // lossy encoding is the reason for using WebP at all
+            #[allow(deprecated)]
+            WebPEncoder::new_with_quality(&mut buffer, WebPQuality::lossy(quality)).encode(
+                image.as_raw(),
+                image.width(),
+                image.height(),
+                ColorType::Rgba8,
+            )
+        }
+    };
+
+    result.map_err(|error| error::Error::Colorizer {
+        details: format!("encoding {} failed: {}", encoding.name(), error),
+    })?;
+
     Ok(buffer.into_inner())
 }
 
@@ -32,22 +128,33 @@ impl<P> ToPng for Grid2D<P>
 where
     P: Pixel + RgbaTransmutable,
 {
-    fn to_png(&self, width: u32, height: u32, colorizer: &Colorizer) -> Result<Vec<u8>> {
+    fn to_image_bytes(
+        &self,
+        width: u32,
+        height: u32,
+        colorizer: &Colorizer,
+        options: PngOptions,
+        encoding: ImageEncoding,
+    ) -> Result<Vec<u8>> {
         // TODO: use PNG color palette once it is available
 
         let [.., raster_y_size, raster_x_size] = self.shape.shape_array;
         let scale_x = (raster_x_size as f64) / f64::from(width);
         let scale_y = (raster_y_size as f64) / f64::from(height);
 
-        let image_buffer = if self.no_data_value().is_some() {
+        let image = if self.no_data_value().is_some() {
             let no_data_fn = move |p: P| self.is_no_data(p);
-            create_rgba_image(self, width, height, colorizer, scale_x, scale_y, no_data_fn)
+            create_image(
+                self, width, height, colorizer, options, scale_x, scale_y, no_data_fn,
+            )
         } else {
             let no_data_fn = move |_| false;
-            create_rgba_image(self, width, height, colorizer, scale_x, scale_y, no_data_fn)
+            create_image(
+                self, width, height, colorizer, options, scale_x, scale_y, no_data_fn,
+            )
         };
 
-        image_buffer_to_png_bytes(image_buffer)
+        encode_image(image, encoding)
     }
 }
 
@@ -55,14 +162,32 @@ impl<P> ToPng for EmptyGrid2D<P>
 where
     P: Pixel + RgbaTransmutable,
 {
-    fn to_png(&self, width: u32, height: u32, colorizer: &Colorizer) -> Result<Vec<u8>> {
+    fn to_image_bytes(
+        &self,
+        width: u32,
+        height: u32,
+        colorizer: &Colorizer,
+        options: PngOptions,
+        encoding: ImageEncoding,
+    ) -> Result<Vec<u8>> {
         // TODO: use PNG color palette once it is available
 
-        let no_data_color: image::Rgba<u8> = colorizer.no_data_color().into();
-
-        let image_buffer = ImageBuffer::from_pixel(width, height, no_data_color);
+        let no_data_color = no_data_color(colorizer, options);
+
+        let image = match options.bit_depth {
+            PngBitDepth::Eight => DynamicImage::ImageRgba8(ImageBuffer::from_pixel(
+                width,
+                height,
+                no_data_color.into(),
+            )),
+            PngBitDepth::Sixteen => DynamicImage::ImageRgba16(ImageBuffer::from_pixel(
+                width,
+                height,
+                to_rgba16(no_data_color.channels()),
+            )),
+        };
 
-        image_buffer_to_png_bytes(image_buffer)
+        encode_image(image, encoding)
     }
 }
 
@@ -70,59 +195,150 @@ impl<P> ToPng for GridOrEmpty2D<P>
 where
     P: Pixel + RgbaTransmutable,
 {
-    fn to_png(&self, width: u32, height: u32, colorizer: &Colorizer) -> Result<Vec<u8>> {
+    fn to_image_bytes(
+        &self,
+        width: u32,
+        height: u32,
+        colorizer: &Colorizer,
+        options: PngOptions,
+        encoding: ImageEncoding,
+    ) -> Result<Vec<u8>> {
         match self {
-            GridOrEmpty::Grid(g) => g.to_png(width, height, colorizer),
-            GridOrEmpty::Empty(n) => n.to_png(width, height, colorizer),
+            GridOrEmpty::Grid(g) => g.to_image_bytes(width, height, colorizer, options, encoding),
+            GridOrEmpty::Empty(n) => n.to_image_bytes(width, height, colorizer, options, encoding),
         }
     }
 }
 
-fn create_rgba_image<P: Pixel + RgbaTransmutable, N: Fn(P) -> bool>(
+/// The no data color of the `colorizer` with the alpha value of the `options`, if specified
+fn no_data_color(colorizer: &Colorizer, options: PngOptions) -> RgbaColor {
+    let color = colorizer.no_data_color();
+    options
+        .no_data_alpha
+        .map_or(color, |alpha| color.with_alpha(alpha))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_image<P: Pixel + RgbaTransmutable, N: Fn(P) -> bool>(
     raster_grid: &Grid2D<P>,
     width: u32,
     height: u32,
     colorizer: &Colorizer,
+    options: PngOptions,
     scale_x: f64,
     scale_y: f64,
     is_no_data: N,
-) -> RgbaImage {
+) -> DynamicImage {
     let color_mapper = colorizer.create_color_mapper();
+    let no_data_color = no_data_color(colorizer, options);
 
-    RgbaImage::from_fn(width, height, |x, y| {
+    // returns `None` for no data pixels
+    let value_at = |x: u32, y: u32| {
         let (grid_pixel_x, grid_pixel_y) = image_pixel_to_raster_pixel(x, y, scale_x, scale_y);
-        if let Ok(pixel_value) = raster_grid.get_at_grid_index([grid_pixel_y, grid_pixel_x]) {
-            if is_no_data(pixel_value) {
-                return colorizer.no_data_color().into();
-            }
-
-            color_mapper.call(pixel_value)
-        } else {
-            colorizer.no_data_color()
+        raster_grid
+            .get_at_grid_index([grid_pixel_y, grid_pixel_x])
+            .ok()
+            .filter(|pixel_value| !is_no_data(*pixel_value))
+    };
+
+    match (options.bit_depth, options.dither) {
+        (PngBitDepth::Eight, false) => {
+            DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+                value_at(x, y)
+                    .map_or(no_data_color, |pixel_value| color_mapper.call(pixel_value))
+                    .into()
+            }))
+        }
+        (PngBitDepth::Eight, true) => {
+            DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+                value_at(x, y).map_or_else(
+                    || no_data_color.into(),
+                    |pixel_value| dither(color_mapper.call_continuous(pixel_value), x, y),
+                )
+            }))
         }
-        .into()
-    })
+        (PngBitDepth::Sixteen, _) => {
+            DynamicImage::ImageRgba16(ImageBuffer::from_fn(width, height, |x, y| {
+                to_rgba16(value_at(x, y).map_or_else(
+                    || no_data_color.channels(),
+                    |pixel_value| color_mapper.call_continuous(pixel_value),
+                ))
+            }))
+        }
+    }
+}
+
+/// Rounds continuous color channels in [0, 255] to 8 bits with an ordered (Bayer) dithering.
+///
+/// The threshold only depends on the image pixel, so that adjacent tiles of a map fit together.
+fn dither(channels: [f64; 4], x: u32, y: u32) -> image::Rgba<u8> {
+    const BAYER_MATRIX: [[u8; 4]; 4] =
+        [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+    let threshold = (f64::from(BAYER_MATRIX[(y % 4) as usize][(x % 4) as usize]) + 0.5) / 16. - 0.5;
+
+    image::Rgba(channels.map(|channel| (channel + threshold).round().clamp(0., 255.) as u8))
+}
+
+/// Scales continuous color channels in [0, 255] to 16 bits
+fn to_rgba16(channels: [f64; 4]) -> image::Rgba<u16> {
+    image::Rgba(channels.map(|channel| (channel * 257.).round().clamp(0., 65535.) as u16))
 }
 
 impl<T: Pixel> ToPng for RasterTile2D<T> {
-    fn to_png(&self, width: u32, height: u32, colorizer: &Colorizer) -> Result<Vec<u8>> {
-        self.grid_array.to_png(width, height, colorizer)
+    fn to_image_bytes(
+        &self,
+        width: u32,
+        height: u32,
+        colorizer: &Colorizer,
+        options: PngOptions,
+        encoding: ImageEncoding,
+    ) -> Result<Vec<u8>> {
+        self.grid_array
+            .to_image_bytes(width, height, colorizer, options, encoding)
     }
 }
 
 impl ToPng for TypedRasterTile2D {
-    fn to_png(&self, width: u32, height: u32, colorizer: &Colorizer) -> Result<Vec<u8>> {
+    fn to_image_bytes(
+        &self,
+        width: u32,
+        height: u32,
+        colorizer: &Colorizer,
+        options: PngOptions,
+        encoding: ImageEncoding,
+    ) -> Result<Vec<u8>> {
         match self {
-            TypedRasterTile2D::U8(r) => r.to_png(width, height, colorizer),
-            TypedRasterTile2D::U16(r) => r.to_png(width, height, colorizer),
-            TypedRasterTile2D::U32(r) => r.to_png(width, height, colorizer),
-            TypedRasterTile2D::U64(r) => r.to_png(width, height, colorizer),
-            TypedRasterTile2D::I8(r) => r.to_png(width, height, colorizer),
-            TypedRasterTile2D::I16(r) => r.to_png(width, height, colorizer),
-            TypedRasterTile2D::I32(r) => r.to_png(width, height, colorizer),
-            TypedRasterTile2D::I64(r) => r.to_png(width, height, colorizer),
-            TypedRasterTile2D::F32(r) => r.to_png(width, height, colorizer),
-            TypedRasterTile2D::F64(r) => r.to_png(width, height, colorizer),
+            TypedRasterTile2D::U8(r) => {
+                r.to_image_bytes(width, height, colorizer, options, encoding)
+            }
+            TypedRasterTile2D::U16(r) => {
+                r.to_image_bytes(width, height, colorizer, options, encoding)
+            }
+            TypedRasterTile2D::U32(r) => {
+                r.to_image_bytes(width, height, colorizer, options, encoding)
+            }
+            TypedRasterTile2D::U64(r) => {
+                r.to_image_bytes(width, height, colorizer, options, encoding)
+            }
+            TypedRasterTile2D::I8(r) => {
+                r.to_image_bytes(width, height, colorizer, options, encoding)
+            }
+            TypedRasterTile2D::I16(r) => {
+                r.to_image_bytes(width, height, colorizer, options, encoding)
+            }
+            TypedRasterTile2D::I32(r) => {
+                r.to_image_bytes(width, height, colorizer, options, encoding)
+            }
+            TypedRasterTile2D::I64(r) => {
+                r.to_image_bytes(width, height, colorizer, options, encoding)
+            }
+            TypedRasterTile2D::F32(r) => {
+                r.to_image_bytes(width, height, colorizer, options, encoding)
+            }
+            TypedRasterTile2D::F64(r) => {
+                r.to_image_bytes(width, height, colorizer, options, encoding)
+            }
         }
     }
 }
@@ -152,7 +368,6 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::operations::image::RgbaColor;
     use crate::raster::GridIndexAccessMut;
     use std::convert::TryInto;
 
@@ -290,6 +505,138 @@ mod tests {
         );
     }
 
+    fn black_white_gradient(no_data_color: RgbaColor) -> Colorizer {
+        Colorizer::linear_gradient(
+            vec![
+                (0.0, RgbaColor::black()).try_into().unwrap(),
+                (255.0, RgbaColor::white()).try_into().unwrap(),
+            ],
+            no_data_color,
+            RgbaColor::pink(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn sixteen_bit() {
+        let raster = Grid2D::new([1, 2].into(), vec![0., 127.5], None).unwrap();
+
+        let image_bytes = raster
+            .to_png_with_options(
+                2,
+                1,
+                &black_white_gradient(RgbaColor::transparent()),
+                PngOptions {
+                    bit_depth: PngBitDepth::Sixteen,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let image = image::load_from_memory(&image_bytes).unwrap();
+        let image = image.as_rgba16().unwrap();
+
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 65535]);
+        assert_eq!(image.get_pixel(1, 0).0, [32768, 32768, 32768, 65535]);
+    }
+
+    #[test]
+    fn no_data_alpha() {
+        let raster = Grid2D::new([1, 2].into(), vec![0, 255], Some(0)).unwrap();
+
+        let image_bytes = raster
+            .to_png_with_options(
+                2,
+                1,
+                &black_white_gradient(RgbaColor::pink()),
+                PngOptions {
+                    no_data_alpha: Some(0),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let image = image::load_from_memory(&image_bytes).unwrap().to_rgba8();
+
+        assert_eq!(image.get_pixel(0, 0).0, [255, 0, 255, 0]);
+        assert_eq!(image.get_pixel(1, 0).0, [255, 255, 255, 255]);
+
+        let image_bytes = EmptyGrid2D::<u8>::new([2, 2].into(), 0)
+            .to_png_with_options(
+                2,
+                2,
+                &black_white_gradient(RgbaColor::pink()),
+                PngOptions {
+                    no_data_alpha: Some(0),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let image = image::load_from_memory(&image_bytes).unwrap().to_rgba8();
+
+        assert!(image.pixels().all(|pixel| pixel.0 == [255, 0, 255, 0]));
+    }
+
+    #[test]
+    fn dithering() {
+        let raster = Grid2D::new([4, 4].into(), vec![127.5; 16], None).unwrap();
+
+        let image_bytes = raster
+            .to_png_with_options(
+                4,
+                4,
+                &black_white_gradient(RgbaColor::transparent()),
+                PngOptions {
+                    dither: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let image = image::load_from_memory(&image_bytes).unwrap().to_rgba8();
+
+        let brighter_pixels = image.pixels().filter(|pixel| pixel.0[0] == 128).count();
+        let darker_pixels = image.pixels().filter(|pixel| pixel.0[0] == 127).count();
+
+        assert_eq!(brighter_pixels, 8);
+        assert_eq!(darker_pixels, 8);
+    }
+
+    #[test]
+    fn lossy_encodings() {
+        let raster = Grid2D::new([2, 2].into(), vec![0, 100, 200, 255], None).unwrap();
+        let colorizer = black_white_gradient(RgbaColor::transparent());
+
+        let jpeg_bytes = raster
+            .to_image_bytes(
+                100,
+                100,
+                &colorizer,
+                PngOptions::default(),
+                ImageEncoding::Jpeg { quality: 80 },
+            )
+            .unwrap();
+
+        assert_eq!(image::guess_format(&jpeg_bytes).unwrap(), ImageFormat::Jpeg);
+        let image = image::load_from_memory(&jpeg_bytes).unwrap();
+        assert_eq!((image.width(), image.height()), (100, 100));
+
+        let webp_bytes = raster
+            .to_image_bytes(
+                100,
+                100,
+                &colorizer,
+                PngOptions::default(),
+                ImageEncoding::WebP { quality: 80 },
+            )
+            .unwrap();
+
+        assert_eq!(image::guess_format(&webp_bytes).unwrap(), ImageFormat::WebP);
+        let image = image::load_from_memory(&webp_bytes).unwrap();
+        assert_eq!((image.width(), image.height()), (100, 100));
+    }
+
     #[test]
     fn no_data_tile() {
         let raster = EmptyGrid2D::new([2, 2].into(), 0);
@@ -1,3 +1,4 @@
+pub mod geodesic;
 pub mod image;
 pub mod reproject;
 mod spatial_relation;
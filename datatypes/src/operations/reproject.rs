@@ -465,6 +465,7 @@ pub fn reproject_query<S: AxisAlignedRectangle>(
         spatial_bounds: p_bbox,
         spatial_resolution: p_spatial_resolution,
         time_interval: query.time_interval,
+        spatial_filter: query.spatial_filter,
     })
 }
 
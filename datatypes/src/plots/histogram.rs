@@ -152,6 +152,7 @@ impl Histogram {
                 }
             }
             FeatureDataRef::Category(category_ref) if !category_ref.has_nulls() => {
+                // categories are binned by their dictionary keys
                 for value in category_ref.as_ref().iter().map(|&v| f64::from(v)) {
                     self.handle_data_item(value, false);
                 }
@@ -400,8 +401,8 @@ impl HistogramBuilder {
 mod tests {
     use super::*;
 
-    use crate::primitives::{CategoryDataRef, FloatDataRef, IntDataRef};
-    use arrow::array::{Array, Float64Builder, Int64Builder, UInt8Builder};
+    use crate::primitives::{CategoryDataRef, CategoryKeyType, FloatDataRef, IntDataRef};
+    use arrow::array::{Array, DictionaryArray, Float64Builder, Int64Builder};
     use num_traits::AsPrimitive;
 
     #[test]
@@ -495,15 +496,13 @@ mod tests {
             .build()
             .unwrap();
 
-        let data = {
-            let mut builder = UInt8Builder::new(4);
-            builder.append_slice(&[0, 1, 0, 0, 1]).unwrap();
-            builder.finish()
-        };
+        let data: DictionaryArray<CategoryKeyType> =
+            vec!["a", "b", "a", "a", "b"].into_iter().collect();
 
         histogram
             .add_feature_data(FeatureDataRef::Category(CategoryDataRef::new(
-                data.values(),
+                data.keys().values(),
+                data.values().as_any().downcast_ref().unwrap(),
                 data.data().null_bitmap(),
             )))
             .unwrap();
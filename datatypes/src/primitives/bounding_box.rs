@@ -1,6 +1,6 @@
 use std::convert::TryFrom;
 
-use super::{AxisAlignedRectangle, Coordinate2D, SpatialBounded};
+use super::{AxisAlignedRectangle, Coordinate2D, SpatialBounded, SpatialFilter};
 use crate::error;
 use crate::util::Result;
 use float_cmp::ApproxEq;
@@ -416,6 +416,9 @@ impl BoundingBox2D {
 }
 
 impl AxisAlignedRectangle for BoundingBox2D {
+    /// Vector queries can be refined by a polygon
+    type SpatialFilter = Option<SpatialFilter>;
+
     fn from_min_max(min: Coordinate2D, max: Coordinate2D) -> Result<Self> {
         BoundingBox2D::new(min, max)
     }
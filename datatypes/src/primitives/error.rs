@@ -19,6 +19,25 @@ pub enum PrimitivesError {
     },
     InvalidConversion,
 
+    #[snafu(display("Expected a GeoJSON {} geometry, but found {}", expected, found))]
+    InvalidGeoJsonGeometry {
+        expected: String,
+        found: String,
+    },
+
+    #[snafu(display("A GeoJSON position must have at least two numbers"))]
+    InvalidGeoJsonPosition,
+
+    #[snafu(display("Invalid WKT: {}", reason))]
+    InvalidWkt {
+        reason: String,
+    },
+
+    #[snafu(display("Invalid WKB: {}", reason))]
+    InvalidWkb {
+        reason: String,
+    },
+
     #[snafu(display("Time instance must be between {} and {}, but is {}", min.inner(), max.inner(), is))]
     InvalidTimeInstance {
         min: TimeInstance,
@@ -11,9 +11,13 @@ use std::convert::TryFrom;
 use std::str;
 use std::{marker::PhantomData, slice};
 
+/// The key type of the dictionary-encoded `arrow` arrays of category columns
+pub type CategoryKeyType = arrow::datatypes::Int32Type;
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum FeatureDataType {
+    /// Text with few distinct values that is stored dictionary-encoded
     Category,
     Int,
     Float,
@@ -41,8 +45,8 @@ impl FeatureDataType {
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub enum FeatureData {
-    Category(Vec<u8>), // TODO: add names to categories
-    NullableCategory(Vec<Option<u8>>),
+    Category(Vec<String>),
+    NullableCategory(Vec<Option<String>>),
     Int(Vec<i64>),
     NullableInt(Vec<Option<i64>>),
     Float(Vec<f64>),
@@ -57,8 +61,8 @@ pub enum FeatureData {
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub enum FeatureDataValue {
-    Category(u8),
-    NullableCategory(Option<u8>),
+    Category(String),
+    NullableCategory(Option<String>),
     Int(i64),
     NullableInt(Option<i64>),
     Float(f64),
@@ -118,6 +122,22 @@ impl<'f> FeatureDataRef<'f> {
         }
     }
 
+    /// Number of values
+    pub fn len(&self) -> usize {
+        match self {
+            FeatureDataRef::Text(data_ref) => data_ref.len(),
+            FeatureDataRef::Float(data_ref) => data_ref.len(),
+            FeatureDataRef::Int(data_ref) => data_ref.len(),
+            FeatureDataRef::Category(data_ref) => data_ref.len(),
+            FeatureDataRef::Bool(data_ref) => data_ref.len(),
+            FeatureDataRef::DateTime(data_ref) => data_ref.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Get the `FeatureDataValue` value at position `i`
     pub fn get_unchecked(&self, i: usize) -> FeatureDataValue {
         match self {
@@ -679,14 +699,48 @@ impl<'f> Iterator for DateTimeDataRefFloatOptionIter<'f> {
     }
 }
 
+/// A reference to dictionary-encoded category data
+///
+/// # Examples
+///
+/// ```rust
+/// use geoengine_datatypes::primitives::{CategoryDataRef, CategoryKeyType, DataRef};
+/// use arrow::array::{Array, DictionaryArray};
+///
+/// let array: DictionaryArray<CategoryKeyType> =
+///     vec![Some("forest"), None, Some("water"), Some("forest")].into_iter().collect();
+///
+/// let category_data_ref = CategoryDataRef::new(
+///     array.keys().values(),
+///     array.values().as_any().downcast_ref().unwrap(),
+///     array.data_ref().null_bitmap(),
+/// );
+///
+/// assert_eq!(category_data_ref.len(), 4);
+/// assert_eq!(category_data_ref.categories().len(), 2);
+///
+/// assert_eq!(category_data_ref.category_at(0), Some("forest"));
+/// assert_eq!(category_data_ref.category_at(1), None);
+/// assert_eq!(category_data_ref.category_at(2), Some("water"));
+/// assert_eq!(category_data_ref.as_ref()[3], 0);
+/// ```
+///
 #[derive(Clone, Debug, PartialEq)]
 pub struct CategoryDataRef<'f> {
-    buffer: &'f [u8],
+    keys: &'f [i32],
+    categories: &'f arrow::array::StringArray,
     valid_bitmap: &'f Option<arrow::bitmap::Bitmap>,
 }
 
-impl<'f> DataRef<'f, u8> for CategoryDataRef<'f> {
-    fn json_value(value: &u8) -> serde_json::Value {
+impl<'f> DataRef<'f, i32> for CategoryDataRef<'f> {
+    fn json_values(&'f self) -> Box<dyn Iterator<Item = serde_json::Value> + 'f> {
+        Box::new((0..self.len()).map(move |i| match self.category_at(i) {
+            Some(category) => category.into(),
+            None => serde_json::Value::Null,
+        }))
+    }
+
+    fn json_value(value: &i32) -> serde_json::Value {
         (*value).into()
     }
 
@@ -705,37 +759,36 @@ impl<'f> DataRef<'f, u8> for CategoryDataRef<'f> {
     }
 
     fn get_unchecked(&self, i: usize) -> FeatureDataValue {
+        let category = self.category_at(i).map(ToString::to_string);
+
         if self.has_nulls() {
-            FeatureDataValue::NullableCategory(if self.is_null(i) {
-                None
-            } else {
-                Some(self.as_ref()[i])
-            })
+            FeatureDataValue::NullableCategory(category)
         } else {
-            FeatureDataValue::Category(self.as_ref()[i])
+            FeatureDataValue::Category(category.expect("cannot be null"))
         }
     }
 
-    type StringsIter = NumberDataRefStringIter<'f, Self, u8>;
+    type StringsIter = CategoryDataRefStringIter<'f>;
 
     fn strings_iter(&'f self) -> Self::StringsIter {
-        NumberDataRefStringIter::new(self)
+        Self::StringsIter::new(self)
     }
 
     fn len(&self) -> usize {
-        self.buffer.len()
+        self.keys.len()
     }
 
-    type FloatOptionsIter = NumberDataRefFloatOptionIter<'f, Self, u8>;
+    type FloatOptionsIter = CategoryDataRefFloatOptionIter<'f>;
 
     fn float_options_iter(&'f self) -> Self::FloatOptionsIter {
-        NumberDataRefFloatOptionIter::new(self)
+        Self::FloatOptionsIter::new(self)
     }
 }
 
-impl AsRef<[u8]> for CategoryDataRef<'_> {
-    fn as_ref(&self) -> &[u8] {
-        self.buffer
+/// The keys of the categories, i.e., the positions in the dictionary of distinct categories
+impl AsRef<[i32]> for CategoryDataRef<'_> {
+    fn as_ref(&self) -> &[i32] {
+        self.keys
     }
 }
 
@@ -746,12 +799,93 @@ impl<'f> From<CategoryDataRef<'f>> for FeatureDataRef<'f> {
 }
 
 impl<'f> CategoryDataRef<'f> {
-    pub fn new(buffer: &'f [u8], null_bitmap: &'f Option<arrow::bitmap::Bitmap>) -> Self {
+    pub fn new(
+        keys: &'f [i32],
+        categories: &'f arrow::array::StringArray,
+        null_bitmap: &'f Option<arrow::bitmap::Bitmap>,
+    ) -> Self {
         Self {
-            buffer,
+            keys,
+            categories,
             valid_bitmap: null_bitmap,
         }
     }
+
+    /// Returns the dictionary of categories the keys refer to.
+    /// It may contain a category more than once.
+    pub fn categories(&self) -> &'f arrow::array::StringArray {
+        self.categories
+    }
+
+    /// Returns the category at position `i` or `None` if it is null.
+    /// This method panics if `i` is too large.
+    pub fn category_at(&self, i: usize) -> Option<&'f str> {
+        if self.is_null(i) {
+            return None;
+        }
+
+        Some(self.categories.value(self.keys[i] as usize))
+    }
+}
+
+pub struct CategoryDataRefStringIter<'r> {
+    data_ref: &'r CategoryDataRef<'r>,
+    i: usize,
+}
+
+impl<'r> CategoryDataRefStringIter<'r> {
+    pub fn new(data_ref: &'r CategoryDataRef<'r>) -> Self {
+        Self { data_ref, i: 0 }
+    }
+}
+
+impl<'r> Iterator for CategoryDataRefStringIter<'r> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.i >= self.data_ref.len() {
+            return None;
+        }
+
+        let i = self.i;
+        self.i += 1;
+
+        Some(
+            self.data_ref
+                .category_at(i)
+                .map_or_else(String::default, ToString::to_string),
+        )
+    }
+}
+
+pub struct CategoryDataRefFloatOptionIter<'r> {
+    data_ref: &'r CategoryDataRef<'r>,
+    i: usize,
+}
+
+impl<'r> CategoryDataRefFloatOptionIter<'r> {
+    pub fn new(data_ref: &'r CategoryDataRef<'r>) -> Self {
+        Self { data_ref, i: 0 }
+    }
+}
+
+impl<'r> Iterator for CategoryDataRefFloatOptionIter<'r> {
+    type Item = Option<f64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.i >= self.data_ref.len() {
+            return None;
+        }
+
+        let i = self.i;
+        self.i += 1;
+
+        Some(
+            self.data_ref
+                .category_at(i)
+                .and_then(|category| category.parse().ok()),
+        )
+    }
 }
 
 unsafe fn byte_ptr_to_str<'d>(bytes: *const u8, length: usize) -> &'d str {
@@ -1015,7 +1149,10 @@ impl FeatureDataType {
             Self::Text => arrow::datatypes::DataType::Utf8,
             Self::Float => arrow::datatypes::DataType::Float64,
             Self::Int => arrow::datatypes::DataType::Int64,
-            Self::Category => arrow::datatypes::DataType::UInt8,
+            Self::Category => arrow::datatypes::DataType::Dictionary(
+                Box::new(arrow::datatypes::DataType::Int32),
+                Box::new(arrow::datatypes::DataType::Utf8),
+            ),
             Self::Bool => arrow::datatypes::DataType::Boolean,
             Self::DateTime => arrow::datatypes::DataType::Date64,
         }
@@ -1031,13 +1168,21 @@ impl FeatureDataType {
             Self::Text => Box::new(arrow::array::StringBuilder::new(len)),
             Self::Float => Box::new(arrow::array::Float64Builder::new(len)),
             Self::Int => Box::new(arrow::array::Int64Builder::new(len)),
-            Self::Category => Box::new(arrow::array::UInt8Builder::new(len)),
+            Self::Category => Box::new(category_builder(len)),
             Self::Bool => Box::new(arrow::array::BooleanBuilder::new(len)),
             Self::DateTime => Box::new(arrow::array::Date64Builder::new(len)),
         }
     }
 }
 
+/// Creates a builder for dictionary-encoded category arrays that stores each distinct category once
+fn category_builder(len: usize) -> arrow::array::StringDictionaryBuilder<CategoryKeyType> {
+    arrow::array::StringDictionaryBuilder::new(
+        arrow::array::PrimitiveBuilder::<CategoryKeyType>::new(len),
+        arrow::array::StringBuilder::new(len),
+    )
+}
+
 impl FeatureData {
     pub fn arrow_data_type(&self) -> arrow::datatypes::DataType {
         FeatureDataType::from(self).arrow_data_type()
@@ -1068,6 +1213,81 @@ impl FeatureData {
         self.len() == 0
     }
 
+    /// Creates a nullable column of `data_type` from single values, where `None` is null.
+    ///
+    /// Values that do not match the `data_type` are treated as nulls.
+    pub fn nullable_from_values(
+        data_type: FeatureDataType,
+        values: impl IntoIterator<Item = Option<FeatureDataValue>>,
+    ) -> Self {
+        let values = values.into_iter();
+
+        match data_type {
+            FeatureDataType::Category => FeatureData::NullableCategory(
+                values
+                    .map(|value| match value {
+                        Some(
+                            FeatureDataValue::Category(v)
+                            | FeatureDataValue::NullableCategory(Some(v)),
+                        ) => Some(v),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            FeatureDataType::Int => FeatureData::NullableInt(
+                values
+                    .map(|value| match value {
+                        Some(FeatureDataValue::Int(v) | FeatureDataValue::NullableInt(Some(v))) => {
+                            Some(v)
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            FeatureDataType::Float => FeatureData::NullableFloat(
+                values
+                    .map(|value| match value {
+                        Some(
+                            FeatureDataValue::Float(v) | FeatureDataValue::NullableFloat(Some(v)),
+                        ) => Some(v),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            FeatureDataType::Text => FeatureData::NullableText(
+                values
+                    .map(|value| match value {
+                        Some(
+                            FeatureDataValue::Text(v) | FeatureDataValue::NullableText(Some(v)),
+                        ) => Some(v),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            FeatureDataType::Bool => FeatureData::NullableBool(
+                values
+                    .map(|value| match value {
+                        Some(
+                            FeatureDataValue::Bool(v) | FeatureDataValue::NullableBool(Some(v)),
+                        ) => Some(v),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            FeatureDataType::DateTime => FeatureData::NullableDateTime(
+                values
+                    .map(|value| match value {
+                        Some(
+                            FeatureDataValue::DateTime(v)
+                            | FeatureDataValue::NullableDateTime(Some(v)),
+                        ) => Some(v),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
     /// Creates an `arrow` array builder.
     ///
     /// # Errors
@@ -1121,14 +1341,20 @@ impl FeatureData {
                 Box::new(builder)
             }
             Self::Category(v) => {
-                let mut builder = arrow::array::UInt8Builder::new(v.len());
-                builder.append_slice(v)?;
+                let mut builder = category_builder(v.len());
+                for category in v {
+                    builder.append(category)?;
+                }
                 Box::new(builder)
             }
             Self::NullableCategory(v) => {
-                let mut builder = arrow::array::UInt8Builder::new(v.len());
-                for &float_option in v {
-                    builder.append_option(float_option)?;
+                let mut builder = category_builder(v.len());
+                for category_option in v {
+                    if let Some(category) = category_option {
+                        builder.append(category)?;
+                    } else {
+                        builder.append_null()?;
+                    }
                 }
                 Box::new(builder)
             }
@@ -1246,7 +1472,10 @@ impl<'s> TryFrom<&'s FeatureDataValue> for &'s str {
 
     fn try_from(value: &FeatureDataValue) -> Result<&str, Self::Error> {
         Ok(match value {
-            FeatureDataValue::Text(v) | FeatureDataValue::NullableText(Some(v)) => v.as_ref(),
+            FeatureDataValue::Text(v)
+            | FeatureDataValue::NullableText(Some(v))
+            | FeatureDataValue::Category(v)
+            | FeatureDataValue::NullableCategory(Some(v)) => v.as_ref(),
             _ => return Err(crate::collections::FeatureCollectionError::WrongDataType),
         })
     }
@@ -1347,6 +1576,22 @@ mod tests {
         assert_eq!(from_dates, from_dates_cmp);
     }
 
+    #[test]
+    fn nullable_from_values() {
+        assert_eq!(
+            FeatureData::nullable_from_values(
+                FeatureDataType::Int,
+                vec![
+                    Some(FeatureDataValue::Int(1)),
+                    None,
+                    Some(FeatureDataValue::NullableInt(Some(3))),
+                    Some(FeatureDataValue::Text("4".to_string())),
+                ]
+            ),
+            FeatureData::NullableInt(vec![Some(1), None, Some(3), None])
+        );
+    }
+
     #[test]
     fn float_options_iter() {
         let collection = DataCollection::from_slices(
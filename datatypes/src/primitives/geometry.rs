@@ -1,5 +1,8 @@
 use crate::collections::VectorDataType;
-use crate::primitives::{BoundingBox2D, MultiLineString, MultiPoint, MultiPolygon, NoGeometry};
+use crate::primitives::{
+    BoundingBox2D, Coordinate2D, MultiLineString, MultiPoint, MultiPolygon, NoGeometry,
+    PrimitivesError, SpatialFilter,
+};
 
 use crate::error::Error;
 use serde::{Deserialize, Serialize};
@@ -19,10 +22,46 @@ pub trait Geometry: Clone + Debug + Send + Sync + TryFrom<TypedGeometry, Error =
 
     /// Is the geometry overlapping the `BoundingBox2D`?
     fn intersects_bbox(&self, bbox: &BoundingBox2D) -> bool;
+
+    /// Is the geometry overlapping the polygon of the `SpatialFilter`?
+    fn intersects_spatial_filter(&self, spatial_filter: &SpatialFilter) -> bool;
 }
 
 pub trait GeometryRef: Into<geojson::Geometry> {}
 
+/// The type name of a `GeoJSON` geometry, e.g., for error messages
+pub(crate) fn geo_json_type_name(value: &geojson::Value) -> &'static str {
+    match value {
+        geojson::Value::Point(_) => "Point",
+        geojson::Value::MultiPoint(_) => "MultiPoint",
+        geojson::Value::LineString(_) => "LineString",
+        geojson::Value::MultiLineString(_) => "MultiLineString",
+        geojson::Value::Polygon(_) => "Polygon",
+        geojson::Value::MultiPolygon(_) => "MultiPolygon",
+        geojson::Value::GeometryCollection(_) => "GeometryCollection",
+    }
+}
+
+/// Converts a `GeoJSON` position into a coordinate and ignores any further dimensions, like the altitude
+pub(crate) fn coordinate_from_geo_json_position(
+    position: &[f64],
+) -> Result<Coordinate2D, PrimitivesError> {
+    match *position {
+        [x, y, ..] => Ok(Coordinate2D::new(x, y)),
+        _ => Err(PrimitivesError::InvalidGeoJsonPosition),
+    }
+}
+
+/// Converts the `GeoJSON` positions of a line or ring into coordinates
+pub(crate) fn coordinates_from_geo_json_positions(
+    positions: &[Vec<f64>],
+) -> Result<Vec<Coordinate2D>, PrimitivesError> {
+    positions
+        .iter()
+        .map(|position| coordinate_from_geo_json_position(position))
+        .collect()
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TypedGeometry {
     Data(NoGeometry),
@@ -1,3 +1,6 @@
+use super::{Unit, UnitConversion};
+use crate::error;
+use crate::util::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 use std::fmt;
@@ -22,6 +25,37 @@ impl Measurement {
             classes,
         })
     }
+
+    /// The physical unit of a continuous measurement
+    ///
+    /// # Errors
+    /// Fails if the measurement has no unit or if the unit is not part of the unit registry
+    ///
+    pub fn physical_unit(&self) -> Result<&'static Unit> {
+        match self {
+            Self::Continuous(ContinuousMeasurement {
+                unit: Some(unit), ..
+            }) => Unit::from_symbol(unit),
+            _ => Err(error::Error::MeasurementWithoutUnit),
+        }
+    }
+
+    /// The conversion of values of this measurement to values in the `target_unit`
+    pub fn unit_conversion(&self, target_unit: &str) -> Result<UnitConversion> {
+        self.physical_unit()?
+            .conversion_to(Unit::from_symbol(target_unit)?)
+    }
+
+    /// The same measurement in another unit, e.g., for annotating the output of a unit conversion
+    #[must_use]
+    pub fn in_unit(&self, unit: &Unit) -> Self {
+        match self {
+            Self::Continuous(ContinuousMeasurement { measurement, .. }) => {
+                Self::continuous(measurement.clone(), Some(unit.symbol.to_string()))
+            }
+            _ => Self::continuous(unit.name.to_string(), Some(unit.symbol.to_string())),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -141,6 +175,28 @@ mod tests {
         assert_eq!(measurement, deserialized);
     }
 
+    #[test]
+    fn unit_conversion() {
+        let measurement = Measurement::continuous("temperature".into(), Some("°C".into()));
+
+        let conversion = measurement.unit_conversion("K").unwrap();
+        assert!((conversion.convert(20.) - 293.15).abs() < 1e-9);
+
+        assert!(matches!(
+            measurement.unit_conversion("mm/day"),
+            Err(error::Error::IncompatibleUnits { .. })
+        ));
+        assert!(matches!(
+            Measurement::Unitless.unit_conversion("K"),
+            Err(error::Error::MeasurementWithoutUnit)
+        ));
+
+        assert_eq!(
+            measurement.in_unit(Unit::from_symbol("K").unwrap()),
+            Measurement::continuous("temperature".into(), Some("K".into()))
+        );
+    }
+
     #[test]
     fn unitless_serialization() {
         let measurement = Measurement::Unitless;
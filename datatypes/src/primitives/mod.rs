@@ -11,20 +11,25 @@ mod multi_point;
 mod multi_polygon;
 mod no_geometry;
 mod query_rectangle;
+mod scalar;
+mod spatial_filter;
 mod spatial_partition;
 mod spatial_resolution;
 mod spatio_temporal_bounded;
 mod time_instance;
 mod time_interval;
 mod time_step;
+mod unit;
+mod wkb;
+mod wkt;
 
 pub use bounding_box::BoundingBox2D;
 pub use circle::Circle;
 pub use coordinate::Coordinate2D;
 pub(crate) use error::PrimitivesError;
 pub use feature_data::{
-    BoolDataRef, CategoryDataRef, DataRef, DateTimeDataRef, FeatureData, FeatureDataRef,
-    FeatureDataType, FeatureDataValue, FloatDataRef, IntDataRef, TextDataRef,
+    BoolDataRef, CategoryDataRef, CategoryKeyType, DataRef, DateTimeDataRef, FeatureData,
+    FeatureDataRef, FeatureDataType, FeatureDataValue, FloatDataRef, IntDataRef, TextDataRef,
 };
 pub use geometry::{Geometry, GeometryRef, TypedGeometry};
 pub use line::Line;
@@ -36,9 +41,14 @@ pub use no_geometry::NoGeometry;
 pub use query_rectangle::{
     PlotQueryRectangle, QueryRectangle, RasterQueryRectangle, VectorQueryRectangle,
 };
+pub use scalar::{ConfidenceInterval, Scalar};
+pub use spatial_filter::SpatialFilter;
 pub use spatial_partition::{AxisAlignedRectangle, SpatialPartition2D, SpatialPartitioned};
 pub use spatial_resolution::SpatialResolution;
 pub use spatio_temporal_bounded::{SpatialBounded, TemporalBounded};
 pub use time_instance::TimeInstance;
 pub use time_interval::TimeInterval;
-pub use time_step::{TimeGranularity, TimeStep, TimeStepIter};
+pub use time_step::{DayOverflowPolicy, TimeGranularity, TimeStep, TimeStepIter};
+pub use unit::{Dimension, Unit, UnitConversion, UNITS};
+pub use wkb::{FromWkb, ToWkb};
+pub use wkt::{FromWkt, ToWkt};
@@ -10,12 +10,14 @@ use snafu::ensure;
 use crate::collections::VectorDataType;
 use crate::error::Error;
 use crate::primitives::{
-    error, BoundingBox2D, GeometryRef, MultiPoint, PrimitivesError, TypedGeometry,
+    error, BoundingBox2D, GeometryRef, MultiPoint, PrimitivesError, SpatialFilter, TypedGeometry,
 };
 use crate::primitives::{Coordinate2D, Geometry};
-use crate::util::arrow::{downcast_array, ArrowTyped};
+use crate::util::arrow::{downcast_array, filter_by_take, ArrowTyped};
 use crate::util::Result;
 
+use super::geometry::{coordinates_from_geo_json_positions, geo_json_type_name};
+
 /// A trait that allows a common access to lines of `MultiLineString`s and its references
 pub trait MultiLineStringAccess {
     type L: AsRef<[Coordinate2D]>;
@@ -67,6 +69,13 @@ impl Geometry for MultiLineString {
 
         false
     }
+    fn intersects_spatial_filter(&self, spatial_filter: &SpatialFilter) -> bool {
+        self.coordinates.iter().any(|line_string| {
+            line_string
+                .windows(2)
+                .any(|line| spatial_filter.intersects_line(&line[0], &line[1]))
+        })
+    }
 }
 
 impl From<&MultiLineString> for geo::MultiLineString<f64> {
@@ -95,6 +104,32 @@ impl TryFrom<TypedGeometry> for MultiLineString {
     }
 }
 
+impl TryFrom<geojson::Value> for MultiLineString {
+    type Error = Error;
+
+    /// Converts a `GeoJSON` `LineString` or `MultiLineString`
+    fn try_from(value: geojson::Value) -> Result<Self, Self::Error> {
+        let lines = match value {
+            geojson::Value::LineString(positions) => vec![positions],
+            geojson::Value::MultiLineString(lines) => lines,
+            other => {
+                return Err(PrimitivesError::InvalidGeoJsonGeometry {
+                    expected: "LineString or MultiLineString".to_string(),
+                    found: geo_json_type_name(&other).to_string(),
+                }
+                .into())
+            }
+        };
+
+        let coordinates = lines
+            .iter()
+            .map(|line| coordinates_from_geo_json_positions(line))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::new(coordinates)
+    }
+}
+
 impl AsRef<[Vec<Coordinate2D>]> for MultiLineString {
     fn as_ref(&self) -> &[Vec<Coordinate2D>] {
         &self.coordinates
@@ -187,42 +222,8 @@ impl ArrowTyped for MultiLineString {
         multi_lines: &Self::ArrowArray,
         filter_array: &BooleanArray,
     ) -> Result<Self::ArrowArray, ArrowError> {
-        use arrow::array::{Array, FixedSizeListArray, Float64Array, ListArray};
-
-        let mut multi_line_builder = Self::arrow_builder(0);
-
-        for multi_line_index in 0..multi_lines.len() {
-            if !filter_array.value(multi_line_index) {
-                continue;
-            }
-
-            let line_builder = multi_line_builder.values();
-
-            let lines_ref = multi_lines.value(multi_line_index);
-            let lines = downcast_array::<ListArray>(&lines_ref);
-
-            for line_index in 0..lines.len() {
-                let coordinate_builder = line_builder.values();
-
-                let coordinates_ref = lines.value(line_index);
-                let coordinates = downcast_array::<FixedSizeListArray>(&coordinates_ref);
-
-                for coordinate_index in 0..(coordinates.len() as usize) {
-                    let floats_ref = coordinates.value(coordinate_index);
-                    let floats: &Float64Array = downcast_array(&floats_ref);
-
-                    coordinate_builder.values().append_slice(floats.values())?;
-
-                    coordinate_builder.append(true)?;
-                }
-
-                line_builder.append(true)?;
-            }
-
-            multi_line_builder.append(true)?;
-        }
-
-        Ok(multi_line_builder.finish())
+        let filtered = filter_by_take(multi_lines, filter_array)?;
+        Ok(filtered.data().clone().into())
     }
 
     fn from_vec(multi_line_strings: Vec<Self>) -> Result<Self::ArrowArray, ArrowError>
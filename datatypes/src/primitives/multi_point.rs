@@ -8,11 +8,16 @@ use snafu::ensure;
 
 use crate::collections::VectorDataType;
 use crate::error::Error;
-use crate::primitives::{error, BoundingBox2D, GeometryRef, PrimitivesError, TypedGeometry};
+use crate::primitives::{
+    error, BoundingBox2D, GeometryRef, PrimitivesError, SpatialFilter, TypedGeometry,
+};
 use crate::primitives::{Coordinate2D, Geometry};
-use crate::util::arrow::{downcast_array, ArrowTyped};
+use crate::util::arrow::{downcast_array, filter_by_take, ArrowTyped};
 use crate::util::Result;
 
+use super::geometry::{
+    coordinate_from_geo_json_position, coordinates_from_geo_json_positions, geo_json_type_name,
+};
 use super::SpatialBounded;
 
 /// A trait that allows a common access to points of `MultiPoint`s and its references
@@ -60,6 +65,12 @@ impl Geometry for MultiPoint {
     fn intersects_bbox(&self, bbox: &BoundingBox2D) -> bool {
         self.coordinates.iter().any(|c| bbox.contains_coordinate(c))
     }
+
+    fn intersects_spatial_filter(&self, spatial_filter: &SpatialFilter) -> bool {
+        self.coordinates
+            .iter()
+            .any(|c| spatial_filter.intersects_coordinate(c))
+    }
 }
 
 impl TryFrom<TypedGeometry> for MultiPoint {
@@ -74,6 +85,29 @@ impl TryFrom<TypedGeometry> for MultiPoint {
     }
 }
 
+impl TryFrom<geojson::Value> for MultiPoint {
+    type Error = Error;
+
+    /// Converts a `GeoJSON` `Point` or `MultiPoint`
+    fn try_from(value: geojson::Value) -> Result<Self, Self::Error> {
+        let coordinates = match value {
+            geojson::Value::Point(position) => vec![coordinate_from_geo_json_position(&position)?],
+            geojson::Value::MultiPoint(positions) => {
+                coordinates_from_geo_json_positions(&positions)?
+            }
+            other => {
+                return Err(PrimitivesError::InvalidGeoJsonGeometry {
+                    expected: "Point or MultiPoint".to_string(),
+                    found: geo_json_type_name(&other).to_string(),
+                }
+                .into())
+            }
+        };
+
+        Self::new(coordinates)
+    }
+}
+
 impl AsRef<[Coordinate2D]> for MultiPoint {
     fn as_ref(&self) -> &[Coordinate2D] {
         &self.coordinates
@@ -167,33 +201,8 @@ impl ArrowTyped for MultiPoint {
         features: &Self::ArrowArray,
         filter_array: &BooleanArray,
     ) -> Result<Self::ArrowArray, ArrowError> {
-        use arrow::array::{Array, FixedSizeListArray, Float64Array};
-
-        let mut new_features = Self::arrow_builder(0);
-
-        for feature_index in 0..features.len() {
-            if filter_array.value(feature_index) {
-                let coordinate_builder = new_features.values();
-
-                let old_coordinates = features.value(feature_index);
-
-                for coordinate_index in 0..features.value_length(feature_index) {
-                    let old_floats_array = downcast_array::<FixedSizeListArray>(&old_coordinates)
-                        .value(coordinate_index as usize);
-
-                    let old_floats: &Float64Array = downcast_array(&old_floats_array);
-
-                    let float_builder = coordinate_builder.values();
-                    float_builder.append_slice(old_floats.values())?;
-
-                    coordinate_builder.append(true)?;
-                }
-
-                new_features.append(true)?;
-            }
-        }
-
-        Ok(new_features.finish())
+        let filtered = filter_by_take(features, filter_array)?;
+        Ok(filtered.data().clone().into())
     }
 
     fn from_vec(multi_points: Vec<Self>) -> Result<Self::ArrowArray, ArrowError>
@@ -10,11 +10,14 @@ use snafu::ensure;
 use crate::collections::VectorDataType;
 use crate::error::Error;
 use crate::primitives::{
-    error, BoundingBox2D, GeometryRef, MultiLineString, PrimitivesError, TypedGeometry,
+    error, BoundingBox2D, GeometryRef, MultiLineString, PrimitivesError, SpatialFilter,
+    TypedGeometry,
 };
 use crate::primitives::{Coordinate2D, Geometry};
-use crate::util::arrow::{downcast_array, ArrowTyped};
+use crate::util::arrow::{downcast_array, filter_by_take, ArrowTyped};
 use crate::util::Result;
+
+use super::geometry::{coordinates_from_geo_json_positions, geo_json_type_name};
 use arrow::datatypes::DataType;
 
 /// A trait that allows a common access to polygons of `MultiPolygon`s and its references
@@ -99,6 +102,13 @@ impl Geometry for MultiPolygon {
 
         false
     }
+    fn intersects_spatial_filter(&self, spatial_filter: &SpatialFilter) -> bool {
+        let geo::MultiPolygon::<f64>(geo_polygons) = self.into();
+
+        geo_polygons
+            .iter()
+            .any(|polygon| spatial_filter.intersects_polygon(polygon))
+    }
 }
 
 impl From<&MultiPolygon> for geo::MultiPolygon<f64> {
@@ -133,6 +143,37 @@ impl TryFrom<TypedGeometry> for MultiPolygon {
     }
 }
 
+impl TryFrom<geojson::Value> for MultiPolygon {
+    type Error = Error;
+
+    /// Converts a `GeoJSON` `Polygon` or `MultiPolygon`
+    fn try_from(value: geojson::Value) -> Result<Self, Self::Error> {
+        let polygons = match value {
+            geojson::Value::Polygon(rings) => vec![rings],
+            geojson::Value::MultiPolygon(polygons) => polygons,
+            other => {
+                return Err(PrimitivesError::InvalidGeoJsonGeometry {
+                    expected: "Polygon or MultiPolygon".to_string(),
+                    found: geo_json_type_name(&other).to_string(),
+                }
+                .into())
+            }
+        };
+
+        let polygons = polygons
+            .iter()
+            .map(|rings| {
+                rings
+                    .iter()
+                    .map(|ring| coordinates_from_geo_json_positions(ring))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::new(polygons)
+    }
+}
+
 impl AsRef<[Polygon]> for MultiPolygon {
     fn as_ref(&self) -> &[Polygon] {
         &self.polygons
@@ -229,51 +270,8 @@ impl ArrowTyped for MultiPolygon {
         multi_polygons: &Self::ArrowArray,
         filter_array: &BooleanArray,
     ) -> Result<Self::ArrowArray, ArrowError> {
-        use arrow::array::{Array, FixedSizeListArray, Float64Array, ListArray};
-
-        let mut multi_polygon_builder = Self::arrow_builder(0);
-
-        for multi_polygon_index in 0..multi_polygons.len() {
-            if !filter_array.value(multi_polygon_index) {
-                continue;
-            }
-
-            let polygon_builder = multi_polygon_builder.values();
-
-            let polygons_ref = multi_polygons.value(multi_polygon_index);
-            let polygons = downcast_array::<ListArray>(&polygons_ref);
-
-            for polygon_index in 0..polygons.len() {
-                let ring_builder = polygon_builder.values();
-
-                let rings_ref = polygons.value(polygon_index);
-                let rings = downcast_array::<ListArray>(&rings_ref);
-
-                for ring_index in 0..rings.len() {
-                    let coordinate_builder = ring_builder.values();
-
-                    let coordinates_ref = rings.value(ring_index);
-                    let coordinates = downcast_array::<FixedSizeListArray>(&coordinates_ref);
-
-                    for coordinate_index in 0..(coordinates.len() as usize) {
-                        let floats_ref = coordinates.value(coordinate_index);
-                        let floats: &Float64Array = downcast_array(&floats_ref);
-
-                        coordinate_builder.values().append_slice(floats.values())?;
-
-                        coordinate_builder.append(true)?;
-                    }
-
-                    ring_builder.append(true)?;
-                }
-
-                polygon_builder.append(true)?;
-            }
-
-            multi_polygon_builder.append(true)?;
-        }
-
-        Ok(multi_polygon_builder.finish())
+        let filtered = filter_by_take(multi_polygons, filter_array)?;
+        Ok(filtered.data().clone().into())
     }
 
     fn from_vec(multi_polygons: Vec<Self>) -> Result<Self::ArrowArray, ArrowError>
@@ -9,7 +9,9 @@ use serde_json::Value;
 
 use crate::collections::VectorDataType;
 use crate::error::Error;
-use crate::primitives::{BoundingBox2D, Geometry, GeometryRef, PrimitivesError, TypedGeometry};
+use crate::primitives::{
+    BoundingBox2D, Geometry, GeometryRef, PrimitivesError, SpatialFilter, TypedGeometry,
+};
 use crate::util::arrow::ArrowTyped;
 
 /// A zero-sized placeholder struct for situations where a geometry is necessary.
@@ -24,6 +26,10 @@ impl Geometry for NoGeometry {
     fn intersects_bbox(&self, _bbox: &BoundingBox2D) -> bool {
         true
     }
+
+    fn intersects_spatial_filter(&self, _spatial_filter: &SpatialFilter) -> bool {
+        true
+    }
 }
 
 impl GeometryRef for NoGeometry {}
@@ -40,6 +46,18 @@ impl TryFrom<TypedGeometry> for NoGeometry {
     }
 }
 
+impl TryFrom<geojson::Value> for NoGeometry {
+    type Error = Error;
+
+    fn try_from(value: geojson::Value) -> Result<Self, Self::Error> {
+        Err(PrimitivesError::InvalidGeoJsonGeometry {
+            expected: "null".to_string(),
+            found: super::geometry::geo_json_type_name(&value).to_string(),
+        }
+        .into())
+    }
+}
+
 impl ArrowTyped for NoGeometry {
     type ArrowArray = NoArrowArray;
     type ArrowBuilder = NoArrowArray;
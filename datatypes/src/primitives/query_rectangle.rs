@@ -4,19 +4,30 @@ use super::{
 };
 use serde::{Deserialize, Serialize};
 
-/// A spatio-temporal rectangle with a specified resolution
-#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// A spatio-temporal rectangle with a specified resolution.
+///
+/// Vector queries may refine their bounds by a `SpatialFilter`. Since it is only a refinement,
+/// processors can drop it, e.g., if they need data outside of it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryRectangle<SpatialBounds: AxisAlignedRectangle> {
     pub spatial_bounds: SpatialBounds,
     pub time_interval: TimeInterval,
     pub spatial_resolution: SpatialResolution,
+    #[serde(default, skip_serializing_if = "is_unfiltered")]
+    pub spatial_filter: SpatialBounds::SpatialFilter,
 }
 
 pub type VectorQueryRectangle = QueryRectangle<BoundingBox2D>;
 pub type RasterQueryRectangle = QueryRectangle<SpatialPartition2D>;
 pub type PlotQueryRectangle = QueryRectangle<BoundingBox2D>;
 
+impl Copy for RasterQueryRectangle {}
+
+fn is_unfiltered<F: Default + PartialEq>(spatial_filter: &F) -> bool {
+    *spatial_filter == F::default()
+}
+
 impl SpatialPartitioned for QueryRectangle<BoundingBox2D> {
     fn spatial_partition(&self) -> SpatialPartition2D {
         SpatialPartition2D::with_bbox_and_resolution(self.spatial_bounds, self.spatial_resolution)
@@ -35,6 +46,7 @@ impl From<QueryRectangle<BoundingBox2D>> for QueryRectangle<SpatialPartition2D>
             spatial_bounds: value.spatial_partition(),
             time_interval: value.time_interval,
             spatial_resolution: value.spatial_resolution,
+            spatial_filter: (),
         }
     }
 }
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use super::Measurement;
+
+/// A single value that summarizes the data of a query, e.g., a total area or a correlation coefficient
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scalar {
+    /// `None` if there was no data to compute the value from
+    pub value: Option<f64>,
+    /// What the value measures, including its unit
+    pub measurement: Measurement,
+    /// The number of values that the scalar was computed from
+    pub sample_size: u64,
+    /// The uncertainty of the value if it is an estimate
+    pub confidence_interval: Option<ConfidenceInterval>,
+}
+
+impl Scalar {
+    /// A scalar of the `sample_size` values or an empty one if there were no valid values, i.e., `value` is `NaN`
+    pub fn new(value: f64, measurement: Measurement, sample_size: u64) -> Self {
+        Self {
+            value: (!value.is_nan() && sample_size > 0).then(|| value),
+            measurement,
+            sample_size,
+            confidence_interval: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_confidence_interval(mut self, confidence_interval: ConfidenceInterval) -> Self {
+        self.confidence_interval = Some(confidence_interval);
+        self
+    }
+}
+
+/// An interval that contains the true value of an estimate with a given probability
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfidenceInterval {
+    /// The probability, e.g., `0.95` for a 95 % confidence interval
+    pub level: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialization() {
+        let scalar = Scalar::new(
+            42.,
+            Measurement::continuous("area".to_string(), Some("m²".to_string())),
+            3,
+        )
+        .with_confidence_interval(ConfidenceInterval {
+            level: 0.95,
+            lower: 40.,
+            upper: 44.,
+        });
+
+        assert_eq!(
+            serde_json::to_value(&scalar).unwrap(),
+            serde_json::json!({
+                "value": 42.0,
+                "measurement": {
+                    "type": "continuous",
+                    "measurement": "area",
+                    "unit": "m²"
+                },
+                "sampleSize": 3,
+                "confidenceInterval": {
+                    "level": 0.95,
+                    "lower": 40.0,
+                    "upper": 44.0
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn it_has_no_value_without_data() {
+        assert_eq!(Scalar::new(f64::NAN, Measurement::Unitless, 3).value, None);
+        assert_eq!(Scalar::new(0., Measurement::Unitless, 0).value, None);
+    }
+}
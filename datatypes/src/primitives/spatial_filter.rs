@@ -0,0 +1,242 @@
+use geo::intersects::Intersects;
+use serde::{Deserialize, Serialize};
+
+use crate::primitives::{BoundingBox2D, Coordinate2D, MultiPolygon, MultiPolygonAccess, ToWkt};
+use crate::spatial_reference::SpatialReference;
+
+/// A polygonal area of interest that refines the bounding box of a vector query.
+///
+/// The filter is tagged with its spatial reference, so that consumers can decide whether they
+/// are able to apply it. It is a hint and not a contract: consumers that cannot use it have to
+/// fall back to the query's bounding box.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "SpatialFilterDefinition", into = "SpatialFilterDefinition")]
+pub struct SpatialFilter {
+    spatial_reference: SpatialReference,
+    polygon: MultiPolygon,
+    bounding_box: BoundingBox2D,
+    geo_polygons: Vec<geo::Polygon<f64>>,
+}
+
+/// The serialized representation of a `SpatialFilter`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpatialFilterDefinition {
+    spatial_reference: SpatialReference,
+    polygon: MultiPolygon,
+}
+
+impl SpatialFilter {
+    pub fn new(spatial_reference: SpatialReference, polygon: MultiPolygon) -> Self {
+        let bounding_box = BoundingBox2D::from_coord_ref_iter(
+            polygon
+                .polygons()
+                .iter()
+                .flat_map(|polygon| polygon.iter().flat_map(|ring| ring.iter())),
+        )
+        .expect("a `MultiPolygon` is never empty");
+
+        let geo::MultiPolygon(geo_polygons) = (&polygon).into();
+
+        Self {
+            spatial_reference,
+            polygon,
+            bounding_box,
+            geo_polygons,
+        }
+    }
+
+    pub fn spatial_reference(&self) -> SpatialReference {
+        self.spatial_reference
+    }
+
+    pub fn polygon(&self) -> &MultiPolygon {
+        &self.polygon
+    }
+
+    /// The bounding box of the filter polygon
+    pub fn bounding_box(&self) -> BoundingBox2D {
+        self.bounding_box
+    }
+
+    /// Does the filter polygon intersect the `coordinate`? Coordinates on the border intersect.
+    pub fn intersects_coordinate(&self, coordinate: &Coordinate2D) -> bool {
+        if !self.bounding_box.contains_coordinate(coordinate) {
+            return false;
+        }
+
+        let coordinate: geo::Coordinate<f64> = coordinate.into();
+
+        self.geo_polygons
+            .iter()
+            .any(|polygon| polygon.intersects(&coordinate))
+    }
+
+    /// Does the filter polygon intersect the line between `start` and `end`?
+    pub fn intersects_line(&self, start: &Coordinate2D, end: &Coordinate2D) -> bool {
+        let line = geo::Line::new(geo::Coordinate::from(start), geo::Coordinate::from(end));
+
+        self.geo_polygons
+            .iter()
+            .any(|polygon| polygon.intersects(&line))
+    }
+
+    /// Does the filter polygon intersect the `polygon`?
+    pub fn intersects_polygon(&self, polygon: &geo::Polygon<f64>) -> bool {
+        self.geo_polygons
+            .iter()
+            .any(|filter_polygon| filter_polygon.intersects(polygon))
+    }
+}
+
+impl PartialEq for SpatialFilter {
+    fn eq(&self, other: &Self) -> bool {
+        self.spatial_reference == other.spatial_reference && self.polygon == other.polygon
+    }
+}
+
+impl From<SpatialFilterDefinition> for SpatialFilter {
+    fn from(definition: SpatialFilterDefinition) -> Self {
+        Self::new(definition.spatial_reference, definition.polygon)
+    }
+}
+
+impl From<SpatialFilter> for SpatialFilterDefinition {
+    fn from(spatial_filter: SpatialFilter) -> Self {
+        Self {
+            spatial_reference: spatial_filter.spatial_reference,
+            polygon: spatial_filter.polygon,
+        }
+    }
+}
+
+impl TryFrom<&SpatialFilter> for gdal::vector::Geometry {
+    type Error = crate::error::Error;
+
+    fn try_from(spatial_filter: &SpatialFilter) -> Result<Self, Self::Error> {
+        gdal::vector::Geometry::from_wkt(&spatial_filter.polygon.to_wkt())
+            .map_err(crate::error::Error::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{Geometry, MultiLineString, MultiPoint};
+
+    fn triangle_filter() -> SpatialFilter {
+        SpatialFilter::new(
+            SpatialReference::epsg_4326(),
+            MultiPolygon::new(vec![vec![vec![
+                (0.0, 0.0).into(),
+                (10.0, 0.0).into(),
+                (0.0, 10.0).into(),
+                (0.0, 0.0).into(),
+            ]]])
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn bounding_box() {
+        assert_eq!(
+            triangle_filter().bounding_box(),
+            BoundingBox2D::new_unchecked((0.0, 0.0).into(), (10.0, 10.0).into())
+        );
+    }
+
+    #[test]
+    fn to_gdal_geometry() {
+        let geometry = gdal::vector::Geometry::try_from(&triangle_filter()).unwrap();
+
+        assert_eq!(
+            geometry.wkt().unwrap(),
+            "MULTIPOLYGON (((0 0,10 0,0 10,0 0)))"
+        );
+    }
+
+    #[test]
+    fn intersects_points() {
+        let filter = triangle_filter();
+
+        assert!(MultiPoint::new(vec![(1.0, 1.0).into()])
+            .unwrap()
+            .intersects_spatial_filter(&filter));
+        assert!(MultiPoint::new(vec![(9.0, 9.0).into(), (2.0, 2.0).into()])
+            .unwrap()
+            .intersects_spatial_filter(&filter));
+
+        // inside the bounding box, but outside of the triangle
+        assert!(!MultiPoint::new(vec![(9.0, 9.0).into()])
+            .unwrap()
+            .intersects_spatial_filter(&filter));
+    }
+
+    #[test]
+    fn intersects_lines() {
+        let filter = triangle_filter();
+
+        assert!(
+            MultiLineString::new(vec![vec![(-1.0, 1.0).into(), (1.0, 1.0).into()]])
+                .unwrap()
+                .intersects_spatial_filter(&filter)
+        );
+        assert!(
+            !MultiLineString::new(vec![vec![(9.0, 8.0).into(), (8.0, 9.0).into()]])
+                .unwrap()
+                .intersects_spatial_filter(&filter)
+        );
+    }
+
+    #[test]
+    fn intersects_polygons() {
+        let filter = triangle_filter();
+
+        assert!(MultiPolygon::new(vec![vec![vec![
+            (4.0, 4.0).into(),
+            (6.0, 4.0).into(),
+            (6.0, 6.0).into(),
+            (4.0, 6.0).into(),
+            (4.0, 4.0).into(),
+        ]]])
+        .unwrap()
+        .intersects_spatial_filter(&filter));
+
+        assert!(!MultiPolygon::new(vec![vec![vec![
+            (8.0, 8.0).into(),
+            (9.0, 8.0).into(),
+            (9.0, 9.0).into(),
+            (8.0, 9.0).into(),
+            (8.0, 8.0).into(),
+        ]]])
+        .unwrap()
+        .intersects_spatial_filter(&filter));
+    }
+
+    #[test]
+    fn serialization() {
+        let filter = triangle_filter();
+
+        let json = serde_json::to_value(&filter).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "spatialReference": "EPSG:4326",
+                "polygon": {
+                    "polygons": [[[
+                        {"x": 0.0, "y": 0.0},
+                        {"x": 10.0, "y": 0.0},
+                        {"x": 0.0, "y": 10.0},
+                        {"x": 0.0, "y": 0.0}
+                    ]]]
+                }
+            })
+        );
+
+        assert_eq!(
+            serde_json::from_value::<SpatialFilter>(json).unwrap(),
+            filter
+        );
+    }
+}
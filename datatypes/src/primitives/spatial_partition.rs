@@ -1,5 +1,7 @@
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use snafu::prelude::*;
+use std::fmt::Debug;
 
 use crate::error;
 use crate::raster::GridShape2D;
@@ -13,6 +15,17 @@ use super::SpatialResolution;
 
 /// Common trait for axis-parallel boxes
 pub trait AxisAlignedRectangle: Copy {
+    /// A refinement of the box that queries with these spatial bounds may carry.
+    /// The default value means that there is no refinement.
+    type SpatialFilter: Clone
+        + Debug
+        + Default
+        + PartialEq
+        + Send
+        + Sync
+        + Serialize
+        + DeserializeOwned;
+
     /// create a new instance defined by `min` (lower left) and `max` (upper right) coordinate
     fn from_min_max(min: Coordinate2D, max: Coordinate2D) -> Result<Self>;
 
@@ -225,6 +238,9 @@ pub trait SpatialPartitioned {
 }
 
 impl AxisAlignedRectangle for SpatialPartition2D {
+    /// Raster queries cannot be refined
+    type SpatialFilter = ();
+
     fn from_min_max(min: Coordinate2D, max: Coordinate2D) -> Result<Self> {
         SpatialPartition2D::new((min.x, max.y).into(), (max.x, min.y).into())
     }
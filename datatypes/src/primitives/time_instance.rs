@@ -1,6 +1,6 @@
 use crate::primitives::error;
 use crate::util::Result;
-use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
 #[cfg(feature = "postgres")]
 use postgres_types::private::BytesMut;
 #[cfg(feature = "postgres")]
@@ -55,6 +55,50 @@ impl TimeInstance {
             .to_rfc3339()
     }
 
+    /// Formats the instance as RFC 3339 in a display time zone, e.g., `2020-01-01T02:00:00+02:00`
+    pub fn as_rfc3339_with_offset(self, time_zone: &FixedOffset) -> String {
+        // the local date time must be representable as well
+        let offset_millis = i64::from(time_zone.local_minus_utc()) * 1000;
+        let instance = self.clamp(
+            TimeInstance::MIN - offset_millis.min(0),
+            TimeInstance::MAX - offset_millis.max(0),
+        );
+
+        instance
+            .as_utc_date_time()
+            .expect("TimeInstance is not valid")
+            .with_timezone(time_zone)
+            .to_rfc3339()
+    }
+
+    /// Interprets a date time without offset in the given time zone
+    pub fn from_local_date_time(date_time: NaiveDateTime, time_zone: &FixedOffset) -> Result<Self> {
+        let utc_date_time = date_time - *time_zone;
+
+        TimeInstance::from_millis(utc_date_time.timestamp_millis())
+    }
+
+    /// Parses an ISO 8601 date time.
+    ///
+    /// Date times with an offset (e.g., `Z` or `+02:00`) are absolute.
+    /// Date times without offset and plain dates are interpreted in the given time zone.
+    pub fn from_iso8601_with_default_offset(
+        s: &str,
+        time_zone: &FixedOffset,
+    ) -> Result<Self, chrono::ParseError> {
+        // use `from_str` instead of `parse_from_rfc3339` to use a relaxed form of RFC3339 that supports dates BC
+        let error = match DateTime::<FixedOffset>::from_str(s) {
+            Ok(date_time) => return Ok(date_time.with_timezone(&Utc).into()),
+            Err(error) => error,
+        };
+
+        let local_date_time = NaiveDateTime::from_str(s)
+            .or_else(|_| NaiveDate::from_str(s).map(|date| date.and_hms(0, 0, 0)))
+            .map_err(|_| error)?;
+
+        Ok(TimeInstance::from(local_date_time - *time_zone))
+    }
+
     pub const fn inner(self) -> i64 {
         self.0
     }
@@ -63,6 +107,29 @@ impl TimeInstance {
         Self::from(chrono::offset::Utc::now())
     }
 
+    /// Returns whether the instance is `TimeInstance::MIN`, i.e., the unbounded past
+    pub fn is_min(self) -> bool {
+        self == Self::MIN
+    }
+
+    /// Returns whether the instance is `TimeInstance::MAX`, i.e., the unbounded future
+    pub fn is_max(self) -> bool {
+        self == Self::MAX
+    }
+
+    /// Adds milliseconds to the instance.
+    ///
+    /// The unbounded instances `MIN` and `MAX` stay unbounded and
+    /// all other results are clamped to the valid range.
+    #[must_use]
+    pub fn saturating_add_millis(self, millis: i64) -> Self {
+        if self.is_min() || self.is_max() {
+            return self;
+        }
+
+        Self(self.0.saturating_add(millis)).clamp(Self::MIN, Self::MAX)
+    }
+
     pub const MIN: Self = TimeInstance::from_millis_unchecked(-8_334_632_851_200_001 + 1);
     pub const MAX: Self = TimeInstance::from_millis_unchecked(8_210_298_412_800_000 - 1);
 }
@@ -154,10 +221,9 @@ impl Sub<i64> for TimeInstance {
 impl FromStr for TimeInstance {
     type Err = chrono::ParseError;
 
+    /// Parses an ISO 8601 date time where date times without an offset are in UTC
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let date_time = DateTime::<chrono::FixedOffset>::parse_from_rfc3339(s)?;
-        let date_time = date_time.with_timezone(&Utc);
-        Ok(date_time.into())
+        Self::from_iso8601_with_default_offset(s, &FixedOffset::east(0))
     }
 }
 
@@ -172,14 +238,20 @@ impl<'de> Deserialize<'de> for TimeInstance {
             type Value = TimeInstance;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("RFC 3339 timestamp string or Unix timestamp integer")
+                formatter.write_str(
+                    "RFC 3339 timestamp string, `-infinity`, `infinity` or Unix timestamp integer",
+                )
             }
 
             fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
             where
                 E: serde::de::Error,
             {
-                TimeInstance::from_str(value).map_err(E::custom)
+                match value {
+                    "-infinity" => Ok(TimeInstance::MIN),
+                    "infinity" | "+infinity" => Ok(TimeInstance::MAX),
+                    _ => TimeInstance::from_str(value).map_err(E::custom),
+                }
             }
 
             fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
@@ -210,4 +282,104 @@ mod tests {
         assert_eq!(TimeInstance::MIN, TimeInstance::from(chrono::MIN_DATETIME));
         assert_eq!(TimeInstance::MAX, TimeInstance::from(chrono::MAX_DATETIME));
     }
+
+    #[test]
+    fn parse_iso8601() {
+        let expected = TimeInstance::from(Utc.ymd(2020, 1, 1).and_hms(12, 0, 0));
+
+        assert_eq!(
+            TimeInstance::from_str("2020-01-01T12:00:00Z").unwrap(),
+            expected
+        );
+        assert_eq!(
+            TimeInstance::from_str("2020-01-01T14:00:00+02:00").unwrap(),
+            expected
+        );
+        assert_eq!(
+            TimeInstance::from_str("2020-01-01T12:00:00").unwrap(),
+            expected
+        );
+        assert_eq!(
+            TimeInstance::from_str("2020-01-01").unwrap(),
+            TimeInstance::from(Utc.ymd(2020, 1, 1).and_hms(0, 0, 0))
+        );
+        assert!(TimeInstance::from_str("2020-01-01 noon").is_err());
+    }
+
+    #[test]
+    fn parse_iso8601_with_default_offset() {
+        let time_zone = FixedOffset::west(5 * 3600);
+        let expected = TimeInstance::from(Utc.ymd(2020, 1, 1).and_hms(17, 0, 0));
+
+        assert_eq!(
+            TimeInstance::from_iso8601_with_default_offset("2020-01-01T12:00:00", &time_zone)
+                .unwrap(),
+            expected
+        );
+        // an explicit offset takes precedence
+        assert_eq!(
+            TimeInstance::from_iso8601_with_default_offset("2020-01-01T17:00:00Z", &time_zone)
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn format_with_offset() {
+        let instance = TimeInstance::from(Utc.ymd(2020, 1, 1).and_hms(12, 0, 0));
+
+        assert_eq!(instance.as_rfc3339(), "2020-01-01T12:00:00+00:00");
+        assert_eq!(
+            instance.as_rfc3339_with_offset(&FixedOffset::east(2 * 3600)),
+            "2020-01-01T14:00:00+02:00"
+        );
+        assert_eq!(
+            TimeInstance::from_str(&instance.as_rfc3339_with_offset(&FixedOffset::west(3600)))
+                .unwrap(),
+            instance
+        );
+    }
+
+    #[test]
+    fn deserialize_unbounded() {
+        assert_eq!(
+            serde_json::from_str::<TimeInstance>("\"-infinity\"").unwrap(),
+            TimeInstance::MIN
+        );
+        assert_eq!(
+            serde_json::from_str::<TimeInstance>("\"infinity\"").unwrap(),
+            TimeInstance::MAX
+        );
+        assert_eq!(
+            serde_json::from_str::<TimeInstance>(
+                &serde_json::to_string(&TimeInstance::MAX).unwrap()
+            )
+            .unwrap(),
+            TimeInstance::MAX
+        );
+    }
+
+    #[test]
+    fn saturating_add_millis() {
+        assert_eq!(
+            TimeInstance::from_millis_unchecked(0).saturating_add_millis(1),
+            TimeInstance::from_millis_unchecked(1)
+        );
+        assert_eq!(
+            TimeInstance::MAX.saturating_add_millis(-1),
+            TimeInstance::MAX
+        );
+        assert_eq!(
+            TimeInstance::MIN.saturating_add_millis(1),
+            TimeInstance::MIN
+        );
+        assert_eq!(
+            (TimeInstance::MAX - 1).saturating_add_millis(i64::MAX),
+            TimeInstance::MAX
+        );
+        assert_eq!(
+            (TimeInstance::MIN + 1).saturating_add_millis(i64::MIN),
+            TimeInstance::MIN
+        );
+    }
 }
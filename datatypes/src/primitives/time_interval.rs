@@ -1,10 +1,11 @@
 use crate::primitives::TimeInstance;
-use crate::util::arrow::{downcast_array, ArrowTyped};
+use crate::util::arrow::{downcast_array, filter_by_take, ArrowTyped};
 use crate::util::Result;
 use crate::{error, util::ranges::value_in_range};
 use arrow::array::{Array, ArrayBuilder, BooleanArray};
 use arrow::datatypes::{DataType, Field};
 use arrow::error::ArrowError;
+use chrono::FixedOffset;
 #[cfg(feature = "postgres")]
 use postgres_types::{FromSql, ToSql};
 use serde::{Deserialize, Serialize};
@@ -212,6 +213,9 @@ impl TimeInterval {
         other == self
             || value_in_range(self.start, other.start, other.end)
             || value_in_range(other.start, self.start, self.end)
+            // an unbounded end also covers the instant at the end of time
+            || (self.end.is_max() && other.start.is_max())
+            || (other.end.is_max() && self.start.is_max())
     }
 
     /// Unites this interval with another one.
@@ -256,6 +260,21 @@ impl TimeInterval {
         self.end
     }
 
+    /// Returns whether the interval is valid since the beginning of time
+    pub fn is_start_unbounded(&self) -> bool {
+        self.start.is_min()
+    }
+
+    /// Returns whether the interval is valid until the end of time
+    pub fn is_end_unbounded(&self) -> bool {
+        self.end.is_max()
+    }
+
+    /// Returns whether the interval is valid always, i.e., it equals the default interval
+    pub fn is_unbounded(&self) -> bool {
+        self.is_start_unbounded() && self.is_end_unbounded()
+    }
+
     /// Creates a geo json event from a time interval
     ///
     /// according to `GeoJSON` event extension (<https://github.com/sgillies/geojson-events>)
@@ -282,6 +301,15 @@ impl TimeInterval {
         })
     }
 
+    /// Like [`TimeInterval::as_geo_json_event`] but with the bounds formatted in a display time zone
+    pub fn as_geo_json_event_with_offset(&self, time_zone: &FixedOffset) -> serde_json::Value {
+        serde_json::json!({
+            "start": self.start.as_rfc3339_with_offset(time_zone),
+            "end": self.end.as_rfc3339_with_offset(time_zone),
+            "type": "Interval"
+        })
+    }
+
     /// Return a new time interval that is the intersection with the `other` time interval, or
     /// `None` if the intervals are disjoint
     pub fn intersect(self, other: &Self) -> Option<TimeInterval> {
@@ -456,27 +484,8 @@ impl ArrowTyped for TimeInterval {
         time_intervals: &Self::ArrowArray,
         filter_array: &BooleanArray,
     ) -> Result<Self::ArrowArray, ArrowError> {
-        // TODO: use date if dates out-of-range is fixed for us
-        // use arrow::array::Date64Array;
-        use arrow::array::Int64Array;
-
-        let mut new_time_intervals = Self::arrow_builder(0);
-
-        for feature_index in 0..time_intervals.len() {
-            if !filter_array.value(feature_index) {
-                continue;
-            }
-
-            let old_timestamps_ref = time_intervals.value(feature_index);
-            let old_timestamps: &Int64Array = downcast_array(&old_timestamps_ref);
-
-            let date_builder = new_time_intervals.values();
-            date_builder.append_slice(old_timestamps.values())?;
-
-            new_time_intervals.append(true)?;
-        }
-
-        Ok(new_time_intervals.finish())
+        let filtered = filter_by_take(time_intervals, filter_array)?;
+        Ok(filtered.data().clone().into())
     }
 
     fn from_vec(time_intervals: Vec<Self>) -> Result<Self::ArrowArray, ArrowError>
@@ -542,6 +551,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn to_geo_json_event_with_offset() {
+        assert_eq!(
+            TimeInterval::new_unchecked(0, 1_585_069_448 * 1000)
+                .as_geo_json_event_with_offset(&FixedOffset::east(3600)),
+            serde_json::json!({
+                "start": "1970-01-01T01:00:00+01:00",
+                "end": "2020-03-24T18:04:08+01:00",
+                "type": "Interval",
+            })
+        );
+        assert_eq!(
+            TimeInterval::default().as_geo_json_event_with_offset(&FixedOffset::west(3600)),
+            serde_json::json!({
+                "start": "-262144-01-01T00:00:00-01:00",
+                "end": "+262143-12-31T22:59:59.999-01:00",
+                "type": "Interval",
+            })
+        );
+    }
+
     #[test]
     fn duration_millis() {
         assert_eq!(
@@ -678,4 +708,24 @@ mod tests {
         assert!(a.is_instant());
         assert!(!b.is_instant());
     }
+
+    #[test]
+    fn intersects_unbounded() {
+        let always = TimeInterval::default();
+        let end_of_time = TimeInterval::new_instant(TimeInstance::MAX).unwrap();
+        let beginning_of_time = TimeInterval::new_instant(TimeInstance::MIN).unwrap();
+        let since = TimeInterval::new(0, TimeInstance::MAX).unwrap();
+
+        assert!(always.is_unbounded());
+        assert!(since.is_end_unbounded());
+        assert!(!since.is_start_unbounded());
+
+        assert!(always.intersects(&end_of_time));
+        assert!(end_of_time.intersects(&since));
+        assert!(always.intersects(&beginning_of_time));
+        assert!(!since.intersects(&beginning_of_time));
+
+        assert_eq!(since.intersect(&end_of_time), Some(end_of_time));
+        assert_eq!(always.intersect(&since), Some(since));
+    }
 }
@@ -1,6 +1,6 @@
 use std::{cmp::max, convert::TryInto, ops::Add};
 
-use chrono::{Datelike, Duration, NaiveDate};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
 use error::Error::NoDateTimeValid;
 use serde::{Deserialize, Serialize};
 
@@ -35,6 +35,23 @@ pub struct TimeStep {
     pub step: u32, // TODO: ensure on deserialization it is > 0
 }
 
+/// Determines what happens if adding months or years to a date results in a day that does not
+/// exist, e.g., January 31 + 1 month.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DayOverflowPolicy {
+    /// Use the last day of the month, e.g., January 31 + 1 month = February 28
+    ClampToMonthEnd,
+    /// Fail with an error
+    Error,
+}
+
+impl Default for DayOverflowPolicy {
+    fn default() -> Self {
+        Self::ClampToMonthEnd
+    }
+}
+
 impl TimeStep {
     /// Resolves how many `TimeSteps` fit into a given `TimeInterval`.
     /// Remember that `TimeInterval` is not inclusive.
@@ -110,19 +127,14 @@ impl TimeStep {
                     i64::from(end.month()) - i64::from(start.month()) + diff_years * 12;
                 let steps = diff_months / i64::from(self.step);
 
-                let shifted_start = (time_interval.start()
-                    + TimeStep {
-                        granularity: TimeGranularity::Months,
-                        step: self.step * steps as u32,
-                    })
-                .expect("is in valid range");
-
-                if (end
-                    - shifted_start
-                        .as_naive_date_time()
-                        .expect("is in valid range"))
-                .is_zero()
-                {
+                let shifted_start = add_months(
+                    start,
+                    i64::from(self.step) * steps,
+                    DayOverflowPolicy::ClampToMonthEnd,
+                )?;
+
+                // the day of the month may have been clamped, so the shifted start can lie after the end
+                if shifted_start >= end {
                     steps - 1
                 } else {
                     steps
@@ -131,11 +143,14 @@ impl TimeStep {
             TimeGranularity::Years => {
                 let steps = i64::from(end.year() - start.year()) / i64::from(self.step);
 
-                let shifted_start = start
-                    .with_year(start.year() + (i64::from(self.step) * steps) as i32)
-                    .expect("is in valid range");
+                let shifted_start = add_months(
+                    start,
+                    12 * i64::from(self.step) * steps,
+                    DayOverflowPolicy::ClampToMonthEnd,
+                )?;
 
-                if (end - shifted_start).is_zero() {
+                // the day of the month may have been clamped, so the shifted start can lie after the end
+                if shifted_start >= end {
                     steps - 1
                 } else {
                     steps
@@ -143,7 +158,7 @@ impl TimeStep {
             }
         };
 
-        Ok(max(0, num_steps as u32))
+        Ok(max(0, num_steps) as u32)
     }
 
     /// Snaps a `TimeInstance` relative to a given reference `TimeInstance`.
@@ -202,49 +217,41 @@ impl TimeStep {
                     * i64::from(self.step);
                 ref_date_time + Duration::days(snapped_days)
             }
-            TimeGranularity::Months => {
+            TimeGranularity::Months | TimeGranularity::Years => {
+                let months_per_step = match self.granularity {
+                    TimeGranularity::Months => i64::from(self.step),
+                    _ => 12 * i64::from(self.step),
+                };
+
                 // first, calculate the total difference in months
-                let diff_months = (time_to_snap_date_time.year() - ref_date_time.year()) * 12
-                    + (time_to_snap_date_time.month() as i32 - ref_date_time.month() as i32);
+                let diff_months = i64::from(time_to_snap_date_time.year() - ref_date_time.year())
+                    * 12
+                    + (i64::from(time_to_snap_date_time.month())
+                        - i64::from(ref_date_time.month()));
 
                 // get the difference in time steps
-                let snapped_months = (f64::from(diff_months) / f64::from(self.step)).floor() as i32
-                    * self.step as i32;
-
-                let snapped_year = if snapped_months.is_negative() {
-                    ref_date_time.year() + (ref_date_time.month() as i32 + snapped_months - 12) / 12
+                let steps = if months_per_step == 0 {
+                    0
                 } else {
-                    ref_date_time.year() + (ref_date_time.month() as i32 + snapped_months - 1) / 12
+                    diff_months.div_euclid(months_per_step)
                 };
 
-                let mut snapped_month =
-                    (ref_date_time.month() as i32 + snapped_months).rem_euclid(12);
-
-                if snapped_month == 0 {
-                    snapped_month = 12;
+                let snapped = add_months(
+                    ref_date_time,
+                    steps * months_per_step,
+                    DayOverflowPolicy::ClampToMonthEnd,
+                )?;
+
+                // the day or time of the reference may lie after the one to snap
+                if snapped > time_to_snap_date_time {
+                    add_months(
+                        ref_date_time,
+                        (steps - 1) * months_per_step,
+                        DayOverflowPolicy::ClampToMonthEnd,
+                    )?
+                } else {
+                    snapped
                 }
-
-                // TODO: _opt
-                NaiveDate::from_ymd_opt(snapped_year, snapped_month as u32, ref_date_time.day())
-                    .ok_or(Error::DateTimeOutOfBounds {
-                        year: snapped_year,
-                        month: snapped_month as u32,
-                        day: ref_date_time.day(),
-                    })?
-                    .and_time(ref_date_time.time())
-            }
-            TimeGranularity::Years => {
-                let diff = (time_to_snap_date_time.year() - ref_date_time.year()) as i32;
-                let snapped_year = ref_date_time.year()
-                    + ((f64::from(diff) / f64::from(self.step)).floor() as i32 * self.step as i32);
-
-                NaiveDate::from_ymd_opt(snapped_year, ref_date_time.month(), ref_date_time.day())
-                    .ok_or(Error::DateTimeOutOfBounds {
-                        year: snapped_year,
-                        month: ref_date_time.month(),
-                        day: ref_date_time.day(),
-                    })?
-                    .and_time(ref_date_time.time())
             }
         };
 
@@ -254,6 +261,7 @@ impl TimeStep {
     /// Snaps a `TimeInstance` relative to a given reference `TimeInstance`.
     ///
     /// This method keeps the result within `TimeInstance::MIN` and `TimeInstance::MAX`, respectively.
+    /// The unbounded instances themselves are returned unchanged.
     ///
     pub fn snap_relative_preserve_bounds<T>(self, reference: T, time_to_snap: T) -> TimeInstance
     where
@@ -262,6 +270,10 @@ impl TimeStep {
         let reference: TimeInstance = reference.into();
         let time_to_snap: TimeInstance = time_to_snap.into();
 
+        if time_to_snap.is_min() || time_to_snap.is_max() {
+            return time_to_snap;
+        }
+
         match self.snap_relative(reference, time_to_snap) {
             Ok(time_instance) => time_instance,
             Err(_) => {
@@ -277,49 +289,120 @@ impl TimeStep {
     }
 }
 
-impl Add<TimeStep> for TimeInstance {
-    type Output = Result<TimeInstance>;
+impl TimeInstance {
+    /// Adds a `TimeStep` to the instance.
+    ///
+    /// If adding months or years results in a day that does not exist, the `day_overflow_policy`
+    /// decides whether to use the end of the month or to fail.
+    ///
+    /// # Errors
+    /// This method fails if the result is outside of the valid time range.
+    ///
+    pub fn checked_add(
+        self,
+        time_step: TimeStep,
+        day_overflow_policy: DayOverflowPolicy,
+    ) -> Result<TimeInstance> {
+        self.checked_add_steps(time_step, 1, day_overflow_policy)
+    }
 
-    fn add(self, rhs: TimeStep) -> Self::Output {
+    /// Adds a `TimeStep` `n` times to the instance.
+    ///
+    /// In contrast to adding the step `n` times successively, the day of the month is not lost
+    /// when clamping, e.g., January 31 + 2 * 1 month = March 31.
+    ///
+    fn checked_add_steps(
+        self,
+        time_step: TimeStep,
+        n: u32,
+        day_overflow_policy: DayOverflowPolicy,
+    ) -> Result<TimeInstance> {
         let date_time = self.as_naive_date_time().ok_or(NoDateTimeValid {
             time_instance: self,
         })?;
 
-        let res_date_time = match rhs.granularity {
-            TimeGranularity::Millis => date_time + Duration::milliseconds(i64::from(rhs.step)),
-            TimeGranularity::Seconds => date_time + Duration::seconds(i64::from(rhs.step)),
-            TimeGranularity::Minutes => date_time + Duration::minutes(i64::from(rhs.step)),
-            TimeGranularity::Hours => date_time + Duration::hours(i64::from(rhs.step)),
-            TimeGranularity::Days => date_time + Duration::days(i64::from(rhs.step)),
-            TimeGranularity::Months => {
-                let months = date_time.month0() + rhs.step;
-                let month = months % 12 + 1;
-                let years_from_months = (months / 12) as i32;
-                let year = date_time.year() + years_from_months;
-                let day = date_time.day();
-                NaiveDate::from_ymd_opt(year, month, day)
-                    .context(error::DateTimeOutOfBounds { year, month, day })?
-                    .and_time(date_time.time())
-            }
-            TimeGranularity::Years => {
-                let year = date_time.year() + rhs.step as i32;
-                let month = date_time.month();
-                let day = date_time.day();
-                NaiveDate::from_ymd_opt(year, month, day)
-                    .context(error::DateTimeOutOfBounds { year, month, day })?
-                    .and_time(date_time.time())
-            }
+        let out_of_bounds = error::TimeStepOutOfBounds {
+            time_instance: self,
+            time_step,
         };
 
-        Ok(TimeInstance::from(res_date_time))
+        let step = i64::from(time_step.step)
+            .checked_mul(i64::from(n))
+            .context(out_of_bounds)?;
+
+        let add_millis = |millis_per_step: i64| {
+            step.checked_mul(millis_per_step)
+                .and_then(|millis| date_time.checked_add_signed(Duration::milliseconds(millis)))
+        };
+
+        let res_date_time = match time_step.granularity {
+            TimeGranularity::Millis => add_millis(1),
+            TimeGranularity::Seconds => add_millis(1000),
+            TimeGranularity::Minutes => add_millis(60 * 1000),
+            TimeGranularity::Hours => add_millis(60 * 60 * 1000),
+            TimeGranularity::Days => add_millis(24 * 60 * 60 * 1000),
+            TimeGranularity::Months => Some(add_months(date_time, step, day_overflow_policy)?),
+            TimeGranularity::Years => Some(add_months(
+                date_time,
+                step.checked_mul(12).context(out_of_bounds)?,
+                day_overflow_policy,
+            )?),
+        }
+        .context(out_of_bounds)?;
+
+        TimeInstance::from_millis(res_date_time.timestamp_millis())
+    }
+}
+
+impl Add<TimeStep> for TimeInstance {
+    type Output = Result<TimeInstance>;
+
+    /// Adds a `TimeStep` and fails for days that do not exist, e.g., January 31 + 1 month.
+    /// Use [`TimeInstance::checked_add`] to choose a different [`DayOverflowPolicy`].
+    fn add(self, rhs: TimeStep) -> Self::Output {
+        self.checked_add(rhs, DayOverflowPolicy::Error)
     }
 }
 
+/// Adds (or subtracts) `months` while keeping the day of the month and the time
+fn add_months(
+    date_time: NaiveDateTime,
+    months: i64,
+    day_overflow_policy: DayOverflowPolicy,
+) -> Result<NaiveDateTime> {
+    let total_months =
+        (i64::from(date_time.year()) * 12 + i64::from(date_time.month0())).saturating_add(months);
+
+    let year = total_months.div_euclid(12);
+    let month = total_months.rem_euclid(12) as u32 + 1;
+    let day = date_time.day();
+
+    let out_of_bounds = || Error::DateTimeOutOfBounds {
+        year: year.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32,
+        month,
+        day,
+    };
+
+    let year: i32 = year.try_into().map_err(|_| out_of_bounds())?;
+
+    let date = match day_overflow_policy {
+        DayOverflowPolicy::Error => NaiveDate::from_ymd_opt(year, month, day),
+        // months have at least 28 days, so this tries at most four days
+        DayOverflowPolicy::ClampToMonthEnd => (1..=day)
+            .rev()
+            .find_map(|day| NaiveDate::from_ymd_opt(year, month, day)),
+    }
+    .ok_or_else(out_of_bounds)?;
+
+    Ok(date.and_time(date_time.time()))
+}
+
 /// An `Iterator` to iterate over time in steps
 #[derive(Debug, Clone)]
 pub struct TimeStepIter {
     reference_time: TimeInstance,
     time_step: TimeStep,
+    day_overflow_policy: DayOverflowPolicy,
     curr: u32,
     max: u32,
 }
@@ -333,11 +416,7 @@ impl TimeStepIter {
         time_step: TimeStep,
         steps: u32,
     ) -> Result<Self> {
-        let _ = (reference_time
-            + TimeStep {
-                granularity: time_step.granularity,
-                step: time_step.step * steps,
-            })?;
+        let _ = reference_time.checked_add_steps(time_step, steps, DayOverflowPolicy::default())?;
         Ok(Self::new_incl_start_unchecked(
             reference_time,
             time_step,
@@ -355,11 +434,30 @@ impl TimeStepIter {
         Self {
             reference_time,
             time_step,
+            day_overflow_policy: DayOverflowPolicy::default(),
             curr: 0,
             max: steps,
         }
     }
 
+    /// Sets how days that do not exist are handled when stepping in months or years.
+    ///
+    /// The default is to clamp them to the end of the month, e.g., stepping monthly from
+    /// January 31 yields February 28 and March 31.
+    /// # Errors
+    /// This method fails if the last step is not valid with the `day_overflow_policy`.
+    pub fn with_day_overflow_policy(
+        mut self,
+        day_overflow_policy: DayOverflowPolicy,
+    ) -> Result<Self> {
+        let _ =
+            self.reference_time
+                .checked_add_steps(self.time_step, self.max, day_overflow_policy)?;
+
+        self.day_overflow_policy = day_overflow_policy;
+        Ok(self)
+    }
+
     /// Create a new `TimeStepIter` which will include the start of the provided `TimeInterval`.
     /// # Errors
     /// This method fails if the start or end values of the interval are not valid in chrono.
@@ -399,12 +497,11 @@ impl Iterator for TimeStepIter {
             return None;
         }
 
-        let next = (self.reference_time
-            + TimeStep {
-                granularity: self.time_step.granularity,
-                step: self.curr * self.time_step.step,
-            })
-        .unwrap();
+        // the steps are added to the reference to not lose the day of the month when clamping
+        let next = self
+            .reference_time
+            .checked_add_steps(self.time_step, self.curr, self.day_overflow_policy)
+            .ok()?;
 
         self.curr += 1;
 
@@ -1393,4 +1490,121 @@ mod tests {
             "2013-01-01T00:00:00.0",
         );
     }
+
+    #[test]
+    fn add_month_to_month_end() {
+        let jan_31 = TimeInstance::from(NaiveDate::from_ymd(2001, 1, 31).and_hms(12, 0, 0));
+        let one_month = TimeStep {
+            granularity: TimeGranularity::Months,
+            step: 1,
+        };
+
+        assert_eq!(
+            jan_31
+                .checked_add(one_month, DayOverflowPolicy::ClampToMonthEnd)
+                .unwrap(),
+            TimeInstance::from(NaiveDate::from_ymd(2001, 2, 28).and_hms(12, 0, 0))
+        );
+        assert!(jan_31
+            .checked_add(one_month, DayOverflowPolicy::Error)
+            .is_err());
+        assert!((jan_31 + one_month).is_err());
+
+        let feb_29 = TimeInstance::from(NaiveDate::from_ymd(2004, 2, 29).and_hms(0, 0, 0));
+        assert_eq!(
+            feb_29
+                .checked_add(
+                    TimeStep {
+                        granularity: TimeGranularity::Years,
+                        step: 1,
+                    },
+                    DayOverflowPolicy::ClampToMonthEnd
+                )
+                .unwrap(),
+            TimeInstance::from(NaiveDate::from_ymd(2005, 2, 28).and_hms(0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn add_out_of_bounds() {
+        assert!(TimeInstance::MAX
+            .checked_add(
+                TimeStep {
+                    granularity: TimeGranularity::Days,
+                    step: 1,
+                },
+                DayOverflowPolicy::ClampToMonthEnd
+            )
+            .is_err());
+        assert!(TimeInstance::from_millis_unchecked(0)
+            .checked_add(
+                TimeStep {
+                    granularity: TimeGranularity::Years,
+                    step: u32::MAX,
+                },
+                DayOverflowPolicy::ClampToMonthEnd
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_iter_month_end() {
+        let t_1 = TimeInstance::from(NaiveDate::from_ymd(2001, 1, 31).and_hms(0, 0, 0));
+        let t_2 = TimeInstance::from(NaiveDate::from_ymd(2001, 4, 15).and_hms(0, 0, 0));
+
+        let t_step = TimeStep {
+            granularity: TimeGranularity::Months,
+            step: 1,
+        };
+
+        let iter = TimeStepIter::new_with_interval_incl_start(
+            TimeInterval::new_unchecked(t_1, t_2),
+            t_step,
+        )
+        .unwrap();
+
+        let t_vec: Vec<TimeInstance> = iter.collect();
+
+        assert_eq!(
+            &t_vec,
+            &[
+                t_1,
+                TimeInstance::from(NaiveDate::from_ymd(2001, 2, 28).and_hms(0, 0, 0)),
+                TimeInstance::from(NaiveDate::from_ymd(2001, 3, 31).and_hms(0, 0, 0)),
+            ]
+        );
+
+        assert!(TimeStepIter::new_with_interval_incl_start(
+            TimeInterval::new_unchecked(t_1, t_2),
+            t_step,
+        )
+        .unwrap()
+        .with_day_overflow_policy(DayOverflowPolicy::Error)
+        .is_err());
+    }
+
+    #[test]
+    fn snap_month_end() {
+        test_snap(
+            TimeGranularity::Months,
+            1,
+            "2000-01-31T00:00:00.0",
+            "2000-02-29T12:00:00.0",
+            "2000-02-29T00:00:00.0",
+        );
+        test_snap(
+            TimeGranularity::Months,
+            1,
+            "2000-01-31T00:00:00.0",
+            "2000-04-15T00:00:00.0",
+            "2000-03-31T00:00:00.0",
+        );
+        test_snap(
+            TimeGranularity::Years,
+            1,
+            "2000-06-01T00:00:00.0",
+            "2001-03-01T00:00:00.0",
+            "2000-06-01T00:00:00.0",
+        );
+    }
 }
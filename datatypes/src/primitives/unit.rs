@@ -0,0 +1,322 @@
+use crate::error;
+use crate::util::Result;
+use snafu::ensure;
+use std::fmt;
+
+/// The exponents of the base dimensions of a physical quantity, e.g., `length¹·time⁻¹` for a velocity.
+///
+/// Values can only be converted between units of the same dimension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Dimension {
+    pub length: i8,
+    pub mass: i8,
+    pub time: i8,
+    pub temperature: i8,
+}
+
+impl Dimension {
+    pub const DIMENSIONLESS: Self = Self::new(0, 0, 0, 0);
+    pub const LENGTH: Self = Self::new(1, 0, 0, 0);
+    pub const AREA: Self = Self::new(2, 0, 0, 0);
+    pub const MASS: Self = Self::new(0, 1, 0, 0);
+    pub const TIME: Self = Self::new(0, 0, 1, 0);
+    pub const TEMPERATURE: Self = Self::new(0, 0, 0, 1);
+    pub const VELOCITY: Self = Self::new(1, 0, -1, 0);
+    pub const PRESSURE: Self = Self::new(-1, 1, -2, 0);
+    pub const IRRADIANCE: Self = Self::new(0, 1, -3, 0);
+
+    pub const fn new(length: i8, mass: i8, time: i8, temperature: i8) -> Self {
+        Self {
+            length,
+            mass,
+            time,
+            temperature,
+        }
+    }
+}
+
+/// A physical unit of the [`UNITS`] registry.
+///
+/// A value `v` in this unit equals `v * factor + offset` in the SI base unit of its dimension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    pub symbol: &'static str,
+    pub name: &'static str,
+    pub dimension: Dimension,
+    factor: f64,
+    offset: f64,
+    /// Alternative spellings of the symbol, e.g., `°K` for `K`
+    aliases: &'static [&'static str],
+}
+
+impl Unit {
+    const fn new(
+        symbol: &'static str,
+        name: &'static str,
+        dimension: Dimension,
+        factor: f64,
+        aliases: &'static [&'static str],
+    ) -> Self {
+        Self {
+            symbol,
+            name,
+            dimension,
+            factor,
+            offset: 0.,
+            aliases,
+        }
+    }
+
+    const fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Looks up a unit by its symbol or one of its aliases in the [`UNITS`] registry
+    pub fn from_symbol(symbol: &str) -> Result<&'static Unit> {
+        let symbol = symbol.trim();
+
+        UNITS
+            .iter()
+            .find(|unit| unit.symbol == symbol || unit.aliases.contains(&symbol))
+            .ok_or_else(|| error::Error::UnknownUnit {
+                unit: symbol.to_string(),
+            })
+    }
+
+    /// The conversion of values in this unit to values in the `target` unit
+    ///
+    /// # Errors
+    /// Fails if the units have different dimensions, e.g., a length and a velocity
+    ///
+    pub fn conversion_to(&self, target: &Unit) -> Result<UnitConversion> {
+        ensure!(
+            self.dimension == target.dimension,
+            error::IncompatibleUnits {
+                from: self.symbol,
+                to: target.symbol,
+            }
+        );
+
+        Ok(UnitConversion {
+            factor: self.factor / target.factor,
+            offset: (self.offset - target.offset) / target.factor,
+        })
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol)
+    }
+}
+
+/// A linear conversion of values from one unit to another one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnitConversion {
+    pub factor: f64,
+    pub offset: f64,
+}
+
+impl UnitConversion {
+    pub fn convert(&self, value: f64) -> f64 {
+        value * self.factor + self.offset
+    }
+
+    /// Whether the conversion does not change any value
+    pub fn is_identity(&self) -> bool {
+        #[allow(clippy::float_cmp)]
+        let identity = self.factor == 1. && self.offset == 0.;
+        identity
+    }
+}
+
+const SECONDS_PER_DAY: f64 = 86_400.;
+const CELSIUS_OFFSET: f64 = 273.15;
+
+/// The registry of all known units
+pub const UNITS: &[Unit] = &[
+    // dimensionless
+    Unit::new("1", "one", Dimension::DIMENSIONLESS, 1., &[]),
+    Unit::new("%", "percent", Dimension::DIMENSIONLESS, 0.01, &[]),
+    // length
+    Unit::new("m", "metre", Dimension::LENGTH, 1., &["meter"]),
+    Unit::new("km", "kilometre", Dimension::LENGTH, 1_000., &["kilometer"]),
+    Unit::new("cm", "centimetre", Dimension::LENGTH, 0.01, &[]),
+    Unit::new("mm", "millimetre", Dimension::LENGTH, 0.001, &[]),
+    // area
+    Unit::new("m²", "square metre", Dimension::AREA, 1., &["m2", "m^2"]),
+    Unit::new(
+        "km²",
+        "square kilometre",
+        Dimension::AREA,
+        1_000_000.,
+        &["km2", "km^2"],
+    ),
+    Unit::new("ha", "hectare", Dimension::AREA, 10_000., &[]),
+    // mass
+    Unit::new("kg", "kilogram", Dimension::MASS, 1., &[]),
+    Unit::new("g", "gram", Dimension::MASS, 0.001, &[]),
+    Unit::new("t", "tonne", Dimension::MASS, 1_000., &[]),
+    // time
+    Unit::new("s", "second", Dimension::TIME, 1., &[]),
+    Unit::new("min", "minute", Dimension::TIME, 60., &[]),
+    Unit::new("h", "hour", Dimension::TIME, 3_600., &[]),
+    Unit::new(
+        "d",
+        "day",
+        Dimension::TIME,
+        SECONDS_PER_DAY,
+        &["day", "days"],
+    ),
+    // temperature
+    Unit::new("K", "kelvin", Dimension::TEMPERATURE, 1., &["°K", "kelvin"]),
+    Unit::new(
+        "°C",
+        "degree Celsius",
+        Dimension::TEMPERATURE,
+        1.,
+        &["degC", "celsius"],
+    )
+    .with_offset(CELSIUS_OFFSET),
+    Unit::new(
+        "°F",
+        "degree Fahrenheit",
+        Dimension::TEMPERATURE,
+        5. / 9.,
+        &["degF", "fahrenheit"],
+    )
+    .with_offset(CELSIUS_OFFSET - 32. * 5. / 9.),
+    // velocity and rates
+    Unit::new(
+        "m/s",
+        "metre per second",
+        Dimension::VELOCITY,
+        1.,
+        &["m s-1", "m·s⁻¹"],
+    ),
+    Unit::new(
+        "km/h",
+        "kilometre per hour",
+        Dimension::VELOCITY,
+        1. / 3.6,
+        &["km h-1"],
+    ),
+    Unit::new(
+        "mm/d",
+        "millimetre per day",
+        Dimension::VELOCITY,
+        0.001 / SECONDS_PER_DAY,
+        &["mm/day", "mm d-1"],
+    ),
+    Unit::new(
+        "mm/h",
+        "millimetre per hour",
+        Dimension::VELOCITY,
+        0.001 / 3_600.,
+        &["mm h-1"],
+    ),
+    // pressure
+    Unit::new("Pa", "pascal", Dimension::PRESSURE, 1., &[]),
+    Unit::new("hPa", "hectopascal", Dimension::PRESSURE, 100., &["mbar"]),
+    Unit::new("kPa", "kilopascal", Dimension::PRESSURE, 1_000., &[]),
+    // irradiance
+    Unit::new(
+        "W/m²",
+        "watt per square metre",
+        Dimension::IRRADIANCE,
+        1.,
+        &["W m-2", "W/m2", "W·m⁻²"],
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_cmp::approx_eq;
+    use std::collections::HashSet;
+
+    fn convert(value: f64, from: &str, to: &str) -> f64 {
+        Unit::from_symbol(from)
+            .unwrap()
+            .conversion_to(Unit::from_symbol(to).unwrap())
+            .unwrap()
+            .convert(value)
+    }
+
+    #[test]
+    fn registry_symbols_are_unique() {
+        let mut symbols = HashSet::new();
+
+        for unit in UNITS {
+            assert!(symbols.insert(unit.symbol), "duplicate {}", unit.symbol);
+
+            for alias in unit.aliases {
+                assert!(symbols.insert(alias), "duplicate {}", alias);
+            }
+        }
+    }
+
+    #[test]
+    fn it_converts_temperatures() {
+        assert!(approx_eq!(f64, convert(0., "°C", "K"), 273.15));
+        assert!(approx_eq!(
+            f64,
+            convert(300., "°K", "°C"),
+            26.85,
+            epsilon = 1e-9
+        ));
+        assert!(approx_eq!(
+            f64,
+            convert(212., "°F", "°C"),
+            100.,
+            epsilon = 1e-9
+        ));
+        assert!(approx_eq!(
+            f64,
+            convert(-40., "°C", "°F"),
+            -40.,
+            epsilon = 1e-9
+        ));
+    }
+
+    #[test]
+    fn it_converts_rates() {
+        assert!(approx_eq!(f64, convert(86.4, "mm/day", "m/s"), 1e-6));
+        assert!(approx_eq!(
+            f64,
+            convert(36., "km/h", "m/s"),
+            10.,
+            epsilon = 1e-9
+        ));
+        assert!(approx_eq!(f64, convert(1., "ha", "m²"), 10_000.));
+        assert!(approx_eq!(f64, convert(50., "%", "1"), 0.5));
+    }
+
+    #[test]
+    fn it_rejects_incompatible_units() {
+        let mm_per_day = Unit::from_symbol("mm/d").unwrap();
+        let millimetre = Unit::from_symbol("mm").unwrap();
+
+        assert!(matches!(
+            mm_per_day.conversion_to(millimetre),
+            Err(error::Error::IncompatibleUnits { .. })
+        ));
+
+        assert!(matches!(
+            Unit::from_symbol("furlong"),
+            Err(error::Error::UnknownUnit { .. })
+        ));
+    }
+
+    #[test]
+    fn identity() {
+        let kelvin = Unit::from_symbol("K").unwrap();
+
+        assert!(kelvin.conversion_to(kelvin).unwrap().is_identity());
+        assert!(!kelvin
+            .conversion_to(Unit::from_symbol("°C").unwrap())
+            .unwrap()
+            .is_identity());
+    }
+}
@@ -0,0 +1,473 @@
+use std::convert::{TryFrom, TryInto};
+
+use crate::error::Error;
+use crate::primitives::{
+    Coordinate2D, MultiLineString, MultiLineStringAccess, MultiLineStringRef, MultiPoint,
+    MultiPointAccess, MultiPointRef, MultiPolygon, MultiPolygonAccess, MultiPolygonRef,
+    PrimitivesError,
+};
+use crate::util::Result;
+
+/// Geometries that can be written as Well-Known Binary (WKB)
+pub trait ToWkb {
+    /// Writes the geometry as a two-dimensional multi geometry in little endian byte order
+    fn to_wkb(&self) -> Vec<u8>;
+}
+
+/// Geometries that can be read from Well-Known Binary (WKB)
+///
+/// Both byte orders, ISO and extended (EWKB) geometry types are supported.
+/// Single geometries are read as multi geometries with one element and Z and M values are dropped.
+pub trait FromWkb: Sized {
+    fn from_wkb(wkb: &[u8]) -> Result<Self>;
+}
+
+const LITTLE_ENDIAN: u8 = 1;
+
+const WKB_POINT: u32 = 1;
+const WKB_LINE_STRING: u32 = 2;
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTI_POINT: u32 = 4;
+const WKB_MULTI_LINE_STRING: u32 = 5;
+const WKB_MULTI_POLYGON: u32 = 6;
+
+const EWKB_Z_FLAG: u32 = 0x8000_0000;
+const EWKB_M_FLAG: u32 = 0x4000_0000;
+const EWKB_SRID_FLAG: u32 = 0x2000_0000;
+
+struct WkbWriter {
+    wkb: Vec<u8>,
+}
+
+impl WkbWriter {
+    fn new() -> Self {
+        Self { wkb: Vec::new() }
+    }
+
+    fn header(&mut self, geometry_type: u32) {
+        self.wkb.push(LITTLE_ENDIAN);
+        self.u32(geometry_type);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.wkb.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn length(&mut self, length: usize) {
+        self.u32(u32::try_from(length).expect("WKB cannot store more than `u32::MAX` elements"));
+    }
+
+    fn coordinates(&mut self, coordinates: &[Coordinate2D]) {
+        self.length(coordinates.len());
+        for coordinate in coordinates {
+            self.wkb.extend_from_slice(&coordinate.x.to_le_bytes());
+            self.wkb.extend_from_slice(&coordinate.y.to_le_bytes());
+        }
+    }
+
+    fn multi_point<G: MultiPointAccess>(mut self, geometry: &G) -> Vec<u8> {
+        let points = geometry.points();
+
+        self.header(WKB_MULTI_POINT);
+        self.length(points.len());
+        for point in points {
+            self.header(WKB_POINT);
+            self.wkb.extend_from_slice(&point.x.to_le_bytes());
+            self.wkb.extend_from_slice(&point.y.to_le_bytes());
+        }
+
+        self.wkb
+    }
+
+    fn multi_line_string<G: MultiLineStringAccess>(mut self, geometry: &G) -> Vec<u8> {
+        let lines = geometry.lines();
+
+        self.header(WKB_MULTI_LINE_STRING);
+        self.length(lines.len());
+        for line in lines {
+            self.header(WKB_LINE_STRING);
+            self.coordinates(line.as_ref());
+        }
+
+        self.wkb
+    }
+
+    fn multi_polygon<G: MultiPolygonAccess>(mut self, geometry: &G) -> Vec<u8> {
+        let polygons = geometry.polygons();
+
+        self.header(WKB_MULTI_POLYGON);
+        self.length(polygons.len());
+        for polygon in polygons {
+            let rings = polygon.as_ref();
+
+            self.header(WKB_POLYGON);
+            self.length(rings.len());
+            for ring in rings {
+                self.coordinates(ring.as_ref());
+            }
+        }
+
+        self.wkb
+    }
+}
+
+impl ToWkb for MultiPoint {
+    fn to_wkb(&self) -> Vec<u8> {
+        WkbWriter::new().multi_point(self)
+    }
+}
+
+impl ToWkb for MultiPointRef<'_> {
+    fn to_wkb(&self) -> Vec<u8> {
+        WkbWriter::new().multi_point(self)
+    }
+}
+
+impl ToWkb for MultiLineString {
+    fn to_wkb(&self) -> Vec<u8> {
+        WkbWriter::new().multi_line_string(self)
+    }
+}
+
+impl ToWkb for MultiLineStringRef<'_> {
+    fn to_wkb(&self) -> Vec<u8> {
+        WkbWriter::new().multi_line_string(self)
+    }
+}
+
+impl ToWkb for MultiPolygon {
+    fn to_wkb(&self) -> Vec<u8> {
+        WkbWriter::new().multi_polygon(self)
+    }
+}
+
+impl ToWkb for MultiPolygonRef<'_> {
+    fn to_wkb(&self) -> Vec<u8> {
+        WkbWriter::new().multi_polygon(self)
+    }
+}
+
+impl FromWkb for MultiPoint {
+    fn from_wkb(wkb: &[u8]) -> Result<Self> {
+        let mut reader = WkbReader::new(wkb);
+
+        let coordinates = match reader.header()? {
+            WKB_POINT => vec![reader.coordinate()?],
+            WKB_MULTI_POINT => reader.elements(|reader| {
+                reader.expect_header(WKB_POINT)?;
+                reader.coordinate()
+            })?,
+            other => return Err(unexpected_type("Point or MultiPoint", other)),
+        };
+
+        reader.end()?;
+
+        Self::new(coordinates)
+    }
+}
+
+impl FromWkb for MultiLineString {
+    fn from_wkb(wkb: &[u8]) -> Result<Self> {
+        let mut reader = WkbReader::new(wkb);
+
+        let lines = match reader.header()? {
+            WKB_LINE_STRING => vec![reader.line()?],
+            WKB_MULTI_LINE_STRING => reader.elements(|reader| {
+                reader.expect_header(WKB_LINE_STRING)?;
+                reader.line()
+            })?,
+            other => return Err(unexpected_type("LineString or MultiLineString", other)),
+        };
+
+        reader.end()?;
+
+        Self::new(lines)
+    }
+}
+
+impl FromWkb for MultiPolygon {
+    fn from_wkb(wkb: &[u8]) -> Result<Self> {
+        let mut reader = WkbReader::new(wkb);
+
+        let polygons = match reader.header()? {
+            WKB_POLYGON => vec![reader.polygon()?],
+            WKB_MULTI_POLYGON => reader.elements(|reader| {
+                reader.expect_header(WKB_POLYGON)?;
+                reader.polygon()
+            })?,
+            other => return Err(unexpected_type("Polygon or MultiPolygon", other)),
+        };
+
+        reader.end()?;
+
+        Self::new(polygons)
+    }
+}
+
+fn unexpected_type(expected: &str, found: u32) -> Error {
+    PrimitivesError::InvalidWkb {
+        reason: format!("expected {}, but found geometry type {}", expected, found),
+    }
+    .into()
+}
+
+struct WkbReader<'b> {
+    wkb: &'b [u8],
+    little_endian: bool,
+    /// The number of values per coordinate, including Z and M values
+    dimensions: usize,
+}
+
+impl<'b> WkbReader<'b> {
+    fn new(wkb: &'b [u8]) -> Self {
+        Self {
+            wkb,
+            little_endian: true,
+            dimensions: 2,
+        }
+    }
+
+    fn error(reason: impl Into<String>) -> Error {
+        PrimitivesError::InvalidWkb {
+            reason: reason.into(),
+        }
+        .into()
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N]> {
+        if self.wkb.len() < N {
+            return Err(Self::error("unexpected end"));
+        }
+
+        let (bytes, rest) = self.wkb.split_at(N);
+        self.wkb = rest;
+
+        Ok(bytes.try_into().expect("length was checked"))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self.bytes()?;
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn f64(&mut self) -> Result<f64> {
+        let bytes = self.bytes()?;
+        Ok(if self.little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    /// Reads the byte order and the geometry type and returns the type without dimension information
+    fn header(&mut self) -> Result<u32> {
+        let [byte_order] = self.bytes()?;
+        self.little_endian = match byte_order {
+            0 => false,
+            1 => true,
+            other => return Err(Self::error(format!("invalid byte order {}", other))),
+        };
+
+        let raw_type = self.u32()?;
+
+        if raw_type & EWKB_SRID_FLAG != 0 {
+            self.u32()?;
+        }
+
+        // ISO types encode the dimensions in the thousands, e.g., 1001 for a point with Z value
+        let iso_type = raw_type & !(EWKB_Z_FLAG | EWKB_M_FLAG | EWKB_SRID_FLAG);
+        let (has_z, has_m) = match iso_type / 1000 {
+            0 => (false, false),
+            1 => (true, false),
+            2 => (false, true),
+            3 => (true, true),
+            _ => return Err(Self::error(format!("invalid geometry type {}", raw_type))),
+        };
+
+        self.dimensions = 2
+            + usize::from(has_z || raw_type & EWKB_Z_FLAG != 0)
+            + usize::from(has_m || raw_type & EWKB_M_FLAG != 0);
+
+        Ok(iso_type % 1000)
+    }
+
+    fn expect_header(&mut self, expected: u32) -> Result<()> {
+        match self.header()? {
+            geometry_type if geometry_type == expected => Ok(()),
+            other => Err(Self::error(format!(
+                "expected geometry type {}, but found {}",
+                expected, other
+            ))),
+        }
+    }
+
+    /// Reads a coordinate and drops the Z and M values
+    fn coordinate(&mut self) -> Result<Coordinate2D> {
+        let x = self.f64()?;
+        let y = self.f64()?;
+
+        for _ in 2..self.dimensions {
+            self.f64()?;
+        }
+
+        Ok(Coordinate2D::new(x, y))
+    }
+
+    /// Reads the number of elements and the elements
+    fn elements<T, F>(&mut self, mut read_element: F) -> Result<Vec<T>>
+    where
+        F: FnMut(&mut Self) -> Result<T>,
+    {
+        let length = self.u32()? as usize;
+
+        // do not trust the length for the allocation since each element has at least one byte
+        let mut elements = Vec::with_capacity(length.min(self.wkb.len()));
+        for _ in 0..length {
+            elements.push(read_element(self)?);
+        }
+
+        Ok(elements)
+    }
+
+    fn line(&mut self) -> Result<Vec<Coordinate2D>> {
+        self.elements(Self::coordinate)
+    }
+
+    fn polygon(&mut self) -> Result<Vec<Vec<Coordinate2D>>> {
+        self.elements(Self::line)
+    }
+
+    fn end(&self) -> Result<()> {
+        if self.wkb.is_empty() {
+            Ok(())
+        } else {
+            Err(Self::error(format!(
+                "{} unexpected bytes at the end",
+                self.wkb.len()
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygons() -> MultiPolygon {
+        MultiPolygon::new(vec![
+            vec![
+                vec![
+                    (0.0, 0.0).into(),
+                    (10.0, 0.0).into(),
+                    (10.0, 10.0).into(),
+                    (0.0, 10.0).into(),
+                    (0.0, 0.0).into(),
+                ],
+                vec![
+                    (1.0, 1.0).into(),
+                    (2.0, 1.0).into(),
+                    (2.0, 2.0).into(),
+                    (1.0, 1.0).into(),
+                ],
+            ],
+            vec![vec![
+                (-0.5, -0.5).into(),
+                (-1.5, -0.5).into(),
+                (-1.5, -1.5).into(),
+                (-0.5, -0.5).into(),
+            ]],
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn it_writes_wkb() {
+        let points = MultiPoint::new(vec![(1.0, 2.0).into()]).unwrap();
+
+        let mut expected = vec![1, 4, 0, 0, 0, 1, 0, 0, 0, 1, 1, 0, 0, 0];
+        expected.extend_from_slice(&1.0_f64.to_le_bytes());
+        expected.extend_from_slice(&2.0_f64.to_le_bytes());
+
+        assert_eq!(points.to_wkb(), expected);
+    }
+
+    #[test]
+    fn it_round_trips() {
+        let points = MultiPoint::new(vec![(0.1, 0.2).into(), (1e10, -1e-10).into()]).unwrap();
+        assert_eq!(MultiPoint::from_wkb(&points.to_wkb()).unwrap(), points);
+
+        let lines = MultiLineString::new(vec![
+            vec![(0.1, 0.2).into(), (0.3, 0.4).into()],
+            vec![(1.0, 2.0).into(), (3.0, 4.0).into(), (5.0, 6.0).into()],
+        ])
+        .unwrap();
+        assert_eq!(MultiLineString::from_wkb(&lines.to_wkb()).unwrap(), lines);
+
+        assert_eq!(
+            MultiPolygon::from_wkb(&polygons().to_wkb()).unwrap(),
+            polygons()
+        );
+    }
+
+    #[test]
+    fn it_reads_big_endian_and_z_values() {
+        // POINT Z (1 2 3) in big endian with an ISO type
+        let mut wkb = vec![0, 0, 0, 0x03, 0xE9];
+        wkb.extend_from_slice(&1.0_f64.to_be_bytes());
+        wkb.extend_from_slice(&2.0_f64.to_be_bytes());
+        wkb.extend_from_slice(&3.0_f64.to_be_bytes());
+
+        assert_eq!(
+            MultiPoint::from_wkb(&wkb).unwrap(),
+            MultiPoint::new(vec![(1.0, 2.0).into()]).unwrap()
+        );
+
+        // POINT Z (1 2 3) as EWKB with SRID 4326
+        let mut wkb = vec![1];
+        wkb.extend_from_slice(&(WKB_POINT | EWKB_Z_FLAG | EWKB_SRID_FLAG).to_le_bytes());
+        wkb.extend_from_slice(&4326_u32.to_le_bytes());
+        wkb.extend_from_slice(&1.0_f64.to_le_bytes());
+        wkb.extend_from_slice(&2.0_f64.to_le_bytes());
+        wkb.extend_from_slice(&3.0_f64.to_le_bytes());
+
+        assert_eq!(
+            MultiPoint::from_wkb(&wkb).unwrap(),
+            MultiPoint::new(vec![(1.0, 2.0).into()]).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_wkb() {
+        let wkb = polygons().to_wkb();
+
+        assert!(MultiPoint::from_wkb(&wkb).is_err());
+        assert!(MultiPolygon::from_wkb(&wkb[..wkb.len() - 1]).is_err());
+        assert!(MultiPolygon::from_wkb(&[wkb.as_slice(), &[0]].concat()).is_err());
+        assert!(MultiPolygon::from_wkb(&[]).is_err());
+        assert!(MultiPolygon::from_wkb(&[2, 3, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn it_round_trips_with_ogr() {
+        let polygons = polygons();
+
+        let ogr_geometry = gdal::vector::Geometry::from_wkb(&polygons.to_wkb()).unwrap();
+
+        assert_eq!(
+            MultiPolygon::from_wkb(&ogr_geometry.wkb().unwrap()).unwrap(),
+            polygons
+        );
+
+        let lines = MultiLineString::new(vec![vec![(1.0, 2.0).into(), (3.0, 4.0).into()]]).unwrap();
+        let ogr_geometry = gdal::vector::Geometry::from_wkb(&lines.to_wkb()).unwrap();
+
+        assert_eq!(
+            MultiLineString::from_wkb(&ogr_geometry.wkb().unwrap()).unwrap(),
+            lines
+        );
+    }
+}
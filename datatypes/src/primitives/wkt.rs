@@ -0,0 +1,498 @@
+use std::fmt::{self, Write};
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use crate::error::Error;
+use crate::primitives::{
+    Coordinate2D, MultiLineString, MultiLineStringAccess, MultiLineStringRef, MultiPoint,
+    MultiPointAccess, MultiPointRef, MultiPolygon, MultiPolygonAccess, MultiPolygonRef,
+    PrimitivesError,
+};
+use crate::util::Result;
+
+/// Geometries that can be written as Well-Known Text (WKT)
+pub trait ToWkt {
+    fn to_wkt(&self) -> String;
+}
+
+/// Geometries that can be read from Well-Known Text (WKT)
+///
+/// Single geometries are read as multi geometries with one element and Z and M values are dropped.
+pub trait FromWkt: Sized {
+    fn from_wkt(wkt: &str) -> Result<Self>;
+}
+
+fn write_coordinate<W: Write>(writer: &mut W, coordinate: Coordinate2D) -> fmt::Result {
+    write!(writer, "{} {}", coordinate.x, coordinate.y)
+}
+
+/// Writes the elements with a separator and each one enclosed in parentheses
+fn write_list<W, T, F>(writer: &mut W, elements: &[T], mut write_element: F) -> fmt::Result
+where
+    W: Write,
+    F: FnMut(&mut W, &T) -> fmt::Result,
+{
+    writer.write_char('(')?;
+    for (i, element) in elements.iter().enumerate() {
+        if i > 0 {
+            writer.write_str(", ")?;
+        }
+        write_element(writer, element)?;
+    }
+    writer.write_char(')')
+}
+
+fn write_line<W: Write, L: AsRef<[Coordinate2D]>>(writer: &mut W, line: &L) -> fmt::Result {
+    write_list(writer, line.as_ref(), |writer, coordinate| {
+        write_coordinate(writer, *coordinate)
+    })
+}
+
+fn write_multi_point<W: Write, G: MultiPointAccess>(writer: &mut W, geometry: &G) -> fmt::Result {
+    writer.write_str("MULTIPOINT ")?;
+    write_list(writer, geometry.points(), |writer, coordinate| {
+        write_list(writer, &[*coordinate], |writer, coordinate| {
+            write_coordinate(writer, *coordinate)
+        })
+    })
+}
+
+fn write_multi_line_string<W: Write, G: MultiLineStringAccess>(
+    writer: &mut W,
+    geometry: &G,
+) -> fmt::Result {
+    writer.write_str("MULTILINESTRING ")?;
+    write_list(writer, geometry.lines(), write_line)
+}
+
+fn write_multi_polygon<W: Write, G: MultiPolygonAccess>(
+    writer: &mut W,
+    geometry: &G,
+) -> fmt::Result {
+    writer.write_str("MULTIPOLYGON ")?;
+    write_list(writer, geometry.polygons(), |writer, polygon| {
+        write_list(writer, polygon.as_ref(), write_line)
+    })
+}
+
+/// Implements `ToWkt` and `Display` for a geometry type by using a write function
+macro_rules! impl_wkt_output {
+    ($write_fn:ident, $($geometry:ty),+) => {
+        $(
+            impl ToWkt for $geometry {
+                fn to_wkt(&self) -> String {
+                    let mut wkt = String::new();
+                    $write_fn(&mut wkt, self).expect("writing to a string must not fail");
+                    wkt
+                }
+            }
+
+            impl fmt::Display for $geometry {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    $write_fn(f, self)
+                }
+            }
+        )+
+    };
+}
+
+impl_wkt_output!(write_multi_point, MultiPoint, MultiPointRef<'_>);
+impl_wkt_output!(
+    write_multi_line_string,
+    MultiLineString,
+    MultiLineStringRef<'_>
+);
+impl_wkt_output!(write_multi_polygon, MultiPolygon, MultiPolygonRef<'_>);
+
+impl FromWkt for MultiPoint {
+    fn from_wkt(wkt: &str) -> Result<Self> {
+        let mut parser = WktParser::new(wkt);
+
+        let coordinates = match parser.geometry_type()?.as_str() {
+            "POINT" => parser
+                .empty_or(|parser| parser.parenthesized(|parser| Ok(vec![parser.coordinate()?])))?,
+            "MULTIPOINT" => parser.empty_or(|parser| {
+                parser.list(|parser| {
+                    // points of multi points may be enclosed in parentheses
+                    if parser.peek_token() == Some(Token::LeftParenthesis) {
+                        parser.parenthesized(WktParser::coordinate)
+                    } else {
+                        parser.coordinate()
+                    }
+                })
+            })?,
+            other => return Err(unexpected_type("POINT or MULTIPOINT", other)),
+        };
+
+        parser.end()?;
+
+        Self::new(coordinates)
+    }
+}
+
+impl FromWkt for MultiLineString {
+    fn from_wkt(wkt: &str) -> Result<Self> {
+        let mut parser = WktParser::new(wkt);
+
+        let lines = match parser.geometry_type()?.as_str() {
+            "LINESTRING" => parser.empty_or(|parser| Ok(vec![parser.line()?]))?,
+            "MULTILINESTRING" => {
+                parser.empty_or(|parser| parser.list(|parser| parser.empty_or(WktParser::line)))?
+            }
+            other => return Err(unexpected_type("LINESTRING or MULTILINESTRING", other)),
+        };
+
+        parser.end()?;
+
+        Self::new(lines)
+    }
+}
+
+impl FromWkt for MultiPolygon {
+    fn from_wkt(wkt: &str) -> Result<Self> {
+        let mut parser = WktParser::new(wkt);
+
+        let polygons = match parser.geometry_type()?.as_str() {
+            "POLYGON" => parser.empty_or(|parser| Ok(vec![parser.polygon()?]))?,
+            "MULTIPOLYGON" => parser
+                .empty_or(|parser| parser.list(|parser| parser.empty_or(WktParser::polygon)))?,
+            other => return Err(unexpected_type("POLYGON or MULTIPOLYGON", other)),
+        };
+
+        parser.end()?;
+
+        Self::new(polygons)
+    }
+}
+
+fn unexpected_type(expected: &str, found: &str) -> Error {
+    PrimitivesError::InvalidWkt {
+        reason: format!("expected {}, but found {}", expected, found),
+    }
+    .into()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'s> {
+    Word(&'s str),
+    Number(f64),
+    LeftParenthesis,
+    RightParenthesis,
+    Comma,
+}
+
+/// A recursive descent parser for the WKT grammar of the supported geometry types
+struct WktParser<'s> {
+    wkt: &'s str,
+    chars: Peekable<CharIndices<'s>>,
+    peeked: Option<Result<Token<'s>>>,
+}
+
+impl<'s> WktParser<'s> {
+    fn new(wkt: &'s str) -> Self {
+        Self {
+            wkt,
+            chars: wkt.char_indices().peekable(),
+            peeked: None,
+        }
+    }
+
+    fn error(reason: impl Into<String>) -> Error {
+        PrimitivesError::InvalidWkt {
+            reason: reason.into(),
+        }
+        .into()
+    }
+
+    fn lex(&mut self) -> Option<Result<Token<'s>>> {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+
+        let (start, c) = self.chars.next()?;
+
+        let token = match c {
+            '(' => Ok(Token::LeftParenthesis),
+            ')' => Ok(Token::RightParenthesis),
+            ',' => Ok(Token::Comma),
+            c if c.is_ascii_alphabetic() => {
+                let end = self.consume_while(start, |c| c.is_ascii_alphanumeric());
+                Ok(Token::Word(&self.wkt[start..end]))
+            }
+            c if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => {
+                let end = self.consume_while(start, |c| {
+                    c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')
+                });
+                let number = &self.wkt[start..end];
+                number
+                    .parse()
+                    .map(Token::Number)
+                    .map_err(|_| Self::error(format!("invalid number `{}`", number)))
+            }
+            c => Err(Self::error(format!("unexpected character `{}`", c))),
+        };
+
+        Some(token)
+    }
+
+    /// Consumes the characters after `start` that match the predicate and returns the end of the token
+    fn consume_while(&mut self, start: usize, predicate: impl Fn(char) -> bool) -> usize {
+        let mut end = start + 1;
+        while let Some((i, c)) = self.chars.next_if(|(_, c)| predicate(*c)) {
+            end = i + c.len_utf8();
+        }
+        end
+    }
+
+    fn peek_token(&mut self) -> Option<Token<'s>> {
+        if self.peeked.is_none() {
+            self.peeked = self.lex();
+        }
+
+        match self.peeked {
+            Some(Ok(token)) => Some(token),
+            _ => None,
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Token<'s>> {
+        self.peeked
+            .take()
+            .or_else(|| self.lex())
+            .unwrap_or_else(|| Err(Self::error("unexpected end")))
+    }
+
+    fn expect(&mut self, expected: Token<'s>) -> Result<()> {
+        match self.next_token()? {
+            token if token == expected => Ok(()),
+            token => Err(Self::error(format!(
+                "expected {:?}, but found {:?}",
+                expected, token
+            ))),
+        }
+    }
+
+    /// Parses the geometry type and skips the dimension tag, e.g., `Z` in `POINT Z (1 2 3)`
+    fn geometry_type(&mut self) -> Result<String> {
+        let geometry_type = match self.next_token()? {
+            Token::Word(word) => word.to_ascii_uppercase(),
+            token => {
+                return Err(Self::error(format!(
+                    "expected a type, but found {:?}",
+                    token
+                )))
+            }
+        };
+
+        if let Some(Token::Word(tag)) = self.peek_token() {
+            if ["Z", "M", "ZM"].contains(&tag.to_ascii_uppercase().as_str()) {
+                self.next_token()?;
+            }
+        }
+
+        Ok(geometry_type)
+    }
+
+    /// Parses an `EMPTY` geometry as an empty vector or the geometry with the `parse` function
+    fn empty_or<T, F>(&mut self, parse: F) -> Result<Vec<T>>
+    where
+        F: FnOnce(&mut Self) -> Result<Vec<T>>,
+    {
+        match self.peek_token() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("EMPTY") => {
+                self.next_token()?;
+                Ok(Vec::new())
+            }
+            _ => parse(self),
+        }
+    }
+
+    fn parenthesized<T, F>(&mut self, parse: F) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        self.expect(Token::LeftParenthesis)?;
+        let result = parse(self)?;
+        self.expect(Token::RightParenthesis)?;
+        Ok(result)
+    }
+
+    /// Parses a comma separated list of elements in parentheses
+    fn list<T, F>(&mut self, mut parse_element: F) -> Result<Vec<T>>
+    where
+        F: FnMut(&mut Self) -> Result<T>,
+    {
+        self.parenthesized(|parser| {
+            let mut elements = vec![parse_element(parser)?];
+            while parser.peek_token() == Some(Token::Comma) {
+                parser.next_token()?;
+                elements.push(parse_element(parser)?);
+            }
+            Ok(elements)
+        })
+    }
+
+    /// Parses a coordinate and drops the Z and M values
+    fn coordinate(&mut self) -> Result<Coordinate2D> {
+        let mut values = Vec::with_capacity(4);
+        while let Some(Token::Number(value)) = self.peek_token() {
+            self.next_token()?;
+            values.push(value);
+        }
+
+        match *values.as_slice() {
+            [x, y] | [x, y, _] | [x, y, _, _] => Ok(Coordinate2D::new(x, y)),
+            _ => Err(Self::error(format!(
+                "a coordinate must have two to four values, but has {}",
+                values.len()
+            ))),
+        }
+    }
+
+    fn line(&mut self) -> Result<Vec<Coordinate2D>> {
+        self.list(Self::coordinate)
+    }
+
+    fn polygon(&mut self) -> Result<Vec<Vec<Coordinate2D>>> {
+        self.list(Self::line)
+    }
+
+    fn end(&mut self) -> Result<()> {
+        match self.peeked.take().or_else(|| self.lex()) {
+            None => Ok(()),
+            Some(Ok(token)) => Err(Self::error(format!("unexpected {:?} at the end", token))),
+            Some(Err(error)) => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygons() -> MultiPolygon {
+        MultiPolygon::new(vec![
+            vec![
+                vec![
+                    (0.0, 0.0).into(),
+                    (10.0, 0.0).into(),
+                    (10.0, 10.0).into(),
+                    (0.0, 10.0).into(),
+                    (0.0, 0.0).into(),
+                ],
+                vec![
+                    (1.0, 1.0).into(),
+                    (2.0, 1.0).into(),
+                    (2.0, 2.0).into(),
+                    (1.0, 1.0).into(),
+                ],
+            ],
+            vec![vec![
+                (-0.5, -0.5).into(),
+                (-1.5, -0.5).into(),
+                (-1.5, -1.5).into(),
+                (-0.5, -0.5).into(),
+            ]],
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn it_writes_wkt() {
+        assert_eq!(
+            MultiPoint::new(vec![(1.0, 2.0).into(), (3.5, -4.25).into()])
+                .unwrap()
+                .to_wkt(),
+            "MULTIPOINT ((1 2), (3.5 -4.25))"
+        );
+        assert_eq!(
+            MultiLineString::new(vec![vec![(1.0, 2.0).into(), (3.0, 4.0).into()]])
+                .unwrap()
+                .to_wkt(),
+            "MULTILINESTRING ((1 2, 3 4))"
+        );
+        assert_eq!(
+            polygons().to_string(),
+            "MULTIPOLYGON (((0 0, 10 0, 10 10, 0 10, 0 0), (1 1, 2 1, 2 2, 1 1)), ((-0.5 -0.5, -1.5 -0.5, -1.5 -1.5, -0.5 -0.5)))"
+        );
+    }
+
+    #[test]
+    fn it_round_trips() {
+        let points = MultiPoint::new(vec![(0.1, 0.2).into(), (1e10, -1e-10).into()]).unwrap();
+        assert_eq!(MultiPoint::from_wkt(&points.to_wkt()).unwrap(), points);
+
+        let lines = MultiLineString::new(vec![
+            vec![(0.1, 0.2).into(), (0.3, 0.4).into()],
+            vec![(1.0, 2.0).into(), (3.0, 4.0).into(), (5.0, 6.0).into()],
+        ])
+        .unwrap();
+        assert_eq!(MultiLineString::from_wkt(&lines.to_wkt()).unwrap(), lines);
+
+        assert_eq!(
+            MultiPolygon::from_wkt(&polygons().to_wkt()).unwrap(),
+            polygons()
+        );
+    }
+
+    #[test]
+    fn it_reads_single_geometries_and_variants() {
+        let points = MultiPoint::new(vec![(1.0, 2.0).into(), (3.0, 4.0).into()]).unwrap();
+
+        assert_eq!(
+            MultiPoint::from_wkt("point(1 2)").unwrap(),
+            MultiPoint::new(vec![(1.0, 2.0).into()]).unwrap()
+        );
+        assert_eq!(
+            MultiPoint::from_wkt("MULTIPOINT (1 2, 3 4)").unwrap(),
+            points
+        );
+        assert_eq!(
+            MultiPoint::from_wkt("MULTIPOINT Z ((1 2 5), (3 4 6))").unwrap(),
+            points
+        );
+        assert_eq!(
+            MultiLineString::from_wkt("LINESTRING ZM (1 2 3 4, 3 4 5 6)").unwrap(),
+            MultiLineString::new(vec![vec![(1.0, 2.0).into(), (3.0, 4.0).into()]]).unwrap()
+        );
+        assert_eq!(
+            MultiPolygon::from_wkt("POLYGON ((0 0, 1 0, 1 1, 0 0))").unwrap(),
+            MultiPolygon::new(vec![vec![vec![
+                (0.0, 0.0).into(),
+                (1.0, 0.0).into(),
+                (1.0, 1.0).into(),
+                (0.0, 0.0).into(),
+            ]]])
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_wkt() {
+        assert!(MultiPoint::from_wkt("LINESTRING (1 2, 3 4)").is_err());
+        assert!(MultiPoint::from_wkt("MULTIPOINT EMPTY").is_err());
+        assert!(MultiPoint::from_wkt("POINT (1)").is_err());
+        assert!(MultiPoint::from_wkt("POINT (1 2").is_err());
+        assert!(MultiPoint::from_wkt("POINT (1 2) foo").is_err());
+        assert!(MultiPoint::from_wkt("POINT (1 2; 3 4)").is_err());
+        assert!(MultiPolygon::from_wkt("POLYGON ((0 0, 1 0, 1 1, 0 1))").is_err());
+    }
+
+    #[test]
+    fn it_round_trips_with_ogr() {
+        let polygons = polygons();
+
+        let ogr_geometry = gdal::vector::Geometry::from_wkt(&polygons.to_wkt()).unwrap();
+
+        assert_eq!(
+            MultiPolygon::from_wkt(&ogr_geometry.wkt().unwrap()).unwrap(),
+            polygons
+        );
+
+        let points = MultiPoint::new(vec![(1.0, 2.0).into(), (3.0, 4.0).into()]).unwrap();
+        let ogr_geometry = gdal::vector::Geometry::from_wkt(&points.to_wkt()).unwrap();
+
+        assert_eq!(
+            MultiPoint::from_wkt(&ogr_geometry.wkt().unwrap()).unwrap(),
+            points
+        );
+    }
+}
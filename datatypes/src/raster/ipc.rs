@@ -0,0 +1,354 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, Float64Array, Int64Array, ListArray, ListBuilder, PrimitiveArray,
+    PrimitiveBuilder, StringArray, UInt64Array,
+};
+use arrow::datatypes::{
+    ArrowPrimitiveType, DataType, Field, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+    Int8Type, Schema, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use arrow::ipc::reader::StreamReader;
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::error;
+use crate::primitives::TimeInterval;
+use crate::raster::{
+    EmptyGrid, GeoTransform, Grid, GridIdx2D, GridOrEmpty, GridShape2D, GridSize, Pixel,
+    RasterProperties, RasterPropertiesEntry, RasterPropertiesKey, RasterTile2D,
+};
+use crate::util::arrow::downcast_array;
+use crate::util::Result;
+
+/// A pixel type that has a corresponding `arrow` primitive type
+pub trait ArrowPixel: Pixel {
+    type ArrowType: ArrowPrimitiveType<Native = Self>;
+}
+
+macro_rules! impl_arrow_pixel {
+    ($($pixel:ty => $arrow:ty),+) => {
+        $(
+            impl ArrowPixel for $pixel {
+                type ArrowType = $arrow;
+            }
+        )+
+    };
+}
+
+impl_arrow_pixel!(
+    u8 => UInt8Type,
+    u16 => UInt16Type,
+    u32 => UInt32Type,
+    u64 => UInt64Type,
+    i8 => Int8Type,
+    i16 => Int16Type,
+    i32 => Int32Type,
+    i64 => Int64Type,
+    f32 => Float32Type,
+    f64 => Float64Type
+);
+
+/// A JSON representation of `RasterProperties`, since their map keys are no strings
+#[derive(Serialize, Deserialize)]
+struct IpcRasterProperties {
+    scale: Option<f64>,
+    offset: Option<f64>,
+    band_name: Option<String>,
+    entries: Vec<IpcRasterProperty>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IpcRasterProperty {
+    domain: Option<String>,
+    key: String,
+    value: IpcRasterPropertyValue,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum IpcRasterPropertyValue {
+    Number(f64),
+    String(String),
+}
+
+impl From<&RasterProperties> for IpcRasterProperties {
+    fn from(properties: &RasterProperties) -> Self {
+        Self {
+            scale: properties.scale,
+            offset: properties.offset,
+            band_name: properties.band_name.clone(),
+            entries: properties
+                .properties_map
+                .iter()
+                .map(|(key, value)| IpcRasterProperty {
+                    domain: key.domain.clone(),
+                    key: key.key.clone(),
+                    value: match value {
+                        RasterPropertiesEntry::Number(number) => {
+                            IpcRasterPropertyValue::Number(*number)
+                        }
+                        RasterPropertiesEntry::String(string) => {
+                            IpcRasterPropertyValue::String(string.clone())
+                        }
+                    },
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<IpcRasterProperties> for RasterProperties {
+    fn from(properties: IpcRasterProperties) -> Self {
+        Self {
+            scale: properties.scale,
+            offset: properties.offset,
+            band_name: properties.band_name,
+            properties_map: properties
+                .entries
+                .into_iter()
+                .map(|entry| {
+                    let value = match entry.value {
+                        IpcRasterPropertyValue::Number(number) => {
+                            RasterPropertiesEntry::Number(number)
+                        }
+                        IpcRasterPropertyValue::String(string) => {
+                            RasterPropertiesEntry::String(string)
+                        }
+                    };
+                    (
+                        RasterPropertiesKey {
+                            domain: entry.domain,
+                            key: entry.key,
+                        },
+                        value,
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The schema of a record batch that holds a single raster tile.
+///
+/// The `data` column is null for tiles that only contain no data.
+pub fn raster_tile_schema<T: ArrowPixel>() -> Schema {
+    let pixel_type = T::ArrowType::DATA_TYPE;
+
+    Schema::new(vec![
+        Field::new("time_start", DataType::Int64, false),
+        Field::new("time_end", DataType::Int64, false),
+        Field::new("tile_position_y", DataType::Int64, false),
+        Field::new("tile_position_x", DataType::Int64, false),
+        Field::new("origin_x", DataType::Float64, false),
+        Field::new("origin_y", DataType::Float64, false),
+        Field::new("x_pixel_size", DataType::Float64, false),
+        Field::new("y_pixel_size", DataType::Float64, false),
+        Field::new("shape_y", DataType::UInt64, false),
+        Field::new("shape_x", DataType::UInt64, false),
+        Field::new("no_data_value", pixel_type.clone(), true),
+        Field::new("properties", DataType::Utf8, false),
+        Field::new(
+            "data",
+            DataType::List(Box::new(Field::new("item", pixel_type, true))),
+            true,
+        ),
+    ])
+}
+
+/// Converts a raster tile into a record batch with a single row
+pub fn raster_tile_to_record_batch<T: ArrowPixel>(tile: &RasterTile2D<T>) -> Result<RecordBatch> {
+    let [tile_position_y, tile_position_x] = *tile.tile_position.inner();
+    let geo_transform = tile.global_geo_transform;
+
+    let (no_data_value, data) = match &tile.grid_array {
+        GridOrEmpty::Grid(grid) => (grid.no_data_value, Some(grid.data.as_slice())),
+        GridOrEmpty::Empty(empty_grid) => (Some(empty_grid.no_data_value), None),
+    };
+
+    let mut data_builder = ListBuilder::new(PrimitiveBuilder::<T::ArrowType>::new(
+        data.map_or(0, <[T]>::len),
+    ));
+    if let Some(data) = data {
+        data_builder.values().append_slice(data)?;
+    }
+    data_builder.append(data.is_some())?;
+
+    let properties =
+        serde_json::to_string(&IpcRasterProperties::from(&tile.properties)).map_err(|error| {
+            error::Error::InvalidRasterTileIpc {
+                details: error.to_string(),
+            }
+        })?;
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(Int64Array::from(vec![tile.time.start().inner()])),
+        Arc::new(Int64Array::from(vec![tile.time.end().inner()])),
+        Arc::new(Int64Array::from(vec![tile_position_y as i64])),
+        Arc::new(Int64Array::from(vec![tile_position_x as i64])),
+        Arc::new(Float64Array::from(vec![geo_transform.origin_coordinate.x])),
+        Arc::new(Float64Array::from(vec![geo_transform.origin_coordinate.y])),
+        Arc::new(Float64Array::from(vec![geo_transform.x_pixel_size()])),
+        Arc::new(Float64Array::from(vec![geo_transform.y_pixel_size()])),
+        Arc::new(UInt64Array::from(
+            vec![tile.grid_array.axis_size_y() as u64],
+        )),
+        Arc::new(UInt64Array::from(
+            vec![tile.grid_array.axis_size_x() as u64],
+        )),
+        Arc::new(PrimitiveArray::<T::ArrowType>::from_iter([no_data_value])),
+        Arc::new(StringArray::from(vec![properties])),
+        Arc::new(data_builder.finish()),
+    ];
+
+    Ok(RecordBatch::try_new(
+        Arc::new(raster_tile_schema::<T>()),
+        columns,
+    )?)
+}
+
+/// Restores a raster tile from a record batch that was created by [`raster_tile_to_record_batch`]
+pub fn raster_tile_from_record_batch<T: ArrowPixel>(
+    batch: &RecordBatch,
+) -> Result<RasterTile2D<T>> {
+    ensure!(
+        batch.schema().as_ref() == &raster_tile_schema::<T>(),
+        error::InvalidRasterTileIpc {
+            details: "unexpected schema"
+        }
+    );
+    ensure!(
+        batch.num_rows() == 1,
+        error::InvalidRasterTileIpc {
+            details: "a batch must contain exactly one tile"
+        }
+    );
+
+    // the schema was checked, so the downcasts cannot fail
+    let int64 = |column: usize| downcast_array::<Int64Array>(batch.column(column)).value(0);
+    let float64 = |column: usize| downcast_array::<Float64Array>(batch.column(column)).value(0);
+    let uint64 = |column: usize| downcast_array::<UInt64Array>(batch.column(column)).value(0);
+
+    let time = TimeInterval::new(int64(0), int64(1))?;
+    let tile_position = GridIdx2D::from([int64(2) as isize, int64(3) as isize]);
+    let global_geo_transform =
+        GeoTransform::new((float64(4), float64(5)).into(), float64(6), float64(7));
+    let shape = GridShape2D::from([uint64(8) as usize, uint64(9) as usize]);
+
+    let no_data_value = downcast_array::<PrimitiveArray<T::ArrowType>>(batch.column(10));
+    let no_data_value = if no_data_value.is_null(0) {
+        None
+    } else {
+        Some(no_data_value.value(0))
+    };
+
+    let properties: IpcRasterProperties = serde_json::from_str(
+        downcast_array::<StringArray>(batch.column(11)).value(0),
+    )
+    .map_err(|error| error::Error::InvalidRasterTileIpc {
+        details: error.to_string(),
+    })?;
+
+    let data = downcast_array::<ListArray>(batch.column(12));
+    let grid_array = if data.is_null(0) {
+        let no_data_value = no_data_value.ok_or(error::Error::InvalidRasterTileIpc {
+            details: "an empty tile requires a no data value".to_string(),
+        })?;
+        GridOrEmpty::Empty(EmptyGrid::new(shape, no_data_value))
+    } else {
+        let values = data.value(0);
+        let values = downcast_array::<PrimitiveArray<T::ArrowType>>(&values);
+        GridOrEmpty::Grid(Grid::new(shape, values.values().to_vec(), no_data_value)?)
+    };
+
+    Ok(RasterTile2D {
+        time,
+        tile_position,
+        global_geo_transform,
+        grid_array,
+        properties: properties.into(),
+    })
+}
+
+/// Writes raster tiles as an Arrow IPC stream with one record batch per tile
+pub fn raster_tiles_to_ipc_stream<T: ArrowPixel>(tiles: &[RasterTile2D<T>]) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+
+    let mut writer = StreamWriter::try_new(&mut bytes, &raster_tile_schema::<T>())?;
+    for tile in tiles {
+        writer.write(&raster_tile_to_record_batch(tile)?)?;
+    }
+    writer.finish()?;
+
+    drop(writer);
+
+    Ok(bytes)
+}
+
+/// Reads raster tiles from an Arrow IPC stream that was created by [`raster_tiles_to_ipc_stream`]
+pub fn raster_tiles_from_ipc_stream<T: ArrowPixel>(bytes: &[u8]) -> Result<Vec<RasterTile2D<T>>> {
+    let reader = StreamReader::try_new(Cursor::new(bytes))?;
+
+    reader
+        .map(|batch| raster_tile_from_record_batch(&batch?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ipc_roundtrip() {
+        let mut properties = RasterProperties {
+            scale: Some(2.),
+            ..Default::default()
+        };
+        properties.properties_map.insert(
+            RasterPropertiesKey {
+                domain: None,
+                key: "foo".to_string(),
+            },
+            RasterPropertiesEntry::String("bar".to_string()),
+        );
+
+        let tiles = vec![
+            RasterTile2D {
+                time: TimeInterval::new_unchecked(0, 10),
+                tile_position: [-1, 2].into(),
+                global_geo_transform: GeoTransform::new((0., 0.).into(), 1., -1.),
+                grid_array: GridOrEmpty::Grid(
+                    Grid::new([2, 2].into(), vec![1_u16, 2, 3, 4], Some(0)).unwrap(),
+                ),
+                properties,
+            },
+            RasterTile2D::new(
+                TimeInterval::new_unchecked(10, 20),
+                [0, 0].into(),
+                GeoTransform::new((0., 0.).into(), 1., -1.),
+                GridOrEmpty::Empty(EmptyGrid::new([2, 2].into(), 42_u16)),
+            ),
+        ];
+
+        let bytes = raster_tiles_to_ipc_stream(&tiles).unwrap();
+
+        assert_eq!(raster_tiles_from_ipc_stream::<u16>(&bytes).unwrap(), tiles);
+    }
+
+    #[test]
+    fn it_rejects_other_pixel_types() {
+        let tile = RasterTile2D::new(
+            TimeInterval::new_unchecked(0, 10),
+            [0, 0].into(),
+            GeoTransform::new((0., 0.).into(), 1., -1.),
+            GridOrEmpty::Grid(Grid::new([1, 1].into(), vec![1_u8], None).unwrap()),
+        );
+
+        let bytes = raster_tiles_to_ipc_stream(&[tile]).unwrap();
+
+        assert!(raster_tiles_from_ipc_stream::<f32>(&bytes).is_err());
+    }
+}
@@ -17,6 +17,10 @@ pub use self::grid_traits::{
     GridSize, GridSpaceToLinearSpace, GridStep,
 };
 pub use self::grid_typed::{TypedGrid, TypedGrid2D, TypedGrid3D};
+pub use self::ipc::{
+    raster_tile_from_record_batch, raster_tile_schema, raster_tile_to_record_batch,
+    raster_tiles_from_ipc_stream, raster_tiles_to_ipc_stream, ArrowPixel,
+};
 pub use self::operations::{
     blit::Blit, convert_data_type::ConvertDataType, convert_data_type::ConvertDataTypeParallel,
     grid_blit::GridBlit,
@@ -47,6 +51,7 @@ mod grid_index;
 mod grid_or_empty;
 mod grid_traits;
 mod grid_typed;
+mod ipc;
 mod macros_raster;
 mod macros_raster_tile;
 mod operations;
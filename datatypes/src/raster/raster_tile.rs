@@ -1,9 +1,10 @@
 use super::RasterProperties;
 use super::{
-    grid_or_empty::GridOrEmpty, GeoTransform, GeoTransformAccess, Grid, GridBounds, GridIdx2D,
-    GridIndexAccess, GridIndexAccessMut, GridShape, GridShape2D, GridShape3D, GridShapeAccess,
-    GridSize, NoDataValue, Raster, TileInformation,
+    grid_or_empty::GridOrEmpty, EmptyGrid, GeoTransform, GeoTransformAccess, Grid, GridBounds,
+    GridIdx2D, GridIndexAccess, GridIndexAccessMut, GridShape, GridShape2D, GridShape3D,
+    GridShapeAccess, GridSize, NoDataValue, Raster, TileInformation,
 };
+use crate::error;
 use crate::primitives::{
     Coordinate2D, SpatialBounded, SpatialPartition2D, SpatialPartitioned, TemporalBounded,
     TimeInterval,
@@ -11,6 +12,7 @@ use crate::primitives::{
 use crate::raster::{CoordinatePixelAccess, Pixel};
 use crate::util::Result;
 use serde::{Deserialize, Serialize};
+use snafu::ensure;
 
 /// A `RasterTile` is a `BaseTile` of raster data where the data is represented by `GridOrEmpty`.
 pub type RasterTile<D, T> = BaseTile<GridOrEmpty<D, T>>;
@@ -225,6 +227,107 @@ where
     }
 }
 
+impl<T> RasterTile3D<T>
+where
+    T: Pixel,
+{
+    /// Stacks 2D tiles as levels of a 3D tile, i.e., the `z` axis of the result is the index in `levels`.
+    /// All tiles must share the same time, tile position, geo transform, shape and no data value.
+    /// The properties are taken from the first level.
+    pub fn from_levels(levels: Vec<RasterTile2D<T>>) -> Result<Self> {
+        let first = levels.first().ok_or(error::Error::InvalidRasterLevels {
+            reason: "there must be at least one level".to_string(),
+        })?;
+
+        let shape = *first.grid_array.shape_ref();
+        let no_data_value = first.no_data_value();
+
+        for level in &levels[1..] {
+            ensure!(
+                level.time == first.time
+                    && level.tile_position == first.tile_position
+                    && level.global_geo_transform == first.global_geo_transform,
+                error::InvalidRasterLevels {
+                    reason: "all levels must have the same time and tile position".to_string()
+                }
+            );
+            ensure!(
+                *level.grid_array.shape_ref() == shape && level.no_data_value() == no_data_value,
+                error::InvalidRasterLevels {
+                    reason: "all levels must have the same shape and no data value".to_string()
+                }
+            );
+        }
+
+        let [y_size, x_size] = shape.shape_array;
+        let shape_3d = GridShape3D::from([levels.len(), y_size, x_size]);
+
+        let time = first.time;
+        let tile_position = first.tile_position;
+        let global_geo_transform = first.global_geo_transform;
+        let properties = first.properties.clone();
+
+        let grid_array = match no_data_value {
+            Some(no_data_value) if levels.iter().all(RasterTile2D::is_empty) => {
+                GridOrEmpty::Empty(EmptyGrid::new(shape_3d, no_data_value))
+            }
+            _ => {
+                let mut data = Vec::with_capacity(shape_3d.number_of_elements());
+                for level in levels {
+                    data.extend(level.grid_array.into_materialized_grid().data);
+                }
+                GridOrEmpty::Grid(Grid::new(shape_3d, data, no_data_value)?)
+            }
+        };
+
+        Ok(Self {
+            time,
+            tile_position,
+            global_geo_transform,
+            grid_array,
+            properties,
+        })
+    }
+
+    /// The number of levels, i.e., the size of the `z` axis
+    pub fn number_of_levels(&self) -> usize {
+        self.grid_array.shape_ref().shape_array[0]
+    }
+
+    /// Extracts a single level as a 2D tile
+    pub fn level(&self, level: usize) -> Result<RasterTile2D<T>> {
+        let [z_size, y_size, x_size] = self.grid_array.shape_ref().shape_array;
+
+        ensure!(
+            level < z_size,
+            error::InvalidRasterLevels {
+                reason: format!("level {} is out of bounds [0, {})", level, z_size)
+            }
+        );
+
+        let shape = GridShape2D::from([y_size, x_size]);
+
+        let grid_array = match &self.grid_array {
+            GridOrEmpty::Grid(grid) => {
+                let level_size = shape.number_of_elements();
+                let data = grid.data[level * level_size..(level + 1) * level_size].to_vec();
+                GridOrEmpty::Grid(Grid::new(shape, data, grid.no_data_value)?)
+            }
+            GridOrEmpty::Empty(empty) => {
+                GridOrEmpty::Empty(EmptyGrid::new(shape, empty.no_data_value))
+            }
+        };
+
+        Ok(RasterTile2D::new_with_properties(
+            self.time,
+            self.tile_position,
+            self.global_geo_transform,
+            grid_array,
+            self.properties.clone(),
+        ))
+    }
+}
+
 impl<G> TemporalBounded for BaseTile<G> {
     fn temporal_bounds(&self) -> TimeInterval {
         self.time
@@ -631,4 +734,58 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn stack_levels() {
+        let level_a = RasterTile2D::new(
+            TimeInterval::default(),
+            [0, 0].into(),
+            TestDefault::test_default(),
+            Grid2D::new([2, 2].into(), vec![1, 2, 3, 4], Some(0))
+                .unwrap()
+                .into(),
+        );
+        let level_b = RasterTile2D::new(
+            TimeInterval::default(),
+            [0, 0].into(),
+            TestDefault::test_default(),
+            EmptyGrid::new([2, 2].into(), 0).into(),
+        );
+
+        let tile_3d = RasterTile3D::from_levels(vec![level_a.clone(), level_b.clone()]).unwrap();
+
+        assert_eq!(tile_3d.number_of_levels(), 2);
+        assert_eq!(
+            tile_3d.get_at_grid_index([0, 1, 0]).unwrap(),
+            3,
+            "the first level is at z = 0"
+        );
+        assert_eq!(tile_3d.get_at_grid_index([1, 1, 0]).unwrap(), 0);
+
+        assert_eq!(tile_3d.level(0).unwrap(), level_a);
+        assert_eq!(
+            tile_3d.level(1).unwrap().into_materialized_tile(),
+            level_b.into_materialized_tile()
+        );
+        assert!(tile_3d.level(2).is_err());
+    }
+
+    #[test]
+    fn stack_levels_requires_same_position() {
+        let level_a = RasterTile2D::new(
+            TimeInterval::default(),
+            [0, 0].into(),
+            TestDefault::test_default(),
+            EmptyGrid::new([2, 2].into(), 0_u8).into(),
+        );
+        let level_b = RasterTile2D::new(
+            TimeInterval::default(),
+            [0, 1].into(),
+            TestDefault::test_default(),
+            EmptyGrid::new([2, 2].into(), 0_u8).into(),
+        );
+
+        assert!(RasterTile3D::from_levels(vec![level_a, level_b]).is_err());
+        assert!(RasterTile3D::<u8>::from_levels(vec![]).is_err());
+    }
 }
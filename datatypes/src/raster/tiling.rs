@@ -1,5 +1,8 @@
 use crate::{
-    primitives::{AxisAlignedRectangle, Coordinate2D, SpatialPartition2D, SpatialPartitioned},
+    primitives::{
+        AxisAlignedRectangle, Coordinate2D, SpatialPartition2D, SpatialPartitioned,
+        SpatialResolution,
+    },
     util::test::TestDefault,
 };
 
@@ -7,6 +10,12 @@ use super::{GeoTransform, GridBoundingBox2D, GridIdx, GridIdx2D, GridShape2D, Gr
 
 use serde::{Deserialize, Serialize};
 
+/// Half the width and height of the square extent of Web Mercator (EPSG:3857) tile maps in meters
+pub const WEB_MERCATOR_HALF_EXTENT: f64 = 20_037_508.342_789_244;
+
+/// The highest zoom level of Web Mercator tile maps that is considered for snapping resolutions
+const MAX_WEB_MERCATOR_ZOOM_LEVEL: u8 = 30;
+
 /// The static parameters of a `TilingStrategy`
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct TilingSpecification {
@@ -29,6 +38,61 @@ impl TilingSpecification {
 
         TilingStrategy::new_with_tiling_spec(self, x_pixel_size, y_pixel_size)
     }
+
+    /// Creates a tiling whose tiles coincide with the tiles of Web Mercator tile maps (XYZ, WMTS)
+    /// with `tile_size` pixels, for queries in EPSG:3857 at the resolution of a zoom level.
+    pub fn web_mercator(tile_size: usize) -> Self {
+        Self {
+            origin_coordinate: Coordinate2D::new(
+                -WEB_MERCATOR_HALF_EXTENT,
+                WEB_MERCATOR_HALF_EXTENT,
+            ),
+            tile_size_in_pixels: GridShape2D::new([tile_size, tile_size]),
+        }
+    }
+
+    /// Whether the tiles coincide with the tiles of Web Mercator tile maps
+    pub fn is_web_mercator_aligned(&self) -> bool {
+        let [tile_size_y, tile_size_x] = self.tile_size_in_pixels.shape_array;
+
+        tile_size_x == tile_size_y
+            && self.origin_coordinate
+                == Coordinate2D::new(-WEB_MERCATOR_HALF_EXTENT, WEB_MERCATOR_HALF_EXTENT)
+    }
+
+    /// The resolution of a Web Mercator zoom level for the tile size of this tiling
+    pub fn web_mercator_zoom_level_resolution(&self, zoom_level: u8) -> f64 {
+        let tiles = 2_f64.powi(i32::from(zoom_level));
+        2. * WEB_MERCATOR_HALF_EXTENT / (self.tile_size_in_pixels.axis_size_x() as f64 * tiles)
+    }
+
+    /// Snaps a resolution that deviates from the resolution of a Web Mercator zoom level only
+    /// by floating point errors to this zoom level.
+    /// Thus, the query of a map tile is answered by exactly one tile.
+    ///
+    /// Returns `None` if this tiling is not Web Mercator aligned or the resolution does not match
+    /// any zoom level.
+    pub fn snap_to_web_mercator_zoom_level(
+        &self,
+        resolution: SpatialResolution,
+    ) -> Option<SpatialResolution> {
+        const MAX_RELATIVE_ERROR: f64 = 1e-6;
+
+        if !self.is_web_mercator_aligned() {
+            return None;
+        }
+
+        (0..=MAX_WEB_MERCATOR_ZOOM_LEVEL)
+            .map(|zoom_level| self.web_mercator_zoom_level_resolution(zoom_level))
+            .find(|zoom_resolution| {
+                ((resolution.x - zoom_resolution) / zoom_resolution).abs() < MAX_RELATIVE_ERROR
+                    && ((resolution.y - zoom_resolution) / zoom_resolution).abs()
+                        < MAX_RELATIVE_ERROR
+            })
+            .map(|zoom_resolution| {
+                SpatialResolution::new_unchecked(zoom_resolution, zoom_resolution)
+            })
+    }
 }
 
 impl TestDefault for TilingSpecification {
@@ -288,6 +352,36 @@ mod tests {
         assert_eq!(strat.lower_right_pixel_idx(partition), [7, 7].into());
     }
 
+    #[test]
+    fn it_aligns_tiles_with_web_mercator_tiles() {
+        let tiling = TilingSpecification::web_mercator(256);
+        assert!(tiling.is_web_mercator_aligned());
+        assert!(!TilingSpecification::test_default().is_web_mercator_aligned());
+
+        // the map tile x=2, y=1 of zoom level 2, with a floating point error in its bounds
+        let tile_extent = WEB_MERCATOR_HALF_EXTENT / 2.;
+        let partition =
+            SpatialPartition2D::new((0., tile_extent).into(), (tile_extent + 1e-9, 0.).into())
+                .unwrap();
+        let resolution =
+            SpatialResolution::new_unchecked(partition.size_x() / 256., partition.size_y() / 256.);
+
+        let snapped = tiling.snap_to_web_mercator_zoom_level(resolution).unwrap();
+        assert_eq!(snapped.x, tiling.web_mercator_zoom_level_resolution(2));
+
+        let tiles = tiling
+            .strategy(snapped.x, -snapped.y)
+            .tile_information_iterator(partition)
+            .collect::<Vec<_>>();
+
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].global_tile_position, [1, 2].into());
+
+        assert!(tiling
+            .snap_to_web_mercator_zoom_level(SpatialResolution::new_unchecked(1000., 1000.))
+            .is_none());
+    }
+
     #[test]
     fn it_generates_only_intersected_tiles() {
         let strat = TilingStrategy {
@@ -114,6 +114,15 @@ impl SpatialReference {
         self.area_of_use::<A>()?.reproject(&p)
     }
 
+    /// Whether the coordinates are longitudes and latitudes in degrees
+    pub fn is_geographic(self) -> Result<bool> {
+        if self == Self::epsg_4326() {
+            return Ok(true);
+        }
+
+        Ok(SpatialRef::try_from(self)?.is_geographic())
+    }
+
     /// Return the srs-string "authority:code"
     #[allow(clippy::trivially_copy_pass_by_ref)]
     pub fn srs_string(&self) -> String {
@@ -1,6 +1,7 @@
 use std::any::Any;
 
-use arrow::array::{Array, ArrayBuilder, ArrayRef, BooleanArray};
+use arrow::array::{Array, ArrayBuilder, ArrayRef, BooleanArray, UInt64Array};
+use arrow::compute::{FilterBuilder, FilterPredicate};
 use arrow::datatypes::{DataType, Field};
 use arrow::error::ArrowError;
 
@@ -36,6 +37,37 @@ pub fn downcast_mut_array<T: Any>(array: &mut dyn ArrayBuilder) -> &mut T {
     array.as_any_mut().downcast_mut().unwrap() // must obey type
 }
 
+/// Computes the indices of the rows of an array of length `len` that are selected by the `predicate`
+///
+/// # Errors
+/// Fails if the `predicate` is longer than `len`
+///
+pub fn selected_indices(
+    predicate: &FilterPredicate,
+    len: usize,
+) -> Result<UInt64Array, ArrowError> {
+    let indices = UInt64Array::from_iter_values(0..len as u64);
+    let selected = predicate.filter(&indices)?;
+    Ok(selected.data().clone().into())
+}
+
+/// Filters an array by using a boolean filter array.
+///
+/// In contrast to `arrow::compute::filter`, this uses the `take` kernel, which supports nested (fixed size) lists
+/// like geometries and time intervals. The result is compact, i.e., its offsets start at zero.
+///
+/// # Errors
+/// Fails if the `filter_array` is longer than the `array`
+///
+pub fn filter_by_take(
+    array: &dyn Array,
+    filter_array: &BooleanArray,
+) -> Result<ArrayRef, ArrowError> {
+    let predicate = FilterBuilder::new(filter_array).build();
+    let indices = selected_indices(&predicate, array.len())?;
+    arrow::compute::take(array, &indices, None)
+}
+
 /// A trait to get information about the corresponding `arrow` type
 pub trait ArrowTyped {
     type ArrowArray: Array + 'static;
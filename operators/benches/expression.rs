@@ -33,6 +33,7 @@ fn expression_on_sources(
             output_no_data_value: 0.,
             output_measurement: Some(Measurement::Unitless),
             map_no_data: false,
+            tile_properties: vec![],
         },
         sources: ExpressionSources::new_a_b(a, b),
     }
@@ -74,6 +75,7 @@ async fn main() {
         spatial_bounds: SpatialPartition2D::new((-180., 90.).into(), (180., -90.).into()).unwrap(),
         time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000).unwrap(),
         spatial_resolution: SpatialResolution::new(0.01, 0.01).unwrap(),
+        spatial_filter: (),
     };
 
     let mut times = NumberStatistics::default();
@@ -40,6 +40,7 @@ async fn pip(points: MultiPointCollection, polygons: MultiPolygonCollection, num
         spatial_bounds: BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
         time_interval: TimeInterval::default(),
         spatial_resolution: SpatialResolution::zero_point_one(),
+        spatial_filter: None,
     };
     let ctx = MockQueryContext::with_chunk_size_and_thread_count(ChunkByteSize::MAX, num_threads);
 
@@ -116,6 +116,7 @@ fn bench_no_data_tiles() {
                 time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000)
                     .unwrap(),
                 spatial_resolution: SpatialResolution::zero_point_one(),
+                spatial_filter: (),
             },
         ),
         (
@@ -126,6 +127,7 @@ fn bench_no_data_tiles() {
                 time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000)
                     .unwrap(),
                 spatial_resolution: SpatialResolution::zero_point_one(),
+                spatial_filter: (),
             },
         ),
         (
@@ -136,6 +138,7 @@ fn bench_no_data_tiles() {
                 time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000)
                     .unwrap(),
                 spatial_resolution: SpatialResolution::zero_point_one(),
+                spatial_filter: (),
             },
         ),
         (
@@ -146,6 +149,7 @@ fn bench_no_data_tiles() {
                 time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000)
                     .unwrap(),
                 spatial_resolution: SpatialResolution::zero_point_one(),
+                spatial_filter: (),
             },
         ),
         (
@@ -156,6 +160,7 @@ fn bench_no_data_tiles() {
                 time_interval: TimeInterval::new(1_000_000_000_000, 1_000_000_000_000 + 1000)
                     .unwrap(),
                 spatial_resolution: SpatialResolution::zero_point_one(),
+                spatial_filter: (),
             },
         ),
     ];
@@ -191,6 +196,7 @@ fn bench_tile_size() {
                 .unwrap(),
             time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000).unwrap(),
             spatial_resolution: SpatialResolution::new(0.01, 0.01).unwrap(),
+            spatial_filter: (),
         },
     )];
 
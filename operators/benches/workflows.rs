@@ -270,6 +270,7 @@ fn bench_mock_source_operator(bench_collector: &mut BenchmarkCollector) {
         spatial_bounds: SpatialPartition2D::new((-180., 90.).into(), (180., -90.).into()).unwrap(),
         time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000).unwrap(),
         spatial_resolution: SpatialResolution::new(0.01, 0.01).unwrap(),
+        spatial_filter: (),
     };
     let tiling_spec = TilingSpecification::new((0., 0.).into(), [512, 512].into());
 
@@ -307,6 +308,7 @@ fn bench_mock_source_operator(bench_collector: &mut BenchmarkCollector) {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(|v| v as f64),
+                    dimensions: vec![],
                 },
             },
         }
@@ -332,6 +334,7 @@ fn bench_mock_source_operator_with_expression(bench_collector: &mut BenchmarkCol
         spatial_bounds: SpatialPartition2D::new((-180., 90.).into(), (180., -90.).into()).unwrap(),
         time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000).unwrap(),
         spatial_resolution: SpatialResolution::new(0.005, 0.005).unwrap(),
+        spatial_filter: (),
     };
 
     let qrects = vec![("World in 72000x36000 pixels", qrect)];
@@ -375,6 +378,7 @@ fn bench_mock_source_operator_with_expression(bench_collector: &mut BenchmarkCol
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(|v| v as f64),
+                    dimensions: vec![],
                 },
             },
         };
@@ -386,6 +390,7 @@ fn bench_mock_source_operator_with_expression(bench_collector: &mut BenchmarkCol
                 output_no_data_value: 0., //  cast no_data_valuee to f64
                 output_measurement: Some(Measurement::Unitless),
                 map_no_data: false,
+                tile_properties: vec![],
             },
             sources: ExpressionSources::new_a_b(
                 mock_raster_operator.clone().boxed(),
@@ -414,6 +419,7 @@ fn bench_mock_source_operator_with_identity_reprojection(bench_collector: &mut B
         spatial_bounds: SpatialPartition2D::new((-180., 90.).into(), (180., -90.).into()).unwrap(),
         time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000).unwrap(),
         spatial_resolution: SpatialResolution::new(0.01, 0.01).unwrap(),
+        spatial_filter: (),
     };
 
     let qrects = vec![("World in 36000x18000 pixels", qrect)];
@@ -456,6 +462,7 @@ fn bench_mock_source_operator_with_identity_reprojection(bench_collector: &mut B
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(|v| v as f64),
+                    dimensions: vec![],
                 },
             },
         };
@@ -494,6 +501,7 @@ fn bench_mock_source_operator_with_4326_to_3857_reprojection(
         .unwrap(),
         time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000).unwrap(),
         spatial_resolution: SpatialResolution::new(1050., 2100.).unwrap(),
+        spatial_filter: (),
     };
     let tiling_spec = TilingSpecification::new((0., 0.).into(), [512, 512].into());
 
@@ -529,6 +537,7 @@ fn bench_mock_source_operator_with_4326_to_3857_reprojection(
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(|v| v as f64),
+                    dimensions: vec![],
                 },
             },
         };
@@ -566,6 +575,7 @@ fn bench_gdal_source_operator_tile_size(bench_collector: &mut BenchmarkCollector
                 time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000)
                     .unwrap(),
                 spatial_resolution: SpatialResolution::new(0.01, 0.01).unwrap(),
+                spatial_filter: (),
             },
         ),
         (
@@ -576,6 +586,7 @@ fn bench_gdal_source_operator_tile_size(bench_collector: &mut BenchmarkCollector
                 time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000)
                     .unwrap(),
                 spatial_resolution: SpatialResolution::new(0.005, 0.005).unwrap(),
+                spatial_filter: (),
             },
         ),
     ];
@@ -629,6 +640,7 @@ fn bench_gdal_source_operator_with_expression_tile_size(bench_collector: &mut Be
                 .unwrap(),
             time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000).unwrap(),
             spatial_resolution: SpatialResolution::new(0.01, 0.01).unwrap(),
+            spatial_filter: (),
         },
     )];
 
@@ -662,6 +674,7 @@ fn bench_gdal_source_operator_with_expression_tile_size(bench_collector: &mut Be
             output_no_data_value: 0., //  cast no_data_valuee to f64
             output_measurement: Some(Measurement::Unitless),
             map_no_data: false,
+            tile_properties: vec![],
         },
         sources: ExpressionSources::new_a_b(gdal_operator.clone().boxed(), gdal_operator.boxed()),
     }
@@ -692,6 +705,7 @@ fn bench_gdal_source_operator_with_identity_reprojection(bench_collector: &mut B
                 .unwrap(),
             time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000).unwrap(),
             spatial_resolution: SpatialResolution::new(0.01, 0.01).unwrap(),
+            spatial_filter: (),
         },
     )];
 
@@ -756,6 +770,7 @@ fn bench_gdal_source_operator_with_4326_to_3857_reprojection(
             .unwrap(),
             time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000).unwrap(),
             spatial_resolution: SpatialResolution::new(1050., 2100.).unwrap(),
+            spatial_filter: (),
         },
     )];
 
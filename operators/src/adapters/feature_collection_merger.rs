@@ -175,11 +175,12 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((0.0, 0.0).into(), (10.0, 10.0).into()).unwrap(),
             time_interval: Default::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
         let cx = MockQueryContext::new((std::mem::size_of::<Coordinate2D>() * 2).into());
 
         let number_of_source_chunks = processor
-            .query(qrect, &cx)
+            .query(qrect.clone(), &cx)
             .await
             .unwrap()
             .fold(0_usize, |i, _| async move { i + 1 })
@@ -247,6 +248,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((0.0, 0.0).into(), (0.0, 0.0).into()).unwrap(),
             time_interval: Default::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
         let cx = MockQueryContext::new((0).into());
 
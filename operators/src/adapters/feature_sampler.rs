@@ -0,0 +1,182 @@
+use crate::util::Result;
+use futures::ready;
+use futures::stream::FusedStream;
+use futures::Stream;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+};
+use geoengine_datatypes::primitives::Geometry;
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use pin_project::pin_project;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Samples a stream of `FeatureCollection`s for previews.
+///
+/// It keeps every `step`th feature and ends the stream after `max_features` features were read,
+/// s.t. the work of expensive queries is bounded.
+/// If features were left out, the `sampled` marker is set.
+#[pin_project(project = FeatureSamplerProjection)]
+pub struct FeatureSampler<St, G>
+where
+    St: Stream<Item = Result<FeatureCollection<G>>>,
+    G: Geometry + ArrowTyped,
+{
+    #[pin]
+    stream: St,
+    step: usize,
+    max_features: usize,
+    read_features: usize,
+    sampled: Arc<AtomicBool>,
+    terminated: bool,
+}
+
+impl<St, G> FeatureSampler<St, G>
+where
+    St: Stream<Item = Result<FeatureCollection<G>>>,
+    G: Geometry + ArrowTyped + 'static,
+{
+    pub fn new(stream: St, step: usize, max_features: usize, sampled: Arc<AtomicBool>) -> Self {
+        Self {
+            stream,
+            step: step.max(1),
+            max_features,
+            read_features: 0,
+            sampled,
+            terminated: false,
+        }
+    }
+}
+
+impl<St, G> Stream for FeatureSampler<St, G>
+where
+    St: Stream<Item = Result<FeatureCollection<G>>>,
+    G: Geometry + ArrowTyped + 'static,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St::Item>> {
+        let FeatureSamplerProjection {
+            stream,
+            step,
+            max_features,
+            read_features,
+            sampled,
+            terminated,
+        } = self.project();
+
+        if *terminated {
+            return Poll::Ready(None);
+        }
+
+        let collection = match ready!(stream.poll_next(cx)) {
+            Some(Ok(collection)) => collection,
+            Some(Err(error)) => return Poll::Ready(Some(Err(error))),
+            None => {
+                *terminated = true;
+                return Poll::Ready(None);
+            }
+        };
+
+        if *read_features >= *max_features {
+            // more results are available, but the budget is exhausted
+            *terminated = true;
+            if !collection.is_empty() {
+                sampled.store(true, Ordering::Relaxed);
+            }
+            return Poll::Ready(None);
+        }
+
+        let remaining = *max_features - *read_features;
+        let mask: Vec<bool> = (0..collection.len())
+            .map(|i| i < remaining && (*read_features + i) % *step == 0)
+            .collect();
+        *read_features += collection.len();
+
+        if mask.contains(&false) {
+            sampled.store(true, Ordering::Relaxed);
+        }
+
+        Poll::Ready(Some(collection.filter(mask).map_err(Into::into)))
+    }
+}
+
+impl<St, G> FusedStream for FeatureSampler<St, G>
+where
+    St: Stream<Item = Result<FeatureCollection<G>>>,
+    G: Geometry + ArrowTyped + 'static,
+{
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::FeatureCollectionStreamExt;
+    use futures::TryStreamExt;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{MultiPoint, TimeInterval};
+
+    fn points(coordinates: &[f64]) -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(coordinates.iter().map(|&c| (c, c)).collect::<Vec<_>>()).unwrap(),
+            vec![TimeInterval::default(); coordinates.len()],
+            Default::default(),
+        )
+        .unwrap()
+    }
+
+    fn stream(
+        collections: Vec<MultiPointCollection>,
+    ) -> impl Stream<Item = Result<MultiPointCollection>> {
+        futures::stream::iter(collections.into_iter().map(Ok))
+    }
+
+    #[tokio::test]
+    async fn it_samples_every_nth_feature() {
+        let sampled = Arc::new(AtomicBool::new(false));
+
+        let collections: Vec<MultiPointCollection> =
+            stream(vec![points(&[0., 1., 2.]), points(&[3., 4., 5.])])
+                .sample(2, 10, sampled.clone())
+                .try_collect()
+                .await
+                .unwrap();
+
+        assert_eq!(collections, vec![points(&[0., 2.]), points(&[4.])]);
+        assert!(sampled.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn it_stops_after_the_budget() {
+        let sampled = Arc::new(AtomicBool::new(false));
+
+        let collections: Vec<MultiPointCollection> =
+            stream(vec![points(&[0., 1.]), points(&[2., 3.]), points(&[4.])])
+                .sample(1, 3, sampled.clone())
+                .try_collect()
+                .await
+                .unwrap();
+
+        assert_eq!(collections, vec![points(&[0., 1.]), points(&[2.])]);
+        assert!(sampled.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn it_does_not_mark_complete_results() {
+        let sampled = Arc::new(AtomicBool::new(false));
+
+        let collections: Vec<MultiPointCollection> = stream(vec![points(&[0., 1.])])
+            .sample(1, 2, sampled.clone())
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(collections, vec![points(&[0., 1.])]);
+        assert!(!sampled.load(Ordering::Relaxed));
+    }
+}
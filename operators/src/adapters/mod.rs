@@ -1,12 +1,16 @@
 mod feature_collection_merger;
+mod feature_sampler;
 mod raster_conversion;
+mod raster_query_splitter;
 mod raster_subquery;
 mod raster_time;
 mod raster_time_substream;
 mod sparse_tiles_fill_adapter;
 
 pub use feature_collection_merger::FeatureCollectionChunkMerger;
+pub use feature_sampler::FeatureSampler;
 pub use raster_conversion::RasterConversionQueryProcessor;
+pub use raster_query_splitter::{split_query, QuerySplitLimits, RasterQuerySplitter};
 pub use raster_subquery::{
     fold_by_coordinate_lookup_future, FoldTileAccu, FoldTileAccuMut, RasterSubQueryAdapter,
     SubQueryTileAggregator, TileReprojectionSubQuery,
@@ -23,6 +27,8 @@ use geoengine_datatypes::{
     raster::{Pixel, RasterTile2D},
     util::arrow::ArrowTyped,
 };
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
 /// This trait extends `RasterTile2D` `Stream`s with Geo-Engine-specific functionality.
 ///
@@ -72,6 +78,20 @@ where
     {
         FeatureCollectionChunkMerger::new(self.fuse(), chunk_size_bytes)
     }
+
+    /// Samples the features for previews by keeping every `step`th feature and ending the stream
+    /// after `max_features` features were read. Sets `sampled` if features were left out.
+    fn sample(
+        self,
+        step: usize,
+        max_features: usize,
+        sampled: Arc<AtomicBool>,
+    ) -> FeatureSampler<Self, CollectionType>
+    where
+        Self: Sized,
+    {
+        FeatureSampler::new(self, step, max_features, sampled)
+    }
 }
 
 impl<T: ?Sized, CollectionType: Geometry + ArrowTyped + 'static>
@@ -46,4 +46,12 @@ where
 
         Ok(converted_stream.boxed())
     }
+
+    async fn query_is_empty<'b>(
+        &'b self,
+        query: RasterQueryRectangle,
+        ctx: &'b dyn QueryContext,
+    ) -> Result<bool> {
+        self.query_processor.query_is_empty(query, ctx).await
+    }
 }
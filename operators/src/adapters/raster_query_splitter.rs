@@ -0,0 +1,336 @@
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, Coordinate2D, RasterQueryRectangle, SpatialPartition2D, TimeInstance,
+    TimeInterval,
+};
+use geoengine_datatypes::raster::{GridIdx, Pixel, RasterTile2D, TilingSpecification};
+use log::debug;
+
+use crate::engine::{BoxRasterQueryProcessor, QueryContext, QueryProcessor, RasterQueryProcessor};
+use crate::util::Result;
+
+/// Limits the size of the sub-queries of a [`RasterQuerySplitter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuerySplitLimits {
+    /// The maximum number of pixels of a sub-query, including the parts of the tiles that
+    /// exceed the query rectangle. A sub-query contains at least one row of tiles.
+    pub max_pixels_per_query: usize,
+    /// The maximum length of the time interval of a sub-query in milliseconds
+    pub max_time_interval_ms: Option<u64>,
+    /// The number of sub-queries that are computed in parallel.
+    /// For more than one, the tiles of each sub-query are buffered until it is completed.
+    pub parallel_queries: usize,
+}
+
+/// Splits oversized raster queries into sub-queries whose results are concatenated.
+///
+/// The query time interval is split into consecutive intervals and the query rectangle is split
+/// into bands of whole tile rows. Hence, no tile is computed twice.
+/// The tiles are returned in the order of the sub-queries, i.e., by time interval first and band
+/// second. Thus, for queries that span multiple time steps, the tiles are not strictly ordered by
+/// time anymore.
+pub struct RasterQuerySplitter<T: Pixel> {
+    processor: BoxRasterQueryProcessor<T>,
+    tiling_specification: TilingSpecification,
+    limits: QuerySplitLimits,
+}
+
+impl<T: Pixel> RasterQuerySplitter<T> {
+    pub fn new(
+        processor: BoxRasterQueryProcessor<T>,
+        tiling_specification: TilingSpecification,
+        limits: QuerySplitLimits,
+    ) -> Self {
+        Self {
+            processor,
+            tiling_specification,
+            limits,
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Pixel> QueryProcessor for RasterQuerySplitter<T> {
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let queries = split_query(query, self.tiling_specification, self.limits);
+
+        if queries.len() == 1 {
+            return self.processor.raster_query(query, ctx).await;
+        }
+
+        debug!("Split raster query into {} sub-queries", queries.len());
+
+        let sub_queries = stream::iter(queries);
+
+        if self.limits.parallel_queries <= 1 {
+            let stream = sub_queries
+                .then(move |query| self.processor.raster_query(query, ctx))
+                .try_flatten();
+
+            return Ok(stream.boxed());
+        }
+
+        let stream = sub_queries
+            .map(move |query| async move {
+                let tiles: Vec<_> = self
+                    .processor
+                    .raster_query(query, ctx)
+                    .await?
+                    .try_collect()
+                    .await?;
+                Result::Ok(stream::iter(tiles.into_iter().map(Result::Ok)))
+            })
+            .buffered(self.limits.parallel_queries)
+            .try_flatten();
+
+        Ok(stream.boxed())
+    }
+}
+
+/// Splits the `query` into sub-queries that satisfy the `limits`
+pub fn split_query(
+    query: RasterQueryRectangle,
+    tiling_specification: TilingSpecification,
+    limits: QuerySplitLimits,
+) -> Vec<RasterQueryRectangle> {
+    let bands = split_spatially(query, tiling_specification, limits.max_pixels_per_query);
+
+    split_temporally(query.time_interval, limits.max_time_interval_ms)
+        .into_iter()
+        .flat_map(|time_interval| {
+            bands
+                .iter()
+                .map(move |&spatial_bounds| RasterQueryRectangle {
+                    spatial_bounds,
+                    time_interval,
+                    spatial_resolution: query.spatial_resolution,
+                    spatial_filter: (),
+                })
+        })
+        .collect()
+}
+
+fn split_spatially(
+    query: RasterQueryRectangle,
+    tiling_specification: TilingSpecification,
+    max_pixels_per_query: usize,
+) -> Vec<SpatialPartition2D> {
+    let bounds = query.spatial_bounds;
+    let strategy =
+        tiling_specification.strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+    let GridIdx([first_row, first_column]) =
+        strategy.pixel_idx_to_tile_idx(strategy.upper_left_pixel_idx(bounds));
+    let GridIdx([last_row, last_column]) =
+        strategy.pixel_idx_to_tile_idx(strategy.lower_right_pixel_idx(bounds));
+
+    let [tile_height, tile_width] = tiling_specification.tile_size_in_pixels.into_inner();
+    let pixels_per_row = (last_column - first_column + 1) as usize * tile_height * tile_width;
+    let rows = (last_row - first_row + 1) as usize;
+
+    if rows * pixels_per_row <= max_pixels_per_query {
+        return vec![bounds];
+    }
+
+    let rows_per_band = (max_pixels_per_query / pixels_per_row).max(1) as isize;
+    let row_upper_y = |row: isize| {
+        strategy
+            .geo_transform
+            .grid_idx_to_upper_left_coordinate_2d([row * tile_height as isize, 0].into())
+            .y
+    };
+
+    (first_row..=last_row)
+        .step_by(rows_per_band as usize)
+        .map(|row| {
+            let upper_y = row_upper_y(row).min(bounds.upper_left().y);
+            let lower_y = row_upper_y(row + rows_per_band).max(bounds.lower_right().y);
+
+            SpatialPartition2D::new_unchecked(
+                Coordinate2D::new(bounds.upper_left().x, upper_y),
+                Coordinate2D::new(bounds.lower_right().x, lower_y),
+            )
+        })
+        .collect()
+}
+
+fn split_temporally(
+    time_interval: TimeInterval,
+    max_time_interval_ms: Option<u64>,
+) -> Vec<TimeInterval> {
+    let max_time_interval_ms = match max_time_interval_ms {
+        Some(max) if max > 0 => max as i64,
+        _ => return vec![time_interval],
+    };
+
+    // there is no meaningful way to split unbounded intervals
+    if time_interval.is_unbounded()
+        || time_interval.is_instant()
+        || time_interval.duration_ms() as i64 <= max_time_interval_ms
+    {
+        return vec![time_interval];
+    }
+
+    let end = time_interval.end().inner();
+
+    (time_interval.start().inner()..end)
+        .step_by(max_time_interval_ms as usize)
+        .map(|start| {
+            TimeInterval::new_unchecked(
+                TimeInstance::from_millis_unchecked(start),
+                TimeInstance::from_millis_unchecked((start + max_time_interval_ms).min(end)),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::MockQueryContext;
+    use crate::mock::MockRasterSourceProcessor;
+    use geoengine_datatypes::primitives::SpatialResolution;
+    use geoengine_datatypes::raster::{GeoTransform, Grid2D};
+
+    fn tiling_specification() -> TilingSpecification {
+        TilingSpecification::new((0., 0.).into(), [10, 10].into())
+    }
+
+    fn query() -> RasterQueryRectangle {
+        RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new((5., 35.).into(), (25., 0.).into()).unwrap(),
+            time_interval: TimeInterval::new_unchecked(0, 10),
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
+        }
+    }
+
+    fn limits(max_pixels_per_query: usize) -> QuerySplitLimits {
+        QuerySplitLimits {
+            max_pixels_per_query,
+            max_time_interval_ms: None,
+            parallel_queries: 1,
+        }
+    }
+
+    #[test]
+    fn it_keeps_small_queries() {
+        // 4 rows of 3 tiles with 100 pixels each
+        assert_eq!(
+            split_query(query(), tiling_specification(), limits(1200)),
+            vec![query()]
+        );
+    }
+
+    #[test]
+    fn it_splits_into_tile_rows() {
+        let queries = split_query(query(), tiling_specification(), limits(700));
+
+        assert_eq!(
+            queries.iter().map(|q| q.spatial_bounds).collect::<Vec<_>>(),
+            vec![
+                SpatialPartition2D::new((5., 35.).into(), (25., 20.).into()).unwrap(),
+                SpatialPartition2D::new((5., 20.).into(), (25., 0.).into()).unwrap(),
+            ]
+        );
+
+        // a sub-query contains at least one row
+        assert_eq!(
+            split_query(query(), tiling_specification(), limits(1)).len(),
+            4
+        );
+    }
+
+    #[test]
+    fn it_splits_temporally() {
+        let queries = split_query(
+            query(),
+            tiling_specification(),
+            QuerySplitLimits {
+                max_pixels_per_query: 700,
+                max_time_interval_ms: Some(4),
+                parallel_queries: 1,
+            },
+        );
+
+        assert_eq!(
+            queries
+                .iter()
+                .map(|q| (q.time_interval, q.spatial_bounds.upper_left().y))
+                .collect::<Vec<_>>(),
+            vec![
+                (TimeInterval::new_unchecked(0, 4), 35.),
+                (TimeInterval::new_unchecked(0, 4), 20.),
+                (TimeInterval::new_unchecked(4, 8), 35.),
+                (TimeInterval::new_unchecked(4, 8), 20.),
+                (TimeInterval::new_unchecked(8, 10), 35.),
+                (TimeInterval::new_unchecked(8, 10), 20.),
+            ]
+        );
+
+        let unbounded = RasterQueryRectangle {
+            time_interval: TimeInterval::default(),
+            ..query()
+        };
+        assert_eq!(
+            split_temporally(unbounded.time_interval, Some(4)),
+            vec![TimeInterval::default()]
+        );
+    }
+
+    async fn query_tiles(parallel_queries: usize) -> Vec<(i64, [isize; 2])> {
+        let time = TimeInterval::new_unchecked(0, 10);
+        let tiles = (-4..0)
+            .flat_map(|y| (0..3).map(move |x| (y, x)))
+            .map(|(y, x)| {
+                RasterTile2D::new(
+                    time,
+                    [y, x].into(),
+                    GeoTransform::new((0., 0.).into(), 1., -1.),
+                    Grid2D::new([10, 10].into(), vec![1_u8; 100], None)
+                        .unwrap()
+                        .into(),
+                )
+            })
+            .collect();
+
+        let splitter = RasterQuerySplitter::new(
+            MockRasterSourceProcessor { data: tiles }.boxed(),
+            tiling_specification(),
+            QuerySplitLimits {
+                max_pixels_per_query: 700,
+                max_time_interval_ms: None,
+                parallel_queries,
+            },
+        );
+
+        let ctx = MockQueryContext::new(1.into());
+        splitter
+            .raster_query(query(), &ctx)
+            .await
+            .unwrap()
+            .map_ok(|tile| (tile.time.start().inner(), *tile.tile_position.inner()))
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_concatenates_sub_queries() {
+        let expected = (-4..0)
+            .flat_map(|y| (0..3).map(move |x| (0, [y, x])))
+            .collect::<Vec<_>>();
+
+        assert_eq!(query_tiles(1).await, expected);
+        assert_eq!(query_tiles(2).await, expected);
+    }
+}
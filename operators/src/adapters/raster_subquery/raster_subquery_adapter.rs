@@ -44,7 +44,9 @@ pub trait FoldTileAccuMut: FoldTileAccu {
 pub type RasterFold<'a, T, FoldFuture, FoldMethod, FoldTileAccu> =
     TryFold<BoxStream<'a, Result<RasterTile2D<T>>>, FoldFuture, FoldTileAccu, FoldMethod>;
 
-type QueryAccuFuture<'a, T, A> = BoxFuture<'a, Result<(BoxStream<'a, Result<RasterTile2D<T>>>, A)>>;
+/// The sub-query with its accumulator or `None` if the sub-query was skipped
+type QueryAccuFuture<'a, T, A> =
+    BoxFuture<'a, Result<Option<(BoxStream<'a, Result<RasterTile2D<T>>>, A)>>>;
 
 /// This adapter allows to generate a tile stream using sub-querys.
 /// This is done using a `TileSubQuery`.
@@ -249,9 +251,18 @@ where
                 *this.current_time_start,
             ) {
                 Ok(Some(tile_query_rectangle)) => {
-                    let tile_query_stream_fut = this
-                        .source_processor
-                        .raster_query(tile_query_rectangle, *this.query_ctx);
+                    let source_processor: &'a RasterProcessorType = *this.source_processor;
+                    let query_ctx: &'a dyn QueryContext = *this.query_ctx;
+
+                    // the source is checked for the whole time of the query, s.t. skipping the tile
+                    // does not affect the time progression of the stream
+                    let skip_if_empty =
+                        this.sub_query
+                            .skip_empty_sub_queries()
+                            .then(|| RasterQueryRectangle {
+                                time_interval: this.query_rect_to_answer.time_interval,
+                                ..tile_query_rectangle
+                            });
 
                     let tile_folding_accu_fut = this.sub_query.new_fold_accu(
                         *this.current_tile_spec,
@@ -259,9 +270,22 @@ where
                         this.query_ctx.thread_pool(),
                     );
 
-                    let joined_future =
-                        async { futures::try_join!(tile_query_stream_fut, tile_folding_accu_fut) }
-                            .boxed();
+                    let joined_future = async move {
+                        if let Some(emptiness_query) = skip_if_empty {
+                            if source_processor
+                                .raster_query_is_empty(emptiness_query, query_ctx)
+                                .await?
+                            {
+                                return Ok(None);
+                            }
+                        }
+
+                        let tile_query_stream_fut =
+                            source_processor.raster_query(tile_query_rectangle, query_ctx);
+
+                        futures::try_join!(tile_query_stream_fut, tile_folding_accu_fut).map(Some)
+                    }
+                    .boxed();
 
                     this.state.set(StateInner::RunningQuery {
                         query_with_accu: joined_future,
@@ -289,12 +313,13 @@ where
             };
 
             match rq_res {
-                Ok((query, tile_folding_accu)) => {
+                Ok(Some((query, tile_folding_accu))) => {
                     let tile_folding_stream =
                         query.try_fold(tile_folding_accu, this.sub_query.fold_method());
 
                     this.state.set(StateInner::RunningFold(tile_folding_stream));
                 }
+                Ok(None) => this.state.set(StateInner::ReturnResult(None)),
                 Err(e) => {
                     this.state.set(StateInner::Ended);
                     return Poll::Ready(Some(Err(e)));
@@ -425,6 +450,12 @@ where
     /// This method generates the method which combines the accumulator and each tile of the sub-query stream in the `TryFold` stream adapter.
     fn fold_method(&self) -> Self::FoldMethod;
 
+    /// Whether tiles are skipped if the source is known to have no data for their sub-query, cf. `RasterQueryProcessor::raster_query_is_empty`.
+    /// Like for sub-queries that cannot be translated, no tile is produced, s.t. this is only sensible if the gaps are filled, e.g., by `filter_and_fill`.
+    fn skip_empty_sub_queries(&self) -> bool {
+        false
+    }
+
     fn into_raster_subquery_adapter<S>(
         self,
         source: &'a S,
@@ -508,6 +539,7 @@ where
             spatial_bounds: tile_info.spatial_partition(),
             time_interval: TimeInterval::new_instant(start_time)?,
             spatial_resolution: query_rect.spatial_resolution,
+            spatial_filter: (),
         }))
     }
 
@@ -648,6 +680,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -662,6 +695,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 1.).into(), (3., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 10),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
 
         let query_ctx = MockQueryContext::test_default();
@@ -93,6 +93,7 @@ where
                 spatial_bounds: projected_bounds,
                 time_interval: TimeInterval::new_instant(start_time)?,
                 spatial_resolution: self.in_spatial_res,
+                spatial_filter: (),
             }))
         } else {
             // output query rectangle is not valid in source projection => produce empty tile
@@ -103,6 +104,10 @@ where
     fn fold_method(&self) -> Self::FoldMethod {
         self.fold_fn.clone()
     }
+
+    fn skip_empty_sub_queries(&self) -> bool {
+        true
+    }
 }
 
 fn build_accu<T: Pixel>(
@@ -421,6 +426,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -435,6 +441,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 1.).into(), (3., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 10),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
 
         let query_ctx = MockQueryContext::test_default();
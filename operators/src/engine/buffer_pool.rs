@@ -0,0 +1,159 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use geoengine_datatypes::raster::Pixel;
+
+/// The default number of bytes that a [`TileBufferPool`] keeps for reuse
+pub const DEFAULT_TILE_BUFFER_POOL_BYTES: usize = 256 * 1024 * 1024;
+
+/// A pool of tile-sized buffers that is shared by the operators of an execution context.
+///
+/// Raster operators obtain the buffers for the data of their output tiles from the pool instead of
+/// allocating them and give the buffers of the input tiles that they consumed back to the pool.
+/// This way, workflows that produce thousands of tiles reuse the same allocations.
+/// The pool keeps at most `max_bytes` and drops buffers that are returned while it is full.
+pub struct TileBufferPool {
+    max_bytes: usize,
+    state: Mutex<PoolState>,
+}
+
+#[derive(Default)]
+struct PoolState {
+    buffers: HashMap<TypeId, Vec<Box<dyn Any + Send>>>,
+    bytes: usize,
+}
+
+impl TileBufferPool {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            state: Mutex::new(PoolState::default()),
+        }
+    }
+
+    /// Returns an empty buffer with a capacity of at least `capacity` pixels
+    pub fn take_with_capacity<T: Pixel>(&self, capacity: usize) -> Vec<T> {
+        let mut state = self.lock();
+        let PoolState { buffers, bytes } = &mut *state;
+
+        let pooled_buffer = buffers.get_mut(&TypeId::of::<T>()).and_then(|buffers| {
+            let index = buffers.iter().position(|buffer| {
+                buffer
+                    .downcast_ref::<Vec<T>>()
+                    .map_or(false, |buffer| buffer.capacity() >= capacity)
+            })?;
+
+            buffers.swap_remove(index).downcast::<Vec<T>>().ok()
+        });
+
+        match pooled_buffer {
+            Some(mut buffer) => {
+                *bytes -= byte_size::<T>(buffer.capacity());
+                buffer.clear();
+                *buffer
+            }
+            None => Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns a buffer of `len` pixels that all have the given `value`
+    pub fn take_filled<T: Pixel>(&self, len: usize, value: T) -> Vec<T> {
+        let mut buffer = self.take_with_capacity(len);
+        buffer.resize(len, value);
+        buffer
+    }
+
+    /// Returns a buffer to the pool, s.t. it can be reused for another tile.
+    /// The buffer is dropped if the pool is full.
+    pub fn give_back<T: Pixel>(&self, buffer: Vec<T>) {
+        let size = byte_size::<T>(buffer.capacity());
+
+        if size == 0 {
+            return;
+        }
+
+        let mut state = self.lock();
+
+        if state.bytes + size > self.max_bytes {
+            return;
+        }
+
+        state.bytes += size;
+        state
+            .buffers
+            .entry(TypeId::of::<T>())
+            .or_default()
+            .push(Box::new(buffer));
+    }
+
+    fn lock(&self) -> MutexGuard<PoolState> {
+        self.state
+            .lock()
+            .expect("buffer pool lock must not be poisoned")
+    }
+
+    /// The number of bytes of the buffers that are currently kept for reuse
+    pub fn pooled_bytes(&self) -> usize {
+        self.lock().bytes
+    }
+}
+
+impl Default for TileBufferPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_TILE_BUFFER_POOL_BYTES)
+    }
+}
+
+fn byte_size<T>(capacity: usize) -> usize {
+    capacity * std::mem::size_of::<T>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reuses_buffers() {
+        let pool = TileBufferPool::new(1024);
+
+        let buffer = pool.take_filled(64, 42_u8);
+        assert_eq!(buffer, vec![42; 64]);
+
+        let pointer = buffer.as_ptr();
+        pool.give_back(buffer);
+        assert_eq!(pool.pooled_bytes(), 64);
+
+        // buffers of other types are not reused
+        assert_ne!(
+            pool.take_with_capacity::<i8>(64).as_ptr().cast::<u8>(),
+            pointer
+        );
+        assert_eq!(pool.pooled_bytes(), 64);
+
+        let buffer = pool.take_filled(32, 0_u8);
+        assert_eq!(buffer.as_ptr(), pointer);
+        assert_eq!(buffer, vec![0; 32]);
+        assert_eq!(pool.pooled_bytes(), 0);
+    }
+
+    #[test]
+    fn it_ignores_small_buffers() {
+        let pool = TileBufferPool::new(1024);
+
+        pool.give_back(Vec::<f32>::with_capacity(16));
+
+        assert!(pool.take_with_capacity::<f32>(32).capacity() >= 32);
+        assert_eq!(pool.pooled_bytes(), 16 * 4);
+    }
+
+    #[test]
+    fn it_drops_buffers_if_full() {
+        let pool = TileBufferPool::new(100);
+
+        pool.give_back(vec![0_u16; 40]);
+        pool.give_back(vec![0_u16; 40]);
+
+        assert_eq!(pool.pooled_bytes(), 80);
+    }
+}
@@ -1,6 +1,7 @@
 use super::{
-    InitializedPlotOperator, InitializedRasterOperator, InitializedVectorOperator, PlotOperator,
-    RasterOperator, VectorOperator,
+    InitializedPlotOperator, InitializedRasterOperator, InitializedVectorOperator,
+    MultiOutputOperator, PlotOperator, RasterOperator, ScalarOperator, TableOperator,
+    VectorOperator,
 };
 
 /// Helper trait for making boxed `RasterOperator`s cloneable
@@ -18,6 +19,21 @@ pub trait CloneablePlotOperator {
     fn clone_boxed_plot(&self) -> Box<dyn PlotOperator>;
 }
 
+/// Helper trait for making boxed `TableOperator`s cloneable
+pub trait CloneableTableOperator {
+    fn clone_boxed_table(&self) -> Box<dyn TableOperator>;
+}
+
+/// Helper trait for making boxed `ScalarOperator`s cloneable
+pub trait CloneableScalarOperator {
+    fn clone_boxed_scalar(&self) -> Box<dyn ScalarOperator>;
+}
+
+/// Helper trait for making boxed `MultiOutputOperator`s cloneable
+pub trait CloneableMultiOutputOperator {
+    fn clone_boxed_multi_output(&self) -> Box<dyn MultiOutputOperator>;
+}
+
 impl<T> CloneableRasterOperator for T
 where
     T: 'static + RasterOperator + Clone,
@@ -45,6 +61,33 @@ where
     }
 }
 
+impl<T> CloneableTableOperator for T
+where
+    T: 'static + TableOperator + Clone,
+{
+    fn clone_boxed_table(&self) -> Box<dyn TableOperator> {
+        Box::new(self.clone())
+    }
+}
+
+impl<T> CloneableScalarOperator for T
+where
+    T: 'static + ScalarOperator + Clone,
+{
+    fn clone_boxed_scalar(&self) -> Box<dyn ScalarOperator> {
+        Box::new(self.clone())
+    }
+}
+
+impl<T> CloneableMultiOutputOperator for T
+where
+    T: 'static + MultiOutputOperator + Clone,
+{
+    fn clone_boxed_multi_output(&self) -> Box<dyn MultiOutputOperator> {
+        Box::new(self.clone())
+    }
+}
+
 impl Clone for Box<dyn RasterOperator> {
     fn clone(&self) -> Box<dyn RasterOperator> {
         self.clone_boxed_raster()
@@ -63,6 +106,24 @@ impl Clone for Box<dyn PlotOperator> {
     }
 }
 
+impl Clone for Box<dyn TableOperator> {
+    fn clone(&self) -> Box<dyn TableOperator> {
+        self.clone_boxed_table()
+    }
+}
+
+impl Clone for Box<dyn ScalarOperator> {
+    fn clone(&self) -> Box<dyn ScalarOperator> {
+        self.clone_boxed_scalar()
+    }
+}
+
+impl Clone for Box<dyn MultiOutputOperator> {
+    fn clone(&self) -> Box<dyn MultiOutputOperator> {
+        self.clone_boxed_multi_output()
+    }
+}
+
 /// Helper trait for making boxed `InitializedRasterOperator`s cloneable
 pub trait CloneableInitializedRasterOperator {
     fn clone_boxed_raster(&self) -> Box<dyn InitializedRasterOperator>;
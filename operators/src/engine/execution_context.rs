@@ -1,6 +1,7 @@
 use super::MockQueryContext;
 use crate::engine::{
-    ChunkByteSize, RasterResultDescriptor, ResultDescriptor, VectorResultDescriptor,
+    Cacheability, ChunkByteSize, RasterResultDescriptor, ResultDescriptor, ResultExtent,
+    TileBufferPool, VectorResultDescriptor,
 };
 use crate::error::Error;
 use crate::mock::MockDatasetDataSourceLoadingInfo;
@@ -25,9 +26,21 @@ pub trait ExecutionContext: Send
     + MetaDataProvider<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>
     + MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
     + MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
+    + MacroProvider
 {
     fn thread_pool(&self) -> &Arc<ThreadPool>;
     fn tiling_specification(&self) -> TilingSpecification;
+
+    /// The pool of tile buffers that raster operators use instead of allocating the data of their tiles
+    fn tile_buffer_pool(&self) -> &Arc<TileBufferPool>;
+}
+
+/// Provides the definitions of reusable sub-workflows that are referenced by name
+#[async_trait]
+pub trait MacroProvider {
+    /// The JSON of the operator that the macro `name` expands to.
+    /// Without a `version`, the latest version of the macro is used.
+    async fn workflow_macro(&self, name: &str, version: Option<u32>) -> Result<serde_json::Value>;
 }
 
 #[async_trait]
@@ -46,6 +59,16 @@ where
     async fn loading_info(&self, query: Q) -> Result<L>;
     async fn result_descriptor(&self) -> Result<R>;
 
+    /// The extent of the dataset, which is unknown by default
+    async fn extent(&self) -> Result<ResultExtent> {
+        Ok(ResultExtent::default())
+    }
+
+    /// Whether the results of loading the dataset can be cached, which they can by default
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::Deterministic
+    }
+
     fn box_clone(&self) -> Box<dyn MetaData<L, R, Q>>;
 }
 
@@ -62,6 +85,8 @@ pub struct MockExecutionContext {
     pub thread_pool: Arc<ThreadPool>,
     pub meta_data: HashMap<DatasetId, Box<dyn Any + Send + Sync>>,
     pub tiling_specification: TilingSpecification,
+    pub macros: HashMap<String, Vec<serde_json::Value>>,
+    pub tile_buffer_pool: Arc<TileBufferPool>,
 }
 
 impl TestDefault for MockExecutionContext {
@@ -70,6 +95,8 @@ impl TestDefault for MockExecutionContext {
             thread_pool: create_rayon_thread_pool(0),
             meta_data: HashMap::default(),
             tiling_specification: TilingSpecification::test_default(),
+            macros: HashMap::default(),
+            tile_buffer_pool: Arc::default(),
         }
     }
 }
@@ -80,6 +107,8 @@ impl MockExecutionContext {
             thread_pool: create_rayon_thread_pool(0),
             meta_data: HashMap::default(),
             tiling_specification,
+            macros: HashMap::default(),
+            tile_buffer_pool: Arc::default(),
         }
     }
 
@@ -91,6 +120,8 @@ impl MockExecutionContext {
             thread_pool: create_rayon_thread_pool(num_threads),
             meta_data: HashMap::default(),
             tiling_specification,
+            macros: HashMap::default(),
+            tile_buffer_pool: Arc::default(),
         }
     }
 
@@ -107,6 +138,13 @@ impl MockExecutionContext {
             .insert(dataset, Box::new(meta_data) as Box<dyn Any + Send + Sync>);
     }
 
+    /// Adds a new version of the macro `name` and returns its version number
+    pub fn add_macro(&mut self, name: &str, operator: serde_json::Value) -> u32 {
+        let versions = self.macros.entry(name.to_string()).or_default();
+        versions.push(operator);
+        versions.len() as u32
+    }
+
     pub fn mock_query_context(&self, chunk_byte_size: ChunkByteSize) -> MockQueryContext {
         MockQueryContext {
             chunk_byte_size,
@@ -123,6 +161,29 @@ impl ExecutionContext for MockExecutionContext {
     fn tiling_specification(&self) -> TilingSpecification {
         self.tiling_specification
     }
+
+    fn tile_buffer_pool(&self) -> &Arc<TileBufferPool> {
+        &self.tile_buffer_pool
+    }
+}
+
+#[async_trait]
+impl MacroProvider for MockExecutionContext {
+    async fn workflow_macro(&self, name: &str, version: Option<u32>) -> Result<serde_json::Value> {
+        let versions = self.macros.get(name).map_or(&[][..], Vec::as_slice);
+
+        let operator = match version {
+            Some(version) => version
+                .checked_sub(1)
+                .and_then(|index| versions.get(index as usize)),
+            None => versions.last(),
+        };
+
+        operator.cloned().ok_or_else(|| Error::UnknownMacro {
+            name: name.to_string(),
+            version,
+        })
+    }
 }
 
 #[async_trait]
@@ -192,6 +253,7 @@ mod tests {
                 data_type: VectorDataType::Data,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
                 columns: Default::default(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -211,6 +273,7 @@ mod tests {
                 data_type: VectorDataType::Data,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
                 columns: Default::default(),
+                column_metadata: Default::default(),
             }
         );
     }
@@ -1,30 +1,42 @@
+pub use buffer_pool::{TileBufferPool, DEFAULT_TILE_BUFFER_POOL_BYTES};
 pub use clonable_operator::{
-    CloneableInitializedRasterOperator, CloneableInitializedVectorOperator, CloneablePlotOperator,
-    CloneableRasterOperator, CloneableVectorOperator,
+    CloneableInitializedRasterOperator, CloneableInitializedVectorOperator,
+    CloneableMultiOutputOperator, CloneablePlotOperator, CloneableRasterOperator,
+    CloneableScalarOperator, CloneableTableOperator, CloneableVectorOperator,
 };
 pub use execution_context::{
-    ExecutionContext, MetaData, MetaDataProvider, MockExecutionContext, StaticMetaData,
+    ExecutionContext, MacroProvider, MetaData, MetaDataProvider, MockExecutionContext,
+    StaticMetaData,
 };
 pub use operator::{
-    InitializedPlotOperator, InitializedRasterOperator, InitializedVectorOperator,
-    OperatorDatasets, PlotOperator, RasterOperator, TypedOperator, VectorOperator,
+    Cacheability, InitializedMultiOutputOperator, InitializedPlotOperator,
+    InitializedRasterOperator, InitializedScalarOperator, InitializedTableOperator,
+    InitializedVectorOperator, MultiOutputOperator, OperatorDatasets, OperatorOutput, PlotOperator,
+    RasterOperator, ResultExtent, ScalarOperator, TableOperator, TypedInitializedOperator,
+    TypedOperator, VectorOperator,
 };
 pub use operator_impl::{
     MultipleRasterOrSingleVectorSource, MultipleRasterSources, MultipleVectorSources, Operator,
-    SingleRasterOrVectorSource, SingleRasterSource, SingleVectorMultipleRasterSources,
-    SingleVectorSource, SourceOperator,
+    SingleMultiOutputSource, SingleRasterOrVectorSource, SingleRasterSource,
+    SingleVectorMultipleRasterSources, SingleVectorOrTableSource, SingleVectorSource,
+    SourceOperator,
+};
+pub use query::{
+    ChunkByteSize, DimensionQueryContext, DimensionSelection, MockQueryContext, QueryContext,
+    QueryWarnings, RasterErrorPolicy, RasterErrorPolicyQueryContext,
 };
-pub use query::{ChunkByteSize, MockQueryContext, QueryContext};
 pub use query_processor::{
     BoxRasterQueryProcessor, PlotQueryProcessor, QueryProcessor, RasterQueryProcessor,
-    TypedPlotQueryProcessor, TypedRasterQueryProcessor, TypedVectorQueryProcessor,
-    VectorQueryProcessor,
+    ScalarQueryProcessor, TableQueryProcessor, TypedPlotQueryProcessor, TypedRasterQueryProcessor,
+    TypedVectorQueryProcessor, VectorQueryProcessor,
 };
 pub use result_descriptor::{
-    PlotResultDescriptor, RasterResultDescriptor, ResultDescriptor, TypedResultDescriptor,
-    VectorResultDescriptor,
+    ColumnMetadata, PlotResultDescriptor, RasterDimension, RasterResultDescriptor,
+    ResultDescriptor, ScalarResultDescriptor, SemanticType, TableColumn, TableDataType,
+    TableResultDescriptor, TypedResultDescriptor, VectorResultDescriptor,
 };
 
+mod buffer_pool;
 mod clonable_operator;
 mod execution_context;
 mod operator;
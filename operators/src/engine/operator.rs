@@ -4,11 +4,17 @@ use crate::error;
 use crate::util::Result;
 use async_trait::async_trait;
 use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, Coordinate2D, TimeInterval,
+};
 
 use super::{
     query_processor::{TypedRasterQueryProcessor, TypedVectorQueryProcessor},
-    CloneablePlotOperator, CloneableRasterOperator, CloneableVectorOperator, ExecutionContext,
-    PlotResultDescriptor, RasterResultDescriptor, TypedPlotQueryProcessor, VectorResultDescriptor,
+    CloneableMultiOutputOperator, CloneablePlotOperator, CloneableRasterOperator,
+    CloneableScalarOperator, CloneableTableOperator, CloneableVectorOperator, ExecutionContext,
+    PlotResultDescriptor, RasterResultDescriptor, ScalarQueryProcessor, ScalarResultDescriptor,
+    TableQueryProcessor, TableResultDescriptor, TypedPlotQueryProcessor, TypedResultDescriptor,
+    VectorResultDescriptor,
 };
 
 pub trait OperatorDatasets {
@@ -83,6 +89,205 @@ pub trait PlotOperator:
     }
 }
 
+/// Common methods for `TableOperator`s, i.e., operators that produce plain tables without geometries
+#[typetag::serde(tag = "type")]
+#[async_trait]
+pub trait TableOperator:
+    CloneableTableOperator + OperatorDatasets + Send + Sync + std::fmt::Debug
+{
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedTableOperator>>;
+
+    /// Wrap a box around a `TableOperator`
+    fn boxed(self) -> Box<dyn TableOperator>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+/// Common methods for `ScalarOperator`s, i.e., operators that summarize their inputs in a single value
+#[typetag::serde(tag = "type")]
+#[async_trait]
+pub trait ScalarOperator:
+    CloneableScalarOperator + OperatorDatasets + Send + Sync + std::fmt::Debug
+{
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedScalarOperator>>;
+
+    /// Wrap a box around a `ScalarOperator`
+    fn boxed(self) -> Box<dyn ScalarOperator>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+/// Common methods for operators with multiple named outputs, e.g., a classifier that produces
+/// a class raster and a probability raster.
+///
+/// Workflows reference a single output by the `SelectOutput` operator. Operators that consume
+/// several outputs should initialize the operator only once, so that the outputs share their computation.
+#[typetag::serde(tag = "type")]
+#[async_trait]
+pub trait MultiOutputOperator:
+    CloneableMultiOutputOperator + OperatorDatasets + Send + Sync + std::fmt::Debug
+{
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedMultiOutputOperator>>;
+
+    /// Wrap a box around a `MultiOutputOperator`
+    fn boxed(self) -> Box<dyn MultiOutputOperator>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+/// Whether the results of an operator can be cached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Cacheability {
+    /// The same query always yields the same result as long as the underlying datasets do not change
+    Deterministic,
+    /// The result depends on when the query is executed, e.g., on the current time
+    TimeDependent,
+    /// The result depends on who executes the query, e.g., because of access restrictions
+    SessionDependent,
+}
+
+impl Default for Cacheability {
+    fn default() -> Self {
+        Self::Deterministic
+    }
+}
+
+impl Cacheability {
+    /// Combines the cacheability of an operator with the cacheability of another one, e.g., of a source.
+    /// The result is only deterministic if both are deterministic.
+    #[must_use]
+    pub fn combine(self, other: Self) -> Self {
+        self.max(other)
+    }
+
+    /// Combines the cacheability of multiple operators, e.g., of all sources of an operator
+    pub fn combine_all<I>(cacheabilities: I) -> Self
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        cacheabilities
+            .into_iter()
+            .fold(Self::Deterministic, Self::combine)
+    }
+
+    pub fn is_deterministic(self) -> bool {
+        self == Self::Deterministic
+    }
+}
+
+/// The spatial and temporal extent of the results of an operator, e.g., to zoom to a layer.
+///
+/// The extent is estimated from the meta data of the datasets and may be larger than the
+/// area and time where the results actually have data. `None` means that it is unknown.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultExtent {
+    /// The bounds in the spatial reference of the operator's results
+    pub spatial: Option<BoundingBox2D>,
+    pub time: Option<TimeInterval>,
+}
+
+impl ResultExtent {
+    pub fn new(spatial: Option<BoundingBox2D>, time: Option<TimeInterval>) -> Self {
+        Self { spatial, time }
+    }
+
+    /// The extent that covers both extents, e.g., for operators that have results where any of
+    /// their sources has data. It is unknown if either of them is unknown.
+    #[must_use]
+    pub fn union(self, other: Self) -> Self {
+        let spatial = match (self.spatial, other.spatial) {
+            (Some(a), Some(b)) => Some(BoundingBox2D::new_unchecked(
+                Coordinate2D::new(
+                    a.lower_left().x.min(b.lower_left().x),
+                    a.lower_left().y.min(b.lower_left().y),
+                ),
+                Coordinate2D::new(
+                    a.upper_right().x.max(b.upper_right().x),
+                    a.upper_right().y.max(b.upper_right().y),
+                ),
+            )),
+            _ => None,
+        };
+
+        let time = match (self.time, other.time) {
+            (Some(a), Some(b)) => Some(TimeInterval::new_unchecked(
+                a.start().min(b.start()),
+                a.end().max(b.end()),
+            )),
+            _ => None,
+        };
+
+        Self { spatial, time }
+    }
+
+    /// The extent where both extents overlap, e.g., for operators that only have results where
+    /// all of their sources have data. If one of them is unknown, the other one is an estimate.
+    #[must_use]
+    pub fn intersection(self, other: Self) -> Self {
+        let spatial = match (self.spatial, other.spatial) {
+            (Some(a), Some(b)) => a.intersection(&b),
+            (a, b) => a.or(b),
+        };
+
+        let time = match (self.time, other.time) {
+            (Some(a), Some(b)) => a.intersect(&b),
+            (a, b) => a.or(b),
+        };
+
+        Self { spatial, time }
+    }
+
+    /// Whether the extent is known to not intersect the given bounds and time, s.t. results for
+    /// them have no data
+    pub fn excludes(&self, bounds: &BoundingBox2D, time: &TimeInterval) -> bool {
+        let outside_bounds = self
+            .spatial
+            .map_or(false, |spatial| !spatial.intersects_bbox(bounds));
+        let outside_time = self.time.map_or(false, |extent| !extent.intersects(time));
+
+        outside_bounds || outside_time
+    }
+
+    /// The union of the extents of multiple operators, e.g., of all sources of an operator
+    pub fn union_all<I>(extents: I) -> Self
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        extents.into_iter().reduce(Self::union).unwrap_or_default()
+    }
+
+    /// The intersection of the extents of multiple operators, e.g., of all sources of an operator
+    pub fn intersection_all<I>(extents: I) -> Self
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        extents
+            .into_iter()
+            .reduce(Self::intersection)
+            .unwrap_or_default()
+    }
+}
+
 pub trait InitializedRasterOperator: Send + Sync {
     /// Get the result descriptor of the `Operator`
     fn result_descriptor(&self) -> &RasterResultDescriptor;
@@ -90,6 +295,18 @@ pub trait InitializedRasterOperator: Send + Sync {
     /// Instantiate a `TypedVectorQueryProcessor` from a `RasterOperator`
     fn query_processor(&self) -> Result<TypedRasterQueryProcessor>;
 
+    /// Whether the results of the operator can be cached.
+    ///
+    /// Operators with sources must combine their own cacheability with that of their sources.
+    fn cacheability(&self) -> Cacheability;
+
+    /// The extent of the results of the operator.
+    ///
+    /// Operators with sources must derive their extent from that of their sources.
+    fn extent(&self) -> ResultExtent {
+        ResultExtent::default()
+    }
+
     /// Wrap a box around a `RasterOperator`
     fn boxed(self) -> Box<dyn InitializedRasterOperator>
     where
@@ -106,6 +323,18 @@ pub trait InitializedVectorOperator: Send + Sync {
     /// Instantiate a `TypedVectorQueryProcessor` from a `RasterOperator`
     fn query_processor(&self) -> Result<TypedVectorQueryProcessor>;
 
+    /// Whether the results of the operator can be cached.
+    ///
+    /// Operators with sources must combine their own cacheability with that of their sources.
+    fn cacheability(&self) -> Cacheability;
+
+    /// The extent of the results of the operator.
+    ///
+    /// Operators with sources must derive their extent from that of their sources.
+    fn extent(&self) -> ResultExtent {
+        ResultExtent::default()
+    }
+
     /// Wrap a box around a `RasterOperator`
     fn boxed(self) -> Box<dyn InitializedVectorOperator>
     where
@@ -122,6 +351,11 @@ pub trait InitializedPlotOperator: Send + Sync {
     /// Instantiate a `TypedVectorQueryProcessor` from a `RasterOperator`
     fn query_processor(&self) -> Result<TypedPlotQueryProcessor>;
 
+    /// Whether the results of the operator can be cached.
+    ///
+    /// Operators with sources must combine their own cacheability with that of their sources.
+    fn cacheability(&self) -> Cacheability;
+
     /// Wrap a box around a `RasterOperator`
     fn boxed(self) -> Box<dyn InitializedPlotOperator>
     where
@@ -131,6 +365,129 @@ pub trait InitializedPlotOperator: Send + Sync {
     }
 }
 
+pub trait InitializedTableOperator: Send + Sync {
+    /// Get the result descriptor of the `Operator`
+    fn result_descriptor(&self) -> &TableResultDescriptor;
+
+    /// Instantiate a `TableQueryProcessor` from a `TableOperator`
+    fn query_processor(&self) -> Result<Box<dyn TableQueryProcessor>>;
+
+    /// Whether the results of the operator can be cached.
+    ///
+    /// Operators with sources must combine their own cacheability with that of their sources.
+    fn cacheability(&self) -> Cacheability;
+
+    /// Wrap a box around a `TableOperator`
+    fn boxed(self) -> Box<dyn InitializedTableOperator>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+pub trait InitializedScalarOperator: Send + Sync {
+    /// Get the result descriptor of the `Operator`
+    fn result_descriptor(&self) -> &ScalarResultDescriptor;
+
+    /// Instantiate a `ScalarQueryProcessor` from a `ScalarOperator`
+    fn query_processor(&self) -> Result<Box<dyn ScalarQueryProcessor>>;
+
+    /// Whether the results of the operator can be cached.
+    ///
+    /// Operators with sources must combine their own cacheability with that of their sources.
+    fn cacheability(&self) -> Cacheability;
+
+    /// Wrap a box around a `ScalarOperator`
+    fn boxed(self) -> Box<dyn InitializedScalarOperator>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+/// A named output of a `MultiOutputOperator`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorOutput {
+    pub name: String,
+    pub result_descriptor: TypedResultDescriptor,
+}
+
+pub trait InitializedMultiOutputOperator: Send + Sync {
+    /// Get the names and result descriptors of all outputs
+    fn outputs(&self) -> &[OperatorOutput];
+
+    /// Get the initialized operator of the output `name`.
+    ///
+    /// The outputs of one initialized operator may share their state, e.g., intermediate results.
+    fn output(&self, name: &str) -> Result<TypedInitializedOperator>;
+
+    /// Get the output `name` which must be a raster
+    fn raster_output(&self, name: &str) -> Result<Box<dyn InitializedRasterOperator>> {
+        match self.output(name)? {
+            TypedInitializedOperator::Raster(operator) => Ok(operator),
+            operator => Err(error::Error::InvalidOperatorType {
+                expected: "Raster".to_owned(),
+                found: operator.type_name().to_owned(),
+            }),
+        }
+    }
+
+    /// Get the output `name` which must be a vector
+    fn vector_output(&self, name: &str) -> Result<Box<dyn InitializedVectorOperator>> {
+        match self.output(name)? {
+            TypedInitializedOperator::Vector(operator) => Ok(operator),
+            operator => Err(error::Error::InvalidOperatorType {
+                expected: "Vector".to_owned(),
+                found: operator.type_name().to_owned(),
+            }),
+        }
+    }
+
+    /// Get the output `name` which must be a plot
+    fn plot_output(&self, name: &str) -> Result<Box<dyn InitializedPlotOperator>> {
+        match self.output(name)? {
+            TypedInitializedOperator::Plot(operator) => Ok(operator),
+            operator => Err(error::Error::InvalidOperatorType {
+                expected: "Plot".to_owned(),
+                found: operator.type_name().to_owned(),
+            }),
+        }
+    }
+
+    /// Get the output `name` which must be a table
+    fn table_output(&self, name: &str) -> Result<Box<dyn InitializedTableOperator>> {
+        match self.output(name)? {
+            TypedInitializedOperator::Table(operator) => Ok(operator),
+            operator => Err(error::Error::InvalidOperatorType {
+                expected: "Table".to_owned(),
+                found: operator.type_name().to_owned(),
+            }),
+        }
+    }
+
+    /// Get the output `name` which must be a scalar
+    fn scalar_output(&self, name: &str) -> Result<Box<dyn InitializedScalarOperator>> {
+        match self.output(name)? {
+            TypedInitializedOperator::Scalar(operator) => Ok(operator),
+            operator => Err(error::Error::InvalidOperatorType {
+                expected: "Scalar".to_owned(),
+                found: operator.type_name().to_owned(),
+            }),
+        }
+    }
+
+    /// Wrap a box around a `InitializedMultiOutputOperator`
+    fn boxed(self) -> Box<dyn InitializedMultiOutputOperator>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
 impl InitializedRasterOperator for Box<dyn InitializedRasterOperator> {
     fn result_descriptor(&self) -> &RasterResultDescriptor {
         self.as_ref().result_descriptor()
@@ -139,6 +496,14 @@ impl InitializedRasterOperator for Box<dyn InitializedRasterOperator> {
     fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
         self.as_ref().query_processor()
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.as_ref().cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.as_ref().extent()
+    }
 }
 
 impl InitializedVectorOperator for Box<dyn InitializedVectorOperator> {
@@ -149,6 +514,14 @@ impl InitializedVectorOperator for Box<dyn InitializedVectorOperator> {
     fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
         self.as_ref().query_processor()
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.as_ref().cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.as_ref().extent()
+    }
 }
 
 impl InitializedPlotOperator for Box<dyn InitializedPlotOperator> {
@@ -159,6 +532,38 @@ impl InitializedPlotOperator for Box<dyn InitializedPlotOperator> {
     fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
         self.as_ref().query_processor()
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.as_ref().cacheability()
+    }
+}
+
+impl InitializedTableOperator for Box<dyn InitializedTableOperator> {
+    fn result_descriptor(&self) -> &TableResultDescriptor {
+        self.as_ref().result_descriptor()
+    }
+
+    fn query_processor(&self) -> Result<Box<dyn TableQueryProcessor>> {
+        self.as_ref().query_processor()
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.as_ref().cacheability()
+    }
+}
+
+impl InitializedScalarOperator for Box<dyn InitializedScalarOperator> {
+    fn result_descriptor(&self) -> &ScalarResultDescriptor {
+        self.as_ref().result_descriptor()
+    }
+
+    fn query_processor(&self) -> Result<Box<dyn ScalarQueryProcessor>> {
+        self.as_ref().query_processor()
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.as_ref().cacheability()
+    }
 }
 
 /// An enum to differentiate between `Operator` variants
@@ -168,6 +573,8 @@ pub enum TypedOperator {
     Vector(Box<dyn VectorOperator>),
     Raster(Box<dyn RasterOperator>),
     Plot(Box<dyn PlotOperator>),
+    Table(Box<dyn TableOperator>),
+    Scalar(Box<dyn ScalarOperator>),
 }
 
 impl TypedOperator {
@@ -201,11 +608,33 @@ impl TypedOperator {
         })
     }
 
+    pub fn get_table(self) -> Result<Box<dyn TableOperator>> {
+        if let TypedOperator::Table(o) = self {
+            return Ok(o);
+        }
+        Err(error::Error::InvalidOperatorType {
+            expected: "Table".to_owned(),
+            found: self.type_name().to_owned(),
+        })
+    }
+
+    pub fn get_scalar(self) -> Result<Box<dyn ScalarOperator>> {
+        if let TypedOperator::Scalar(o) = self {
+            return Ok(o);
+        }
+        Err(error::Error::InvalidOperatorType {
+            expected: "Scalar".to_owned(),
+            found: self.type_name().to_owned(),
+        })
+    }
+
     fn type_name(&self) -> &str {
         match self {
             TypedOperator::Vector(_) => "Vector",
             TypedOperator::Raster(_) => "Raster",
             TypedOperator::Plot(_) => "Plot",
+            TypedOperator::Table(_) => "Table",
+            TypedOperator::Scalar(_) => "Scalar",
         }
     }
 }
@@ -228,11 +657,37 @@ impl From<Box<dyn PlotOperator>> for TypedOperator {
     }
 }
 
+impl From<Box<dyn TableOperator>> for TypedOperator {
+    fn from(operator: Box<dyn TableOperator>) -> Self {
+        Self::Table(operator)
+    }
+}
+
+impl From<Box<dyn ScalarOperator>> for TypedOperator {
+    fn from(operator: Box<dyn ScalarOperator>) -> Self {
+        Self::Scalar(operator)
+    }
+}
+
 /// An enum to differentiate between `InitializedOperator` variants
 pub enum TypedInitializedOperator {
     Vector(Box<dyn InitializedVectorOperator>),
     Raster(Box<dyn InitializedRasterOperator>),
     Plot(Box<dyn InitializedPlotOperator>),
+    Table(Box<dyn InitializedTableOperator>),
+    Scalar(Box<dyn InitializedScalarOperator>),
+}
+
+impl TypedInitializedOperator {
+    fn type_name(&self) -> &str {
+        match self {
+            TypedInitializedOperator::Vector(_) => "Vector",
+            TypedInitializedOperator::Raster(_) => "Raster",
+            TypedInitializedOperator::Plot(_) => "Plot",
+            TypedInitializedOperator::Table(_) => "Table",
+            TypedInitializedOperator::Scalar(_) => "Scalar",
+        }
+    }
 }
 
 impl From<Box<dyn InitializedVectorOperator>> for TypedInitializedOperator {
@@ -253,6 +708,18 @@ impl From<Box<dyn InitializedPlotOperator>> for TypedInitializedOperator {
     }
 }
 
+impl From<Box<dyn InitializedTableOperator>> for TypedInitializedOperator {
+    fn from(operator: Box<dyn InitializedTableOperator>) -> Self {
+        TypedInitializedOperator::Table(operator)
+    }
+}
+
+impl From<Box<dyn InitializedScalarOperator>> for TypedInitializedOperator {
+    fn from(operator: Box<dyn InitializedScalarOperator>) -> Self {
+        TypedInitializedOperator::Scalar(operator)
+    }
+}
+
 #[macro_export]
 macro_rules! call_on_typed_operator {
     ($typed_operator:expr, $operator_var:ident => $function_call:expr) => {
@@ -260,6 +727,8 @@ macro_rules! call_on_typed_operator {
             $crate::engine::TypedOperator::Vector($operator_var) => $function_call,
             $crate::engine::TypedOperator::Raster($operator_var) => $function_call,
             $crate::engine::TypedOperator::Plot($operator_var) => $function_call,
+            $crate::engine::TypedOperator::Table($operator_var) => $function_call,
+            $crate::engine::TypedOperator::Scalar($operator_var) => $function_call,
         }
     };
 }
@@ -270,6 +739,60 @@ impl OperatorDatasets for TypedOperator {
             TypedOperator::Vector(v) => v.datasets_collect(datasets),
             TypedOperator::Raster(r) => r.datasets_collect(datasets),
             TypedOperator::Plot(p) => p.datasets_collect(datasets),
+            TypedOperator::Table(t) => t.datasets_collect(datasets),
+            TypedOperator::Scalar(s) => s.datasets_collect(datasets),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn result_extent_union_and_intersection() {
+        let a = ResultExtent::new(
+            Some(BoundingBox2D::new_unchecked(
+                (0., 0.).into(),
+                (2., 2.).into(),
+            )),
+            Some(TimeInterval::new_unchecked(0, 10)),
+        );
+        let b = ResultExtent::new(
+            Some(BoundingBox2D::new_unchecked(
+                (1., 1.).into(),
+                (3., 3.).into(),
+            )),
+            Some(TimeInterval::new_unchecked(5, 15)),
+        );
+        let unknown = ResultExtent::default();
+
+        assert_eq!(
+            a.union(b),
+            ResultExtent::new(
+                Some(BoundingBox2D::new_unchecked(
+                    (0., 0.).into(),
+                    (3., 3.).into()
+                )),
+                Some(TimeInterval::new_unchecked(0, 15)),
+            )
+        );
+        assert_eq!(
+            a.intersection(b),
+            ResultExtent::new(
+                Some(BoundingBox2D::new_unchecked(
+                    (1., 1.).into(),
+                    (2., 2.).into()
+                )),
+                Some(TimeInterval::new_unchecked(5, 10)),
+            )
+        );
+
+        assert_eq!(a.union(unknown), unknown);
+        assert_eq!(a.intersection(unknown), a);
+        assert_eq!(
+            ResultExtent::intersection_all([a, b, unknown]),
+            a.intersection(b)
+        );
+    }
+}
@@ -1,9 +1,11 @@
 use geoengine_datatypes::dataset::DatasetId;
 use serde::{Deserialize, Serialize};
 
-use crate::util::input::{MultiRasterOrVectorOperator, RasterOrVectorOperator};
+use crate::util::input::{
+    MultiRasterOrVectorOperator, RasterOrVectorOperator, VectorOrTableOperator,
+};
 
-use super::{OperatorDatasets, RasterOperator, VectorOperator};
+use super::{MultiOutputOperator, OperatorDatasets, RasterOperator, TableOperator, VectorOperator};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -36,6 +38,12 @@ pub struct SingleRasterOrVectorSource {
     pub source: RasterOrVectorOperator,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SingleVectorOrTableSource {
+    pub source: VectorOrTableOperator,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MultipleRasterOrSingleVectorSource {
@@ -61,6 +69,12 @@ pub struct SingleVectorMultipleRasterSources {
     pub rasters: Vec<Box<dyn RasterOperator>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SingleMultiOutputSource {
+    pub operator: Box<dyn MultiOutputOperator>,
+}
+
 impl From<Box<dyn VectorOperator>> for SingleVectorSource {
     fn from(vector: Box<dyn VectorOperator>) -> Self {
         Self { vector }
@@ -73,6 +87,12 @@ impl From<Box<dyn RasterOperator>> for SingleRasterSource {
     }
 }
 
+impl From<Box<dyn MultiOutputOperator>> for SingleMultiOutputSource {
+    fn from(operator: Box<dyn MultiOutputOperator>) -> Self {
+        Self { operator }
+    }
+}
+
 impl From<Vec<Box<dyn RasterOperator>>> for MultipleRasterSources {
     fn from(rasters: Vec<Box<dyn RasterOperator>>) -> Self {
         Self { rasters }
@@ -101,6 +121,22 @@ impl From<Box<dyn RasterOperator>> for SingleRasterOrVectorSource {
     }
 }
 
+impl From<Box<dyn VectorOperator>> for SingleVectorOrTableSource {
+    fn from(vector: Box<dyn VectorOperator>) -> Self {
+        Self {
+            source: VectorOrTableOperator::Vector(vector),
+        }
+    }
+}
+
+impl From<Box<dyn TableOperator>> for SingleVectorOrTableSource {
+    fn from(table: Box<dyn TableOperator>) -> Self {
+        Self {
+            source: VectorOrTableOperator::Table(table),
+        }
+    }
+}
+
 impl From<Box<dyn VectorOperator>> for MultipleRasterOrSingleVectorSource {
     fn from(vector: Box<dyn VectorOperator>) -> Self {
         Self {
@@ -149,6 +185,12 @@ impl OperatorDatasets for SingleRasterOrVectorSource {
     }
 }
 
+impl OperatorDatasets for SingleVectorOrTableSource {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.source.datasets_collect(datasets);
+    }
+}
+
 impl OperatorDatasets for MultipleRasterOrSingleVectorSource {
     fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
         self.source.datasets_collect(datasets);
@@ -167,6 +209,12 @@ impl OperatorDatasets for SingleRasterSource {
     }
 }
 
+impl OperatorDatasets for SingleMultiOutputSource {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.operator.datasets_collect(datasets);
+    }
+}
+
 impl OperatorDatasets for MultipleRasterSources {
     fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
         for source in &self.rasters {
@@ -1,4 +1,5 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use crate::util::create_rayon_thread_pool;
 use geoengine_datatypes::util::test::TestDefault;
@@ -43,6 +44,161 @@ impl TestDefault for ChunkByteSize {
 pub trait QueryContext: Send + Sync {
     fn chunk_byte_size(&self) -> ChunkByteSize;
     fn thread_pool(&self) -> &Arc<ThreadPool>;
+
+    /// How raster sources handle files of a query that are corrupt or cannot be opened
+    fn raster_error_policy(&self) -> RasterErrorPolicy {
+        RasterErrorPolicy::Fail
+    }
+
+    /// Reports a problem that did not fail the query, e.g., a skipped time slice
+    fn add_warning(&self, _warning: String) {}
+
+    /// The selected values of additional raster dimensions, e.g., elevation or ensemble member.
+    /// Sources use the default values of dimensions without a selection.
+    fn dimension_selection(&self) -> Option<&DimensionSelection> {
+        None
+    }
+}
+
+/// The selected values of additional raster dimensions by their case-insensitive names
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct DimensionSelection(HashMap<String, String>);
+
+impl DimensionSelection {
+    pub fn insert(&mut self, dimension: &str, value: String) {
+        self.0.insert(dimension.to_lowercase(), value);
+    }
+
+    pub fn get(&self, dimension: &str) -> Option<&str> {
+        self.0.get(&dimension.to_lowercase()).map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Specifies how raster sources handle single files of a query that are corrupt or cannot be opened.
+/// Files that are missing and marked with `FileNotFoundHandling::NoData` are not affected.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RasterErrorPolicy {
+    /// Fail the whole query
+    Fail,
+    /// Omit the time slices whose files cannot be opened.
+    /// Tiles that cannot be read from an opened file are filled with no data.
+    SkipSlice,
+    /// Fill the tiles of the affected files with no data
+    FillNoData,
+}
+
+impl Default for RasterErrorPolicy {
+    fn default() -> Self {
+        Self::Fail
+    }
+}
+
+/// A shared list of the distinct warnings of a query
+#[derive(Clone, Debug, Default)]
+pub struct QueryWarnings(Arc<Mutex<Vec<String>>>);
+
+impl QueryWarnings {
+    pub fn push(&self, warning: String) {
+        let mut warnings = self.0.lock().expect("warnings lock must not be poisoned");
+
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+
+    pub fn to_vec(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .expect("warnings lock must not be poisoned")
+            .clone()
+    }
+}
+
+/// A `QueryContext` that sets the `RasterErrorPolicy` of another `QueryContext` and collects the
+/// warnings of the query
+pub struct RasterErrorPolicyQueryContext<C: QueryContext> {
+    context: C,
+    raster_error_policy: RasterErrorPolicy,
+    warnings: QueryWarnings,
+}
+
+impl<C: QueryContext> RasterErrorPolicyQueryContext<C> {
+    pub fn new(context: C, raster_error_policy: RasterErrorPolicy) -> Self {
+        Self {
+            context,
+            raster_error_policy,
+            warnings: QueryWarnings::default(),
+        }
+    }
+
+    /// A handle to the warnings of the query that remains valid after the context is consumed
+    pub fn warnings(&self) -> QueryWarnings {
+        self.warnings.clone()
+    }
+}
+
+impl<C: QueryContext> QueryContext for RasterErrorPolicyQueryContext<C> {
+    fn chunk_byte_size(&self) -> ChunkByteSize {
+        self.context.chunk_byte_size()
+    }
+
+    fn thread_pool(&self) -> &Arc<ThreadPool> {
+        self.context.thread_pool()
+    }
+
+    fn raster_error_policy(&self) -> RasterErrorPolicy {
+        self.raster_error_policy
+    }
+
+    fn add_warning(&self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    fn dimension_selection(&self) -> Option<&DimensionSelection> {
+        self.context.dimension_selection()
+    }
+}
+
+/// A `QueryContext` that selects the values of additional raster dimensions for another `QueryContext`
+pub struct DimensionQueryContext<C: QueryContext> {
+    context: C,
+    dimension_selection: DimensionSelection,
+}
+
+impl<C: QueryContext> DimensionQueryContext<C> {
+    pub fn new(context: C, dimension_selection: DimensionSelection) -> Self {
+        Self {
+            context,
+            dimension_selection,
+        }
+    }
+}
+
+impl<C: QueryContext> QueryContext for DimensionQueryContext<C> {
+    fn chunk_byte_size(&self) -> ChunkByteSize {
+        self.context.chunk_byte_size()
+    }
+
+    fn thread_pool(&self) -> &Arc<ThreadPool> {
+        self.context.thread_pool()
+    }
+
+    fn raster_error_policy(&self) -> RasterErrorPolicy {
+        self.context.raster_error_policy()
+    }
+
+    fn add_warning(&self, warning: String) {
+        self.context.add_warning(warning);
+    }
+
+    fn dimension_selection(&self) -> Option<&DimensionSelection> {
+        Some(&self.dimension_selection)
+    }
 }
 
 pub struct MockQueryContext {
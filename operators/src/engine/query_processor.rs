@@ -1,6 +1,7 @@
 use super::query::QueryContext;
 use crate::adapters::RasterConversionQueryProcessor;
 use crate::util::Result;
+use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
 use futures::stream::BoxStream;
 use geoengine_datatypes::collections::{
@@ -9,7 +10,7 @@ use geoengine_datatypes::collections::{
 use geoengine_datatypes::plots::{PlotData, PlotOutputFormat};
 use geoengine_datatypes::primitives::{
     AxisAlignedRectangle, BoundingBox2D, PlotQueryRectangle, QueryRectangle, RasterQueryRectangle,
-    SpatialPartition2D, VectorQueryRectangle,
+    Scalar, SpatialPartition2D, VectorQueryRectangle,
 };
 use geoengine_datatypes::raster::Pixel;
 use geoengine_datatypes::{collections::MultiPointCollection, raster::RasterTile2D};
@@ -24,6 +25,20 @@ pub trait QueryProcessor: Send + Sync {
         query: QueryRectangle<Self::SpatialBounds>,
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::Output>>>;
+
+    /// Whether the results of the `query` are known to have no data anywhere without running it,
+    /// e.g., because the query is outside of the extent of the data. Consumers can then skip the
+    /// query and the encoding of its results.
+    ///
+    /// By default, the results may have data. Processors should only override this if they can
+    /// decide it cheaply.
+    async fn query_is_empty<'a>(
+        &'a self,
+        _query: QueryRectangle<Self::SpatialBounds>,
+        _ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        Ok(false)
+    }
 }
 
 /// An instantiation of a raster operator that produces a stream of raster results for a query
@@ -37,6 +52,15 @@ pub trait RasterQueryProcessor: Sync + Send {
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<RasterTile2D<Self::RasterType>>>>;
 
+    /// Whether the tiles of the `query` are known to be empty without running it, cf. `QueryProcessor::query_is_empty`
+    async fn raster_query_is_empty<'a>(
+        &'a self,
+        _query: RasterQueryRectangle,
+        _ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
     fn boxed(self) -> Box<dyn RasterQueryProcessor<RasterType = Self::RasterType>>
     where
         Self: Sized + 'static,
@@ -61,6 +85,31 @@ where
     ) -> Result<BoxStream<'a, Result<RasterTile2D<Self::RasterType>>>> {
         self.query(query, ctx).await
     }
+
+    async fn raster_query_is_empty<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        self.query_is_empty(query, ctx).await
+    }
+}
+
+/// An instantiation of a scalar operator that computes a single value for a query
+#[async_trait]
+pub trait ScalarQueryProcessor: Sync + Send {
+    async fn scalar_query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Scalar>;
+
+    fn boxed(self) -> Box<dyn ScalarQueryProcessor>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
 }
 
 /// An instantiation of a vector operator that produces a stream of vector results for a query
@@ -73,6 +122,15 @@ pub trait VectorQueryProcessor: Sync + Send {
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::VectorType>>>;
 
+    /// Whether the collections of the `query` are known to be empty without running it, cf. `QueryProcessor::query_is_empty`
+    async fn vector_query_is_empty<'a>(
+        &'a self,
+        _query: VectorQueryRectangle,
+        _ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        Ok(false)
+    }
+
     fn boxed(self) -> Box<dyn VectorQueryProcessor<VectorType = Self::VectorType>>
     where
         Self: Sized + 'static,
@@ -95,6 +153,14 @@ where
     ) -> Result<BoxStream<'a, Result<Self::VectorType>>> {
         self.query(query, ctx).await
     }
+
+    async fn vector_query_is_empty<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        self.query_is_empty(query, ctx).await
+    }
 }
 
 /// An instantiation of a plot operator that produces a stream of vector results for a query
@@ -118,6 +184,39 @@ pub trait PlotQueryProcessor: Sync + Send {
     }
 }
 
+/// An instantiation of a table operator that produces a stream of record batches for a query.
+///
+/// The schema of the batches is given by the operator's `TableResultDescriptor`.
+#[async_trait]
+pub trait TableQueryProcessor: Sync + Send {
+    async fn table_query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<RecordBatch>>>;
+
+    fn boxed(self) -> Box<dyn TableQueryProcessor>
+    where
+        Self: Sized + 'static,
+    {
+        Box::new(self)
+    }
+}
+
+#[async_trait]
+impl<S> TableQueryProcessor for S
+where
+    S: QueryProcessor<Output = RecordBatch, SpatialBounds = BoundingBox2D> + Sync + Send,
+{
+    async fn table_query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<RecordBatch>>> {
+        self.query(query, ctx).await
+    }
+}
+
 #[async_trait]
 impl<T, S> QueryProcessor for Box<dyn QueryProcessor<Output = T, SpatialBounds = S>>
 where
@@ -133,6 +232,14 @@ where
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
         self.as_ref().query(query, ctx).await
     }
+
+    async fn query_is_empty<'a>(
+        &'a self,
+        query: QueryRectangle<S>,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        self.as_ref().query_is_empty(query, ctx).await
+    }
 }
 
 #[async_trait]
@@ -150,6 +257,14 @@ where
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
         self.as_ref().raster_query(query, ctx).await
     }
+
+    async fn query_is_empty<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        self.as_ref().raster_query_is_empty(query, ctx).await
+    }
 }
 
 #[async_trait]
@@ -167,6 +282,28 @@ where
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
         self.as_ref().vector_query(query, ctx).await
     }
+
+    async fn query_is_empty<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        self.as_ref().vector_query_is_empty(query, ctx).await
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for Box<dyn TableQueryProcessor> {
+    type Output = RecordBatch;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        self.as_ref().table_query(query, ctx).await
+    }
 }
 
 /// An enum to differentiate between outputs of raster processors
@@ -1,3 +1,6 @@
+use crate::engine::DimensionSelection;
+use crate::error::Error;
+use crate::util::Result;
 use geoengine_datatypes::primitives::{FeatureDataType, Measurement};
 use geoengine_datatypes::raster::FromPrimitive;
 use geoengine_datatypes::{
@@ -47,6 +50,9 @@ pub struct RasterResultDescriptor {
     pub spatial_reference: SpatialReferenceOption,
     pub measurement: Measurement,
     pub no_data_value: Option<f64>,
+    /// Additional dimensions besides space and time, e.g., elevation or ensemble members
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dimensions: Vec<RasterDimension>,
 }
 
 impl ResultDescriptor for RasterResultDescriptor {
@@ -67,6 +73,7 @@ impl ResultDescriptor for RasterResultDescriptor {
         Self {
             data_type: f(&self.data_type),
             measurement: self.measurement.clone(),
+            dimensions: self.dimensions.clone(),
             ..*self
         }
     }
@@ -78,6 +85,7 @@ impl ResultDescriptor for RasterResultDescriptor {
         Self {
             spatial_reference: f(&self.spatial_reference),
             measurement: self.measurement.clone(),
+            dimensions: self.dimensions.clone(),
             ..*self
         }
     }
@@ -89,6 +97,60 @@ impl RasterResultDescriptor {
     }
 }
 
+/// A dimension of raster data besides space and time, e.g., pressure levels or ensemble members.
+///
+/// The bands of the underlying dataset enumerate all combinations of the dimension values, where
+/// the values of the last dimension are adjacent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterDimension {
+    /// The name of the dimension, e.g., `elevation` or `ensemble`
+    pub name: String,
+    pub units: Option<String>,
+    /// The possible values in the order of the bands
+    pub values: Vec<String>,
+    /// The value if none is selected, the first value otherwise
+    pub default: Option<String>,
+}
+
+impl RasterDimension {
+    /// Computes the (0-based) band offset of the `selection`'s values of the `dimensions`.
+    /// Dimensions without a selected value use their default value.
+    ///
+    /// # Errors
+    ///
+    /// This call fails if a selected value is not a value of its dimension.
+    ///
+    pub fn band_offset(
+        dimensions: &[RasterDimension],
+        selection: Option<&DimensionSelection>,
+    ) -> Result<usize> {
+        let mut offset = 0;
+
+        for dimension in dimensions {
+            let value = selection
+                .and_then(|selection| selection.get(&dimension.name))
+                .or(dimension.default.as_deref());
+
+            let index = match value {
+                Some(value) => dimension
+                    .values
+                    .iter()
+                    .position(|v| v == value)
+                    .ok_or_else(|| Error::InvalidDimensionValue {
+                        dimension: dimension.name.clone(),
+                        value: value.to_string(),
+                    })?,
+                None => 0,
+            };
+
+            offset = offset * dimension.values.len() + index;
+        }
+
+        Ok(offset)
+    }
+}
+
 /// A `ResultDescriptor` for vector queries
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -96,21 +158,68 @@ pub struct VectorResultDescriptor {
     pub data_type: VectorDataType,
     pub spatial_reference: SpatialReferenceOption,
     pub columns: HashMap<String, FeatureDataType>,
+    /// Descriptions of the columns, columns without an entry are undocumented
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub column_metadata: HashMap<String, ColumnMetadata>,
 }
 
 impl VectorResultDescriptor {
-    /// Create a new `VectorResultDescriptor` by only modifying the columns
+    /// Create a new `VectorResultDescriptor` by only modifying the columns.
+    /// The metadata of removed columns is dropped.
     #[must_use]
     pub fn map_columns<F>(&self, f: F) -> Self
     where
         F: Fn(&HashMap<String, FeatureDataType>) -> HashMap<String, FeatureDataType>,
     {
+        let columns = f(&self.columns);
+
         Self {
             data_type: self.data_type,
             spatial_reference: self.spatial_reference,
-            columns: f(&self.columns),
+            column_metadata: self.column_metadata_of(columns.keys()),
+            columns,
         }
     }
+
+    /// Returns the metadata of the given `columns` that have any
+    pub fn column_metadata_of<'c, I>(&self, columns: I) -> HashMap<String, ColumnMetadata>
+    where
+        I: IntoIterator<Item = &'c String>,
+    {
+        columns
+            .into_iter()
+            .filter_map(|column| {
+                self.column_metadata
+                    .get(column)
+                    .map(|metadata| (column.clone(), metadata.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Describes what the values of a column mean
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnMetadata {
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The unit of the values, e.g., `m` or `°C`
+    #[serde(default)]
+    pub unit: Option<String>,
+    #[serde(default)]
+    pub semantic_type: Option<SemanticType>,
+}
+
+/// The role of a column's values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SemanticType {
+    /// Identifies a feature, e.g., a station id
+    Id,
+    /// A class out of a fixed set, e.g., a land use type
+    Category,
+    /// A measured or computed quantity
+    Measurement,
 }
 
 impl ResultDescriptor for VectorResultDescriptor {
@@ -132,6 +241,7 @@ impl ResultDescriptor for VectorResultDescriptor {
             data_type: f(&self.data_type),
             spatial_reference: self.spatial_reference,
             columns: self.columns.clone(),
+            column_metadata: self.column_metadata.clone(),
         }
     }
 
@@ -143,6 +253,7 @@ impl ResultDescriptor for VectorResultDescriptor {
             data_type: self.data_type,
             spatial_reference: f(&self.spatial_reference),
             columns: self.columns.clone(),
+            column_metadata: self.column_metadata.clone(),
         }
     }
 }
@@ -177,12 +288,144 @@ impl ResultDescriptor for PlotResultDescriptor {
         *self
     }
 }
+
+/// A `ResultDescriptor` for table queries, i.e., record batches without geometries or time
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableResultDescriptor {
+    /// The columns of the table in the order of the record batches' schema
+    pub columns: Vec<TableColumn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableColumn {
+    pub name: String,
+    pub data_type: TableDataType,
+}
+
+/// The data type of a table column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TableDataType {
+    Text,
+    Float,
+    Int,
+    Bool,
+    /// Milliseconds since the Unix epoch
+    DateTime,
+}
+
+impl TableDataType {
+    pub fn arrow_data_type(self) -> arrow::datatypes::DataType {
+        match self {
+            Self::Text => arrow::datatypes::DataType::Utf8,
+            Self::Float => arrow::datatypes::DataType::Float64,
+            Self::Int => arrow::datatypes::DataType::Int64,
+            Self::Bool => arrow::datatypes::DataType::Boolean,
+            Self::DateTime => arrow::datatypes::DataType::Date64,
+        }
+    }
+}
+
+impl From<FeatureDataType> for TableDataType {
+    fn from(data_type: FeatureDataType) -> Self {
+        match data_type {
+            FeatureDataType::Text | FeatureDataType::Category => Self::Text,
+            FeatureDataType::Float => Self::Float,
+            FeatureDataType::Int => Self::Int,
+            FeatureDataType::Bool => Self::Bool,
+            FeatureDataType::DateTime => Self::DateTime,
+        }
+    }
+}
+
+impl TableResultDescriptor {
+    /// The Arrow schema of the record batches that the table's query processor produces
+    pub fn arrow_schema(&self) -> arrow::datatypes::Schema {
+        arrow::datatypes::Schema::new(
+            self.columns
+                .iter()
+                .map(|column| {
+                    arrow::datatypes::Field::new(
+                        &column.name,
+                        column.data_type.arrow_data_type(),
+                        true,
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+impl ResultDescriptor for TableResultDescriptor {
+    type DataType = ();
+
+    fn data_type(&self) -> Self::DataType {}
+
+    fn spatial_reference(&self) -> SpatialReferenceOption {
+        SpatialReferenceOption::Unreferenced
+    }
+
+    fn map_data_type<F>(&self, _f: F) -> Self
+    where
+        F: Fn(&Self::DataType) -> Self::DataType,
+    {
+        self.clone()
+    }
+
+    fn map_spatial_reference<F>(&self, _f: F) -> Self
+    where
+        F: Fn(&SpatialReferenceOption) -> SpatialReferenceOption,
+    {
+        self.clone()
+    }
+}
+
+/// A `ResultDescriptor` for scalar queries, i.e., a single value that summarizes the inputs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScalarResultDescriptor {
+    /// The spatial reference of the query rectangles that the scalar is computed for
+    pub spatial_reference: SpatialReferenceOption,
+    pub measurement: Measurement,
+}
+
+impl ResultDescriptor for ScalarResultDescriptor {
+    type DataType = ();
+
+    fn data_type(&self) -> Self::DataType {}
+
+    fn spatial_reference(&self) -> SpatialReferenceOption {
+        self.spatial_reference
+    }
+
+    fn map_data_type<F>(&self, _f: F) -> Self
+    where
+        F: Fn(&Self::DataType) -> Self::DataType,
+    {
+        self.clone()
+    }
+
+    fn map_spatial_reference<F>(&self, f: F) -> Self
+    where
+        F: Fn(&SpatialReferenceOption) -> SpatialReferenceOption,
+    {
+        Self {
+            spatial_reference: f(&self.spatial_reference),
+            measurement: self.measurement.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum TypedResultDescriptor {
     Plot(PlotResultDescriptor),
     Raster(RasterResultDescriptor),
     Vector(VectorResultDescriptor),
+    Table(TableResultDescriptor),
+    Scalar(ScalarResultDescriptor),
 }
 
 impl From<PlotResultDescriptor> for TypedResultDescriptor {
@@ -203,17 +446,62 @@ impl From<VectorResultDescriptor> for TypedResultDescriptor {
     }
 }
 
+impl From<TableResultDescriptor> for TypedResultDescriptor {
+    fn from(value: TableResultDescriptor) -> Self {
+        Self::Table(value)
+    }
+}
+
+impl From<ScalarResultDescriptor> for TypedResultDescriptor {
+    fn from(value: ScalarResultDescriptor) -> Self {
+        Self::Scalar(value)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use geoengine_datatypes::spatial_reference::SpatialReference;
 
+    #[test]
+    fn it_computes_dimension_band_offsets() {
+        let dimensions = vec![
+            RasterDimension {
+                name: "elevation".to_string(),
+                units: Some("hPa".to_string()),
+                values: vec!["1000".to_string(), "850".to_string(), "500".to_string()],
+                default: Some("850".to_string()),
+            },
+            RasterDimension {
+                name: "ensemble".to_string(),
+                units: None,
+                values: vec!["1".to_string(), "2".to_string()],
+                default: None,
+            },
+        ];
+
+        assert_eq!(RasterDimension::band_offset(&[], None).unwrap(), 0);
+        assert_eq!(RasterDimension::band_offset(&dimensions, None).unwrap(), 2);
+
+        let mut selection = DimensionSelection::default();
+        selection.insert("Elevation", "500".to_string());
+        selection.insert("ensemble", "2".to_string());
+        assert_eq!(
+            RasterDimension::band_offset(&dimensions, Some(&selection)).unwrap(),
+            5
+        );
+
+        selection.insert("ensemble", "3".to_string());
+        assert!(RasterDimension::band_offset(&dimensions, Some(&selection)).is_err());
+    }
+
     #[test]
     fn map_vector_descriptor() {
         let descriptor = VectorResultDescriptor {
             data_type: VectorDataType::Data,
             spatial_reference: SpatialReferenceOption::Unreferenced,
             columns: Default::default(),
+            column_metadata: Default::default(),
         };
 
         let columns = {
@@ -233,7 +521,62 @@ mod tests {
                 data_type: VectorDataType::MultiPoint,
                 spatial_reference: SpatialReference::epsg_4326().into(),
                 columns,
+                column_metadata: Default::default(),
             }
         );
     }
+
+    #[test]
+    fn it_keeps_the_metadata_of_remaining_columns() {
+        let metadata = ColumnMetadata {
+            description: Some("Air temperature two meters above ground".to_string()),
+            unit: Some("°C".to_string()),
+            semantic_type: Some(SemanticType::Measurement),
+        };
+
+        let descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPoint,
+            spatial_reference: SpatialReferenceOption::Unreferenced,
+            columns: [
+                ("temperature".to_string(), FeatureDataType::Float),
+                ("station".to_string(), FeatureDataType::Text),
+            ]
+            .into_iter()
+            .collect(),
+            column_metadata: [
+                ("temperature".to_string(), metadata.clone()),
+                (
+                    "station".to_string(),
+                    ColumnMetadata {
+                        semantic_type: Some(SemanticType::Id),
+                        ..Default::default()
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let descriptor = descriptor.map_columns(|columns| {
+            let mut columns = columns.clone();
+            columns.remove("station");
+            columns
+        });
+
+        assert_eq!(
+            descriptor.column_metadata,
+            [("temperature".to_string(), metadata)]
+                .into_iter()
+                .collect::<HashMap<_, _>>()
+        );
+
+        assert_eq!(
+            serde_json::to_value(&descriptor.column_metadata["temperature"]).unwrap(),
+            serde_json::json!({
+                "description": "Air temperature two meters above ground",
+                "unit": "°C",
+                "semanticType": "measurement"
+            })
+        );
+    }
 }
@@ -60,6 +60,12 @@ pub enum Error {
         reason: String,
     },
 
+    #[snafu(display("Invalid feature expression `{}`: {}", expression, reason))]
+    InvalidFeatureExpression {
+        expression: String,
+        reason: String,
+    },
+
     // TODO: use something more general than `Range`, e.g. `dyn RangeBounds` that can, however not be made into an object
     #[snafu(display("InvalidNumberOfRasterInputsError: expected \"[{} .. {}]\" found \"{}\"", expected.start, expected.end, found))]
     InvalidNumberOfRasterInputs {
@@ -105,6 +111,40 @@ pub enum Error {
 
     InvalidNoDataValueValueForOutputDataType,
 
+    #[snafu(display("At least one source of a mosaic must have a no data value"))]
+    MosaicWithoutNoDataValue,
+
+    #[snafu(display("The time steps of the ensemble members are not aligned"))]
+    EnsembleTimesNotAligned,
+
+    #[snafu(display(
+        "The column `{}` is missing in source {} of the union but strict schemas are required",
+        column,
+        source_index
+    ))]
+    UnionColumnMissing {
+        column: String,
+        source_index: usize,
+    },
+
+    #[snafu(display(
+        "The column `{}` has the type {:?} in one source of the union and {:?} in another one",
+        column,
+        left,
+        right
+    ))]
+    UnionColumnTypeMismatch {
+        column: String,
+        left: FeatureDataType,
+        right: FeatureDataType,
+    },
+
+    #[snafu(display("The scenes of the composite's raster, mask and score are not aligned"))]
+    CompositeScenesNotAligned,
+
+    #[snafu(display("The time steps or tiles of the correlated rasters are not aligned"))]
+    CorrelationSourcesNotAligned,
+
     #[snafu(display("Invalid type: expected {} found {}", expected, found))]
     InvalidType {
         expected: String,
@@ -117,6 +157,11 @@ pub enum Error {
         found: String,
     },
 
+    #[snafu(display("The operator has no output {}", output))]
+    UnknownOperatorOutput {
+        output: String,
+    },
+
     #[snafu(display("Invalid vector type: expected {} found {}", expected, found))]
     InvalidVectorType {
         expected: String,
@@ -161,6 +206,34 @@ pub enum Error {
         source: Box<dyn std::error::Error + Send + Sync>,
     },
 
+    #[snafu(display("There is no macro {} with version {:?}", name, version))]
+    UnknownMacro {
+        name: String,
+        version: Option<u32>,
+    },
+    #[snafu(display("The macro {} has no input {}", name, input))]
+    UnknownMacroInput {
+        name: String,
+        input: String,
+    },
+    #[snafu(display("The input {} of macro {} is not given", input, name))]
+    MissingMacroInput {
+        name: String,
+        input: String,
+    },
+    #[snafu(display(
+        "The macro {} does not expand to an operator of this type: {}",
+        name,
+        source
+    ))]
+    InvalidMacroExpansion {
+        name: String,
+        source: serde_json::Error,
+    },
+    WorkflowMacro {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
     Arrow {
         source: arrow::error::ArrowError,
     },
@@ -181,6 +254,24 @@ pub enum Error {
         file_path: String,
     },
 
+    #[snafu(display(
+        "Reading the remote file {} timed out after {} seconds",
+        file_path,
+        timeout_seconds
+    ))]
+    RemoteReadTimeout {
+        file_path: String,
+        timeout_seconds: f64,
+    },
+
+    #[snafu(display(
+        "The remote host {} is temporarily not queried after repeatedly failing reads",
+        host
+    ))]
+    RemoteHostUnavailable {
+        host: String,
+    },
+
     FilePathNotRepresentableAsString,
 
     TokioJoin {
@@ -194,6 +285,7 @@ pub enum Error {
     OgrFieldValueIsNotDateTime,
     OgrFieldValueIsNotString,
     OgrFieldValueIsNotValidForSeconds,
+    OgrFieldValueIsNotValidForDuration,
     OgrColumnFieldTypeMismatch {
         expected: String,
         field_value: gdal::vector::FieldValue,
@@ -213,6 +305,7 @@ pub enum Error {
     TemporalRasterAggregationLastValidRequiresNoData,
     TemporalRasterAggregationFirstValidRequiresNoData,
     TemporalRasterAggregationMeanRequiresNoData,
+    TemporalRasterAggregationPercentileRequiresNoData,
 
     NoSpatialBoundsAvailable,
 
@@ -266,6 +359,21 @@ pub enum Error {
     ))]
     GeoTransformOrigin,
 
+    #[snafu(display("The value {} is not a value of the dimension {}", value, dimension))]
+    InvalidDimensionValue {
+        dimension: String,
+        value: String,
+    },
+
+    #[snafu(display("Query on worker {} failed: {}", worker, details))]
+    DistributedQuery {
+        worker: String,
+        details: String,
+    },
+
+    #[snafu(display("Unable to compute a set operation on polygons"))]
+    PolygonSetOperation,
+
     #[snafu(display("Statistics error: {}", source))]
     Statistics {
         source: crate::util::statistics::StatisticsError,
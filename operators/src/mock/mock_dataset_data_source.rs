@@ -37,6 +37,7 @@ impl MetaData<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQu
             data_type: VectorDataType::MultiPoint,
             spatial_reference: SpatialReferenceOption::Unreferenced,
             columns: Default::default(),
+            column_metadata: Default::default(),
         })
     }
 
@@ -196,6 +197,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
         let ctx = MockQueryContext::new((2 * std::mem::size_of::<Coordinate2D>()).into());
 
@@ -40,6 +40,17 @@ where
 
         Ok(stream::iter(self.collections.iter().map(|c| Ok(c.clone()))).boxed())
     }
+
+    async fn vector_query_is_empty<'a>(
+        &'a self,
+        _query: VectorQueryRectangle,
+        _ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        Ok(self
+            .collections
+            .iter()
+            .all(FeatureCollectionInfos::is_empty))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -126,6 +137,7 @@ macro_rules! impl_mock_feature_collection_source {
                     data_type: <$geometry>::DATA_TYPE,
                     spatial_reference: self.params.spatial_reference,
                     columns: self.params.collections[0].column_types(),
+                    column_metadata: Default::default(),
                 };
 
                 Ok(InitializedMockFeatureCollectionSource {
@@ -299,6 +311,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
         let ctx = MockQueryContext::new((2 * std::mem::size_of::<Coordinate2D>()).into());
 
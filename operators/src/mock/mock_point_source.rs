@@ -1,4 +1,4 @@
-use crate::engine::{OperatorDatasets, QueryContext};
+use crate::engine::{Cacheability, OperatorDatasets, QueryContext};
 use crate::{
     engine::{
         ExecutionContext, InitializedVectorOperator, SourceOperator, TypedVectorQueryProcessor,
@@ -63,23 +63,29 @@ impl VectorOperator for MockPointSource {
         self: Box<Self>,
         _context: &dyn ExecutionContext,
     ) -> Result<Box<dyn InitializedVectorOperator>> {
-        Ok(InitializedMockPointSource {
+        Ok(InitializedMockPointSource::new(self.params.points).boxed())
+    }
+}
+
+pub struct InitializedMockPointSource {
+    result_descriptor: VectorResultDescriptor,
+    points: Vec<Coordinate2D>,
+}
+
+impl InitializedMockPointSource {
+    pub(crate) fn new(points: Vec<Coordinate2D>) -> Self {
+        Self {
             result_descriptor: VectorResultDescriptor {
                 data_type: VectorDataType::MultiPoint,
                 spatial_reference: SpatialReference::epsg_4326().into(),
                 columns: Default::default(),
+                column_metadata: Default::default(),
             },
-            points: self.params.points,
+            points,
         }
-        .boxed())
     }
 }
 
-pub struct InitializedMockPointSource {
-    result_descriptor: VectorResultDescriptor,
-    points: Vec<Coordinate2D>,
-}
-
 impl InitializedVectorOperator for InitializedMockPointSource {
     fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
         Ok(TypedVectorQueryProcessor::MultiPoint(
@@ -93,6 +99,10 @@ impl InitializedVectorOperator for InitializedMockPointSource {
     fn result_descriptor(&self) -> &VectorResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::Deterministic
+    }
 }
 
 #[cfg(test)]
@@ -141,6 +151,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
         let ctx = MockQueryContext::new((2 * std::mem::size_of::<Coordinate2D>()).into());
 
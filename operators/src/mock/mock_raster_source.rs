@@ -1,13 +1,16 @@
 use crate::call_generic_raster_processor;
 use crate::engine::{
-    InitializedRasterOperator, OperatorDatasets, RasterOperator, RasterQueryProcessor,
-    RasterResultDescriptor, SourceOperator, TypedRasterQueryProcessor,
+    Cacheability, InitializedRasterOperator, OperatorDatasets, RasterOperator,
+    RasterQueryProcessor, RasterResultDescriptor, ResultExtent, SourceOperator,
+    TypedRasterQueryProcessor,
 };
 use crate::util::Result;
 use async_trait::async_trait;
 use futures::{stream, stream::StreamExt};
 use geoengine_datatypes::dataset::DatasetId;
-use geoengine_datatypes::primitives::{RasterQueryRectangle, SpatialPartitioned};
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, RasterQueryRectangle, SpatialPartitioned,
+};
 use geoengine_datatypes::raster::{ConvertDataType, FromPrimitive, Pixel, RasterTile2D};
 use num_traits::AsPrimitive;
 use serde::{Deserialize, Serialize};
@@ -55,6 +58,20 @@ where
         )
         .boxed())
     }
+
+    async fn raster_query_is_empty<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        _ctx: &'a dyn crate::engine::QueryContext,
+    ) -> Result<bool> {
+        Ok(!self.data.iter().any(|t| {
+            !t.is_empty()
+                && t.time.intersects(&query.time_interval)
+                && t.tile_information()
+                    .spatial_partition()
+                    .intersects(&query.spatial_bounds)
+        }))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -167,6 +184,16 @@ impl<T: Pixel> InitializedRasterOperator for InitializedMockRasterSource<T> {
     fn result_descriptor(&self) -> &RasterResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::Deterministic
+    }
+
+    fn extent(&self) -> ResultExtent {
+        ResultExtent::union_all(self.data.iter().map(|tile| {
+            ResultExtent::new(Some(tile.spatial_partition().as_bbox()), Some(tile.time))
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -205,6 +232,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -0,0 +1,124 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedScalarOperator, OperatorDatasets, QueryContext,
+    ScalarOperator, ScalarQueryProcessor, ScalarResultDescriptor, SourceOperator,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{Scalar, VectorQueryRectangle};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use serde::{Deserialize, Serialize};
+
+/// Returns the same scalar for every query
+pub struct MockScalarSourceProcessor {
+    scalar: Scalar,
+}
+
+#[async_trait]
+impl ScalarQueryProcessor for MockScalarSourceProcessor {
+    async fn scalar_query<'a>(
+        &'a self,
+        _query: VectorQueryRectangle,
+        _ctx: &'a dyn QueryContext,
+    ) -> Result<Scalar> {
+        Ok(self.scalar.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MockScalarSourceParams {
+    pub scalar: Scalar,
+}
+
+pub type MockScalarSource = SourceOperator<MockScalarSourceParams>;
+
+impl OperatorDatasets for MockScalarSource {
+    fn datasets_collect(&self, _datasets: &mut Vec<DatasetId>) {}
+}
+
+#[typetag::serde]
+#[async_trait]
+impl ScalarOperator for MockScalarSource {
+    async fn initialize(
+        self: Box<Self>,
+        _context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedScalarOperator>> {
+        Ok(InitializedMockScalarSource {
+            result_descriptor: ScalarResultDescriptor {
+                spatial_reference: SpatialReference::epsg_4326().into(),
+                measurement: self.params.scalar.measurement.clone(),
+            },
+            scalar: self.params.scalar,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedMockScalarSource {
+    result_descriptor: ScalarResultDescriptor,
+    scalar: Scalar,
+}
+
+impl InitializedScalarOperator for InitializedMockScalarSource {
+    fn result_descriptor(&self) -> &ScalarResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::Deterministic
+    }
+
+    fn query_processor(&self) -> Result<Box<dyn ScalarQueryProcessor>> {
+        Ok(MockScalarSourceProcessor {
+            scalar: self.scalar.clone(),
+        }
+        .boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, Measurement, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[tokio::test]
+    async fn execute() {
+        let scalar = Scalar::new(42., Measurement::Unitless, 1);
+
+        let operator = MockScalarSource {
+            params: MockScalarSourceParams {
+                scalar: scalar.clone(),
+            },
+        }
+        .boxed();
+
+        let serialized = serde_json::to_value(&operator).unwrap();
+        let operator: Box<dyn ScalarOperator> = serde_json::from_value(serialized).unwrap();
+
+        let processor = operator
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap();
+
+        let result = processor
+            .scalar_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::zero_point_one(),
+                    spatial_filter: None,
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result, scalar);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::engine::{
+    ExecutionContext, InitializedMultiOutputOperator, InitializedVectorOperator,
+    MultiOutputOperator, OperatorDatasets, OperatorOutput, SourceOperator,
+    TypedInitializedOperator,
+};
+use crate::error::Error;
+use crate::mock::InitializedMockPointSource;
+use crate::util::Result;
+use async_trait::async_trait;
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::Coordinate2D;
+use serde::{Deserialize, Serialize};
+
+/// The output names of the [`MockSplitPointSource`]
+pub const MOCK_SPLIT_HEAD_OUTPUT: &str = "head";
+pub const MOCK_SPLIT_TAIL_OUTPUT: &str = "tail";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MockSplitPointSourceParams {
+    pub points: Vec<Coordinate2D>,
+    /// The index of the first point of the `tail` output
+    pub split_at: usize,
+}
+
+/// A source with the outputs `head` and `tail` that contain the points before and after `split_at`
+pub type MockSplitPointSource = SourceOperator<MockSplitPointSourceParams>;
+
+impl OperatorDatasets for MockSplitPointSource {
+    fn datasets_collect(&self, _datasets: &mut Vec<DatasetId>) {}
+}
+
+#[typetag::serde]
+#[async_trait]
+impl MultiOutputOperator for MockSplitPointSource {
+    async fn initialize(
+        self: Box<Self>,
+        _context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedMultiOutputOperator>> {
+        let mut head = self.params.points;
+        let tail = head.split_off(self.params.split_at.min(head.len()));
+
+        let outputs = [MOCK_SPLIT_HEAD_OUTPUT, MOCK_SPLIT_TAIL_OUTPUT]
+            .into_iter()
+            .map(|name| OperatorOutput {
+                name: name.to_string(),
+                result_descriptor: InitializedMockPointSource::new(Vec::new())
+                    .result_descriptor()
+                    .clone()
+                    .into(),
+            })
+            .collect();
+
+        Ok(InitializedMockSplitPointSource {
+            outputs,
+            head,
+            tail,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedMockSplitPointSource {
+    outputs: Vec<OperatorOutput>,
+    head: Vec<Coordinate2D>,
+    tail: Vec<Coordinate2D>,
+}
+
+impl InitializedMultiOutputOperator for InitializedMockSplitPointSource {
+    fn outputs(&self) -> &[OperatorOutput] {
+        &self.outputs
+    }
+
+    fn output(&self, name: &str) -> Result<TypedInitializedOperator> {
+        let points = match name {
+            MOCK_SPLIT_HEAD_OUTPUT => &self.head,
+            MOCK_SPLIT_TAIL_OUTPUT => &self.tail,
+            _ => {
+                return Err(Error::UnknownOperatorOutput {
+                    output: name.to_string(),
+                })
+            }
+        };
+
+        Ok(InitializedMockPointSource::new(points.clone())
+            .boxed()
+            .into())
+    }
+}
@@ -2,8 +2,12 @@ mod mock_dataset_data_source;
 mod mock_feature_collection_source;
 mod mock_point_source;
 mod mock_raster_source;
+mod mock_scalar_source;
+mod mock_split_point_source;
 
 pub use mock_dataset_data_source::*;
 pub use mock_feature_collection_source::*;
 pub use mock_point_source::*;
 pub use mock_raster_source::*;
+pub use mock_scalar_source::*;
+pub use mock_split_point_source::*;
@@ -9,7 +9,7 @@ use geoengine_datatypes::plots::{BoxPlotAttribute, Plot, PlotData};
 use geoengine_datatypes::raster::{GridOrEmpty, GridSize, NoDataValue};
 
 use crate::engine::{
-    ExecutionContext, InitializedPlotOperator, InitializedRasterOperator,
+    Cacheability, ExecutionContext, InitializedPlotOperator, InitializedRasterOperator,
     InitializedVectorOperator, MultipleRasterOrSingleVectorSource, Operator, PlotOperator,
     PlotQueryProcessor, PlotResultDescriptor, QueryContext, QueryProcessor, ResultDescriptor,
     TypedPlotQueryProcessor, TypedRasterQueryProcessor, TypedVectorQueryProcessor,
@@ -174,6 +174,10 @@ impl InitializedPlotOperator for InitializedBoxPlot<Box<dyn InitializedVectorOpe
 
         Ok(TypedPlotQueryProcessor::JsonVega(processor.boxed()))
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
 }
 
 impl InitializedPlotOperator for InitializedBoxPlot<Vec<Box<dyn InitializedRasterOperator>>> {
@@ -195,6 +199,14 @@ impl InitializedPlotOperator for InitializedBoxPlot<Vec<Box<dyn InitializedRaste
         };
         Ok(TypedPlotQueryProcessor::JsonVega(processor.boxed()))
     }
+
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::combine_all(
+            self.source
+                .iter()
+                .map(InitializedRasterOperator::cacheability),
+        )
+    }
 }
 
 /// A query processor that calculates the boxplots about its vector input.
@@ -311,7 +323,7 @@ impl PlotQueryProcessor for BoxPlotRasterQueryProcessor {
             .iter()
             .zip(self.names.iter())
             .map(|(proc, name)| {
-                Self::process_raster(name.clone(), self.include_no_data, proc, query, ctx)
+                Self::process_raster(name.clone(), self.include_no_data, proc, query.clone(), ctx)
             })
             .collect();
 
@@ -589,6 +601,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -656,6 +669,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -762,6 +776,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -814,6 +829,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -868,6 +884,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -917,6 +934,7 @@ mod tests {
                         spatial_reference: SpatialReference::epsg_4326().into(),
                         measurement: Measurement::Unitless,
                         no_data_value: no_data_value.map(AsPrimitive::as_),
+                        dimensions: vec![],
                     },
                 },
             }
@@ -943,6 +961,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -980,6 +999,7 @@ mod tests {
                         spatial_reference: SpatialReference::epsg_4326().into(),
                         measurement: Measurement::Unitless,
                         no_data_value: no_data_value.map(AsPrimitive::as_),
+                        dimensions: vec![],
                     },
                 },
             }
@@ -1006,6 +1026,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -1044,6 +1065,7 @@ mod tests {
                         spatial_reference: SpatialReference::epsg_4326().into(),
                         measurement: Measurement::Unitless,
                         no_data_value: no_data_value.map(AsPrimitive::as_),
+                        dimensions: vec![],
                     },
                 },
             }
@@ -1070,6 +1092,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -1105,6 +1128,7 @@ mod tests {
                         spatial_reference: SpatialReference::epsg_4326().into(),
                         measurement: Measurement::Unitless,
                         no_data_value: no_data_value.map(AsPrimitive::as_),
+                        dimensions: vec![],
                     },
                 },
             }
@@ -1131,6 +1155,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -1173,6 +1198,7 @@ mod tests {
                         spatial_reference: SpatialReference::epsg_4326().into(),
                         measurement: Measurement::Unitless,
                         no_data_value: no_data_value.map(AsPrimitive::as_),
+                        dimensions: vec![],
                     },
                 },
             }
@@ -1200,6 +1226,7 @@ mod tests {
                     )
                     .unwrap(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::test_default(),
             )
@@ -1242,6 +1269,7 @@ mod tests {
                         spatial_reference: SpatialReference::epsg_4326().into(),
                         measurement: Measurement::Unitless,
                         no_data_value: no_data_value.map(AsPrimitive::as_),
+                        dimensions: vec![],
                     },
                 },
             }
@@ -1269,6 +1297,7 @@ mod tests {
                     )
                     .unwrap(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::test_default(),
             )
@@ -1311,6 +1340,7 @@ mod tests {
                         spatial_reference: SpatialReference::epsg_4326().into(),
                         measurement: Measurement::Unitless,
                         no_data_value: no_data_value.map(AsPrimitive::as_),
+                        dimensions: vec![],
                     },
                 },
             }
@@ -1338,6 +1368,7 @@ mod tests {
                     )
                     .unwrap(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::test_default(),
             )
@@ -1375,6 +1406,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         };
@@ -1412,6 +1444,7 @@ mod tests {
                     )
                     .unwrap(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::test_default(),
             )
@@ -0,0 +1,768 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedPlotOperator, InitializedRasterOperator,
+    InitializedVectorOperator, Operator, OperatorDatasets, PlotOperator, PlotQueryProcessor,
+    PlotResultDescriptor, QueryContext, RasterOperator, RasterQueryProcessor,
+    TypedPlotQueryProcessor, TypedRasterQueryProcessor, VectorQueryProcessor,
+};
+use crate::error::{self, Error};
+use crate::util::input::RasterOrVectorOperator;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    FeatureCollectionInfos, GeometryCollection, MultiPointCollection, VectorDataType,
+};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{
+    Coordinate2D, MultiPointAccess, RasterQueryRectangle, SpatialPartitioned, TimeInterval,
+    VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::{
+    CoordinatePixelAccess, GridIdx2D, NoDataValue, Pixel, RasterTile2D,
+};
+use num_traits::AsPrimitive;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+pub const CLASSIFICATION_ACCURACY_OPERATOR_NAME: &str = "ClassificationAccuracy";
+
+/// A plot that assesses the accuracy of a classified raster by comparing it to reference data.
+///
+/// The reference data is either a raster of classes or a point collection with a column of classes.
+/// The output is a confusion matrix with per-class precision and recall, the overall accuracy and
+/// Cohen's kappa.
+pub type ClassificationAccuracy =
+    Operator<ClassificationAccuracyParams, ClassificationAccuracySources>;
+
+/// The parameter spec for `ClassificationAccuracy`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassificationAccuracyParams {
+    /// The (numeric) column of the reference points that contains the classes.
+    /// It is required iff the reference is a vector source.
+    #[serde(default)]
+    pub reference_column: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassificationAccuracySources {
+    pub classification: Box<dyn RasterOperator>,
+    pub reference: RasterOrVectorOperator,
+}
+
+impl OperatorDatasets for ClassificationAccuracySources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.classification.datasets_collect(datasets);
+        self.reference.datasets_collect(datasets);
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl PlotOperator for ClassificationAccuracy {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedPlotOperator>> {
+        let classification = self.sources.classification.initialize(context).await?;
+        let spatial_reference = classification.result_descriptor().spatial_reference;
+
+        let reference = match self.sources.reference {
+            RasterOrVectorOperator::Raster(raster) => {
+                let raster = raster.initialize(context).await?;
+
+                ensure!(
+                    raster.result_descriptor().spatial_reference == spatial_reference,
+                    error::AllSourcesMustHaveSameSpatialReference
+                );
+
+                InitializedReference::Raster(raster)
+            }
+            RasterOrVectorOperator::Vector(vector) => {
+                let vector = vector.initialize(context).await?;
+                let result_descriptor = vector.result_descriptor();
+
+                ensure!(
+                    result_descriptor.spatial_reference == spatial_reference,
+                    error::AllSourcesMustHaveSameSpatialReference
+                );
+                ensure!(
+                    result_descriptor.data_type == VectorDataType::MultiPoint,
+                    error::InvalidType {
+                        expected: VectorDataType::MultiPoint.to_string(),
+                        found: result_descriptor.data_type.to_string(),
+                    }
+                );
+
+                let column = self.params.reference_column.ok_or_else(|| {
+                    Error::InvalidOperatorSpec {
+                        reason: "ClassificationAccuracy on reference points requires a `referenceColumn`"
+                            .to_string(),
+                    }
+                })?;
+
+                match result_descriptor.columns.get(&column) {
+                    Some(data_type) if data_type.is_numeric() => {}
+                    Some(_) => {
+                        return Err(Error::InvalidOperatorSpec {
+                            reason: format!("Column '{}' is not numeric.", column),
+                        })
+                    }
+                    None => return Err(Error::ColumnDoesNotExist { column }),
+                }
+
+                InitializedReference::Points { vector, column }
+            }
+        };
+
+        Ok(InitializedClassificationAccuracy {
+            result_descriptor: PlotResultDescriptor { spatial_reference },
+            classification,
+            reference,
+        }
+        .boxed())
+    }
+}
+
+enum InitializedReference {
+    Raster(Box<dyn InitializedRasterOperator>),
+    Points {
+        vector: Box<dyn InitializedVectorOperator>,
+        column: String,
+    },
+}
+
+/// The initialization of `ClassificationAccuracy`
+pub struct InitializedClassificationAccuracy {
+    result_descriptor: PlotResultDescriptor,
+    classification: Box<dyn InitializedRasterOperator>,
+    reference: InitializedReference,
+}
+
+impl InitializedPlotOperator for InitializedClassificationAccuracy {
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        let reference = match &self.reference {
+            InitializedReference::Raster(raster) => {
+                ReferenceProcessor::Raster(raster.query_processor()?)
+            }
+            InitializedReference::Points { vector, column } => {
+                let points = vector.query_processor()?.multi_point().ok_or_else(|| {
+                    Error::InvalidVectorType {
+                        expected: VectorDataType::MultiPoint.to_string(),
+                        found: vector.result_descriptor().data_type.to_string(),
+                    }
+                })?;
+
+                ReferenceProcessor::Points {
+                    points,
+                    column: column.clone(),
+                }
+            }
+        };
+
+        Ok(TypedPlotQueryProcessor::JsonPlain(
+            ClassificationAccuracyQueryProcessor {
+                classification: self.classification.query_processor()?,
+                reference,
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        match &self.reference {
+            InitializedReference::Raster(raster) => raster.cacheability(),
+            InitializedReference::Points { vector, .. } => vector.cacheability(),
+        }
+        .combine(self.classification.cacheability())
+    }
+}
+
+enum ReferenceProcessor {
+    Raster(TypedRasterQueryProcessor),
+    Points {
+        points: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+        column: String,
+    },
+}
+
+/// A query processor that computes the confusion matrix of a classification.
+pub struct ClassificationAccuracyQueryProcessor {
+    classification: TypedRasterQueryProcessor,
+    reference: ReferenceProcessor,
+}
+
+/// A raster tile's pixels as classes, where no data is `None`
+struct ClassTile {
+    time: TimeInterval,
+    tile_position: GridIdx2D,
+    classes: Vec<Option<i64>>,
+}
+
+impl<P: Pixel> From<RasterTile2D<P>> for ClassTile {
+    fn from(tile: RasterTile2D<P>) -> Self {
+        let time = tile.time;
+        let tile_position = tile.tile_position;
+        let grid = tile.grid_array.into_materialized_grid();
+
+        let classes = grid
+            .data
+            .iter()
+            .map(|&value| {
+                if grid.is_no_data(value) {
+                    None
+                } else {
+                    Some(value.as_())
+                }
+            })
+            .collect();
+
+        Self {
+            time,
+            tile_position,
+            classes,
+        }
+    }
+}
+
+#[async_trait]
+impl PlotQueryProcessor for ClassificationAccuracyQueryProcessor {
+    type OutputFormat = serde_json::Value;
+
+    fn plot_type(&self) -> &'static str {
+        CLASSIFICATION_ACCURACY_OPERATOR_NAME
+    }
+
+    async fn plot_query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let confusion_matrix = match &self.reference {
+            ReferenceProcessor::Raster(reference) => {
+                self.compare_with_raster(reference, query.into(), ctx)
+                    .await?
+            }
+            ReferenceProcessor::Points { points, column } => {
+                let points =
+                    collect_reference_points(points.as_ref(), column, query.clone(), ctx).await?;
+
+                call_on_generic_raster_processor!(&self.classification, processor => {
+                    compare_with_points(processor.as_ref(), &points, query.into(), ctx).await?
+                })
+            }
+        };
+
+        serde_json::to_value(&confusion_matrix.output()).map_err(Into::into)
+    }
+}
+
+impl ClassificationAccuracyQueryProcessor {
+    async fn compare_with_raster(
+        &self,
+        reference: &TypedRasterQueryProcessor,
+        query: RasterQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<ConfusionMatrix> {
+        let mut classification_tiles = call_on_generic_raster_processor!(&self.classification, processor => {
+            class_tiles(processor.as_ref(), query, ctx).await?
+        });
+        let mut reference_tiles = call_on_generic_raster_processor!(reference, processor => {
+            class_tiles(processor.as_ref(), query, ctx).await?
+        });
+
+        // the tiles of the reference time step that is compared with the current classification tiles
+        let mut reference_time: Option<TimeInterval> = None;
+        let mut reference_slice: HashMap<[isize; 2], Vec<Option<i64>>> = HashMap::new();
+        // the first tile of the next reference time step
+        let mut next_reference: Option<ClassTile> = None;
+
+        let mut confusion_matrix = ConfusionMatrix::default();
+
+        while let Some(classification) = classification_tiles.try_next().await? {
+            // the time steps of the rasters may differ, so each classification tile is compared with
+            // the reference time step that is valid at its start (within the query)
+            let instant = classification.time.start().max(query.time_interval.start());
+            let valid_at = TimeInterval::new_instant(instant)?;
+
+            while reference_time.map_or(true, |time| {
+                time.end() <= instant && !time.intersects(&valid_at)
+            }) {
+                let first = match next_reference.take() {
+                    Some(tile) => tile,
+                    None => match reference_tiles.try_next().await? {
+                        Some(tile) => tile,
+                        None => break,
+                    },
+                };
+
+                reference_time = Some(first.time);
+                reference_slice.clear();
+                reference_slice.insert(first.tile_position.0, first.classes);
+
+                while let Some(tile) = reference_tiles.try_next().await? {
+                    if tile.time != first.time {
+                        next_reference = Some(tile);
+                        break;
+                    }
+                    reference_slice.insert(tile.tile_position.0, tile.classes);
+                }
+            }
+
+            if !reference_time.map_or(false, |time| time.intersects(&valid_at)) {
+                continue;
+            }
+
+            let reference = match reference_slice.get(&classification.tile_position.0) {
+                Some(reference) => reference,
+                None => continue,
+            };
+
+            for (classified, reference) in classification.classes.iter().zip(reference) {
+                if let (Some(classified), Some(reference)) = (classified, reference) {
+                    confusion_matrix.add(*reference, *classified);
+                }
+            }
+        }
+
+        Ok(confusion_matrix)
+    }
+}
+
+async fn class_tiles<'a, P: Pixel>(
+    processor: &'a dyn RasterQueryProcessor<RasterType = P>,
+    query: RasterQueryRectangle,
+    ctx: &'a dyn QueryContext,
+) -> Result<BoxStream<'a, Result<ClassTile>>> {
+    Ok(processor
+        .raster_query(query, ctx)
+        .await?
+        .map_ok(ClassTile::from)
+        .boxed())
+}
+
+/// A reference point with its class
+struct ReferencePoint {
+    coordinate: Coordinate2D,
+    time: TimeInterval,
+    class: i64,
+}
+
+async fn collect_reference_points(
+    points: &dyn VectorQueryProcessor<VectorType = MultiPointCollection>,
+    column: &str,
+    query: VectorQueryRectangle,
+    ctx: &dyn QueryContext,
+) -> Result<Vec<ReferencePoint>> {
+    let mut reference_points = Vec::new();
+
+    let mut collections = points.vector_query(query, ctx).await?;
+
+    while let Some(collection) = collections.try_next().await? {
+        let classes = collection.data(column)?;
+
+        for ((geometry, time), class) in collection
+            .geometries()
+            .zip(collection.time_intervals())
+            .zip(classes.float_options_iter())
+        {
+            let class = match class {
+                Some(class) => class as i64,
+                None => continue,
+            };
+
+            for coordinate in geometry.points() {
+                reference_points.push(ReferencePoint {
+                    coordinate: *coordinate,
+                    time: *time,
+                    class,
+                });
+            }
+        }
+    }
+
+    Ok(reference_points)
+}
+
+async fn compare_with_points<P: Pixel>(
+    classification: &dyn RasterQueryProcessor<RasterType = P>,
+    points: &[ReferencePoint],
+    query: RasterQueryRectangle,
+    ctx: &dyn QueryContext,
+) -> Result<ConfusionMatrix> {
+    let mut confusion_matrix = ConfusionMatrix::default();
+
+    let mut tiles = classification.raster_query(query, ctx).await?;
+
+    while let Some(tile) = tiles.try_next().await? {
+        let partition = tile.spatial_partition();
+
+        for point in points {
+            if !point.time.intersects(&tile.time)
+                || !partition.contains_coordinate(&point.coordinate)
+            {
+                continue;
+            }
+
+            let value = tile.pixel_value_at_coord(point.coordinate)?;
+
+            if !tile.is_no_data(value) {
+                confusion_matrix.add(point.class, value.as_());
+            }
+        }
+    }
+
+    Ok(confusion_matrix)
+}
+
+/// Counts the pairs of reference and classified classes
+#[derive(Debug, Default)]
+struct ConfusionMatrix {
+    counts: BTreeMap<(i64, i64), u64>,
+}
+
+impl ConfusionMatrix {
+    fn add(&mut self, reference: i64, classified: i64) {
+        *self.counts.entry((reference, classified)).or_default() += 1;
+    }
+
+    fn output(&self) -> ClassificationAccuracyOutput {
+        let classes: Vec<i64> = self
+            .counts
+            .keys()
+            .flat_map(|&(reference, classified)| [reference, classified])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let matrix: Vec<Vec<u64>> = classes
+            .iter()
+            .map(|&reference| {
+                classes
+                    .iter()
+                    .map(|&classified| {
+                        self.counts
+                            .get(&(reference, classified))
+                            .copied()
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let total: u64 = self.counts.values().sum();
+        let correct: u64 = (0..classes.len()).map(|i| matrix[i][i]).sum();
+
+        let row_sums: Vec<u64> = matrix.iter().map(|row| row.iter().sum()).collect();
+        let column_sums: Vec<u64> = (0..classes.len())
+            .map(|j| matrix.iter().map(|row| row[j]).sum())
+            .collect();
+
+        let overall_accuracy = correct as f64 / total as f64;
+
+        let expected_accuracy = row_sums
+            .iter()
+            .zip(&column_sums)
+            .map(|(&row_sum, &column_sum)| row_sum as f64 * column_sum as f64)
+            .sum::<f64>()
+            / (total as f64 * total as f64);
+
+        let kappa = (overall_accuracy - expected_accuracy) / (1. - expected_accuracy);
+
+        let per_class = classes
+            .iter()
+            .enumerate()
+            .map(|(i, &class)| ClassAccuracy {
+                class,
+                precision: matrix[i][i] as f64 / column_sums[i] as f64,
+                recall: matrix[i][i] as f64 / row_sums[i] as f64,
+            })
+            .collect();
+
+        ClassificationAccuracyOutput {
+            classes,
+            matrix,
+            total,
+            overall_accuracy,
+            kappa,
+            per_class,
+        }
+    }
+}
+
+/// The output of the `ClassificationAccuracy` plot
+///
+/// Undefined ratios, e.g., the precision of a class that was never predicted, are `null`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClassificationAccuracyOutput {
+    classes: Vec<i64>,
+    /// The rows are the reference classes and the columns are the classified classes
+    matrix: Vec<Vec<u64>>,
+    total: u64,
+    overall_accuracy: f64,
+    kappa: f64,
+    per_class: Vec<ClassAccuracy>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClassAccuracy {
+    class: i64,
+    precision: f64,
+    recall: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        ChunkByteSize, MockExecutionContext, MockQueryContext, RasterResultDescriptor,
+        VectorOperator,
+    };
+    use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, FeatureData, Measurement, MultiPoint, SpatialResolution,
+    };
+    use geoengine_datatypes::raster::{
+        Grid2D, RasterDataType, TileInformation, TilingSpecification,
+    };
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+    use serde_json::json;
+
+    #[test]
+    fn serialization() {
+        let serialized = json!({
+            "type": "ClassificationAccuracy",
+            "params": {
+                "referenceColumn": "class",
+            },
+            "sources": {
+                "classification": {
+                    "type": "MockRasterSource",
+                    "params": {
+                        "data": [],
+                        "resultDescriptor": {
+                            "dataType": "U8",
+                            "spatialReference": "EPSG:4326",
+                            "measurement": {
+                                "type": "unitless"
+                            },
+                            "noDataValue": null
+                        }
+                    }
+                },
+                "reference": {
+                    "type": "MockFeatureCollectionSourceMultiPoint",
+                    "params": {
+                        "collections": [],
+                        "spatialReference": "EPSG:4326"
+                    }
+                },
+            },
+        })
+        .to_string();
+
+        let deserialized: ClassificationAccuracy = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(
+            deserialized.params,
+            ClassificationAccuracyParams {
+                reference_column: Some("class".to_string()),
+            }
+        );
+        assert!(deserialized.sources.reference.is_vector());
+    }
+
+    #[test]
+    fn confusion_matrix() {
+        let mut confusion_matrix = ConfusionMatrix::default();
+
+        for (reference, classified, count) in [(1, 1, 5), (1, 2, 1), (2, 1, 2), (2, 2, 2)] {
+            for _ in 0..count {
+                confusion_matrix.add(reference, classified);
+            }
+        }
+
+        let output = confusion_matrix.output();
+
+        assert_eq!(output.classes, vec![1, 2]);
+        assert_eq!(output.matrix, vec![vec![5, 1], vec![2, 2]]);
+        assert_eq!(output.total, 10);
+        assert!((output.overall_accuracy - 0.7).abs() < f64::EPSILON);
+        // p_e = (6 * 7 + 4 * 3) / 100 = 0.54
+        assert!((output.kappa - (0.7 - 0.54) / (1. - 0.54)).abs() < 1e-12);
+        assert!((output.per_class[0].precision - 5. / 7.).abs() < f64::EPSILON);
+        assert!((output.per_class[0].recall - 5. / 6.).abs() < f64::EPSILON);
+        assert!((output.per_class[1].precision - 2. / 3.).abs() < f64::EPSILON);
+        assert!((output.per_class[1].recall - 0.5).abs() < f64::EPSILON);
+    }
+
+    fn class_raster(data: Vec<u8>) -> Box<dyn RasterOperator> {
+        class_raster_time_series(vec![(TimeInterval::default(), data)])
+    }
+
+    fn class_raster_time_series(
+        time_steps: Vec<(TimeInterval, Vec<u8>)>,
+    ) -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: time_steps
+                    .into_iter()
+                    .map(|(time, data)| {
+                        RasterTile2D::new_with_tile_info(
+                            time,
+                            TileInformation {
+                                global_geo_transform: TestDefault::test_default(),
+                                global_tile_position: [0, 0].into(),
+                                tile_size_in_pixels: [2, 2].into(),
+                            },
+                            Grid2D::new([2, 2].into(), data, Some(0)).unwrap().into(),
+                        )
+                    })
+                    .collect(),
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(0.),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn run(operator: ClassificationAccuracy) -> serde_json::Value {
+        let processor = operator
+            .boxed()
+            .initialize(&MockExecutionContext::new_with_tiling_spec(
+                TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+            ))
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .json_plain()
+            .unwrap();
+
+        processor
+            .plot_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., -2.).into(), (2., 0.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
+                },
+                &MockQueryContext::new(ChunkByteSize::MIN),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn reference_raster() {
+        let result = run(ClassificationAccuracy {
+            params: ClassificationAccuracyParams {
+                reference_column: None,
+            },
+            sources: ClassificationAccuracySources {
+                classification: class_raster(vec![1, 1, 2, 0]),
+                reference: class_raster(vec![1, 2, 2, 2]).into(),
+            },
+        })
+        .await;
+
+        assert_eq!(result["classes"], json!([1, 2]));
+        assert_eq!(result["matrix"], json!([[1, 0], [1, 1]]));
+        assert_eq!(result["total"], json!(3));
+    }
+
+    #[tokio::test]
+    async fn reference_raster_with_other_time_steps() {
+        let result = run(ClassificationAccuracy {
+            params: ClassificationAccuracyParams {
+                reference_column: None,
+            },
+            sources: ClassificationAccuracySources {
+                classification: class_raster_time_series(vec![
+                    (TimeInterval::new_unchecked(0, 10), vec![1, 1, 2, 0]),
+                    (TimeInterval::new_unchecked(10, 20), vec![2, 2, 2, 0]),
+                ]),
+                reference: class_raster_time_series(vec![(
+                    TimeInterval::new_unchecked(0, 20),
+                    vec![1, 2, 2, 2],
+                )])
+                .into(),
+            },
+        })
+        .await;
+
+        // both classification time steps are compared with the single reference time step
+        assert_eq!(result["classes"], json!([1, 2]));
+        assert_eq!(result["matrix"], json!([[1, 1], [1, 3]]));
+        assert_eq!(result["total"], json!(6));
+    }
+
+    #[tokio::test]
+    async fn reference_raster_with_finer_time_steps() {
+        let result = run(ClassificationAccuracy {
+            params: ClassificationAccuracyParams {
+                reference_column: None,
+            },
+            sources: ClassificationAccuracySources {
+                classification: class_raster_time_series(vec![(
+                    TimeInterval::new_unchecked(0, 20),
+                    vec![1, 1, 2, 0],
+                )]),
+                reference: class_raster_time_series(vec![
+                    (TimeInterval::new_unchecked(0, 10), vec![1, 2, 2, 2]),
+                    (TimeInterval::new_unchecked(10, 20), vec![2, 2, 2, 2]),
+                ])
+                .into(),
+            },
+        })
+        .await;
+
+        // each pixel is only compared with the reference time step valid at the classification's start
+        assert_eq!(result["classes"], json!([1, 2]));
+        assert_eq!(result["matrix"], json!([[1, 0], [1, 1]]));
+        assert_eq!(result["total"], json!(3));
+    }
+
+    #[tokio::test]
+    async fn reference_points() {
+        let points = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.5, -0.5), (1.5, -0.5), (0.5, -1.5)]).unwrap(),
+            vec![TimeInterval::default(); 3],
+            [("class".to_string(), FeatureData::Int(vec![1, 1, 2]))]
+                .iter()
+                .cloned()
+                .collect(),
+        )
+        .unwrap();
+
+        let result = run(ClassificationAccuracy {
+            params: ClassificationAccuracyParams {
+                reference_column: Some("class".to_string()),
+            },
+            sources: ClassificationAccuracySources {
+                classification: class_raster(vec![1, 2, 2, 0]),
+                reference: MockFeatureCollectionSource::single(points).boxed().into(),
+            },
+        })
+        .await;
+
+        assert_eq!(result["classes"], json!([1, 2]));
+        assert_eq!(result["matrix"], json!([[1, 1], [0, 1]]));
+        assert_eq!(result["overallAccuracy"], json!(2. / 3.));
+    }
+}
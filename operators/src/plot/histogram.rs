@@ -187,6 +187,10 @@ impl InitializedPlotOperator for InitializedHistogram<Box<dyn InitializedRasterO
     fn result_descriptor(&self) -> &PlotResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
 }
 
 impl InitializedPlotOperator for InitializedHistogram<Box<dyn InitializedVectorOperator>> {
@@ -205,6 +209,10 @@ impl InitializedPlotOperator for InitializedHistogram<Box<dyn InitializedVectorO
     fn result_descriptor(&self) -> &PlotResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
 }
 
 /// A query processor that calculates the Histogram about its raster inputs.
@@ -237,7 +245,7 @@ impl PlotQueryProcessor for HistogramRasterQueryProcessor {
         query: VectorQueryRectangle,
         ctx: &'p dyn QueryContext,
     ) -> Result<Self::OutputFormat> {
-        self.preprocess(query, ctx)
+        self.preprocess(query.clone(), ctx)
             .and_then(move |mut histogram_metadata| async move {
                 histogram_metadata.sanitize();
                 if histogram_metadata.has_invalid_parameters() {
@@ -264,7 +272,7 @@ impl PlotQueryProcessor for HistogramVectorQueryProcessor {
         query: VectorQueryRectangle,
         ctx: &'p dyn QueryContext,
     ) -> Result<Self::OutputFormat> {
-        self.preprocess(query, ctx)
+        self.preprocess(query.clone(), ctx)
             .and_then(move |mut histogram_metadata| async move {
                 histogram_metadata.sanitize();
                 if histogram_metadata.has_invalid_parameters() {
@@ -589,7 +597,7 @@ mod tests {
     use super::*;
 
     use crate::engine::{
-        ChunkByteSize, MockExecutionContext, MockQueryContext, RasterOperator,
+        Cacheability, ChunkByteSize, MockExecutionContext, MockQueryContext, RasterOperator,
         RasterResultDescriptor, StaticMetaData, VectorOperator, VectorResultDescriptor,
     };
     use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
@@ -735,6 +743,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -772,6 +781,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -820,6 +830,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -884,6 +895,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -952,6 +964,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -1017,6 +1030,7 @@ mod tests {
                         ],
                         bool: vec![],
                         datetime: vec![],
+                        category: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
@@ -1024,6 +1038,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -1038,6 +1053,7 @@ mod tests {
                     .iter()
                     .cloned()
                     .collect(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -1080,6 +1096,7 @@ mod tests {
                         spatial_reference: SpatialReference::epsg_4326().into(),
                         measurement: Measurement::Unitless,
                         no_data_value: no_data_value.map(AsPrimitive::as_),
+                        dimensions: vec![],
                     },
                 },
             }
@@ -1106,6 +1123,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -1163,6 +1181,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -1220,6 +1239,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -1267,6 +1287,7 @@ mod tests {
                         spatial_reference: SpatialReference::epsg_4326().into(),
                         measurement: Measurement::Unitless,
                         no_data_value: no_data_value.map(AsPrimitive::as_),
+                        dimensions: vec![],
                     },
                 },
             }
@@ -1294,6 +1315,7 @@ mod tests {
                     )
                     .unwrap(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -1,10 +1,16 @@
 mod box_plot;
+mod classification_accuracy;
 mod histogram;
 mod scatter_plot;
 mod statistics;
 mod temporal_raster_mean_plot;
 mod temporal_vector_line_plot;
+mod vertical_profile;
 
+pub use self::classification_accuracy::{
+    ClassificationAccuracy, ClassificationAccuracyParams, ClassificationAccuracyQueryProcessor,
+    ClassificationAccuracySources, InitializedClassificationAccuracy,
+};
 pub use self::histogram::{
     Histogram, HistogramBounds, HistogramParams, HistogramRasterQueryProcessor,
     HistogramVectorQueryProcessor, InitializedHistogram,
@@ -16,3 +22,7 @@ pub use self::temporal_raster_mean_plot::{
     InitializedMeanRasterPixelValuesOverTime, MeanRasterPixelValuesOverTime,
     MeanRasterPixelValuesOverTimeParams, MeanRasterPixelValuesOverTimeQueryProcessor,
 };
+pub use self::vertical_profile::{
+    InitializedVerticalProfile, VerticalProfile, VerticalProfileParams,
+    VerticalProfileQueryProcessor,
+};
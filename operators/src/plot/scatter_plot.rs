@@ -6,9 +6,9 @@ use geoengine_datatypes::collections::FeatureCollectionInfos;
 use geoengine_datatypes::plots::{Histogram2D, HistogramDimension, Plot, PlotData};
 
 use crate::engine::{
-    ExecutionContext, InitializedPlotOperator, InitializedVectorOperator, Operator, PlotOperator,
-    PlotQueryProcessor, PlotResultDescriptor, QueryContext, QueryProcessor, SingleVectorSource,
-    TypedPlotQueryProcessor, TypedVectorQueryProcessor,
+    Cacheability, ExecutionContext, InitializedPlotOperator, InitializedVectorOperator, Operator,
+    PlotOperator, PlotQueryProcessor, PlotResultDescriptor, QueryContext, QueryProcessor,
+    SingleVectorSource, TypedPlotQueryProcessor, TypedVectorQueryProcessor,
 };
 use crate::error::Error;
 use crate::util::Result;
@@ -114,6 +114,10 @@ impl InitializedPlotOperator for InitializedScatterPlot<Box<dyn InitializedVecto
 
         Ok(TypedPlotQueryProcessor::JsonVega(processor.boxed()))
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
 }
 
 /// A query processor that calculates the scatter plot about its vector input.
@@ -365,6 +369,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -442,6 +447,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -615,6 +621,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -668,6 +675,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -723,6 +731,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -804,6 +813,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
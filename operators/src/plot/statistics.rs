@@ -1,15 +1,16 @@
 use crate::engine::{
-    ExecutionContext, InitializedPlotOperator, InitializedRasterOperator, MultipleRasterSources,
-    Operator, PlotOperator, PlotQueryProcessor, PlotResultDescriptor, QueryContext, QueryProcessor,
-    TypedPlotQueryProcessor, TypedRasterQueryProcessor,
+    Cacheability, ExecutionContext, InitializedPlotOperator, InitializedRasterOperator,
+    MultipleRasterSources, Operator, PlotOperator, PlotQueryProcessor, PlotResultDescriptor,
+    QueryContext, QueryProcessor, TypedPlotQueryProcessor, TypedRasterQueryProcessor,
 };
 use crate::error;
 use crate::util::number_statistics::NumberStatistics;
+use crate::util::statistics::{HyperLogLog, TDigest};
 use crate::util::Result;
 use async_trait::async_trait;
 use futures::future::join_all;
 use futures::stream::select_all;
-use futures::{FutureExt, StreamExt, TryFutureExt, TryStreamExt};
+use futures::{StreamExt, TryFutureExt, TryStreamExt};
 use geoengine_datatypes::primitives::VectorQueryRectangle;
 use geoengine_datatypes::raster::ConvertDataTypeParallel;
 use geoengine_datatypes::raster::{Grid2D, GridOrEmpty, GridSize, NoDataValue};
@@ -27,9 +28,21 @@ pub const STATISTICS_OPERATOR_NAME: &str = "Statistics";
 pub type Statistics = Operator<StatisticsParams, MultipleRasterSources>;
 
 /// The parameter spec for `Statistics`
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+///
+/// Percentiles and distinct counts are estimated in a single pass and are only computed if requested.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct StatisticsParams {}
+pub struct StatisticsParams {
+    /// Percentiles in the interval [0, 1], e.g., `0.5` for the median
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub percentiles: Vec<f64>,
+    /// Whether to estimate the number of distinct values
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub count_distinct: bool,
+}
+
+const T_DIGEST_COMPRESSION: f64 = 100.;
+const HYPER_LOG_LOG_PRECISION: u8 = 12;
 
 #[typetag::serde]
 #[async_trait]
@@ -47,6 +60,16 @@ impl PlotOperator for Statistics {
         .await;
         let rasters = rasters.into_iter().collect::<Result<Vec<_>>>()?;
 
+        ensure!(
+            self.params
+                .percentiles
+                .iter()
+                .all(|percentile| (0.0..=1.0).contains(percentile)),
+            error::InvalidOperatorSpec {
+                reason: "Percentiles must be in the interval [0, 1]".to_string(),
+            }
+        );
+
         if rasters.len() > 1 {
             let srs = rasters[0].result_descriptor().spatial_reference;
             ensure!(
@@ -64,6 +87,7 @@ impl PlotOperator for Statistics {
                     |r| r.result_descriptor().spatial_reference,
                 ),
             },
+            params: self.params,
             rasters,
         };
 
@@ -74,6 +98,7 @@ impl PlotOperator for Statistics {
 /// The initialization of `Statistics`
 pub struct InitializedStatistics {
     result_descriptor: PlotResultDescriptor,
+    params: StatisticsParams,
     rasters: Vec<Box<dyn InitializedRasterOperator>>,
 }
 
@@ -86,6 +111,7 @@ impl InitializedPlotOperator for InitializedStatistics {
                     .iter()
                     .map(InitializedRasterOperator::query_processor)
                     .collect::<Result<Vec<_>>>()?,
+                params: self.params.clone(),
             }
             .boxed(),
         ))
@@ -94,11 +120,20 @@ impl InitializedPlotOperator for InitializedStatistics {
     fn result_descriptor(&self) -> &PlotResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::combine_all(
+            self.rasters
+                .iter()
+                .map(InitializedRasterOperator::cacheability),
+        )
+    }
 }
 
 /// A query processor that calculates the statistics about its inputs.
 pub struct StatisticsQueryProcessor {
     rasters: Vec<TypedRasterQueryProcessor>,
+    params: StatisticsParams,
 }
 
 #[async_trait]
@@ -118,58 +153,115 @@ impl PlotQueryProcessor for StatisticsQueryProcessor {
         for (i, raster_processor) in self.rasters.iter().enumerate() {
             queries.push(
                 call_on_generic_raster_processor!(raster_processor, processor => {
-                    processor.query(query.into(), ctx).await?
+                    processor.query(query.clone().into(), ctx).await?
                              .and_then(move |tile| crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || (i, tile.convert_data_type_parallel()) ).map_err(Into::into))
                              .boxed()
                 }),
             );
         }
 
-        let number_statistics = vec![NumberStatistics::default(); self.rasters.len()];
+        let raster_statistics = (0..self.rasters.len())
+            .map(|_| RasterStatistics::new(&self.params))
+            .collect::<Result<Vec<_>>>()?;
 
-        select_all(queries)
-            .fold(
-                Ok(number_statistics),
-                |number_statistics: Result<Vec<NumberStatistics>>, enumerated_raster_tile| async move {
-                    let mut number_statistics = number_statistics?;
-                    let (i, raster_tile) = enumerated_raster_tile?;
+        let raster_statistics = select_all(queries)
+            .try_fold(
+                raster_statistics,
+                |mut raster_statistics, (i, raster_tile)| async move {
                     match raster_tile.grid_array {
-                        GridOrEmpty::Grid(g) => process_raster(&mut number_statistics[i], &g),
-                        GridOrEmpty::Empty(n) => number_statistics[i].add_no_data_batch(n.number_of_elements())
+                        GridOrEmpty::Grid(g) => raster_statistics[i].process_raster(&g),
+                        GridOrEmpty::Empty(n) => raster_statistics[i]
+                            .number_statistics
+                            .add_no_data_batch(n.number_of_elements()),
                     }
 
-                    Ok(number_statistics)
+                    Ok(raster_statistics)
                 },
             )
-            .map(|number_statistics| {
-                let output: Vec<StatisticsOutput> = number_statistics?.iter().map(StatisticsOutput::from).collect();
-                serde_json::to_value(&output).map_err(Into::into)
-            })
-            .await
+            .await?;
+
+        let output: Vec<StatisticsOutput> = raster_statistics
+            .iter()
+            .map(|statistics| statistics.output(&self.params.percentiles))
+            .collect();
+        serde_json::to_value(&output).map_err(Into::into)
     }
 }
 
-#[allow(clippy::float_cmp)] // allow since NO DATA is a specific value
-fn process_raster(number_statistics: &mut NumberStatistics, tile_grid: &Grid2D<f64>) {
-    let no_data_value = tile_grid.no_data_value();
+/// The streaming estimators for a single raster input
+struct RasterStatistics {
+    number_statistics: NumberStatistics,
+    digest: Option<TDigest>,
+    distinct_values: Option<HyperLogLog>,
+}
 
-    if let Some(no_data_value) = no_data_value {
-        for &value in &tile_grid.data {
-            if value == no_data_value {
-                number_statistics.add_no_data();
+impl RasterStatistics {
+    fn new(params: &StatisticsParams) -> Result<Self> {
+        Ok(Self {
+            number_statistics: NumberStatistics::default(),
+            digest: if params.percentiles.is_empty() {
+                None
+            } else {
+                Some(TDigest::new(T_DIGEST_COMPRESSION)?)
+            },
+            distinct_values: if params.count_distinct {
+                Some(HyperLogLog::new(HYPER_LOG_LOG_PRECISION)?)
             } else {
-                number_statistics.add(value);
+                None
+            },
+        })
+    }
+
+    #[allow(clippy::float_cmp)] // allow since NO DATA is a specific value
+    fn process_raster(&mut self, tile_grid: &Grid2D<f64>) {
+        let no_data_value = tile_grid.no_data_value();
+
+        for &value in &tile_grid.data {
+            if Some(value) == no_data_value {
+                self.number_statistics.add_no_data();
+                continue;
+            }
+
+            self.number_statistics.add(value);
+
+            if let Some(digest) = &mut self.digest {
+                digest.update(value);
+            }
+            if let Some(distinct_values) = &mut self.distinct_values {
+                distinct_values.update_f64(value);
             }
         }
-    } else {
-        for &value in &tile_grid.data {
-            number_statistics.add(value);
+    }
+
+    fn output(&self, percentiles: &[f64]) -> StatisticsOutput {
+        let number_statistics = &self.number_statistics;
+
+        StatisticsOutput {
+            pixel_count: number_statistics.count(),
+            nan_count: number_statistics.nan_count(),
+            min: number_statistics.min(),
+            max: number_statistics.max(),
+            mean: number_statistics.mean(),
+            stddev: number_statistics.std_dev(),
+            percentiles: self.digest.as_ref().map_or_else(Vec::new, |digest| {
+                percentiles
+                    .iter()
+                    .map(|&percentile| PercentileOutput {
+                        percentile,
+                        value: digest.quantile(percentile),
+                    })
+                    .collect()
+            }),
+            distinct_count: self
+                .distinct_values
+                .as_ref()
+                .map(|distinct_values| distinct_values.estimate().round() as u64),
         }
     }
 }
 
 /// The statistics summary output type for each raster input
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct StatisticsOutput {
     pub pixel_count: usize,
@@ -178,19 +270,18 @@ struct StatisticsOutput {
     pub max: f64,
     pub mean: f64,
     pub stddev: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub percentiles: Vec<PercentileOutput>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distinct_count: Option<u64>,
 }
 
-impl From<&NumberStatistics> for StatisticsOutput {
-    fn from(number_statistics: &NumberStatistics) -> Self {
-        Self {
-            pixel_count: number_statistics.count(),
-            nan_count: number_statistics.nan_count(),
-            min: number_statistics.min(),
-            max: number_statistics.max(),
-            mean: number_statistics.mean(),
-            stddev: number_statistics.std_dev(),
-        }
-    }
+/// An estimated percentile of a raster input
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PercentileOutput {
+    pub percentile: f64,
+    pub value: f64,
 }
 
 #[cfg(test)]
@@ -214,7 +305,7 @@ mod tests {
     #[test]
     fn serialization() {
         let statistics = Statistics {
-            params: StatisticsParams {},
+            params: StatisticsParams::default(),
             sources: MultipleRasterSources { rasters: vec![] },
         };
 
@@ -253,13 +344,14 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
         .boxed();
 
         let statistics = Statistics {
-            params: StatisticsParams {},
+            params: StatisticsParams::default(),
             sources: vec![raster_source].into(),
         };
 
@@ -280,6 +372,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -299,4 +392,93 @@ mod tests {
             .to_string()
         );
     }
+
+    #[tokio::test]
+    async fn percentiles_and_distinct_count() {
+        let no_data_value = Some(0);
+        let raster_source = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::default(),
+                    TileInformation {
+                        global_geo_transform: TestDefault::test_default(),
+                        global_tile_position: [0, 0].into(),
+                        tile_size_in_pixels: [3, 2].into(),
+                    },
+                    Grid2D::new([3, 2].into(), vec![1, 2, 2, 4, 5, 0], no_data_value)
+                        .unwrap()
+                        .into(),
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed();
+
+        let statistics = Statistics {
+            params: StatisticsParams {
+                percentiles: vec![0., 0.5, 1.],
+                count_distinct: true,
+            },
+            sources: vec![raster_source].into(),
+        };
+
+        let execution_context = MockExecutionContext::test_default();
+
+        let statistics = statistics
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap();
+
+        let processor = statistics.query_processor().unwrap().json_plain().unwrap();
+
+        let result = processor
+            .plot_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
+                },
+                &MockQueryContext::new(ChunkByteSize::MIN),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(result[0]["pixelCount"], json!(5));
+        assert_eq!(result[0]["nanCount"], json!(1));
+        assert_eq!(
+            result[0]["percentiles"],
+            json!([
+                {"percentile": 0.0, "value": 1.0},
+                {"percentile": 0.5, "value": 2.0},
+                {"percentile": 1.0, "value": 5.0},
+            ])
+        );
+        assert_eq!(result[0]["distinctCount"], json!(4));
+    }
+
+    #[tokio::test]
+    async fn invalid_percentile() {
+        let statistics = Statistics {
+            params: StatisticsParams {
+                percentiles: vec![1.5],
+                count_distinct: false,
+            },
+            sources: MultipleRasterSources { rasters: vec![] },
+        };
+
+        assert!(statistics
+            .boxed()
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .is_err());
+    }
 }
@@ -1,7 +1,7 @@
 use crate::engine::{
-    ExecutionContext, InitializedPlotOperator, InitializedRasterOperator, Operator, PlotOperator,
-    PlotQueryProcessor, PlotResultDescriptor, QueryContext, QueryProcessor, RasterQueryProcessor,
-    SingleRasterSource, TypedPlotQueryProcessor,
+    Cacheability, ExecutionContext, InitializedPlotOperator, InitializedRasterOperator, Operator,
+    PlotOperator, PlotQueryProcessor, PlotResultDescriptor, QueryContext, QueryProcessor,
+    RasterQueryProcessor, SingleRasterSource, TypedPlotQueryProcessor,
 };
 use crate::util::math::average_floor;
 use crate::util::Result;
@@ -91,6 +91,10 @@ impl InitializedPlotOperator for InitializedMeanRasterPixelValuesOverTime {
     fn result_descriptor(&self) -> &PlotResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.raster.cacheability()
+    }
 }
 
 /// A query processor that calculates the `TemporalRasterMeanPlot` about its input.
@@ -360,6 +364,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -407,6 +412,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -470,6 +476,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
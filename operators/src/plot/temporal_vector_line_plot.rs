@@ -1,7 +1,7 @@
 use crate::engine::QueryProcessor;
 use crate::engine::{
-    ExecutionContext, InitializedPlotOperator, InitializedVectorOperator, Operator, PlotOperator,
-    PlotQueryProcessor, PlotResultDescriptor, QueryContext, SingleVectorSource,
+    Cacheability, ExecutionContext, InitializedPlotOperator, InitializedVectorOperator, Operator,
+    PlotOperator, PlotQueryProcessor, PlotResultDescriptor, QueryContext, SingleVectorSource,
     TypedPlotQueryProcessor, VectorQueryProcessor,
 };
 use crate::error;
@@ -80,10 +80,7 @@ impl PlotOperator for FeatureAttributeValuesOverTime {
             error::InvalidFeatureDataType,
         );
 
-        ensure!(
-            value_type.is_numeric() || value_type == &FeatureDataType::Category,
-            error::InvalidFeatureDataType,
-        );
+        ensure!(value_type.is_numeric(), error::InvalidFeatureDataType);
 
         Ok(InitializedFeatureAttributeValuesOverTime {
             result_descriptor: PlotResultDescriptor {
@@ -117,6 +114,10 @@ impl InitializedPlotOperator for InitializedFeatureAttributeValuesOverTime {
     fn result_descriptor(&self) -> &PlotResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source.cacheability()
+    }
 }
 
 /// A query processor that calculates the `TemporalVectorLinePlot` on its input.
@@ -327,6 +328,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -427,6 +429,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -515,6 +518,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -0,0 +1,447 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedPlotOperator, InitializedRasterOperator,
+    InitializedVectorOperator, Operator, PlotOperator, PlotQueryProcessor, PlotResultDescriptor,
+    QueryContext, RasterQueryProcessor, SingleVectorMultipleRasterSources, TypedPlotQueryProcessor,
+    TypedRasterQueryProcessor, VectorQueryProcessor,
+};
+use crate::error;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::future::join_all;
+use futures::TryStreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollectionInfos, GeometryCollection, MultiPointCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    Coordinate2D, MultiPointAccess, SpatialPartitioned, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::{CoordinatePixelAccess, NoDataValue, Pixel};
+use num_traits::AsPrimitive;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::BTreeMap;
+
+pub const VERTICAL_PROFILE_OPERATOR_NAME: &str = "VerticalProfile";
+
+/// A plot that extracts vertical profiles, i.e., the values of all levels, at the points of the vector input.
+///
+/// Each raster input represents one level, e.g., a depth or a pressure level.
+pub type VerticalProfile = Operator<VerticalProfileParams, SingleVectorMultipleRasterSources>;
+
+/// The parameter spec for `VerticalProfile`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerticalProfileParams {
+    /// The level (e.g., depth or pressure) of each raster input
+    pub levels: Vec<f64>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl PlotOperator for VerticalProfile {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedPlotOperator>> {
+        ensure!(
+            !self.sources.rasters.is_empty(),
+            error::InvalidNumberOfRasterInputs {
+                expected: 1..usize::MAX,
+                found: 0_usize
+            }
+        );
+        ensure!(
+            self.sources.rasters.len() == self.params.levels.len(),
+            error::InvalidOperatorSpec {
+                reason: "`rasters` must be of equal length as `levels`"
+            }
+        );
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let vector_rd = vector_source.result_descriptor();
+
+        ensure!(
+            vector_rd.data_type == VectorDataType::MultiPoint,
+            error::InvalidType {
+                expected: VectorDataType::MultiPoint.to_string(),
+                found: vector_rd.data_type.to_string(),
+            }
+        );
+
+        let raster_sources = join_all(
+            self.sources
+                .rasters
+                .into_iter()
+                .map(|s| s.initialize(context)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        let spatial_reference = vector_rd.spatial_reference;
+
+        for other_spatial_reference in raster_sources
+            .iter()
+            .map(|source| source.result_descriptor().spatial_reference)
+        {
+            ensure!(
+                spatial_reference == other_spatial_reference,
+                error::InvalidSpatialReference {
+                    expected: spatial_reference,
+                    found: other_spatial_reference,
+                }
+            );
+        }
+
+        Ok(InitializedVerticalProfile {
+            result_descriptor: PlotResultDescriptor { spatial_reference },
+            vector_source,
+            raster_sources,
+            levels: self.params.levels,
+        }
+        .boxed())
+    }
+}
+
+/// The initialization of `VerticalProfile`
+pub struct InitializedVerticalProfile {
+    result_descriptor: PlotResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    raster_sources: Vec<Box<dyn InitializedRasterOperator>>,
+    levels: Vec<f64>,
+}
+
+impl InitializedPlotOperator for InitializedVerticalProfile {
+    fn query_processor(&self) -> Result<TypedPlotQueryProcessor> {
+        let points = self
+            .vector_source
+            .query_processor()?
+            .multi_point()
+            .ok_or_else(|| error::Error::InvalidVectorType {
+                expected: VectorDataType::MultiPoint.to_string(),
+                found: self.vector_source.result_descriptor().data_type.to_string(),
+            })?;
+
+        Ok(TypedPlotQueryProcessor::JsonPlain(
+            VerticalProfileQueryProcessor {
+                points,
+                rasters: self
+                    .raster_sources
+                    .iter()
+                    .map(InitializedRasterOperator::query_processor)
+                    .collect::<Result<Vec<_>>>()?,
+                levels: self.levels.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &PlotResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source
+            .cacheability()
+            .combine(Cacheability::combine_all(
+                self.raster_sources
+                    .iter()
+                    .map(InitializedRasterOperator::cacheability),
+            ))
+    }
+}
+
+/// A query processor that extracts the vertical profiles at the input points.
+pub struct VerticalProfileQueryProcessor {
+    points: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    rasters: Vec<TypedRasterQueryProcessor>,
+    levels: Vec<f64>,
+}
+
+/// A single point of the input with its feature's validity
+struct ProfilePoint {
+    feature: usize,
+    coordinate: Coordinate2D,
+    time: TimeInterval,
+}
+
+/// The profiles of all points, indexed by point and the start and end of the time interval
+type Profiles = BTreeMap<(usize, i64, i64), Vec<Option<f64>>>;
+
+#[async_trait]
+impl PlotQueryProcessor for VerticalProfileQueryProcessor {
+    type OutputFormat = serde_json::Value;
+
+    fn plot_type(&self) -> &'static str {
+        VERTICAL_PROFILE_OPERATOR_NAME
+    }
+
+    async fn plot_query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Self::OutputFormat> {
+        let points = self.collect_points(query.clone(), ctx).await?;
+
+        let mut profiles = Profiles::new();
+
+        for (level, raster) in self.rasters.iter().enumerate() {
+            call_on_generic_raster_processor!(raster, processor => {
+                self.extract_level(processor.as_ref(), level, &points, &mut profiles, query.clone(), ctx).await?;
+            });
+        }
+
+        let output = VerticalProfileOutput {
+            levels: self.levels.clone(),
+            profiles: profiles
+                .into_iter()
+                .map(|((point, start, end), values)| {
+                    let point = &points[point];
+                    Ok(ProfileOutput {
+                        feature: point.feature,
+                        coordinate: point.coordinate,
+                        time: TimeInterval::new(start, end)?,
+                        values,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?,
+        };
+
+        serde_json::to_value(&output).map_err(Into::into)
+    }
+}
+
+impl VerticalProfileQueryProcessor {
+    async fn collect_points(
+        &self,
+        query: VectorQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<Vec<ProfilePoint>> {
+        let mut points = Vec::new();
+        let mut feature_offset = 0;
+
+        let mut collections = self.points.vector_query(query, ctx).await?;
+
+        while let Some(collection) = collections.try_next().await? {
+            for (feature, (geometry, time)) in collection
+                .geometries()
+                .zip(collection.time_intervals())
+                .enumerate()
+            {
+                for coordinate in geometry.points() {
+                    points.push(ProfilePoint {
+                        feature: feature_offset + feature,
+                        coordinate: *coordinate,
+                        time: *time,
+                    });
+                }
+            }
+
+            feature_offset += collection.len();
+        }
+
+        Ok(points)
+    }
+
+    async fn extract_level<P: Pixel>(
+        &self,
+        processor: &dyn RasterQueryProcessor<RasterType = P>,
+        level: usize,
+        points: &[ProfilePoint],
+        profiles: &mut Profiles,
+        query: VectorQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<()> {
+        let mut tiles = processor.raster_query(query.into(), ctx).await?;
+
+        while let Some(tile) = tiles.try_next().await? {
+            let partition = tile.spatial_partition();
+
+            for (point_index, point) in points.iter().enumerate() {
+                let time = match point.time.intersect(&tile.time) {
+                    Some(time) if partition.contains_coordinate(&point.coordinate) => time,
+                    _ => continue,
+                };
+
+                let value = tile.pixel_value_at_coord(point.coordinate)?;
+                let value = if tile.is_no_data(value) {
+                    None
+                } else {
+                    Some(value.as_())
+                };
+
+                let profile = profiles
+                    .entry((point_index, time.start().inner(), time.end().inner()))
+                    .or_insert_with(|| vec![None; self.levels.len()]);
+
+                profile[level] = value;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The output of the `VerticalProfile` plot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VerticalProfileOutput {
+    levels: Vec<f64>,
+    profiles: Vec<ProfileOutput>,
+}
+
+/// The values of all levels for a point and a time interval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileOutput {
+    feature: usize,
+    coordinate: Coordinate2D,
+    time: TimeInterval,
+    values: Vec<Option<f64>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        ChunkByteSize, MockExecutionContext, MockQueryContext, RasterOperator,
+        RasterResultDescriptor, VectorOperator,
+    };
+    use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, Measurement, MultiPoint, SpatialResolution,
+    };
+    use geoengine_datatypes::raster::{
+        Grid2D, RasterDataType, RasterTile2D, TileInformation, TilingSpecification,
+    };
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+    use serde_json::json;
+
+    #[test]
+    fn serialization() {
+        let profile = VerticalProfile {
+            params: VerticalProfileParams {
+                levels: vec![10., 20.],
+            },
+            sources: SingleVectorMultipleRasterSources {
+                vector: MockFeatureCollectionSource::<MultiPoint>::multiple(vec![]).boxed(),
+                rasters: vec![],
+            },
+        };
+
+        let serialized = json!({
+            "type": "VerticalProfile",
+            "params": {
+                "levels": [10., 20.],
+            },
+            "sources": {
+                "vector": {
+                    "type": "MockFeatureCollectionSourceMultiPoint",
+                    "params": {
+                        "collections": [],
+                        "spatialReference": "EPSG:4326"
+                    }
+                },
+                "rasters": [],
+            },
+        })
+        .to_string();
+
+        let deserialized: VerticalProfile = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.params, profile.params);
+    }
+
+    fn level_source(data: Vec<u8>) -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::default(),
+                    TileInformation {
+                        global_geo_transform: TestDefault::test_default(),
+                        global_tile_position: [0, 0].into(),
+                        tile_size_in_pixels: [2, 2].into(),
+                    },
+                    Grid2D::new([2, 2].into(), data, Some(0)).unwrap().into(),
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(0.),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed()
+    }
+
+    #[tokio::test]
+    async fn profiles_at_points() {
+        let points = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.5, -0.5), (1.5, -1.5)]).unwrap(),
+            vec![TimeInterval::default(); 2],
+            Default::default(),
+        )
+        .unwrap();
+
+        let profile = VerticalProfile {
+            params: VerticalProfileParams {
+                levels: vec![10., 20.],
+            },
+            sources: SingleVectorMultipleRasterSources {
+                vector: MockFeatureCollectionSource::single(points).boxed(),
+                rasters: vec![
+                    level_source(vec![1, 2, 3, 4]),
+                    level_source(vec![5, 6, 7, 0]),
+                ],
+            },
+        };
+
+        let execution_context = MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+        );
+
+        let processor = profile
+            .boxed()
+            .initialize(&execution_context)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .json_plain()
+            .unwrap();
+
+        let result = processor
+            .plot_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., -2.).into(), (2., 0.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
+                },
+                &MockQueryContext::new(ChunkByteSize::MIN),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            json!({
+                "levels": [10.0, 20.0],
+                "profiles": [{
+                    "feature": 0,
+                    "coordinate": {"x": 0.5, "y": -0.5},
+                    "time": TimeInterval::default(),
+                    "values": [1.0, 5.0]
+                }, {
+                    "feature": 1,
+                    "coordinate": {"x": 1.5, "y": -1.5},
+                    "time": TimeInterval::default(),
+                    "values": [4.0, null]
+                }]
+            })
+        );
+    }
+}
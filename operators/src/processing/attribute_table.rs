@@ -0,0 +1,404 @@
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Date64Array};
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::{FeatureCollection, FeatureCollectionInfos};
+use geoengine_datatypes::primitives::{BoundingBox2D, Geometry, VectorQueryRectangle};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedTableOperator, InitializedVectorOperator, Operator,
+    QueryContext, QueryProcessor, SingleVectorSource, TableColumn, TableDataType, TableOperator,
+    TableQueryProcessor, TableResultDescriptor, VectorQueryProcessor,
+};
+use crate::error::{self, Error};
+use crate::util::input::VectorOrTableOperator;
+use crate::util::Result;
+
+/// The name of the column with the start of the features' validity if `include_time` is set
+pub const ATTRIBUTE_TABLE_START_COLUMN: &str = "start";
+/// The name of the column with the end of the features' validity if `include_time` is set
+pub const ATTRIBUTE_TABLE_END_COLUMN: &str = "end";
+
+/// The attribute table drops the geometries of a vector source and outputs its attributes as a table.
+///
+/// Category columns are output as text columns.
+pub type AttributeTable = Operator<AttributeTableParams, SingleVectorSource>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeTableParams {
+    /// The columns of the table, all columns in alphabetical order if not specified
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+    /// Whether to prepend the start and end of the features' validity as columns
+    #[serde(default)]
+    pub include_time: bool,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl TableOperator for AttributeTable {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedTableOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let source_columns = &vector_source.result_descriptor().columns;
+
+        let column_names = if let Some(columns) = self.params.columns {
+            columns
+        } else {
+            let mut columns: Vec<String> = source_columns.keys().cloned().collect();
+            columns.sort();
+            columns
+        };
+
+        let mut columns = Vec::with_capacity(column_names.len() + 2);
+
+        if self.params.include_time {
+            for name in [ATTRIBUTE_TABLE_START_COLUMN, ATTRIBUTE_TABLE_END_COLUMN] {
+                ensure!(
+                    !column_names.iter().any(|column| column == name),
+                    error::InvalidOperatorSpec {
+                        reason: format!("Column '{}' collides with the time column.", name),
+                    }
+                );
+
+                columns.push(TableColumn {
+                    name: name.to_string(),
+                    data_type: TableDataType::DateTime,
+                });
+            }
+        }
+
+        for name in column_names {
+            let data_type =
+                source_columns
+                    .get(&name)
+                    .copied()
+                    .ok_or_else(|| Error::ColumnDoesNotExist {
+                        column: name.clone(),
+                    })?;
+
+            columns.push(TableColumn {
+                name,
+                data_type: data_type.into(),
+            });
+        }
+
+        let result_descriptor = TableResultDescriptor { columns };
+
+        Ok(InitializedAttributeTable {
+            schema: Arc::new(result_descriptor.arrow_schema()),
+            result_descriptor,
+            vector_source,
+            include_time: self.params.include_time,
+        }
+        .boxed())
+    }
+}
+
+/// Initializes a table source or turns a vector source into an attribute table of the `columns`
+pub(super) async fn initialize_as_table(
+    source: VectorOrTableOperator,
+    columns: Vec<String>,
+    context: &dyn ExecutionContext,
+) -> Result<Box<dyn InitializedTableOperator>> {
+    match source {
+        VectorOrTableOperator::Vector(vector) => {
+            AttributeTable {
+                params: AttributeTableParams {
+                    columns: Some(columns),
+                    include_time: false,
+                },
+                sources: vector.into(),
+            }
+            .boxed()
+            .initialize(context)
+            .await
+        }
+        VectorOrTableOperator::Table(table) => table.initialize(context).await,
+    }
+}
+
+pub struct InitializedAttributeTable {
+    result_descriptor: TableResultDescriptor,
+    schema: SchemaRef,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    include_time: bool,
+}
+
+impl InitializedTableOperator for InitializedAttributeTable {
+    fn result_descriptor(&self) -> &TableResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<Box<dyn TableQueryProcessor>> {
+        Ok(call_on_generic_vector_processor!(
+            self.vector_source.query_processor()?,
+            source => TableQueryProcessor::boxed(AttributeTableProcessor::new(
+                source,
+                self.schema.clone(),
+                self.include_time,
+            ))
+        ))
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source.cacheability()
+    }
+}
+
+pub struct AttributeTableProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    schema: SchemaRef,
+    include_time: bool,
+}
+
+impl<G> AttributeTableProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send,
+{
+    fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        schema: SchemaRef,
+        include_time: bool,
+    ) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+            schema,
+            include_time,
+        }
+    }
+
+    fn record_batch(&self, collection: &FeatureCollection<G>) -> Result<RecordBatch> {
+        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(self.schema.fields().len());
+
+        if self.include_time {
+            let time_intervals = collection.time_intervals();
+
+            arrays.push(Arc::new(Date64Array::from(
+                time_intervals
+                    .iter()
+                    .map(|time| time.start().inner())
+                    .collect::<Vec<_>>(),
+            )));
+            arrays.push(Arc::new(Date64Array::from(
+                time_intervals
+                    .iter()
+                    .map(|time| time.end().inner())
+                    .collect::<Vec<_>>(),
+            )));
+        }
+
+        for field in self.schema.fields().iter().skip(arrays.len()) {
+            let array = collection.column_array(field.name())?;
+
+            arrays.push(if let DataType::Dictionary(_, _) = array.data_type() {
+                arrow::compute::cast(array, &DataType::Utf8)?
+            } else {
+                array.clone()
+            });
+        }
+
+        RecordBatch::try_new(self.schema.clone(), arrays).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for AttributeTableProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = RecordBatch;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let stream = self
+            .source
+            .query(query, ctx)
+            .await?
+            .map(move |collection| self.record_batch(&collection?));
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use arrow::array::{Float64Array, StringArray};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn source() -> Box<dyn VectorOperator> {
+        MockFeatureCollectionSource::single(
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1)]).unwrap(),
+                vec![
+                    TimeInterval::new(0, 10).unwrap(),
+                    TimeInterval::new(5, 15).unwrap(),
+                ],
+                [
+                    (
+                        "name".to_string(),
+                        FeatureData::Category(vec!["a".to_string(), "b".to_string()]),
+                    ),
+                    ("value".to_string(), FeatureData::Float(vec![1.5, 2.5])),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap(),
+        )
+        .boxed()
+    }
+
+    async fn query(operator: Box<dyn TableOperator>) -> Vec<RecordBatch> {
+        let initialized = operator
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap();
+
+        let processor = initialized.query_processor().unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: Default::default(),
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: None,
+        };
+        let ctx = MockQueryContext::test_default();
+
+        processor
+            .table_query(query, &ctx)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn serde() {
+        let operator = AttributeTable {
+            params: AttributeTableParams {
+                columns: Some(vec!["value".to_string()]),
+                include_time: true,
+            },
+            sources: source().into(),
+        }
+        .boxed();
+
+        let serialized = serde_json::to_value(&operator).unwrap();
+
+        assert_eq!(serialized["type"], "AttributeTable");
+        assert_eq!(
+            serialized["params"],
+            serde_json::json!({
+                "columns": ["value"],
+                "includeTime": true
+            })
+        );
+
+        let _operator: Box<dyn TableOperator> = serde_json::from_value(serialized).unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_outputs_attributes() {
+        let batches = query(
+            AttributeTable {
+                params: AttributeTableParams {
+                    columns: None,
+                    include_time: false,
+                },
+                sources: source().into(),
+            }
+            .boxed(),
+        )
+        .await;
+
+        assert_eq!(batches.len(), 1);
+
+        let batch = &batches[0];
+        assert_eq!(
+            batch
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| (field.name().as_str(), field.data_type().clone()))
+                .collect::<Vec<_>>(),
+            vec![("name", DataType::Utf8), ("value", DataType::Float64)]
+        );
+
+        let names: &StringArray = batch.column(0).as_any().downcast_ref().unwrap();
+        assert_eq!(names.value(0), "a");
+        assert_eq!(names.value(1), "b");
+
+        let values: &Float64Array = batch.column(1).as_any().downcast_ref().unwrap();
+        assert_eq!(values.values(), &[1.5, 2.5]);
+    }
+
+    #[tokio::test]
+    async fn it_includes_time() {
+        let batches = query(
+            AttributeTable {
+                params: AttributeTableParams {
+                    columns: Some(vec!["value".to_string()]),
+                    include_time: true,
+                },
+                sources: source().into(),
+            }
+            .boxed(),
+        )
+        .await;
+
+        let batch = &batches[0];
+        assert_eq!(batch.num_columns(), 3);
+
+        let start: &Date64Array = batch.column(0).as_any().downcast_ref().unwrap();
+        assert_eq!(start.values(), &[0, 5]);
+
+        let end: &Date64Array = batch.column(1).as_any().downcast_ref().unwrap();
+        assert_eq!(end.values(), &[10, 15]);
+    }
+
+    #[tokio::test]
+    async fn it_checks_columns() {
+        let result = AttributeTable {
+            params: AttributeTableParams {
+                columns: Some(vec!["foo".to_string()]),
+                include_time: false,
+            },
+            sources: source().into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::ColumnDoesNotExist { column }) if column == "foo"
+        ));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, QueryContext,
+    QueryProcessor, ResultExtent, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error::Error;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    is_castable, CastErrorPolicy, FeatureCollection, FeatureCollectionModifications,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataType, Geometry, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+/// The cast column operator converts the values of a column to another type, e.g., texts of a
+/// CSV file to numbers.
+///
+/// Columns can be cast to `int`, `float`, `text` and `dateTime`. Ints are interpreted as
+/// milliseconds since the Unix epoch when cast to date times and texts as ISO 8601 date times.
+pub type CastColumn = Operator<CastColumnParams, SingleVectorSource>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CastColumnParams {
+    pub column: String,
+    pub data_type: FeatureDataType,
+    /// Decides about values that cannot be represented in the `data_type`
+    #[serde(default = "default_cast_error_policy")]
+    pub on_error: CastErrorPolicy,
+}
+
+fn default_cast_error_policy() -> CastErrorPolicy {
+    CastErrorPolicy::Fail
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for CastColumn {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+
+        let mut result_descriptor = vector_source.result_descriptor().clone();
+
+        let column_type = result_descriptor
+            .columns
+            .get_mut(&self.params.column)
+            .ok_or_else(|| Error::ColumnDoesNotExist {
+                column: self.params.column.clone(),
+            })?;
+
+        if !is_castable(*column_type, self.params.data_type) {
+            return Err(Error::InvalidOperatorSpec {
+                reason: format!(
+                    "Column '{}' of type {:?} cannot be cast to {:?}.",
+                    self.params.column, column_type, self.params.data_type
+                ),
+            });
+        }
+
+        *column_type = self.params.data_type;
+
+        let initialized_operator = InitializedCastColumn {
+            result_descriptor,
+            vector_source,
+            params: self.params,
+        };
+
+        Ok(initialized_operator.boxed())
+    }
+}
+
+pub struct InitializedCastColumn {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    params: CastColumnParams,
+}
+
+impl InitializedVectorOperator for InitializedCastColumn {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => CastColumnProcessor::new(source, self.params.clone()).boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.vector_source.extent()
+    }
+}
+
+pub struct CastColumnProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    params: CastColumnParams,
+}
+
+impl<G> CastColumnProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send,
+{
+    fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        params: CastColumnParams,
+    ) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+            params,
+        }
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for CastColumnProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let stream = self.source.query(query, ctx).await?.map(move |collection| {
+            collection?
+                .cast_column(
+                    &self.params.column,
+                    self.params.data_type,
+                    self.params.on_error,
+                )
+                .map_err(Into::into)
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::{FeatureCollectionInfos, MultiPointCollection};
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn collection() -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1), (2.0, 2.1)]).unwrap(),
+            vec![TimeInterval::new(0, 1).unwrap(); 3],
+            [(
+                "population".to_string(),
+                FeatureData::Text(vec!["73836".to_string(), "".to_string(), "n/a".to_string()]),
+            )]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap()
+    }
+
+    async fn cast(
+        data_type: FeatureDataType,
+        on_error: CastErrorPolicy,
+    ) -> Result<Vec<MultiPointCollection>> {
+        let operator = CastColumn {
+            params: CastColumnParams {
+                column: "population".to_string(),
+                data_type,
+                on_error,
+            },
+            sources: MockFeatureCollectionSource::single(collection())
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await?;
+
+        assert_eq!(
+            operator.result_descriptor().columns.get("population"),
+            Some(&data_type)
+        );
+
+        let processor = operator.query_processor()?.multi_point().unwrap();
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
+        };
+
+        let ctx = MockQueryContext::test_default();
+
+        let stream = processor.query(query_rectangle, &ctx).await?;
+
+        stream.collect::<Vec<_>>().await.into_iter().collect()
+    }
+
+    #[tokio::test]
+    async fn text_to_int() {
+        let result = cast(FeatureDataType::Int, CastErrorPolicy::Null)
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].column_type("population").unwrap(),
+            FeatureDataType::Int
+        );
+        assert_eq!(
+            result[0]
+                .data("population")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(73836.), None, None]
+        );
+
+        assert!(cast(FeatureDataType::Int, CastErrorPolicy::Fail)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn invalid_casts() {
+        let ctx = MockExecutionContext::test_default();
+
+        let missing_column = CastColumn {
+            params: CastColumnParams {
+                column: "foo".to_string(),
+                data_type: FeatureDataType::Int,
+                on_error: CastErrorPolicy::Fail,
+            },
+            sources: MockFeatureCollectionSource::single(collection())
+                .boxed()
+                .into(),
+        }
+        .boxed();
+
+        assert!(matches!(
+            missing_column.initialize(&ctx).await,
+            Err(Error::ColumnDoesNotExist { .. })
+        ));
+
+        assert!(matches!(
+            cast(FeatureDataType::Bool, CastErrorPolicy::Fail).await,
+            Err(Error::InvalidOperatorSpec { .. })
+        ));
+    }
+
+    #[test]
+    fn serde() {
+        let params: CastColumnParams = serde_json::from_value(serde_json::json!({
+            "column": "population",
+            "dataType": "int",
+        }))
+        .unwrap();
+
+        assert_eq!(
+            params,
+            CastColumnParams {
+                column: "population".to_string(),
+                data_type: FeatureDataType::Int,
+                on_error: CastErrorPolicy::Fail,
+            }
+        );
+
+        let params: CastColumnParams = serde_json::from_value(serde_json::json!({
+            "column": "population",
+            "dataType": "float",
+            "onError": "clamp",
+        }))
+        .unwrap();
+
+        assert_eq!(params.on_error, CastErrorPolicy::Clamp);
+    }
+}
@@ -0,0 +1,645 @@
+use crate::engine::{
+    BoxRasterQueryProcessor, Cacheability, ColumnMetadata, ExecutionContext,
+    InitializedRasterOperator, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
+    ResultExtent, SemanticType, SingleRasterSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::gdal::gdal_union_polygons;
+use crate::util::pixel_time_series::PixelTimeSeries;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    BuilderProvider, GeoFeatureCollectionRowBuilder, MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataType, FeatureDataValue, Measurement, RasterQueryRectangle,
+    TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::{GeoTransform, GridSize, TileInformation, TilingSpecification};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::BTreeMap;
+
+pub const CHANGE_AREA_COLUMN: &str = "area";
+pub const CHANGE_MAGNITUDE_COLUMN: &str = "magnitude";
+
+/// The `ChangeDetection` operator compares two periods of a raster source and outputs the
+/// areas that changed between them as polygons, e.g., to find clear-cuts in a series of NDVI
+/// composites.
+///
+/// Each period is averaged per pixel and the difference from `before` to `after` is thresholded.
+/// Adjacent pixels that changed in the same direction form one polygon with the attributes
+/// `area`, in squared units of the spatial reference, and `magnitude`, the mean difference of
+/// its pixels. The polygons are valid for the time from the start of `before` to the end of
+/// `after` and ignore the time of the query otherwise.
+pub type ChangeDetection = Operator<ChangeDetectionParams, SingleRasterSource>;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeDetectionParams {
+    pub before: TimeInterval,
+    pub after: TimeInterval,
+    /// The minimum absolute difference of a changed pixel
+    pub threshold: f64,
+    #[serde(default)]
+    pub difference: ChangeDifference,
+    #[serde(default)]
+    pub direction: ChangeDirection,
+    /// Changes that cover fewer pixels are dropped, e.g., to suppress noise
+    #[serde(default)]
+    pub min_pixels: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeDifference {
+    /// `after - before`
+    Absolute,
+    /// `(after - before) / |before|`, pixels where `before` is zero have no difference
+    Relative,
+}
+
+impl Default for ChangeDifference {
+    fn default() -> Self {
+        ChangeDifference::Absolute
+    }
+}
+
+impl ChangeDifference {
+    fn compute(self, before: f64, after: f64) -> Option<f64> {
+        let difference = match self {
+            ChangeDifference::Absolute => after - before,
+            ChangeDifference::Relative if before == 0. => return None,
+            ChangeDifference::Relative => (after - before) / before.abs(),
+        };
+
+        difference.is_finite().then(|| difference)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeDirection {
+    Any,
+    Increase,
+    Decrease,
+}
+
+impl Default for ChangeDirection {
+    fn default() -> Self {
+        ChangeDirection::Any
+    }
+}
+
+impl ChangeDetectionParams {
+    fn is_change(&self, difference: f64) -> bool {
+        match self.direction {
+            ChangeDirection::Any => difference.abs() >= self.threshold,
+            ChangeDirection::Increase => difference >= self.threshold,
+            ChangeDirection::Decrease => -difference >= self.threshold,
+        }
+    }
+
+    fn time(&self) -> TimeInterval {
+        self.before.extend(&self.after)
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for ChangeDetection {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(
+            self.params.threshold.is_finite() && self.params.threshold > 0.,
+            error::InvalidOperatorSpec {
+                reason: "The threshold must be positive".to_string(),
+            }
+        );
+        ensure!(
+            !self.params.before.intersects(&self.params.after),
+            error::InvalidOperatorSpec {
+                reason: "The periods before and after the change must not overlap".to_string(),
+            }
+        );
+
+        let source = self.sources.raster.initialize(context).await?;
+
+        let magnitude_unit = match (
+            self.params.difference,
+            &source.result_descriptor().measurement,
+        ) {
+            (ChangeDifference::Absolute, Measurement::Continuous(measurement)) => {
+                measurement.unit.clone()
+            }
+            (ChangeDifference::Relative, _) => Some("fraction".to_string()),
+            _ => None,
+        };
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPolygon,
+            spatial_reference: source.result_descriptor().spatial_reference,
+            columns: [
+                (CHANGE_AREA_COLUMN.to_string(), FeatureDataType::Float),
+                (CHANGE_MAGNITUDE_COLUMN.to_string(), FeatureDataType::Float),
+            ]
+            .into_iter()
+            .collect(),
+            column_metadata: [
+                (
+                    CHANGE_AREA_COLUMN.to_string(),
+                    ColumnMetadata {
+                        description: Some(
+                            "The area of the change in squared units of the spatial reference"
+                                .to_string(),
+                        ),
+                        unit: None,
+                        semantic_type: Some(SemanticType::Measurement),
+                    },
+                ),
+                (
+                    CHANGE_MAGNITUDE_COLUMN.to_string(),
+                    ColumnMetadata {
+                        description: Some("The mean difference of the changed pixels".to_string()),
+                        unit: magnitude_unit,
+                        semantic_type: Some(SemanticType::Measurement),
+                    },
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        Ok(InitializedChangeDetection {
+            result_descriptor,
+            source,
+            params: self.params,
+            tiling_specification: context.tiling_specification(),
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedChangeDetection {
+    result_descriptor: VectorResultDescriptor,
+    source: Box<dyn InitializedRasterOperator>,
+    params: ChangeDetectionParams,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedVectorOperator for InitializedChangeDetection {
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.source.extent()
+    }
+
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(TypedVectorQueryProcessor::MultiPolygon(
+            ChangeDetectionProcessor {
+                source: self.source.query_processor()?.into_f64(),
+                params: self.params,
+                tiling_specification: self.tiling_specification,
+            }
+            .boxed(),
+        ))
+    }
+}
+
+pub struct ChangeDetectionProcessor {
+    source: BoxRasterQueryProcessor<f64>,
+    params: ChangeDetectionParams,
+    tiling_specification: TilingSpecification,
+}
+
+#[async_trait]
+impl QueryProcessor for ChangeDetectionProcessor {
+    type Output = MultiPolygonCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        if !self.params.time().intersects(&query.time_interval) {
+            return Ok(stream::once(async { Ok(MultiPolygonCollection::empty()) }).boxed());
+        }
+
+        let query: RasterQueryRectangle = query.into();
+        let tiling_strategy = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+        // a change can span many tiles, so we have to collect the changed pixels of all tiles
+        // before they are polygonized
+        let mut changes = ChangedPixels::new(self.params, tiling_strategy.geo_transform);
+        for tile_info in tiling_strategy.tile_information_iterator(query.spatial_bounds) {
+            let before = self.period_series(tile_info, query, self.params.before, ctx);
+            let after = self.period_series(tile_info, query, self.params.after, ctx);
+            let (before, after) = futures::try_join!(before, after)?;
+
+            changes.add_tile(tile_info, &before, &after);
+        }
+
+        let polygons =
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                changes.polygonize()
+            })
+            .await??;
+
+        Ok(stream::once(async move { Ok(polygons) }).boxed())
+    }
+}
+
+impl ChangeDetectionProcessor {
+    async fn period_series(
+        &self,
+        tile_info: TileInformation,
+        query: RasterQueryRectangle,
+        period: TimeInterval,
+        ctx: &dyn QueryContext,
+    ) -> Result<PixelTimeSeries> {
+        PixelTimeSeries::query(
+            self.source.as_ref(),
+            tile_info,
+            RasterQueryRectangle {
+                time_interval: period,
+                ..query
+            },
+            ctx,
+        )
+        .await
+    }
+}
+
+/// The mean of the observations of a pixel, `None` if it has none
+fn mean(series: &PixelTimeSeries, pixel: usize) -> Option<f64> {
+    let (sum, count) = series
+        .observations(pixel)
+        .fold((0., 0_usize), |(sum, count), (_, value)| {
+            (sum + value, count + 1)
+        });
+
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// The differences of the changed pixels by their global `(y, x)` pixel index
+struct ChangedPixels {
+    params: ChangeDetectionParams,
+    geo_transform: GeoTransform,
+    differences: BTreeMap<(isize, isize), f64>,
+}
+
+impl ChangedPixels {
+    fn new(params: ChangeDetectionParams, geo_transform: GeoTransform) -> Self {
+        Self {
+            params,
+            geo_transform,
+            differences: BTreeMap::new(),
+        }
+    }
+
+    fn add_tile(
+        &mut self,
+        tile_info: TileInformation,
+        before: &PixelTimeSeries,
+        after: &PixelTimeSeries,
+    ) {
+        let width = tile_info.tile_size_in_pixels.axis_size_x();
+        let [upper_left_y, upper_left_x] = *tile_info.global_upper_left_pixel_idx().inner();
+
+        for pixel in 0..tile_info.tile_size_in_pixels.number_of_elements() {
+            let difference = match (mean(before, pixel), mean(after, pixel)) {
+                (Some(before), Some(after)) => self.params.difference.compute(before, after),
+                _ => None,
+            };
+
+            if let Some(difference) = difference.filter(|&d| self.params.is_change(d)) {
+                let y = upper_left_y + (pixel / width) as isize;
+                let x = upper_left_x + (pixel % width) as isize;
+                self.differences.insert((y, x), difference);
+            }
+        }
+    }
+
+    /// Removes the change that contains `start` from the pixels and returns its pixels. Pixels
+    /// belong to the same change if they share an edge and changed in the same direction.
+    fn take_change(&mut self, start: (isize, isize)) -> Vec<((isize, isize), f64)> {
+        let mut change = Vec::new();
+
+        let increase = self.differences[&start] > 0.;
+        let mut stack = vec![start];
+        while let Some((y, x)) = stack.pop() {
+            let difference = match self.differences.get(&(y, x)) {
+                Some(&difference) if (difference > 0.) == increase => difference,
+                _ => continue,
+            };
+            self.differences.remove(&(y, x));
+            change.push(((y, x), difference));
+
+            stack.extend([(y - 1, x), (y + 1, x), (y, x - 1), (y, x + 1)]);
+        }
+
+        change
+    }
+
+    fn pixel_polygon(&self, (y, x): (isize, isize)) -> geo::Polygon<f64> {
+        let upper_left = self
+            .geo_transform
+            .grid_idx_to_upper_left_coordinate_2d([y, x].into());
+        let lower_right = self
+            .geo_transform
+            .grid_idx_to_upper_left_coordinate_2d([y + 1, x + 1].into());
+
+        geo::Polygon::new(
+            vec![
+                (upper_left.x, upper_left.y),
+                (lower_right.x, upper_left.y),
+                (lower_right.x, lower_right.y),
+                (upper_left.x, lower_right.y),
+                (upper_left.x, upper_left.y),
+            ]
+            .into(),
+            vec![],
+        )
+    }
+
+    /// Merges the changed pixels into one polygon per change in the order of their upper left
+    /// pixel
+    fn polygonize(mut self) -> Result<MultiPolygonCollection> {
+        let pixel_area =
+            (self.geo_transform.x_pixel_size() * self.geo_transform.y_pixel_size()).abs();
+        let time = self.params.time();
+
+        let mut builder = MultiPolygonCollection::builder();
+        builder.add_column(CHANGE_AREA_COLUMN.to_string(), FeatureDataType::Float)?;
+        builder.add_column(CHANGE_MAGNITUDE_COLUMN.to_string(), FeatureDataType::Float)?;
+        let mut builder = builder.finish_header();
+
+        while let Some(&start) = self.differences.keys().next() {
+            let change = self.take_change(start);
+            if change.len() < self.params.min_pixels {
+                continue;
+            }
+
+            let pixels = change
+                .iter()
+                .map(|&(pixel, _)| self.pixel_polygon(pixel))
+                .collect();
+            let magnitude = change
+                .iter()
+                .map(|&(_, difference)| difference)
+                .sum::<f64>()
+                / change.len() as f64;
+
+            builder.push_geometry(gdal_union_polygons(&geo::MultiPolygon(pixels))?)?;
+            builder.push_time_interval(time)?;
+            builder.push_data(
+                CHANGE_AREA_COLUMN,
+                FeatureDataValue::Float(change.len() as f64 * pixel_area),
+            )?;
+            builder.push_data(CHANGE_MAGNITUDE_COLUMN, FeatureDataValue::Float(magnitude))?;
+            builder.finish_row();
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, RasterOperator, RasterResultDescriptor,
+        VectorQueryProcessor,
+    };
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use futures::TryStreamExt;
+    use geo::algorithm::area::Area;
+    use geoengine_datatypes::collections::{FeatureCollectionInfos, GeometryCollection};
+    use geoengine_datatypes::primitives::{MultiPolygon, SpatialResolution};
+    use geoengine_datatypes::raster::{Grid2D, RasterDataType, RasterTile2D};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    /// Two scenes of two 2x2 tiles side by side, s.t. the raster covers `(0, 0)` to `(4, 2)`
+    fn make_raster(before: [f64; 8], after: [f64; 8]) -> Box<dyn RasterOperator> {
+        let tile = |time: i64, position: isize, data: &[f64]| {
+            RasterTile2D::new_with_tile_info(
+                TimeInterval::new_unchecked(time, time + 1),
+                TileInformation {
+                    global_tile_position: [-1, position].into(),
+                    tile_size_in_pixels: [2, 2].into(),
+                    global_geo_transform: TestDefault::test_default(),
+                },
+                Grid2D::new([2, 2].into(), data.to_vec(), Some(-1.))
+                    .unwrap()
+                    .into(),
+            )
+        };
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![
+                    tile(0, 0, &before[..4]),
+                    tile(0, 1, &before[4..]),
+                    tile(1, 0, &after[..4]),
+                    tile(1, 1, &after[4..]),
+                ],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::F64,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::continuous(
+                        "NDVI".to_string(),
+                        Some("index".to_string()),
+                    ),
+                    no_data_value: Some(-1.),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn detect_changes(
+        params: ChangeDetectionParams,
+        raster: Box<dyn RasterOperator>,
+    ) -> MultiPolygonCollection {
+        let processor = ChangeDetection {
+            params,
+            sources: SingleRasterSource { raster },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+        ))
+        .await
+        .unwrap()
+        .query_processor()
+        .unwrap()
+        .multi_polygon()
+        .unwrap();
+
+        let collections: Vec<MultiPolygonCollection> = processor
+            .vector_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new_unchecked((0., 0.).into(), (4., 2.).into()),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
+                },
+                &MockQueryContext::new(1.into()),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(collections.len(), 1);
+        collections.into_iter().next().unwrap()
+    }
+
+    fn params(threshold: f64) -> ChangeDetectionParams {
+        ChangeDetectionParams {
+            before: TimeInterval::new_unchecked(0, 1),
+            after: TimeInterval::new_unchecked(1, 2),
+            threshold,
+            difference: ChangeDifference::Absolute,
+            direction: ChangeDirection::Any,
+            min_pixels: 0,
+        }
+    }
+
+    #[test]
+    fn serialization() {
+        let params: ChangeDetectionParams = serde_json::from_value(serde_json::json!({
+            "before": {"start": 0, "end": 1},
+            "after": {"start": 1, "end": 2},
+            "threshold": 0.2,
+        }))
+        .unwrap();
+
+        assert_eq!(params, self::params(0.2));
+    }
+
+    #[tokio::test]
+    async fn it_merges_changes_across_tiles() {
+        // the pixels in the bottom row lose vegetation across both tiles,
+        // the upper right pixel of the second tile gains some
+        let changes = detect_changes(
+            params(0.2),
+            make_raster(
+                [0.5, 0.5, 0.8, 0.8, 0.5, 0.5, 0.8, 0.5],
+                [0.5, 0.6, 0.2, 0.4, 0.5, 0.9, 0.3, 0.5],
+            ),
+        )
+        .await;
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            changes.time_intervals(),
+            &[TimeInterval::new_unchecked(0, 2); 2]
+        );
+
+        let areas: Vec<f64> = changes
+            .data(CHANGE_AREA_COLUMN)
+            .unwrap()
+            .float_options_iter()
+            .map(Option::unwrap)
+            .collect();
+        assert_eq!(areas, vec![1., 3.]);
+
+        let magnitudes: Vec<f64> = changes
+            .data(CHANGE_MAGNITUDE_COLUMN)
+            .unwrap()
+            .float_options_iter()
+            .map(Option::unwrap)
+            .collect();
+        float_cmp::assert_approx_eq!(f64, magnitudes[0], 0.4, epsilon = 1e-10);
+        float_cmp::assert_approx_eq!(f64, magnitudes[1], -0.5, epsilon = 1e-10);
+
+        let geometries: Vec<MultiPolygon> = changes.geometries().map(MultiPolygon::from).collect();
+        let geo::MultiPolygon(decrease) = (&geometries[1]).into();
+        assert_eq!(decrease.len(), 1);
+        assert!((decrease[0].unsigned_area() - 3.).abs() < 1e-10);
+    }
+
+    #[tokio::test]
+    async fn it_filters_changes() {
+        let raster = || {
+            make_raster(
+                [0.5, 0.5, 0.8, 0.8, 0.5, 0.5, 0.8, 0.5],
+                [0.5, 0.6, 0.2, 0.4, 0.5, 0.9, 0.3, 0.5],
+            )
+        };
+
+        let increases = detect_changes(
+            ChangeDetectionParams {
+                direction: ChangeDirection::Increase,
+                ..params(0.2)
+            },
+            raster(),
+        )
+        .await;
+        assert_eq!(increases.len(), 1);
+
+        let large = detect_changes(
+            ChangeDetectionParams {
+                min_pixels: 2,
+                ..params(0.2)
+            },
+            raster(),
+        )
+        .await;
+        assert_eq!(large.len(), 1);
+
+        let relative = detect_changes(
+            ChangeDetectionParams {
+                difference: ChangeDifference::Relative,
+                ..params(0.78)
+            },
+            raster(),
+        )
+        .await;
+        assert_eq!(relative.len(), 1);
+        assert_eq!(
+            relative
+                .data(CHANGE_AREA_COLUMN)
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(1.)]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_rejects_overlapping_periods() {
+        let result = ChangeDetection {
+            params: ChangeDetectionParams {
+                after: TimeInterval::new_unchecked(0, 2),
+                ..params(0.2)
+            },
+            sources: SingleRasterSource {
+                raster: make_raster([0.; 8], [0.; 8]),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -15,9 +15,9 @@ use snafu::ensure;
 
 use crate::adapters::FeatureCollectionStreamExt;
 use crate::engine::{
-    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
-    SingleVectorSource, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
-    VectorResultDescriptor,
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, QueryContext,
+    QueryProcessor, ResultExtent, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
 };
 use crate::error::{self, Error};
 use crate::processing::circle_merging_quadtree::aggregates::MeanAggregator;
@@ -146,6 +146,19 @@ impl VectorOperator for VisualPointClustering {
                 data_type: VectorDataType::MultiPoint,
                 spatial_reference: vector_source.result_descriptor().spatial_reference,
                 columns: new_columns,
+                // the aggregates keep the meaning and unit of their input columns
+                column_metadata: self
+                    .params
+                    .column_aggregates
+                    .iter()
+                    .filter_map(|(column, aggregate)| {
+                        vector_source
+                            .result_descriptor()
+                            .column_metadata
+                            .get(column)
+                            .map(|metadata| (aggregate.column_name.clone(), metadata.clone()))
+                    })
+                    .collect(),
             },
             vector_source,
             radius_model,
@@ -200,6 +213,14 @@ impl InitializedVectorOperator for InitializedVisualPointClustering {
     fn result_descriptor(&self) -> &VectorResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.vector_source.extent()
+    }
 }
 
 pub struct VisualPointClusteringProcessor {
@@ -290,10 +311,6 @@ impl VisualPointClusteringProcessor {
         aggregate_type: AttributeAggregateType,
     ) -> AttributeAggregate {
         match (feature_data, aggregate_type) {
-            (
-                FeatureDataValue::Category(value) | FeatureDataValue::NullableCategory(Some(value)),
-                AttributeAggregateType::MeanNumber,
-            ) => AttributeAggregate::MeanNumber(MeanAggregator::from_value(f64::from(value))),
             (
                 FeatureDataValue::Float(value) | FeatureDataValue::NullableFloat(Some(value)),
                 AttributeAggregateType::MeanNumber,
@@ -303,7 +320,10 @@ impl VisualPointClusteringProcessor {
                 AttributeAggregateType::MeanNumber,
             ) => AttributeAggregate::MeanNumber(MeanAggregator::from_value(value as f64)),
             (
-                FeatureDataValue::Text(value) | FeatureDataValue::NullableText(Some(value)),
+                FeatureDataValue::Text(value)
+                | FeatureDataValue::NullableText(Some(value))
+                | FeatureDataValue::Category(value)
+                | FeatureDataValue::NullableCategory(Some(value)),
                 AttributeAggregateType::StringSample,
             ) => AttributeAggregate::StringSample(StringSampler::from_value(value)),
             _ => AttributeAggregate::Null,
@@ -479,6 +499,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+            spatial_filter: None,
         };
 
         let query = query_processor.query(qrect, &query_context).await.unwrap();
@@ -560,6 +581,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+            spatial_filter: None,
         };
 
         let query = query_processor.query(qrect, &query_context).await.unwrap();
@@ -642,6 +664,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+            spatial_filter: None,
         };
 
         let query = query_processor.query(qrect, &query_context).await.unwrap();
@@ -732,6 +755,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+            spatial_filter: None,
         };
 
         let query = query_processor.query(qrect, &query_context).await.unwrap();
@@ -0,0 +1,551 @@
+use crate::engine::{
+    BoxRasterQueryProcessor, Cacheability, ExecutionContext, InitializedRasterOperator, Operator,
+    OperatorDatasets, QueryContext, QueryProcessor, RasterOperator, RasterResultDescriptor,
+    ResultExtent, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::math::percentile_of_sorted;
+use crate::util::pixel_time_series::{tile_from_pixels, PixelTimeSeries};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{
+    RasterQueryRectangle, SpatialPartition2D, TimeInterval, TimeStep,
+};
+use geoengine_datatypes::raster::{
+    GridSize, RasterDataType, RasterTile2D, TileInformation, TilingSpecification,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// The output of the composite, pixels without a clear observation are `NaN`
+type PixelOut = f32;
+const OUT_NO_DATA_VALUE: PixelOut = PixelOut::NAN;
+
+/// The `CloudFreeComposite` operator builds one composite per time window from the scenes of
+/// optical imagery, e.g., monthly composites of a Sentinel-2 band.
+///
+/// A pixel of a scene is a candidate if the `mask` raster marks it as clear. Among the
+/// candidates, the `criterion` chooses the value of the composite. Pixels without any clear
+/// observation in a window are no data.
+pub type CloudFreeComposite = Operator<CloudFreeCompositeParams, CloudFreeCompositeSources>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudFreeCompositeParams {
+    /// The length of the composite windows, starting at the beginning of the query
+    pub window: TimeStep,
+    pub criterion: CompositeCriterion,
+    pub clear: ClearMask,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudFreeCompositeSources {
+    /// The band that is composited
+    pub raster: Box<dyn RasterOperator>,
+    /// The quality or cloud mask of the scenes
+    pub mask: Box<dyn RasterOperator>,
+    /// The score of the `maxScore` criterion, e.g., the NDVI
+    #[serde(default)]
+    pub score: Option<Box<dyn RasterOperator>>,
+}
+
+impl OperatorDatasets for CloudFreeCompositeSources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.raster.datasets_collect(datasets);
+        self.mask.datasets_collect(datasets);
+
+        if let Some(score) = &self.score {
+            score.datasets_collect(datasets);
+        }
+    }
+}
+
+/// Chooses the value of a pixel from its clear observations within a window
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CompositeCriterion {
+    /// The observation with the highest value of the `score` raster, e.g., the max NDVI
+    MaxScore,
+    Median,
+    /// The clear observation of the latest scene
+    MostRecent,
+}
+
+/// Determines the clear pixels from the values of the mask
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ClearMask {
+    /// Classifications, e.g., the Sentinel-2 scene classification, whose listed classes are clear
+    Values { values: Vec<f64> },
+    /// Quality bit flags, e.g., the Landsat `QA_PIXEL` band, that are clear if none of the bits is set
+    Bits { bits: u64 },
+}
+
+impl ClearMask {
+    fn is_clear(&self, value: f64) -> bool {
+        match self {
+            ClearMask::Values { values } => values.contains(&value),
+            ClearMask::Bits { bits } => value >= 0. && (value as u64) & bits == 0,
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for CloudFreeComposite {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        ensure!(
+            (self.params.criterion == CompositeCriterion::MaxScore) == self.sources.score.is_some(),
+            error::InvalidOperatorSpec {
+                reason:
+                    "A score raster is required for and only allowed with the `maxScore` criterion"
+                        .to_string(),
+            }
+        );
+
+        ensure!(
+            self.params.window.step > 0,
+            error::InvalidOperatorSpec {
+                reason: "The composite window must not be empty".to_string(),
+            }
+        );
+
+        let raster = self.sources.raster.initialize(context).await?;
+        let mask = self.sources.mask.initialize(context).await?;
+        let score = match self.sources.score {
+            Some(score) => Some(score.initialize(context).await?),
+            None => None,
+        };
+
+        let spatial_reference = raster.result_descriptor().spatial_reference;
+
+        for other in std::iter::once(&mask).chain(&score) {
+            ensure!(
+                spatial_reference == other.result_descriptor().spatial_reference,
+                error::InvalidSpatialReference {
+                    expected: spatial_reference,
+                    found: other.result_descriptor().spatial_reference,
+                }
+            );
+        }
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: RasterDataType::F32,
+            spatial_reference,
+            measurement: raster.result_descriptor().measurement.clone(),
+            no_data_value: Some(f64::from(OUT_NO_DATA_VALUE)),
+            dimensions: vec![],
+        };
+
+        Ok(InitializedCloudFreeComposite {
+            result_descriptor,
+            raster,
+            mask,
+            score,
+            params: self.params,
+            tiling_specification: context.tiling_specification(),
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedCloudFreeComposite {
+    result_descriptor: RasterResultDescriptor,
+    raster: Box<dyn InitializedRasterOperator>,
+    mask: Box<dyn InitializedRasterOperator>,
+    score: Option<Box<dyn InitializedRasterOperator>>,
+    params: CloudFreeCompositeParams,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedCloudFreeComposite {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        let cacheability = self.raster.cacheability().combine(self.mask.cacheability());
+
+        self.score.as_ref().map_or(cacheability, |score| {
+            cacheability.combine(score.cacheability())
+        })
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.raster.extent().intersection(self.mask.extent())
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let score = match &self.score {
+            Some(score) => Some(score.query_processor()?.into_f64()),
+            None => None,
+        };
+
+        Ok(TypedRasterQueryProcessor::F32(
+            CloudFreeCompositeProcessor {
+                raster: self.raster.query_processor()?.into_f64(),
+                mask: self.mask.query_processor()?.into_f64(),
+                score,
+                params: self.params.clone(),
+                tiling_specification: self.tiling_specification,
+            }
+            .boxed(),
+        ))
+    }
+}
+
+pub struct CloudFreeCompositeProcessor {
+    raster: BoxRasterQueryProcessor<f64>,
+    mask: BoxRasterQueryProcessor<f64>,
+    score: Option<BoxRasterQueryProcessor<f64>>,
+    params: CloudFreeCompositeParams,
+    tiling_specification: TilingSpecification,
+}
+
+impl CloudFreeCompositeProcessor {
+    /// Splits the query's time interval into consecutive windows
+    fn windows(&self, time_interval: TimeInterval) -> Result<Vec<TimeInterval>> {
+        let mut windows = Vec::new();
+        let mut start = time_interval.start();
+
+        loop {
+            let end = (start + self.params.window)?;
+            windows.push(TimeInterval::new(start, end)?);
+
+            if end >= time_interval.end() {
+                return Ok(windows);
+            }
+
+            start = end;
+        }
+    }
+
+    async fn composite_tile(
+        &self,
+        tile_info: TileInformation,
+        window: TimeInterval,
+        query: RasterQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<RasterTile2D<PixelOut>> {
+        let window_query = RasterQueryRectangle {
+            time_interval: window,
+            ..query
+        };
+
+        let rasters =
+            PixelTimeSeries::query(self.raster.as_ref(), tile_info, window_query, ctx).await?;
+        let masks =
+            PixelTimeSeries::query(self.mask.as_ref(), tile_info, window_query, ctx).await?;
+        let scores = match &self.score {
+            Some(score) => {
+                Some(PixelTimeSeries::query(score.as_ref(), tile_info, window_query, ctx).await?)
+            }
+            None => None,
+        };
+
+        ensure!(
+            masks.is_aligned_with(&rasters)
+                && scores
+                    .as_ref()
+                    .map_or(true, |scores| scores.is_aligned_with(&rasters)),
+            error::CompositeScenesNotAligned
+        );
+
+        let params = self.params.clone();
+
+        Ok(
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                composite(
+                    &params,
+                    tile_info,
+                    window,
+                    &rasters,
+                    &masks,
+                    scores.as_ref(),
+                )
+            })
+            .await?,
+        )
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for CloudFreeCompositeProcessor {
+    type Output = RasterTile2D<PixelOut>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let tiling_strategy = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+        let tiles = self
+            .windows(query.time_interval)?
+            .into_iter()
+            .flat_map(move |window| {
+                tiling_strategy
+                    .tile_information_iterator(query.spatial_bounds)
+                    .map(move |tile_info| (window, tile_info))
+            });
+
+        let stream = stream::iter(tiles)
+            .then(move |(window, tile_info)| self.composite_tile(tile_info, window, query, ctx));
+
+        Ok(stream.boxed())
+    }
+}
+
+/// The scenes and their values where a pixel is clear
+fn clear_observations<'t>(
+    rasters: &'t PixelTimeSeries,
+    masks: &'t PixelTimeSeries,
+    clear: &'t ClearMask,
+    pixel: usize,
+) -> impl DoubleEndedIterator<Item = (usize, f64)> + 't {
+    let masks = masks.pixel(pixel);
+
+    rasters
+        .observations(pixel)
+        .filter(move |&(scene, _)| !masks[scene].is_nan() && clear.is_clear(masks[scene]))
+}
+
+/// Composites the scenes of a tile within a window
+fn composite(
+    params: &CloudFreeCompositeParams,
+    tile_info: TileInformation,
+    window: TimeInterval,
+    rasters: &PixelTimeSeries,
+    masks: &PixelTimeSeries,
+    scores: Option<&PixelTimeSeries>,
+) -> RasterTile2D<PixelOut> {
+    let mut values = Vec::with_capacity(rasters.time_steps().len());
+    let data: Vec<PixelOut> = (0..tile_info.tile_size_in_pixels.number_of_elements())
+        .map(|pixel| {
+            // the most recent scene comes last
+            let mut observations = clear_observations(rasters, masks, &params.clear, pixel);
+
+            let value = match (params.criterion, scores) {
+                (CompositeCriterion::MaxScore, Some(scores)) => {
+                    let scores = scores.pixel(pixel);
+                    observations
+                        .filter(|&(scene, _)| !scores[scene].is_nan())
+                        .map(|(scene, value)| (scores[scene], value))
+                        .fold(
+                            None,
+                            |best: Option<(f64, f64)>, (score, value)| match best {
+                                Some((best_score, _)) if best_score >= score => best,
+                                _ => Some((score, value)),
+                            },
+                        )
+                        .map(|(_, value)| value)
+                }
+                (CompositeCriterion::MaxScore, None) => None,
+                (CompositeCriterion::Median, _) => {
+                    values.clear();
+                    values.extend(observations.map(|(_, value)| value));
+                    values.sort_unstable_by(|a, b| {
+                        a.partial_cmp(b).expect("`NaN` values are filtered")
+                    });
+                    (!values.is_empty()).then(|| percentile_of_sorted(&values, 50.))
+                }
+                (CompositeCriterion::MostRecent, _) => {
+                    observations.next_back().map(|(_, value)| value)
+                }
+            };
+
+            value.map_or(OUT_NO_DATA_VALUE, |value| value as PixelOut)
+        })
+        .collect();
+
+    tile_from_pixels(window, tile_info, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{Measurement, SpatialResolution, TimeGranularity};
+    use geoengine_datatypes::raster::Grid2D;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn make_raster(scenes: Vec<(TimeInterval, Vec<u8>)>) -> Box<dyn RasterOperator> {
+        let tile_information = TileInformation {
+            global_tile_position: [-1, 0].into(),
+            tile_size_in_pixels: [2, 2].into(),
+            global_geo_transform: TestDefault::test_default(),
+        };
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: scenes
+                    .into_iter()
+                    .map(|(time, data)| {
+                        RasterTile2D::new_with_tile_info(
+                            time,
+                            tile_information,
+                            Grid2D::new([2, 2].into(), data, Some(255)).unwrap().into(),
+                        )
+                    })
+                    .collect(),
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(255.),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed()
+    }
+
+    fn scenes(data: [Vec<u8>; 3]) -> Vec<(TimeInterval, Vec<u8>)> {
+        let [a, b, c] = data;
+        vec![
+            (TimeInterval::new_unchecked(0, 1), a),
+            (TimeInterval::new_unchecked(1, 2), b),
+            (TimeInterval::new_unchecked(2, 3), c),
+        ]
+    }
+
+    /// The mask is `0` for clear pixels and `1` for clouds
+    fn sources(score: bool) -> CloudFreeCompositeSources {
+        CloudFreeCompositeSources {
+            raster: make_raster(scenes([
+                vec![10, 20, 30, 255],
+                vec![11, 21, 31, 41],
+                vec![12, 22, 32, 42],
+            ])),
+            mask: make_raster(scenes([
+                vec![0, 0, 1, 0],
+                vec![0, 1, 1, 1],
+                vec![0, 0, 1, 1],
+            ])),
+            score: score.then(|| {
+                make_raster(scenes([
+                    vec![5, 9, 0, 0],
+                    vec![7, 0, 0, 0],
+                    vec![6, 1, 0, 0],
+                ]))
+            }),
+        }
+    }
+
+    async fn composite(
+        criterion: CompositeCriterion,
+        sources: CloudFreeCompositeSources,
+    ) -> Result<Vec<RasterTile2D<f32>>> {
+        let operator = CloudFreeComposite {
+            params: CloudFreeCompositeParams {
+                window: TimeStep {
+                    granularity: TimeGranularity::Millis,
+                    step: 3,
+                },
+                criterion,
+                clear: ClearMask::Values { values: vec![0.] },
+            },
+            sources,
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+        ))
+        .await?;
+
+        let processor = operator.query_processor()?.get_f32().unwrap();
+
+        let ctx = MockQueryContext::new(1.into());
+        processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 2.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval: TimeInterval::new_unchecked(0, 3),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
+                },
+                &ctx,
+            )
+            .await?
+            .try_collect()
+            .await
+    }
+
+    fn data(tiles: &[RasterTile2D<f32>]) -> Vec<f32> {
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].time, TimeInterval::new_unchecked(0, 3));
+        tiles[0].grid_array.clone().into_materialized_grid().data
+    }
+
+    #[tokio::test]
+    async fn it_composites_the_most_recent_clear_pixel() {
+        let tiles = composite(CompositeCriterion::MostRecent, sources(false))
+            .await
+            .unwrap();
+        let data = data(&tiles);
+
+        assert_eq!(data[..2], [12., 22.]);
+        assert!(data[2].is_nan());
+        assert!(data[3].is_nan());
+    }
+
+    #[tokio::test]
+    async fn it_composites_the_median() {
+        let tiles = composite(CompositeCriterion::Median, sources(false))
+            .await
+            .unwrap();
+        let data = data(&tiles);
+
+        assert_eq!(data[..2], [11., 21.]);
+        assert!(data[2].is_nan());
+        assert!(data[3].is_nan());
+    }
+
+    #[tokio::test]
+    async fn it_composites_the_max_score() {
+        let tiles = composite(CompositeCriterion::MaxScore, sources(true))
+            .await
+            .unwrap();
+        let data = data(&tiles);
+
+        assert_eq!(data[..2], [11., 20.]);
+        assert!(data[2].is_nan());
+        assert!(data[3].is_nan());
+    }
+
+    #[tokio::test]
+    async fn it_requires_a_score_for_max_score() {
+        assert!(composite(CompositeCriterion::MaxScore, sources(false))
+            .await
+            .is_err());
+        assert!(composite(CompositeCriterion::Median, sources(true))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn it_checks_quality_bits() {
+        let clear = ClearMask::Bits { bits: 0b1010 };
+
+        assert!(clear.is_clear(0.));
+        assert!(clear.is_clear(5.));
+        assert!(!clear.is_clear(2.));
+        assert!(!clear.is_clear(8.));
+    }
+}
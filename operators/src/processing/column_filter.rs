@@ -0,0 +1,649 @@
+use crate::adapters::FeatureCollectionChunkMerger;
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, QueryContext,
+    QueryProcessor, ResultExtent, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error::Error;
+use crate::util::input::StringOrNumber;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, DataRef, FeatureDataRef, FeatureDataType, Geometry, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// The column filter keeps all features whose attributes satisfy the `predicate`.
+///
+/// In contrast to the `ColumnRangeFilter`, it supports set membership, text patterns and
+/// null checks on several columns that can be combined with `and` and `or`.
+pub type ColumnFilter = Operator<ColumnFilterParams, SingleVectorSource>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnFilterParams {
+    pub predicate: ColumnPredicate,
+}
+
+/// A predicate on the attributes of a feature.
+///
+/// Null values never satisfy `in`, `startsWith`, `endsWith` or `regex`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ColumnPredicate {
+    /// All predicates must hold, an empty list is always true
+    And {
+        predicates: Vec<ColumnPredicate>,
+    },
+    /// At least one predicate must hold, an empty list is always false
+    Or {
+        predicates: Vec<ColumnPredicate>,
+    },
+    /// The value is one of `values`, the column must be a text, category, int or float column
+    In {
+        column: String,
+        values: Vec<StringOrNumber>,
+    },
+    /// The text starts with `prefix`
+    StartsWith {
+        column: String,
+        prefix: String,
+    },
+    /// The text ends with `suffix`
+    EndsWith {
+        column: String,
+        suffix: String,
+    },
+    /// The text contains a match of the regular expression `pattern`
+    Regex {
+        column: String,
+        pattern: String,
+    },
+    IsNull {
+        column: String,
+    },
+    IsNotNull {
+        column: String,
+    },
+}
+
+impl ColumnPredicate {
+    /// Checks the predicate against the `columns` and prepares it for the evaluation
+    pub(super) fn compile(
+        &self,
+        columns: &HashMap<String, FeatureDataType>,
+    ) -> Result<CompiledPredicate> {
+        let column_type = |column: &String| {
+            columns
+                .get(column)
+                .copied()
+                .ok_or_else(|| Error::ColumnDoesNotExist {
+                    column: column.clone(),
+                })
+        };
+
+        Ok(match self {
+            ColumnPredicate::And { predicates } => CompiledPredicate::And(
+                predicates
+                    .iter()
+                    .map(|predicate| predicate.compile(columns))
+                    .collect::<Result<_>>()?,
+            ),
+            ColumnPredicate::Or { predicates } => CompiledPredicate::Or(
+                predicates
+                    .iter()
+                    .map(|predicate| predicate.compile(columns))
+                    .collect::<Result<_>>()?,
+            ),
+            ColumnPredicate::In { column, values } => match column_type(column)? {
+                FeatureDataType::Text | FeatureDataType::Category => CompiledPredicate::InText {
+                    column: column.clone(),
+                    values: values.iter().map(String::try_from).collect::<Result<_>>()?,
+                },
+                FeatureDataType::Int => CompiledPredicate::InInt {
+                    column: column.clone(),
+                    values: values.iter().map(i64::try_from).collect::<Result<_>>()?,
+                },
+                FeatureDataType::Float => CompiledPredicate::InFloat {
+                    column: column.clone(),
+                    values: values.iter().map(f64::try_from).collect::<Result<_>>()?,
+                },
+                data_type => {
+                    return Err(Error::InvalidOperatorSpec {
+                        reason: format!(
+                            "Column '{}' of type {:?} does not support `in` filters.",
+                            column, data_type
+                        ),
+                    })
+                }
+            },
+            ColumnPredicate::StartsWith { column, prefix } => CompiledPredicate::Text {
+                column: checked_text_column(column, column_type(column)?)?,
+                matcher: TextMatcher::Prefix(prefix.clone()),
+            },
+            ColumnPredicate::EndsWith { column, suffix } => CompiledPredicate::Text {
+                column: checked_text_column(column, column_type(column)?)?,
+                matcher: TextMatcher::Suffix(suffix.clone()),
+            },
+            ColumnPredicate::Regex { column, pattern } => CompiledPredicate::Text {
+                column: checked_text_column(column, column_type(column)?)?,
+                matcher: TextMatcher::Regex(Regex::new(pattern).map_err(|e| {
+                    Error::InvalidOperatorSpec {
+                        reason: format!("Invalid regular expression '{}': {}", pattern, e),
+                    }
+                })?),
+            },
+            ColumnPredicate::IsNull { column } => CompiledPredicate::IsNull {
+                column: column_type(column).map(|_| column.clone())?,
+                null: true,
+            },
+            ColumnPredicate::IsNotNull { column } => CompiledPredicate::IsNull {
+                column: column_type(column).map(|_| column.clone())?,
+                null: false,
+            },
+        })
+    }
+}
+
+fn checked_text_column(column: &str, data_type: FeatureDataType) -> Result<String> {
+    match data_type {
+        FeatureDataType::Text | FeatureDataType::Category => Ok(column.to_string()),
+        _ => Err(Error::InvalidOperatorSpec {
+            reason: format!(
+                "Column '{}' of type {:?} does not support text filters.",
+                column, data_type
+            ),
+        }),
+    }
+}
+
+/// A `ColumnPredicate` that was checked against the columns of the source
+#[derive(Debug, Clone)]
+pub(super) enum CompiledPredicate {
+    And(Vec<CompiledPredicate>),
+    Or(Vec<CompiledPredicate>),
+    InText {
+        column: String,
+        values: HashSet<String>,
+    },
+    InInt {
+        column: String,
+        values: HashSet<i64>,
+    },
+    InFloat {
+        column: String,
+        values: Vec<f64>,
+    },
+    Text {
+        column: String,
+        matcher: TextMatcher,
+    },
+    IsNull {
+        column: String,
+        null: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum TextMatcher {
+    Prefix(String),
+    Suffix(String),
+    Regex(Regex),
+}
+
+impl TextMatcher {
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            TextMatcher::Prefix(prefix) => value.starts_with(prefix.as_str()),
+            TextMatcher::Suffix(suffix) => value.ends_with(suffix.as_str()),
+            TextMatcher::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+impl CompiledPredicate {
+    /// Computes for each feature of the `collection` whether it satisfies the predicate
+    pub(super) fn evaluate<C: FeatureCollectionInfos>(&self, collection: &C) -> Result<Vec<bool>> {
+        let len = collection.len();
+
+        match self {
+            CompiledPredicate::And(predicates) => {
+                let mut mask = vec![true; len];
+                for predicate in predicates {
+                    for (m, v) in mask.iter_mut().zip(predicate.evaluate(collection)?) {
+                        *m &= v;
+                    }
+                }
+                Ok(mask)
+            }
+            CompiledPredicate::Or(predicates) => {
+                let mut mask = vec![false; len];
+                for predicate in predicates {
+                    for (m, v) in mask.iter_mut().zip(predicate.evaluate(collection)?) {
+                        *m |= v;
+                    }
+                }
+                Ok(mask)
+            }
+            CompiledPredicate::InText { column, values } => {
+                text_mask(column, &collection.data(column)?, len, |v| {
+                    values.contains(v)
+                })
+            }
+            CompiledPredicate::InInt { column, values } => match collection.data(column)? {
+                FeatureDataRef::Int(data) => Ok(data
+                    .as_ref()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| data.is_valid(i) && values.contains(v))
+                    .collect()),
+                _ => Err(invalid_column_type(column, "int")),
+            },
+            CompiledPredicate::InFloat { column, values } => match collection.data(column)? {
+                data @ FeatureDataRef::Float(_) => Ok(data
+                    .float_options_iter()
+                    .map(|v| v.map_or(false, |v| values.contains(&v)))
+                    .collect()),
+                _ => Err(invalid_column_type(column, "float")),
+            },
+            CompiledPredicate::Text { column, matcher } => {
+                text_mask(column, &collection.data(column)?, len, |v| {
+                    matcher.matches(v)
+                })
+            }
+            CompiledPredicate::IsNull { column, null } => Ok(collection
+                .data(column)?
+                .nulls()
+                .into_iter()
+                .map(|is_null| is_null == *null)
+                .collect()),
+        }
+    }
+}
+
+/// Applies `matches` to all non-null values of a text or category column
+fn text_mask<F>(column: &str, data: &FeatureDataRef, len: usize, matches: F) -> Result<Vec<bool>>
+where
+    F: Fn(&str) -> bool,
+{
+    match data {
+        FeatureDataRef::Text(data) => (0..len)
+            .map(|i| Ok(data.text_at(i)?.map_or(false, &matches)))
+            .collect(),
+        FeatureDataRef::Category(data) => {
+            // evaluate each distinct category only once
+            let categories = data.categories();
+            let category_matches: Vec<bool> = (0..categories.len())
+                .map(|i| matches(categories.value(i)))
+                .collect();
+
+            Ok(data
+                .as_ref()
+                .iter()
+                .enumerate()
+                .map(|(i, key)| data.is_valid(i) && category_matches[*key as usize])
+                .collect())
+        }
+        _ => Err(invalid_column_type(column, "text")),
+    }
+}
+
+fn invalid_column_type(column: &str, expected: &str) -> Error {
+    Error::InvalidOperatorSpec {
+        reason: format!("Column '{}' is no {} column.", column, expected),
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for ColumnFilter {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+
+        let predicate = self
+            .params
+            .predicate
+            .compile(&vector_source.result_descriptor().columns)?;
+
+        let initialized_operator = InitializedColumnFilter {
+            result_descriptor: vector_source.result_descriptor().clone(),
+            vector_source,
+            predicate: Arc::new(predicate),
+        };
+
+        Ok(initialized_operator.boxed())
+    }
+}
+
+pub struct InitializedColumnFilter {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    predicate: Arc<CompiledPredicate>,
+}
+
+impl InitializedVectorOperator for InitializedColumnFilter {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => ColumnFilterProcessor::new(source, self.predicate.clone()).boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.vector_source.extent()
+    }
+}
+
+pub struct ColumnFilterProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    predicate: Arc<CompiledPredicate>,
+}
+
+impl<G> ColumnFilterProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send,
+{
+    fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        predicate: Arc<CompiledPredicate>,
+    ) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+            predicate,
+        }
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for ColumnFilterProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let filter_stream = self.source.query(query, ctx).await?.map(move |collection| {
+            let collection = collection?;
+
+            let mask = self.predicate.evaluate(&collection)?;
+
+            collection.filter(mask).map_err(Into::into)
+        });
+
+        let merged_chunks_stream =
+            FeatureCollectionChunkMerger::new(filter_stream.fuse(), ctx.chunk_byte_size().into());
+
+        Ok(merged_chunks_stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn collection() -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1), (2.0, 2.1), (3.0, 3.1)]).unwrap(),
+            vec![TimeInterval::new(0, 1).unwrap(); 4],
+            [
+                (
+                    "name".to_string(),
+                    FeatureData::NullableText(vec![
+                        Some("Marburg".to_string()),
+                        Some("Gießen".to_string()),
+                        None,
+                        Some("Marbach".to_string()),
+                    ]),
+                ),
+                (
+                    "landcover".to_string(),
+                    FeatureData::NullableCategory(vec![
+                        Some("forest".to_string()),
+                        Some("water".to_string()),
+                        None,
+                        Some("field".to_string()),
+                    ]),
+                ),
+                ("id".to_string(), FeatureData::Int(vec![1, 2, 3, 4])),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap()
+    }
+
+    async fn filter(predicate: ColumnPredicate) -> Result<Vec<MultiPointCollection>> {
+        let source = MockFeatureCollectionSource::single(collection()).boxed();
+
+        let filter = ColumnFilter {
+            params: ColumnFilterParams { predicate },
+            sources: source.into(),
+        }
+        .boxed();
+
+        let initialized = filter
+            .initialize(&MockExecutionContext::test_default())
+            .await?;
+
+        let processor = initialized.query_processor()?.multi_point().unwrap();
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
+        };
+
+        let ctx = MockQueryContext::test_default();
+
+        let stream = processor.query(query_rectangle, &ctx).await?;
+
+        stream.collect::<Vec<_>>().await.into_iter().collect()
+    }
+
+    #[test]
+    fn serde() {
+        let params = ColumnFilterParams {
+            predicate: ColumnPredicate::Or {
+                predicates: vec![
+                    ColumnPredicate::In {
+                        column: "landcover".to_string(),
+                        values: vec!["forest".into(), "water".into()],
+                    },
+                    ColumnPredicate::IsNull {
+                        column: "name".to_string(),
+                    },
+                ],
+            },
+        };
+
+        let json = serde_json::json!({
+            "predicate": {
+                "type": "or",
+                "predicates": [{
+                    "type": "in",
+                    "column": "landcover",
+                    "values": ["forest", "water"]
+                }, {
+                    "type": "isNull",
+                    "column": "name"
+                }]
+            }
+        });
+
+        assert_eq!(serde_json::to_value(&params).unwrap(), json);
+        assert_eq!(
+            serde_json::from_value::<ColumnFilterParams>(json).unwrap(),
+            params
+        );
+    }
+
+    #[tokio::test]
+    async fn in_list() {
+        let result = filter(ColumnPredicate::In {
+            column: "landcover".to_string(),
+            values: vec!["forest".into(), "field".into()],
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0],
+            collection().filter(vec![true, false, false, true]).unwrap()
+        );
+
+        let result = filter(ColumnPredicate::In {
+            column: "id".to_string(),
+            values: vec![2_i64.into(), 3_i64.into()],
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result[0],
+            collection().filter(vec![false, true, true, false]).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn text_patterns() {
+        let result = filter(ColumnPredicate::StartsWith {
+            column: "name".to_string(),
+            prefix: "Mar".to_string(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result[0],
+            collection().filter(vec![true, false, false, true]).unwrap()
+        );
+
+        let result = filter(ColumnPredicate::EndsWith {
+            column: "landcover".to_string(),
+            suffix: "er".to_string(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result[0],
+            collection()
+                .filter(vec![false, true, false, false])
+                .unwrap()
+        );
+
+        let result = filter(ColumnPredicate::Regex {
+            column: "name".to_string(),
+            pattern: "^M.*g$".to_string(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result[0],
+            collection()
+                .filter(vec![true, false, false, false])
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn combined() {
+        let result = filter(ColumnPredicate::Or {
+            predicates: vec![
+                ColumnPredicate::IsNull {
+                    column: "landcover".to_string(),
+                },
+                ColumnPredicate::And {
+                    predicates: vec![
+                        ColumnPredicate::IsNotNull {
+                            column: "name".to_string(),
+                        },
+                        ColumnPredicate::In {
+                            column: "landcover".to_string(),
+                            values: vec!["water".into(), "field".into()],
+                        },
+                    ],
+                },
+            ],
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result[0],
+            collection().filter(vec![false, true, true, true]).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_predicates() {
+        assert!(matches!(
+            filter(ColumnPredicate::IsNull {
+                column: "foo".to_string(),
+            })
+            .await,
+            Err(Error::ColumnDoesNotExist { column }) if column == "foo"
+        ));
+
+        assert!(filter(ColumnPredicate::Regex {
+            column: "name".to_string(),
+            pattern: "(".to_string(),
+        })
+        .await
+        .is_err());
+
+        assert!(filter(ColumnPredicate::StartsWith {
+            column: "id".to_string(),
+            prefix: "1".to_string(),
+        })
+        .await
+        .is_err());
+
+        assert!(filter(ColumnPredicate::In {
+            column: "id".to_string(),
+            values: vec!["a".into()],
+        })
+        .await
+        .is_err());
+    }
+}
@@ -1,8 +1,8 @@
 use crate::engine::{
-    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, QueryProcessor,
-    TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, QueryContext,
+    QueryProcessor, ResultExtent, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorResultDescriptor,
 };
-use crate::error;
 use crate::util::input::StringOrNumberRange;
 use crate::util::Result;
 use crate::{adapters::FeatureCollectionChunkMerger, engine::SingleVectorSource};
@@ -66,6 +66,14 @@ impl InitializedVectorOperator for InitializedColumnRangeFilter {
     fn result_descriptor(&self) -> &VectorResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.vector_source.extent()
+    }
 }
 
 pub struct ColumnRangeFilterProcessor<G> {
@@ -117,7 +125,7 @@ where
             // TODO: do transformation work only once
             let ranges: Result<Vec<RangeInclusive<FeatureDataValue>>> =
                 match collection.column_type(&column_name)? {
-                    FeatureDataType::Text => ranges
+                    FeatureDataType::Text | FeatureDataType::Category => ranges
                         .iter()
                         .cloned()
                         .map(|range| range.into_string_range().map(Into::into))
@@ -142,10 +150,6 @@ where
                         .cloned()
                         .map(|range| range.into_int_range().map(Into::into))
                         .collect(),
-                    FeatureDataType::Category => Err(error::Error::InvalidType {
-                        expected: "text, float, int, bool or datetime".to_string(),
-                        found: "category".to_string(),
-                    }),
                 };
 
             collection
@@ -257,6 +261,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
 
         let ctx = MockQueryContext::new((2 * std::mem::size_of::<Coordinate2D>()).into());
@@ -272,4 +277,69 @@ mod tests {
             collection.filter(vec![false, true, true, false]).unwrap()
         );
     }
+
+    #[tokio::test]
+    async fn execute_category() {
+        let column_name = "foo";
+
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1), (2.0, 2.1), (3.0, 3.1)]).unwrap(),
+            vec![TimeInterval::new(0, 1).unwrap(); 4],
+            [(
+                column_name.to_string(),
+                FeatureData::NullableCategory(vec![
+                    Some("forest".to_string()),
+                    Some("water".to_string()),
+                    None,
+                    Some("field".to_string()),
+                ]),
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap();
+
+        let source = MockFeatureCollectionSource::single(collection.clone()).boxed();
+
+        let filter = ColumnRangeFilter {
+            params: ColumnRangeFilterParams {
+                column: column_name.to_string(),
+                ranges: vec![("field"..="forest").into()],
+                keep_nulls: true,
+            },
+            sources: source.into(),
+        }
+        .boxed();
+
+        let initialized = filter
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap();
+
+        let point_processor = match initialized.query_processor() {
+            Ok(TypedVectorQueryProcessor::MultiPoint(processor)) => processor,
+            _ => panic!(),
+        };
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
+        };
+
+        let ctx = MockQueryContext::test_default();
+
+        let stream = point_processor.query(query_rectangle, &ctx).await.unwrap();
+
+        let collections: Vec<MultiPointCollection> = stream.map(Result::unwrap).collect().await;
+
+        assert_eq!(collections.len(), 1);
+
+        assert_eq!(
+            collections[0],
+            collection.filter(vec![true, false, true, true]).unwrap()
+        );
+    }
 }
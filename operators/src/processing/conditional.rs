@@ -0,0 +1,568 @@
+use crate::engine::{
+    BoxRasterQueryProcessor, Cacheability, ExecutionContext, InitializedRasterOperator, Operator,
+    OperatorDatasets, QueryContext, QueryProcessor, RasterOperator, RasterResultDescriptor,
+    ResultExtent, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::input::float_option_with_nan;
+use crate::util::input::float_with_nan;
+use crate::util::stream_zip::StreamVectorZip;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{Measurement, RasterQueryRectangle, SpatialPartition2D};
+use geoengine_datatypes::raster::{
+    EmptyGrid2D, FromPrimitive, Grid2D, GridOrEmpty, GridOrEmpty2D, NoDataValue, Pixel,
+    RasterDataType, RasterTile2D,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// The `Conditional` operator chooses the value of each pixel from one of two alternatives,
+/// depending on a condition raster, i.e., `if condition then ifTrue else ifFalse`.
+///
+/// Each alternative is either a raster or a constant value. The condition holds for pixels
+/// that are neither zero, NaN nor no data. Pixels are no data if the condition is no data
+/// or if the chosen alternative is a raster with no data at this pixel.
+pub type Conditional = Operator<ConditionalParams, ConditionalSources>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionalParams {
+    /// The value where the condition holds, if there is no `ifTrue` raster
+    #[serde(default, with = "float_option_with_nan")]
+    pub true_value: Option<f64>,
+    /// The value where the condition does not hold, if there is no `ifFalse` raster
+    #[serde(default, with = "float_option_with_nan")]
+    pub false_value: Option<f64>,
+    pub output_type: RasterDataType,
+    #[serde(with = "float_with_nan")]
+    pub output_no_data_value: f64,
+    /// Defaults to the measurement of the first alternative that is a raster
+    pub output_measurement: Option<Measurement>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionalSources {
+    pub condition: Box<dyn RasterOperator>,
+    #[serde(default)]
+    pub if_true: Option<Box<dyn RasterOperator>>,
+    #[serde(default)]
+    pub if_false: Option<Box<dyn RasterOperator>>,
+}
+
+impl OperatorDatasets for ConditionalSources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.condition.datasets_collect(datasets);
+
+        for alternative in [&self.if_true, &self.if_false].into_iter().flatten() {
+            alternative.datasets_collect(datasets);
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for Conditional {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let output_type = self.params.output_type;
+
+        ensure!(
+            output_type.is_valid(self.params.output_no_data_value),
+            error::InvalidNoDataValueValueForOutputDataType
+        );
+
+        let condition = self.sources.condition.initialize(context).await?;
+        let if_true = InitializedAlternative::new(
+            "true",
+            self.sources.if_true,
+            self.params.true_value,
+            output_type,
+            context,
+        )
+        .await?;
+        let if_false = InitializedAlternative::new(
+            "false",
+            self.sources.if_false,
+            self.params.false_value,
+            output_type,
+            context,
+        )
+        .await?;
+
+        let spatial_reference = condition.result_descriptor().spatial_reference;
+
+        for other in [&if_true, &if_false]
+            .into_iter()
+            .filter_map(InitializedAlternative::raster)
+        {
+            ensure!(
+                spatial_reference == other.result_descriptor().spatial_reference,
+                error::InvalidSpatialReference {
+                    expected: spatial_reference,
+                    found: other.result_descriptor().spatial_reference,
+                }
+            );
+        }
+
+        let measurement = self.params.output_measurement.unwrap_or_else(|| {
+            [&if_true, &if_false]
+                .into_iter()
+                .find_map(InitializedAlternative::raster)
+                .map_or(Measurement::Unitless, |raster| {
+                    raster.result_descriptor().measurement.clone()
+                })
+        });
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: output_type,
+            spatial_reference,
+            measurement,
+            no_data_value: Some(self.params.output_no_data_value),
+            dimensions: vec![],
+        };
+
+        Ok(InitializedConditional {
+            result_descriptor,
+            condition,
+            if_true,
+            if_false,
+        }
+        .boxed())
+    }
+}
+
+/// An alternative of the `Conditional` operator, which is either a raster or a constant
+enum InitializedAlternative {
+    Raster(Box<dyn InitializedRasterOperator>),
+    Value(f64),
+}
+
+impl InitializedAlternative {
+    async fn new(
+        name: &str,
+        raster: Option<Box<dyn RasterOperator>>,
+        value: Option<f64>,
+        output_type: RasterDataType,
+        context: &dyn ExecutionContext,
+    ) -> Result<Self> {
+        match (raster, value) {
+            (Some(raster), None) => Ok(Self::Raster(raster.initialize(context).await?)),
+            (None, Some(value)) => {
+                ensure!(
+                    output_type.is_valid(value),
+                    error::InvalidOperatorSpec {
+                        reason: format!(
+                            "The {} value {} is not valid for the output type {:?}.",
+                            name, value, output_type
+                        ),
+                    }
+                );
+
+                Ok(Self::Value(value))
+            }
+            _ => Err(error::Error::InvalidOperatorSpec {
+                reason: format!(
+                    "Either a raster or a value is required if the condition is {}.",
+                    name
+                ),
+            }),
+        }
+    }
+
+    #[allow(clippy::borrowed_box)]
+    fn raster(&self) -> Option<&Box<dyn InitializedRasterOperator>> {
+        match self {
+            Self::Raster(raster) => Some(raster),
+            Self::Value(_) => None,
+        }
+    }
+
+    fn query_processor(&self) -> Result<Alternative> {
+        Ok(match self {
+            Self::Raster(raster) => Alternative::Raster(raster.query_processor()?.into_f64()),
+            Self::Value(value) => Alternative::Value(*value),
+        })
+    }
+}
+
+pub struct InitializedConditional {
+    result_descriptor: RasterResultDescriptor,
+    condition: Box<dyn InitializedRasterOperator>,
+    if_true: InitializedAlternative,
+    if_false: InitializedAlternative,
+}
+
+impl InitializedRasterOperator for InitializedConditional {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let condition = self.condition.query_processor()?.into_f64();
+        let if_true = self.if_true.query_processor()?;
+        let if_false = self.if_false.query_processor()?;
+
+        let no_data_value = self
+            .result_descriptor
+            .no_data_value
+            .expect("the output no data value is set during initialization");
+
+        Ok(call_generic_raster_processor!(
+            self.result_descriptor.data_type,
+            ConditionalQueryProcessor::new(condition, if_true, if_false, no_data_value).boxed()
+        ))
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::combine_all(
+            std::iter::once(&self.condition)
+                .chain(
+                    [&self.if_true, &self.if_false]
+                        .into_iter()
+                        .filter_map(InitializedAlternative::raster),
+                )
+                .map(|raster| raster.cacheability()),
+        )
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.condition.extent()
+    }
+}
+
+enum Alternative {
+    Raster(BoxRasterQueryProcessor<f64>),
+    Value(f64),
+}
+
+pub struct ConditionalQueryProcessor<T>
+where
+    T: Pixel,
+{
+    condition: BoxRasterQueryProcessor<f64>,
+    if_true: Alternative,
+    if_false: Alternative,
+    no_data_value: T,
+}
+
+impl<T> ConditionalQueryProcessor<T>
+where
+    T: Pixel,
+{
+    fn new(
+        condition: BoxRasterQueryProcessor<f64>,
+        if_true: Alternative,
+        if_false: Alternative,
+        no_data_value: f64,
+    ) -> Self {
+        Self {
+            condition,
+            if_true,
+            if_false,
+            no_data_value: T::from_(no_data_value),
+        }
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for ConditionalQueryProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        // the condition comes first, followed by the alternatives that are rasters
+        let mut streams = vec![self.condition.raster_query(query, ctx).await?];
+        for alternative in [&self.if_true, &self.if_false] {
+            if let Alternative::Raster(raster) = alternative {
+                streams.push(raster.raster_query(query, ctx).await?);
+            }
+        }
+
+        let no_data_value = self.no_data_value;
+
+        let stream = StreamVectorZip::new(streams).map(move |tiles| {
+            let mut tiles = tiles.into_iter().collect::<Result<Vec<_>>>()?.into_iter();
+
+            let condition = tiles.next().expect("the condition is always queried");
+            let mut next_tile = |alternative: &Alternative| match alternative {
+                Alternative::Raster(_) => {
+                    AlternativeTile::Grid(tiles.next().expect("rasters are queried").grid_array)
+                }
+                Alternative::Value(value) => AlternativeTile::Value(*value),
+            };
+            let if_true = next_tile(&self.if_true);
+            let if_false = next_tile(&self.if_false);
+
+            Ok(conditional_tile(
+                condition,
+                &if_true,
+                &if_false,
+                no_data_value,
+            ))
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+/// The tile of an alternative at the position of the condition's tile
+enum AlternativeTile {
+    Grid(GridOrEmpty2D<f64>),
+    Value(f64),
+}
+
+impl AlternativeTile {
+    /// Returns the value at index `i` or `None` if it is no data
+    fn value(&self, i: usize) -> Option<f64> {
+        match self {
+            AlternativeTile::Grid(GridOrEmpty::Grid(grid)) => {
+                let value = grid.data[i];
+                (!grid.is_no_data(value)).then(|| value)
+            }
+            AlternativeTile::Grid(GridOrEmpty::Empty(_)) => None,
+            AlternativeTile::Value(value) => Some(*value),
+        }
+    }
+}
+
+fn conditional_tile<T: Pixel>(
+    condition: RasterTile2D<f64>,
+    if_true: &AlternativeTile,
+    if_false: &AlternativeTile,
+    no_data_value: T,
+) -> RasterTile2D<T> {
+    let tile_information = condition.tile_information();
+    let shape = tile_information.tile_size_in_pixels;
+
+    let condition_grid = match condition.grid_array {
+        GridOrEmpty::Grid(grid) => grid,
+        GridOrEmpty::Empty(_) => {
+            return RasterTile2D::new_with_tile_info(
+                condition.time,
+                tile_information,
+                EmptyGrid2D::new(shape, no_data_value).into(),
+            )
+        }
+    };
+
+    let data = condition_grid
+        .data
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| {
+            if condition_grid.is_no_data(value) {
+                return no_data_value;
+            }
+
+            let alternative = if value == 0. || value.is_nan() {
+                if_false
+            } else {
+                if_true
+            };
+
+            alternative.value(i).map_or(no_data_value, T::from_)
+        })
+        .collect();
+
+    let grid = Grid2D::new(shape, data, Some(no_data_value))
+        .expect("the data has the size of the condition's tile");
+
+    RasterTile2D::new_with_tile_info(condition.time, tile_information, grid.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::TileInformation;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn make_raster(data: Option<Vec<u8>>, no_data_value: u8) -> Box<dyn RasterOperator> {
+        let tile_information = TileInformation {
+            global_tile_position: [-1, 0].into(),
+            tile_size_in_pixels: [3, 2].into(),
+            global_geo_transform: TestDefault::test_default(),
+        };
+
+        let grid = match data {
+            Some(data) => Grid2D::new([3, 2].into(), data, Some(no_data_value))
+                .unwrap()
+                .into(),
+            None => EmptyGrid2D::new([3, 2].into(), no_data_value).into(),
+        };
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    TimeInterval::default(),
+                    tile_information,
+                    grid,
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(f64::from(no_data_value)),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn conditional(
+        sources: ConditionalSources,
+        true_value: Option<f64>,
+        false_value: Option<f64>,
+    ) -> Result<Vec<RasterTile2D<u8>>> {
+        let operator = Conditional {
+            params: ConditionalParams {
+                true_value,
+                false_value,
+                output_type: RasterDataType::U8,
+                output_no_data_value: 0.,
+                output_measurement: None,
+            },
+            sources,
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await?;
+
+        let processor = operator.query_processor()?.get_u8().unwrap();
+
+        let ctx = MockQueryContext::new(1.into());
+        processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 3.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval: TimeInterval::new_unchecked(5, 6),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
+                },
+                &ctx,
+            )
+            .await?
+            .try_collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn it_chooses_between_raster_and_value() {
+        let tiles = conditional(
+            ConditionalSources {
+                condition: make_raster(Some(vec![1, 0, 255, 1, 0, 1]), 255),
+                if_true: Some(make_raster(Some(vec![10, 20, 30, 0, 50, 60]), 0)),
+                if_false: None,
+            },
+            None,
+            Some(7.),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(
+            tiles[0].grid_array.clone().into_materialized_grid().data,
+            vec![10, 7, 0, 0, 7, 60]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_chooses_between_rasters() {
+        let tiles = conditional(
+            ConditionalSources {
+                condition: make_raster(Some(vec![1, 0, 1, 0, 1, 0]), 255),
+                if_true: Some(make_raster(Some(vec![1, 2, 3, 4, 5, 6]), 0)),
+                if_false: Some(make_raster(None, 0)),
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            tiles[0].grid_array.clone().into_materialized_grid().data,
+            vec![1, 0, 3, 0, 5, 0]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_returns_empty_tiles_without_condition() {
+        let tiles = conditional(
+            ConditionalSources {
+                condition: make_raster(None, 255),
+                if_true: None,
+                if_false: None,
+            },
+            Some(1.),
+            Some(2.),
+        )
+        .await
+        .unwrap();
+
+        assert!(tiles[0].is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_requires_exactly_one_alternative() {
+        assert!(conditional(
+            ConditionalSources {
+                condition: make_raster(None, 255),
+                if_true: Some(make_raster(None, 0)),
+                if_false: None,
+            },
+            Some(1.),
+            Some(2.),
+        )
+        .await
+        .is_err());
+
+        assert!(conditional(
+            ConditionalSources {
+                condition: make_raster(None, 255),
+                if_true: None,
+                if_false: None,
+            },
+            Some(1.),
+            None,
+        )
+        .await
+        .is_err());
+
+        // values must fit into the output type
+        assert!(conditional(
+            ConditionalSources {
+                condition: make_raster(None, 255),
+                if_true: None,
+                if_false: None,
+            },
+            Some(-1.),
+            Some(2.),
+        )
+        .await
+        .is_err());
+    }
+}
@@ -0,0 +1,585 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, QueryContext,
+    QueryProcessor, ResultExtent, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error::{self, Error};
+use crate::util::gdal::gdal_union_polygons;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollectionInfos, GeoFeatureCollectionRowBuilder, GeometryCollection,
+    MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataType, FeatureDataValue, MultiPolygon, TimeInterval,
+    VectorQueryRectangle,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The dissolve operator merges all polygons that share the same value in the key column.
+///
+/// Polygons are only merged if they also share the same time interval.
+/// The attributes listed in `aggregations` are aggregated per group, all other attributes are dropped.
+pub type Dissolve = Operator<DissolveParams, SingleVectorSource>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DissolveParams {
+    /// The column that defines which polygons are merged
+    pub key_column: String,
+    /// The aggregations of the attribute columns, the output columns keep their names
+    #[serde(default)]
+    pub aggregations: HashMap<String, DissolveAggregation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DissolveAggregation {
+    /// The sum of all non-null values
+    Sum,
+    /// The mean of all non-null values
+    Mean,
+    /// The value of the first feature in the group
+    First,
+}
+
+impl DissolveAggregation {
+    fn output_type(self, input_type: FeatureDataType) -> FeatureDataType {
+        match self {
+            DissolveAggregation::Sum | DissolveAggregation::Mean => FeatureDataType::Float,
+            DissolveAggregation::First => input_type,
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Dissolve {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let source_descriptor = vector_source.result_descriptor();
+
+        ensure!(
+            source_descriptor.data_type == VectorDataType::MultiPolygon,
+            error::InvalidType {
+                expected: VectorDataType::MultiPolygon.to_string(),
+                found: source_descriptor.data_type.to_string(),
+            }
+        );
+
+        let key_type = match source_descriptor.columns.get(&self.params.key_column) {
+            Some(FeatureDataType::Float) => {
+                return Err(Error::InvalidOperatorSpec {
+                    reason: format!(
+                        "Key column '{}' must not be a float column.",
+                        self.params.key_column
+                    ),
+                })
+            }
+            Some(data_type) => *data_type,
+            None => {
+                return Err(Error::ColumnDoesNotExist {
+                    column: self.params.key_column,
+                })
+            }
+        };
+
+        let mut columns = HashMap::with_capacity(self.params.aggregations.len() + 1);
+        columns.insert(self.params.key_column.clone(), key_type);
+
+        for (column, aggregation) in &self.params.aggregations {
+            ensure!(
+                column != &self.params.key_column,
+                error::InvalidOperatorSpec {
+                    reason: format!("Cannot aggregate the key column '{}'.", column),
+                }
+            );
+
+            let input_type =
+                source_descriptor
+                    .columns
+                    .get(column)
+                    .ok_or_else(|| Error::ColumnDoesNotExist {
+                        column: column.clone(),
+                    })?;
+
+            ensure!(
+                *aggregation == DissolveAggregation::First || input_type.is_numeric(),
+                error::InvalidOperatorSpec {
+                    reason: format!("Column '{}' must be numeric to be aggregated.", column),
+                }
+            );
+
+            columns.insert(column.clone(), aggregation.output_type(*input_type));
+        }
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPolygon,
+            spatial_reference: source_descriptor.spatial_reference,
+            column_metadata: source_descriptor.column_metadata_of(columns.keys()),
+            columns,
+        };
+
+        Ok(InitializedDissolve {
+            result_descriptor,
+            vector_source,
+            params: Arc::new(self.params),
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedDissolve {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    params: Arc<DissolveParams>,
+}
+
+impl InitializedVectorOperator for InitializedDissolve {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let source = self
+            .vector_source
+            .query_processor()?
+            .multi_polygon()
+            .ok_or_else(|| Error::InvalidVectorType {
+                expected: VectorDataType::MultiPolygon.to_string(),
+                found: self.vector_source.result_descriptor().data_type.to_string(),
+            })?;
+
+        Ok(TypedVectorQueryProcessor::MultiPolygon(
+            DissolveProcessor {
+                source,
+                params: self.params.clone(),
+                column_types: self.result_descriptor.columns.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.vector_source.extent()
+    }
+}
+
+pub struct DissolveProcessor {
+    source: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    params: Arc<DissolveParams>,
+    column_types: HashMap<String, FeatureDataType>,
+}
+
+#[async_trait]
+impl QueryProcessor for DissolveProcessor {
+    type Output = MultiPolygonCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let params = self.params.clone();
+
+        // polygons of a group can be spread over all chunks, so we have to collect them first
+        let groups = self
+            .source
+            .query(query, ctx)
+            .await?
+            .try_fold(
+                DissolveGroups::new(params),
+                |mut groups, collection| async move {
+                    groups.add_collection(&collection)?;
+                    Ok(groups)
+                },
+            )
+            .await?;
+
+        let column_types = self.column_types.clone();
+
+        let dissolved = crate::util::spawn_blocking(move || groups.dissolve(&column_types)).await?;
+
+        Ok(futures::stream::once(async move { dissolved }).boxed())
+    }
+}
+
+/// A hashable representation of the values of the key column
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum DissolveKey {
+    Null,
+    Int(i64),
+    Text(String),
+}
+
+impl From<&FeatureDataValue> for DissolveKey {
+    fn from(value: &FeatureDataValue) -> Self {
+        match value {
+            FeatureDataValue::Int(value) | FeatureDataValue::NullableInt(Some(value)) => {
+                DissolveKey::Int(*value)
+            }
+            FeatureDataValue::Text(value)
+            | FeatureDataValue::NullableText(Some(value))
+            | FeatureDataValue::Category(value)
+            | FeatureDataValue::NullableCategory(Some(value)) => DissolveKey::Text(value.clone()),
+            FeatureDataValue::Bool(value) | FeatureDataValue::NullableBool(Some(value)) => {
+                DissolveKey::Int(i64::from(*value))
+            }
+            FeatureDataValue::DateTime(value) | FeatureDataValue::NullableDateTime(Some(value)) => {
+                DissolveKey::Int(value.inner())
+            }
+            // float keys are rejected during initialization
+            FeatureDataValue::Float(_)
+            | FeatureDataValue::NullableFloat(_)
+            | FeatureDataValue::NullableCategory(None)
+            | FeatureDataValue::NullableInt(None)
+            | FeatureDataValue::NullableText(None)
+            | FeatureDataValue::NullableBool(None)
+            | FeatureDataValue::NullableDateTime(None) => DissolveKey::Null,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum AggregateState {
+    Sum(Option<f64>),
+    Mean { sum: f64, count: usize },
+    First(FeatureDataValue),
+}
+
+impl AggregateState {
+    fn new(aggregation: DissolveAggregation, value: FeatureDataValue) -> Self {
+        let mut state = match aggregation {
+            DissolveAggregation::Sum => AggregateState::Sum(None),
+            DissolveAggregation::Mean => AggregateState::Mean { sum: 0., count: 0 },
+            DissolveAggregation::First => return AggregateState::First(value),
+        };
+
+        state.add(&value);
+
+        state
+    }
+
+    fn add(&mut self, value: &FeatureDataValue) {
+        let value = match value {
+            FeatureDataValue::Int(value) | FeatureDataValue::NullableInt(Some(value)) => {
+                *value as f64
+            }
+            FeatureDataValue::Float(value) | FeatureDataValue::NullableFloat(Some(value)) => *value,
+            _ => return,
+        };
+
+        match self {
+            AggregateState::Sum(sum) => *sum = Some(sum.unwrap_or_default() + value),
+            AggregateState::Mean { sum, count } => {
+                *sum += value;
+                *count += 1;
+            }
+            AggregateState::First(_) => {}
+        }
+    }
+
+    fn finish(self) -> FeatureDataValue {
+        match self {
+            AggregateState::Sum(sum) => FeatureDataValue::NullableFloat(sum),
+            AggregateState::Mean { sum, count } => {
+                FeatureDataValue::NullableFloat((count > 0).then(|| sum / count as f64))
+            }
+            AggregateState::First(value) => value,
+        }
+    }
+}
+
+struct DissolveGroup {
+    key: FeatureDataValue,
+    time: TimeInterval,
+    polygons: Vec<geo::Polygon<f64>>,
+    aggregates: Vec<(String, AggregateState)>,
+}
+
+/// The groups of polygons in order of their first occurrence
+struct DissolveGroups {
+    params: Arc<DissolveParams>,
+    groups: Vec<DissolveGroup>,
+    group_indices: HashMap<(DissolveKey, i64, i64), usize>,
+}
+
+impl DissolveGroups {
+    fn new(params: Arc<DissolveParams>) -> Self {
+        Self {
+            params,
+            groups: Vec::new(),
+            group_indices: HashMap::new(),
+        }
+    }
+
+    fn add_collection(&mut self, collection: &MultiPolygonCollection) -> Result<()> {
+        let keys = collection.data(&self.params.key_column)?;
+        let columns = self
+            .params
+            .aggregations
+            .iter()
+            .map(|(column, aggregation)| Ok((column, *aggregation, collection.data(column)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        for (i, (geometry, time)) in collection
+            .geometries()
+            .zip(collection.time_intervals())
+            .enumerate()
+        {
+            let key = keys.get_unchecked(i);
+            let group_key = (
+                DissolveKey::from(&key),
+                time.start().inner(),
+                time.end().inner(),
+            );
+            let geo::MultiPolygon(polygons) = (&MultiPolygon::from(geometry)).into();
+
+            if let Some(&group_index) = self.group_indices.get(&group_key) {
+                let group = &mut self.groups[group_index];
+
+                group.polygons.extend(polygons);
+
+                for ((_, state), (_, _, data)) in group.aggregates.iter_mut().zip(&columns) {
+                    state.add(&data.get_unchecked(i));
+                }
+            } else {
+                self.group_indices.insert(group_key, self.groups.len());
+                self.groups.push(DissolveGroup {
+                    key,
+                    time: *time,
+                    polygons,
+                    aggregates: columns
+                        .iter()
+                        .map(|(column, aggregation, data)| {
+                            (
+                                (*column).clone(),
+                                AggregateState::new(*aggregation, data.get_unchecked(i)),
+                            )
+                        })
+                        .collect(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn dissolve(
+        self,
+        column_types: &HashMap<String, FeatureDataType>,
+    ) -> Result<MultiPolygonCollection> {
+        let mut builder = MultiPolygonCollection::builder();
+        for (column, data_type) in column_types {
+            builder.add_column(column.clone(), *data_type)?;
+        }
+        let mut builder = builder.finish_header();
+
+        for group in self.groups {
+            let union = gdal_union_polygons(&geo::MultiPolygon(group.polygons))?;
+
+            builder.push_geometry(union)?;
+            builder.push_time_interval(group.time)?;
+
+            push_value(&mut builder, &self.params.key_column, group.key)?;
+            for (column, state) in group.aggregates {
+                push_value(&mut builder, &column, state.finish())?;
+            }
+
+            builder.finish_row();
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+fn push_value(
+    builder: &mut geoengine_datatypes::collections::FeatureCollectionRowBuilder<MultiPolygon>,
+    column: &str,
+    value: FeatureDataValue,
+) -> Result<()> {
+    let is_null = matches!(
+        value,
+        FeatureDataValue::NullableCategory(None)
+            | FeatureDataValue::NullableInt(None)
+            | FeatureDataValue::NullableFloat(None)
+            | FeatureDataValue::NullableText(None)
+            | FeatureDataValue::NullableBool(None)
+            | FeatureDataValue::NullableDateTime(None)
+    );
+
+    if is_null {
+        builder.push_null(column)?;
+    } else {
+        builder.push_data(column, value)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geo::algorithm::area::Area;
+    use geoengine_datatypes::primitives::{FeatureData, SpatialResolution};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[test]
+    fn serialization() {
+        let dissolve = Dissolve {
+            params: DissolveParams {
+                key_column: "state".to_string(),
+                aggregations: [("population".to_string(), DissolveAggregation::Sum)]
+                    .into_iter()
+                    .collect(),
+            },
+            sources: MockFeatureCollectionSource::<MultiPolygon>::multiple(vec![])
+                .boxed()
+                .into(),
+        }
+        .boxed();
+
+        let serialized = serde_json::to_value(&dissolve).unwrap();
+
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "type": "Dissolve",
+                "params": {
+                    "keyColumn": "state",
+                    "aggregations": {
+                        "population": "sum"
+                    }
+                },
+                "sources": {
+                    "vector": {
+                        "type": "MockFeatureCollectionSourceMultiPolygon",
+                        "params": {
+                            "collections": [],
+                            "spatialReference": "EPSG:4326"
+                        }
+                    }
+                }
+            })
+        );
+
+        let _operator: Box<dyn VectorOperator> = serde_json::from_value(serialized).unwrap();
+    }
+
+    fn square(x: f64) -> MultiPolygon {
+        MultiPolygon::new(vec![vec![vec![
+            (x, 0.).into(),
+            (x + 1., 0.).into(),
+            (x + 1., 1.).into(),
+            (x, 1.).into(),
+            (x, 0.).into(),
+        ]]])
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn dissolve_adjacent_squares() {
+        let polygons = MultiPolygonCollection::from_data(
+            vec![square(0.), square(1.), square(5.)],
+            vec![TimeInterval::default(); 3],
+            [
+                (
+                    "state".to_string(),
+                    FeatureData::Text(vec!["a".to_string(), "a".to_string(), "b".to_string()]),
+                ),
+                ("population".to_string(), FeatureData::Int(vec![1, 2, 4])),
+                (
+                    "name".to_string(),
+                    FeatureData::Text(vec!["x".to_string(), "y".to_string(), "z".to_string()]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+
+        let dissolve = Dissolve {
+            params: DissolveParams {
+                key_column: "state".to_string(),
+                aggregations: [
+                    ("population".to_string(), DissolveAggregation::Mean),
+                    ("name".to_string(), DissolveAggregation::First),
+                ]
+                .into_iter()
+                .collect(),
+            },
+            sources: MockFeatureCollectionSource::single(polygons).boxed().into(),
+        }
+        .boxed();
+
+        let processor = dissolve
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_polygon()
+            .unwrap();
+
+        let collections: Vec<MultiPolygonCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(collections.len(), 1);
+        let collection = &collections[0];
+
+        assert_eq!(collection.len(), 2);
+
+        let geometries: Vec<MultiPolygon> =
+            collection.geometries().map(MultiPolygon::from).collect();
+        let geo::MultiPolygon(merged) = (&geometries[0]).into();
+        assert_eq!(merged.len(), 1);
+        assert!((merged[0].unsigned_area() - 2.).abs() < 1e-10);
+        assert_eq!(geometries[1], square(5.));
+
+        assert_eq!(
+            collection
+                .data("population")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(1.5), Some(4.)]
+        );
+        assert_eq!(
+            collection
+                .data("name")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["x".to_string(), "z".to_string()]
+        );
+    }
+}
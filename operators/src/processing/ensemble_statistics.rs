@@ -0,0 +1,547 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedRasterOperator, MultipleRasterSources, Operator,
+    QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor, RasterResultDescriptor,
+    ResultExtent, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::math::percentile_of_sorted;
+use crate::util::stream_zip::StreamVectorZip;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::future::join_all;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::primitives::{RasterQueryRectangle, SpatialPartition2D};
+use geoengine_datatypes::raster::{
+    EmptyGrid2D, Grid2D, GridOrEmpty, GridSize, NoDataValue, Pixel, RasterDataType, RasterTile2D,
+};
+use num_traits::AsPrimitive;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+const MAX_NUMBER_OF_RASTER_INPUTS: usize = 64;
+
+/// The output of the statistics, invalid pixels are `NaN`
+type PixelOut = f32;
+const OUT_NO_DATA_VALUE: PixelOut = PixelOut::NAN;
+
+/// The `EnsembleStatistics` operator computes per-pixel statistics across rasters that represent
+/// the members of an ensemble or different scenarios, e.g., of climate projections.
+/// All sources must have the same data type and spatial reference and the same time steps.
+///
+/// Pixels where a member has no data are computed from the remaining members.
+pub type EnsembleStatistics = Operator<EnsembleStatisticsParams, MultipleRasterSources>;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnsembleStatisticsParams {
+    pub statistic: EnsembleStatistic,
+}
+
+/// The statistic that is computed from the values of all members at a pixel
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum EnsembleStatistic {
+    Mean,
+    Median,
+    /// The percentile in `[0, 100]`, interpolated linearly between the closest members
+    Percentile {
+        percentile: f64,
+    },
+    /// The population standard deviation as a measure of spread
+    StandardDeviation,
+    /// The difference between the maximum and the minimum as a measure of spread
+    Range,
+}
+
+impl EnsembleStatistic {
+    fn compute(self, values: &mut [f64]) -> f64 {
+        if values.is_empty() {
+            return f64::NAN;
+        }
+
+        let len = values.len() as f64;
+
+        match self {
+            EnsembleStatistic::Mean => values.iter().sum::<f64>() / len,
+            EnsembleStatistic::Median => {
+                sort(values);
+                percentile_of_sorted(values, 50.)
+            }
+            EnsembleStatistic::Percentile { percentile } => {
+                sort(values);
+                percentile_of_sorted(values, percentile)
+            }
+            EnsembleStatistic::StandardDeviation => {
+                let mean = values.iter().sum::<f64>() / len;
+                let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / len;
+                variance.sqrt()
+            }
+            EnsembleStatistic::Range => {
+                let (min, max) = values
+                    .iter()
+                    .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &v| {
+                        (min.min(v), max.max(v))
+                    });
+                max - min
+            }
+        }
+    }
+}
+
+fn sort(values: &mut [f64]) {
+    values.sort_unstable_by(|a, b| a.partial_cmp(b).expect("`NaN` values are filtered"));
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for EnsembleStatistics {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        ensure!(
+            (1..=MAX_NUMBER_OF_RASTER_INPUTS).contains(&self.sources.rasters.len()),
+            error::InvalidNumberOfRasterInputs {
+                expected: 1..MAX_NUMBER_OF_RASTER_INPUTS,
+                found: self.sources.rasters.len()
+            }
+        );
+
+        if let EnsembleStatistic::Percentile { percentile } = self.params.statistic {
+            ensure!(
+                (0. ..=100.).contains(&percentile),
+                error::InvalidOperatorSpec {
+                    reason: format!("The percentile must be in [0, 100], found {}", percentile),
+                }
+            );
+        }
+
+        let sources = join_all(
+            self.sources
+                .rasters
+                .into_iter()
+                .map(|s| s.initialize(context)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        let first = sources[0].result_descriptor();
+
+        for other in sources.iter().skip(1).map(|s| s.result_descriptor()) {
+            ensure!(
+                first.spatial_reference == other.spatial_reference,
+                error::InvalidSpatialReference {
+                    expected: first.spatial_reference,
+                    found: other.spatial_reference,
+                }
+            );
+            ensure!(
+                first.data_type == other.data_type,
+                error::InvalidType {
+                    expected: format!("{:?}", first.data_type),
+                    found: format!("{:?}", other.data_type),
+                }
+            );
+        }
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: RasterDataType::F32,
+            spatial_reference: first.spatial_reference,
+            measurement: first.measurement.clone(),
+            no_data_value: Some(f64::from(OUT_NO_DATA_VALUE)),
+            dimensions: vec![],
+        };
+
+        Ok(InitializedEnsembleStatistics {
+            result_descriptor,
+            sources,
+            statistic: self.params.statistic,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedEnsembleStatistics {
+    result_descriptor: RasterResultDescriptor,
+    sources: Vec<Box<dyn InitializedRasterOperator>>,
+    statistic: EnsembleStatistic,
+}
+
+impl InitializedRasterOperator for InitializedEnsembleStatistics {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::combine_all(
+            self.sources
+                .iter()
+                .map(InitializedRasterOperator::cacheability),
+        )
+    }
+
+    fn extent(&self) -> ResultExtent {
+        ResultExtent::union_all(self.sources.iter().map(InitializedRasterOperator::extent))
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let processors = self
+            .sources
+            .iter()
+            .map(|s| s.query_processor())
+            .collect::<Result<Vec<_>>>()?;
+
+        let statistic = self.statistic;
+
+        Ok(TypedRasterQueryProcessor::F32(
+            match self.sources[0].result_descriptor().data_type {
+                RasterDataType::U8 => {
+                    EnsembleStatisticsProcessor::new(processors, |p| p.get_u8(), statistic).boxed()
+                }
+                RasterDataType::U16 => {
+                    EnsembleStatisticsProcessor::new(processors, |p| p.get_u16(), statistic).boxed()
+                }
+                RasterDataType::U32 => {
+                    EnsembleStatisticsProcessor::new(processors, |p| p.get_u32(), statistic).boxed()
+                }
+                RasterDataType::U64 => {
+                    EnsembleStatisticsProcessor::new(processors, |p| p.get_u64(), statistic).boxed()
+                }
+                RasterDataType::I8 => {
+                    EnsembleStatisticsProcessor::new(processors, |p| p.get_i8(), statistic).boxed()
+                }
+                RasterDataType::I16 => {
+                    EnsembleStatisticsProcessor::new(processors, |p| p.get_i16(), statistic).boxed()
+                }
+                RasterDataType::I32 => {
+                    EnsembleStatisticsProcessor::new(processors, |p| p.get_i32(), statistic).boxed()
+                }
+                RasterDataType::I64 => {
+                    EnsembleStatisticsProcessor::new(processors, |p| p.get_i64(), statistic).boxed()
+                }
+                RasterDataType::F32 => {
+                    EnsembleStatisticsProcessor::new(processors, |p| p.get_f32(), statistic).boxed()
+                }
+                RasterDataType::F64 => {
+                    EnsembleStatisticsProcessor::new(processors, |p| p.get_f64(), statistic).boxed()
+                }
+            },
+        ))
+    }
+}
+
+pub struct EnsembleStatisticsProcessor<T>
+where
+    T: Pixel,
+{
+    sources: Vec<Box<dyn RasterQueryProcessor<RasterType = T>>>,
+    statistic: EnsembleStatistic,
+}
+
+impl<T> EnsembleStatisticsProcessor<T>
+where
+    T: Pixel,
+{
+    /// Creates the processor from sources that were checked to be of type `T`
+    fn new<F>(
+        sources: Vec<TypedRasterQueryProcessor>,
+        typed: F,
+        statistic: EnsembleStatistic,
+    ) -> Self
+    where
+        F: Fn(TypedRasterQueryProcessor) -> Option<Box<dyn RasterQueryProcessor<RasterType = T>>>,
+    {
+        Self {
+            sources: sources
+                .into_iter()
+                .map(|s| typed(s).expect("data types are checked during initialization"))
+                .collect(),
+            statistic,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for EnsembleStatisticsProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<PixelOut>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let mut streams = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            streams.push(source.raster_query(query, ctx).await?);
+        }
+
+        let statistic = self.statistic;
+
+        let stream = StreamVectorZip::new(streams).map(move |tiles| {
+            let tiles = tiles.into_iter().collect::<Result<Vec<_>>>()?;
+            ensemble_statistic(tiles, statistic)
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+/// Computes the statistic of the members' tiles at the same position and time
+fn ensemble_statistic<T: Pixel>(
+    tiles: Vec<RasterTile2D<T>>,
+    statistic: EnsembleStatistic,
+) -> Result<RasterTile2D<PixelOut>> {
+    let time = tiles
+        .iter()
+        .skip(1)
+        .try_fold(tiles[0].time, |time, tile| time.intersect(&tile.time))
+        .ok_or(error::Error::EnsembleTimesNotAligned)?;
+    let tile_information = tiles[0].tile_information();
+    let shape = tile_information.tile_size_in_pixels;
+
+    let grids = tiles
+        .into_iter()
+        .filter_map(|tile| match tile.grid_array {
+            GridOrEmpty::Grid(grid) => Some(grid),
+            GridOrEmpty::Empty(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    if grids.is_empty() {
+        return Ok(RasterTile2D::new_with_tile_info(
+            time,
+            tile_information,
+            EmptyGrid2D::new(shape, OUT_NO_DATA_VALUE).into(),
+        ));
+    }
+
+    let mut values = Vec::with_capacity(grids.len());
+    let data = (0..shape.number_of_elements())
+        .map(|i| {
+            values.clear();
+            values.extend(
+                grids
+                    .iter()
+                    .filter(|grid| !grid.is_no_data(grid.data[i]))
+                    .map(|grid| AsPrimitive::<f64>::as_(grid.data[i]))
+                    .filter(|value| !value.is_nan()),
+            );
+
+            statistic.compute(&mut values) as PixelOut
+        })
+        .collect();
+
+    let grid = Grid2D::new(shape, data, Some(OUT_NO_DATA_VALUE))
+        .expect("the tiles of all sources have the same shape");
+
+    Ok(RasterTile2D::new_with_tile_info(
+        time,
+        tile_information,
+        grid.into(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{Measurement, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::TileInformation;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn make_raster(data: Option<Vec<u8>>, time: TimeInterval) -> Box<dyn RasterOperator> {
+        let tile_information = TileInformation {
+            global_tile_position: [-1, 0].into(),
+            tile_size_in_pixels: [3, 2].into(),
+            global_geo_transform: TestDefault::test_default(),
+        };
+
+        let grid = match data {
+            Some(data) => Grid2D::new([3, 2].into(), data, Some(0)).unwrap().into(),
+            None => EmptyGrid2D::new([3, 2].into(), 0).into(),
+        };
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    time,
+                    tile_information,
+                    grid,
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(0.),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn ensemble_statistics(
+        statistic: EnsembleStatistic,
+        rasters: Vec<Box<dyn RasterOperator>>,
+    ) -> Result<Vec<RasterTile2D<f32>>> {
+        let operator = EnsembleStatistics {
+            params: EnsembleStatisticsParams { statistic },
+            sources: rasters.into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await?;
+
+        let processor = operator.query_processor()?.get_f32().unwrap();
+
+        let ctx = MockQueryContext::new(1.into());
+        processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 3.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval: TimeInterval::new_unchecked(0, 10),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
+                },
+                &ctx,
+            )
+            .await?
+            .try_collect()
+            .await
+    }
+
+    fn members() -> Vec<Box<dyn RasterOperator>> {
+        vec![
+            make_raster(Some(vec![1, 0, 4, 2, 9, 1]), TimeInterval::default()),
+            make_raster(Some(vec![3, 0, 2, 4, 3, 1]), TimeInterval::default()),
+            make_raster(Some(vec![2, 5, 0, 6, 6, 1]), TimeInterval::default()),
+            make_raster(None, TimeInterval::default()),
+        ]
+    }
+
+    fn data(tiles: &[RasterTile2D<f32>]) -> Vec<f32> {
+        tiles[0].grid_array.clone().into_materialized_grid().data
+    }
+
+    #[tokio::test]
+    async fn it_computes_the_mean_and_median() {
+        let tiles = ensemble_statistics(EnsembleStatistic::Mean, members())
+            .await
+            .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(data(&tiles), vec![2., 5., 3., 4., 6., 1.]);
+
+        let tiles = ensemble_statistics(EnsembleStatistic::Median, members())
+            .await
+            .unwrap();
+
+        assert_eq!(data(&tiles), vec![2., 5., 3., 4., 6., 1.]);
+    }
+
+    #[tokio::test]
+    async fn it_computes_percentiles_and_spread() {
+        let tiles = ensemble_statistics(
+            EnsembleStatistic::Percentile { percentile: 100. },
+            members(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(data(&tiles), vec![3., 5., 4., 6., 9., 1.]);
+
+        let tiles = ensemble_statistics(EnsembleStatistic::Range, members())
+            .await
+            .unwrap();
+
+        assert_eq!(data(&tiles), vec![2., 0., 2., 4., 6., 0.]);
+
+        let tiles = ensemble_statistics(EnsembleStatistic::StandardDeviation, members())
+            .await
+            .unwrap();
+
+        assert!((data(&tiles)[3] - (8_f32 / 3.).sqrt()).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn it_outputs_no_data_without_valid_members() {
+        let tiles = ensemble_statistics(
+            EnsembleStatistic::Mean,
+            vec![
+                make_raster(Some(vec![1, 0, 1, 0, 1, 0]), TimeInterval::default()),
+                make_raster(None, TimeInterval::default()),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let data = data(&tiles);
+        assert!(data[1].is_nan());
+        assert!((data[0] - 1.).abs() < f32::EPSILON);
+
+        let tiles = ensemble_statistics(
+            EnsembleStatistic::Mean,
+            vec![
+                make_raster(None, TimeInterval::default()),
+                make_raster(None, TimeInterval::default()),
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert!(tiles[0].is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_requires_aligned_times() {
+        let result = ensemble_statistics(
+            EnsembleStatistic::Mean,
+            vec![
+                make_raster(Some(vec![1; 6]), TimeInterval::new_unchecked(0, 5)),
+                make_raster(Some(vec![1; 6]), TimeInterval::new_unchecked(5, 10)),
+            ],
+        )
+        .await;
+
+        assert!(matches!(result, Err(error::Error::EnsembleTimesNotAligned)));
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_percentile() {
+        let result = ensemble_statistics(
+            EnsembleStatistic::Percentile { percentile: 120. },
+            members(),
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(error::Error::InvalidOperatorSpec { .. })
+        ));
+    }
+
+    #[test]
+    fn it_deserializes_params() {
+        assert_eq!(
+            serde_json::from_str::<EnsembleStatisticsParams>(
+                r#"{"statistic":{"type":"percentile","percentile":90.0}}"#
+            )
+            .unwrap(),
+            EnsembleStatisticsParams {
+                statistic: EnsembleStatistic::Percentile { percentile: 90. }
+            }
+        );
+    }
+}
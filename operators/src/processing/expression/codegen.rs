@@ -66,6 +66,19 @@ impl ToTokens for ExpressionAst {
             .map(|p| match p {
                 Parameter::Number(param) => quote! { #param: #dtype },
                 Parameter::Boolean(param) => quote! { #param: bool },
+                Parameter::NumberArray { name, .. } => quote! { #name: *const #dtype },
+            })
+            .collect();
+        let unpacked_arrays: Vec<TokenStream> = self
+            .parameters
+            .iter()
+            .flat_map(|p| match p {
+                Parameter::NumberArray { name, entries } => entries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, entry)| quote! { let #entry = unsafe { *#name.add(#i) }; })
+                    .collect(),
+                Parameter::Number(_) | Parameter::Boolean(_) => vec![],
             })
             .collect();
         let content = &self.root;
@@ -73,6 +86,7 @@ impl ToTokens for ExpressionAst {
         tokens.extend(quote! {
             #[no_mangle]
             pub extern "C" fn #fn_name (#(#params),*) -> #dtype {
+                #(#unpacked_arrays)*
                 #content
             }
         });
@@ -326,12 +340,31 @@ impl ToTokens for Assignment {
 pub enum Parameter {
     Number(Identifier),
     Boolean(Identifier),
+    /// A pointer to numbers that are available as the variables `entries`
+    NumberArray {
+        name: Identifier,
+        entries: Vec<Identifier>,
+    },
+}
+
+impl Parameter {
+    /// The names of the parameter and of the variables it provides
+    pub fn identifiers(&self) -> Vec<&Identifier> {
+        match self {
+            Self::Number(identifier) | Self::Boolean(identifier) => vec![identifier],
+            Self::NumberArray { name, entries } => std::iter::once(name).chain(entries).collect(),
+        }
+    }
 }
 
 impl AsRef<str> for Parameter {
     fn as_ref(&self) -> &str {
         match self {
-            Self::Number(identifier) | Self::Boolean(identifier) => identifier.as_ref(),
+            Self::Number(identifier)
+            | Self::Boolean(identifier)
+            | Self::NumberArray {
+                name: identifier, ..
+            } => identifier.as_ref(),
         }
     }
 }
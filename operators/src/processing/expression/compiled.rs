@@ -56,18 +56,20 @@ impl LinkedExpression {
         })
     }
 
-    /// Returns a function with 3 input parameters
+    /// Returns a function with 4 input parameters
     #[allow(clippy::type_complexity)]
-    pub unsafe fn function_3<A, B, C>(&self) -> Result<Symbol<fn(A, B, C) -> f64>> {
+    pub unsafe fn function_4<A, B, C, D>(&self) -> Result<Symbol<fn(A, B, C, D) -> f64>> {
         self.library
             .get(self.function_name.as_bytes())
             .map_err(|error| ExpressionError::LinkedFunctionNotFound {
                 error: error.to_string(),
             })
     }
-    /// Returns a function with 5 input parameters
+    /// Returns a function with 6 input parameters
     #[allow(clippy::type_complexity)]
-    pub unsafe fn function_5<A, B, C, D, E>(&self) -> Result<Symbol<fn(A, B, C, D, E) -> f64>> {
+    pub unsafe fn function_6<A, B, C, D, E, F>(
+        &self,
+    ) -> Result<Symbol<fn(A, B, C, D, E, F) -> f64>> {
         self.library
             .get(self.function_name.as_bytes())
             .map_err(|error| ExpressionError::LinkedFunctionNotFound {
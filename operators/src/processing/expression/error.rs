@@ -75,4 +75,10 @@ pub enum ExpressionError {
     MissingIdentifier,
     MissingOutputNoDataValue,
     SourcesMustBeConsecutive,
+    InvalidTilePropertyVariable {
+        variable: String,
+    },
+    UnknownTilePropertyRaster {
+        raster: String,
+    },
 }
@@ -1,17 +1,23 @@
 use self::{codegen::ExpressionAst, compiled::LinkedExpression, parser::ExpressionParser};
 use crate::{
     engine::{
-        ExecutionContext, InitializedRasterOperator, Operator, OperatorDatasets, RasterOperator,
-        RasterQueryProcessor, RasterResultDescriptor, TypedRasterQueryProcessor,
+        Cacheability, ExecutionContext, InitializedRasterOperator, Operator, OperatorDatasets,
+        RasterOperator, RasterQueryProcessor, RasterResultDescriptor, ResultExtent, TileBufferPool,
+        TypedRasterQueryProcessor,
     },
     processing::expression::{codegen::Parameter, query_processor::ExpressionQueryProcessor},
     util::{input::float_with_nan, Result},
 };
 use async_trait::async_trait;
-use geoengine_datatypes::{dataset::DatasetId, primitives::Measurement, raster::RasterDataType};
+use geoengine_datatypes::{
+    dataset::DatasetId,
+    primitives::Measurement,
+    raster::{RasterDataType, RasterPropertiesKey},
+};
 use num_traits::AsPrimitive;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, OptionExt};
+use std::sync::Arc;
 
 pub use self::error::ExpressionError;
 
@@ -28,6 +34,7 @@ mod query_processor;
 /// * `output_type` is the data type of the produced raster tiles.
 /// * `output_no_data_value` is the no data value of the output raster
 /// * `output_measurement` is the measurement description of the output
+/// * `tile_properties` are properties of the source tiles that are available as variables
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ExpressionParams {
@@ -37,9 +44,21 @@ pub struct ExpressionParams {
     pub output_no_data_value: f64, // TODO: check value is valid for given output type during deserialization
     pub output_measurement: Option<Measurement>,
     pub map_no_data: bool,
+    #[serde(default)]
+    pub tile_properties: Vec<ExpressionTileProperty>,
+}
+
+/// A property of the current tile of a source raster, e.g., a scale factor, that the expression
+/// can use as the scalar `variable`. Missing or non-numeric properties are `NaN`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpressionTileProperty {
+    pub variable: String,
+    /// The source raster, i.e., `A`, `B`, …
+    pub raster: String,
+    pub property: RasterPropertiesKey,
 }
 
-// TODO: rename to `Expression`
 /// The `Expression` operator calculates an expression for all pixels of the input rasters and
 /// produces raster tiles of a given output type
 pub type Expression = Operator<ExpressionParams, ExpressionSources>;
@@ -195,6 +214,35 @@ fn index_to_parameter(index: usize) -> String {
     parameter.to_string()
 }
 
+/// Resolves the tile properties to the index of their source raster
+fn resolve_tile_properties(
+    tile_properties: &[ExpressionTileProperty],
+    number_of_sources: usize,
+) -> Result<Vec<(usize, RasterPropertiesKey)>> {
+    tile_properties
+        .iter()
+        .map(|tile_property| -> Result<(usize, RasterPropertiesKey)> {
+            let variable = &tile_property.variable;
+            let mut chars = variable.chars();
+            ensure!(
+                chars.next().map_or(false, |c| c.is_ascii_alphabetic())
+                    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+                error::InvalidTilePropertyVariable {
+                    variable: variable.clone()
+                }
+            );
+
+            let source = (0..number_of_sources)
+                .find(|&i| index_to_parameter(i) == tile_property.raster)
+                .context(error::UnknownTilePropertyRaster {
+                    raster: tile_property.raster.clone(),
+                })?;
+
+            Ok((source, tile_property.property.clone()))
+        })
+        .collect()
+}
+
 #[typetag::serde]
 #[async_trait]
 impl RasterOperator for Expression {
@@ -202,7 +250,6 @@ impl RasterOperator for Expression {
         self: Box<Self>,
         context: &dyn crate::engine::ExecutionContext,
     ) -> Result<Box<dyn InitializedRasterOperator>> {
-        // TODO: handle more then 2 inputs, i.e. 1-8
         ensure!(
             (1..=8).contains(&self.sources.number_of_sources()),
             crate::error::InvalidNumberOfRasterInputs {
@@ -211,6 +258,11 @@ impl RasterOperator for Expression {
             }
         );
 
+        let tile_properties = resolve_tile_properties(
+            &self.params.tile_properties,
+            self.sources.number_of_sources(),
+        )?;
+
         // we refer to rasters by A, B, C, …
         let parameters = (0..self.sources.number_of_sources())
             .flat_map(|i| {
@@ -221,7 +273,18 @@ impl RasterOperator for Expression {
                     Parameter::Boolean(boolean_parameter.into()),
                 ]
             })
-            .chain([Parameter::Number("out_nodata".into())])
+            .chain([
+                Parameter::Number("out_nodata".into()),
+                Parameter::NumberArray {
+                    name: "tile_properties".into(),
+                    entries: self
+                        .params
+                        .tile_properties
+                        .iter()
+                        .map(|tile_property| tile_property.variable.as_str().into())
+                        .collect(),
+                },
+            ])
             .collect::<Vec<_>>();
 
         let expression = ExpressionParser::new(&parameters)?.parse(
@@ -263,6 +326,7 @@ impl RasterOperator for Expression {
                 .as_ref()
                 .map_or(Measurement::Unitless, Measurement::clone),
             no_data_value: Some(self.params.output_no_data_value), // TODO: is it possible to have none?
+            dimensions: vec![],
         };
 
         let initialized_operator = InitializedExpression {
@@ -270,6 +334,8 @@ impl RasterOperator for Expression {
             sources,
             expression,
             map_no_data: self.params.map_no_data,
+            tile_properties,
+            tile_buffer_pool: context.tile_buffer_pool().clone(),
         };
 
         Ok(initialized_operator.boxed())
@@ -281,6 +347,8 @@ pub struct InitializedExpression {
     sources: ExpressionInitializedSources,
     expression: ExpressionAst,
     map_no_data: bool,
+    tile_properties: Vec<(usize, RasterPropertiesKey)>,
+    tile_buffer_pool: Arc<TileBufferPool>,
 }
 
 pub struct ExpressionInitializedSources {
@@ -340,6 +408,8 @@ impl InitializedRasterOperator for InitializedExpression {
                         query_processor,
                         output_no_data_value.as_(),
                         self.map_no_data,
+                        self.tile_properties.clone(),
+                        self.tile_buffer_pool.clone(),
                     )
                     .boxed()
                 )
@@ -370,6 +440,8 @@ impl InitializedRasterOperator for InitializedExpression {
                         query_processors,
                         output_no_data_value.as_(),
                         self.map_no_data,
+                        self.tile_properties.clone(),
+                        self.tile_buffer_pool.clone(),
                     )
                     .boxed()
                 )
@@ -400,6 +472,8 @@ impl InitializedRasterOperator for InitializedExpression {
                         query_processors,
                         output_no_data_value.as_(),
                         self.map_no_data,
+                        self.tile_properties.clone(),
+                        self.tile_buffer_pool.clone(),
                     )
                     .boxed()
                 )
@@ -415,6 +489,8 @@ impl InitializedRasterOperator for InitializedExpression {
                         query_processors,
                         output_no_data_value.as_(),
                         self.map_no_data,
+                        self.tile_properties.clone(),
+                        self.tile_buffer_pool.clone(),
                     )
                     .boxed()
                 )
@@ -436,6 +512,8 @@ impl InitializedRasterOperator for InitializedExpression {
                         query_processors,
                         output_no_data_value.as_(),
                         self.map_no_data,
+                        self.tile_properties.clone(),
+                        self.tile_buffer_pool.clone(),
                     )
                     .boxed()
                 )
@@ -458,6 +536,8 @@ impl InitializedRasterOperator for InitializedExpression {
                         query_processors,
                         output_no_data_value.as_(),
                         self.map_no_data,
+                        self.tile_properties.clone(),
+                        self.tile_buffer_pool.clone(),
                     )
                     .boxed()
                 )
@@ -481,6 +561,8 @@ impl InitializedRasterOperator for InitializedExpression {
                         query_processors,
                         output_no_data_value.as_(),
                         self.map_no_data,
+                        self.tile_properties.clone(),
+                        self.tile_buffer_pool.clone(),
                     )
                     .boxed()
                 )
@@ -505,6 +587,8 @@ impl InitializedRasterOperator for InitializedExpression {
                         query_processors,
                         output_no_data_value.as_(),
                         self.map_no_data,
+                        self.tile_properties.clone(),
+                        self.tile_buffer_pool.clone(),
                     )
                     .boxed()
                 )
@@ -516,6 +600,18 @@ impl InitializedRasterOperator for InitializedExpression {
     fn result_descriptor(&self) -> &RasterResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::combine_all(
+            self.sources
+                .iter()
+                .map(InitializedRasterOperator::cacheability),
+        )
+    }
+
+    fn extent(&self) -> ResultExtent {
+        ResultExtent::intersection_all(self.sources.iter().map(InitializedRasterOperator::extent))
+    }
 }
 
 #[cfg(test)]
@@ -527,7 +623,9 @@ mod tests {
     use geoengine_datatypes::primitives::{
         Measurement, RasterQueryRectangle, SpatialPartition2D, SpatialResolution, TimeInterval,
     };
-    use geoengine_datatypes::raster::{Grid2D, RasterTile2D, TileInformation};
+    use geoengine_datatypes::raster::{
+        Grid2D, RasterProperties, RasterPropertiesEntry, RasterTile2D, TileInformation,
+    };
     use geoengine_datatypes::spatial_reference::SpatialReference;
     use geoengine_datatypes::util::test::TestDefault;
 
@@ -543,6 +641,7 @@ mod tests {
                 output_no_data_value: 0.0,
                 output_measurement: None,
                 map_no_data: false,
+                tile_properties: vec![],
             }
         );
     }
@@ -567,7 +666,7 @@ mod tests {
 
     #[test]
     fn serialize_params() {
-        let s = r#"{"expression":"1*A","outputType":"F64","outputNoDataValue":0.0,"outputMeasurement":null,"mapNoData":false}"#;
+        let s = r#"{"expression":"1*A","outputType":"F64","outputNoDataValue":0.0,"outputMeasurement":null,"mapNoData":false,"tileProperties":[]}"#;
 
         assert_eq!(
             s,
@@ -577,6 +676,7 @@ mod tests {
                 output_no_data_value: 0.0,
                 output_measurement: None,
                 map_no_data: false,
+                tile_properties: vec![],
             })
             .unwrap()
         );
@@ -584,7 +684,7 @@ mod tests {
 
     #[test]
     fn serialize_params_no_data() {
-        let s = r#"{"expression":"1*A","outputType":"F64","outputNoDataValue":"nan","outputMeasurement":null,"mapNoData":false}"#;
+        let s = r#"{"expression":"1*A","outputType":"F64","outputNoDataValue":"nan","outputMeasurement":null,"mapNoData":false,"tileProperties":[]}"#;
 
         assert_eq!(
             s,
@@ -594,6 +694,7 @@ mod tests {
                 output_no_data_value: f64::NAN,
                 output_measurement: None,
                 map_no_data: false,
+                tile_properties: vec![],
             })
             .unwrap()
         );
@@ -613,6 +714,7 @@ mod tests {
                 output_no_data_value: no_data_value.as_(), //  cast no_data_value to f64
                 output_measurement: Some(Measurement::Unitless),
                 map_no_data: false,
+                tile_properties: vec![],
             },
             sources: ExpressionSources {
                 a: raster_a,
@@ -642,6 +744,7 @@ mod tests {
                     ),
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
                 },
                 &ctx,
             )
@@ -678,6 +781,7 @@ mod tests {
                 output_no_data_value: no_data_value.as_(), //  cast no_data_value to f64
                 output_measurement: Some(Measurement::Unitless),
                 map_no_data: true,
+                tile_properties: vec![],
             },
             sources: ExpressionSources {
                 a: raster_a,
@@ -707,6 +811,7 @@ mod tests {
                     ),
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
                 },
                 &ctx,
             )
@@ -737,6 +842,8 @@ mod tests {
         let raster_a = make_raster(None);
         let raster_b = make_raster(None);
 
+        let exe_ctx = MockExecutionContext::test_default();
+
         let o = Expression {
             params: ExpressionParams {
                 expression: "A+B".to_string(),
@@ -744,6 +851,7 @@ mod tests {
                 output_no_data_value: no_data_value.as_(), //  cast no_data_valuee to f64
                 output_measurement: Some(Measurement::Unitless),
                 map_no_data: false,
+                tile_properties: vec![],
             },
             sources: ExpressionSources {
                 a: raster_a,
@@ -757,7 +865,7 @@ mod tests {
             },
         }
         .boxed()
-        .initialize(&MockExecutionContext::test_default())
+        .initialize(&exe_ctx)
         .await
         .unwrap();
 
@@ -773,6 +881,7 @@ mod tests {
                     ),
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
                 },
                 &ctx,
             )
@@ -793,6 +902,9 @@ mod tests {
             .unwrap()
             .into()
         );
+
+        // the buffers of the consumed source tiles are kept for reuse
+        assert!(exe_ctx.tile_buffer_pool.pooled_bytes() > 0);
     }
 
     #[tokio::test]
@@ -817,6 +929,7 @@ mod tests {
                 output_no_data_value: no_data_value.as_(), //  cast no_data_valuee to f64
                 output_measurement: Some(Measurement::Unitless),
                 map_no_data: true,
+                tile_properties: vec![],
             },
             sources: ExpressionSources {
                 a: raster_a,
@@ -846,6 +959,7 @@ mod tests {
                     ),
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
                 },
                 &ctx,
             )
@@ -880,6 +994,7 @@ mod tests {
                 output_no_data_value: no_data_value.as_(), //  cast no_data_valuee to f64
                 output_measurement: Some(Measurement::Unitless),
                 map_no_data: false,
+                tile_properties: vec![],
             },
             sources: ExpressionSources {
                 a: raster_a,
@@ -909,6 +1024,7 @@ mod tests {
                     ),
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
                 },
                 &ctx,
             )
@@ -952,6 +1068,7 @@ mod tests {
                 output_no_data_value: no_data_value.as_(), //  cast no_data_valuee to f64
                 output_measurement: Some(Measurement::Unitless),
                 map_no_data: false,
+                tile_properties: vec![],
             },
             sources: ExpressionSources {
                 a: raster_a,
@@ -981,6 +1098,7 @@ mod tests {
                     ),
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
                 },
                 &ctx,
             )
@@ -1017,6 +1135,7 @@ mod tests {
                 output_no_data_value: no_data_value.as_(), //  cast no_data_value to f64
                 output_measurement: Some(Measurement::Unitless),
                 map_no_data: false,
+                tile_properties: vec![],
             },
             sources: ExpressionSources {
                 a: raster_a,
@@ -1046,6 +1165,7 @@ mod tests {
                     ),
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
                 },
                 &ctx,
             )
@@ -1068,6 +1188,108 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn tile_properties() {
+        let factor = RasterPropertiesKey {
+            domain: None,
+            key: "factor".to_string(),
+        };
+
+        let mut properties = RasterProperties::default();
+        properties
+            .properties_map
+            .insert(factor.clone(), RasterPropertiesEntry::Number(2.));
+
+        let raster_a = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info_and_properties(
+                    TimeInterval::default(),
+                    TileInformation {
+                        global_tile_position: [-1, 0].into(),
+                        tile_size_in_pixels: [3, 2].into(),
+                        global_geo_transform: TestDefault::test_default(),
+                    },
+                    Grid2D::new([3, 2].into(), vec![1, 2, 3, 4, 5, 6], None)
+                        .unwrap()
+                        .into(),
+                    properties,
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::I8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: None,
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed();
+
+        let params = ExpressionParams {
+            expression: "A * factor".to_string(),
+            output_type: RasterDataType::I8,
+            output_no_data_value: 0.,
+            output_measurement: None,
+            map_no_data: false,
+            tile_properties: vec![ExpressionTileProperty {
+                variable: "factor".to_string(),
+                raster: "A".to_string(),
+                property: factor,
+            }],
+        };
+
+        let o = Expression {
+            params: params.clone(),
+            sources: ExpressionSources::new_a(raster_a.clone()),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        let processor = o.query_processor().unwrap().get_i8().unwrap();
+
+        let ctx = MockQueryContext::new(1.into());
+        let result: Vec<Result<RasterTile2D<i8>>> = processor
+            .query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 4.).into(),
+                        (3., 0.).into(),
+                    ),
+                    time_interval: Default::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
+                },
+                &ctx,
+            )
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].as_ref().unwrap().grid_array,
+            Grid2D::new([3, 2].into(), vec![2, 4, 6, 8, 10, 12], Some(0))
+                .unwrap()
+                .into()
+        );
+
+        // there is no raster `B`
+        let mut params_with_unknown_raster = params;
+        params_with_unknown_raster.tile_properties[0].raster = "B".to_string();
+
+        assert!(Expression {
+            params: params_with_unknown_raster,
+            sources: ExpressionSources::new_a(raster_a),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .is_err());
+    }
+
     fn make_raster(no_data_value: Option<i8>) -> Box<dyn RasterOperator> {
         let raster = Grid2D::new([3, 2].into(), vec![1, 2, 3, 4, 5, 6], no_data_value).unwrap();
 
@@ -1089,6 +1311,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(f64::from),
+                    dimensions: vec![],
                 },
             },
         }
@@ -51,7 +51,10 @@ lazy_static::lazy_static! {
 
 impl ExpressionParser {
     pub fn new(parameters: &[Parameter]) -> Result<Self> {
-        match duplicate_or_empty_str_slice(parameters) {
+        let identifiers: Vec<&Identifier> =
+            parameters.iter().flat_map(Parameter::identifiers).collect();
+
+        match duplicate_or_empty_str_slice(&identifiers) {
             crate::util::DuplicateOrEmpty::Ok => (), // fine
             crate::util::DuplicateOrEmpty::Duplicate(parameter) => {
                 return Err(ExpressionError::DuplicateParameterName { parameter });
@@ -65,9 +68,16 @@ impl ExpressionParser {
         let mut boolean_parameters = HashSet::with_capacity(parameters.len() / 2);
         for parameter in parameters {
             match parameter {
-                Parameter::Number(name) => numeric_parameters.insert(name.clone()),
-                Parameter::Boolean(name) => boolean_parameters.insert(name.clone()),
-            };
+                Parameter::Number(name) => {
+                    numeric_parameters.insert(name.clone());
+                }
+                Parameter::Boolean(name) => {
+                    boolean_parameters.insert(name.clone());
+                }
+                Parameter::NumberArray { entries, .. } => {
+                    numeric_parameters.extend(entries.iter().cloned());
+                }
+            }
         }
 
         Ok(Self {
@@ -848,4 +858,43 @@ mod tests {
             .to_string()
         );
     }
+    #[test]
+    fn number_arrays() {
+        let parameters = [
+            Parameter::Number("A".into()),
+            Parameter::NumberArray {
+                name: "tile_properties".into(),
+                entries: vec!["scale".into(), "offset".into()],
+            },
+        ];
+
+        let ast = ExpressionParser::new(&parameters)
+            .unwrap()
+            .parse("expression", "A * scale + offset")
+            .unwrap();
+
+        assert_eq!(
+            ast.into_token_stream().to_string(),
+            quote! {
+                #[no_mangle]
+                pub extern "C" fn expression(A: f64, tile_properties: *const f64) -> f64 {
+                    let scale = unsafe { *tile_properties.add(0usize) };
+                    let offset = unsafe { *tile_properties.add(1usize) };
+                    ((A * scale) + offset)
+                }
+            }
+            .to_string()
+        );
+
+        assert!(matches!(
+            ExpressionParser::new(&[
+                Parameter::Number("A".into()),
+                Parameter::NumberArray {
+                    name: "tile_properties".into(),
+                    entries: vec!["A".into()],
+                },
+            ]),
+            Err(ExpressionError::DuplicateParameterName { .. })
+        ));
+    }
 }
@@ -6,7 +6,7 @@ use geoengine_datatypes::{
     primitives::{RasterQueryRectangle, SpatialPartition2D, TimeInterval},
     raster::{
         ConvertDataType, GeoTransform, Grid2D, GridIdx2D, GridShape2D, GridShapeAccess, GridSize,
-        NoDataValue, Pixel, RasterTile2D,
+        NoDataValue, Pixel, RasterProperties, RasterPropertiesKey, RasterTile2D,
     },
 };
 use libloading::Symbol;
@@ -16,7 +16,7 @@ use rayon::iter::{
 };
 
 use crate::{
-    engine::{BoxRasterQueryProcessor, QueryContext, QueryProcessor},
+    engine::{BoxRasterQueryProcessor, QueryContext, QueryProcessor, TileBufferPool},
     util::{stream_zip::StreamTupleZip, Result},
 };
 
@@ -31,6 +31,10 @@ where
     pub program: Arc<LinkedExpression>,
     pub no_data_value: TO,
     pub map_no_data: bool,
+    /// The source index and key of the tile properties that the expression uses
+    pub tile_properties: Vec<(usize, RasterPropertiesKey)>,
+    /// Provides the buffers of the output tiles and takes the buffers of the consumed source tiles
+    pub tile_buffer_pool: Arc<TileBufferPool>,
 }
 
 impl<TO, Sources> ExpressionQueryProcessor<TO, Sources>
@@ -42,6 +46,8 @@ where
         sources: Sources,
         no_data_value: TO,
         map_no_data: bool,
+        tile_properties: Vec<(usize, RasterPropertiesKey)>,
+        tile_buffer_pool: Arc<TileBufferPool>,
     ) -> Self {
         Self {
             sources,
@@ -49,6 +55,8 @@ where
             phantom_data: PhantomData::default(),
             no_data_value,
             map_no_data,
+            tile_properties,
+            tile_buffer_pool,
         }
     }
 }
@@ -83,10 +91,22 @@ where
 
                 let program = self.program.clone();
                 let map_no_data = self.map_no_data;
+                let tile_properties =
+                    tile_property_values(&Tuple::properties(&rasters), &self.tile_properties);
+                let tile_buffer_pool = self.tile_buffer_pool.clone();
 
                 let data = crate::util::spawn_blocking_with_thread_pool(
                     ctx.thread_pool().clone(),
-                    move || Tuple::compute_expression(rasters, &program, map_no_data, out_no_data),
+                    move || {
+                        Tuple::compute_expression(
+                            rasters,
+                            &program,
+                            map_no_data,
+                            out_no_data,
+                            &tile_properties,
+                            &tile_buffer_pool,
+                        )
+                    },
                 )
                 .await??;
 
@@ -103,6 +123,32 @@ where
 
         Ok(stream.boxed())
     }
+
+    async fn query_is_empty<'b>(
+        &'b self,
+        query: RasterQueryRectangle,
+        ctx: &'b dyn QueryContext,
+    ) -> Result<bool> {
+        // tiles are only computed if any of the sources has data
+        self.sources.all_queries_empty(query, ctx).await
+    }
+}
+
+/// Looks up the values of the tile properties that the expression uses.
+/// Properties that are missing or not numeric are NaN.
+fn tile_property_values(
+    properties: &[&RasterProperties],
+    references: &[(usize, RasterPropertiesKey)],
+) -> Vec<f64> {
+    references
+        .iter()
+        .map(|(source, key)| {
+            properties
+                .get(*source)
+                .and_then(|properties| properties.number_property::<f64>(key).ok())
+                .unwrap_or(f64::NAN)
+        })
+        .collect()
 }
 
 #[async_trait]
@@ -115,17 +161,29 @@ trait ExpressionTupleProcessor<TO: Pixel>: Send + Sync {
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::Tuple>>>;
 
+    /// Whether the queries of all sources are known to be empty
+    async fn all_queries_empty<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<bool>;
+
     fn all_empty(tuple: &Self::Tuple) -> bool;
 
     fn empty_raster(tuple: &Self::Tuple) -> RasterTile2D<TO>;
 
     fn metadata(tuple: &Self::Tuple) -> (TimeInterval, GridIdx2D, GeoTransform, GridShape2D);
 
+    /// The tile properties of the sources in the order of the sources
+    fn properties(tuple: &Self::Tuple) -> Vec<&RasterProperties>;
+
     fn compute_expression(
         tuple: Self::Tuple,
         program: &LinkedExpression,
         map_no_data: bool,
         out_no_data: TO,
+        tile_properties: &[f64],
+        tile_buffer_pool: &TileBufferPool,
     ) -> Result<Vec<TO>>;
 }
 
@@ -148,6 +206,15 @@ where
         Ok(stream.boxed())
     }
 
+    #[inline]
+    async fn all_queries_empty<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        self.query_is_empty(query, ctx).await
+    }
+
     #[inline]
     fn all_empty(tuple: &Self::Tuple) -> bool {
         tuple.grid_array.is_empty()
@@ -170,23 +237,30 @@ where
         )
     }
 
+    #[inline]
+    fn properties(tuple: &Self::Tuple) -> Vec<&RasterProperties> {
+        vec![&tuple.properties]
+    }
+
     #[inline]
     fn compute_expression(
         raster: Self::Tuple,
         program: &LinkedExpression,
         map_no_data: bool,
         out_no_data: TO,
+        tile_properties: &[f64],
+        tile_buffer_pool: &TileBufferPool,
     ) -> Result<Vec<TO>> {
         let expression = unsafe {
             // we have to "trust" that the function has the signature we expect
-            program.function_3::<f64, bool, f64>()?
+            program.function_4::<f64, bool, f64, *const f64>()?
         };
 
         // cannot be empty at this point
         let tile = raster.into_materialized_tile();
 
-        let data = tile
-            .grid_array
+        let mut data = tile_buffer_pool.take_with_capacity(tile.grid_array.data.len());
+        tile.grid_array
             .data
             .par_iter()
             .with_min_len(tile.grid_array.grid_shape().axis_size_x())
@@ -197,10 +271,17 @@ where
                     return out_no_data;
                 }
 
-                let result = expression(a.as_(), is_no_data, out_no_data.as_());
+                let result = expression(
+                    a.as_(),
+                    is_no_data,
+                    out_no_data.as_(),
+                    tile_properties.as_ptr(),
+                );
                 TO::from_(result)
             })
-            .collect();
+            .collect_into_vec(&mut data);
+
+        tile_buffer_pool.give_back(tile.grid_array.data);
 
         Result::<Vec<TO>>::Ok(data)
     }
@@ -232,6 +313,20 @@ where
         Ok(stream.boxed())
     }
 
+    #[inline]
+    async fn all_queries_empty<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        let empty = try_join!(
+            self.0.query_is_empty(query, ctx),
+            self.1.query_is_empty(query, ctx)
+        )?;
+
+        Ok(empty.0 && empty.1)
+    }
+
     #[inline]
     fn all_empty(tuple: &Self::Tuple) -> bool {
         tuple.0.grid_array.is_empty() && tuple.1.grid_array.is_empty()
@@ -254,23 +349,31 @@ where
         )
     }
 
+    #[inline]
+    fn properties(tuple: &Self::Tuple) -> Vec<&RasterProperties> {
+        vec![&tuple.0.properties, &tuple.1.properties]
+    }
+
     #[inline]
     fn compute_expression(
         rasters: Self::Tuple,
         program: &LinkedExpression,
         map_no_data: bool,
         out_no_data: TO,
+        tile_properties: &[f64],
+        tile_buffer_pool: &TileBufferPool,
     ) -> Result<Vec<TO>> {
         let expression = unsafe {
             // we have to "trust" that the function has the signature we expect
-            program.function_5::<f64, bool, f64, bool, f64>()?
+            program.function_6::<f64, bool, f64, bool, f64, *const f64>()?
         };
 
         // TODO: allow iterating over empty rasters
         let tile_0 = rasters.0.into_materialized_tile();
         let tile_1 = rasters.1.into_materialized_tile();
 
-        let data = (&tile_0.grid_array.data, &tile_1.grid_array.data)
+        let mut data = tile_buffer_pool.take_with_capacity(tile_0.grid_array.data.len());
+        (&tile_0.grid_array.data, &tile_1.grid_array.data)
             .into_par_iter()
             .with_min_len(tile_0.grid_array.grid_shape().axis_size_x())
             .map(|(a, b)| {
@@ -287,21 +390,42 @@ where
                     b.as_(),
                     is_b_no_data,
                     out_no_data.as_(),
+                    tile_properties.as_ptr(),
                 );
                 TO::from_(result)
             })
-            .collect();
+            .collect_into_vec(&mut data);
+
+        tile_buffer_pool.give_back(tile_0.grid_array.data);
+        tile_buffer_pool.give_back(tile_1.grid_array.data);
 
         Result::<Vec<TO>>::Ok(data)
     }
 }
 
-type Function3 = fn(f64, bool, f64, bool, f64, bool, f64) -> f64;
-type Function4 = fn(f64, bool, f64, bool, f64, bool, f64, bool, f64) -> f64;
-type Function5 = fn(f64, bool, f64, bool, f64, bool, f64, bool, f64, bool, f64) -> f64;
-type Function6 = fn(f64, bool, f64, bool, f64, bool, f64, bool, f64, bool, f64, bool, f64) -> f64;
-type Function7 =
-    fn(f64, bool, f64, bool, f64, bool, f64, bool, f64, bool, f64, bool, f64, bool, f64) -> f64;
+type Function3 = fn(f64, bool, f64, bool, f64, bool, f64, *const f64) -> f64;
+type Function4 = fn(f64, bool, f64, bool, f64, bool, f64, bool, f64, *const f64) -> f64;
+type Function5 = fn(f64, bool, f64, bool, f64, bool, f64, bool, f64, bool, f64, *const f64) -> f64;
+type Function6 =
+    fn(f64, bool, f64, bool, f64, bool, f64, bool, f64, bool, f64, bool, f64, *const f64) -> f64;
+type Function7 = fn(
+    f64,
+    bool,
+    f64,
+    bool,
+    f64,
+    bool,
+    f64,
+    bool,
+    f64,
+    bool,
+    f64,
+    bool,
+    f64,
+    bool,
+    f64,
+    *const f64,
+) -> f64;
 type Function8 = fn(
     f64,
     bool,
@@ -320,6 +444,7 @@ type Function8 = fn(
     f64,
     bool,
     f64,
+    *const f64,
 ) -> f64;
 
 macro_rules! impl_expression_tuple_processor {
@@ -375,6 +500,19 @@ macro_rules! impl_expression_tuple_processor {
                 Ok(stream.boxed())
             }
 
+            #[inline]
+            async fn all_queries_empty<'a>(
+                &'a self,
+                query: RasterQueryRectangle,
+                ctx: &'a dyn QueryContext,
+            ) -> Result<bool> {
+                let empty = try_join!(
+                    $( self.$I.query_is_empty(query, ctx) ),*
+                )?;
+
+                Ok($( empty.$I )&&*)
+            }
+
             #[inline]
             fn all_empty(tuple: &Self::Tuple) -> bool {
                 $( tuple.$I.grid_array.is_empty() )&&*
@@ -397,11 +535,18 @@ macro_rules! impl_expression_tuple_processor {
                 )
             }
 
+            #[inline]
+            fn properties(tuple: &Self::Tuple) -> Vec<&RasterProperties> {
+                vec![ $( &tuple.$I.properties ),* ]
+            }
+
             fn compute_expression(
                 rasters: Self::Tuple,
                 program: &LinkedExpression,
                 map_no_data: bool,
                 out_no_data: TO,
+                tile_properties: &[f64],
+                tile_buffer_pool: &TileBufferPool,
             ) -> Result<Vec<TO>> {
                 let expression: Symbol<$FN_T> = unsafe {
                     // we have to "trust" that the function has the signature we expect
@@ -409,13 +554,15 @@ macro_rules! impl_expression_tuple_processor {
                 };
 
                 let min_batch_size = rasters.0.grid_array.grid_shape().axis_size_x();
+                let number_of_pixels = rasters.0.grid_array.grid_shape().number_of_elements();
 
                 // TODO: allow iterating over empty rasters
                 $(
                     let $TILE = rasters.$I.into_materialized_tile();
                 )*
 
-                let data = (
+                let mut data = tile_buffer_pool.take_with_capacity(number_of_pixels);
+                (
                     $(
                         & $TILE.grid_array.data
                     ),*
@@ -437,10 +584,15 @@ macro_rules! impl_expression_tuple_processor {
                                 $IS_NODATA,
                             )*
                             out_no_data.as_(),
+                            tile_properties.as_ptr(),
                         );
                         TO::from_(result)
                     })
-                    .collect();
+                    .collect_into_vec(&mut data);
+
+                $(
+                    tile_buffer_pool.give_back($TILE.grid_array.data);
+                )*
 
                 Result::<Vec<TO>>::Ok(data)
             }
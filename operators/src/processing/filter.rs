@@ -0,0 +1,774 @@
+use crate::adapters::FeatureCollectionChunkMerger;
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, QueryContext,
+    QueryProcessor, ResultExtent, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error::Error;
+use crate::processing::column_filter::CompiledPredicate;
+use crate::processing::ColumnPredicate;
+use crate::util::input::StringOrNumber;
+use crate::util::Result;
+use arrow::array::{Array, BooleanArray};
+use arrow::compute::kernels::{boolean, comparison};
+use arrow::datatypes::DataType;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    DataCollection, FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications,
+    IntoGeometryIterator, MultiLineStringCollection, MultiPointCollection, MultiPolygonCollection,
+    VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataType, Geometry, MultiLineStringAccess, MultiPolygonAccess,
+    SpatialBounded, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// The filter keeps all features that satisfy a boolean expression over their attributes,
+/// geometries and time intervals.
+///
+/// The expression is evaluated in a single pass over the columns of each collection using
+/// Arrow's compute kernels, so it replaces chains of single-column filters.
+pub type Filter = Operator<FilterParams, SingleVectorSource>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterParams {
+    pub expression: FilterExpression,
+}
+
+/// A boolean expression on features.
+///
+/// Comparisons with null values are unknown, as in SQL. Features are only kept if the
+/// expression is true, i.e., neither false nor unknown.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum FilterExpression {
+    /// All expressions must hold, an empty list is always true
+    And {
+        expressions: Vec<FilterExpression>,
+    },
+    /// At least one expression must hold, an empty list is always false
+    Or {
+        expressions: Vec<FilterExpression>,
+    },
+    Not {
+        expression: Box<FilterExpression>,
+    },
+    /// Compares the values of a column with a constant
+    Comparison {
+        column: String,
+        operator: ComparisonOperator,
+        value: StringOrNumber,
+    },
+    /// Compares the values of two columns of the same feature
+    ColumnComparison {
+        left: String,
+        operator: ComparisonOperator,
+        right: String,
+    },
+    /// A predicate of the `ColumnFilter`, e.g., an IN-list or a regular expression
+    Column {
+        predicate: ColumnPredicate,
+    },
+    /// The bounding box of the feature's geometry intersects `bbox`
+    IntersectsBbox {
+        bbox: BoundingBox2D,
+    },
+    /// The time interval of the feature intersects `time`
+    IntersectsTime {
+        time: TimeInterval,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ComparisonOperator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl ComparisonOperator {
+    fn compare_arrays(
+        self,
+        left: &dyn Array,
+        right: &dyn Array,
+    ) -> arrow::error::Result<BooleanArray> {
+        match self {
+            ComparisonOperator::Equal => comparison::eq_dyn(left, right),
+            ComparisonOperator::NotEqual => comparison::neq_dyn(left, right),
+            ComparisonOperator::Less => comparison::lt_dyn(left, right),
+            ComparisonOperator::LessOrEqual => comparison::lt_eq_dyn(left, right),
+            ComparisonOperator::Greater => comparison::gt_dyn(left, right),
+            ComparisonOperator::GreaterOrEqual => comparison::gt_eq_dyn(left, right),
+        }
+    }
+
+    fn compare_scalar(
+        self,
+        left: &dyn Array,
+        right: &Scalar,
+    ) -> arrow::error::Result<BooleanArray> {
+        match right {
+            Scalar::Int(right) => self.compare_number(left, *right),
+            Scalar::Float(right) => self.compare_number(left, *right),
+            Scalar::Text(right) => match self {
+                ComparisonOperator::Equal => comparison::eq_dyn_utf8_scalar(left, right),
+                ComparisonOperator::NotEqual => comparison::neq_dyn_utf8_scalar(left, right),
+                ComparisonOperator::Less => comparison::lt_dyn_utf8_scalar(left, right),
+                ComparisonOperator::LessOrEqual => comparison::lt_eq_dyn_utf8_scalar(left, right),
+                ComparisonOperator::Greater => comparison::gt_dyn_utf8_scalar(left, right),
+                ComparisonOperator::GreaterOrEqual => {
+                    comparison::gt_eq_dyn_utf8_scalar(left, right)
+                }
+            },
+        }
+    }
+
+    fn compare_number<T>(self, left: &dyn Array, right: T) -> arrow::error::Result<BooleanArray>
+    where
+        T: num_traits::ToPrimitive + std::fmt::Debug,
+    {
+        match self {
+            ComparisonOperator::Equal => comparison::eq_dyn_scalar(left, right),
+            ComparisonOperator::NotEqual => comparison::neq_dyn_scalar(left, right),
+            ComparisonOperator::Less => comparison::lt_dyn_scalar(left, right),
+            ComparisonOperator::LessOrEqual => comparison::lt_eq_dyn_scalar(left, right),
+            ComparisonOperator::Greater => comparison::gt_dyn_scalar(left, right),
+            ComparisonOperator::GreaterOrEqual => comparison::gt_eq_dyn_scalar(left, right),
+        }
+    }
+}
+
+impl FilterExpression {
+    /// Checks the expression against the `columns` and determines the types in which values are compared
+    fn compile(&self, columns: &HashMap<String, FeatureDataType>) -> Result<CompiledExpression> {
+        let column_type = |column: &String| {
+            columns
+                .get(column)
+                .copied()
+                .ok_or_else(|| Error::ColumnDoesNotExist {
+                    column: column.clone(),
+                })
+        };
+
+        Ok(match self {
+            FilterExpression::And { expressions } => CompiledExpression::And(
+                expressions
+                    .iter()
+                    .map(|expression| expression.compile(columns))
+                    .collect::<Result<_>>()?,
+            ),
+            FilterExpression::Or { expressions } => CompiledExpression::Or(
+                expressions
+                    .iter()
+                    .map(|expression| expression.compile(columns))
+                    .collect::<Result<_>>()?,
+            ),
+            FilterExpression::Not { expression } => {
+                CompiledExpression::Not(Box::new(expression.compile(columns)?))
+            }
+            FilterExpression::Comparison {
+                column,
+                operator,
+                value,
+            } => {
+                let (compare_as, value) = match (column_type(column)?, value) {
+                    (FeatureDataType::Int | FeatureDataType::DateTime, StringOrNumber::Int(v)) => {
+                        (DataType::Int64, Scalar::Int(*v))
+                    }
+                    (
+                        FeatureDataType::Int | FeatureDataType::Float,
+                        StringOrNumber::Int(_) | StringOrNumber::Float(_),
+                    ) => (DataType::Float64, Scalar::Float(f64::try_from(value)?)),
+                    (FeatureDataType::Text, StringOrNumber::String(v)) => {
+                        (DataType::Utf8, Scalar::Text(v.clone()))
+                    }
+                    // compares the categories instead of all values
+                    (FeatureDataType::Category, StringOrNumber::String(v)) => (
+                        FeatureDataType::Category.arrow_data_type(),
+                        Scalar::Text(v.clone()),
+                    ),
+                    (data_type, _) => {
+                        return Err(Error::InvalidOperatorSpec {
+                            reason: format!(
+                                "Column '{}' of type {:?} cannot be compared with {:?}.",
+                                column, data_type, value
+                            ),
+                        })
+                    }
+                };
+
+                CompiledExpression::Comparison {
+                    column: column.clone(),
+                    operator: *operator,
+                    compare_as,
+                    value,
+                }
+            }
+            FilterExpression::ColumnComparison {
+                left,
+                operator,
+                right,
+            } => {
+                let compare_as = match (column_type(left)?, column_type(right)?) {
+                    (FeatureDataType::Int, FeatureDataType::Int)
+                    | (FeatureDataType::DateTime, FeatureDataType::DateTime) => DataType::Int64,
+                    (
+                        FeatureDataType::Int | FeatureDataType::Float,
+                        FeatureDataType::Int | FeatureDataType::Float,
+                    ) => DataType::Float64,
+                    (
+                        FeatureDataType::Text | FeatureDataType::Category,
+                        FeatureDataType::Text | FeatureDataType::Category,
+                    ) => DataType::Utf8,
+                    (FeatureDataType::Bool, FeatureDataType::Bool) => DataType::Boolean,
+                    (left_type, right_type) => {
+                        return Err(Error::InvalidOperatorSpec {
+                            reason: format!(
+                            "Columns '{}' of type {:?} and '{}' of type {:?} cannot be compared.",
+                            left, left_type, right, right_type
+                        ),
+                        })
+                    }
+                };
+
+                CompiledExpression::ColumnComparison {
+                    left: left.clone(),
+                    operator: *operator,
+                    right: right.clone(),
+                    compare_as,
+                }
+            }
+            FilterExpression::Column { predicate } => {
+                CompiledExpression::Column(predicate.compile(columns)?)
+            }
+            FilterExpression::IntersectsBbox { bbox } => CompiledExpression::IntersectsBbox(*bbox),
+            FilterExpression::IntersectsTime { time } => CompiledExpression::IntersectsTime(*time),
+        })
+    }
+
+    fn has_spatial_predicate(&self) -> bool {
+        match self {
+            FilterExpression::And { expressions } | FilterExpression::Or { expressions } => {
+                expressions.iter().any(Self::has_spatial_predicate)
+            }
+            FilterExpression::Not { expression } => expression.has_spatial_predicate(),
+            FilterExpression::IntersectsBbox { .. } => true,
+            FilterExpression::Comparison { .. }
+            | FilterExpression::ColumnComparison { .. }
+            | FilterExpression::Column { .. }
+            | FilterExpression::IntersectsTime { .. } => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Scalar {
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+/// A `FilterExpression` whose columns and types were checked against the source
+#[derive(Debug, Clone)]
+enum CompiledExpression {
+    And(Vec<CompiledExpression>),
+    Or(Vec<CompiledExpression>),
+    Not(Box<CompiledExpression>),
+    Comparison {
+        column: String,
+        operator: ComparisonOperator,
+        /// the type the column is cast to before comparing it with `value`
+        compare_as: DataType,
+        value: Scalar,
+    },
+    ColumnComparison {
+        left: String,
+        operator: ComparisonOperator,
+        right: String,
+        /// the type both columns are cast to before comparing them
+        compare_as: DataType,
+    },
+    Column(CompiledPredicate),
+    IntersectsBbox(BoundingBox2D),
+    IntersectsTime(TimeInterval),
+}
+
+impl CompiledExpression {
+    /// Computes for each feature whether it satisfies the expression, null means unknown
+    fn evaluate<G>(&self, collection: &FeatureCollection<G>) -> Result<BooleanArray>
+    where
+        G: Geometry + ArrowTyped,
+        FeatureCollection<G>: FeatureBounds,
+    {
+        Ok(match self {
+            CompiledExpression::And(expressions) => {
+                let mut result = BooleanArray::from(vec![true; collection.len()]);
+                for expression in expressions {
+                    result = boolean::and_kleene(&result, &expression.evaluate(collection)?)?;
+                }
+                result
+            }
+            CompiledExpression::Or(expressions) => {
+                let mut result = BooleanArray::from(vec![false; collection.len()]);
+                for expression in expressions {
+                    result = boolean::or_kleene(&result, &expression.evaluate(collection)?)?;
+                }
+                result
+            }
+            CompiledExpression::Not(expression) => boolean::not(&expression.evaluate(collection)?)?,
+            CompiledExpression::Comparison {
+                column,
+                operator,
+                compare_as,
+                value,
+            } => {
+                let array = arrow::compute::cast(collection.column_array(column)?, compare_as)?;
+                operator.compare_scalar(array.as_ref(), value)?
+            }
+            CompiledExpression::ColumnComparison {
+                left,
+                operator,
+                right,
+                compare_as,
+            } => {
+                let left = arrow::compute::cast(collection.column_array(left)?, compare_as)?;
+                let right = arrow::compute::cast(collection.column_array(right)?, compare_as)?;
+                operator.compare_arrays(left.as_ref(), right.as_ref())?
+            }
+            CompiledExpression::Column(predicate) => {
+                BooleanArray::from(predicate.evaluate(collection)?)
+            }
+            CompiledExpression::IntersectsBbox(bbox) => {
+                BooleanArray::from(collection.features_intersecting_bbox(bbox)?)
+            }
+            CompiledExpression::IntersectsTime(time) => collection
+                .time_intervals()
+                .iter()
+                .map(|t| Some(t.intersects(time)))
+                .collect(),
+        })
+    }
+}
+
+/// Computes which features' bounding boxes intersect a bounding box
+trait FeatureBounds {
+    fn features_intersecting_bbox(&self, bbox: &BoundingBox2D) -> Result<Vec<bool>>;
+}
+
+impl FeatureBounds for MultiPointCollection {
+    fn features_intersecting_bbox(&self, bbox: &BoundingBox2D) -> Result<Vec<bool>> {
+        Ok(self
+            .geometries()
+            .map(|geometry| geometry.spatial_bounds().intersects_bbox(bbox))
+            .collect())
+    }
+}
+
+impl FeatureBounds for MultiLineStringCollection {
+    fn features_intersecting_bbox(&self, bbox: &BoundingBox2D) -> Result<Vec<bool>> {
+        Ok(self
+            .geometries()
+            .map(|geometry| {
+                BoundingBox2D::from_coord_ref_iter(
+                    geometry.lines().iter().flat_map(|line| line.iter()),
+                )
+                .map_or(false, |bounds| bounds.intersects_bbox(bbox))
+            })
+            .collect())
+    }
+}
+
+impl FeatureBounds for MultiPolygonCollection {
+    fn features_intersecting_bbox(&self, bbox: &BoundingBox2D) -> Result<Vec<bool>> {
+        Ok(self
+            .geometries()
+            .map(|geometry| {
+                BoundingBox2D::from_coord_ref_iter(
+                    geometry
+                        .polygons()
+                        .iter()
+                        .flat_map(|polygon| polygon.iter())
+                        .flat_map(|ring| ring.iter()),
+                )
+                .map_or(false, |bounds| bounds.intersects_bbox(bbox))
+            })
+            .collect())
+    }
+}
+
+impl FeatureBounds for DataCollection {
+    fn features_intersecting_bbox(&self, _bbox: &BoundingBox2D) -> Result<Vec<bool>> {
+        Err(Error::InvalidVectorType {
+            expected: "a collection with geometries".to_string(),
+            found: VectorDataType::Data.to_string(),
+        })
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Filter {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let result_descriptor = vector_source.result_descriptor().clone();
+
+        if result_descriptor.data_type == VectorDataType::Data
+            && self.params.expression.has_spatial_predicate()
+        {
+            return Err(Error::InvalidOperatorSpec {
+                reason: "Spatial predicates require a source with geometries.".to_string(),
+            });
+        }
+
+        let expression = self.params.expression.compile(&result_descriptor.columns)?;
+
+        let initialized_operator = InitializedFilter {
+            result_descriptor,
+            vector_source,
+            expression: Arc::new(expression),
+        };
+
+        Ok(initialized_operator.boxed())
+    }
+}
+
+pub struct InitializedFilter {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    expression: Arc<CompiledExpression>,
+}
+
+impl InitializedVectorOperator for InitializedFilter {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => FilterProcessor::new(source, self.expression.clone()).boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.vector_source.extent()
+    }
+}
+
+pub struct FilterProcessor<G> {
+    vector_type: PhantomData<FeatureCollection<G>>,
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    expression: Arc<CompiledExpression>,
+}
+
+impl<G> FilterProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send,
+{
+    fn new(
+        source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+        expression: Arc<CompiledExpression>,
+    ) -> Self {
+        Self {
+            vector_type: Default::default(),
+            source,
+            expression,
+        }
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for FilterProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+    FeatureCollection<G>: FeatureBounds,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let filter_stream = self.source.query(query, ctx).await?.map(move |collection| {
+            let collection = collection?;
+
+            // unknown results do not satisfy the expression
+            let mask =
+                arrow::compute::prep_null_mask_filter(&self.expression.evaluate(&collection)?);
+
+            collection.filter(mask).map_err(Into::into)
+        });
+
+        let merged_chunks_stream =
+            FeatureCollectionChunkMerger::new(filter_stream.fuse(), ctx.chunk_byte_size().into());
+
+        Ok(merged_chunks_stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::primitives::{FeatureData, MultiPoint, SpatialResolution};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn collection() -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1), (2.0, 2.1), (3.0, 3.1)]).unwrap(),
+            vec![
+                TimeInterval::new(0, 10).unwrap(),
+                TimeInterval::new(10, 20).unwrap(),
+                TimeInterval::new(20, 30).unwrap(),
+                TimeInterval::new(30, 40).unwrap(),
+            ],
+            [
+                (
+                    "population".to_string(),
+                    FeatureData::NullableInt(vec![Some(100), Some(2000), None, Some(50)]),
+                ),
+                (
+                    "area".to_string(),
+                    FeatureData::Float(vec![10.0, 20.0, 30.0, 40.0]),
+                ),
+                (
+                    "landcover".to_string(),
+                    FeatureData::Category(vec![
+                        "forest".to_string(),
+                        "water".to_string(),
+                        "forest".to_string(),
+                        "field".to_string(),
+                    ]),
+                ),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        )
+        .unwrap()
+    }
+
+    async fn filter(expression: FilterExpression) -> Result<Vec<MultiPointCollection>> {
+        let source = MockFeatureCollectionSource::single(collection()).boxed();
+
+        let filter = Filter {
+            params: FilterParams { expression },
+            sources: source.into(),
+        }
+        .boxed();
+
+        let initialized = filter
+            .initialize(&MockExecutionContext::test_default())
+            .await?;
+
+        let processor = initialized.query_processor()?.multi_point().unwrap();
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
+        };
+
+        let ctx = MockQueryContext::test_default();
+
+        let stream = processor.query(query_rectangle, &ctx).await?;
+
+        stream.collect::<Vec<_>>().await.into_iter().collect()
+    }
+
+    #[test]
+    fn serde() {
+        let params = FilterParams {
+            expression: FilterExpression::Not {
+                expression: Box::new(FilterExpression::Comparison {
+                    column: "population".to_string(),
+                    operator: ComparisonOperator::GreaterOrEqual,
+                    value: 1000_i64.into(),
+                }),
+            },
+        };
+
+        let json = serde_json::json!({
+            "expression": {
+                "type": "not",
+                "expression": {
+                    "type": "comparison",
+                    "column": "population",
+                    "operator": "greaterOrEqual",
+                    "value": 1000
+                }
+            }
+        });
+
+        assert_eq!(serde_json::to_value(&params).unwrap(), json);
+        assert_eq!(
+            serde_json::from_value::<FilterParams>(json).unwrap(),
+            params
+        );
+    }
+
+    #[tokio::test]
+    async fn comparisons() {
+        let result = filter(FilterExpression::Comparison {
+            column: "population".to_string(),
+            operator: ComparisonOperator::Less,
+            value: 1000.5.into(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0],
+            collection().filter(vec![true, false, false, true]).unwrap()
+        );
+
+        let result = filter(FilterExpression::Comparison {
+            column: "landcover".to_string(),
+            operator: ComparisonOperator::NotEqual,
+            value: "forest".into(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result[0],
+            collection().filter(vec![false, true, false, true]).unwrap()
+        );
+
+        let result = filter(FilterExpression::ColumnComparison {
+            left: "area".to_string(),
+            operator: ComparisonOperator::Less,
+            right: "population".to_string(),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result[0],
+            collection().filter(vec![true, true, false, true]).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn nulls_are_unknown() {
+        // the feature without population is neither kept by the comparison nor by its negation
+        let result = filter(FilterExpression::Not {
+            expression: Box::new(FilterExpression::Comparison {
+                column: "population".to_string(),
+                operator: ComparisonOperator::Greater,
+                value: 1000_i64.into(),
+            }),
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result[0],
+            collection().filter(vec![true, false, false, true]).unwrap()
+        );
+
+        let result = filter(FilterExpression::Or {
+            expressions: vec![
+                FilterExpression::Comparison {
+                    column: "population".to_string(),
+                    operator: ComparisonOperator::Greater,
+                    value: 1000_i64.into(),
+                },
+                FilterExpression::Column {
+                    predicate: ColumnPredicate::IsNull {
+                        column: "population".to_string(),
+                    },
+                },
+            ],
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result[0],
+            collection().filter(vec![false, true, true, false]).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn spatial_and_temporal() {
+        let result = filter(FilterExpression::And {
+            expressions: vec![
+                FilterExpression::IntersectsBbox {
+                    bbox: BoundingBox2D::new((0.5, 0.5).into(), (2.5, 2.5).into()).unwrap(),
+                },
+                FilterExpression::IntersectsTime {
+                    time: TimeInterval::new(15, 25).unwrap(),
+                },
+                FilterExpression::Column {
+                    predicate: ColumnPredicate::In {
+                        column: "landcover".to_string(),
+                        values: vec!["forest".into(), "water".into()],
+                    },
+                },
+            ],
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            result[0],
+            collection().filter(vec![false, true, true, false]).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_expressions() {
+        assert!(filter(FilterExpression::Comparison {
+            column: "landcover".to_string(),
+            operator: ComparisonOperator::Equal,
+            value: 1_i64.into(),
+        })
+        .await
+        .is_err());
+
+        assert!(filter(FilterExpression::ColumnComparison {
+            left: "landcover".to_string(),
+            operator: ComparisonOperator::Equal,
+            right: "area".to_string(),
+        })
+        .await
+        .is_err());
+
+        assert!(matches!(
+            filter(FilterExpression::Not {
+                expression: Box::new(FilterExpression::Comparison {
+                    column: "foo".to_string(),
+                    operator: ComparisonOperator::Equal,
+                    value: 1_i64.into(),
+                }),
+            })
+            .await,
+            Err(Error::ColumnDoesNotExist { column }) if column == "foo"
+        ));
+    }
+}
@@ -0,0 +1,717 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, BooleanArray, Date64Array, Float64Array, Int64Array, StringArray,
+};
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::primitives::{BoundingBox2D, VectorQueryRectangle};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedTableOperator, Operator, QueryContext,
+    QueryProcessor, SingleVectorOrTableSource, TableColumn, TableDataType, TableOperator,
+    TableQueryProcessor, TableResultDescriptor,
+};
+use crate::error::{self, Error};
+use crate::processing::attribute_table::initialize_as_table;
+use crate::util::{duplicate_or_empty_str_slice, DuplicateOrEmpty, Result};
+
+/// The group-by operator groups the rows of a table or the features of a vector source by the values
+/// of its `group_by` columns and computes the `aggregates` of each group.
+///
+/// The output is a table with the group columns followed by one column per aggregate.
+/// Groups are output in the order of their first occurrence.
+pub type GroupBy = Operator<GroupByParams, SingleVectorOrTableSource>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupByParams {
+    /// The columns whose values form the groups, all rows form a single group if empty
+    pub group_by: Vec<String>,
+    pub aggregates: Vec<GroupByAggregate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupByAggregate {
+    pub column: String,
+    pub function: GroupByAggregateFunction,
+    /// The name of the output column, `<column>_<function>` if not specified
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl GroupByAggregate {
+    fn output_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("{}_{}", self.column, self.function.name()))
+    }
+}
+
+/// The aggregates of a group ignore null values.
+/// All functions except for `count` require a numeric column and result in null for groups without values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupByAggregateFunction {
+    Count,
+    Sum,
+    Mean,
+    Min,
+    Max,
+}
+
+impl GroupByAggregateFunction {
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            GroupByAggregateFunction::Count => "count",
+            GroupByAggregateFunction::Sum => "sum",
+            GroupByAggregateFunction::Mean => "mean",
+            GroupByAggregateFunction::Min => "min",
+            GroupByAggregateFunction::Max => "max",
+        }
+    }
+
+    pub(super) fn output_data_type(self) -> TableDataType {
+        match self {
+            GroupByAggregateFunction::Count => TableDataType::Int,
+            _ => TableDataType::Float,
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl TableOperator for GroupBy {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedTableOperator>> {
+        let params = self.params;
+
+        let output_names: Vec<String> = params
+            .group_by
+            .iter()
+            .cloned()
+            .chain(params.aggregates.iter().map(GroupByAggregate::output_name))
+            .collect();
+
+        match duplicate_or_empty_str_slice(&output_names) {
+            DuplicateOrEmpty::Ok => (),
+            DuplicateOrEmpty::Duplicate(name) => {
+                return Err(Error::InvalidOperatorSpec {
+                    reason: format!("The output column '{}' is not unique.", name),
+                })
+            }
+            DuplicateOrEmpty::Empty => {
+                return Err(Error::InvalidOperatorSpec {
+                    reason: "Output columns must not be empty.".to_string(),
+                })
+            }
+        }
+
+        let mut used_columns: Vec<String> = params.group_by.clone();
+        for aggregate in &params.aggregates {
+            if !used_columns.contains(&aggregate.column) {
+                used_columns.push(aggregate.column.clone());
+            }
+        }
+
+        let source = initialize_as_table(self.sources.source, used_columns, context).await?;
+
+        let source_columns = &source.result_descriptor().columns;
+        let column_index = |name: &String| {
+            source_columns
+                .iter()
+                .position(|column| &column.name == name)
+                .ok_or_else(|| Error::ColumnDoesNotExist {
+                    column: name.clone(),
+                })
+        };
+
+        let mut keys = Vec::with_capacity(params.group_by.len());
+        let mut columns = Vec::with_capacity(output_names.len());
+
+        for name in &params.group_by {
+            let index = column_index(name)?;
+            let data_type = source_columns[index].data_type;
+
+            ensure!(
+                data_type != TableDataType::Float,
+                error::InvalidOperatorSpec {
+                    reason: format!("Cannot group by the float column '{}'.", name),
+                }
+            );
+
+            keys.push(GroupKeyColumn { index, data_type });
+            columns.push(TableColumn {
+                name: name.clone(),
+                data_type,
+            });
+        }
+
+        let mut aggregates = Vec::with_capacity(params.aggregates.len());
+
+        for aggregate in &params.aggregates {
+            let index = column_index(&aggregate.column)?;
+            let data_type = source_columns[index].data_type;
+
+            ensure!(
+                aggregate.function == GroupByAggregateFunction::Count
+                    || matches!(data_type, TableDataType::Float | TableDataType::Int),
+                error::InvalidOperatorSpec {
+                    reason: format!(
+                        "Cannot compute the {} of the non-numeric column '{}'.",
+                        aggregate.function.name(),
+                        aggregate.column
+                    ),
+                }
+            );
+
+            aggregates.push(AggregateColumn {
+                index,
+                data_type,
+                function: aggregate.function,
+            });
+            columns.push(TableColumn {
+                name: aggregate.output_name(),
+                data_type: aggregate.function.output_data_type(),
+            });
+        }
+
+        let result_descriptor = TableResultDescriptor { columns };
+
+        Ok(InitializedGroupBy {
+            schema: Arc::new(result_descriptor.arrow_schema()),
+            result_descriptor,
+            source,
+            keys: Arc::new(keys),
+            aggregates: Arc::new(aggregates),
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedGroupBy {
+    result_descriptor: TableResultDescriptor,
+    schema: SchemaRef,
+    source: Box<dyn InitializedTableOperator>,
+    keys: Arc<Vec<GroupKeyColumn>>,
+    aggregates: Arc<Vec<AggregateColumn>>,
+}
+
+impl InitializedTableOperator for InitializedGroupBy {
+    fn result_descriptor(&self) -> &TableResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<Box<dyn TableQueryProcessor>> {
+        Ok(TableQueryProcessor::boxed(GroupByProcessor {
+            source: self.source.query_processor()?,
+            schema: self.schema.clone(),
+            keys: self.keys.clone(),
+            aggregates: self.aggregates.clone(),
+        }))
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
+}
+
+/// A column of the source that is part of the group key
+#[derive(Debug, Clone, Copy)]
+pub(super) struct GroupKeyColumn {
+    pub index: usize,
+    pub data_type: TableDataType,
+}
+
+/// A column of the source that is aggregated
+#[derive(Debug, Clone, Copy)]
+struct AggregateColumn {
+    index: usize,
+    data_type: TableDataType,
+    function: GroupByAggregateFunction,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) enum GroupKeyValue {
+    Null,
+    Text(String),
+    Int(i64),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Accumulator {
+    count: i64,
+    sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for Accumulator {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            sum: 0.,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Accumulator {
+    pub fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    pub fn result(&self, function: GroupByAggregateFunction) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+
+        Some(match function {
+            GroupByAggregateFunction::Count => self.count as f64,
+            GroupByAggregateFunction::Sum => self.sum,
+            GroupByAggregateFunction::Mean => self.sum / self.count as f64,
+            GroupByAggregateFunction::Min => self.min,
+            GroupByAggregateFunction::Max => self.max,
+        })
+    }
+}
+
+/// The groups seen so far in the order of their first occurrence
+#[derive(Default)]
+struct Groups {
+    indices: HashMap<Vec<GroupKeyValue>, usize>,
+    keys: Vec<Vec<GroupKeyValue>>,
+    accumulators: Vec<Vec<Accumulator>>,
+}
+
+impl Groups {
+    fn add_batch(
+        &mut self,
+        batch: &RecordBatch,
+        keys: &[GroupKeyColumn],
+        aggregates: &[AggregateColumn],
+    ) -> Result<()> {
+        let key_arrays: Vec<&ArrayRef> = keys.iter().map(|key| batch.column(key.index)).collect();
+        let value_arrays: Vec<&ArrayRef> = aggregates
+            .iter()
+            .map(|aggregate| batch.column(aggregate.index))
+            .collect();
+
+        for row in 0..batch.num_rows() {
+            let key = keys
+                .iter()
+                .zip(&key_arrays)
+                .map(|(key, array)| group_key_value(array, key.data_type, row))
+                .collect::<Result<Vec<_>>>()?;
+
+            let group = if let Some(&group) = self.indices.get(&key) {
+                group
+            } else {
+                let group = self.keys.len();
+                self.indices.insert(key.clone(), group);
+                self.keys.push(key);
+                self.accumulators
+                    .push(vec![Accumulator::default(); aggregates.len()]);
+                group
+            };
+
+            for ((aggregate, array), accumulator) in aggregates
+                .iter()
+                .zip(&value_arrays)
+                .zip(&mut self.accumulators[group])
+            {
+                if array.is_null(row) {
+                    continue;
+                }
+
+                let value = match aggregate.data_type {
+                    TableDataType::Float => downcast::<Float64Array>(array)?.value(row),
+                    TableDataType::Int => downcast::<Int64Array>(array)?.value(row) as f64,
+                    // only counted, so the value does not matter
+                    _ => 0.,
+                };
+
+                accumulator.add(value);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn into_record_batch(
+        self,
+        schema: SchemaRef,
+        keys: &[GroupKeyColumn],
+        aggregates: &[AggregateColumn],
+    ) -> Result<RecordBatch> {
+        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(keys.len() + aggregates.len());
+
+        for (i, key) in keys.iter().enumerate() {
+            let values = self.keys.iter().map(|group| &group[i]);
+
+            arrays.push(match key.data_type {
+                TableDataType::Text => Arc::new(StringArray::from(
+                    values
+                        .map(|value| match value {
+                            GroupKeyValue::Text(value) => Some(value.as_str()),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>(),
+                )),
+                TableDataType::Bool => Arc::new(BooleanArray::from(
+                    values
+                        .map(|value| match value {
+                            GroupKeyValue::Bool(value) => Some(*value),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>(),
+                )),
+                data_type => {
+                    let values = values
+                        .map(|value| match value {
+                            GroupKeyValue::Int(value) => Some(*value),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+
+                    if data_type == TableDataType::DateTime {
+                        Arc::new(Date64Array::from(values))
+                    } else {
+                        Arc::new(Int64Array::from(values))
+                    }
+                }
+            });
+        }
+
+        for (i, aggregate) in aggregates.iter().enumerate() {
+            let results = self
+                .accumulators
+                .iter()
+                .map(|accumulators| accumulators[i].result(aggregate.function));
+
+            arrays.push(if aggregate.function == GroupByAggregateFunction::Count {
+                // groups without values have a count of zero instead of null
+                Arc::new(Int64Array::from(
+                    results
+                        .map(|count| count.unwrap_or_default() as i64)
+                        .collect::<Vec<_>>(),
+                ))
+            } else {
+                Arc::new(Float64Array::from(results.collect::<Vec<_>>()))
+            });
+        }
+
+        if arrays.is_empty() {
+            return Ok(RecordBatch::new_empty(schema));
+        }
+
+        RecordBatch::try_new(schema, arrays).map_err(Into::into)
+    }
+}
+
+pub(super) fn downcast<T: 'static>(array: &ArrayRef) -> Result<&T> {
+    array
+        .as_any()
+        .downcast_ref::<T>()
+        .ok_or_else(|| Error::InvalidOperatorSpec {
+            reason: "The record batch does not match the schema of the table.".to_string(),
+        })
+}
+
+pub(super) fn group_key_value(
+    array: &ArrayRef,
+    data_type: TableDataType,
+    row: usize,
+) -> Result<GroupKeyValue> {
+    if array.is_null(row) {
+        return Ok(GroupKeyValue::Null);
+    }
+
+    Ok(match data_type {
+        TableDataType::Text => {
+            GroupKeyValue::Text(downcast::<StringArray>(array)?.value(row).to_string())
+        }
+        TableDataType::Int => GroupKeyValue::Int(downcast::<Int64Array>(array)?.value(row)),
+        TableDataType::DateTime => GroupKeyValue::Int(downcast::<Date64Array>(array)?.value(row)),
+        TableDataType::Bool => GroupKeyValue::Bool(downcast::<BooleanArray>(array)?.value(row)),
+        TableDataType::Float => {
+            return Err(Error::InvalidOperatorSpec {
+                reason: "Cannot group by float columns.".to_string(),
+            })
+        }
+    })
+}
+
+pub struct GroupByProcessor {
+    source: Box<dyn TableQueryProcessor>,
+    schema: SchemaRef,
+    keys: Arc<Vec<GroupKeyColumn>>,
+    aggregates: Arc<Vec<AggregateColumn>>,
+}
+
+#[async_trait]
+impl QueryProcessor for GroupByProcessor {
+    type Output = RecordBatch;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let mut batches = self.source.query(query, ctx).await?;
+
+        let stream = futures::stream::once(async move {
+            let mut groups = Groups::default();
+
+            while let Some(batch) = batches.next().await {
+                groups.add_batch(&batch?, &self.keys, &self.aggregates)?;
+            }
+
+            groups.into_record_batch(self.schema.clone(), &self.keys, &self.aggregates)
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use crate::processing::{AttributeTable, AttributeTableParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn collection(stations: &[&str], values: &[Option<f64>]) -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1); stations.len()]).unwrap(),
+            vec![TimeInterval::default(); stations.len()],
+            [
+                (
+                    "station".to_string(),
+                    FeatureData::Category(stations.iter().map(ToString::to_string).collect()),
+                ),
+                (
+                    "temperature".to_string(),
+                    FeatureData::NullableFloat(values.to_vec()),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap()
+    }
+
+    /// Two chunks to check that groups are aggregated across chunks
+    fn source() -> Box<dyn VectorOperator> {
+        MockFeatureCollectionSource::multiple(vec![
+            collection(&["a", "b", "a"], &[Some(1.), Some(10.), Some(3.)]),
+            collection(&["b", "c", "a"], &[Some(20.), None, Some(5.)]),
+        ])
+        .boxed()
+    }
+
+    fn aggregate(function: GroupByAggregateFunction) -> GroupByAggregate {
+        GroupByAggregate {
+            column: "temperature".to_string(),
+            function,
+            name: None,
+        }
+    }
+
+    async fn query(operator: Box<dyn TableOperator>) -> Result<Vec<RecordBatch>> {
+        let initialized = operator
+            .initialize(&MockExecutionContext::test_default())
+            .await?;
+
+        let processor = initialized.query_processor()?;
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: Default::default(),
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: None,
+        };
+        let ctx = MockQueryContext::test_default();
+
+        processor
+            .table_query(query, &ctx)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    fn float_column(batch: &RecordBatch, index: usize) -> Vec<Option<f64>> {
+        batch
+            .column(index)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap()
+            .iter()
+            .collect()
+    }
+
+    #[test]
+    fn serde() {
+        let params: GroupByParams = serde_json::from_value(serde_json::json!({
+            "groupBy": ["station"],
+            "aggregates": [
+                { "column": "temperature", "function": "mean" },
+                { "column": "temperature", "function": "count", "name": "n" }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            params,
+            GroupByParams {
+                group_by: vec!["station".to_string()],
+                aggregates: vec![
+                    aggregate(GroupByAggregateFunction::Mean),
+                    GroupByAggregate {
+                        name: Some("n".to_string()),
+                        ..aggregate(GroupByAggregateFunction::Count)
+                    }
+                ],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_aggregates_groups() {
+        let batches = query(
+            GroupBy {
+                params: GroupByParams {
+                    group_by: vec!["station".to_string()],
+                    aggregates: vec![
+                        aggregate(GroupByAggregateFunction::Count),
+                        aggregate(GroupByAggregateFunction::Sum),
+                        aggregate(GroupByAggregateFunction::Mean),
+                        aggregate(GroupByAggregateFunction::Min),
+                        aggregate(GroupByAggregateFunction::Max),
+                    ],
+                },
+                sources: source().into(),
+            }
+            .boxed(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+
+        assert_eq!(
+            batch
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.name().as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "station",
+                "temperature_count",
+                "temperature_sum",
+                "temperature_mean",
+                "temperature_min",
+                "temperature_max"
+            ]
+        );
+
+        let stations: &StringArray = batch.column(0).as_any().downcast_ref().unwrap();
+        assert_eq!(
+            stations.iter().collect::<Vec<_>>(),
+            vec![Some("a"), Some("b"), Some("c")]
+        );
+
+        let counts: &Int64Array = batch.column(1).as_any().downcast_ref().unwrap();
+        assert_eq!(counts.values(), &[3, 2, 0]);
+
+        assert_eq!(float_column(batch, 2), vec![Some(9.), Some(30.), None]);
+        assert_eq!(float_column(batch, 3), vec![Some(3.), Some(15.), None]);
+        assert_eq!(float_column(batch, 4), vec![Some(1.), Some(10.), None]);
+        assert_eq!(float_column(batch, 5), vec![Some(5.), Some(20.), None]);
+    }
+
+    #[tokio::test]
+    async fn it_aggregates_tables() {
+        let table = AttributeTable {
+            params: AttributeTableParams {
+                columns: None,
+                include_time: false,
+            },
+            sources: source().into(),
+        }
+        .boxed();
+
+        let batches = query(
+            GroupBy {
+                params: GroupByParams {
+                    group_by: vec![],
+                    aggregates: vec![aggregate(GroupByAggregateFunction::Mean)],
+                },
+                sources: table.into(),
+            }
+            .boxed(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(float_column(&batches[0], 0), vec![Some(39. / 5.)]);
+    }
+
+    #[tokio::test]
+    async fn it_checks_columns() {
+        let group_by = |group_by: &str, function| {
+            GroupBy {
+                params: GroupByParams {
+                    group_by: vec![group_by.to_string()],
+                    aggregates: vec![GroupByAggregate {
+                        column: "station".to_string(),
+                        function,
+                        name: None,
+                    }],
+                },
+                sources: source().into(),
+            }
+            .boxed()
+        };
+
+        assert!(matches!(
+            query(group_by("foo", GroupByAggregateFunction::Count)).await,
+            Err(Error::ColumnDoesNotExist { column }) if column == "foo"
+        ));
+        assert!(matches!(
+            query(group_by("temperature", GroupByAggregateFunction::Count)).await,
+            Err(Error::InvalidOperatorSpec { .. })
+        ));
+        assert!(matches!(
+            query(group_by("station", GroupByAggregateFunction::Sum)).await,
+            Err(Error::InvalidOperatorSpec { .. })
+        ));
+        assert!(matches!(
+            query(group_by("station_count", GroupByAggregateFunction::Count)).await,
+            Err(Error::InvalidOperatorSpec { .. })
+        ));
+    }
+}
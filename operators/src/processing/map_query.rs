@@ -32,6 +32,17 @@ where
         let rewritten_query = (self.query_fn)(query)?;
         self.source.raster_query(rewritten_query, ctx).await
     }
+
+    async fn raster_query_is_empty<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        let rewritten_query = (self.query_fn)(query)?;
+        self.source
+            .raster_query_is_empty(rewritten_query, ctx)
+            .await
+    }
 }
 
 #[async_trait]
@@ -49,4 +60,15 @@ where
         let rewritten_query = (self.query_fn)(query)?;
         self.source.vector_query(rewritten_query, ctx).await
     }
+
+    async fn vector_query_is_empty<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        let rewritten_query = (self.query_fn)(query)?;
+        self.source
+            .vector_query_is_empty(rewritten_query, ctx)
+            .await
+    }
 }
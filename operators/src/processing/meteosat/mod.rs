@@ -141,6 +141,7 @@ mod test_util {
                 ),
             ),
             spatial_resolution: sr,
+            spatial_filter: (),
         }
     }
 
@@ -149,6 +150,7 @@ mod test_util {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 4.).into(), (3., 0.).into()),
             time_interval: Default::default(),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         }
     }
 
@@ -205,6 +207,7 @@ mod test_util {
                         })
                     }),
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -262,6 +265,7 @@ mod test_util {
                 ]),
                 gdal_open_options: None,
                 gdal_config_options: None,
+                apply_scale_offset: false,
             },
             result_descriptor: RasterResultDescriptor {
                 data_type: RasterDataType::I16,
@@ -272,6 +276,7 @@ mod test_util {
                     unit: None,
                 }),
                 no_data_value,
+                dimensions: vec![],
             },
         };
         ctx.add_meta_data(dataset_id.clone(), Box::new(meta));
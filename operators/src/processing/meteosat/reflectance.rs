@@ -1,9 +1,9 @@
 use std::sync::Arc;
 
 use crate::engine::{
-    ExecutionContext, InitializedRasterOperator, Operator, QueryContext, QueryProcessor,
-    RasterOperator, RasterQueryProcessor, RasterResultDescriptor, SingleRasterSource,
-    TypedRasterQueryProcessor,
+    Cacheability, ExecutionContext, InitializedRasterOperator, Operator, QueryContext,
+    QueryProcessor, RasterOperator, RasterQueryProcessor, RasterResultDescriptor, ResultExtent,
+    SingleRasterSource, TypedRasterQueryProcessor,
 };
 use crate::util::Result;
 use async_trait::async_trait;
@@ -111,6 +111,7 @@ impl RasterOperator for Reflectance {
                 unit: Some("fraction".into()),
             }),
             no_data_value: Some(f64::from(OUT_NO_DATA_VALUE)),
+            dimensions: in_desc.dimensions.clone(),
         };
 
         let initialized_operator = InitializedReflectance {
@@ -141,6 +142,14 @@ impl InitializedRasterOperator for InitializedReflectance {
             Err(Error::InvalidRasterDataType)
         }
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.source.extent()
+    }
 }
 
 struct ReflectanceProcessor<Q>
@@ -1,9 +1,9 @@
 use std::sync::Arc;
 
 use crate::engine::{
-    ExecutionContext, InitializedRasterOperator, Operator, QueryContext, QueryProcessor,
-    RasterOperator, RasterQueryProcessor, RasterResultDescriptor, SingleRasterSource,
-    TypedRasterQueryProcessor,
+    Cacheability, ExecutionContext, InitializedRasterOperator, Operator, QueryContext,
+    QueryProcessor, RasterOperator, RasterQueryProcessor, RasterResultDescriptor, ResultExtent,
+    SingleRasterSource, TypedRasterQueryProcessor,
 };
 use crate::util::Result;
 use async_trait::async_trait;
@@ -104,6 +104,7 @@ impl RasterOperator for Temperature {
                 unit: Some("k".into()),
             }),
             no_data_value: Some(f64::from(OUT_NO_DATA_VALUE)),
+            dimensions: in_desc.dimensions.clone(),
         };
 
         let initialized_operator = InitializedTemperature {
@@ -157,6 +158,14 @@ impl InitializedRasterOperator for InitializedTemperature {
             }
         })
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.source.extent()
+    }
 }
 
 struct TemperatureProcessor<Q, P>
@@ -1,19 +1,83 @@
+mod attribute_table;
+mod cast_column;
+mod change_detection;
 mod circle_merging_quadtree;
+mod cloud_free_composite;
+mod column_filter;
 mod column_range_filter;
+mod conditional;
+mod dissolve;
+mod ensemble_statistics;
 mod expression;
+mod filter;
+mod group_by;
 mod map_query;
 mod meteosat;
+mod mosaic;
+mod nearest_neighbor_join;
+mod overlay;
+mod pivot;
 mod point_in_polygon;
+mod proximity_events;
+mod raster_correlation;
 mod raster_vector_join;
 mod reprojection;
+mod select_output;
 mod temporal_raster_aggregation;
+mod temporal_trend;
+mod temporal_vector_aggregation;
 mod time_projection;
+mod trajectories;
+mod union_vector_sources;
+mod unit_conversion;
 mod vector_join;
+mod workflow_macro;
+mod zonal_statistics;
 
-pub use expression::{Expression, ExpressionError, ExpressionParams, ExpressionSources};
+pub use attribute_table::{
+    AttributeTable, AttributeTableParams, ATTRIBUTE_TABLE_END_COLUMN, ATTRIBUTE_TABLE_START_COLUMN,
+};
+pub use cast_column::{CastColumn, CastColumnParams};
+pub use change_detection::{
+    ChangeDetection, ChangeDetectionParams, ChangeDifference, ChangeDirection, CHANGE_AREA_COLUMN,
+    CHANGE_MAGNITUDE_COLUMN,
+};
+pub use cloud_free_composite::{
+    ClearMask, CloudFreeComposite, CloudFreeCompositeParams, CloudFreeCompositeSources,
+    CompositeCriterion,
+};
+pub use column_filter::{ColumnFilter, ColumnFilterParams, ColumnPredicate};
+pub use conditional::{Conditional, ConditionalParams, ConditionalSources};
+pub use dissolve::{Dissolve, DissolveAggregation, DissolveParams};
+pub use ensemble_statistics::{EnsembleStatistic, EnsembleStatistics, EnsembleStatisticsParams};
+pub use expression::{
+    Expression, ExpressionError, ExpressionParams, ExpressionSources, ExpressionTileProperty,
+};
+pub use filter::{ComparisonOperator, Filter, FilterExpression, FilterParams};
+pub use group_by::{GroupBy, GroupByAggregate, GroupByAggregateFunction, GroupByParams};
+pub use mosaic::{Mosaic, MosaicMethod, MosaicParams};
+pub use nearest_neighbor_join::{
+    NearestNeighborJoin, NearestNeighborJoinParams, NearestNeighborJoinSources,
+};
+pub use overlay::{Overlay, OverlayOperation, OverlayParams, OverlaySources};
+pub use pivot::{Pivot, PivotParams};
 pub use point_in_polygon::{
     PointInPolygonFilter, PointInPolygonFilterParams, PointInPolygonFilterSource,
     PointInPolygonTester,
 };
+pub use proximity_events::{ProximityEvents, ProximityEventsParams, ProximityEventsSources};
+pub use raster_correlation::{
+    CorrelationStatistic, RasterCorrelation, RasterCorrelationParams, RasterCorrelationSources,
+};
 pub use reprojection::{Reprojection, ReprojectionParams};
+pub use select_output::{SelectOutput, SelectOutputParams};
+pub use temporal_trend::{TemporalTrend, TemporalTrendParams, TrendCoefficient};
+pub use temporal_vector_aggregation::{
+    TemporalVectorAggregation, TemporalVectorAggregationFunction, TemporalVectorAggregationParams,
+};
 pub use time_projection::{TimeProjection, TimeProjectionError, TimeProjectionParams};
+pub use trajectories::{Trajectories, TrajectoriesParams};
+pub use union_vector_sources::{UnionStrictness, UnionVectorSources, UnionVectorSourcesParams};
+pub use unit_conversion::{ConvertUnit, ConvertUnitParams};
+pub use workflow_macro::{Macro, MacroParams, MacroSources, MACRO_INPUT_PLACEHOLDER_KEY};
+pub use zonal_statistics::{ZonalStatistic, ZonalStatistics, ZonalStatisticsParams};
@@ -0,0 +1,493 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedRasterOperator, MultipleRasterSources, Operator,
+    QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor, RasterResultDescriptor,
+    ResultExtent, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::stream_zip::StreamVectorZip;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::future::join_all;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::primitives::{RasterQueryRectangle, SpatialPartition2D};
+use geoengine_datatypes::raster::{
+    EmptyGrid2D, FromPrimitive, Grid2D, GridOrEmpty, GridSize, NoDataValue, Pixel, RasterDataType,
+    RasterTile2D,
+};
+use num_traits::AsPrimitive;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt};
+
+const MAX_NUMBER_OF_RASTER_INPUTS: usize = 64;
+
+/// The `Mosaic` operator combines rasters that cover different spatial extents into a single
+/// raster. All sources must have the same data type and spatial reference.
+///
+/// The sources are queried with the same query rectangle, s.t. sources with different
+/// resolutions are resampled to the resolution of the query.
+pub type Mosaic = Operator<MosaicParams, MultipleRasterSources>;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MosaicParams {
+    pub method: MosaicMethod,
+}
+
+/// Determines the value of a pixel that is covered by multiple sources
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MosaicMethod {
+    /// the first source, in the order of the sources, with a valid value
+    First,
+    /// the source with the latest valid value, i.e., the tile with the latest start of its time
+    MostRecent,
+    /// the mean of all valid values
+    Mean,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for Mosaic {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        ensure!(
+            (1..=MAX_NUMBER_OF_RASTER_INPUTS).contains(&self.sources.rasters.len()),
+            error::InvalidNumberOfRasterInputs {
+                expected: 1..MAX_NUMBER_OF_RASTER_INPUTS,
+                found: self.sources.rasters.len()
+            }
+        );
+
+        let sources = join_all(
+            self.sources
+                .rasters
+                .into_iter()
+                .map(|s| s.initialize(context)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        let first = sources[0].result_descriptor();
+
+        for other in sources.iter().skip(1).map(|s| s.result_descriptor()) {
+            ensure!(
+                first.spatial_reference == other.spatial_reference,
+                error::InvalidSpatialReference {
+                    expected: first.spatial_reference,
+                    found: other.spatial_reference,
+                }
+            );
+            ensure!(
+                first.data_type == other.data_type,
+                error::InvalidType {
+                    expected: format!("{:?}", first.data_type),
+                    found: format!("{:?}", other.data_type),
+                }
+            );
+        }
+
+        let no_data_value = sources
+            .iter()
+            .find_map(|s| s.result_descriptor().no_data_value)
+            .context(error::MosaicWithoutNoDataValue)?;
+
+        let result_descriptor = RasterResultDescriptor {
+            no_data_value: Some(no_data_value),
+            ..first.clone()
+        };
+
+        Ok(InitializedMosaic {
+            result_descriptor,
+            sources,
+            method: self.params.method,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedMosaic {
+    result_descriptor: RasterResultDescriptor,
+    sources: Vec<Box<dyn InitializedRasterOperator>>,
+    method: MosaicMethod,
+}
+
+impl InitializedRasterOperator for InitializedMosaic {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::combine_all(
+            self.sources
+                .iter()
+                .map(InitializedRasterOperator::cacheability),
+        )
+    }
+
+    fn extent(&self) -> ResultExtent {
+        ResultExtent::union_all(self.sources.iter().map(InitializedRasterOperator::extent))
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let processors = self
+            .sources
+            .iter()
+            .map(|s| s.query_processor())
+            .collect::<Result<Vec<_>>>()?;
+
+        let no_data_value = self
+            .result_descriptor
+            .no_data_value
+            .context(error::MosaicWithoutNoDataValue)?;
+
+        Ok(match self.result_descriptor.data_type {
+            RasterDataType::U8 => TypedRasterQueryProcessor::U8(
+                MosaicQueryProcessor::new(processors, |p| p.get_u8(), self.method, no_data_value)
+                    .boxed(),
+            ),
+            RasterDataType::U16 => TypedRasterQueryProcessor::U16(
+                MosaicQueryProcessor::new(processors, |p| p.get_u16(), self.method, no_data_value)
+                    .boxed(),
+            ),
+            RasterDataType::U32 => TypedRasterQueryProcessor::U32(
+                MosaicQueryProcessor::new(processors, |p| p.get_u32(), self.method, no_data_value)
+                    .boxed(),
+            ),
+            RasterDataType::U64 => TypedRasterQueryProcessor::U64(
+                MosaicQueryProcessor::new(processors, |p| p.get_u64(), self.method, no_data_value)
+                    .boxed(),
+            ),
+            RasterDataType::I8 => TypedRasterQueryProcessor::I8(
+                MosaicQueryProcessor::new(processors, |p| p.get_i8(), self.method, no_data_value)
+                    .boxed(),
+            ),
+            RasterDataType::I16 => TypedRasterQueryProcessor::I16(
+                MosaicQueryProcessor::new(processors, |p| p.get_i16(), self.method, no_data_value)
+                    .boxed(),
+            ),
+            RasterDataType::I32 => TypedRasterQueryProcessor::I32(
+                MosaicQueryProcessor::new(processors, |p| p.get_i32(), self.method, no_data_value)
+                    .boxed(),
+            ),
+            RasterDataType::I64 => TypedRasterQueryProcessor::I64(
+                MosaicQueryProcessor::new(processors, |p| p.get_i64(), self.method, no_data_value)
+                    .boxed(),
+            ),
+            RasterDataType::F32 => TypedRasterQueryProcessor::F32(
+                MosaicQueryProcessor::new(processors, |p| p.get_f32(), self.method, no_data_value)
+                    .boxed(),
+            ),
+            RasterDataType::F64 => TypedRasterQueryProcessor::F64(
+                MosaicQueryProcessor::new(processors, |p| p.get_f64(), self.method, no_data_value)
+                    .boxed(),
+            ),
+        })
+    }
+}
+
+pub struct MosaicQueryProcessor<T>
+where
+    T: Pixel,
+{
+    sources: Vec<Box<dyn RasterQueryProcessor<RasterType = T>>>,
+    method: MosaicMethod,
+    no_data_value: T,
+}
+
+impl<T> MosaicQueryProcessor<T>
+where
+    T: Pixel,
+{
+    /// Creates the processor from sources that were checked to be of type `T`
+    fn new<F>(
+        sources: Vec<TypedRasterQueryProcessor>,
+        typed: F,
+        method: MosaicMethod,
+        no_data_value: f64,
+    ) -> Self
+    where
+        F: Fn(TypedRasterQueryProcessor) -> Option<Box<dyn RasterQueryProcessor<RasterType = T>>>,
+    {
+        Self {
+            sources: sources
+                .into_iter()
+                .map(|s| typed(s).expect("data types are checked during initialization"))
+                .collect(),
+            method,
+            no_data_value: T::from_(no_data_value),
+        }
+    }
+}
+
+#[async_trait]
+impl<T> QueryProcessor for MosaicQueryProcessor<T>
+where
+    T: Pixel,
+{
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let mut streams = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            streams.push(source.raster_query(query, ctx).await?);
+        }
+
+        let method = self.method;
+        let no_data_value = self.no_data_value;
+
+        let stream = StreamVectorZip::new(streams).map(move |tiles| {
+            let tiles = tiles.into_iter().collect::<Result<Vec<_>>>()?;
+            Ok(mosaic_tiles(tiles, method, no_data_value))
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+/// Combines the tiles of all sources at the same position
+fn mosaic_tiles<T: Pixel>(
+    mut tiles: Vec<RasterTile2D<T>>,
+    method: MosaicMethod,
+    no_data_value: T,
+) -> RasterTile2D<T> {
+    if method == MosaicMethod::MostRecent {
+        // the sort is stable, so the order of the sources decides between equal times
+        tiles.sort_by(|a, b| b.time.start().cmp(&a.time.start()));
+    }
+
+    let time = tiles
+        .iter()
+        .skip(1)
+        .try_fold(tiles[0].time, |time, tile| time.intersect(&tile.time))
+        .unwrap_or(tiles[0].time);
+    let tile_information = tiles[0].tile_information();
+    let shape = tile_information.tile_size_in_pixels;
+
+    let grids = tiles
+        .into_iter()
+        .filter_map(|tile| match tile.grid_array {
+            GridOrEmpty::Grid(grid) => Some(grid),
+            GridOrEmpty::Empty(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    if grids.is_empty() {
+        return RasterTile2D::new_with_tile_info(
+            time,
+            tile_information,
+            EmptyGrid2D::new(shape, no_data_value).into(),
+        );
+    }
+
+    let data = match method {
+        MosaicMethod::First | MosaicMethod::MostRecent => {
+            first_valid_values(&grids, shape.number_of_elements(), no_data_value)
+        }
+        MosaicMethod::Mean => mean_values(&grids, shape.number_of_elements(), no_data_value),
+    };
+
+    let grid = Grid2D::new(shape, data, Some(no_data_value))
+        .expect("the tiles of all sources have the same shape");
+
+    RasterTile2D::new_with_tile_info(time, tile_information, grid.into())
+}
+
+fn first_valid_values<T: Pixel>(grids: &[Grid2D<T>], len: usize, no_data_value: T) -> Vec<T> {
+    (0..len)
+        .map(|i| {
+            grids
+                .iter()
+                .map(|grid| grid.data[i])
+                .zip(grids)
+                .find(|(value, grid)| !grid.is_no_data(*value))
+                .map_or(no_data_value, |(value, _)| value)
+        })
+        .collect()
+}
+
+fn mean_values<T: Pixel>(grids: &[Grid2D<T>], len: usize, no_data_value: T) -> Vec<T> {
+    (0..len)
+        .map(|i| {
+            let (sum, count) = grids
+                .iter()
+                .filter(|grid| !grid.is_no_data(grid.data[i]))
+                .fold((0., 0_usize), |(sum, count), grid| {
+                    (sum + AsPrimitive::<f64>::as_(grid.data[i]), count + 1)
+                });
+
+            if count == 0 {
+                no_data_value
+            } else {
+                T::from_(sum / count as f64)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{Measurement, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::TileInformation;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn make_raster(data: Option<Vec<u8>>, time: TimeInterval) -> Box<dyn RasterOperator> {
+        let tile_information = TileInformation {
+            global_tile_position: [-1, 0].into(),
+            tile_size_in_pixels: [3, 2].into(),
+            global_geo_transform: TestDefault::test_default(),
+        };
+
+        let grid = match data {
+            Some(data) => Grid2D::new([3, 2].into(), data, Some(0)).unwrap().into(),
+            None => EmptyGrid2D::new([3, 2].into(), 0).into(),
+        };
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![RasterTile2D::new_with_tile_info(
+                    time,
+                    tile_information,
+                    grid,
+                )],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(0.),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn mosaic(
+        method: MosaicMethod,
+        rasters: Vec<Box<dyn RasterOperator>>,
+    ) -> Result<Vec<RasterTile2D<u8>>> {
+        let operator = Mosaic {
+            params: MosaicParams { method },
+            sources: rasters.into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await?;
+
+        let processor = operator.query_processor()?.get_u8().unwrap();
+
+        let ctx = MockQueryContext::new(1.into());
+        processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 3.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval: TimeInterval::new_unchecked(5, 6),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
+                },
+                &ctx,
+            )
+            .await?
+            .try_collect()
+            .await
+    }
+
+    fn data(tiles: &[RasterTile2D<u8>]) -> Vec<u8> {
+        tiles[0].grid_array.clone().into_materialized_grid().data
+    }
+
+    #[tokio::test]
+    async fn it_takes_the_first_valid_value() {
+        let tiles = mosaic(
+            MosaicMethod::First,
+            vec![
+                make_raster(Some(vec![1, 0, 1, 0, 1, 0]), TimeInterval::default()),
+                make_raster(Some(vec![2, 2, 0, 0, 2, 2]), TimeInterval::default()),
+                make_raster(None, TimeInterval::default()),
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(data(&tiles), vec![1, 2, 1, 0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn it_prefers_the_most_recent_value() {
+        let tiles = mosaic(
+            MosaicMethod::MostRecent,
+            vec![
+                make_raster(
+                    Some(vec![1, 0, 1, 0, 1, 0]),
+                    TimeInterval::new_unchecked(0, 10),
+                ),
+                make_raster(
+                    Some(vec![2, 2, 0, 0, 2, 2]),
+                    TimeInterval::new_unchecked(4, 8),
+                ),
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(data(&tiles), vec![2, 2, 1, 0, 2, 2]);
+        assert_eq!(tiles[0].time, TimeInterval::new_unchecked(4, 8));
+    }
+
+    #[tokio::test]
+    async fn it_blends_by_the_mean() {
+        let tiles = mosaic(
+            MosaicMethod::Mean,
+            vec![
+                make_raster(Some(vec![1, 0, 1, 0, 4, 0]), TimeInterval::default()),
+                make_raster(Some(vec![3, 2, 0, 0, 2, 2]), TimeInterval::default()),
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(data(&tiles), vec![2, 2, 1, 0, 3, 2]);
+    }
+
+    #[tokio::test]
+    async fn it_returns_empty_tiles() {
+        let tiles = mosaic(
+            MosaicMethod::First,
+            vec![
+                make_raster(None, TimeInterval::default()),
+                make_raster(None, TimeInterval::default()),
+            ],
+        )
+        .await
+        .unwrap();
+
+        assert!(tiles[0].is_empty());
+    }
+
+    #[test]
+    fn it_deserializes_params() {
+        assert_eq!(
+            serde_json::from_str::<MosaicParams>(r#"{"method":"mostRecent"}"#).unwrap(),
+            MosaicParams {
+                method: MosaicMethod::MostRecent
+            }
+        );
+    }
+}
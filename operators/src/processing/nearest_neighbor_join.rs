@@ -0,0 +1,548 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, OperatorDatasets,
+    QueryContext, QueryProcessor, ResultExtent, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error::{self, Error};
+use crate::processing::vector_join::translation_table;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications, GeometryCollection,
+    MultiPointCollection, SpatialIndex, ToSpatialIndex, VectorDataType,
+};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::operations::geodesic::Measurement;
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, FeatureData, FeatureDataType, FeatureDataValue, Geometry,
+    MultiPointAccess, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::sync::Arc;
+
+/// The nearest neighbor join finds for each point feature of the left source the nearest
+/// feature of the right source and copies its attributes.
+///
+/// Only right features whose time interval intersects the left feature's are considered.
+///
+/// For geographic spatial references, distances are measured geodesically in meters.
+/// The nearest feature is still searched in the coordinate space, so at high latitudes a
+/// slightly farther feature may be chosen.
+pub type NearestNeighborJoin = Operator<NearestNeighborJoinParams, NearestNeighborJoinSources>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearestNeighborJoinParams {
+    /// The columns of the right source that are copied to the left features
+    #[serde(default)]
+    pub right_columns: Vec<String>,
+    /// which suffix to use if columns have conflicting names?
+    /// the default is "right"
+    #[serde(default)]
+    pub right_column_suffix: Option<String>,
+    /// The output column that contains the distance to the nearest neighbor
+    pub distance_column: String,
+    /// Right features that are farther away are not considered.
+    /// The distance is in meters for geographic spatial references.
+    ///
+    /// Without a maximum distance, only right features within the query rectangle are considered.
+    #[serde(default)]
+    pub max_distance: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearestNeighborJoinSources {
+    pub left: Box<dyn VectorOperator>,
+    pub right: Box<dyn VectorOperator>,
+}
+
+impl OperatorDatasets for NearestNeighborJoinSources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.left.datasets_collect(datasets);
+        self.right.datasets_collect(datasets);
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for NearestNeighborJoin {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let left = self.sources.left.initialize(context).await?;
+        let right = self.sources.right.initialize(context).await?;
+
+        ensure!(
+            left.result_descriptor().data_type == VectorDataType::MultiPoint,
+            error::InvalidType {
+                expected: VectorDataType::MultiPoint.to_string(),
+                found: left.result_descriptor().data_type.to_string(),
+            }
+        );
+        ensure!(
+            right.result_descriptor().data_type != VectorDataType::Data,
+            error::InvalidType {
+                expected: "a geo data collection".to_string(),
+                found: right.result_descriptor().data_type.to_string(),
+            }
+        );
+        ensure!(
+            left.result_descriptor().spatial_reference
+                == right.result_descriptor().spatial_reference,
+            error::AllSourcesMustHaveSameSpatialReference
+        );
+
+        if let Some(max_distance) = self.params.max_distance {
+            ensure!(
+                max_distance >= 0.,
+                error::InvalidOperatorSpec {
+                    reason: "`maxDistance` must not be negative".to_string(),
+                }
+            );
+        }
+
+        for column in &self.params.right_columns {
+            ensure!(
+                right.result_descriptor().columns.contains_key(column),
+                error::ColumnDoesNotExist {
+                    column: column.clone(),
+                }
+            );
+        }
+
+        let right_column_suffix: &str = self
+            .params
+            .right_column_suffix
+            .as_ref()
+            .map_or("right", String::as_str);
+        let column_translation_table = translation_table(
+            left.result_descriptor()
+                .columns
+                .keys()
+                .chain(std::iter::once(&self.params.distance_column)),
+            self.params.right_columns.iter(),
+            right_column_suffix,
+        );
+
+        ensure!(
+            !left
+                .result_descriptor()
+                .columns
+                .contains_key(&self.params.distance_column),
+            error::InvalidOperatorSpec {
+                reason: format!(
+                    "Distance column '{}' already exists.",
+                    self.params.distance_column
+                ),
+            }
+        );
+
+        let measurement =
+            Measurement::for_spatial_reference(left.result_descriptor().spatial_reference)?;
+
+        let right_columns: Vec<RightColumn> = self
+            .params
+            .right_columns
+            .iter()
+            .map(|column| RightColumn {
+                input: column.clone(),
+                output: column_translation_table[column].clone(),
+                data_type: right.result_descriptor().columns[column],
+            })
+            .collect();
+
+        let result_descriptor = left.result_descriptor().map_columns(|left_columns| {
+            let mut columns = left_columns.clone();
+            columns.insert(self.params.distance_column.clone(), FeatureDataType::Float);
+            for column in &right_columns {
+                columns.insert(column.output.clone(), column.data_type);
+            }
+            columns
+        });
+
+        Ok(InitializedNearestNeighborJoin {
+            result_descriptor,
+            left,
+            right,
+            state: Arc::new(NearestNeighborJoinState {
+                right_columns,
+                distance_column: self.params.distance_column,
+                max_distance: self.params.max_distance,
+                measurement,
+            }),
+        }
+        .boxed())
+    }
+}
+
+#[derive(Debug)]
+struct RightColumn {
+    input: String,
+    output: String,
+    data_type: FeatureDataType,
+}
+
+#[derive(Debug)]
+struct NearestNeighborJoinState {
+    right_columns: Vec<RightColumn>,
+    distance_column: String,
+    max_distance: Option<f64>,
+    measurement: Measurement,
+}
+
+pub struct InitializedNearestNeighborJoin {
+    result_descriptor: VectorResultDescriptor,
+    left: Box<dyn InitializedVectorOperator>,
+    right: Box<dyn InitializedVectorOperator>,
+    state: Arc<NearestNeighborJoinState>,
+}
+
+impl InitializedVectorOperator for InitializedNearestNeighborJoin {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let left =
+            self.left
+                .query_processor()?
+                .multi_point()
+                .ok_or_else(|| Error::InvalidVectorType {
+                    expected: VectorDataType::MultiPoint.to_string(),
+                    found: self.left.result_descriptor().data_type.to_string(),
+                })?;
+
+        Ok(TypedVectorQueryProcessor::MultiPoint(
+            NearestNeighborJoinProcessor {
+                left,
+                right: self.right.query_processor()?,
+                state: self.state.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.left.cacheability().combine(self.right.cacheability())
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.left.extent()
+    }
+}
+
+pub struct NearestNeighborJoinProcessor {
+    left: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    right: TypedVectorQueryProcessor,
+    state: Arc<NearestNeighborJoinState>,
+}
+
+#[async_trait]
+impl QueryProcessor for NearestNeighborJoinProcessor {
+    type Output = MultiPointCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        // the nearest neighbor of a feature in the spatial filter may lie outside of it
+        let mut right_query = VectorQueryRectangle {
+            spatial_filter: None,
+            ..query.clone()
+        };
+        if let Some(max_distance) = self.state.max_distance {
+            right_query.spatial_bounds = self
+                .state
+                .measurement
+                .enlarge_bbox(query.spatial_bounds, max_distance);
+        }
+
+        let index = Arc::new(match &self.right {
+            TypedVectorQueryProcessor::Data(_) => unreachable!("checked in initialization"),
+            TypedVectorQueryProcessor::MultiPoint(right) => {
+                RightIndex::new(right.as_ref(), right_query, ctx, &self.state).await?
+            }
+            TypedVectorQueryProcessor::MultiLineString(right) => {
+                RightIndex::new(right.as_ref(), right_query, ctx, &self.state).await?
+            }
+            TypedVectorQueryProcessor::MultiPolygon(right) => {
+                RightIndex::new(right.as_ref(), right_query, ctx, &self.state).await?
+            }
+        });
+
+        let state = self.state.clone();
+
+        let stream = self
+            .left
+            .query(query, ctx)
+            .await?
+            .and_then(move |collection| {
+                let index = index.clone();
+                let state = state.clone();
+
+                async move {
+                    crate::util::spawn_blocking(move || index.join(&collection, &state)).await?
+                }
+            });
+
+        Ok(stream.boxed())
+    }
+}
+
+/// The features of the right source in spatial indexes together with the copied attributes
+struct RightIndex {
+    /// an index per chunk of the right source with the number of features before the chunk
+    indexes: Vec<(SpatialIndex, usize)>,
+    time_intervals: Vec<TimeInterval>,
+    /// the attributes of the right features per output column
+    attributes: Vec<Vec<FeatureDataValue>>,
+}
+
+impl RightIndex {
+    async fn new<G>(
+        processor: &dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>,
+        query: VectorQueryRectangle,
+        ctx: &dyn QueryContext,
+        state: &NearestNeighborJoinState,
+    ) -> Result<Self>
+    where
+        G: Geometry + ArrowTyped,
+        FeatureCollection<G>: ToSpatialIndex,
+    {
+        let mut indexes = Vec::new();
+        let mut time_intervals = Vec::new();
+        let mut attributes = vec![Vec::new(); state.right_columns.len()];
+
+        let mut collections = processor.vector_query(query, ctx).await?;
+
+        while let Some(collection) = collections.try_next().await? {
+            for (column, values) in state.right_columns.iter().zip(&mut attributes) {
+                let data = collection.data(&column.input)?;
+                values.extend((0..collection.len()).map(|i| data.get_unchecked(i)));
+            }
+
+            indexes.push((collection.spatial_index(), time_intervals.len()));
+
+            time_intervals.extend_from_slice(collection.time_intervals());
+        }
+
+        Ok(Self {
+            indexes,
+            time_intervals,
+            attributes,
+        })
+    }
+
+    /// Finds the nearest right feature and its distance for the `coordinates` of a left feature
+    fn nearest(
+        &self,
+        coordinates: &[Coordinate2D],
+        time: &TimeInterval,
+        state: &NearestNeighborJoinState,
+    ) -> Option<(usize, f64)> {
+        let mut nearest: Option<(usize, f64)> = None;
+
+        for coordinate in coordinates {
+            for (index, offset) in &self.indexes {
+                for (feature, closest) in index.nearest_coordinates(*coordinate) {
+                    let distance = state.measurement.distance(*coordinate, closest);
+
+                    if state
+                        .max_distance
+                        .map_or(false, |max_distance| distance > max_distance)
+                        || nearest
+                            .map_or(false, |(_, nearest_distance)| distance >= nearest_distance)
+                    {
+                        break;
+                    }
+
+                    if self.time_intervals[offset + feature].intersects(time) {
+                        nearest = Some((offset + feature, distance));
+                        break;
+                    }
+                }
+            }
+        }
+
+        nearest
+    }
+
+    fn join(
+        &self,
+        collection: &MultiPointCollection,
+        state: &NearestNeighborJoinState,
+    ) -> Result<MultiPointCollection> {
+        let nearest: Vec<Option<(usize, f64)>> = collection
+            .geometries()
+            .zip(collection.time_intervals())
+            .map(|(geometry, time)| self.nearest(geometry.points(), time, state))
+            .collect();
+
+        let mut columns = Vec::with_capacity(state.right_columns.len() + 1);
+
+        columns.push((
+            state.distance_column.as_str(),
+            FeatureData::NullableFloat(
+                nearest
+                    .iter()
+                    .map(|nearest| nearest.map(|(_, distance)| distance))
+                    .collect(),
+            ),
+        ));
+
+        for (column, values) in state.right_columns.iter().zip(&self.attributes) {
+            columns.push((
+                column.output.as_str(),
+                FeatureData::nullable_from_values(
+                    column.data_type,
+                    nearest
+                        .iter()
+                        .map(|nearest| nearest.map(|(feature, _)| values[feature].clone())),
+                ),
+            ));
+        }
+
+        collection.add_columns(&columns).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiLineStringCollection;
+    use geoengine_datatypes::primitives::{MultiLineString, MultiPoint, SpatialResolution};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[test]
+    fn serialization() {
+        let join = NearestNeighborJoin {
+            params: NearestNeighborJoinParams {
+                right_columns: vec!["name".to_string()],
+                right_column_suffix: None,
+                distance_column: "distance".to_string(),
+                max_distance: Some(10.),
+            },
+            sources: NearestNeighborJoinSources {
+                left: MockFeatureCollectionSource::<MultiPoint>::multiple(vec![]).boxed(),
+                right: MockFeatureCollectionSource::<MultiPoint>::multiple(vec![]).boxed(),
+            },
+        }
+        .boxed();
+
+        let serialized = serde_json::to_value(&join).unwrap();
+
+        assert_eq!(
+            serialized["params"],
+            serde_json::json!({
+                "rightColumns": ["name"],
+                "rightColumnSuffix": null,
+                "distanceColumn": "distance",
+                "maxDistance": 10.0
+            })
+        );
+
+        let _operator: Box<dyn VectorOperator> = serde_json::from_value(serialized).unwrap();
+    }
+
+    #[tokio::test]
+    async fn nearest_lines() {
+        let observations = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0., 1.), (10., 3.), (50., 50.)]).unwrap(),
+            vec![TimeInterval::default(); 3],
+            [(
+                "name".to_string(),
+                FeatureData::Text(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+            )]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+
+        let roads = MultiLineStringCollection::from_data(
+            vec![
+                MultiLineString::new(vec![vec![(-5., 0.).into(), (5., 0.).into()]]).unwrap(),
+                MultiLineString::new(vec![vec![(10., -5.).into(), (10., 5.).into()]]).unwrap(),
+            ],
+            vec![TimeInterval::default(); 2],
+            [(
+                "name".to_string(),
+                FeatureData::Text(vec!["A1".to_string(), "B2".to_string()]),
+            )]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+
+        let join = NearestNeighborJoin {
+            params: NearestNeighborJoinParams {
+                right_columns: vec!["name".to_string()],
+                right_column_suffix: Some("_road".to_string()),
+                distance_column: "distance".to_string(),
+                max_distance: Some(200_000.),
+            },
+            sources: NearestNeighborJoinSources {
+                left: MockFeatureCollectionSource::single(observations).boxed(),
+                right: MockFeatureCollectionSource::single(roads).boxed(),
+            },
+        }
+        .boxed();
+
+        let processor = join
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_point()
+            .unwrap();
+
+        let collections: Vec<MultiPointCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-10., -10.).into(), (60., 60.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(collections.len(), 1);
+        let collection = &collections[0];
+
+        assert_eq!(
+            collection
+                .data("distance")
+                .unwrap()
+                .float_options_iter()
+                .map(|distance| distance.map(f64::round))
+                .collect::<Vec<_>>(),
+            // one degree of latitude at the equator
+            vec![Some(110_574.), Some(0.), None]
+        );
+        assert_eq!(
+            collection
+                .data("name_road")
+                .unwrap()
+                .strings_iter()
+                .collect::<Vec<_>>(),
+            vec!["A1", "B2", ""]
+        );
+    }
+}
@@ -0,0 +1,642 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, OperatorDatasets,
+    QueryContext, QueryProcessor, ResultExtent, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error::{self, Error};
+use crate::processing::vector_join::translation_table;
+use crate::util::gdal::{gdal_polygon_set_operation, PolygonSetOperation};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geo::algorithm::bounding_rect::BoundingRect;
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollectionInfos, FeatureCollectionRowBuilder,
+    GeoFeatureCollectionRowBuilder, GeometryCollection, MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::operations::geodesic::Measurement;
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureDataType, FeatureDataValue, MultiPolygon, TimeInterval,
+    VectorQueryRectangle,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The overlay operator combines two polygon collections geometrically.
+///
+/// The resulting polygons carry the attributes of the input features they stem from.
+/// Features are only combined if their time intervals intersect.
+pub type Overlay = Operator<OverlayParams, OverlaySources>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayParams {
+    pub operation: OverlayOperation,
+    /// which suffix to use if columns have conflicting names?
+    /// the default is "right"
+    #[serde(default)]
+    pub right_column_suffix: Option<String>,
+    /// An optional output column that contains the area of the resulting polygons.
+    /// The area is in square meters for geographic spatial references.
+    #[serde(default)]
+    pub area_column: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OverlayOperation {
+    /// The parts that are covered by a left and a right feature, with the attributes of both
+    Intersection,
+    /// The intersection plus the parts that are only covered by one side.
+    /// The attributes of the other side are null.
+    Union,
+    /// The parts of the left features that are not covered by any right feature
+    Difference,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlaySources {
+    pub left: Box<dyn VectorOperator>,
+    pub right: Box<dyn VectorOperator>,
+}
+
+impl OperatorDatasets for OverlaySources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.left.datasets_collect(datasets);
+        self.right.datasets_collect(datasets);
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Overlay {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let left = self.sources.left.initialize(context).await?;
+        let right = self.sources.right.initialize(context).await?;
+
+        for source in [&left, &right] {
+            ensure!(
+                source.result_descriptor().data_type == VectorDataType::MultiPolygon,
+                error::InvalidType {
+                    expected: VectorDataType::MultiPolygon.to_string(),
+                    found: source.result_descriptor().data_type.to_string(),
+                }
+            );
+        }
+
+        ensure!(
+            left.result_descriptor().spatial_reference
+                == right.result_descriptor().spatial_reference,
+            error::AllSourcesMustHaveSameSpatialReference
+        );
+
+        let right_column_suffix: &str = self
+            .params
+            .right_column_suffix
+            .as_ref()
+            .map_or("right", String::as_str);
+
+        let right_columns = if self.params.operation == OverlayOperation::Difference {
+            HashMap::new()
+        } else {
+            translation_table(
+                left.result_descriptor().columns.keys(),
+                right.result_descriptor().columns.keys(),
+                right_column_suffix,
+            )
+        };
+
+        let mut columns = left.result_descriptor().columns.clone();
+        let mut column_metadata = left.result_descriptor().column_metadata.clone();
+        for (right_column, output_column) in &right_columns {
+            columns.insert(
+                output_column.clone(),
+                right.result_descriptor().columns[right_column],
+            );
+
+            if let Some(metadata) = right.result_descriptor().column_metadata.get(right_column) {
+                column_metadata.insert(output_column.clone(), metadata.clone());
+            }
+        }
+
+        if let Some(area_column) = &self.params.area_column {
+            ensure!(
+                !columns.contains_key(area_column),
+                error::InvalidOperatorSpec {
+                    reason: format!("Area column '{}' already exists.", area_column),
+                }
+            );
+
+            columns.insert(area_column.clone(), FeatureDataType::Float);
+        }
+
+        let measurement =
+            Measurement::for_spatial_reference(left.result_descriptor().spatial_reference)?;
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPolygon,
+            spatial_reference: left.result_descriptor().spatial_reference,
+            columns,
+            column_metadata,
+        };
+
+        Ok(InitializedOverlay {
+            result_descriptor,
+            left,
+            right,
+            state: Arc::new(OverlayState {
+                operation: self.params.operation,
+                right_columns,
+                area_column: self.params.area_column,
+                measurement,
+            }),
+        }
+        .boxed())
+    }
+}
+
+/// The parameters of the overlay after resolving the column names
+#[derive(Debug)]
+struct OverlayState {
+    operation: OverlayOperation,
+    /// maps the right input columns to the output columns
+    right_columns: HashMap<String, String>,
+    area_column: Option<String>,
+    measurement: Measurement,
+}
+
+pub struct InitializedOverlay {
+    result_descriptor: VectorResultDescriptor,
+    left: Box<dyn InitializedVectorOperator>,
+    right: Box<dyn InitializedVectorOperator>,
+    state: Arc<OverlayState>,
+}
+
+impl InitializedVectorOperator for InitializedOverlay {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let polygon_processor = |operator: &dyn InitializedVectorOperator| {
+            operator
+                .query_processor()?
+                .multi_polygon()
+                .ok_or_else(|| Error::InvalidVectorType {
+                    expected: VectorDataType::MultiPolygon.to_string(),
+                    found: operator.result_descriptor().data_type.to_string(),
+                })
+        };
+
+        Ok(TypedVectorQueryProcessor::MultiPolygon(
+            OverlayProcessor {
+                left: polygon_processor(self.left.as_ref())?,
+                right: polygon_processor(self.right.as_ref())?,
+                state: self.state.clone(),
+                column_types: self.result_descriptor.columns.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.left.cacheability().combine(self.right.cacheability())
+    }
+
+    fn extent(&self) -> ResultExtent {
+        match self.state.operation {
+            OverlayOperation::Intersection => self.left.extent().intersection(self.right.extent()),
+            OverlayOperation::Union => self.left.extent().union(self.right.extent()),
+            OverlayOperation::Difference => self.left.extent(),
+        }
+    }
+}
+
+pub struct OverlayProcessor {
+    left: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    right: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    state: Arc<OverlayState>,
+    column_types: HashMap<String, FeatureDataType>,
+}
+
+#[async_trait]
+impl QueryProcessor for OverlayProcessor {
+    type Output = MultiPolygonCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        // every left feature has to be compared with every right feature
+        let left = collect_features(self.left.as_ref(), query.clone(), ctx, |column| {
+            Some(column.to_string())
+        })
+        .await?;
+        // right features may intersect left features outside of the spatial filter
+        let right_query = VectorQueryRectangle {
+            spatial_filter: None,
+            ..query
+        };
+        let right = collect_features(self.right.as_ref(), right_query, ctx, |column| {
+            self.state.right_columns.get(column).cloned()
+        })
+        .await?;
+
+        let state = self.state.clone();
+        let column_types = self.column_types.clone();
+
+        let result =
+            crate::util::spawn_blocking(move || overlay(&state, &left, &right, &column_types))
+                .await?;
+
+        Ok(futures::stream::once(async move { result }).boxed())
+    }
+}
+
+struct OverlayFeature {
+    polygon: geo::MultiPolygon<f64>,
+    bbox: Option<BoundingBox2D>,
+    time: TimeInterval,
+    /// the attributes with their output column names
+    attributes: Vec<(String, FeatureDataValue)>,
+}
+
+impl OverlayFeature {
+    fn may_intersect(&self, other: &Self) -> bool {
+        let bboxes_intersect = match (&self.bbox, &other.bbox) {
+            (Some(bbox), Some(other_bbox)) => bbox.intersects_bbox(other_bbox),
+            _ => false,
+        };
+
+        bboxes_intersect && self.time.intersects(&other.time)
+    }
+}
+
+/// Collects all features of the `processor` and renames their columns.
+/// Columns that are not mapped to an output column are dropped.
+async fn collect_features(
+    processor: &dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>,
+    query: VectorQueryRectangle,
+    ctx: &dyn QueryContext,
+    output_column: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<OverlayFeature>> {
+    let mut features = Vec::new();
+
+    let mut collections = processor.vector_query(query, ctx).await?;
+
+    while let Some(collection) = collections.try_next().await? {
+        let columns = collection
+            .column_names()
+            .filter_map(|column| Some((column.clone(), output_column(column)?)))
+            .map(|(column, output_column)| Ok((output_column, collection.data(&column)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        for (i, (geometry, time)) in collection
+            .geometries()
+            .zip(collection.time_intervals())
+            .enumerate()
+        {
+            let polygon: geo::MultiPolygon<f64> = (&MultiPolygon::from(geometry)).into();
+            let bbox = polygon
+                .bounding_rect()
+                .map(|rect| BoundingBox2D::new_unchecked(rect.min().into(), rect.max().into()));
+
+            features.push(OverlayFeature {
+                polygon,
+                bbox,
+                time: *time,
+                attributes: columns
+                    .iter()
+                    .map(|(column, data)| (column.clone(), data.get_unchecked(i)))
+                    .collect(),
+            });
+        }
+    }
+
+    Ok(features)
+}
+
+fn overlay(
+    state: &OverlayState,
+    left: &[OverlayFeature],
+    right: &[OverlayFeature],
+    column_types: &HashMap<String, FeatureDataType>,
+) -> Result<MultiPolygonCollection> {
+    let mut builder = MultiPolygonCollection::builder();
+    for (column, data_type) in column_types {
+        builder.add_column(column.clone(), *data_type)?;
+    }
+    let mut builder = OverlayBuilder {
+        builder: builder.finish_header(),
+        column_types,
+        area_column: state.area_column.as_deref(),
+        measurement: &state.measurement,
+    };
+
+    if state.operation != OverlayOperation::Difference {
+        for left_feature in left {
+            for right_feature in right.iter().filter(|r| left_feature.may_intersect(r)) {
+                let intersection = gdal_polygon_set_operation(
+                    PolygonSetOperation::Intersection,
+                    &left_feature.polygon,
+                    &right_feature.polygon,
+                )?;
+
+                if let (Some(polygon), Some(time)) = (
+                    intersection,
+                    left_feature.time.intersect(&right_feature.time),
+                ) {
+                    builder.push(
+                        polygon,
+                        time,
+                        left_feature
+                            .attributes
+                            .iter()
+                            .chain(&right_feature.attributes),
+                    )?;
+                }
+            }
+        }
+    }
+
+    if state.operation != OverlayOperation::Intersection {
+        for left_feature in left {
+            if let Some(polygon) = difference(left_feature, right)? {
+                builder.push(polygon, left_feature.time, &left_feature.attributes)?;
+            }
+        }
+    }
+
+    if state.operation == OverlayOperation::Union {
+        for right_feature in right {
+            if let Some(polygon) = difference(right_feature, left)? {
+                builder.push(polygon, right_feature.time, &right_feature.attributes)?;
+            }
+        }
+    }
+
+    builder.builder.build().map_err(Into::into)
+}
+
+/// Subtracts all `others` that intersect the `feature` from it
+fn difference(feature: &OverlayFeature, others: &[OverlayFeature]) -> Result<Option<MultiPolygon>> {
+    let mut rest = MultiPolygon::new(
+        feature
+            .polygon
+            .iter()
+            .map(|polygon| {
+                std::iter::once(polygon.exterior())
+                    .chain(polygon.interiors())
+                    .map(|ring| ring.0.iter().copied().map(Into::into).collect())
+                    .collect()
+            })
+            .collect(),
+    )?;
+
+    for other in others.iter().filter(|other| feature.may_intersect(other)) {
+        match gdal_polygon_set_operation(
+            PolygonSetOperation::Difference,
+            &(&rest).into(),
+            &other.polygon,
+        )? {
+            Some(polygon) => rest = polygon,
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(rest))
+}
+
+struct OverlayBuilder<'s> {
+    builder: FeatureCollectionRowBuilder<MultiPolygon>,
+    column_types: &'s HashMap<String, FeatureDataType>,
+    area_column: Option<&'s str>,
+    measurement: &'s Measurement,
+}
+
+impl<'s> OverlayBuilder<'s> {
+    /// Pushes a feature and sets all columns without attributes to null
+    fn push<'a>(
+        &mut self,
+        polygon: MultiPolygon,
+        time: TimeInterval,
+        attributes: impl IntoIterator<Item = &'a (String, FeatureDataValue)>,
+    ) -> Result<()> {
+        let area = self.measurement.area(&polygon);
+
+        self.builder.push_geometry(polygon)?;
+        self.builder.push_time_interval(time)?;
+
+        let mut attributes: HashMap<&str, &FeatureDataValue> = attributes
+            .into_iter()
+            .map(|(column, value)| (column.as_str(), value))
+            .collect();
+
+        for column in self.column_types.keys() {
+            if Some(column.as_str()) == self.area_column {
+                self.builder
+                    .push_data(column, FeatureDataValue::Float(area))?;
+                continue;
+            }
+
+            match attributes.remove(column.as_str()) {
+                Some(value) if !is_null(value) => self.builder.push_data(column, value.clone())?,
+                _ => self.builder.push_null(column)?,
+            }
+        }
+
+        self.builder.finish_row();
+
+        Ok(())
+    }
+}
+
+fn is_null(value: &FeatureDataValue) -> bool {
+    matches!(
+        value,
+        FeatureDataValue::NullableCategory(None)
+            | FeatureDataValue::NullableInt(None)
+            | FeatureDataValue::NullableFloat(None)
+            | FeatureDataValue::NullableText(None)
+            | FeatureDataValue::NullableBool(None)
+            | FeatureDataValue::NullableDateTime(None)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::primitives::{FeatureData, SpatialResolution};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[test]
+    fn serialization() {
+        let overlay = Overlay {
+            params: OverlayParams {
+                operation: OverlayOperation::Intersection,
+                right_column_suffix: None,
+                area_column: Some("area".to_string()),
+            },
+            sources: OverlaySources {
+                left: MockFeatureCollectionSource::<MultiPolygon>::multiple(vec![]).boxed(),
+                right: MockFeatureCollectionSource::<MultiPolygon>::multiple(vec![]).boxed(),
+            },
+        }
+        .boxed();
+
+        let serialized = serde_json::to_value(&overlay).unwrap();
+
+        assert_eq!(
+            serialized["params"],
+            serde_json::json!({
+                "operation": "intersection",
+                "rightColumnSuffix": null,
+                "areaColumn": "area"
+            })
+        );
+
+        let _operator: Box<dyn VectorOperator> = serde_json::from_value(serialized).unwrap();
+    }
+
+    fn rectangle(x_min: f64, x_max: f64) -> MultiPolygon {
+        MultiPolygon::new(vec![vec![vec![
+            (x_min, 0.).into(),
+            (x_max, 0.).into(),
+            (x_max, 1.).into(),
+            (x_min, 1.).into(),
+            (x_min, 0.).into(),
+        ]]])
+        .unwrap()
+    }
+
+    async fn run_overlay(operation: OverlayOperation) -> MultiPolygonCollection {
+        let left = MultiPolygonCollection::from_data(
+            vec![rectangle(0., 2.)],
+            vec![TimeInterval::default()],
+            [("name".to_string(), FeatureData::Text(vec!["l".to_string()]))]
+                .into_iter()
+                .collect(),
+        )
+        .unwrap();
+        let right = MultiPolygonCollection::from_data(
+            vec![rectangle(1., 4.)],
+            vec![TimeInterval::default()],
+            [("name".to_string(), FeatureData::Text(vec!["r".to_string()]))]
+                .into_iter()
+                .collect(),
+        )
+        .unwrap();
+
+        let overlay = Overlay {
+            params: OverlayParams {
+                operation,
+                right_column_suffix: Some("_r".to_string()),
+                area_column: Some("area".to_string()),
+            },
+            sources: OverlaySources {
+                left: MockFeatureCollectionSource::single(left).boxed(),
+                right: MockFeatureCollectionSource::single(right).boxed(),
+            },
+        }
+        .boxed();
+
+        let processor = overlay
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_polygon()
+            .unwrap();
+
+        let mut collections: Vec<MultiPolygonCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(collections.len(), 1);
+        collections.remove(0)
+    }
+
+    fn areas(collection: &MultiPolygonCollection) -> Vec<Option<f64>> {
+        collection
+            .data("area")
+            .unwrap()
+            .float_options_iter()
+            .map(|area| area.map(f64::round))
+            .collect()
+    }
+
+    /// the expected area in square meters since the mock sources are in EPSG:4326
+    fn geodesic_area(x_min: f64, x_max: f64) -> Option<f64> {
+        Some(
+            Measurement::geodesic()
+                .area(&rectangle(x_min, x_max))
+                .round(),
+        )
+    }
+
+    fn strings(collection: &MultiPolygonCollection, column: &str) -> Vec<String> {
+        collection.data(column).unwrap().strings_iter().collect()
+    }
+
+    #[tokio::test]
+    async fn intersection() {
+        let result = run_overlay(OverlayOperation::Intersection).await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(areas(&result), vec![geodesic_area(1., 2.)]);
+        assert_eq!(strings(&result, "name"), vec!["l"]);
+        assert_eq!(strings(&result, "name_r"), vec!["r"]);
+    }
+
+    #[tokio::test]
+    async fn difference() {
+        let result = run_overlay(OverlayOperation::Difference).await;
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(areas(&result), vec![geodesic_area(0., 1.)]);
+        assert_eq!(strings(&result, "name"), vec!["l"]);
+        assert!(result.data("name_r").is_err());
+    }
+
+    #[tokio::test]
+    async fn union() {
+        let result = run_overlay(OverlayOperation::Union).await;
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(
+            areas(&result),
+            vec![
+                geodesic_area(1., 2.),
+                geodesic_area(0., 1.),
+                geodesic_area(2., 4.)
+            ]
+        );
+        assert_eq!(strings(&result, "name"), vec!["l", "l", ""]);
+        assert_eq!(strings(&result, "name_r"), vec!["r", "", "r"]);
+    }
+}
@@ -0,0 +1,661 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow::array::{
+    Array, ArrayRef, BooleanArray, Date64Array, Float64Array, Int64Array, StringArray,
+};
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::primitives::{BoundingBox2D, TimeInstance, VectorQueryRectangle};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedTableOperator, Operator, QueryContext,
+    QueryProcessor, SingleVectorOrTableSource, TableColumn, TableDataType, TableOperator,
+    TableQueryProcessor, TableResultDescriptor,
+};
+use crate::error::{self, Error};
+use crate::processing::attribute_table::initialize_as_table;
+use crate::processing::group_by::{
+    downcast, group_key_value, Accumulator, GroupKeyColumn, GroupKeyValue,
+};
+use crate::processing::GroupByAggregateFunction;
+use crate::util::input::StringOrNumber;
+use crate::util::{duplicate_or_empty_str_slice, DuplicateOrEmpty, Result};
+
+/// The pivot operator reshapes a long table into a wide one, e.g., a time series of measurements per station
+/// into one row per station with one column per time step.
+///
+/// Each distinct combination of values of the `index` columns becomes a row.
+/// Each of the `pivot_values` becomes a column that contains the values of the `value_column` of the rows with
+/// this value in the `pivot_column`. Rows with other values in the `pivot_column` are ignored.
+pub type Pivot = Operator<PivotParams, SingleVectorOrTableSource>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PivotParams {
+    /// The columns that identify a row of the output
+    pub index: Vec<String>,
+    /// The column whose values become columns, must be a text, int or date time column
+    pub pivot_column: String,
+    /// The values of the `pivot_column` that become columns, in the order of the output.
+    /// Date times are given as ISO 8601 strings or milliseconds since the Unix epoch.
+    pub pivot_values: Vec<StringOrNumber>,
+    pub value_column: String,
+    /// How multiple values of the same cell are combined, the last value is kept if not specified
+    #[serde(default)]
+    pub aggregation: Option<GroupByAggregateFunction>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl TableOperator for Pivot {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedTableOperator>> {
+        let params = self.params;
+
+        let mut used_columns = params.index.clone();
+        for column in [&params.pivot_column, &params.value_column] {
+            if !used_columns.contains(column) {
+                used_columns.push(column.clone());
+            }
+        }
+
+        let source = initialize_as_table(self.sources.source, used_columns, context).await?;
+
+        let source_columns = &source.result_descriptor().columns;
+        let column = |name: &String| {
+            source_columns
+                .iter()
+                .position(|column| &column.name == name)
+                .map(|index| (index, source_columns[index].data_type))
+                .ok_or_else(|| Error::ColumnDoesNotExist {
+                    column: name.clone(),
+                })
+        };
+
+        let mut columns = Vec::with_capacity(params.index.len() + params.pivot_values.len());
+        let mut keys = Vec::with_capacity(params.index.len());
+
+        for name in &params.index {
+            let (index, data_type) = column(name)?;
+
+            ensure!(
+                data_type != TableDataType::Float,
+                error::InvalidOperatorSpec {
+                    reason: format!("Cannot use the float column '{}' as index.", name),
+                }
+            );
+
+            keys.push(GroupKeyColumn { index, data_type });
+            columns.push(TableColumn {
+                name: name.clone(),
+                data_type,
+            });
+        }
+
+        let (pivot_index, pivot_data_type) = column(&params.pivot_column)?;
+        let (value_index, value_data_type) = column(&params.value_column)?;
+
+        let cell_data_type = match params.aggregation {
+            None => value_data_type,
+            Some(function) => {
+                ensure!(
+                    function == GroupByAggregateFunction::Count
+                        || matches!(value_data_type, TableDataType::Float | TableDataType::Int),
+                    error::InvalidOperatorSpec {
+                        reason: format!(
+                            "Cannot compute the {} of the non-numeric column '{}'.",
+                            function.name(),
+                            params.value_column
+                        ),
+                    }
+                );
+
+                function.output_data_type()
+            }
+        };
+
+        let mut pivot_values = HashMap::with_capacity(params.pivot_values.len());
+
+        for value in &params.pivot_values {
+            let (key, name) = pivot_key(value, pivot_data_type)?;
+
+            pivot_values.insert(key, pivot_values.len());
+            columns.push(TableColumn {
+                name,
+                data_type: cell_data_type,
+            });
+        }
+
+        let names: Vec<&str> = columns.iter().map(|column| column.name.as_str()).collect();
+        match duplicate_or_empty_str_slice(&names) {
+            DuplicateOrEmpty::Ok => (),
+            DuplicateOrEmpty::Duplicate(name) => {
+                return Err(Error::InvalidOperatorSpec {
+                    reason: format!("The output column '{}' is not unique.", name),
+                })
+            }
+            DuplicateOrEmpty::Empty => {
+                return Err(Error::InvalidOperatorSpec {
+                    reason: "Output columns must not be empty.".to_string(),
+                })
+            }
+        }
+
+        let result_descriptor = TableResultDescriptor { columns };
+
+        Ok(InitializedPivot {
+            schema: Arc::new(result_descriptor.arrow_schema()),
+            result_descriptor,
+            source,
+            layout: Arc::new(PivotLayout {
+                keys,
+                pivot: GroupKeyColumn {
+                    index: pivot_index,
+                    data_type: pivot_data_type,
+                },
+                pivot_values,
+                value: GroupKeyColumn {
+                    index: value_index,
+                    data_type: value_data_type,
+                },
+                aggregation: params.aggregation,
+            }),
+        }
+        .boxed())
+    }
+}
+
+/// Parses a pivot value for a column of `data_type` and returns it with the name of its output column
+fn pivot_key(value: &StringOrNumber, data_type: TableDataType) -> Result<(GroupKeyValue, String)> {
+    Ok(match data_type {
+        TableDataType::Text => {
+            let text = String::try_from(value)?;
+            (GroupKeyValue::Text(text.clone()), text)
+        }
+        TableDataType::Int => {
+            let int = i64::try_from(value)?;
+            (GroupKeyValue::Int(int), int.to_string())
+        }
+        TableDataType::DateTime => {
+            let time = match value {
+                StringOrNumber::String(string) => {
+                    TimeInstance::from_str(string).map_err(|e| Error::InvalidOperatorSpec {
+                        reason: format!("Invalid date time '{}': {}", string, e),
+                    })?
+                }
+                number => TimeInstance::from_millis(i64::try_from(number)?)?,
+            };
+            (GroupKeyValue::Int(time.inner()), time.to_string())
+        }
+        TableDataType::Float | TableDataType::Bool => {
+            return Err(Error::InvalidOperatorSpec {
+                reason: format!("Cannot pivot columns of type {:?}.", data_type),
+            })
+        }
+    })
+}
+
+/// The columns of the source and how they are reshaped
+#[derive(Debug)]
+struct PivotLayout {
+    keys: Vec<GroupKeyColumn>,
+    pivot: GroupKeyColumn,
+    /// The output column index of each pivot value
+    pivot_values: HashMap<GroupKeyValue, usize>,
+    value: GroupKeyColumn,
+    aggregation: Option<GroupByAggregateFunction>,
+}
+
+pub struct InitializedPivot {
+    result_descriptor: TableResultDescriptor,
+    schema: SchemaRef,
+    source: Box<dyn InitializedTableOperator>,
+    layout: Arc<PivotLayout>,
+}
+
+impl InitializedTableOperator for InitializedPivot {
+    fn result_descriptor(&self) -> &TableResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<Box<dyn TableQueryProcessor>> {
+        Ok(TableQueryProcessor::boxed(PivotProcessor {
+            source: self.source.query_processor()?,
+            schema: self.schema.clone(),
+            layout: self.layout.clone(),
+        }))
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
+}
+
+/// A value of the `value_column` that is kept as is
+#[derive(Debug, Clone)]
+enum CellValue {
+    Text(String),
+    Float(f64),
+    Int(i64),
+    Bool(bool),
+}
+
+#[derive(Debug, Clone, Default)]
+struct Cell {
+    accumulator: Accumulator,
+    last: Option<CellValue>,
+}
+
+/// The rows seen so far in the order of their first occurrence
+#[derive(Default)]
+struct PivotRows {
+    indices: HashMap<Vec<GroupKeyValue>, usize>,
+    keys: Vec<Vec<GroupKeyValue>>,
+    cells: Vec<Vec<Cell>>,
+}
+
+impl PivotRows {
+    fn add_batch(&mut self, batch: &RecordBatch, layout: &PivotLayout) -> Result<()> {
+        let key_arrays: Vec<&ArrayRef> = layout
+            .keys
+            .iter()
+            .map(|key| batch.column(key.index))
+            .collect();
+        let pivot_array = batch.column(layout.pivot.index);
+        let value_array = batch.column(layout.value.index);
+
+        for row in 0..batch.num_rows() {
+            let pivot_value = group_key_value(pivot_array, layout.pivot.data_type, row)?;
+            let column = match layout.pivot_values.get(&pivot_value) {
+                Some(&column) => column,
+                None => continue,
+            };
+
+            let key = layout
+                .keys
+                .iter()
+                .zip(&key_arrays)
+                .map(|(key, array)| group_key_value(array, key.data_type, row))
+                .collect::<Result<Vec<_>>>()?;
+
+            let index = if let Some(&index) = self.indices.get(&key) {
+                index
+            } else {
+                let index = self.keys.len();
+                self.indices.insert(key.clone(), index);
+                self.keys.push(key);
+                self.cells
+                    .push(vec![Cell::default(); layout.pivot_values.len()]);
+                index
+            };
+
+            if value_array.is_null(row) {
+                continue;
+            }
+
+            let value = match layout.value.data_type {
+                TableDataType::Text => {
+                    CellValue::Text(downcast::<StringArray>(value_array)?.value(row).to_string())
+                }
+                TableDataType::Float => {
+                    CellValue::Float(downcast::<Float64Array>(value_array)?.value(row))
+                }
+                TableDataType::Int => {
+                    CellValue::Int(downcast::<Int64Array>(value_array)?.value(row))
+                }
+                TableDataType::DateTime => {
+                    CellValue::Int(downcast::<Date64Array>(value_array)?.value(row))
+                }
+                TableDataType::Bool => {
+                    CellValue::Bool(downcast::<BooleanArray>(value_array)?.value(row))
+                }
+            };
+
+            let cell = &mut self.cells[index][column];
+            cell.accumulator.add(match value {
+                CellValue::Float(float) => float,
+                CellValue::Int(int) => int as f64,
+                // only counted, so the value does not matter
+                CellValue::Text(_) | CellValue::Bool(_) => 0.,
+            });
+            cell.last = Some(value);
+        }
+
+        Ok(())
+    }
+
+    fn into_record_batch(self, schema: SchemaRef, layout: &PivotLayout) -> Result<RecordBatch> {
+        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+
+        for (i, key) in layout.keys.iter().enumerate() {
+            let values = self.keys.iter().map(|row| &row[i]);
+
+            arrays.push(match key.data_type {
+                TableDataType::Text => Arc::new(StringArray::from(
+                    values
+                        .map(|value| match value {
+                            GroupKeyValue::Text(value) => Some(value.as_str()),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>(),
+                )),
+                TableDataType::Bool => Arc::new(BooleanArray::from(
+                    values
+                        .map(|value| match value {
+                            GroupKeyValue::Bool(value) => Some(*value),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>(),
+                )),
+                data_type => int_array(
+                    data_type,
+                    values
+                        .map(|value| match value {
+                            GroupKeyValue::Int(value) => Some(*value),
+                            _ => None,
+                        })
+                        .collect(),
+                ),
+            });
+        }
+
+        for column in 0..layout.pivot_values.len() {
+            let cells = self.cells.iter().map(|row| &row[column]);
+
+            arrays.push(match layout.aggregation {
+                // cells without values have a count of zero instead of null
+                Some(GroupByAggregateFunction::Count) => Arc::new(Int64Array::from(
+                    cells
+                        .map(|cell| {
+                            cell.accumulator
+                                .result(GroupByAggregateFunction::Count)
+                                .unwrap_or_default() as i64
+                        })
+                        .collect::<Vec<_>>(),
+                )),
+                Some(function) => Arc::new(Float64Array::from(
+                    cells
+                        .map(|cell| cell.accumulator.result(function))
+                        .collect::<Vec<_>>(),
+                )),
+                None => match layout.value.data_type {
+                    TableDataType::Text => Arc::new(StringArray::from(
+                        cells
+                            .map(|cell| match &cell.last {
+                                Some(CellValue::Text(value)) => Some(value.as_str()),
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>(),
+                    )),
+                    TableDataType::Float => Arc::new(Float64Array::from(
+                        cells
+                            .map(|cell| match cell.last {
+                                Some(CellValue::Float(value)) => Some(value),
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>(),
+                    )),
+                    TableDataType::Bool => Arc::new(BooleanArray::from(
+                        cells
+                            .map(|cell| match cell.last {
+                                Some(CellValue::Bool(value)) => Some(value),
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>(),
+                    )),
+                    data_type => int_array(
+                        data_type,
+                        cells
+                            .map(|cell| match cell.last {
+                                Some(CellValue::Int(value)) => Some(value),
+                                _ => None,
+                            })
+                            .collect(),
+                    ),
+                },
+            });
+        }
+
+        if arrays.is_empty() {
+            return Ok(RecordBatch::new_empty(schema));
+        }
+
+        RecordBatch::try_new(schema, arrays).map_err(Into::into)
+    }
+}
+
+fn int_array(data_type: TableDataType, values: Vec<Option<i64>>) -> ArrayRef {
+    if data_type == TableDataType::DateTime {
+        Arc::new(Date64Array::from(values))
+    } else {
+        Arc::new(Int64Array::from(values))
+    }
+}
+
+pub struct PivotProcessor {
+    source: Box<dyn TableQueryProcessor>,
+    schema: SchemaRef,
+    layout: Arc<PivotLayout>,
+}
+
+#[async_trait]
+impl QueryProcessor for PivotProcessor {
+    type Output = RecordBatch;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let mut batches = self.source.query(query, ctx).await?;
+
+        let stream = futures::stream::once(async move {
+            let mut rows = PivotRows::default();
+
+            while let Some(batch) = batches.next().await {
+                rows.add_batch(&batch?, &self.layout)?;
+            }
+
+            rows.into_record_batch(self.schema.clone(), &self.layout)
+        });
+
+        Ok(stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use futures::TryStreamExt;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    /// A time series of temperatures per station
+    fn source() -> Box<dyn VectorOperator> {
+        let stations = ["a", "a", "b", "b", "a", "b"];
+        let days = [0, 1, 0, 1, 1, 2];
+
+        MockFeatureCollectionSource::single(
+            MultiPointCollection::from_data(
+                MultiPoint::many(vec![(0.0, 0.1); stations.len()]).unwrap(),
+                vec![TimeInterval::default(); stations.len()],
+                [
+                    (
+                        "station".to_string(),
+                        FeatureData::Text(stations.iter().map(ToString::to_string).collect()),
+                    ),
+                    (
+                        "day".to_string(),
+                        FeatureData::DateTime(
+                            days.iter()
+                                .map(|day| TimeInstance::from_millis_unchecked(day * 86_400_000))
+                                .collect(),
+                        ),
+                    ),
+                    (
+                        "temperature".to_string(),
+                        FeatureData::NullableFloat(vec![
+                            Some(1.),
+                            Some(2.),
+                            Some(10.),
+                            None,
+                            Some(4.),
+                            Some(30.),
+                        ]),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .unwrap(),
+        )
+        .boxed()
+    }
+
+    fn pivot(aggregation: Option<GroupByAggregateFunction>) -> Box<dyn TableOperator> {
+        Pivot {
+            params: PivotParams {
+                index: vec!["station".to_string()],
+                pivot_column: "day".to_string(),
+                pivot_values: vec![
+                    StringOrNumber::String("1970-01-01T00:00:00Z".to_string()),
+                    StringOrNumber::Int(86_400_000),
+                ],
+                value_column: "temperature".to_string(),
+                aggregation,
+            },
+            sources: source().into(),
+        }
+        .boxed()
+    }
+
+    async fn query(operator: Box<dyn TableOperator>) -> Result<RecordBatch> {
+        let initialized = operator
+            .initialize(&MockExecutionContext::test_default())
+            .await?;
+
+        let processor = initialized.query_processor()?;
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: Default::default(),
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: None,
+        };
+        let ctx = MockQueryContext::test_default();
+
+        let mut batches: Vec<RecordBatch> = processor
+            .table_query(query, &ctx)
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(batches.remove(0))
+    }
+
+    fn float_column(batch: &RecordBatch, index: usize) -> Vec<Option<f64>> {
+        batch
+            .column(index)
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap()
+            .iter()
+            .collect()
+    }
+
+    #[test]
+    fn serde() {
+        let params: PivotParams = serde_json::from_value(serde_json::json!({
+            "index": ["station"],
+            "pivotColumn": "day",
+            "pivotValues": ["1970-01-01T00:00:00Z", 86_400_000],
+            "valueColumn": "temperature"
+        }))
+        .unwrap();
+
+        assert_eq!(params.pivot_values[1], StringOrNumber::Int(86_400_000));
+        assert_eq!(params.aggregation, None);
+    }
+
+    #[tokio::test]
+    async fn it_pivots() {
+        let batch = query(pivot(None)).await.unwrap();
+
+        assert_eq!(
+            batch
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.name().as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "station",
+                "1970-01-01T00:00:00+00:00",
+                "1970-01-02T00:00:00+00:00"
+            ]
+        );
+
+        let stations: &StringArray = batch.column(0).as_any().downcast_ref().unwrap();
+        assert_eq!(
+            stations.iter().collect::<Vec<_>>(),
+            vec![Some("a"), Some("b")]
+        );
+
+        // the last value of a cell is kept, the value of day 2 is ignored
+        assert_eq!(float_column(&batch, 1), vec![Some(1.), Some(10.)]);
+        assert_eq!(float_column(&batch, 2), vec![Some(4.), None]);
+    }
+
+    #[tokio::test]
+    async fn it_aggregates_cells() {
+        let batch = query(pivot(Some(GroupByAggregateFunction::Mean)))
+            .await
+            .unwrap();
+
+        assert_eq!(float_column(&batch, 1), vec![Some(1.), Some(10.)]);
+        assert_eq!(float_column(&batch, 2), vec![Some(3.), None]);
+
+        let batch = query(pivot(Some(GroupByAggregateFunction::Count)))
+            .await
+            .unwrap();
+
+        let counts: &Int64Array = batch.column(2).as_any().downcast_ref().unwrap();
+        assert_eq!(counts.values(), &[2, 0]);
+    }
+
+    #[tokio::test]
+    async fn it_checks_pivot_values() {
+        let operator = Pivot {
+            params: PivotParams {
+                index: vec!["station".to_string()],
+                pivot_column: "day".to_string(),
+                pivot_values: vec![StringOrNumber::String("yesterday".to_string())],
+                value_column: "temperature".to_string(),
+                aggregation: None,
+            },
+            sources: source().into(),
+        }
+        .boxed();
+
+        assert!(matches!(
+            query(operator).await,
+            Err(Error::InvalidOperatorSpec { .. })
+        ));
+    }
+}
@@ -14,8 +14,9 @@ use snafu::ensure;
 
 use crate::adapters::FeatureCollectionChunkMerger;
 use crate::engine::{
-    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, TypedVectorQueryProcessor,
-    VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, QueryContext,
+    ResultExtent, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorResultDescriptor,
 };
 use crate::engine::{OperatorDatasets, QueryProcessor};
 use crate::error;
@@ -125,6 +126,16 @@ impl InitializedVectorOperator for InitializedPointInPolygonFilter {
     fn result_descriptor(&self) -> &VectorResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.points
+            .cacheability()
+            .combine(self.polygons.cacheability())
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.points.extent().intersection(self.polygons.extent())
+    }
 }
 
 pub struct PointInPolygonFilterProcessor {
@@ -224,32 +235,36 @@ impl VectorQueryProcessor for PointInPolygonFilterProcessor {
     ) -> Result<BoxStream<'a, Result<Self::VectorType>>> {
         let filtered_stream =
             self.points
-                .query(query, ctx)
+                .query(query.clone(), ctx)
                 .await?
-                .and_then(move |points| async move {
-                    if points.is_empty() {
-                        return Ok(points);
+                .and_then(move |points| {
+                    let query = query.clone();
+                    async move {
+                        if points.is_empty() {
+                            return Ok(points);
+                        }
+
+                        let initial_filter = BooleanArray::from(vec![false; points.len()]);
+                        let arc_points = Arc::new(points);
+
+                        let filter = self
+                            .polygons
+                            .query(query, ctx)
+                            .await?
+                            .fold(Ok(initial_filter), |filter, polygons| async {
+                                let polygons = polygons?;
+
+                                if polygons.is_empty() {
+                                    return filter;
+                                }
+
+                                Self::filter_points(ctx, arc_points.clone(), polygons, &filter?)
+                                    .await
+                            })
+                            .await?;
+
+                        arc_points.filter(filter).map_err(Into::into)
                     }
-
-                    let initial_filter = BooleanArray::from(vec![false; points.len()]);
-                    let arc_points = Arc::new(points);
-
-                    let filter = self
-                        .polygons
-                        .query(query, ctx)
-                        .await?
-                        .fold(Ok(initial_filter), |filter, polygons| async {
-                            let polygons = polygons?;
-
-                            if polygons.is_empty() {
-                                return filter;
-                            }
-
-                            Self::filter_points(ctx, arc_points.clone(), polygons, &filter?).await
-                        })
-                        .await?;
-
-                    arc_points.filter(filter).map_err(Into::into)
                 });
 
         Ok(
@@ -387,6 +402,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
         let ctx = MockQueryContext::new(ChunkByteSize::MAX);
 
@@ -436,6 +452,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
         let ctx = MockQueryContext::new(ChunkByteSize::MAX);
 
@@ -498,6 +515,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
         let ctx = MockQueryContext::new(ChunkByteSize::MAX);
 
@@ -577,13 +595,14 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
 
         let ctx_one_chunk = MockQueryContext::new(ChunkByteSize::MAX);
         let ctx_minimal_chunks = MockQueryContext::new(ChunkByteSize::MIN);
 
         let query = query_processor
-            .query(query_rectangle, &ctx_minimal_chunks)
+            .query(query_rectangle.clone(), &ctx_minimal_chunks)
             .await
             .unwrap();
 
@@ -652,6 +671,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((-10., -10.).into(), (10., 10.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
 
         let query_processor = operator.query_processor().unwrap().multi_point().unwrap();
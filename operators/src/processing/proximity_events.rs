@@ -0,0 +1,559 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, OperatorDatasets,
+    QueryContext, QueryProcessor, ResultExtent, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error::{self, Error};
+use crate::processing::vector_join::translation_table;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollectionInfos, FeatureCollectionRowBuilder,
+    GeoFeatureCollectionRowBuilder, GeometryCollection, MultiPointCollection, VectorDataType,
+};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::operations::geodesic::Measurement;
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, FeatureDataType, FeatureDataValue, MultiPoint, TimeInstance,
+    TimeInterval, VectorQueryRectangle,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::sync::Arc;
+
+/// The proximity events operator detects encounters of moving objects from two sources.
+///
+/// Both sources contain the timestamped fixes of moving objects, like the input of the
+/// trajectories operator. An event is emitted for each pair of a left and a right fix that are
+/// at most `maxDistance` apart and whose times differ by at most `timeTolerance` milliseconds.
+/// The event is located at the left fix and its time interval spans both fixes' times.
+///
+/// For geographic spatial references, distances are measured geodesically in meters.
+pub type ProximityEvents = Operator<ProximityEventsParams, ProximityEventsSources>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProximityEventsParams {
+    /// The column that identifies the moving objects of the left source
+    pub left_id_column: String,
+    /// The column that identifies the moving objects of the right source.
+    /// It is suffixed with "right" if it conflicts with the other output columns.
+    pub right_id_column: String,
+    /// The maximum distance of the fixes of an event
+    pub max_distance: f64,
+    /// The maximum difference of the fixes' times in milliseconds
+    #[serde(default)]
+    pub time_tolerance: u64,
+    /// The output column that contains the distance of the fixes
+    pub distance_column: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProximityEventsSources {
+    pub left: Box<dyn VectorOperator>,
+    pub right: Box<dyn VectorOperator>,
+}
+
+impl OperatorDatasets for ProximityEventsSources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.left.datasets_collect(datasets);
+        self.right.datasets_collect(datasets);
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for ProximityEvents {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let left = self.sources.left.initialize(context).await?;
+        let right = self.sources.right.initialize(context).await?;
+
+        for source in [&left, &right] {
+            ensure!(
+                source.result_descriptor().data_type == VectorDataType::MultiPoint,
+                error::InvalidType {
+                    expected: VectorDataType::MultiPoint.to_string(),
+                    found: source.result_descriptor().data_type.to_string(),
+                }
+            );
+        }
+        ensure!(
+            left.result_descriptor().spatial_reference
+                == right.result_descriptor().spatial_reference,
+            error::AllSourcesMustHaveSameSpatialReference
+        );
+        ensure!(
+            self.params.max_distance >= 0.,
+            error::InvalidOperatorSpec {
+                reason: "`maxDistance` must not be negative".to_string(),
+            }
+        );
+        ensure!(
+            self.params.time_tolerance <= i64::MAX as u64,
+            error::InvalidOperatorSpec {
+                reason: "`timeTolerance` is too large".to_string(),
+            }
+        );
+        ensure!(
+            self.params.left_id_column != self.params.distance_column,
+            error::InvalidOperatorSpec {
+                reason: format!(
+                    "Distance column '{}' conflicts with the left id column.",
+                    self.params.distance_column
+                ),
+            }
+        );
+
+        let left_id_type = *left
+            .result_descriptor()
+            .columns
+            .get(&self.params.left_id_column)
+            .ok_or_else(|| Error::ColumnDoesNotExist {
+                column: self.params.left_id_column.clone(),
+            })?;
+        let right_id_type = *right
+            .result_descriptor()
+            .columns
+            .get(&self.params.right_id_column)
+            .ok_or_else(|| Error::ColumnDoesNotExist {
+                column: self.params.right_id_column.clone(),
+            })?;
+
+        let right_id_output = translation_table(
+            [&self.params.left_id_column, &self.params.distance_column].into_iter(),
+            std::iter::once(&self.params.right_id_column),
+            "right",
+        )
+        .remove(&self.params.right_id_column)
+        .expect("translation table contains the right id column");
+
+        let measurement =
+            Measurement::for_spatial_reference(left.result_descriptor().spatial_reference)?;
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiPoint,
+            spatial_reference: left.result_descriptor().spatial_reference,
+            columns: [
+                (self.params.left_id_column.clone(), left_id_type),
+                (right_id_output.clone(), right_id_type),
+                (self.params.distance_column.clone(), FeatureDataType::Float),
+            ]
+            .into_iter()
+            .collect(),
+            column_metadata: left
+                .result_descriptor()
+                .column_metadata_of([&self.params.left_id_column])
+                .into_iter()
+                .chain(
+                    right
+                        .result_descriptor()
+                        .column_metadata
+                        .get(&self.params.right_id_column)
+                        .map(|metadata| (right_id_output.clone(), metadata.clone())),
+                )
+                .collect(),
+        };
+
+        Ok(InitializedProximityEvents {
+            result_descriptor,
+            left,
+            right,
+            state: Arc::new(ProximityEventsState {
+                params: self.params,
+                right_id_output,
+                left_id_type,
+                right_id_type,
+                measurement,
+            }),
+        }
+        .boxed())
+    }
+}
+
+#[derive(Debug)]
+struct ProximityEventsState {
+    params: ProximityEventsParams,
+    right_id_output: String,
+    left_id_type: FeatureDataType,
+    right_id_type: FeatureDataType,
+    measurement: Measurement,
+}
+
+impl ProximityEventsState {
+    fn time_tolerance(&self) -> i64 {
+        self.params.time_tolerance as i64
+    }
+}
+
+pub struct InitializedProximityEvents {
+    result_descriptor: VectorResultDescriptor,
+    left: Box<dyn InitializedVectorOperator>,
+    right: Box<dyn InitializedVectorOperator>,
+    state: Arc<ProximityEventsState>,
+}
+
+impl InitializedVectorOperator for InitializedProximityEvents {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let multi_point_processor = |source: &dyn InitializedVectorOperator| {
+            source
+                .query_processor()?
+                .multi_point()
+                .ok_or_else(|| Error::InvalidVectorType {
+                    expected: VectorDataType::MultiPoint.to_string(),
+                    found: source.result_descriptor().data_type.to_string(),
+                })
+        };
+
+        Ok(TypedVectorQueryProcessor::MultiPoint(
+            ProximityEventsProcessor {
+                left: multi_point_processor(self.left.as_ref())?,
+                right: multi_point_processor(self.right.as_ref())?,
+                state: self.state.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.left.cacheability().combine(self.right.cacheability())
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.left.extent().intersection(self.right.extent())
+    }
+}
+
+pub struct ProximityEventsProcessor {
+    left: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    right: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    state: Arc<ProximityEventsState>,
+}
+
+#[async_trait]
+impl QueryProcessor for ProximityEventsProcessor {
+    type Output = MultiPointCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let time_tolerance = self.state.time_tolerance();
+        let right_query = VectorQueryRectangle {
+            spatial_bounds: self
+                .state
+                .measurement
+                .enlarge_bbox(query.spatial_bounds, self.state.params.max_distance),
+            time_interval: TimeInterval::new(
+                TimeInstance::from_millis_unchecked(
+                    query
+                        .time_interval
+                        .start()
+                        .inner()
+                        .saturating_sub(time_tolerance)
+                        .max(TimeInstance::MIN.inner()),
+                ),
+                TimeInstance::from_millis_unchecked(
+                    query
+                        .time_interval
+                        .end()
+                        .inner()
+                        .saturating_add(time_tolerance)
+                        .min(TimeInstance::MAX.inner()),
+                ),
+            )?,
+            spatial_resolution: query.spatial_resolution,
+            spatial_filter: None,
+        };
+
+        let fixes = Arc::new(
+            self.right
+                .query(right_query, ctx)
+                .await?
+                .try_fold(
+                    RightFixes::new(self.state.params.right_id_column.clone()),
+                    |mut fixes, collection| async move {
+                        fixes.add_collection(&collection)?;
+                        Ok(fixes)
+                    },
+                )
+                .await?
+                .sorted(),
+        );
+
+        let state = self.state.clone();
+
+        let stream = self
+            .left
+            .query(query, ctx)
+            .await?
+            .and_then(move |collection| {
+                let fixes = fixes.clone();
+                let state = state.clone();
+
+                async move {
+                    crate::util::spawn_blocking(move || fixes.events(&collection, &state)).await?
+                }
+            });
+
+        Ok(stream.boxed())
+    }
+}
+
+/// The fixes of the right source, ordered by time after calling `sorted`
+struct RightFixes {
+    id_column: String,
+    fixes: Vec<(i64, Coordinate2D, FeatureDataValue)>,
+}
+
+impl RightFixes {
+    fn new(id_column: String) -> Self {
+        Self {
+            id_column,
+            fixes: Vec::new(),
+        }
+    }
+
+    fn add_collection(&mut self, collection: &MultiPointCollection) -> Result<()> {
+        let ids = collection.data(&self.id_column)?;
+        let coordinates = collection.coordinates();
+        let offsets = collection.feature_offsets();
+
+        for (i, time) in collection.time_intervals().iter().enumerate() {
+            self.fixes.push((
+                time.start().inner(),
+                coordinates[offsets[i] as usize],
+                ids.get_unchecked(i),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn sorted(mut self) -> Self {
+        self.fixes.sort_by_key(|(time, _, _)| *time);
+        self
+    }
+
+    /// Finds the right fixes near the fixes of the left `collection`
+    fn events(
+        &self,
+        collection: &MultiPointCollection,
+        state: &ProximityEventsState,
+    ) -> Result<MultiPointCollection> {
+        let params = &state.params;
+        let time_tolerance = state.time_tolerance();
+
+        let ids = collection.data(&params.left_id_column)?;
+        let coordinates = collection.coordinates();
+        let offsets = collection.feature_offsets();
+
+        let mut builder = MultiPointCollection::builder();
+        builder.add_column(params.left_id_column.clone(), state.left_id_type)?;
+        builder.add_column(state.right_id_output.clone(), state.right_id_type)?;
+        builder.add_column(params.distance_column.clone(), FeatureDataType::Float)?;
+        let mut builder = builder.finish_header();
+
+        for (i, time) in collection.time_intervals().iter().enumerate() {
+            let left_time = time.start().inner();
+            let left_coordinate = coordinates[offsets[i] as usize];
+
+            let first = self
+                .fixes
+                .partition_point(|(time, _, _)| *time < left_time.saturating_sub(time_tolerance));
+
+            for (right_time, right_coordinate, right_id) in self.fixes[first..]
+                .iter()
+                .take_while(|(time, _, _)| *time <= left_time.saturating_add(time_tolerance))
+            {
+                let distance = state
+                    .measurement
+                    .distance(left_coordinate, *right_coordinate);
+                if distance > params.max_distance {
+                    continue;
+                }
+
+                builder.push_geometry(MultiPoint::new(vec![left_coordinate])?)?;
+                builder.push_time_interval(TimeInterval::new(
+                    left_time.min(*right_time),
+                    left_time.max(*right_time),
+                )?)?;
+                push_id(&mut builder, &params.left_id_column, ids.get_unchecked(i))?;
+                push_id(&mut builder, &state.right_id_output, right_id.clone())?;
+                builder.push_data(&params.distance_column, FeatureDataValue::Float(distance))?;
+                builder.finish_row();
+            }
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+fn push_id(
+    builder: &mut FeatureCollectionRowBuilder<MultiPoint>,
+    column: &str,
+    id: FeatureDataValue,
+) -> Result<()> {
+    let is_null = matches!(
+        id,
+        FeatureDataValue::NullableCategory(None)
+            | FeatureDataValue::NullableInt(None)
+            | FeatureDataValue::NullableFloat(None)
+            | FeatureDataValue::NullableText(None)
+            | FeatureDataValue::NullableBool(None)
+            | FeatureDataValue::NullableDateTime(None)
+    );
+
+    if is_null {
+        builder.push_null(column)?;
+    } else {
+        builder.push_data(column, id)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::primitives::{FeatureData, SpatialResolution};
+    use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceAuthority};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn fixes(
+        id_column: &str,
+        ids: Vec<i64>,
+        coordinates: Vec<(f64, f64)>,
+        times: Vec<i64>,
+    ) -> Box<dyn VectorOperator> {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(coordinates).unwrap(),
+            times
+                .into_iter()
+                .map(|t| TimeInterval::new_unchecked(t, t + 1))
+                .collect(),
+            [(id_column.to_string(), FeatureData::Int(ids))]
+                .into_iter()
+                .collect(),
+        )
+        .unwrap();
+
+        MockFeatureCollectionSource::with_collections_and_sref(
+            vec![collection],
+            SpatialReference::new(SpatialReferenceAuthority::Epsg, 3857),
+        )
+        .boxed()
+    }
+
+    #[tokio::test]
+    async fn it_detects_encounters() {
+        let operator = ProximityEvents {
+            params: ProximityEventsParams {
+                left_id_column: "id".to_string(),
+                right_id_column: "id".to_string(),
+                max_distance: 5.,
+                time_tolerance: 10,
+                distance_column: "distance".to_string(),
+            },
+            sources: ProximityEventsSources {
+                left: fixes(
+                    "id",
+                    vec![1, 1, 2],
+                    vec![(0., 0.), (10., 0.), (50., 50.)],
+                    vec![0, 100, 0],
+                ),
+                right: fixes(
+                    "id",
+                    vec![7, 7, 8],
+                    vec![(3., 4.), (10., 1.), (50., 50.)],
+                    vec![5, 200, 20],
+                ),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        assert_eq!(
+            operator.result_descriptor().columns,
+            [
+                ("id".to_string(), FeatureDataType::Int),
+                ("idright".to_string(), FeatureDataType::Int),
+                ("distance".to_string(), FeatureDataType::Float),
+            ]
+            .into_iter()
+            .collect()
+        );
+
+        let processor = operator.query_processor().unwrap().multi_point().unwrap();
+
+        let results: Vec<MultiPointCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-10., -10.).into(), (100., 100.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::new_unchecked(0, 1_000),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        // the second fix of 1 is close to 7 but too late, 2 and 8 are too far apart in time
+        let expected = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0., 0.)]).unwrap(),
+            vec![TimeInterval::new_unchecked(0, 5)],
+            [
+                ("id".to_string(), FeatureData::Int(vec![1])),
+                ("idright".to_string(), FeatureData::Int(vec![7])),
+                ("distance".to_string(), FeatureData::Float(vec![5.])),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+
+        assert_eq!(results, vec![expected]);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_negative_distances() {
+        let operator = ProximityEvents {
+            params: ProximityEventsParams {
+                left_id_column: "id".to_string(),
+                right_id_column: "id".to_string(),
+                max_distance: -1.,
+                time_tolerance: 0,
+                distance_column: "distance".to_string(),
+            },
+            sources: ProximityEventsSources {
+                left: fixes("id", vec![1], vec![(0., 0.)], vec![0]),
+                right: fixes("id", vec![1], vec![(0., 0.)], vec![0]),
+            },
+        };
+
+        assert!(Box::new(operator)
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,642 @@
+use crate::engine::{
+    BoxRasterQueryProcessor, Cacheability, ExecutionContext, InitializedRasterOperator,
+    InitializedScalarOperator, Operator, OperatorDatasets, QueryContext, QueryProcessor,
+    RasterOperator, RasterQueryProcessor, RasterResultDescriptor, ResultExtent, ScalarOperator,
+    ScalarQueryProcessor, ScalarResultDescriptor, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::pixel_time_series::{tile_from_pixels, PixelTimeSeries};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{
+    ConfidenceInterval, Measurement, RasterQueryRectangle, Scalar, SpatialPartition2D,
+    VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::{
+    GridOrEmpty, GridSize, NoDataValue, RasterDataType, RasterTile2D, TileInformation,
+    TilingSpecification,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// The output of per-pixel coefficients, pixels without enough observations are `NaN`
+type PixelOut = f32;
+const OUT_NO_DATA_VALUE: PixelOut = PixelOut::NAN;
+
+/// The two-sided 95 % quantile of the standard normal distribution
+const Z_95: f64 = 1.959_963_984_540_054;
+
+/// The `RasterCorrelation` operator relates the values of two co-registered rasters, e.g.,
+/// precipitation and NDVI, by their correlation or a simple linear regression of `y` on `x`.
+///
+/// As a scalar operator, it computes one coefficient from all pixels and time steps of the query
+/// where both rasters have data.
+/// As a raster operator, it computes the coefficient of each pixel from its time series within
+/// the query's time interval, s.t. each output tile covers the whole interval.
+///
+/// Both sources must have the same spatial reference and the same time steps.
+pub type RasterCorrelation = Operator<RasterCorrelationParams, RasterCorrelationSources>;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterCorrelationParams {
+    pub statistic: CorrelationStatistic,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RasterCorrelationSources {
+    /// The explanatory variable of a regression
+    pub x: Box<dyn RasterOperator>,
+    /// The dependent variable of a regression
+    pub y: Box<dyn RasterOperator>,
+}
+
+impl OperatorDatasets for RasterCorrelationSources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        self.x.datasets_collect(datasets);
+        self.y.datasets_collect(datasets);
+    }
+}
+
+/// The coefficient that is computed from the pairs of values of `x` and `y`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CorrelationStatistic {
+    /// Pearson's correlation coefficient of the values
+    Pearson,
+    /// Spearman's rank correlation coefficient, i.e., Pearson's coefficient of the ranks
+    Spearman,
+    /// The slope of the least squares regression line of `y` on `x`
+    Slope,
+    /// The intercept of the least squares regression line of `y` on `x`
+    Intercept,
+    /// The coefficient of determination of the regression
+    RSquared,
+}
+
+impl CorrelationStatistic {
+    /// Computes the statistic or `None` if it is undefined, e.g., for constant values
+    fn compute(self, pairs: &[(f64, f64)]) -> Option<f64> {
+        if pairs.len() < 2 {
+            return None;
+        }
+
+        let ranked;
+        let pairs = if self == CorrelationStatistic::Spearman {
+            let (x, y): (Vec<f64>, Vec<f64>) = pairs.iter().copied().unzip();
+            ranked = ranks(&x).into_iter().zip(ranks(&y)).collect::<Vec<_>>();
+            &ranked
+        } else {
+            pairs
+        };
+
+        let len = pairs.len() as f64;
+        let (sum_x, sum_y) = pairs
+            .iter()
+            .fold((0., 0.), |(sum_x, sum_y), (x, y)| (sum_x + x, sum_y + y));
+        let (mean_x, mean_y) = (sum_x / len, sum_y / len);
+
+        let (mut sxx, mut syy, mut sxy) = (0., 0., 0.);
+        for (x, y) in pairs {
+            let (dx, dy) = (x - mean_x, y - mean_y);
+            sxx += dx * dx;
+            syy += dy * dy;
+            sxy += dx * dy;
+        }
+
+        if sxx == 0. {
+            return None;
+        }
+
+        let slope = sxy / sxx;
+
+        match self {
+            CorrelationStatistic::Pearson | CorrelationStatistic::Spearman => {
+                (syy > 0.).then(|| (sxy / (sxx * syy).sqrt()).clamp(-1., 1.))
+            }
+            CorrelationStatistic::Slope => Some(slope),
+            CorrelationStatistic::Intercept => Some(mean_y - slope * mean_x),
+            CorrelationStatistic::RSquared => (syy > 0.).then(|| (sxy * sxy / (sxx * syy)).min(1.)),
+        }
+    }
+
+    /// The intercept has the unit of `y`, the other statistics are unitless
+    fn measurement(self, y: &Measurement) -> Measurement {
+        match self {
+            CorrelationStatistic::Intercept => y.clone(),
+            _ => Measurement::Unitless,
+        }
+    }
+}
+
+/// The ranks of the `values` starting at 1, tied values get the average of their ranks
+#[allow(clippy::float_cmp)] // ties are exactly equal values
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_unstable_by(|&a, &b| {
+        values[a]
+            .partial_cmp(&values[b])
+            .expect("`NaN` values are filtered")
+    });
+
+    let mut ranks = vec![0.; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+
+        // the average of the ranks `start + 1 ..= end`
+        let rank = (start + end + 1) as f64 / 2.;
+        for &index in &order[start..end] {
+            ranks[index] = rank;
+        }
+
+        start = end;
+    }
+
+    ranks
+}
+
+/// The 95 % confidence interval of Pearson's coefficient `r` of `n` pairs by Fisher's z-transformation
+fn pearson_confidence_interval(r: f64, n: u64) -> Option<ConfidenceInterval> {
+    if n <= 3 || r.abs() >= 1. {
+        return None;
+    }
+
+    let z = r.atanh();
+    let standard_error = 1. / ((n - 3) as f64).sqrt();
+
+    Some(ConfidenceInterval {
+        level: 0.95,
+        lower: (z - Z_95 * standard_error).tanh(),
+        upper: (z + Z_95 * standard_error).tanh(),
+    })
+}
+
+impl RasterCorrelation {
+    async fn initialize_sources(
+        self,
+        context: &dyn ExecutionContext,
+    ) -> Result<InitializedRasterCorrelation> {
+        let x = self.sources.x.initialize(context).await?;
+        let y = self.sources.y.initialize(context).await?;
+
+        let spatial_reference = x.result_descriptor().spatial_reference;
+        ensure!(
+            spatial_reference == y.result_descriptor().spatial_reference,
+            error::InvalidSpatialReference {
+                expected: spatial_reference,
+                found: y.result_descriptor().spatial_reference,
+            }
+        );
+
+        let measurement = self
+            .params
+            .statistic
+            .measurement(&y.result_descriptor().measurement);
+
+        Ok(InitializedRasterCorrelation {
+            raster_result_descriptor: RasterResultDescriptor {
+                data_type: RasterDataType::F32,
+                spatial_reference,
+                measurement: measurement.clone(),
+                no_data_value: Some(f64::from(OUT_NO_DATA_VALUE)),
+                dimensions: vec![],
+            },
+            scalar_result_descriptor: ScalarResultDescriptor {
+                spatial_reference,
+                measurement,
+            },
+            x,
+            y,
+            statistic: self.params.statistic,
+            tiling_specification: context.tiling_specification(),
+        })
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for RasterCorrelation {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        Ok(InitializedRasterOperator::boxed(
+            self.initialize_sources(context).await?,
+        ))
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl ScalarOperator for RasterCorrelation {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedScalarOperator>> {
+        Ok(InitializedScalarOperator::boxed(
+            self.initialize_sources(context).await?,
+        ))
+    }
+}
+
+pub struct InitializedRasterCorrelation {
+    raster_result_descriptor: RasterResultDescriptor,
+    scalar_result_descriptor: ScalarResultDescriptor,
+    x: Box<dyn InitializedRasterOperator>,
+    y: Box<dyn InitializedRasterOperator>,
+    statistic: CorrelationStatistic,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterCorrelation {
+    fn processor(&self) -> Result<RasterCorrelationProcessor> {
+        Ok(RasterCorrelationProcessor {
+            x: self.x.query_processor()?.into_f64(),
+            y: self.y.query_processor()?.into_f64(),
+            statistic: self.statistic,
+            measurement: self.scalar_result_descriptor.measurement.clone(),
+            tiling_specification: self.tiling_specification,
+        })
+    }
+}
+
+impl InitializedRasterOperator for InitializedRasterCorrelation {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.raster_result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.x.cacheability().combine(self.y.cacheability())
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.x.extent().intersection(self.y.extent())
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        Ok(TypedRasterQueryProcessor::F32(RasterQueryProcessor::boxed(
+            self.processor()?,
+        )))
+    }
+}
+
+impl InitializedScalarOperator for InitializedRasterCorrelation {
+    fn result_descriptor(&self) -> &ScalarResultDescriptor {
+        &self.scalar_result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        InitializedRasterOperator::cacheability(self)
+    }
+
+    fn query_processor(&self) -> Result<Box<dyn ScalarQueryProcessor>> {
+        Ok(ScalarQueryProcessor::boxed(self.processor()?))
+    }
+}
+
+pub struct RasterCorrelationProcessor {
+    x: BoxRasterQueryProcessor<f64>,
+    y: BoxRasterQueryProcessor<f64>,
+    statistic: CorrelationStatistic,
+    measurement: Measurement,
+    tiling_specification: TilingSpecification,
+}
+
+impl RasterCorrelationProcessor {
+    async fn coefficient_tile(
+        &self,
+        tile_info: TileInformation,
+        query: RasterQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<RasterTile2D<PixelOut>> {
+        let x = PixelTimeSeries::query(self.x.as_ref(), tile_info, query, ctx).await?;
+        let y = PixelTimeSeries::query(self.y.as_ref(), tile_info, query, ctx).await?;
+
+        ensure!(x.is_aligned_with(&y), error::CorrelationSourcesNotAligned);
+
+        let statistic = self.statistic;
+
+        Ok(
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                coefficients(statistic, tile_info, query, &x, &y)
+            })
+            .await?,
+        )
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for RasterCorrelationProcessor {
+    type Output = RasterTile2D<PixelOut>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let tiling_strategy = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+        let stream = stream::iter(tiling_strategy.tile_information_iterator(query.spatial_bounds))
+            .then(move |tile_info| self.coefficient_tile(tile_info, query, ctx));
+
+        Ok(stream.boxed())
+    }
+}
+
+#[async_trait]
+impl ScalarQueryProcessor for RasterCorrelationProcessor {
+    async fn scalar_query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<Scalar> {
+        let query: RasterQueryRectangle = query.into();
+
+        let mut tiles = self
+            .x
+            .raster_query(query, ctx)
+            .await?
+            .zip(self.y.raster_query(query, ctx).await?);
+
+        let mut pairs = Vec::new();
+        while let Some((x, y)) = tiles.next().await {
+            let (x, y) = (x?, y?);
+            ensure!(aligned(&x, &y), error::CorrelationSourcesNotAligned);
+
+            pairs.extend(
+                (0..x
+                    .tile_information()
+                    .tile_size_in_pixels
+                    .number_of_elements())
+                    .filter_map(|pixel| value_of(&x, pixel).zip(value_of(&y, pixel))),
+            );
+        }
+
+        let statistic = self.statistic;
+        let sample_size = pairs.len() as u64;
+
+        let value =
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                statistic.compute(&pairs)
+            })
+            .await?;
+
+        let scalar = Scalar::new(
+            value.unwrap_or(f64::NAN),
+            self.measurement.clone(),
+            sample_size,
+        );
+
+        Ok(match (statistic, value) {
+            (CorrelationStatistic::Pearson, Some(r)) => {
+                match pearson_confidence_interval(r, sample_size) {
+                    Some(confidence_interval) => {
+                        scalar.with_confidence_interval(confidence_interval)
+                    }
+                    None => scalar,
+                }
+            }
+            _ => scalar,
+        })
+    }
+}
+
+/// Tiles of both sources belong together if they are at the same position and time
+fn aligned(x: &RasterTile2D<f64>, y: &RasterTile2D<f64>) -> bool {
+    x.tile_position == y.tile_position && x.time.intersects(&y.time)
+}
+
+fn value_of(tile: &RasterTile2D<f64>, pixel: usize) -> Option<f64> {
+    match &tile.grid_array {
+        GridOrEmpty::Grid(grid) if !grid.is_no_data(grid.data[pixel]) => {
+            Some(grid.data[pixel]).filter(|value| !value.is_nan())
+        }
+        _ => None,
+    }
+}
+
+/// Computes the coefficient of each pixel of a tile from its time series
+fn coefficients(
+    statistic: CorrelationStatistic,
+    tile_info: TileInformation,
+    query: RasterQueryRectangle,
+    x: &PixelTimeSeries,
+    y: &PixelTimeSeries,
+) -> RasterTile2D<PixelOut> {
+    let mut pairs = Vec::with_capacity(x.time_steps().len());
+    let data: Vec<PixelOut> = (0..tile_info.tile_size_in_pixels.number_of_elements())
+        .map(|pixel| {
+            pairs.clear();
+            pairs.extend(
+                x.pixel(pixel)
+                    .iter()
+                    .copied()
+                    .zip(y.pixel(pixel).iter().copied())
+                    .filter(|(x, y)| !x.is_nan() && !y.is_nan()),
+            );
+
+            statistic
+                .compute(&pairs)
+                .map_or(OUT_NO_DATA_VALUE, |value| value as PixelOut)
+        })
+        .collect();
+
+    tile_from_pixels(query.time_interval, tile_info, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{BoundingBox2D, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::Grid2D;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn make_raster(data: [Vec<u8>; 3]) -> Box<dyn RasterOperator> {
+        let tile_information = TileInformation {
+            global_tile_position: [-1, 0].into(),
+            tile_size_in_pixels: [2, 2].into(),
+            global_geo_transform: TestDefault::test_default(),
+        };
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: data
+                    .into_iter()
+                    .enumerate()
+                    .map(|(time, data)| {
+                        RasterTile2D::new_with_tile_info(
+                            TimeInterval::new_unchecked(time as i64, time as i64 + 1),
+                            tile_information,
+                            Grid2D::new([2, 2].into(), data, Some(255)).unwrap().into(),
+                        )
+                    })
+                    .collect(),
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: Some(255.),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed()
+    }
+
+    /// Pixel 0 grows linearly with `x`, pixel 1 falls and `x` is constant or missing at the others
+    fn correlation(statistic: CorrelationStatistic) -> RasterCorrelation {
+        RasterCorrelation {
+            params: RasterCorrelationParams { statistic },
+            sources: RasterCorrelationSources {
+                x: make_raster([vec![1, 1, 1, 255], vec![2, 2, 1, 255], vec![3, 3, 1, 255]]),
+                y: make_raster([vec![3, 5, 1, 1], vec![5, 4, 2, 2], vec![7, 3, 3, 3]]),
+            },
+        }
+    }
+
+    fn execution_context() -> MockExecutionContext {
+        MockExecutionContext::new_with_tiling_spec(TilingSpecification::new(
+            (0., 0.).into(),
+            [2, 2].into(),
+        ))
+    }
+
+    async fn coefficients(statistic: CorrelationStatistic) -> Vec<f32> {
+        let operator = RasterOperator::boxed(correlation(statistic))
+            .initialize(&execution_context())
+            .await
+            .unwrap();
+
+        let processor = operator.query_processor().unwrap().get_f32().unwrap();
+
+        let ctx = MockQueryContext::new(1.into());
+        let tiles: Vec<RasterTile2D<f32>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 2.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval: TimeInterval::new_unchecked(0, 3),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
+                },
+                &ctx,
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].time, TimeInterval::new_unchecked(0, 3));
+        tiles[0].grid_array.clone().into_materialized_grid().data
+    }
+
+    async fn scalar(statistic: CorrelationStatistic) -> Scalar {
+        let operator = ScalarOperator::boxed(correlation(statistic))
+            .initialize(&execution_context())
+            .await
+            .unwrap();
+
+        let ctx = MockQueryContext::new(1.into());
+        operator
+            .query_processor()
+            .unwrap()
+            .scalar_query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new_unchecked((0., 0.).into(), (2., 2.).into()),
+                    time_interval: TimeInterval::new_unchecked(0, 3),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
+                },
+                &ctx,
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_computes_per_pixel_regressions() {
+        let pearson = coefficients(CorrelationStatistic::Pearson).await;
+        assert_eq!(pearson[..2], [1., -1.]);
+        assert!(pearson[2].is_nan());
+        assert!(pearson[3].is_nan());
+
+        assert_eq!(
+            coefficients(CorrelationStatistic::Slope).await[..2],
+            [2., -1.]
+        );
+        assert_eq!(
+            coefficients(CorrelationStatistic::Intercept).await[..2],
+            [1., 6.]
+        );
+        assert_eq!(
+            coefficients(CorrelationStatistic::RSquared).await[..2],
+            [1., 1.]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_computes_the_correlation_of_all_pixels() {
+        let pearson = scalar(CorrelationStatistic::Pearson).await;
+
+        assert_eq!(pearson.sample_size, 9);
+        assert_eq!(pearson.measurement, Measurement::Unitless);
+        float_cmp::assert_approx_eq!(
+            f64,
+            pearson.value.unwrap(),
+            0.560_448_538_317_805,
+            epsilon = 1e-10
+        );
+
+        let confidence_interval = pearson.confidence_interval.unwrap();
+        float_cmp::assert_approx_eq!(
+            f64,
+            confidence_interval.lower,
+            -0.165_138_845_373_812,
+            epsilon = 1e-10
+        );
+        float_cmp::assert_approx_eq!(
+            f64,
+            confidence_interval.upper,
+            0.892_409_893_358_845,
+            epsilon = 1e-10
+        );
+
+        let slope = scalar(CorrelationStatistic::Slope).await;
+        float_cmp::assert_approx_eq!(f64, slope.value.unwrap(), 7. / 6., epsilon = 1e-10);
+        assert!(slope.confidence_interval.is_none());
+    }
+
+    #[test]
+    fn it_ranks_ties_by_their_average_rank() {
+        assert_eq!(ranks(&[3., 1., 2., 2.]), vec![4., 1., 2.5, 2.5]);
+    }
+
+    #[test]
+    fn it_computes_spearman_on_ranks() {
+        // monotonic but not linear
+        let pairs = [(1., 1.), (2., 4.), (3., 9.), (4., 100.)];
+
+        assert_eq!(CorrelationStatistic::Spearman.compute(&pairs), Some(1.));
+        assert!(CorrelationStatistic::Pearson.compute(&pairs).unwrap() < 1.);
+        assert_eq!(CorrelationStatistic::Pearson.compute(&pairs[..1]), None);
+    }
+}
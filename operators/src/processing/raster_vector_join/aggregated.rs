@@ -75,6 +75,7 @@ where
                 spatial_bounds: query.spatial_bounds,
                 time_interval: time_span.time_interval,
                 spatial_resolution: query.spatial_resolution,
+                spatial_filter: None,
             };
 
             let mut rasters = raster_processor.raster_query(query.into(), ctx).await?;
@@ -198,16 +199,18 @@ where
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
         let stream = self.collection
-            .query(query, ctx).await?
-            .and_then(move |mut collection| async move {
+            .query(query.clone(), ctx).await?
+            .and_then(move |mut collection| {
+                let query = query.clone();
+                async move {
+                    for (raster, new_column_name) in self.raster_processors.iter().zip(&self.column_names) {
+                        collection = call_on_generic_raster_processor!(raster, raster => {
+                            Self::extract_raster_values(&collection, raster, new_column_name, self.feature_aggregation, self.temporal_aggregation, query.clone(), ctx).await?
+                        });
+                    }
 
-                for (raster, new_column_name) in self.raster_processors.iter().zip(&self.column_names) {
-                    collection = call_on_generic_raster_processor!(raster, raster => {
-                        Self::extract_raster_values(&collection, raster, new_column_name, self.feature_aggregation, self.temporal_aggregation, query, ctx).await?
-                    });
+                    Ok(collection)
                 }
-
-                Ok(collection)
             })
             .boxed();
 
@@ -256,6 +259,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None,
+                    dimensions: vec![],
                 },
             },
         }
@@ -292,6 +296,7 @@ mod tests {
                 spatial_bounds: BoundingBox2D::new((0.0, -3.0).into(), (2.0, 0.).into()).unwrap(),
                 time_interval: Default::default(),
                 spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                spatial_filter: None,
             },
             &MockQueryContext::new(ChunkByteSize::MIN),
         )
@@ -339,6 +344,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None,
+                    dimensions: vec![],
                 },
             },
         }
@@ -375,6 +381,7 @@ mod tests {
                 spatial_bounds: BoundingBox2D::new((0.0, -3.0).into(), (2.0, 0.0).into()).unwrap(),
                 time_interval: Default::default(),
                 spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                spatial_filter: None,
             },
             &MockQueryContext::new(ChunkByteSize::MIN),
         )
@@ -449,6 +456,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None,
+                    dimensions: vec![],
                 },
             },
         }
@@ -481,6 +489,7 @@ mod tests {
                 spatial_bounds: BoundingBox2D::new((0.0, -3.0).into(), (4.0, 0.0).into()).unwrap(),
                 time_interval: Default::default(),
                 spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                spatial_filter: None,
             },
             &MockQueryContext::new(ChunkByteSize::MIN),
         )
@@ -559,6 +568,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None,
+                    dimensions: vec![],
                 },
             },
         }
@@ -593,6 +603,7 @@ mod tests {
                 spatial_bounds: BoundingBox2D::new((0.0, -3.0).into(), (4.0, 0.0).into()).unwrap(),
                 time_interval: Default::default(),
                 spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                spatial_filter: None,
             },
             &MockQueryContext::new(ChunkByteSize::MIN),
         )
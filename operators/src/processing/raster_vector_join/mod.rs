@@ -1,11 +1,11 @@
 mod aggregated;
 mod aggregator;
 mod non_aggregated;
-mod util;
+pub(crate) mod util;
 
 use crate::engine::{
-    ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, Operator,
-    SingleVectorMultipleRasterSources, TypedVectorQueryProcessor, VectorOperator,
+    Cacheability, ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, Operator,
+    ResultExtent, SingleVectorMultipleRasterSources, TypedVectorQueryProcessor, VectorOperator,
     VectorQueryProcessor, VectorResultDescriptor,
 };
 use crate::error::{self, Error};
@@ -232,6 +232,20 @@ impl InitializedVectorOperator for InitializedRasterVectorJoin {
             TypedVectorQueryProcessor::MultiLineString(_) => return Err(Error::NotYetImplemented),
         })
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source
+            .cacheability()
+            .combine(Cacheability::combine_all(
+                self.raster_sources
+                    .iter()
+                    .map(InitializedRasterOperator::cacheability),
+            ))
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.vector_source.extent()
+    }
 }
 
 pub fn create_feature_aggregator<P: Pixel>(
@@ -377,6 +391,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -450,6 +465,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -524,6 +540,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MIN),
             )
@@ -66,7 +66,7 @@ where
                 collection,
                 raster_processor,
                 new_column_name,
-                query,
+                query.clone(),
                 ctx,
                 aggregation_method,
             )
@@ -110,6 +110,7 @@ where
                 .and_then(|time| time.intersect(&query.time_interval))
                 .unwrap_or(query.time_interval),
             spatial_resolution: query.spatial_resolution,
+            spatial_filter: None,
         };
 
         let raster_query = raster_processor.raster_query(query.into(), ctx).await?;
@@ -254,7 +255,7 @@ where
         query: VectorQueryRectangle,
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
-        let mut stream = self.collection.query(query, ctx).await?;
+        let mut stream = self.collection.query(query.clone(), ctx).await?;
 
         for (raster_processor, new_column_name) in
             self.raster_processors.iter().zip(&self.column_names)
@@ -263,7 +264,7 @@ where
                 stream,
                 raster_processor,
                 new_column_name,
-                query,
+                query.clone(),
                 ctx,
                 self.aggregation_method,
             )
@@ -359,6 +360,7 @@ mod tests {
                         .unwrap(),
                     time_interval: time_instant,
                     spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MAX),
             )
@@ -456,6 +458,7 @@ mod tests {
                     )
                     .unwrap(),
                     spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MAX),
             )
@@ -555,6 +558,7 @@ mod tests {
                     )
                     .unwrap(),
                     spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MAX),
             )
@@ -659,6 +663,7 @@ mod tests {
                     )
                     .unwrap(),
                     spatial_resolution: SpatialResolution::new(0.1, 0.1).unwrap(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MAX),
             )
@@ -769,6 +774,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None,
+                    dimensions: vec![],
                 },
             },
         }
@@ -821,6 +827,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::new_unchecked(0, 20),
                     spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MAX),
             )
@@ -925,6 +932,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None,
+                    dimensions: vec![],
                 },
             },
         }
@@ -979,6 +987,7 @@ mod tests {
                         .unwrap(),
                     time_interval: TimeInterval::new_unchecked(0, 20),
                     spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                    spatial_filter: None,
                 },
                 &MockQueryContext::new(ChunkByteSize::MAX),
             )
@@ -2,10 +2,11 @@ use super::map_query::MapQueryProcessor;
 use crate::{
     adapters::{fold_by_coordinate_lookup_future, RasterSubQueryAdapter, TileReprojectionSubQuery},
     engine::{
-        ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, Operator,
-        QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor, RasterResultDescriptor,
-        SingleRasterOrVectorSource, TypedRasterQueryProcessor, TypedVectorQueryProcessor,
-        VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+        Cacheability, ExecutionContext, InitializedRasterOperator, InitializedVectorOperator,
+        Operator, QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor,
+        RasterResultDescriptor, ResultExtent, SingleRasterOrVectorSource,
+        TypedRasterQueryProcessor, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+        VectorResultDescriptor,
     },
     error,
     util::{input::RasterOrVectorOperator, Result},
@@ -83,6 +84,7 @@ impl VectorOperator for Reprojection {
             spatial_reference: self.params.target_spatial_reference.into(),
             data_type: in_desc.data_type,
             columns: in_desc.columns.clone(),
+            column_metadata: in_desc.column_metadata.clone(),
         };
 
         let state = VectorReprojectionState {
@@ -146,6 +148,32 @@ impl InitializedVectorOperator for InitializedVectorReprojection {
             }
         }
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        reproject_extent(
+            self.source.extent(),
+            self.state.source_srs,
+            self.state.target_srs,
+        )
+    }
+}
+
+/// Reprojects the spatial extent of a source, which becomes unknown if it cannot be reprojected
+fn reproject_extent(
+    extent: ResultExtent,
+    source_srs: SpatialReference,
+    target_srs: SpatialReference,
+) -> ResultExtent {
+    let spatial = extent.spatial.and_then(|bbox| {
+        let projector = CoordinateProjector::from_known_srs(source_srs, target_srs).ok()?;
+        bbox.reproject(&projector).ok()
+    });
+
+    ResultExtent::new(spatial, extent.time)
 }
 
 struct VectorReprojectionProcessor<Q, G>
@@ -195,6 +223,15 @@ where
             })
             .boxed())
     }
+
+    async fn query_is_empty<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        let rewritten_query = reproject_query(query, self.from, self.to)?;
+        self.source.query_is_empty(rewritten_query, ctx).await
+    }
 }
 
 #[typetag::serde]
@@ -224,6 +261,7 @@ impl RasterOperator for Reprojection {
             data_type: in_desc.data_type,
             measurement: in_desc.measurement.clone(),
             no_data_value: Some(out_no_data_value),
+            dimensions: in_desc.dimensions.clone(),
         };
 
         let state = RasterReprojectionState {
@@ -359,6 +397,18 @@ impl InitializedRasterOperator for InitializedRasterReprojection {
             }
         })
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        reproject_extent(
+            self.source.extent(),
+            self.state.source_srs,
+            self.state.target_srs,
+        )
+    }
 }
 
 struct RasterReprojectionProcessor<Q, P>
@@ -468,12 +518,41 @@ where
         )
         .filter_and_fill(self.no_data_and_fill_value))
     }
+
+    async fn query_is_empty<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<bool> {
+        let valid_bounds_out = Self::valid_bounds::<SpatialPartition2D>(self.to, self.from)?;
+
+        // the parts of the query outside of the valid bounds are always empty
+        let bounds =
+            match valid_bounds_out.and_then(|bounds| bounds.intersection(&query.spatial_bounds)) {
+                Some(bounds) => bounds,
+                None => return Ok(true),
+            };
+
+        let projector = CoordinateProjector::from_known_srs(self.to, self.from)?;
+
+        self.source
+            .query_is_empty(
+                RasterQueryRectangle {
+                    spatial_bounds: bounds.reproject(&projector)?,
+                    time_interval: query.time_interval,
+                    spatial_resolution: query.spatial_resolution,
+                    spatial_filter: (),
+                },
+                ctx,
+            )
+            .await
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::engine::{Cacheability, MockExecutionContext, MockQueryContext};
     use crate::mock::MockFeatureCollectionSource;
     use crate::mock::{MockRasterSource, MockRasterSourceParams};
     use crate::{
@@ -562,6 +641,7 @@ mod tests {
             .unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
         let ctx = MockQueryContext::new(ChunkByteSize::MAX);
 
@@ -633,6 +713,7 @@ mod tests {
             .unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
         let ctx = MockQueryContext::new(ChunkByteSize::MAX);
 
@@ -711,6 +792,7 @@ mod tests {
             .unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
         let ctx = MockQueryContext::new(ChunkByteSize::MAX);
 
@@ -790,6 +872,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -824,6 +907,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 1.).into(), (3., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 10),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
 
         let a = qp.raster_query(query_rect, &query_ctx).await?;
@@ -891,6 +975,7 @@ mod tests {
                     spatial_bounds: output_bounds,
                     time_interval,
                     spatial_resolution,
+                    spatial_filter: (),
                 },
                 &query_ctx,
             )
@@ -921,6 +1006,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new_unchecked((-180., -90.).into(), (180., 90.).into()),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
 
         let expected = BoundingBox2D::new_unchecked(
@@ -978,6 +1064,7 @@ mod tests {
                 properties_mapping: None,
                 gdal_open_options: None,
                 gdal_config_options: None,
+                apply_scale_offset: false,
             },
             result_descriptor: RasterResultDescriptor {
                 data_type: RasterDataType::U8,
@@ -985,6 +1072,7 @@ mod tests {
                     .into(),
                 measurement: Measurement::Unitless,
                 no_data_value: Some(0.),
+                dimensions: vec![],
             },
         };
 
@@ -1033,6 +1121,7 @@ mod tests {
                     spatial_bounds: output_bounds,
                     time_interval,
                     spatial_resolution,
+                    spatial_filter: (),
                 },
                 &query_ctx,
             )
@@ -1107,6 +1196,7 @@ mod tests {
                 properties_mapping: None,
                 gdal_open_options: None,
                 gdal_config_options: None,
+                apply_scale_offset: false,
             },
             result_descriptor: RasterResultDescriptor {
                 data_type: RasterDataType::U8,
@@ -1114,6 +1204,7 @@ mod tests {
                     .into(),
                 measurement: Measurement::Unitless,
                 no_data_value: Some(0.),
+                dimensions: vec![],
             },
         };
 
@@ -1164,6 +1255,7 @@ mod tests {
                     spatial_bounds: output_bounds,
                     time_interval,
                     spatial_resolution,
+                    spatial_filter: (),
                 },
                 &query_ctx,
             )
@@ -1233,6 +1325,7 @@ mod tests {
                     spatial_bounds,
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::zero_point_one(),
+                    spatial_filter: None,
                 },
                 &query_ctx,
             )
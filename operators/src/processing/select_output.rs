@@ -0,0 +1,254 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{
+    ExecutionContext, InitializedPlotOperator, InitializedRasterOperator,
+    InitializedScalarOperator, InitializedTableOperator, InitializedVectorOperator, Operator,
+    PlotOperator, RasterOperator, ScalarOperator, SingleMultiOutputSource, TableOperator,
+    VectorOperator,
+};
+use crate::util::Result;
+
+/// Selects a single output of an operator with multiple outputs.
+///
+/// Depending on the type of the selected output, this is a raster, vector, plot, table or scalar
+/// operator.
+pub type SelectOutput = Operator<SelectOutputParams, SingleMultiOutputSource>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectOutputParams {
+    /// The name of the output
+    pub output: String,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for SelectOutput {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        self.sources
+            .operator
+            .initialize(context)
+            .await?
+            .raster_output(&self.params.output)
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for SelectOutput {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        self.sources
+            .operator
+            .initialize(context)
+            .await?
+            .vector_output(&self.params.output)
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl PlotOperator for SelectOutput {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedPlotOperator>> {
+        self.sources
+            .operator
+            .initialize(context)
+            .await?
+            .plot_output(&self.params.output)
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl TableOperator for SelectOutput {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedTableOperator>> {
+        self.sources
+            .operator
+            .initialize(context)
+            .await?
+            .table_output(&self.params.output)
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl ScalarOperator for SelectOutput {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedScalarOperator>> {
+        self.sources
+            .operator
+            .initialize(context)
+            .await?
+            .scalar_output(&self.params.output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        MockExecutionContext, MockQueryContext, MultiOutputOperator, QueryProcessor,
+    };
+    use crate::error::Error;
+    use crate::mock::{MockSplitPointSource, MockSplitPointSourceParams};
+    use futures::StreamExt;
+    use geoengine_datatypes::collections::{FeatureCollectionInfos, MultiPointCollection};
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, Coordinate2D, SpatialResolution, VectorQueryRectangle,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn split_source() -> Box<dyn MultiOutputOperator> {
+        MockSplitPointSource {
+            params: MockSplitPointSourceParams {
+                points: vec![
+                    Coordinate2D::new(1., 1.),
+                    Coordinate2D::new(2., 2.),
+                    Coordinate2D::new(3., 3.),
+                ],
+                split_at: 1,
+            },
+        }
+        .boxed()
+    }
+
+    async fn number_of_points(operator: Box<dyn InitializedVectorOperator>) -> usize {
+        let processor = operator.query_processor().unwrap().multi_point().unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: Default::default(),
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: None,
+        };
+        let ctx = MockQueryContext::test_default();
+
+        let collections: Vec<MultiPointCollection> = processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        collections.iter().map(FeatureCollectionInfos::len).sum()
+    }
+
+    #[test]
+    fn serde() {
+        let operator = VectorOperator::boxed(SelectOutput {
+            params: SelectOutputParams {
+                output: "tail".to_string(),
+            },
+            sources: split_source().into(),
+        });
+
+        let serialized = serde_json::to_value(&operator).unwrap();
+
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "type": "SelectOutput",
+                "params": {
+                    "output": "tail"
+                },
+                "sources": {
+                    "operator": {
+                        "type": "MockSplitPointSource",
+                        "params": {
+                            "points": [
+                                { "x": 1.0, "y": 1.0 },
+                                { "x": 2.0, "y": 2.0 },
+                                { "x": 3.0, "y": 3.0 }
+                            ],
+                            "splitAt": 1
+                        }
+                    }
+                }
+            })
+        );
+
+        let _operator: Box<dyn VectorOperator> = serde_json::from_value(serialized).unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_selects_outputs() {
+        let context = MockExecutionContext::test_default();
+
+        for (output, expected_points) in [("head", 1), ("tail", 2)] {
+            let operator = VectorOperator::boxed(SelectOutput {
+                params: SelectOutputParams {
+                    output: output.to_string(),
+                },
+                sources: split_source().into(),
+            });
+
+            let initialized = operator.initialize(&context).await.unwrap();
+
+            assert_eq!(number_of_points(initialized).await, expected_points);
+        }
+    }
+
+    #[tokio::test]
+    async fn it_shares_one_initialization() {
+        let context = MockExecutionContext::test_default();
+
+        let initialized = split_source().initialize(&context).await.unwrap();
+
+        let names: Vec<&str> = initialized
+            .outputs()
+            .iter()
+            .map(|output| output.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["head", "tail"]);
+
+        let head = initialized.vector_output("head").unwrap();
+        let tail = initialized.vector_output("tail").unwrap();
+
+        assert_eq!(number_of_points(head).await, 1);
+        assert_eq!(number_of_points(tail).await, 2);
+    }
+
+    #[tokio::test]
+    async fn it_checks_outputs() {
+        let context = MockExecutionContext::test_default();
+
+        let operator = VectorOperator::boxed(SelectOutput {
+            params: SelectOutputParams {
+                output: "middle".to_string(),
+            },
+            sources: split_source().into(),
+        });
+
+        assert!(matches!(
+            operator.initialize(&context).await,
+            Err(Error::UnknownOperatorOutput { output }) if output == "middle"
+        ));
+
+        let operator = RasterOperator::boxed(SelectOutput {
+            params: SelectOutputParams {
+                output: "head".to_string(),
+            },
+            sources: split_source().into(),
+        });
+
+        assert!(matches!(
+            operator.initialize(&context).await,
+            Err(Error::InvalidOperatorType { expected, found }) if expected == "Raster" && found == "Vector"
+        ));
+    }
+}
@@ -231,6 +231,7 @@ where
             spatial_bounds: tile_info.spatial_partition(),
             spatial_resolution: query_rect.spatial_resolution,
             time_interval: TimeInterval::new(start_time, (start_time + self.step)?)?,
+            spatial_filter: (),
         }))
     }
 
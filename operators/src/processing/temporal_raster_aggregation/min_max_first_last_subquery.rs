@@ -6,8 +6,12 @@ use geoengine_datatypes::{
         QueryRectangle, RasterQueryRectangle, SpatialPartitioned, TimeInstance, TimeInterval,
         TimeStep,
     },
-    raster::{EmptyGrid2D, Grid2D, GridOrEmpty, Pixel, RasterTile2D, TileInformation},
+    raster::{
+        EmptyGrid2D, FromPrimitive, Grid2D, GridOrEmpty, NoDataValue, Pixel, RasterTile2D,
+        TileInformation,
+    },
 };
+use num_traits::AsPrimitive;
 use rayon::ThreadPool;
 
 use crate::{
@@ -100,6 +104,43 @@ impl NoDataIgnoringAccFunction for MaxIgnoreNoDataAccFunction {
     }
 }
 
+pub struct SumAccFunction {}
+
+impl AccFunction for SumAccFunction {
+    fn acc<T: Pixel>(no_data: Option<T>, acc: T, value: T) -> T {
+        if let Some(no_data) = no_data {
+            if acc == no_data || value == no_data {
+                return no_data;
+            }
+        }
+
+        saturating_add(acc, value)
+    }
+}
+
+pub struct SumIgnoreNoDataAccFunction {}
+
+impl NoDataIgnoringAccFunction for SumIgnoreNoDataAccFunction {
+    fn acc_ignore_no_data<T: Pixel>(no_data: Option<T>, acc: T, value: T) -> T {
+        if let Some(no_data) = no_data {
+            if value == no_data {
+                return acc;
+            } else if acc == no_data {
+                return value;
+            }
+        }
+
+        saturating_add(acc, value)
+    }
+}
+
+/// Adds two values and saturates at the bounds of the data type
+fn saturating_add<T: Pixel>(a: T, b: T) -> T {
+    let a: f64 = a.as_();
+    let b: f64 = b.as_();
+    T::from_(a + b)
+}
+
 pub struct LastValidAccFunction {}
 
 impl NoDataIgnoringAccFunction for LastValidAccFunction {
@@ -301,6 +342,60 @@ where
     })
 }
 
+/// Counts the valid values of each pixel, pixels without a valid value remain no data
+pub fn count_fold_fn<T>(
+    acc: TemporalRasterAggregationTileAccu<T>,
+    tile: RasterTile2D<T>,
+) -> TemporalRasterAggregationTileAccu<T>
+where
+    T: Pixel,
+{
+    let TemporalRasterAggregationTileAccu {
+        mut accu_tile,
+        initial_state: _initial_state,
+        pool,
+    } = acc;
+
+    if let GridOrEmpty::Grid(g) = tile.grid_array {
+        let mut counts = accu_tile.grid_array.into_materialized_grid();
+
+        for (count, value) in counts.data.iter_mut().zip(g.inner_ref()) {
+            if g.is_no_data(*value) {
+                continue;
+            }
+
+            *count = if counts.no_data_value == Some(*count) {
+                T::one()
+            } else {
+                saturating_add(*count, T::one())
+            };
+        }
+
+        accu_tile.grid_array = counts.into();
+    }
+
+    TemporalRasterAggregationTileAccu {
+        accu_tile,
+        initial_state: false,
+        pool,
+    }
+}
+
+pub fn count_fold_future<T>(
+    accu: TemporalRasterAggregationTileAccu<T>,
+    tile: RasterTile2D<T>,
+) -> impl Future<Output = Result<TemporalRasterAggregationTileAccu<T>>>
+where
+    T: Pixel,
+{
+    crate::util::spawn_blocking(|| count_fold_fn(accu, tile)).then(move |x| async move {
+        match x {
+            Ok(r) => Ok(r),
+            Err(e) => Err(e.into()),
+        }
+    })
+}
+
 #[derive(Debug, Clone)]
 pub struct TemporalRasterAggregationTileAccu<T> {
     accu_tile: RasterTile2D<T>,
@@ -378,6 +473,7 @@ where
             spatial_bounds: tile_info.spatial_partition(),
             spatial_resolution: query_rect.spatial_resolution,
             time_interval: TimeInterval::new(start_time, (start_time + self.step)?)?,
+            spatial_filter: (),
         }))
     }
 
@@ -456,6 +552,7 @@ where
             spatial_bounds: tile_info.spatial_partition(),
             spatial_resolution: query_rect.spatial_resolution,
             time_interval: TimeInterval::new(start_time, (start_time + self.step)?)?,
+            spatial_filter: (),
         }))
     }
 
@@ -1,5 +1,6 @@
 mod mean_aggregation_subquery;
 mod min_max_first_last_subquery;
+mod percentile_aggregation_subquery;
 mod temporal_aggregation_operator;
 
 pub use temporal_aggregation_operator::{
@@ -0,0 +1,353 @@
+use std::sync::Arc;
+
+use futures::{future::BoxFuture, Future, FutureExt, TryFuture, TryFutureExt};
+use geoengine_datatypes::{
+    primitives::{RasterQueryRectangle, SpatialPartitioned, TimeInstance, TimeInterval, TimeStep},
+    raster::{
+        EmptyGrid2D, GeoTransform, Grid2D, GridIdx2D, GridOrEmpty, GridShape2D, GridSize,
+        NoDataValue, Pixel, RasterTile2D, TileInformation,
+    },
+};
+use num_traits::AsPrimitive;
+use rayon::ThreadPool;
+
+use crate::{
+    adapters::{FoldTileAccu, SubQueryTileAggregator},
+    util::{math::percentile_of_sorted, statistics::TDigest, Result},
+};
+
+/// The compression of the t-digests, i.e., about 100 centroids per pixel
+const T_DIGEST_COMPRESSION: f64 = 100.;
+
+pub fn percentile_tile_fold_future<T>(
+    accu: TemporalPercentileTileAccu<T>,
+    tile: RasterTile2D<T>,
+) -> impl Future<Output = Result<TemporalPercentileTileAccu<T>>>
+where
+    T: Pixel,
+{
+    crate::util::spawn_blocking(|| {
+        let mut accu = accu;
+        accu.add_tile(tile)?;
+        Ok(accu)
+    })
+    .then(|x| async move {
+        match x {
+            Ok(r) => r,
+            Err(e) => Err(e.into()),
+        }
+    })
+}
+
+/// The values of all pixels of a tile, either exact or as t-digest estimates
+#[derive(Debug, Clone)]
+enum PixelValues {
+    Exact(Vec<Vec<f64>>),
+    Estimated(Vec<TDigest>),
+}
+
+/// Buffers the values of the pixels of a tile until they exceed the memory budget.
+/// Then, the values are summarized by t-digests, s.t. the percentiles are estimated.
+#[derive(Debug, Clone)]
+pub struct TemporalPercentileTileAccu<T> {
+    time: TimeInterval,
+    tile_position: GridIdx2D,
+    global_geo_transform: GeoTransform,
+    shape: GridShape2D,
+
+    values: PixelValues,
+    buffered_values: usize,
+    /// pixels that had no data while no data is not ignored
+    invalid: Vec<bool>,
+
+    percentile: f64,
+    ignore_no_data: bool,
+    memory_budget: usize,
+    out_no_data_value: T,
+
+    pool: Arc<ThreadPool>,
+}
+
+impl<T> TemporalPercentileTileAccu<T>
+where
+    T: Pixel,
+{
+    pub fn add_tile(&mut self, in_tile: RasterTile2D<T>) -> Result<()> {
+        self.time = self.time.union(&in_tile.time)?;
+
+        let in_tile_grid = match in_tile.grid_array {
+            GridOrEmpty::Grid(g) => g,
+            GridOrEmpty::Empty(_) => {
+                if !self.ignore_no_data {
+                    self.invalid.iter_mut().for_each(|invalid| *invalid = true);
+                }
+                return Ok(());
+            }
+        };
+
+        let new_values = in_tile_grid.data.len();
+        if matches!(self.values, PixelValues::Exact(_))
+            && (self.buffered_values + new_values) * std::mem::size_of::<f64>() > self.memory_budget
+        {
+            self.switch_to_estimation();
+        }
+
+        for (i, value) in in_tile_grid.data.iter().enumerate() {
+            if self.invalid[i] {
+                continue;
+            }
+
+            if in_tile_grid.is_no_data(*value) {
+                if !self.ignore_no_data {
+                    self.invalid[i] = true;
+                }
+                continue;
+            }
+
+            let value: f64 = value.as_();
+            if value.is_nan() {
+                continue;
+            }
+
+            match &mut self.values {
+                PixelValues::Exact(values) => {
+                    values[i].push(value);
+                    self.buffered_values += 1;
+                }
+                PixelValues::Estimated(digests) => digests[i].update(value),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Summarizes the buffered values by t-digests
+    fn switch_to_estimation(&mut self) {
+        let values = match &mut self.values {
+            PixelValues::Exact(values) => std::mem::take(values),
+            PixelValues::Estimated(_) => return,
+        };
+
+        let digests = values
+            .into_iter()
+            .map(|values| {
+                let mut digest =
+                    TDigest::new(T_DIGEST_COMPRESSION).expect("the compression is valid");
+                for value in values {
+                    digest.update(value);
+                }
+                digest
+            })
+            .collect();
+
+        self.values = PixelValues::Estimated(digests);
+        self.buffered_values = 0;
+    }
+
+    fn percentile_of_pixel(&mut self, pixel: usize) -> Option<f64> {
+        if self.invalid[pixel] {
+            return None;
+        }
+
+        let percentile = match &mut self.values {
+            PixelValues::Exact(values) => {
+                let values = &mut values[pixel];
+                values.sort_unstable_by(|a, b| {
+                    a.partial_cmp(b).expect("`NaN` values are not buffered")
+                });
+                percentile_of_sorted(values, self.percentile)
+            }
+            PixelValues::Estimated(digests) => digests[pixel].quantile(self.percentile / 100.),
+        };
+
+        (!percentile.is_nan()).then(|| percentile)
+    }
+}
+
+impl<T> FoldTileAccu for TemporalPercentileTileAccu<T>
+where
+    T: Pixel,
+{
+    type RasterType = T;
+
+    fn into_tile(mut self) -> RasterTile2D<Self::RasterType> {
+        let data: Vec<T> = (0..self.shape.number_of_elements())
+            .map(|pixel| {
+                self.percentile_of_pixel(pixel)
+                    .map_or(self.out_no_data_value, T::from_)
+            })
+            .collect();
+
+        let grid = if data.iter().all(|&v| v == self.out_no_data_value) {
+            EmptyGrid2D::new(self.shape, self.out_no_data_value).into()
+        } else {
+            Grid2D {
+                shape: self.shape,
+                data,
+                no_data_value: Some(self.out_no_data_value),
+            }
+            .into()
+        };
+
+        RasterTile2D::new(
+            self.time,
+            self.tile_position,
+            self.global_geo_transform,
+            grid,
+        )
+    }
+
+    fn thread_pool(&self) -> &Arc<ThreadPool> {
+        &self.pool
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TemporalRasterPercentileAggregationSubQuery<F, T: Pixel> {
+    pub fold_fn: F,
+    pub no_data_value: T,
+    pub percentile: f64,
+    pub ignore_no_data: bool,
+    pub memory_budget: usize,
+    pub step: TimeStep,
+}
+
+impl<'a, T, FoldM, FoldF> SubQueryTileAggregator<'a, T>
+    for TemporalRasterPercentileAggregationSubQuery<FoldM, T>
+where
+    T: Pixel,
+    FoldM:
+        Send + Sync + 'static + Clone + Fn(TemporalPercentileTileAccu<T>, RasterTile2D<T>) -> FoldF,
+    FoldF: Send + TryFuture<Ok = TemporalPercentileTileAccu<T>, Error = crate::error::Error>,
+{
+    type TileAccu = TemporalPercentileTileAccu<T>;
+    type TileAccuFuture = BoxFuture<'a, Result<Self::TileAccu>>;
+
+    type FoldFuture = FoldF;
+
+    type FoldMethod = FoldM;
+
+    fn new_fold_accu(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+        pool: &Arc<ThreadPool>,
+    ) -> Self::TileAccuFuture {
+        let percentile = self.percentile;
+        let ignore_no_data = self.ignore_no_data;
+        let memory_budget = self.memory_budget;
+        let no_data_value = self.no_data_value;
+        let pool = pool.clone();
+
+        crate::util::spawn_blocking(move || {
+            let pixels = tile_info.tile_size_in_pixels.number_of_elements();
+
+            TemporalPercentileTileAccu {
+                time: query_rect.time_interval,
+                tile_position: tile_info.global_tile_position,
+                global_geo_transform: tile_info.global_geo_transform,
+                shape: tile_info.tile_size_in_pixels,
+                values: PixelValues::Exact(vec![Vec::new(); pixels]),
+                buffered_values: 0,
+                invalid: vec![false; pixels],
+                percentile,
+                ignore_no_data,
+                memory_budget,
+                out_no_data_value: no_data_value,
+                pool,
+            }
+        })
+        .map_err(From::from)
+        .boxed()
+    }
+
+    fn tile_query_rectangle(
+        &self,
+        tile_info: TileInformation,
+        query_rect: RasterQueryRectangle,
+        start_time: TimeInstance,
+    ) -> Result<Option<RasterQueryRectangle>> {
+        Ok(Some(RasterQueryRectangle {
+            spatial_bounds: tile_info.spatial_partition(),
+            spatial_resolution: query_rect.spatial_resolution,
+            time_interval: TimeInterval::new(start_time, (start_time + self.step)?)?,
+            spatial_filter: (),
+        }))
+    }
+
+    fn fold_method(&self) -> Self::FoldMethod {
+        self.fold_fn.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::create_rayon_thread_pool;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn accu(ignore_no_data: bool, memory_budget: usize) -> TemporalPercentileTileAccu<u8> {
+        TemporalPercentileTileAccu {
+            time: TimeInterval::new_unchecked(0, 10),
+            tile_position: [0, 0].into(),
+            global_geo_transform: TestDefault::test_default(),
+            shape: [2, 2].into(),
+            values: PixelValues::Exact(vec![Vec::new(); 4]),
+            buffered_values: 0,
+            invalid: vec![false; 4],
+            percentile: 50.,
+            ignore_no_data,
+            memory_budget,
+            out_no_data_value: 0,
+            pool: create_rayon_thread_pool(0),
+        }
+    }
+
+    fn tile(data: Vec<u8>) -> RasterTile2D<u8> {
+        RasterTile2D::new(
+            TimeInterval::new_unchecked(0, 10),
+            [0, 0].into(),
+            TestDefault::test_default(),
+            Grid2D::new([2, 2].into(), data, Some(0)).unwrap().into(),
+        )
+    }
+
+    fn data(accu: TemporalPercentileTileAccu<u8>) -> Vec<u8> {
+        accu.into_tile().grid_array.into_materialized_grid().data
+    }
+
+    #[test]
+    fn it_estimates_beyond_the_memory_budget() {
+        let mut exact = accu(true, usize::MAX);
+        let mut estimated = accu(true, 4 * std::mem::size_of::<f64>());
+
+        for i in 1..=99 {
+            exact.add_tile(tile(vec![i, 100 - i, 50, 1])).unwrap();
+            estimated.add_tile(tile(vec![i, 100 - i, 50, 1])).unwrap();
+        }
+
+        assert!(matches!(exact.values, PixelValues::Exact(_)));
+        assert!(matches!(estimated.values, PixelValues::Estimated(_)));
+
+        assert_eq!(data(exact), vec![50, 50, 50, 1]);
+
+        let estimated = data(estimated);
+        assert!((49..=51).contains(&estimated[0]));
+        assert!((49..=51).contains(&estimated[1]));
+        assert_eq!(estimated[2..], [50, 1]);
+    }
+
+    #[test]
+    fn it_respects_no_data() {
+        let mut accu_with_no_data = accu(false, usize::MAX);
+        let mut accu_ignoring_no_data = accu(true, usize::MAX);
+
+        for data in [vec![1, 0, 3, 4], vec![3, 2, 0, 4], vec![2, 2, 4, 4]] {
+            accu_with_no_data.add_tile(tile(data.clone())).unwrap();
+            accu_ignoring_no_data.add_tile(tile(data)).unwrap();
+        }
+
+        assert_eq!(data(accu_with_no_data), vec![2, 0, 0, 4]);
+        assert_eq!(data(accu_ignoring_no_data), vec![2, 2, 3, 4]);
+    }
+}
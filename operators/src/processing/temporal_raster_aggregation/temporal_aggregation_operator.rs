@@ -1,5 +1,6 @@
 use crate::engine::{
-    ExecutionContext, Operator, QueryProcessor, RasterOperator, SingleRasterSource,
+    Cacheability, ExecutionContext, Operator, QueryProcessor, RasterOperator, ResultExtent,
+    SingleRasterSource,
 };
 use crate::{
     adapters::SubQueryTileAggregator,
@@ -11,7 +12,9 @@ use crate::{
     util::Result,
 };
 use async_trait::async_trait;
-use geoengine_datatypes::primitives::{RasterQueryRectangle, SpatialPartition2D};
+use geoengine_datatypes::primitives::{
+    RasterQueryRectangle, SpatialPartition2D, TimeInstance, TimeInterval,
+};
 use geoengine_datatypes::raster::{Pixel, RasterTile2D};
 use geoengine_datatypes::{primitives::TimeStep, raster::TilingSpecification};
 use log::debug;
@@ -23,18 +26,24 @@ use super::mean_aggregation_subquery::{
     mean_tile_fold_future, TemporalRasterMeanAggregationSubQuery,
 };
 use super::min_max_first_last_subquery::{
-    first_tile_fold_future, fold_future, last_tile_fold_future, no_data_ignoring_fold_future,
-    FirstValidAccFunction, LastValidAccFunction, MaxAccFunction, MaxIgnoreNoDataAccFunction,
-    MinAccFunction, MinIgnoreNoDataAccFunction, TemporalRasterAggregationSubQuery,
+    count_fold_future, first_tile_fold_future, fold_future, last_tile_fold_future,
+    no_data_ignoring_fold_future, FirstValidAccFunction, LastValidAccFunction, MaxAccFunction,
+    MaxIgnoreNoDataAccFunction, MinAccFunction, MinIgnoreNoDataAccFunction, SumAccFunction,
+    SumIgnoreNoDataAccFunction, TemporalRasterAggregationSubQuery,
     TemporalRasterAggregationSubQueryNoDataOnly,
 };
+use super::percentile_aggregation_subquery::{
+    percentile_tile_fold_future, TemporalRasterPercentileAggregationSubQuery,
+};
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TemporalRasterAggregationParameters {
     aggregation: Aggregation,
     window: TimeStep,
-    // TODO: allow specifying window start instead of using query.start?
+    /// A start of a window, defaults to the Unix epoch
+    #[serde(default)]
+    window_reference: Option<TimeInstance>,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
@@ -51,6 +60,34 @@ pub enum Aggregation {
     Last { ignore_no_data: bool },
     #[serde(rename_all = "camelCase")]
     Mean { ignore_no_data: bool },
+    /// The sum of each pixel, saturating at the bounds of the data type
+    #[serde(rename_all = "camelCase")]
+    Sum { ignore_no_data: bool },
+    /// The number of valid values of each pixel, saturating at the maximum of the data type.
+    /// Pixels without any valid value are no data.
+    Count,
+    /// The median of each pixel, see `Percentile`
+    #[serde(rename_all = "camelCase")]
+    Median {
+        ignore_no_data: bool,
+        #[serde(default = "default_percentile_memory_budget")]
+        memory_budget: usize,
+    },
+    /// The `percentile` (in `[0, 100]`) of each pixel, e.g., for robust composites.
+    /// The values of a tile are buffered for exact results until they exceed the `memory_budget`
+    /// in bytes. Then, the percentiles are estimated with t-digests.
+    #[serde(rename_all = "camelCase")]
+    Percentile {
+        percentile: f64,
+        ignore_no_data: bool,
+        #[serde(default = "default_percentile_memory_budget")]
+        memory_budget: usize,
+    },
+}
+
+/// Allows exact percentiles of about 200 time steps for tiles of 512x512 pixels
+const fn default_percentile_memory_budget() -> usize {
+    400 * 1024 * 1024
 }
 
 pub type TemporalRasterAggregation =
@@ -65,6 +102,15 @@ impl RasterOperator for TemporalRasterAggregation {
     ) -> Result<Box<dyn InitializedRasterOperator>> {
         ensure!(self.params.window.step > 0, error::WindowSizeMustNotBeZero);
 
+        if let Aggregation::Percentile { percentile, .. } = self.params.aggregation {
+            ensure!(
+                (0. ..=100.).contains(&percentile),
+                error::InvalidOperatorSpec {
+                    reason: format!("The percentile must be in [0, 100], found {}", percentile),
+                }
+            );
+        }
+
         let source = self.sources.raster.initialize(context).await?;
 
         debug!(
@@ -75,6 +121,10 @@ impl RasterOperator for TemporalRasterAggregation {
         let initialized_operator = InitializedTemporalRasterAggregation {
             aggregation_type: self.params.aggregation,
             window: self.params.window,
+            window_reference: self
+                .params
+                .window_reference
+                .unwrap_or_else(|| TimeInstance::from_millis_unchecked(0)),
             result_descriptor: source.result_descriptor().clone(),
             source,
             tiling_specification: context.tiling_specification(),
@@ -87,6 +137,7 @@ impl RasterOperator for TemporalRasterAggregation {
 pub struct InitializedTemporalRasterAggregation {
     aggregation_type: Aggregation,
     window: TimeStep,
+    window_reference: TimeInstance,
     source: Box<dyn InitializedRasterOperator>,
     result_descriptor: RasterResultDescriptor,
     tiling_specification: TilingSpecification,
@@ -105,6 +156,7 @@ impl InitializedRasterOperator for InitializedTemporalRasterAggregation {
            TemporalRasterAggregationProcessor::new(
                 self.aggregation_type,
                 self.window,
+                self.window_reference,
                 p,
                 self.tiling_specification,
                 self.source.result_descriptor().no_data_value
@@ -114,6 +166,14 @@ impl InitializedRasterOperator for InitializedTemporalRasterAggregation {
 
         Ok(res)
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.source.extent()
+    }
 }
 
 pub struct TemporalRasterAggregationProcessor<Q, P>
@@ -123,6 +183,7 @@ where
 {
     aggregation_type: Aggregation,
     window: TimeStep,
+    window_reference: TimeInstance,
     source: Q,
     tiling_specification: TilingSpecification,
     no_data_value: Option<P>,
@@ -136,6 +197,7 @@ where
     fn new(
         aggregation_type: Aggregation,
         window: TimeStep,
+        window_reference: TimeInstance,
         source: Q,
         tiling_specification: TilingSpecification,
         no_data_value: Option<f64>,
@@ -143,6 +205,7 @@ where
         Self {
             aggregation_type,
             window,
+            window_reference,
             source,
             tiling_specification,
             no_data_value: no_data_value.map(P::from_),
@@ -194,6 +257,27 @@ where
             )
     }
 
+    fn create_subquery_percentile<F>(
+        &self,
+        fold_fn: F,
+        percentile: f64,
+        ignore_no_data: bool,
+        memory_budget: usize,
+    ) -> Result<TemporalRasterPercentileAggregationSubQuery<F, P>> {
+        self.no_data_value
+            .ok_or(error::Error::TemporalRasterAggregationPercentileRequiresNoData)
+            .map(
+                |no_data_value| TemporalRasterPercentileAggregationSubQuery {
+                    fold_fn,
+                    no_data_value,
+                    percentile,
+                    ignore_no_data,
+                    memory_budget,
+                    step: self.window,
+                },
+            )
+    }
+
     fn create_subquery_mean<F>(
         &self,
         fold_fn: F,
@@ -225,6 +309,16 @@ where
         query: RasterQueryRectangle,
         ctx: &'a dyn crate::engine::QueryContext,
     ) -> Result<futures::stream::BoxStream<'a, Result<Self::Output>>> {
+        // the windows start at multiples of the window size relative to the window reference
+        let query = RasterQueryRectangle {
+            time_interval: TimeInterval::new(
+                self.window
+                    .snap_relative(self.window_reference, query.time_interval.start())?,
+                query.time_interval.end(),
+            )?,
+            ..query
+        };
+
         match self.aggregation_type {
             Aggregation::Min {
                 ignore_no_data: true,
@@ -322,6 +416,67 @@ where
                     )
                     .expect("no tiles must be skipped in Aggregation::Mean")
                 }),
+
+            Aggregation::Sum {
+                ignore_no_data: true,
+            } => Ok(self
+                .create_subquery(
+                    no_data_ignoring_fold_future::<P, SumIgnoreNoDataAccFunction>,
+                    P::zero(),
+                )
+                .into_raster_subquery_adapter(&self.source, query, ctx, self.tiling_specification)
+                .expect("no tiles must be skipped in Aggregation::Sum")),
+            Aggregation::Sum {
+                ignore_no_data: false,
+            } => Ok(self
+                .create_subquery(fold_future::<P, SumAccFunction>, P::zero())
+                .into_raster_subquery_adapter(&self.source, query, ctx, self.tiling_specification)
+                .expect("no tiles must be skipped in Aggregation::Sum")),
+            Aggregation::Count => Ok(self
+                .create_subquery(count_fold_future::<P>, P::zero())
+                .into_raster_subquery_adapter(&self.source, query, ctx, self.tiling_specification)
+                .expect("no tiles must be skipped in Aggregation::Count")),
+
+            Aggregation::Median {
+                ignore_no_data,
+                memory_budget,
+            } => self
+                .create_subquery_percentile(
+                    percentile_tile_fold_future::<P>,
+                    50.,
+                    ignore_no_data,
+                    memory_budget,
+                )
+                .map(|o| {
+                    o.into_raster_subquery_adapter(
+                        &self.source,
+                        query,
+                        ctx,
+                        self.tiling_specification,
+                    )
+                    .expect("no tiles must be skipped in Aggregation::Median")
+                }),
+
+            Aggregation::Percentile {
+                percentile,
+                ignore_no_data,
+                memory_budget,
+            } => self
+                .create_subquery_percentile(
+                    percentile_tile_fold_future::<P>,
+                    percentile,
+                    ignore_no_data,
+                    memory_budget,
+                )
+                .map(|o| {
+                    o.into_raster_subquery_adapter(
+                        &self.source,
+                        query,
+                        ctx,
+                        self.tiling_specification,
+                    )
+                    .expect("no tiles must be skipped in Aggregation::Percentile")
+                }),
         }
     }
 }
@@ -357,6 +512,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -371,6 +527,7 @@ mod tests {
                     granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
                     step: 20,
                 },
+                window_reference: None,
             },
             sources: SingleRasterSource { raster: mrs },
         }
@@ -384,6 +541,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 40),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
         let query_ctx = MockQueryContext::test_default();
 
@@ -479,6 +637,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -493,6 +652,7 @@ mod tests {
                     granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
                     step: 20,
                 },
+                window_reference: None,
             },
             sources: SingleRasterSource { raster: mrs },
         }
@@ -506,6 +666,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 40),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
         let query_ctx = MockQueryContext::test_default();
 
@@ -606,6 +767,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -620,6 +782,7 @@ mod tests {
                     granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
                     step: 20,
                 },
+                window_reference: None,
             },
             sources: SingleRasterSource { raster: mrs },
         }
@@ -633,6 +796,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 40),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
         let query_ctx = MockQueryContext::test_default();
 
@@ -738,6 +902,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -752,6 +917,7 @@ mod tests {
                     granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
                     step: 20,
                 },
+                window_reference: None,
             },
             sources: SingleRasterSource { raster: mrs },
         }
@@ -765,6 +931,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 40),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
         let query_ctx = MockQueryContext::test_default();
 
@@ -868,6 +1035,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -882,6 +1050,7 @@ mod tests {
                     granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
                     step: 20,
                 },
+                window_reference: None,
             },
             sources: SingleRasterSource { raster: mrs },
         }
@@ -895,6 +1064,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (2., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 20),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
         let query_ctx = MockQueryContext::test_default();
 
@@ -942,6 +1112,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -956,6 +1127,7 @@ mod tests {
                     granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
                     step: 30,
                 },
+                window_reference: None,
             },
             sources: SingleRasterSource { raster: mrs },
         }
@@ -969,6 +1141,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 30),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
         let query_ctx = MockQueryContext::test_default();
 
@@ -1033,6 +1206,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -1047,6 +1221,7 @@ mod tests {
                     granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
                     step: 30,
                 },
+                window_reference: None,
             },
             sources: SingleRasterSource { raster: mrs },
         }
@@ -1060,6 +1235,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 30),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
         let query_ctx = MockQueryContext::test_default();
 
@@ -1124,6 +1300,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -1138,6 +1315,7 @@ mod tests {
                     granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
                     step: 30,
                 },
+                window_reference: None,
             },
             sources: SingleRasterSource { raster: mrs },
         }
@@ -1151,6 +1329,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 30),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
         let query_ctx = MockQueryContext::test_default();
 
@@ -1214,6 +1393,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -1228,6 +1408,7 @@ mod tests {
                     granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
                     step: 30,
                 },
+                window_reference: None,
             },
             sources: SingleRasterSource { raster: mrs },
         }
@@ -1241,6 +1422,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 30),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
         let query_ctx = MockQueryContext::test_default();
 
@@ -1303,6 +1485,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -1317,6 +1500,7 @@ mod tests {
                     granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
                     step: 30,
                 },
+                window_reference: None,
             },
             sources: SingleRasterSource { raster: mrs },
         }
@@ -1330,6 +1514,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 30),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
         let query_ctx = MockQueryContext::test_default();
 
@@ -1392,6 +1577,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -1406,6 +1592,7 @@ mod tests {
                     granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
                     step: 30,
                 },
+                window_reference: None,
             },
             sources: SingleRasterSource { raster: mrs },
         }
@@ -1419,6 +1606,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
             time_interval: TimeInterval::new_unchecked(0, 30),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
         let query_ctx = MockQueryContext::test_default();
 
@@ -1471,6 +1659,268 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_percentile_ignore_nodata() {
+        let (no_data_value, raster_tiles) = make_raster_with_no_data();
+
+        let mrs = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: raster_tiles,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed();
+
+        let agg = TemporalRasterAggregation {
+            params: TemporalRasterAggregationParameters {
+                aggregation: Aggregation::Percentile {
+                    percentile: 100.,
+                    ignore_no_data: true,
+                    memory_budget: default_percentile_memory_budget(),
+                },
+                window: TimeStep {
+                    granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
+                    step: 30,
+                },
+                window_reference: None,
+            },
+            sources: SingleRasterSource { raster: mrs },
+        }
+        .boxed();
+
+        let exe_ctx = MockExecutionContext::new_with_tiling_spec(TilingSpecification::new(
+            (0., 0.).into(),
+            [3, 2].into(),
+        ));
+        let query_rect = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
+            time_interval: TimeInterval::new_unchecked(0, 30),
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
+        };
+        let query_ctx = MockQueryContext::test_default();
+
+        let qp = agg
+            .initialize(&exe_ctx)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .get_u8()
+            .unwrap();
+
+        let result = qp
+            .raster_query(query_rect, &query_ctx)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(result.len(), 2);
+
+        assert_eq!(
+            result[0].as_ref().unwrap(),
+            &RasterTile2D::new_with_tile_info(
+                TimeInterval::new_unchecked(0, 30),
+                TileInformation {
+                    global_tile_position: [-1, 0].into(),
+                    tile_size_in_pixels: [3, 2].into(),
+                    global_geo_transform: TestDefault::test_default(),
+                },
+                GridOrEmpty::Grid(
+                    Grid2D::new([3, 2].into(), vec![13, 8, 15, 16, 17, 18], no_data_value).unwrap()
+                )
+            )
+        );
+
+        assert_eq!(
+            result[1].as_ref().unwrap(),
+            &RasterTile2D::new_with_tile_info(
+                TimeInterval::new_unchecked(0, 30),
+                TileInformation {
+                    global_tile_position: [-1, 1].into(),
+                    tile_size_in_pixels: [3, 2].into(),
+                    global_geo_transform: TestDefault::test_default(),
+                },
+                GridOrEmpty::Grid(
+                    Grid2D::new([3, 2].into(), vec![1, 2, 3, 42, 5, 6], no_data_value).unwrap()
+                )
+            )
+        );
+    }
+
+    async fn aggregate(
+        aggregation: Aggregation,
+        window_reference: Option<TimeInstance>,
+        (no_data_value, raster_tiles): (Option<u8>, Vec<RasterTile2D<u8>>),
+        time_interval: TimeInterval,
+    ) -> Vec<RasterTile2D<u8>> {
+        let mrs = MockRasterSource {
+            params: MockRasterSourceParams {
+                data: raster_tiles,
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed();
+
+        let agg = TemporalRasterAggregation {
+            params: TemporalRasterAggregationParameters {
+                aggregation,
+                window: TimeStep {
+                    granularity: geoengine_datatypes::primitives::TimeGranularity::Millis,
+                    step: 20,
+                },
+                window_reference,
+            },
+            sources: SingleRasterSource { raster: mrs },
+        }
+        .boxed();
+
+        let exe_ctx = MockExecutionContext::new_with_tiling_spec(TilingSpecification::new(
+            (0., 0.).into(),
+            [3, 2].into(),
+        ));
+        let query_rect = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 3.).into(), (4., 0.).into()),
+            time_interval,
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
+        };
+        let query_ctx = MockQueryContext::test_default();
+
+        let qp = agg
+            .initialize(&exe_ctx)
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .get_u8()
+            .unwrap();
+
+        qp.raster_query(query_rect, &query_ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>()
+            .await
+    }
+
+    fn tile_data(tile: &RasterTile2D<u8>) -> Vec<u8> {
+        tile.grid_array.clone().into_materialized_grid().data
+    }
+
+    #[tokio::test]
+    async fn test_sum_ignore_no_data() {
+        let result = aggregate(
+            Aggregation::Sum {
+                ignore_no_data: true,
+            },
+            None,
+            make_raster_with_no_data(),
+            TimeInterval::new_unchecked(0, 20),
+        )
+        .await;
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].time, TimeInterval::new_unchecked(0, 20));
+        assert_eq!(tile_data(&result[0]), vec![7, 8, 9, 42, 11, 12]);
+        assert_eq!(tile_data(&result[1]), vec![1, 2, 3, 42, 5, 6]);
+
+        let result = aggregate(
+            Aggregation::Sum {
+                ignore_no_data: false,
+            },
+            None,
+            make_raster(),
+            TimeInterval::new_unchecked(0, 20),
+        )
+        .await;
+
+        assert_eq!(tile_data(&result[0]), vec![13, 13, 13, 13, 13, 13]);
+        assert_eq!(tile_data(&result[1]), vec![13, 13, 13, 13, 13, 13]);
+    }
+
+    #[tokio::test]
+    async fn test_count() {
+        let result = aggregate(
+            Aggregation::Count,
+            None,
+            make_raster_with_no_data(),
+            TimeInterval::new_unchecked(0, 40),
+        )
+        .await;
+
+        assert_eq!(result.len(), 4);
+
+        // the second window only contains the third time step
+        assert_eq!(tile_data(&result[0]), vec![1, 1, 1, 42, 1, 1]);
+        assert_eq!(tile_data(&result[1]), vec![1, 1, 1, 42, 1, 1]);
+        assert_eq!(tile_data(&result[2]), vec![1, 42, 1, 1, 1, 1]);
+        assert_eq!(tile_data(&result[3]), vec![42, 42, 42, 42, 42, 42]);
+    }
+
+    #[tokio::test]
+    async fn test_window_reference() {
+        let result = aggregate(
+            Aggregation::Sum {
+                ignore_no_data: false,
+            },
+            Some(TimeInstance::from_millis_unchecked(10)),
+            make_raster(),
+            TimeInterval::new_unchecked(15, 25),
+        )
+        .await;
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].time, TimeInterval::new_unchecked(10, 30));
+        assert_eq!(tile_data(&result[0]), vec![13, 13, 13, 13, 13, 13]);
+        assert_eq!(tile_data(&result[1]), vec![13, 13, 13, 13, 13, 13]);
+    }
+
+    #[test]
+    fn test_deserialize_aggregations() {
+        let params: TemporalRasterAggregationParameters =
+            serde_json::from_value(serde_json::json!({
+                "aggregation": { "type": "count" },
+                "window": { "granularity": "Months", "step": 1 },
+            }))
+            .unwrap();
+
+        assert!(matches!(params.aggregation, Aggregation::Count));
+        assert_eq!(params.window_reference, None);
+
+        let params: TemporalRasterAggregationParameters =
+            serde_json::from_value(serde_json::json!({
+                "aggregation": { "type": "sum", "ignoreNoData": true },
+                "window": { "granularity": "Months", "step": 1 },
+                "windowReference": "2014-01-01T00:00:00Z",
+            }))
+            .unwrap();
+
+        assert!(matches!(
+            params.aggregation,
+            Aggregation::Sum {
+                ignore_no_data: true
+            }
+        ));
+        assert_eq!(
+            params.window_reference,
+            Some(TimeInstance::from_millis_unchecked(1_388_534_400_000))
+        );
+    }
+
     fn make_raster() -> (
         Option<u8>,
         Vec<geoengine_datatypes::raster::RasterTile2D<u8>>,
@@ -0,0 +1,625 @@
+use crate::engine::{
+    BoxRasterQueryProcessor, Cacheability, ExecutionContext, InitializedRasterOperator, Operator,
+    QueryContext, QueryProcessor, RasterOperator, RasterResultDescriptor, ResultExtent,
+    SingleRasterSource, TypedRasterQueryProcessor,
+};
+use crate::error;
+use crate::util::math::student_t_p_value;
+use crate::util::pixel_time_series::{tile_from_pixels, PixelTimeSeries};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use geoengine_datatypes::primitives::{
+    Measurement, RasterQueryRectangle, SpatialPartition2D, TimeInstance,
+};
+use geoengine_datatypes::raster::{
+    GridSize, RasterDataType, RasterTile2D, TileInformation, TilingSpecification,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// The output of per-pixel coefficients, pixels without enough observations are `NaN`
+type PixelOut = f32;
+const OUT_NO_DATA_VALUE: PixelOut = PixelOut::NAN;
+
+/// The time axis of the trend, an average year of the Gregorian calendar
+const MILLIS_PER_YEAR: f64 = 365.2425 * 24. * 60. * 60. * 1000.;
+
+/// The `TemporalTrend` operator fits a linear trend to the time series of each pixel of a raster
+/// within the query's time interval, e.g., to find greening in a series of NDVI composites. It
+/// outputs one coefficient of the fit, s.t. each output tile covers the whole interval.
+///
+/// If a `seasonalPeriod` is given, the series is decomposed into the trend and a seasonal cycle,
+/// which is a harmonic with this period that is fitted together with the trend. Then the trend is
+/// not biased by the season and the amplitude and phase of the cycle can be output, too.
+///
+/// Observations are placed at the start of their time step and time is measured in years.
+pub type TemporalTrend = Operator<TemporalTrendParams, SingleRasterSource>;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TemporalTrendParams {
+    pub coefficient: TrendCoefficient,
+    /// The length of the seasonal cycle in years, e.g., `1.0` for the annual cycle of vegetation
+    #[serde(default)]
+    pub seasonal_period: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TrendCoefficient {
+    /// The change of the value per year
+    Slope,
+    /// The value of the trend at the start of the first time step
+    Intercept,
+    /// The two-sided p-value of the slope, i.e., how likely such a trend appears by chance
+    PValue,
+    /// Half the difference between the peak and the low of the seasonal cycle
+    Amplitude,
+    /// The time of the peak of the seasonal cycle in years after the start of a cycle, where
+    /// cycles start at the Unix epoch, e.g., on January 1st for annual cycles
+    Phase,
+}
+
+impl TrendCoefficient {
+    fn is_seasonal(self) -> bool {
+        matches!(self, TrendCoefficient::Amplitude | TrendCoefficient::Phase)
+    }
+
+    fn measurement(self, source: &Measurement) -> Measurement {
+        match (self, source) {
+            (TrendCoefficient::Slope, Measurement::Continuous(source)) => Measurement::continuous(
+                format!("{} trend", source.measurement),
+                Some(format!(
+                    "{} per year",
+                    source.unit.as_deref().unwrap_or("units")
+                )),
+            ),
+            (TrendCoefficient::Intercept | TrendCoefficient::Amplitude, _) => source.clone(),
+            (TrendCoefficient::Phase, _) => {
+                Measurement::continuous("phase".to_string(), Some("years".to_string()))
+            }
+            _ => Measurement::Unitless,
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for TemporalTrend {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        ensure!(
+            self.params
+                .seasonal_period
+                .map_or(true, |period| period.is_finite() && period > 0.),
+            error::InvalidOperatorSpec {
+                reason: "The seasonal period must be positive".to_string(),
+            }
+        );
+        ensure!(
+            !self.params.coefficient.is_seasonal() || self.params.seasonal_period.is_some(),
+            error::InvalidOperatorSpec {
+                reason: "The amplitude and phase require a seasonal period".to_string(),
+            }
+        );
+
+        let source = self.sources.raster.initialize(context).await?;
+
+        let result_descriptor = RasterResultDescriptor {
+            data_type: RasterDataType::F32,
+            spatial_reference: source.result_descriptor().spatial_reference,
+            measurement: self
+                .params
+                .coefficient
+                .measurement(&source.result_descriptor().measurement),
+            no_data_value: Some(f64::from(OUT_NO_DATA_VALUE)),
+            dimensions: vec![],
+        };
+
+        Ok(InitializedTemporalTrend {
+            result_descriptor,
+            source,
+            params: self.params,
+            tiling_specification: context.tiling_specification(),
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedTemporalTrend {
+    result_descriptor: RasterResultDescriptor,
+    source: Box<dyn InitializedRasterOperator>,
+    params: TemporalTrendParams,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedTemporalTrend {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.source.extent()
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        Ok(TypedRasterQueryProcessor::F32(
+            TemporalTrendProcessor {
+                source: self.source.query_processor()?.into_f64(),
+                params: self.params,
+                tiling_specification: self.tiling_specification,
+            }
+            .boxed(),
+        ))
+    }
+}
+
+pub struct TemporalTrendProcessor {
+    source: BoxRasterQueryProcessor<f64>,
+    params: TemporalTrendParams,
+    tiling_specification: TilingSpecification,
+}
+
+impl TemporalTrendProcessor {
+    async fn trend_tile(
+        &self,
+        tile_info: TileInformation,
+        query: RasterQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<RasterTile2D<PixelOut>> {
+        let series = PixelTimeSeries::query(self.source.as_ref(), tile_info, query, ctx).await?;
+
+        let params = self.params;
+
+        Ok(
+            crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                trends(params, tile_info, query, &series)
+            })
+            .await?,
+        )
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for TemporalTrendProcessor {
+    type Output = RasterTile2D<PixelOut>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let tiling_strategy = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+        let stream = stream::iter(tiling_strategy.tile_information_iterator(query.spatial_bounds))
+            .then(move |tile_info| self.trend_tile(tile_info, query, ctx));
+
+        Ok(stream.boxed())
+    }
+}
+
+fn years(time: TimeInstance) -> f64 {
+    time.inner() as f64 / MILLIS_PER_YEAR
+}
+
+/// Computes the coefficient of each pixel of a tile from its time series
+fn trends(
+    params: TemporalTrendParams,
+    tile_info: TileInformation,
+    query: RasterQueryRectangle,
+    series: &PixelTimeSeries,
+) -> RasterTile2D<PixelOut> {
+    let times: Vec<f64> = series
+        .time_steps()
+        .iter()
+        .map(|time| years(time.start()))
+        .collect();
+
+    let model = TrendModel {
+        origin: times.first().copied().unwrap_or_default(),
+        seasonal_period: params.seasonal_period,
+    };
+
+    let mut observations = Vec::with_capacity(times.len());
+    let data: Vec<PixelOut> = (0..tile_info.tile_size_in_pixels.number_of_elements())
+        .map(|pixel| {
+            observations.clear();
+            observations.extend(
+                series
+                    .observations(pixel)
+                    .map(|(step, value)| (times[step], value)),
+            );
+
+            model
+                .fit(&observations)
+                .and_then(|fit| fit.coefficient(params.coefficient, &model))
+                .map_or(OUT_NO_DATA_VALUE, |value| value as PixelOut)
+        })
+        .collect();
+
+    tile_from_pixels(query.time_interval, tile_info, data)
+}
+
+/// The parameters are the intercept, the slope and, for seasonal trends, the weights of the
+/// cosine and sine of the harmonic
+const MAX_PARAMETERS: usize = 4;
+
+/// A linear trend, optionally with a seasonal harmonic, that is fitted by least squares
+struct TrendModel {
+    /// The time in years where the trend line intercepts
+    origin: f64,
+    seasonal_period: Option<f64>,
+}
+
+impl TrendModel {
+    fn parameters(&self) -> usize {
+        if self.seasonal_period.is_some() {
+            4
+        } else {
+            2
+        }
+    }
+
+    /// The regressors of an observation at `time` in years
+    fn regressors(&self, time: f64) -> [f64; MAX_PARAMETERS] {
+        match self.seasonal_period {
+            Some(period) => {
+                let angle = std::f64::consts::TAU * time / period;
+                [1., time - self.origin, angle.cos(), angle.sin()]
+            }
+            None => [1., time - self.origin, 0., 0.],
+        }
+    }
+
+    /// Fits the model to the `(time, value)` observations, `None` if they do not determine it
+    fn fit(&self, observations: &[(f64, f64)]) -> Option<TrendFit> {
+        let k = self.parameters();
+        if observations.len() < k {
+            return None;
+        }
+
+        let mut normal_matrix = [[0.; MAX_PARAMETERS]; MAX_PARAMETERS];
+        let mut moments = [0.; MAX_PARAMETERS];
+        for &(time, value) in observations {
+            let x = self.regressors(time);
+            for ((row, moment), x_i) in normal_matrix.iter_mut().zip(&mut moments).zip(x).take(k) {
+                for (entry, x_j) in row.iter_mut().zip(x).take(k) {
+                    *entry += x_i * x_j;
+                }
+                *moment += x_i * value;
+            }
+        }
+
+        let inverse = invert(normal_matrix, k)?;
+
+        let mut coefficients = [0.; MAX_PARAMETERS];
+        for (coefficient, row) in coefficients.iter_mut().zip(&inverse).take(k) {
+            *coefficient = row.iter().zip(&moments).take(k).map(|(a, b)| a * b).sum();
+        }
+
+        let residual_sum_of_squares = observations
+            .iter()
+            .map(|&(time, value)| {
+                let prediction: f64 = self
+                    .regressors(time)
+                    .iter()
+                    .zip(&coefficients)
+                    .take(k)
+                    .map(|(x, coefficient)| x * coefficient)
+                    .sum();
+                (value - prediction).powi(2)
+            })
+            .sum();
+
+        Some(TrendFit {
+            coefficients,
+            inverse,
+            residual_sum_of_squares,
+            degrees_of_freedom: observations.len() - k,
+        })
+    }
+}
+
+struct TrendFit {
+    coefficients: [f64; MAX_PARAMETERS],
+    /// The inverse of the normal matrix, which scales the variances of the coefficients
+    inverse: [[f64; MAX_PARAMETERS]; MAX_PARAMETERS],
+    residual_sum_of_squares: f64,
+    degrees_of_freedom: usize,
+}
+
+impl TrendFit {
+    #[allow(clippy::float_cmp)] // a slope of exactly zero has no significance
+    fn coefficient(&self, coefficient: TrendCoefficient, model: &TrendModel) -> Option<f64> {
+        let [intercept, slope, cosine, sine] = self.coefficients;
+
+        match coefficient {
+            TrendCoefficient::Slope => Some(slope),
+            TrendCoefficient::Intercept => Some(intercept),
+            TrendCoefficient::PValue => {
+                if self.degrees_of_freedom == 0 {
+                    return None;
+                }
+
+                let degrees_of_freedom = self.degrees_of_freedom as f64;
+                let variance = self.residual_sum_of_squares / degrees_of_freedom;
+                let standard_error = (variance * self.inverse[1][1]).sqrt();
+
+                Some(if standard_error > 0. {
+                    student_t_p_value(slope / standard_error, degrees_of_freedom)
+                } else if slope == 0. {
+                    1.
+                } else {
+                    // a perfect fit
+                    0.
+                })
+            }
+            TrendCoefficient::Amplitude => Some(cosine.hypot(sine)),
+            TrendCoefficient::Phase => {
+                let period = model.seasonal_period?;
+                // `c cos(a) + s sin(a)` peaks where `a = atan2(s, c)`
+                let angle = sine.atan2(cosine);
+                Some((angle / std::f64::consts::TAU * period).rem_euclid(period))
+            }
+        }
+    }
+}
+
+/// Inverts the upper left `k` x `k` part of the symmetric `matrix` by Gauss-Jordan elimination,
+/// `None` if it is singular
+#[allow(clippy::needless_range_loop)] // the elimination combines rows of both matrices
+fn invert(
+    mut matrix: [[f64; MAX_PARAMETERS]; MAX_PARAMETERS],
+    k: usize,
+) -> Option<[[f64; MAX_PARAMETERS]; MAX_PARAMETERS]> {
+    const SINGULARITY_THRESHOLD: f64 = 1e-12;
+
+    let mut inverse = [[0.; MAX_PARAMETERS]; MAX_PARAMETERS];
+    for (i, row) in inverse.iter_mut().enumerate().take(k) {
+        row[i] = 1.;
+    }
+
+    for column in 0..k {
+        let pivot_row = (column..k).max_by(|&a, &b| {
+            matrix[a][column]
+                .abs()
+                .partial_cmp(&matrix[b][column].abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+
+        if matrix[pivot_row][column].abs() < SINGULARITY_THRESHOLD {
+            return None;
+        }
+
+        matrix.swap(column, pivot_row);
+        inverse.swap(column, pivot_row);
+
+        let pivot = matrix[column][column];
+        for j in 0..k {
+            matrix[column][j] /= pivot;
+            inverse[column][j] /= pivot;
+        }
+
+        for row in 0..k {
+            if row == column {
+                continue;
+            }
+
+            let factor = matrix[row][column];
+            for j in 0..k {
+                matrix[row][j] -= factor * matrix[column][j];
+                inverse[row][j] -= factor * inverse[column][j];
+            }
+        }
+    }
+
+    Some(inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, RasterQueryProcessor};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::{SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::Grid2D;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    const MILLIS_PER_QUARTER: i64 = (MILLIS_PER_YEAR / 4.) as i64;
+
+    /// Eight quarterly scenes of four pixels
+    fn make_raster(pixels: [fn(f64) -> f64; 4]) -> Box<dyn RasterOperator> {
+        let tile_information = TileInformation {
+            global_tile_position: [-1, 0].into(),
+            tile_size_in_pixels: [2, 2].into(),
+            global_geo_transform: TestDefault::test_default(),
+        };
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: (0..8)
+                    .map(|quarter| {
+                        let years = quarter as f64 / 4.;
+                        RasterTile2D::new_with_tile_info(
+                            TimeInterval::new_unchecked(
+                                quarter * MILLIS_PER_QUARTER,
+                                (quarter + 1) * MILLIS_PER_QUARTER,
+                            ),
+                            tile_information,
+                            Grid2D::new(
+                                [2, 2].into(),
+                                pixels.iter().map(|pixel| pixel(years)).collect(),
+                                Some(-1.),
+                            )
+                            .unwrap()
+                            .into(),
+                        )
+                    })
+                    .collect(),
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::F64,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::continuous(
+                        "NDVI".to_string(),
+                        Some("index".to_string()),
+                    ),
+                    no_data_value: Some(-1.),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed()
+    }
+
+    async fn coefficients(
+        coefficient: TrendCoefficient,
+        seasonal_period: Option<f64>,
+        pixels: [fn(f64) -> f64; 4],
+    ) -> Vec<f32> {
+        let operator = TemporalTrend {
+            params: TemporalTrendParams {
+                coefficient,
+                seasonal_period,
+            },
+            sources: SingleRasterSource {
+                raster: make_raster(pixels),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [2, 2].into()),
+        ))
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().get_f32().unwrap();
+
+        let ctx = MockQueryContext::new(1.into());
+        let tiles: Vec<RasterTile2D<f32>> = processor
+            .raster_query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 2.).into(),
+                        (2., 0.).into(),
+                    ),
+                    time_interval: TimeInterval::new_unchecked(0, 8 * MILLIS_PER_QUARTER),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
+                },
+                &ctx,
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+        tiles[0].grid_array.clone().into_materialized_grid().data
+    }
+
+    fn greening(years: f64) -> f64 {
+        0.2 + 0.1 * years
+    }
+
+    fn constant(_years: f64) -> f64 {
+        0.5
+    }
+
+    fn missing(_years: f64) -> f64 {
+        -1.
+    }
+
+    /// A trend with a seasonal cycle that peaks a quarter year after the start of each year
+    fn seasonal(years: f64) -> f64 {
+        0.4 + 0.05 * years + 0.2 * (std::f64::consts::TAU * (years - 0.25)).cos()
+    }
+
+    fn noisy(years: f64) -> f64 {
+        0.5 + [0.1, -0.1, 0.05, 0., -0.05, 0.1, -0.1, 0.][(years * 4.).round() as usize]
+    }
+
+    #[tokio::test]
+    async fn it_computes_linear_trends() {
+        let pixels = [greening, constant, missing, noisy];
+
+        let slopes = coefficients(TrendCoefficient::Slope, None, pixels).await;
+        float_cmp::assert_approx_eq!(f32, slopes[0], 0.1, epsilon = 1e-5);
+        float_cmp::assert_approx_eq!(f32, slopes[1], 0., epsilon = 1e-5);
+        assert!(slopes[2].is_nan());
+
+        let intercepts = coefficients(TrendCoefficient::Intercept, None, pixels).await;
+        float_cmp::assert_approx_eq!(f32, intercepts[0], 0.2, epsilon = 1e-5);
+        float_cmp::assert_approx_eq!(f32, intercepts[1], 0.5, epsilon = 1e-5);
+
+        let p_values = coefficients(TrendCoefficient::PValue, None, pixels).await;
+        float_cmp::assert_approx_eq!(f32, p_values[0], 0.);
+        assert!(p_values[2].is_nan());
+        // no significant trend in the noise
+        assert!(p_values[3] > 0.5);
+    }
+
+    #[tokio::test]
+    async fn it_decomposes_seasonal_cycles() {
+        let pixels = [seasonal, constant, missing, greening];
+
+        let slopes = coefficients(TrendCoefficient::Slope, Some(1.), pixels).await;
+        float_cmp::assert_approx_eq!(f32, slopes[0], 0.05, epsilon = 1e-5);
+        float_cmp::assert_approx_eq!(f32, slopes[3], 0.1, epsilon = 1e-5);
+
+        let amplitudes = coefficients(TrendCoefficient::Amplitude, Some(1.), pixels).await;
+        float_cmp::assert_approx_eq!(f32, amplitudes[0], 0.2, epsilon = 1e-5);
+        float_cmp::assert_approx_eq!(f32, amplitudes[1], 0., epsilon = 1e-5);
+        float_cmp::assert_approx_eq!(f32, amplitudes[3], 0., epsilon = 1e-5);
+
+        let phases = coefficients(TrendCoefficient::Phase, Some(1.), pixels).await;
+        float_cmp::assert_approx_eq!(f32, phases[0], 0.25, epsilon = 1e-5);
+    }
+
+    #[tokio::test]
+    async fn it_requires_a_period_for_seasonal_coefficients() {
+        let result = TemporalTrend {
+            params: TemporalTrendParams {
+                coefficient: TrendCoefficient::Phase,
+                seasonal_period: None,
+            },
+            sources: SingleRasterSource {
+                raster: make_raster([constant; 4]),
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_inverts_matrices() {
+        let mut matrix = [[0.; MAX_PARAMETERS]; MAX_PARAMETERS];
+        matrix[0][0] = 4.;
+        matrix[0][1] = 2.;
+        matrix[1][0] = 2.;
+        matrix[1][1] = 2.;
+
+        let inverse = invert(matrix, 2).unwrap();
+        assert_eq!(inverse[0][..2], [0.5, -0.5]);
+        assert_eq!(inverse[1][..2], [-0.5, 1.]);
+
+        matrix[1][1] = 1.;
+        assert!(invert(matrix, 2).is_none());
+    }
+}
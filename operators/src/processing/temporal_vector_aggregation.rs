@@ -0,0 +1,784 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, QueryContext,
+    QueryProcessor, ResultExtent, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error::{self, Error};
+use crate::processing::dissolve::DissolveKey;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollection, FeatureCollectionInfos, FeatureCollectionRowBuilder,
+    GeoFeatureCollectionRowBuilder, IntoGeometryIterator,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, FeatureDataType, FeatureDataValue, Geometry, MultiLineString,
+    MultiLineStringAccess, MultiPoint, MultiPointAccess, MultiPolygon, MultiPolygonAccess,
+    NoGeometry, TimeInstance, TimeInterval, TimeStep, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The temporal vector aggregation aggregates the attributes of features over time windows.
+///
+/// Features are aggregated if they have the same geometry, i.e., belong to the same spatial
+/// unit, the same value in the optional `group_by` column and start in the same window.
+/// The operator emits one feature per spatial unit, group and window whose time interval is the
+/// window. The attributes listed in `aggregations` are aggregated, all other attributes are
+/// dropped.
+pub type TemporalVectorAggregation = Operator<TemporalVectorAggregationParams, SingleVectorSource>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemporalVectorAggregationParams {
+    /// The length of the time windows
+    pub window: TimeStep,
+    /// A start of a window, defaults to the Unix epoch
+    #[serde(default)]
+    pub window_reference: Option<TimeInstance>,
+    /// The column that further divides the features of a spatial unit
+    #[serde(default)]
+    pub group_by: Option<String>,
+    /// The aggregations of the attribute columns, the output columns keep their names
+    pub aggregations: HashMap<String, TemporalVectorAggregationFunction>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TemporalVectorAggregationFunction {
+    /// The number of non-null values
+    Count,
+    /// The sum of all non-null values
+    Sum,
+    /// The mean of all non-null values
+    Mean,
+    /// The minimum of all non-null values
+    Min,
+    /// The maximum of all non-null values
+    Max,
+}
+
+impl TemporalVectorAggregationFunction {
+    fn output_type(self) -> FeatureDataType {
+        match self {
+            TemporalVectorAggregationFunction::Count => FeatureDataType::Int,
+            TemporalVectorAggregationFunction::Sum
+            | TemporalVectorAggregationFunction::Mean
+            | TemporalVectorAggregationFunction::Min
+            | TemporalVectorAggregationFunction::Max => FeatureDataType::Float,
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for TemporalVectorAggregation {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let source_descriptor = vector_source.result_descriptor();
+
+        let mut columns = HashMap::with_capacity(self.params.aggregations.len() + 1);
+
+        if let Some(group_by) = &self.params.group_by {
+            let group_type = match source_descriptor.columns.get(group_by) {
+                Some(FeatureDataType::Float) => {
+                    return Err(Error::InvalidOperatorSpec {
+                        reason: format!("Group column '{}' must not be a float column.", group_by),
+                    })
+                }
+                Some(data_type) => *data_type,
+                None => {
+                    return Err(Error::ColumnDoesNotExist {
+                        column: group_by.clone(),
+                    })
+                }
+            };
+
+            columns.insert(group_by.clone(), group_type);
+        }
+
+        for (column, aggregation) in &self.params.aggregations {
+            ensure!(
+                Some(column) != self.params.group_by.as_ref(),
+                error::InvalidOperatorSpec {
+                    reason: format!("Cannot aggregate the group column '{}'.", column),
+                }
+            );
+
+            let input_type =
+                source_descriptor
+                    .columns
+                    .get(column)
+                    .ok_or_else(|| Error::ColumnDoesNotExist {
+                        column: column.clone(),
+                    })?;
+
+            ensure!(
+                *aggregation == TemporalVectorAggregationFunction::Count || input_type.is_numeric(),
+                error::InvalidOperatorSpec {
+                    reason: format!("Column '{}' must be numeric to be aggregated.", column),
+                }
+            );
+
+            columns.insert(column.clone(), aggregation.output_type());
+        }
+
+        // counts do not describe the values of their column anymore
+        let column_metadata =
+            source_descriptor.column_metadata_of(columns.keys().filter(|column| {
+                self.params.aggregations.get(*column)
+                    != Some(&TemporalVectorAggregationFunction::Count)
+            }));
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: source_descriptor.data_type,
+            spatial_reference: source_descriptor.spatial_reference,
+            columns,
+            column_metadata,
+        };
+
+        Ok(InitializedTemporalVectorAggregation {
+            result_descriptor,
+            vector_source,
+            params: Arc::new(self.params),
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedTemporalVectorAggregation {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    params: Arc<TemporalVectorAggregationParams>,
+}
+
+impl InitializedVectorOperator for InitializedTemporalVectorAggregation {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        Ok(map_typed_query_processor!(
+            self.vector_source.query_processor()?,
+            source => TemporalVectorAggregationProcessor {
+                source,
+                params: self.params.clone(),
+                column_types: self.result_descriptor.columns.clone(),
+            }
+            .boxed()
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.vector_source.extent()
+    }
+}
+
+pub struct TemporalVectorAggregationProcessor<G> {
+    source: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    params: Arc<TemporalVectorAggregationParams>,
+    column_types: HashMap<String, FeatureDataType>,
+}
+
+impl<G> TemporalVectorAggregationProcessor<G> {
+    fn window_reference(&self) -> TimeInstance {
+        self.params
+            .window_reference
+            .unwrap_or_else(|| TimeInstance::from_millis_unchecked(0))
+    }
+
+    /// Extends the query time interval to the windows it intersects
+    fn window_query(&self, query: VectorQueryRectangle) -> Result<VectorQueryRectangle> {
+        let reference = self.window_reference();
+        let time = query.time_interval;
+
+        let start = self.params.window.snap_relative(reference, time.start())?;
+        let mut end = self.params.window.snap_relative(reference, time.end())?;
+        if end < time.end() {
+            end = (end + self.params.window)?;
+        }
+
+        Ok(VectorQueryRectangle {
+            time_interval: TimeInterval::new(start, end)?,
+            ..query
+        })
+    }
+}
+
+#[async_trait]
+impl<G> QueryProcessor for TemporalVectorAggregationProcessor<G>
+where
+    G: SpatialUnit + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let window_query = self.window_query(query)?;
+        let reference = self.window_reference();
+
+        // the features of a window can be spread over all chunks, so we have to collect them first
+        let groups = self
+            .source
+            .query(window_query, ctx)
+            .await?
+            .try_fold(
+                AggregationGroups::new(self.params.clone(), reference),
+                |mut groups, collection| async move {
+                    groups.add_collection(&collection)?;
+                    Ok(groups)
+                },
+            )
+            .await?;
+
+        let aggregated = groups.finish(&self.column_types);
+
+        Ok(futures::stream::once(async move { aggregated }).boxed())
+    }
+}
+
+/// Geometries whose features are aggregated per spatial unit, i.e., per equal geometry
+pub trait SpatialUnit: Geometry + ArrowTyped + Sized {
+    /// The geometries of the features together with a hashable representation
+    fn spatial_units(collection: &FeatureCollection<Self>) -> Vec<(Self, Vec<u64>)>;
+
+    fn push_spatial_unit(
+        builder: &mut FeatureCollectionRowBuilder<Self>,
+        geometry: Self,
+    ) -> Result<()>;
+}
+
+fn push_coordinates(key: &mut Vec<u64>, coordinates: &[Coordinate2D]) {
+    key.push(coordinates.len() as u64);
+    key.extend(
+        coordinates
+            .iter()
+            .flat_map(|coordinate| [coordinate.x.to_bits(), coordinate.y.to_bits()]),
+    );
+}
+
+impl SpatialUnit for NoGeometry {
+    fn spatial_units(collection: &FeatureCollection<Self>) -> Vec<(Self, Vec<u64>)> {
+        vec![(NoGeometry, vec![]); collection.len()]
+    }
+
+    fn push_spatial_unit(
+        _builder: &mut FeatureCollectionRowBuilder<Self>,
+        _geometry: Self,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl SpatialUnit for MultiPoint {
+    fn spatial_units(collection: &FeatureCollection<Self>) -> Vec<(Self, Vec<u64>)> {
+        collection
+            .geometries()
+            .map(|geometry| {
+                let geometry = MultiPoint::from(geometry);
+                let mut key = Vec::new();
+                push_coordinates(&mut key, geometry.points());
+                (geometry, key)
+            })
+            .collect()
+    }
+
+    fn push_spatial_unit(
+        builder: &mut FeatureCollectionRowBuilder<Self>,
+        geometry: Self,
+    ) -> Result<()> {
+        builder.push_geometry(geometry).map_err(Into::into)
+    }
+}
+
+impl SpatialUnit for MultiLineString {
+    fn spatial_units(collection: &FeatureCollection<Self>) -> Vec<(Self, Vec<u64>)> {
+        collection
+            .geometries()
+            .map(|geometry| {
+                let geometry = MultiLineString::from(geometry);
+                let mut key = Vec::new();
+                for line in geometry.lines() {
+                    push_coordinates(&mut key, line);
+                }
+                (geometry, key)
+            })
+            .collect()
+    }
+
+    fn push_spatial_unit(
+        builder: &mut FeatureCollectionRowBuilder<Self>,
+        geometry: Self,
+    ) -> Result<()> {
+        builder.push_geometry(geometry).map_err(Into::into)
+    }
+}
+
+impl SpatialUnit for MultiPolygon {
+    fn spatial_units(collection: &FeatureCollection<Self>) -> Vec<(Self, Vec<u64>)> {
+        collection
+            .geometries()
+            .map(|geometry| {
+                let geometry = MultiPolygon::from(geometry);
+                let mut key = Vec::new();
+                for polygon in geometry.polygons() {
+                    key.push(polygon.len() as u64);
+                    for ring in polygon {
+                        push_coordinates(&mut key, ring);
+                    }
+                }
+                (geometry, key)
+            })
+            .collect()
+    }
+
+    fn push_spatial_unit(
+        builder: &mut FeatureCollectionRowBuilder<Self>,
+        geometry: Self,
+    ) -> Result<()> {
+        builder.push_geometry(geometry).map_err(Into::into)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum AggregateState {
+    Count(i64),
+    Sum(Option<f64>),
+    Mean { sum: f64, count: usize },
+    Min(Option<f64>),
+    Max(Option<f64>),
+}
+
+impl AggregateState {
+    fn new(aggregation: TemporalVectorAggregationFunction) -> Self {
+        match aggregation {
+            TemporalVectorAggregationFunction::Count => AggregateState::Count(0),
+            TemporalVectorAggregationFunction::Sum => AggregateState::Sum(None),
+            TemporalVectorAggregationFunction::Mean => AggregateState::Mean { sum: 0., count: 0 },
+            TemporalVectorAggregationFunction::Min => AggregateState::Min(None),
+            TemporalVectorAggregationFunction::Max => AggregateState::Max(None),
+        }
+    }
+
+    fn add(&mut self, value: &FeatureDataValue) {
+        if is_null(value) {
+            return;
+        }
+
+        if let AggregateState::Count(count) = self {
+            *count += 1;
+            return;
+        }
+
+        let value = match value {
+            FeatureDataValue::Int(value) | FeatureDataValue::NullableInt(Some(value)) => {
+                *value as f64
+            }
+            FeatureDataValue::Float(value) | FeatureDataValue::NullableFloat(Some(value)) => *value,
+            _ => return,
+        };
+
+        match self {
+            AggregateState::Count(_) => {}
+            AggregateState::Sum(sum) => *sum = Some(sum.unwrap_or_default() + value),
+            AggregateState::Mean { sum, count } => {
+                *sum += value;
+                *count += 1;
+            }
+            AggregateState::Min(min) => *min = Some(min.map_or(value, |min| min.min(value))),
+            AggregateState::Max(max) => *max = Some(max.map_or(value, |max| max.max(value))),
+        }
+    }
+
+    fn finish(self) -> FeatureDataValue {
+        match self {
+            AggregateState::Count(count) => FeatureDataValue::Int(count),
+            AggregateState::Sum(value)
+            | AggregateState::Min(value)
+            | AggregateState::Max(value) => FeatureDataValue::NullableFloat(value),
+            AggregateState::Mean { sum, count } => {
+                FeatureDataValue::NullableFloat((count > 0).then(|| sum / count as f64))
+            }
+        }
+    }
+}
+
+fn is_null(value: &FeatureDataValue) -> bool {
+    matches!(
+        value,
+        FeatureDataValue::NullableCategory(None)
+            | FeatureDataValue::NullableInt(None)
+            | FeatureDataValue::NullableFloat(None)
+            | FeatureDataValue::NullableText(None)
+            | FeatureDataValue::NullableBool(None)
+            | FeatureDataValue::NullableDateTime(None)
+    )
+}
+
+struct AggregationGroup<G> {
+    geometry: G,
+    group: Option<FeatureDataValue>,
+    window: TimeInterval,
+    aggregates: Vec<(String, AggregateState)>,
+}
+
+/// The groups of features in order of their first occurrence
+struct AggregationGroups<G> {
+    params: Arc<TemporalVectorAggregationParams>,
+    window_reference: TimeInstance,
+    groups: Vec<AggregationGroup<G>>,
+    group_indices: HashMap<(Vec<u64>, DissolveKey, i64), usize>,
+}
+
+impl<G: SpatialUnit> AggregationGroups<G> {
+    fn new(params: Arc<TemporalVectorAggregationParams>, window_reference: TimeInstance) -> Self {
+        Self {
+            params,
+            window_reference,
+            groups: Vec::new(),
+            group_indices: HashMap::new(),
+        }
+    }
+
+    fn add_collection(&mut self, collection: &FeatureCollection<G>) -> Result<()> {
+        let group_data = self
+            .params
+            .group_by
+            .as_ref()
+            .map(|column| collection.data(column))
+            .transpose()?;
+        let columns = self
+            .params
+            .aggregations
+            .iter()
+            .map(|(column, aggregation)| Ok((column, *aggregation, collection.data(column)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        for (i, ((geometry, geometry_key), time)) in G::spatial_units(collection)
+            .into_iter()
+            .zip(collection.time_intervals())
+            .enumerate()
+        {
+            let window_start = self
+                .params
+                .window
+                .snap_relative(self.window_reference, time.start())?;
+
+            let group = group_data.as_ref().map(|data| data.get_unchecked(i));
+            let group_key = (
+                geometry_key,
+                group.as_ref().map_or(DissolveKey::Null, DissolveKey::from),
+                window_start.inner(),
+            );
+
+            let group_index = if let Some(&group_index) = self.group_indices.get(&group_key) {
+                group_index
+            } else {
+                self.groups.push(AggregationGroup {
+                    geometry,
+                    group,
+                    window: TimeInterval::new(window_start, (window_start + self.params.window)?)?,
+                    aggregates: columns
+                        .iter()
+                        .map(|(column, aggregation, _)| {
+                            ((*column).clone(), AggregateState::new(*aggregation))
+                        })
+                        .collect(),
+                });
+                self.group_indices.insert(group_key, self.groups.len() - 1);
+                self.groups.len() - 1
+            };
+
+            for ((_, state), (_, _, data)) in
+                self.groups[group_index].aggregates.iter_mut().zip(&columns)
+            {
+                state.add(&data.get_unchecked(i));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(
+        self,
+        column_types: &HashMap<String, FeatureDataType>,
+    ) -> Result<FeatureCollection<G>> {
+        let mut builder = FeatureCollection::<G>::builder();
+        for (column, data_type) in column_types {
+            builder.add_column(column.clone(), *data_type)?;
+        }
+        let mut builder = builder.finish_header();
+
+        for group in self.groups {
+            G::push_spatial_unit(&mut builder, group.geometry)?;
+            builder.push_time_interval(group.window)?;
+
+            if let (Some(column), Some(value)) = (&self.params.group_by, group.group) {
+                push_value(&mut builder, column, value)?;
+            }
+            for (column, state) in group.aggregates {
+                push_value(&mut builder, &column, state.finish())?;
+            }
+
+            builder.finish_row();
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+fn push_value<G: Geometry + ArrowTyped>(
+    builder: &mut FeatureCollectionRowBuilder<G>,
+    column: &str,
+    value: FeatureDataValue,
+) -> Result<()> {
+    if is_null(&value) {
+        builder.push_null(column)?;
+    } else {
+        builder.push_data(column, value)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{FeatureData, SpatialResolution, TimeGranularity};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[test]
+    fn serialization() {
+        let aggregation = TemporalVectorAggregation {
+            params: TemporalVectorAggregationParams {
+                window: TimeStep {
+                    granularity: TimeGranularity::Months,
+                    step: 1,
+                },
+                window_reference: None,
+                group_by: Some("species".to_string()),
+                aggregations: [(
+                    "abundance".to_string(),
+                    TemporalVectorAggregationFunction::Sum,
+                )]
+                .into_iter()
+                .collect(),
+            },
+            sources: MockFeatureCollectionSource::<MultiPoint>::multiple(vec![])
+                .boxed()
+                .into(),
+        }
+        .boxed();
+
+        let serialized = serde_json::to_value(&aggregation).unwrap();
+
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "type": "TemporalVectorAggregation",
+                "params": {
+                    "window": {
+                        "granularity": "months",
+                        "step": 1
+                    },
+                    "windowReference": null,
+                    "groupBy": "species",
+                    "aggregations": {
+                        "abundance": "sum"
+                    }
+                },
+                "sources": {
+                    "vector": {
+                        "type": "MockFeatureCollectionSourceMultiPoint",
+                        "params": {
+                            "collections": [],
+                            "spatialReference": "EPSG:4326"
+                        }
+                    }
+                }
+            })
+        );
+
+        let _operator: Box<dyn VectorOperator> = serde_json::from_value(serialized).unwrap();
+    }
+
+    #[tokio::test]
+    async fn aggregate_observations() {
+        let observations = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0., 0.), (0., 0.), (0., 0.), (1., 1.), (0., 0.)]).unwrap(),
+            vec![
+                TimeInterval::new_unchecked(0, 1),
+                TimeInterval::new_unchecked(3, 4),
+                TimeInterval::new_unchecked(5, 6),
+                TimeInterval::new_unchecked(2, 3),
+                TimeInterval::new_unchecked(4, 5),
+            ],
+            [
+                (
+                    "species".to_string(),
+                    FeatureData::Text(vec![
+                        "a".to_string(),
+                        "a".to_string(),
+                        "a".to_string(),
+                        "a".to_string(),
+                        "b".to_string(),
+                    ]),
+                ),
+                (
+                    "abundance".to_string(),
+                    FeatureData::NullableInt(vec![Some(1), Some(2), Some(4), Some(8), None]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+
+        let aggregation = TemporalVectorAggregation {
+            params: TemporalVectorAggregationParams {
+                window: TimeStep {
+                    granularity: TimeGranularity::Millis,
+                    step: 5,
+                },
+                window_reference: None,
+                group_by: Some("species".to_string()),
+                aggregations: [(
+                    "abundance".to_string(),
+                    TemporalVectorAggregationFunction::Sum,
+                )]
+                .into_iter()
+                .collect(),
+            },
+            sources: MockFeatureCollectionSource::single(observations)
+                .boxed()
+                .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        assert_eq!(
+            aggregation.result_descriptor().columns,
+            [
+                ("species".to_string(), FeatureDataType::Text),
+                ("abundance".to_string(), FeatureDataType::Float),
+            ]
+            .into_iter()
+            .collect()
+        );
+
+        let processor = aggregation
+            .query_processor()
+            .unwrap()
+            .multi_point()
+            .unwrap();
+
+        let results: Vec<MultiPointCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-1., -1.).into(), (2., 2.).into()).unwrap(),
+                    time_interval: TimeInterval::new_unchecked(1, 6),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        let expected = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0., 0.), (0., 0.), (1., 1.), (0., 0.)]).unwrap(),
+            vec![
+                TimeInterval::new_unchecked(0, 5),
+                TimeInterval::new_unchecked(5, 10),
+                TimeInterval::new_unchecked(0, 5),
+                TimeInterval::new_unchecked(0, 5),
+            ],
+            [
+                (
+                    "species".to_string(),
+                    FeatureData::Text(vec![
+                        "a".to_string(),
+                        "a".to_string(),
+                        "a".to_string(),
+                        "b".to_string(),
+                    ]),
+                ),
+                (
+                    "abundance".to_string(),
+                    FeatureData::NullableFloat(vec![Some(3.), Some(4.), Some(8.), None]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+
+        assert_eq!(results[0], expected);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_non_numeric_sums() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0., 0.)]).unwrap(),
+            vec![TimeInterval::default()],
+            [("name".to_string(), FeatureData::Text(vec!["a".to_string()]))]
+                .into_iter()
+                .collect(),
+        )
+        .unwrap();
+
+        let aggregation = |function| TemporalVectorAggregation {
+            params: TemporalVectorAggregationParams {
+                window: TimeStep {
+                    granularity: TimeGranularity::Days,
+                    step: 1,
+                },
+                window_reference: None,
+                group_by: None,
+                aggregations: [("name".to_string(), function)].into_iter().collect(),
+            },
+            sources: MockFeatureCollectionSource::single(collection.clone())
+                .boxed()
+                .into(),
+        };
+
+        assert!(
+            Box::new(aggregation(TemporalVectorAggregationFunction::Mean))
+                .initialize(&MockExecutionContext::test_default())
+                .await
+                .is_err()
+        );
+        assert!(
+            Box::new(aggregation(TemporalVectorAggregationFunction::Count))
+                .initialize(&MockExecutionContext::test_default())
+                .await
+                .is_ok()
+        );
+    }
+}
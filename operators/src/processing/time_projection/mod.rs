@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
 use crate::engine::{
-    ExecutionContext, InitializedVectorOperator, Operator, QueryContext, SingleVectorSource,
-    TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, QueryContext,
+    ResultExtent, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
 };
 use crate::util::Result;
 use async_trait::async_trait;
@@ -104,6 +105,15 @@ impl InitializedVectorOperator for InitializedVectorTimeProjection {
             }.boxed().into()),
         )
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        // the time intervals are projected to the steps at query time
+        ResultExtent::new(self.source.extent().spatial, None)
+    }
 }
 
 pub struct VectorTimeProjectionProcessor<G>
@@ -204,6 +214,7 @@ fn expand_query_rectangle(
         spatial_bounds: query.spatial_bounds,
         time_interval: expand_time_interval(step, step_reference, query.time_interval)?,
         spatial_resolution: query.spatial_resolution,
+        spatial_filter: query.spatial_filter,
     })
 }
 
@@ -427,6 +438,7 @@ mod tests {
                     )
                     .unwrap(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &query_context,
             )
@@ -529,6 +541,7 @@ mod tests {
                     )
                     .unwrap(),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
                 },
                 &query_context,
             )
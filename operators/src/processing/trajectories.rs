@@ -0,0 +1,522 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, QueryContext,
+    QueryProcessor, ResultExtent, SingleVectorSource, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error::{self, Error};
+use crate::processing::dissolve::DissolveKey;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    BuilderProvider, FeatureCollectionInfos, GeoFeatureCollectionRowBuilder, GeometryCollection,
+    MultiLineStringCollection, MultiPointCollection, VectorDataType,
+};
+use geoengine_datatypes::operations::geodesic::Measurement;
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, FeatureDataType, FeatureDataValue, MultiLineString, TimeInstance,
+    TimeInterval, TimeStep, VectorQueryRectangle,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The trajectories operator builds the tracks of moving objects from timestamped points.
+///
+/// Points are grouped by the value of the id column and ordered by the start of their time
+/// interval. Each pair of consecutive points of an object becomes a line segment whose time
+/// interval spans from the first to the second point's time. For multi points, only the first
+/// point of each feature is used.
+///
+/// Only points within the query rectangle are considered, so tracks that leave it are cut.
+pub type Trajectories = Operator<TrajectoriesParams, SingleVectorSource>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrajectoriesParams {
+    /// The column that identifies the moving objects
+    pub id_column: String,
+    /// Consecutive points whose times are farther apart are not connected, i.e., the track is split
+    #[serde(default)]
+    pub max_gap: Option<TimeStep>,
+    /// An output column that contains the length of the segments.
+    /// The length is in meters for geographic spatial references.
+    #[serde(default)]
+    pub length_column: Option<String>,
+    /// An output column that contains the speed on the segments in length units per second
+    #[serde(default)]
+    pub speed_column: Option<String>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Trajectories {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let source_descriptor = vector_source.result_descriptor();
+
+        ensure!(
+            source_descriptor.data_type == VectorDataType::MultiPoint,
+            error::InvalidType {
+                expected: VectorDataType::MultiPoint.to_string(),
+                found: source_descriptor.data_type.to_string(),
+            }
+        );
+
+        let id_type = match source_descriptor.columns.get(&self.params.id_column) {
+            Some(FeatureDataType::Float) => {
+                return Err(Error::InvalidOperatorSpec {
+                    reason: format!(
+                        "Id column '{}' must not be a float column.",
+                        self.params.id_column
+                    ),
+                })
+            }
+            Some(data_type) => *data_type,
+            None => {
+                return Err(Error::ColumnDoesNotExist {
+                    column: self.params.id_column.clone(),
+                })
+            }
+        };
+
+        let mut columns = HashMap::from([(self.params.id_column.clone(), id_type)]);
+
+        for column in self
+            .params
+            .length_column
+            .iter()
+            .chain(&self.params.speed_column)
+        {
+            ensure!(
+                !columns.contains_key(column),
+                error::InvalidOperatorSpec {
+                    reason: format!("Column '{}' is used more than once.", column),
+                }
+            );
+
+            columns.insert(column.clone(), FeatureDataType::Float);
+        }
+
+        let measurement = Measurement::for_spatial_reference(source_descriptor.spatial_reference)?;
+
+        let result_descriptor = VectorResultDescriptor {
+            data_type: VectorDataType::MultiLineString,
+            spatial_reference: source_descriptor.spatial_reference,
+            columns,
+            column_metadata: source_descriptor.column_metadata_of([&self.params.id_column]),
+        };
+
+        Ok(InitializedTrajectories {
+            result_descriptor,
+            vector_source,
+            state: Arc::new(TrajectoriesState {
+                params: self.params,
+                id_type,
+                measurement,
+            }),
+        }
+        .boxed())
+    }
+}
+
+#[derive(Debug)]
+struct TrajectoriesState {
+    params: TrajectoriesParams,
+    id_type: FeatureDataType,
+    measurement: Measurement,
+}
+
+pub struct InitializedTrajectories {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    state: Arc<TrajectoriesState>,
+}
+
+impl InitializedVectorOperator for InitializedTrajectories {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let source = self
+            .vector_source
+            .query_processor()?
+            .multi_point()
+            .ok_or_else(|| Error::InvalidVectorType {
+                expected: VectorDataType::MultiPoint.to_string(),
+                found: self.vector_source.result_descriptor().data_type.to_string(),
+            })?;
+
+        Ok(TypedVectorQueryProcessor::MultiLineString(
+            TrajectoriesProcessor {
+                source,
+                state: self.state.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.vector_source.extent()
+    }
+}
+
+pub struct TrajectoriesProcessor {
+    source: Box<dyn VectorQueryProcessor<VectorType = MultiPointCollection>>,
+    state: Arc<TrajectoriesState>,
+}
+
+#[async_trait]
+impl QueryProcessor for TrajectoriesProcessor {
+    type Output = MultiLineStringCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        // the points of a track can be spread over all chunks, so we have to collect them first
+        let tracks = self
+            .source
+            .query(query, ctx)
+            .await?
+            .try_fold(
+                Tracks::new(self.state.params.id_column.clone()),
+                |mut tracks, collection| async move {
+                    tracks.add_collection(&collection)?;
+                    Ok(tracks)
+                },
+            )
+            .await?;
+
+        let state = self.state.clone();
+        let trajectories =
+            crate::util::spawn_blocking(move || tracks.into_segments(&state)).await??;
+
+        Ok(futures::stream::once(async move { Ok(trajectories) }).boxed())
+    }
+}
+
+/// The id of a moving object together with its positions
+struct Track {
+    id: FeatureDataValue,
+    positions: Vec<(TimeInstance, Coordinate2D)>,
+}
+
+/// The tracks of all moving objects in order of their first occurrence
+struct Tracks {
+    id_column: String,
+    tracks: Vec<Track>,
+    track_indices: HashMap<DissolveKey, usize>,
+}
+
+impl Tracks {
+    fn new(id_column: String) -> Self {
+        Self {
+            id_column,
+            tracks: Vec::new(),
+            track_indices: HashMap::new(),
+        }
+    }
+
+    fn add_collection(&mut self, collection: &MultiPointCollection) -> Result<()> {
+        let ids = collection.data(&self.id_column)?;
+        let coordinates = collection.coordinates();
+        let offsets = collection.feature_offsets();
+        let time_intervals = collection.time_intervals();
+
+        for i in 0..collection.len() {
+            let id = ids.get_unchecked(i);
+            let key = DissolveKey::from(&id);
+
+            // points without an id cannot be assigned to a track
+            if key == DissolveKey::Null {
+                continue;
+            }
+
+            let track_index = if let Some(&track_index) = self.track_indices.get(&key) {
+                track_index
+            } else {
+                self.tracks.push(Track {
+                    id,
+                    positions: Vec::new(),
+                });
+                self.track_indices.insert(key, self.tracks.len() - 1);
+                self.tracks.len() - 1
+            };
+
+            self.tracks[track_index]
+                .positions
+                .push((time_intervals[i].start(), coordinates[offsets[i] as usize]));
+        }
+
+        Ok(())
+    }
+
+    fn into_segments(self, state: &TrajectoriesState) -> Result<MultiLineStringCollection> {
+        let params = &state.params;
+
+        let mut builder = MultiLineStringCollection::builder();
+        builder.add_column(params.id_column.clone(), state.id_type)?;
+        for column in params.length_column.iter().chain(&params.speed_column) {
+            builder.add_column(column.clone(), FeatureDataType::Float)?;
+        }
+        let mut builder = builder.finish_header();
+
+        for mut track in self.tracks {
+            track.positions.sort_by_key(|(time, _)| *time);
+
+            for segment in track.positions.windows(2) {
+                let (start, from) = segment[0];
+                let (end, to) = segment[1];
+
+                if let Some(max_gap) = params.max_gap {
+                    if (start + max_gap)? < end {
+                        continue;
+                    }
+                }
+
+                builder.push_geometry(MultiLineString::new(vec![vec![from, to]])?)?;
+                builder.push_time_interval(TimeInterval::new(start, end)?)?;
+                builder.push_data(&params.id_column, track.id.clone())?;
+
+                let length = state.measurement.distance(from, to);
+                if let Some(length_column) = &params.length_column {
+                    builder.push_data(length_column, FeatureDataValue::Float(length))?;
+                }
+                if let Some(speed_column) = &params.speed_column {
+                    let seconds = (end.inner() - start.inner()) as f64 / 1000.;
+                    if seconds > 0. {
+                        builder
+                            .push_data(speed_column, FeatureDataValue::Float(length / seconds))?;
+                    } else {
+                        builder.push_null(speed_column)?;
+                    }
+                }
+
+                builder.finish_row();
+            }
+        }
+
+        builder.build().map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::primitives::{
+        FeatureData, MultiPoint, SpatialResolution, TimeGranularity,
+    };
+    use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceAuthority};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn fixes() -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![
+                (0., 0.),
+                (5., 5.),
+                (0., 3.),
+                (100., 100.),
+                (0., 4.),
+                (0., 0.),
+            ])
+            .unwrap(),
+            vec![
+                TimeInterval::new_unchecked(0, 1),
+                TimeInterval::new_unchecked(0, 1),
+                TimeInterval::new_unchecked(2_000, 2_001),
+                TimeInterval::new_unchecked(0, 1),
+                TimeInterval::new_unchecked(1_000, 1_001),
+                TimeInterval::new_unchecked(60_000, 60_001),
+            ],
+            [(
+                "animal".to_string(),
+                FeatureData::NullableText(vec![
+                    Some("stork".to_string()),
+                    Some("crane".to_string()),
+                    Some("stork".to_string()),
+                    None,
+                    Some("stork".to_string()),
+                    Some("stork".to_string()),
+                ]),
+            )]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn serialization() {
+        let trajectories = Trajectories {
+            params: TrajectoriesParams {
+                id_column: "animal".to_string(),
+                max_gap: Some(TimeStep {
+                    granularity: TimeGranularity::Minutes,
+                    step: 10,
+                }),
+                length_column: Some("length".to_string()),
+                speed_column: None,
+            },
+            sources: MockFeatureCollectionSource::<MultiPoint>::multiple(vec![])
+                .boxed()
+                .into(),
+        }
+        .boxed();
+
+        let serialized = serde_json::to_value(&trajectories).unwrap();
+
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "type": "Trajectories",
+                "params": {
+                    "idColumn": "animal",
+                    "maxGap": {
+                        "granularity": "minutes",
+                        "step": 10
+                    },
+                    "lengthColumn": "length",
+                    "speedColumn": null
+                },
+                "sources": {
+                    "vector": {
+                        "type": "MockFeatureCollectionSourceMultiPoint",
+                        "params": {
+                            "collections": [],
+                            "spatialReference": "EPSG:4326"
+                        }
+                    }
+                }
+            })
+        );
+
+        let _operator: Box<dyn VectorOperator> = serde_json::from_value(serialized).unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_builds_trajectories() {
+        let trajectories = Trajectories {
+            params: TrajectoriesParams {
+                id_column: "animal".to_string(),
+                max_gap: Some(TimeStep {
+                    granularity: TimeGranularity::Seconds,
+                    step: 10,
+                }),
+                length_column: Some("length".to_string()),
+                speed_column: Some("speed".to_string()),
+            },
+            sources: MockFeatureCollectionSource::with_collections_and_sref(
+                vec![fixes()],
+                SpatialReference::new(SpatialReferenceAuthority::Epsg, 3857),
+            )
+            .boxed()
+            .into(),
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        assert_eq!(
+            trajectories.result_descriptor().data_type,
+            VectorDataType::MultiLineString
+        );
+
+        let processor = trajectories
+            .query_processor()
+            .unwrap()
+            .multi_line_string()
+            .unwrap();
+
+        let results: Vec<MultiLineStringCollection> = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-1., -1.).into(), (200., 200.).into())
+                        .unwrap(),
+                    time_interval: TimeInterval::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
+                },
+                &MockQueryContext::test_default(),
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+
+        // the crane has only one fix and the last stork fix is too far apart
+        let expected = MultiLineStringCollection::from_data(
+            vec![
+                MultiLineString::new(vec![vec![(0., 0.).into(), (0., 4.).into()]]).unwrap(),
+                MultiLineString::new(vec![vec![(0., 4.).into(), (0., 3.).into()]]).unwrap(),
+            ],
+            vec![
+                TimeInterval::new_unchecked(0, 1_000),
+                TimeInterval::new_unchecked(1_000, 2_000),
+            ],
+            [
+                (
+                    "animal".to_string(),
+                    FeatureData::NullableText(vec![
+                        Some("stork".to_string()),
+                        Some("stork".to_string()),
+                    ]),
+                ),
+                ("length".to_string(), FeatureData::Float(vec![4., 1.])),
+                ("speed".to_string(), FeatureData::Float(vec![4., 1.])),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+
+        assert_eq!(results[0], expected);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_float_ids() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0., 0.)]).unwrap(),
+            vec![TimeInterval::default()],
+            [("id".to_string(), FeatureData::Float(vec![1.]))]
+                .into_iter()
+                .collect(),
+        )
+        .unwrap();
+
+        let trajectories = Trajectories {
+            params: TrajectoriesParams {
+                id_column: "id".to_string(),
+                max_gap: None,
+                length_column: None,
+                speed_column: None,
+            },
+            sources: MockFeatureCollectionSource::single(collection)
+                .boxed()
+                .into(),
+        };
+
+        assert!(Box::new(trajectories)
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,536 @@
+use crate::adapters::FeatureCollectionChunkMerger;
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedVectorOperator, MultipleVectorSources, Operator,
+    QueryContext, QueryProcessor, ResultExtent, TypedVectorQueryProcessor, VectorOperator,
+    VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error;
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::future::join_all;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, FeatureCollectionModifications, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureData, FeatureDataType, Geometry, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const MAX_NUMBER_OF_VECTOR_INPUTS: usize = 64;
+
+/// The `UnionVectorSources` operator merges the feature collections of multiple sources into a
+/// single stream, e.g., to combine regional extracts of the same dataset.
+///
+/// All sources must have the same geometry type and spatial reference.
+/// The columns of the output are the union of the columns of all sources. Columns with the same
+/// name must have the same type. The `strictness` decides about columns that are missing in some
+/// of the sources.
+///
+/// The sources are queried one after another in the order of the sources.
+pub type UnionVectorSources = Operator<UnionVectorSourcesParams, MultipleVectorSources>;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UnionVectorSourcesParams {
+    #[serde(default)]
+    pub strictness: UnionStrictness,
+}
+
+/// Determines how the column schemas of the sources are reconciled
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UnionStrictness {
+    /// all sources must have the same columns
+    Strict,
+    /// columns that are missing in a source are filled with nulls
+    Lenient,
+}
+
+impl Default for UnionStrictness {
+    fn default() -> Self {
+        Self::Lenient
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for UnionVectorSources {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(
+            (1..=MAX_NUMBER_OF_VECTOR_INPUTS).contains(&self.sources.vectors.len()),
+            error::InvalidNumberOfVectorInputs {
+                expected: 1..MAX_NUMBER_OF_VECTOR_INPUTS,
+                found: self.sources.vectors.len()
+            }
+        );
+
+        let sources = join_all(
+            self.sources
+                .vectors
+                .into_iter()
+                .map(|s| s.initialize(context)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        let result_descriptor = union_result_descriptor(
+            sources.iter().map(|s| s.result_descriptor()),
+            self.params.strictness,
+        )?;
+
+        Ok(InitializedUnionVectorSources {
+            result_descriptor,
+            sources,
+        }
+        .boxed())
+    }
+}
+
+/// Checks that the sources are compatible and merges their column schemas
+fn union_result_descriptor<'d>(
+    descriptors: impl Iterator<Item = &'d VectorResultDescriptor> + Clone,
+    strictness: UnionStrictness,
+) -> Result<VectorResultDescriptor> {
+    let mut others = descriptors.clone();
+    let mut union = others.next().expect("there is at least one source").clone();
+
+    for descriptor in others {
+        ensure!(
+            union.data_type == descriptor.data_type,
+            error::InvalidVectorType {
+                expected: union.data_type.to_string(),
+                found: descriptor.data_type.to_string(),
+            }
+        );
+        ensure!(
+            union.spatial_reference == descriptor.spatial_reference,
+            error::InvalidSpatialReference {
+                expected: union.spatial_reference,
+                found: descriptor.spatial_reference,
+            }
+        );
+
+        for (column, &data_type) in &descriptor.columns {
+            match union.columns.entry(column.clone()) {
+                Entry::Occupied(entry) => ensure!(
+                    *entry.get() == data_type,
+                    error::UnionColumnTypeMismatch {
+                        column: column.clone(),
+                        left: *entry.get(),
+                        right: data_type,
+                    }
+                ),
+                Entry::Vacant(entry) => {
+                    entry.insert(data_type);
+                }
+            }
+        }
+
+        for (column, metadata) in &descriptor.column_metadata {
+            union
+                .column_metadata
+                .entry(column.clone())
+                .or_insert_with(|| metadata.clone());
+        }
+    }
+
+    if strictness == UnionStrictness::Strict {
+        for (source_index, descriptor) in descriptors.enumerate() {
+            if let Some(column) = union
+                .columns
+                .keys()
+                .find(|column| !descriptor.columns.contains_key(*column))
+            {
+                return Err(error::Error::UnionColumnMissing {
+                    column: column.clone(),
+                    source_index,
+                });
+            }
+        }
+    }
+
+    Ok(union)
+}
+
+pub struct InitializedUnionVectorSources {
+    result_descriptor: VectorResultDescriptor,
+    sources: Vec<Box<dyn InitializedVectorOperator>>,
+}
+
+impl InitializedVectorOperator for InitializedUnionVectorSources {
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let processors = self
+            .sources
+            .iter()
+            .map(|s| s.query_processor())
+            .collect::<Result<Vec<_>>>()?;
+
+        let columns = Arc::new(self.result_descriptor.columns.clone());
+
+        Ok(match self.result_descriptor.data_type {
+            VectorDataType::Data => TypedVectorQueryProcessor::Data(
+                UnionVectorSourcesProcessor::new(
+                    processors,
+                    TypedVectorQueryProcessor::data,
+                    columns,
+                )
+                .boxed(),
+            ),
+            VectorDataType::MultiPoint => TypedVectorQueryProcessor::MultiPoint(
+                UnionVectorSourcesProcessor::new(
+                    processors,
+                    TypedVectorQueryProcessor::multi_point,
+                    columns,
+                )
+                .boxed(),
+            ),
+            VectorDataType::MultiLineString => TypedVectorQueryProcessor::MultiLineString(
+                UnionVectorSourcesProcessor::new(
+                    processors,
+                    TypedVectorQueryProcessor::multi_line_string,
+                    columns,
+                )
+                .boxed(),
+            ),
+            VectorDataType::MultiPolygon => TypedVectorQueryProcessor::MultiPolygon(
+                UnionVectorSourcesProcessor::new(
+                    processors,
+                    TypedVectorQueryProcessor::multi_polygon,
+                    columns,
+                )
+                .boxed(),
+            ),
+        })
+    }
+
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::combine_all(
+            self.sources
+                .iter()
+                .map(InitializedVectorOperator::cacheability),
+        )
+    }
+
+    fn extent(&self) -> ResultExtent {
+        ResultExtent::union_all(self.sources.iter().map(InitializedVectorOperator::extent))
+    }
+}
+
+pub struct UnionVectorSourcesProcessor<G> {
+    sources: Vec<Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>>,
+    columns: Arc<HashMap<String, FeatureDataType>>,
+}
+
+impl<G> UnionVectorSourcesProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send,
+{
+    /// Creates the processor from sources that were checked to be of type `G`
+    fn new<F>(
+        sources: Vec<TypedVectorQueryProcessor>,
+        typed: F,
+        columns: Arc<HashMap<String, FeatureDataType>>,
+    ) -> Self
+    where
+        F: Fn(
+            TypedVectorQueryProcessor,
+        ) -> Option<Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>>,
+    {
+        Self {
+            sources: sources
+                .into_iter()
+                .map(|s| typed(s).expect("vector types are checked during initialization"))
+                .collect(),
+            columns,
+        }
+    }
+}
+
+/// Adds the columns of `columns` that are missing in the `collection` as null columns
+fn add_missing_columns<G>(
+    collection: FeatureCollection<G>,
+    columns: &HashMap<String, FeatureDataType>,
+) -> Result<FeatureCollection<G>>
+where
+    G: Geometry + ArrowTyped,
+{
+    let existing_columns = collection.column_types();
+
+    let missing_columns: Vec<(&str, FeatureData)> = columns
+        .iter()
+        .filter(|(column, _)| !existing_columns.contains_key(*column))
+        .map(|(column, &data_type)| {
+            let nulls = std::iter::repeat(None).take(collection.len());
+            (
+                column.as_str(),
+                FeatureData::nullable_from_values(data_type, nulls),
+            )
+        })
+        .collect();
+
+    if missing_columns.is_empty() {
+        return Ok(collection);
+    }
+
+    collection.add_columns(&missing_columns).map_err(Into::into)
+}
+
+#[async_trait]
+impl<G> QueryProcessor for UnionVectorSourcesProcessor<G>
+where
+    G: Geometry + ArrowTyped + Sync + Send + 'static,
+{
+    type Output = FeatureCollection<G>;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let mut streams = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            streams.push(source.query(query.clone(), ctx).await?);
+        }
+
+        let union_stream = futures::stream::iter(streams)
+            .flatten()
+            .map(move |collection| add_missing_columns(collection?, &self.columns));
+
+        let merged_chunks_stream =
+            FeatureCollectionChunkMerger::new(union_stream.fuse(), ctx.chunk_byte_size().into());
+
+        Ok(merged_chunks_stream.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::error::Error;
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::collections::{DataCollection, MultiPointCollection};
+    use geoengine_datatypes::primitives::{MultiPoint, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn points(columns: Vec<(&str, FeatureData)>) -> MultiPointCollection {
+        MultiPointCollection::from_data(
+            MultiPoint::many(vec![(0.0, 0.1), (1.0, 1.1)]).unwrap(),
+            vec![TimeInterval::new(0, 1).unwrap(); 2],
+            columns
+                .into_iter()
+                .map(|(name, data)| (name.to_string(), data))
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    fn union(
+        sources: Vec<Box<dyn VectorOperator>>,
+        strictness: UnionStrictness,
+    ) -> Box<dyn VectorOperator> {
+        UnionVectorSources {
+            params: UnionVectorSourcesParams { strictness },
+            sources: sources.into(),
+        }
+        .boxed()
+    }
+
+    async fn query_points(operator: Box<dyn VectorOperator>) -> Result<Vec<MultiPointCollection>> {
+        let processor = operator
+            .initialize(&MockExecutionContext::test_default())
+            .await?
+            .query_processor()?
+            .multi_point()
+            .unwrap();
+
+        let query_rectangle = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (4., 4.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
+        };
+
+        let ctx = MockQueryContext::test_default();
+
+        let stream = processor.query(query_rectangle, &ctx).await?;
+
+        stream.collect::<Vec<_>>().await.into_iter().collect()
+    }
+
+    #[tokio::test]
+    async fn same_schema() {
+        let a = points(vec![("id", FeatureData::Int(vec![1, 2]))]);
+        let b = points(vec![("id", FeatureData::Int(vec![3, 4]))]);
+
+        let result = query_points(union(
+            vec![
+                MockFeatureCollectionSource::single(a.clone()).boxed(),
+                MockFeatureCollectionSource::single(b.clone()).boxed(),
+            ],
+            UnionStrictness::Strict,
+        ))
+        .await
+        .unwrap();
+
+        assert_eq!(result, vec![a.append(&b).unwrap()]);
+    }
+
+    #[tokio::test]
+    async fn missing_columns_become_nulls() {
+        let a = points(vec![
+            ("id", FeatureData::Int(vec![1, 2])),
+            (
+                "name",
+                FeatureData::Text(vec!["a".to_string(), "b".to_string()]),
+            ),
+        ]);
+        let b = points(vec![
+            ("id", FeatureData::Int(vec![3, 4])),
+            ("value", FeatureData::Float(vec![3.5, 4.5])),
+        ]);
+
+        let operator = union(
+            vec![
+                MockFeatureCollectionSource::single(a).boxed(),
+                MockFeatureCollectionSource::single(b).boxed(),
+            ],
+            UnionStrictness::Lenient,
+        );
+
+        let result = query_points(operator).await.unwrap();
+
+        assert_eq!(result.len(), 1);
+        let result = &result[0];
+
+        assert_eq!(result.len(), 4);
+        assert_eq!(
+            result.column_types(),
+            [
+                ("id".to_string(), FeatureDataType::Int),
+                ("name".to_string(), FeatureDataType::Text),
+                ("value".to_string(), FeatureDataType::Float),
+            ]
+            .into_iter()
+            .collect()
+        );
+
+        assert_eq!(
+            result.data("name").unwrap().nulls(),
+            vec![false, false, true, true]
+        );
+        assert_eq!(
+            result
+                .data("value")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![None, None, Some(3.5), Some(4.5)]
+        );
+        assert_eq!(
+            result
+                .data("id")
+                .unwrap()
+                .float_options_iter()
+                .collect::<Vec<_>>(),
+            vec![Some(1.), Some(2.), Some(3.), Some(4.)]
+        );
+    }
+
+    #[tokio::test]
+    async fn strict_rejects_missing_columns() {
+        let operator = union(
+            vec![
+                MockFeatureCollectionSource::single(points(vec![(
+                    "id",
+                    FeatureData::Int(vec![1, 2]),
+                )]))
+                .boxed(),
+                MockFeatureCollectionSource::single(points(vec![])).boxed(),
+            ],
+            UnionStrictness::Strict,
+        );
+
+        let result = operator
+            .initialize(&MockExecutionContext::test_default())
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::UnionColumnMissing { column, source_index: 1 }) if column == "id"
+        ));
+    }
+
+    #[tokio::test]
+    async fn incompatible_sources() {
+        let ctx = MockExecutionContext::test_default();
+
+        let type_mismatch = union(
+            vec![
+                MockFeatureCollectionSource::single(points(vec![(
+                    "id",
+                    FeatureData::Int(vec![1, 2]),
+                )]))
+                .boxed(),
+                MockFeatureCollectionSource::single(points(vec![(
+                    "id",
+                    FeatureData::Text(vec!["1".to_string(), "2".to_string()]),
+                )]))
+                .boxed(),
+            ],
+            UnionStrictness::Lenient,
+        );
+
+        assert!(matches!(
+            type_mismatch.initialize(&ctx).await,
+            Err(Error::UnionColumnTypeMismatch { .. })
+        ));
+
+        let geometry_mismatch = union(
+            vec![
+                MockFeatureCollectionSource::single(points(vec![])).boxed(),
+                MockFeatureCollectionSource::single(DataCollection::empty()).boxed(),
+            ],
+            UnionStrictness::Lenient,
+        );
+
+        assert!(matches!(
+            geometry_mismatch.initialize(&ctx).await,
+            Err(Error::InvalidVectorType { .. })
+        ));
+
+        assert!(matches!(
+            union(vec![], UnionStrictness::Lenient)
+                .initialize(&ctx)
+                .await,
+            Err(Error::InvalidNumberOfVectorInputs { .. })
+        ));
+    }
+
+    #[test]
+    fn serde() {
+        let params: UnionVectorSourcesParams =
+            serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(params.strictness, UnionStrictness::Lenient);
+
+        let params: UnionVectorSourcesParams =
+            serde_json::from_value(serde_json::json!({ "strictness": "strict" })).unwrap();
+        assert_eq!(params.strictness, UnionStrictness::Strict);
+    }
+}
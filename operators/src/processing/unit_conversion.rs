@@ -0,0 +1,355 @@
+use std::sync::Arc;
+
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedRasterOperator, Operator, QueryContext,
+    QueryProcessor, RasterOperator, RasterQueryProcessor, RasterResultDescriptor, ResultExtent,
+    SingleRasterSource, TileBufferPool, TypedRasterQueryProcessor,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::{
+    RasterQueryRectangle, SpatialPartition2D, Unit, UnitConversion,
+};
+use geoengine_datatypes::raster::{
+    EmptyGrid, Grid2D, GridShapeAccess, GridSize, NoDataValue, Pixel, RasterDataType, RasterTile2D,
+};
+use num_traits::AsPrimitive;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use rayon::ThreadPool;
+use serde::{Deserialize, Serialize};
+
+// Output type is always f32
+type PixelOut = f32;
+use RasterDataType::F32 as RasterOut;
+use TypedRasterQueryProcessor::F32 as QueryProcessorOut;
+
+const OUT_NO_DATA_VALUE: PixelOut = PixelOut::NAN;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertUnitParams {
+    /// The symbol of the unit of the output, e.g., `K` or `mm/day`
+    pub output_unit: String,
+}
+
+/// The unit conversion operator converts the pixel values of a raster from the unit of its
+/// measurement into the `output_unit`.
+///
+/// Both units must be part of the unit registry (`geoengine_datatypes::primitives::UNITS`) and
+/// have the same physical dimension, e.g., `°C` and `K` or `mm/day` and `m/s`.
+/// This is verified when the operator is initialized.
+/// The measurement of the output carries the `output_unit`.
+pub type ConvertUnit = Operator<ConvertUnitParams, SingleRasterSource>;
+
+pub struct InitializedConvertUnit {
+    result_descriptor: RasterResultDescriptor,
+    source: Box<dyn InitializedRasterOperator>,
+    conversion: UnitConversion,
+    tile_buffer_pool: Arc<TileBufferPool>,
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for ConvertUnit {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let input = self.sources.raster.initialize(context).await?;
+
+        let in_desc = input.result_descriptor();
+
+        let output_unit = Unit::from_symbol(&self.params.output_unit)?;
+        let conversion = in_desc
+            .measurement
+            .physical_unit()?
+            .conversion_to(output_unit)?;
+
+        let out_desc = RasterResultDescriptor {
+            spatial_reference: in_desc.spatial_reference,
+            data_type: RasterOut,
+            measurement: in_desc.measurement.in_unit(output_unit),
+            no_data_value: Some(f64::from(OUT_NO_DATA_VALUE)),
+            dimensions: in_desc.dimensions.clone(),
+        };
+
+        let initialized_operator = InitializedConvertUnit {
+            result_descriptor: out_desc,
+            source: input,
+            conversion,
+            tile_buffer_pool: context.tile_buffer_pool().clone(),
+        };
+
+        Ok(initialized_operator.boxed())
+    }
+}
+
+impl InitializedRasterOperator for InitializedConvertUnit {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        let q = self.source.query_processor()?;
+
+        Ok(call_on_generic_raster_processor!(q, p => {
+            QueryProcessorOut(Box::new(ConvertUnitProcessor::new(
+                p,
+                self.conversion,
+                self.tile_buffer_pool.clone(),
+            )))
+        }))
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.source.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.source.extent()
+    }
+}
+
+struct ConvertUnitProcessor<Q, P>
+where
+    Q: RasterQueryProcessor<RasterType = P>,
+{
+    source: Q,
+    conversion: UnitConversion,
+    tile_buffer_pool: Arc<TileBufferPool>,
+}
+
+impl<Q, P> ConvertUnitProcessor<Q, P>
+where
+    Q: RasterQueryProcessor<RasterType = P>,
+    P: Pixel,
+{
+    pub fn new(
+        source: Q,
+        conversion: UnitConversion,
+        tile_buffer_pool: Arc<TileBufferPool>,
+    ) -> Self {
+        Self {
+            source,
+            conversion,
+            tile_buffer_pool,
+        }
+    }
+
+    async fn process_tile_async(
+        &self,
+        tile: RasterTile2D<P>,
+        pool: Arc<ThreadPool>,
+    ) -> Result<RasterTile2D<PixelOut>> {
+        if tile.is_empty() {
+            return Ok(RasterTile2D::new_with_properties(
+                tile.time,
+                tile.tile_position,
+                tile.global_geo_transform,
+                EmptyGrid::new(tile.grid_array.grid_shape(), OUT_NO_DATA_VALUE).into(),
+                tile.properties,
+            ));
+        }
+
+        let mat_tile = tile.into_materialized_tile();
+        let conversion = self.conversion;
+        let tile_buffer_pool = self.tile_buffer_pool.clone();
+
+        let (grid, mat_tile) = crate::util::spawn_blocking_with_thread_pool(pool, move || {
+            let mut mat_tile = mat_tile;
+            let grid = convert_grid(&mat_tile.grid_array, conversion, &tile_buffer_pool);
+            tile_buffer_pool.give_back(std::mem::take(&mut mat_tile.grid_array.data));
+            (grid, mat_tile)
+        })
+        .await?;
+
+        Ok(RasterTile2D::new_with_properties(
+            mat_tile.time,
+            mat_tile.tile_position,
+            mat_tile.global_geo_transform,
+            grid.into(),
+            mat_tile.properties,
+        ))
+    }
+}
+
+fn convert_grid<P: Pixel>(
+    grid: &Grid2D<P>,
+    conversion: UnitConversion,
+    tile_buffer_pool: &TileBufferPool,
+) -> Grid2D<PixelOut> {
+    let mut data = tile_buffer_pool.take_with_capacity(grid.data.len());
+
+    grid.data
+        .par_iter()
+        .with_min_len(grid.axis_size_x())
+        .map(|p| {
+            if grid.is_no_data(*p) {
+                OUT_NO_DATA_VALUE
+            } else {
+                let value: f64 = p.as_();
+                conversion.convert(value).as_()
+            }
+        })
+        .collect_into_vec(&mut data);
+
+    Grid2D::new(grid.grid_shape(), data, Some(OUT_NO_DATA_VALUE))
+        .expect("raster creation must succeed")
+}
+
+#[async_trait]
+impl<Q, P> QueryProcessor for ConvertUnitProcessor<Q, P>
+where
+    Q: QueryProcessor<Output = RasterTile2D<P>, SpatialBounds = SpatialPartition2D>,
+    P: Pixel,
+{
+    type Output = RasterTile2D<PixelOut>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let src = self.source.query(query, ctx).await?;
+        let rs = src.and_then(move |tile| self.process_tile_async(tile, ctx.thread_pool().clone()));
+        Ok(rs.boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::mock::{MockRasterSource, MockRasterSourceParams};
+    use float_cmp::approx_eq;
+    use geoengine_datatypes::primitives::{Measurement, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::TileInformation;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    fn make_raster(measurement: Measurement) -> Box<dyn RasterOperator> {
+        let raster =
+            Grid2D::new([3, 2].into(), vec![-10_i16, 0, 10, 20, 30, 255], Some(255)).unwrap();
+
+        let raster_tile = RasterTile2D::new_with_tile_info(
+            TimeInterval::default(),
+            TileInformation {
+                global_tile_position: [-1, 0].into(),
+                tile_size_in_pixels: [3, 2].into(),
+                global_geo_transform: TestDefault::test_default(),
+            },
+            raster.into(),
+        );
+
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![raster_tile],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::I16,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement,
+                    no_data_value: Some(255.),
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed()
+    }
+
+    fn convert_unit(measurement: Measurement, output_unit: &str) -> Box<dyn RasterOperator> {
+        ConvertUnit {
+            params: ConvertUnitParams {
+                output_unit: output_unit.to_string(),
+            },
+            sources: SingleRasterSource {
+                raster: make_raster(measurement),
+            },
+        }
+        .boxed()
+    }
+
+    #[tokio::test]
+    async fn celsius_to_kelvin() {
+        let operator = convert_unit(
+            Measurement::continuous("temperature".into(), Some("°C".into())),
+            "K",
+        )
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        assert_eq!(
+            operator.result_descriptor().measurement,
+            Measurement::continuous("temperature".into(), Some("K".into()))
+        );
+        assert_eq!(operator.result_descriptor().data_type, RasterDataType::F32);
+
+        let processor = operator.query_processor().unwrap().get_f32().unwrap();
+
+        let ctx = MockQueryContext::new(1.into());
+        let result = processor
+            .query(
+                RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        (0., 4.).into(),
+                        (3., 0.).into(),
+                    ),
+                    time_interval: Default::default(),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
+                },
+                &ctx,
+            )
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(result.len(), 1);
+
+        let tile = result[0].as_ref().unwrap().clone().into_materialized_tile();
+        let data = tile.grid_array.data;
+
+        for (value, expected) in data.iter().zip([263.15, 273.15, 283.15, 293.15, 303.15]) {
+            assert!(approx_eq!(f32, *value, expected));
+        }
+        assert!(data[5].is_nan());
+    }
+
+    #[tokio::test]
+    async fn incompatible_units() {
+        let ctx = MockExecutionContext::test_default();
+
+        assert!(convert_unit(
+            Measurement::continuous("precipitation".into(), Some("mm/day".into())),
+            "m/s",
+        )
+        .initialize(&ctx)
+        .await
+        .is_ok());
+
+        assert!(convert_unit(
+            Measurement::continuous("precipitation".into(), Some("mm/day".into())),
+            "K",
+        )
+        .initialize(&ctx)
+        .await
+        .is_err());
+
+        assert!(convert_unit(Measurement::Unitless, "K")
+            .initialize(&ctx)
+            .await
+            .is_err());
+
+        assert!(convert_unit(
+            Measurement::continuous("temperature".into(), Some("K".into())),
+            "parsec",
+        )
+        .initialize(&ctx)
+        .await
+        .is_err());
+    }
+}
@@ -11,7 +11,7 @@ use geoengine_datatypes::collections::{
     GeometryRandomAccess,
 };
 use geoengine_datatypes::primitives::{
-    BoundingBox2D, FeatureDataRef, Geometry, TimeInterval, VectorQueryRectangle,
+    BoundingBox2D, DataRef, FeatureDataRef, Geometry, TimeInterval, VectorQueryRectangle,
 };
 use geoengine_datatypes::util::arrow::ArrowTyped;
 
@@ -169,10 +169,13 @@ where
                 )
             }
             (FeatureDataRef::Category(left), FeatureDataRef::Category(right)) => {
-                let left_value = left.as_ref()[left_idx];
+                // the dictionaries of both sides differ, so the categories must be compared
+                let left_value = left.category_at(left_idx);
+                let right_values: Vec<Option<&str>> =
+                    (0..right.len()).map(|i| right.category_at(i)).collect();
                 matches(
-                    right.as_ref(),
-                    |right_value| left_value == right_value,
+                    &right_values,
+                    |right_value| left_value.is_some() && left_value == right_value,
                     left_time_interval,
                     right_time_intervals,
                 )
@@ -355,29 +358,32 @@ where
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
         let result_stream = self
             .left_processor
-            .query(query, ctx)
+            .query(query.clone(), ctx)
             .await?
-            .and_then(move |left_collection| async move {
-                // This implementation is a nested-loop join
-                let left_collection = Arc::new(left_collection);
-
-                let data_query = self.right_processor.query(query, ctx).await?;
-
-                let out = data_query
-                    .flat_map(move |right_collection| {
-                        match right_collection.and_then(|right_collection| {
-                            self.join(
-                                left_collection.clone(),
-                                right_collection,
-                                ctx.chunk_byte_size().into(),
-                            )
-                        }) {
-                            Ok(batch_iter) => stream::iter(batch_iter).boxed(),
-                            Err(e) => stream::once(async { Err(e) }).boxed(),
-                        }
-                    })
-                    .boxed();
-                Ok(out)
+            .and_then(move |left_collection| {
+                let query = query.clone();
+                async move {
+                    // This implementation is a nested-loop join
+                    let left_collection = Arc::new(left_collection);
+
+                    let data_query = self.right_processor.query(query, ctx).await?;
+
+                    let out = data_query
+                        .flat_map(move |right_collection| {
+                            match right_collection.and_then(|right_collection| {
+                                self.join(
+                                    left_collection.clone(),
+                                    right_collection,
+                                    ctx.chunk_byte_size().into(),
+                                )
+                            }) {
+                                Ok(batch_iter) => stream::iter(batch_iter).boxed(),
+                                Err(e) => stream::once(async { Err(e) }).boxed(),
+                            }
+                        })
+                        .boxed();
+                    Ok(out)
+                }
             })
             .try_flatten();
 
@@ -435,6 +441,7 @@ mod tests {
             .unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
 
         let ctx = MockQueryContext::new(ChunkByteSize::MAX);
@@ -5,20 +5,22 @@ use snafu::ensure;
 use geoengine_datatypes::collections::VectorDataType;
 
 use crate::engine::{
-    ExecutionContext, InitializedVectorOperator, Operator, OperatorDatasets,
-    TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+    Cacheability, ExecutionContext, InitializedVectorOperator, Operator, OperatorDatasets,
+    ResultExtent, TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor,
+    VectorResultDescriptor,
 };
 use crate::error;
 use crate::util::Result;
 
 use self::equi_data_join::EquiGeoToDataJoinProcessor;
-use crate::processing::vector_join::util::translation_table;
 use async_trait::async_trait;
 use std::collections::HashMap;
 
 mod equi_data_join;
 mod util;
 
+pub(crate) use self::util::translation_table;
+
 /// The vector join operator requires two inputs and the join type.
 pub type VectorJoin = Operator<VectorJoinParams, VectorJoinSources>;
 
@@ -204,6 +206,14 @@ impl InitializedVectorOperator for InitializedVectorJoin {
     fn result_descriptor(&self) -> &VectorResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.left.cacheability().combine(self.right.cacheability())
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.left.extent()
+    }
 }
 
 #[cfg(test)]
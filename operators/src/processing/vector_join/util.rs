@@ -1,7 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
 /// Create a translation table to resolve name conflicts in the `DataCollection`
-pub(super) fn translation_table<'i>(
+pub(crate) fn translation_table<'i>(
     existing_column_names: impl Iterator<Item = &'i String>,
     new_column_names: impl Iterator<Item = &'i String>,
     right_column_suffix: &str,
@@ -0,0 +1,364 @@
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use geoengine_datatypes::dataset::DatasetId;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::engine::{
+    ExecutionContext, InitializedPlotOperator, InitializedRasterOperator,
+    InitializedScalarOperator, InitializedTableOperator, InitializedVectorOperator, Operator,
+    OperatorDatasets, PlotOperator, RasterOperator, ScalarOperator, TableOperator, VectorOperator,
+};
+use crate::error::{self, Error};
+use crate::util::Result;
+
+/// The key of the objects that are placeholders for the inputs of a macro, e.g., `{ "$input": "raster" }`
+pub const MACRO_INPUT_PLACEHOLDER_KEY: &str = "$input";
+
+/// A reference to a reusable sub-workflow that is expanded on initialization.
+///
+/// The operator that the macro expands to is retrieved from the [`ExecutionContext`].
+/// Its placeholders for inputs are replaced by the operators of the `sources`.
+pub type Macro = Operator<MacroParams, MacroSources>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroParams {
+    pub name: String,
+    /// The version of the macro, the latest version if not specified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+}
+
+/// The named inputs of a macro
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroSources {
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub rasters: HashMap<String, Box<dyn RasterOperator>>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub vectors: HashMap<String, Box<dyn VectorOperator>>,
+}
+
+impl OperatorDatasets for MacroSources {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        for raster in self.rasters.values() {
+            raster.datasets_collect(datasets);
+        }
+        for vector in self.vectors.values() {
+            vector.datasets_collect(datasets);
+        }
+    }
+}
+
+impl Macro {
+    /// Retrieves the macro's operator and substitutes its inputs
+    async fn expand<T: DeserializeOwned>(self, context: &dyn ExecutionContext) -> Result<T> {
+        let name = self.params.name;
+
+        let mut operator = context.workflow_macro(&name, self.params.version).await?;
+
+        let mut inputs = HashMap::new();
+        for (input, raster) in self.sources.rasters {
+            inputs.insert(input, serde_json::to_value(raster)?);
+        }
+        for (input, vector) in self.sources.vectors {
+            inputs.insert(input, serde_json::to_value(vector)?);
+        }
+
+        let mut used_inputs = HashSet::new();
+        substitute_inputs(&mut operator, &name, &inputs, &mut used_inputs)?;
+
+        if let Some(input) = inputs
+            .into_keys()
+            .find(|input| !used_inputs.contains(input))
+        {
+            return Err(Error::UnknownMacroInput { name, input });
+        }
+
+        serde_json::from_value(operator).context(error::InvalidMacroExpansion { name })
+    }
+}
+
+fn substitute_inputs(
+    value: &mut serde_json::Value,
+    name: &str,
+    inputs: &HashMap<String, serde_json::Value>,
+    used_inputs: &mut HashSet<String>,
+) -> Result<()> {
+    let placeholder = value
+        .as_object()
+        .filter(|map| map.len() == 1)
+        .and_then(|map| map.get(MACRO_INPUT_PLACEHOLDER_KEY))
+        .and_then(serde_json::Value::as_str)
+        .map(ToString::to_string);
+
+    if let Some(input) = placeholder {
+        *value = inputs
+            .get(&input)
+            .cloned()
+            .ok_or_else(|| Error::MissingMacroInput {
+                name: name.to_string(),
+                input: input.clone(),
+            })?;
+        used_inputs.insert(input);
+        return Ok(());
+    }
+
+    match value {
+        serde_json::Value::Array(values) => {
+            for value in values {
+                substitute_inputs(value, name, inputs, used_inputs)?;
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for value in map.values_mut() {
+                substitute_inputs(value, name, inputs, used_inputs)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for Macro {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        let operator: Box<dyn RasterOperator> = self.expand(context).await?;
+        operator.initialize(context).await
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for Macro {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        let operator: Box<dyn VectorOperator> = self.expand(context).await?;
+        operator.initialize(context).await
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl PlotOperator for Macro {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedPlotOperator>> {
+        let operator: Box<dyn PlotOperator> = self.expand(context).await?;
+        operator.initialize(context).await
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl TableOperator for Macro {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedTableOperator>> {
+        let operator: Box<dyn TableOperator> = self.expand(context).await?;
+        operator.initialize(context).await
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl ScalarOperator for Macro {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedScalarOperator>> {
+        let operator: Box<dyn ScalarOperator> = self.expand(context).await?;
+        operator.initialize(context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MacroProvider, MockExecutionContext, MockQueryContext, QueryProcessor};
+    use crate::mock::{MockPointSource, MockPointSourceParams};
+    use futures::StreamExt;
+    use geoengine_datatypes::collections::{MultiPointCollection, VectorDataType};
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, Coordinate2D, SpatialResolution, VectorQueryRectangle,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+    use serde_json::json;
+
+    fn points() -> Box<dyn VectorOperator> {
+        MockPointSource {
+            params: MockPointSourceParams {
+                points: vec![Coordinate2D::new(1., 2.), Coordinate2D::new(20., 20.)],
+            },
+        }
+        .boxed()
+    }
+
+    fn macro_context() -> MockExecutionContext {
+        let mut context = MockExecutionContext::test_default();
+
+        context.add_macro(
+            "clip",
+            json!({
+                "type": "PointInPolygonFilter",
+                "params": {},
+                "sources": {
+                    "points": { "$input": "points" },
+                    "polygons": { "$input": "polygons" }
+                }
+            }),
+        );
+        context.add_macro(
+            "clip",
+            json!({
+                "type": "ColumnFilter",
+                "params": { "predicate": { "type": "isNull", "column": "foo" } },
+                "sources": { "vector": { "$input": "points" } }
+            }),
+        );
+
+        context
+    }
+
+    #[tokio::test]
+    async fn it_expands_macros() {
+        let mut context = MockExecutionContext::test_default();
+        context.add_macro("identity", json!({ "$input": "points" }));
+
+        let operator = VectorOperator::boxed(Macro {
+            params: MacroParams {
+                name: "identity".to_string(),
+                version: None,
+            },
+            sources: MacroSources {
+                rasters: HashMap::new(),
+                vectors: [("points".to_string(), points())].into_iter().collect(),
+            },
+        });
+
+        let initialized = operator.initialize(&context).await.unwrap();
+
+        assert_eq!(
+            initialized.result_descriptor().data_type,
+            VectorDataType::MultiPoint
+        );
+
+        let processor = initialized
+            .query_processor()
+            .unwrap()
+            .multi_point()
+            .unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (30., 30.).into()).unwrap(),
+            time_interval: Default::default(),
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: None,
+        };
+        let ctx = MockQueryContext::test_default();
+
+        let collections: Vec<MultiPointCollection> = processor
+            .query(query, &ctx)
+            .await
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(collections.len(), 1);
+        assert_eq!(collections[0].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_resolves_versions() {
+        let context = macro_context();
+
+        assert_eq!(
+            context.workflow_macro("clip", None).await.unwrap()["type"],
+            "ColumnFilter"
+        );
+        assert_eq!(
+            context.workflow_macro("clip", Some(1)).await.unwrap()["type"],
+            "PointInPolygonFilter"
+        );
+        assert!(matches!(
+            context.workflow_macro("clip", Some(3)).await,
+            Err(Error::UnknownMacro { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_checks_inputs() {
+        let context = macro_context();
+
+        let operator = Macro {
+            params: MacroParams {
+                name: "clip".to_string(),
+                version: Some(1),
+            },
+            sources: MacroSources {
+                rasters: HashMap::new(),
+                vectors: [("points".to_string(), points())].into_iter().collect(),
+            },
+        };
+
+        assert!(matches!(
+            operator.clone().expand::<Box<dyn VectorOperator>>(&context).await,
+            Err(Error::MissingMacroInput { input, .. }) if input == "polygons"
+        ));
+
+        let operator = Macro {
+            params: MacroParams {
+                name: "clip".to_string(),
+                version: None,
+            },
+            sources: MacroSources {
+                rasters: HashMap::new(),
+                vectors: [
+                    ("points".to_string(), points()),
+                    ("lines".to_string(), points()),
+                ]
+                .into_iter()
+                .collect(),
+            },
+        };
+
+        assert!(matches!(
+            operator.expand::<Box<dyn VectorOperator>>(&context).await,
+            Err(Error::UnknownMacroInput { input, .. }) if input == "lines"
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_checks_the_output_type() {
+        let context = macro_context();
+
+        let operator = Macro {
+            params: MacroParams {
+                name: "clip".to_string(),
+                version: None,
+            },
+            sources: MacroSources {
+                rasters: HashMap::new(),
+                vectors: [("points".to_string(), points())].into_iter().collect(),
+            },
+        };
+
+        assert!(matches!(
+            operator.expand::<Box<dyn RasterOperator>>(&context).await,
+            Err(Error::InvalidMacroExpansion { .. })
+        ));
+    }
+}
@@ -0,0 +1,618 @@
+use crate::engine::{
+    Cacheability, ExecutionContext, InitializedRasterOperator, InitializedVectorOperator, Operator,
+    QueryContext, QueryProcessor, RasterQueryProcessor, ResultExtent,
+    SingleVectorMultipleRasterSources, TypedRasterQueryProcessor, TypedVectorQueryProcessor,
+    VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
+};
+use crate::error::{self, Error};
+use crate::processing::raster_vector_join::util::{
+    CoveredPixels, MultiPolygonCoveredPixels, PixelCoverCreator,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::future::join_all;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::{
+    FeatureCollectionInfos, FeatureCollectionModifications, MultiPolygonCollection, VectorDataType,
+};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, FeatureData, FeatureDataType, VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::{GridIndexAccess, NoDataValue, Pixel};
+use num_traits::AsPrimitive;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+/// The zonal statistics operator computes statistics of raster values inside the polygons of a
+/// vector source and attaches them as new columns.
+///
+/// For each raster source and statistic, there is a column named `{name}_{statistic}`, e.g.,
+/// `ndvi_mean`. The statistics include all valid pixels of all raster time steps that overlap
+/// a polygon's time interval. Polygons without valid pixels get null values.
+pub type ZonalStatistics = Operator<ZonalStatisticsParams, SingleVectorMultipleRasterSources>;
+
+const MAX_NUMBER_OF_RASTER_INPUTS: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ZonalStatisticsParams {
+    /// The column name prefix for each raster input
+    pub names: Vec<String>,
+    /// The statistics that are computed for each raster input, defaults to all statistics
+    #[serde(default = "all_statistics")]
+    pub statistics: Vec<ZonalStatistic>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ZonalStatistic {
+    Mean,
+    Min,
+    Max,
+    /// The population standard deviation
+    StdDev,
+}
+
+impl ZonalStatistic {
+    fn column_suffix(self) -> &'static str {
+        match self {
+            ZonalStatistic::Mean => "mean",
+            ZonalStatistic::Min => "min",
+            ZonalStatistic::Max => "max",
+            ZonalStatistic::StdDev => "stddev",
+        }
+    }
+}
+
+fn all_statistics() -> Vec<ZonalStatistic> {
+    vec![
+        ZonalStatistic::Mean,
+        ZonalStatistic::Min,
+        ZonalStatistic::Max,
+        ZonalStatistic::StdDev,
+    ]
+}
+
+fn column_name(name: &str, statistic: ZonalStatistic) -> String {
+    format!("{}_{}", name, statistic.column_suffix())
+}
+
+#[typetag::serde]
+#[async_trait]
+impl VectorOperator for ZonalStatistics {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn ExecutionContext,
+    ) -> Result<Box<dyn InitializedVectorOperator>> {
+        ensure!(
+            (1..=MAX_NUMBER_OF_RASTER_INPUTS).contains(&self.sources.rasters.len()),
+            error::InvalidNumberOfRasterInputs {
+                expected: 1..MAX_NUMBER_OF_RASTER_INPUTS,
+                found: self.sources.rasters.len()
+            }
+        );
+        ensure!(
+            self.sources.rasters.len() == self.params.names.len(),
+            error::InvalidOperatorSpec {
+                reason: "`rasters` must be of equal length as `names`"
+            }
+        );
+        ensure!(
+            !self.params.statistics.is_empty(),
+            error::InvalidOperatorSpec {
+                reason: "`statistics` must not be empty"
+            }
+        );
+
+        let vector_source = self.sources.vector.initialize(context).await?;
+        let vector_rd = vector_source.result_descriptor();
+
+        ensure!(
+            vector_rd.data_type == VectorDataType::MultiPolygon,
+            error::InvalidType {
+                expected: VectorDataType::MultiPolygon.to_string(),
+                found: vector_rd.data_type.to_string(),
+            }
+        );
+
+        let raster_sources = join_all(
+            self.sources
+                .rasters
+                .into_iter()
+                .map(|s| s.initialize(context)),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>>>()?;
+
+        let spatial_reference = vector_rd.spatial_reference;
+
+        for other_spatial_reference in raster_sources
+            .iter()
+            .map(|source| source.result_descriptor().spatial_reference)
+        {
+            ensure!(
+                spatial_reference == other_spatial_reference,
+                error::InvalidSpatialReference {
+                    expected: spatial_reference,
+                    found: other_spatial_reference,
+                }
+            );
+        }
+
+        let params = self.params;
+
+        let mut columns = vector_rd.columns.clone();
+        for name in &params.names {
+            for &statistic in &params.statistics {
+                let previous = columns.insert(column_name(name, statistic), FeatureDataType::Float);
+                ensure!(previous.is_none(), error::DuplicateOutputColumns);
+            }
+        }
+
+        let result_descriptor = vector_rd.map_columns(|_| columns.clone());
+
+        Ok(InitializedZonalStatistics {
+            result_descriptor,
+            vector_source,
+            raster_sources,
+            params,
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedZonalStatistics {
+    result_descriptor: VectorResultDescriptor,
+    vector_source: Box<dyn InitializedVectorOperator>,
+    raster_sources: Vec<Box<dyn InitializedRasterOperator>>,
+    params: ZonalStatisticsParams,
+}
+
+impl InitializedVectorOperator for InitializedZonalStatistics {
+    fn result_descriptor(&self) -> &VectorResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn query_processor(&self) -> Result<TypedVectorQueryProcessor> {
+        let raster_processors = self
+            .raster_sources
+            .iter()
+            .map(InitializedRasterOperator::query_processor)
+            .collect::<Result<Vec<_>>>()?;
+
+        let polygons = self
+            .vector_source
+            .query_processor()?
+            .multi_polygon()
+            .ok_or_else(|| Error::InvalidType {
+                expected: VectorDataType::MultiPolygon.to_string(),
+                found: self.vector_source.result_descriptor().data_type.to_string(),
+            })?;
+
+        Ok(TypedVectorQueryProcessor::MultiPolygon(
+            ZonalStatisticsProcessor {
+                polygons,
+                raster_processors,
+                params: self.params.clone(),
+            }
+            .boxed(),
+        ))
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.vector_source
+            .cacheability()
+            .combine(Cacheability::combine_all(
+                self.raster_sources
+                    .iter()
+                    .map(InitializedRasterOperator::cacheability),
+            ))
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.vector_source.extent()
+    }
+}
+
+pub struct ZonalStatisticsProcessor {
+    polygons: Box<dyn VectorQueryProcessor<VectorType = MultiPolygonCollection>>,
+    raster_processors: Vec<TypedRasterQueryProcessor>,
+    params: ZonalStatisticsParams,
+}
+
+impl ZonalStatisticsProcessor {
+    async fn add_statistics(
+        &self,
+        collection: MultiPolygonCollection,
+        query: VectorQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<MultiPolygonCollection> {
+        let covered_pixels = collection.create_covered_pixels();
+
+        let mut column_names = Vec::new();
+        let mut column_data = Vec::new();
+
+        for (raster_processor, name) in self.raster_processors.iter().zip(&self.params.names) {
+            let accumulators = call_on_generic_raster_processor!(raster_processor, processor => {
+                Self::accumulate(&covered_pixels, processor, query.clone(), ctx).await?
+            });
+
+            for &statistic in &self.params.statistics {
+                column_names.push(column_name(name, statistic));
+                column_data.push(FeatureData::NullableFloat(
+                    accumulators
+                        .iter()
+                        .map(|accumulator| accumulator.statistic(statistic))
+                        .collect(),
+                ));
+            }
+        }
+
+        let new_columns: Vec<(&str, FeatureData)> = column_names
+            .iter()
+            .map(String::as_str)
+            .zip(column_data)
+            .collect();
+
+        covered_pixels
+            .collection_ref()
+            .add_columns(&new_columns)
+            .map_err(Into::into)
+    }
+
+    /// Accumulates the valid pixels of the `raster_processor` for each polygon
+    async fn accumulate<P: Pixel>(
+        covered_pixels: &MultiPolygonCoveredPixels,
+        raster_processor: &dyn RasterQueryProcessor<RasterType = P>,
+        query: VectorQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<Vec<StatisticsAccumulator>> {
+        let time_intervals = covered_pixels.collection_ref().time_intervals();
+
+        let mut accumulators = vec![StatisticsAccumulator::default(); time_intervals.len()];
+
+        // query the rasters once for the time span of all polygons and assign the tiles by time
+        let time_span = match time_intervals.split_first() {
+            Some((first, rest)) => rest.iter().fold(*first, |time_span, time_interval| {
+                time_span.extend(time_interval)
+            }),
+            None => return Ok(accumulators),
+        };
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: query.spatial_bounds,
+            time_interval: time_span,
+            spatial_resolution: query.spatial_resolution,
+            spatial_filter: None,
+        };
+
+        let mut tiles = raster_processor.raster_query(query.into(), ctx).await?;
+
+        while let Some(tile) = tiles.next().await {
+            let tile = tile?;
+
+            if tile.is_empty() {
+                continue;
+            }
+
+            let no_data_value = tile.no_data_value();
+
+            for (feature_index, time_interval) in time_intervals.iter().enumerate() {
+                if !time_interval.intersects(&tile.time) {
+                    continue;
+                }
+
+                for grid_idx in covered_pixels.covered_pixels(feature_index, &tile) {
+                    let value = tile.get_at_grid_index(grid_idx)?;
+
+                    if no_data_value != Some(value) {
+                        accumulators[feature_index].add(value.as_());
+                    }
+                }
+            }
+        }
+
+        Ok(accumulators)
+    }
+}
+
+#[async_trait]
+impl QueryProcessor for ZonalStatisticsProcessor {
+    type Output = MultiPolygonCollection;
+    type SpatialBounds = BoundingBox2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: VectorQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let stream = self
+            .polygons
+            .query(query.clone(), ctx)
+            .await?
+            .and_then(move |collection| self.add_statistics(collection, query.clone(), ctx))
+            .boxed();
+
+        Ok(stream)
+    }
+}
+
+/// Computes the statistics of a stream of values in a single pass using Welford's algorithm
+#[derive(Debug, Clone, Copy)]
+struct StatisticsAccumulator {
+    count: u64,
+    mean: f64,
+    squared_deviations: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for StatisticsAccumulator {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            mean: 0.,
+            squared_deviations: 0.,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl StatisticsAccumulator {
+    fn add(&mut self, value: f64) {
+        if value.is_nan() {
+            return;
+        }
+
+        self.count += 1;
+
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.squared_deviations += delta * (value - self.mean);
+
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn statistic(&self, statistic: ZonalStatistic) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+
+        Some(match statistic {
+            ZonalStatistic::Mean => self.mean,
+            ZonalStatistic::Min => self.min,
+            ZonalStatistic::Max => self.max,
+            ZonalStatistic::StdDev => (self.squared_deviations / self.count as f64).sqrt(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{
+        ChunkByteSize, MockExecutionContext, MockQueryContext, RasterOperator,
+        RasterResultDescriptor,
+    };
+    use crate::mock::{MockFeatureCollectionSource, MockRasterSource, MockRasterSourceParams};
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{
+        Measurement, MultiPoint, MultiPolygon, SpatialResolution, TimeInterval,
+    };
+    use geoengine_datatypes::raster::{
+        Grid2D, RasterDataType, RasterTile2D, TileInformation, TilingSpecification,
+    };
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+    use serde_json::json;
+
+    fn raster_tile<P: Pixel>(data: Vec<P>, no_data_value: Option<P>) -> RasterTile2D<P> {
+        RasterTile2D::new_with_tile_info(
+            TimeInterval::new(0, 10).unwrap(),
+            TileInformation {
+                global_geo_transform: TestDefault::test_default(),
+                global_tile_position: [0, 0].into(),
+                tile_size_in_pixels: [3, 2].into(),
+            },
+            Grid2D::new([3, 2].into(), data, no_data_value)
+                .unwrap()
+                .into(),
+        )
+    }
+
+    fn raster_result_descriptor(
+        data_type: RasterDataType,
+        no_data_value: Option<f64>,
+    ) -> RasterResultDescriptor {
+        RasterResultDescriptor {
+            data_type,
+            spatial_reference: SpatialReference::epsg_4326().into(),
+            measurement: Measurement::Unitless,
+            no_data_value,
+            dimensions: vec![],
+        }
+    }
+
+    fn u8_raster_source(data: Vec<u8>, no_data_value: Option<u8>) -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams {
+                data: vec![raster_tile(data, no_data_value)],
+                result_descriptor: raster_result_descriptor(
+                    RasterDataType::U8,
+                    no_data_value.map(f64::from),
+                ),
+            },
+        }
+        .boxed()
+    }
+
+    fn square(x: f64, y: f64, width: f64, height: f64) -> MultiPolygon {
+        MultiPolygon::new(vec![vec![vec![
+            (x, y).into(),
+            (x + width, y).into(),
+            (x + width, y - height).into(),
+            (x, y - height).into(),
+            (x, y).into(),
+        ]]])
+        .unwrap()
+    }
+
+    #[tokio::test]
+    #[allow(clippy::float_cmp)]
+    async fn statistics_per_polygon() {
+        let polygons = MultiPolygonCollection::from_data(
+            vec![
+                // covers the pixels of the first two rows
+                square(-0.5, 0.5, 2., 2.),
+                // covers the pixels of the last row
+                square(-0.5, -1.5, 2., 1.),
+                // outside of the raster
+                square(4.5, 0.5, 1., 1.),
+            ],
+            vec![TimeInterval::new(0, 10).unwrap(); 3],
+            Default::default(),
+        )
+        .unwrap();
+
+        let operator = ZonalStatistics {
+            params: ZonalStatisticsParams {
+                names: vec!["a".to_string(), "b".to_string()],
+                statistics: all_statistics(),
+            },
+            sources: SingleVectorMultipleRasterSources {
+                vector: MockFeatureCollectionSource::single(polygons).boxed(),
+                rasters: vec![
+                    u8_raster_source(vec![1, 2, 3, 4, 5, 0], Some(0)),
+                    MockRasterSource {
+                        params: MockRasterSourceParams {
+                            data: vec![raster_tile(vec![0.5_f32; 6], None)],
+                            result_descriptor: raster_result_descriptor(RasterDataType::F32, None),
+                        },
+                    }
+                    .boxed(),
+                ],
+            },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), [3, 2].into()),
+        ))
+        .await
+        .unwrap();
+
+        assert_eq!(
+            operator.result_descriptor().columns.get("a_stddev"),
+            Some(&FeatureDataType::Float)
+        );
+
+        let processor = operator.query_processor().unwrap().multi_polygon().unwrap();
+
+        let result = processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((-1., -3.).into(), (6., 1.).into()).unwrap(),
+                    time_interval: TimeInterval::new(0, 10).unwrap(),
+                    spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: None,
+                },
+                &MockQueryContext::new(ChunkByteSize::MAX),
+            )
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 1);
+        let result = &result[0];
+
+        let column = |name: &str| -> Vec<Option<f64>> {
+            result.data(name).unwrap().float_options_iter().collect()
+        };
+
+        assert_eq!(column("a_mean"), vec![Some(2.5), Some(5.), None]);
+        assert_eq!(column("a_min"), vec![Some(1.), Some(5.), None]);
+        assert_eq!(column("a_max"), vec![Some(4.), Some(5.), None]);
+        assert_eq!(
+            column("a_stddev"),
+            vec![Some(1.25_f64.sqrt()), Some(0.), None]
+        );
+        assert_eq!(column("b_mean"), vec![Some(0.5), Some(0.5), None]);
+        assert_eq!(column("b_stddev"), vec![Some(0.), Some(0.), None]);
+    }
+
+    #[tokio::test]
+    async fn invalid_sources() {
+        let ctx = MockExecutionContext::test_default();
+
+        let points = ZonalStatistics {
+            params: ZonalStatisticsParams {
+                names: vec!["a".to_string()],
+                statistics: all_statistics(),
+            },
+            sources: SingleVectorMultipleRasterSources {
+                vector: MockFeatureCollectionSource::single(
+                    MultiPointCollection::from_data(
+                        MultiPoint::many(vec![(0., 0.)]).unwrap(),
+                        vec![TimeInterval::default()],
+                        Default::default(),
+                    )
+                    .unwrap(),
+                )
+                .boxed(),
+                rasters: vec![u8_raster_source(vec![0; 6], None)],
+            },
+        }
+        .boxed();
+
+        assert!(matches!(
+            points.initialize(&ctx).await,
+            Err(Error::InvalidType { .. })
+        ));
+
+        let duplicate_names = ZonalStatistics {
+            params: ZonalStatisticsParams {
+                names: vec!["a".to_string(), "a".to_string()],
+                statistics: vec![ZonalStatistic::Mean],
+            },
+            sources: SingleVectorMultipleRasterSources {
+                vector: MockFeatureCollectionSource::<MultiPolygon>::multiple(vec![]).boxed(),
+                rasters: vec![
+                    u8_raster_source(vec![0; 6], None),
+                    u8_raster_source(vec![0; 6], None),
+                ],
+            },
+        }
+        .boxed();
+
+        assert!(matches!(
+            duplicate_names.initialize(&ctx).await,
+            Err(Error::DuplicateOutputColumns)
+        ));
+    }
+
+    #[test]
+    fn serialization() {
+        let params: ZonalStatisticsParams = serde_json::from_value(json!({
+            "names": ["ndvi"],
+        }))
+        .unwrap();
+
+        assert_eq!(params.statistics, all_statistics());
+
+        let params: ZonalStatisticsParams = serde_json::from_value(json!({
+            "names": ["ndvi"],
+            "statistics": ["mean", "stdDev"],
+        }))
+        .unwrap();
+
+        assert_eq!(
+            params.statistics,
+            vec![ZonalStatistic::Mean, ZonalStatistic::StdDev]
+        );
+    }
+}
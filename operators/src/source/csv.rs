@@ -22,7 +22,7 @@ use geoengine_datatypes::{
 
 use crate::engine::QueryProcessor;
 use crate::engine::{
-    InitializedVectorOperator, OperatorDatasets, QueryContext, SourceOperator,
+    Cacheability, InitializedVectorOperator, OperatorDatasets, QueryContext, SourceOperator,
     TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
 };
 use crate::error;
@@ -161,6 +161,7 @@ impl VectorOperator for CsvSource {
                 data_type: VectorDataType::MultiPoint, // TODO: get as user input
                 spatial_reference: SpatialReference::epsg_4326().into(), // TODO: get as user input
                 columns: Default::default(), // TODO: get when source allows loading other columns
+                column_metadata: Default::default(),
             },
             state: self.params,
         };
@@ -187,6 +188,10 @@ impl InitializedVectorOperator for InitializedCsvSource {
     fn result_descriptor(&self) -> &VectorResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::Deterministic
+    }
 }
 
 impl CsvSourceStream {
@@ -559,6 +564,7 @@ x,y
             ),
             time_interval: TimeInterval::new_unchecked(0, 1),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
         let ctx = MockQueryContext::new((10 * 8 * 2).into());
 
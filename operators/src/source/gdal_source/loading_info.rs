@@ -2,12 +2,13 @@ use std::collections::HashMap;
 
 use async_trait::async_trait;
 use geoengine_datatypes::primitives::{
-    RasterQueryRectangle, TimeInstance, TimeInterval, TimeStep, TimeStepIter,
+    AxisAlignedRectangle, RasterQueryRectangle, SpatialPartitioned, TimeInstance, TimeInterval,
+    TimeStep, TimeStepIter,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    engine::{MetaData, RasterResultDescriptor},
+    engine::{MetaData, RasterResultDescriptor, ResultExtent},
     error::Error,
     util::Result,
 };
@@ -17,6 +18,7 @@ use super::{GdalDatasetParameters, GdalSourceTimePlaceholder};
 #[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GdalMetaDataStatic {
+    /// The validity of the dataset, where `None` means that it is valid always
     pub time: Option<TimeInterval>,
     pub params: GdalDatasetParameters,
     pub result_descriptor: RasterResultDescriptor,
@@ -48,6 +50,13 @@ impl MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
         Ok(self.result_descriptor.clone())
     }
 
+    async fn extent(&self) -> Result<ResultExtent> {
+        Ok(ResultExtent::new(
+            Some(self.params.spatial_partition().as_bbox()),
+            Some(self.time.unwrap_or_default()),
+        ))
+    }
+
     fn box_clone(
         &self,
     ) -> Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>> {
@@ -103,6 +112,14 @@ impl MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
         Ok(self.result_descriptor.clone())
     }
 
+    async fn extent(&self) -> Result<ResultExtent> {
+        // the time series is unbounded in both directions
+        Ok(ResultExtent::new(
+            Some(self.params.spatial_partition().as_bbox()),
+            None,
+        ))
+    }
+
     fn box_clone(
         &self,
     ) -> Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>> {
@@ -176,6 +193,13 @@ impl MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
         Ok(self.result_descriptor.clone())
     }
 
+    async fn extent(&self) -> Result<ResultExtent> {
+        Ok(ResultExtent::new(
+            Some(self.params.spatial_partition().as_bbox()),
+            Some(TimeInterval::new(self.start, self.end)?),
+        ))
+    }
+
     fn box_clone(
         &self,
     ) -> Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>> {
@@ -381,6 +405,7 @@ mod tests {
                 spatial_reference: SpatialReference::epsg_4326().into(),
                 measurement: Measurement::Unitless,
                 no_data_value,
+                dimensions: vec![],
             },
             params: GdalDatasetParameters {
                 file_path: "/foo/bar_%TIME%.tiff".into(),
@@ -393,6 +418,7 @@ mod tests {
                 properties_mapping: None,
                 gdal_open_options: None,
                 gdal_config_options: None,
+                apply_scale_offset: false,
             },
             time_placeholders: hashmap! {
                 "%TIME%".to_string() => GdalSourceTimePlaceholder {
@@ -413,7 +439,8 @@ mod tests {
                 data_type: RasterDataType::U8,
                 spatial_reference: SpatialReference::epsg_4326().into(),
                 measurement: Measurement::Unitless,
-                no_data_value: Some(0.)
+                no_data_value: Some(0.),
+                dimensions: vec![]
             }
         );
 
@@ -426,6 +453,7 @@ mod tests {
                     ),
                     time_interval: TimeInterval::new_unchecked(0, 30),
                     spatial_resolution: SpatialResolution::one(),
+                    spatial_filter: (),
                 })
                 .await
                 .unwrap()
@@ -462,6 +490,7 @@ mod tests {
                 spatial_reference: SpatialReference::epsg_4326().into(),
                 measurement: Measurement::Unitless,
                 no_data_value: None,
+                dimensions: vec![],
             },
             params: GdalDatasetParameters {
                 file_path: "path/to/ds".into(),
@@ -478,6 +507,7 @@ mod tests {
                 properties_mapping: None,
                 gdal_open_options: None,
                 gdal_config_options: None,
+                apply_scale_offset: false,
             },
             start: time_start,
             end: time_end,
@@ -489,6 +519,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 128.).into(), (128., 0.).into()),
             time_interval: TimeInterval::new(time_start, time_end).unwrap(),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
 
         let loading_info = metadata.loading_info(query).await.unwrap();
@@ -524,6 +555,7 @@ mod tests {
                 spatial_reference: SpatialReference::epsg_4326().into(),
                 measurement: Measurement::Unitless,
                 no_data_value: None,
+                dimensions: vec![],
             },
             params: GdalDatasetParameters {
                 file_path: "path/to/ds".into(),
@@ -540,6 +572,7 @@ mod tests {
                 properties_mapping: None,
                 gdal_open_options: None,
                 gdal_config_options: None,
+                apply_scale_offset: false,
             },
             start: time_start,
             end: time_end,
@@ -551,6 +584,7 @@ mod tests {
             spatial_bounds: SpatialPartition2D::new_unchecked((0., 128.).into(), (128., 0.).into()),
             time_interval: TimeInterval::new(time_start, time_end).unwrap(),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
 
         let loading_info = metadata.loading_info(query).await.unwrap();
@@ -586,6 +620,7 @@ mod tests {
                 spatial_reference: SpatialReference::epsg_4326().into(),
                 measurement: Measurement::Unitless,
                 no_data_value: None,
+                dimensions: vec![],
             },
             params: GdalDatasetParameters {
                 file_path: "path/to/ds".into(),
@@ -602,6 +637,7 @@ mod tests {
                 properties_mapping: None,
                 gdal_open_options: None,
                 gdal_config_options: None,
+                apply_scale_offset: false,
             },
             start: time_start,
             end: time_end,
@@ -616,6 +652,7 @@ mod tests {
                 TimeInstance::from(NaiveDate::from_ymd(2013, 3, 1).and_hms(0, 0, 0)),
             ),
             spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
         };
 
         let loading_info = metadata.loading_info(query).await.unwrap();
@@ -689,6 +726,7 @@ mod tests {
                 properties_mapping: None,
                 gdal_open_options: None,
                 gdal_config_options: None,
+                apply_scale_offset: false,
             },
             step: time_step,
             dataset_time_start: time_start,
@@ -769,6 +807,7 @@ mod tests {
                     properties_mapping: None,
                     gdal_open_options: None,
                     gdal_config_options: None,
+                    apply_scale_offset: false,
                 },
                 step: time_step,
                 dataset_time_start: TimeInstance::from(
@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{AxisAlignedRectangle, RasterQueryRectangle};
+use lazy_static::lazy_static;
+use log::debug;
+use serde::Serialize;
+
+use crate::engine::{Cacheability, MetaData, RasterResultDescriptor, ResultExtent};
+use crate::util::Result;
+
+use super::{GdalLoadingInfo, GdalLoadingInfoTemporalSlice, GdalLoadingInfoTemporalSliceIterator};
+
+/// Loading infos with more temporal slices are not cached, e.g., for unbounded queries
+const MAX_CACHED_SLICES: usize = 1024;
+
+lazy_static! {
+    static ref GLOBAL_CACHE: LoadingInfoCache =
+        LoadingInfoCache::new(Duration::from_secs(60), 4096);
+}
+
+/// Memoizes the temporal slices of GDAL loading infos per dataset and query.
+///
+/// Regular time series re-compute the time snapping and the file paths for each query,
+/// e.g., for every repeated WMS tile of an animation. Subsequent identical queries reuse them
+/// until they expire.
+///
+/// The key contains the spatial bounds and resolution of the query, because loading infos of some
+/// meta data, e.g., of Sentinel-2 scenes, depend on the queried area.
+pub struct LoadingInfoCache {
+    state: Mutex<CacheState>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+struct CacheState {
+    ttl: Duration,
+    capacity: usize,
+    entries: HashMap<CacheKey, CacheEntry>,
+}
+
+/// The dataset and the query, with floats as bits to make them hashable
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    dataset: DatasetId,
+    time: (i64, i64),
+    spatial_bounds: [u64; 4],
+    spatial_resolution: [u64; 2],
+}
+
+fn cache_key(dataset: DatasetId, query: &RasterQueryRectangle) -> CacheKey {
+    let upper_left = query.spatial_bounds.upper_left();
+    let lower_right = query.spatial_bounds.lower_right();
+
+    CacheKey {
+        dataset,
+        time: (
+            query.time_interval.start().inner(),
+            query.time_interval.end().inner(),
+        ),
+        spatial_bounds: [
+            upper_left.x.to_bits(),
+            upper_left.y.to_bits(),
+            lower_right.x.to_bits(),
+            lower_right.y.to_bits(),
+        ],
+        spatial_resolution: [
+            query.spatial_resolution.x.to_bits(),
+            query.spatial_resolution.y.to_bits(),
+        ],
+    }
+}
+
+struct CacheEntry {
+    slices: Vec<GdalLoadingInfoTemporalSlice>,
+    created: Instant,
+}
+
+/// Usage statistics of a [`LoadingInfoCache`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadingInfoCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+impl LoadingInfoCache {
+    /// Creates a cache whose entries expire after `ttl` and that holds at most `capacity` entries.
+    /// A `capacity` of zero disables the cache.
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(CacheState {
+                ttl,
+                capacity,
+                entries: HashMap::new(),
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The cache that is shared by all GDAL sources of this process
+    pub fn global() -> &'static Self {
+        &GLOBAL_CACHE
+    }
+
+    /// Changes the expiration time and capacity and drops all entries
+    pub fn configure(&self, ttl: Duration, capacity: usize) {
+        let mut state = self.state.lock().expect("cache lock must not be poisoned");
+        state.ttl = ttl;
+        state.capacity = capacity;
+        state.entries.clear();
+    }
+
+    /// Removes all entries of a dataset, e.g., because its meta data has changed
+    pub fn invalidate_dataset(&self, dataset: &DatasetId) {
+        self.state
+            .lock()
+            .expect("cache lock must not be poisoned")
+            .entries
+            .retain(|key, _| &key.dataset != dataset);
+    }
+
+    pub fn stats(&self) -> LoadingInfoCacheStats {
+        LoadingInfoCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self
+                .state
+                .lock()
+                .expect("cache lock must not be poisoned")
+                .entries
+                .len(),
+        }
+    }
+
+    fn get(
+        &self,
+        dataset: &DatasetId,
+        query: &RasterQueryRectangle,
+    ) -> Option<Vec<GdalLoadingInfoTemporalSlice>> {
+        let mut state = self.state.lock().expect("cache lock must not be poisoned");
+        let key = cache_key(dataset.clone(), query);
+
+        let slices = match state.entries.get(&key) {
+            Some(entry) if entry.created.elapsed() <= state.ttl => Some(entry.slices.clone()),
+            Some(_) => {
+                state.entries.remove(&key);
+                None
+            }
+            None => None,
+        };
+
+        let counter = if slices.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        slices
+    }
+
+    fn insert(
+        &self,
+        dataset: DatasetId,
+        query: &RasterQueryRectangle,
+        slices: Vec<GdalLoadingInfoTemporalSlice>,
+    ) {
+        let mut state = self.state.lock().expect("cache lock must not be poisoned");
+
+        if state.capacity == 0 {
+            return;
+        }
+
+        if state.entries.len() >= state.capacity {
+            let ttl = state.ttl;
+            state
+                .entries
+                .retain(|_, entry| entry.created.elapsed() <= ttl);
+        }
+
+        if state.entries.len() >= state.capacity {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.created)
+                .map(|(key, _)| key.clone());
+
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+
+        state.entries.insert(
+            cache_key(dataset, query),
+            CacheEntry {
+                slices,
+                created: Instant::now(),
+            },
+        );
+    }
+}
+
+impl std::fmt::Debug for LoadingInfoCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoadingInfoCache")
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+/// Serves the loading infos of the wrapped meta data from a [`LoadingInfoCache`]
+#[derive(Debug, Clone)]
+pub struct CachedGdalMetaData {
+    dataset: DatasetId,
+    meta_data: Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
+    cache: &'static LoadingInfoCache,
+}
+
+impl CachedGdalMetaData {
+    pub fn new(
+        dataset: DatasetId,
+        meta_data: Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
+        cache: &'static LoadingInfoCache,
+    ) -> Self {
+        Self {
+            dataset,
+            meta_data,
+            cache,
+        }
+    }
+}
+
+#[async_trait]
+impl MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
+    for CachedGdalMetaData
+{
+    async fn loading_info(&self, query: RasterQueryRectangle) -> Result<GdalLoadingInfo> {
+        if let Some(parts) = self.cache.get(&self.dataset, &query) {
+            return Ok(GdalLoadingInfo {
+                info: GdalLoadingInfoTemporalSliceIterator::Static {
+                    parts: parts.into_iter(),
+                },
+            });
+        }
+
+        let loading_info = self.meta_data.loading_info(query).await?;
+
+        // errors are not cached but surface when the original iterator is consumed
+        let slices = loading_info
+            .info
+            .clone()
+            .take(MAX_CACHED_SLICES + 1)
+            .collect::<Result<Vec<_>>>();
+
+        if let Some(slices) = slices
+            .ok()
+            .filter(|slices| slices.len() <= MAX_CACHED_SLICES)
+        {
+            debug!(
+                "Caching {} loading info slices of {:?}",
+                slices.len(),
+                self.dataset
+            );
+
+            self.cache.insert(self.dataset.clone(), &query, slices);
+        }
+
+        Ok(loading_info)
+    }
+
+    async fn result_descriptor(&self) -> Result<RasterResultDescriptor> {
+        self.meta_data.result_descriptor().await
+    }
+
+    async fn extent(&self) -> Result<ResultExtent> {
+        self.meta_data.extent().await
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        self.meta_data.cacheability()
+    }
+
+    fn box_clone(
+        &self,
+    ) -> Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{
+        FileNotFoundHandling, GdalDatasetGeoTransform, GdalDatasetParameters, GdalMetaDataRegular,
+        GdalSourceTimePlaceholder, TimeReference,
+    };
+    use geoengine_datatypes::dataset::InternalDatasetId;
+    use geoengine_datatypes::primitives::{
+        Measurement, SpatialPartition2D, SpatialResolution, TimeGranularity, TimeInstance,
+        TimeInterval, TimeStep,
+    };
+    use geoengine_datatypes::raster::RasterDataType;
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::Identifier;
+
+    fn meta_data(
+    ) -> Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>> {
+        Box::new(GdalMetaDataRegular {
+            result_descriptor: RasterResultDescriptor {
+                data_type: RasterDataType::U8,
+                spatial_reference: SpatialReference::epsg_4326().into(),
+                measurement: Measurement::Unitless,
+                no_data_value: Some(0.),
+                dimensions: vec![],
+            },
+            params: GdalDatasetParameters {
+                file_path: "/foo/bar_%TIME%.tiff".into(),
+                rasterband_channel: 0,
+                geo_transform: GdalDatasetGeoTransform {
+                    origin_coordinate: (0., 0.).into(),
+                    x_pixel_size: 1.,
+                    y_pixel_size: -1.,
+                },
+                width: 360,
+                height: 180,
+                file_not_found_handling: FileNotFoundHandling::NoData,
+                no_data_value: Some(0.),
+                properties_mapping: None,
+                gdal_open_options: None,
+                gdal_config_options: None,
+                apply_scale_offset: false,
+            },
+            time_placeholders: [(
+                "%TIME%".to_string(),
+                GdalSourceTimePlaceholder {
+                    format: "%f".to_string(),
+                    reference: TimeReference::Start,
+                },
+            )]
+            .into_iter()
+            .collect(),
+            start: TimeInstance::from_millis_unchecked(0),
+            step: TimeStep {
+                granularity: TimeGranularity::Millis,
+                step: 11,
+            },
+        })
+    }
+
+    fn query(time_interval: TimeInterval) -> RasterQueryRectangle {
+        RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((0., 1.).into(), (1., 0.).into()),
+            time_interval,
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
+        }
+    }
+
+    async fn slices(
+        meta_data: &CachedGdalMetaData,
+        time_interval: TimeInterval,
+    ) -> Vec<GdalLoadingInfoTemporalSlice> {
+        meta_data
+            .loading_info(query(time_interval))
+            .await
+            .unwrap()
+            .info
+            .collect::<Result<_>>()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_caches_loading_infos() {
+        lazy_static! {
+            static ref CACHE: LoadingInfoCache = LoadingInfoCache::new(Duration::from_secs(60), 2);
+        }
+
+        let dataset: DatasetId = InternalDatasetId::new().into();
+        let meta_data = CachedGdalMetaData::new(dataset.clone(), meta_data(), &CACHE);
+
+        let time = TimeInterval::new_unchecked(0, 30);
+        let uncached = slices(&meta_data, time).await;
+        assert_eq!(uncached.len(), 3);
+
+        assert_eq!(slices(&meta_data, time).await, uncached);
+        assert_eq!(
+            CACHE.stats(),
+            LoadingInfoCacheStats {
+                hits: 1,
+                misses: 1,
+                entries: 1,
+            }
+        );
+
+        // the oldest entry is evicted
+        slices(&meta_data, TimeInterval::new_unchecked(0, 10)).await;
+        slices(&meta_data, TimeInterval::new_unchecked(0, 20)).await;
+        assert_eq!(CACHE.stats().entries, 2);
+        slices(&meta_data, time).await;
+        assert_eq!(CACHE.stats().misses, 4);
+
+        CACHE.invalidate_dataset(&dataset);
+        assert_eq!(CACHE.stats().entries, 0);
+    }
+
+    #[tokio::test]
+    async fn it_distinguishes_spatial_queries() {
+        lazy_static! {
+            static ref CACHE: LoadingInfoCache = LoadingInfoCache::new(Duration::from_secs(60), 16);
+        }
+
+        let meta_data =
+            CachedGdalMetaData::new(InternalDatasetId::new().into(), meta_data(), &CACHE);
+        let time = TimeInterval::new_unchecked(0, 30);
+
+        slices(&meta_data, time).await;
+
+        let other_area = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked((1., 1.).into(), (2., 0.).into()),
+            ..query(time)
+        };
+        meta_data.loading_info(other_area).await.unwrap();
+
+        let other_resolution = RasterQueryRectangle {
+            spatial_resolution: SpatialResolution::zero_point_one(),
+            ..query(time)
+        };
+        meta_data.loading_info(other_resolution).await.unwrap();
+
+        assert_eq!(
+            CACHE.stats(),
+            LoadingInfoCacheStats {
+                hits: 0,
+                misses: 3,
+                entries: 3,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_expires_entries() {
+        lazy_static! {
+            static ref CACHE: LoadingInfoCache = LoadingInfoCache::new(Duration::ZERO, 16);
+        }
+
+        let meta_data =
+            CachedGdalMetaData::new(InternalDatasetId::new().into(), meta_data(), &CACHE);
+        let time = TimeInterval::new_unchecked(0, 30);
+
+        slices(&meta_data, time).await;
+        std::thread::sleep(Duration::from_millis(1));
+        slices(&meta_data, time).await;
+
+        assert_eq!(CACHE.stats().hits, 0);
+    }
+}
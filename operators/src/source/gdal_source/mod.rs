@@ -1,6 +1,11 @@
 use crate::adapters::SparseTilesFillAdapter;
-use crate::engine::{MetaData, OperatorDatasets, QueryProcessor};
-use crate::util::gdal::gdal_open_dataset_ex;
+use crate::engine::{
+    Cacheability, MetaData, OperatorDatasets, QueryContext, QueryProcessor, RasterDimension,
+    RasterErrorPolicy, ResultExtent,
+};
+use crate::util::gdal::{
+    gdal_open_dataset_ex, path_specific_config_options, TemporaryGdalThreadLocalConfigOptions,
+};
 use crate::util::input::float_option_with_nan;
 use crate::{
     engine::{
@@ -17,9 +22,10 @@ use futures::{
 };
 use futures::{Future, TryStreamExt};
 use gdal::raster::{GdalType, RasterBand as GdalRasterBand};
-use gdal::{DatasetOptions, GdalOpenFlags, Metadata as GdalMetadata};
+use gdal::{Dataset, DatasetOptions, GdalOpenFlags, Metadata as GdalMetadata};
 use geoengine_datatypes::primitives::{
-    Coordinate2D, RasterQueryRectangle, SpatialPartition2D, SpatialPartitioned,
+    AxisAlignedRectangle, Coordinate2D, RasterQueryRectangle, SpatialPartition2D,
+    SpatialPartitioned,
 };
 use geoengine_datatypes::raster::{
     EmptyGrid, GeoTransform, Grid2D, GridShape2D, GridShapeAccess, Pixel, RasterDataType,
@@ -35,7 +41,7 @@ use geoengine_datatypes::{
         TilingSpecification,
     },
 };
-use log::{debug, info};
+use log::debug;
 use serde::{Deserialize, Serialize};
 use snafu::{ensure, ResultExt};
 use std::collections::HashMap;
@@ -47,8 +53,12 @@ pub use loading_info::{
     GdalLoadingInfo, GdalLoadingInfoTemporalSlice, GdalLoadingInfoTemporalSliceIterator,
     GdalMetaDataRegular, GdalMetaDataStatic, GdalMetadataNetCdfCf,
 };
+pub use loading_info_cache::{CachedGdalMetaData, LoadingInfoCache, LoadingInfoCacheStats};
+pub use remote_reads::{RemoteReadPolicy, RemoteReadSettings, RemoteReadStats};
 
 mod loading_info;
+mod loading_info_cache;
+mod remote_reads;
 
 /// Parameters for the GDAL Source Operator
 ///
@@ -127,6 +137,10 @@ pub struct GdalDatasetParameters {
     // `vec!["AWS_REGION".to_owned(), "eu-central-1".to_owned()]` and unset afterwards
     // TODO: validate the config options: only allow specific keys and specific values
     pub gdal_config_options: Option<Vec<(String, String)>>,
+    /// Convert the raw values of the band to physical values using the band's scale and offset,
+    /// i.e., `value * scale + offset`. The result descriptor should have a floating point data type.
+    #[serde(default)]
+    pub apply_scale_offset: bool,
 }
 
 /// A user friendly representation of Gdal's geo transform. In contrast to [`GeoTransform`] this
@@ -179,48 +193,6 @@ impl From<gdal::GeoTransform> for GdalDatasetGeoTransform {
     }
 }
 
-/// Set thread local gdal options and revert them on drop
-struct TemporaryGdalThreadLocalConfigOptions {
-    original_configs: Vec<(String, Option<String>)>,
-}
-
-impl TemporaryGdalThreadLocalConfigOptions {
-    /// Set thread local gdal options and revert them on drop
-    fn new(configs: &[(String, String)]) -> Result<Self> {
-        let mut original_configs = vec![];
-
-        for (key, value) in configs {
-            let old = gdal::config::get_thread_local_config_option(key, "").map(|value| {
-                if value.is_empty() {
-                    None
-                } else {
-                    Some(value)
-                }
-            })?;
-
-            // TODO: check if overriding existing config (local & global) is ok for the given key
-            gdal::config::set_thread_local_config_option(key, value)?;
-            info!("set {}={}", key, value);
-
-            original_configs.push((key.clone(), old));
-        }
-
-        Ok(Self { original_configs })
-    }
-}
-
-impl Drop for TemporaryGdalThreadLocalConfigOptions {
-    fn drop(&mut self) {
-        for (key, value) in &self.original_configs {
-            if let Some(value) = value {
-                let _result = gdal::config::set_thread_local_config_option(key, value);
-            } else {
-                let _result = gdal::config::clear_thread_local_config_option(key);
-            }
-        }
-    }
-}
-
 impl SpatialPartitioned for GdalDatasetParameters {
     fn spatial_partition(&self) -> SpatialPartition2D {
         let lower_right_coordinate = self.geo_transform.origin_coordinate
@@ -284,6 +256,30 @@ impl GdalDatasetParameters {
             ..*self
         })
     }
+
+    /// Opens the raster dataset with the open options and the thread local config options of the parameters.
+    /// The config options are reverted as soon as the dataset is opened.
+    pub fn open_raster_dataset(&self) -> Result<Dataset> {
+        let options = self
+            .gdal_open_options
+            .as_ref()
+            .map(|o| o.iter().map(String::as_str).collect::<Vec<_>>());
+
+        // reverts the thread local configs on drop
+        let _thread_local_configs = self
+            .gdal_config_options
+            .as_ref()
+            .map(|config_options| TemporaryGdalThreadLocalConfigOptions::new(config_options));
+
+        gdal_open_dataset_ex(
+            &self.file_path,
+            DatasetOptions {
+                open_flags: GdalOpenFlags::GDAL_OF_RASTER,
+                open_options: options.as_deref(),
+                ..DatasetOptions::default()
+            },
+        )
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
@@ -314,11 +310,14 @@ impl GdalRasterLoader {
         tile_information: TileInformation,
         tile_time: TimeInterval,
     ) -> Result<RasterTile2D<T>> {
-        crate::util::spawn_blocking(move || {
-            Self::load_tile_data(&dataset_params, tile_information, tile_time)
-        })
-        .await
-        .context(error::TokioJoin)?
+        let file_path = dataset_params.file_path.clone();
+
+        // reads of remote files are retried and timed out
+        RemoteReadPolicy::global()
+            .read(&file_path, move || {
+                Self::load_tile_data(&dataset_params, tile_information, tile_time)
+            })
+            .await
     }
 
     async fn load_tile_async<T: Pixel + GdalType>(
@@ -391,6 +390,11 @@ impl GdalRasterLoader {
             .as_ref()
             .map(|config_options| TemporaryGdalThreadLocalConfigOptions::new(config_options));
 
+        // the path specific configs must also be set while reading, e.g., for the sources of VRTs
+        let _path_specific_configs = TemporaryGdalThreadLocalConfigOptions::new(
+            &path_specific_config_options(&dataset_params.file_path),
+        )?;
+
         let dataset_result = gdal_open_dataset_ex(
             &dataset_params.file_path,
             DatasetOptions {
@@ -441,43 +445,96 @@ impl GdalRasterLoader {
         Ok(result_tile)
     }
 
+    ///
+    /// A method to check whether the GDAL dataset of a temporal slice can be opened.
+    ///
+    async fn can_open_dataset_async(dataset_params: GdalDatasetParameters) -> Result<bool> {
+        crate::util::spawn_blocking(move || dataset_params.open_raster_dataset().is_ok())
+            .await
+            .context(error::TokioJoin)
+    }
+
     ///
     /// A stream of futures producing `RasterTile2D` for a single slice in time
     ///
-    fn temporal_slice_tile_future_stream<T: Pixel + GdalType>(
+    fn temporal_slice_tile_future_stream<'a, T: Pixel + GdalType>(
         query: RasterQueryRectangle,
         info: GdalLoadingInfoTemporalSlice,
         no_data_value: Option<T>,
         tiling_strategy: TilingStrategy,
-    ) -> impl Stream<Item = impl Future<Output = Result<RasterTile2D<T>>>> {
+        ctx: &'a dyn QueryContext,
+    ) -> impl Stream<Item = impl Future<Output = Result<RasterTile2D<T>>> + 'a> + 'a {
+        let error_policy = ctx.raster_error_policy();
+
         stream::iter(tiling_strategy.tile_information_iterator(query.spatial_bounds)).map(
             move |tile| {
-                GdalRasterLoader::load_tile_async(
-                    info.params.clone(),
-                    tile,
-                    info.time,
-                    no_data_value,
-                )
+                let params = info.params.clone();
+                let time = info.time;
+
+                async move {
+                    match GdalRasterLoader::load_tile_async(params, tile, time, no_data_value).await
+                    {
+                        Err(error) if error_policy != RasterErrorPolicy::Fail => {
+                            ctx.add_warning(format!(
+                                "Filled tiles of {} with no data: {}",
+                                time, error
+                            ));
+
+                            Ok(create_no_data_tile(tile, time, no_data_value))
+                        }
+                        result => result,
+                    }
+                }
             },
         )
     }
 
     fn loading_info_to_tile_stream<
+        'a,
         T: Pixel + GdalType,
-        S: Stream<Item = Result<GdalLoadingInfoTemporalSlice>>,
+        S: Stream<Item = Result<GdalLoadingInfoTemporalSlice>> + 'a,
     >(
         loading_info_stream: S,
         query: RasterQueryRectangle,
         no_data_value: Option<T>,
         tiling_strategy: TilingStrategy,
-    ) -> impl Stream<Item = Result<RasterTile2D<T>>> {
+        ctx: &'a dyn QueryContext,
+    ) -> impl Stream<Item = Result<RasterTile2D<T>>> + 'a {
+        let error_policy = ctx.raster_error_policy();
+
         loading_info_stream
+            .try_filter_map(move |info| async move {
+                // only files that must exist are checked, missing files are handled by `FileNotFoundHandling`
+                let params = match &info.params {
+                    Some(params)
+                        if error_policy == RasterErrorPolicy::SkipSlice
+                            && params.file_not_found_handling == FileNotFoundHandling::Error =>
+                    {
+                        params.clone()
+                    }
+                    _ => return Ok(Some(info)),
+                };
+
+                let file_path = params.file_path.to_string_lossy().to_string();
+
+                if GdalRasterLoader::can_open_dataset_async(params).await? {
+                    Ok(Some(info))
+                } else {
+                    ctx.add_warning(format!(
+                        "Skipped time slice {} because the file {} could not be opened",
+                        info.time, file_path
+                    ));
+
+                    Ok(None)
+                }
+            })
             .map_ok(move |info| {
                 GdalRasterLoader::temporal_slice_tile_future_stream(
                     query,
                     info,
                     no_data_value,
                     tiling_strategy,
+                    ctx,
                 )
                 .map(Result::Ok)
             })
@@ -499,7 +556,7 @@ where
     async fn query<'a>(
         &'a self,
         query: RasterQueryRectangle,
-        _ctx: &'a dyn crate::engine::QueryContext,
+        ctx: &'a dyn crate::engine::QueryContext,
     ) -> Result<BoxStream<Result<Self::Output>>> {
         let start = Instant::now();
         debug!(
@@ -531,14 +588,24 @@ where
             .tiling_specification
             .strategy(pixel_size_x, pixel_size_y);
 
+        // additional dimensions are stored in the bands after the band of their first values
+        let dimensions = self.meta_data.result_descriptor().await?.dimensions;
+        let band_offset = RasterDimension::band_offset(&dimensions, ctx.dimension_selection())?;
+
         // TODO: what to do if loading info is empty?
-        let source_stream = stream::iter(meta_data.info);
+        let source_stream = stream::iter(meta_data.info).map_ok(move |mut info| {
+            if let Some(params) = info.params.as_mut() {
+                params.rasterband_channel += band_offset;
+            }
+            info
+        });
 
         let source_stream = GdalRasterLoader::loading_info_to_tile_stream(
             source_stream,
             query,
             self.no_data_value,
             tiling_strategy,
+            ctx,
         );
 
         // use SparseTilesFillAdapter to fill all the gaps
@@ -552,6 +619,15 @@ where
 
         Ok(filled_stream.boxed())
     }
+
+    async fn query_is_empty<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        _ctx: &'a dyn crate::engine::QueryContext,
+    ) -> Result<bool> {
+        let extent = self.meta_data.extent().await?;
+        Ok(extent.excludes(&query.spatial_bounds.as_bbox(), &query.time_interval))
+    }
 }
 
 pub type GdalSource = SourceOperator<GdalSourceParameters>;
@@ -564,11 +640,17 @@ impl RasterOperator for GdalSource {
         context: &dyn crate::engine::ExecutionContext,
     ) -> Result<Box<dyn InitializedRasterOperator>> {
         let meta_data: GdalMetaData = context.meta_data(&self.params.dataset).await?;
+        let meta_data: GdalMetaData = Box::new(CachedGdalMetaData::new(
+            self.params.dataset.clone(),
+            meta_data,
+            LoadingInfoCache::global(),
+        ));
 
         debug!("Initializing GdalSource for {:?}.", &self.params.dataset);
 
         Ok(InitializedGdalSourceOperator {
             result_descriptor: meta_data.result_descriptor().await?,
+            extent: meta_data.extent().await?,
             meta_data,
             tiling_specification: context.tiling_specification(),
         }
@@ -579,6 +661,7 @@ impl RasterOperator for GdalSource {
 pub struct InitializedGdalSourceOperator {
     pub meta_data: GdalMetaData,
     pub result_descriptor: RasterResultDescriptor,
+    pub extent: ResultExtent,
     pub tiling_specification: TilingSpecification,
 }
 
@@ -587,6 +670,14 @@ impl InitializedRasterOperator for InitializedGdalSourceOperator {
         &self.result_descriptor
     }
 
+    fn cacheability(&self) -> Cacheability {
+        self.meta_data.cacheability()
+    }
+
+    fn extent(&self) -> ResultExtent {
+        self.extent
+    }
+
     fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
         Ok(match self.result_descriptor().data_type {
             RasterDataType::U8 => TypedRasterQueryProcessor::U8(
@@ -761,7 +852,7 @@ where
 
 /// This method reads the data for a single tile with a specified size from the GDAL dataset and adds the requested metadata as properties to the tile.
 fn read_raster_tile_with_properties<T: Pixel + gdal::raster::GdalType>(
-    dataset: &gdal::Dataset,
+    dataset: &Dataset,
     dataset_params: &GdalDatasetParameters,
     tile_info: TileInformation,
     tile_time: TimeInterval,
@@ -773,14 +864,14 @@ fn read_raster_tile_with_properties<T: Pixel + gdal::raster::GdalType>(
     if let Some(properties_mapping) = dataset_params.properties_mapping.as_ref() {
         properties_from_gdal(&mut properties, dataset, properties_mapping);
         properties_from_gdal(&mut properties, &rasterband, properties_mapping);
-        properties_from_band(&mut properties, &rasterband);
     }
+    properties_from_band(&mut properties, &rasterband);
 
     let no_data_value = dataset_params.no_data_value.map(T::from_);
     let dataset_geo_transform = dataset_params.geo_transform.try_into()?;
     let dataset_bounds = dataset_params.spatial_partition();
 
-    let result_grid = read_grid_and_handle_edges(
+    let mut result_grid = read_grid_and_handle_edges(
         tile_info,
         &rasterband,
         dataset_bounds,
@@ -788,6 +879,16 @@ fn read_raster_tile_with_properties<T: Pixel + gdal::raster::GdalType>(
         no_data_value,
     )?;
 
+    if dataset_params.apply_scale_offset {
+        if let Some(grid) = result_grid.as_mut() {
+            apply_scale_offset(grid, &properties);
+        }
+
+        // the values are physical values now, so the scale and offset must not be applied again
+        properties.scale = None;
+        properties.offset = None;
+    }
+
     Ok(result_grid.map(|grid| {
         RasterTile2D::new_with_tile_info_and_properties(
             tile_time,
@@ -798,6 +899,28 @@ fn read_raster_tile_with_properties<T: Pixel + gdal::raster::GdalType>(
     }))
 }
 
+/// Converts the raw values of the `grid` to physical values, i.e., `value * scale + offset`.
+/// No data values are not changed.
+fn apply_scale_offset<T: Pixel>(grid: &mut Grid2D<T>, properties: &RasterProperties) {
+    if properties.scale.is_none() && properties.offset.is_none() {
+        return;
+    }
+
+    let scale = properties.scale.unwrap_or(1.);
+    let offset = properties.offset.unwrap_or(0.);
+
+    let no_data_value = grid.no_data_value;
+
+    for value in &mut grid.data {
+        if no_data_value == Some(*value) {
+            continue;
+        }
+
+        let physical_value: f64 = value.as_();
+        *value = T::from_(physical_value * scale + offset);
+    }
+}
+
 fn create_no_data_tile<T: Pixel>(
     tile_info: TileInformation,
     tile_time: TimeInterval,
@@ -881,10 +1004,14 @@ fn properties_from_gdal<'a, I, M>(
 fn properties_from_band(properties: &mut RasterProperties, gdal_dataset: &GdalRasterBand) {
     if let Some(scale) = gdal_dataset.metadata_item("scale", "") {
         properties.scale = scale.parse::<f64>().ok();
+    } else {
+        properties.scale = gdal_dataset.scale();
     };
 
     if let Some(offset) = gdal_dataset.metadata_item("offset", "") {
         properties.offset = offset.parse::<f64>().ok();
+    } else {
+        properties.offset = gdal_dataset.offset();
     };
 
     if let Some(band_name) = gdal_dataset.metadata_item("band_name", "") {
@@ -895,7 +1022,7 @@ fn properties_from_band(properties: &mut RasterProperties, gdal_dataset: &GdalRa
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use crate::engine::{MockExecutionContext, MockQueryContext, RasterErrorPolicyQueryContext};
     use crate::test_data;
     use crate::util::gdal::add_ndvi_dataset;
     use crate::util::Result;
@@ -937,6 +1064,7 @@ mod tests {
                     spatial_bounds: output_bounds,
                     time_interval,
                     spatial_resolution,
+                    spatial_filter: (),
                 },
                 query_ctx,
             )
@@ -989,6 +1117,7 @@ mod tests {
                 ]),
                 gdal_open_options: None,
                 gdal_config_options: None,
+                apply_scale_offset: false,
             },
             TileInformation::with_partition_and_shape(output_bounds, output_shape),
             TimeInterval::default(),
@@ -1163,6 +1292,7 @@ mod tests {
             properties_mapping: None,
             gdal_open_options: None,
             gdal_config_options: None,
+            apply_scale_offset: false,
         };
         let replaced = params
             .replace_time_placeholders(
@@ -1443,6 +1573,125 @@ mod tests {
         assert_eq!(tile.unwrap(), expected);
     }
 
+    #[tokio::test]
+    async fn it_applies_raster_error_policies() {
+        let existing_file = GdalDatasetParameters {
+            file_path: test_data!("raster/modis_ndvi/MOD13A2_M_NDVI_2014-01-01.TIFF").into(),
+            rasterband_channel: 1,
+            geo_transform: GdalDatasetGeoTransform {
+                origin_coordinate: (-180., 90.).into(),
+                x_pixel_size: 0.1,
+                y_pixel_size: -0.1,
+            },
+            width: 3600,
+            height: 1800,
+            file_not_found_handling: FileNotFoundHandling::Error,
+            no_data_value: Some(0.),
+            properties_mapping: None,
+            gdal_open_options: None,
+            gdal_config_options: None,
+            apply_scale_offset: false,
+        };
+        let missing_file = GdalDatasetParameters {
+            file_path: test_data!("raster/modis_ndvi/MOD13A2_M_NDVI_2014-02-01_missing.TIFF")
+                .into(),
+            ..existing_file.clone()
+        };
+
+        let slices = || {
+            stream::iter(vec![
+                Ok(GdalLoadingInfoTemporalSlice {
+                    time: TimeInterval::new_unchecked(1_388_534_400_000, 1_391_212_800_000),
+                    params: Some(existing_file.clone()),
+                }),
+                Ok(GdalLoadingInfoTemporalSlice {
+                    time: TimeInterval::new_unchecked(1_391_212_800_000, 1_393_632_000_000),
+                    params: Some(missing_file.clone()),
+                }),
+            ])
+        };
+
+        let query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked(
+                (-180., 90.).into(),
+                (180., -90.).into(),
+            ),
+            time_interval: TimeInterval::new_unchecked(1_388_534_400_000, 1_393_632_000_000),
+            spatial_resolution: SpatialResolution::new_unchecked(1., 1.),
+            spatial_filter: (),
+        };
+
+        // four tiles per time slice
+        let tiling_strategy =
+            TilingSpecification::new((0., 0.).into(), [600, 600].into()).strategy(1., -1.);
+
+        let query_ctx = RasterErrorPolicyQueryContext::new(
+            MockQueryContext::test_default(),
+            RasterErrorPolicy::Fail,
+        );
+        let tiles: Vec<Result<RasterTile2D<u8>>> = GdalRasterLoader::loading_info_to_tile_stream(
+            slices(),
+            query,
+            Some(0),
+            tiling_strategy,
+            &query_ctx,
+        )
+        .collect()
+        .await;
+
+        assert!(tiles.iter().any(Result::is_err));
+        assert!(query_ctx.warnings().to_vec().is_empty());
+
+        let query_ctx = RasterErrorPolicyQueryContext::new(
+            MockQueryContext::test_default(),
+            RasterErrorPolicy::FillNoData,
+        );
+        let tiles: Vec<Result<RasterTile2D<u8>>> = GdalRasterLoader::loading_info_to_tile_stream(
+            slices(),
+            query,
+            Some(0),
+            tiling_strategy,
+            &query_ctx,
+        )
+        .collect()
+        .await;
+        let tiles = tiles.into_iter().collect::<Result<Vec<_>>>().unwrap();
+
+        assert_eq!(tiles.len(), 8);
+        assert!(tiles[..4].iter().all(|tile| !tile.is_empty()));
+        assert!(tiles[4..].iter().all(RasterTile2D::is_empty));
+        assert_eq!(query_ctx.warnings().to_vec().len(), 1);
+
+        let query_ctx = RasterErrorPolicyQueryContext::new(
+            MockQueryContext::test_default(),
+            RasterErrorPolicy::SkipSlice,
+        );
+        let tiles: Vec<Result<RasterTile2D<u8>>> = GdalRasterLoader::loading_info_to_tile_stream(
+            slices(),
+            query,
+            Some(0),
+            tiling_strategy,
+            &query_ctx,
+        )
+        .collect()
+        .await;
+        let tiles = tiles.into_iter().collect::<Result<Vec<_>>>().unwrap();
+
+        assert_eq!(tiles.len(), 4);
+        assert!(tiles
+            .iter()
+            .all(|tile| tile.time
+                == TimeInterval::new_unchecked(1_388_534_400_000, 1_391_212_800_000)));
+        assert_eq!(
+            query_ctx.warnings().to_vec(),
+            vec![format!(
+                "Skipped time slice {} because the file {} could not be opened",
+                TimeInterval::new_unchecked(1_391_212_800_000, 1_393_632_000_000),
+                missing_file.file_path.to_string_lossy()
+            )]
+        );
+    }
+
     #[test]
     fn it_reverts_config_options() {
         let config_options = vec![("foo".to_owned(), "bar".to_owned())];
@@ -1463,6 +1712,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_applies_scale_and_offset() {
+        let mut grid = Grid2D::new([2, 2].into(), vec![0_f32, 1., 2., 255.], Some(255.)).unwrap();
+
+        apply_scale_offset(
+            &mut grid,
+            &RasterProperties {
+                scale: Some(0.5),
+                offset: Some(-1.),
+                ..RasterProperties::default()
+            },
+        );
+
+        assert_eq!(grid.data, vec![-1., -0.5, 0., 255.]);
+
+        // without scale and offset the values are already physical values
+        apply_scale_offset(&mut grid, &RasterProperties::default());
+
+        assert_eq!(grid.data, vec![-1., -0.5, 0., 255.]);
+    }
+
     #[test]
     #[allow(clippy::too_many_lines)]
     fn deserialize_dataset_parameters() {
@@ -1504,6 +1774,7 @@ mod tests {
             ]),
             gdal_open_options: None,
             gdal_config_options: None,
+            apply_scale_offset: false,
         };
 
         let dataset_parameters_json = serde_json::to_value(&dataset_parameters).unwrap();
@@ -1549,7 +1820,8 @@ mod tests {
                     }
                 ],
                 "gdalOpenOptions": null,
-                "gdalConfigOptions": null
+                "gdalConfigOptions": null,
+                "applyScaleOffset": false
             })
         );
 
@@ -1592,5 +1864,48 @@ mod tests {
             deserialized_parameters.gdal_config_options,
             dataset_parameters.gdal_config_options,
         );
+        assert_eq!(
+            deserialized_parameters.apply_scale_offset,
+            dataset_parameters.apply_scale_offset,
+        );
+    }
+
+    #[tokio::test]
+    async fn it_detects_queries_outside_of_the_extent() {
+        let processor = GdalSourceProcessor::<u8> {
+            tiling_specification: TilingSpecification::new((0., 0.).into(), [600, 600].into()),
+            meta_data: Box::new(crate::util::gdal::create_ndvi_meta_data()),
+            no_data_value: Some(0),
+        };
+        let query_ctx = MockQueryContext::test_default();
+
+        let query = |spatial_bounds| RasterQueryRectangle {
+            spatial_bounds,
+            time_interval: TimeInterval::new_instant(1_388_534_400_000).unwrap(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: (),
+        };
+
+        assert!(!processor
+            .query_is_empty(
+                query(SpatialPartition2D::new_unchecked(
+                    (-10., 80.).into(),
+                    (50., 20.).into()
+                )),
+                &query_ctx
+            )
+            .await
+            .unwrap());
+
+        assert!(processor
+            .query_is_empty(
+                query(SpatialPartition2D::new_unchecked(
+                    (200., 80.).into(),
+                    (250., 20.).into()
+                )),
+                &query_ctx
+            )
+            .await
+            .unwrap());
     }
 }
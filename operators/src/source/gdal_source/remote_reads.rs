@@ -0,0 +1,414 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+use log::{debug, warn};
+use serde::Serialize;
+use snafu::ResultExt;
+
+use crate::error::{self, Error};
+use crate::util::Result;
+
+/// GDAL virtual file systems that read from the network
+const REMOTE_VSI_PREFIXES: [&str; 8] = [
+    "/vsicurl/",
+    "/vsicurl_streaming/",
+    "/vsis3/",
+    "/vsigs/",
+    "/vsiaz/",
+    "/vsiadls/",
+    "/vsioss/",
+    "/vsiswift/",
+];
+
+lazy_static! {
+    static ref GLOBAL_POLICY: RemoteReadPolicy =
+        RemoteReadPolicy::new(RemoteReadSettings::default());
+}
+
+/// How reads of remote GDAL datasets are retried, timed out and circuit-broken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteReadSettings {
+    /// Number of retries after the first failed attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for every further retry
+    pub initial_backoff: Duration,
+    /// Time after which a single attempt is considered failed
+    pub read_timeout: Duration,
+    /// Number of consecutive failed reads after which a host is not queried anymore
+    pub circuit_failure_threshold: u32,
+    /// How long a host is not queried after reaching the failure threshold
+    pub circuit_open_duration: Duration,
+}
+
+impl Default for RemoteReadSettings {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            read_timeout: Duration::from_secs(30),
+            circuit_failure_threshold: 5,
+            circuit_open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Usage statistics of a [`RemoteReadPolicy`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteReadStats {
+    pub reads: u64,
+    pub retries: u64,
+    pub timeouts: u64,
+    pub failures: u64,
+    /// Reads that were rejected because the circuit of their host was open
+    pub rejected: u64,
+    pub open_circuits: usize,
+}
+
+/// Wraps reads of GDAL datasets on remote virtual file systems, e.g., `/vsicurl/` or `/vsis3/`.
+///
+/// Failed reads are retried with an exponential backoff and each attempt is limited by a timeout.
+/// Hosts that failed repeatedly are not queried for a while, so that queries fail fast instead of
+/// waiting for timeouts of every tile.
+pub struct RemoteReadPolicy {
+    state: Mutex<PolicyState>,
+    reads: AtomicU64,
+    retries: AtomicU64,
+    timeouts: AtomicU64,
+    failures: AtomicU64,
+    rejected: AtomicU64,
+}
+
+struct PolicyState {
+    settings: RemoteReadSettings,
+    circuits: HashMap<String, Circuit>,
+}
+
+#[derive(Default)]
+struct Circuit {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl RemoteReadPolicy {
+    pub fn new(settings: RemoteReadSettings) -> Self {
+        Self {
+            state: Mutex::new(PolicyState {
+                settings,
+                circuits: HashMap::new(),
+            }),
+            reads: AtomicU64::new(0),
+            retries: AtomicU64::new(0),
+            timeouts: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// The process-wide policy that is used by the GDAL source
+    pub fn global() -> &'static Self {
+        &GLOBAL_POLICY
+    }
+
+    /// Changes the settings and closes all circuits
+    pub fn configure(&self, settings: RemoteReadSettings) {
+        let mut state = self.state.lock().expect("policy lock must not be poisoned");
+        state.settings = settings;
+        state.circuits.clear();
+    }
+
+    pub fn stats(&self) -> RemoteReadStats {
+        let now = Instant::now();
+
+        RemoteReadStats {
+            reads: self.reads.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+            open_circuits: self
+                .state
+                .lock()
+                .expect("policy lock must not be poisoned")
+                .circuits
+                .values()
+                .filter(|circuit| circuit.open_until.map_or(false, |until| until > now))
+                .count(),
+        }
+    }
+
+    /// Runs the blocking `read` of `file_path` on a blocking thread.
+    /// Reads of remote files are retried, timed out and rejected if their host is unavailable.
+    pub async fn read<T, F>(&self, file_path: &Path, read: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: Fn() -> Result<T> + Clone + Send + 'static,
+    {
+        let host = match remote_host(file_path) {
+            Some(host) => host,
+            None => {
+                return crate::util::spawn_blocking(read)
+                    .await
+                    .context(error::TokioJoin)?
+            }
+        };
+
+        self.reads.fetch_add(1, Ordering::Relaxed);
+
+        let settings = self.check_circuit(&host)?;
+
+        let mut attempt = 0;
+        loop {
+            let result = match tokio::time::timeout(
+                settings.read_timeout,
+                crate::util::spawn_blocking(read.clone()),
+            )
+            .await
+            {
+                Ok(result) => result.context(error::TokioJoin)?,
+                Err(_) => {
+                    // the blocking read cannot be cancelled, but the query does not wait for it anymore
+                    self.timeouts.fetch_add(1, Ordering::Relaxed);
+                    Err(Error::RemoteReadTimeout {
+                        file_path: file_path.to_string_lossy().to_string(),
+                        timeout_seconds: settings.read_timeout.as_secs_f64(),
+                    })
+                }
+            };
+
+            match result {
+                Ok(value) => {
+                    self.record_success(&host);
+                    return Ok(value);
+                }
+                Err(error) if attempt < settings.max_retries => {
+                    let backoff = settings
+                        .initial_backoff
+                        .saturating_mul(2_u32.saturating_pow(attempt));
+                    debug!(
+                        "Retrying read of {:?} in {:?} after error: {}",
+                        file_path, backoff, error
+                    );
+
+                    self.retries.fetch_add(1, Ordering::Relaxed);
+                    attempt += 1;
+
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(error) => {
+                    self.failures.fetch_add(1, Ordering::Relaxed);
+                    self.record_failure(&host);
+                    return Err(error);
+                }
+            }
+        }
+    }
+
+    /// Returns the current settings if the circuit of `host` is closed
+    fn check_circuit(&self, host: &str) -> Result<RemoteReadSettings> {
+        let state = self.state.lock().expect("policy lock must not be poisoned");
+
+        // after the open duration, the next read decides whether the circuit is closed again
+        let is_open = state
+            .circuits
+            .get(host)
+            .and_then(|circuit| circuit.open_until)
+            .map_or(false, |until| until > Instant::now());
+
+        if is_open {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+            return Err(Error::RemoteHostUnavailable {
+                host: host.to_owned(),
+            });
+        }
+
+        Ok(state.settings)
+    }
+
+    fn record_success(&self, host: &str) {
+        self.state
+            .lock()
+            .expect("policy lock must not be poisoned")
+            .circuits
+            .remove(host);
+    }
+
+    fn record_failure(&self, host: &str) {
+        let mut state = self.state.lock().expect("policy lock must not be poisoned");
+        let settings = state.settings;
+
+        let circuit = state.circuits.entry(host.to_owned()).or_default();
+        circuit.consecutive_failures += 1;
+
+        if circuit.consecutive_failures >= settings.circuit_failure_threshold {
+            warn!(
+                "Not reading from {} for {:?} after {} failed reads",
+                host, settings.circuit_open_duration, circuit.consecutive_failures
+            );
+            circuit.open_until = Some(Instant::now() + settings.circuit_open_duration);
+        }
+    }
+}
+
+/// The host of a file on a remote virtual file system, i.e., the server for `/vsicurl/` and
+/// the bucket or container for cloud storages.
+/// Returns `None` for local files.
+fn remote_host(file_path: &Path) -> Option<String> {
+    let file_path = file_path.to_str()?;
+
+    let (prefix, remainder) = REMOTE_VSI_PREFIXES.iter().find_map(|prefix| {
+        file_path
+            .strip_prefix(prefix)
+            .map(|remainder| (*prefix, remainder))
+    })?;
+
+    let remainder = remainder
+        .strip_prefix("http://")
+        .or_else(|| remainder.strip_prefix("https://"))
+        .unwrap_or(remainder);
+
+    let host = remainder.split('/').next().unwrap_or_default();
+
+    Some(format!("{}{}", prefix, host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+    use std::sync::Arc;
+
+    fn test_settings() -> RemoteReadSettings {
+        RemoteReadSettings {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+            read_timeout: Duration::from_millis(500),
+            circuit_failure_threshold: 2,
+            circuit_open_duration: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn it_detects_remote_hosts() {
+        assert_eq!(
+            remote_host(Path::new("/vsicurl/https://example.com/data/ndvi.tif")),
+            Some("/vsicurl/example.com".to_owned())
+        );
+        assert_eq!(
+            remote_host(Path::new("/vsis3/sentinel-cogs/tiles/B04.tif")),
+            Some("/vsis3/sentinel-cogs".to_owned())
+        );
+        assert_eq!(remote_host(Path::new("/data/ndvi.tif")), None);
+        assert_eq!(
+            remote_host(Path::new("/vsizip//data/ndvi.zip/ndvi.tif")),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn it_retries_failed_reads() {
+        let policy = RemoteReadPolicy::new(test_settings());
+        let attempts = Arc::new(AtomicU32::new(0));
+
+        let read_attempts = attempts.clone();
+        let result = policy
+            .read(Path::new("/vsicurl/https://example.com/a.tif"), move || {
+                if read_attempts.fetch_add(1, Ordering::Relaxed) < 2 {
+                    Err(Error::CouldNotOpenGdalDataset {
+                        file_path: "a.tif".to_owned(),
+                    })
+                } else {
+                    Ok(42)
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::Relaxed), 3);
+
+        let stats = policy.stats();
+        assert_eq!(stats.reads, 1);
+        assert_eq!(stats.retries, 2);
+        assert_eq!(stats.failures, 0);
+    }
+
+    #[tokio::test]
+    async fn it_does_not_retry_local_reads() {
+        let policy = RemoteReadPolicy::new(test_settings());
+        let attempts = Arc::new(AtomicU32::new(0));
+
+        let read_attempts = attempts.clone();
+        let result: Result<()> = policy
+            .read(Path::new("/data/a.tif"), move || {
+                read_attempts.fetch_add(1, Ordering::Relaxed);
+                Err(Error::CouldNotOpenGdalDataset {
+                    file_path: "a.tif".to_owned(),
+                })
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+        assert_eq!(policy.stats().reads, 0);
+    }
+
+    #[tokio::test]
+    async fn it_times_out_reads() {
+        let policy = RemoteReadPolicy::new(RemoteReadSettings {
+            max_retries: 0,
+            read_timeout: Duration::from_millis(10),
+            ..test_settings()
+        });
+
+        let result = policy
+            .read(Path::new("/vsicurl/https://example.com/a.tif"), || {
+                std::thread::sleep(Duration::from_millis(200));
+                Ok(())
+            })
+            .await;
+
+        assert!(matches!(result, Err(Error::RemoteReadTimeout { .. })));
+        assert_eq!(policy.stats().timeouts, 1);
+    }
+
+    #[tokio::test]
+    async fn it_opens_the_circuit_of_failing_hosts() {
+        let policy = RemoteReadPolicy::new(RemoteReadSettings {
+            max_retries: 0,
+            ..test_settings()
+        });
+
+        let failing_read = || -> Result<()> {
+            Err(Error::CouldNotOpenGdalDataset {
+                file_path: "a.tif".to_owned(),
+            })
+        };
+
+        for _ in 0..2 {
+            let result = policy
+                .read(Path::new("/vsis3/bucket/a.tif"), failing_read)
+                .await;
+            assert!(matches!(result, Err(Error::CouldNotOpenGdalDataset { .. })));
+        }
+
+        let result = policy
+            .read(Path::new("/vsis3/bucket/b.tif"), failing_read)
+            .await;
+        assert!(matches!(result, Err(Error::RemoteHostUnavailable { .. })));
+
+        // other hosts are not affected
+        let result = policy
+            .read(Path::new("/vsis3/other-bucket/a.tif"), || Ok(()))
+            .await;
+        assert!(result.is_ok());
+
+        let stats = policy.stats();
+        assert_eq!(stats.failures, 2);
+        assert_eq!(stats.rejected, 1);
+        assert_eq!(stats.open_circuits, 1);
+    }
+}
@@ -1,18 +1,22 @@
 mod csv;
 mod gdal_source;
 mod ogr_source;
+mod pixel_area;
 
 pub use self::csv::{
     CsvGeometrySpecification, CsvSource, CsvSourceParameters, CsvSourceStream, CsvTimeSpecification,
 };
 pub use self::gdal_source::{
-    FileNotFoundHandling, GdalDatasetGeoTransform, GdalDatasetParameters, GdalLoadingInfo,
-    GdalLoadingInfoTemporalSlice, GdalLoadingInfoTemporalSliceIterator, GdalMetaDataRegular,
-    GdalMetaDataStatic, GdalMetadataMapping, GdalMetadataNetCdfCf, GdalSource,
-    GdalSourceParameters, GdalSourceProcessor, GdalSourceTimePlaceholder, TimeReference,
+    CachedGdalMetaData, FileNotFoundHandling, GdalDatasetGeoTransform, GdalDatasetParameters,
+    GdalLoadingInfo, GdalLoadingInfoTemporalSlice, GdalLoadingInfoTemporalSliceIterator,
+    GdalMetaDataRegular, GdalMetaDataStatic, GdalMetadataMapping, GdalMetadataNetCdfCf, GdalSource,
+    GdalSourceParameters, GdalSourceProcessor, GdalSourceTimePlaceholder, LoadingInfoCache,
+    LoadingInfoCacheStats, RemoteReadPolicy, RemoteReadSettings, RemoteReadStats, TimeReference,
 };
 pub use self::ogr_source::{
     AttributeFilter, CsvHeader, FormatSpecifics, OgrSource, OgrSourceColumnSpec, OgrSourceDataset,
-    OgrSourceDatasetTimeType, OgrSourceDurationSpec, OgrSourceErrorSpec, OgrSourceParameters,
+    OgrSourceDatasetTimeType, OgrSourceDurationFormat, OgrSourceDurationSpec,
+    OgrSourceEndInclusion, OgrSourceErrorSpec, OgrSourceGeneralization, OgrSourceParameters,
     OgrSourceProcessor, OgrSourceTimeFormat,
 };
+pub use self::pixel_area::{PixelArea, PixelAreaParams};
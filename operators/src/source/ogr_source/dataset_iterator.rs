@@ -8,7 +8,7 @@ use crate::util::Result;
 use gdal::vector::sql::Dialect;
 use gdal::vector::Feature;
 use gdal::{Dataset, DatasetOptions, GdalOpenFlags};
-use geoengine_datatypes::primitives::VectorQueryRectangle;
+use geoengine_datatypes::primitives::{SpatialFilter, VectorQueryRectangle};
 use log::debug;
 use ouroboros::self_referencing;
 use std::cell::Cell;
@@ -47,6 +47,7 @@ impl OgrDatasetIterator {
     pub fn new(
         dataset_information: &OgrSourceDataset,
         query_rectangle: &VectorQueryRectangle,
+        spatial_filter: Option<&SpatialFilter>,
         attribute_filters: Vec<AttributeFilter>,
     ) -> Result<OgrDatasetIterator> {
         let adjusted_filters =
@@ -59,16 +60,19 @@ impl OgrDatasetIterator {
                     dataset,
                     dataset_information,
                     query_rectangle,
+                    spatial_filter,
                     &adjusted_filters,
                 )
             },
         }
         .try_build()?;
 
-        let use_ogr_spatial_filter = dataset_information.force_ogr_spatial_filter
-            || dataset_iterator
-                .borrow_features_provider()
-                .has_gdal_capability(gdal::vector::LayerCaps::OLCFastSpatialFilter);
+        // a filter polygon may exceed the query's bounding box, so the bounding box has to be checked afterwards
+        let use_ogr_spatial_filter = spatial_filter.is_none()
+            && (dataset_information.force_ogr_spatial_filter
+                || dataset_iterator
+                    .borrow_features_provider()
+                    .has_gdal_capability(gdal::vector::LayerCaps::OLCFastSpatialFilter));
 
         Ok(Self {
             dataset_iterator,
@@ -117,6 +121,7 @@ impl OgrDatasetIterator {
         dataset: &'d Dataset,
         dataset_information: &OgrSourceDataset,
         query_rectangle: &VectorQueryRectangle,
+        spatial_filter: Option<&SpatialFilter>,
         attribute_filters: &[AttributeFilter],
     ) -> Result<FeaturesProvider<'d>> {
         // TODO: add OGR time filter if forced
@@ -135,12 +140,22 @@ impl OgrDatasetIterator {
             || features_provider.has_gdal_capability(gdal::vector::LayerCaps::OLCFastSpatialFilter);
 
         if use_ogr_spatial_filter {
-            debug!(
-                "using spatial filter {:?} for layer {:?}",
-                query_rectangle.spatial_bounds, &dataset_information.layer_name
-            );
-            // NOTE: the OGR-filter may be inaccurately allowing more features that should be returned in a "strict" fashion.
-            features_provider.set_spatial_filter(&query_rectangle.spatial_bounds);
+            if let Some(spatial_filter) = spatial_filter {
+                debug!(
+                    "using spatial filter polygon with bounds {:?} for layer {:?}",
+                    spatial_filter.bounding_box(),
+                    &dataset_information.layer_name
+                );
+                let geometry = gdal::vector::Geometry::try_from(spatial_filter)?;
+                features_provider.set_spatial_filter_geometry(&geometry);
+            } else {
+                debug!(
+                    "using spatial filter {:?} for layer {:?}",
+                    query_rectangle.spatial_bounds, &dataset_information.layer_name
+                );
+                // NOTE: the OGR-filter may be inaccurately allowing more features that should be returned in a "strict" fashion.
+                features_provider.set_spatial_filter(&query_rectangle.spatial_bounds);
+            }
         }
 
         let filter_string = if dataset.driver().short_name() == "CSV" {
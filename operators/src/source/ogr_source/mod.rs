@@ -1,5 +1,6 @@
 mod dataset_iterator;
 
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::marker::PhantomData;
@@ -32,11 +33,16 @@ use geoengine_datatypes::collections::{
     FeatureCollectionModifications, FeatureCollectionRowBuilder, GeoFeatureCollectionRowBuilder,
     VectorDataType,
 };
+use geoengine_datatypes::operations::reproject::{
+    CoordinateProjection, CoordinateProjector, Reproject,
+};
 use geoengine_datatypes::primitives::{
-    AxisAlignedRectangle, BoundingBox2D, Coordinate2D, FeatureDataType, FeatureDataValue, Geometry,
-    MultiLineString, MultiPoint, MultiPolygon, NoGeometry, TimeInstance, TimeInterval, TimeStep,
+    AxisAlignedRectangle, BoundingBox2D, Coordinate2D, DayOverflowPolicy, FeatureDataType,
+    FeatureDataValue, Geometry, MultiLineString, MultiPoint, MultiPolygon, NoGeometry,
+    SpatialFilter, SpatialResolution, TimeGranularity, TimeInstance, TimeInterval, TimeStep,
     TypedGeometry, VectorQueryRectangle,
 };
+use geoengine_datatypes::spatial_reference::SpatialReference;
 use geoengine_datatypes::util::arrow::ArrowTyped;
 
 use crate::engine::{OperatorDatasets, QueryProcessor};
@@ -45,7 +51,7 @@ use crate::util::input::StringOrNumberRange;
 use crate::util::Result;
 use crate::{
     engine::{
-        InitializedVectorOperator, MetaData, QueryContext, SourceOperator,
+        Cacheability, InitializedVectorOperator, MetaData, QueryContext, SourceOperator,
         TypedVectorQueryProcessor, VectorOperator, VectorQueryProcessor, VectorResultDescriptor,
     },
     error,
@@ -90,6 +96,7 @@ pub type OgrSource = SourceOperator<OgrSourceParameters>;
 ///    (result: empty collection), but has better performance for wfs requests (optional, false if not provided)
 ///  - `on_error`: specify the type of error handling
 ///  - `provenance`: specify the provenance of a file
+///  - `generalizations`: pre-generalized versions of the geometries that are used for coarse queries
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OgrSourceDataset {
@@ -107,6 +114,8 @@ pub struct OgrSourceDataset {
     pub on_error: OgrSourceErrorSpec,
     pub sql_query: Option<String>,
     pub attribute_query: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub generalizations: Vec<OgrSourceGeneralization>,
 }
 
 impl OgrSourceDataset {
@@ -115,18 +124,54 @@ impl OgrSourceDataset {
             columns.project_columns(attribute_projection);
         }
     }
+
+    /// Replaces the file and layer by the coarsest generalization that is still at least as
+    /// detailed as the query's `spatial_resolution`
+    pub fn generalize(mut self, spatial_resolution: SpatialResolution) -> Self {
+        let resolution = f64::max(spatial_resolution.x, spatial_resolution.y);
+
+        let generalization = self
+            .generalizations
+            .iter()
+            .filter(|g| g.min_resolution <= resolution)
+            .max_by(|a, b| {
+                a.min_resolution
+                    .partial_cmp(&b.min_resolution)
+                    .unwrap_or(Ordering::Equal)
+            });
+
+        if let Some(generalization) = generalization {
+            self.file_name = generalization.file_name.clone();
+            self.layer_name = generalization.layer_name.clone();
+        }
+
+        self
+    }
+}
+
+/// A pre-generalized version of a dataset's geometries with the same columns as the original
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OgrSourceGeneralization {
+    /// the finest query resolution, in units of the spatial reference per pixel, that this
+    /// version is used for
+    pub min_resolution: f64,
+    pub file_name: PathBuf,
+    pub layer_name: String,
 }
 
 /// The type of the time attribute(s):
 ///  - "none": no time information is mapped
 ///  - "start": only start information is mapped. duration has to specified in the duration attribute
-///  - "start+end": start and end information is mapped
+///  - "start+end": start and end information is mapped. A missing start or end leaves the interval open-ended
 ///  - "start+duration": start and duration information is mapped
 ///
 /// There are different options within these variants:
 ///  - `start_field` and `end_field`: the name of the field that contains time information
 ///  - `start_format` and `start_format`: a mapping of a field type to a time value (cf. `OgrSourceDatasetTimeType`)
 ///  - `duration`: the duration of the time validity for all features in the file
+///  - `end_inclusion`: whether the end belongs to the interval, e.g., for dates that denote the last day
+///  - `duration_format`: how the values of the duration field are interpreted
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum OgrSourceDatasetTimeType {
@@ -144,6 +189,8 @@ pub enum OgrSourceDatasetTimeType {
         start_format: OgrSourceTimeFormat,
         end_field: String,
         end_format: OgrSourceTimeFormat,
+        #[serde(default)]
+        end_inclusion: OgrSourceEndInclusion,
     },
     #[serde(rename = "start+duration")]
     #[serde(rename_all = "camelCase")]
@@ -151,6 +198,8 @@ pub enum OgrSourceDatasetTimeType {
         start_field: String,
         start_format: OgrSourceTimeFormat,
         duration_field: String,
+        #[serde(default)]
+        duration_format: OgrSourceDurationFormat,
     },
 }
 
@@ -193,6 +242,7 @@ impl Default for OgrSourceTimeFormat {
 ///  - text: an array of column names containing alpha-numeric values
 ///  - bool: an array of column names containing boolean values
 ///  - datetime: an array of column names containing timestamps or date strings
+///  - category: an array of column names containing alpha-numeric values with only a few distinct values
 ///  - rename: a. optional map of column names from data source to the name in the resulting collection
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -210,6 +260,8 @@ pub struct OgrSourceColumnSpec {
     pub bool: Vec<String>,
     #[serde(default)]
     pub datetime: Vec<String>,
+    #[serde(default)]
+    pub category: Vec<String>,
     pub rename: Option<HashMap<String, String>>,
 }
 
@@ -229,6 +281,8 @@ impl OgrSourceColumnSpec {
         self.bool.retain(|attribute| attributes.contains(attribute));
         self.datetime
             .retain(|attribute| attributes.contains(attribute));
+        self.category
+            .retain(|attribute| attributes.contains(attribute));
     }
 }
 
@@ -283,12 +337,33 @@ impl OgrSourceErrorSpec {
     }
 }
 
+/// The duration of the time validity of features with only a start time
+///  - "infinite": the features are valid from their start on
+///  - "zero": the features are valid at their start only
+///  - "value": the features are valid for a time step
+///  - "period": the start denotes the whole period of the `granularity` that contains it,
+///    e.g., the date `2020-05-01` with `months` denotes May 2020
 #[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum OgrSourceDurationSpec {
     Infinite,
     Zero,
     Value(TimeStep),
+    Period { granularity: TimeGranularity },
+}
+
+impl OgrSourceDurationSpec {
+    /// The time validity of a feature with the `start` time
+    fn interval(self, start: TimeInstance) -> Result<TimeInterval> {
+        let start = match self {
+            OgrSourceDurationSpec::Period { granularity } => period_start(start, granularity)?,
+            OgrSourceDurationSpec::Infinite
+            | OgrSourceDurationSpec::Zero
+            | OgrSourceDurationSpec::Value(_) => start,
+        };
+
+        TimeInterval::new(start, (start + self)?).map_err(Into::into)
+    }
 }
 
 impl Add<OgrSourceDurationSpec> for TimeInstance {
@@ -298,9 +373,219 @@ impl Add<OgrSourceDurationSpec> for TimeInstance {
         match rhs {
             OgrSourceDurationSpec::Infinite => Ok(TimeInstance::MAX),
             OgrSourceDurationSpec::Zero => Ok(self),
-            OgrSourceDurationSpec::Value(step) => (self + step).context(error::DataType),
+            // a duration beyond the valid time range leaves the interval open-ended
+            OgrSourceDurationSpec::Value(step) => Ok(self
+                .checked_add(step, DayOverflowPolicy::ClampToMonthEnd)
+                .unwrap_or(TimeInstance::MAX)),
+            // the end of the period that contains the instance
+            OgrSourceDurationSpec::Period { granularity } => Ok(period_start(self, granularity)?
+                .checked_add(
+                    TimeStep {
+                        granularity,
+                        step: 1,
+                    },
+                    DayOverflowPolicy::ClampToMonthEnd,
+                )
+                .unwrap_or(TimeInstance::MAX)),
+        }
+    }
+}
+
+/// The start of the period of the `granularity` that contains `time`, e.g., the start of its day
+fn period_start(time: TimeInstance, granularity: TimeGranularity) -> Result<TimeInstance> {
+    if time.is_min() || time.is_max() {
+        return Ok(time);
+    }
+
+    TimeStep {
+        granularity,
+        step: 1,
+    }
+    .snap_relative(TimeInstance::from_millis_unchecked(0), time)
+    .context(error::DataType)
+}
+
+/// Whether the end time of a feature belongs to its time validity
+///  - "exclusive": the end is the first instant after the validity
+///  - "inclusive": the end denotes the last period of the `granularity` that belongs to the
+///    validity, e.g., the date `2020-05-31` with `days` ends the validity at `2020-06-01`
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum OgrSourceEndInclusion {
+    Exclusive,
+    Inclusive { granularity: TimeGranularity },
+}
+
+impl Default for OgrSourceEndInclusion {
+    fn default() -> Self {
+        Self::Exclusive
+    }
+}
+
+impl OgrSourceEndInclusion {
+    /// The exclusive end of a validity with the `end` time
+    fn exclusive_end(self, end: TimeInstance) -> Result<TimeInstance> {
+        match self {
+            OgrSourceEndInclusion::Exclusive => Ok(end),
+            OgrSourceEndInclusion::Inclusive { granularity } if !end.is_max() => {
+                end + OgrSourceDurationSpec::Period { granularity }
+            }
+            OgrSourceEndInclusion::Inclusive { .. } => Ok(end),
+        }
+    }
+}
+
+/// The format of the values of a duration field
+///  - "millis": numbers of milliseconds
+///  - "seconds": numbers of seconds
+///  - "steps": numbers of steps of the `granularity`, e.g., days or months
+///  - "iso8601": ISO 8601 durations, e.g., `P1M` or `PT1H30M`
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum OgrSourceDurationFormat {
+    Millis,
+    Seconds,
+    Steps { granularity: TimeGranularity },
+    Iso8601,
+}
+
+impl Default for OgrSourceDurationFormat {
+    fn default() -> Self {
+        Self::Millis
+    }
+}
+
+impl OgrSourceDurationFormat {
+    /// The end of a validity with the `start` time and the `duration`
+    fn end(self, start: TimeInstance, duration: FieldValue) -> Result<TimeInstance> {
+        match self {
+            OgrSourceDurationFormat::Millis => {
+                let millis = duration_number(duration)?.round() as i64;
+                Ok(start.saturating_add_millis(millis))
+            }
+            OgrSourceDurationFormat::Seconds => {
+                let millis = (duration_number(duration)? * 1000.).round() as i64;
+                Ok(start.saturating_add_millis(millis))
+            }
+            OgrSourceDurationFormat::Steps { granularity } => {
+                let steps = duration_number(duration)?;
+                if steps < 0. || steps.fract() != 0. || steps > f64::from(u32::MAX) {
+                    return Err(Error::OgrFieldValueIsNotValidForDuration);
+                }
+
+                Ok(start
+                    .checked_add(
+                        TimeStep {
+                            granularity,
+                            step: steps as u32,
+                        },
+                        DayOverflowPolicy::ClampToMonthEnd,
+                    )
+                    .unwrap_or(TimeInstance::MAX))
+            }
+            OgrSourceDurationFormat::Iso8601 => {
+                let duration = duration
+                    .into_string()
+                    .ok_or(Error::OgrFieldValueIsNotValidForDuration)?;
+                let (months, millis) = parse_iso8601_duration(&duration)
+                    .ok_or(Error::OgrFieldValueIsNotValidForDuration)?;
+
+                let start = if months > 0 {
+                    start
+                        .checked_add(
+                            TimeStep {
+                                granularity: TimeGranularity::Months,
+                                step: months,
+                            },
+                            DayOverflowPolicy::ClampToMonthEnd,
+                        )
+                        .unwrap_or(TimeInstance::MAX)
+                } else {
+                    start
+                };
+
+                Ok(start.saturating_add_millis(millis))
+            }
+        }
+    }
+}
+
+fn duration_number(duration: FieldValue) -> Result<f64> {
+    match duration {
+        FieldValue::IntegerValue(v) => Ok(f64::from(v)),
+        FieldValue::Integer64Value(v) => Ok(v as f64),
+        FieldValue::RealValue(v) => Ok(v),
+        FieldValue::StringValue(v) => v
+            .trim()
+            .parse()
+            .map_err(|_| Error::OgrFieldValueIsNotValidForDuration),
+        _ => Err(Error::OgrFieldValueIsNotValidForDuration),
+    }
+}
+
+/// Parses an ISO 8601 duration like `P1Y2M10DT2H30M` into months and milliseconds.
+/// Only the seconds may have a fraction.
+fn parse_iso8601_duration(duration: &str) -> Option<(u32, i64)> {
+    let duration = duration.trim().strip_prefix('P')?;
+    let (date_part, time_part) = match duration.split_once('T') {
+        Some((date_part, time_part)) if !time_part.is_empty() => (date_part, Some(time_part)),
+        Some(_) => return None,
+        None => (duration, None),
+    };
+
+    let mut months: u32 = 0;
+    let mut millis: i64 = 0;
+    let mut has_components = false;
+
+    for (value, designator) in iso8601_components(date_part)? {
+        let value: u32 = value.parse().ok()?;
+        match designator {
+            'Y' => months = months.checked_add(value.checked_mul(12)?)?,
+            'M' => months = months.checked_add(value)?,
+            'W' => millis = millis.checked_add(i64::from(value) * 7 * 86_400_000)?,
+            'D' => millis = millis.checked_add(i64::from(value) * 86_400_000)?,
+            _ => return None,
+        }
+        has_components = true;
+    }
+
+    for (value, designator) in iso8601_components(time_part.unwrap_or_default())? {
+        let value = match designator {
+            'H' => i64::from(value.parse::<u32>().ok()?) * 3_600_000,
+            'M' => i64::from(value.parse::<u32>().ok()?) * 60_000,
+            'S' => {
+                let seconds: f64 = value.parse().ok()?;
+                if !seconds.is_finite() || seconds < 0. {
+                    return None;
+                }
+                (seconds * 1000.).round() as i64
+            }
+            _ => return None,
+        };
+        millis = millis.checked_add(value)?;
+        has_components = true;
+    }
+
+    has_components.then(|| (months, millis))
+}
+
+/// Splits ISO 8601 duration components like `1Y2M` into values and designators
+fn iso8601_components(part: &str) -> Option<Vec<(&str, char)>> {
+    let mut components = Vec::new();
+    let mut value_start = 0;
+
+    for (i, c) in part.char_indices() {
+        if c.is_ascii_alphabetic() {
+            if i == value_start {
+                return None;
+            }
+            components.push((&part[value_start..i], c));
+            value_start = i + c.len_utf8();
         }
     }
+
+    // a trailing value without designator is invalid
+    (value_start == part.len()).then(|| components)
 }
 
 #[derive(Clone, Debug)]
@@ -312,6 +597,7 @@ pub struct OgrSourceState {
 
 pub struct InitializedOgrSource {
     result_descriptor: VectorResultDescriptor,
+    cacheability: Cacheability,
     state: OgrSourceState,
 }
 
@@ -336,7 +622,10 @@ impl VectorOperator for OgrSource {
                     for range in &filter.ranges {
                         match range {
                             StringOrNumberRange::String(_) => {
-                                if column_type != &FeatureDataType::Text {
+                                if !matches!(
+                                    column_type,
+                                    FeatureDataType::Text | FeatureDataType::Category
+                                ) {
                                     return Err(error::Error::InvalidFeatureDataType);
                                 }
                             }
@@ -362,6 +651,7 @@ impl VectorOperator for OgrSource {
 
         let initialized_source = InitializedOgrSource {
             result_descriptor,
+            cacheability: info.cacheability(),
             state: OgrSourceState {
                 dataset_information: info,
                 attribute_filters: self.params.attribute_filters.unwrap_or_default(),
@@ -429,6 +719,10 @@ impl InitializedVectorOperator for InitializedOgrSource {
     fn result_descriptor(&self) -> &VectorResultDescriptor {
         &self.result_descriptor
     }
+
+    fn cacheability(&self) -> Cacheability {
+        self.cacheability
+    }
 }
 
 pub struct OgrSourceProcessor<G>
@@ -457,6 +751,60 @@ where
             _collection_type: Default::default(),
         }
     }
+
+    /// Returns the `SpatialFilter` of the query in the spatial reference of the data.
+    ///
+    /// Since the filter only refines the query's bounding box, it is dropped if the data is
+    /// unreferenced or if the filter polygon cannot be reprojected.
+    async fn applicable_spatial_filter(
+        &self,
+        query: &VectorQueryRectangle,
+    ) -> Result<Option<Arc<SpatialFilter>>> {
+        let spatial_filter = match &query.spatial_filter {
+            Some(spatial_filter) => spatial_filter,
+            None => return Ok(None),
+        };
+
+        let spatial_reference: Option<SpatialReference> = self
+            .dataset_information
+            .result_descriptor()
+            .await?
+            .spatial_reference
+            .into();
+
+        let spatial_reference = match spatial_reference {
+            Some(spatial_reference) => spatial_reference,
+            None => {
+                debug!("ignoring spatial filter for unreferenced data");
+                return Ok(None);
+            }
+        };
+
+        if spatial_reference == spatial_filter.spatial_reference() {
+            return Ok(Some(Arc::new(spatial_filter.clone())));
+        }
+
+        let projector = CoordinateProjector::from_known_srs(
+            spatial_filter.spatial_reference(),
+            spatial_reference,
+        )?;
+
+        match spatial_filter.polygon().reproject(&projector) {
+            Ok(polygon) => Ok(Some(Arc::new(SpatialFilter::new(
+                spatial_reference,
+                polygon,
+            )))),
+            Err(error) => {
+                debug!(
+                    "ignoring spatial filter that cannot be reprojected from {} to {}: {}",
+                    spatial_filter.spatial_reference(),
+                    spatial_reference,
+                    error
+                );
+                Ok(None)
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -473,9 +821,15 @@ where
         query: VectorQueryRectangle,
         ctx: &'a dyn QueryContext,
     ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let spatial_filter = self.applicable_spatial_filter(&query).await?;
+
         Ok(OgrSourceStream::new(
-            self.dataset_information.loading_info(query).await?,
+            self.dataset_information
+                .loading_info(query.clone())
+                .await?
+                .generalize(query.spatial_resolution),
             query,
+            spatial_filter,
             ctx.chunk_byte_size().into(),
             self.attribute_filters.clone(),
         )
@@ -497,6 +851,7 @@ where
     time_attribute_parser:
         Arc<Box<dyn Fn(FieldValue) -> Result<TimeInstance> + Send + Sync + 'static>>,
     query_rectangle: VectorQueryRectangle,
+    spatial_filter: Option<Arc<SpatialFilter>>,
     chunk_byte_size: usize,
     #[pin]
     future: Option<BoxFuture<'static, Result<FeatureCollection<G>>>>,
@@ -538,6 +893,13 @@ impl FeaturesProvider<'_> {
         }
     }
 
+    fn set_spatial_filter_geometry(&mut self, geometry: &gdal::vector::Geometry) {
+        match self {
+            FeaturesProvider::Layer(l) => l.set_spatial_filter(geometry),
+            FeaturesProvider::ResultSet(r) => r.deref_mut().set_spatial_filter(geometry),
+        }
+    }
+
     fn set_attribute_filter(&mut self, attribute_query: &str) -> Result<()> {
         match self {
             FeaturesProvider::Layer(l) => l.set_attribute_filter(attribute_query)?,
@@ -694,12 +1056,17 @@ where
     pub async fn new(
         dataset_information: OgrSourceDataset,
         query_rectangle: VectorQueryRectangle,
+        spatial_filter: Option<Arc<SpatialFilter>>,
         chunk_byte_size: usize,
         attribute_filters: Vec<AttributeFilter>,
     ) -> Result<Self> {
         crate::util::spawn_blocking(move || {
-            let dataset_iterator =
-                OgrDatasetIterator::new(&dataset_information, &query_rectangle, attribute_filters)?;
+            let dataset_iterator = OgrDatasetIterator::new(
+                &dataset_information,
+                &query_rectangle,
+                spatial_filter.as_deref(),
+                attribute_filters,
+            )?;
 
             let (data_types, feature_collection_builder) =
                 Self::initialize_types_and_builder(&dataset_information);
@@ -715,6 +1082,7 @@ where
                 data_types: Arc::new(data_types),
                 feature_collection_builder,
                 query_rectangle,
+                spatial_filter,
                 time_extractor: Arc::new(time_extractor),
                 time_attribute_parser: Arc::new(time_attribute_parser),
                 chunk_byte_size,
@@ -733,6 +1101,7 @@ where
         feature_collection_builder: FeatureCollectionBuilder<G>,
         data_types: Arc<HashMap<String, FeatureDataType>>,
         query_rectangle: VectorQueryRectangle,
+        spatial_filter: Option<Arc<SpatialFilter>>,
         time_extractor: Arc<Box<dyn Fn(&Feature) -> Result<TimeInterval> + Send + Sync>>,
         time_attribute_parser: Arc<Box<dyn Fn(FieldValue) -> Result<TimeInstance> + Send + Sync>>,
         chunk_byte_size: usize,
@@ -746,6 +1115,7 @@ where
                 &dataset_information,
                 &data_types,
                 &query_rectangle,
+                spatial_filter.as_deref(),
                 time_extractor.as_ref(),
                 time_attribute_parser.as_ref(),
                 chunk_byte_size,
@@ -828,7 +1198,7 @@ where
                     let field_value = feature.field(&start_field)?;
                     if let Some(field_value) = field_value {
                         let time_start = time_start_parser(field_value)?;
-                        TimeInterval::new(time_start, (time_start + duration)?).map_err(Into::into)
+                        duration.interval(time_start)
                     } else {
                         // TODO: throw error or use some user defined default time (like for geometries)?
                         Ok(TimeInterval::default())
@@ -840,6 +1210,7 @@ where
                 start_format,
                 end_field,
                 end_format,
+                end_inclusion,
             } => {
                 let time_start_parser = Self::create_time_parser(start_format);
                 let time_end_parser = Self::create_time_parser(end_format);
@@ -848,23 +1219,26 @@ where
                     let start_field_value = feature.field(&start_field)?;
                     let end_field_value = feature.field(&end_field)?;
 
-                    if let (Some(start_field_value), Some(end_field_value)) =
-                        (start_field_value, end_field_value)
-                    {
-                        let time_start = time_start_parser(start_field_value)?;
-                        let time_end = time_end_parser(end_field_value)?;
-
-                        TimeInterval::new(time_start, time_end).map_err(Into::into)
-                    } else {
-                        // TODO: throw error or use some user defined default time (like for geometries)?
-                        Ok(TimeInterval::default())
-                    }
+                    // a missing start or end makes the interval open-ended in that direction
+                    let time_start = start_field_value
+                        .map(&time_start_parser)
+                        .transpose()?
+                        .unwrap_or(TimeInstance::MIN);
+                    let time_end = end_field_value
+                        .map(&time_end_parser)
+                        .transpose()?
+                        .map(|time_end| end_inclusion.exclusive_end(time_end))
+                        .transpose()?
+                        .unwrap_or(TimeInstance::MAX);
+
+                    TimeInterval::new(time_start, time_end).map_err(Into::into)
                 })
             }
             OgrSourceDatasetTimeType::StartDuration {
                 start_field,
                 start_format,
                 duration_field,
+                duration_format,
             } => {
                 let time_start_parser = Self::create_time_parser(start_format);
 
@@ -876,13 +1250,9 @@ where
                         (start_field_value, duration_field_value)
                     {
                         let time_start = time_start_parser(start_field_value)?;
-                        let duration = i64::from(
-                            duration_field_value
-                                .into_int()
-                                .ok_or(Error::OgrFieldValueIsNotValidForSeconds)?,
-                        );
+                        let time_end = duration_format.end(time_start, duration_field_value)?;
 
-                        TimeInterval::new(time_start, time_start + duration).map_err(Into::into)
+                        TimeInterval::new(time_start, time_end).map_err(Into::into)
                     } else {
                         // TODO: throw error or use some user defined default time (like for geometries)?
                         Ok(TimeInterval::default())
@@ -948,6 +1318,12 @@ where
                     .add_column(attribute.clone(), FeatureDataType::DateTime)
                     .unwrap();
             }
+            for attribute in &column_spec.category {
+                data_types.insert(attribute.clone(), FeatureDataType::Category);
+                feature_collection_builder
+                    .add_column(attribute.clone(), FeatureDataType::Category)
+                    .unwrap();
+            }
         }
         (data_types, feature_collection_builder)
     }
@@ -959,6 +1335,7 @@ where
         dataset_information: &OgrSourceDataset,
         data_types: &HashMap<String, FeatureDataType>,
         query_rectangle: &VectorQueryRectangle,
+        spatial_filter: Option<&SpatialFilter>,
         time_extractor: &dyn Fn(&Feature) -> Result<TimeInterval>,
         time_attribute_parser: &dyn Fn(FieldValue) -> Result<TimeInstance>,
         chunk_byte_size: usize,
@@ -978,6 +1355,7 @@ where
                 &default_geometry,
                 data_types,
                 query_rectangle,
+                spatial_filter,
                 time_extractor,
                 time_attribute_parser,
                 &mut builder,
@@ -1061,16 +1439,19 @@ where
             }
             FeatureDataType::Category => {
                 #[allow(clippy::match_same_arms)]
-                let _value_option: Option<u8> = match field {
+                let category_option = match field {
+                    Ok(Some(FieldValue::IntegerValue(v))) => Some(v.to_string()),
+                    Ok(Some(FieldValue::Integer64Value(v))) => Some(v.to_string()),
+                    Ok(Some(FieldValue::StringValue(s))) => Some(s),
                     Ok(None) => None,
                     Ok(Some(v)) => error_spec.on_error(Error::OgrColumnFieldTypeMismatch {
                         expected: "Category".to_string(),
                         field_value: v,
-                    })?, // TODO: handle other types
+                    })?,
                     Err(e) => error_spec.on_error(Error::Gdal { source: e })?,
                 };
 
-                Err(Error::InvalidFeatureDataType) // TODO: ?
+                Ok(FeatureDataValue::NullableCategory(category_option))
             }
             FeatureDataType::Bool => {
                 #[allow(clippy::match_same_arms)]
@@ -1110,6 +1491,7 @@ where
         default_geometry: &Option<G>,
         data_types: &HashMap<String, FeatureDataType>,
         query_rectangle: &VectorQueryRectangle,
+        spatial_filter: Option<&SpatialFilter>,
         time_extractor: &dyn Fn(&Feature) -> Result<TimeInterval, Error>,
         time_attribute_parser: &dyn Fn(FieldValue) -> Result<TimeInstance>,
         builder: &mut FeatureCollectionRowBuilder<G>,
@@ -1148,6 +1530,13 @@ where
             return Ok(());
         }
 
+        // filter out geometries that are not intersecting the spatial filter polygon exactly
+        if let Some(spatial_filter) = spatial_filter {
+            if !geometry.intersects_spatial_filter(spatial_filter) {
+                return Ok(());
+            }
+        }
+
         builder.push_generic_geometry(geometry)?;
         builder.push_time_interval(time_interval)?;
 
@@ -1218,7 +1607,8 @@ where
                 this.dataset_information.clone(),
                 this.feature_collection_builder.clone(),
                 this.data_types.clone(),
-                *this.query_rectangle,
+                this.query_rectangle.clone(),
+                this.spatial_filter.clone(),
                 this.time_extractor.clone(),
                 this.time_attribute_parser.clone(),
                 *this.chunk_byte_size,
@@ -1397,7 +1787,9 @@ mod tests {
     use geoengine_datatypes::primitives::{
         BoundingBox2D, FeatureData, SpatialResolution, TimeGranularity,
     };
-    use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
+    use geoengine_datatypes::spatial_reference::{
+        SpatialReferenceAuthority, SpatialReferenceOption,
+    };
     use geoengine_datatypes::util::test::TestDefault;
     use geoengine_datatypes::util::Identifier;
     use serde_json::json;
@@ -1433,6 +1825,7 @@ mod tests {
                 text: vec!["text".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -1440,6 +1833,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let serialized_spec = serde_json::to_string(&spec).unwrap();
@@ -1558,6 +1952,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -1566,6 +1961,7 @@ mod tests {
                 data_type: VectorDataType::MultiPoint,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
                 columns: Default::default(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -1579,6 +1975,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -1593,6 +1990,105 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn it_selects_generalizations() {
+        let generalization = |min_resolution: f64, layer_name: &str| OgrSourceGeneralization {
+            min_resolution,
+            file_name: format!("{}.gpkg", layer_name).into(),
+            layer_name: layer_name.to_string(),
+        };
+
+        let dataset = OgrSourceDataset {
+            file_name: "full.gpkg".into(),
+            layer_name: "full".to_string(),
+            data_type: None,
+            time: OgrSourceDatasetTimeType::None,
+            default_geometry: None,
+            columns: None,
+            force_ogr_time_filter: false,
+            force_ogr_spatial_filter: false,
+            on_error: OgrSourceErrorSpec::Ignore,
+            sql_query: None,
+            attribute_query: None,
+            generalizations: vec![
+                generalization(100., "coarse"),
+                generalization(10., "medium"),
+            ],
+        };
+
+        let layer = |x: f64, y: f64| {
+            dataset
+                .clone()
+                .generalize(SpatialResolution::new_unchecked(x, y))
+                .layer_name
+        };
+
+        assert_eq!(layer(1., 1.), "full");
+        assert_eq!(layer(10., 10.), "medium");
+        assert_eq!(layer(1., 50.), "medium");
+        assert_eq!(layer(500., 500.), "coarse");
+
+        let generalized = dataset.generalize(SpatialResolution::new_unchecked(20., 20.));
+        assert_eq!(generalized.file_name, PathBuf::from("medium.gpkg"));
+    }
+
+    #[tokio::test]
+    async fn generalized_geojson() -> Result<()> {
+        let dataset_information = OgrSourceDataset {
+            file_name: "".into(),
+            layer_name: "".to_string(),
+            data_type: None,
+            time: OgrSourceDatasetTimeType::None,
+            default_geometry: None,
+            columns: None,
+            force_ogr_time_filter: false,
+            force_ogr_spatial_filter: false,
+            on_error: OgrSourceErrorSpec::Abort,
+            sql_query: None,
+            attribute_query: None,
+            generalizations: vec![OgrSourceGeneralization {
+                min_resolution: 1.,
+                file_name: test_data!("vector/data/empty.json").into(),
+                layer_name: "empty".to_string(),
+            }],
+        };
+
+        let info = StaticMetaData {
+            loading_info: dataset_information,
+            result_descriptor: VectorResultDescriptor {
+                data_type: VectorDataType::MultiPoint,
+                spatial_reference: SpatialReferenceOption::Unreferenced,
+                columns: Default::default(),
+                column_metadata: Default::default(),
+            },
+            phantom: Default::default(),
+        };
+
+        let query_processor = OgrSourceProcessor::<MultiPoint>::new(Box::new(info), vec![]);
+        let context = MockQueryContext::new(ChunkByteSize::MAX);
+
+        let query = |resolution: f64| VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new_unchecked((0., 0.).into(), (1., 1.).into()),
+            time_interval: Default::default(),
+            spatial_resolution: SpatialResolution::new_unchecked(resolution, resolution),
+            spatial_filter: None,
+        };
+
+        // the coarse query reads the generalized file
+        let result: Vec<MultiPointCollection> = query_processor
+            .query(query(1.), &context)
+            .await?
+            .try_collect()
+            .await?;
+        assert_eq!(result.len(), 1);
+        assert!(result[0].is_empty());
+
+        // the detailed query tries to read the missing original file
+        assert!(query_processor.query(query(0.5), &context).await.is_err());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn early_error() {
         let dataset_information = OgrSourceDataset {
@@ -1607,6 +2103,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -1615,6 +2112,7 @@ mod tests {
                 data_type: VectorDataType::MultiPoint,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
                 columns: Default::default(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -1628,6 +2126,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into()).unwrap(),
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -1650,6 +2149,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Abort,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
         let info = StaticMetaData {
             loading_info: dataset_information,
@@ -1657,6 +2157,7 @@ mod tests {
                 data_type: VectorDataType::MultiPoint,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
                 columns: Default::default(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -1670,6 +2171,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (5., 5.).into()).unwrap(),
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -1697,6 +2199,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
         let info = StaticMetaData {
             loading_info: dataset_information,
@@ -1704,6 +2207,7 @@ mod tests {
                 data_type: VectorDataType::MultiPoint,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
                 columns: Default::default(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -1717,6 +2221,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (5., 5.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -1760,11 +2265,13 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     columns: Default::default(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -1799,6 +2306,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((1.85, 50.88).into(), (4.82, 52.95).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -1856,11 +2364,13 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     columns: Default::default(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -1895,6 +2405,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((1.85, 50.88).into(), (4.82, 52.95).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -1955,11 +2466,13 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     columns: Default::default(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -1994,6 +2507,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((1.85, 50.88).into(), (4.82, 52.95).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -2030,6 +2544,104 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn ne_10m_ports_reprojected_spatial_filter() -> Result<()> {
+        let dataset = DatasetId::Internal {
+            dataset_id: InternalDatasetId::new(),
+        };
+        let mut exe_ctx = MockExecutionContext::test_default();
+        exe_ctx.add_meta_data::<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>(
+            dataset.clone(),
+            Box::new(StaticMetaData {
+                loading_info: OgrSourceDataset {
+                    file_name: test_data!(
+                        "vector/data/ne_10m_ports/with_spatial_index/ne_10m_ports.gpkg"
+                    )
+                    .into(),
+                    layer_name: "ne_10m_ports".to_string(),
+                    data_type: Some(VectorDataType::MultiPoint),
+                    time: OgrSourceDatasetTimeType::None,
+                    default_geometry: None,
+                    columns: None,
+                    force_ogr_time_filter: false,
+                    force_ogr_spatial_filter: false,
+                    on_error: OgrSourceErrorSpec::Ignore,
+                    sql_query: None,
+                    attribute_query: None,
+                    generalizations: vec![],
+                },
+                result_descriptor: VectorResultDescriptor {
+                    data_type: VectorDataType::MultiPoint,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    columns: Default::default(),
+                    column_metadata: Default::default(),
+                },
+                phantom: Default::default(),
+            }),
+        );
+
+        let source = OgrSource {
+            params: OgrSourceParameters {
+                dataset,
+                attribute_projection: None,
+                attribute_filters: None,
+            },
+        }
+        .boxed()
+        .initialize(&exe_ctx)
+        .await?;
+
+        let query_processor = source.query_processor()?.multi_point().unwrap();
+
+        // the area between 0°E and 3.5°E and between 50°N and 54°N in web mercator
+        let spatial_filter = SpatialFilter::new(
+            SpatialReference::new(SpatialReferenceAuthority::Epsg, 3857),
+            MultiPolygon::new(vec![vec![vec![
+                (0., 6_446_275.84).into(),
+                (389_618.22, 6_446_275.84).into(),
+                (389_618.22, 7_170_156.29).into(),
+                (0., 7_170_156.29).into(),
+                (0., 6_446_275.84).into(),
+            ]]])?,
+        );
+
+        let context = MockQueryContext::new(ChunkByteSize::MAX);
+        let query = query_processor
+            .query(
+                VectorQueryRectangle {
+                    spatial_bounds: BoundingBox2D::new((1.85, 50.88).into(), (4.82, 52.95).into())?,
+                    time_interval: Default::default(),
+                    spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: Some(spatial_filter),
+                },
+                &context,
+            )
+            .await
+            .unwrap();
+
+        let result: Vec<MultiPointCollection> = query.try_collect().await?;
+
+        assert_eq!(result.len(), 1);
+
+        let coordinates = MultiPoint::many(vec![
+            (1.850_176_678, 50.965_833_33),
+            (2.170_906_949, 51.021_666_67),
+            (2.933_686_69, 51.23),
+            (3.204_593_64_f64, 51.336_388_89),
+        ])?;
+
+        assert_eq!(
+            result[0],
+            MultiPointCollection::from_data(
+                coordinates,
+                vec![Default::default(); 4],
+                HashMap::new(),
+            )?
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     #[allow(clippy::too_many_lines)]
     async fn ne_10m_ports_columns() -> Result<()> {
@@ -2059,6 +2671,7 @@ mod tests {
                         ],
                         bool: vec![],
                         datetime: vec![],
+                        category: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
@@ -2066,6 +2679,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -2080,6 +2694,7 @@ mod tests {
                     .iter()
                     .cloned()
                     .collect(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -2114,6 +2729,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((1.85, 50.88).into(), (4.82, 52.95).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -2246,11 +2862,13 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     columns: Default::default(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -2288,6 +2906,7 @@ mod tests {
                     )?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -3414,6 +4033,7 @@ mod tests {
                 text: vec!["c".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -3421,6 +4041,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -3436,6 +4057,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -3449,6 +4071,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -3511,6 +4134,7 @@ mod tests {
                 text: vec!["c".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -3518,6 +4142,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -3533,6 +4158,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -3546,6 +4172,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 2.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -3609,11 +4236,13 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     columns: Default::default(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -3752,6 +4381,7 @@ mod tests {
                     spatial_bounds: query_bbox,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context1,
             )
@@ -3786,6 +4416,7 @@ mod tests {
                     spatial_bounds: query_bbox,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -3857,11 +4488,13 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     columns: Default::default(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -3900,6 +4533,7 @@ mod tests {
                     spatial_bounds: query_bbox,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -3937,6 +4571,7 @@ mod tests {
                         text: vec![],
                         bool: vec![],
                         datetime: vec![],
+                        category: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
@@ -3944,11 +4579,13 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPolygon,
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     columns: Default::default(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -3986,6 +4623,7 @@ mod tests {
                     spatial_bounds: query_bbox,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -4035,6 +4673,7 @@ mod tests {
                         text: vec!["txt".to_owned()],
                         bool: vec![],
                         datetime: vec![],
+                        category: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
@@ -4042,6 +4681,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4053,6 +4693,7 @@ mod tests {
                     .iter()
                     .cloned()
                     .collect(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -4090,6 +4731,7 @@ mod tests {
                     spatial_bounds: query_bbox,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -4154,6 +4796,7 @@ mod tests {
                         text: vec!["Name".to_owned()],
                         bool: vec![],
                         datetime: vec![],
+                        category: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
@@ -4161,6 +4804,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4169,6 +4813,7 @@ mod tests {
                         .iter()
                         .cloned()
                         .collect(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -4206,6 +4851,7 @@ mod tests {
                     spatial_bounds: query_bbox,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -4266,6 +4912,7 @@ mod tests {
                         text: vec!["Name".to_owned()],
                         bool: vec![],
                         datetime: vec![],
+                        category: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
@@ -4273,6 +4920,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4281,6 +4929,7 @@ mod tests {
                         .iter()
                         .cloned()
                         .collect(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -4318,6 +4967,7 @@ mod tests {
                     spatial_bounds: query_bbox,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -4378,6 +5028,7 @@ mod tests {
                         text: vec!["Name".to_owned()],
                         bool: vec![],
                         datetime: vec![],
+                        category: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
@@ -4385,6 +5036,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4393,6 +5045,7 @@ mod tests {
                         .iter()
                         .cloned()
                         .collect(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -4430,6 +5083,7 @@ mod tests {
                     spatial_bounds: query_bbox,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -4491,6 +5145,7 @@ mod tests {
                         text: vec!["Name".to_owned()],
                         bool: vec![],
                         datetime: vec!["DateTime".to_owned()],
+                        category: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
@@ -4498,6 +5153,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4509,6 +5165,7 @@ mod tests {
                     .iter()
                     .cloned()
                     .collect(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -4546,6 +5203,7 @@ mod tests {
                     spatial_bounds: query_bbox,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -4603,6 +5261,7 @@ mod tests {
                         text: vec![],
                         bool: vec!["bool".to_owned()],
                         datetime: vec![],
+                        category: vec![],
                         rename: None,
                     }),
                     force_ogr_time_filter: false,
@@ -4610,6 +5269,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Abort,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: VectorResultDescriptor {
                     data_type: VectorDataType::MultiPoint,
@@ -4618,6 +5278,7 @@ mod tests {
                         .iter()
                         .cloned()
                         .collect(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             }),
@@ -4655,6 +5316,7 @@ mod tests {
                     spatial_bounds: query_bbox,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.).unwrap(),
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -4703,6 +5365,7 @@ mod tests {
                 text: vec!["c".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: Some(
                     [("a".to_owned(), "foo".to_owned())]
                         .iter()
@@ -4715,6 +5378,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -4730,6 +5394,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -4743,6 +5408,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -4803,6 +5469,7 @@ mod tests {
                 text: vec!["c".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -4810,6 +5477,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -4825,6 +5493,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -4847,6 +5516,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -4898,6 +5568,7 @@ mod tests {
                 text: vec!["c".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -4905,6 +5576,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -4920,6 +5592,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -4940,6 +5613,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -4991,6 +5665,7 @@ mod tests {
                 text: vec!["c".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -4998,6 +5673,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -5013,6 +5689,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -5033,6 +5710,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -5084,6 +5762,7 @@ mod tests {
                 text: vec!["c".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: Some(
                     [("a".to_string(), "d".to_string())]
                         .into_iter()
@@ -5095,6 +5774,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -5110,6 +5790,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -5130,6 +5811,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -5181,6 +5863,7 @@ mod tests {
                 text: vec!["c".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -5188,6 +5871,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -5203,6 +5887,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -5226,6 +5911,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -5286,6 +5972,7 @@ mod tests {
                 text: vec!["c".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -5293,6 +5980,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -5308,6 +5996,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -5337,6 +6026,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -5388,6 +6078,7 @@ mod tests {
                 text: vec!["c".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -5395,6 +6086,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: Some("\"c\" = 'foo'".to_string()),
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -5410,6 +6102,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -5430,6 +6123,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -5481,6 +6175,7 @@ mod tests {
                 text: vec!["name".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -5488,6 +6183,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -5502,6 +6198,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -5522,6 +6219,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -5556,6 +6254,7 @@ mod tests {
                 text: vec!["name".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -5563,6 +6262,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -5577,6 +6277,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -5600,6 +6301,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -5634,6 +6336,7 @@ mod tests {
                 text: vec!["name".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -5641,6 +6344,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: Some("\"name\" = 'Bangkok'".to_string()),
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -5655,6 +6359,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -5675,6 +6380,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -5709,6 +6415,7 @@ mod tests {
                 text: vec!["name".to_string()],
                 bool: vec![],
                 datetime: vec![],
+                category: vec![],
                 rename: None,
             }),
             force_ogr_time_filter: false,
@@ -5716,6 +6423,7 @@ mod tests {
             on_error: OgrSourceErrorSpec::Ignore,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         };
 
         let info = StaticMetaData {
@@ -5730,6 +6438,7 @@ mod tests {
                 .iter()
                 .cloned()
                 .collect(),
+                column_metadata: Default::default(),
             },
             phantom: Default::default(),
         };
@@ -5750,6 +6459,7 @@ mod tests {
                     spatial_bounds: BoundingBox2D::new((0., 0.).into(), (1., 1.).into())?,
                     time_interval: Default::default(),
                     spatial_resolution: SpatialResolution::new(1., 1.)?,
+                    spatial_filter: None,
                 },
                 &context,
             )
@@ -5764,4 +6474,124 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn duration_beyond_time_range() {
+        let start = TimeInstance::MAX - 1;
+
+        assert_eq!(
+            (start
+                + OgrSourceDurationSpec::Value(TimeStep {
+                    granularity: TimeGranularity::Years,
+                    step: 1,
+                }))
+            .unwrap(),
+            TimeInstance::MAX
+        );
+        assert_eq!(
+            (TimeInstance::from_millis(0).unwrap() + OgrSourceDurationSpec::Infinite).unwrap(),
+            TimeInstance::MAX
+        );
+    }
+    #[test]
+    fn period_durations() {
+        let day = |date: &str| -> TimeInstance {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .unwrap()
+                .and_hms(0, 0, 0)
+                .into()
+        };
+
+        assert_eq!(
+            OgrSourceDurationSpec::Period {
+                granularity: TimeGranularity::Months
+            }
+            .interval(day("2020-05-01"))
+            .unwrap(),
+            TimeInterval::new(day("2020-05-01"), day("2020-06-01")).unwrap()
+        );
+
+        // the period contains the instant
+        assert_eq!(
+            OgrSourceDurationSpec::Period {
+                granularity: TimeGranularity::Days
+            }
+            .interval(day("2020-05-31") + 3_600_000)
+            .unwrap(),
+            TimeInterval::new(day("2020-05-31"), day("2020-06-01")).unwrap()
+        );
+
+        assert_eq!(
+            OgrSourceEndInclusion::Inclusive {
+                granularity: TimeGranularity::Days
+            }
+            .exclusive_end(day("2020-05-31"))
+            .unwrap(),
+            day("2020-06-01")
+        );
+        assert_eq!(
+            OgrSourceEndInclusion::Exclusive
+                .exclusive_end(day("2020-05-31"))
+                .unwrap(),
+            day("2020-05-31")
+        );
+    }
+
+    #[test]
+    fn duration_formats() {
+        let start: TimeInstance = NaiveDate::from_ymd(2020, 1, 31).and_hms(0, 0, 0).into();
+
+        assert_eq!(
+            OgrSourceDurationFormat::Millis
+                .end(start, FieldValue::IntegerValue(1000))
+                .unwrap(),
+            start + 1000
+        );
+        assert_eq!(
+            OgrSourceDurationFormat::Seconds
+                .end(start, FieldValue::RealValue(1.5))
+                .unwrap(),
+            start + 1500
+        );
+        assert_eq!(
+            OgrSourceDurationFormat::Steps {
+                granularity: TimeGranularity::Months
+            }
+            .end(start, FieldValue::StringValue("1".to_string()))
+            .unwrap(),
+            NaiveDate::from_ymd(2020, 2, 29).and_hms(0, 0, 0).into()
+        );
+        assert_eq!(
+            OgrSourceDurationFormat::Iso8601
+                .end(start, FieldValue::StringValue("P1MT1H30M".to_string()))
+                .unwrap(),
+            NaiveDate::from_ymd(2020, 2, 29).and_hms(1, 30, 0).into()
+        );
+
+        assert!(OgrSourceDurationFormat::Steps {
+            granularity: TimeGranularity::Days
+        }
+        .end(start, FieldValue::RealValue(-1.))
+        .is_err());
+        assert!(OgrSourceDurationFormat::Iso8601
+            .end(start, FieldValue::StringValue("1 day".to_string()))
+            .is_err());
+    }
+
+    #[test]
+    fn iso8601_durations() {
+        assert_eq!(parse_iso8601_duration("P1Y2M"), Some((14, 0)));
+        assert_eq!(
+            parse_iso8601_duration("P1W2DT3H4M5.5S"),
+            Some((0, 9 * 86_400_000 + 3 * 3_600_000 + 4 * 60_000 + 5500))
+        );
+        assert_eq!(parse_iso8601_duration("PT0S"), Some((0, 0)));
+
+        assert_eq!(parse_iso8601_duration("P"), None);
+        assert_eq!(parse_iso8601_duration("PT"), None);
+        assert_eq!(parse_iso8601_duration("P1"), None);
+        assert_eq!(parse_iso8601_duration("P1H"), None);
+        assert_eq!(parse_iso8601_duration("P1.5D"), None);
+        assert_eq!(parse_iso8601_duration("1D"), None);
+    }
 }
@@ -0,0 +1,304 @@
+use crate::engine::{
+    Cacheability, InitializedRasterOperator, OperatorDatasets, QueryContext, QueryProcessor,
+    RasterOperator, RasterResultDescriptor, SourceOperator, TypedRasterQueryProcessor,
+};
+use crate::util::Result;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::operations::reproject::{
+    project_coordinates_fail_tolerant, CoordinateProjection, CoordinateProjector,
+};
+use geoengine_datatypes::primitives::{
+    Coordinate2D, Measurement, RasterQueryRectangle, SpatialPartition2D, TimeInterval,
+};
+use geoengine_datatypes::raster::{
+    Grid2D, GridIdx2D, RasterDataType, RasterTile2D, TileInformation, TilingSpecification,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use serde::{Deserialize, Serialize};
+
+/// The radius of the sphere with the same surface area as the WGS 84 ellipsoid
+const AUTHALIC_EARTH_RADIUS: f64 = 6_371_007.180_918_476;
+/// The squared eccentricity of the WGS 84 ellipsoid
+const WGS84_ECCENTRICITY_SQUARED: f64 = 0.006_694_379_990_141_316;
+
+const OUT_NO_DATA_VALUE: f64 = f64::NAN;
+
+/// The `PixelArea` source produces a raster of the true area of each pixel in square meters,
+/// e.g., to weight the pixels of land cover classes in EPSG:4326 by their area.
+///
+/// The corners of each pixel are projected to geographic coordinates and the area of the
+/// resulting quadrilateral is computed on the authalic sphere, i.e., areas of pixels that are
+/// aligned to meridians and parallels are exact for the WGS 84 ellipsoid.
+/// Pixels whose corners cannot be projected are no data.
+pub type PixelArea = SourceOperator<PixelAreaParams>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PixelAreaParams {
+    /// The spatial reference of the grid whose pixel areas are computed
+    pub spatial_reference: SpatialReference,
+}
+
+impl OperatorDatasets for PixelAreaParams {
+    fn datasets_collect(&self, _datasets: &mut Vec<DatasetId>) {}
+}
+
+#[typetag::serde]
+#[async_trait]
+impl RasterOperator for PixelArea {
+    async fn initialize(
+        self: Box<Self>,
+        context: &dyn crate::engine::ExecutionContext,
+    ) -> Result<Box<dyn InitializedRasterOperator>> {
+        // fail early if the spatial reference cannot be projected to geographic coordinates
+        CoordinateProjector::from_known_srs(
+            self.params.spatial_reference,
+            SpatialReference::epsg_4326(),
+        )?;
+
+        Ok(InitializedPixelArea {
+            result_descriptor: RasterResultDescriptor {
+                data_type: RasterDataType::F64,
+                spatial_reference: self.params.spatial_reference.into(),
+                measurement: Measurement::continuous("area".to_string(), Some("m²".to_string())),
+                no_data_value: Some(OUT_NO_DATA_VALUE),
+                dimensions: vec![],
+            },
+            spatial_reference: self.params.spatial_reference,
+            tiling_specification: context.tiling_specification(),
+        }
+        .boxed())
+    }
+}
+
+pub struct InitializedPixelArea {
+    result_descriptor: RasterResultDescriptor,
+    spatial_reference: SpatialReference,
+    tiling_specification: TilingSpecification,
+}
+
+impl InitializedRasterOperator for InitializedPixelArea {
+    fn result_descriptor(&self) -> &RasterResultDescriptor {
+        &self.result_descriptor
+    }
+
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::Deterministic
+    }
+
+    fn query_processor(&self) -> Result<TypedRasterQueryProcessor> {
+        Ok(TypedRasterQueryProcessor::F64(
+            PixelAreaProcessor {
+                spatial_reference: self.spatial_reference,
+                tiling_specification: self.tiling_specification,
+            }
+            .boxed(),
+        ))
+    }
+}
+
+pub struct PixelAreaProcessor {
+    spatial_reference: SpatialReference,
+    tiling_specification: TilingSpecification,
+}
+
+#[async_trait]
+impl QueryProcessor for PixelAreaProcessor {
+    type Output = RasterTile2D<f64>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let tiling_strategy = self
+            .tiling_specification
+            .strategy(query.spatial_resolution.x, -query.spatial_resolution.y);
+
+        let spatial_reference = self.spatial_reference;
+        let time = query.time_interval;
+
+        let stream = stream::iter(tiling_strategy.tile_information_iterator(query.spatial_bounds))
+            .then(move |tile_info| {
+                crate::util::spawn_blocking_with_thread_pool(ctx.thread_pool().clone(), move || {
+                    pixel_area_tile(spatial_reference, tile_info, time)
+                })
+            })
+            .map(|result| match result {
+                Ok(tile) => tile,
+                Err(e) => Err(e.into()),
+            });
+
+        Ok(stream.boxed())
+    }
+}
+
+/// Computes the areas of the pixels of a tile
+fn pixel_area_tile(
+    spatial_reference: SpatialReference,
+    tile_info: TileInformation,
+    time: TimeInterval,
+) -> Result<RasterTile2D<f64>> {
+    let [height, width] = tile_info.tile_size_in_pixels.shape_array;
+    let geo_transform = tile_info.tile_geo_transform();
+
+    let corners: Vec<Coordinate2D> = (0..=height as isize)
+        .flat_map(|y| (0..=width as isize).map(move |x| GridIdx2D::from([y, x])))
+        .map(|idx| geo_transform.grid_idx_to_upper_left_coordinate_2d(idx))
+        .collect();
+
+    let corners = if spatial_reference == SpatialReference::epsg_4326() {
+        corners.into_iter().map(Some).collect()
+    } else {
+        let projector =
+            CoordinateProjector::from_known_srs(spatial_reference, SpatialReference::epsg_4326())?;
+        project_coordinates_fail_tolerant(&corners, &projector)
+    };
+
+    let corner = |y: usize, x: usize| corners[y * (width + 1) + x];
+
+    let data = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (y, x)))
+        .map(|(y, x)| {
+            let quadrilateral = [
+                corner(y, x),
+                corner(y, x + 1),
+                corner(y + 1, x + 1),
+                corner(y + 1, x),
+            ];
+
+            quadrilateral
+                .into_iter()
+                .collect::<Option<Vec<_>>>()
+                .map_or(OUT_NO_DATA_VALUE, |ring| spherical_area(&ring))
+        })
+        .collect();
+
+    let grid = Grid2D::new(tile_info.tile_size_in_pixels, data, Some(OUT_NO_DATA_VALUE))
+        .expect("the data has the shape of the tile");
+
+    Ok(RasterTile2D::new_with_tile_info(
+        time,
+        tile_info,
+        grid.into(),
+    ))
+}
+
+/// The sine of the authalic latitude, i.e., the latitude on the authalic sphere that preserves
+/// the areas of the ellipsoid, for a geodetic latitude in degrees
+fn sin_authalic_latitude(latitude: f64) -> f64 {
+    let e2 = WGS84_ECCENTRICITY_SQUARED;
+    let e = e2.sqrt();
+
+    let q = |sin: f64| {
+        (1. - e2)
+            * (sin / (1. - e2 * sin * sin) - ((1. - e * sin) / (1. + e * sin)).ln() / (2. * e))
+    };
+
+    q(latitude.to_radians().sin()) / q(1.)
+}
+
+/// The area of a ring of geographic coordinates in square meters
+fn spherical_area(ring: &[Coordinate2D]) -> f64 {
+    let n = ring.len();
+
+    let sum: f64 = (0..n)
+        .map(|i| {
+            let previous = ring[(i + n - 1) % n];
+            let next = ring[(i + 1) % n];
+            (next.x - previous.x).to_radians() * sin_authalic_latitude(ring[i].y)
+        })
+        .sum();
+
+    (sum * AUTHALIC_EARTH_RADIUS * AUTHALIC_EARTH_RADIUS / 2.).abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext};
+    use futures::TryStreamExt;
+    use geoengine_datatypes::primitives::SpatialResolution;
+    use geoengine_datatypes::spatial_reference::SpatialReferenceAuthority;
+
+    async fn pixel_areas(
+        spatial_reference: SpatialReference,
+        query: RasterQueryRectangle,
+        tile_size: [usize; 2],
+    ) -> Vec<f64> {
+        let operator = PixelArea {
+            params: PixelAreaParams { spatial_reference },
+        }
+        .boxed()
+        .initialize(&MockExecutionContext::new_with_tiling_spec(
+            TilingSpecification::new((0., 0.).into(), tile_size.into()),
+        ))
+        .await
+        .unwrap();
+
+        let processor = operator.query_processor().unwrap().get_f64().unwrap();
+
+        let ctx = MockQueryContext::new(1.into());
+        let tiles: Vec<RasterTile2D<f64>> = processor
+            .raster_query(query, &ctx)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(tiles.len(), 1);
+        tiles[0].grid_array.clone().into_materialized_grid().data
+    }
+
+    #[tokio::test]
+    async fn it_computes_geographic_pixel_areas() {
+        let areas = pixel_areas(
+            SpatialReference::epsg_4326(),
+            RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new_unchecked((0., 2.).into(), (2., 0.).into()),
+                time_interval: TimeInterval::default(),
+                spatial_resolution: SpatialResolution::one(),
+                spatial_filter: (),
+            },
+            [2, 2],
+        )
+        .await;
+
+        // the areas of 1° cells of the WGS 84 ellipsoid
+        let equator = 12_308_463_893.975;
+        let north = 12_304_814_950.073;
+
+        for (area, expected) in areas.into_iter().zip([north, north, equator, equator]) {
+            float_cmp::assert_approx_eq!(f64, area, expected, epsilon = 10.);
+        }
+    }
+
+    #[tokio::test]
+    async fn it_computes_projected_pixel_areas() {
+        // 1 km pixels in UTM zone 32N close to its central meridian
+        let areas = pixel_areas(
+            SpatialReference::new(SpatialReferenceAuthority::Epsg, 32632),
+            RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new_unchecked(
+                    (500_000., 5_002_000.).into(),
+                    (502_000., 5_000_000.).into(),
+                ),
+                time_interval: TimeInterval::default(),
+                spatial_resolution: SpatialResolution::new_unchecked(1000., 1000.),
+                spatial_filter: (),
+            },
+            [2, 2],
+        )
+        .await;
+
+        for area in areas {
+            // the scale factor of UTM is 0.9996 at the central meridian
+            assert!((area - 1_000_800.).abs() < 1_000., "{}", area);
+        }
+    }
+}
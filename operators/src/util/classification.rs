@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of values that natural breaks are computed on.
+/// Larger inputs are sampled evenly, since the optimization is quadratic in the number of values.
+const NATURAL_BREAKS_MAX_VALUES: usize = 1000;
+
+/// A method for dividing values into ordered classes, e.g., for classified map symbologies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClassificationMethod {
+    /// Classes of equal width between the minimum and the maximum value
+    EqualInterval,
+    /// Classes with (roughly) the same number of values
+    Quantile,
+    /// Classes that minimize the variance within each class (Jenks)
+    NaturalBreaks,
+}
+
+/// Computes the breaks that divide the `values` into at most `classes` classes.
+///
+/// Each break is the lower bound of a class, except for the first class that has no lower bound.
+/// Thus, there are `classes - 1` breaks unless there are fewer values than classes.
+/// Non-finite values are ignored.
+pub fn class_breaks(method: ClassificationMethod, values: &[f64], classes: usize) -> Vec<f64> {
+    let mut values: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+
+    if values.is_empty() || classes < 2 {
+        return Vec::new();
+    }
+
+    values.sort_unstable_by(|a, b| a.partial_cmp(b).expect("values are finite"));
+
+    match method {
+        ClassificationMethod::EqualInterval => equal_interval_breaks(&values, classes),
+        ClassificationMethod::Quantile => quantile_breaks(&values, classes),
+        ClassificationMethod::NaturalBreaks => natural_breaks(&values, classes),
+    }
+}
+
+/// The class of a `value` for the `breaks` of `class_breaks`
+pub fn class_index(breaks: &[f64], value: f64) -> usize {
+    breaks.partition_point(|&class_break| class_break <= value)
+}
+
+fn equal_interval_breaks(sorted_values: &[f64], classes: usize) -> Vec<f64> {
+    let min = sorted_values[0];
+    let max = sorted_values[sorted_values.len() - 1];
+
+    (1..classes)
+        .map(|i| {
+            let fraction = i as f64 / classes as f64;
+            min * (1. - fraction) + max * fraction
+        })
+        .collect()
+}
+
+fn quantile_breaks(sorted_values: &[f64], classes: usize) -> Vec<f64> {
+    let n = sorted_values.len();
+    let classes = classes.min(n);
+
+    (1..classes)
+        .map(|i| sorted_values[i * n / classes])
+        .collect()
+}
+
+/// Fisher-Jenks natural breaks that minimize the sum of squared deviations within the classes
+fn natural_breaks(sorted_values: &[f64], classes: usize) -> Vec<f64> {
+    let values = sample_evenly(sorted_values, NATURAL_BREAKS_MAX_VALUES);
+
+    let n = values.len();
+    let classes = classes.min(n);
+
+    // prefix sums for computing the squared deviations of a range in constant time
+    let mut sums = vec![0.; n + 1];
+    let mut squared_sums = vec![0.; n + 1];
+    for (i, value) in values.iter().enumerate() {
+        sums[i + 1] = sums[i] + value;
+        squared_sums[i + 1] = squared_sums[i] + value * value;
+    }
+    let deviation = |start: usize, end: usize| {
+        let sum = sums[end + 1] - sums[start];
+        let squared_sum = squared_sums[end + 1] - squared_sums[start];
+        squared_sum - sum * sum / (end + 1 - start) as f64
+    };
+
+    // `costs[c][j]` is the minimal deviation of `c + 1` classes for the values `0..=j` and
+    // `starts[c][j]` is the start of the last of these classes
+    let mut costs = vec![vec![f64::INFINITY; n]; classes];
+    let mut starts = vec![vec![0; n]; classes];
+
+    for (j, cost) in costs[0].iter_mut().enumerate() {
+        *cost = deviation(0, j);
+    }
+
+    for c in 1..classes {
+        for j in c..n {
+            for start in c..=j {
+                let cost = costs[c - 1][start - 1] + deviation(start, j);
+                if cost < costs[c][j] {
+                    costs[c][j] = cost;
+                    starts[c][j] = start;
+                }
+            }
+        }
+    }
+
+    let mut breaks = Vec::with_capacity(classes - 1);
+    let mut end = n - 1;
+    for c in (1..classes).rev() {
+        let start = starts[c][end];
+        breaks.push(values[start]);
+        end = start - 1;
+    }
+    breaks.reverse();
+
+    breaks
+}
+
+/// Takes at most `max_values` values in equal steps
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn sample_evenly(sorted_values: &[f64], max_values: usize) -> Vec<f64> {
+    if sorted_values.len() <= max_values {
+        return sorted_values.to_vec();
+    }
+
+    let step = sorted_values.len() as f64 / max_values as f64;
+
+    (0..max_values)
+        .map(|i| sorted_values[(i as f64 * step) as usize])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_interval() {
+        assert_eq!(
+            class_breaks(
+                ClassificationMethod::EqualInterval,
+                &[0., 10., 2., f64::NAN],
+                5
+            ),
+            vec![2., 4., 6., 8.]
+        );
+    }
+
+    #[test]
+    fn quantile() {
+        let values: Vec<f64> = (0..8).map(f64::from).collect();
+
+        assert_eq!(
+            class_breaks(ClassificationMethod::Quantile, &values, 4),
+            vec![2., 4., 6.]
+        );
+        assert_eq!(
+            class_breaks(ClassificationMethod::Quantile, &[1., 2.], 4),
+            vec![2.]
+        );
+    }
+
+    #[test]
+    fn natural_breaks() {
+        let values = [1., 2., 1.5, 10., 11., 10.5, 30., 31.];
+
+        let breaks = class_breaks(ClassificationMethod::NaturalBreaks, &values, 3);
+
+        assert_eq!(breaks, vec![10., 30.]);
+        assert_eq!(class_index(&breaks, 1.), 0);
+        assert_eq!(class_index(&breaks, 10.), 1);
+        assert_eq!(class_index(&breaks, 100.), 2);
+    }
+
+    #[test]
+    fn empty() {
+        assert!(class_breaks(ClassificationMethod::NaturalBreaks, &[], 3).is_empty());
+        assert!(class_breaks(ClassificationMethod::Quantile, &[1., 2.], 1).is_empty());
+    }
+}
@@ -0,0 +1,479 @@
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use geoengine_datatypes::collections::FeatureCollectionInfos;
+
+use crate::error::Error;
+use crate::util::Result;
+
+/// An arithmetic expression over the numeric attributes of features, e.g., `sqrt(area) / 100`.
+///
+/// Expressions consist of numbers, columns, the operators `+`, `-`, `*`, `/`, `**` and the
+/// functions `abs`, `sqrt`, `exp`, `ln`, `log10`, `round`, `floor`, `ceil`, `min` and `max`.
+/// Column names that are no identifiers are quoted, e.g., `"area (km²)" * 2`.
+///
+/// The result of a feature is missing if one of its columns is null or the result is not finite.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureExpression {
+    expression: String,
+    node: Node,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Constant(f64),
+    Column(String),
+    Negate(Box<Node>),
+    Operation {
+        operator: Operator,
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+    Function {
+        function: Function,
+        arguments: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Power,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    Abs,
+    Sqrt,
+    Exp,
+    Ln,
+    Log10,
+    Round,
+    Floor,
+    Ceil,
+    Min,
+    Max,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "abs" => Self::Abs,
+            "sqrt" => Self::Sqrt,
+            "exp" => Self::Exp,
+            "ln" => Self::Ln,
+            "log10" => Self::Log10,
+            "round" => Self::Round,
+            "floor" => Self::Floor,
+            "ceil" => Self::Ceil,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            _ => return None,
+        })
+    }
+
+    fn is_variadic(self) -> bool {
+        matches!(self, Self::Min | Self::Max)
+    }
+
+    fn apply(self, arguments: &[f64]) -> f64 {
+        match (self, arguments) {
+            (Self::Abs, [a]) => a.abs(),
+            (Self::Sqrt, [a]) => a.sqrt(),
+            (Self::Exp, [a]) => a.exp(),
+            (Self::Ln, [a]) => a.ln(),
+            (Self::Log10, [a]) => a.log10(),
+            (Self::Round, [a]) => a.round(),
+            (Self::Floor, [a]) => a.floor(),
+            (Self::Ceil, [a]) => a.ceil(),
+            (Self::Min, arguments) => arguments.iter().copied().fold(f64::INFINITY, f64::min),
+            (Self::Max, arguments) => arguments.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            _ => f64::NAN,
+        }
+    }
+}
+
+impl FeatureExpression {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let mut parser = Parser {
+            expression,
+            chars: expression.char_indices().peekable(),
+        };
+
+        let node = parser.expression()?;
+
+        parser.skip_whitespace();
+        if let Some(&(position, _)) = parser.chars.peek() {
+            return Err(parser.error(format!("unexpected input at position {}", position)));
+        }
+
+        Ok(Self {
+            expression: expression.to_string(),
+            node,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.expression
+    }
+
+    /// The columns that the expression refers to
+    pub fn columns(&self) -> Vec<&str> {
+        fn collect<'n>(node: &'n Node, columns: &mut Vec<&'n str>) {
+            match node {
+                Node::Constant(_) => {}
+                Node::Column(column) => {
+                    if !columns.contains(&column.as_str()) {
+                        columns.push(column);
+                    }
+                }
+                Node::Negate(node) => collect(node, columns),
+                Node::Operation { left, right, .. } => {
+                    collect(left, columns);
+                    collect(right, columns);
+                }
+                Node::Function { arguments, .. } => {
+                    for argument in arguments {
+                        collect(argument, columns);
+                    }
+                }
+            }
+        }
+
+        let mut columns = Vec::new();
+        collect(&self.node, &mut columns);
+        columns
+    }
+
+    /// Evaluates the expression for each feature of the `collection`
+    pub fn evaluate<C: FeatureCollectionInfos>(&self, collection: &C) -> Result<Vec<Option<f64>>> {
+        let columns = self
+            .columns()
+            .into_iter()
+            .map(|column| {
+                let values: Vec<Option<f64>> =
+                    collection.data(column)?.float_options_iter().collect();
+                Ok((column, values))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        Ok((0..collection.len())
+            .map(|feature| {
+                evaluate(&self.node, &columns, feature).filter(|value| value.is_finite())
+            })
+            .collect())
+    }
+}
+
+fn evaluate(node: &Node, columns: &HashMap<&str, Vec<Option<f64>>>, feature: usize) -> Option<f64> {
+    Some(match node {
+        Node::Constant(value) => *value,
+        Node::Column(column) => columns.get(column.as_str())?[feature]?,
+        Node::Negate(node) => -evaluate(node, columns, feature)?,
+        Node::Operation {
+            operator,
+            left,
+            right,
+        } => {
+            let left = evaluate(left, columns, feature)?;
+            let right = evaluate(right, columns, feature)?;
+
+            match operator {
+                Operator::Add => left + right,
+                Operator::Subtract => left - right,
+                Operator::Multiply => left * right,
+                Operator::Divide => left / right,
+                Operator::Power => left.powf(right),
+            }
+        }
+        Node::Function {
+            function,
+            arguments,
+        } => {
+            let arguments = arguments
+                .iter()
+                .map(|argument| evaluate(argument, columns, feature))
+                .collect::<Option<Vec<f64>>>()?;
+
+            function.apply(&arguments)
+        }
+    })
+}
+
+/// A recursive descent parser with the usual operator precedences.
+/// `**` binds stronger than an unary minus and is right-associative.
+struct Parser<'e> {
+    expression: &'e str,
+    chars: Peekable<CharIndices<'e>>,
+}
+
+impl<'e> Parser<'e> {
+    fn error(&self, reason: String) -> Error {
+        Error::InvalidFeatureExpression {
+            expression: self.expression.to_string(),
+            reason,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    /// Consumes `token` if it is the next input
+    fn consume(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+
+        let position = match self.chars.peek() {
+            Some((position, _)) => *position,
+            None => return false,
+        };
+
+        if !self.expression[position..].starts_with(token) {
+            return false;
+        }
+
+        for _ in token.chars() {
+            self.chars.next();
+        }
+
+        true
+    }
+
+    fn expression(&mut self) -> Result<Node> {
+        let mut node = self.product()?;
+
+        loop {
+            let operator = if self.consume("+") {
+                Operator::Add
+            } else if self.consume("-") {
+                Operator::Subtract
+            } else {
+                return Ok(node);
+            };
+
+            node = Node::Operation {
+                operator,
+                left: Box::new(node),
+                right: Box::new(self.product()?),
+            };
+        }
+    }
+
+    fn product(&mut self) -> Result<Node> {
+        let mut node = self.unary()?;
+
+        loop {
+            let operator = if self.consume("*") {
+                Operator::Multiply
+            } else if self.consume("/") {
+                Operator::Divide
+            } else {
+                return Ok(node);
+            };
+
+            node = Node::Operation {
+                operator,
+                left: Box::new(node),
+                right: Box::new(self.unary()?),
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Result<Node> {
+        if self.consume("-") {
+            return Ok(Node::Negate(Box::new(self.unary()?)));
+        }
+
+        let base = self.primary()?;
+
+        if self.consume("**") {
+            return Ok(Node::Operation {
+                operator: Operator::Power,
+                left: Box::new(base),
+                right: Box::new(self.unary()?),
+            });
+        }
+
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Node> {
+        let expression = self.expression;
+
+        self.skip_whitespace();
+
+        let (start, c) = match self.chars.peek() {
+            Some(next) => *next,
+            None => return Err(self.error("unexpected end of expression".to_string())),
+        };
+
+        if self.consume("(") {
+            let node = self.expression()?;
+
+            if !self.consume(")") {
+                return Err(self.error("missing `)`".to_string()));
+            }
+
+            return Ok(node);
+        }
+
+        if c == '"' {
+            self.chars.next();
+
+            let mut column = String::new();
+            loop {
+                match self.chars.next() {
+                    Some((_, '"')) => return Ok(Node::Column(column)),
+                    Some((_, c)) => column.push(c),
+                    None => return Err(self.error("missing closing `\"`".to_string())),
+                }
+            }
+        }
+
+        if c.is_ascii_digit() || c == '.' {
+            let end = self.take_while(|c| c.is_ascii_digit() || c == '.');
+            let number = &expression[start..end];
+
+            return number
+                .parse()
+                .map(Node::Constant)
+                .map_err(|_| self.error(format!("invalid number `{}`", number)));
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let end = self.take_while(|c| c.is_alphanumeric() || c == '_');
+            let identifier = &expression[start..end];
+
+            if !self.consume("(") {
+                return Ok(Node::Column(identifier.to_string()));
+            }
+
+            let function = Function::from_name(identifier)
+                .ok_or_else(|| self.error(format!("unknown function `{}`", identifier)))?;
+
+            let mut arguments = vec![self.expression()?];
+            while self.consume(",") {
+                arguments.push(self.expression()?);
+            }
+
+            if !self.consume(")") {
+                return Err(self.error("missing `)`".to_string()));
+            }
+
+            if !function.is_variadic() && arguments.len() != 1 {
+                return Err(self.error(format!("function `{}` expects one argument", identifier)));
+            }
+
+            return Ok(Node::Function {
+                function,
+                arguments,
+            });
+        }
+
+        Err(self.error(format!("unexpected `{}` at position {}", c, start)))
+    }
+
+    /// Consumes characters while `predicate` holds and returns the end position
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> usize {
+        while self.chars.next_if(|(_, c)| predicate(*c)).is_some() {}
+
+        self.chars
+            .peek()
+            .map_or(self.expression.len(), |(position, _)| *position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::collections::DataCollection;
+    use geoengine_datatypes::primitives::{FeatureData, NoGeometry, TimeInterval};
+
+    fn collection() -> DataCollection {
+        DataCollection::from_data(
+            vec![NoGeometry; 3],
+            vec![TimeInterval::default(); 3],
+            [
+                (
+                    "area".to_string(),
+                    FeatureData::NullableFloat(vec![Some(4.), Some(16.), None]),
+                ),
+                (
+                    "population count".to_string(),
+                    FeatureData::Int(vec![10, 20, 30]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap()
+    }
+
+    fn evaluate(expression: &str) -> Vec<Option<f64>> {
+        FeatureExpression::parse(expression)
+            .unwrap()
+            .evaluate(&collection())
+            .unwrap()
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(evaluate("1 + 2 * 3"), vec![Some(7.); 3]);
+        assert_eq!(evaluate("(1 + 2) * 3"), vec![Some(9.); 3]);
+        assert_eq!(evaluate("-2 ** 2"), vec![Some(-4.); 3]);
+        assert_eq!(evaluate("2 ** 3 ** 2"), vec![Some(512.); 3]);
+        assert_eq!(evaluate("8 / 4 / 2"), vec![Some(1.); 3]);
+        assert_eq!(evaluate("1 / 0"), vec![None; 3]);
+    }
+
+    #[test]
+    fn columns_and_functions() {
+        assert_eq!(
+            evaluate("sqrt(area) + \"population count\""),
+            vec![Some(12.), Some(24.), None]
+        );
+        assert_eq!(
+            evaluate("max(area, 5, \"population count\" / 2)"),
+            vec![Some(5.), Some(16.), None]
+        );
+
+        assert_eq!(
+            FeatureExpression::parse("area * area + \"population count\"")
+                .unwrap()
+                .columns(),
+            vec!["area", "population count"]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        for expression in [
+            "",
+            "1 +",
+            "(1",
+            "foo(1)",
+            "sqrt(1, 2)",
+            "1 1",
+            "\"area",
+            "1.2.3",
+        ] {
+            assert!(
+                matches!(
+                    FeatureExpression::parse(expression),
+                    Err(Error::InvalidFeatureExpression { .. })
+                ),
+                "{}",
+                expression
+            );
+        }
+
+        assert!(FeatureExpression::parse("foo")
+            .unwrap()
+            .evaluate(&collection())
+            .is_err());
+    }
+}
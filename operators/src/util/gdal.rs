@@ -1,17 +1,29 @@
 use std::{
     convert::TryInto,
+    ffi::{CStr, CString},
+    os::raw::c_int,
     path::{Path, PathBuf},
+    sync::RwLock,
 };
 
-use gdal::{raster::GDALDataType, Dataset, DatasetOptions};
+use gdal::{
+    errors::GdalError,
+    raster::GDALDataType,
+    vector::{Geometry, OGRwkbGeometryType, ToGdal},
+    Dataset, DatasetOptions, Metadata,
+};
 use geoengine_datatypes::{
     dataset::{DatasetId, InternalDatasetId},
     hashmap,
-    primitives::{Measurement, TimeGranularity, TimeInstance, TimeStep},
+    primitives::{
+        Coordinate2D, Measurement, MultiPolygon, TimeGranularity, TimeInstance, TimeStep,
+    },
     raster::RasterDataType,
     spatial_reference::SpatialReference,
     util::Identifier,
 };
+use lazy_static::lazy_static;
+use log::debug;
 use snafu::ResultExt;
 
 use crate::{
@@ -25,6 +37,11 @@ use crate::{
     util::Result,
 };
 
+lazy_static! {
+    static ref PATH_SPECIFIC_CONFIG_OPTIONS: RwLock<Vec<(String, Vec<(String, String)>)>> =
+        RwLock::new(vec![]);
+}
+
 // TODO: move test helper somewhere else?
 #[allow(clippy::missing_panics_doc)]
 pub fn create_ndvi_meta_data() -> GdalMetaDataRegular {
@@ -56,12 +73,14 @@ pub fn create_ndvi_meta_data() -> GdalMetaDataRegular {
             properties_mapping: None,
             gdal_open_options: None,
             gdal_config_options: None,
+            apply_scale_offset: false,
         },
         result_descriptor: RasterResultDescriptor {
             data_type: RasterDataType::U8,
             spatial_reference: SpatialReference::epsg_4326().into(),
             measurement: Measurement::Unitless,
             no_data_value,
+            dimensions: vec![],
         },
     }
 }
@@ -73,6 +92,81 @@ pub fn add_ndvi_dataset(ctx: &mut MockExecutionContext) -> DatasetId {
     id
 }
 
+/// Registers Gdal config options, e.g., credentials, that are only set while accessing files whose
+/// path starts with `path_prefix`. Registering options for the same prefix replaces them.
+///
+/// This mimics `VSISetPathSpecificOption`, which is not available before GDAL 3.6.
+///
+/// # Panics
+/// Panics if the lock of the options is poisoned
+pub fn set_path_specific_config_options(path_prefix: &str, options: Vec<(String, String)>) {
+    let mut path_specific_options = PATH_SPECIFIC_CONFIG_OPTIONS
+        .write()
+        .expect("config options lock must not be poisoned");
+
+    path_specific_options.retain(|(prefix, _)| prefix != path_prefix);
+    path_specific_options.push((path_prefix.to_string(), options));
+}
+
+/// Returns the path specific config options that apply to `path`
+///
+/// # Panics
+/// Panics if the lock of the options is poisoned
+pub fn path_specific_config_options(path: &Path) -> Vec<(String, String)> {
+    let path = path.to_string_lossy();
+
+    PATH_SPECIFIC_CONFIG_OPTIONS
+        .read()
+        .expect("config options lock must not be poisoned")
+        .iter()
+        .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+        .flat_map(|(_, options)| options.iter().cloned())
+        .collect()
+}
+
+/// Set thread local gdal options and revert them on drop
+pub struct TemporaryGdalThreadLocalConfigOptions {
+    original_configs: Vec<(String, Option<String>)>,
+}
+
+impl TemporaryGdalThreadLocalConfigOptions {
+    /// Set thread local gdal options and revert them on drop
+    pub fn new(configs: &[(String, String)]) -> Result<Self> {
+        let mut original_configs = vec![];
+
+        for (key, value) in configs {
+            let old = gdal::config::get_thread_local_config_option(key, "").map(|value| {
+                if value.is_empty() {
+                    None
+                } else {
+                    Some(value)
+                }
+            })?;
+
+            // TODO: check if overriding existing config (local & global) is ok for the given key
+            gdal::config::set_thread_local_config_option(key, value)?;
+            // values are not logged as they may contain credentials
+            debug!("set {}", key);
+
+            original_configs.push((key.clone(), old));
+        }
+
+        Ok(Self { original_configs })
+    }
+}
+
+impl Drop for TemporaryGdalThreadLocalConfigOptions {
+    fn drop(&mut self) {
+        for (key, value) in &self.original_configs {
+            if let Some(value) = value {
+                let _result = gdal::config::set_thread_local_config_option(key, value);
+            } else {
+                let _result = gdal::config::clear_thread_local_config_option(key);
+            }
+        }
+    }
+}
+
 /// Opens a Gdal Dataset with the given `path`.
 /// Other crates should use this method for Gdal Dataset access as a workaround to avoid strange errors.
 pub fn gdal_open_dataset(path: &Path) -> Result<Dataset> {
@@ -89,6 +183,10 @@ pub fn gdal_open_dataset_ex(path: &Path, dataset_options: DatasetOptions) -> Res
         dataset_options
     };
 
+    // reverts the path specific configs on drop
+    let _path_specific_configs =
+        TemporaryGdalThreadLocalConfigOptions::new(&path_specific_config_options(path))?;
+
     Dataset::open_ex(path, dataset_options).context(error::Gdal)
 }
 
@@ -122,9 +220,76 @@ pub fn raster_descriptor_from_dataset(
         spatial_reference: spatial_ref.into(),
         measurement: Measurement::Unitless,
         no_data_value: rasterband.no_data_value(),
+        dimensions: vec![],
     })
 }
 
+/// Adapts the `descriptor` of the `band` in `dataset` to loading physical values, i.e., with
+/// `apply_scale_offset` in the `GdalDatasetParameters`. The data type becomes a floating point type
+/// and unitless measurements become continuous measurements with the unit of the band.
+pub fn scaled_raster_descriptor(
+    descriptor: RasterResultDescriptor,
+    dataset: &Dataset,
+    band: isize,
+) -> Result<RasterResultDescriptor> {
+    let rasterband = &dataset.rasterband(band)?;
+
+    let data_type = match descriptor.data_type {
+        RasterDataType::U8
+        | RasterDataType::I8
+        | RasterDataType::U16
+        | RasterDataType::I16
+        | RasterDataType::F32 => RasterDataType::F32,
+        RasterDataType::U32
+        | RasterDataType::I32
+        | RasterDataType::U64
+        | RasterDataType::I64
+        | RasterDataType::F64 => RasterDataType::F64,
+    };
+
+    let measurement = match descriptor.measurement {
+        Measurement::Unitless => {
+            let description = rasterband.description().unwrap_or_default();
+            Measurement::continuous(
+                if description.is_empty() {
+                    "value".to_string()
+                } else {
+                    description
+                },
+                raster_band_unit(dataset, band),
+            )
+        }
+        measurement => measurement,
+    };
+
+    Ok(RasterResultDescriptor {
+        data_type,
+        measurement,
+        ..descriptor
+    })
+}
+
+/// The unit of the values of the `band` in `dataset`, e.g., `K` or `m`, if it is set
+fn raster_band_unit(dataset: &Dataset, band: isize) -> Option<String> {
+    let unit = unsafe {
+        // the gdal crate does not expose the unit of raster bands
+        let c_rasterband =
+            gdal_sys::GDALGetRasterBand(dataset.c_dataset(), band as std::os::raw::c_int);
+        if c_rasterband.is_null() {
+            return None;
+        }
+
+        let c_unit = gdal_sys::GDALGetRasterUnitType(c_rasterband);
+        if c_unit.is_null() {
+            return None;
+        }
+
+        CStr::from_ptr(c_unit).to_string_lossy().into_owned()
+    };
+
+    (!unit.is_empty()).then(|| unit)
+}
+
 /// Create `GdalDatasetParameters` from the infos in the given `dataset` and its `band`.
 /// `path` is the location of the actual data, `band_out` allows optionally specifying a different
 /// band in the resulting parameters, otherwise `band` is used.
@@ -148,5 +313,168 @@ pub fn gdal_parameters_from_dataset(
         height: rasterband.y_size(),
         gdal_open_options: open_options,
         gdal_config_options: None,
+        apply_scale_offset: false,
     })
 }
+
+/// The set operations on polygons that are provided by GDAL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolygonSetOperation {
+    Intersection,
+    Union,
+    Difference,
+}
+
+/// Computes the exact union of all `polygons` using GDAL.
+///
+/// Overlapping and adjacent polygons are merged, disjoint polygons remain separate parts of the result.
+pub fn gdal_union_polygons(polygons: &geo::MultiPolygon<f64>) -> Result<MultiPolygon> {
+    let geometry = polygons.to_gdal()?;
+
+    let union = unsafe { gdal_sys::OGR_G_UnionCascaded(geometry.c_geometry()) };
+
+    unsafe { polygons_from_ogr_handle(union) }?.ok_or(Error::PolygonSetOperation)
+}
+
+/// Applies the set `operation` to the polygons `a` and `b` using GDAL.
+///
+/// Only the polygonal parts of the result are kept, e.g., the intersection of polygons that only
+/// touch each other is `None`.
+pub fn gdal_polygon_set_operation(
+    operation: PolygonSetOperation,
+    a: &geo::MultiPolygon<f64>,
+    b: &geo::MultiPolygon<f64>,
+) -> Result<Option<MultiPolygon>> {
+    let a = a.to_gdal()?;
+    let b = b.to_gdal()?;
+
+    let result = unsafe {
+        match operation {
+            PolygonSetOperation::Intersection => {
+                gdal_sys::OGR_G_Intersection(a.c_geometry(), b.c_geometry())
+            }
+            PolygonSetOperation::Union => gdal_sys::OGR_G_Union(a.c_geometry(), b.c_geometry()),
+            PolygonSetOperation::Difference => {
+                gdal_sys::OGR_G_Difference(a.c_geometry(), b.c_geometry())
+            }
+        }
+    };
+
+    unsafe { polygons_from_ogr_handle(result) }
+}
+
+/// Collects the polygons of a geometry that was created by GDAL and frees it afterwards.
+///
+/// # Safety
+///
+/// `handle` must be null or a valid geometry that is owned by the caller.
+unsafe fn polygons_from_ogr_handle(handle: gdal_sys::OGRGeometryH) -> Result<Option<MultiPolygon>> {
+    if handle.is_null() {
+        return Err(Error::PolygonSetOperation);
+    }
+
+    // the wrapper does not own the geometry, so we have to free it manually
+    let geometry = Geometry::lazy_feature_geometry();
+    geometry.set_c_geometry(handle);
+
+    let mut polygons = Vec::new();
+    collect_ogr_polygons(&geometry, &mut polygons);
+
+    drop(geometry);
+    gdal_sys::OGR_G_DestroyGeometry(handle);
+
+    if polygons.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(MultiPolygon::new(polygons)?))
+}
+
+fn collect_ogr_polygons(geometry: &Geometry, polygons: &mut Vec<Vec<Vec<Coordinate2D>>>) {
+    match geometry.geometry_type() {
+        OGRwkbGeometryType::wkbPolygon if !geometry.is_empty() => {
+            polygons.push(
+                (0..geometry.geometry_count())
+                    .map(|i| {
+                        unsafe { geometry.get_unowned_geometry(i) }
+                            .get_point_vec()
+                            .into_iter()
+                            .map(|(x, y, _z)| Coordinate2D::new(x, y))
+                            .collect()
+                    })
+                    .collect(),
+            );
+        }
+        OGRwkbGeometryType::wkbMultiPolygon | OGRwkbGeometryType::wkbGeometryCollection => {
+            for i in 0..geometry.geometry_count() {
+                collect_ogr_polygons(&unsafe { geometry.get_unowned_geometry(i) }, polygons);
+            }
+        }
+        // lower-dimensional parts, e.g. shared borders of intersections, are dropped
+        _ => {}
+    }
+}
+
+/// Writes a VRT to `vrt_path` that mosaics the rasters at `file_paths`.
+///
+/// Files in the directory of the VRT (or below) are referenced relative to it, s.t. the VRT and its
+/// files can be moved together.
+#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+pub fn gdal_build_vrt(vrt_path: &Path, file_paths: &[PathBuf]) -> Result<()> {
+    let c_vrt_path = path_to_c_string(vrt_path)?;
+    let c_file_paths = file_paths
+        .iter()
+        .map(|file_path| path_to_c_string(file_path))
+        .collect::<Result<Vec<_>>>()?;
+    let c_file_path_ptrs = c_file_paths
+        .iter()
+        .map(|file_path| file_path.as_ptr())
+        .collect::<Vec<_>>();
+
+    // reverts the path specific configs on drop
+    let path_specific_configs = std::iter::once(vrt_path)
+        .chain(file_paths.iter().map(PathBuf::as_path))
+        .flat_map(path_specific_config_options)
+        .collect::<Vec<_>>();
+    let _path_specific_configs =
+        TemporaryGdalThreadLocalConfigOptions::new(&path_specific_configs)?;
+
+    let mut usage_error: c_int = 0;
+    let c_dataset = unsafe {
+        gdal_sys::GDALBuildVRT(
+            c_vrt_path.as_ptr(),
+            c_file_path_ptrs.len() as c_int,
+            std::ptr::null_mut(),
+            c_file_path_ptrs.as_ptr(),
+            std::ptr::null(),
+            &mut usage_error,
+        )
+    };
+
+    if c_dataset.is_null() {
+        let msg = unsafe { CStr::from_ptr(gdal_sys::CPLGetLastErrorMsg()) }
+            .to_string_lossy()
+            .into_owned();
+        unsafe { gdal_sys::CPLErrorReset() };
+
+        return Err(GdalError::NullPointer {
+            method_name: "GDALBuildVRT",
+            msg,
+        }
+        .into());
+    }
+
+    // closing the dataset writes the VRT to disk
+    drop(unsafe { Dataset::from_c_dataset(c_dataset) });
+
+    Ok(())
+}
+
+fn path_to_c_string(file_path: &Path) -> Result<CString> {
+    file_path
+        .to_str()
+        .and_then(|file_path| CString::new(file_path).ok())
+        .ok_or_else(|| Error::InvalidGdalFilePath {
+            file_path: file_path.to_owned(),
+        })
+}
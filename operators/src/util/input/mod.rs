@@ -3,9 +3,11 @@ mod multi_raster_or_vector;
 mod raster_or_vector;
 mod string_or_number;
 mod string_or_number_range;
+mod vector_or_table;
 
 pub use float_with_nan_serde::{float as float_with_nan, float_option as float_option_with_nan};
 pub use multi_raster_or_vector::MultiRasterOrVectorOperator;
 pub use raster_or_vector::RasterOrVectorOperator;
 pub use string_or_number::StringOrNumber;
 pub use string_or_number_range::StringOrNumberRange;
+pub use vector_or_table::VectorOrTableOperator;
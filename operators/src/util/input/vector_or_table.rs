@@ -0,0 +1,41 @@
+use crate::engine::{OperatorDatasets, TableOperator, TypedOperator, VectorOperator};
+use geoengine_datatypes::dataset::DatasetId;
+use serde::{Deserialize, Serialize};
+
+/// It is either a `VectorOperator` or a `TableOperator`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum VectorOrTableOperator {
+    Vector(Box<dyn VectorOperator>),
+    Table(Box<dyn TableOperator>),
+}
+
+impl From<VectorOrTableOperator> for TypedOperator {
+    fn from(operator: VectorOrTableOperator) -> Self {
+        match operator {
+            VectorOrTableOperator::Vector(operator) => Self::Vector(operator),
+            VectorOrTableOperator::Table(operator) => Self::Table(operator),
+        }
+    }
+}
+
+impl From<Box<dyn VectorOperator>> for VectorOrTableOperator {
+    fn from(operator: Box<dyn VectorOperator>) -> Self {
+        Self::Vector(operator)
+    }
+}
+
+impl From<Box<dyn TableOperator>> for VectorOrTableOperator {
+    fn from(operator: Box<dyn TableOperator>) -> Self {
+        Self::Table(operator)
+    }
+}
+
+impl OperatorDatasets for VectorOrTableOperator {
+    fn datasets_collect(&self, datasets: &mut Vec<DatasetId>) {
+        match self {
+            VectorOrTableOperator::Vector(v) => v.datasets_collect(datasets),
+            VectorOrTableOperator::Table(t) => t.datasets_collect(datasets),
+        }
+    }
+}
@@ -15,6 +15,114 @@ where
     (a & b) + ((a ^ b) >> 1)
 }
 
+/// Computes the `percentile` (in `[0, 100]`) of the ascending `sorted_values` by linearly
+/// interpolating between the closest ranks. The result is `NaN` if there are no values.
+pub fn percentile_of_sorted(sorted_values: &[f64], percentile: f64) -> f64 {
+    match sorted_values.len() {
+        0 => f64::NAN,
+        1 => sorted_values[0],
+        len => {
+            let rank = percentile / 100. * (len - 1) as f64;
+            let lower = rank.floor() as usize;
+            let upper = rank.ceil() as usize;
+            let weight = rank - lower as f64;
+
+            sorted_values[lower] * (1. - weight) + sorted_values[upper] * weight
+        }
+    }
+}
+
+/// The natural logarithm of the gamma function for `x > 0` by the Lanczos approximation
+pub fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .fold(1.000_000_000_190_015, |series, (i, coefficient)| {
+            series + coefficient / (x + 1. + i as f64)
+        });
+
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+/// The regularized incomplete beta function `I_x(a, b)` for `a, b > 0`
+pub fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0. {
+        return 0.;
+    }
+    if x >= 1. {
+        return 1.;
+    }
+
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1. - x).ln()).exp();
+
+    // the continued fraction converges quickly on this side of the mean
+    if x < (a + 1.) / (a + b + 2.) {
+        front * beta_continued_fraction(x, a, b) / a
+    } else {
+        1. - front * beta_continued_fraction(1. - x, b, a) / b
+    }
+}
+
+/// Evaluates the continued fraction of the incomplete beta function by Lentz's method
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 300;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let not_tiny = |value: f64| if value.abs() < TINY { TINY } else { value };
+
+    let mut c = 1.;
+    let mut d = 1. / not_tiny(1. - (a + b) * x / (a + 1.));
+    let mut fraction = d;
+
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+
+        let even = m * (b - m) * x / ((a + 2. * m - 1.) * (a + 2. * m));
+        d = 1. / not_tiny(1. + even * d);
+        c = not_tiny(1. + even / c);
+        fraction *= d * c;
+
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2. * m) * (a + 2. * m + 1.));
+        d = 1. / not_tiny(1. + odd * d);
+        c = not_tiny(1. + odd / c);
+        let delta = d * c;
+        fraction *= delta;
+
+        if (delta - 1.).abs() < EPSILON {
+            break;
+        }
+    }
+
+    fraction
+}
+
+/// The two-sided p-value of the statistic `t` of a Student's t-test with `degrees_of_freedom`
+pub fn student_t_p_value(t: f64, degrees_of_freedom: f64) -> f64 {
+    if t.is_nan() {
+        return f64::NAN;
+    }
+
+    regularized_incomplete_beta(
+        degrees_of_freedom / (degrees_of_freedom + t * t),
+        degrees_of_freedom / 2.,
+        0.5,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -28,4 +136,48 @@ mod tests {
 
         assert_eq!(average_floor(i64::MIN, i64::MAX), -1);
     }
+
+    #[test]
+    fn percentiles() {
+        assert!(percentile_of_sorted(&[], 50.).is_nan());
+        float_cmp::assert_approx_eq!(f64, percentile_of_sorted(&[3.], 90.), 3.);
+
+        let values = [1., 2., 3., 4., 5.];
+        float_cmp::assert_approx_eq!(f64, percentile_of_sorted(&values, 0.), 1.);
+        float_cmp::assert_approx_eq!(f64, percentile_of_sorted(&values, 50.), 3.);
+        float_cmp::assert_approx_eq!(f64, percentile_of_sorted(&values, 100.), 5.);
+        float_cmp::assert_approx_eq!(
+            f64,
+            percentile_of_sorted(&values, 90.),
+            4.6,
+            epsilon = 1e-10
+        );
+        float_cmp::assert_approx_eq!(f64, percentile_of_sorted(&[1., 2., 3., 4.], 50.), 2.5);
+    }
+
+    #[test]
+    fn gamma_function() {
+        float_cmp::assert_approx_eq!(f64, ln_gamma(1.), 0., epsilon = 1e-9);
+        float_cmp::assert_approx_eq!(f64, ln_gamma(5.), 24_f64.ln(), epsilon = 1e-9);
+        float_cmp::assert_approx_eq!(
+            f64,
+            ln_gamma(0.5),
+            std::f64::consts::PI.sqrt().ln(),
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn t_test_p_values() {
+        float_cmp::assert_approx_eq!(f64, student_t_p_value(0., 5.), 1., epsilon = 1e-10);
+        // the t-distribution with one degree of freedom is the Cauchy distribution
+        float_cmp::assert_approx_eq!(f64, student_t_p_value(1., 1.), 0.5, epsilon = 1e-10);
+        float_cmp::assert_approx_eq!(
+            f64,
+            student_t_p_value(-2.228_138_851_986_274, 10.),
+            0.05,
+            epsilon = 1e-8
+        );
+        float_cmp::assert_approx_eq!(f64, student_t_p_value(f64::INFINITY, 3.), 0.);
+    }
 }
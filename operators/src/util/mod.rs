@@ -1,8 +1,11 @@
 mod async_util;
+pub mod classification;
+pub mod feature_expression;
 pub mod gdal;
 pub mod input;
 pub mod math;
 pub mod number_statistics;
+pub mod pixel_time_series;
 pub mod raster_stream_to_geotiff;
 pub mod raster_stream_to_png;
 mod rayon;
@@ -10,6 +13,8 @@ pub mod statistics;
 pub mod stream_zip;
 pub mod string_token;
 pub mod sunpos;
+pub mod table_stream_to_bytes;
+pub mod vector_stream_to_png;
 
 use crate::error::Error;
 use std::collections::HashSet;
@@ -61,6 +61,29 @@ impl NumberStatistics {
         self.value_nan_count += batch_size;
     }
 
+    /// Merges the statistics of another sample into these statistics.
+    ///
+    /// This uses the parallel variant of Welford's algorithm by Chan et al.
+    pub fn merge(&mut self, other: &NumberStatistics) {
+        self.value_nan_count += other.value_nan_count;
+
+        if other.value_count == 0 {
+            return;
+        }
+
+        self.min_value = f64::min(self.min_value, other.min_value);
+        self.max_value = f64::max(self.max_value, other.max_value);
+
+        let value_count = self.value_count + other.value_count;
+        let delta = other.mean_value - self.mean_value;
+
+        self.mean_value += delta * (other.value_count as f64) / (value_count as f64);
+        self.m2 += other.m2
+            + delta * delta * (self.value_count as f64) * (other.value_count as f64)
+                / (value_count as f64);
+        self.value_count = value_count;
+    }
+
     pub fn count(&self) -> usize {
         self.value_count
     }
@@ -152,4 +175,28 @@ mod tests {
         assert_eq!(number_statistics.count(), 1);
         assert_eq!(number_statistics.nan_count(), 2);
     }
+
+    #[test]
+    fn merge() {
+        let mut first = NumberStatistics::default();
+        let mut second = NumberStatistics::default();
+
+        for &v in &[2, 4, 4] {
+            first.add(v);
+        }
+        for &v in &[4, 5, 5, 7, 9] {
+            second.add(v);
+        }
+        second.add_no_data();
+
+        first.merge(&second);
+        first.merge(&NumberStatistics::default());
+
+        assert_eq!(first.count(), 8);
+        assert_eq!(first.nan_count(), 1);
+        float_cmp::assert_approx_eq!(f64, first.min(), 2.);
+        float_cmp::assert_approx_eq!(f64, first.max(), 9.);
+        float_cmp::assert_approx_eq!(f64, first.mean(), 5., epsilon = 1e-10);
+        float_cmp::assert_approx_eq!(f64, first.var(), 4., epsilon = 1e-10);
+    }
 }
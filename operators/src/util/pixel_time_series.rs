@@ -0,0 +1,174 @@
+use crate::engine::{QueryContext, RasterQueryProcessor};
+use crate::util::Result;
+use futures::TryStreamExt;
+use geoengine_datatypes::primitives::{RasterQueryRectangle, SpatialPartitioned, TimeInterval};
+use geoengine_datatypes::raster::{
+    EmptyGrid2D, Grid2D, GridOrEmpty, GridSize, NoDataValue, RasterTile2D, TileInformation,
+};
+
+/// The time series of all pixels of a tile, i.e., of the tiles at one position at all time steps
+/// of a query, for per-pixel temporal analyses.
+///
+/// The values are stored pixel by pixel, s.t. the series of each pixel is a contiguous slice that
+/// is ordered by time. No data is `NaN`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelTimeSeries {
+    time_steps: Vec<TimeInterval>,
+    values: Vec<f64>,
+}
+
+impl PixelTimeSeries {
+    /// Assembles the series from the `tiles` at the position of `tile_info` in any order
+    pub fn new(mut tiles: Vec<RasterTile2D<f64>>, tile_info: TileInformation) -> Self {
+        tiles.sort_by_key(|tile| tile.time.start());
+
+        let number_of_time_steps = tiles.len();
+        let number_of_pixels = tile_info.tile_size_in_pixels.number_of_elements();
+
+        let mut values = vec![f64::NAN; number_of_pixels * number_of_time_steps];
+        for (step, tile) in tiles.iter().enumerate() {
+            if let GridOrEmpty::Grid(grid) = &tile.grid_array {
+                for (pixel, &value) in grid.data.iter().enumerate() {
+                    if !grid.is_no_data(value) {
+                        values[pixel * number_of_time_steps + step] = value;
+                    }
+                }
+            }
+        }
+
+        Self {
+            time_steps: tiles.iter().map(|tile| tile.time).collect(),
+            values,
+        }
+    }
+
+    /// Queries the tiles of the `processor` at the position of `tile_info` within the time
+    /// interval of the `query`
+    pub async fn query(
+        processor: &dyn RasterQueryProcessor<RasterType = f64>,
+        tile_info: TileInformation,
+        query: RasterQueryRectangle,
+        ctx: &dyn QueryContext,
+    ) -> Result<Self> {
+        let tile_query = RasterQueryRectangle {
+            spatial_bounds: tile_info.spatial_partition(),
+            ..query
+        };
+
+        let tiles = processor
+            .raster_query(tile_query, ctx)
+            .await?
+            .try_collect()
+            .await?;
+
+        Ok(Self::new(tiles, tile_info))
+    }
+
+    /// The time steps of the series in ascending order
+    pub fn time_steps(&self) -> &[TimeInterval] {
+        &self.time_steps
+    }
+
+    /// The values of a `pixel` in the order of the time steps
+    pub fn pixel(&self, pixel: usize) -> &[f64] {
+        let len = self.time_steps.len();
+        &self.values[pixel * len..(pixel + 1) * len]
+    }
+
+    /// The time steps and values of a `pixel` where it has data
+    pub fn observations(&self, pixel: usize) -> impl DoubleEndedIterator<Item = (usize, f64)> + '_ {
+        self.pixel(pixel)
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, value)| !value.is_nan())
+    }
+
+    /// Whether both series have the same time steps, e.g., as the series of co-registered rasters
+    pub fn is_aligned_with(&self, other: &Self) -> bool {
+        self.time_steps.len() == other.time_steps.len()
+            && self
+                .time_steps
+                .iter()
+                .zip(&other.time_steps)
+                .all(|(a, b)| a.intersects(b))
+    }
+}
+
+/// Creates the tile of the per-pixel results of a temporal analysis whose pixels without a result
+/// are `NaN`. The tile is empty if no pixel has a result.
+pub fn tile_from_pixels(
+    time: TimeInterval,
+    tile_info: TileInformation,
+    data: Vec<f32>,
+) -> RasterTile2D<f32> {
+    let shape = tile_info.tile_size_in_pixels;
+
+    let grid = if data.iter().all(|value| value.is_nan()) {
+        EmptyGrid2D::new(shape, f32::NAN).into()
+    } else {
+        Grid2D::new(shape, data, Some(f32::NAN))
+            .expect("the data has the shape of the tile")
+            .into()
+    };
+
+    RasterTile2D::new_with_tile_info(time, tile_info, grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::raster::GridOrEmpty2D;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[test]
+    fn it_assembles_the_series_of_each_pixel() {
+        let tile_info = TileInformation {
+            global_tile_position: [0, 0].into(),
+            tile_size_in_pixels: [1, 2].into(),
+            global_geo_transform: TestDefault::test_default(),
+        };
+
+        let tile = |time: i64, grid_array: GridOrEmpty2D<f64>| {
+            RasterTile2D::new_with_tile_info(
+                TimeInterval::new_unchecked(time, time + 1),
+                tile_info,
+                grid_array,
+            )
+        };
+
+        let series = PixelTimeSeries::new(
+            vec![
+                tile(
+                    2,
+                    Grid2D::new([1, 2].into(), vec![3., 0.], Some(0.))
+                        .unwrap()
+                        .into(),
+                ),
+                tile(0, EmptyGrid2D::new([1, 2].into(), 0.).into()),
+                tile(
+                    1,
+                    Grid2D::new([1, 2].into(), vec![1., 2.], Some(0.))
+                        .unwrap()
+                        .into(),
+                ),
+            ],
+            tile_info,
+        );
+
+        assert_eq!(
+            series.time_steps(),
+            &[
+                TimeInterval::new_unchecked(0, 1),
+                TimeInterval::new_unchecked(1, 2),
+                TimeInterval::new_unchecked(2, 3)
+            ]
+        );
+        assert_eq!(
+            series.observations(0).collect::<Vec<_>>(),
+            vec![(1, 1.), (2, 3.)]
+        );
+        assert_eq!(series.observations(1).collect::<Vec<_>>(), vec![(1, 2.)]);
+        assert!(series.pixel(1)[2].is_nan());
+    }
+}
@@ -479,6 +479,7 @@ mod tests {
                     query_bbox.size_x() / 600.,
                     query_bbox.size_y() / 600.,
                 ),
+                spatial_filter: (),
             },
             ctx,
             GdalGeoTiffDatasetMetadata {
@@ -531,6 +532,7 @@ mod tests {
                     query_bbox.size_x() / 600.,
                     query_bbox.size_y() / 600.,
                 ),
+                spatial_filter: (),
             },
             ctx,
             GdalGeoTiffDatasetMetadata {
@@ -583,6 +585,7 @@ mod tests {
                     query_bbox.size_x() / 600.,
                     query_bbox.size_y() / 600.,
                 ),
+                spatial_filter: (),
             },
             ctx,
             GdalGeoTiffDatasetMetadata {
@@ -638,6 +641,7 @@ mod tests {
                     query_bbox.size_x() / 600.,
                     query_bbox.size_y() / 600.,
                 ),
+                spatial_filter: (),
             },
             ctx,
             GdalGeoTiffDatasetMetadata {
@@ -693,6 +697,7 @@ mod tests {
                     query_bbox.size_x() / 600.,
                     query_bbox.size_y() / 600.,
                 ),
+                spatial_filter: (),
             },
             ctx,
             GdalGeoTiffDatasetMetadata {
@@ -742,6 +747,7 @@ mod tests {
                     0.228_716_645_489_199_48,
                     0.226_407_384_987_887_26,
                 ),
+                spatial_filter: (),
             },
             ctx,
             GdalGeoTiffDatasetMetadata {
@@ -1,6 +1,6 @@
 use futures::StreamExt;
 use geoengine_datatypes::{
-    operations::image::{Colorizer, RgbaColor, ToPng},
+    operations::image::{Colorizer, ImageEncoding, PngOptions, RgbaColor, ToPng},
     primitives::{AxisAlignedRectangle, RasterQueryRectangle, TimeInterval},
     raster::{Blit, EmptyGrid2D, GeoTransform, Grid2D, Pixel, RasterTile2D},
 };
@@ -20,13 +20,23 @@ pub async fn raster_stream_to_png_bytes<T, C: QueryContext>(
     time: Option<TimeInterval>,
     colorizer: Option<Colorizer>,
     no_data_value: Option<T>,
+    png_options: PngOptions,
+    encoding: ImageEncoding,
 ) -> Result<Vec<u8>>
 where
     T: Pixel,
 {
     let colorizer = colorizer.unwrap_or(default_colorizer_gradient::<T>()?);
 
-    let tile_stream = processor.query(query_rect, &query_ctx).await?;
+    // if the results are known to have no data, the empty image is encoded without querying
+    let tile_stream = if processor
+        .raster_query_is_empty(query_rect, &query_ctx)
+        .await?
+    {
+        futures::stream::empty().boxed()
+    } else {
+        processor.query(query_rect, &query_ctx).await?
+    };
 
     let x_query_resolution = query_rect.spatial_bounds.size_x() / f64::from(width);
     let y_query_resolution = query_rect.spatial_bounds.size_y() / f64::from(height);
@@ -75,7 +85,9 @@ where
         })
         .await?;
 
-    Ok(output_tile.grid_array.to_png(width, height, &colorizer)?)
+    Ok(output_tile
+        .grid_array
+        .to_image_bytes(width, height, &colorizer, png_options, encoding)?)
 }
 
 /// Method to generate a default `Colorizer`.
@@ -134,6 +146,7 @@ mod tests {
                 time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000)
                     .unwrap(),
                 spatial_resolution: SpatialResolution::zero_point_one(),
+                spatial_filter: (),
             },
             ctx,
             600,
@@ -141,6 +154,8 @@ mod tests {
             None,
             None,
             Some(0),
+            PngOptions::default(),
+            ImageEncoding::Png,
         )
         .await
         .unwrap();
@@ -1,5 +1,7 @@
 use num_traits::AsPrimitive;
-use snafu::Snafu;
+use snafu::{ensure, Snafu};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::ops::Range;
 
@@ -10,6 +12,8 @@ pub enum StatisticsError {
     Initialization { reason: String },
     #[snafu(display("Cannot compute statistics on empty sample."))]
     Empty,
+    #[snafu(display("Cannot merge estimators with different parameters."))]
+    IncompatibleMerge,
 }
 
 /// Single quantile estimation with the P^2 algorithm
@@ -485,9 +489,344 @@ impl PSquareHistogramBucket {
     }
 }
 
+/// Quantile estimation with a merging t-digest
+///
+/// The t-digest summarizes the samples as centroids, i.e., means with a weight. Centroids at
+/// the tails of the distribution are kept small, so that extreme quantiles are estimated accurately,
+/// while centroids near the median may comprise many samples. New samples are buffered and merged
+/// into the centroids when the buffer is full. In contrast to the P^2 algorithm, any quantile can
+/// be estimated after the fact and digests of partial samples can be merged, e.g., for tiles that
+/// were processed in parallel.
+///
+/// For further details, see
+///
+/// T. Dunning and O. Ertl, Computing Extremely Accurate Quantiles Using t-Digests, 2019.
+/// <https://arxiv.org/abs/1902.04023>
+///
+#[derive(Debug, Clone)]
+pub struct TDigest {
+    compression: f64,
+    centroids: Vec<Centroid>,
+    buffer: Vec<f64>,
+    sample_count: u64,
+    min: f64,
+    max: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+impl TDigest {
+    /// Creates an empty digest. A higher `compression` leads to more centroids and
+    /// thus to more accurate estimates. A typical value is 100.
+    ///
+    /// # Errors
+    /// If the `compression` is less than 1.
+    pub fn new(compression: f64) -> Result<TDigest, StatisticsError> {
+        if compression.is_nan() || compression < 1.0 {
+            return Err(StatisticsError::Initialization {
+                reason: "The compression must be at least 1.".to_owned(),
+            });
+        }
+
+        Ok(TDigest {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            sample_count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        })
+    }
+
+    /// Updates the digest with the given sample.
+    ///
+    /// # Note
+    /// A `sample` that does not satisfy `f64::is_finite` is silently ignored.
+    pub fn update<T>(&mut self, sample: T)
+    where
+        T: AsPrimitive<f64>,
+    {
+        let sample = sample.as_();
+
+        if !sample.is_finite() {
+            return;
+        }
+
+        self.sample_count += 1;
+        self.min = f64::min(self.min, sample);
+        self.max = f64::max(self.max, sample);
+
+        self.buffer.push(sample);
+
+        if self.buffer.len() >= 5 * self.compression as usize {
+            self.compress();
+        }
+    }
+
+    /// Merges the samples of the `other` digest into this digest.
+    pub fn merge(&mut self, other: &TDigest) {
+        self.sample_count += other.sample_count;
+        self.min = f64::min(self.min, other.min);
+        self.max = f64::max(self.max, other.max);
+
+        self.centroids.extend_from_slice(&other.centroids);
+        self.buffer.extend_from_slice(&other.buffer);
+
+        self.compress();
+    }
+
+    /// Returns the number of samples seen so far
+    pub fn sample_count(&self) -> u64 {
+        self.sample_count
+    }
+
+    /// Returns the minimum of the samples seen so far
+    pub fn min(&self) -> f64 {
+        if self.sample_count > 0 {
+            self.min
+        } else {
+            f64::NAN
+        }
+    }
+
+    /// Returns the maximum of the samples seen so far
+    pub fn max(&self) -> f64 {
+        if self.sample_count > 0 {
+            self.max
+        } else {
+            f64::NAN
+        }
+    }
+
+    /// Estimates the given quantile of the samples seen so far.
+    /// Returns `NaN` if there are no samples.
+    ///
+    /// # Panics
+    /// If the given quantile is not within the interval [0,1].
+    pub fn quantile(&self, quantile: f64) -> f64 {
+        assert!(
+            (0.0..=1.0).contains(&quantile),
+            "The desired quantile must be in the interval [0,1]"
+        );
+
+        if self.sample_count == 0 {
+            return f64::NAN;
+        }
+
+        if self.buffer.is_empty() {
+            return self.quantile_of_centroids(quantile);
+        }
+
+        let mut digest = self.clone();
+        digest.compress();
+        digest.quantile_of_centroids(quantile)
+    }
+
+    /// Merges the buffered samples into the centroids
+    fn compress(&mut self) {
+        let mut centroids = std::mem::take(&mut self.centroids);
+        centroids.extend(
+            self.buffer
+                .drain(..)
+                .map(|mean| Centroid { mean, weight: 1.0 }),
+        );
+        centroids.sort_unstable_by(|a, b| {
+            a.mean
+                .partial_cmp(&b.mean)
+                .expect("Infinite values are filtered out")
+        });
+
+        let total_weight: f64 = centroids.iter().map(|c| c.weight).sum();
+
+        let mut iter = centroids.into_iter();
+        let mut current = match iter.next() {
+            Some(centroid) => centroid,
+            None => return,
+        };
+        let mut weight_so_far = 0.0;
+
+        for centroid in iter {
+            let proposed_weight = current.weight + centroid.weight;
+
+            // the weight limit is small at the tails and large at the median
+            let q = (weight_so_far + proposed_weight / 2.0) / total_weight;
+            let weight_limit = 4.0 * total_weight * q * (1.0 - q) / self.compression;
+
+            if proposed_weight <= weight_limit {
+                current.mean += (centroid.mean - current.mean) * centroid.weight / proposed_weight;
+                current.weight = proposed_weight;
+            } else {
+                weight_so_far += current.weight;
+                self.centroids.push(current);
+                current = centroid;
+            }
+        }
+
+        self.centroids.push(current);
+    }
+
+    /// Interpolates the quantile between the centroids, which are located at the center of their weight
+    fn quantile_of_centroids(&self, quantile: f64) -> f64 {
+        let index = quantile * self.sample_count as f64;
+
+        let mut previous = (0.0, self.min);
+        let mut weight_so_far = 0.0;
+
+        for centroid in &self.centroids {
+            let center = weight_so_far + centroid.weight / 2.0;
+
+            if index < center {
+                return Self::interpolate(previous, (center, centroid.mean), index);
+            }
+
+            previous = (center, centroid.mean);
+            weight_so_far += centroid.weight;
+        }
+
+        Self::interpolate(previous, (weight_so_far, self.max), index)
+    }
+
+    fn interpolate((x0, y0): (f64, f64), (x1, y1): (f64, f64), x: f64) -> f64 {
+        if x1 <= x0 {
+            return y1;
+        }
+
+        y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+    }
+}
+
+/// Distinct count estimation with `HyperLogLog`
+///
+/// `HyperLogLog` hashes each sample and uses the first bits of the hash to select one of
+/// 2^precision registers. Each register stores the maximum number of leading zeros of the
+/// remaining bits. The memory is constant and the relative standard error of the estimate is
+/// about 1.04 / sqrt(2^precision). Sketches of partial samples can be merged, e.g., for tiles
+/// that were processed in parallel.
+///
+/// For further details, see
+///
+/// P. Flajolet, É. Fusy, O. Gandouet and F. Meunier, `HyperLogLog`: the analysis of a near-optimal
+/// cardinality estimation algorithm, Analysis of Algorithms (AofA), 2007, p. 137-156.
+/// <https://algo.inria.fr/flajolet/Publications/FlFuGaMe07.pdf>
+///
+#[derive(Debug, Clone)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    pub const MIN_PRECISION: u8 = 4;
+    pub const MAX_PRECISION: u8 = 18;
+
+    /// Creates an empty sketch with 2^`precision` registers.
+    ///
+    /// # Errors
+    /// If the `precision` is not within [`Self::MIN_PRECISION`, `Self::MAX_PRECISION`].
+    pub fn new(precision: u8) -> Result<HyperLogLog, StatisticsError> {
+        if !(Self::MIN_PRECISION..=Self::MAX_PRECISION).contains(&precision) {
+            return Err(StatisticsError::Initialization {
+                reason: format!(
+                    "The precision must be in the interval [{}, {}].",
+                    Self::MIN_PRECISION,
+                    Self::MAX_PRECISION
+                ),
+            });
+        }
+
+        Ok(HyperLogLog {
+            precision,
+            registers: vec![0; 1 << precision],
+        })
+    }
+
+    /// Updates the sketch with the given sample.
+    pub fn update<T>(&mut self, sample: &T)
+    where
+        T: Hash + ?Sized,
+    {
+        let mut hasher = DefaultHasher::new();
+        sample.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let register = (hash >> (64 - self.precision)) as usize;
+
+        // the sentinel bit bounds the number of leading zeros of the remaining bits
+        let remaining = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = remaining.leading_zeros() as u8 + 1;
+
+        self.registers[register] = u8::max(self.registers[register], rank);
+    }
+
+    /// Updates the sketch with the given number.
+    ///
+    /// # Note
+    /// A `sample` that is `NaN` is silently ignored.
+    pub fn update_f64(&mut self, sample: f64) {
+        if sample.is_nan() {
+            return;
+        }
+
+        // adding zero maps `-0.0` to `0.0`, so both are counted as the same value
+        self.update(&(sample + 0.0).to_bits());
+    }
+
+    /// Merges the samples of the `other` sketch into this sketch.
+    ///
+    /// # Errors
+    /// If the sketches have different precisions.
+    pub fn merge(&mut self, other: &HyperLogLog) -> Result<(), StatisticsError> {
+        ensure!(self.precision == other.precision, IncompatibleMergeSnafu);
+
+        for (register, &other_register) in self.registers.iter_mut().zip(&other.registers) {
+            *register = u8::max(*register, other_register);
+        }
+
+        Ok(())
+    }
+
+    /// Estimates the number of distinct samples seen so far
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&register| f64::powi(2.0, -i32::from(register)))
+            .sum();
+        let estimate = alpha * m * m / sum;
+
+        let empty_registers = self
+            .registers
+            .iter()
+            .filter(|&&register| register == 0)
+            .count();
+
+        // use linear counting for small cardinalities
+        if estimate <= 2.5 * m && empty_registers > 0 {
+            m * (m / empty_registers as f64).ln()
+        } else {
+            estimate
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::util::statistics::{PSquareHistogram, PSquareQuantileEstimator};
+    use crate::util::statistics::{
+        HyperLogLog, PSquareHistogram, PSquareQuantileEstimator, TDigest,
+    };
     use rand::seq::SliceRandom;
 
     #[test]
@@ -735,4 +1074,110 @@ mod tests {
 
         assert_eq!(samples + 1, estimator.sample_count());
     }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_t_digest_small() {
+        let mut digest = TDigest::new(100.0).unwrap();
+
+        assert!(digest.quantile(0.5).is_nan());
+
+        for v in [4, 2, 6, 1, 5, 3] {
+            digest.update(v);
+        }
+        digest.update(f64::NAN);
+
+        assert_eq!(digest.sample_count(), 6);
+        assert_eq!(digest.quantile(0.0), 1.0);
+        assert_eq!(digest.quantile(0.5), 3.5);
+        assert_eq!(digest.quantile(1.0), 6.0);
+    }
+
+    #[test]
+    fn test_t_digest_large() {
+        // a permutation of 0..10000
+        let data: Vec<u32> = (0..10_000).map(|i| (i * 7919) % 10_000).collect();
+
+        let mut digest = TDigest::new(100.0).unwrap();
+        for &v in &data {
+            digest.update(v);
+        }
+
+        assert!(digest.centroids.len() < 2000);
+
+        assert!((digest.quantile(0.5) - 5000.0).abs() < 100.0);
+        assert!((digest.quantile(0.01) - 100.0).abs() < 10.0);
+        assert!((digest.quantile(0.99) - 9900.0).abs() < 10.0);
+
+        let mut first = TDigest::new(100.0).unwrap();
+        let mut second = TDigest::new(100.0).unwrap();
+        for &v in &data[..3000] {
+            first.update(v);
+        }
+        for &v in &data[3000..] {
+            second.update(v);
+        }
+        first.merge(&second);
+
+        assert_eq!(first.sample_count(), 10_000);
+        assert!((first.quantile(0.5) - 5000.0).abs() < 100.0);
+        assert!((first.quantile(0.01) - 100.0).abs() < 10.0);
+        assert!((first.quantile(0.99) - 9900.0).abs() < 10.0);
+    }
+
+    #[test]
+    fn test_t_digest_bad_compression() {
+        assert!(TDigest::new(0.5).is_err());
+        assert!(TDigest::new(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_hyper_log_log() {
+        let mut sketch = HyperLogLog::new(12).unwrap();
+
+        assert!(sketch.estimate().abs() < f64::EPSILON);
+
+        for _ in 0..3 {
+            for i in 0..10_u64 {
+                sketch.update(&i);
+            }
+        }
+
+        assert_eq!(sketch.estimate().round() as u64, 10);
+
+        let mut first = HyperLogLog::new(12).unwrap();
+        let mut second = HyperLogLog::new(12).unwrap();
+        for i in 0..60_000 {
+            first.update_f64(f64::from(i));
+        }
+        for i in 40_000..100_000 {
+            second.update_f64(f64::from(i));
+        }
+        first.merge(&second).unwrap();
+
+        // the standard error for a precision of 12 is about 1.6%
+        let estimate = first.estimate();
+        assert!((estimate - 100_000.0).abs() < 5_000.0, "{}", estimate);
+    }
+
+    #[test]
+    fn test_hyper_log_log_signed_zero() {
+        let mut sketch = HyperLogLog::new(4).unwrap();
+
+        sketch.update_f64(0.0);
+        sketch.update_f64(-0.0);
+        sketch.update_f64(f64::NAN);
+
+        assert_eq!(sketch.estimate().round() as u64, 1);
+    }
+
+    #[test]
+    fn test_hyper_log_log_bad_precision() {
+        assert!(HyperLogLog::new(3).is_err());
+        assert!(HyperLogLog::new(19).is_err());
+        assert!(HyperLogLog::new(4)
+            .unwrap()
+            .merge(&HyperLogLog::new(5).unwrap())
+            .is_err());
+    }
 }
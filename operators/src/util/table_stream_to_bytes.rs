@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use arrow::datatypes::Schema;
+use arrow::record_batch::RecordBatch;
+use futures::TryStreamExt;
+use geoengine_datatypes::primitives::VectorQueryRectangle;
+
+use crate::engine::{QueryContext, TableQueryProcessor};
+use crate::util::Result;
+
+/// Queries the `processor` and writes all record batches as CSV with a header row.
+///
+/// The `schema` is used for writing the header if the query yields no batches.
+pub async fn table_stream_to_csv_bytes<C: QueryContext>(
+    processor: Box<dyn TableQueryProcessor>,
+    schema: Schema,
+    query_rect: VectorQueryRectangle,
+    query_ctx: C,
+) -> Result<Vec<u8>> {
+    let batches = collect_batches(processor, schema, query_rect, &query_ctx).await?;
+
+    let mut bytes = Vec::new();
+    {
+        let mut writer = arrow::csv::Writer::new(&mut bytes);
+        for batch in &batches {
+            writer.write(batch)?;
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Queries the `processor` and writes all record batches in the Arrow IPC streaming format
+pub async fn table_stream_to_arrow_ipc_bytes<C: QueryContext>(
+    processor: Box<dyn TableQueryProcessor>,
+    schema: Schema,
+    query_rect: VectorQueryRectangle,
+    query_ctx: C,
+) -> Result<Vec<u8>> {
+    let batches = collect_batches(processor, schema.clone(), query_rect, &query_ctx).await?;
+
+    let mut bytes = Vec::new();
+    {
+        let mut writer = arrow::ipc::writer::StreamWriter::try_new(&mut bytes, &schema)?;
+        for batch in &batches {
+            writer.write(batch)?;
+        }
+        writer.finish()?;
+    }
+
+    Ok(bytes)
+}
+
+async fn collect_batches(
+    processor: Box<dyn TableQueryProcessor>,
+    schema: Schema,
+    query_rect: VectorQueryRectangle,
+    query_ctx: &dyn QueryContext,
+) -> Result<Vec<RecordBatch>> {
+    let mut batches: Vec<RecordBatch> = processor
+        .table_query(query_rect, query_ctx)
+        .await?
+        .try_collect()
+        .await?;
+
+    if batches.is_empty() {
+        batches.push(RecordBatch::new_empty(Arc::new(schema)));
+    }
+
+    Ok(batches)
+}
@@ -0,0 +1,515 @@
+use futures::StreamExt;
+use geoengine_datatypes::collections::{
+    FeatureCollection, FeatureCollectionInfos, IntoGeometryIterator, MultiLineStringCollection,
+    MultiPointCollection, MultiPolygonCollection,
+};
+use geoengine_datatypes::operations::image::{Canvas, Colorizer, ImageEncoding, RgbaColor};
+use geoengine_datatypes::primitives::{
+    Geometry, MultiLineStringAccess, MultiPointAccess, MultiPolygonAccess, VectorQueryRectangle,
+};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+
+use crate::engine::{QueryContext, QueryProcessor, VectorQueryProcessor};
+use crate::util::classification::class_index;
+use crate::util::feature_expression::FeatureExpression;
+use crate::util::Result;
+
+/// The style for rendering features as an image.
+///
+/// Points are drawn as circles with a `radius`, lines with the stroke and polygons are filled
+/// and outlined with the stroke. Radii and stroke widths are given in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorStyle {
+    pub fill_color: StyleColor,
+    pub stroke_color: StyleColor,
+    pub stroke_width: StyleNumber,
+    pub radius: StyleNumber,
+}
+
+impl Default for VectorStyle {
+    fn default() -> Self {
+        Self {
+            fill_color: StyleColor::Static(RgbaColor::white()),
+            stroke_color: StyleColor::Static(RgbaColor::black()),
+            stroke_width: StyleNumber::Static(1.),
+            radius: StyleNumber::Static(10.),
+        }
+    }
+}
+
+/// A color that is either fixed or derived from the attributes of each feature
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleColor {
+    Static(RgbaColor),
+    /// Features with missing values get the colorizer's no data color
+    Derived {
+        attribute: String,
+        colorizer: Colorizer,
+    },
+    /// Features with missing values get the colorizer's no data color
+    Expression {
+        expression: FeatureExpression,
+        colorizer: Colorizer,
+    },
+    /// The color of the class of the expression's value, see `class_index`.
+    /// Missing classes get the last color.
+    Classified {
+        expression: FeatureExpression,
+        breaks: Vec<f64>,
+        colors: Vec<RgbaColor>,
+        no_data_color: RgbaColor,
+    },
+}
+
+/// A number that is either fixed or derived from the attributes of each feature
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleNumber {
+    Static(f64),
+    /// The attribute's value times the `factor` or the `default_value` for missing values
+    Derived {
+        attribute: String,
+        factor: f64,
+        default_value: f64,
+    },
+    Expression {
+        expression: FeatureExpression,
+        default_value: f64,
+    },
+    /// The value of the class of the expression's value, see `class_index`.
+    /// Missing classes get the last value.
+    Classified {
+        expression: FeatureExpression,
+        breaks: Vec<f64>,
+        values: Vec<f64>,
+        default_value: f64,
+    },
+}
+
+impl StyleColor {
+    fn resolve<C: FeatureCollectionInfos>(&self, collection: &C) -> Result<Vec<RgbaColor>> {
+        Ok(match self {
+            StyleColor::Static(color) => vec![*color; collection.len()],
+            StyleColor::Derived {
+                attribute,
+                colorizer,
+            } => colorize(collection.data(attribute)?.float_options_iter(), colorizer),
+            StyleColor::Expression {
+                expression,
+                colorizer,
+            } => colorize(expression.evaluate(collection)?.into_iter(), colorizer),
+            StyleColor::Classified {
+                expression,
+                breaks,
+                colors,
+                no_data_color,
+            } => expression
+                .evaluate(collection)?
+                .into_iter()
+                .map(|value| {
+                    value
+                        .and_then(|v| class_value(colors, breaks, v))
+                        .unwrap_or(*no_data_color)
+                })
+                .collect(),
+        })
+    }
+}
+
+impl StyleNumber {
+    fn resolve<C: FeatureCollectionInfos>(&self, collection: &C) -> Result<Vec<f64>> {
+        Ok(match self {
+            StyleNumber::Static(number) => vec![*number; collection.len()],
+            StyleNumber::Derived {
+                attribute,
+                factor,
+                default_value,
+            } => collection
+                .data(attribute)?
+                .float_options_iter()
+                .map(|value| value.map_or(*default_value, |v| v * factor))
+                .collect(),
+            StyleNumber::Expression {
+                expression,
+                default_value,
+            } => expression
+                .evaluate(collection)?
+                .into_iter()
+                .map(|value| value.unwrap_or(*default_value))
+                .collect(),
+            StyleNumber::Classified {
+                expression,
+                breaks,
+                values,
+                default_value,
+            } => expression
+                .evaluate(collection)?
+                .into_iter()
+                .map(|value| {
+                    value
+                        .and_then(|v| class_value(values, breaks, v))
+                        .unwrap_or(*default_value)
+                })
+                .collect(),
+        })
+    }
+}
+
+fn colorize(values: impl Iterator<Item = Option<f64>>, colorizer: &Colorizer) -> Vec<RgbaColor> {
+    let color_mapper = colorizer.create_color_mapper();
+
+    values
+        .map(|value| value.map_or_else(|| colorizer.no_data_color(), |v| color_mapper.call(v)))
+        .collect()
+}
+
+/// The entry of `class_values` for the class of `value`
+fn class_value<T: Copy>(class_values: &[T], breaks: &[f64], value: f64) -> Option<T> {
+    let class = class_index(breaks, value);
+
+    class_values
+        .get(class)
+        .or_else(|| class_values.last())
+        .copied()
+}
+
+/// The style of each feature of a collection
+struct FeatureStyles {
+    fill_colors: Vec<RgbaColor>,
+    stroke_colors: Vec<RgbaColor>,
+    stroke_widths: Vec<f64>,
+    radii: Vec<f64>,
+}
+
+impl FeatureStyles {
+    fn new<C: FeatureCollectionInfos>(style: &VectorStyle, collection: &C) -> Result<Self> {
+        Ok(Self {
+            fill_colors: style.fill_color.resolve(collection)?,
+            stroke_colors: style.stroke_color.resolve(collection)?,
+            stroke_widths: style.stroke_width.resolve(collection)?,
+            radii: style.radius.resolve(collection)?,
+        })
+    }
+}
+
+/// Draws the features of a collection onto a `Canvas`
+pub trait DrawFeatures {
+    fn draw(&self, canvas: &mut Canvas, style: &VectorStyle) -> Result<()>;
+}
+
+impl DrawFeatures for MultiPointCollection {
+    fn draw(&self, canvas: &mut Canvas, style: &VectorStyle) -> Result<()> {
+        let styles = FeatureStyles::new(style, self)?;
+
+        for (i, multi_point) in self.geometries().enumerate() {
+            for point in multi_point.points() {
+                canvas.draw_point(
+                    *point,
+                    styles.radii[i],
+                    styles.fill_colors[i],
+                    styles.stroke_widths[i],
+                    styles.stroke_colors[i],
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl DrawFeatures for MultiLineStringCollection {
+    fn draw(&self, canvas: &mut Canvas, style: &VectorStyle) -> Result<()> {
+        let styles = FeatureStyles::new(style, self)?;
+
+        for (i, multi_line_string) in self.geometries().enumerate() {
+            for line_string in multi_line_string.lines() {
+                canvas.draw_line_string(
+                    line_string,
+                    styles.stroke_widths[i],
+                    styles.stroke_colors[i],
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl DrawFeatures for MultiPolygonCollection {
+    fn draw(&self, canvas: &mut Canvas, style: &VectorStyle) -> Result<()> {
+        let styles = FeatureStyles::new(style, self)?;
+
+        for (i, multi_polygon) in self.geometries().enumerate() {
+            for polygon in multi_polygon.polygons() {
+                canvas.draw_polygon(
+                    polygon,
+                    styles.fill_colors[i],
+                    styles.stroke_widths[i],
+                    styles.stroke_colors[i],
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders the features of a query to a canvas of size `width` x `height` that covers the
+/// query's spatial bounds
+pub async fn vector_stream_to_canvas<G, C: QueryContext>(
+    processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    query_rect: VectorQueryRectangle,
+    query_ctx: C,
+    width: u32,
+    height: u32,
+    style: &VectorStyle,
+) -> Result<Canvas>
+where
+    G: Geometry + ArrowTyped,
+    FeatureCollection<G>: DrawFeatures,
+{
+    let mut canvas = Canvas::new(width, height, query_rect.spatial_bounds);
+
+    let mut collections = processor.query(query_rect, &query_ctx).await?;
+
+    while let Some(collection) = collections.next().await {
+        collection?.draw(&mut canvas, style)?;
+    }
+
+    Ok(canvas)
+}
+
+/// Renders the features of a query to an image of size `width` x `height` that is encoded as
+/// `encoding`
+pub async fn vector_stream_to_png_bytes<G, C: QueryContext>(
+    processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    query_rect: VectorQueryRectangle,
+    query_ctx: C,
+    width: u32,
+    height: u32,
+    style: &VectorStyle,
+    encoding: ImageEncoding,
+) -> Result<Vec<u8>>
+where
+    G: Geometry + ArrowTyped,
+    FeatureCollection<G>: DrawFeatures,
+{
+    let canvas =
+        vector_stream_to_canvas(processor, query_rect, query_ctx, width, height, style).await?;
+
+    Ok(canvas.to_image_bytes(encoding)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{MockExecutionContext, MockQueryContext, VectorOperator};
+    use crate::mock::MockFeatureCollectionSource;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, FeatureData, MultiLineString, MultiPoint, MultiPolygon, SpatialResolution,
+        TimeInterval,
+    };
+    use geoengine_datatypes::util::test::TestDefault;
+    use std::convert::TryInto;
+
+    fn query_rect() -> VectorQueryRectangle {
+        VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap(),
+            time_interval: TimeInterval::default(),
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn points_with_derived_colors() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(1.5, 8.5), (8.5, 1.5), (5.5, 5.5)]).unwrap(),
+            vec![TimeInterval::default(); 3],
+            [(
+                "value".to_string(),
+                FeatureData::NullableFloat(vec![Some(0.), Some(1.), None]),
+            )]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+
+        let processor = MockFeatureCollectionSource::single(collection)
+            .boxed()
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_point()
+            .unwrap();
+
+        let style = VectorStyle {
+            fill_color: StyleColor::Derived {
+                attribute: "value".to_string(),
+                colorizer: Colorizer::linear_gradient(
+                    vec![
+                        (0.0, RgbaColor::black()).try_into().unwrap(),
+                        (1.0, RgbaColor::white()).try_into().unwrap(),
+                    ],
+                    RgbaColor::pink(),
+                    RgbaColor::transparent(),
+                )
+                .unwrap(),
+            },
+            stroke_color: StyleColor::Static(RgbaColor::transparent()),
+            stroke_width: StyleNumber::Static(0.),
+            radius: StyleNumber::Static(0.5),
+        };
+
+        let canvas = vector_stream_to_canvas(
+            processor,
+            query_rect(),
+            MockQueryContext::test_default(),
+            10,
+            10,
+            &style,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(canvas.pixel(1, 1), RgbaColor::black());
+        assert_eq!(canvas.pixel(8, 8), RgbaColor::white());
+        assert_eq!(canvas.pixel(5, 4), RgbaColor::pink());
+        assert_eq!(canvas.pixel(0, 0), RgbaColor::transparent());
+    }
+
+    #[tokio::test]
+    async fn points_with_classified_expressions() {
+        let collection = MultiPointCollection::from_data(
+            MultiPoint::many(vec![(1.5, 8.5), (8.5, 1.5), (5.5, 5.5)]).unwrap(),
+            vec![TimeInterval::default(); 3],
+            [(
+                "value".to_string(),
+                FeatureData::NullableFloat(vec![Some(1.), Some(3.), None]),
+            )]
+            .into_iter()
+            .collect(),
+        )
+        .unwrap();
+
+        let processor = MockFeatureCollectionSource::single(collection)
+            .boxed()
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_point()
+            .unwrap();
+
+        let style = VectorStyle {
+            fill_color: StyleColor::Classified {
+                expression: FeatureExpression::parse("value * 10").unwrap(),
+                breaks: vec![20.],
+                colors: vec![RgbaColor::black(), RgbaColor::white()],
+                no_data_color: RgbaColor::pink(),
+            },
+            stroke_color: StyleColor::Static(RgbaColor::transparent()),
+            stroke_width: StyleNumber::Static(0.),
+            radius: StyleNumber::Expression {
+                expression: FeatureExpression::parse("value / 2").unwrap(),
+                default_value: 0.5,
+            },
+        };
+
+        let canvas = vector_stream_to_canvas(
+            processor,
+            query_rect(),
+            MockQueryContext::test_default(),
+            10,
+            10,
+            &style,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(canvas.pixel(1, 1), RgbaColor::black());
+        assert_eq!(canvas.pixel(2, 1), RgbaColor::transparent());
+        assert_eq!(canvas.pixel(8, 8), RgbaColor::white());
+        // the radius of 1.5 pixels also covers the neighbors
+        assert_eq!(canvas.pixel(9, 8), RgbaColor::white());
+        assert_eq!(canvas.pixel(5, 4), RgbaColor::pink());
+    }
+
+    #[tokio::test]
+    async fn lines_and_polygons() {
+        let lines = MultiLineStringCollection::from_data(
+            vec![MultiLineString::new(vec![vec![(0.5, 0.5).into(), (9.5, 0.5).into()]]).unwrap()],
+            vec![TimeInterval::default()],
+            Default::default(),
+        )
+        .unwrap();
+
+        let processor = MockFeatureCollectionSource::single(lines)
+            .boxed()
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_line_string()
+            .unwrap();
+
+        let canvas = vector_stream_to_canvas(
+            processor,
+            query_rect(),
+            MockQueryContext::test_default(),
+            10,
+            10,
+            &VectorStyle::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(canvas.pixel(4, 9), RgbaColor::black());
+        assert_eq!(canvas.pixel(4, 8), RgbaColor::transparent());
+
+        let polygons = MultiPolygonCollection::from_data(
+            vec![MultiPolygon::new(vec![vec![vec![
+                (2., 2.).into(),
+                (8., 2.).into(),
+                (8., 8.).into(),
+                (2., 8.).into(),
+                (2., 2.).into(),
+            ]]])
+            .unwrap()],
+            vec![TimeInterval::default()],
+            Default::default(),
+        )
+        .unwrap();
+
+        let processor = MockFeatureCollectionSource::single(polygons)
+            .boxed()
+            .initialize(&MockExecutionContext::test_default())
+            .await
+            .unwrap()
+            .query_processor()
+            .unwrap()
+            .multi_polygon()
+            .unwrap();
+
+        let canvas = vector_stream_to_canvas(
+            processor,
+            query_rect(),
+            MockQueryContext::test_default(),
+            10,
+            10,
+            &VectorStyle::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(canvas.pixel(2, 2), RgbaColor::black());
+        assert_eq!(canvas.pixel(5, 5), RgbaColor::white());
+        assert_eq!(canvas.pixel(0, 0), RgbaColor::transparent());
+
+        let image_bytes = canvas.to_image_bytes(ImageEncoding::Png).unwrap();
+        assert_eq!(&image_bytes[1..4], b"PNG");
+    }
+}
@@ -0,0 +1,693 @@
+//! Command line tool for administrating a Geo Engine instance via its API.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, BoundingBox2D, SpatialPartition2D, SpatialResolution, TimeInterval,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::TypedResultDescriptor;
+use geoengine_services::client::{
+    Client, FeaturesQuery, MapQuery, PlotQuery, ScalarQuery, TableQuery,
+};
+use geoengine_services::contexts::backup::{OnConflict, RestoreOptions};
+use geoengine_services::contexts::SessionId;
+use geoengine_services::datasets::storage::{AutoCreateDataset, CreateDataset, DatasetDefinition};
+use geoengine_services::error::{Error, Result};
+use geoengine_services::ogc::util::{parse_bbox, parse_time};
+use geoengine_services::util::parsing::parse_spatial_resolution;
+use geoengine_services::workflows::workflow::Workflow;
+#[cfg(feature = "pro")]
+use geoengine_services::{
+    pro::projects::{ProjectPermission, UserProjectPermission},
+    pro::users::{UserCredentials, UserId, UserRegistration},
+    projects::ProjectId,
+};
+use serde::de::value::{Error as DeserializationError, StrDeserializer};
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+use url::Url;
+
+const USAGE: &str = "\
+Usage: geoengine-cli [OPTIONS] <COMMAND>
+
+Options:
+  --url <URL>            base url of the API [env: GEOENGINE_URL, default: http://localhost:3030/]
+  --session <TOKEN>      session token to use [env: GEOENGINE_SESSION],
+                         otherwise an anonymous session is created
+  --email <EMAIL>        log in as this user (pro)
+  --password <PASSWORD>  password of the user (pro) [env: GEOENGINE_PASSWORD]
+  --admin-token <TOKEN>  admin token for backups [env: GEOENGINE_ADMIN_TOKEN]
+
+Commands:
+  register-dataset <DEFINITION> <FILE>...
+      Uploads the files and registers a dataset from a JSON dataset definition
+  import-rasters <DIRECTORY>
+      Uploads all raster files of a directory and registers each as a dataset
+  run-workflow <WORKFLOW> <OUTPUT> --bbox <X1,Y1,X2,Y2> [--crs <CRS>] [--time <TIME>]
+               [--resolution <X,Y>] [--width <PIXELS>] [--height <PIXELS>]
+      Registers a JSON workflow and writes its result to a file,
+      i.e., a PNG for rasters, GeoJSON for vectors and JSON for plots
+  backup <OUTPUT>
+      Writes a backup of the complete instance to a JSON file, requires the admin token
+  restore <BACKUP> [--on-conflict <abort|skip|newId>]
+      Restores a backup, requires the admin token
+  register-user <EMAIL> <REAL_NAME> <PASSWORD>  (pro)
+  list-permissions <PROJECT>  (pro)
+  add-permission <PROJECT> <USER> <Read|Write|Owner>  (pro)
+  remove-permission <PROJECT> <USER> <Read|Write|Owner>  (pro)
+";
+
+/// File extensions of the files that `import-rasters` imports
+const RASTER_EXTENSIONS: [&str; 6] = ["tif", "tiff", "nc", "jp2", "img", "vrt"];
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    url: Url,
+    session: Option<SessionId>,
+    #[cfg(feature = "pro")]
+    email: Option<String>,
+    #[cfg(feature = "pro")]
+    password: Option<String>,
+    command: Command,
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    RegisterDataset {
+        definition: PathBuf,
+        files: Vec<PathBuf>,
+    },
+    ImportRasters {
+        directory: PathBuf,
+    },
+    RunWorkflow {
+        workflow: PathBuf,
+        output: PathBuf,
+        query: QueryArgs,
+    },
+    Backup {
+        admin_token: String,
+        output: PathBuf,
+    },
+    Restore {
+        admin_token: String,
+        backup: PathBuf,
+        options: RestoreOptions,
+    },
+    #[cfg(feature = "pro")]
+    RegisterUser(UserRegistration),
+    #[cfg(feature = "pro")]
+    ListPermissions {
+        project: ProjectId,
+    },
+    #[cfg(feature = "pro")]
+    AddPermission(UserProjectPermission),
+    #[cfg(feature = "pro")]
+    RemovePermission(UserProjectPermission),
+}
+
+#[derive(Debug, PartialEq)]
+struct QueryArgs {
+    bbox: BoundingBox2D,
+    crs: SpatialReference,
+    time: TimeInterval,
+    resolution: Option<SpatialResolution>,
+    width: u32,
+    height: u32,
+}
+
+impl Args {
+    fn parse<I>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter().peekable();
+
+        let mut url = std::env::var("GEOENGINE_URL")
+            .unwrap_or_else(|_| "http://localhost:3030/".to_owned())
+            .parse::<Url>()?;
+        let mut session = std::env::var("GEOENGINE_SESSION")
+            .ok()
+            .map(|token| SessionId::from_str(&token))
+            .transpose()?;
+        let mut admin_token = std::env::var("GEOENGINE_ADMIN_TOKEN").ok();
+        #[cfg(feature = "pro")]
+        let mut email = None;
+        #[cfg(feature = "pro")]
+        let mut password = std::env::var("GEOENGINE_PASSWORD").ok();
+
+        while let Some(option) = args.next_if(|arg| arg.starts_with("--")) {
+            let value = required(&mut args, &option)?;
+            match option.as_str() {
+                "--url" => url = value.parse()?,
+                "--session" => session = Some(SessionId::from_str(&value)?),
+                "--admin-token" => admin_token = Some(value),
+                #[cfg(feature = "pro")]
+                "--email" => email = Some(value),
+                #[cfg(feature = "pro")]
+                "--password" => password = Some(value),
+                _ => return Err(invalid_argument(&option)),
+            }
+        }
+
+        let command = required(&mut args, "<COMMAND>")?;
+        let command = match command.as_str() {
+            "register-dataset" => {
+                let definition = required(&mut args, "<DEFINITION>")?.into();
+                let files: Vec<PathBuf> = args.by_ref().map(PathBuf::from).collect();
+                if files.is_empty() {
+                    return Err(invalid_argument("<FILE>"));
+                }
+                Command::RegisterDataset { definition, files }
+            }
+            "import-rasters" => Command::ImportRasters {
+                directory: required(&mut args, "<DIRECTORY>")?.into(),
+            },
+            "run-workflow" => Command::RunWorkflow {
+                workflow: required(&mut args, "<WORKFLOW>")?.into(),
+                output: required(&mut args, "<OUTPUT>")?.into(),
+                query: QueryArgs::parse(&mut args)?,
+            },
+            "backup" => Command::Backup {
+                admin_token: admin_token.ok_or_else(|| invalid_argument("--admin-token"))?,
+                output: required(&mut args, "<OUTPUT>")?.into(),
+            },
+            "restore" => Command::Restore {
+                admin_token: admin_token.ok_or_else(|| invalid_argument("--admin-token"))?,
+                backup: required(&mut args, "<BACKUP>")?.into(),
+                options: parse_restore_options(&mut args)?,
+            },
+            #[cfg(feature = "pro")]
+            "register-user" => Command::RegisterUser(UserRegistration {
+                email: required(&mut args, "<EMAIL>")?,
+                real_name: required(&mut args, "<REAL_NAME>")?,
+                password: required(&mut args, "<PASSWORD>")?,
+            }),
+            #[cfg(feature = "pro")]
+            "list-permissions" => Command::ListPermissions {
+                project: ProjectId::from_str(&required(&mut args, "<PROJECT>")?)?,
+            },
+            #[cfg(feature = "pro")]
+            "add-permission" => Command::AddPermission(parse_permission(&mut args)?),
+            #[cfg(feature = "pro")]
+            "remove-permission" => Command::RemovePermission(parse_permission(&mut args)?),
+            _ => return Err(invalid_argument(&command)),
+        };
+
+        if let Some(argument) = args.next() {
+            return Err(invalid_argument(argument));
+        }
+
+        Ok(Self {
+            url,
+            session,
+            #[cfg(feature = "pro")]
+            email,
+            #[cfg(feature = "pro")]
+            password,
+            command,
+        })
+    }
+}
+
+impl QueryArgs {
+    fn parse<I>(args: &mut std::iter::Peekable<I>) -> Result<Self>
+    where
+        I: Iterator<Item = String>,
+    {
+        let mut bbox = None;
+        let mut crs = SpatialReference::epsg_4326();
+        let mut time = TimeInterval::default();
+        let mut resolution = None;
+        let mut width = 512;
+        let mut height = 512;
+
+        while let Some(option) = args.next_if(|arg| arg.starts_with("--")) {
+            let value = required(args, &option)?;
+            let invalid_value = |_| invalid_argument(format!("{} {}", option, value));
+            match option.as_str() {
+                "--bbox" => bbox = Some(parse_bbox(deserializer(&value)).map_err(invalid_value)?),
+                "--crs" => crs = SpatialReference::from_str(&value)?,
+                "--time" => time = parse_time(deserializer(&value)).map_err(invalid_value)?,
+                "--resolution" => {
+                    resolution = Some(
+                        parse_spatial_resolution(deserializer(&value)).map_err(invalid_value)?,
+                    );
+                }
+                "--width" => width = value.parse().map_err(|_| invalid_argument(&value))?,
+                "--height" => height = value.parse().map_err(|_| invalid_argument(&value))?,
+                _ => return Err(invalid_argument(&option)),
+            }
+        }
+
+        Ok(Self {
+            bbox: bbox.ok_or_else(|| invalid_argument("--bbox"))?,
+            crs,
+            time,
+            resolution,
+            width,
+            height,
+        })
+    }
+
+    fn resolution(&self) -> SpatialResolution {
+        self.resolution.unwrap_or_else(|| {
+            SpatialResolution::new_unchecked(
+                self.bbox.size_x() / f64::from(self.width),
+                self.bbox.size_y() / f64::from(self.height),
+            )
+        })
+    }
+}
+
+fn parse_restore_options<I>(args: &mut std::iter::Peekable<I>) -> Result<RestoreOptions>
+where
+    I: Iterator<Item = String>,
+{
+    let mut options = RestoreOptions::default();
+
+    while let Some(option) = args.next_if(|arg| arg.starts_with("--")) {
+        let value = required(args, &option)?;
+        match option.as_str() {
+            "--on-conflict" => {
+                options.on_conflict = OnConflict::deserialize(deserializer(&value))
+                    .map_err(|_| invalid_argument(format!("{} {}", option, value)))?;
+            }
+            _ => return Err(invalid_argument(&option)),
+        }
+    }
+
+    Ok(options)
+}
+
+#[cfg(feature = "pro")]
+fn parse_permission<I>(args: &mut I) -> Result<UserProjectPermission>
+where
+    I: Iterator<Item = String>,
+{
+    let project = ProjectId::from_str(&required(args, "<PROJECT>")?)?;
+    let user = UserId::from_str(&required(args, "<USER>")?)?;
+    let permission = match required(args, "<PERMISSION>")?.as_str() {
+        "Read" => ProjectPermission::Read,
+        "Write" => ProjectPermission::Write,
+        "Owner" => ProjectPermission::Owner,
+        other => return Err(invalid_argument(other)),
+    };
+
+    Ok(UserProjectPermission {
+        project,
+        permission,
+        user,
+    })
+}
+
+fn required<I>(args: &mut I, name: &str) -> Result<String>
+where
+    I: Iterator<Item = String>,
+{
+    args.next().ok_or_else(|| invalid_argument(name))
+}
+
+fn invalid_argument<S: ToString>(argument: S) -> Error {
+    Error::InvalidCommandLineArgument {
+        argument: argument.to_string(),
+    }
+}
+
+fn deserializer(value: &str) -> StrDeserializer<DeserializationError> {
+    value.into_deserializer()
+}
+
+#[tokio::main]
+async fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(error) = run(args).await {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
+
+async fn run(args: Args) -> Result<()> {
+    // backups are authorized by the admin token and do not need a session
+    match &args.command {
+        Command::Backup {
+            admin_token,
+            output,
+        } => {
+            let backup = Client::new(args.url.clone()).backup(admin_token).await?;
+            return Ok(tokio::fs::write(output, backup).await?);
+        }
+        Command::Restore {
+            admin_token,
+            backup,
+            options,
+        } => {
+            let backup = tokio::fs::read(backup).await?;
+            let summary = Client::new(args.url.clone())
+                .restore(admin_token, backup.into(), options)
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let client = connect(&args).await?;
+
+    match args.command {
+        Command::RegisterDataset { definition, files } => {
+            register_dataset(&client, &definition, &files).await
+        }
+        Command::ImportRasters { directory } => import_rasters(&client, &directory).await,
+        Command::RunWorkflow {
+            workflow,
+            output,
+            query,
+        } => run_workflow(&client, &workflow, &output, &query).await,
+        Command::Backup { .. } | Command::Restore { .. } => unreachable!("handled above"),
+        #[cfg(feature = "pro")]
+        Command::RegisterUser(user) => {
+            let id = client.register_user(&user).await?;
+            println!("{}", id);
+            Ok(())
+        }
+        #[cfg(feature = "pro")]
+        Command::ListPermissions { project } => {
+            for permission in client.project_permissions(project).await? {
+                println!("{}\t{:?}", permission.user, permission.permission);
+            }
+            Ok(())
+        }
+        #[cfg(feature = "pro")]
+        Command::AddPermission(permission) => client.add_project_permission(&permission).await,
+        #[cfg(feature = "pro")]
+        Command::RemovePermission(permission) => {
+            client.remove_project_permission(&permission).await
+        }
+    }
+}
+
+/// Creates a client with the session of the arguments, a user session or an anonymous session
+async fn connect(args: &Args) -> Result<Client> {
+    let mut client = Client::new(args.url.clone());
+
+    if let Some(session) = args.session {
+        return Ok(client.with_session_token(session));
+    }
+
+    #[cfg(feature = "pro")]
+    if let Some(email) = &args.email {
+        client
+            .login(&UserCredentials {
+                email: email.clone(),
+                password: args.password.clone().unwrap_or_default(),
+            })
+            .await?;
+        return Ok(client);
+    }
+
+    client.create_anonymous_session().await?;
+
+    Ok(client)
+}
+
+async fn register_dataset(client: &Client, definition: &Path, files: &[PathBuf]) -> Result<()> {
+    let definition: DatasetDefinition = read_json(definition).await?;
+
+    let upload = client.upload(files).await?;
+    let id = client
+        .create_dataset(&CreateDataset { upload, definition })
+        .await?;
+
+    println!("{}", serde_json::to_string(&id)?);
+
+    Ok(())
+}
+
+/// Imports all raster files of the directory and reports failed files instead of stopping
+async fn import_rasters(client: &Client, directory: &Path) -> Result<()> {
+    let mut files = Vec::new();
+    let mut entries = tokio::fs::read_dir(directory).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let is_raster = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map_or(false, |extension| {
+                RASTER_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+            });
+        if is_raster && entry.file_type().await?.is_file() {
+            files.push(path);
+        }
+    }
+    files.sort();
+
+    let mut failed = 0;
+    for file in &files {
+        match import_raster(client, file).await {
+            Ok(id) => println!("{}\t{}", file.display(), serde_json::to_string(&id)?),
+            Err(error) => {
+                failed += 1;
+                eprintln!("{}\t{}", file.display(), error);
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::InvalidCommandLineArgument {
+            argument: format!(
+                "{} of {} rasters could not be imported",
+                failed,
+                files.len()
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+async fn import_raster(
+    client: &Client,
+    file: &Path,
+) -> Result<geoengine_datatypes::dataset::DatasetId> {
+    let upload = client.upload(&[file]).await?;
+
+    let file_name = file
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or(Error::UploadFieldMissingFileName)?;
+    let name = file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(file_name);
+
+    client
+        .auto_create_dataset(&AutoCreateDataset {
+            upload,
+            dataset_name: name.to_owned(),
+            dataset_description: format!("Imported from {}", file.display()),
+            main_file: file_name.to_owned(),
+        })
+        .await
+}
+
+async fn run_workflow(
+    client: &Client,
+    workflow: &Path,
+    output: &Path,
+    query: &QueryArgs,
+) -> Result<()> {
+    let workflow: Workflow = read_json(workflow).await?;
+
+    let id = client.register_workflow(&workflow).await?;
+
+    let bytes: Vec<u8> = match client.workflow_metadata(id).await? {
+        TypedResultDescriptor::Raster(_) => client
+            .map(
+                id,
+                &MapQuery {
+                    bbox: SpatialPartition2D::new(
+                        query.bbox.upper_left(),
+                        query.bbox.lower_right(),
+                    )?,
+                    crs: query.crs,
+                    time: Some(query.time),
+                    width: query.width,
+                    height: query.height,
+                    styles: String::new(),
+                },
+            )
+            .await?
+            .to_vec(),
+        TypedResultDescriptor::Vector(_) => {
+            let features = client
+                .features(
+                    id,
+                    &FeaturesQuery {
+                        bbox: query.bbox,
+                        crs: query.crs,
+                        time: Some(query.time),
+                        spatial_resolution: query.resolution,
+                    },
+                )
+                .await?;
+            serde_json::to_vec(&features)?
+        }
+        TypedResultDescriptor::Plot(_) => {
+            let plot = client
+                .plot(
+                    id,
+                    &PlotQuery {
+                        bbox: query.bbox,
+                        crs: query.crs,
+                        time: query.time,
+                        spatial_resolution: query.resolution(),
+                    },
+                )
+                .await?;
+            serde_json::to_vec(&plot)?
+        }
+        TypedResultDescriptor::Table(_) => client
+            .table(
+                id,
+                &TableQuery {
+                    bbox: query.bbox,
+                    time: query.time,
+                    spatial_resolution: query.resolution(),
+                },
+            )
+            .await?
+            .to_vec(),
+        TypedResultDescriptor::Scalar(_) => {
+            let scalar = client
+                .scalar(
+                    id,
+                    &ScalarQuery {
+                        bbox: query.bbox,
+                        crs: query.crs,
+                        time: query.time,
+                        spatial_resolution: query.resolution(),
+                    },
+                )
+                .await?;
+            serde_json::to_vec(&scalar)?
+        }
+    };
+
+    tokio::fs::write(output, bytes).await?;
+
+    println!("{}", id);
+
+    Ok(())
+}
+
+async fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let content = tokio::fs::read(path).await?;
+    Ok(serde_json::from_slice(&content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Args> {
+        Args::parse(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn it_parses_commands() {
+        let parsed = args(&[
+            "--url",
+            "http://example.com/api/",
+            "run-workflow",
+            "workflow.json",
+            "out.png",
+            "--bbox",
+            "-180,-90,180,90",
+            "--width",
+            "360",
+            "--height",
+            "180",
+        ])
+        .unwrap();
+
+        assert_eq!(parsed.url.as_str(), "http://example.com/api/");
+
+        match parsed.command {
+            Command::RunWorkflow {
+                workflow,
+                output,
+                query,
+            } => {
+                assert_eq!(workflow, PathBuf::from("workflow.json"));
+                assert_eq!(output, PathBuf::from("out.png"));
+                assert_eq!(
+                    query.bbox,
+                    BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap()
+                );
+                assert_eq!(query.crs, SpatialReference::epsg_4326());
+                assert_eq!(query.resolution(), SpatialResolution::one());
+            }
+            command => panic!("unexpected command {:?}", command),
+        }
+
+        assert_eq!(
+            args(&["register-dataset", "definition.json", "a.tif", "b.tif"])
+                .unwrap()
+                .command,
+            Command::RegisterDataset {
+                definition: "definition.json".into(),
+                files: vec!["a.tif".into(), "b.tif".into()],
+            }
+        );
+
+        assert_eq!(
+            args(&[
+                "--admin-token",
+                "secret",
+                "restore",
+                "backup.json",
+                "--on-conflict",
+                "newId"
+            ])
+            .unwrap()
+            .command,
+            Command::Restore {
+                admin_token: "secret".to_string(),
+                backup: "backup.json".into(),
+                options: RestoreOptions {
+                    on_conflict: OnConflict::NewId,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_arguments() {
+        assert!(args(&[]).is_err());
+        assert!(args(&["--url"]).is_err());
+        assert!(args(&["unknown"]).is_err());
+        assert!(args(&["register-dataset", "definition.json"]).is_err());
+        assert!(args(&["import-rasters", "a", "b"]).is_err());
+        assert!(args(&["run-workflow", "workflow.json", "out.png"]).is_err());
+        assert!(args(&["run-workflow", "w.json", "o.png", "--bbox", "1,2,3"]).is_err());
+        assert!(args(&[
+            "--admin-token",
+            "a",
+            "restore",
+            "b.json",
+            "--on-conflict",
+            "x"
+        ])
+        .is_err());
+    }
+}
@@ -10,6 +10,10 @@ use tracing_subscriber::prelude::*;
 
 #[tokio::main]
 async fn main() {
+    let config_args = config::ConfigArgs::parse(std::env::args().skip(1))
+        .expect("invalid command line arguments");
+    config::initialize(&config_args).expect("invalid configuration");
+
     let _log_writer_handle = initialize_logging();
 
     start_server().await.unwrap();
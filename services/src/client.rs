@@ -0,0 +1,787 @@
+//! A typed client for the Geo Engine API.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # async fn example() -> geoengine_services::error::Result<()> {
+//! use geoengine_services::client::Client;
+//! use geoengine_services::datasets::listing::{DatasetListOptions, OrderBy};
+//!
+//! let mut client = Client::new("http://localhost:3030/".parse()?);
+//! client.create_anonymous_session().await?;
+//!
+//! let datasets = client
+//!     .list_datasets(&DatasetListOptions {
+//!         filter: None,
+//!         order: OrderBy::NameAsc,
+//!         offset: 0,
+//!         limit: 10,
+//!         bbox: None,
+//!         time: None,
+//!     })
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::ffi::OsStr;
+use std::path::Path;
+
+use bytes::Bytes;
+use geoengine_datatypes::dataset::{DatasetId, InternalDatasetId};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Scalar, SpatialPartition2D, SpatialResolution, TimeInterval,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::TypedResultDescriptor;
+use reqwest::multipart::{Form, Part};
+use reqwest::{Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use snafu::ResultExt;
+use tokio_util::codec::{BytesCodec, FramedRead};
+use url::Url;
+
+use crate::contexts::backup::{RestoreOptions, RestoreSummary};
+use crate::contexts::{Session, SessionId, SimpleSession};
+use crate::datasets::listing::{DatasetListOptions, DatasetListing, ProvenanceOutput};
+use crate::datasets::storage::{
+    AutoCreateDataset, CreateDataset, Dataset, DatasetProviderListOptions, DatasetProviderListing,
+};
+use crate::datasets::upload::UploadId;
+use crate::error::{self, Error, Result};
+use crate::handlers::plots::WrappedPlotOutput;
+use crate::handlers::ErrorResponse;
+use crate::ogc::util::ogc_params_from_rectangle;
+#[cfg(feature = "pro")]
+use crate::pro::projects::UserProjectPermission;
+#[cfg(feature = "pro")]
+use crate::pro::users::{UserCredentials, UserId, UserRegistration, UserSession};
+use crate::projects::{
+    CreateProject, Project, ProjectId, ProjectListOptions, ProjectListing, STRectangle,
+    UpdateProject,
+};
+use crate::util::IdResponse;
+use crate::workflows::workflow::{Workflow, WorkflowId};
+
+/// The query parameters of a plot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlotQuery {
+    pub bbox: BoundingBox2D,
+    pub crs: SpatialReference,
+    pub time: TimeInterval,
+    pub spatial_resolution: SpatialResolution,
+}
+
+/// The query parameters of a PNG map of a raster workflow
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapQuery {
+    pub bbox: SpatialPartition2D,
+    pub crs: SpatialReference,
+    pub time: Option<TimeInterval>,
+    pub width: u32,
+    pub height: u32,
+    /// WMS styles, e.g., `custom:{...}` for a custom colorizer
+    pub styles: String,
+}
+
+/// The query parameters of the features of a vector workflow
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeaturesQuery {
+    pub bbox: BoundingBox2D,
+    pub crs: SpatialReference,
+    pub time: Option<TimeInterval>,
+    pub spatial_resolution: Option<SpatialResolution>,
+}
+
+/// The query parameters of a table workflow
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TableQuery {
+    /// The bounds in the spatial reference of the data that the table is computed from
+    pub bbox: BoundingBox2D,
+    pub time: TimeInterval,
+    pub spatial_resolution: SpatialResolution,
+}
+
+/// The query parameters of a scalar workflow
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScalarQuery {
+    pub bbox: BoundingBox2D,
+    pub crs: SpatialReference,
+    pub time: TimeInterval,
+    pub spatial_resolution: SpatialResolution,
+}
+
+/// A client for the Geo Engine API that uses the serde types of the services.
+///
+/// All methods except for creating sessions require a session token.
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: Url,
+    session_token: Option<SessionId>,
+}
+
+impl Client {
+    /// Creates a client for the API at `base_url`, e.g., `http://localhost:3030/`
+    pub fn new(mut base_url: Url) -> Self {
+        // relative paths must be resolved below the base url
+        if !base_url.path().ends_with('/') {
+            let path = format!("{}/", base_url.path());
+            base_url.set_path(&path);
+        }
+
+        Self {
+            http: reqwest::Client::new(),
+            base_url,
+            session_token: None,
+        }
+    }
+
+    /// Uses an existing session for all subsequent requests
+    #[must_use]
+    pub fn with_session_token(mut self, session_token: SessionId) -> Self {
+        self.session_token = Some(session_token);
+        self
+    }
+
+    pub fn session_token(&self) -> Option<SessionId> {
+        self.session_token
+    }
+
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// Creates an anonymous session and uses it for all subsequent requests
+    pub async fn create_anonymous_session(&mut self) -> Result<SimpleSession> {
+        let session: SimpleSession = self
+            .send_json(self.request(Method::POST, "anonymous")?)
+            .await?;
+
+        self.session_token = Some(session.id());
+
+        Ok(session)
+    }
+
+    /// Retrieves the current session, e.g., a [`SimpleSession`] or a `UserSession`
+    pub async fn session<S: DeserializeOwned>(&self) -> Result<S> {
+        self.send_json(self.request(Method::GET, "session")?).await
+    }
+
+    pub async fn set_session_project(&self, project: ProjectId) -> Result<()> {
+        self.send(self.request(Method::POST, &format!("session/project/{}", project))?)
+            .await
+            .map(drop)
+    }
+
+    pub async fn set_session_view(&self, view: &STRectangle) -> Result<()> {
+        self.send(self.request(Method::POST, "session/view")?.json(view))
+            .await
+            .map(drop)
+    }
+
+    pub async fn list_datasets(&self, options: &DatasetListOptions) -> Result<Vec<DatasetListing>> {
+        self.send_json(self.request(Method::GET, "datasets")?.query(options))
+            .await
+    }
+
+    pub async fn dataset(&self, dataset: InternalDatasetId) -> Result<Dataset> {
+        self.send_json(self.request(Method::GET, &format!("dataset/internal/{}", dataset))?)
+            .await
+    }
+
+    pub async fn list_providers(
+        &self,
+        options: &DatasetProviderListOptions,
+    ) -> Result<Vec<DatasetProviderListing>> {
+        self.send_json(self.request(Method::GET, "providers")?.query(options))
+            .await
+    }
+
+    /// Uploads files, e.g., the data files of a dataset
+    pub async fn upload<P: AsRef<Path>>(&self, files: &[P]) -> Result<UploadId> {
+        let mut form = Form::new();
+
+        for path in files {
+            let path = path.as_ref();
+            let file_name = path
+                .file_name()
+                .and_then(OsStr::to_str)
+                .ok_or(Error::UploadFieldMissingFileName)?
+                .to_owned();
+
+            let file = tokio::fs::File::open(path).await.context(error::Io)?;
+            let length = file.metadata().await.context(error::Io)?.len();
+            let body = reqwest::Body::wrap_stream(FramedRead::new(file, BytesCodec::new()));
+
+            form = form.part(
+                "files[]",
+                Part::stream_with_length(body, length).file_name(file_name),
+            );
+        }
+
+        let response: IdResponse<UploadId> = self
+            .send_json(self.request(Method::POST, "upload")?.multipart(form))
+            .await?;
+        Ok(response.id)
+    }
+
+    /// Creates a dataset from the files of an upload
+    pub async fn create_dataset(&self, create: &CreateDataset) -> Result<DatasetId> {
+        let response: IdResponse<DatasetId> = self
+            .send_json(self.request(Method::POST, "dataset")?.json(create))
+            .await?;
+        Ok(response.id)
+    }
+
+    /// Creates a dataset from a file of an upload and detects its meta data
+    pub async fn auto_create_dataset(&self, create: &AutoCreateDataset) -> Result<DatasetId> {
+        let response: IdResponse<DatasetId> = self
+            .send_json(self.request(Method::POST, "dataset/auto")?.json(create))
+            .await?;
+        Ok(response.id)
+    }
+
+    /// Registers a workflow after checking that it is valid
+    pub async fn register_workflow(&self, workflow: &Workflow) -> Result<WorkflowId> {
+        let response: IdResponse<WorkflowId> = self
+            .send_json(self.request(Method::POST, "workflow")?.json(workflow))
+            .await?;
+        Ok(response.id)
+    }
+
+    pub async fn workflow(&self, workflow: WorkflowId) -> Result<Workflow> {
+        self.send_json(self.request(Method::GET, &format!("workflow/{}", workflow))?)
+            .await
+    }
+
+    pub async fn workflow_metadata(&self, workflow: WorkflowId) -> Result<TypedResultDescriptor> {
+        self.send_json(self.request(Method::GET, &format!("workflow/{}/metadata", workflow))?)
+            .await
+    }
+
+    pub async fn workflow_provenance(&self, workflow: WorkflowId) -> Result<Vec<ProvenanceOutput>> {
+        self.send_json(self.request(Method::GET, &format!("workflow/{}/provenance", workflow))?)
+            .await
+    }
+
+    pub async fn create_project(&self, project: &CreateProject) -> Result<ProjectId> {
+        let response: IdResponse<ProjectId> = self
+            .send_json(self.request(Method::POST, "project")?.json(project))
+            .await?;
+        Ok(response.id)
+    }
+
+    pub async fn list_projects(&self, options: &ProjectListOptions) -> Result<Vec<ProjectListing>> {
+        self.send_json(self.request(Method::GET, "projects")?.query(options))
+            .await
+    }
+
+    /// Loads the latest version of a project
+    pub async fn project(&self, project: ProjectId) -> Result<Project> {
+        self.send_json(self.request(Method::GET, &format!("project/{}", project))?)
+            .await
+    }
+
+    pub async fn update_project(&self, update: &UpdateProject) -> Result<()> {
+        self.send(
+            self.request(Method::PATCH, &format!("project/{}", update.id))?
+                .json(update),
+        )
+        .await
+        .map(drop)
+    }
+
+    pub async fn delete_project(&self, project: ProjectId) -> Result<()> {
+        self.send(self.request(Method::DELETE, &format!("project/{}", project))?)
+            .await
+            .map(drop)
+    }
+
+    /// Computes the plot of a plot workflow
+    pub async fn plot(&self, workflow: WorkflowId, query: &PlotQuery) -> Result<WrappedPlotOutput> {
+        let bbox = query.bbox;
+        let params = [
+            (
+                "bbox",
+                format!(
+                    "{},{},{},{}",
+                    bbox.lower_left().x,
+                    bbox.lower_left().y,
+                    bbox.upper_right().x,
+                    bbox.upper_right().y
+                ),
+            ),
+            ("crs", query.crs.to_string()),
+            ("time", time_param(query.time)),
+            (
+                "spatialResolution",
+                format!(
+                    "{},{}",
+                    query.spatial_resolution.x, query.spatial_resolution.y
+                ),
+            ),
+        ];
+
+        self.send_json(
+            self.request(Method::GET, &format!("plot/{}", workflow))?
+                .query(&params),
+        )
+        .await
+    }
+
+    /// Computes the value of a scalar workflow
+    pub async fn scalar(&self, workflow: WorkflowId, query: &ScalarQuery) -> Result<Scalar> {
+        let bbox = query.bbox;
+        let params = [
+            (
+                "bbox",
+                format!(
+                    "{},{},{},{}",
+                    bbox.lower_left().x,
+                    bbox.lower_left().y,
+                    bbox.upper_right().x,
+                    bbox.upper_right().y
+                ),
+            ),
+            ("crs", query.crs.to_string()),
+            ("time", time_param(query.time)),
+            (
+                "spatialResolution",
+                format!(
+                    "{},{}",
+                    query.spatial_resolution.x, query.spatial_resolution.y
+                ),
+            ),
+        ];
+
+        self.send_json(
+            self.request(Method::GET, &format!("scalar/{}", workflow))?
+                .query(&params),
+        )
+        .await
+    }
+
+    /// Renders a raster workflow as PNG using the WMS `GetMap` request
+    pub async fn map(&self, workflow: WorkflowId, query: &MapQuery) -> Result<Bytes> {
+        let [a, b, c, d] = ogc_params_from_rectangle(query.bbox, query.crs)?;
+
+        let mut params = vec![
+            ("service", "WMS".to_string()),
+            ("version", "1.3.0".to_string()),
+            ("request", "GetMap".to_string()),
+            ("layers", workflow.to_string()),
+            ("styles", query.styles.clone()),
+            ("crs", query.crs.to_string()),
+            ("bbox", format!("{},{},{},{}", a, b, c, d)),
+            ("width", query.width.to_string()),
+            ("height", query.height.to_string()),
+            ("format", "image/png".to_string()),
+        ];
+
+        if let Some(time) = query.time {
+            params.push(("time", time_param(time)));
+        }
+
+        let response = self
+            .send(
+                self.request(Method::GET, &format!("wms/{}", workflow))?
+                    .query(&params),
+            )
+            .await?;
+
+        Ok(response.bytes().await?)
+    }
+
+    /// Retrieves the result of a table workflow as CSV
+    pub async fn table(&self, workflow: WorkflowId, query: &TableQuery) -> Result<Bytes> {
+        let bbox = query.bbox;
+        let params = [
+            (
+                "bbox",
+                format!(
+                    "{},{},{},{}",
+                    bbox.lower_left().x,
+                    bbox.lower_left().y,
+                    bbox.upper_right().x,
+                    bbox.upper_right().y
+                ),
+            ),
+            ("time", time_param(query.time)),
+            (
+                "spatialResolution",
+                format!(
+                    "{},{}",
+                    query.spatial_resolution.x, query.spatial_resolution.y
+                ),
+            ),
+            ("format", "csv".to_string()),
+        ];
+
+        let response = self
+            .send(
+                self.request(Method::GET, &format!("table/{}", workflow))?
+                    .query(&params),
+            )
+            .await?;
+
+        Ok(response.bytes().await?)
+    }
+
+    /// Retrieves the features of a vector workflow as `GeoJSON` using the WFS `GetFeature` request
+    pub async fn features(
+        &self,
+        workflow: WorkflowId,
+        query: &FeaturesQuery,
+    ) -> Result<serde_json::Value> {
+        let bbox = query.bbox;
+
+        let mut params = vec![
+            ("service", "WFS".to_string()),
+            ("version", "2.0.0".to_string()),
+            ("request", "GetFeature".to_string()),
+            ("typeNames", workflow.to_string()),
+            (
+                "bbox",
+                format!(
+                    "{},{},{},{}",
+                    bbox.lower_left().x,
+                    bbox.lower_left().y,
+                    bbox.upper_right().x,
+                    bbox.upper_right().y
+                ),
+            ),
+            ("srsName", query.crs.to_string()),
+        ];
+
+        if let Some(time) = query.time {
+            params.push(("time", time_param(time)));
+        }
+
+        if let Some(resolution) = query.spatial_resolution {
+            params.push((
+                "queryResolution",
+                format!("{},{}", resolution.x, resolution.y),
+            ));
+        }
+
+        self.send_json(
+            self.request(Method::GET, &format!("wfs/{}", workflow))?
+                .query(&params),
+        )
+        .await
+    }
+
+    /// Exports the complete state of the instance as JSON.
+    ///
+    /// The backup is not deserialized, so it can be stored as is regardless of whether the
+    /// instance is a pro instance.
+    pub async fn backup(&self, admin_token: &str) -> Result<Bytes> {
+        let response = self
+            .send(self.admin_request(Method::GET, "backup", admin_token)?)
+            .await?;
+
+        Ok(response.bytes().await?)
+    }
+
+    /// Restores a backup that was created with [`Client::backup`]
+    pub async fn restore(
+        &self,
+        admin_token: &str,
+        backup: Bytes,
+        options: &RestoreOptions,
+    ) -> Result<RestoreSummary> {
+        self.send_json(
+            self.admin_request(Method::POST, "restore", admin_token)?
+                .query(options)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(backup),
+        )
+        .await
+    }
+
+    fn request(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        let url = self.base_url.join(path)?;
+
+        let request = self.http.request(method, url);
+
+        Ok(match self.session_token {
+            Some(session_token) => request.bearer_auth(session_token),
+            None => request,
+        })
+    }
+
+    /// Creates a request that is authorized by the admin token instead of the session
+    fn admin_request(
+        &self,
+        method: Method,
+        path: &str,
+        admin_token: &str,
+    ) -> Result<RequestBuilder> {
+        let url = self.base_url.join(path)?;
+
+        Ok(self.http.request(method, url).bearer_auth(admin_token))
+    }
+
+    async fn send_json<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T> {
+        Ok(self.send(request).await?.json().await?)
+    }
+
+    /// Sends the request and turns error responses of the API into [`Error::ApiResponse`]
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let response = request.send().await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let body = response.bytes().await?;
+        let (error, message) = match serde_json::from_slice::<ErrorResponse>(&body) {
+            Ok(ErrorResponse { error, message }) => (error, message),
+            Err(_) => (
+                status.canonical_reason().unwrap_or_default().to_string(),
+                String::from_utf8_lossy(&body).into_owned(),
+            ),
+        };
+
+        Err(Error::ApiResponse {
+            status: status.as_u16(),
+            error,
+            message,
+        })
+    }
+}
+
+#[cfg(feature = "pro")]
+impl Client {
+    pub async fn register_user(&self, user: &UserRegistration) -> Result<UserId> {
+        let response: IdResponse<UserId> = self
+            .send_json(self.request(Method::POST, "user")?.json(user))
+            .await?;
+        Ok(response.id)
+    }
+
+    /// Logs in and uses the user's session for all subsequent requests
+    pub async fn login(&mut self, credentials: &UserCredentials) -> Result<UserSession> {
+        let session: UserSession = self
+            .send_json(self.request(Method::POST, "login")?.json(credentials))
+            .await?;
+
+        self.session_token = Some(session.id);
+
+        Ok(session)
+    }
+
+    /// Ends the current session
+    pub async fn logout(&mut self) -> Result<()> {
+        self.send(self.request(Method::POST, "logout")?).await?;
+
+        self.session_token = None;
+
+        Ok(())
+    }
+
+    pub async fn project_permissions(
+        &self,
+        project: ProjectId,
+    ) -> Result<Vec<UserProjectPermission>> {
+        self.send_json(self.request(Method::GET, &format!("project/{}/permissions", project))?)
+            .await
+    }
+
+    pub async fn add_project_permission(&self, permission: &UserProjectPermission) -> Result<()> {
+        self.send(
+            self.request(Method::POST, "project/permission/add")?
+                .json(permission),
+        )
+        .await
+        .map(drop)
+    }
+
+    pub async fn remove_project_permission(
+        &self,
+        permission: &UserProjectPermission,
+    ) -> Result<()> {
+        self.send(
+            self.request(Method::DELETE, "project/permission")?
+                .json(permission),
+        )
+        .await
+        .map(drop)
+    }
+}
+
+/// Formats a time interval as `start/end`, or as an instant if it is one
+fn time_param(time: TimeInterval) -> String {
+    if time.is_instant() {
+        time.start().as_rfc3339()
+    } else {
+        format!("{}/{}", time.start().as_rfc3339(), time.end().as_rfc3339())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::InMemoryContext;
+    use crate::datasets::listing::OrderBy;
+    use crate::projects::{ProjectFilter, STRectangle};
+    use crate::util::tests::{add_ndvi_to_datasets, start_test_server};
+    use geoengine_datatypes::plots::PlotOutputFormat;
+    use geoengine_datatypes::primitives::Measurement;
+    use geoengine_datatypes::raster::RasterDataType;
+    use geoengine_datatypes::spatial_reference::SpatialReferenceOption;
+    use geoengine_datatypes::util::test::TestDefault;
+    use geoengine_operators::engine::{PlotOperator, RasterOperator, RasterResultDescriptor};
+    use geoengine_operators::mock::{MockRasterSource, MockRasterSourceParams};
+    use geoengine_operators::plot::{Statistics, StatisticsParams};
+
+    #[actix_rt::test]
+    async fn it_manages_projects() {
+        let ctx = InMemoryContext::test_default();
+        let mut client = Client::new(start_test_server(ctx));
+
+        let session = client.create_anonymous_session().await.unwrap();
+        assert_eq!(client.session::<SimpleSession>().await.unwrap(), session);
+
+        let project = client
+            .create_project(&CreateProject {
+                name: "Test".to_string(),
+                description: "Foo".to_string(),
+                bounds: STRectangle::new(
+                    SpatialReferenceOption::Unreferenced,
+                    0.,
+                    0.,
+                    1.,
+                    1.,
+                    0,
+                    1,
+                )
+                .unwrap(),
+                time_step: None,
+            })
+            .await
+            .unwrap();
+
+        client
+            .update_project(&UpdateProject {
+                id: project,
+                name: Some("Renamed".to_string()),
+                description: None,
+                layers: None,
+                plots: None,
+                bounds: None,
+                time_step: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(client.project(project).await.unwrap().name, "Renamed");
+
+        let projects = client
+            .list_projects(&ProjectListOptions {
+                filter: ProjectFilter::None,
+                order: crate::projects::OrderBy::NameAsc,
+                offset: 0,
+                limit: 10,
+            })
+            .await
+            .unwrap();
+        assert_eq!(projects.len(), 1);
+
+        client.set_session_project(project).await.unwrap();
+
+        client.delete_project(project).await.unwrap();
+
+        let error = client.project(project).await.unwrap_err();
+        assert!(matches!(error, Error::ApiResponse { status: 400, .. }));
+    }
+
+    #[actix_rt::test]
+    async fn it_lists_datasets_and_runs_workflows() {
+        let ctx = InMemoryContext::test_default();
+        let dataset = add_ndvi_to_datasets(&ctx).await;
+        let mut client = Client::new(start_test_server(ctx));
+
+        client.create_anonymous_session().await.unwrap();
+
+        let datasets = client
+            .list_datasets(&DatasetListOptions {
+                filter: None,
+                order: OrderBy::NameAsc,
+                offset: 0,
+                limit: 10,
+                bbox: None,
+                time: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(datasets.len(), 1);
+        assert_eq!(datasets[0].id, dataset);
+
+        let workflow = Workflow {
+            operator: Statistics {
+                params: StatisticsParams::default(),
+                sources: vec![MockRasterSource {
+                    params: MockRasterSourceParams::<u8> {
+                        data: vec![],
+                        result_descriptor: RasterResultDescriptor {
+                            data_type: RasterDataType::U8,
+                            spatial_reference: SpatialReference::epsg_4326().into(),
+                            measurement: Measurement::Unitless,
+                            no_data_value: None,
+                            dimensions: vec![],
+                        },
+                    },
+                }
+                .boxed()]
+                .into(),
+            }
+            .boxed()
+            .into(),
+        };
+
+        let id = client.register_workflow(&workflow).await.unwrap();
+        assert!(matches!(
+            client.workflow_metadata(id).await.unwrap(),
+            TypedResultDescriptor::Plot(_)
+        ));
+
+        let plot = client
+            .plot(
+                id,
+                &PlotQuery {
+                    bbox: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
+                    crs: SpatialReference::epsg_4326(),
+                    time: TimeInterval::new_instant(0).unwrap(),
+                    spatial_resolution: SpatialResolution::one(),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(plot.output_format, PlotOutputFormat::JsonPlain);
+        assert_eq!(plot.plot_type, "Statistics");
+    }
+
+    #[actix_rt::test]
+    async fn it_reports_api_errors() {
+        let ctx = InMemoryContext::test_default();
+        let client = Client::new(start_test_server(ctx));
+
+        let error = client.session::<SimpleSession>().await.unwrap_err();
+
+        match error {
+            Error::ApiResponse {
+                status,
+                error,
+                message: _,
+            } => {
+                assert_eq!(status, 401);
+                assert_eq!(error, "MissingAuthorizationHeader");
+            }
+            _ => panic!("unexpected error {:?}", error),
+        }
+    }
+}
@@ -0,0 +1,661 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use geoengine_datatypes::dataset::{DatasetId, InternalDatasetId};
+use geoengine_datatypes::util::Identifier;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+
+use crate::contexts::Context;
+use crate::datasets::storage::DatasetDefinition;
+use crate::error::{self, Error, Result};
+use crate::projects::{Project, ProjectId, ProjectVersionId};
+use crate::workflows::workflow::{Workflow, WorkflowId};
+
+/// The version of the backup format, which is increased on incompatible changes
+pub const BACKUP_VERSION: u32 = 1;
+
+/// A storage whose complete content can be exported and imported into another instance
+#[async_trait]
+pub trait BackupDb<T>: Send + Sync {
+    /// Exports all items regardless of sessions and permissions
+    async fn export(&self) -> Result<Vec<T>>;
+
+    /// Imports an item with its original id.
+    ///
+    /// Fails with [`Error::Duplicate`] if an item with the same id already exists.
+    async fn import(&mut self, item: T) -> Result<()>;
+}
+
+/// The complete state of an instance, i.e., all dataset definitions, workflows and the latest
+/// versions of all projects
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Backup {
+    pub version: u32,
+    pub created: DateTime<Utc>,
+    pub datasets: Vec<DatasetDefinition>,
+    pub workflows: Vec<Workflow>,
+    pub projects: Vec<Project>,
+}
+
+/// How to handle items of a backup whose ids already exist in the instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OnConflict {
+    /// Stop restoring, items that were restored before the conflict are kept
+    Abort,
+    /// Keep the existing item and skip the one from the backup
+    Skip,
+    /// Restore the item with a new id and update all references to it
+    NewId,
+}
+
+impl Default for OnConflict {
+    fn default() -> Self {
+        OnConflict::Abort
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreOptions {
+    #[serde(default)]
+    pub on_conflict: OnConflict,
+}
+
+/// An item that was restored with a new id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Remapped<T> {
+    pub original: T,
+    pub new: T,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreSummary {
+    pub restored: usize,
+    pub skipped: usize,
+    pub remapped_datasets: Vec<Remapped<DatasetId>>,
+    pub remapped_workflows: Vec<Remapped<WorkflowId>>,
+    pub remapped_projects: Vec<Remapped<ProjectId>>,
+}
+
+/// Exports the complete state of the context
+pub async fn backup<C: Context>(ctx: &C) -> Result<Backup> {
+    Ok(Backup {
+        version: BACKUP_VERSION,
+        created: Utc::now(),
+        datasets: ctx.dataset_db_ref().await.export().await?,
+        workflows: ctx.workflow_registry_ref().await.export().await?,
+        projects: ctx.project_db_ref().await.export().await?,
+    })
+}
+
+/// Restores a backup into the context
+pub async fn restore<C: Context>(
+    ctx: &C,
+    backup: Backup,
+    options: RestoreOptions,
+) -> Result<RestoreSummary> {
+    let mut restore = Restore::new(backup.version, options)?;
+
+    restore
+        .datasets(&mut *ctx.dataset_db_ref_mut().await, backup.datasets)
+        .await?;
+    restore
+        .workflows(
+            &mut *ctx.workflow_registry_ref_mut().await,
+            backup.workflows,
+        )
+        .await?;
+    restore
+        .projects(&mut *ctx.project_db_ref_mut().await, backup.projects)
+        .await?;
+
+    Ok(restore.finish())
+}
+
+/// Restores the parts of a backup and keeps track of the ids that were changed to resolve
+/// conflicts, so that references to them can be updated.
+///
+/// Datasets have to be restored before workflows and workflows before projects.
+pub struct Restore {
+    options: RestoreOptions,
+    summary: RestoreSummary,
+    dataset_ids: HashMap<DatasetId, DatasetId>,
+    workflow_ids: HashMap<WorkflowId, WorkflowId>,
+    project_ids: HashMap<ProjectId, ProjectId>,
+}
+
+impl Restore {
+    pub fn new(version: u32, options: RestoreOptions) -> Result<Self> {
+        ensure!(
+            version == BACKUP_VERSION,
+            error::UnsupportedBackupVersion {
+                found: version,
+                expected: BACKUP_VERSION,
+            }
+        );
+
+        Ok(Self {
+            options,
+            summary: RestoreSummary::default(),
+            dataset_ids: HashMap::new(),
+            workflow_ids: HashMap::new(),
+            project_ids: HashMap::new(),
+        })
+    }
+
+    pub fn options(&self) -> RestoreOptions {
+        self.options
+    }
+
+    /// The id under which the dataset of the backup was restored
+    pub fn dataset_id(&self, dataset: DatasetId) -> DatasetId {
+        self.dataset_ids.get(&dataset).cloned().unwrap_or(dataset)
+    }
+
+    /// The id under which the project of the backup was restored
+    pub fn project_id(&self, project: ProjectId) -> ProjectId {
+        self.project_ids.get(&project).copied().unwrap_or(project)
+    }
+
+    pub async fn datasets<D: BackupDb<DatasetDefinition>>(
+        &mut self,
+        db: &mut D,
+        datasets: Vec<DatasetDefinition>,
+    ) -> Result<()> {
+        for mut dataset in datasets {
+            if self.import(db, dataset.clone()).await? {
+                continue;
+            }
+
+            if let (OnConflict::NewId, Some(original)) =
+                (self.options.on_conflict, dataset.properties.id.clone())
+            {
+                let new: DatasetId = InternalDatasetId::new().into();
+                dataset.properties.id = Some(new.clone());
+
+                db.import(dataset).await?;
+
+                self.summary.restored += 1;
+                self.dataset_ids.insert(original.clone(), new.clone());
+                self.summary
+                    .remapped_datasets
+                    .push(Remapped { original, new });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Restores the workflows and updates their references to remapped datasets.
+    ///
+    /// Workflow ids are derived from the workflows, so they change if a dataset was remapped.
+    pub async fn workflows<W: BackupDb<Workflow>>(
+        &mut self,
+        db: &mut W,
+        workflows: Vec<Workflow>,
+    ) -> Result<()> {
+        for workflow in workflows {
+            let original = WorkflowId::from_hash(&workflow);
+            let workflow = replace_ids(workflow, &self.dataset_ids)?;
+            let new = WorkflowId::from_hash(&workflow);
+
+            // workflows with the same id are identical, so there are no conflicts
+            db.import(workflow).await?;
+            self.summary.restored += 1;
+
+            if new != original {
+                self.workflow_ids.insert(original, new);
+                self.summary
+                    .remapped_workflows
+                    .push(Remapped { original, new });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Restores the projects and updates their references to remapped workflows
+    pub async fn projects<P: BackupDb<Project>>(
+        &mut self,
+        db: &mut P,
+        projects: Vec<Project>,
+    ) -> Result<()> {
+        for mut project in projects {
+            for layer in &mut project.layers {
+                layer.workflow = self.workflow_id(layer.workflow);
+            }
+            for plot in &mut project.plots {
+                plot.workflow = self.workflow_id(plot.workflow);
+            }
+
+            if self.import(db, project.clone()).await? {
+                continue;
+            }
+
+            if self.options.on_conflict == OnConflict::NewId {
+                let original = project.id;
+                let new = ProjectId::new();
+                project.id = new;
+                project.version.id = ProjectVersionId::new();
+
+                db.import(project).await?;
+
+                self.summary.restored += 1;
+                self.project_ids.insert(original, new);
+                self.summary
+                    .remapped_projects
+                    .push(Remapped { original, new });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Restores items that are not referenced by other items of the backup, so they are never
+    /// remapped and skipped on conflicts unless the restore is aborted
+    pub async fn items<T, D>(&mut self, db: &mut D, items: Vec<T>) -> Result<()>
+    where
+        T: Send + 'static,
+        D: BackupDb<T>,
+    {
+        for item in items {
+            self.item(db, item).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Restores a single item like [`Restore::items`] and returns whether it was restored
+    pub async fn item<T, D>(&mut self, db: &mut D, item: T) -> Result<bool>
+    where
+        T: Send + 'static,
+        D: BackupDb<T>,
+    {
+        let restored = self.import(db, item).await?;
+
+        if !restored && self.options.on_conflict == OnConflict::NewId {
+            self.summary.skipped += 1;
+        }
+
+        Ok(restored)
+    }
+
+    pub fn finish(self) -> RestoreSummary {
+        self.summary
+    }
+
+    fn workflow_id(&self, workflow: WorkflowId) -> WorkflowId {
+        self.workflow_ids
+            .get(&workflow)
+            .copied()
+            .unwrap_or(workflow)
+    }
+
+    /// Imports the item and returns whether it was restored.
+    ///
+    /// Conflicts abort the restore or are counted as skipped, unless new ids are assigned.
+    async fn import<T, D>(&mut self, db: &mut D, item: T) -> Result<bool>
+    where
+        T: Send + 'static,
+        D: BackupDb<T>,
+    {
+        match db.import(item).await {
+            Ok(()) => {
+                self.summary.restored += 1;
+                Ok(true)
+            }
+            Err(Error::Duplicate { .. }) if self.options.on_conflict == OnConflict::Skip => {
+                self.summary.skipped += 1;
+                Ok(false)
+            }
+            Err(Error::Duplicate { .. }) if self.options.on_conflict == OnConflict::NewId => {
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Replaces all occurrences of the mapped dataset ids, e.g., in the parameters of the
+/// operators of a workflow
+fn replace_ids<T>(item: T, dataset_ids: &HashMap<DatasetId, DatasetId>) -> Result<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    if dataset_ids.is_empty() {
+        return Ok(item);
+    }
+
+    let replacements = dataset_ids
+        .iter()
+        .map(|(original, new)| Ok((serde_json::to_value(original)?, serde_json::to_value(new)?)))
+        .collect::<Result<Vec<_>, serde_json::Error>>()
+        .context(error::SerdeJson)?;
+
+    let mut value = serde_json::to_value(item).context(error::SerdeJson)?;
+    replace_values(&mut value, &replacements);
+
+    serde_json::from_value(value).context(error::SerdeJson)
+}
+
+fn replace_values(
+    value: &mut serde_json::Value,
+    replacements: &[(serde_json::Value, serde_json::Value)],
+) {
+    if let Some((_, new)) = replacements.iter().find(|(original, _)| original == value) {
+        *value = new.clone();
+        return;
+    }
+
+    match value {
+        serde_json::Value::Array(values) => {
+            for value in values {
+                replace_values(value, replacements);
+            }
+        }
+        serde_json::Value::Object(values) => {
+            for value in values.values_mut() {
+                replace_values(value, replacements);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::{InMemoryContext, SimpleContext};
+    use crate::datasets::listing::DatasetProvider;
+    use crate::datasets::storage::{AddDataset, DatasetStore, MetaDataDefinition};
+    use crate::projects::{
+        CreateProject, Layer, LayerUpdate, LayerVisibility, ProjectDb, RasterSymbology,
+        STRectangle, Symbology, UpdateProject,
+    };
+    use crate::util::user_input::UserInput;
+    use crate::workflows::registry::WorkflowRegistry;
+    use geoengine_datatypes::collections::VectorDataType;
+    use geoengine_datatypes::operations::image::Colorizer;
+    use geoengine_datatypes::spatial_reference::SpatialReferenceOption;
+    use geoengine_datatypes::util::test::TestDefault;
+    use geoengine_operators::engine::{
+        StaticMetaData, TypedOperator, VectorOperator, VectorResultDescriptor,
+    };
+    use geoengine_operators::mock::{
+        MockDatasetDataSource, MockDatasetDataSourceLoadingInfo, MockDatasetDataSourceParams,
+    };
+
+    async fn add_mock_dataset<C: SimpleContext>(ctx: &C) -> DatasetId {
+        let session = ctx.default_session_ref().await.clone();
+
+        let meta_data = MetaDataDefinition::MockMetaData(StaticMetaData {
+            loading_info: MockDatasetDataSourceLoadingInfo { points: vec![] },
+            result_descriptor: VectorResultDescriptor {
+                data_type: VectorDataType::MultiPoint,
+                spatial_reference: SpatialReferenceOption::Unreferenced,
+                columns: Default::default(),
+                column_metadata: Default::default(),
+            },
+            phantom: Default::default(),
+        });
+
+        let mut db = ctx.dataset_db_ref_mut().await;
+        let meta_data = db.wrap_meta_data(meta_data);
+        db.add_dataset(
+            &session,
+            AddDataset {
+                id: None,
+                name: "Mock".to_string(),
+                description: "A mock dataset".to_string(),
+                source_operator: "MockDatasetDataSource".to_string(),
+                symbology: None,
+                provenance: None,
+            }
+            .validated()
+            .unwrap(),
+            meta_data,
+        )
+        .await
+        .unwrap()
+    }
+
+    fn mock_workflow(dataset: DatasetId) -> Workflow {
+        Workflow {
+            operator: TypedOperator::Vector(
+                MockDatasetDataSource {
+                    params: MockDatasetDataSourceParams { dataset },
+                }
+                .boxed(),
+            ),
+        }
+    }
+
+    async fn add_project<C: SimpleContext>(ctx: &C, workflow: WorkflowId) -> ProjectId {
+        let session = ctx.default_session_ref().await.clone();
+        let mut db = ctx.project_db_ref_mut().await;
+
+        let project = db
+            .create(
+                &session,
+                CreateProject {
+                    name: "Project".to_string(),
+                    description: "A project".to_string(),
+                    bounds: STRectangle::new(
+                        SpatialReferenceOption::Unreferenced,
+                        0.,
+                        0.,
+                        1.,
+                        1.,
+                        0,
+                        1,
+                    )
+                    .unwrap(),
+                    time_step: None,
+                }
+                .validated()
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        db.update(
+            &session,
+            UpdateProject {
+                id: project,
+                name: None,
+                description: None,
+                layers: Some(vec![LayerUpdate::UpdateOrInsert(Layer {
+                    workflow,
+                    name: "Layer".to_string(),
+                    visibility: LayerVisibility::default(),
+                    symbology: Symbology::Raster(RasterSymbology {
+                        opacity: 1.,
+                        colorizer: Colorizer::Rgba,
+                    }),
+                })]),
+                plots: None,
+                bounds: None,
+                time_step: None,
+            }
+            .validated()
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+
+        project
+    }
+
+    async fn populated_context() -> (InMemoryContext, DatasetId, WorkflowId, ProjectId) {
+        let ctx = InMemoryContext::test_default();
+
+        let dataset = add_mock_dataset(&ctx).await;
+        let workflow = ctx
+            .workflow_registry_ref_mut()
+            .await
+            .register(mock_workflow(dataset.clone()))
+            .await
+            .unwrap();
+        let project = add_project(&ctx, workflow).await;
+
+        (ctx, dataset, workflow, project)
+    }
+
+    #[tokio::test]
+    async fn it_restores_into_a_fresh_instance() {
+        let (ctx, dataset, workflow, project) = populated_context().await;
+
+        let backup = backup(&ctx).await.unwrap();
+        assert_eq!(backup.version, BACKUP_VERSION);
+        assert_eq!(backup.datasets.len(), 1);
+        assert_eq!(backup.workflows.len(), 1);
+        assert_eq!(backup.projects.len(), 1);
+
+        // the backup survives serialization
+        let backup: Backup =
+            serde_json::from_str(&serde_json::to_string(&backup).unwrap()).unwrap();
+
+        let fresh = InMemoryContext::test_default();
+        let summary = restore(&fresh, backup, RestoreOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            summary,
+            RestoreSummary {
+                restored: 3,
+                ..Default::default()
+            }
+        );
+
+        let session = fresh.default_session_ref().await.clone();
+        assert_eq!(
+            fresh
+                .dataset_db_ref()
+                .await
+                .load(&session, &dataset)
+                .await
+                .unwrap()
+                .name,
+            "Mock"
+        );
+        assert!(fresh
+            .workflow_registry_ref()
+            .await
+            .load(&workflow)
+            .await
+            .is_ok());
+        assert_eq!(
+            fresh
+                .project_db_ref()
+                .await
+                .load(&session, project)
+                .await
+                .unwrap()
+                .layers[0]
+                .workflow,
+            workflow
+        );
+    }
+
+    #[tokio::test]
+    async fn it_handles_conflicts() {
+        let (ctx, dataset, workflow, project) = populated_context().await;
+        let backup = backup(&ctx).await.unwrap();
+
+        let result = restore(&ctx, backup.clone(), RestoreOptions::default()).await;
+        assert!(matches!(result, Err(Error::Duplicate { .. })));
+
+        let summary = restore(
+            &ctx,
+            backup.clone(),
+            RestoreOptions {
+                on_conflict: OnConflict::Skip,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.restored, 1); // the identical workflow
+        assert_eq!(summary.skipped, 2);
+
+        let summary = restore(
+            &ctx,
+            backup,
+            RestoreOptions {
+                on_conflict: OnConflict::NewId,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.restored, 3);
+        assert_eq!(summary.skipped, 0);
+
+        let new_dataset = summary.remapped_datasets[0].new.clone();
+        assert_eq!(summary.remapped_datasets[0].original, dataset);
+        assert_ne!(new_dataset, dataset);
+
+        let new_workflow = summary.remapped_workflows[0].new;
+        assert_eq!(summary.remapped_workflows[0].original, workflow);
+        assert_eq!(
+            new_workflow,
+            WorkflowId::from_hash(&mock_workflow(new_dataset))
+        );
+
+        let new_project = summary.remapped_projects[0].new;
+        assert_eq!(summary.remapped_projects[0].original, project);
+
+        let session = ctx.default_session_ref().await.clone();
+        assert_eq!(
+            ctx.project_db_ref()
+                .await
+                .load(&session, new_project)
+                .await
+                .unwrap()
+                .layers[0]
+                .workflow,
+            new_workflow
+        );
+    }
+
+    #[tokio::test]
+    async fn it_rejects_unsupported_versions() {
+        let ctx = InMemoryContext::test_default();
+
+        let backup = Backup {
+            version: BACKUP_VERSION + 1,
+            created: Utc::now(),
+            datasets: vec![],
+            workflows: vec![],
+            projects: vec![],
+        };
+
+        assert!(matches!(
+            restore(&ctx, backup, RestoreOptions::default()).await,
+            Err(Error::UnsupportedBackupVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn it_replaces_dataset_ids() {
+        let original: DatasetId = InternalDatasetId::new().into();
+        let new: DatasetId = InternalDatasetId::new().into();
+
+        let workflow = replace_ids(
+            mock_workflow(original.clone()),
+            &[(original, new.clone())].into_iter().collect(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            WorkflowId::from_hash(&workflow),
+            WorkflowId::from_hash(&mock_workflow(new))
+        );
+    }
+}
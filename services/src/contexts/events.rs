@@ -0,0 +1,255 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_operators::source::LoadingInfoCache;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, RwLock};
+
+use crate::workflows::cache::ResultCache;
+
+/// Number of events that are buffered for slow subscribers before they miss events
+const EVENT_BUFFER_SIZE: usize = 256;
+
+/// A change of a dataset that affects resources depending on it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum DatasetEvent {
+    /// The meta data of a dataset, e.g., its loading info, was changed
+    #[serde(rename_all = "camelCase")]
+    MetaDataChanged { dataset: DatasetId },
+    /// The data of a dataset, e.g., its files, was changed
+    #[serde(rename_all = "camelCase")]
+    DataChanged { dataset: DatasetId },
+    /// The dataset was deleted
+    #[serde(rename_all = "camelCase")]
+    Deleted { dataset: DatasetId },
+    /// A dataset was materialized from a workflow whose input has changed since
+    #[serde(rename_all = "camelCase")]
+    Stale {
+        dataset: DatasetId,
+        changed_source: DatasetId,
+    },
+}
+
+impl DatasetEvent {
+    pub fn dataset(&self) -> &DatasetId {
+        match self {
+            DatasetEvent::MetaDataChanged { dataset }
+            | DatasetEvent::DataChanged { dataset }
+            | DatasetEvent::Deleted { dataset }
+            | DatasetEvent::Stale { dataset, .. } => dataset,
+        }
+    }
+}
+
+/// Distributes dataset changes to the resources that depend on the dataset.
+///
+/// Publishing an event invalidates the cached results and loading infos of the dataset,
+/// marks datasets that were materialized from it as stale and notifies all subscribers.
+/// The dependencies between datasets and the times of their last changes are only kept in memory.
+pub struct DatasetEventBus {
+    sender: broadcast::Sender<DatasetEvent>,
+    result_cache: Arc<ResultCache>,
+    /// maps materialized datasets to the datasets they were computed from
+    sources: RwLock<HashMap<DatasetId, Vec<DatasetId>>>,
+    stale: RwLock<HashSet<DatasetId>>,
+    /// changes before the bus was created are unknown, so they are assumed to happen at creation
+    created: DateTime<Utc>,
+    last_changes: RwLock<HashMap<DatasetId, DateTime<Utc>>>,
+}
+
+impl DatasetEventBus {
+    pub fn new(result_cache: Arc<ResultCache>) -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER_SIZE);
+
+        Self {
+            sender,
+            result_cache,
+            sources: Default::default(),
+            stale: Default::default(),
+            created: Utc::now(),
+            last_changes: Default::default(),
+        }
+    }
+
+    /// Receives all events that are published after subscribing
+    pub fn subscribe(&self) -> broadcast::Receiver<DatasetEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Remembers that `dataset` was computed from the `sources` datasets
+    pub async fn register_materialized_dataset(&self, dataset: DatasetId, sources: Vec<DatasetId>) {
+        self.sources.write().await.insert(dataset, sources);
+    }
+
+    /// Whether the dataset was materialized from datasets that have changed since
+    pub async fn is_stale(&self, dataset: &DatasetId) -> bool {
+        self.stale.read().await.contains(dataset)
+    }
+
+    /// The time of the latest change of any of the `datasets`, e.g., for `Last-Modified` headers
+    pub async fn last_modified(&self, datasets: &[DatasetId]) -> DateTime<Utc> {
+        let last_changes = self.last_changes.read().await;
+
+        datasets
+            .iter()
+            .filter_map(|dataset| last_changes.get(dataset))
+            .copied()
+            .fold(self.created, DateTime::max)
+    }
+
+    pub async fn publish(&self, event: DatasetEvent) {
+        let changed = event.dataset().clone();
+
+        if !matches!(event, DatasetEvent::Stale { .. }) {
+            self.result_cache.invalidate_dataset(&changed).await;
+            LoadingInfoCache::global().invalidate_dataset(&changed);
+            self.last_changes
+                .write()
+                .await
+                .insert(changed.clone(), Utc::now());
+        }
+
+        let stale_datasets = self.mark_dependents_stale(&changed).await;
+
+        self.send(event);
+
+        for (dataset, changed_source) in stale_datasets {
+            self.send(DatasetEvent::Stale {
+                dataset,
+                changed_source,
+            });
+        }
+    }
+
+    /// Marks all datasets that were (transitively) materialized from `changed` as stale and
+    /// returns the newly stale datasets together with the source they were derived from
+    async fn mark_dependents_stale(&self, changed: &DatasetId) -> Vec<(DatasetId, DatasetId)> {
+        let sources = self.sources.read().await;
+        let mut stale = self.stale.write().await;
+
+        let mut newly_stale = Vec::new();
+        let mut changed_datasets = vec![changed.clone()];
+
+        while let Some(changed) = changed_datasets.pop() {
+            for (dataset, dataset_sources) in sources.iter() {
+                if dataset_sources.contains(&changed) && stale.insert(dataset.clone()) {
+                    newly_stale.push((dataset.clone(), changed.clone()));
+                    changed_datasets.push(dataset.clone());
+                }
+            }
+        }
+
+        newly_stale
+    }
+
+    fn send(&self, event: DatasetEvent) {
+        // sending only fails if there are no subscribers, which is fine
+        drop(self.sender.send(event));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflows::cache::{CacheKey, CachedResult};
+    use crate::workflows::workflow::WorkflowId;
+    use geoengine_datatypes::dataset::InternalDatasetId;
+    use geoengine_datatypes::util::Identifier;
+
+    #[tokio::test]
+    async fn it_propagates_changes() {
+        let result_cache = Arc::new(ResultCache::new(1024));
+        let events = DatasetEventBus::new(result_cache.clone());
+
+        let source: DatasetId = InternalDatasetId::new().into();
+        let materialized: DatasetId = InternalDatasetId::new().into();
+        let materialized_from_materialized: DatasetId = InternalDatasetId::new().into();
+
+        events
+            .register_materialized_dataset(materialized.clone(), vec![source.clone()])
+            .await;
+        events
+            .register_materialized_dataset(
+                materialized_from_materialized.clone(),
+                vec![materialized.clone()],
+            )
+            .await;
+
+        let workflow = WorkflowId::new();
+        result_cache
+            .insert(
+                CacheKey {
+                    workflow,
+                    query: "query".to_string(),
+                },
+                CachedResult {
+                    content_type: "image/png".to_string(),
+                    bytes: vec![0; 8].into(),
+                },
+                vec![source.clone()],
+            )
+            .await;
+
+        let mut receiver = events.subscribe();
+
+        events
+            .publish(DatasetEvent::DataChanged {
+                dataset: source.clone(),
+            })
+            .await;
+
+        assert!(result_cache.entries(&workflow).await.is_empty());
+        assert!(!events.is_stale(&source).await);
+        assert!(events.is_stale(&materialized).await);
+        assert!(events.is_stale(&materialized_from_materialized).await);
+
+        assert_eq!(
+            receiver.recv().await.unwrap(),
+            DatasetEvent::DataChanged {
+                dataset: source.clone()
+            }
+        );
+        assert_eq!(
+            receiver.recv().await.unwrap(),
+            DatasetEvent::Stale {
+                dataset: materialized.clone(),
+                changed_source: source,
+            }
+        );
+        assert_eq!(
+            receiver.recv().await.unwrap(),
+            DatasetEvent::Stale {
+                dataset: materialized_from_materialized,
+                changed_source: materialized,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_tracks_last_changes() {
+        let events = DatasetEventBus::new(Arc::new(ResultCache::new(0)));
+
+        let changed: DatasetId = InternalDatasetId::new().into();
+        let unchanged: DatasetId = InternalDatasetId::new().into();
+
+        let created = events.last_modified(&[]).await;
+        assert_eq!(events.last_modified(&[unchanged.clone()]).await, created);
+
+        events
+            .publish(DatasetEvent::MetaDataChanged {
+                dataset: changed.clone(),
+            })
+            .await;
+
+        let last_modified = events.last_modified(&[changed.clone()]).await;
+        assert!(last_modified >= created);
+        assert_eq!(
+            events.last_modified(&[unchanged.clone(), changed]).await,
+            last_modified
+        );
+        assert_eq!(events.last_modified(&[unchanged]).await, created);
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::Utc;
+use snafu::ensure;
+
+use crate::contexts::{Session, SessionId};
+use crate::error::{self, Result};
+use crate::util::config::{self, get_config_element};
+
+/// The read-only guest sessions of a context, which are only kept in memory.
+///
+/// Each guest session may execute a limited number of workflows and the number of guest
+/// sessions that are valid at the same time is limited as well.
+pub struct GuestSessions<S> {
+    sessions: Mutex<HashMap<SessionId, GuestSession<S>>>,
+}
+
+struct GuestSession<S> {
+    session: S,
+    remaining_queries: u32,
+}
+
+impl<S> Default for GuestSessions<S> {
+    fn default() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<S> GuestSessions<S>
+where
+    S: Session + Clone,
+{
+    /// Adds a new guest session unless the maximum number of guest sessions is reached
+    pub fn add(&self, session: S) -> Result<S> {
+        debug_assert!(session.is_guest());
+
+        let config = get_config_element::<config::Session>()?;
+
+        let mut sessions = self.sessions.lock().expect("the lock is not poisoned");
+
+        let now = Utc::now();
+        sessions.retain(|_, guest| *guest.session.valid_until() > now);
+
+        ensure!(
+            sessions.len() < config.guest_max_sessions,
+            error::GuestSessionLimitReached
+        );
+
+        sessions.insert(
+            session.id(),
+            GuestSession {
+                session: session.clone(),
+                remaining_queries: config.guest_max_queries,
+            },
+        );
+
+        Ok(session)
+    }
+
+    /// Returns the guest session with the given id if it exists and did not expire yet
+    pub fn session(&self, session_id: SessionId) -> Option<S> {
+        let sessions = self.sessions.lock().expect("the lock is not poisoned");
+
+        sessions
+            .get(&session_id)
+            .filter(|guest| *guest.session.valid_until() > Utc::now())
+            .map(|guest| guest.session.clone())
+    }
+
+    /// Counts the execution of a workflow against the quota of a guest session.
+    /// Sessions of registered or anonymous users are not limited.
+    pub fn charge_query(&self, session: &S) -> Result<()> {
+        if !session.is_guest() {
+            return Ok(());
+        }
+
+        let mut sessions = self.sessions.lock().expect("the lock is not poisoned");
+
+        let guest = sessions
+            .get_mut(&session.id())
+            .ok_or(error::Error::InvalidSession)?;
+
+        ensure!(
+            guest.remaining_queries > 0,
+            error::GuestQuotaExceeded {
+                limit: get_config_element::<config::Session>()?.guest_max_queries
+            }
+        );
+
+        guest.remaining_queries -= 1;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::SimpleSession;
+
+    #[test]
+    fn it_limits_the_queries_of_guests() {
+        let guests = GuestSessions::default();
+
+        let session = guests.add(SimpleSession::guest().unwrap()).unwrap();
+        assert_eq!(guests.session(session.id()), Some(session.clone()));
+
+        let limit = get_config_element::<config::Session>()
+            .unwrap()
+            .guest_max_queries;
+
+        for _ in 0..limit {
+            guests.charge_query(&session).unwrap();
+        }
+
+        assert!(matches!(
+            guests.charge_query(&session),
+            Err(error::Error::GuestQuotaExceeded { .. })
+        ));
+
+        // sessions that are not guests are not limited
+        guests.charge_query(&SimpleSession::default()).unwrap();
+    }
+
+    #[test]
+    fn it_ignores_unknown_sessions() {
+        let guests = GuestSessions::<SimpleSession>::default();
+
+        assert_eq!(guests.session(SessionId::new()), None);
+        assert!(guests
+            .charge_query(&SimpleSession::guest().unwrap())
+            .is_err());
+    }
+}
@@ -3,9 +3,14 @@ use std::sync::Arc;
 
 use super::{Context, Db, SimpleSession};
 use super::{Session, SimpleContext};
-use crate::contexts::{ExecutionContextImpl, QueryContextImpl, SessionId};
+use crate::contexts::DatasetEventBus;
+use crate::contexts::{
+    tile_buffer_pool_from_config, ExecutionContextImpl, GuestSessions, QueryContextImpl, SessionId,
+};
 use crate::datasets::in_memory::HashMapDatasetDb;
 use crate::error::Error;
+use crate::object_storage::{object_storage_from_config, ObjectStorage};
+use crate::workflows::cache::ResultCache;
 use crate::{
     datasets::add_from_directory::{add_datasets_from_directory, add_providers_from_directory},
     error::Result,
@@ -15,6 +20,7 @@ use async_trait::async_trait;
 use geoengine_datatypes::raster::TilingSpecification;
 use geoengine_datatypes::util::test::TestDefault;
 use geoengine_operators::engine::ChunkByteSize;
+use geoengine_operators::engine::TileBufferPool;
 use geoengine_operators::util::create_rayon_thread_pool;
 use rayon::ThreadPool;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
@@ -25,20 +31,32 @@ pub struct InMemoryContext {
     project_db: Db<HashMapProjectDb>,
     workflow_registry: Db<HashMapRegistry>,
     dataset_db: Db<HashMapDatasetDb>,
+    result_cache: Arc<ResultCache>,
+    dataset_events: Arc<DatasetEventBus>,
+    object_storage: Arc<dyn ObjectStorage>,
+    guest_sessions: Arc<GuestSessions<SimpleSession>>,
     session: Db<SimpleSession>,
     thread_pool: Arc<ThreadPool>,
+    tile_buffer_pool: Arc<TileBufferPool>,
     exe_ctx_tiling_spec: TilingSpecification,
     query_ctx_chunk_size: ChunkByteSize,
 }
 
 impl TestDefault for InMemoryContext {
     fn test_default() -> Self {
+        let result_cache = Arc::new(ResultCache::from_config());
+
         Self {
             project_db: Default::default(),
             workflow_registry: Default::default(),
             dataset_db: Default::default(),
+            dataset_events: Arc::new(DatasetEventBus::new(result_cache.clone())),
+            result_cache,
+            object_storage: object_storage_from_config(),
+            guest_sessions: Default::default(),
             session: Default::default(),
             thread_pool: create_rayon_thread_pool(0),
+            tile_buffer_pool: tile_buffer_pool_from_config(),
             exe_ctx_tiling_spec: TestDefault::test_default(),
             query_ctx_chunk_size: TestDefault::test_default(),
         }
@@ -56,14 +74,21 @@ impl InMemoryContext {
         add_datasets_from_directory(&mut db, dataset_defs_path).await;
         add_providers_from_directory(&mut db, provider_defs_path).await;
 
+        let result_cache = Arc::new(ResultCache::from_config());
+
         Self {
             project_db: Default::default(),
             workflow_registry: Default::default(),
             session: Default::default(),
             thread_pool: create_rayon_thread_pool(0),
+            tile_buffer_pool: tile_buffer_pool_from_config(),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
             dataset_db: Arc::new(RwLock::new(db)),
+            dataset_events: Arc::new(DatasetEventBus::new(result_cache.clone())),
+            result_cache,
+            object_storage: object_storage_from_config(),
+            guest_sessions: Default::default(),
         }
     }
 
@@ -71,12 +96,19 @@ impl InMemoryContext {
         exe_ctx_tiling_spec: TilingSpecification,
         query_ctx_chunk_size: ChunkByteSize,
     ) -> Self {
+        let result_cache = Arc::new(ResultCache::from_config());
+
         Self {
             project_db: Default::default(),
             workflow_registry: Default::default(),
             dataset_db: Default::default(),
+            dataset_events: Arc::new(DatasetEventBus::new(result_cache.clone())),
+            result_cache,
+            object_storage: object_storage_from_config(),
+            guest_sessions: Default::default(),
             session: Default::default(),
             thread_pool: create_rayon_thread_pool(0),
+            tile_buffer_pool: tile_buffer_pool_from_config(),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
         }
@@ -90,7 +122,7 @@ impl Context for InMemoryContext {
     type WorkflowRegistry = HashMapRegistry;
     type DatasetDB = HashMapDatasetDb;
     type QueryContext = QueryContextImpl;
-    type ExecutionContext = ExecutionContextImpl<SimpleSession, HashMapDatasetDb>;
+    type ExecutionContext = ExecutionContextImpl<SimpleSession, HashMapDatasetDb, HashMapRegistry>;
 
     fn project_db(&self) -> Db<Self::ProjectDB> {
         self.project_db.clone()
@@ -122,6 +154,22 @@ impl Context for InMemoryContext {
         self.dataset_db.write().await
     }
 
+    fn result_cache(&self) -> Arc<ResultCache> {
+        self.result_cache.clone()
+    }
+
+    fn dataset_events(&self) -> Arc<DatasetEventBus> {
+        self.dataset_events.clone()
+    }
+
+    fn object_storage(&self) -> Arc<dyn ObjectStorage> {
+        self.object_storage.clone()
+    }
+
+    fn guest_sessions(&self) -> Arc<GuestSessions<Self::Session>> {
+        self.guest_sessions.clone()
+    }
+
     fn query_context(&self) -> Result<Self::QueryContext> {
         Ok(QueryContextImpl {
             chunk_byte_size: self.query_ctx_chunk_size,
@@ -130,17 +178,27 @@ impl Context for InMemoryContext {
     }
 
     fn execution_context(&self, session: SimpleSession) -> Result<Self::ExecutionContext> {
-        Ok(
-            ExecutionContextImpl::<SimpleSession, HashMapDatasetDb>::new(
-                self.dataset_db.clone(),
-                self.thread_pool.clone(),
-                session,
-                self.exe_ctx_tiling_spec,
-            ),
-        )
+        self.guest_sessions.charge_query(&session)?;
+
+        Ok(ExecutionContextImpl::<
+            SimpleSession,
+            HashMapDatasetDb,
+            HashMapRegistry,
+        >::new(
+            self.dataset_db.clone(),
+            self.workflow_registry.clone(),
+            self.thread_pool.clone(),
+            self.tile_buffer_pool.clone(),
+            session,
+            self.exe_ctx_tiling_spec,
+        ))
     }
 
     async fn session_by_id(&self, session_id: SessionId) -> Result<Self::Session> {
+        if let Some(guest) = self.guest_sessions.session(session_id) {
+            return Ok(guest);
+        }
+
         let default_session = self.default_session_ref().await;
 
         if default_session.id() != session_id {
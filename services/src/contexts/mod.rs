@@ -1,4 +1,10 @@
+use crate::datasets::storage::DatasetDefinition;
 use crate::error::Result;
+use crate::object_storage::ObjectStorage;
+use crate::projects::Project;
+use crate::util::config::{self, get_config_element};
+use crate::workflows::cache::ResultCache;
+use crate::workflows::workflow::Workflow;
 use crate::{projects::ProjectDb, workflows::registry::WorkflowRegistry};
 use async_trait::async_trait;
 use geoengine_datatypes::primitives::{RasterQueryRectangle, VectorQueryRectangle};
@@ -6,6 +12,9 @@ use rayon::ThreadPool;
 use std::sync::Arc;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
+pub mod backup;
+mod events;
+mod guest;
 mod in_memory;
 mod session;
 mod simple_context;
@@ -16,13 +25,16 @@ use geoengine_datatypes::dataset::DatasetId;
 
 use geoengine_datatypes::raster::TilingSpecification;
 use geoengine_operators::engine::{
-    ChunkByteSize, ExecutionContext, MetaData, MetaDataProvider, QueryContext,
-    RasterResultDescriptor, VectorResultDescriptor,
+    ChunkByteSize, ExecutionContext, MacroProvider, MetaData, MetaDataProvider, QueryContext,
+    RasterResultDescriptor, TileBufferPool, VectorResultDescriptor, DEFAULT_TILE_BUFFER_POOL_BYTES,
 };
 use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
 use geoengine_operators::source::{GdalLoadingInfo, OgrSourceDataset};
 
 use crate::datasets::listing::SessionMetaDataProvider;
+pub use backup::BackupDb;
+pub use events::{DatasetEvent, DatasetEventBus};
+pub use guest::GuestSessions;
 pub use in_memory::InMemoryContext;
 pub use session::{MockableSession, Session, SessionId, SimpleSession};
 pub use simple_context::SimpleContext;
@@ -35,10 +47,10 @@ pub type Db<T> = Arc<RwLock<T>>;
 #[async_trait]
 pub trait Context: 'static + Send + Sync + Clone {
     type Session: MockableSession + Clone; // TODO: change to `[Session]` when workarounds are gone
-    type ProjectDB: ProjectDb<Self::Session>;
-    type WorkflowRegistry: WorkflowRegistry;
-    type DatasetDB: DatasetDb<Self::Session>;
-    type QueryContext: QueryContext;
+    type ProjectDB: ProjectDb<Self::Session> + BackupDb<Project>;
+    type WorkflowRegistry: WorkflowRegistry + BackupDb<Workflow>;
+    type DatasetDB: DatasetDb<Self::Session> + BackupDb<DatasetDefinition>;
+    type QueryContext: QueryContext + 'static;
     type ExecutionContext: ExecutionContext;
 
     fn project_db(&self) -> Db<Self::ProjectDB>;
@@ -53,6 +65,14 @@ pub trait Context: 'static + Send + Sync + Clone {
     async fn dataset_db_ref(&self) -> RwLockReadGuard<Self::DatasetDB>;
     async fn dataset_db_ref_mut(&self) -> RwLockWriteGuard<Self::DatasetDB>;
 
+    fn result_cache(&self) -> Arc<ResultCache>;
+
+    fn dataset_events(&self) -> Arc<DatasetEventBus>;
+
+    fn object_storage(&self) -> Arc<dyn ObjectStorage>;
+
+    fn guest_sessions(&self) -> Arc<GuestSessions<Self::Session>>;
+
     fn query_context(&self) -> Result<Self::QueryContext>;
 
     fn execution_context(&self, session: Self::Session) -> Result<Self::ExecutionContext>;
@@ -60,6 +80,14 @@ pub trait Context: 'static + Send + Sync + Clone {
     async fn session_by_id(&self, session_id: SessionId) -> Result<Self::Session>;
 }
 
+/// Creates the pool of tile buffers that is shared by the execution contexts of a context
+pub fn tile_buffer_pool_from_config() -> Arc<TileBufferPool> {
+    let max_bytes = get_config_element::<config::TileBufferPool>()
+        .map_or(DEFAULT_TILE_BUFFER_POOL_BYTES, |config| config.max_bytes);
+
+    Arc::new(TileBufferPool::new(max_bytes))
+}
+
 pub struct QueryContextImpl {
     chunk_byte_size: ChunkByteSize,
     pub thread_pool: Arc<ThreadPool>,
@@ -84,38 +112,46 @@ impl QueryContext for QueryContextImpl {
     }
 }
 
-pub struct ExecutionContextImpl<S, D>
+pub struct ExecutionContextImpl<S, D, W>
 where
     D: DatasetDb<S>,
     S: Session,
+    W: WorkflowRegistry,
 {
     dataset_db: Db<D>,
+    workflow_registry: Db<W>,
     thread_pool: Arc<ThreadPool>,
+    tile_buffer_pool: Arc<TileBufferPool>,
     session: S,
     tiling_specification: TilingSpecification,
 }
 
-impl<S, D> ExecutionContextImpl<S, D>
+impl<S, D, W> ExecutionContextImpl<S, D, W>
 where
     D: DatasetDb<S>,
     S: Session,
+    W: WorkflowRegistry,
 {
     pub fn new(
         dataset_db: Db<D>,
+        workflow_registry: Db<W>,
         thread_pool: Arc<ThreadPool>,
+        tile_buffer_pool: Arc<TileBufferPool>,
         session: S,
         tiling_specification: TilingSpecification,
     ) -> Self {
         Self {
             dataset_db,
+            workflow_registry,
             thread_pool,
+            tile_buffer_pool,
             session,
             tiling_specification,
         }
     }
 }
 
-impl<S, D> ExecutionContext for ExecutionContextImpl<S, D>
+impl<S, D, W> ExecutionContext for ExecutionContextImpl<S, D, W>
 where
     D: DatasetDb<S>
         + SessionMetaDataProvider<
@@ -126,6 +162,7 @@ where
         > + SessionMetaDataProvider<S, OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
         + SessionMetaDataProvider<S, GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>,
     S: Session,
+    W: WorkflowRegistry,
 {
     fn thread_pool(&self) -> &Arc<ThreadPool> {
         &self.thread_pool
@@ -134,13 +171,41 @@ where
     fn tiling_specification(&self) -> TilingSpecification {
         self.tiling_specification
     }
+
+    fn tile_buffer_pool(&self) -> &Arc<TileBufferPool> {
+        &self.tile_buffer_pool
+    }
+}
+
+#[async_trait]
+impl<S, D, W> MacroProvider for ExecutionContextImpl<S, D, W>
+where
+    D: DatasetDb<S>,
+    S: Session,
+    W: WorkflowRegistry,
+{
+    async fn workflow_macro(
+        &self,
+        name: &str,
+        version: Option<u32>,
+    ) -> Result<serde_json::Value, geoengine_operators::error::Error> {
+        self.workflow_registry
+            .read()
+            .await
+            .load_macro(name, version)
+            .await
+            .map(|workflow_macro| workflow_macro.operator)
+            .map_err(|e| geoengine_operators::error::Error::WorkflowMacro {
+                source: Box::new(e),
+            })
+    }
 }
 
 // TODO: use macro(?) for delegating meta_data function to DatasetDB to avoid redundant code
 #[async_trait]
-impl<S, D>
+impl<S, D, W>
     MetaDataProvider<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>
-    for ExecutionContextImpl<S, D>
+    for ExecutionContextImpl<S, D, W>
 where
     D: DatasetDb<S>
         + SessionMetaDataProvider<
@@ -150,6 +215,7 @@ where
             VectorQueryRectangle,
         >,
     S: Session,
+    W: WorkflowRegistry,
 {
     async fn meta_data(
         &self,
@@ -192,12 +258,13 @@ where
 
 // TODO: use macro(?) for delegating meta_data function to DatasetDB to avoid redundant code
 #[async_trait]
-impl<S, D> MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
-    for ExecutionContextImpl<S, D>
+impl<S, D, W> MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
+    for ExecutionContextImpl<S, D, W>
 where
     D: DatasetDb<S>
         + SessionMetaDataProvider<S, OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
     S: Session,
+    W: WorkflowRegistry,
 {
     async fn meta_data(
         &self,
@@ -234,12 +301,13 @@ where
 
 // TODO: use macro(?) for delegating meta_data function to DatasetDB to avoid redundant code
 #[async_trait]
-impl<S, D> MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
-    for ExecutionContextImpl<S, D>
+impl<S, D, W> MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
+    for ExecutionContextImpl<S, D, W>
 where
     D: DatasetDb<S>
         + SessionMetaDataProvider<S, GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>,
     S: Session,
+    W: WorkflowRegistry,
 {
     async fn meta_data(
         &self,
@@ -21,6 +21,19 @@ pub trait Session: Send + Sync + Serialize {
     fn valid_until(&self) -> &DateTime<Utc>;
     fn project(&self) -> Option<ProjectId>;
     fn view(&self) -> Option<&STRectangle>;
+    /// Guest sessions are read-only and may only execute a limited number of workflows
+    fn is_guest(&self) -> bool;
+
+    /// Fails for guest sessions, i.e., for requests that modify data
+    fn ensure_not_guest(&self) -> error::Result<()> {
+        if self.is_guest() {
+            return Err(error::Error::Authorization {
+                source: Box::new(error::Error::GuestSessionReadOnly),
+            });
+        }
+
+        Ok(())
+    }
 }
 
 pub trait MockableSession: Session {
@@ -28,10 +41,33 @@ pub trait MockableSession: Session {
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct SimpleSession {
     id: SessionId,
     pub project: Option<ProjectId>,
     pub view: Option<STRectangle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid_until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    guest: bool,
+}
+
+impl SimpleSession {
+    /// Creates a new read-only guest session that expires after the configured duration
+    pub fn guest() -> error::Result<Self> {
+        let config = config::get_config_element::<crate::util::config::Session>()?;
+
+        Ok(Self {
+            id: SessionId::new(),
+            project: None,
+            view: None,
+            valid_until: Some(
+                Utc::now()
+                    + chrono::Duration::minutes(config.guest_session_duration_minutes.into()),
+            ),
+            guest: true,
+        })
+    }
 }
 
 impl Default for SimpleSession {
@@ -45,6 +81,8 @@ impl Default for SimpleSession {
             id,
             project: None,
             view: None,
+            valid_until: None,
+            guest: false,
         }
     }
 }
@@ -59,7 +97,7 @@ impl Session for SimpleSession {
     }
 
     fn valid_until(&self) -> &DateTime<Utc> {
-        &MAX_DATETIME
+        self.valid_until.as_ref().unwrap_or(&MAX_DATETIME)
     }
 
     fn project(&self) -> Option<ProjectId> {
@@ -69,6 +107,10 @@ impl Session for SimpleSession {
     fn view(&self) -> Option<&STRectangle> {
         self.view.as_ref()
     }
+
+    fn is_guest(&self) -> bool {
+        self.guest
+    }
 }
 
 impl MockableSession for SimpleSession {
@@ -8,6 +8,7 @@ use crate::util::user_input::UserInput;
 use crate::{contexts::MockableSession, datasets::storage::DatasetDb};
 use crate::{datasets::storage::ExternalDatasetProviderDefinition, error::Result};
 
+use super::preview;
 use super::storage::DatasetDefinition;
 
 use log::warn;
@@ -23,12 +24,21 @@ pub async fn add_datasets_from_directory<S: MockableSession, D: DatasetDb<S>>(
         let def: DatasetDefinition =
             serde_json::from_reader(BufReader::new(File::open(entry.path())?))?;
 
-        db.add_dataset(
-            &S::mock(), // TODO: find suitable way to add public dataset
-            def.properties.validated()?,
-            db.wrap_meta_data(def.meta_data),
-        )
-        .await?; // TODO: add as system user
+        let preview_meta_data = def.meta_data.clone();
+        let symbology = def.properties.symbology.clone();
+
+        let id = db
+            .add_dataset(
+                &S::mock(), // TODO: find suitable way to add public dataset
+                def.properties.validated()?,
+                db.wrap_meta_data(def.meta_data),
+            )
+            .await?; // TODO: add as system user
+
+        // previews are rendered only once as the datasets are added on every start
+        if let Err(e) = preview::create_preview(&id, preview_meta_data, symbology, false).await {
+            warn!("Could not create the preview of dataset {:?}: {}", id, e);
+        }
 
         Ok(())
     }
@@ -0,0 +1,261 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use geoengine_datatypes::dataset::DatasetId;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::contexts::Context;
+use crate::datasets::listing::{DatasetProvider, Provenance};
+use crate::error::Result;
+
+/// The formats in which the provenance of datasets can be exported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CitationFormat {
+    /// `@misc` entries of a BibTeX bibliography
+    Bibtex,
+    /// A list of DataCite metadata records in JSON
+    DataCite,
+}
+
+impl Default for CitationFormat {
+    fn default() -> Self {
+        CitationFormat::Bibtex
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CitationOptions {
+    #[serde(default)]
+    pub format: CitationFormat,
+}
+
+/// The provenance of a dataset together with the date it was retrieved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Citation {
+    pub dataset: DatasetId,
+    /// The name of internal datasets
+    pub name: Option<String>,
+    pub provenance: Option<Provenance>,
+    pub retrieved: DateTime<Utc>,
+}
+
+/// Collects the provenance of the `datasets`, ignoring duplicates
+pub async fn dataset_citations<C: Context>(
+    ctx: &C,
+    session: &C::Session,
+    datasets: Vec<DatasetId>,
+) -> Result<Vec<Citation>> {
+    let retrieved = Utc::now();
+    let db = ctx.dataset_db_ref().await;
+
+    let mut seen = HashSet::new();
+    let mut citations = Vec::new();
+
+    for dataset in datasets {
+        if !seen.insert(dataset.clone()) {
+            continue;
+        }
+
+        let provenance = db.provenance(session, &dataset).await?.provenance;
+
+        let name = match dataset {
+            DatasetId::Internal { .. } => Some(db.load(session, &dataset).await?.name),
+            DatasetId::External(_) => None,
+        };
+
+        citations.push(Citation {
+            dataset,
+            name,
+            provenance,
+            retrieved,
+        });
+    }
+
+    Ok(citations)
+}
+
+/// Renders the citations as `@misc` BibTeX entries
+pub fn to_bibtex(citations: &[Citation]) -> String {
+    citations
+        .iter()
+        .map(|citation| {
+            let mut fields = vec![("title", escape_bibtex(&title(citation)))];
+
+            if let Some(provenance) = &citation.provenance {
+                fields.push(("note", escape_bibtex(&provenance.citation)));
+                fields.push(("copyright", escape_bibtex(&provenance.license)));
+
+                if let Some(version) = &provenance.version {
+                    fields.push(("version", escape_bibtex(version)));
+                }
+
+                // urls must not be escaped
+                if !provenance.uri.is_empty() {
+                    fields.push(("url", provenance.uri.clone()));
+                }
+            }
+
+            fields.push(("urldate", citation.retrieved.format("%Y-%m-%d").to_string()));
+
+            let fields: String = fields
+                .into_iter()
+                .map(|(key, value)| format!("  {} = {{{}}},\n", key, value))
+                .collect();
+
+            format!(
+                "@misc{{{},\n{}}}\n",
+                citation_key(&citation.dataset),
+                fields
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Renders the citations as a list of DataCite metadata records
+pub fn to_data_cite(citations: &[Citation]) -> serde_json::Value {
+    citations
+        .iter()
+        .map(|citation| {
+            let mut record = json!({
+                "identifiers": [{
+                    "identifier": citation_key(&citation.dataset),
+                    "identifierType": "Geo Engine dataset"
+                }],
+                "titles": [{ "title": title(citation) }],
+                "types": { "resourceTypeGeneral": "Dataset" },
+                "dates": [{
+                    "date": citation.retrieved.to_rfc3339_opts(SecondsFormat::Secs, true),
+                    "dateType": "Accessed"
+                }],
+            });
+
+            if let Some(provenance) = &citation.provenance {
+                record["descriptions"] = json!([{
+                    "description": provenance.citation,
+                    "descriptionType": "Other"
+                }]);
+                record["rightsList"] = json!([{ "rights": provenance.license }]);
+
+                if let Some(version) = &provenance.version {
+                    record["version"] = json!(version);
+                }
+
+                if !provenance.uri.is_empty() {
+                    record["url"] = json!(provenance.uri);
+                }
+            }
+
+            record
+        })
+        .collect()
+}
+
+fn title(citation: &Citation) -> String {
+    match (&citation.name, &citation.provenance) {
+        (Some(name), _) => name.clone(),
+        (None, Some(provenance)) if !provenance.citation.is_empty() => provenance.citation.clone(),
+        (None, _) => citation_key(&citation.dataset),
+    }
+}
+
+/// A key that consists only of characters that are allowed in BibTeX keys
+fn citation_key(dataset: &DatasetId) -> String {
+    let id = match dataset {
+        DatasetId::Internal { dataset_id } => dataset_id.to_string(),
+        DatasetId::External(id) => format!("{}:{}", id.provider_id, id.dataset_id),
+    };
+
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == ':' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Escapes the characters that have a special meaning in BibTeX fields
+fn escape_bibtex(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '{' | '}' | '%' | '&' | '$' | '#' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use geoengine_datatypes::dataset::InternalDatasetId;
+    use std::str::FromStr;
+
+    fn citation() -> Citation {
+        Citation {
+            dataset: InternalDatasetId::from_str("9c874b9e-cea0-4553-b727-a13cb26ae4bb")
+                .unwrap()
+                .into(),
+            name: Some("NDVI".to_string()),
+            provenance: Some(Provenance {
+                citation: "Author, NDVI & more".to_string(),
+                license: "CC-BY 4.0".to_string(),
+                uri: "http://example.org/ndvi_data".to_string(),
+                version: Some("2.1".to_string()),
+            }),
+            retrieved: Utc.ymd(2022, 3, 1).and_hms(12, 0, 0),
+        }
+    }
+
+    #[test]
+    fn it_renders_bibtex() {
+        assert_eq!(
+            to_bibtex(&[citation()]),
+            "@misc{9c874b9e-cea0-4553-b727-a13cb26ae4bb,\n  \
+            title = {NDVI},\n  \
+            note = {Author, NDVI \\& more},\n  \
+            copyright = {CC-BY 4.0},\n  \
+            version = {2.1},\n  \
+            url = {http://example.org/ndvi_data},\n  \
+            urldate = {2022-03-01},\n\
+            }\n"
+        );
+    }
+
+    #[test]
+    fn it_renders_data_cite() {
+        assert_eq!(
+            to_data_cite(&[citation()]),
+            json!([{
+                "identifiers": [{
+                    "identifier": "9c874b9e-cea0-4553-b727-a13cb26ae4bb",
+                    "identifierType": "Geo Engine dataset"
+                }],
+                "titles": [{ "title": "NDVI" }],
+                "types": { "resourceTypeGeneral": "Dataset" },
+                "dates": [{ "date": "2022-03-01T12:00:00Z", "dateType": "Accessed" }],
+                "descriptions": [{
+                    "description": "Author, NDVI & more",
+                    "descriptionType": "Other"
+                }],
+                "rightsList": [{ "rights": "CC-BY 4.0" }],
+                "version": "2.1",
+                "url": "http://example.org/ndvi_data"
+            }])
+        );
+    }
+}
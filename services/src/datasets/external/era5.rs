@@ -0,0 +1,732 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::datasets::listing::{
+    DatasetListOptions, DatasetListing, ExternalDatasetProvider, Provenance, ProvenanceOutput,
+};
+use crate::datasets::storage::ExternalDatasetProviderDefinition;
+use crate::error::{self, Error, Result};
+use crate::util::user_input::Validated;
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, Timelike};
+use geoengine_datatypes::dataset::{DatasetId, DatasetProviderId, ExternalDatasetId};
+use geoengine_datatypes::primitives::{
+    Measurement, RasterQueryRectangle, TimeInstance, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::RasterDataType;
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::{
+    MetaData, MetaDataProvider, RasterResultDescriptor, ResultExtent, TypedResultDescriptor,
+    VectorResultDescriptor,
+};
+use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
+use geoengine_operators::source::{
+    FileNotFoundHandling, GdalDatasetGeoTransform, GdalDatasetParameters, GdalLoadingInfo,
+    GdalLoadingInfoTemporalSlice, GdalLoadingInfoTemporalSliceIterator, OgrSourceDataset,
+};
+use log::debug;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+pub const ERA5_PROVIDER_ID: DatasetProviderId =
+    DatasetProviderId::from_u128(0x5a8b_2c4e_7f31_4d6a_9b0c_3e1f_6d2a_8c47);
+
+/// The CDS dataset of the hourly ERA5 reanalysis on single levels
+const CDS_DATASET: &str = "reanalysis-era5-single-levels";
+
+/// The resolution of the requested grid in degrees
+const GRID_RESOLUTION: f64 = 0.25;
+
+const HOUR_MILLIS: i64 = 60 * 60 * 1000;
+
+/// The ERA5 provider offers variables of the ERA5 reanalysis from the Copernicus Climate
+/// Data Store (CDS) <https://cds.climate.copernicus.eu/> as hourly raster datasets.
+///
+/// The CDS processes requests asynchronously, so the data of a day is requested, polled until
+/// it is ready and downloaded on first use. The downloaded GRIB files are cached per variable
+/// and day.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Era5DataProviderDefinition {
+    name: String,
+    #[serde(default = "Era5DataProviderDefinition::default_api_url")]
+    api_url: String,
+    /// The CDS API key in the form `UID:KEY`
+    api_key: String,
+    cache_path: PathBuf,
+    /// The variables that are offered as datasets
+    variables: Vec<Era5Variable>,
+    #[serde(default = "Era5DataProviderDefinition::default_poll_interval_seconds")]
+    poll_interval_seconds: u64,
+    /// Requests that are not completed in time fail
+    #[serde(default = "Era5DataProviderDefinition::default_request_timeout_seconds")]
+    request_timeout_seconds: u64,
+    /// Queries that span more days fail instead of queuing an excessive number of requests
+    #[serde(default = "Era5DataProviderDefinition::default_max_days_per_query")]
+    max_days_per_query: usize,
+}
+
+impl Era5DataProviderDefinition {
+    fn default_api_url() -> String {
+        "https://cds.climate.copernicus.eu/api/v2".to_owned()
+    }
+
+    fn default_poll_interval_seconds() -> u64 {
+        5
+    }
+
+    fn default_request_timeout_seconds() -> u64 {
+        3600
+    }
+
+    fn default_max_days_per_query() -> usize {
+        31
+    }
+}
+
+/// A variable of the ERA5 reanalysis, e.g., `2m_temperature`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Era5Variable {
+    /// The name of the variable in the CDS API
+    pub name: String,
+    pub title: String,
+    pub measurement: Measurement,
+}
+
+impl Era5Variable {
+    fn result_descriptor(&self) -> RasterResultDescriptor {
+        RasterResultDescriptor {
+            data_type: RasterDataType::F32,
+            spatial_reference: SpatialReference::epsg_4326().into(),
+            measurement: self.measurement.clone(),
+            no_data_value: None,
+            dimensions: vec![],
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl ExternalDatasetProviderDefinition for Era5DataProviderDefinition {
+    async fn initialize(self: Box<Self>) -> Result<Box<dyn ExternalDatasetProvider>> {
+        Ok(Box::new(Era5DataProvider {
+            client: Era5Client {
+                client: Client::new(),
+                api_url: self.api_url,
+                api_key: self.api_key,
+                cache_path: self.cache_path,
+                poll_interval: Duration::from_secs(self.poll_interval_seconds),
+                request_timeout: Duration::from_secs(self.request_timeout_seconds),
+            },
+            variables: self.variables,
+            max_days_per_query: self.max_days_per_query,
+        }))
+    }
+
+    fn type_name(&self) -> String {
+        "ERA5".to_owned()
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn id(&self) -> DatasetProviderId {
+        ERA5_PROVIDER_ID
+    }
+}
+
+#[derive(Debug)]
+pub struct Era5DataProvider {
+    client: Era5Client,
+    variables: Vec<Era5Variable>,
+    max_days_per_query: usize,
+}
+
+impl Era5DataProvider {
+    fn variable(&self, dataset: &DatasetId) -> Result<&Era5Variable> {
+        let variable = dataset
+            .external()
+            .ok_or(Error::InvalidDatasetId)?
+            .dataset_id;
+
+        self.variables
+            .iter()
+            .find(|v| v.name == variable)
+            .ok_or(Error::Era5UnknownVariable { variable })
+    }
+}
+
+#[async_trait]
+impl ExternalDatasetProvider for Era5DataProvider {
+    async fn list(&self, options: Validated<DatasetListOptions>) -> Result<Vec<DatasetListing>> {
+        let options = options.user_input;
+
+        Ok(self
+            .variables
+            .iter()
+            .filter(|variable| {
+                options.filter.as_ref().map_or(true, |filter| {
+                    variable.name.contains(filter) || variable.title.contains(filter)
+                })
+            })
+            .skip(options.offset as usize)
+            .take(options.limit as usize)
+            .map(|variable| DatasetListing {
+                id: DatasetId::External(ExternalDatasetId {
+                    provider_id: ERA5_PROVIDER_ID,
+                    dataset_id: variable.name.clone(),
+                }),
+                name: variable.title.clone(),
+                description: format!("ERA5 hourly reanalysis of {}", variable.name),
+                tags: vec!["ERA5".to_owned()],
+                source_operator: "GdalSource".to_owned(),
+                result_descriptor: TypedResultDescriptor::Raster(variable.result_descriptor()),
+                symbology: None,
+                extent: ResultExtent::default(),
+            })
+            .collect())
+    }
+
+    async fn provenance(&self, dataset: &DatasetId) -> Result<ProvenanceOutput> {
+        self.variable(dataset)?;
+
+        Ok(ProvenanceOutput {
+            dataset: dataset.clone(),
+            provenance: Some(Provenance {
+                citation: "Hersbach, H., Bell, B., Berrisford, P., et al. (2018): \
+                    ERA5 hourly data on single levels from 1959 to present. \
+                    Copernicus Climate Change Service (C3S) Climate Data Store (CDS). \
+                    DOI: 10.24381/cds.adbb2d47"
+                    .to_owned(),
+                license: "Licence to use Copernicus Products".to_owned(),
+                uri: format!(
+                    "https://cds.climate.copernicus.eu/cdsapp#!/dataset/{}",
+                    CDS_DATASET
+                ),
+                version: None,
+            }),
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
+    for Era5DataProvider
+{
+    async fn meta_data(
+        &self,
+        dataset: &DatasetId,
+    ) -> Result<
+        Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        let variable =
+            self.variable(dataset)
+                .map_err(|e| geoengine_operators::error::Error::LoadingInfo {
+                    source: Box::new(e),
+                })?;
+
+        Ok(Box::new(Era5MetaData {
+            client: self.client.clone(),
+            variable: variable.clone(),
+            max_days_per_query: self.max_days_per_query,
+        }))
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
+    for Era5DataProvider
+{
+    async fn meta_data(
+        &self,
+        _dataset: &DatasetId,
+    ) -> Result<
+        Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        Err(geoengine_operators::error::Error::NotImplemented)
+    }
+}
+
+#[async_trait]
+impl
+    MetaDataProvider<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>
+    for Era5DataProvider
+{
+    async fn meta_data(
+        &self,
+        _dataset: &DatasetId,
+    ) -> Result<
+        Box<
+            dyn MetaData<
+                MockDatasetDataSourceLoadingInfo,
+                VectorResultDescriptor,
+                VectorQueryRectangle,
+            >,
+        >,
+        geoengine_operators::error::Error,
+    > {
+        Err(geoengine_operators::error::Error::NotImplemented)
+    }
+}
+
+/// The state of a CDS request
+#[derive(Debug, Deserialize)]
+struct CdsTask {
+    state: CdsTaskState,
+    request_id: Option<String>,
+    location: Option<String>,
+    error: Option<CdsTaskError>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum CdsTaskState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Deserialize)]
+struct CdsTaskError {
+    message: String,
+}
+
+/// Retrieves the days of variables from the CDS and caches them locally
+#[derive(Debug, Clone)]
+struct Era5Client {
+    client: Client,
+    api_url: String,
+    api_key: String,
+    cache_path: PathBuf,
+    poll_interval: Duration,
+    request_timeout: Duration,
+}
+
+impl Era5Client {
+    fn cache_file(&self, variable: &str, day: NaiveDate) -> PathBuf {
+        self.cache_path
+            .join(variable)
+            .join(format!("{}.grib", day.format("%Y-%m-%d")))
+    }
+
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        let (user, password) = self
+            .api_key
+            .split_once(':')
+            .unwrap_or((self.api_key.as_str(), ""));
+        builder.basic_auth(user, Some(password))
+    }
+
+    /// Returns the GRIB file with the 24 hourly values of the `variable` on the `day`
+    async fn day(&self, variable: &str, day: NaiveDate) -> Result<PathBuf> {
+        let cache_file = self.cache_file(variable, day);
+
+        if tokio::fs::metadata(&cache_file).await.is_ok() {
+            return Ok(cache_file);
+        }
+
+        debug!("Requesting ERA5 {} of {} from the CDS", variable, day);
+
+        let request = serde_json::json!({
+            "product_type": "reanalysis",
+            "format": "grib",
+            "variable": variable,
+            "year": day.format("%Y").to_string(),
+            "month": day.format("%m").to_string(),
+            "day": day.format("%d").to_string(),
+            "time": (0..24).map(|hour| format!("{:02}:00", hour)).collect::<Vec<_>>(),
+            "area": [90, -180, -90, 180],
+            "grid": [GRID_RESOLUTION, GRID_RESOLUTION],
+        });
+
+        let mut task: CdsTask = self
+            .request(
+                self.client
+                    .post(format!("{}/resources/{}", self.api_url, CDS_DATASET)),
+            )
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let started = Instant::now();
+
+        let location = loop {
+            match task.state {
+                CdsTaskState::Completed => {
+                    break task.location.ok_or_else(|| Error::Era5RetrievalFailed {
+                        reason: "completed request has no location".to_owned(),
+                    })?
+                }
+                CdsTaskState::Failed => {
+                    return Err(Error::Era5RetrievalFailed {
+                        reason: task
+                            .error
+                            .map_or_else(|| "unknown error".to_owned(), |e| e.message),
+                    })
+                }
+                CdsTaskState::Queued | CdsTaskState::Running => {}
+            }
+
+            ensure!(
+                started.elapsed() < self.request_timeout,
+                error::Era5RetrievalFailed {
+                    reason: "request timed out".to_owned(),
+                }
+            );
+
+            let request_id = task.request_id.ok_or_else(|| Error::Era5RetrievalFailed {
+                reason: "queued request has no id".to_owned(),
+            })?;
+
+            tokio::time::sleep(self.poll_interval).await;
+
+            task = self
+                .request(
+                    self.client
+                        .get(format!("{}/tasks/{}", self.api_url, request_id)),
+                )
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+        };
+
+        let data = self
+            .client
+            .get(location)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        let directory = self.cache_path.join(variable);
+        tokio::fs::create_dir_all(&directory).await?;
+
+        // write to a temporary file first s.t. concurrent queries never read a partial file
+        let partial_file = directory.join(format!("{}.{}.partial", day, uuid::Uuid::new_v4()));
+        tokio::fs::write(&partial_file, data).await?;
+        tokio::fs::rename(&partial_file, &cache_file).await?;
+
+        Ok(cache_file)
+    }
+}
+
+/// Retrieves the days of a query from the CDS and loads their hours from the cache
+#[derive(Debug, Clone)]
+struct Era5MetaData {
+    client: Era5Client,
+    variable: Era5Variable,
+    max_days_per_query: usize,
+}
+
+impl Era5MetaData {
+    fn gdal_parameters(file_path: PathBuf, hour: u32) -> GdalDatasetParameters {
+        // the grid points are the pixel centers, including both -180° and 180°
+        GdalDatasetParameters {
+            file_path,
+            rasterband_channel: hour as usize + 1,
+            geo_transform: GdalDatasetGeoTransform {
+                origin_coordinate: (-180. - GRID_RESOLUTION / 2., 90. + GRID_RESOLUTION / 2.)
+                    .into(),
+                x_pixel_size: GRID_RESOLUTION,
+                y_pixel_size: -GRID_RESOLUTION,
+            },
+            width: (360. / GRID_RESOLUTION) as usize + 1,
+            height: (180. / GRID_RESOLUTION) as usize + 1,
+            file_not_found_handling: FileNotFoundHandling::Error,
+            no_data_value: None,
+            properties_mapping: None,
+            gdal_open_options: None,
+            gdal_config_options: None,
+            apply_scale_offset: false,
+        }
+    }
+}
+
+/// The hours that intersect the `time` interval
+fn hours(time: TimeInterval) -> Result<Vec<NaiveDateTime>> {
+    let start = time.start().inner().div_euclid(HOUR_MILLIS) * HOUR_MILLIS;
+    let end = time.end().inner();
+
+    let mut hours = Vec::new();
+    let mut hour = start;
+    loop {
+        hours.push(
+            TimeInstance::from_millis(hour)?
+                .as_naive_date_time()
+                .ok_or_else(|| Error::Era5RetrievalFailed {
+                    reason: "query time is out of range".to_owned(),
+                })?,
+        );
+
+        hour += HOUR_MILLIS;
+        if hour >= end {
+            break;
+        }
+    }
+
+    Ok(hours)
+}
+
+#[async_trait]
+impl MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle> for Era5MetaData {
+    async fn loading_info(
+        &self,
+        query: RasterQueryRectangle,
+    ) -> geoengine_operators::util::Result<GdalLoadingInfo> {
+        let to_loading_info_error = |e: Error| geoengine_operators::error::Error::LoadingInfo {
+            source: Box::new(e),
+        };
+
+        let hours = hours(query.time_interval).map_err(to_loading_info_error)?;
+
+        let mut days: Vec<NaiveDate> = hours.iter().map(NaiveDateTime::date).collect();
+        days.dedup();
+
+        if days.len() > self.max_days_per_query {
+            return Err(to_loading_info_error(Error::Era5TooManyDays {
+                days: days.len(),
+                max: self.max_days_per_query,
+            }));
+        }
+
+        let mut parts = Vec::with_capacity(hours.len());
+        for day in days {
+            let file = self
+                .client
+                .day(&self.variable.name, day)
+                .await
+                .map_err(to_loading_info_error)?;
+
+            for hour in hours.iter().filter(|hour| hour.date() == day) {
+                let start = TimeInstance::from(*hour);
+
+                parts.push(GdalLoadingInfoTemporalSlice {
+                    time: TimeInterval::new(start, start + HOUR_MILLIS)?,
+                    params: Some(Self::gdal_parameters(file.clone(), hour.hour())),
+                });
+            }
+        }
+
+        Ok(GdalLoadingInfo {
+            info: GdalLoadingInfoTemporalSliceIterator::Static {
+                parts: parts.into_iter(),
+            },
+        })
+    }
+
+    async fn result_descriptor(&self) -> geoengine_operators::util::Result<RasterResultDescriptor> {
+        Ok(self.variable.result_descriptor())
+    }
+
+    fn box_clone(
+        &self,
+    ) -> Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::primitives::{SpatialPartition2D, SpatialResolution};
+    use httptest::matchers::{json_decoded, request};
+    use httptest::responders::{json_encoded, status_code};
+    use httptest::{all_of, Expectation, Server};
+
+    async fn provider(server: &Server, cache_path: PathBuf) -> Box<dyn ExternalDatasetProvider> {
+        Box::new(Era5DataProviderDefinition {
+            name: "ERA5".to_owned(),
+            api_url: server.url_str("/api/v2"),
+            api_key: "123:secret".to_owned(),
+            cache_path,
+            variables: vec![Era5Variable {
+                name: "2m_temperature".to_owned(),
+                title: "2m temperature".to_owned(),
+                measurement: Measurement::continuous(
+                    "temperature".to_owned(),
+                    Some("K".to_owned()),
+                ),
+            }],
+            poll_interval_seconds: 0,
+            request_timeout_seconds: 60,
+            max_days_per_query: 2,
+        })
+        .initialize()
+        .await
+        .unwrap()
+    }
+
+    fn dataset(variable: &str) -> DatasetId {
+        DatasetId::External(ExternalDatasetId {
+            provider_id: ERA5_PROVIDER_ID,
+            dataset_id: variable.to_owned(),
+        })
+    }
+
+    fn query(start: i64, end: i64) -> RasterQueryRectangle {
+        RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new_unchecked(
+                (-180., 90.).into(),
+                (180., -90.).into(),
+            ),
+            time_interval: TimeInterval::new_unchecked(start, end),
+            spatial_resolution: SpatialResolution::new_unchecked(0.25, 0.25),
+            spatial_filter: (),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_retrieves_days_asynchronously() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/api/v2/resources/reanalysis-era5-single-levels"),
+                request::headers(httptest::matchers::contains((
+                    "authorization",
+                    "Basic MTIzOnNlY3JldA=="
+                ))),
+                request::body(json_decoded(httptest::matchers::eq(serde_json::json!({
+                    "product_type": "reanalysis",
+                    "format": "grib",
+                    "variable": "2m_temperature",
+                    "year": "2022",
+                    "month": "01",
+                    "day": "01",
+                    "time": (0..24).map(|hour| format!("{:02}:00", hour)).collect::<Vec<_>>(),
+                    "area": [90, -180, -90, 180],
+                    "grid": [0.25, 0.25],
+                }))))
+            ])
+            .times(1)
+            .respond_with(json_encoded(serde_json::json!({
+                "state": "queued",
+                "request_id": "abc"
+            }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/api/v2/tasks/abc"))
+                .times(1)
+                .respond_with(json_encoded(serde_json::json!({
+                    "state": "completed",
+                    "request_id": "abc",
+                    "location": server.url_str("/download/abc.grib")
+                }))),
+        );
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/download/abc.grib"))
+                .times(1)
+                .respond_with(status_code(200).body("GRIB")),
+        );
+
+        let cache = tempfile::tempdir().unwrap();
+        let provider = provider(&server, cache.path().to_path_buf()).await;
+        let meta_data: Box<
+            dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>,
+        > = provider
+            .meta_data(&dataset("2m_temperature"))
+            .await
+            .unwrap();
+
+        // 2022-01-01T01:30 to 2022-01-01T03:00
+        let start = 1_640_995_200_000 + HOUR_MILLIS + HOUR_MILLIS / 2;
+        let query = query(start, start + HOUR_MILLIS + HOUR_MILLIS / 2);
+
+        // the second query is served from the cache
+        for _ in 0..2 {
+            let parts = meta_data
+                .loading_info(query)
+                .await
+                .unwrap()
+                .info
+                .collect::<geoengine_operators::util::Result<Vec<_>>>()
+                .unwrap();
+
+            assert_eq!(
+                parts
+                    .iter()
+                    .map(|part| (part.time, part.params.as_ref().unwrap().rasterband_channel))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (
+                        TimeInterval::new_unchecked(
+                            1_640_995_200_000 + HOUR_MILLIS,
+                            1_640_995_200_000 + 2 * HOUR_MILLIS
+                        ),
+                        2
+                    ),
+                    (
+                        TimeInterval::new_unchecked(
+                            1_640_995_200_000 + 2 * HOUR_MILLIS,
+                            1_640_995_200_000 + 3 * HOUR_MILLIS
+                        ),
+                        3
+                    ),
+                ]
+            );
+            assert_eq!(
+                parts[0].params.as_ref().unwrap().file_path,
+                cache.path().join("2m_temperature").join("2022-01-01.grib")
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn it_reports_failed_requests() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method("POST")).respond_with(json_encoded(
+                serde_json::json!({
+                    "state": "failed",
+                    "request_id": "abc",
+                    "error": { "message": "no data" }
+                }),
+            )),
+        );
+
+        let cache = tempfile::tempdir().unwrap();
+        let provider = provider(&server, cache.path().to_path_buf()).await;
+        let meta_data: Box<
+            dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>,
+        > = provider
+            .meta_data(&dataset("2m_temperature"))
+            .await
+            .unwrap();
+
+        assert!(meta_data.loading_info(query(0, HOUR_MILLIS)).await.is_err());
+
+        // too many days
+        assert!(meta_data
+            .loading_info(query(0, 3 * 24 * HOUR_MILLIS))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn it_lists_the_variables() {
+        let server = Server::run();
+        let cache = tempfile::tempdir().unwrap();
+        let provider = provider(&server, cache.path().to_path_buf()).await;
+
+        assert!(provider.provenance(&dataset("unknown")).await.is_err());
+        assert!(provider
+            .provenance(&dataset("2m_temperature"))
+            .await
+            .is_ok());
+    }
+}
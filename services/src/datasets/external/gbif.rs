@@ -0,0 +1,731 @@
+use std::path::{Path, PathBuf};
+
+use crate::datasets::listing::{
+    DatasetListOptions, DatasetListing, ExternalDatasetProvider, Provenance, ProvenanceOutput,
+};
+use crate::datasets::storage::ExternalDatasetProviderDefinition;
+use crate::error::{Error, Result};
+use crate::util::user_input::Validated;
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate, Utc};
+use geoengine_datatypes::collections::VectorDataType;
+use geoengine_datatypes::dataset::{DatasetId, DatasetProviderId, ExternalDatasetId};
+use geoengine_datatypes::primitives::{
+    FeatureDataType, RasterQueryRectangle, VectorQueryRectangle,
+};
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::{
+    MetaData, MetaDataProvider, RasterResultDescriptor, ResultExtent, TypedResultDescriptor,
+    VectorResultDescriptor,
+};
+use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
+use geoengine_operators::source::{
+    CsvHeader, FormatSpecifics, GdalLoadingInfo, OgrSourceColumnSpec, OgrSourceDataset,
+    OgrSourceDatasetTimeType, OgrSourceEndInclusion, OgrSourceErrorSpec, OgrSourceTimeFormat,
+};
+use log::debug;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+pub const GBIF_PROVIDER_ID: DatasetProviderId =
+    DatasetProviderId::from_u128(0x1c01_dbb9_e3ab_4f9a_86f5_228b_a4b6_bf7a);
+
+/// The maximum page size of the GBIF occurrence search
+const OCCURRENCE_PAGE_SIZE: usize = 300;
+
+/// The GBIF provider offers the occurrences of taxa from the
+/// Global Biodiversity Information Facility <https://www.gbif.org/>.
+///
+/// Each taxon of the GBIF backbone is a dataset whose id is the taxon key.
+/// The occurrences of a taxon are downloaded on first use and cached as CSV files.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GbifDataProviderDefinition {
+    name: String,
+    #[serde(default = "GbifDataProviderDefinition::default_base_url")]
+    base_url: String,
+    cache_path: PathBuf,
+    /// Taxa with more occurrences are truncated
+    #[serde(default = "GbifDataProviderDefinition::default_max_occurrences")]
+    max_occurrences: usize,
+}
+
+impl GbifDataProviderDefinition {
+    fn default_base_url() -> String {
+        "https://api.gbif.org/v1".to_owned()
+    }
+
+    fn default_max_occurrences() -> usize {
+        10_000
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl ExternalDatasetProviderDefinition for GbifDataProviderDefinition {
+    async fn initialize(self: Box<Self>) -> Result<Box<dyn ExternalDatasetProvider>> {
+        Ok(Box::new(GbifDataProvider {
+            client: Client::new(),
+            base_url: self.base_url,
+            cache_path: self.cache_path,
+            max_occurrences: self.max_occurrences,
+        }))
+    }
+
+    fn type_name(&self) -> String {
+        "GBIF".to_owned()
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn id(&self) -> DatasetProviderId {
+        GBIF_PROVIDER_ID
+    }
+}
+
+#[derive(Debug)]
+pub struct GbifDataProvider {
+    client: Client,
+    base_url: String,
+    cache_path: PathBuf,
+    max_occurrences: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Taxon {
+    key: u64,
+    scientific_name: String,
+    rank: Option<String>,
+    kingdom: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TaxonSearchResponse {
+    results: Vec<Taxon>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Occurrence {
+    key: u64,
+    scientific_name: Option<String>,
+    decimal_longitude: Option<f64>,
+    decimal_latitude: Option<f64>,
+    basis_of_record: Option<String>,
+    country_code: Option<String>,
+    dataset_key: Option<String>,
+    individual_count: Option<i64>,
+    year: Option<i32>,
+    month: Option<u32>,
+    day: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OccurrenceSearchResponse {
+    end_of_records: bool,
+    results: Vec<Occurrence>,
+}
+
+impl GbifDataProvider {
+    fn taxon_key(dataset: &DatasetId) -> Result<u64> {
+        dataset
+            .external()
+            .ok_or(Error::InvalidDatasetId)?
+            .dataset_id
+            .parse()
+            .map_err(|_| Error::InvalidExternalDatasetId {
+                provider: GBIF_PROVIDER_ID,
+            })
+    }
+
+    fn dataset_listing(taxon: Taxon) -> DatasetListing {
+        DatasetListing {
+            id: DatasetId::External(ExternalDatasetId {
+                provider_id: GBIF_PROVIDER_ID,
+                dataset_id: taxon.key.to_string(),
+            }),
+            name: taxon.scientific_name.clone(),
+            description: format!("GBIF occurrences of {}", taxon.scientific_name),
+            tags: taxon.rank.into_iter().chain(taxon.kingdom).collect(),
+            source_operator: "OgrSource".to_owned(),
+            result_descriptor: TypedResultDescriptor::Vector(occurrence_result_descriptor()),
+            symbology: None,
+            extent: ResultExtent::default(),
+        }
+    }
+}
+
+/// The standard columns of the occurrences, named after the Darwin Core terms
+fn occurrence_result_descriptor() -> VectorResultDescriptor {
+    VectorResultDescriptor {
+        data_type: VectorDataType::MultiPoint,
+        spatial_reference: SpatialReference::epsg_4326().into(),
+        columns: [
+            ("gbifID".to_owned(), FeatureDataType::Int),
+            ("scientificName".to_owned(), FeatureDataType::Text),
+            ("basisOfRecord".to_owned(), FeatureDataType::Text),
+            ("countryCode".to_owned(), FeatureDataType::Text),
+            ("datasetKey".to_owned(), FeatureDataType::Text),
+            ("individualCount".to_owned(), FeatureDataType::Int),
+        ]
+        .into_iter()
+        .collect(),
+        column_metadata: Default::default(),
+    }
+}
+
+#[async_trait]
+impl ExternalDatasetProvider for GbifDataProvider {
+    /// Searches the taxa of the GBIF backbone by the filter of the `options`
+    async fn list(&self, options: Validated<DatasetListOptions>) -> Result<Vec<DatasetListing>> {
+        let options = options.user_input;
+
+        let mut query = vec![
+            ("status", "ACCEPTED".to_owned()),
+            ("offset", options.offset.to_string()),
+            ("limit", options.limit.to_string()),
+        ];
+        if let Some(filter) = options.filter {
+            query.push(("q", filter));
+        }
+
+        let response: TaxonSearchResponse = self
+            .client
+            .get(format!("{}/species/search", self.base_url))
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(response
+            .results
+            .into_iter()
+            .map(Self::dataset_listing)
+            .collect())
+    }
+
+    async fn provenance(&self, dataset: &DatasetId) -> Result<ProvenanceOutput> {
+        let taxon_key = Self::taxon_key(dataset)?;
+
+        let taxon: Taxon = self
+            .client
+            .get(format!("{}/species/{}", self.base_url, taxon_key))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // cf. <https://www.gbif.org/citation-guidelines>
+        let citation = format!(
+            "GBIF.org ({}) GBIF Occurrence Data of {}, {}/occurrence/search?taxonKey={}",
+            Utc::now().format("%d %B %Y"),
+            taxon.scientific_name,
+            self.base_url,
+            taxon_key
+        );
+
+        Ok(ProvenanceOutput {
+            dataset: dataset.clone(),
+            provenance: Some(Provenance {
+                citation,
+                // the occurrences are licensed individually, the strictest being CC BY-NC 4.0
+                license: "https://www.gbif.org/terms".to_owned(),
+                uri: format!("https://www.gbif.org/species/{}", taxon_key),
+                version: None,
+            }),
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
+    for GbifDataProvider
+{
+    async fn meta_data(
+        &self,
+        dataset: &DatasetId,
+    ) -> Result<
+        Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        let taxon_key = Self::taxon_key(dataset).map_err(|e| {
+            geoengine_operators::error::Error::LoadingInfo {
+                source: Box::new(e),
+            }
+        })?;
+
+        Ok(Box::new(GbifOccurrenceMetaData {
+            client: self.client.clone(),
+            base_url: self.base_url.clone(),
+            cache_path: self.cache_path.clone(),
+            max_occurrences: self.max_occurrences,
+            taxon_key,
+        }))
+    }
+}
+
+#[async_trait]
+impl MetaDataProvider<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
+    for GbifDataProvider
+{
+    async fn meta_data(
+        &self,
+        _dataset: &DatasetId,
+    ) -> Result<
+        Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
+        geoengine_operators::error::Error,
+    > {
+        Err(geoengine_operators::error::Error::NotImplemented)
+    }
+}
+
+#[async_trait]
+impl
+    MetaDataProvider<MockDatasetDataSourceLoadingInfo, VectorResultDescriptor, VectorQueryRectangle>
+    for GbifDataProvider
+{
+    async fn meta_data(
+        &self,
+        _dataset: &DatasetId,
+    ) -> Result<
+        Box<
+            dyn MetaData<
+                MockDatasetDataSourceLoadingInfo,
+                VectorResultDescriptor,
+                VectorQueryRectangle,
+            >,
+        >,
+        geoengine_operators::error::Error,
+    > {
+        Err(geoengine_operators::error::Error::NotImplemented)
+    }
+}
+
+/// Downloads the occurrences of a taxon into the cache on the first query
+#[derive(Debug, Clone)]
+struct GbifOccurrenceMetaData {
+    client: Client,
+    base_url: String,
+    cache_path: PathBuf,
+    max_occurrences: usize,
+    taxon_key: u64,
+}
+
+impl GbifOccurrenceMetaData {
+    fn cache_file(&self) -> PathBuf {
+        self.cache_path.join(format!("{}.csv", self.taxon_key))
+    }
+
+    async fn ensure_cached(&self) -> Result<PathBuf> {
+        let cache_file = self.cache_file();
+
+        if tokio::fs::metadata(&cache_file).await.is_ok() {
+            return Ok(cache_file);
+        }
+
+        debug!("Downloading GBIF occurrences of taxon {}", self.taxon_key);
+
+        let mut csv = String::from(
+            "gbifID,scientificName,basisOfRecord,countryCode,datasetKey,individualCount,\
+            eventStart,eventEnd,decimalLongitude,decimalLatitude\n",
+        );
+
+        let mut offset = 0;
+        while offset < self.max_occurrences {
+            let limit = OCCURRENCE_PAGE_SIZE.min(self.max_occurrences - offset);
+
+            let page: OccurrenceSearchResponse = self
+                .client
+                .get(format!("{}/occurrence/search", self.base_url))
+                .query(&[
+                    ("taxonKey", self.taxon_key.to_string()),
+                    ("hasCoordinate", "true".to_owned()),
+                    ("hasGeospatialIssue", "false".to_owned()),
+                    ("offset", offset.to_string()),
+                    ("limit", limit.to_string()),
+                ])
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            offset += page.results.len();
+
+            for occurrence in &page.results {
+                write_occurrence(&mut csv, occurrence);
+            }
+
+            if page.end_of_records || page.results.is_empty() {
+                break;
+            }
+        }
+
+        tokio::fs::create_dir_all(&self.cache_path).await?;
+
+        // write to a temporary file first s.t. concurrent queries never read a partial file
+        let partial_file = self.cache_path.join(format!(
+            "{}.{}.partial",
+            self.taxon_key,
+            uuid::Uuid::new_v4()
+        ));
+        tokio::fs::write(&partial_file, csv).await?;
+        tokio::fs::rename(&partial_file, &cache_file).await?;
+
+        Ok(cache_file)
+    }
+}
+
+/// Appends the occurrence as a CSV line, skipping occurrences without coordinates
+fn write_occurrence(csv: &mut String, occurrence: &Occurrence) {
+    let (longitude, latitude) = match (occurrence.decimal_longitude, occurrence.decimal_latitude) {
+        (Some(longitude), Some(latitude)) => (longitude, latitude),
+        _ => return,
+    };
+
+    let (event_start, event_end) =
+        event_interval(occurrence.year, occurrence.month, occurrence.day)
+            .map(|(start, end)| (start.to_string(), end.to_string()))
+            .unwrap_or_default();
+
+    let fields = [
+        occurrence.key.to_string(),
+        csv_text(occurrence.scientific_name.as_deref()),
+        csv_text(occurrence.basis_of_record.as_deref()),
+        csv_text(occurrence.country_code.as_deref()),
+        csv_text(occurrence.dataset_key.as_deref()),
+        occurrence
+            .individual_count
+            .map(|count| count.to_string())
+            .unwrap_or_default(),
+        event_start,
+        event_end,
+        longitude.to_string(),
+        latitude.to_string(),
+    ];
+
+    csv.push_str(&fields.join(","));
+    csv.push('\n');
+}
+
+fn csv_text(text: Option<&str>) -> String {
+    text.map(|text| format!("\"{}\"", text.replace('"', "\"\"")))
+        .unwrap_or_default()
+}
+
+/// The days an occurrence may have been observed on, given its possibly incomplete date
+fn event_interval(
+    year: Option<i32>,
+    month: Option<u32>,
+    day: Option<u32>,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let year = year?;
+
+    match (month, day) {
+        (Some(month), Some(day)) => {
+            let start = NaiveDate::from_ymd_opt(year, month, day)?;
+            Some((start, start.succ_opt()?))
+        }
+        (Some(month), None) => {
+            let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+            let end = if start.month() == 12 {
+                NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+            } else {
+                NaiveDate::from_ymd_opt(year, month + 1, 1)?
+            };
+            Some((start, end))
+        }
+        _ => Some((
+            NaiveDate::from_ymd_opt(year, 1, 1)?,
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)?,
+        )),
+    }
+}
+
+fn occurrence_loading_info(cache_file: &Path) -> OgrSourceDataset {
+    let date_format = OgrSourceTimeFormat::Custom {
+        custom_format: "%Y-%m-%d".to_owned(),
+    };
+
+    OgrSourceDataset {
+        file_name: cache_file.to_path_buf(),
+        layer_name: cache_file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        data_type: Some(VectorDataType::MultiPoint),
+        time: OgrSourceDatasetTimeType::StartEnd {
+            start_field: "eventStart".to_owned(),
+            start_format: date_format.clone(),
+            end_field: "eventEnd".to_owned(),
+            end_format: date_format,
+            end_inclusion: OgrSourceEndInclusion::Exclusive,
+        },
+        default_geometry: None,
+        columns: Some(OgrSourceColumnSpec {
+            format_specifics: Some(FormatSpecifics::Csv {
+                header: CsvHeader::Yes,
+            }),
+            x: "decimalLongitude".to_owned(),
+            y: Some("decimalLatitude".to_owned()),
+            int: vec!["gbifID".to_owned(), "individualCount".to_owned()],
+            float: vec![],
+            text: vec![
+                "scientificName".to_owned(),
+                "basisOfRecord".to_owned(),
+                "countryCode".to_owned(),
+                "datasetKey".to_owned(),
+            ],
+            bool: vec![],
+            datetime: vec![],
+            category: vec![],
+            rename: None,
+        }),
+        force_ogr_time_filter: false,
+        force_ogr_spatial_filter: false,
+        on_error: OgrSourceErrorSpec::Ignore,
+        sql_query: None,
+        attribute_query: None,
+        generalizations: vec![],
+    }
+}
+
+#[async_trait]
+impl MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
+    for GbifOccurrenceMetaData
+{
+    async fn loading_info(
+        &self,
+        _query: VectorQueryRectangle,
+    ) -> geoengine_operators::util::Result<OgrSourceDataset> {
+        let cache_file = self.ensure_cached().await.map_err(|e| {
+            geoengine_operators::error::Error::LoadingInfo {
+                source: Box::new(e),
+            }
+        })?;
+
+        Ok(occurrence_loading_info(&cache_file))
+    }
+
+    async fn result_descriptor(&self) -> geoengine_operators::util::Result<VectorResultDescriptor> {
+        Ok(occurrence_result_descriptor())
+    }
+
+    fn box_clone(
+        &self,
+    ) -> Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasets::listing::OrderBy;
+    use crate::util::user_input::UserInput;
+    use geoengine_datatypes::primitives::{BoundingBox2D, SpatialResolution};
+    use httptest::matchers::{contains, request, url_decoded};
+    use httptest::responders::json_encoded;
+    use httptest::{all_of, Expectation, Server};
+
+    async fn provider(server: &Server, cache_path: &Path) -> Box<dyn ExternalDatasetProvider> {
+        Box::new(GbifDataProviderDefinition {
+            name: "GBIF".to_owned(),
+            base_url: server.url_str("").strip_suffix('/').unwrap().to_owned(),
+            cache_path: cache_path.to_path_buf(),
+            max_occurrences: 3,
+        })
+        .initialize()
+        .await
+        .unwrap()
+    }
+
+    fn dataset(taxon_key: &str) -> DatasetId {
+        DatasetId::External(ExternalDatasetId {
+            provider_id: GBIF_PROVIDER_ID,
+            dataset_id: taxon_key.to_owned(),
+        })
+    }
+
+    #[tokio::test]
+    async fn it_searches_taxa() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/species/search"),
+                request::query(url_decoded(contains(("q", "Ciconia"))))
+            ])
+            .respond_with(json_encoded(serde_json::json!({
+                "offset": 0,
+                "limit": 2,
+                "endOfRecords": true,
+                "results": [{
+                    "key": 2481912,
+                    "scientificName": "Ciconia ciconia (Linnaeus, 1758)",
+                    "rank": "SPECIES",
+                    "kingdom": "Animalia"
+                }]
+            }))),
+        );
+
+        let cache = tempfile::tempdir().unwrap();
+        let listing = provider(&server, cache.path())
+            .await
+            .list(
+                DatasetListOptions {
+                    filter: Some("Ciconia".to_owned()),
+                    order: OrderBy::NameAsc,
+                    offset: 0,
+                    limit: 2,
+                    bbox: None,
+                    time: None,
+                }
+                .validated()
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(listing.len(), 1);
+        assert_eq!(listing[0].id, dataset("2481912"));
+        assert_eq!(listing[0].name, "Ciconia ciconia (Linnaeus, 1758)");
+        assert_eq!(
+            listing[0].tags,
+            vec!["SPECIES".to_owned(), "Animalia".to_owned()]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_downloads_and_caches_occurrences() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/occurrence/search"),
+                request::query(url_decoded(contains(("taxonKey", "2481912")))),
+                request::query(url_decoded(contains(("offset", "0"))))
+            ])
+            .times(1)
+            .respond_with(json_encoded(serde_json::json!({
+                "endOfRecords": false,
+                "results": [{
+                    "key": 1,
+                    "scientificName": "Ciconia ciconia",
+                    "decimalLongitude": 8.7,
+                    "decimalLatitude": 50.8,
+                    "basisOfRecord": "HUMAN_OBSERVATION",
+                    "countryCode": "DE",
+                    "year": 2020,
+                    "month": 4,
+                    "day": 12
+                }, {
+                    "key": 2,
+                    "scientificName": "Ciconia \"white\" ciconia",
+                    "decimalLongitude": 9.1,
+                    "decimalLatitude": 51.0,
+                    "individualCount": 3,
+                    "year": 2021,
+                    "month": 12
+                }]
+            }))),
+        );
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/occurrence/search"),
+                request::query(url_decoded(contains(("offset", "2")))),
+                request::query(url_decoded(contains(("limit", "1"))))
+            ])
+            .times(1)
+            .respond_with(json_encoded(serde_json::json!({
+                "endOfRecords": true,
+                "results": [{
+                    "key": 3,
+                    "decimalLongitude": 10.,
+                    "decimalLatitude": 52.
+                }]
+            }))),
+        );
+
+        let cache = tempfile::tempdir().unwrap();
+        let provider = provider(&server, cache.path()).await;
+        let meta_data: Box<
+            dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
+        > = provider.meta_data(&dataset("2481912")).await.unwrap();
+
+        let query = VectorQueryRectangle {
+            spatial_bounds: BoundingBox2D::new_unchecked((-180., -90.).into(), (180., 90.).into()),
+            time_interval: Default::default(),
+            spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
+        };
+
+        let loading_info = meta_data.loading_info(query.clone()).await.unwrap();
+        assert_eq!(loading_info.file_name, cache.path().join("2481912.csv"));
+        assert_eq!(loading_info.layer_name, "2481912");
+
+        // the second query is served from the cache
+        meta_data.loading_info(query).await.unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(cache.path().join("2481912.csv")).unwrap(),
+            "gbifID,scientificName,basisOfRecord,countryCode,datasetKey,individualCount,\
+            eventStart,eventEnd,decimalLongitude,decimalLatitude\n\
+            1,\"Ciconia ciconia\",\"HUMAN_OBSERVATION\",\"DE\",,,2020-04-12,2020-04-13,8.7,50.8\n\
+            2,\"Ciconia \"\"white\"\" ciconia\",,,,3,2021-12-01,2022-01-01,9.1,51\n\
+            3,,,,,,,,10,52\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_cites_gbif() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/species/2481912")).respond_with(
+                json_encoded(serde_json::json!({
+                    "key": 2481912,
+                    "scientificName": "Ciconia ciconia (Linnaeus, 1758)"
+                })),
+            ),
+        );
+
+        let cache = tempfile::tempdir().unwrap();
+        let provenance = provider(&server, cache.path())
+            .await
+            .provenance(&dataset("2481912"))
+            .await
+            .unwrap()
+            .provenance
+            .unwrap();
+
+        assert!(provenance.citation.starts_with("GBIF.org ("));
+        assert!(provenance
+            .citation
+            .contains("GBIF Occurrence Data of Ciconia ciconia (Linnaeus, 1758)"));
+        assert_eq!(provenance.uri, "https://www.gbif.org/species/2481912");
+    }
+
+    #[test]
+    fn it_widens_incomplete_dates() {
+        let date = |y, m, d| NaiveDate::from_ymd(y, m, d);
+
+        assert_eq!(
+            event_interval(Some(2020), None, None),
+            Some((date(2020, 1, 1), date(2021, 1, 1)))
+        );
+        assert_eq!(
+            event_interval(Some(2020), Some(2), None),
+            Some((date(2020, 2, 1), date(2020, 3, 1)))
+        );
+        assert_eq!(event_interval(None, Some(2), Some(3)), None);
+    }
+}
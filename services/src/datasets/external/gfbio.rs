@@ -21,7 +21,7 @@ use geoengine_datatypes::primitives::{
     FeatureDataType, RasterQueryRectangle, VectorQueryRectangle,
 };
 use geoengine_datatypes::spatial_reference::SpatialReference;
-use geoengine_operators::engine::{StaticMetaData, TypedResultDescriptor};
+use geoengine_operators::engine::{ResultExtent, StaticMetaData, TypedResultDescriptor};
 use geoengine_operators::source::{
     OgrSourceColumnSpec, OgrSourceDatasetTimeType, OgrSourceErrorSpec,
 };
@@ -212,8 +212,10 @@ impl ExternalDatasetProvider for GfbioDataProvider {
                         .filter(|(_, name)| name.starts_with("/DataSets/DataSet/Units/Unit/"))
                         .map(|(_, name)| (name.clone(), FeatureDataType::Text))
                         .collect(),
+                    column_metadata: Default::default(),
                 }),
                 symbology: None,
+                extent: ResultExtent::default(),
             })
             .collect();
 
@@ -264,6 +266,7 @@ impl ExternalDatasetProvider for GfbioDataProvider {
                 citation: row.try_get(0).unwrap_or_else(|_| "".to_owned()),
                 license: row.try_get(1).unwrap_or_else(|_| "".to_owned()),
                 uri: row.try_get(2).unwrap_or_else(|_| "".to_owned()),
+                version: None,
             }),
         })
     }
@@ -317,6 +320,7 @@ impl MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRecta
                         .collect(),
                     bool: vec![],
                     datetime: vec![],
+                    category: vec![],
                     rename: Some(
                         self.column_hash_to_name
                             .iter()
@@ -332,6 +336,7 @@ impl MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRecta
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: Some(GfbioDataProvider::build_attribute_query(surrogate_key)),
+                generalizations: vec![],
             },
             result_descriptor: VectorResultDescriptor {
                 data_type: VectorDataType::MultiPoint,
@@ -342,6 +347,7 @@ impl MetaDataProvider<OgrSourceDataset, VectorResultDescriptor, VectorQueryRecta
                     .filter(|(_, name)| name.starts_with("/DataSets/DataSet/Units/Unit"))
                     .map(|(_, name)| (name.clone(), FeatureDataType::Text))
                     .collect(),
+                column_metadata: Default::default(),
             },
             phantom: PhantomData::default(),
         }))
@@ -483,6 +489,8 @@ mod tests {
                     order: OrderBy::NameAsc,
                     offset: 0,
                     limit: 10,
+                    bbox: None,
+                    time: None,
                 }
                 .validated()
                 .unwrap(),
@@ -533,8 +541,10 @@ mod tests {
                         .iter()
                         .cloned()
                         .collect(),
+                    column_metadata: Default::default(),
                 }),
                 symbology: None,
+                extent: ResultExtent::default(),
             }]
         );
     }
@@ -600,7 +610,8 @@ mod tests {
                     ]
                     .iter()
                     .cloned()
-                    .collect()
+                    .collect(),
+                column_metadata: Default::default(),
             };
 
             let result_descriptor = meta.result_descriptor().await.map_err(|e| e.to_string())?;
@@ -617,6 +628,7 @@ mod tests {
                     ),
                     time_interval: TimeInterval::default(),
                     spatial_resolution: SpatialResolution::zero_point_one(),
+                    spatial_filter: None,
                 })
                 .await
                 .map_err(|e| e.to_string())?;
@@ -665,6 +677,7 @@ mod tests {
                     ],
                     bool: vec![],
                     datetime: vec![],
+                    category: vec![],
                     rename: Some([
                         ("8003ddd80b42736ebf36b87018e51db3ee84efaf".to_owned(), "/DataSets/DataSet/Units/Unit/Gathering/Country/Name".to_owned()),
                         ("f2374ad051911a65bc0d0a46c13ada2625f55a10".to_owned(), "/DataSets/DataSet/Units/Unit/SourceID".to_owned()),
@@ -694,6 +707,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: Some("surrogate_key = 1".to_string()),
+                generalizations: vec![],
             };
 
             if loading_info != expected {
@@ -748,6 +762,7 @@ mod tests {
                 spatial_bounds: BoundingBox2D::new((0., -90.).into(), (180., 90.).into()).unwrap(),
                 time_interval: TimeInterval::default(),
                 spatial_resolution: SpatialResolution::zero_point_one(),
+                spatial_filter: None,
             };
             let ctx = MockQueryContext::test_default();
 
@@ -854,6 +869,7 @@ mod tests {
                     citation: "Example Description".to_owned(),
                     license: "CC-BY-SA".to_owned(),
                     uri: "http://example.org".to_owned(),
+                    version: None,
                 }),
             };
 
@@ -12,7 +12,9 @@ use async_trait::async_trait;
 use geoengine_datatypes::dataset::{DatasetId, DatasetProviderId};
 use geoengine_datatypes::primitives::{RasterQueryRectangle, VectorQueryRectangle};
 use geoengine_operators::{
-    engine::{MetaData, MetaDataProvider, RasterResultDescriptor, VectorResultDescriptor},
+    engine::{
+        MetaData, MetaDataProvider, RasterResultDescriptor, ResultExtent, VectorResultDescriptor,
+    },
     mock::MockDatasetDataSourceLoadingInfo,
     source::{GdalLoadingInfo, OgrSourceDataset},
 };
@@ -70,6 +72,7 @@ impl ExternalDatasetProvider for MockExternalDataProvider {
                 source_operator: dataset.properties.source_operator.clone(),
                 result_descriptor: dataset.meta_data.result_descriptor().await?,
                 symbology: dataset.properties.symbology.clone(),
+                extent: ResultExtent::default(),
             }));
         }
 
@@ -1,3 +1,5 @@
+pub mod era5;
+pub mod gbif;
 #[cfg(feature = "nfdi")]
 pub mod gfbio;
 pub mod mock;
@@ -19,7 +19,7 @@ use gdal::DatasetOptions;
 use gdal::Metadata;
 use geoengine_datatypes::dataset::{DatasetId, DatasetProviderId, ExternalDatasetId};
 use geoengine_datatypes::primitives::{RasterQueryRectangle, VectorQueryRectangle};
-use geoengine_operators::engine::TypedResultDescriptor;
+use geoengine_operators::engine::{ResultExtent, TypedResultDescriptor};
 use geoengine_operators::source::GdalMetaDataStatic;
 use geoengine_operators::util::gdal::{
     gdal_open_dataset_ex, gdal_parameters_from_dataset, raster_descriptor_from_dataset,
@@ -174,6 +174,7 @@ impl ExternalDatasetProvider for Nature40DataProvider {
                             source_operator: "GdalSource".to_owned(),
                             result_descriptor: TypedResultDescriptor::Raster(result_descriptor),
                             symbology: None, // TODO: build symbology
+                            extent: ResultExtent::default(),
                         }));
                     } else {
                         info!(
@@ -704,6 +705,8 @@ mod tests {
                     order: OrderBy::NameAsc,
                     offset: 0,
                     limit: 10,
+                    bbox: None,
+                    time: None,
                 }
                 .validated()
                 .unwrap(),
@@ -734,9 +737,11 @@ mod tests {
                         )
                         .into(),
                         measurement: Measurement::Unitless,
-                        no_data_value: None
+                        no_data_value: None,
+                        dimensions: vec![]
                     }),
-                    symbology: None
+                    symbology: None,
+                    extent: ResultExtent::default(),
                 },
                 DatasetListing {
                     id: DatasetId::External(ExternalDatasetId {
@@ -758,9 +763,11 @@ mod tests {
                         )
                         .into(),
                         measurement: Measurement::Unitless,
-                        no_data_value: None
+                        no_data_value: None,
+                        dimensions: vec![]
                     }),
-                    symbology: None
+                    symbology: None,
+                    extent: ResultExtent::default(),
                 },
                 DatasetListing {
                     id: DatasetId::External(ExternalDatasetId {
@@ -782,9 +789,11 @@ mod tests {
                         )
                         .into(),
                         measurement: Measurement::Unitless,
-                        no_data_value: None
+                        no_data_value: None,
+                        dimensions: vec![]
                     }),
-                    symbology: None
+                    symbology: None,
+                    extent: ResultExtent::default(),
                 },
                 DatasetListing {
                     id: DatasetId::External(ExternalDatasetId {
@@ -806,9 +815,11 @@ mod tests {
                         )
                         .into(),
                         measurement: Measurement::Unitless,
-                        no_data_value: None
+                        no_data_value: None,
+                        dimensions: vec![]
                     }),
-                    symbology: None
+                    symbology: None,
+                    extent: ResultExtent::default(),
                 }
             ]
         );
@@ -852,7 +863,8 @@ mod tests {
                 spatial_reference: SpatialReference::new(SpatialReferenceAuthority::Epsg, 25832)
                     .into(),
                 measurement: Measurement::Unitless,
-                no_data_value: None
+                no_data_value: None,
+                dimensions: vec![]
             }
         );
 
@@ -867,6 +879,7 @@ mod tests {
                     (473_924.500 - 473_922.500) / 2.,
                     (5_634_057.500 - 5_634_055.50) / 2.,
                 ),
+                spatial_filter: (),
             })
             .await
             .unwrap();
@@ -893,6 +906,7 @@ mod tests {
                         properties_mapping: None,
                         gdal_open_options: Some(vec!["UserPwd=geoengine:pwd".to_owned(), "HttpAuth=BASIC".to_owned()]),
                         gdal_config_options: None,
+                        apply_scale_offset: false,
                     })
                 }
             );
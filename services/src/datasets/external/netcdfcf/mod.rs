@@ -33,7 +33,7 @@ use geoengine_datatypes::primitives::{
 };
 use geoengine_datatypes::raster::{GdalGeoTransform, RasterDataType};
 use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
-use geoengine_operators::engine::TypedResultDescriptor;
+use geoengine_operators::engine::{ResultExtent, TypedResultDescriptor};
 use geoengine_operators::source::{
     FileNotFoundHandling, GdalDatasetGeoTransform, GdalDatasetParameters, GdalMetadataNetCdfCf,
 };
@@ -748,11 +748,13 @@ impl NetCdfCfDataProvider {
                         spatial_reference: tree.spatial_reference.into(),
                         measurement: derive_measurement(tail.unit.clone()),
                         no_data_value: None, // we don't want to open the dataset at this point. We should get rid of the result descriptor in the listing in general
+                        dimensions: vec![],
                     }),
                     symbology: Some(Symbology::Raster(RasterSymbology {
                         opacity: 1.0,
                         colorizer: tree.colorizer.clone(),
                     })),
+                    extent: ResultExtent::default(),
                 });
             }
         }
@@ -847,6 +849,7 @@ impl NetCdfCfDataProvider {
             spatial_reference: data_array.spatial_reference()?,
             measurement: derive_measurement(data_array.unit().context(error::CannotRetrieveUnit)?),
             no_data_value: data_array.no_data_value(),
+            dimensions: vec![],
         };
 
         let params = GdalDatasetParameters {
@@ -860,6 +863,7 @@ impl NetCdfCfDataProvider {
             height: dimensions.lat,
             gdal_open_options: None,
             gdal_config_options: None,
+            apply_scale_offset: false,
         };
 
         Ok(Box::new(GdalMetadataNetCdfCf {
@@ -1207,6 +1211,7 @@ mod tests {
             spatial_reference: SpatialReference::new(SpatialReferenceAuthority::Epsg, 4326).into(),
             measurement: Measurement::Unitless,
             no_data_value: None,
+            dimensions: vec![],
         }
         .into();
 
@@ -1244,6 +1249,7 @@ mod tests {
                 source_operator: "GdalSource".into(),
                 result_descriptor: result_descriptor.clone(),
                 symbology: symbology.clone(),
+                extent: ResultExtent::default(),
             }
         );
         assert_eq!(
@@ -1264,6 +1270,7 @@ mod tests {
                 source_operator: "GdalSource".into(),
                 result_descriptor: result_descriptor.clone(),
                 symbology: symbology.clone(),
+                extent: ResultExtent::default(),
             }
         );
         assert_eq!(
@@ -1284,6 +1291,7 @@ mod tests {
                 source_operator: "GdalSource".into(),
                 result_descriptor: result_descriptor.clone(),
                 symbology: symbology.clone(),
+                extent: ResultExtent::default(),
             }
         );
         assert_eq!(
@@ -1304,6 +1312,7 @@ mod tests {
                 source_operator: "GdalSource".into(),
                 result_descriptor: result_descriptor.clone(),
                 symbology: symbology.clone(),
+                extent: ResultExtent::default(),
             }
         );
         assert_eq!(
@@ -1324,6 +1333,7 @@ mod tests {
                 source_operator: "GdalSource".into(),
                 result_descriptor: result_descriptor.clone(),
                 symbology: symbology.clone(),
+                extent: ResultExtent::default(),
             }
         );
         assert_eq!(
@@ -1344,6 +1354,7 @@ mod tests {
                 source_operator: "GdalSource".into(),
                 result_descriptor,
                 symbology,
+                extent: ResultExtent::default(),
             }
         );
     }
@@ -1367,6 +1378,7 @@ mod tests {
             spatial_reference: SpatialReference::new(SpatialReferenceAuthority::Epsg, 3035).into(),
             measurement: Measurement::Unitless,
             no_data_value: None,
+            dimensions: vec![],
         }
         .into();
 
@@ -1403,6 +1415,7 @@ mod tests {
                 source_operator: "GdalSource".into(),
                 result_descriptor: result_descriptor.clone(),
                 symbology: symbology.clone(),
+                extent: ResultExtent::default(),
             }
         );
         assert_eq!(
@@ -1423,6 +1436,7 @@ mod tests {
                 source_operator: "GdalSource".into(),
                 result_descriptor,
                 symbology,
+                extent: ResultExtent::default(),
             }
         );
     }
@@ -1454,6 +1468,7 @@ mod tests {
                     .into(),
                 measurement: Measurement::Unitless,
                 no_data_value: Some(-9999.),
+                dimensions: vec![],
             }
         );
 
@@ -1470,6 +1485,7 @@ mod tests {
                     0.000_343_322_7, // 256 pixel
                     0.000_343_322_7, // 256 pixel
                 ),
+                spatial_filter: (),
             })
             .await
             .unwrap();
@@ -1509,6 +1525,7 @@ mod tests {
                     properties_mapping: None,
                     gdal_open_options: None,
                     gdal_config_options: None
+                    apply_scale_offset: false,
                 })
             }
         );
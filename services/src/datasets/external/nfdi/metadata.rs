@@ -112,6 +112,7 @@ mod tests {
                 citation: "Test".to_string(),
                 license: "MIT".to_string(),
                 uri: "http://geoengine.io".to_string(),
+                version: None,
             }),
         };
 
@@ -163,6 +164,7 @@ mod tests {
                 citation: "Test".to_string(),
                 license: "MIT".to_string(),
                 uri: "http://geoengine.io".to_string(),
+                version: None,
             }),
         };
 
@@ -12,14 +12,16 @@ use geoengine_datatypes::primitives::{
 };
 use geoengine_datatypes::spatial_reference::SpatialReferenceOption;
 use geoengine_operators::engine::{
-    MetaData, MetaDataProvider, RasterResultDescriptor, ResultDescriptor, TypedResultDescriptor,
+    MetaData, MetaDataProvider, RasterResultDescriptor, ResultDescriptor, ResultExtent,
+    TypedResultDescriptor,
     VectorResultDescriptor,
 };
 use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
 use geoengine_operators::source::{
     FileNotFoundHandling, GdalDatasetParameters, GdalLoadingInfo, GdalLoadingInfoTemporalSlice,
     GdalLoadingInfoTemporalSliceIterator, OgrSourceColumnSpec, OgrSourceDataset,
-    OgrSourceDatasetTimeType, OgrSourceDurationSpec, OgrSourceErrorSpec, OgrSourceTimeFormat,
+    OgrSourceDatasetTimeType, OgrSourceDurationFormat, OgrSourceDurationSpec, OgrSourceEndInclusion,
+    OgrSourceErrorSpec, OgrSourceTimeFormat,
 };
 use scienceobjectsdb_rust_api::sciobjectsdb::sciobjsdb::api::storage::models::v1::Object;
 use scienceobjectsdb_rust_api::sciobjectsdb::sciobjsdb::api::storage::services::v1::dataset_service_client::DatasetServiceClient;
@@ -218,6 +220,7 @@ impl NFDIDataProvider {
             result_descriptor,
             symbology: None,
             provenance: md.provenance.clone(),
+            extent: ResultExtent::default(),
         }
     }
 
@@ -236,6 +239,7 @@ impl NFDIDataProvider {
             data_type: info.vector_type,
             spatial_reference: crs,
             columns,
+            column_metadata: Default::default(),
         }
     }
 
@@ -252,6 +256,7 @@ impl NFDIDataProvider {
                 .as_ref()
                 .map_or(Measurement::Unitless, Clone::clone),
             no_data_value: info.no_data_value,
+            dimensions: vec![],
         }
     }
 
@@ -296,12 +301,14 @@ impl NFDIDataProvider {
         let mut text = vec![];
         let mut bool = vec![];
         let mut datetime = vec![];
+        let mut category = vec![];
 
         for (k, v) in &rd.columns {
             match v {
-                FeatureDataType::Category | FeatureDataType::Int => int.push(k.to_string()),
+                FeatureDataType::Int => int.push(k.to_string()),
                 FeatureDataType::Float => float.push(k.to_string()),
                 FeatureDataType::Text => text.push(k.to_string()),
+                FeatureDataType::Category => category.push(k.to_string()),
                 FeatureDataType::Bool => bool.push(k.to_string()),
                 FeatureDataType::DateTime => datetime.push(k.to_string()),
             }
@@ -318,6 +325,7 @@ impl NFDIDataProvider {
             text,
             bool,
             datetime,
+            category,
             rename: None,
         };
 
@@ -337,15 +345,17 @@ impl NFDIDataProvider {
                 start_format: OgrSourceTimeFormat::Auto,
                 end_field: attribute_end.clone(),
                 end_format: OgrSourceTimeFormat::Auto,
+                end_inclusion: OgrSourceEndInclusion::Exclusive,
             },
             Some(metadata::TemporalExtend::Duration {
                 attribute_start,
                 attribute_duration,
-                unit: _,
+                unit,
             }) => OgrSourceDatasetTimeType::StartDuration {
                 start_field: attribute_start.clone(),
                 start_format: OgrSourceTimeFormat::Auto,
                 duration_field: attribute_duration.clone(),
+                duration_format: OgrSourceDurationFormat::Steps { granularity: *unit },
             },
             None => OgrSourceDatasetTimeType::None,
         };
@@ -362,6 +372,7 @@ impl NFDIDataProvider {
             on_error: OgrSourceErrorSpec::Abort,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         }
     }
 
@@ -384,6 +395,7 @@ impl NFDIDataProvider {
                 properties_mapping: None,
                 gdal_open_options: None,
                 gdal_config_options: None,
+                apply_scale_offset: false,
             }),
         };
 
@@ -577,6 +589,7 @@ impl ExpiringDownloadLink for OgrSourceDataset {
             on_error: self.on_error,
             sql_query: self.sql_query.clone(),
             attribute_query: self.attribute_query.clone(),
+            generalizations: self.generalizations.clone(),
         })
     }
 }
@@ -1038,6 +1051,8 @@ mod tests {
         let opts = DatasetListOptions {
             filter: None,
             limit: 100,
+            bbox: None,
+            time: None,
             offset: 0,
             order: OrderBy::NameAsc,
         };
@@ -1314,6 +1329,7 @@ mod tests {
             spatial_bounds: BoundingBox2D::new((-180., -90.).into(), (180., 90.).into()).unwrap(),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::zero_point_one(),
+            spatial_filter: None,
         };
 
         let result: Vec<MultiPointCollection> = proc
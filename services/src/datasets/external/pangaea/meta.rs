@@ -68,6 +68,7 @@ impl PangeaMetaData {
             spatial_reference: SpatialReference::epsg_4326().into(),
             data_type: feature_type,
             columns: column_map,
+            column_metadata: Default::default(),
         }
     }
 
@@ -112,6 +113,7 @@ impl PangeaMetaData {
                 .collect(),
             bool: vec![],
             datetime: vec![],
+            category: vec![],
         }
     }
 
@@ -138,6 +140,7 @@ impl PangeaMetaData {
             on_error: OgrSourceErrorSpec::Abort,
             sql_query: None,
             attribute_query: None,
+            generalizations: vec![],
         }
     }
 
@@ -1,14 +1,14 @@
-use crate::contexts::SimpleSession;
+use crate::contexts::{BackupDb, SimpleSession};
 use crate::datasets::listing::{
     DatasetListOptions, DatasetListing, DatasetProvider, ExternalDatasetProvider, OrderBy,
 };
 use crate::datasets::storage::{
-    AddDataset, Dataset, DatasetDb, DatasetProviderDb, DatasetProviderListOptions,
-    DatasetProviderListing, DatasetStore, DatasetStorer,
+    AddDataset, Dataset, DatasetDb, DatasetDefinition, DatasetProviderDb,
+    DatasetProviderListOptions, DatasetProviderListing, DatasetStore, DatasetStorer, UpdateDataset,
 };
 use crate::error;
 use crate::error::Result;
-use crate::util::user_input::Validated;
+use crate::util::user_input::{UserInput, Validated};
 use async_trait::async_trait;
 use geoengine_datatypes::primitives::{RasterQueryRectangle, VectorQueryRectangle};
 use geoengine_datatypes::{
@@ -22,6 +22,7 @@ use geoengine_operators::source::{
     GdalLoadingInfo, GdalMetaDataRegular, GdalMetadataNetCdfCf, OgrSourceDataset,
 };
 use geoengine_operators::{mock::MockDatasetDataSourceLoadingInfo, source::GdalMetaDataStatic};
+use snafu::ensure;
 use std::collections::HashMap;
 
 use super::listing::ProvenanceOutput;
@@ -50,6 +51,8 @@ pub struct HashMapDatasetDb {
         InternalDatasetId,
         Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
     >,
+    /// the definitions of the meta data above for exporting them
+    meta_data_definitions: HashMap<InternalDatasetId, MetaDataDefinition>,
     uploads: HashMap<UploadId, Upload>,
     external_providers: HashMap<DatasetProviderId, Box<dyn ExternalDatasetProviderDefinition>>,
 }
@@ -124,6 +127,8 @@ impl HashMapStorable
 {
     fn store(&self, id: InternalDatasetId, db: &mut HashMapDatasetDb) -> TypedResultDescriptor {
         db.ogr_datasets.insert(id, self.clone());
+        db.meta_data_definitions
+            .insert(id, MetaDataDefinition::OgrMetaData(self.clone()));
         self.result_descriptor.clone().into()
     }
 }
@@ -137,6 +142,8 @@ impl HashMapStorable
 {
     fn store(&self, id: InternalDatasetId, db: &mut HashMapDatasetDb) -> TypedResultDescriptor {
         db.mock_datasets.insert(id, self.clone());
+        db.meta_data_definitions
+            .insert(id, MetaDataDefinition::MockMetaData(self.clone()));
         self.result_descriptor.clone().into()
     }
 }
@@ -144,6 +151,8 @@ impl HashMapStorable
 impl HashMapStorable for GdalMetaDataRegular {
     fn store(&self, id: InternalDatasetId, db: &mut HashMapDatasetDb) -> TypedResultDescriptor {
         db.gdal_datasets.insert(id, Box::new(self.clone()));
+        db.meta_data_definitions
+            .insert(id, MetaDataDefinition::GdalMetaDataRegular(self.clone()));
         self.result_descriptor.clone().into()
     }
 }
@@ -151,6 +160,8 @@ impl HashMapStorable for GdalMetaDataRegular {
 impl HashMapStorable for GdalMetaDataStatic {
     fn store(&self, id: InternalDatasetId, db: &mut HashMapDatasetDb) -> TypedResultDescriptor {
         db.gdal_datasets.insert(id, Box::new(self.clone()));
+        db.meta_data_definitions
+            .insert(id, MetaDataDefinition::GdalStatic(self.clone()));
         self.result_descriptor.clone().into()
     }
 }
@@ -158,6 +169,8 @@ impl HashMapStorable for GdalMetaDataStatic {
 impl HashMapStorable for GdalMetadataNetCdfCf {
     fn store(&self, id: InternalDatasetId, db: &mut HashMapDatasetDb) -> TypedResultDescriptor {
         db.gdal_datasets.insert(id, Box::new(self.clone()));
+        db.meta_data_definitions
+            .insert(id, MetaDataDefinition::GdalMetadataNetCdfCf(self.clone()));
         self.result_descriptor.clone().into()
     }
 }
@@ -174,7 +187,9 @@ impl DatasetStore<SimpleSession> for HashMapDatasetDb {
         let id = dataset
             .id
             .unwrap_or_else(|| InternalDatasetId::new().into());
-        let result_descriptor = meta_data.store(id.internal().expect("from AddDataset"), self);
+        let internal_id = id.internal().expect("from AddDataset");
+        let result_descriptor = meta_data.store(internal_id, self);
+        let extent = self.meta_data_definitions[&internal_id].extent().await;
 
         let d: Dataset = Dataset {
             id: id.clone(),
@@ -184,12 +199,61 @@ impl DatasetStore<SimpleSession> for HashMapDatasetDb {
             source_operator: dataset.source_operator,
             symbology: dataset.symbology,
             provenance: dataset.provenance,
+            extent,
         };
         self.datasets.push(d);
 
         Ok(id)
     }
 
+    async fn update_dataset(
+        &mut self,
+        _session: &SimpleSession,
+        dataset: &DatasetId,
+        update: Validated<UpdateDataset>,
+    ) -> Result<()> {
+        let dataset = self
+            .datasets
+            .iter_mut()
+            .find(|d| d.id == *dataset)
+            .ok_or(error::Error::UnknownDatasetId)?;
+
+        update.user_input.apply(dataset);
+
+        Ok(())
+    }
+
+    async fn ensure_writable(&self, _session: &SimpleSession, dataset: &DatasetId) -> Result<()> {
+        ensure!(
+            self.datasets.iter().any(|d| d.id == *dataset),
+            error::UnknownDatasetId
+        );
+
+        Ok(())
+    }
+
+    async fn delete_dataset(
+        &mut self,
+        _session: &SimpleSession,
+        dataset: &DatasetId,
+    ) -> Result<()> {
+        let index = self
+            .datasets
+            .iter()
+            .position(|d| d.id == *dataset)
+            .ok_or(error::Error::UnknownDatasetId)?;
+        self.datasets.remove(index);
+
+        if let Some(id) = dataset.internal() {
+            self.ogr_datasets.remove(&id);
+            self.mock_datasets.remove(&id);
+            self.gdal_datasets.remove(&id);
+            self.meta_data_definitions.remove(&id);
+        }
+
+        Ok(())
+    }
+
     fn wrap_meta_data(&self, meta: MetaDataDefinition) -> Self::StorageType {
         Box::new(meta)
     }
@@ -215,6 +279,7 @@ impl DatasetProvider<SimpleSession> for HashMapDatasetDb {
         } else {
             self.datasets.iter().collect()
         };
+        list.retain(|d| options.matches_extent(&d.extent));
 
         match options.order {
             OrderBy::NameAsc => list.sort_by(|a, b| a.name.cmp(&b.name)),
@@ -373,6 +438,46 @@ impl UploadDb<SimpleSession> for HashMapDatasetDb {
     }
 }
 
+#[async_trait]
+impl BackupDb<DatasetDefinition> for HashMapDatasetDb {
+    async fn export(&self) -> Result<Vec<DatasetDefinition>> {
+        self.datasets
+            .iter()
+            .map(|dataset| {
+                let id = dataset
+                    .id
+                    .internal()
+                    .ok_or(error::Error::InvalidDatasetId)?;
+                let meta_data = self
+                    .meta_data_definitions
+                    .get(&id)
+                    .ok_or(error::Error::UnknownDatasetId)?;
+
+                Ok(dataset.definition(meta_data.clone()))
+            })
+            .collect()
+    }
+
+    async fn import(&mut self, dataset: DatasetDefinition) -> Result<()> {
+        if let Some(id) = &dataset.properties.id {
+            ensure!(
+                self.datasets.iter().all(|d| d.id != *id),
+                error::Duplicate {
+                    reason: format!("Dataset {:?} already exists", id),
+                }
+            );
+        }
+
+        self.add_dataset(
+            &SimpleSession::default(),
+            dataset.properties.validated()?,
+            Box::new(dataset.meta_data),
+        )
+        .await
+        .map(drop)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -380,9 +485,10 @@ mod tests {
     use crate::datasets::listing::OrderBy;
     use crate::util::user_input::UserInput;
     use geoengine_datatypes::collections::VectorDataType;
+    use geoengine_datatypes::primitives::TimeInterval;
     use geoengine_datatypes::spatial_reference::SpatialReferenceOption;
     use geoengine_datatypes::util::test::TestDefault;
-    use geoengine_operators::engine::MetaDataProvider;
+    use geoengine_operators::engine::{MetaDataProvider, ResultExtent};
     use geoengine_operators::source::OgrSourceErrorSpec;
 
     #[tokio::test]
@@ -395,6 +501,7 @@ mod tests {
             data_type: VectorDataType::Data,
             spatial_reference: SpatialReferenceOption::Unreferenced,
             columns: Default::default(),
+            column_metadata: Default::default(),
         };
 
         let ds = AddDataset {
@@ -419,6 +526,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                generalizations: vec![],
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -441,7 +549,8 @@ mod tests {
             VectorResultDescriptor {
                 data_type: VectorDataType::Data,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
-                columns: Default::default()
+                columns: Default::default(),
+                column_metadata: Default::default(),
             }
         );
 
@@ -455,6 +564,8 @@ mod tests {
                     order: OrderBy::NameAsc,
                     offset: 0,
                     limit: 1,
+                    bbox: None,
+                    time: None,
                 }
                 .validated()?,
             )
@@ -472,6 +583,8 @@ mod tests {
                 source_operator: "OgrSource".to_string(),
                 result_descriptor: descriptor.into(),
                 symbology: None,
+                // the file cannot be scanned, but features without time are valid forever
+                extent: ResultExtent::new(None, Some(TimeInterval::default())),
             }
         );
 
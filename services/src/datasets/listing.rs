@@ -2,15 +2,20 @@ use crate::contexts::Session;
 use crate::datasets::storage::Dataset;
 use crate::error;
 use crate::error::Result;
+use crate::ogc::util::{
+    parse_bbox_option, parse_time_option, serialize_bbox_option, serialize_time_option,
+};
 use crate::projects::Symbology;
 use crate::util::config::{get_config_element, DatasetService};
 use crate::util::user_input::{UserInput, Validated};
 use async_trait::async_trait;
 use geoengine_datatypes::dataset::DatasetId;
-use geoengine_datatypes::primitives::{RasterQueryRectangle, VectorQueryRectangle};
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, RasterQueryRectangle, TimeInterval, VectorQueryRectangle,
+};
 use geoengine_operators::engine::{
-    MetaData, MetaDataProvider, RasterResultDescriptor, ResultDescriptor, TypedResultDescriptor,
-    VectorResultDescriptor,
+    MetaData, MetaDataProvider, RasterResultDescriptor, ResultDescriptor, ResultExtent,
+    TypedResultDescriptor, VectorResultDescriptor,
 };
 use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
 use geoengine_operators::source::{GdalLoadingInfo, OgrSourceDataset};
@@ -27,7 +32,10 @@ pub struct DatasetListing {
     pub source_operator: String,
     pub result_descriptor: TypedResultDescriptor,
     pub symbology: Option<Symbology>,
-    // TODO: meta data like bounds, resolution
+    /// The spatial and temporal extent of the dataset's data as far as it is known
+    #[serde(default)]
+    pub extent: ResultExtent,
+    // TODO: meta data like resolution
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,6 +45,37 @@ pub struct DatasetListOptions {
     pub order: OrderBy,
     pub offset: u32,
     pub limit: u32,
+    /// Only lists datasets whose extent intersects these bounds, format is: "x1,y1,x2,y2"
+    #[serde(
+        default,
+        deserialize_with = "parse_bbox_option",
+        serialize_with = "serialize_bbox_option"
+    )]
+    pub bbox: Option<BoundingBox2D>,
+    /// Only lists datasets whose extent overlaps this time, e.g., "2014-01-01T00:00:00Z/2015-01-01T00:00:00Z"
+    #[serde(
+        default,
+        deserialize_with = "parse_time_option",
+        serialize_with = "serialize_time_option"
+    )]
+    pub time: Option<TimeInterval>,
+}
+
+impl DatasetListOptions {
+    /// Whether a dataset with the `extent` passes the `bbox` and `time` filters.
+    /// Datasets are only filtered out if their extent is known.
+    pub fn matches_extent(&self, extent: &ResultExtent) -> bool {
+        let outside_bbox = match (&self.bbox, &extent.spatial) {
+            (Some(bbox), Some(spatial)) => !spatial.intersects_bbox(bbox),
+            _ => false,
+        };
+        let outside_time = match (&self.time, &extent.time) {
+            (Some(time), Some(extent)) => !extent.intersects(time),
+            _ => false,
+        };
+
+        !outside_bbox && !outside_time
+    }
 }
 
 impl UserInput for DatasetListOptions {
@@ -143,4 +182,46 @@ pub struct Provenance {
     pub citation: String,
     pub license: String,
     pub uri: String,
+    /// The version of the data, e.g., a release number or the date of the last update
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_filters_by_known_extents() {
+        let options: DatasetListOptions = serde_urlencoded::from_str(
+            "order=NameAsc&offset=0&limit=10&bbox=0,0,10,10&time=2014-01-01T00:00:00Z/2015-01-01T00:00:00Z",
+        )
+        .unwrap();
+
+        assert_eq!(
+            options.bbox,
+            Some(BoundingBox2D::new((0., 0.).into(), (10., 10.).into()).unwrap())
+        );
+        assert_eq!(
+            options.time,
+            Some(TimeInterval::new(1_388_534_400_000, 1_420_070_400_000).unwrap())
+        );
+        assert_eq!(
+            serde_urlencoded::from_str::<DatasetListOptions>(
+                &serde_urlencoded::to_string(&options).unwrap()
+            )
+            .unwrap()
+            .bbox,
+            options.bbox
+        );
+
+        let inside = BoundingBox2D::new((5., 5.).into(), (20., 20.).into()).unwrap();
+        let outside = BoundingBox2D::new((15., 15.).into(), (20., 20.).into()).unwrap();
+        let later = TimeInterval::new(1_420_070_400_000, 1_451_606_400_000).unwrap();
+
+        assert!(options.matches_extent(&ResultExtent::default()));
+        assert!(options.matches_extent(&ResultExtent::new(Some(inside), None)));
+        assert!(!options.matches_extent(&ResultExtent::new(Some(outside), None)));
+        assert!(!options.matches_extent(&ResultExtent::new(Some(inside), Some(later))));
+    }
 }
@@ -1,6 +1,10 @@
 pub mod add_from_directory;
+pub mod citation;
 pub mod external;
 pub mod in_memory;
 pub mod listing;
+pub mod preview;
 pub mod storage;
 pub mod upload;
+pub mod validation;
+pub mod watcher;
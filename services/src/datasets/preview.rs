@@ -0,0 +1,371 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use gdal::vector::Geometry;
+use gdal::{DatasetOptions, GdalOpenFlags};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::operations::image::{Colorizer, RgbaColor, ToPng};
+use geoengine_datatypes::primitives::{Coordinate2D, TimeInterval};
+use geoengine_datatypes::raster::Grid2D;
+use geoengine_operators::source::{GdalDatasetParameters, OgrSourceDataset};
+use geoengine_operators::util::gdal::gdal_open_dataset_ex;
+use image::{ImageBuffer, ImageFormat, Rgba, RgbaImage};
+use snafu::ResultExt;
+
+use crate::datasets::storage::MetaDataDefinition;
+use crate::error::{self, Result};
+use crate::projects::Symbology;
+use crate::util::config::{self, get_config_element};
+
+/// The maximum number of features of a vector dataset that are rendered
+const MAX_PREVIEW_FEATURES: usize = 1000;
+
+const VECTOR_COLOR: [u8; 4] = [30, 100, 200, 255];
+
+/// Renders the preview image of a dataset and stores it in the directory of the
+/// `dataset_preview` settings, s.t. it can be served without rendering it again.
+///
+/// Previews that already exist are kept unless `replace` is set.
+pub async fn create_preview(
+    dataset: &DatasetId,
+    meta_data: MetaDataDefinition,
+    symbology: Option<Symbology>,
+    replace: bool,
+) -> Result<()> {
+    let config = get_config_element::<config::DatasetPreview>()?;
+    let path = preview_path(&config, dataset);
+
+    if !replace && tokio::fs::metadata(&path).await.is_ok() {
+        return Ok(());
+    }
+
+    let size = config.size;
+    let preview =
+        crate::util::spawn_blocking(move || render_preview(&meta_data, symbology.as_ref(), size))
+            .await
+            .context(error::TokioJoin)??;
+
+    let preview = match preview {
+        Some(preview) => preview,
+        None => return Ok(()),
+    };
+
+    tokio::fs::create_dir_all(&config.path).await?;
+    tokio::fs::write(path, preview).await?;
+
+    Ok(())
+}
+
+/// Loads the PNG preview of a dataset if it was created before
+pub async fn load_preview(dataset: &DatasetId) -> Result<Option<Vec<u8>>> {
+    let config = get_config_element::<config::DatasetPreview>()?;
+
+    match tokio::fs::read(preview_path(&config, dataset)).await {
+        Ok(preview) => Ok(Some(preview)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Deletes the preview of a dataset if it exists
+pub async fn delete_preview(dataset: &DatasetId) -> Result<()> {
+    let config = get_config_element::<config::DatasetPreview>()?;
+
+    match tokio::fs::remove_file(preview_path(&config, dataset)).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn preview_path(config: &config::DatasetPreview, dataset: &DatasetId) -> PathBuf {
+    let name = match dataset {
+        DatasetId::Internal { dataset_id } => dataset_id.to_string(),
+        DatasetId::External(external) => {
+            format!("{}-{}", external.provider_id, external.dataset_id)
+        }
+    };
+
+    config.path.join(format!("{}.png", name))
+}
+
+/// Renders a PNG of at most `size` x `size` pixels or returns `None` if the dataset has no data to render
+fn render_preview(
+    meta_data: &MetaDataDefinition,
+    symbology: Option<&Symbology>,
+    size: u32,
+) -> Result<Option<Vec<u8>>> {
+    let colorizer = match symbology {
+        Some(Symbology::Raster(raster)) => Some(&raster.colorizer),
+        _ => None,
+    };
+
+    match meta_data {
+        MetaDataDefinition::MockMetaData(_) => Ok(None),
+        MetaDataDefinition::OgrMetaData(m) => render_vector_preview(&m.loading_info, size),
+        MetaDataDefinition::GdalStatic(m) => render_raster_preview(&m.params, colorizer, size),
+        MetaDataDefinition::GdalMetaDataRegular(m) => {
+            let time = TimeInterval::new(m.start, (m.start + m.step)?)?;
+            let params = m
+                .params
+                .replace_time_placeholders(&m.time_placeholders, time)?;
+            render_raster_preview(&params, colorizer, size)
+        }
+        MetaDataDefinition::GdalMetadataNetCdfCf(m) => {
+            let mut params = m.params.clone();
+            params.rasterband_channel = 1 /* GDAL starts at 1 */ + m.band_offset;
+            render_raster_preview(&params, colorizer, size)
+        }
+    }
+}
+
+/// Renders the band of a raster file colorized by the dataset's colorizer or in grayscale.
+/// GDAL reads from the file's overviews if they exist.
+fn render_raster_preview(
+    params: &GdalDatasetParameters,
+    colorizer: Option<&Colorizer>,
+    size: u32,
+) -> Result<Option<Vec<u8>>> {
+    let dataset = params.open_raster_dataset()?;
+    let band = dataset.rasterband(params.rasterband_channel as isize)?;
+
+    let (width, height) = dataset.raster_size();
+    if width == 0 || height == 0 {
+        return Ok(None);
+    }
+
+    let (preview_width, preview_height) = preview_size(width as f64, height as f64, size);
+
+    let buffer = band.read_as::<f64>(
+        (0, 0),
+        (width, height),
+        (preview_width as usize, preview_height as usize),
+        None,
+    )?;
+
+    let no_data_value = params.no_data_value.or_else(|| band.no_data_value());
+    let grid = Grid2D::new(
+        [preview_height as usize, preview_width as usize].into(),
+        buffer.data,
+        no_data_value,
+    )?;
+
+    let colorizer = match colorizer {
+        Some(colorizer) => colorizer.clone(),
+        None => match grayscale(&grid)? {
+            Some(colorizer) => colorizer,
+            None => return Ok(None),
+        },
+    };
+
+    Ok(Some(grid.to_png(
+        preview_width,
+        preview_height,
+        &colorizer,
+    )?))
+}
+
+/// A gradient from black to white over the range of valid values
+fn grayscale(grid: &Grid2D<f64>) -> Result<Option<Colorizer>> {
+    let (min, max) = grid
+        .data
+        .iter()
+        .filter(|&&value| !value.is_nan() && Some(value) != grid.no_data_value)
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+            (min.min(value), max.max(value))
+        });
+
+    if min > max {
+        return Ok(None);
+    }
+
+    // the gradient requires distinct breakpoints
+    let max = if max > min { max } else { min + 1. };
+
+    Ok(Some(Colorizer::linear_gradient(
+        vec![
+            (min, RgbaColor::black())
+                .try_into()
+                .expect("the values are not NaN"),
+            (max, RgbaColor::white())
+                .try_into()
+                .expect("the values are not NaN"),
+        ],
+        RgbaColor::transparent(),
+        RgbaColor::transparent(),
+    )?))
+}
+
+/// Renders the geometries of the first features of a vector layer
+fn render_vector_preview(dataset: &OgrSourceDataset, size: u32) -> Result<Option<Vec<u8>>> {
+    let gdal_dataset = gdal_open_dataset_ex(
+        &dataset.file_name,
+        DatasetOptions {
+            open_flags: GdalOpenFlags::GDAL_OF_VECTOR,
+            ..DatasetOptions::default()
+        },
+    )?;
+    let mut layer = gdal_dataset.layer_by_name(&dataset.layer_name)?;
+
+    let mut paths = Vec::new();
+    for feature in layer.features().take(MAX_PREVIEW_FEATURES) {
+        if let Ok(geometry) = feature.geometry_by_index(0) {
+            collect_paths(geometry, &mut paths);
+        }
+    }
+
+    let (min, max) = paths.iter().flatten().fold(
+        (
+            Coordinate2D::new(f64::INFINITY, f64::INFINITY),
+            Coordinate2D::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+        ),
+        |(min, max), c| {
+            (
+                Coordinate2D::new(min.x.min(c.x), min.y.min(c.y)),
+                Coordinate2D::new(max.x.max(c.x), max.y.max(c.y)),
+            )
+        },
+    );
+
+    if min.x > max.x || min.y > max.y {
+        return Ok(None);
+    }
+
+    let (width, height) = preview_size(max.x - min.x, max.y - min.y, size);
+    let mut image: RgbaImage = ImageBuffer::new(width, height);
+
+    // a single point or a horizontal or vertical line has no extent in one dimension
+    let extent = (max.x - min.x).max(max.y - min.y).max(f64::EPSILON);
+    let scale = f64::from(size - 1) / extent;
+    let to_pixel = |c: &Coordinate2D| {
+        (
+            ((c.x - min.x) * scale).round(),
+            ((max.y - c.y) * scale).round(),
+        )
+    };
+
+    for path in &paths {
+        match path.as_slice() {
+            [point] => draw_point(&mut image, to_pixel(point)),
+            path => {
+                for segment in path.windows(2) {
+                    draw_line(&mut image, to_pixel(&segment[0]), to_pixel(&segment[1]));
+                }
+            }
+        }
+    }
+
+    encode_png(image).map(Some)
+}
+
+/// Collects the points, lines and rings of a (multi) geometry
+fn collect_paths(geometry: &Geometry, paths: &mut Vec<Vec<Coordinate2D>>) {
+    let count = geometry.geometry_count();
+
+    if count == 0 {
+        let path: Vec<Coordinate2D> = geometry
+            .get_point_vec()
+            .into_iter()
+            .map(|(x, y, _z)| Coordinate2D::new(x, y))
+            .collect();
+
+        if !path.is_empty() {
+            paths.push(path);
+        }
+
+        return;
+    }
+
+    for i in 0..count {
+        collect_paths(&unsafe { geometry.get_unowned_geometry(i) }, paths);
+    }
+}
+
+/// The size of an image of at most `size` x `size` pixels with the aspect ratio of `width` / `height`
+fn preview_size(width: f64, height: f64, size: u32) -> (u32, u32) {
+    let extent = width.max(height);
+    if extent <= 0. {
+        return (size, size);
+    }
+
+    let scale = f64::from(size) / extent;
+    (
+        ((width * scale).round() as u32).max(1),
+        ((height * scale).round() as u32).max(1),
+    )
+}
+
+fn put_pixel(image: &mut RgbaImage, (x, y): (f64, f64)) {
+    if x >= 0. && y >= 0. && x < f64::from(image.width()) && y < f64::from(image.height()) {
+        image.put_pixel(x as u32, y as u32, Rgba(VECTOR_COLOR));
+    }
+}
+
+/// Draws a point as a square of 3 x 3 pixels
+fn draw_point(image: &mut RgbaImage, (x, y): (f64, f64)) {
+    for dy in -1..=1 {
+        for dx in -1..=1 {
+            put_pixel(image, (x + f64::from(dx), y + f64::from(dy)));
+        }
+    }
+}
+
+fn draw_line(image: &mut RgbaImage, (x0, y0): (f64, f64), (x1, y1): (f64, f64)) {
+    let steps = (x1 - x0).abs().max((y1 - y0).abs()).max(1.);
+
+    for step in 0..=steps as u32 {
+        let t = f64::from(step) / steps;
+        put_pixel(
+            image,
+            ((x0 + t * (x1 - x0)).round(), (y0 + t * (y1 - y0)).round()),
+        );
+    }
+}
+
+fn encode_png(image: RgbaImage) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| error::Error::DatasetPreview {
+            reason: e.to_string(),
+        })?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_operators::util::gdal::create_ndvi_meta_data;
+
+    #[test]
+    fn it_renders_raster_previews() {
+        let meta_data = MetaDataDefinition::GdalMetaDataRegular(create_ndvi_meta_data());
+
+        let preview = render_preview(&meta_data, None, 64).unwrap().unwrap();
+
+        let image = image::load_from_memory_with_format(&preview, ImageFormat::Png).unwrap();
+        // the NDVI covers the whole world and is twice as wide as high
+        assert_eq!((image.width(), image.height()), (64, 32));
+    }
+
+    #[test]
+    fn it_renders_vector_paths() {
+        let mut image: RgbaImage = ImageBuffer::new(8, 8);
+
+        draw_line(&mut image, (0., 0.), (7., 7.));
+        draw_point(&mut image, (7., 0.));
+
+        for i in 0..8 {
+            assert_eq!(image.get_pixel(i, i), &Rgba(VECTOR_COLOR));
+        }
+        assert_eq!(image.get_pixel(6, 1), &Rgba(VECTOR_COLOR));
+        assert_eq!(image.get_pixel(0, 7), &Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn it_keeps_the_aspect_ratio() {
+        assert_eq!(preview_size(360., 180., 256), (256, 128));
+        assert_eq!(preview_size(10., 40., 256), (64, 256));
+        assert_eq!(preview_size(0., 0., 256), (256, 256));
+    }
+}
@@ -7,10 +7,14 @@ use crate::error::Result;
 use crate::projects::Symbology;
 use crate::util::user_input::{UserInput, Validated};
 use async_trait::async_trait;
+use gdal::{DatasetOptions, GdalOpenFlags};
 use geoengine_datatypes::dataset::{DatasetId, DatasetProviderId};
-use geoengine_datatypes::primitives::VectorQueryRectangle;
-use geoengine_operators::engine::MetaData;
-use geoengine_operators::source::GdalMetadataNetCdfCf;
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Coordinate2D, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_operators::engine::{MetaData, ResultExtent};
+use geoengine_operators::source::{GdalMetadataNetCdfCf, OgrSourceDatasetTimeType};
+use geoengine_operators::util::gdal::gdal_open_dataset_ex;
 use geoengine_operators::{engine::StaticMetaData, source::OgrSourceDataset};
 use geoengine_operators::{
     engine::TypedResultDescriptor, mock::MockDatasetDataSourceLoadingInfo,
@@ -33,6 +37,9 @@ pub struct Dataset {
     pub source_operator: String,
     pub symbology: Option<Symbology>,
     pub provenance: Option<Provenance>,
+    /// The spatial and temporal extent of the data, computed when the dataset is added
+    #[serde(default)]
+    pub extent: ResultExtent,
 }
 
 impl Dataset {
@@ -45,6 +52,22 @@ impl Dataset {
             source_operator: self.source_operator.clone(),
             result_descriptor: self.result_descriptor.clone(),
             symbology: self.symbology.clone(),
+            extent: self.extent,
+        }
+    }
+
+    /// The definition for adding the dataset with the same id again, e.g., to another instance
+    pub fn definition(&self, meta_data: MetaDataDefinition) -> DatasetDefinition {
+        DatasetDefinition {
+            properties: AddDataset {
+                id: Some(self.id.clone()),
+                name: self.name.clone(),
+                description: self.description.clone(),
+                source_operator: self.source_operator.clone(),
+                symbology: self.symbology.clone(),
+                provenance: self.provenance.clone(),
+            },
+            meta_data,
         }
     }
 }
@@ -67,6 +90,45 @@ impl UserInput for AddDataset {
     }
 }
 
+/// Changes the properties of a dataset, i.e., all properties that are given
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDataset {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub symbology: Option<Symbology>,
+    pub provenance: Option<Provenance>,
+}
+
+impl UpdateDataset {
+    /// Applies the changes to the `dataset`
+    pub fn apply(self, dataset: &mut Dataset) {
+        if let Some(name) = self.name {
+            dataset.name = name;
+        }
+        if let Some(description) = self.description {
+            dataset.description = description;
+        }
+        if let Some(symbology) = self.symbology {
+            dataset.symbology = Some(symbology);
+        }
+        if let Some(provenance) = self.provenance {
+            dataset.provenance = Some(provenance);
+        }
+    }
+}
+
+impl UserInput for UpdateDataset {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.name.as_ref().map_or(true, |name| !name.is_empty()),
+            error::InvalidDatasetName
+        );
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct DatasetProviderListing {
@@ -214,6 +276,55 @@ impl MetaDataDefinition {
                 .context(error::Operator),
         }
     }
+
+    /// The extent of the dataset from its meta data. The bounds of vector datasets are not part of
+    /// their meta data, so their files are scanned. Parts that cannot be determined are unknown.
+    pub async fn extent(&self) -> ResultExtent {
+        let extent = match self {
+            MetaDataDefinition::MockMetaData(m) => m.extent().await,
+            MetaDataDefinition::OgrMetaData(m) => {
+                let dataset = m.loading_info.clone();
+                let spatial = crate::util::spawn_blocking(move || ogr_layer_bounds(&dataset))
+                    .await
+                    .ok()
+                    .flatten();
+                // features without time are valid forever
+                let time = matches!(m.loading_info.time, OgrSourceDatasetTimeType::None)
+                    .then(TimeInterval::default);
+
+                Ok(ResultExtent::new(spatial, time))
+            }
+            MetaDataDefinition::GdalMetaDataRegular(m) => m.extent().await,
+            MetaDataDefinition::GdalStatic(m) => m.extent().await,
+            MetaDataDefinition::GdalMetadataNetCdfCf(m) => m.extent().await,
+        };
+
+        extent.unwrap_or_default()
+    }
+}
+
+/// The bounds of the features of the layer of an OGR dataset, if it can be opened
+fn ogr_layer_bounds(dataset: &OgrSourceDataset) -> Option<BoundingBox2D> {
+    let gdal_dataset = gdal_open_dataset_ex(
+        &dataset.file_name,
+        DatasetOptions {
+            open_flags: GdalOpenFlags::GDAL_OF_VECTOR,
+            ..DatasetOptions::default()
+        },
+    )
+    .ok()?;
+
+    let envelope = gdal_dataset
+        .layer_by_name(&dataset.layer_name)
+        .ok()?
+        .get_extent()
+        .ok()?;
+
+    BoundingBox2D::new(
+        Coordinate2D::new(envelope.MinX, envelope.MinY),
+        Coordinate2D::new(envelope.MaxX, envelope.MaxY),
+    )
+    .ok()
 }
 
 /// Handling of datasets provided by geo engine internally, staged and by external providers
@@ -265,6 +376,21 @@ pub trait DatasetStore<S: Session>: DatasetStorer {
         meta_data: Self::StorageType,
     ) -> Result<DatasetId>;
 
+    /// Changes the properties of a `dataset`. Its meta data is not affected.
+    async fn update_dataset(
+        &mut self,
+        session: &S,
+        dataset: &DatasetId,
+        update: Validated<UpdateDataset>,
+    ) -> Result<()>;
+
+    /// Removes a `dataset` together with its meta data and permissions.
+    /// The files of the dataset are not deleted since they may be used by other datasets.
+    async fn delete_dataset(&mut self, session: &S, dataset: &DatasetId) -> Result<()>;
+
+    /// Checks that the `session` may modify the `dataset`, i.e., that it has a write permission.
+    async fn ensure_writable(&self, session: &S, dataset: &DatasetId) -> Result<()>;
+
     /// turn given `meta` data definition into the corresponding `StorageType` for the `DatasetStore`
     /// for use in the `add_dataset` method
     fn wrap_meta_data(&self, meta: MetaDataDefinition) -> Self::StorageType;
@@ -1,7 +1,9 @@
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 
 use crate::contexts::Session;
 use crate::error::Result;
+use crate::object_storage::ObjectStorage;
 use crate::{
     error,
     util::config::{self, get_config_element},
@@ -17,6 +19,14 @@ pub trait UploadRootPath {
     fn root_path(&self) -> Result<PathBuf>;
 }
 
+impl UploadId {
+    /// The key of a file of this upload in the object storage
+    pub fn file_key(&self, file_name: &str) -> String {
+        format!("{}/{}", self, file_name)
+    }
+}
+
+/// The local directory of an upload. Only valid for the local object storage.
 impl UploadRootPath for UploadId {
     fn root_path(&self) -> Result<PathBuf> {
         let root = get_config_element::<config::Upload>()?.path;
@@ -31,11 +41,18 @@ pub struct Upload {
 }
 
 impl Upload {
-    /// turns a user defined file path (pattern) into a path that points to the upload directory
-    pub fn adjust_file_path(&self, file_path: &Path) -> Result<PathBuf> {
-        let file_name = file_path.file_name().ok_or(error::Error::PathIsNotAFile)?;
+    /// turns a user defined file path (pattern) into a path that points to the upload in the storage
+    pub fn adjust_file_path(
+        &self,
+        file_path: &Path,
+        storage: &dyn ObjectStorage,
+    ) -> Result<PathBuf> {
+        let file_name = file_path
+            .file_name()
+            .and_then(OsStr::to_str)
+            .ok_or(error::Error::PathIsNotAFile)?;
 
-        Ok(self.id.root_path()?.join(file_name))
+        Ok(storage.gdal_path(&self.id.file_key(file_name)))
     }
 }
 
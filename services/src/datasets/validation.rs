@@ -0,0 +1,407 @@
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use gdal::{DatasetOptions, GdalOpenFlags};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{TimeInstance, TimeInterval, TimeStepIter};
+use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceOption};
+use geoengine_operators::source::{GdalDatasetParameters, OgrSourceDataset};
+use geoengine_operators::util::gdal::gdal_open_dataset_ex;
+use lazy_static::lazy_static;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use tokio::sync::RwLock;
+
+use crate::contexts::{BackupDb, Context};
+use crate::datasets::storage::{DatasetDefinition, MetaDataDefinition};
+use crate::error::{self, Result};
+
+lazy_static! {
+    static ref LATEST_REPORT: RwLock<Option<DatasetValidationReport>> = RwLock::new(None);
+}
+
+/// The result of validating the files of all datasets of the instance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetValidationReport {
+    pub created: DateTime<Utc>,
+    /// The number of datasets that were checked
+    pub checked: usize,
+    /// The datasets with at least one issue
+    pub datasets: Vec<DatasetValidation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetValidation {
+    pub id: Option<DatasetId>,
+    pub name: String,
+    pub issues: Vec<DatasetIssue>,
+}
+
+/// A problem of a dataset that lets queries on it fail or return incomplete results
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum DatasetIssue {
+    /// A referenced file does not exist
+    MissingFile { file: PathBuf },
+    /// A referenced file exists but cannot be opened
+    UnreadableFile { file: PathBuf, message: String },
+    /// The spatial reference of a file differs from the one of the dataset
+    CrsMismatch {
+        file: PathBuf,
+        expected: SpatialReferenceOption,
+        found: SpatialReferenceOption,
+    },
+    /// Consecutive time steps of the dataset have no data, i.e., files or bands are missing
+    TimeGap { time: TimeInterval, steps: u32 },
+}
+
+/// Validates the files of all datasets, stores the report as the latest one and returns it
+pub async fn validate_datasets<C: Context>(
+    ctx: &C,
+    max_time_steps: u32,
+) -> Result<DatasetValidationReport> {
+    let definitions: Vec<DatasetDefinition> = ctx.dataset_db_ref().await.export().await?;
+
+    let mut report = DatasetValidationReport {
+        created: Utc::now(),
+        checked: definitions.len(),
+        datasets: vec![],
+    };
+
+    for definition in definitions {
+        let issues = validate_meta_data(definition.meta_data, max_time_steps).await?;
+
+        if !issues.is_empty() {
+            report.datasets.push(DatasetValidation {
+                id: definition.properties.id,
+                name: definition.properties.name,
+                issues,
+            });
+        }
+    }
+
+    *LATEST_REPORT.write().await = Some(report.clone());
+
+    Ok(report)
+}
+
+/// Returns the report of the most recent validation
+pub async fn latest_validation_report() -> Option<DatasetValidationReport> {
+    LATEST_REPORT.read().await.clone()
+}
+
+/// Validates the datasets every `interval` in the background, starting after the first `interval`
+pub fn schedule_dataset_validation<C: Context>(ctx: C, interval: Duration, max_time_steps: u32) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+
+        // the first tick completes immediately
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            match validate_datasets(&ctx, max_time_steps).await {
+                Ok(report) => info!(
+                    "Validated {} datasets, {} have issues",
+                    report.checked,
+                    report.datasets.len()
+                ),
+                Err(e) => warn!("Dataset validation failed: {}", e),
+            }
+        }
+    });
+}
+
+async fn validate_meta_data(
+    meta_data: MetaDataDefinition,
+    max_time_steps: u32,
+) -> Result<Vec<DatasetIssue>> {
+    crate::util::spawn_blocking(move || validate_meta_data_blocking(meta_data, max_time_steps))
+        .await
+        .context(error::TokioJoin)?
+}
+
+fn validate_meta_data_blocking(
+    meta_data: MetaDataDefinition,
+    max_time_steps: u32,
+) -> Result<Vec<DatasetIssue>> {
+    match meta_data {
+        MetaDataDefinition::MockMetaData(_) => Ok(vec![]),
+        MetaDataDefinition::OgrMetaData(m) => Ok(validate_ogr_dataset(
+            &m.loading_info,
+            &m.result_descriptor.spatial_reference,
+        )),
+        MetaDataDefinition::GdalStatic(m) => {
+            Ok(validate_gdal_dataset(&m.params, &m.result_descriptor.spatial_reference).0)
+        }
+        MetaDataDefinition::GdalMetaDataRegular(m) => {
+            if m.time_placeholders.is_empty() {
+                return Ok(validate_gdal_dataset(
+                    &m.params,
+                    &m.result_descriptor.spatial_reference,
+                )
+                .0);
+            }
+
+            let now = TimeInstance::now();
+            let mut issues = vec![];
+            let mut gap: Option<(TimeInterval, u32)> = None;
+            let mut checked_first_file = false;
+
+            let time_steps = TimeStepIter::new_incl_start_unchecked(
+                m.start,
+                m.step,
+                max_time_steps.saturating_sub(1),
+            )
+            .take_while(|&t| t <= now);
+
+            for start in time_steps {
+                let time = TimeInterval::new(start, (start + m.step)?)?;
+                let params = m
+                    .params
+                    .replace_time_placeholders(&m.time_placeholders, time)?;
+
+                if is_local(&params.file_path) && !params.file_path.exists() {
+                    gap = Some(match gap {
+                        Some((gap_time, steps)) => {
+                            (TimeInterval::new(gap_time.start(), time.end())?, steps + 1)
+                        }
+                        None => (time, 1),
+                    });
+                    continue;
+                }
+
+                if let Some((time, steps)) = gap.take() {
+                    issues.push(DatasetIssue::TimeGap { time, steps });
+                }
+
+                // opening all files of long time series is too expensive, so only the first one is checked
+                if !checked_first_file {
+                    issues.extend(
+                        validate_gdal_dataset(&params, &m.result_descriptor.spatial_reference).0,
+                    );
+                    checked_first_file = true;
+                }
+            }
+
+            if let Some((time, steps)) = gap {
+                issues.push(DatasetIssue::TimeGap { time, steps });
+            }
+
+            Ok(issues)
+        }
+        MetaDataDefinition::GdalMetadataNetCdfCf(m) => {
+            let (mut issues, band_count) =
+                validate_gdal_dataset(&m.params, &m.result_descriptor.spatial_reference);
+
+            let band_count = match band_count {
+                Some(band_count) => band_count,
+                None => return Ok(issues),
+            };
+
+            let steps = m
+                .step
+                .num_steps_in_interval(TimeInterval::new(m.start, m.end)?)?;
+            let available_steps =
+                u32::try_from(band_count.saturating_sub(m.band_offset)).unwrap_or(u32::MAX);
+
+            if available_steps < steps {
+                let gap_start = TimeStepIter::new_incl_start(m.start, m.step, available_steps)?
+                    .last()
+                    .unwrap_or(m.start);
+                issues.push(DatasetIssue::TimeGap {
+                    time: TimeInterval::new(gap_start, m.end)?,
+                    steps: steps - available_steps,
+                });
+            }
+
+            Ok(issues)
+        }
+    }
+}
+
+/// Checks that the raster file of `params` can be opened and has the `expected` spatial reference.
+/// Returns the issues and the number of bands of the file if it could be opened.
+fn validate_gdal_dataset(
+    params: &GdalDatasetParameters,
+    expected: &SpatialReferenceOption,
+) -> (Vec<DatasetIssue>, Option<usize>) {
+    let file = &params.file_path;
+
+    if is_local(file) && !file.exists() {
+        return (vec![DatasetIssue::MissingFile { file: file.clone() }], None);
+    }
+
+    let dataset = match params.open_raster_dataset() {
+        Ok(dataset) => dataset,
+        Err(e) => {
+            return (
+                vec![DatasetIssue::UnreadableFile {
+                    file: file.clone(),
+                    message: e.to_string(),
+                }],
+                None,
+            )
+        }
+    };
+
+    let found = dataset
+        .spatial_ref()
+        .ok()
+        .and_then(|srs| SpatialReference::try_from(srs).ok());
+
+    let band_count = usize::try_from(dataset.raster_count()).unwrap_or_default();
+
+    (crs_mismatch(file, expected, found), Some(band_count))
+}
+
+/// Checks that the layer of the vector file exists and has the `expected` spatial reference
+fn validate_ogr_dataset(
+    dataset: &OgrSourceDataset,
+    expected: &SpatialReferenceOption,
+) -> Vec<DatasetIssue> {
+    let file = &dataset.file_name;
+
+    if is_local(file) && !file.exists() {
+        return vec![DatasetIssue::MissingFile { file: file.clone() }];
+    }
+
+    let unreadable = |message: String| {
+        vec![DatasetIssue::UnreadableFile {
+            file: file.clone(),
+            message,
+        }]
+    };
+
+    let gdal_dataset = match gdal_open_dataset_ex(
+        file,
+        DatasetOptions {
+            open_flags: GdalOpenFlags::GDAL_OF_VECTOR,
+            ..DatasetOptions::default()
+        },
+    ) {
+        Ok(gdal_dataset) => gdal_dataset,
+        Err(e) => return unreadable(e.to_string()),
+    };
+
+    let layer = match gdal_dataset.layer_by_name(&dataset.layer_name) {
+        Ok(layer) => layer,
+        Err(e) => return unreadable(e.to_string()),
+    };
+
+    let found = layer
+        .spatial_ref()
+        .ok()
+        .and_then(|srs| SpatialReference::try_from(srs).ok());
+
+    crs_mismatch(file, expected, found)
+}
+
+/// Reports a mismatch if the spatial reference of the file is known and differs from the `expected` one
+fn crs_mismatch(
+    file: &Path,
+    expected: &SpatialReferenceOption,
+    found: Option<SpatialReference>,
+) -> Vec<DatasetIssue> {
+    match found {
+        Some(found) if *expected != SpatialReferenceOption::SpatialReference(found) => {
+            vec![DatasetIssue::CrsMismatch {
+                file: file.to_path_buf(),
+                expected: *expected,
+                found: found.into(),
+            }]
+        }
+        _ => vec![],
+    }
+}
+
+/// Files on GDAL's virtual file systems, e.g., `/vsicurl/`, cannot be checked for existence
+fn is_local(file: &Path) -> bool {
+    !file.to_string_lossy().starts_with("/vsi")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::{InMemoryContext, SimpleSession};
+    use crate::datasets::storage::{AddDataset, DatasetStore};
+    use crate::util::tests::add_ndvi_to_datasets;
+    use crate::util::user_input::UserInput;
+    use geoengine_datatypes::util::test::TestDefault;
+    use geoengine_operators::source::GdalMetaDataStatic;
+    use geoengine_operators::util::gdal::create_ndvi_meta_data;
+
+    #[tokio::test]
+    async fn it_reports_missing_files_and_time_gaps() {
+        let ctx = InMemoryContext::test_default();
+
+        let ndvi = add_ndvi_to_datasets(&ctx).await;
+
+        let ndvi_meta_data = create_ndvi_meta_data();
+        let mut params = ndvi_meta_data.params.clone();
+        params.file_path = "/path/to/missing.tiff".into();
+
+        let missing = ctx
+            .dataset_db_ref_mut()
+            .await
+            .add_dataset(
+                &SimpleSession::default(),
+                AddDataset {
+                    id: None,
+                    name: "Missing".to_string(),
+                    description: "A dataset whose file is missing".to_string(),
+                    source_operator: "GdalSource".to_string(),
+                    symbology: None,
+                    provenance: None,
+                }
+                .validated()
+                .unwrap(),
+                Box::new(MetaDataDefinition::GdalStatic(GdalMetaDataStatic {
+                    time: None,
+                    params,
+                    result_descriptor: ndvi_meta_data.result_descriptor,
+                })),
+            )
+            .await
+            .unwrap();
+
+        assert!(latest_validation_report().await.is_none());
+
+        // the NDVI files cover January to June 2014
+        let report = validate_datasets(&ctx, 8).await.unwrap();
+
+        assert_eq!(report.checked, 2);
+        assert_eq!(latest_validation_report().await, Some(report.clone()));
+
+        let issues = |id: &DatasetId| {
+            report
+                .datasets
+                .iter()
+                .find(|dataset| dataset.id.as_ref() == Some(id))
+                .unwrap()
+                .issues
+                .clone()
+        };
+
+        assert_eq!(
+            issues(&missing),
+            vec![DatasetIssue::MissingFile {
+                file: "/path/to/missing.tiff".into()
+            }]
+        );
+
+        assert_eq!(
+            issues(&ndvi),
+            vec![DatasetIssue::TimeGap {
+                time: TimeInterval::new(1_404_172_800_000, 1_409_529_600_000).unwrap(),
+                steps: 2,
+            }]
+        );
+    }
+}
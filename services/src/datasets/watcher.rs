@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::{TimeInstance, TimeInterval};
+use geoengine_operators::source::{GdalMetaDataRegular, TimeReference};
+use lazy_static::lazy_static;
+use log::{debug, info, warn};
+use regex::Regex;
+use snafu::ResultExt;
+use tokio::sync::RwLock;
+
+use crate::contexts::{BackupDb, Context, DatasetEvent};
+use crate::datasets::storage::{DatasetDefinition, MetaDataDefinition};
+use crate::error::{self, Result};
+
+lazy_static! {
+    static ref WATCHED_TIME_SLICES: RwLock<HashMap<DatasetId, TimeSlices>> =
+        RwLock::new(HashMap::new());
+}
+
+/// The time slices of a dataset whose files were found in a watched directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TimeSlices {
+    /// from the start of the first slice to the end of the last slice
+    time: TimeInterval,
+    files: usize,
+}
+
+/// Returns the time extent of the dataset's files in the watched directories, i.e., from the start
+/// of the first time slice to the end of the last one
+pub async fn watched_time_extent(dataset: &DatasetId) -> Option<TimeInterval> {
+    WATCHED_TIME_SLICES
+        .read()
+        .await
+        .get(dataset)
+        .map(|slices| slices.time)
+}
+
+/// Scans the `paths` every `interval` in the background, starting immediately
+pub fn watch_directories<C: Context>(ctx: C, paths: Vec<PathBuf>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+
+        loop {
+            interval.tick().await;
+
+            match scan_directories(&ctx, &paths).await {
+                Ok(changed) if !changed.is_empty() => {
+                    info!("New time slices of {} datasets were found", changed.len());
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Scanning the watched directories failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Looks for the files of datasets with time placeholders, e.g., `ndvi_%TIME%.tif`, that are
+/// located in one of the `paths`.
+///
+/// Datasets whose time slices changed since the previous scan are announced as changed, which
+/// invalidates their cached results. The datasets are returned.
+pub async fn scan_directories<C: Context>(ctx: &C, paths: &[PathBuf]) -> Result<Vec<DatasetId>> {
+    let definitions: Vec<DatasetDefinition> = ctx.dataset_db_ref().await.export().await?;
+
+    let mut changed = vec![];
+
+    for definition in definitions {
+        let (dataset, meta_data) = match (definition.properties.id, definition.meta_data) {
+            (Some(dataset), MetaDataDefinition::GdalMetaDataRegular(meta_data)) => {
+                (dataset, meta_data)
+            }
+            _ => continue,
+        };
+
+        let pattern = match FilePattern::new(&meta_data) {
+            Some(pattern) if paths.iter().any(|path| pattern.directory.starts_with(path)) => {
+                pattern
+            }
+            _ => continue,
+        };
+
+        let slices = crate::util::spawn_blocking(move || pattern.scan(&meta_data))
+            .await
+            .context(error::TokioJoin)??;
+
+        let slices = match slices {
+            Some(slices) => slices,
+            None => continue,
+        };
+
+        let previous = WATCHED_TIME_SLICES
+            .write()
+            .await
+            .insert(dataset.clone(), slices);
+
+        // the first scan only registers the existing files
+        if matches!(previous, Some(previous) if previous != slices) {
+            debug!(
+                "Time slices of dataset {:?} changed to {:?}",
+                dataset, slices
+            );
+
+            ctx.dataset_events()
+                .publish(DatasetEvent::DataChanged {
+                    dataset: dataset.clone(),
+                })
+                .await;
+
+            changed.push(dataset);
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Matches the file names of a dataset's time slices and extracts their start times
+struct FilePattern {
+    directory: PathBuf,
+    file_name: Regex,
+    /// the chrono format of the start time
+    format: String,
+}
+
+impl FilePattern {
+    /// Creates the pattern if the file name of the dataset has a placeholder for the start
+    /// time and its directory has no placeholders
+    fn new(meta_data: &GdalMetaDataRegular) -> Option<Self> {
+        let directory = meta_data.params.file_path.parent()?.to_path_buf();
+        let file_name = meta_data.params.file_path.file_name()?.to_str()?;
+
+        if meta_data
+            .time_placeholders
+            .keys()
+            .any(|placeholder| directory.to_string_lossy().contains(placeholder.as_str()))
+        {
+            return None;
+        }
+
+        let (start_placeholder, start) = meta_data
+            .time_placeholders
+            .iter()
+            .find(|(_, placeholder)| placeholder.reference == TimeReference::Start)?;
+
+        let mut regex = regex::escape(file_name);
+        for placeholder in meta_data.time_placeholders.keys() {
+            let group = if placeholder == start_placeholder {
+                "(?P<start>.+?)"
+            } else {
+                ".+?"
+            };
+            regex = regex.replace(&regex::escape(placeholder), group);
+        }
+
+        Some(Self {
+            directory,
+            file_name: Regex::new(&format!("^{}$", regex)).ok()?,
+            format: start.format.clone(),
+        })
+    }
+
+    /// Returns the time slices of the matching files or `None` if there are none
+    fn scan(&self, meta_data: &GdalMetaDataRegular) -> Result<Option<TimeSlices>> {
+        let mut first: Option<TimeInstance> = None;
+        let mut last: Option<TimeInstance> = None;
+        let mut files = 0;
+
+        for entry in std::fs::read_dir(&self.directory)? {
+            let file_name = entry?.file_name();
+
+            let start = match file_name.to_str().and_then(|name| self.start_time(name)) {
+                Some(start) => start,
+                None => continue,
+            };
+
+            // ignore files that do not fit the time steps of the dataset
+            if meta_data.step.snap_relative(meta_data.start, start)? != start {
+                continue;
+            }
+
+            first = Some(first.map_or(start, |first| first.min(start)));
+            last = Some(last.map_or(start, |last| last.max(start)));
+            files += 1;
+        }
+
+        match (first, last) {
+            (Some(first), Some(last)) => Ok(Some(TimeSlices {
+                time: TimeInterval::new(first, (last + meta_data.step)?)?,
+                files,
+            })),
+            _ => Ok(None),
+        }
+    }
+
+    fn start_time(&self, file_name: &str) -> Option<TimeInstance> {
+        let start = self.file_name.captures(file_name)?.name("start")?.as_str();
+
+        NaiveDateTime::parse_from_str(start, &self.format)
+            .or_else(|_| {
+                NaiveDate::parse_from_str(start, &self.format).map(|date| date.and_hms(0, 0, 0))
+            })
+            .ok()
+            .map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::{InMemoryContext, SimpleSession};
+    use crate::datasets::storage::{AddDataset, DatasetStore};
+    use crate::util::user_input::UserInput;
+    use geoengine_datatypes::util::test::TestDefault;
+    use geoengine_operators::source::GdalSourceTimePlaceholder;
+    use geoengine_operators::util::gdal::create_ndvi_meta_data;
+
+    #[tokio::test]
+    async fn it_registers_new_time_slices() {
+        let directory = tempfile::tempdir().unwrap();
+        let create_file = |date: &str| {
+            std::fs::write(directory.path().join(format!("ndvi_{}.tif", date)), b"").unwrap();
+        };
+
+        create_file("2014-01-01");
+        create_file("2014-02-01");
+        create_file("2014-02-15");
+
+        let mut meta_data = create_ndvi_meta_data();
+        meta_data.params.file_path = directory.path().join("ndvi_%TIME%.tif");
+        meta_data.time_placeholders = HashMap::from([(
+            "%TIME%".to_string(),
+            GdalSourceTimePlaceholder {
+                format: "%Y-%m-%d".to_string(),
+                reference: TimeReference::Start,
+            },
+        )]);
+
+        assert_eq!(
+            FilePattern::new(&meta_data).unwrap().directory,
+            directory.path()
+        );
+
+        let ctx = InMemoryContext::test_default();
+        let dataset = ctx
+            .dataset_db_ref_mut()
+            .await
+            .add_dataset(
+                &SimpleSession::default(),
+                AddDataset {
+                    id: None,
+                    name: "NDVI".to_string(),
+                    description: "Daily NDVI files".to_string(),
+                    source_operator: "GdalSource".to_string(),
+                    symbology: None,
+                    provenance: None,
+                }
+                .validated()
+                .unwrap(),
+                Box::new(MetaDataDefinition::GdalMetaDataRegular(meta_data)),
+            )
+            .await
+            .unwrap();
+
+        let paths = [directory.path().to_path_buf()];
+        let mut events = ctx.dataset_events().subscribe();
+
+        assert!(scan_directories(&ctx, &paths).await.unwrap().is_empty());
+        assert_eq!(
+            watched_time_extent(&dataset).await,
+            Some(TimeInterval::new(1_388_534_400_000, 1_393_632_000_000).unwrap())
+        );
+
+        create_file("2014-03-01");
+
+        assert_eq!(
+            scan_directories(&ctx, &paths).await.unwrap(),
+            vec![dataset.clone()]
+        );
+        assert_eq!(
+            watched_time_extent(&dataset).await,
+            Some(TimeInterval::new(1_388_534_400_000, 1_396_310_400_000).unwrap())
+        );
+        assert_eq!(
+            events.recv().await.unwrap(),
+            DatasetEvent::DataChanged {
+                dataset: dataset.clone()
+            }
+        );
+
+        assert!(scan_directories(&ctx, &[PathBuf::from("/other")])
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,27 @@
+//! Distributed execution of raster queries.
+//!
+//! A dispatching instance splits a raster query into partitions and sends each partition,
+//! together with the operator graph, to a worker instance.
+//! The workers answer with the resulting tiles encoded as an Arrow IPC stream.
+//! Finally, the dispatcher merges the tiles of all partitions into a single tile stream.
+
+mod partitioning;
+mod processor;
+
+use geoengine_datatypes::primitives::RasterQueryRectangle;
+use geoengine_operators::engine::RasterOperator;
+use serde::{Deserialize, Serialize};
+
+pub use partitioning::{merge_partition_results, QueryPartitioning};
+pub use processor::{remote_raster_query_processor, RemoteRasterQueryProcessor};
+
+/// Content type of tiles that are sent from a worker to the dispatcher
+pub const ARROW_STREAM_CONTENT_TYPE: &str = "application/vnd.apache.arrow.stream";
+
+/// A partition of a raster query that is executed on a worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkerRasterQuery {
+    pub operator: Box<dyn RasterOperator>,
+    pub query: RasterQueryRectangle,
+}
@@ -0,0 +1,207 @@
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, Coordinate2D, RasterQueryRectangle, SpatialPartition2D, TimeInstance,
+    TimeInterval,
+};
+use geoengine_datatypes::raster::{Pixel, RasterTile2D};
+use serde::{Deserialize, Serialize};
+
+/// Specifies how a raster query is split into partitions for the workers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QueryPartitioning {
+    /// Split the query rectangle into horizontal bands
+    Spatial,
+    /// Split the query time interval into consecutive intervals
+    Temporal,
+}
+
+impl QueryPartitioning {
+    /// Splits the query into at most `parts` partitions that cover the whole query.
+    ///
+    /// Tiles that intersect more than one partition are computed for each of them.
+    /// Use [`merge_partition_results`] to remove the duplicates.
+    pub fn split(self, query: RasterQueryRectangle, parts: usize) -> Vec<RasterQueryRectangle> {
+        if parts <= 1 {
+            return vec![query];
+        }
+
+        match self {
+            QueryPartitioning::Spatial => split_spatially(query, parts),
+            QueryPartitioning::Temporal => split_temporally(query, parts),
+        }
+    }
+}
+
+fn split_spatially(query: RasterQueryRectangle, parts: usize) -> Vec<RasterQueryRectangle> {
+    let upper_left = query.spatial_bounds.upper_left();
+    let lower_right = query.spatial_bounds.lower_right();
+    let band_height = query.spatial_bounds.size_y() / parts as f64;
+
+    (0..parts)
+        .map(|i| {
+            let upper_y = upper_left.y - i as f64 * band_height;
+            let lower_y = if i + 1 == parts {
+                lower_right.y
+            } else {
+                upper_left.y - (i + 1) as f64 * band_height
+            };
+
+            RasterQueryRectangle {
+                spatial_bounds: SpatialPartition2D::new_unchecked(
+                    Coordinate2D::new(upper_left.x, upper_y),
+                    Coordinate2D::new(lower_right.x, lower_y),
+                ),
+                ..query
+            }
+        })
+        .collect()
+}
+
+fn split_temporally(query: RasterQueryRectangle, parts: usize) -> Vec<RasterQueryRectangle> {
+    let time = query.time_interval;
+
+    // there is no meaningful way to split unbounded intervals
+    if time.is_unbounded() || time.is_instant() {
+        return vec![query];
+    }
+
+    let start = time.start().inner();
+    let duration = time.duration_ms() as i64;
+    let parts = parts.min(duration as usize) as i64;
+
+    (0..parts)
+        .map(|i| {
+            let part_start = start + duration * i / parts;
+            let part_end = start + duration * (i + 1) / parts;
+
+            RasterQueryRectangle {
+                time_interval: TimeInterval::new_unchecked(
+                    TimeInstance::from_millis_unchecked(part_start),
+                    TimeInstance::from_millis_unchecked(part_end),
+                ),
+                ..query
+            }
+        })
+        .collect()
+}
+
+/// Merges the tiles of all partitions of a query into a single result, ordered by time and
+/// tile position.
+///
+/// Tiles that were computed for more than one partition are only kept once.
+pub fn merge_partition_results<T: Pixel>(
+    results: Vec<Vec<RasterTile2D<T>>>,
+) -> Vec<RasterTile2D<T>> {
+    let mut tiles: Vec<RasterTile2D<T>> = results.into_iter().flatten().collect();
+
+    let order = |tile: &RasterTile2D<T>| {
+        (
+            tile.time.start(),
+            tile.time.end(),
+            *tile.tile_position.inner(),
+        )
+    };
+
+    // the sort is stable, so the tile of the first partition is kept
+    tiles.sort_by_key(order);
+    tiles.dedup_by(|a, b| order(a) == order(b));
+
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::primitives::SpatialResolution;
+    use geoengine_datatypes::raster::{GeoTransform, Grid2D};
+
+    fn query() -> RasterQueryRectangle {
+        RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new((0., 30.).into(), (10., 0.).into()).unwrap(),
+            time_interval: TimeInterval::new_unchecked(0, 10),
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
+        }
+    }
+
+    #[test]
+    fn it_splits_spatially() {
+        let partitions = QueryPartitioning::Spatial.split(query(), 3);
+
+        assert_eq!(
+            partitions
+                .iter()
+                .map(|q| q.spatial_bounds)
+                .collect::<Vec<_>>(),
+            vec![
+                SpatialPartition2D::new((0., 30.).into(), (10., 20.).into()).unwrap(),
+                SpatialPartition2D::new((0., 20.).into(), (10., 10.).into()).unwrap(),
+                SpatialPartition2D::new((0., 10.).into(), (10., 0.).into()).unwrap(),
+            ]
+        );
+        assert!(partitions
+            .iter()
+            .all(|q| q.time_interval == query().time_interval));
+    }
+
+    #[test]
+    fn it_splits_temporally() {
+        let partitions = QueryPartitioning::Temporal.split(query(), 3);
+
+        assert_eq!(
+            partitions
+                .iter()
+                .map(|q| q.time_interval)
+                .collect::<Vec<_>>(),
+            vec![
+                TimeInterval::new_unchecked(0, 3),
+                TimeInterval::new_unchecked(3, 6),
+                TimeInterval::new_unchecked(6, 10),
+            ]
+        );
+
+        let unbounded = RasterQueryRectangle {
+            time_interval: TimeInterval::default(),
+            ..query()
+        };
+        assert_eq!(QueryPartitioning::Temporal.split(unbounded, 3).len(), 1);
+    }
+
+    #[test]
+    fn it_merges_partition_results() {
+        let tile = |time: TimeInterval, y: isize, x: isize, value: u8| {
+            RasterTile2D::new(
+                time,
+                [y, x].into(),
+                GeoTransform::new((0., 0.).into(), 1., -1.),
+                Grid2D::new([1, 1].into(), vec![value], None)
+                    .unwrap()
+                    .into(),
+            )
+        };
+
+        let t1 = TimeInterval::new_unchecked(0, 5);
+        let t2 = TimeInterval::new_unchecked(5, 10);
+
+        let merged = merge_partition_results(vec![
+            vec![tile(t1, 0, 0, 1), tile(t1, 0, 1, 1), tile(t2, 0, 0, 1)],
+            vec![tile(t1, 0, 1, 2), tile(t1, 1, 0, 2), tile(t2, 1, 0, 2)],
+        ]);
+
+        assert_eq!(
+            merged
+                .iter()
+                .map(|t| (t.time, *t.tile_position.inner()))
+                .collect::<Vec<_>>(),
+            vec![
+                (t1, [0, 0]),
+                (t1, [0, 1]),
+                (t1, [1, 0]),
+                (t2, [0, 0]),
+                (t2, [1, 0]),
+            ]
+        );
+        // duplicates are taken from the first partition
+        assert_eq!(merged[1], tile(t1, 0, 1, 1));
+    }
+}
@@ -0,0 +1,271 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::primitives::{RasterQueryRectangle, SpatialPartition2D};
+use geoengine_datatypes::raster::{
+    raster_tiles_from_ipc_stream, ArrowPixel, RasterDataType, RasterTile2D,
+};
+use geoengine_operators::engine::{
+    QueryContext, QueryProcessor, RasterOperator, RasterQueryProcessor, TypedRasterQueryProcessor,
+};
+use geoengine_operators::error::Error;
+use geoengine_operators::util::Result;
+use reqwest::{Client, Url};
+
+use super::{merge_partition_results, QueryPartitioning, WorkerRasterQuery};
+
+/// A query processor that splits its queries into partitions and executes them on worker instances
+pub struct RemoteRasterQueryProcessor<T> {
+    client: Client,
+    workers: Vec<Url>,
+    partitioning: QueryPartitioning,
+    operator: Box<dyn RasterOperator>,
+    session_token: String,
+    pixel_type: PhantomData<T>,
+}
+
+impl<T: ArrowPixel> RemoteRasterQueryProcessor<T> {
+    /// Creates a processor that executes `operator` on the `workers`.
+    /// The workers must accept the `session_token` for authentication.
+    pub fn new(
+        workers: Vec<Url>,
+        partitioning: QueryPartitioning,
+        operator: Box<dyn RasterOperator>,
+        session_token: String,
+    ) -> Self {
+        Self {
+            client: Client::new(),
+            workers,
+            partitioning,
+            operator,
+            session_token,
+            pixel_type: PhantomData,
+        }
+    }
+
+    async fn query_worker(
+        &self,
+        worker: &Url,
+        query: RasterQueryRectangle,
+    ) -> Result<Vec<RasterTile2D<T>>> {
+        let worker_error = |details: String| Error::DistributedQuery {
+            worker: worker.to_string(),
+            details,
+        };
+
+        let url = worker
+            .join("worker/raster")
+            .map_err(|e| worker_error(e.to_string()))?;
+
+        let response = self
+            .client
+            .post(url)
+            .bearer_auth(&self.session_token)
+            .json(&WorkerRasterQuery {
+                operator: self.operator.clone(),
+                query,
+            })
+            .send()
+            .await
+            .map_err(|e| worker_error(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(worker_error(format!("{}: {}", status, body)));
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| worker_error(e.to_string()))?;
+
+        raster_tiles_from_ipc_stream(&bytes).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl<T: ArrowPixel> QueryProcessor for RemoteRasterQueryProcessor<T> {
+    type Output = RasterTile2D<T>;
+    type SpatialBounds = SpatialPartition2D;
+
+    async fn query<'a>(
+        &'a self,
+        query: RasterQueryRectangle,
+        _ctx: &'a dyn QueryContext,
+    ) -> Result<BoxStream<'a, Result<Self::Output>>> {
+        let partitions = self.partitioning.split(query, self.workers.len());
+
+        // the results must be merged to remove tiles that were computed for multiple partitions
+        let results: Vec<Vec<RasterTile2D<T>>> =
+            stream::iter(partitions.into_iter().zip(self.workers.iter()))
+                .map(|(partition, worker)| self.query_worker(worker, partition))
+                .buffered(self.workers.len())
+                .try_collect()
+                .await?;
+
+        let tiles = merge_partition_results(results);
+
+        Ok(stream::iter(tiles.into_iter().map(Ok)).boxed())
+    }
+}
+
+/// Creates a processor for `operator` that executes its queries on the `workers`
+pub fn remote_raster_query_processor(
+    data_type: RasterDataType,
+    workers: Vec<Url>,
+    partitioning: QueryPartitioning,
+    operator: Box<dyn RasterOperator>,
+    session_token: String,
+) -> TypedRasterQueryProcessor {
+    fn boxed<T: ArrowPixel>(
+        processor: RemoteRasterQueryProcessor<T>,
+    ) -> Box<dyn RasterQueryProcessor<RasterType = T>> {
+        processor.boxed()
+    }
+
+    macro_rules! remote {
+        ($variant:ident) => {
+            TypedRasterQueryProcessor::$variant(boxed(RemoteRasterQueryProcessor::new(
+                workers,
+                partitioning,
+                operator,
+                session_token,
+            )))
+        };
+    }
+
+    match data_type {
+        RasterDataType::U8 => remote!(U8),
+        RasterDataType::U16 => remote!(U16),
+        RasterDataType::U32 => remote!(U32),
+        RasterDataType::U64 => remote!(U64),
+        RasterDataType::I8 => remote!(I8),
+        RasterDataType::I16 => remote!(I16),
+        RasterDataType::I32 => remote!(I32),
+        RasterDataType::I64 => remote!(I64),
+        RasterDataType::F32 => remote!(F32),
+        RasterDataType::F64 => remote!(F64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distributed::ARROW_STREAM_CONTENT_TYPE;
+    use geoengine_datatypes::primitives::{Measurement, SpatialResolution, TimeInterval};
+    use geoengine_datatypes::raster::{raster_tiles_to_ipc_stream, GeoTransform, Grid2D};
+    use geoengine_datatypes::spatial_reference::SpatialReference;
+    use geoengine_datatypes::util::test::TestDefault;
+    use geoengine_operators::engine::{MockQueryContext, RasterResultDescriptor};
+    use geoengine_operators::mock::{MockRasterSource, MockRasterSourceParams};
+    use httptest::{
+        all_of,
+        matchers::{contains, request},
+        responders::status_code,
+        Expectation, Server,
+    };
+
+    fn tile(y: isize, x: isize) -> RasterTile2D<u8> {
+        RasterTile2D::new(
+            TimeInterval::new_unchecked(0, 10),
+            [y, x].into(),
+            GeoTransform::new((0., 0.).into(), 1., -1.),
+            Grid2D::new([2, 2].into(), vec![1, 2, 3, 4], None)
+                .unwrap()
+                .into(),
+        )
+    }
+
+    fn mock_operator() -> Box<dyn RasterOperator> {
+        MockRasterSource {
+            params: MockRasterSourceParams::<u8> {
+                data: vec![],
+                result_descriptor: RasterResultDescriptor {
+                    data_type: RasterDataType::U8,
+                    spatial_reference: SpatialReference::epsg_4326().into(),
+                    measurement: Measurement::Unitless,
+                    no_data_value: None,
+                    dimensions: vec![],
+                },
+            },
+        }
+        .boxed()
+    }
+
+    fn query() -> RasterQueryRectangle {
+        RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new((0., 0.).into(), (2., -6.).into()).unwrap(),
+            time_interval: TimeInterval::new_unchecked(0, 10),
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
+        }
+    }
+
+    fn worker(tiles: &[RasterTile2D<u8>]) -> Server {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/worker/raster"),
+                request::headers(contains(("authorization", "Bearer token"))),
+            ])
+            .respond_with(
+                status_code(200)
+                    .append_header("Content-Type", ARROW_STREAM_CONTENT_TYPE)
+                    .body(raster_tiles_to_ipc_stream(tiles).unwrap()),
+            ),
+        );
+        server
+    }
+
+    #[tokio::test]
+    async fn it_merges_the_results_of_all_workers() {
+        // the middle row intersects both partitions
+        let worker_a = worker(&[tile(0, 0), tile(1, 0)]);
+        let worker_b = worker(&[tile(1, 0), tile(2, 0)]);
+
+        let processor = RemoteRasterQueryProcessor::<u8>::new(
+            vec![
+                Url::parse(&worker_a.url_str("/")).unwrap(),
+                Url::parse(&worker_b.url_str("/")).unwrap(),
+            ],
+            QueryPartitioning::Spatial,
+            mock_operator(),
+            "token".to_string(),
+        );
+
+        let tiles: Vec<RasterTile2D<u8>> = processor
+            .raster_query(query(), &MockQueryContext::test_default())
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(tiles, vec![tile(0, 0), tile(1, 0), tile(2, 0)]);
+    }
+
+    #[tokio::test]
+    async fn it_reports_worker_errors() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/worker/raster"))
+                .respond_with(status_code(500).body("boom")),
+        );
+
+        let processor = RemoteRasterQueryProcessor::<u8>::new(
+            vec![Url::parse(&server.url_str("/")).unwrap()],
+            QueryPartitioning::Spatial,
+            mock_operator(),
+            "token".to_string(),
+        );
+
+        let result = processor
+            .raster_query(query(), &MockQueryContext::test_default())
+            .await;
+
+        assert!(matches!(result, Err(Error::DistributedQuery { .. })));
+    }
+}
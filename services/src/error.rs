@@ -1,3 +1,4 @@
+use crate::workflows::template::WorkflowParameterType;
 use crate::workflows::workflow::WorkflowId;
 use crate::{datasets::external::netcdfcf::NetCdfCf4DProviderError, handlers::ErrorResponse};
 use actix_web::http::StatusCode;
@@ -111,6 +112,35 @@ pub enum Error {
     InvalidWfsTypeNames,
 
     NoWorkflowForGivenId,
+    NoWorkflowTemplateForGivenId,
+
+    #[snafu(display("There is no workflow macro {} with version {:?}", name, version))]
+    UnknownWorkflowMacro {
+        name: String,
+        version: Option<u32>,
+    },
+
+    #[snafu(display("The workflow parameter {} is declared more than once", parameter))]
+    DuplicateWorkflowParameter {
+        parameter: String,
+    },
+    #[snafu(display("The workflow parameter {} is not declared", parameter))]
+    UnknownWorkflowParameter {
+        parameter: String,
+    },
+    #[snafu(display("The workflow parameter {} has no value and no default", parameter))]
+    MissingWorkflowParameter {
+        parameter: String,
+    },
+    #[snafu(display(
+        "The value of workflow parameter {} is not of type {:?}",
+        parameter,
+        expected
+    ))]
+    InvalidWorkflowParameterValue {
+        parameter: String,
+        expected: WorkflowParameterType,
+    },
 
     #[cfg(feature = "postgres")]
     TokioPostgres {
@@ -139,6 +169,16 @@ pub enum Error {
 
     MissingSettingsDirectory,
 
+    #[snafu(display("Invalid command line argument: {}", argument))]
+    InvalidCommandLineArgument {
+        argument: String,
+    },
+
+    #[snafu(display("Invalid configuration: {}", errors.join("; ")))]
+    InvalidConfiguration {
+        errors: Vec<String>,
+    },
+
     DatasetIdTypeMissMatch,
     UnknownDatasetId,
     UnknownProviderId,
@@ -163,6 +203,80 @@ pub enum Error {
         permission: String,
     },
 
+    #[snafu(display(
+        "Managing the access policies of dataset {:?} requires its ownership",
+        dataset
+    ))]
+    DatasetAccessPolicyPermissionDenied {
+        dataset: DatasetId,
+    },
+
+    #[snafu(display("Updating dataset {:?} requires a write permission", dataset))]
+    DatasetUpdatePermissionDenied {
+        dataset: DatasetId,
+    },
+
+    #[snafu(display("Deleting dataset {:?} requires its ownership", dataset))]
+    DatasetDeletionPermissionDenied {
+        dataset: DatasetId,
+    },
+
+    #[snafu(display("Invalid access policy: {}", reason))]
+    InvalidDatasetAccessPolicy {
+        reason: String,
+    },
+
+    UnknownDatasetAccessPolicy,
+
+    #[snafu(display("Invalid IP range: {}", range))]
+    InvalidIpRange {
+        range: String,
+    },
+
+    #[snafu(display(
+        "Dataset {:?} cannot be queried from the address of this client",
+        dataset
+    ))]
+    DatasetAccessDeniedForIp {
+        dataset: DatasetId,
+    },
+
+    #[snafu(display(
+        "Dataset {:?} can only be queried by members of certain institutions",
+        dataset
+    ))]
+    DatasetAccessDeniedForInstitution {
+        dataset: DatasetId,
+    },
+
+    #[snafu(display("Managing the license of dataset {:?} requires its ownership", dataset))]
+    DatasetLicensePermissionDenied {
+        dataset: DatasetId,
+    },
+
+    #[snafu(display("Invalid dataset license: {}", reason))]
+    InvalidDatasetLicense {
+        reason: String,
+    },
+
+    UnknownDatasetLicense,
+
+    #[snafu(display(
+        "The terms of use of dataset {:?} must be accepted before querying it",
+        dataset
+    ))]
+    DatasetTermsNotAccepted {
+        dataset: DatasetId,
+    },
+
+    #[snafu(display(
+        "The terms of use have changed to version {}, please review them again",
+        current
+    ))]
+    OutdatedDatasetTerms {
+        current: u32,
+    },
+
     #[snafu(display("Parameter {} must have length between {} and {}", parameter, min, max))]
     InvalidStringLength {
         parameter: String,
@@ -182,6 +296,93 @@ pub enum Error {
         source: actix_multipart::MultipartError,
     },
     InvalidUploadFileName,
+    #[snafu(display("The upload has no file named {}", file_name))]
+    UnknownUploadFile {
+        file_name: String,
+    },
+    #[snafu(display("The upload exceeds the limit of {} bytes", limit))]
+    UploadTooLarge {
+        limit: usize,
+    },
+    #[snafu(display("The signature of the download URL is invalid"))]
+    InvalidDownloadSignature,
+    #[snafu(display("The download URL has expired"))]
+    DownloadUrlExpired,
+    #[snafu(display("Object storage error: {}", details))]
+    ObjectStorage {
+        details: String,
+    },
+    #[snafu(display("The requested range of the object is not satisfiable"))]
+    RangeNotSatisfiable,
+
+    #[snafu(display("Too many requests, retry after {} seconds", retry_after_seconds))]
+    TooManyRequests {
+        retry_after_seconds: u64,
+    },
+
+    #[snafu(display("API request failed with status {}: {}: {}", status, error, message))]
+    ApiResponse {
+        status: u16,
+        error: String,
+        message: String,
+    },
+
+    #[snafu(display("Backup and restore are disabled on this instance."))]
+    BackupDisabled,
+    #[snafu(display("The admin token is invalid."))]
+    InvalidAdminToken,
+    #[snafu(display(
+        "Backup version {} is not supported, expected version {}",
+        found,
+        expected
+    ))]
+    UnsupportedBackupVersion {
+        found: u32,
+        expected: u32,
+    },
+
+    #[snafu(display("No dataset validation has been run yet."))]
+    NoDatasetValidationReport,
+
+    #[snafu(display("There is no preview of the dataset."))]
+    DatasetPreviewNotAvailable,
+
+    #[snafu(display("Could not render the preview of the dataset: {}", reason))]
+    DatasetPreview {
+        reason: String,
+    },
+
+    #[snafu(display("Invalid organization: {}", reason))]
+    InvalidOrganization {
+        reason: String,
+    },
+    #[snafu(display("Creating organizations is disabled on this instance."))]
+    OrganizationCreationDisabled,
+    #[snafu(display("The user is not a member of an organization."))]
+    NoOrganization,
+    #[snafu(display("The user is already a member of an organization."))]
+    AlreadyOrganizationMember,
+    #[snafu(display("The user does not exist."))]
+    UnknownUser,
+    #[snafu(display("Only admins of the organization can manage its members."))]
+    OrganizationPermissionDenied,
+    #[snafu(display("The organization may not have more than {} {}.", limit, resource))]
+    QuotaExceeded {
+        resource: &'static str,
+        limit: usize,
+    },
+
+    #[snafu(display("Invalid group: {}", reason))]
+    InvalidGroup {
+        reason: String,
+    },
+    #[snafu(display("The group does not exist or the user is not a member of it."))]
+    UnknownGroup,
+    #[snafu(display("The user is already a member of the group."))]
+    AlreadyGroupMember,
+    #[snafu(display("Only admins of the group can manage its members."))]
+    GroupPermissionDenied,
+
     InvalidDatasetName,
     DatasetHasNoAutoImportableLayer,
     #[snafu(display("GdalError: {}", source))]
@@ -196,6 +397,11 @@ pub enum Error {
         srs_string: String,
     },
 
+    #[snafu(display("Invalid time {}", time))]
+    InvalidOgcTime {
+        time: String,
+    },
+
     NotYetImplemented,
 
     StacNoSuchBand {
@@ -214,6 +420,31 @@ pub enum Error {
 
     MissingSpatialReference,
 
+    #[snafu(display(
+        "Deriving a raster symbology requires a query rectangle (bbox, time and spatialResolution)"
+    ))]
+    SymbologyRequiresQueryRectangle,
+    #[snafu(display("There is no symbology for data of type {}", data_type))]
+    NoSymbologyForDataType {
+        data_type: String,
+    },
+
+    #[snafu(display("The plot {} cannot be output as {}", plot_type, format))]
+    PlotFormatNotAvailable {
+        plot_type: String,
+        format: String,
+    },
+    #[snafu(display("Rendering plots as PNG requires a configured Vega-Lite renderer"))]
+    NoVegaLiteRendererConfigured,
+    #[snafu(display("Vega-Lite renderer failed: {}", details))]
+    VegaLiteRenderer {
+        details: String,
+    },
+    #[snafu(display("Vega-Lite renderer did not finish within {} seconds", seconds))]
+    VegaLiteRendererTimeout {
+        seconds: u64,
+    },
+
     WcsVersionNotSupported,
     WcsGridOriginMustEqualBoundingboxUpperLeft,
     WcsBoundingboxCrsMustEqualGridBaseCrs,
@@ -223,6 +454,19 @@ pub enum Error {
 
     PangaeaNoTsv,
     GfbioMissingAbcdField,
+    #[snafu(display("The ERA5 variable {} is not offered", variable))]
+    Era5UnknownVariable {
+        variable: String,
+    },
+    #[snafu(display("Could not retrieve ERA5 data from the CDS: {}", reason))]
+    Era5RetrievalFailed {
+        reason: String,
+    },
+    #[snafu(display("The query spans {} days but at most {} are allowed", days, max))]
+    Era5TooManyDays {
+        days: usize,
+        max: usize,
+    },
     ExpectedExternalDatasetId,
     InvalidExternalDatasetId {
         provider: DatasetProviderId,
@@ -259,9 +503,37 @@ pub enum Error {
     #[snafu(display("Anonymous access is disabled, please log in"))]
     AnonymousAccessDisabled,
 
+    #[snafu(display("Guest access is disabled"))]
+    GuestAccessDisabled,
+    #[snafu(display("Guest sessions have read-only access, please log in"))]
+    GuestSessionReadOnly,
+    #[snafu(display("The maximum number of guest sessions is reached, please try again later"))]
+    GuestSessionLimitReached,
+    #[snafu(display("A guest session may not execute more than {} workflows", limit))]
+    GuestQuotaExceeded {
+        limit: u32,
+    },
+
     #[snafu(display("User registration is disabled"))]
     UserRegistrationDisabled,
 
+    #[cfg(feature = "pro")]
+    #[snafu(display("Sending the e-mail failed: {}", reason))]
+    Mail {
+        reason: String,
+    },
+    #[cfg(feature = "pro")]
+    #[snafu(display("E-mail notifications are disabled on this instance."))]
+    NotificationsDisabled,
+    #[cfg(feature = "pro")]
+    #[snafu(display("The password reset token is invalid or expired."))]
+    InvalidPasswordResetToken,
+    #[cfg(feature = "pro")]
+    #[snafu(display("Invalid password: {}", reason))]
+    InvalidPassword {
+        reason: String,
+    },
+
     #[snafu(display(
         "WCS request endpoint {} must match identifier {}",
         endpoint,
@@ -285,6 +557,10 @@ pub enum Error {
         endpoint: WorkflowId,
         layer: WorkflowId,
     },
+    #[snafu(display("WMS vector layers cannot be rendered with a raster symbology"))]
+    WMSRasterSymbologyForVectorLayer,
+    #[snafu(display("WMS vector layers must have geometries to be rendered"))]
+    WMSVectorLayerWithoutGeometries,
     #[snafu(display(
         "WFS request endpoint {} must match type_names {}",
         endpoint,
@@ -346,7 +622,13 @@ impl actix_web::error::ResponseError for Error {
     fn status_code(&self) -> StatusCode {
         match self {
             Error::Authorization { source: _ } => StatusCode::UNAUTHORIZED,
+            Error::InvalidDownloadSignature | Error::DownloadUrlExpired => StatusCode::FORBIDDEN,
             Error::Duplicate { reason: _ } => StatusCode::CONFLICT,
+            Error::UploadTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Error::RangeNotSatisfiable => StatusCode::RANGE_NOT_SATISFIABLE,
+            Error::TooManyRequests { .. }
+            | Error::GuestSessionLimitReached
+            | Error::GuestQuotaExceeded { .. } => StatusCode::TOO_MANY_REQUESTS,
             _ => StatusCode::BAD_REQUEST,
         }
     }
@@ -0,0 +1,172 @@
+use actix_web::{web, FromRequest, HttpRequest, Responder};
+use snafu::ensure;
+
+use crate::contexts::backup::{self, Backup, RestoreOptions};
+use crate::error::{self, Result};
+use crate::handlers::{authorize_bearer_token, Context};
+use crate::util::config::{self, get_config_element};
+use crate::util::streaming_json::{BodyLimit, StreamingJson};
+
+pub(crate) fn init_backup_routes<C>(cfg: &mut web::ServiceConfig)
+where
+    C: Context,
+    C::Session: FromRequest,
+{
+    cfg.service(web::resource("/backup").route(web::get().to(backup_handler::<C>)))
+        .service(
+            web::resource("/restore")
+                .app_data(BodyLimit::Restore)
+                .route(web::post().to(restore_handler::<C>)),
+        );
+}
+
+/// Exports all datasets, workflows and projects of the instance.
+///
+/// The request must be authorized with the admin token of the `backup` settings.
+///
+/// # Example
+///
+/// ```text
+/// GET /backup
+/// Authorization: Bearer my-admin-token
+/// ```
+/// Response:
+/// ```text
+/// {
+///   "version": 1,
+///   "created": "2022-03-01T12:00:00Z",
+///   "datasets": [...],
+///   "workflows": [...],
+///   "projects": [...]
+/// }
+/// ```
+async fn backup_handler<C: Context>(req: HttpRequest, ctx: web::Data<C>) -> Result<impl Responder> {
+    authorize_backup(&req)?;
+
+    Ok(web::Json(backup::backup(ctx.get_ref()).await?))
+}
+
+/// Restores a backup that was created with `GET /backup`.
+///
+/// Items whose ids already exist abort the restore (`onConflict=abort`), are skipped
+/// (`onConflict=skip`) or are restored with new ids (`onConflict=newId`).
+/// References to remapped items are updated accordingly.
+///
+/// # Example
+///
+/// ```text
+/// POST /restore?onConflict=skip
+/// Authorization: Bearer my-admin-token
+///
+/// {
+///   "version": 1,
+///   ...
+/// }
+/// ```
+/// Response:
+/// ```text
+/// {
+///   "restored": 12,
+///   "skipped": 1,
+///   "remappedDatasets": [],
+///   "remappedWorkflows": [],
+///   "remappedProjects": []
+/// }
+/// ```
+async fn restore_handler<C: Context>(
+    req: HttpRequest,
+    ctx: web::Data<C>,
+    options: web::Query<RestoreOptions>,
+    payload: web::Payload,
+) -> actix_web::Result<impl Responder> {
+    authorize_backup(&req)?;
+
+    let backup = StreamingJson::<Backup>::from_payload(&req, payload)
+        .await?
+        .into_inner();
+
+    Ok(web::Json(
+        backup::restore(ctx.get_ref(), backup, options.into_inner()).await?,
+    ))
+}
+
+/// Checks that backups are enabled and that the request carries the configured backup token
+pub(crate) fn authorize_backup(req: &HttpRequest) -> Result<()> {
+    let config = get_config_element::<config::Backup>()?;
+
+    ensure!(config.enabled, error::BackupDisabled);
+
+    authorize_bearer_token(req, config.admin_token.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::backup::RestoreSummary;
+    use crate::contexts::{Context, InMemoryContext};
+    use crate::handlers::ErrorResponse;
+    use crate::util::tests::{register_ndvi_workflow_helper, send_test_request};
+    use crate::workflows::registry::WorkflowRegistry;
+    use actix_web::http::header;
+    use actix_web::test;
+    use actix_web_httpauth::headers::authorization::Bearer;
+    use geoengine_datatypes::util::test::TestDefault;
+
+    #[tokio::test]
+    async fn it_backups_and_restores() {
+        let ctx = InMemoryContext::test_default();
+        let (_, workflow_id) = register_ndvi_workflow_helper(&ctx).await;
+
+        let req = test::TestRequest::get()
+            .uri("/backup")
+            .append_header((header::AUTHORIZATION, Bearer::new("admin")));
+        let res = send_test_request(req, ctx.clone()).await;
+        ErrorResponse::assert(
+            res,
+            400,
+            "BackupDisabled",
+            "Backup and restore are disabled on this instance.",
+        )
+        .await;
+
+        config::set_config("backup.enabled", true).unwrap();
+        config::set_config("backup.admin_token", "admin").unwrap();
+
+        let req = test::TestRequest::get()
+            .uri("/backup")
+            .append_header((header::AUTHORIZATION, Bearer::new("wrong")));
+        let res = send_test_request(req, ctx.clone()).await;
+        ErrorResponse::assert(res, 401, "InvalidAdminToken", "The admin token is invalid.").await;
+
+        let req = test::TestRequest::get()
+            .uri("/backup")
+            .append_header((header::AUTHORIZATION, Bearer::new("admin")));
+        let res = send_test_request(req, ctx.clone()).await;
+        assert_eq!(res.status(), 200);
+
+        let backup = test::read_body(res).await;
+
+        let restored = InMemoryContext::test_default();
+        let req = test::TestRequest::post()
+            .uri("/restore?onConflict=skip")
+            .append_header((header::AUTHORIZATION, Bearer::new("admin")))
+            .append_header((header::CONTENT_TYPE, mime::APPLICATION_JSON))
+            .set_payload(backup);
+        let res = send_test_request(req, restored.clone()).await;
+
+        config::set_config("backup.enabled", false).unwrap();
+
+        assert_eq!(res.status(), 200);
+
+        let summary: RestoreSummary = test::read_body_json(res).await;
+        assert_eq!(summary.skipped, 0);
+        assert!(summary.restored > 0);
+
+        assert!(restored
+            .workflow_registry_ref()
+            .await
+            .load(&workflow_id)
+            .await
+            .is_ok());
+    }
+}
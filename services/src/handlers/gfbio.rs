@@ -494,6 +494,7 @@ mod tests {
                         SpatialReference::epsg_4326(),
                     ),
                     columns: Default::default(),
+                    column_metadata: Default::default(),
                 }),
                 attribute_filters: None,
             }),
@@ -533,6 +534,7 @@ mod tests {
                         SpatialReference::epsg_4326(),
                     ),
                     columns: Default::default(),
+                    column_metadata: Default::default(),
                 }),
                 attribute_filters: Some(vec![AttributeFilter {
                     attribute: "a".to_string(),
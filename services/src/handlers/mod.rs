@@ -1,27 +1,33 @@
 use crate::contexts::Context;
 use crate::contexts::SessionId;
 use crate::error::{Error, Result};
+use crate::util::config::{self, get_config_element};
 use actix_web::dev::ServiceResponse;
 use actix_web::http::{header, StatusCode};
-use actix_web::{test, HttpRequest, HttpResponse};
+use actix_web::{test, HttpRequest, HttpResponse, HttpResponseBuilder};
 use actix_web_httpauth::headers::authorization::{Bearer, Scheme};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
 
+pub mod backup;
 pub mod datasets;
 #[cfg(feature = "ebv")]
 pub mod ebv;
 #[cfg(feature = "nfdi")]
 pub mod gfbio;
+pub mod info;
 pub mod plots;
 pub mod projects;
+pub mod scalars;
 pub mod session;
 pub mod spatial_references;
+pub mod tables;
 pub mod upload;
 pub mod wcs;
 pub mod wfs;
 pub mod wms;
+pub mod workers;
 pub mod workflows;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -66,6 +72,20 @@ impl fmt::Display for ErrorResponse {
     }
 }
 
+/// Response header that carries the warnings of a query that succeeded only partially, one per header line
+pub const QUERY_WARNINGS_HEADER: &str = "X-Query-Warnings";
+
+/// Response header that marks a result of a preview query that contains only a sample of the full result
+pub const QUERY_SAMPLED_HEADER: &str = "X-Query-Sampled";
+
+/// Appends the `warnings` of a query to the `response`.
+/// Non-ASCII characters are escaped since they are not allowed in header values.
+pub fn append_query_warnings(response: &mut HttpResponseBuilder, warnings: &[String]) {
+    for warning in warnings {
+        response.append_header((QUERY_WARNINGS_HEADER, warning.escape_default().to_string()));
+    }
+}
+
 pub fn get_token(req: &HttpRequest) -> Result<SessionId> {
     let header = req
         .headers()
@@ -80,3 +100,36 @@ pub fn get_token(req: &HttpRequest) -> Result<SessionId> {
         source: Box::new(err),
     })
 }
+
+/// Checks that the request carries the configured admin token
+pub(crate) fn authorize_admin(req: &HttpRequest) -> Result<()> {
+    let config = get_config_element::<config::Admin>()?;
+
+    authorize_bearer_token(req, config.token.as_deref())
+}
+
+/// Checks that the request carries the `expected` bearer token.
+/// Fails if no token is expected at all.
+pub(crate) fn authorize_bearer_token(req: &HttpRequest, expected: Option<&str>) -> Result<()> {
+    let token = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|header| Bearer::parse(header).ok());
+
+    match (token, expected) {
+        (Some(token), Some(expected)) if constant_time_eq(token.token(), expected) => Ok(()),
+        _ => Err(Error::Authorization {
+            source: Box::new(Error::InvalidAdminToken),
+        }),
+    }
+}
+
+/// Compares without short-circuiting so that the response time does not reveal how many
+/// leading characters of a guessed token are correct
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
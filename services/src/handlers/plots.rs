@@ -3,9 +3,11 @@ use crate::error::Result;
 use crate::handlers::Context;
 use crate::ogc::util::{parse_bbox, parse_time};
 use crate::util::parsing::parse_spatial_resolution;
+use crate::util::rate_limiting::QueryRateLimiting;
+use crate::util::vega::{render_vega_lite_png, set_vega_lite_size, vega_lite_data};
 use crate::workflows::registry::WorkflowRegistry;
 use crate::workflows::workflow::WorkflowId;
-use actix_web::{web, FromRequest, Responder};
+use actix_web::{web, FromRequest, HttpResponse};
 use geoengine_datatypes::operations::reproject::reproject_query;
 use geoengine_datatypes::plots::PlotOutputFormat;
 use geoengine_datatypes::primitives::{
@@ -22,7 +24,11 @@ where
     C: Context,
     C::Session: FromRequest,
 {
-    cfg.service(web::resource("/plot/{id}").route(web::get().to(get_plot_handler::<C>)));
+    cfg.service(
+        web::resource("/plot/{id}")
+            .wrap(QueryRateLimiting)
+            .route(web::get().to(get_plot_handler::<C>)),
+    );
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -35,10 +41,36 @@ pub(crate) struct GetPlot {
     pub time: TimeInterval,
     #[serde(deserialize_with = "parse_spatial_resolution")]
     pub spatial_resolution: SpatialResolution,
+    /// Overrides the output format of the plot operator
+    pub format: Option<PlotFormat>,
+    /// The width of rendered PNG images in pixels
+    pub width: Option<u32>,
+    /// The height of rendered PNG images in pixels
+    pub height: Option<u32>,
+}
+
+/// The formats a plot can be requested in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, strum::Display)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub(crate) enum PlotFormat {
+    /// The data of the plot as JSON
+    Data,
+    /// A Vega-Lite spec that contains the data
+    Vega,
+    /// An image that is rendered on the server
+    Png,
 }
 
 /// Generates a [plot](WrappedPlotOutput).
 ///
+/// By default, the plot is returned in the output format of its operator.
+/// The optional `format` parameter requests another format instead:
+/// - `data`: the data of the plot as JSON,
+/// - `vega`: a Vega-Lite spec including the data,
+/// - `png`: an image of `width` x `height` pixels that is rendered on the server.
+///   This requires a Vega-Lite renderer in the `plots` config for plots that are not images.
+///
 /// # Example
 ///
 /// 1. Create a statistics workflow.
@@ -122,7 +154,7 @@ async fn get_plot_handler<C: Context>(
     params: web::Query<GetPlot>,
     session: C::Session,
     ctx: web::Data<C>,
-) -> Result<impl Responder> {
+) -> Result<HttpResponse> {
     let workflow = ctx
         .workflow_registry_ref()
         .await
@@ -151,6 +183,7 @@ async fn get_plot_handler<C: Context>(
         spatial_bounds: params.bbox,
         time_interval: params.time,
         spatial_resolution: params.spatial_resolution,
+        spatial_filter: None,
     };
 
     let query_rect = if request_spatial_ref == workflow_spatial_ref {
@@ -168,20 +201,61 @@ async fn get_plot_handler<C: Context>(
     let output_format = PlotOutputFormat::from(&processor);
     let plot_type = processor.plot_type();
 
-    let data = match processor {
-        TypedPlotQueryProcessor::JsonPlain(processor) => processor
-            .plot_query(query_rect, &query_ctx)
-            .await
-            .context(error::Operator)?,
-        TypedPlotQueryProcessor::JsonVega(processor) => {
+    let format_not_available = |format: PlotFormat| error::Error::PlotFormatNotAvailable {
+        plot_type: plot_type.to_string(),
+        format: format.to_string(),
+    };
+
+    let (output_format, data) = match (params.format, processor) {
+        (None | Some(PlotFormat::Data), TypedPlotQueryProcessor::JsonPlain(processor)) => {
+            let data = processor
+                .plot_query(query_rect, &query_ctx)
+                .await
+                .context(error::Operator)?;
+
+            (PlotOutputFormat::JsonPlain, data)
+        }
+        (None | Some(PlotFormat::Vega), TypedPlotQueryProcessor::JsonVega(processor)) => {
+            let chart = processor
+                .plot_query(query_rect, &query_ctx)
+                .await
+                .context(error::Operator)?;
+
+            (
+                output_format,
+                serde_json::to_value(&chart).context(error::SerdeJson)?,
+            )
+        }
+        (Some(PlotFormat::Data), TypedPlotQueryProcessor::JsonVega(processor)) => {
+            let chart = processor
+                .plot_query(query_rect, &query_ctx)
+                .await
+                .context(error::Operator)?;
+
+            let spec: serde_json::Value =
+                serde_json::from_str(&chart.vega_string).context(error::SerdeJson)?;
+            let data =
+                vega_lite_data(&spec).ok_or_else(|| format_not_available(PlotFormat::Data))?;
+
+            (PlotOutputFormat::JsonPlain, data)
+        }
+        (Some(PlotFormat::Png), TypedPlotQueryProcessor::JsonVega(processor)) => {
             let chart = processor
                 .plot_query(query_rect, &query_ctx)
                 .await
                 .context(error::Operator)?;
 
-            serde_json::to_value(&chart).context(error::SerdeJson)?
+            let mut spec: serde_json::Value =
+                serde_json::from_str(&chart.vega_string).context(error::SerdeJson)?;
+            set_vega_lite_size(&mut spec, params.width, params.height);
+
+            let png_bytes = render_vega_lite_png(&spec).await?;
+
+            return Ok(HttpResponse::Ok()
+                .content_type(mime::IMAGE_PNG)
+                .body(png_bytes));
         }
-        TypedPlotQueryProcessor::ImagePng(processor) => {
+        (None, TypedPlotQueryProcessor::ImagePng(processor)) => {
             let png_bytes = processor
                 .plot_query(query_rect, &query_ctx)
                 .await
@@ -189,31 +263,46 @@ async fn get_plot_handler<C: Context>(
 
             let data_uri = format!("data:image/png;base64,{}", base64::encode(png_bytes));
 
-            serde_json::to_value(&data_uri).context(error::SerdeJson)?
+            (
+                output_format,
+                serde_json::to_value(&data_uri).context(error::SerdeJson)?,
+            )
+        }
+        (Some(PlotFormat::Png), TypedPlotQueryProcessor::ImagePng(processor)) => {
+            let png_bytes = processor
+                .plot_query(query_rect, &query_ctx)
+                .await
+                .context(error::Operator)?;
+
+            return Ok(HttpResponse::Ok()
+                .content_type(mime::IMAGE_PNG)
+                .body(png_bytes));
         }
+        (Some(format), _) => return Err(format_not_available(format)),
     };
 
     let output = WrappedPlotOutput {
         output_format,
-        plot_type,
+        plot_type: plot_type.to_string(),
         data,
     };
 
-    Ok(web::Json(output))
+    Ok(HttpResponse::Ok().json(output))
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct WrappedPlotOutput {
-    output_format: PlotOutputFormat,
-    plot_type: &'static str,
-    data: serde_json::Value,
+pub struct WrappedPlotOutput {
+    pub output_format: PlotOutputFormat,
+    pub plot_type: String,
+    pub data: serde_json::Value,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::contexts::{InMemoryContext, Session, SimpleContext};
+    use crate::handlers::ErrorResponse;
     use crate::util::tests::{check_allowed_http_methods, read_body_string, send_test_request};
     use crate::workflows::workflow::Workflow;
     use actix_web;
@@ -254,6 +343,7 @@ mod tests {
                     spatial_reference: SpatialReference::epsg_4326().into(),
                     measurement: Measurement::Unitless,
                     no_data_value: no_data_value.map(AsPrimitive::as_),
+                    dimensions: vec![],
                 },
             },
         }
@@ -267,7 +357,7 @@ mod tests {
 
         let workflow = Workflow {
             operator: Statistics {
-                params: StatisticsParams {},
+                params: StatisticsParams::default(),
                 sources: vec![example_raster_source()].into(),
             }
             .boxed()
@@ -378,6 +468,108 @@ mod tests {
         );
     }
 
+    async fn plot_format_test_helper(
+        operator: Box<dyn PlotOperator>,
+        format: &str,
+    ) -> ServiceResponse {
+        let ctx = InMemoryContext::test_default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let id = ctx
+            .workflow_registry()
+            .write()
+            .await
+            .register(Workflow {
+                operator: operator.into(),
+            })
+            .await
+            .unwrap();
+
+        let params = &[
+            ("bbox", "-180,-90,180,90"),
+            ("crs", "EPSG:4326"),
+            ("time", "2020-01-01T00:00:00.0Z"),
+            ("spatialResolution", "0.1,0.1"),
+            ("format", format),
+        ];
+        let req = actix_web::test::TestRequest::get()
+            .uri(&format!(
+                "/plot/{}/?{}",
+                id,
+                &serde_urlencoded::to_string(params).unwrap()
+            ))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        send_test_request(req, ctx).await
+    }
+
+    fn example_histogram() -> Box<dyn PlotOperator> {
+        Histogram {
+            params: HistogramParams {
+                column_name: None,
+                bounds: HistogramBounds::Values {
+                    min: 0.0,
+                    max: 10.0,
+                },
+                buckets: Some(2),
+                interactive: false,
+            },
+            sources: example_raster_source().into(),
+        }
+        .boxed()
+    }
+
+    #[tokio::test]
+    async fn vega_as_data() {
+        let res = plot_format_test_helper(example_histogram(), "data").await;
+
+        assert_eq!(res.status(), 200);
+
+        assert_eq!(
+            read_body_string(res).await,
+            json!({
+                "outputFormat": "JsonPlain",
+                "plotType": "Histogram",
+                "data": [
+                    { "binStart": 0.0, "binEnd": 5.0, "Frequency": 4 },
+                    { "binStart": 5.0, "binEnd": 10.0, "Frequency": 2 }
+                ]
+            })
+            .to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn vega_as_png_requires_renderer() {
+        let res = plot_format_test_helper(example_histogram(), "png").await;
+
+        ErrorResponse::assert(
+            res,
+            400,
+            "NoVegaLiteRendererConfigured",
+            "Rendering plots as PNG requires a configured Vega-Lite renderer",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn json_as_vega_is_not_available() {
+        let operator = Statistics {
+            params: StatisticsParams::default(),
+            sources: vec![example_raster_source()].into(),
+        }
+        .boxed();
+
+        let res = plot_format_test_helper(operator, "vega").await;
+
+        ErrorResponse::assert(
+            res,
+            400,
+            "PlotFormatNotAvailable",
+            "The plot Statistics cannot be output as vega",
+        )
+        .await;
+    }
+
     #[test]
     fn deserialize_get_plot() {
         let params = &[
@@ -399,6 +591,9 @@ mod tests {
                 )
                 .unwrap(),
                 spatial_resolution: SpatialResolution::zero_point_one(),
+                format: None,
+                width: None,
+                height: None,
             }
         );
     }
@@ -411,7 +606,7 @@ mod tests {
 
             let workflow = Workflow {
                 operator: Statistics {
-                    params: StatisticsParams {},
+                    params: StatisticsParams::default(),
                     sources: vec![example_raster_source()].into(),
                 }
                 .boxed()
@@ -1,5 +1,5 @@
 use crate::{
-    contexts::{Context, SimpleContext},
+    contexts::{Context, Session, SimpleContext, SimpleSession},
     error::{self, Result},
     projects::{ProjectId, STRectangle},
     util::config,
@@ -11,6 +11,7 @@ where
     C: SimpleContext,
 {
     cfg.service(web::resource("/anonymous").route(web::post().to(anonymous_handler::<C>)))
+        .service(web::resource("/guest").route(web::post().to(guest_handler::<C>)))
         .service(
             web::scope("/session")
                 .service(web::resource("").route(web::get().to(session_handler::<C>)))
@@ -55,6 +56,38 @@ async fn anonymous_handler<C: SimpleContext>(ctx: web::Data<C>) -> Result<impl R
     Ok(web::Json(session))
 }
 
+/// Creates a read-only guest session, which may only execute a limited number of workflows.
+///
+/// # Example
+///
+/// ```text
+/// POST /guest
+/// ```
+/// Response:
+/// ```text
+/// {
+///   "id": "d4b2c1e5-66b8-4f0e-a3f4-3e7f6a1f0c2d",
+///   "project": null,
+///   "view": null,
+///   "validUntil": "2021-04-18T17:54:55.730196200Z",
+///   "guest": true
+/// }
+/// ```
+///
+/// # Errors
+///
+/// This call fails if guest access is disabled or if the maximum number of guest sessions is reached.
+async fn guest_handler<C: SimpleContext>(ctx: web::Data<C>) -> Result<impl Responder> {
+    if !config::get_config_element::<crate::util::config::Session>()?.guest_access {
+        return Err(error::Error::Authorization {
+            source: Box::new(error::Error::GuestAccessDisabled),
+        });
+    }
+
+    let session = ctx.guest_sessions().add(SimpleSession::guest()?)?;
+    Ok(web::Json(session))
+}
+
 /// Retrieves details about the [Session].
 ///
 /// # Example
@@ -101,12 +134,14 @@ pub(crate) async fn session_handler<C: Context>(session: C::Session) -> impl Res
 /// This call fails if the session is invalid.
 async fn session_project_handler<C: SimpleContext>(
     project: web::Path<ProjectId>,
-    _session: C::Session,
+    session: C::Session,
     ctx: web::Data<C>,
-) -> impl Responder {
+) -> Result<impl Responder> {
+    session.ensure_not_guest()?;
+
     ctx.default_session_ref_mut().await.project = Some(project.into_inner());
 
-    HttpResponse::Ok()
+    Ok(HttpResponse::Ok())
 }
 
 // TODO: /view instead of /session/view
@@ -135,13 +170,15 @@ async fn session_project_handler<C: SimpleContext>(
 ///
 /// This call fails if the session is invalid.
 async fn session_view_handler<C: SimpleContext>(
-    _session: C::Session,
+    session: C::Session,
     ctx: web::Data<C>,
     view: web::Json<STRectangle>,
-) -> impl Responder {
+) -> Result<impl Responder> {
+    session.ensure_not_guest()?;
+
     ctx.default_session_ref_mut().await.view = Some(view.into_inner());
 
-    HttpResponse::Ok()
+    Ok(HttpResponse::Ok())
 }
 
 #[cfg(test)]
@@ -266,4 +303,66 @@ mod tests {
         )
         .await;
     }
+
+    #[tokio::test]
+    async fn guest() {
+        let ctx = InMemoryContext::test_default();
+
+        let req = test::TestRequest::post().uri("/guest");
+        let res = send_test_request(req, ctx.clone()).await;
+
+        assert_eq!(res.status(), 200);
+
+        let session: SimpleSession = test::read_body_json(res).await;
+
+        assert!(session.is_guest());
+        assert_ne!(session.id(), ctx.default_session_ref().await.id());
+
+        let req = test::TestRequest::get()
+            .uri("/session")
+            .append_header((header::AUTHORIZATION, Bearer::new(session.id().to_string())));
+        let res = send_test_request(req, ctx.clone()).await;
+        let deserialized_session: SimpleSession = test::read_body_json(res).await;
+
+        assert_eq!(session, deserialized_session);
+
+        let rect =
+            STRectangle::new_unchecked(SpatialReferenceOption::Unreferenced, 0., 0., 1., 1., 0, 1);
+        let req = test::TestRequest::post()
+            .uri("/session/view")
+            .append_header((header::AUTHORIZATION, Bearer::new(session.id().to_string())))
+            .set_json(&rect);
+        let res = send_test_request(req, ctx.clone()).await;
+
+        ErrorResponse::assert(
+            res,
+            401,
+            "GuestSessionReadOnly",
+            "Guest sessions have read-only access, please log in",
+        )
+        .await;
+
+        // the test settings allow three workflow executions per guest session
+        for _ in 0..3 {
+            assert!(ctx.execution_context(session.clone()).is_ok());
+        }
+        assert!(matches!(
+            ctx.execution_context(session),
+            Err(error::Error::GuestQuotaExceeded { limit: 3 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_disables_guest_access() {
+        config::set_config("session.guest_access", false).unwrap();
+
+        let ctx = InMemoryContext::test_default();
+
+        let req = test::TestRequest::post().uri("/guest");
+        let res = send_test_request(req, ctx.clone()).await;
+
+        config::set_config("session.guest_access", true).unwrap();
+
+        ErrorResponse::assert(res, 401, "GuestAccessDisabled", "Guest access is disabled").await;
+    }
 }
@@ -17,17 +17,18 @@ use geoengine_datatypes::{primitives::SpatialResolution, spatial_reference::Spat
 use crate::error::Result;
 use crate::error::{self, Error};
 use crate::handlers::spatial_references::{spatial_reference_specification, AxisOrder};
-use crate::handlers::Context;
+use crate::handlers::{append_query_warnings, Context};
 use crate::ogc::wcs::request::{DescribeCoverage, GetCapabilities, GetCoverage, WcsRequest};
 use crate::util::config;
 use crate::util::config::get_config_element;
+use crate::util::rate_limiting::QueryRateLimiting;
 use crate::util::user_input::QueryEx;
 use crate::workflows::registry::WorkflowRegistry;
 use crate::workflows::workflow::WorkflowId;
 
 use geoengine_datatypes::primitives::{TimeInstance, TimeInterval};
-use geoengine_operators::engine::RasterOperator;
 use geoengine_operators::engine::ResultDescriptor;
+use geoengine_operators::engine::{RasterErrorPolicyQueryContext, RasterOperator};
 use geoengine_operators::processing::{Reprojection, ReprojectionParams};
 
 pub(crate) fn init_wcs_routes<C>(cfg: &mut web::ServiceConfig)
@@ -35,7 +36,11 @@ where
     C: Context,
     C::Session: FromRequest,
 {
-    cfg.service(web::resource("/wcs/{workflow}").route(web::get().to(wcs_handler::<C>)));
+    cfg.service(
+        web::resource("/wcs/{workflow}")
+            .wrap(QueryRateLimiting)
+            .route(web::get().to(wcs_handler::<C>)),
+    );
 }
 
 async fn wcs_handler<C: Context>(
@@ -344,9 +349,14 @@ async fn get_coverage<C: Context>(
         spatial_bounds: request_partition,
         time_interval: request.time.unwrap_or_else(default_time_from_config),
         spatial_resolution,
+        spatial_filter: (),
     };
 
-    let query_ctx = ctx.query_context()?;
+    let query_ctx = RasterErrorPolicyQueryContext::new(
+        ctx.query_context()?,
+        request.error_policy.unwrap_or_default(),
+    );
+    let query_warnings = query_ctx.warnings();
 
     let bytes = call_on_generic_raster_processor_gdal_types!(processor, p =>
         raster_stream_to_geotiff_bytes(
@@ -368,7 +378,10 @@ async fn get_coverage<C: Context>(
         .await)?
     .map_err(error::Error::from)?;
 
-    Ok(HttpResponse::Ok().content_type("image/tiff").body(bytes))
+    let mut response = HttpResponse::Ok();
+    append_query_warnings(&mut response, &query_warnings.to_vec());
+
+    Ok(response.content_type("image/tiff").body(bytes))
 }
 
 fn default_time_from_config() -> TimeInterval {
@@ -1,4 +1,5 @@
 use actix_web::{web, FromRequest, HttpResponse};
+use chrono::FixedOffset;
 use geoengine_datatypes::primitives::VectorQueryRectangle;
 use reqwest::Url;
 use snafu::{ensure, ResultExt};
@@ -6,28 +7,28 @@ use snafu::{ensure, ResultExt};
 use crate::error::Result;
 use crate::error::{self, Error};
 use crate::handlers::Context;
+use crate::ogc::wfs::geojson_stream::geo_json_stream;
 use crate::ogc::wfs::request::{GetCapabilities, GetFeature, WfsRequest};
 use crate::util::config;
 use crate::util::config::get_config_element;
+use crate::util::query_preview::QueryPreview;
+use crate::util::rate_limiting::QueryRateLimiting;
 use crate::util::user_input::QueryEx;
+use crate::workflows::cache::{CacheKey, ConditionalRequest, ResultValidators};
 use crate::workflows::registry::WorkflowRegistry;
 use crate::workflows::workflow::{Workflow, WorkflowId};
-use futures::StreamExt;
+use bytes::Bytes;
+use futures::{StreamExt, TryStreamExt};
 use geoengine_datatypes::collections::ToGeoJson;
+use geoengine_datatypes::{collections::MultiPointCollection, primitives::SpatialResolution};
 use geoengine_datatypes::{
-    collections::{FeatureCollection, MultiPointCollection},
-    primitives::SpatialResolution,
-};
-use geoengine_datatypes::{
-    primitives::{FeatureData, Geometry, MultiPoint, TimeInstance, TimeInterval},
+    primitives::{FeatureData, MultiPoint, SpatialFilter, TimeInstance, TimeInterval},
     spatial_reference::SpatialReference,
 };
 use geoengine_operators::engine::{
-    QueryContext, ResultDescriptor, TypedVectorQueryProcessor, VectorQueryProcessor,
+    OperatorDatasets, ResultDescriptor, TypedVectorQueryProcessor, VectorOperator,
 };
-use geoengine_operators::engine::{QueryProcessor, VectorOperator};
 use geoengine_operators::processing::{Reprojection, ReprojectionParams};
-use serde_json::json;
 use std::str::FromStr;
 
 pub(crate) fn init_wfs_routes<C>(cfg: &mut web::ServiceConfig)
@@ -35,7 +36,11 @@ where
     C: Context,
     C::Session: FromRequest,
 {
-    cfg.service(web::resource("/wfs/{workflow}").route(web::get().to(wfs_handler::<C>)));
+    cfg.service(
+        web::resource("/wfs/{workflow}")
+            .wrap(QueryRateLimiting)
+            .route(web::get().to(wfs_handler::<C>)),
+    );
 }
 
 async fn wfs_handler<C: Context>(
@@ -43,13 +48,21 @@ async fn wfs_handler<C: Context>(
     request: QueryEx<WfsRequest>,
     ctx: web::Data<C>,
     session: C::Session,
+    conditions: ConditionalRequest,
 ) -> Result<HttpResponse> {
     match request.into_inner() {
         WfsRequest::GetCapabilities(request) => {
             get_capabilities(&request, ctx.get_ref(), session, workflow.into_inner()).await
         }
         WfsRequest::GetFeature(request) => {
-            get_feature(&request, ctx.get_ref(), session, workflow.into_inner()).await
+            get_feature(
+                &request,
+                ctx.get_ref(),
+                session,
+                workflow.into_inner(),
+                &conditions,
+            )
+            .await
         }
         _ => Ok(HttpResponse::NotImplemented().finish()),
     }
@@ -398,6 +411,7 @@ async fn get_feature<C: Context>(
     ctx: &C,
     session: C::Session,
     endpoint: WorkflowId,
+    conditions: &ConditionalRequest,
 ) -> Result<HttpResponse> {
     let type_names = match request.type_names.namespace.as_deref() {
         None => WorkflowId::from_str(&request.type_names.feature_type)?,
@@ -431,6 +445,27 @@ async fn get_feature<C: Context>(
         .await
         .context(error::Operator)?;
 
+    // without an explicit time, the result may depend on the configured default time
+    let validators = if request.time.is_some() && initialized.cacheability().is_deterministic() {
+        let key = CacheKey {
+            workflow: endpoint,
+            query: serde_json::to_string(request)?,
+        };
+        let last_modified = ctx
+            .dataset_events()
+            .last_modified(&operator.datasets())
+            .await;
+        Some(ResultValidators::new(&key, last_modified))
+    } else {
+        None
+    };
+
+    if let Some(validators) = &validators {
+        if validators.is_fresh(conditions) {
+            return Ok(validators.not_modified());
+        }
+    }
+
     // handle request and workflow crs matching
     let workflow_spatial_ref: Option<SpatialReference> =
         initialized.result_descriptor().spatial_reference().into();
@@ -463,82 +498,62 @@ async fn get_feature<C: Context>(
 
     let query_rect = VectorQueryRectangle {
         spatial_bounds: request.bbox,
-        time_interval: request.time.unwrap_or_else(default_time_from_config),
+        time_interval: request
+            .time
+            .map(|time| time.time_interval(request.time_zone))
+            .transpose()?
+            .unwrap_or_else(default_time_from_config),
         spatial_resolution: request
             .query_resolution
             // TODO: find a reasonable fallback, e.g., dependent on the SRS or BBox
             .unwrap_or_else(SpatialResolution::zero_point_one),
+        spatial_filter: request
+            .spatial_filter
+            .clone()
+            .map(|polygon| SpatialFilter::new(request_spatial_ref, polygon)),
     };
     let query_ctx = ctx.query_context()?;
 
-    let json = match processor {
+    let time_zone = request.time_zone.unwrap_or_else(|| FixedOffset::east(0));
+
+    let preview = QueryPreview::from_request(request.preview)?;
+
+    let mut stream = match processor {
         TypedVectorQueryProcessor::Data(p) => {
-            vector_stream_to_geojson(p, query_rect, &query_ctx).await
+            geo_json_stream(p, query_rect, query_ctx, time_zone, preview.clone()).boxed()
         }
         TypedVectorQueryProcessor::MultiPoint(p) => {
-            vector_stream_to_geojson(p, query_rect, &query_ctx).await
+            geo_json_stream(p, query_rect, query_ctx, time_zone, preview.clone()).boxed()
         }
         TypedVectorQueryProcessor::MultiLineString(p) => {
-            vector_stream_to_geojson(p, query_rect, &query_ctx).await
+            geo_json_stream(p, query_rect, query_ctx, time_zone, preview.clone()).boxed()
         }
         TypedVectorQueryProcessor::MultiPolygon(p) => {
-            vector_stream_to_geojson(p, query_rect, &query_ctx).await
+            geo_json_stream(p, query_rect, query_ctx, time_zone, preview.clone()).boxed()
         }
-    }?;
+    };
 
-    Ok(HttpResponse::Ok().json(json))
-}
+    // errors of starting the query are reported as error responses, later ones abort the response
+    let first_chunk = stream.next().await.transpose()?;
 
-async fn vector_stream_to_geojson<G>(
-    processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
-    query_rect: VectorQueryRectangle,
-    query_ctx: &dyn QueryContext,
-) -> Result<serde_json::Value>
-where
-    G: Geometry + 'static,
-    for<'c> FeatureCollection<G>: ToGeoJson<'c>,
-{
-    let features: Vec<serde_json::Value> = Vec::new();
-
-    // TODO: more efficient merging of the partial feature collections
-    let stream = processor.query(query_rect, query_ctx).await?;
-
-    let features = stream
-        .fold(
-            Result::<Vec<serde_json::Value>, error::Error>::Ok(features),
-            |output, collection| async move {
-                match (output, collection) {
-                    (Ok(mut output), Ok(collection)) => {
-                        // TODO: avoid parsing the generated json
-                        let mut json: serde_json::Value =
-                            serde_json::from_str(&collection.to_geo_json())
-                                .expect("to_geojson is correct");
-                        let more_features = json
-                            .get_mut("features")
-                            .expect("to_geojson is correct")
-                            .as_array_mut()
-                            .expect("to geojson is correct");
-
-                        output.append(more_features);
-                        Ok(output)
-                    }
-                    (Err(error), _) => Err(error),
-                    (_, Err(error)) => Err(error.into()),
-                }
-            },
-        )
-        .await?;
+    let mut response = HttpResponse::Ok();
+    response.content_type(mime::APPLICATION_JSON);
+    if let Some(validators) = &validators {
+        validators.insert_headers(&mut response);
+    }
 
-    let mut output = json!({
-        "type": "FeatureCollection"
-    });
+    if let Some(preview) = preview {
+        // previews are bounded by their budget, but whether they are sampled is only known at the end
+        let chunks: Vec<Bytes> = futures::stream::iter(first_chunk.map(Ok))
+            .chain(stream)
+            .try_collect()
+            .await?;
 
-    output
-        .as_object_mut()
-        .expect("as defined")
-        .insert("features".into(), serde_json::Value::Array(features));
+        preview.insert_header(&mut response);
+        return Ok(response.body(chunks.concat()));
+    }
 
-    Ok(output)
+    Ok(response.streaming(futures::stream::iter(first_chunk.map(Ok)).chain(stream)))
 }
 
 #[allow(clippy::unnecessary_wraps)] // TODO: remove line once implemented fully
@@ -1,47 +1,79 @@
-use actix_web::{web, FromRequest, HttpResponse};
+use actix_web::http::header::ContentEncoding;
+use actix_web::{web, FromRequest, HttpRequest, HttpResponse};
 use reqwest::Url;
 use snafu::{ensure, ResultExt};
 
 use geoengine_datatypes::primitives::{
-    AxisAlignedRectangle, RasterQueryRectangle, SpatialPartition2D,
+    AxisAlignedRectangle, BoundingBox2D, RasterQueryRectangle, SpatialPartition2D,
+    VectorQueryRectangle,
 };
 use geoengine_datatypes::{
-    operations::image::Colorizer, primitives::SpatialResolution,
-    spatial_reference::SpatialReference,
+    operations::image::{Colorizer, ImageEncoding, PngBitDepth, PngOptions, RgbaColor},
+    primitives::SpatialResolution,
+    spatial_reference::{SpatialReference, SpatialReferenceAuthority},
 };
 
+use crate::contexts::Session;
+use crate::distributed::remote_raster_query_processor;
 use crate::error::Result;
 use crate::error::{self, Error};
-use crate::handlers::Context;
-use crate::ogc::wms::request::{GetCapabilities, GetLegendGraphic, GetMap, WmsRequest};
+use crate::handlers::{append_query_warnings, Context};
+use crate::ogc::wms::request::{
+    GetCapabilities, GetLegendGraphic, GetMap, GetMapFormat, WmsRequest,
+};
+use crate::projects::{ColorParam, NumberParam, Symbology};
 use crate::util::config;
 use crate::util::config::get_config_element;
+use crate::util::query_preview::QueryPreview;
+use crate::util::rate_limiting::QueryRateLimiting;
 use crate::util::user_input::QueryEx;
+use crate::workflows::cache::{
+    CacheHint, CacheKey, CachedResult, ConditionalRequest, ResultCache, ResultValidators,
+    CACHE_STATUS_HEADER,
+};
 use crate::workflows::registry::WorkflowRegistry;
 use crate::workflows::workflow::WorkflowId;
 
+use bytes::Bytes;
+use geoengine_datatypes::dataset::DatasetId;
 use geoengine_datatypes::primitives::{TimeInstance, TimeInterval};
-use geoengine_operators::engine::{RasterOperator, ResultDescriptor};
+use geoengine_operators::engine::{
+    Cacheability, DimensionQueryContext, ExecutionContext, OperatorDatasets, RasterDimension,
+    RasterErrorPolicyQueryContext, RasterOperator, ResultDescriptor, TypedOperator,
+    TypedVectorQueryProcessor, VectorOperator,
+};
 use geoengine_operators::processing::{Reprojection, ReprojectionParams};
+use geoengine_operators::util::feature_expression::FeatureExpression;
+use geoengine_operators::util::vector_stream_to_png::{
+    vector_stream_to_png_bytes, StyleColor, StyleNumber, VectorStyle,
+};
 use geoengine_operators::{
     call_on_generic_raster_processor, util::raster_stream_to_png::raster_stream_to_png_bytes,
 };
 use num_traits::AsPrimitive;
 use std::str::FromStr;
+use xml::escape::{escape_str_attribute, escape_str_pcdata};
 
 pub(crate) fn init_wms_routes<C>(cfg: &mut web::ServiceConfig)
 where
     C: Context,
     C::Session: FromRequest,
 {
-    cfg.service(web::resource("/wms/{workflow}").route(web::get().to(wms_handler::<C>)));
+    cfg.service(
+        web::resource("/wms/{workflow}")
+            .wrap(QueryRateLimiting)
+            .route(web::get().to(wms_handler::<C>)),
+    );
 }
 
 async fn wms_handler<C: Context>(
+    req: HttpRequest,
     workflow: web::Path<WorkflowId>,
     request: QueryEx<WmsRequest>,
     ctx: web::Data<C>,
     session: C::Session,
+    cache_hint: CacheHint,
+    conditions: ConditionalRequest,
 ) -> Result<HttpResponse> {
     match request.into_inner() {
         WmsRequest::GetCapabilities(request) => {
@@ -58,8 +90,20 @@ async fn wms_handler<C: Context>(
             )
             .await
         }
-        WmsRequest::GetMap(request) => {
-            get_map(&request, ctx.get_ref(), session, workflow.into_inner()).await
+        WmsRequest::GetMap(mut request) => {
+            request
+                .set_dimensions_from_query(req.query_string())
+                .context(error::UnableToParseQueryString)?;
+
+            get_map(
+                &request,
+                ctx.get_ref(),
+                session,
+                workflow.into_inner(),
+                cache_hint,
+                &conditions,
+            )
+            .await
         }
         WmsRequest::GetLegendGraphic(request) => {
             get_legend_graphic(&request, ctx.get_ref(), workflow.into_inner())
@@ -199,14 +243,15 @@ where
                 <southBoundLatitude>-90</southBoundLatitude>
                 <northBoundLatitude>90</northBoundLatitude>
             </EX_GeographicBoundingBox>
-            <BoundingBox CRS="EPSG:4326" minx="-90.0" miny="-180.0" maxx="90.0" maxy="180.0"/>
+            <BoundingBox CRS="EPSG:4326" minx="-90.0" miny="-180.0" maxx="90.0" maxy="180.0"/>{dimensions}
         </Layer>
     </Capability>
 </WMS_Capabilities>"#,
         wms_url = wms_url,
         workflow = workflow_id,
         srs_authority = spatial_reference.authority(),
-        srs_code = spatial_reference.code()
+        srs_code = spatial_reference.code(),
+        dimensions = dimensions_xml(&result_descriptor.dimensions),
     );
 
     Ok(HttpResponse::Ok()
@@ -214,6 +259,31 @@ where
         .body(response))
 }
 
+/// Lists the values of additional dimensions as `Dimension` elements of a layer.
+/// Clients request `elevation` values with the `ELEVATION` parameter and others with `DIM_<name>`.
+fn dimensions_xml(dimensions: &[RasterDimension]) -> String {
+    dimensions
+        .iter()
+        .map(|dimension| {
+            let default = dimension
+                .default
+                .as_ref()
+                .or_else(|| dimension.values.first())
+                .map(String::as_str)
+                .unwrap_or_default();
+
+            format!(
+                r#"
+            <Dimension name="{name}" units="{units}" default="{default}">{values}</Dimension>"#,
+                name = escape_str_attribute(&dimension.name.to_lowercase()),
+                units = escape_str_attribute(dimension.units.as_deref().unwrap_or_default()),
+                default = escape_str_attribute(default),
+                values = escape_str_pcdata(&dimension.values.join(",")),
+            )
+        })
+        .collect()
+}
+
 fn wms_url(external_address: &Url, workflow: WorkflowId) -> Result<Url> {
     external_address
         .join("wms/")?
@@ -235,6 +305,8 @@ async fn get_map<C: Context>(
     ctx: &C,
     session: C::Session,
     endpoint: WorkflowId,
+    cache_hint: CacheHint,
+    conditions: &ConditionalRequest,
 ) -> Result<HttpResponse> {
     let layer = WorkflowId::from_str(&request.layers)?;
 
@@ -251,7 +323,19 @@ async fn get_map<C: Context>(
         .load(&WorkflowId::from_str(&request.layers)?)
         .await?;
 
-    let operator = workflow.operator.get_raster().context(error::Operator)?;
+    let operator = match workflow.operator {
+        TypedOperator::Vector(operator) => {
+            return get_vector_map(
+                request, ctx, session, endpoint, cache_hint, conditions, operator,
+            )
+            .await
+        }
+        operator => operator.get_raster().context(error::Operator)?,
+    };
+    let datasets = operator.datasets();
+
+    // workers authenticate the partitions of the query with the session of the request
+    let session_token = session.id().to_string();
 
     let execution_context = ctx.execution_context(session)?;
 
@@ -271,60 +355,484 @@ async fn get_map<C: Context>(
         request.crs.ok_or(error::Error::MissingSpatialReference)?;
 
     // perform reprojection if necessary
-    let initialized = if request_spatial_ref == workflow_spatial_ref {
-        initialized
+    let (operator, initialized) = if request_spatial_ref == workflow_spatial_ref {
+        (operator, initialized)
     } else {
-        let proj = Reprojection {
+        let proj: Box<dyn RasterOperator> = Box::new(Reprojection {
             params: ReprojectionParams {
                 target_spatial_reference: request_spatial_ref,
             },
             sources: operator.into(),
-        };
+        });
 
         // TODO: avoid re-initialization of the whole operator graph
-        Box::new(proj)
+        let initialized = proj
+            .clone()
             .initialize(&execution_context)
             .await
-            .context(error::Operator)?
+            .context(error::Operator)?;
+
+        (proj, initialized)
     };
 
+    let result_cache = ctx.result_cache();
+
+    let caching = MapCaching::new(
+        ctx,
+        request,
+        endpoint,
+        initialized.cacheability(),
+        datasets,
+        cache_hint,
+    )
+    .await?;
+
+    if let Some(response) = caching.cached_response(&result_cache, conditions).await {
+        return Ok(response);
+    }
+
     let no_data_value: Option<f64> = initialized.result_descriptor().no_data_value;
 
-    let processor = initialized.query_processor().context(error::Operator)?;
+    let distributed_config = config::get_config_element::<config::Distributed>()?;
+
+    let processor = if distributed_config.workers.is_empty() {
+        initialized.query_processor().context(error::Operator)?
+    } else {
+        remote_raster_query_processor(
+            initialized.result_descriptor().data_type,
+            distributed_config.workers,
+            distributed_config.partitioning,
+            operator,
+            session_token,
+        )
+    };
 
     let query_bbox: SpatialPartition2D = request.bbox.bounds(request_spatial_ref)?;
     let x_query_resolution = query_bbox.size_x() / f64::from(request.width);
     let y_query_resolution = query_bbox.size_y() / f64::from(request.height);
+    let query_resolution = SpatialResolution::new_unchecked(x_query_resolution, y_query_resolution);
+
+    let preview = QueryPreview::from_request(request.preview)?;
+    let query_resolution = match &preview {
+        Some(preview) => preview.raster_resolution(query_resolution, request.width, request.height),
+        None => query_resolution,
+    };
+
+    // with a web mercator aligned tiling, map tiles are answered by exactly one engine tile
+    // if their resolution is not distorted by floating point errors
+    let query_resolution =
+        if request_spatial_ref == SpatialReference::new(SpatialReferenceAuthority::Epsg, 3857) {
+            execution_context
+                .tiling_specification()
+                .snap_to_web_mercator_zoom_level(query_resolution)
+                .unwrap_or(query_resolution)
+        } else {
+            query_resolution
+        };
+
+    let time = request
+        .time
+        .map(|time| time.time_interval(request.time_zone))
+        .transpose()?;
 
     let query_rect = RasterQueryRectangle {
         spatial_bounds: query_bbox,
-        time_interval: request.time.unwrap_or_else(default_time_from_config),
-        spatial_resolution: SpatialResolution::new_unchecked(
-            x_query_resolution,
-            y_query_resolution,
-        ),
+        time_interval: time.unwrap_or_else(default_time_from_config),
+        spatial_resolution: query_resolution,
+        spatial_filter: (),
     };
 
-    let query_ctx = ctx.query_context()?;
-
-    let colorizer = colorizer_from_style(&request.styles)?;
+    let query_ctx = RasterErrorPolicyQueryContext::new(
+        ctx.query_context()?,
+        request.error_policy.unwrap_or_default(),
+    );
+    let query_warnings = query_ctx.warnings();
+    let query_ctx = DimensionQueryContext::new(query_ctx, request.dimension_selection());
+
+    let (colorizer, dither) = colorizer_from_style(&request.styles)?;
+
+    let (encoding, content_type) = image_encoding(&request.format)?;
+
+    let png_options = PngOptions {
+        bit_depth: if request.format == GetMapFormat::ImagePng16Bit {
+            PngBitDepth::Sixteen
+        } else {
+            PngBitDepth::Eight
+        },
+        // an explicit `TRANSPARENT` parameter overrides the alpha value of the no data color
+        no_data_alpha: request
+            .transparent
+            .map(|transparent| if transparent { 0 } else { u8::MAX }),
+        dither,
+    };
 
     let image_bytes = call_on_generic_raster_processor!(
         processor,
         p =>
-            raster_stream_to_png_bytes(p, query_rect, query_ctx, request.width, request.height, request.time, colorizer, no_data_value.map(AsPrimitive::as_)).await
+            raster_stream_to_png_bytes(p, query_rect, query_ctx, request.width, request.height, time, colorizer, no_data_value.map(AsPrimitive::as_), png_options, encoding).await
     ).map_err(error::Error::from)?;
 
-    Ok(HttpResponse::Ok()
-        .content_type(mime::IMAGE_PNG)
-        .body(image_bytes))
+    Ok(image_response(
+        image_bytes,
+        content_type,
+        &query_warnings.to_vec(),
+        preview.as_ref(),
+        caching,
+        &result_cache,
+    )
+    .await)
+}
+
+/// Renders the features of a vector workflow with the symbology of a `custom:{symbology}` style
+/// or a default style.
+async fn get_vector_map<C: Context>(
+    request: &GetMap,
+    ctx: &C,
+    session: C::Session,
+    endpoint: WorkflowId,
+    cache_hint: CacheHint,
+    conditions: &ConditionalRequest,
+    operator: Box<dyn VectorOperator>,
+) -> Result<HttpResponse> {
+    let datasets = operator.datasets();
+
+    let execution_context = ctx.execution_context(session)?;
+
+    let initialized = operator
+        .clone()
+        .initialize(&execution_context)
+        .await
+        .context(error::Operator)?;
+
+    // handle request and workflow crs matching
+    let workflow_spatial_ref: Option<SpatialReference> =
+        initialized.result_descriptor().spatial_reference().into();
+    let workflow_spatial_ref = workflow_spatial_ref.ok_or(error::Error::InvalidSpatialReference)?;
+
+    let request_spatial_ref: SpatialReference =
+        request.crs.ok_or(error::Error::MissingSpatialReference)?;
+
+    // perform reprojection if necessary
+    let initialized = if request_spatial_ref == workflow_spatial_ref {
+        initialized
+    } else {
+        let proj: Box<dyn VectorOperator> = Box::new(Reprojection {
+            params: ReprojectionParams {
+                target_spatial_reference: request_spatial_ref,
+            },
+            sources: operator.into(),
+        });
+
+        // TODO: avoid re-initialization of the whole operator graph
+        proj.initialize(&execution_context)
+            .await
+            .context(error::Operator)?
+    };
+
+    let result_cache = ctx.result_cache();
+
+    let caching = MapCaching::new(
+        ctx,
+        request,
+        endpoint,
+        initialized.cacheability(),
+        datasets,
+        cache_hint,
+    )
+    .await?;
+
+    if let Some(response) = caching.cached_response(&result_cache, conditions).await {
+        return Ok(response);
+    }
+
+    let processor = initialized.query_processor().context(error::Operator)?;
+
+    let query_bbox: BoundingBox2D = request.bbox.bounds(request_spatial_ref)?;
+    let query_rect = VectorQueryRectangle {
+        spatial_bounds: query_bbox,
+        time_interval: request
+            .time
+            .map(|time| time.time_interval(request.time_zone))
+            .transpose()?
+            .unwrap_or_else(default_time_from_config),
+        spatial_resolution: SpatialResolution::new_unchecked(
+            query_bbox.size_x() / f64::from(request.width),
+            query_bbox.size_y() / f64::from(request.height),
+        ),
+        spatial_filter: None,
+    };
+
+    let query_ctx = ctx.query_context()?;
+
+    let style = vector_style_from_style(&request.styles)?;
+
+    let (encoding, content_type) = image_encoding(&request.format)?;
+
+    let (width, height) = (request.width, request.height);
+    let image_bytes = match processor {
+        TypedVectorQueryProcessor::Data(_) => {
+            return Err(error::Error::WMSVectorLayerWithoutGeometries)
+        }
+        TypedVectorQueryProcessor::MultiPoint(p) => {
+            vector_stream_to_png_bytes(p, query_rect, query_ctx, width, height, &style, encoding)
+                .await
+        }
+        TypedVectorQueryProcessor::MultiLineString(p) => {
+            vector_stream_to_png_bytes(p, query_rect, query_ctx, width, height, &style, encoding)
+                .await
+        }
+        TypedVectorQueryProcessor::MultiPolygon(p) => {
+            vector_stream_to_png_bytes(p, query_rect, query_ctx, width, height, &style, encoding)
+                .await
+        }
+    }
+    .map_err(error::Error::from)?;
+
+    Ok(image_response(image_bytes, content_type, &[], None, caching, &result_cache).await)
+}
+
+/// The encoding and the content type of a `GetMap` format
+fn image_encoding(format: &GetMapFormat) -> Result<(ImageEncoding, String)> {
+    let wms_config = get_config_element::<config::Wms>()?;
+
+    Ok(match format {
+        GetMapFormat::ImagePng | GetMapFormat::ImagePng16Bit => {
+            (ImageEncoding::Png, mime::IMAGE_PNG.to_string())
+        }
+        GetMapFormat::ImageJpeg => (
+            ImageEncoding::Jpeg {
+                quality: wms_config.jpeg_quality,
+            },
+            mime::IMAGE_JPEG.to_string(),
+        ),
+        GetMapFormat::ImageWebp => (
+            ImageEncoding::WebP {
+                quality: wms_config.webp_quality,
+            },
+            "image/webp".to_owned(),
+        ),
+    })
+}
+
+/// How a `GetMap` result is cached by the server and revalidated by clients
+struct MapCaching {
+    /// The key of the result in the result cache if it is enabled and the result is deterministic
+    cache_key: Option<CacheKey>,
+    /// The validators for conditional requests if the result is deterministic
+    validators: Option<ResultValidators>,
+    hint: CacheHint,
+    datasets: Vec<DatasetId>,
+}
+
+impl MapCaching {
+    async fn new<C: Context>(
+        ctx: &C,
+        request: &GetMap,
+        endpoint: WorkflowId,
+        cacheability: Cacheability,
+        datasets: Vec<DatasetId>,
+        hint: CacheHint,
+    ) -> Result<Self> {
+        // without an explicit time, the result may depend on the configured default time,
+        // and previews are cheap, so they are not cached
+        if request.time.is_none()
+            || request.preview.unwrap_or(false)
+            || !cacheability.is_deterministic()
+        {
+            return Ok(Self {
+                cache_key: None,
+                validators: None,
+                hint,
+                datasets,
+            });
+        }
+
+        let key = CacheKey {
+            workflow: endpoint,
+            query: serde_json::to_string(request)?,
+        };
+
+        let last_modified = ctx.dataset_events().last_modified(&datasets).await;
+        let validators = ResultValidators::new(&key, last_modified);
+
+        Ok(Self {
+            cache_key: ctx.result_cache().is_enabled().then(|| key),
+            validators: Some(validators),
+            hint,
+            datasets,
+        })
+    }
+
+    /// Answers the request without computing the result if the client's copy is still up to date
+    /// or if the result is cached
+    async fn cached_response(
+        &self,
+        result_cache: &ResultCache,
+        conditions: &ConditionalRequest,
+    ) -> Option<HttpResponse> {
+        if let Some(validators) = &self.validators {
+            if validators.is_fresh(conditions) {
+                return Some(validators.not_modified());
+            }
+        }
+
+        let cache_key = self.cache_key.as_ref().filter(|_| self.hint.read)?;
+        let cached = result_cache.get(cache_key).await?;
+
+        let mut response = HttpResponse::Ok();
+        response
+            .content_type(cached.content_type)
+            .insert_header(ContentEncoding::Identity)
+            .insert_header((CACHE_STATUS_HEADER, "HIT"));
+
+        if let Some(validators) = &self.validators {
+            validators.insert_headers(&mut response);
+        }
+
+        Some(response.body(cached.bytes))
+    }
 }
 
-fn colorizer_from_style(styles: &str) -> Result<Option<Colorizer>> {
-    match styles.strip_prefix("custom:") {
-        None => Ok(None),
-        Some(suffix) => serde_json::from_str(suffix).map_err(error::Error::from),
+/// Responds with the rendered image and stores it in the result cache if it is cacheable
+async fn image_response(
+    image_bytes: Vec<u8>,
+    content_type: String,
+    query_warnings: &[String],
+    preview: Option<&QueryPreview>,
+    caching: MapCaching,
+    result_cache: &ResultCache,
+) -> HttpResponse {
+    let mut response = HttpResponse::Ok();
+    response.content_type(content_type.as_str());
+    // images are already compressed
+    response.insert_header(ContentEncoding::Identity);
+    append_query_warnings(&mut response, query_warnings);
+    if let Some(preview) = preview {
+        preview.insert_header(&mut response);
     }
+
+    // partial results must neither be cached nor revalidated
+    if !query_warnings.is_empty() {
+        return response.body(image_bytes);
+    }
+
+    if let Some(validators) = &caching.validators {
+        validators.insert_headers(&mut response);
+    }
+
+    if let Some(cache_key) = caching.cache_key {
+        let image_bytes = Bytes::from(image_bytes);
+
+        if caching.hint.store {
+            result_cache
+                .insert(
+                    cache_key,
+                    CachedResult {
+                        content_type,
+                        bytes: image_bytes.clone(),
+                    },
+                    caching.datasets,
+                )
+                .await;
+        }
+
+        return response
+            .insert_header((CACHE_STATUS_HEADER, "MISS"))
+            .body(image_bytes);
+    }
+
+    response.body(image_bytes)
+}
+
+/// Parses the colorizer of a `custom:{colorizer}` style and whether continuous colors are dithered.
+/// Dithering is requested by prefixing the style with `dithered`, e.g., `dithered:custom:{colorizer}`.
+fn colorizer_from_style(styles: &str) -> Result<(Option<Colorizer>, bool)> {
+    let (styles, dither) = match styles.strip_prefix("dithered") {
+        Some(suffix) => (suffix.strip_prefix(':').unwrap_or(suffix), true),
+        None => (styles, false),
+    };
+
+    let colorizer = match styles.strip_prefix("custom:") {
+        None => None,
+        Some(suffix) => serde_json::from_str(suffix).map_err(error::Error::from)?,
+    };
+
+    Ok((colorizer, dither))
+}
+
+/// Parses the symbology of a `custom:{symbology}` style for rendering vector layers.
+/// Text symbologies are not rendered.
+fn vector_style_from_style(styles: &str) -> Result<VectorStyle> {
+    let symbology: Symbology = match styles.strip_prefix("custom:") {
+        None => return Ok(VectorStyle::default()),
+        Some(suffix) => serde_json::from_str(suffix).map_err(error::Error::from)?,
+    };
+
+    let default = VectorStyle::default();
+
+    Ok(match symbology {
+        Symbology::Raster(_) => return Err(error::Error::WMSRasterSymbologyForVectorLayer),
+        Symbology::Point(point) => VectorStyle {
+            fill_color: style_color(point.fill_color)?,
+            stroke_color: style_color(point.stroke.color)?,
+            stroke_width: style_number(point.stroke.width)?,
+            radius: style_number(point.radius)?,
+        },
+        Symbology::Line(line) => VectorStyle {
+            fill_color: StyleColor::Static(RgbaColor::transparent()),
+            stroke_color: style_color(line.stroke.color)?,
+            stroke_width: style_number(line.stroke.width)?,
+            radius: default.radius,
+        },
+        Symbology::Polygon(polygon) => VectorStyle {
+            fill_color: style_color(polygon.fill_color)?,
+            stroke_color: style_color(polygon.stroke.color)?,
+            stroke_width: style_number(polygon.stroke.width)?,
+            radius: default.radius,
+        },
+    })
+}
+
+fn style_color(color: ColorParam) -> Result<StyleColor> {
+    Ok(match color {
+        ColorParam::Static { color } => StyleColor::Static(color),
+        ColorParam::Derived(derived) => StyleColor::Derived {
+            attribute: derived.attribute,
+            colorizer: derived.colorizer,
+        },
+        ColorParam::Expression(expression) => StyleColor::Expression {
+            expression: FeatureExpression::parse(&expression.expression)?,
+            colorizer: expression.colorizer,
+        },
+        ColorParam::Classified(classified) => StyleColor::Classified {
+            expression: FeatureExpression::parse(&classified.expression)?,
+            breaks: classified.breaks,
+            colors: classified.colors,
+            no_data_color: classified.no_data_color,
+        },
+    })
+}
+
+fn style_number(number: NumberParam) -> Result<StyleNumber> {
+    Ok(match number {
+        #[allow(clippy::cast_precision_loss)]
+        NumberParam::Static { value } => StyleNumber::Static(value as f64),
+        NumberParam::Derived(derived) => StyleNumber::Derived {
+            attribute: derived.attribute,
+            factor: derived.factor,
+            default_value: derived.default_value,
+        },
+        NumberParam::Expression(expression) => StyleNumber::Expression {
+            expression: FeatureExpression::parse(&expression.expression)?,
+            default_value: expression.default_value,
+        },
+        NumberParam::Classified(classified) => StyleNumber::Classified {
+            expression: FeatureExpression::parse(&classified.expression)?,
+            breaks: classified.breaks,
+            values: classified.values,
+            default_value: classified.default_value,
+        },
+    })
 }
 
 #[allow(clippy::unnecessary_wraps)] // TODO: remove line once implemented fully
@@ -366,15 +874,17 @@ mod tests {
     use crate::util::tests::{
         check_allowed_http_methods, register_ndvi_workflow_helper, send_test_request,
     };
+    use crate::workflows::cache::{CacheEntryInfo, CacheInvalidation};
+    use crate::workflows::workflow::Workflow;
     use actix_web::dev::ServiceResponse;
     use actix_web::http::header;
     use actix_web::http::Method;
     use actix_web_httpauth::headers::authorization::Bearer;
-    use geoengine_datatypes::operations::image::RgbaColor;
     use geoengine_datatypes::primitives::SpatialPartition2D;
     use geoengine_datatypes::raster::{GridShape2D, TilingSpecification};
     use geoengine_datatypes::util::test::TestDefault;
     use geoengine_operators::engine::{ExecutionContext, RasterQueryProcessor};
+    use geoengine_operators::mock::{MockPointSource, MockPointSourceParams};
     use geoengine_operators::source::GdalSourceProcessor;
     use geoengine_operators::util::gdal::create_ndvi_meta_data;
     use std::convert::TryInto;
@@ -503,6 +1013,7 @@ mod tests {
                 time_interval: TimeInterval::new(1_388_534_400_000, 1_388_534_400_000 + 1000)
                     .unwrap(),
                 spatial_resolution: SpatialResolution::new_unchecked(1.0, 1.0),
+                spatial_filter: (),
             },
             ctx.query_context().unwrap(),
             360,
@@ -510,6 +1021,8 @@ mod tests {
             None,
             None,
             None,
+            PngOptions::default(),
+            ImageEncoding::Png,
         )
         .await
         .unwrap();
@@ -587,6 +1100,127 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn get_map_cached() {
+        let ctx = InMemoryContext::test_default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let (_, id) = register_ndvi_workflow_helper(&ctx).await;
+
+        let uri = format!("/wms/{id}?service=WMS&version=1.3.0&request=GetMap&layers={id}&styles=&width=335&height=168&crs=EPSG:4326&bbox=-90.0,-180.0,90.0,180.0&format=image/png&time=2014-04-01T12%3A00%3A00.000%2B00%3A00", id = id.to_string());
+
+        let get_map = |cache_control: Option<&'static str>| {
+            let mut req = actix_web::test::TestRequest::get()
+                .uri(&uri)
+                .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+            if let Some(cache_control) = cache_control {
+                req = req.append_header((header::CACHE_CONTROL, cache_control));
+            }
+            send_test_request(req, ctx.clone())
+        };
+
+        let cache_status = |res: &ServiceResponse| {
+            res.headers()
+                .get(CACHE_STATUS_HEADER)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string()
+        };
+
+        let res = get_map(Some("no-store")).await;
+        assert_eq!(res.status(), 200);
+        assert_eq!(cache_status(&res), "MISS");
+
+        let res = get_map(None).await;
+        assert_eq!(cache_status(&res), "MISS");
+        let computed = actix_web::test::read_body(res).await;
+
+        let res = get_map(None).await;
+        assert_eq!(cache_status(&res), "HIT");
+        assert_eq!(actix_web::test::read_body(res).await, computed);
+
+        let res = get_map(Some("no-cache")).await;
+        assert_eq!(cache_status(&res), "MISS");
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&format!("/workflow/{}/cache", id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, ctx.clone()).await;
+        assert_eq!(res.status(), 200);
+        let entries: Vec<CacheEntryInfo> = actix_web::test::read_body_json(res).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].size_in_bytes, computed.len());
+
+        let req = actix_web::test::TestRequest::delete()
+            .uri(&format!("/workflow/{}/cache", id))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, ctx.clone()).await;
+        assert_eq!(res.status(), 200);
+        let invalidation: CacheInvalidation = actix_web::test::read_body_json(res).await;
+        assert_eq!(invalidation.invalidated_entries, 1);
+
+        let res = get_map(None).await;
+        assert_eq!(cache_status(&res), "MISS");
+    }
+
+    #[tokio::test]
+    async fn get_map_conditional() {
+        let ctx = InMemoryContext::test_default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let (_, id) = register_ndvi_workflow_helper(&ctx).await;
+
+        let uri = format!("/wms/{id}?service=WMS&version=1.3.0&request=GetMap&layers={id}&styles=&width=335&height=168&crs=EPSG:4326&bbox=-90.0,-180.0,90.0,180.0&format=image/png&time=2014-04-01T12%3A00%3A00.000%2B00%3A00", id = id.to_string());
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&uri)
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .append_header((header::ACCEPT_ENCODING, "gzip"));
+        let res = send_test_request(req, ctx.clone()).await;
+        assert_eq!(res.status(), 200);
+        assert_eq!(
+            res.headers().get(header::CONTENT_ENCODING).unwrap(),
+            "identity"
+        );
+
+        let etag = res.headers().get(header::ETAG).unwrap().clone();
+        let last_modified = res.headers().get(header::LAST_MODIFIED).unwrap().clone();
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&uri)
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .append_header((header::IF_NONE_MATCH, etag.clone()));
+        let res = send_test_request(req, ctx.clone()).await;
+        assert_eq!(res.status(), 304);
+        assert_eq!(res.headers().get(header::ETAG).unwrap(), &etag);
+        assert!(actix_web::test::read_body(res).await.is_empty());
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&uri)
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .append_header((header::IF_MODIFIED_SINCE, last_modified));
+        let res = send_test_request(req, ctx.clone()).await;
+        assert_eq!(res.status(), 304);
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&uri)
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .append_header((header::IF_NONE_MATCH, "W/\"outdated\""));
+        let res = send_test_request(req, ctx.clone()).await;
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers().get(header::ETAG).unwrap(), &etag);
+
+        // without a time, the result depends on the default time and is not validated
+        let req = actix_web::test::TestRequest::get()
+            .uri(uri.split("&time=").next().unwrap())
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())))
+            .append_header((header::IF_NONE_MATCH, etag));
+        let res = send_test_request(req, ctx).await;
+        assert_eq!(res.status(), 200);
+        assert!(res.headers().get(header::ETAG).is_none());
+    }
+
     ///Actix uses serde_urlencoded inside web::Query which does not support this
     #[tokio::test]
     async fn get_map_uppercase() {
@@ -620,6 +1254,54 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn get_map_preview() {
+        let exe_ctx_tiling_spec = TilingSpecification {
+            origin_coordinate: (0., 0.).into(),
+            tile_size_in_pixels: GridShape2D::new([600, 600]),
+        };
+
+        let ctx = InMemoryContext::new_with_context_spec(
+            exe_ctx_tiling_spec,
+            TestDefault::test_default(),
+        );
+
+        let session_id = ctx.default_session_ref().await.id();
+
+        let (_, id) = register_ndvi_workflow_helper(&ctx).await;
+
+        let uri = format!("/wms/{id}?service=WMS&version=1.3.0&request=GetMap&layers={id}&bbox=20,-10,80,50&width=600&height=600&crs=EPSG:4326&styles=&format=image/png&time=2014-01-01T00:00:00.0Z", id = id.to_string());
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&format!("{}&preview=true", uri))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, ctx.clone()).await;
+
+        assert_eq!(res.status(), 200);
+        assert_eq!(
+            res.headers()
+                .get(crate::handlers::QUERY_SAMPLED_HEADER)
+                .unwrap(),
+            "true"
+        );
+
+        let image_bytes = actix_web::test::read_body(res).await;
+        let image =
+            image::load_from_memory_with_format(&image_bytes, image::ImageFormat::Png).unwrap();
+        assert_eq!((image.width(), image.height()), (600, 600));
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&uri)
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, ctx).await;
+
+        assert_eq!(res.status(), 200);
+        assert!(res
+            .headers()
+            .get(crate::handlers::QUERY_SAMPLED_HEADER)
+            .is_none());
+    }
+
     #[tokio::test]
     async fn get_map_invalid_method() {
         check_allowed_http_methods(|method| get_map_test_helper(method, None), &[Method::GET])
@@ -704,6 +1386,274 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn get_map_16_bit() {
+        let ctx = InMemoryContext::test_default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let (_, id) = register_ndvi_workflow_helper(&ctx).await;
+
+        let params = &[
+            ("request", "GetMap"),
+            ("service", "WMS"),
+            ("version", "1.3.0"),
+            ("layers", &id.to_string()),
+            ("bbox", "-90,-180,90,180"),
+            ("width", "36"),
+            ("height", "18"),
+            ("crs", "EPSG:4326"),
+            ("styles", "dithered"),
+            ("format", "image/png; mode=16bit"),
+            ("transparent", "true"),
+            ("time", "2014-01-01T00:00:00.0Z"),
+        ];
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&format!(
+                "/wms/{}?{}",
+                id,
+                serde_urlencoded::to_string(params).unwrap()
+            ))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, ctx).await;
+
+        assert_eq!(res.status(), 200);
+
+        let image_bytes = actix_web::test::read_body(res).await;
+        let image = image::load_from_memory(&image_bytes).unwrap();
+
+        assert_eq!(image.color(), image::ColorType::Rgba16);
+        assert_eq!((image.width(), image.height()), (36, 18));
+    }
+
+    #[tokio::test]
+    async fn get_map_jpeg_and_webp() {
+        let ctx = InMemoryContext::test_default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let (_, id) = register_ndvi_workflow_helper(&ctx).await;
+
+        for (format, image_format) in [
+            ("image/jpeg", image::ImageFormat::Jpeg),
+            ("image/webp", image::ImageFormat::WebP),
+        ] {
+            let params = &[
+                ("request", "GetMap"),
+                ("service", "WMS"),
+                ("version", "1.3.0"),
+                ("layers", &id.to_string()),
+                ("bbox", "-90,-180,90,180"),
+                ("width", "36"),
+                ("height", "18"),
+                ("crs", "EPSG:4326"),
+                ("styles", ""),
+                ("format", format),
+                ("time", "2014-01-01T00:00:00.0Z"),
+            ];
+
+            let req = actix_web::test::TestRequest::get()
+                .uri(&format!(
+                    "/wms/{}?{}",
+                    id,
+                    serde_urlencoded::to_string(params).unwrap()
+                ))
+                .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+            let res = send_test_request(req, ctx.clone()).await;
+
+            assert_eq!(res.status(), 200);
+            assert_eq!(res.headers().get(header::CONTENT_TYPE).unwrap(), format);
+
+            let image_bytes = actix_web::test::read_body(res).await;
+
+            assert_eq!(image::guess_format(&image_bytes).unwrap(), image_format);
+        }
+    }
+
+    #[test]
+    fn it_parses_dithered_styles() {
+        let (colorizer, dither) = colorizer_from_style("").unwrap();
+        assert!(colorizer.is_none());
+        assert!(!dither);
+
+        let (colorizer, dither) = colorizer_from_style("dithered").unwrap();
+        assert!(colorizer.is_none());
+        assert!(dither);
+
+        let (colorizer, dither) =
+            colorizer_from_style(r#"dithered:custom:{"type":"rgba"}"#).unwrap();
+        assert_eq!(colorizer, Some(Colorizer::rgba()));
+        assert!(dither);
+
+        assert!(colorizer_from_style("dithered:custom:{").is_err());
+    }
+
+    #[tokio::test]
+    async fn get_map_vector() {
+        let ctx = InMemoryContext::test_default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let workflow = Workflow {
+            operator: TypedOperator::Vector(
+                MockPointSource {
+                    params: MockPointSourceParams {
+                        points: vec![(0., 0.).into()],
+                    },
+                }
+                .boxed(),
+            ),
+        };
+
+        let id = ctx
+            .workflow_registry()
+            .write()
+            .await
+            .register(workflow)
+            .await
+            .unwrap();
+
+        let req = actix_web::test::TestRequest::get()
+            .uri(&format!("/wms/{id}?request=GetMap&service=WMS&version=1.3.0&layers={id}&bbox=-10,-10,10,10&width=20&height=20&crs=EPSG:4326&styles=&format=image/png&time=2014-01-01T00:00:00.0Z", id = id.to_string()))
+            .append_header((header::AUTHORIZATION, Bearer::new(session_id.to_string())));
+        let res = send_test_request(req, ctx).await;
+
+        assert_eq!(res.status(), 200);
+
+        let image_bytes = actix_web::test::read_body(res).await;
+        let image = image::load_from_memory(&image_bytes).unwrap().to_rgba8();
+
+        // the default style draws white circles with a radius of 10 pixels and a black outline
+        assert_eq!(
+            *image.get_pixel(10, 10),
+            image::Rgba::from(RgbaColor::white())
+        );
+        assert_eq!(
+            *image.get_pixel(0, 0),
+            image::Rgba::from(RgbaColor::transparent())
+        );
+    }
+
+    #[test]
+    fn it_parses_vector_styles() {
+        assert_eq!(vector_style_from_style("").unwrap(), VectorStyle::default());
+
+        let style = vector_style_from_style(
+            r#"custom:{
+                "type": "point",
+                "radius": { "type": "derived", "attribute": "size", "factor": 2.0, "defaultValue": 1.0 },
+                "fillColor": { "type": "static", "color": [255, 0, 0, 255] },
+                "stroke": {
+                    "width": { "type": "static", "value": 0 },
+                    "color": { "type": "static", "color": [0, 0, 0, 255] }
+                },
+                "text": null
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            style,
+            VectorStyle {
+                fill_color: StyleColor::Static(RgbaColor::new(255, 0, 0, 255)),
+                stroke_color: StyleColor::Static(RgbaColor::black()),
+                stroke_width: StyleNumber::Static(0.),
+                radius: StyleNumber::Derived {
+                    attribute: "size".to_string(),
+                    factor: 2.,
+                    default_value: 1.,
+                },
+            }
+        );
+
+        assert!(matches!(
+            vector_style_from_style(
+                r#"custom:{"type":"raster","opacity":1.0,"colorizer":{"type":"rgba"}}"#
+            ),
+            Err(Error::WMSRasterSymbologyForVectorLayer)
+        ));
+    }
+
+    #[test]
+    fn it_parses_classified_vector_styles() {
+        let style = vector_style_from_style(
+            r#"custom:{
+                "type": "polygon",
+                "fillColor": {
+                    "type": "classified",
+                    "expression": "population / area",
+                    "breaks": [10.0],
+                    "colors": [[0, 0, 0, 255], [255, 255, 255, 255]],
+                    "noDataColor": [0, 0, 0, 0]
+                },
+                "stroke": {
+                    "width": { "type": "expression", "expression": "sqrt(area)", "defaultValue": 1.0 },
+                    "color": { "type": "static", "color": [0, 0, 0, 255] }
+                },
+                "text": null
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            style.fill_color,
+            StyleColor::Classified {
+                expression: FeatureExpression::parse("population / area").unwrap(),
+                breaks: vec![10.],
+                colors: vec![RgbaColor::black(), RgbaColor::white()],
+                no_data_color: RgbaColor::transparent(),
+            }
+        );
+        assert_eq!(
+            style.stroke_width,
+            StyleNumber::Expression {
+                expression: FeatureExpression::parse("sqrt(area)").unwrap(),
+                default_value: 1.,
+            }
+        );
+
+        assert!(matches!(
+            vector_style_from_style(
+                r#"custom:{
+                    "type": "line",
+                    "stroke": {
+                        "width": { "type": "expression", "expression": "sqrt(", "defaultValue": 1.0 },
+                        "color": { "type": "static", "color": [0, 0, 0, 255] }
+                    },
+                    "text": null
+                }"#
+            ),
+            Err(Error::Operator {
+                source: geoengine_operators::error::Error::InvalidFeatureExpression { .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn it_lists_dimensions() {
+        assert_eq!(dimensions_xml(&[]), "");
+
+        let dimensions = dimensions_xml(&[
+            RasterDimension {
+                name: "elevation".to_string(),
+                units: Some("hPa".to_string()),
+                values: vec!["1000".to_string(), "850".to_string(), "500".to_string()],
+                default: Some("850".to_string()),
+            },
+            RasterDimension {
+                name: "Ensemble".to_string(),
+                units: None,
+                values: vec!["a&b".to_string(), "c".to_string()],
+                default: None,
+            },
+        ]);
+
+        assert_eq!(
+            dimensions,
+            r#"
+            <Dimension name="elevation" units="hPa" default="850">1000,850,500</Dimension>
+            <Dimension name="ensemble" units="" default="a&amp;b">a&amp;b,c</Dimension>"#
+        );
+    }
+
     #[tokio::test]
     async fn it_zoomes_very_far() {
         let ctx = InMemoryContext::test_default();
@@ -21,10 +21,13 @@
 // enable some restriction lints
 #![warn(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)]
 
+pub mod client;
 pub mod contexts;
 pub mod datasets;
+pub mod distributed;
 pub mod error;
 pub mod handlers;
+pub mod object_storage;
 pub mod ogc;
 pub mod projects;
 pub mod server;
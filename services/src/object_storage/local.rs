@@ -0,0 +1,221 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt};
+use snafu::ResultExt;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio_util::codec::{BytesCodec, FramedRead};
+use url::Url;
+
+use super::{ObjectInfo, ObjectStorage, ObjectStream, ObjectWriter};
+use crate::error::{self, Result};
+
+/// Stores objects as files below a root directory
+pub struct LocalObjectStorage {
+    root: PathBuf,
+}
+
+impl LocalObjectStorage {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+#[async_trait]
+impl ObjectStorage for LocalObjectStorage {
+    async fn writer(&self, key: &str) -> Result<Box<dyn ObjectWriter>> {
+        let path = self.path(key);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await.context(error::Io)?;
+        }
+
+        let file = fs::File::create(&path).await.context(error::Io)?;
+
+        Ok(Box::new(LocalObjectWriter {
+            path,
+            file,
+            byte_size: 0,
+        }))
+    }
+
+    async fn read(&self, key: &str) -> Result<Bytes> {
+        let bytes = fs::read(self.path(key)).await.context(error::Io)?;
+        Ok(bytes.into())
+    }
+
+    /// Streams the whole file. Ranges are left to the caller, which can serve the local file directly.
+    async fn read_stream(&self, key: &str, _range: Option<&str>) -> Result<ObjectStream> {
+        let file = fs::File::open(self.path(key)).await.context(error::Io)?;
+
+        Ok(ObjectStream {
+            chunks: FramedRead::new(file, BytesCodec::new())
+                .map_ok(bytes::BytesMut::freeze)
+                .map_err(|source| error::Error::Io { source })
+                .boxed(),
+            content_range: None,
+        })
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        fs::remove_file(self.path(key)).await.context(error::Io)
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<ObjectInfo>> {
+        let mut objects = vec![];
+        let mut directories = vec![self.path(prefix)];
+
+        while let Some(directory) = directories.pop() {
+            let mut entries = match fs::read_dir(&directory).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e).context(error::Io),
+            };
+
+            while let Some(entry) = entries.next_entry().await.context(error::Io)? {
+                let path = entry.path();
+                let metadata = entry.metadata().await.context(error::Io)?;
+
+                if metadata.is_dir() {
+                    directories.push(path);
+                    continue;
+                }
+
+                let key = match path.strip_prefix(&self.root) {
+                    Ok(relative_path) => relative_path
+                        .iter()
+                        .map(|component| component.to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/"),
+                    Err(_) => continue,
+                };
+
+                objects.push(ObjectInfo {
+                    key,
+                    last_modified: DateTime::<Utc>::from(metadata.modified().context(error::Io)?),
+                });
+            }
+        }
+
+        Ok(objects)
+    }
+
+    fn gdal_path(&self, key: &str) -> PathBuf {
+        self.path(key)
+    }
+
+    fn local_path(&self, key: &str) -> Option<PathBuf> {
+        Some(self.path(key))
+    }
+
+    fn presigned_download_url(&self, _key: &str) -> Result<Option<Url>> {
+        Ok(None)
+    }
+
+    async fn put_file(&self, key: &str, file_path: &Path) -> Result<u64> {
+        let path = self.path(key);
+
+        if path == file_path {
+            let metadata = fs::metadata(&path).await.context(error::Io)?;
+            return Ok(metadata.len());
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await.context(error::Io)?;
+        }
+
+        fs::copy(file_path, path).await.context(error::Io)
+    }
+}
+
+struct LocalObjectWriter {
+    path: PathBuf,
+    file: fs::File,
+    byte_size: u64,
+}
+
+#[async_trait]
+impl ObjectWriter for LocalObjectWriter {
+    async fn write(&mut self, bytes: Bytes) -> Result<()> {
+        self.file.write_all(&bytes).await.context(error::Io)?;
+        self.byte_size += bytes.len() as u64;
+        Ok(())
+    }
+
+    async fn finish(mut self: Box<Self>) -> Result<u64> {
+        self.file.flush().await.context(error::Io)?;
+        Ok(self.byte_size)
+    }
+
+    async fn abort(self: Box<Self>) -> Result<()> {
+        drop(self.file);
+        fs::remove_file(self.path).await.context(error::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn it_writes_and_reads_objects() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalObjectStorage::new(dir.path().to_path_buf());
+
+        let mut writer = storage.writer("upload/file.txt").await.unwrap();
+        writer.write(Bytes::from_static(b"foo")).await.unwrap();
+        writer.write(Bytes::from_static(b"bar")).await.unwrap();
+        assert_eq!(writer.finish().await.unwrap(), 6);
+
+        assert_eq!(
+            storage.read("upload/file.txt").await.unwrap(),
+            Bytes::from_static(b"foobar")
+        );
+        assert_eq!(
+            storage.gdal_path("upload/file.txt"),
+            dir.path().join("upload/file.txt")
+        );
+        assert!(storage
+            .presigned_download_url("upload/file.txt")
+            .unwrap()
+            .is_none());
+
+        storage.delete("upload/file.txt").await.unwrap();
+        assert!(storage.read("upload/file.txt").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_lists_objects() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalObjectStorage::new(dir.path().to_path_buf());
+
+        for key in [
+            "exports/a/file.tiff",
+            "exports/b/file.zip",
+            "upload/file.txt",
+        ] {
+            let writer = storage.writer(key).await.unwrap();
+            writer.finish().await.unwrap();
+        }
+
+        let mut keys: Vec<String> = storage
+            .list("exports/")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|object| object.key)
+            .collect();
+        keys.sort();
+
+        assert_eq!(keys, vec!["exports/a/file.tiff", "exports/b/file.zip"]);
+
+        assert!(storage.list("missing/").await.unwrap().is_empty());
+    }
+}
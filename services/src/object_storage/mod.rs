@@ -0,0 +1,130 @@
+//! Storage for uploads and materialized results.
+//!
+//! Depending on the deployment, objects are stored on the local file system or in an
+//! S3 compatible object storage.
+//! Objects are identified by keys like `{upload_id}/{file_name}`.
+
+mod local;
+mod s3;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use tokio::io::AsyncReadExt;
+use url::Url;
+
+use crate::error::{self, Result};
+use crate::util::config::{self, get_config_element};
+use snafu::ResultExt;
+
+pub use local::LocalObjectStorage;
+pub use s3::{S3Credentials, S3ObjectStorage};
+
+/// Size of the chunks in which local files are copied into the storage
+const FILE_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+#[async_trait]
+pub trait ObjectStorage: Send + Sync {
+    /// Creates a writer for the object with the given key.
+    /// The object replaces an existing object with the same key once the writer is finished.
+    async fn writer(&self, key: &str) -> Result<Box<dyn ObjectWriter>>;
+
+    async fn read(&self, key: &str) -> Result<Bytes>;
+
+    /// Reads the object in chunks without loading it into memory.
+    /// If given, `range` is the value of an HTTP `Range` header that storages may apply.
+    async fn read_stream(&self, key: &str, range: Option<&str>) -> Result<ObjectStream>;
+
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    /// Lists the objects below `prefix`, which denotes a directory like `exports/`
+    async fn list(&self, prefix: &str) -> Result<Vec<ObjectInfo>>;
+
+    /// A path to the object that GDAL and OGR can open, e.g., using a virtual file system
+    fn gdal_path(&self, key: &str) -> PathBuf;
+
+    /// The path to the object on the local file system if the storage is local
+    fn local_path(&self, key: &str) -> Option<PathBuf>;
+
+    /// A URL that allows downloading the object without further authentication,
+    /// if the storage supports it
+    fn presigned_download_url(&self, key: &str) -> Result<Option<Url>>;
+
+    /// Copies a local file into the storage and returns its size in bytes
+    async fn put_file(&self, key: &str, file_path: &Path) -> Result<u64> {
+        let mut file = tokio::fs::File::open(file_path).await.context(error::Io)?;
+        let mut writer = self.writer(key).await?;
+
+        loop {
+            let mut buffer = Vec::with_capacity(FILE_CHUNK_SIZE);
+            let read = (&mut file)
+                .take(FILE_CHUNK_SIZE as u64)
+                .read_to_end(&mut buffer)
+                .await
+                .context(error::Io)?;
+
+            if read == 0 {
+                break;
+            }
+
+            writer.write(buffer.into()).await?;
+        }
+
+        writer.finish().await
+    }
+}
+
+/// An object in the storage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectInfo {
+    pub key: String,
+    pub last_modified: DateTime<Utc>,
+}
+
+/// The chunks of an object that is read from the storage
+pub struct ObjectStream {
+    pub chunks: BoxStream<'static, Result<Bytes>>,
+    /// The value of the `Content-Range` header if only a range of the object is read
+    pub content_range: Option<String>,
+}
+
+/// Writes an object in chunks, e.g., from a streamed upload
+#[async_trait]
+pub trait ObjectWriter: Send {
+    async fn write(&mut self, bytes: Bytes) -> Result<()>;
+
+    /// Completes the object and returns its size in bytes
+    async fn finish(self: Box<Self>) -> Result<u64>;
+
+    /// Discards everything that was written so far
+    async fn abort(self: Box<Self>) -> Result<()>;
+}
+
+/// Creates the object storage that is configured for this deployment
+///
+/// # Panics
+/// Panics if the configuration is invalid
+pub fn object_storage_from_config() -> Arc<dyn ObjectStorage> {
+    let storage_config = get_config_element::<config::ObjectStorage>().expect("config error");
+
+    match storage_config.backend {
+        config::ObjectStorageBackend::Local => {
+            let upload_path = get_config_element::<config::Upload>()
+                .expect("config error")
+                .path;
+
+            Arc::new(LocalObjectStorage::new(upload_path))
+        }
+        config::ObjectStorageBackend::S3 => {
+            let storage = S3ObjectStorage::new(storage_config.s3);
+
+            storage.configure_gdal();
+
+            Arc::new(storage)
+        }
+    }
+}
@@ -1,8 +1,10 @@
-use chrono::FixedOffset;
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime};
 use geoengine_datatypes::primitives::{AxisAlignedRectangle, BoundingBox2D};
-use geoengine_datatypes::primitives::{Coordinate2D, SpatialResolution, TimeInterval};
+use geoengine_datatypes::primitives::{
+    Coordinate2D, MultiPolygon, SpatialResolution, TimeInstance, TimeInterval,
+};
 use geoengine_datatypes::spatial_reference::SpatialReference;
-use serde::de::Error;
+use serde::de::{Error, IntoDeserializer};
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
 use std::str::FromStr;
@@ -45,6 +47,42 @@ where
     }
 }
 
+/// Parse an optional bbox, format is: "x1,y1,x2,y2"
+pub fn parse_bbox_option<'de, D>(deserializer: D) -> Result<Option<BoundingBox2D>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+
+    if s.is_empty() {
+        return Ok(None);
+    }
+
+    let deserializer: serde::de::value::StrDeserializer<D::Error> = s.as_str().into_deserializer();
+    parse_bbox(deserializer).map(Some)
+}
+
+/// Serialize an optional bbox in the format of [`parse_bbox_option`]
+#[allow(clippy::ref_option_ref, clippy::trivially_copy_pass_by_ref)] // signature required by serde
+pub fn serialize_bbox_option<S>(
+    bbox: &Option<BoundingBox2D>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match bbox {
+        Some(bbox) => serializer.serialize_some(&format!(
+            "{},{},{},{}",
+            bbox.lower_left().x,
+            bbox.lower_left().y,
+            bbox.upper_right().x,
+            bbox.upper_right().y
+        )),
+        None => serializer.serialize_none(),
+    }
+}
+
 /// Parse bbox, format is: "x1,y1,x2,y2"
 pub fn parse_ogc_bbox<'de, D>(deserializer: D) -> Result<OgcBoundingBox, D::Error>
 where
@@ -82,6 +120,26 @@ where
     parse_time_from_str::<D>(&s).map(Some)
 }
 
+/// Serialize an optional time interval in the format of [`parse_time_option`]
+#[allow(clippy::ref_option_ref, clippy::trivially_copy_pass_by_ref)] // signature required by serde
+pub fn serialize_time_option<S>(
+    time: &Option<TimeInterval>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match time {
+        Some(time) if time.is_instant() => serializer.serialize_some(&time.start().as_rfc3339()),
+        Some(time) => serializer.serialize_some(&format!(
+            "{}/{}",
+            time.start().as_rfc3339(),
+            time.end().as_rfc3339()
+        )),
+        None => serializer.serialize_none(),
+    }
+}
+
 /// Parse the time string of a WMS request
 /// time is specified in ISO8601, it can either be an instant (single datetime) or an interval
 /// An interval is separated by "/". "Either the start value or the end value can be omitted to
@@ -102,19 +160,186 @@ fn parse_time_from_str<'de, D>(s: &str) -> Result<TimeInterval, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    let split: Vec<_> = s
-        .split('/')
+    OgcTime::from_str(s)
+        .and_then(|time| time.time_interval(None))
+        .map_err(D::Error::custom)
+}
+
+/// The time of a WMS or WFS request, i.e., an instant or an interval.
+///
+/// Date times without an offset are local to the (optional) time zone of the request and
+/// are resolved by [`OgcTime::time_interval`]. Without a time zone, they are in UTC.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct OgcTime {
+    start: OgcDateTime,
+    end: OgcDateTime,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
+enum OgcDateTime {
+    Absolute(TimeInstance),
+    Local(NaiveDateTime),
+}
+
+impl OgcDateTime {
+    fn time_instance(self, time_zone: Option<FixedOffset>) -> Result<TimeInstance> {
+        match self {
+            OgcDateTime::Absolute(time_instance) => Ok(time_instance),
+            OgcDateTime::Local(date_time) => Ok(TimeInstance::from_local_date_time(
+                date_time,
+                &time_zone.unwrap_or_else(|| FixedOffset::east(0)),
+            )?),
+        }
+    }
+}
+
+impl FromStr for OgcDateTime {
+    type Err = error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         // use `from_str` instead of `parse_from_rfc3339` to use a relaxed form of RFC3339 that supports dates BC
-        .map(chrono::DateTime::<FixedOffset>::from_str)
-        .collect();
+        if let Ok(date_time) = chrono::DateTime::<FixedOffset>::from_str(s) {
+            return Ok(OgcDateTime::Absolute(
+                date_time.with_timezone(&chrono::Utc).into(),
+            ));
+        }
 
-    match *split.as_slice() {
-        [Ok(time)] => TimeInterval::new(time.timestamp_millis(), time.timestamp_millis())
-            .map_err(D::Error::custom),
-        [Ok(start), Ok(end)] => TimeInterval::new(start.timestamp_millis(), end.timestamp_millis())
-            .map_err(D::Error::custom),
-        _ => Err(D::Error::custom(format!("Invalid time {}", s))),
+        NaiveDateTime::from_str(s)
+            .or_else(|_| NaiveDate::from_str(s).map(|date| date.and_hms(0, 0, 0)))
+            .map(OgcDateTime::Local)
+            .map_err(|_| error::Error::InvalidOgcTime {
+                time: s.to_string(),
+            })
+    }
+}
+
+impl OgcTime {
+    /// Resolves the time interval where date times without offset are in the `time_zone`
+    pub fn time_interval(self, time_zone: Option<FixedOffset>) -> Result<TimeInterval> {
+        Ok(TimeInterval::new(
+            self.start.time_instance(time_zone)?,
+            self.end.time_instance(time_zone)?,
+        )?)
+    }
+}
+
+impl FromStr for OgcTime {
+    type Err = error::Error;
+
+    /// Parses an instant or an interval `start/end`.
+    ///
+    /// The start or the end of an interval can be omitted or set to `..` for an open-ended interval.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn bound(s: &str, unbounded: TimeInstance) -> Result<OgcDateTime> {
+            if s.is_empty() || s == ".." {
+                Ok(OgcDateTime::Absolute(unbounded))
+            } else {
+                OgcDateTime::from_str(s)
+            }
+        }
+
+        let split: Vec<&str> = s.split('/').collect();
+
+        match *split.as_slice() {
+            [time] => {
+                let time = OgcDateTime::from_str(time)?;
+                Ok(OgcTime {
+                    start: time,
+                    end: time,
+                })
+            }
+            [start, end] => Ok(OgcTime {
+                start: bound(start, TimeInstance::MIN)?,
+                end: bound(end, TimeInstance::MAX)?,
+            }),
+            _ => Err(error::Error::InvalidOgcTime {
+                time: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl From<TimeInterval> for OgcTime {
+    fn from(time_interval: TimeInterval) -> Self {
+        Self {
+            start: OgcDateTime::Absolute(time_interval.start()),
+            end: OgcDateTime::Absolute(time_interval.end()),
+        }
+    }
+}
+
+/// Parse the time string of a WMS or WFS request without resolving date times without offset
+pub fn parse_ogc_time_option<'de, D>(deserializer: D) -> Result<Option<OgcTime>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+
+    if s.is_empty() {
+        return Ok(None);
+    }
+
+    OgcTime::from_str(&s).map(Some).map_err(D::Error::custom)
+}
+
+/// Parse a time zone offset, format is "Z", "UTC" or "[+-]hh[:mm]"
+pub fn parse_time_zone_option<'de, D>(deserializer: D) -> Result<Option<FixedOffset>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+
+    if s.is_empty() {
+        return Ok(None);
+    }
+
+    parse_time_zone(&s)
+        .map(Some)
+        .ok_or_else(|| D::Error::custom(format!("Invalid time zone {}", s)))
+}
+
+/// Serialize a time zone offset in the format of [`parse_time_zone_option`]
+#[allow(clippy::ref_option_ref, clippy::trivially_copy_pass_by_ref)] // signature required by serde
+pub fn serialize_time_zone_option<S>(
+    time_zone: &Option<FixedOffset>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match time_zone {
+        Some(time_zone) => serializer.serialize_some(&time_zone.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn parse_time_zone(s: &str) -> Option<FixedOffset> {
+    if s == "Z" || s.eq_ignore_ascii_case("UTC") {
+        return Some(FixedOffset::east(0));
+    }
+
+    let (sign, offset) = if let Some(offset) = s.strip_prefix('+') {
+        (1, offset)
+    } else if let Some(offset) = s.strip_prefix('-') {
+        (-1, offset)
+    } else {
+        return None;
+    };
+
+    let (hours, minutes) = match offset.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if offset.len() == 4 => offset.split_at(2),
+        None => (offset, "0"),
+    };
+
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+
+    if !(0..24).contains(&hours) || !(0..60).contains(&minutes) {
+        return None;
     }
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
 }
 
 /// Parse a spatial resolution, format is: "resolution" or "xResolution,yResolution"
@@ -145,6 +370,28 @@ where
     Ok(Some(spatial_resolution))
 }
 
+/// Parse a polygonal spatial filter, format is a `GeoJSON` `Polygon` or `MultiPolygon` geometry
+pub fn parse_spatial_filter_polygon_option<'de, D>(
+    deserializer: D,
+) -> Result<Option<MultiPolygon>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+
+    if s.is_empty() {
+        return Ok(None);
+    }
+
+    let geometry = geojson::GeoJson::from_str(&s)
+        .and_then(geojson::Geometry::try_from)
+        .map_err(D::Error::custom)?;
+
+    MultiPolygon::try_from(geometry.value)
+        .map(Some)
+        .map_err(D::Error::custom)
+}
+
 /// Parse wcs 1.1.1 bbox, format is: "x1,y1,x2,y2,crs", crs format is like `urn:ogc:def:crs:EPSG::4326`
 pub fn parse_wcs_bbox<'de, D>(deserializer: D) -> Result<WcsBoundingbox, D::Error>
 where
@@ -244,6 +491,23 @@ pub fn rectangle_from_ogc_params<A: AxisAlignedRectangle>(
     }
 }
 
+/// create the values "a,b,c,d" of OGC bbox-like parameters for a rectangle using the axis ordering for `spatial_reference`
+pub fn ogc_params_from_rectangle<A: AxisAlignedRectangle>(
+    rectangle: A,
+    spatial_reference: SpatialReference,
+) -> Result<[f64; 4]> {
+    let min = rectangle.lower_left();
+    let max = rectangle.upper_right();
+    match spatial_reference_specification(&spatial_reference.proj_string()?)?
+        .axis_order
+        .ok_or(error::Error::AxisOrderingNotKnownForSrs {
+            srs_string: spatial_reference.srs_string(),
+        })? {
+        AxisOrder::EastNorth => Ok([min.x, min.y, max.x, max.y]),
+        AxisOrder::NorthEast => Ok([min.y, min.x, max.y, max.x]),
+    }
+}
+
 /// reorders the given tuple of coordinates, resolutions, etc. using the axis ordering for `spatial_reference` to give (x, y)
 pub fn tuple_from_ogc_params(
     a: f64,
@@ -269,6 +533,35 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn parse_spatial_filter_polygon() {
+        assert_eq!(
+            parse_spatial_filter_polygon_option(to_deserializer(
+                r#"{"type":"Polygon","coordinates":[[[0,0],[10,0],[0,10],[0,0]]]}"#
+            ))
+            .unwrap(),
+            Some(
+                MultiPolygon::new(vec![vec![vec![
+                    (0.0, 0.0).into(),
+                    (10.0, 0.0).into(),
+                    (0.0, 10.0).into(),
+                    (0.0, 0.0).into(),
+                ]]])
+                .unwrap()
+            )
+        );
+
+        assert_eq!(
+            parse_spatial_filter_polygon_option(to_deserializer("")).unwrap(),
+            None
+        );
+
+        assert!(parse_spatial_filter_polygon_option(to_deserializer(
+            r#"{"type":"Point","coordinates":[0,0]}"#
+        ))
+        .is_err());
+    }
+
     #[test]
     fn parse_time_normal() {
         assert_eq!(
@@ -347,6 +640,100 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_time_without_offset() {
+        assert_eq!(
+            TimeInterval::new_instant(Utc.ymd(2014, 4, 1).and_hms_milli(12, 0, 0, 0)).unwrap(),
+            parse_time(to_deserializer("2014-04-01T12:00:00.000")).unwrap()
+        );
+        assert_eq!(
+            TimeInterval::new(
+                Utc.ymd(2014, 1, 1).and_hms_milli(0, 0, 0, 0),
+                Utc.ymd(2015, 1, 1).and_hms_milli(0, 0, 0, 0)
+            )
+            .unwrap(),
+            parse_time(to_deserializer("2014-01-01/2015-01-01")).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_time_open_ended() {
+        let start = TimeInstance::from(Utc.ymd(2014, 4, 1).and_hms_milli(12, 0, 0, 0));
+
+        assert_eq!(
+            parse_time(to_deserializer("2014-04-01T12:00:00Z/")).unwrap(),
+            TimeInterval::new(start, TimeInstance::MAX).unwrap()
+        );
+        assert_eq!(
+            parse_time(to_deserializer("2014-04-01T12:00:00Z/..")).unwrap(),
+            TimeInterval::new(start, TimeInstance::MAX).unwrap()
+        );
+        assert_eq!(
+            parse_time(to_deserializer("../2014-04-01T12:00:00Z")).unwrap(),
+            TimeInterval::new(TimeInstance::MIN, start).unwrap()
+        );
+        assert_eq!(
+            parse_time(to_deserializer("/")).unwrap(),
+            TimeInterval::default()
+        );
+        assert_eq!(
+            parse_ogc_time_option(to_deserializer("2014-04-01T12:00:00/.."))
+                .unwrap()
+                .unwrap()
+                .time_interval(Some(FixedOffset::east(2 * 3600)))
+                .unwrap(),
+            TimeInterval::new(
+                Utc.ymd(2014, 4, 1).and_hms_milli(10, 0, 0, 0),
+                TimeInstance::MAX
+            )
+            .unwrap()
+        );
+
+        assert!(parse_time(to_deserializer("..")).is_err());
+    }
+
+    #[test]
+    fn ogc_time_in_time_zone() {
+        let time =
+            parse_ogc_time_option(to_deserializer("2014-04-01T12:00:00/2014-04-01T12:00:00Z"))
+                .unwrap()
+                .unwrap();
+
+        assert_eq!(
+            time.time_interval(Some(FixedOffset::east(2 * 3600)))
+                .unwrap(),
+            TimeInterval::new(
+                Utc.ymd(2014, 4, 1).and_hms_milli(10, 0, 0, 0),
+                Utc.ymd(2014, 4, 1).and_hms_milli(12, 0, 0, 0)
+            )
+            .unwrap()
+        );
+        assert!(time
+            .time_interval(Some(FixedOffset::west(2 * 3600)))
+            .is_err());
+    }
+
+    #[test]
+    fn parse_time_zones() {
+        for (s, seconds) in [
+            ("Z", 0),
+            ("utc", 0),
+            ("+02:00", 7200),
+            ("-05:30", -19800),
+            ("+0100", 3600),
+            ("-3", -10800),
+        ] {
+            assert_eq!(
+                parse_time_zone_option(to_deserializer(s)).unwrap(),
+                Some(FixedOffset::east(seconds))
+            );
+        }
+
+        assert_eq!(parse_time_zone_option(to_deserializer("")).unwrap(), None);
+        assert!(parse_time_zone_option(to_deserializer("02:00")).is_err());
+        assert!(parse_time_zone_option(to_deserializer("+25:00")).is_err());
+    }
+
     fn to_deserializer(s: &str) -> StringDeserializer<serde::de::value::Error> {
         s.to_owned().into_deserializer()
     }
@@ -6,6 +6,7 @@ use crate::ogc::util::{
 use crate::util::from_str_option;
 use geoengine_datatypes::primitives::{Coordinate2D, SpatialPartition2D, SpatialResolution};
 use geoengine_datatypes::{primitives::TimeInterval, spatial_reference::SpatialReference};
+use geoengine_operators::engine::RasterErrorPolicy;
 use serde::de::Error;
 use serde::{Deserialize, Serialize};
 use snafu::ResultExt;
@@ -77,6 +78,11 @@ pub struct GetCoverage {
     #[serde(default)]
     #[serde(deserialize_with = "from_str_option")]
     resy: Option<f64>,
+
+    /// Vendor parameter for handling corrupt or missing files of raster sources
+    #[serde(default)]
+    #[serde(alias = "ERROR_POLICY")]
+    pub error_policy: Option<RasterErrorPolicy>,
 }
 
 impl GetCoverage {
@@ -231,7 +237,8 @@ mod tests {
                 }),
                 time: Some(TimeInterval::new_instant(1_388_534_400_000).unwrap()),
                 resx: None,
-                resy: None
+                resy: None,
+                error_policy: None,
             },
             coverage
         );
@@ -0,0 +1,201 @@
+use bytes::Bytes;
+use chrono::FixedOffset;
+use futures::{Stream, StreamExt};
+use geoengine_datatypes::collections::{FeatureCollection, FeatureCollectionInfos, ToGeoJson};
+use geoengine_datatypes::primitives::{Geometry, VectorQueryRectangle};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use geoengine_operators::engine::{QueryContext, VectorQueryProcessor};
+use tokio::sync::mpsc;
+
+use crate::error::Result;
+use crate::util::query_preview::QueryPreview;
+
+/// The number of serialized chunks that are buffered until the response consumed them
+const CHANNEL_CAPACITY: usize = 16;
+
+// the keys are in the same (alphabetical) order as the ones of the serialized features
+const PREFIX: &[u8] = br#"{"features":["#;
+const SEPARATOR: &[u8] = b",";
+const SUFFIX: &[u8] = br#"],"type":"FeatureCollection"}"#;
+
+/// Serializes the result of a vector query as a `GeoJSON` feature collection chunk by chunk.
+///
+/// Each collection of the query stream becomes one chunk of features, s.t. the result is never
+/// held in memory as a whole. The query runs in its own task that owns the processor and the
+/// query context. It stops as soon as the returned stream is dropped, e.g., if the client
+/// disconnects.
+///
+/// Errors that occur before the first chunk are the first item of the stream. Errors of later
+/// collections end the stream after the chunks that were already serialized.
+pub fn geo_json_stream<G, Q>(
+    processor: Box<dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>>,
+    query_rect: VectorQueryRectangle,
+    query_ctx: Q,
+    time_zone: FixedOffset,
+    preview: Option<QueryPreview>,
+) -> impl Stream<Item = Result<Bytes>> + Send + 'static
+where
+    G: Geometry + ArrowTyped + 'static,
+    for<'c> FeatureCollection<G>: ToGeoJson<'c>,
+    Q: QueryContext + 'static,
+{
+    let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        let result = send_features(
+            processor.as_ref(),
+            query_rect,
+            &query_ctx,
+            &time_zone,
+            preview.as_ref(),
+            &sender,
+        )
+        .await;
+
+        if let Err(error) = result {
+            // the response may already be gone, so there is nobody to report the error to
+            let _ = sender.send(Err(error)).await;
+        }
+    });
+
+    futures::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    })
+}
+
+/// Sends the serialized features until the query is exhausted or the receiver was dropped
+async fn send_features<G>(
+    processor: &dyn VectorQueryProcessor<VectorType = FeatureCollection<G>>,
+    query_rect: VectorQueryRectangle,
+    query_ctx: &dyn QueryContext,
+    time_zone: &FixedOffset,
+    preview: Option<&QueryPreview>,
+    sender: &mpsc::Sender<Result<Bytes>>,
+) -> Result<()>
+where
+    G: Geometry + ArrowTyped + 'static,
+    for<'c> FeatureCollection<G>: ToGeoJson<'c>,
+{
+    // if the results are known to have no features, the empty collection is returned without querying
+    let stream = if processor
+        .vector_query_is_empty(query_rect.clone(), query_ctx)
+        .await?
+    {
+        futures::stream::empty().boxed()
+    } else {
+        processor.vector_query(query_rect, query_ctx).await?
+    };
+
+    let mut stream = match preview {
+        Some(preview) => preview.sample_features(stream),
+        None => stream,
+    };
+
+    if sender.send(Ok(Bytes::from_static(PREFIX))).await.is_err() {
+        return Ok(());
+    }
+
+    let mut is_first_feature = true;
+
+    while let Some(collection) = stream.next().await {
+        let collection = collection?;
+
+        if collection.is_empty() {
+            continue;
+        }
+
+        let mut chunk = Vec::new();
+        for feature in collection.to_geo_json_features(time_zone) {
+            if is_first_feature {
+                is_first_feature = false;
+            } else {
+                chunk.extend_from_slice(SEPARATOR);
+            }
+            serde_json::to_writer(&mut chunk, &feature)?;
+        }
+
+        if sender.send(Ok(chunk.into())).await.is_err() {
+            return Ok(());
+        }
+    }
+
+    // the receiver may be gone, but there is nothing left to do anyway
+    let _ = sender.send(Ok(Bytes::from_static(SUFFIX))).await;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+    use geoengine_datatypes::collections::MultiPointCollection;
+    use geoengine_datatypes::primitives::{
+        BoundingBox2D, MultiPoint, SpatialResolution, TimeInterval,
+    };
+    use geoengine_operators::engine::{
+        ChunkByteSize, MockExecutionContext, MockQueryContext, TypedVectorQueryProcessor,
+        VectorOperator,
+    };
+    use geoengine_operators::mock::MockFeatureCollectionSource;
+
+    #[tokio::test]
+    async fn it_streams_features_of_all_collections() {
+        let collection = |coordinates: Vec<(f64, f64)>| {
+            MultiPointCollection::from_data(
+                MultiPoint::many(coordinates.clone()).unwrap(),
+                vec![TimeInterval::new_unchecked(0, 1); coordinates.len()],
+                Default::default(),
+            )
+            .unwrap()
+        };
+
+        let source = MockFeatureCollectionSource::multiple(vec![
+            collection(vec![(0.0, 0.1)]),
+            MultiPointCollection::empty(),
+            collection(vec![(1.0, 1.1), (2.0, 2.1)]),
+        ])
+        .boxed()
+        .initialize(&MockExecutionContext::test_default())
+        .await
+        .unwrap();
+
+        let processor = match source.query_processor().unwrap() {
+            TypedVectorQueryProcessor::MultiPoint(processor) => processor,
+            _ => unreachable!(),
+        };
+
+        let chunks: Vec<Bytes> = geo_json_stream(
+            processor,
+            VectorQueryRectangle {
+                spatial_bounds: BoundingBox2D::new((0., 0.).into(), (3., 3.).into()).unwrap(),
+                time_interval: TimeInterval::default(),
+                spatial_resolution: SpatialResolution::zero_point_one(),
+                spatial_filter: None,
+            },
+            MockQueryContext::new(ChunkByteSize::MIN),
+            FixedOffset::east(0),
+            None,
+        )
+        .try_collect()
+        .await
+        .unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&chunks.concat()).unwrap();
+
+        assert_eq!(json["type"], "FeatureCollection");
+        assert_eq!(
+            json["features"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|feature| feature["geometry"]["coordinates"].clone())
+                .collect::<Vec<_>>(),
+            vec![
+                serde_json::json!([0.0, 0.1]),
+                serde_json::json!([1.0, 1.1]),
+                serde_json::json!([2.0, 2.1]),
+            ]
+        );
+    }
+}
@@ -1 +1,2 @@
+pub mod geojson_stream;
 pub mod request;
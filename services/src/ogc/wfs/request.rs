@@ -1,6 +1,10 @@
-use crate::ogc::util::{parse_bbox, parse_spatial_resolution_option, parse_time_option};
-use crate::util::from_str_option;
-use geoengine_datatypes::primitives::{BoundingBox2D, SpatialResolution, TimeInterval};
+use crate::ogc::util::{
+    parse_bbox, parse_ogc_time_option, parse_spatial_filter_polygon_option,
+    parse_spatial_resolution_option, parse_time_zone_option, serialize_time_zone_option, OgcTime,
+};
+use crate::util::{bool_option_case_insensitive, from_str_option};
+use chrono::FixedOffset;
+use geoengine_datatypes::primitives::{BoundingBox2D, MultiPolygon, SpatialResolution};
 use geoengine_datatypes::spatial_reference::SpatialReference;
 use serde::{Deserialize, Serialize};
 
@@ -54,8 +58,8 @@ pub struct GetFeature {
     #[serde(deserialize_with = "parse_bbox")]
     pub bbox: BoundingBox2D,
     #[serde(default)]
-    #[serde(deserialize_with = "parse_time_option")]
-    pub time: Option<TimeInterval>,
+    #[serde(deserialize_with = "parse_ogc_time_option")]
+    pub time: Option<OgcTime>,
     pub srs_name: Option<SpatialReference>,
     pub namespaces: Option<String>, // TODO e.g. xmlns(dog=http://www.example.com/namespaces/dog)
     #[serde(default)]
@@ -70,6 +74,20 @@ pub struct GetFeature {
     #[serde(default)]
     #[serde(deserialize_with = "parse_spatial_resolution_option")]
     pub query_resolution: Option<SpatialResolution>,
+    /// Vendor parameter for refining the `bbox` by a `GeoJSON` polygon in the request's `srsName`
+    #[serde(default)]
+    #[serde(deserialize_with = "parse_spatial_filter_polygon_option")]
+    pub spatial_filter: Option<MultiPolygon>,
+    /// Vendor parameter for the time zone of `time` values without an offset and of the
+    /// times in the `GeoJSON` output, e.g., `+02:00`
+    #[serde(default)]
+    #[serde(deserialize_with = "parse_time_zone_option")]
+    #[serde(serialize_with = "serialize_time_zone_option")]
+    pub time_zone: Option<FixedOffset>,
+    /// Vendor parameter for a fast preview that contains a sample of the features
+    #[serde(default)]
+    #[serde(deserialize_with = "bool_option_case_insensitive")]
+    pub preview: Option<bool>,
 }
 
 #[derive(PartialEq, Debug, Deserialize, Serialize)]
@@ -138,7 +156,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use geoengine_datatypes::primitives::Coordinate2D;
+    use geoengine_datatypes::primitives::{Coordinate2D, TimeInterval};
     use geoengine_datatypes::spatial_reference::SpatialReferenceAuthority;
 
     #[test]
@@ -162,6 +180,9 @@ mod tests {
             },
             property_name: None,
             query_resolution: None,
+            spatial_filter: None,
+            time_zone: None,
+            preview: None,
         });
 
         assert_eq!(parsed, request);
@@ -190,13 +211,15 @@ mod tests {
 </Filter>"),
             ("propertyName","P1,P2"),
             ("queryResolution","0.1,0.1"),
+            ("timeZone","+01:00"),
+            ("preview","true"),
         ];
         let query = serde_urlencoded::to_string(params).unwrap();
         let parsed: WfsRequest = serde_urlencoded::from_str(&query).unwrap();
 
         let request = WfsRequest::GetFeature(GetFeature {
             version: "2.0.0".into(),
-            time: Some(TimeInterval::new(946_684_800_000, 946_771_200_000).unwrap()),
+            time: Some(TimeInterval::new(946_684_800_000, 946_771_200_000).unwrap().into()),
             srs_name: Some(SpatialReference::new(SpatialReferenceAuthority::Epsg, 4326)),
             namespaces: Some("xmlns(dog=http://www.example.com/namespaces/dog)".into()),
             count: Some(10),
@@ -215,6 +238,9 @@ mod tests {
             },
             property_name: Some("P1,P2".into()),
             query_resolution: Some(SpatialResolution::zero_point_one()),
+            spatial_filter: None,
+            time_zone: Some(FixedOffset::east(3600)),
+            preview: Some(true),
         });
 
         assert_eq!(parsed, request);
@@ -253,6 +279,9 @@ mod tests {
             },
             property_name: None,
             query_resolution: None,
+            spatial_filter: None,
+            time_zone: None,
+            preview: None,
         });
 
         assert_eq!(parsed, request);
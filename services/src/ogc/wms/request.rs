@@ -1,8 +1,13 @@
-use crate::ogc::util::{parse_ogc_bbox, parse_time_option, OgcBoundingBox};
+use crate::ogc::util::{
+    parse_ogc_bbox, parse_ogc_time_option, parse_time_zone_option, serialize_time_zone_option,
+    OgcBoundingBox, OgcTime,
+};
 use crate::util::{bool_option_case_insensitive, from_str};
-use geoengine_datatypes::primitives::TimeInterval;
+use chrono::FixedOffset;
 use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::{DimensionSelection, RasterErrorPolicy};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 // TODO: ignore case for field names
 
@@ -54,8 +59,14 @@ pub struct GetMap {
     pub styles: String,
     #[serde(default)]
     #[serde(alias = "TIME")]
-    #[serde(deserialize_with = "parse_time_option")]
-    pub time: Option<TimeInterval>,
+    #[serde(deserialize_with = "parse_ogc_time_option")]
+    pub time: Option<OgcTime>,
+    /// Vendor parameter for the time zone of `time` values without an offset, e.g., `+02:00`
+    #[serde(default)]
+    #[serde(alias = "TIME_ZONE")]
+    #[serde(deserialize_with = "parse_time_zone_option")]
+    #[serde(serialize_with = "serialize_time_zone_option")]
+    pub time_zone: Option<FixedOffset>,
     #[serde(alias = "TRANSPARENT")]
     #[serde(default)]
     #[serde(deserialize_with = "bool_option_case_insensitive")]
@@ -70,7 +81,54 @@ pub struct GetMap {
     pub elevation: Option<String>,
     #[serde(alias = "EXCEPTIONS")]
     pub exceptions: Option<String>, // TODO: parse Option<GetMapExceptionFormat>
-                                    // TODO: DIM_<name>
+    /// The values of `DIM_<name>` parameters by their lower case names, see `set_dimensions_from_query`
+    #[serde(skip_deserializing)]
+    pub dimensions: BTreeMap<String, String>,
+    /// Vendor parameter for handling corrupt or missing files of raster sources
+    #[serde(default)]
+    #[serde(alias = "ERROR_POLICY")]
+    pub error_policy: Option<RasterErrorPolicy>,
+    /// Vendor parameter for a fast preview that is computed at a coarser resolution
+    #[serde(default)]
+    #[serde(alias = "PREVIEW")]
+    #[serde(deserialize_with = "bool_option_case_insensitive")]
+    pub preview: Option<bool>,
+}
+
+impl GetMap {
+    /// Reads the `DIM_<name>` parameters of the `query` string since their names are not known in advance
+    pub fn set_dimensions_from_query(
+        &mut self,
+        query: &str,
+    ) -> Result<(), serde_urlencoded::de::Error> {
+        let parameters: Vec<(String, String)> = serde_urlencoded::from_str(query)?;
+
+        self.dimensions = parameters
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let key = key.to_lowercase();
+                key.strip_prefix("dim_")
+                    .map(|dimension| (dimension.to_string(), value))
+            })
+            .collect();
+
+        Ok(())
+    }
+
+    /// The selected values of the `elevation` and custom dimensions
+    pub fn dimension_selection(&self) -> DimensionSelection {
+        let mut selection = DimensionSelection::default();
+
+        if let Some(elevation) = &self.elevation {
+            selection.insert("elevation", elevation.clone());
+        }
+
+        for (dimension, value) in &self.dimensions {
+            selection.insert(dimension, value.clone());
+        }
+
+        selection
+    }
 }
 
 #[derive(PartialEq, Debug, Deserialize, Serialize)]
@@ -80,8 +138,17 @@ pub enum GetMapExceptionFormat {
 
 #[derive(PartialEq, Debug, Deserialize, Serialize)]
 pub enum GetMapFormat {
-    #[serde(rename = "image/png")]
-    ImagePng, // TODO: remaining formats
+    #[serde(rename = "image/png", alias = "image/png; mode=8bit")]
+    ImagePng,
+    /// PNG with 16 bits per channel for subtle differences of continuous values
+    #[serde(rename = "image/png; mode=16bit")]
+    ImagePng16Bit,
+    /// Lossy JPEG for opaque layers, e.g., satellite imagery
+    #[serde(rename = "image/jpeg")]
+    ImageJpeg,
+    /// Lossy WebP for opaque layers, e.g., satellite imagery
+    #[serde(rename = "image/webp")]
+    ImageWebp, // TODO: remaining formats
 }
 
 #[derive(PartialEq, Debug, Deserialize, Serialize)]
@@ -113,11 +180,12 @@ pub struct GetLegendGraphic {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use geoengine_datatypes::primitives::TimeInterval;
     use geoengine_datatypes::spatial_reference::SpatialReference;
 
     #[test]
     fn deserialize_get_map() {
-        let query = "request=GetMap&service=WMS&version=1.3.0&layers=modis_ndvi&bbox=1,2,3,4&width=2&height=2&crs=EPSG:4326&styles=ssss&format=image/png&time=2000-01-01T00:00:00.0Z/2000-01-02T00:00:00.0Z&transparent=true&bgcolor=#000000&sld=sld_spec&sld_body=sld_body&elevation=elevation&exceptions=exceptions";
+        let query = "request=GetMap&service=WMS&version=1.3.0&layers=modis_ndvi&bbox=1,2,3,4&width=2&height=2&crs=EPSG:4326&styles=ssss&format=image/png&time=2000-01-01T00:00:00.0Z/2000-01-02T00:00:00.0Z&transparent=true&bgcolor=#000000&sld=sld_spec&sld_body=sld_body&elevation=elevation&exceptions=exceptions&error_policy=skipSlice&preview=true";
         let parsed: WmsRequest = serde_urlencoded::from_str(query).unwrap();
 
         let request = WmsRequest::GetMap(GetMap {
@@ -126,7 +194,12 @@ mod tests {
             layers: "modis_ndvi".into(),
             crs: Some(SpatialReference::epsg_4326()),
             styles: "ssss".into(),
-            time: Some(TimeInterval::new(946_684_800_000, 946_771_200_000).unwrap()),
+            time: Some(
+                TimeInterval::new(946_684_800_000, 946_771_200_000)
+                    .unwrap()
+                    .into(),
+            ),
+            time_zone: None,
             transparent: Some(true),
             bgcolor: Some("#000000".into()),
             sld: Some("sld_spec".into()),
@@ -136,6 +209,9 @@ mod tests {
             height: 2,
             format: GetMapFormat::ImagePng,
             exceptions: Some("exceptions".into()),
+            dimensions: BTreeMap::new(),
+            error_policy: Some(RasterErrorPolicy::SkipSlice),
+            preview: Some(true),
         });
 
         assert_eq!(parsed, request);
@@ -153,6 +229,7 @@ mod tests {
             crs: SpatialReference::epsg_4326().into(),
             styles: "ssss".into(),
             time: None,
+            time_zone: None,
             transparent: None,
             bgcolor: None,
             sld: None,
@@ -162,8 +239,56 @@ mod tests {
             height: 2,
             format: GetMapFormat::ImagePng,
             exceptions: None,
+            dimensions: BTreeMap::new(),
+            error_policy: None,
+            preview: None,
         });
 
         assert_eq!(parsed, request);
     }
+
+    #[test]
+    fn deserialize_get_map_with_time_zone() {
+        let query = "request=GetMap&service=WMS&version=1.3.0&layers=modis_ndvi&bbox=1,2,3,4&width=2&height=2&crs=EPSG:4326&styles=ssss&format=image/png&time=2000-01-01T02:00:00&time_zone=%2B02:00";
+        let parsed: WmsRequest = serde_urlencoded::from_str(query).unwrap();
+
+        let request = match parsed {
+            WmsRequest::GetMap(request) => request,
+            _ => panic!("expected GetMap"),
+        };
+
+        assert_eq!(request.time_zone, Some(FixedOffset::east(2 * 3600)));
+        assert_eq!(
+            request
+                .time
+                .unwrap()
+                .time_interval(request.time_zone)
+                .unwrap(),
+            TimeInterval::new_instant(946_684_800_000).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_parses_dimensions() {
+        let query = "request=GetMap&service=WMS&version=1.3.0&layers=modis_ndvi&bbox=1,2,3,4&width=2&height=2&crs=EPSG:4326&styles=ssss&format=image/png&elevation=850&DIM_ENSEMBLE=3";
+        let parsed: WmsRequest = serde_urlencoded::from_str(query).unwrap();
+
+        let mut request = match parsed {
+            WmsRequest::GetMap(request) => request,
+            _ => panic!("expected GetMap"),
+        };
+        request.set_dimensions_from_query(query).unwrap();
+
+        assert_eq!(
+            request.dimensions,
+            [("ensemble".to_string(), "3".to_string())]
+                .into_iter()
+                .collect::<BTreeMap<_, _>>()
+        );
+
+        let selection = request.dimension_selection();
+        assert_eq!(selection.get("elevation"), Some("850"));
+        assert_eq!(selection.get("Ensemble"), Some("3"));
+        assert_eq!(selection.get("member"), None);
+    }
 }
@@ -0,0 +1,524 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::contexts::backup::{self, Backup, BackupDb, Restore, RestoreOptions, RestoreSummary};
+use crate::error::Result;
+use crate::pro::contexts::ProContext;
+use crate::pro::datasets::{DatasetPermission, Role, RoleId};
+use crate::pro::projects::{ProProjectDb, ProjectOrganization, UserProjectPermission};
+use crate::pro::users::{
+    Group, GroupId, GroupMembership, Organization, OrganizationId, OrganizationMembership, User,
+    UserId,
+};
+
+/// The complete state of a pro instance, i.e., the [`Backup`] of the default instance
+/// together with the users, their organizations and groups, and their permissions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProBackup {
+    #[serde(flatten)]
+    pub backup: Backup,
+    pub users: Vec<User>,
+    #[serde(default)]
+    pub organizations: Vec<Organization>,
+    #[serde(default)]
+    pub organization_memberships: Vec<OrganizationMembership>,
+    #[serde(default)]
+    pub groups: Vec<Group>,
+    #[serde(default)]
+    pub group_memberships: Vec<GroupMembership>,
+    pub dataset_permissions: Vec<DatasetPermission>,
+    pub project_permissions: Vec<UserProjectPermission>,
+    #[serde(default)]
+    pub project_organizations: Vec<ProjectOrganization>,
+}
+
+/// Exports the complete state of the context.
+///
+/// Memberships and permissions of users that are not part of the backup, e.g., anonymous users,
+/// are omitted.
+pub async fn backup<C>(ctx: &C) -> Result<ProBackup>
+where
+    C: ProContext,
+    C::ProjectDB: ProProjectDb,
+    C::DatasetDB: BackupDb<DatasetPermission>,
+{
+    let backup = backup::backup(ctx).await?;
+    let user_db = ctx.user_db_ref().await;
+    let users = BackupDb::<User>::export(&*user_db).await?;
+    let organizations = BackupDb::<Organization>::export(&*user_db).await?;
+    let organization_memberships = BackupDb::<OrganizationMembership>::export(&*user_db).await?;
+    let groups = BackupDb::<Group>::export(&*user_db).await?;
+    let group_memberships = BackupDb::<GroupMembership>::export(&*user_db).await?;
+    drop(user_db);
+
+    let user_ids: HashSet<UserId> = users.iter().map(|user| user.id).collect();
+    let organization_ids: HashSet<OrganizationId> = organizations
+        .iter()
+        .map(|organization| organization.id)
+        .collect();
+    let group_ids: HashSet<GroupId> = groups.iter().map(|group| group.id).collect();
+    let roles = known_roles(&user_ids, &organization_ids, &group_ids);
+
+    let organization_memberships = organization_memberships
+        .into_iter()
+        .filter(|membership| user_ids.contains(&membership.user))
+        .collect();
+
+    let group_memberships = group_memberships
+        .into_iter()
+        .filter(|membership| user_ids.contains(&membership.user))
+        .collect();
+
+    let dataset_permissions = BackupDb::<DatasetPermission>::export(&*ctx.dataset_db_ref().await)
+        .await?
+        .into_iter()
+        .filter(|permission| roles.contains(&permission.role))
+        .collect();
+
+    let project_db = ctx.project_db_ref().await;
+    let project_permissions = BackupDb::<UserProjectPermission>::export(&*project_db)
+        .await?
+        .into_iter()
+        .filter(|permission| user_ids.contains(&permission.user))
+        .collect();
+    let project_organizations = BackupDb::<ProjectOrganization>::export(&*project_db).await?;
+    drop(project_db);
+
+    Ok(ProBackup {
+        backup,
+        users,
+        organizations,
+        organization_memberships,
+        groups,
+        group_memberships,
+        dataset_permissions,
+        project_permissions,
+        project_organizations,
+    })
+}
+
+/// Restores a backup into the context.
+///
+/// Users, organizations and groups are never remapped, so memberships and permissions of the ones
+/// that were skipped on conflicts are not restored.
+pub async fn restore<C>(
+    ctx: &C,
+    backup: ProBackup,
+    options: RestoreOptions,
+) -> Result<RestoreSummary>
+where
+    C: ProContext,
+    C::ProjectDB: ProProjectDb,
+    C::DatasetDB: BackupDb<DatasetPermission>,
+{
+    let ProBackup {
+        backup,
+        users,
+        organizations,
+        organization_memberships,
+        groups,
+        group_memberships,
+        dataset_permissions,
+        project_permissions,
+        project_organizations,
+    } = backup;
+
+    let mut restore = Restore::new(backup.version, options)?;
+
+    let mut user_ids = HashSet::new();
+    let mut organization_ids = HashSet::new();
+    let mut group_ids = HashSet::new();
+    {
+        let mut user_db = ctx.user_db_ref_mut().await;
+        for user in users {
+            let id = user.id;
+            if restore.item(&mut *user_db, user).await? {
+                user_ids.insert(id);
+            }
+        }
+
+        for organization in organizations {
+            let id = organization.id;
+            if restore.item(&mut *user_db, organization).await? {
+                organization_ids.insert(id);
+            }
+        }
+
+        let organization_memberships: Vec<OrganizationMembership> = organization_memberships
+            .into_iter()
+            .filter(|membership| {
+                user_ids.contains(&membership.user)
+                    && organization_ids.contains(&membership.organization)
+            })
+            .collect();
+        restore
+            .items(&mut *user_db, organization_memberships)
+            .await?;
+
+        for group in groups {
+            let id = group.id;
+            if restore.item(&mut *user_db, group).await? {
+                group_ids.insert(id);
+            }
+        }
+
+        let group_memberships: Vec<GroupMembership> = group_memberships
+            .into_iter()
+            .filter(|membership| {
+                user_ids.contains(&membership.user) && group_ids.contains(&membership.group)
+            })
+            .collect();
+        restore.items(&mut *user_db, group_memberships).await?;
+    }
+
+    let roles = known_roles(&user_ids, &organization_ids, &group_ids);
+
+    {
+        let mut dataset_db = ctx.dataset_db_ref_mut().await;
+        restore.datasets(&mut *dataset_db, backup.datasets).await?;
+
+        let dataset_permissions: Vec<DatasetPermission> = dataset_permissions
+            .into_iter()
+            .filter(|permission| roles.contains(&permission.role))
+            .map(|permission| DatasetPermission {
+                dataset: restore.dataset_id(permission.dataset.clone()),
+                ..permission
+            })
+            .collect();
+        restore.items(&mut *dataset_db, dataset_permissions).await?;
+    }
+
+    restore
+        .workflows(
+            &mut *ctx.workflow_registry_ref_mut().await,
+            backup.workflows,
+        )
+        .await?;
+
+    {
+        let mut project_db = ctx.project_db_ref_mut().await;
+        restore.projects(&mut *project_db, backup.projects).await?;
+
+        let project_permissions: Vec<UserProjectPermission> = project_permissions
+            .into_iter()
+            .filter(|permission| user_ids.contains(&permission.user))
+            .map(|permission| UserProjectPermission {
+                project: restore.project_id(permission.project),
+                ..permission
+            })
+            .collect();
+        restore.items(&mut *project_db, project_permissions).await?;
+
+        let project_organizations: Vec<ProjectOrganization> = project_organizations
+            .into_iter()
+            .filter(|project| organization_ids.contains(&project.organization))
+            .map(|project| ProjectOrganization {
+                project: restore.project_id(project.project),
+                ..project
+            })
+            .collect();
+        restore
+            .items(&mut *project_db, project_organizations)
+            .await?;
+    }
+
+    Ok(restore.finish())
+}
+
+/// The built-in roles together with the roles of the users, organizations and groups
+fn known_roles(
+    user_ids: &HashSet<UserId>,
+    organization_ids: &HashSet<OrganizationId>,
+    group_ids: &HashSet<GroupId>,
+) -> HashSet<RoleId> {
+    [
+        Role::system_role_id(),
+        Role::user_role_id(),
+        Role::anonymous_role_id(),
+    ]
+    .into_iter()
+    .chain(user_ids.iter().map(|&user| RoleId::from(user)))
+    .chain(
+        organization_ids
+            .iter()
+            .map(|&organization| RoleId::from(organization)),
+    )
+    .chain(group_ids.iter().map(|&group| RoleId::from(group)))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::Context;
+    use crate::pro::contexts::ProInMemoryContext;
+    use crate::pro::datasets::Permission;
+    use crate::pro::projects::ProjectPermission;
+    use crate::pro::users::{
+        CreateGroup, CreateOrganization, GroupRole, OrganizationRole, Quota, UserCredentials,
+        UserDb, UserRegistration, UserSession,
+    };
+    use crate::projects::{CreateProject, ProjectDb, ProjectId, STRectangle};
+    use crate::util::user_input::UserInput;
+    use geoengine_datatypes::dataset::{DatasetId, InternalDatasetId};
+    use geoengine_datatypes::spatial_reference::SpatialReferenceOption;
+    use geoengine_datatypes::util::test::TestDefault;
+    use geoengine_datatypes::util::Identifier;
+
+    async fn register_and_login(ctx: &ProInMemoryContext, email: &str) -> UserSession {
+        ctx.user_db_ref_mut()
+            .await
+            .register(
+                UserRegistration {
+                    email: email.to_string(),
+                    password: "secret123".to_string(),
+                    real_name: "Foo Bar".to_string(),
+                }
+                .validated()
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        login(ctx, email).await
+    }
+
+    async fn login(ctx: &ProInMemoryContext, email: &str) -> UserSession {
+        ctx.user_db_ref_mut()
+            .await
+            .login(UserCredentials {
+                email: email.to_string(),
+                password: "secret123".to_string(),
+            })
+            .await
+            .unwrap()
+    }
+
+    async fn create_project(ctx: &ProInMemoryContext, session: &UserSession) -> ProjectId {
+        ctx.project_db_ref_mut()
+            .await
+            .create(
+                session,
+                CreateProject {
+                    name: "Project".to_string(),
+                    description: "A project".to_string(),
+                    bounds: STRectangle::new(
+                        SpatialReferenceOption::Unreferenced,
+                        0.,
+                        0.,
+                        1.,
+                        1.,
+                        0,
+                        1,
+                    )
+                    .unwrap(),
+                    time_step: None,
+                }
+                .validated()
+                .unwrap(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_restores_users_and_permissions() {
+        let ctx = ProInMemoryContext::test_default();
+
+        let session = register_and_login(&ctx, "foo@bar.de").await;
+
+        let project = create_project(&ctx, &session).await;
+
+        // anonymous users are not part of the backup
+        let anonymous = ctx.user_db_ref_mut().await.anonymous().await.unwrap();
+
+        let backup = backup(&ctx).await.unwrap();
+        assert_eq!(backup.users.len(), 1);
+        assert!(backup
+            .project_permissions
+            .iter()
+            .all(|permission| permission.user != anonymous.user.id));
+
+        let backup: ProBackup =
+            serde_json::from_str(&serde_json::to_string(&backup).unwrap()).unwrap();
+
+        let restored = ProInMemoryContext::test_default();
+        let summary = restore(&restored, backup, RestoreOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(summary.skipped, 0);
+
+        let session = login(&restored, "foo@bar.de").await;
+
+        let restored_project = restored
+            .project_db_ref()
+            .await
+            .load(&session, project)
+            .await
+            .unwrap();
+        assert_eq!(restored_project.name, "Project");
+
+        let permissions = restored
+            .project_db_ref()
+            .await
+            .list_permissions(&session, project)
+            .await
+            .unwrap();
+        assert_eq!(
+            permissions,
+            vec![UserProjectPermission {
+                user: session.user.id,
+                project,
+                permission: ProjectPermission::Owner,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_restores_groups_with_their_members_and_permissions() {
+        let ctx = ProInMemoryContext::test_default();
+
+        let admin = register_and_login(&ctx, "foo@bar.de").await;
+        let member = register_and_login(&ctx, "bar@foo.de").await;
+
+        let group = ctx
+            .user_db_ref_mut()
+            .await
+            .create_group(
+                &admin,
+                CreateGroup {
+                    name: "Group".to_string(),
+                }
+                .validated()
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+        ctx.user_db_ref_mut()
+            .await
+            .add_group_member(&admin, group, member.user.id, GroupRole::Member)
+            .await
+            .unwrap();
+
+        let permission = DatasetPermission {
+            role: group.into(),
+            dataset: DatasetId::Internal {
+                dataset_id: InternalDatasetId::new(),
+            },
+            permission: Permission::Read,
+        };
+        BackupDb::<DatasetPermission>::import(
+            &mut *ctx.dataset_db_ref_mut().await,
+            permission.clone(),
+        )
+        .await
+        .unwrap();
+
+        let backup = backup(&ctx).await.unwrap();
+        assert_eq!(backup.groups.len(), 1);
+        assert_eq!(backup.group_memberships.len(), 2);
+        assert!(backup.dataset_permissions.contains(&permission));
+
+        let backup: ProBackup =
+            serde_json::from_str(&serde_json::to_string(&backup).unwrap()).unwrap();
+
+        let restored = ProInMemoryContext::test_default();
+        let summary = restore(&restored, backup, RestoreOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(summary.skipped, 0);
+
+        let session = login(&restored, "bar@foo.de").await;
+        assert!(session.roles.contains(&group.into()));
+
+        let members = restored
+            .user_db_ref()
+            .await
+            .group_members(&session, group)
+            .await
+            .unwrap();
+        assert_eq!(members.len(), 2);
+
+        let permissions = BackupDb::<DatasetPermission>::export(&*restored.dataset_db_ref().await)
+            .await
+            .unwrap();
+        assert!(permissions.contains(&permission));
+    }
+
+    #[tokio::test]
+    async fn it_restores_organizations_with_their_members_and_projects() {
+        let ctx = ProInMemoryContext::test_default();
+
+        let admin = register_and_login(&ctx, "foo@bar.de").await;
+        let member = register_and_login(&ctx, "bar@foo.de").await;
+
+        let quota = Quota {
+            max_datasets: Some(5),
+            max_projects: Some(3),
+        };
+        let organization = ctx
+            .user_db_ref_mut()
+            .await
+            .create_organization(
+                &admin,
+                CreateOrganization {
+                    name: "Organization".to_string(),
+                }
+                .validated()
+                .unwrap(),
+                quota,
+            )
+            .await
+            .unwrap();
+
+        let admin = login(&ctx, "foo@bar.de").await;
+        ctx.user_db_ref_mut()
+            .await
+            .add_organization_member(&admin, member.user.id, OrganizationRole::Member)
+            .await
+            .unwrap();
+
+        let project = create_project(&ctx, &admin).await;
+
+        let backup = backup(&ctx).await.unwrap();
+        assert_eq!(backup.organizations.len(), 1);
+        assert_eq!(backup.organization_memberships.len(), 2);
+        assert_eq!(
+            backup.project_organizations,
+            vec![ProjectOrganization {
+                project,
+                organization,
+            }]
+        );
+
+        let backup: ProBackup =
+            serde_json::from_str(&serde_json::to_string(&backup).unwrap()).unwrap();
+
+        let restored = ProInMemoryContext::test_default();
+        let summary = restore(&restored, backup, RestoreOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(summary.skipped, 0);
+
+        let session = login(&restored, "bar@foo.de").await;
+        assert_eq!(
+            session.organization,
+            Some(Organization {
+                id: organization,
+                name: "Organization".to_string(),
+                quota,
+            })
+        );
+
+        // members can read the projects of their organization
+        let restored_project = restored
+            .project_db_ref()
+            .await
+            .load(&session, project)
+            .await
+            .unwrap();
+        assert_eq!(restored_project.name, "Project");
+    }
+}
@@ -1,15 +1,22 @@
-use crate::contexts::{ExecutionContextImpl, QueryContextImpl};
+use crate::contexts::DatasetEventBus;
+use crate::contexts::{
+    tile_buffer_pool_from_config, ExecutionContextImpl, GuestSessions, QueryContextImpl,
+};
 use crate::error;
+use crate::object_storage::{object_storage_from_config, ObjectStorage};
 use crate::pro::contexts::{Context, Db, ProContext};
 use crate::pro::datasets::{add_datasets_from_directory, ProHashMapDatasetDb};
+use crate::pro::notifications::{mailer_from_config, Mailer};
 use crate::pro::projects::ProHashMapProjectDb;
 use crate::pro::users::{HashMapUserDb, UserDb, UserSession};
+use crate::workflows::cache::ResultCache;
 use crate::workflows::registry::HashMapRegistry;
 use crate::{datasets::add_from_directory::add_providers_from_directory, error::Result};
 use async_trait::async_trait;
 use geoengine_datatypes::raster::TilingSpecification;
 use geoengine_datatypes::util::test::TestDefault;
 use geoengine_operators::engine::ChunkByteSize;
+use geoengine_operators::engine::TileBufferPool;
 use geoengine_operators::util::create_rayon_thread_pool;
 use rayon::ThreadPool;
 use snafu::ResultExt;
@@ -24,19 +31,33 @@ pub struct ProInMemoryContext {
     project_db: Db<ProHashMapProjectDb>,
     workflow_registry: Db<HashMapRegistry>,
     dataset_db: Db<ProHashMapDatasetDb>,
+    result_cache: Arc<ResultCache>,
+    dataset_events: Arc<DatasetEventBus>,
+    object_storage: Arc<dyn ObjectStorage>,
+    guest_sessions: Arc<GuestSessions<UserSession>>,
+    mailer: Option<Arc<dyn Mailer>>,
     thread_pool: Arc<ThreadPool>,
+    tile_buffer_pool: Arc<TileBufferPool>,
     exe_ctx_tiling_spec: TilingSpecification,
     query_ctx_chunk_size: ChunkByteSize,
 }
 
 impl TestDefault for ProInMemoryContext {
     fn test_default() -> Self {
+        let result_cache = Arc::new(ResultCache::from_config());
+
         Self {
             user_db: Default::default(),
             project_db: Default::default(),
             workflow_registry: Default::default(),
             dataset_db: Default::default(),
+            dataset_events: Arc::new(DatasetEventBus::new(result_cache.clone())),
+            result_cache,
+            object_storage: object_storage_from_config(),
+            guest_sessions: Default::default(),
+            mailer: mailer_from_config(),
             thread_pool: create_rayon_thread_pool(0),
+            tile_buffer_pool: tile_buffer_pool_from_config(),
             exe_ctx_tiling_spec: TestDefault::test_default(),
             query_ctx_chunk_size: TestDefault::test_default(),
         }
@@ -55,27 +76,50 @@ impl ProInMemoryContext {
         add_providers_from_directory(&mut db, provider_defs_path.clone()).await;
         add_providers_from_directory(&mut db, provider_defs_path.join("pro")).await;
 
+        let result_cache = Arc::new(ResultCache::from_config());
+
         Self {
             user_db: Default::default(),
             project_db: Default::default(),
             workflow_registry: Default::default(),
             thread_pool: create_rayon_thread_pool(0),
+            tile_buffer_pool: tile_buffer_pool_from_config(),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
             dataset_db: Arc::new(RwLock::new(db)),
+            dataset_events: Arc::new(DatasetEventBus::new(result_cache.clone())),
+            result_cache,
+            object_storage: object_storage_from_config(),
+            guest_sessions: Default::default(),
+            mailer: mailer_from_config(),
         }
     }
 
+    /// Sends notifications with the `mailer` regardless of the config, e.g., to capture them in tests
+    #[must_use]
+    pub fn with_mailer(mut self, mailer: Arc<dyn Mailer>) -> Self {
+        self.mailer = Some(mailer);
+        self
+    }
+
     pub fn new_with_context_spec(
         exe_ctx_tiling_spec: TilingSpecification,
         query_ctx_chunk_size: ChunkByteSize,
     ) -> Self {
+        let result_cache = Arc::new(ResultCache::from_config());
+
         ProInMemoryContext {
             user_db: Default::default(),
             project_db: Default::default(),
             workflow_registry: Default::default(),
             dataset_db: Default::default(),
+            dataset_events: Arc::new(DatasetEventBus::new(result_cache.clone())),
+            result_cache,
+            object_storage: object_storage_from_config(),
+            guest_sessions: Default::default(),
+            mailer: mailer_from_config(),
             thread_pool: create_rayon_thread_pool(0),
+            tile_buffer_pool: tile_buffer_pool_from_config(),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
         }
@@ -95,6 +139,10 @@ impl ProContext for ProInMemoryContext {
     async fn user_db_ref_mut(&self) -> RwLockWriteGuard<'_, Self::UserDB> {
         self.user_db.write().await
     }
+
+    fn mailer(&self) -> Option<Arc<dyn Mailer>> {
+        self.mailer.clone()
+    }
 }
 
 #[async_trait]
@@ -104,7 +152,7 @@ impl Context for ProInMemoryContext {
     type WorkflowRegistry = HashMapRegistry;
     type DatasetDB = ProHashMapDatasetDb;
     type QueryContext = QueryContextImpl;
-    type ExecutionContext = ExecutionContextImpl<UserSession, ProHashMapDatasetDb>;
+    type ExecutionContext = ExecutionContextImpl<UserSession, ProHashMapDatasetDb, HashMapRegistry>;
 
     fn project_db(&self) -> Db<Self::ProjectDB> {
         self.project_db.clone()
@@ -136,6 +184,22 @@ impl Context for ProInMemoryContext {
         self.dataset_db.write().await
     }
 
+    fn result_cache(&self) -> Arc<ResultCache> {
+        self.result_cache.clone()
+    }
+
+    fn dataset_events(&self) -> Arc<DatasetEventBus> {
+        self.dataset_events.clone()
+    }
+
+    fn object_storage(&self) -> Arc<dyn ObjectStorage> {
+        self.object_storage.clone()
+    }
+
+    fn guest_sessions(&self) -> Arc<GuestSessions<Self::Session>> {
+        self.guest_sessions.clone()
+    }
+
     fn query_context(&self) -> Result<Self::QueryContext> {
         Ok(QueryContextImpl::new(
             self.query_ctx_chunk_size,
@@ -144,22 +208,45 @@ impl Context for ProInMemoryContext {
     }
 
     fn execution_context(&self, session: UserSession) -> Result<Self::ExecutionContext> {
-        Ok(
-            ExecutionContextImpl::<UserSession, ProHashMapDatasetDb>::new(
-                self.dataset_db.clone(),
-                self.thread_pool.clone(),
-                session,
-                self.exe_ctx_tiling_spec,
-            ),
-        )
+        self.guest_sessions.charge_query(&session)?;
+
+        Ok(ExecutionContextImpl::<
+            UserSession,
+            ProHashMapDatasetDb,
+            HashMapRegistry,
+        >::new(
+            self.dataset_db.clone(),
+            self.workflow_registry.clone(),
+            self.thread_pool.clone(),
+            self.tile_buffer_pool.clone(),
+            session,
+            self.exe_ctx_tiling_spec,
+        ))
     }
 
     async fn session_by_id(&self, session_id: crate::contexts::SessionId) -> Result<Self::Session> {
-        self.user_db_ref()
+        if let Some(guest) = self.guest_sessions.session(session_id) {
+            return Ok(guest);
+        }
+
+        let session = self
+            .user_db_ref()
             .await
             .session(session_id)
             .await
             .map_err(Box::new)
-            .context(error::Authorization)
+            .context(error::Authorization)?;
+
+        if session.needs_renewal()? {
+            return self
+                .user_db_ref_mut()
+                .await
+                .renew_session(session_id)
+                .await
+                .map_err(Box::new)
+                .context(error::Authorization);
+        }
+
+        Ok(session)
     }
 }
@@ -1,3 +1,4 @@
+pub mod backup;
 mod in_memory;
 
 #[cfg(feature = "postgres")]
@@ -7,19 +8,31 @@ pub use in_memory::ProInMemoryContext;
 #[cfg(feature = "postgres")]
 pub use postgres::PostgresContext;
 
-use crate::contexts::{Context, Db};
-use crate::pro::users::{UserDb, UserSession};
+use crate::contexts::{BackupDb, Context, Db};
+use crate::pro::notifications::Mailer;
+use crate::pro::users::{
+    Group, GroupMembership, Organization, OrganizationMembership, User, UserDb, UserSession,
+};
 
 use async_trait::async_trait;
+use std::sync::Arc;
 use tokio::sync::{RwLockReadGuard, RwLockWriteGuard};
 
 /// A pro contexts that extends the default context.
 // TODO: avoid locking the individual DBs here IF they are already thread safe (e.g. guaranteed by postgres)
 #[async_trait]
 pub trait ProContext: Context<Session = UserSession> {
-    type UserDB: UserDb;
+    type UserDB: UserDb
+        + BackupDb<User>
+        + BackupDb<Organization>
+        + BackupDb<OrganizationMembership>
+        + BackupDb<Group>
+        + BackupDb<GroupMembership>;
 
     fn user_db(&self) -> Db<Self::UserDB>;
     async fn user_db_ref(&self) -> RwLockReadGuard<Self::UserDB>;
     async fn user_db_ref_mut(&self) -> RwLockWriteGuard<Self::UserDB>;
+
+    /// The mailer for notifications, `None` if notifications are disabled
+    fn mailer(&self) -> Option<Arc<dyn Mailer>>;
 }
@@ -1,10 +1,14 @@
+use crate::contexts::{tile_buffer_pool_from_config, DatasetEventBus, GuestSessions};
 use crate::datasets::add_from_directory::add_providers_from_directory;
 use crate::error::{self, Result};
+use crate::object_storage::{object_storage_from_config, ObjectStorage};
 use crate::pro::datasets::{add_datasets_from_directory, PostgresDatasetDb, Role};
+use crate::pro::notifications::{mailer_from_config, Mailer};
 use crate::pro::projects::ProjectPermission;
 use crate::pro::users::{UserDb, UserId, UserSession};
 use crate::pro::workflows::postgres_workflow_registry::PostgresWorkflowRegistry;
 use crate::projects::ProjectId;
+use crate::workflows::cache::ResultCache;
 use crate::{
     contexts::{Context, Db},
     pro::users::PostgresUserDb,
@@ -22,6 +26,7 @@ use bb8_postgres::{
 };
 use geoengine_datatypes::raster::TilingSpecification;
 use geoengine_operators::engine::ChunkByteSize;
+use geoengine_operators::engine::TileBufferPool;
 use geoengine_operators::util::create_rayon_thread_pool;
 use log::{debug, warn};
 use rayon::ThreadPool;
@@ -47,7 +52,13 @@ where
     project_db: Db<PostgresProjectDb<Tls>>,
     workflow_registry: Db<PostgresWorkflowRegistry<Tls>>,
     dataset_db: Db<PostgresDatasetDb<Tls>>,
+    result_cache: Arc<ResultCache>,
+    dataset_events: Arc<DatasetEventBus>,
+    object_storage: Arc<dyn ObjectStorage>,
+    guest_sessions: Arc<GuestSessions<UserSession>>,
+    mailer: Option<Arc<dyn Mailer>>,
     thread_pool: Arc<ThreadPool>,
+    tile_buffer_pool: Arc<TileBufferPool>,
     exe_ctx_tiling_spec: TilingSpecification,
     query_ctx_chunk_size: ChunkByteSize,
 }
@@ -71,12 +82,20 @@ where
 
         Self::update_schema(pool.get().await?).await?;
 
+        let result_cache = Arc::new(ResultCache::from_config());
+
         Ok(Self {
             user_db: Arc::new(RwLock::new(PostgresUserDb::new(pool.clone()))),
             project_db: Arc::new(RwLock::new(PostgresProjectDb::new(pool.clone()))),
             workflow_registry: Arc::new(RwLock::new(PostgresWorkflowRegistry::new(pool.clone()))),
             dataset_db: Arc::new(RwLock::new(PostgresDatasetDb::new(pool.clone()))),
+            dataset_events: Arc::new(DatasetEventBus::new(result_cache.clone())),
+            result_cache,
+            object_storage: object_storage_from_config(),
+            guest_sessions: Default::default(),
+            mailer: mailer_from_config(),
             thread_pool: create_rayon_thread_pool(0),
+            tile_buffer_pool: tile_buffer_pool_from_config(),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
         })
@@ -102,12 +121,20 @@ where
         add_providers_from_directory(&mut dataset_db, provider_defs_path.clone()).await;
         add_providers_from_directory(&mut dataset_db, provider_defs_path.join("pro")).await;
 
+        let result_cache = Arc::new(ResultCache::from_config());
+
         Ok(Self {
             user_db: Arc::new(RwLock::new(PostgresUserDb::new(pool.clone()))),
             project_db: Arc::new(RwLock::new(PostgresProjectDb::new(pool.clone()))),
             workflow_registry: Arc::new(RwLock::new(PostgresWorkflowRegistry::new(pool.clone()))),
             dataset_db: Arc::new(RwLock::new(dataset_db)),
+            dataset_events: Arc::new(DatasetEventBus::new(result_cache.clone())),
+            result_cache,
+            object_storage: object_storage_from_config(),
+            guest_sessions: Default::default(),
+            mailer: mailer_from_config(),
             thread_pool: create_rayon_thread_pool(0),
+            tile_buffer_pool: tile_buffer_pool_from_config(),
             exe_ctx_tiling_spec,
             query_ctx_chunk_size,
         })
@@ -382,13 +409,234 @@ where
                     .await?;
                     debug!("Updated user database to schema version {}", version + 1);
                 }
-                // 1 => {
+                1 => {
+                    conn.batch_execute(
+                        r#"
+                        -- organizations are roles that are granted to all their members
+                        CREATE TABLE organizations (
+                            id UUID PRIMARY KEY REFERENCES roles(id) ON DELETE CASCADE,
+                            name text NOT NULL UNIQUE,
+                            max_datasets bigint,
+                            max_projects bigint
+                        );
+
+                        CREATE TYPE "OrganizationRole" AS ENUM ('Admin', 'Member');
+
+                        -- a user is a member of at most one organization
+                        CREATE TABLE organization_members (
+                            organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE NOT NULL,
+                            user_id UUID REFERENCES users(id) ON DELETE CASCADE NOT NULL UNIQUE,
+                            role "OrganizationRole" NOT NULL,
+                            PRIMARY KEY (organization_id, user_id)
+                        );
+
+                        ALTER TABLE projects
+                            ADD COLUMN organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL;
+
+                        -- the permissions of users on projects including the read permissions
+                        -- that members have on the projects of their organization
+                        CREATE VIEW user_project_access AS
+                            SELECT user_id, project_id, permission
+                            FROM user_project_permissions
+                            UNION ALL
+                            SELECT m.user_id, p.id, 'Read'::"ProjectPermission"
+                            FROM projects p JOIN organization_members m ON (p.organization_id = m.organization_id)
+                            WHERE NOT EXISTS (
+                                SELECT 1 FROM user_project_permissions u
+                                WHERE u.user_id = m.user_id AND u.project_id = p.id
+                            );
+
+                        UPDATE version SET version = 2;
+                        "#,
+                    )
+                    .await?;
+                    debug!("Updated user database to schema version {}", version + 1);
+                }
+                2 => {
+                    conn.batch_execute(
+                        r#"
+                        CREATE TABLE dataset_access_policies (
+                            dataset_id UUID REFERENCES datasets(id) ON DELETE CASCADE NOT NULL,
+                            role_id UUID REFERENCES roles(id) ON DELETE CASCADE NOT NULL,
+                            hidden_columns text[] NOT NULL,
+                            row_filter text,
+                            PRIMARY KEY (dataset_id, role_id)
+                        );
+
+                        UPDATE version SET version = 3;
+                        "#,
+                    )
+                    .await?;
+                    debug!("Updated user database to schema version {}", version + 1);
+                }
+                3 => {
+                    conn.batch_execute(
+                        r#"
+                        CREATE TABLE workflow_templates (
+                            id UUID PRIMARY KEY,
+                            template json NOT NULL
+                        );
+
+                        UPDATE version SET version = 4;
+                        "#,
+                    )
+                    .await?;
+                    debug!("Updated user database to schema version {}", version + 1);
+                }
+                4 => {
+                    conn.batch_execute(
+                        r#"
+                        CREATE TABLE workflow_macros (
+                            name text NOT NULL,
+                            version int NOT NULL,
+                            operator json NOT NULL,
+                            PRIMARY KEY (name, version)
+                        );
+
+                        UPDATE version SET version = 5;
+                        "#,
+                    )
+                    .await?;
+                    debug!("Updated user database to schema version {}", version + 1);
+                }
+                5 => {
+                    conn.batch_execute(
+                        r#"
+                        CREATE TABLE user_states (
+                            user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+                            state json NOT NULL
+                        );
+
+                        UPDATE version SET version = 6;
+                        "#,
+                    )
+                    .await?;
+                    debug!("Updated user database to schema version {}", version + 1);
+                }
+                6 => {
+                    conn.batch_execute(
+                        r#"
+                        CREATE TABLE notification_settings (
+                            user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+                            task_status boolean NOT NULL,
+                            shares boolean NOT NULL
+                        );
+
+                        CREATE TABLE password_resets (
+                            token UUID PRIMARY KEY,
+                            user_id UUID REFERENCES users(id) ON DELETE CASCADE NOT NULL,
+                            valid_until timestamp with time zone NOT NULL
+                        );
+
+                        UPDATE version SET version = 7;
+                        "#,
+                    )
+                    .await?;
+                    debug!("Updated user database to schema version {}", version + 1);
+                }
+                7 => {
+                    conn.batch_execute(
+                        r#"
+                        CREATE TABLE dataset_licenses (
+                            dataset_id UUID PRIMARY KEY REFERENCES datasets(id) ON DELETE CASCADE,
+                            license text NOT NULL,
+                            terms text,
+                            terms_version int NOT NULL
+                        );
+
+                        CREATE TABLE dataset_terms_acceptances (
+                            dataset_id UUID REFERENCES datasets(id) ON DELETE CASCADE NOT NULL,
+                            user_id UUID REFERENCES users(id) ON DELETE CASCADE NOT NULL,
+                            terms_version int NOT NULL,
+                            accepted timestamp with time zone NOT NULL,
+                            PRIMARY KEY (dataset_id, user_id)
+                        );
+
+                        UPDATE version SET version = 8;
+                        "#,
+                    )
+                    .await?;
+                    debug!("Updated user database to schema version {}", version + 1);
+                }
+                8 => {
+                    conn.batch_execute(
+                        r#"
+                        ALTER TABLE users ADD COLUMN institution text;
+
+                        ALTER TABLE dataset_access_policies 
+                            ADD COLUMN ip_ranges text[] NOT NULL DEFAULT '{}',
+                            ADD COLUMN institutions text[] NOT NULL DEFAULT '{}';
+
+                        UPDATE version SET version = 9;
+                        "#,
+                    )
+                    .await?;
+                    debug!("Updated user database to schema version {}", version + 1);
+                }
+                9 => {
+                    conn.batch_execute(
+                        r#"
+                        -- groups are roles that are granted to all their members
+                        CREATE TABLE groups (
+                            id UUID PRIMARY KEY REFERENCES roles(id) ON DELETE CASCADE,
+                            name text NOT NULL
+                        );
+
+                        CREATE TYPE "GroupRole" AS ENUM ('Admin', 'Member');
+
+                        CREATE TABLE group_members (
+                            group_id UUID REFERENCES groups(id) ON DELETE CASCADE NOT NULL,
+                            user_id UUID REFERENCES users(id) ON DELETE CASCADE NOT NULL,
+                            role "GroupRole" NOT NULL,
+                            PRIMARY KEY (group_id, user_id)
+                        );
+
+                        UPDATE version SET version = 10;
+                        "#,
+                    )
+                    .await?;
+                    debug!("Updated user database to schema version {}", version + 1);
+                }
+                10 => {
+                    // the extents of existing datasets stay unknown
+                    conn.batch_execute(
+                        r#"
+                        ALTER TABLE datasets ADD COLUMN extent json;
+
+                        UPDATE version SET version = 11;
+                        "#,
+                    )
+                    .await?;
+                    debug!("Updated user database to schema version {}", version + 1);
+                }
+                11 => {
+                    // all guest sessions share a built-in user instead of storing one per guest
+                    conn.batch_execute(&format!(
+                        r#"
+                        DELETE FROM users WHERE id IN (SELECT id FROM roles WHERE name = 'guest_user');
+                        DELETE FROM roles WHERE name = 'guest_user';
+
+                        INSERT INTO roles (id, name) VALUES ('{guest_user_id}', 'guest');
+                        INSERT INTO users (id, active) VALUES ('{guest_user_id}', TRUE);
+                        INSERT INTO user_roles (user_id, role_id) VALUES
+                            ('{guest_user_id}', '{guest_user_id}'),
+                            ('{guest_user_id}', '{anonymous_role_id}');
+
+                        UPDATE version SET version = 12;
+                        "#,
+                        guest_user_id = UserId::guest_user_id(),
+                        anonymous_role_id = Role::anonymous_role_id(),
+                    ))
+                    .await?;
+                    debug!("Updated user database to schema version {}", version + 1);
+                }
+                // 12 => {
                 // next version
                 // conn.batch_execute(
                 //     "\
                 //     ALTER TABLE users ...
                 //
-                //     UPDATE version SET version = 2;\
+                //     UPDATE version SET version = 13;\
                 //     ",
                 // )
                 // .await?;
@@ -410,7 +658,7 @@ where
             .prepare(
                 "
                 SELECT TRUE
-                FROM user_project_permissions
+                FROM user_project_access
                 WHERE user_id = $1 AND project_id = $2 AND permission = ANY ($3);",
             )
             .await?;
@@ -442,6 +690,10 @@ where
     async fn user_db_ref_mut(&self) -> RwLockWriteGuard<'_, Self::UserDB> {
         self.user_db.write().await
     }
+
+    fn mailer(&self) -> Option<Arc<dyn Mailer>> {
+        self.mailer.clone()
+    }
 }
 
 #[async_trait]
@@ -457,7 +709,8 @@ where
     type WorkflowRegistry = PostgresWorkflowRegistry<Tls>;
     type DatasetDB = PostgresDatasetDb<Tls>;
     type QueryContext = QueryContextImpl;
-    type ExecutionContext = ExecutionContextImpl<UserSession, PostgresDatasetDb<Tls>>;
+    type ExecutionContext =
+        ExecutionContextImpl<UserSession, PostgresDatasetDb<Tls>, PostgresWorkflowRegistry<Tls>>;
 
     fn project_db(&self) -> Db<Self::ProjectDB> {
         self.project_db.clone()
@@ -491,6 +744,22 @@ where
         self.dataset_db.write().await
     }
 
+    fn result_cache(&self) -> Arc<ResultCache> {
+        self.result_cache.clone()
+    }
+
+    fn dataset_events(&self) -> Arc<DatasetEventBus> {
+        self.dataset_events.clone()
+    }
+
+    fn object_storage(&self) -> Arc<dyn ObjectStorage> {
+        self.object_storage.clone()
+    }
+
+    fn guest_sessions(&self) -> Arc<GuestSessions<Self::Session>> {
+        self.guest_sessions.clone()
+    }
+
     fn query_context(&self) -> Result<Self::QueryContext> {
         // TODO: load config only once
         Ok(QueryContextImpl::new(
@@ -500,23 +769,46 @@ where
     }
 
     fn execution_context(&self, session: UserSession) -> Result<Self::ExecutionContext> {
-        Ok(
-            ExecutionContextImpl::<UserSession, PostgresDatasetDb<Tls>>::new(
-                self.dataset_db.clone(),
-                self.thread_pool.clone(),
-                session,
-                self.exe_ctx_tiling_spec,
-            ),
-        )
+        self.guest_sessions.charge_query(&session)?;
+
+        Ok(ExecutionContextImpl::<
+            UserSession,
+            PostgresDatasetDb<Tls>,
+            PostgresWorkflowRegistry<Tls>,
+        >::new(
+            self.dataset_db.clone(),
+            self.workflow_registry.clone(),
+            self.thread_pool.clone(),
+            self.tile_buffer_pool.clone(),
+            session,
+            self.exe_ctx_tiling_spec,
+        ))
     }
 
     async fn session_by_id(&self, session_id: crate::contexts::SessionId) -> Result<Self::Session> {
-        self.user_db_ref()
+        if let Some(guest) = self.guest_sessions.session(session_id) {
+            return Ok(guest);
+        }
+
+        let session = self
+            .user_db_ref()
             .await
             .session(session_id)
             .await
             .map_err(Box::new)
-            .context(error::Authorization)
+            .context(error::Authorization)?;
+
+        if session.needs_renewal()? {
+            return self
+                .user_db_ref_mut()
+                .await
+                .renew_session(session_id)
+                .await
+                .map_err(Box::new)
+                .context(error::Authorization);
+        }
+
+        Ok(session)
     }
 }
 
@@ -535,9 +827,12 @@ mod tests {
     };
     use crate::datasets::upload::{FileId, UploadId};
     use crate::datasets::upload::{FileUpload, Upload, UploadDb};
-    use crate::pro::datasets::{DatasetPermission, Permission, UpdateDatasetPermissions};
+    use crate::pro::datasets::{
+        DatasetLicense, DatasetLicenses, DatasetPermission, Permission, UpdateDatasetPermissions,
+    };
+    use crate::pro::notifications::NotificationSettings;
     use crate::pro::projects::{LoadVersion, ProProjectDb, UserProjectPermission};
-    use crate::pro::users::{UserCredentials, UserDb, UserRegistration};
+    use crate::pro::users::{PasswordReset, UserCredentials, UserDb, UserRegistration, UserState};
     use crate::projects::{
         CreateProject, Layer, LayerUpdate, OrderBy, Plot, PlotUpdate, PointSymbology, ProjectDb,
         ProjectFilter, ProjectId, ProjectListOptions, ProjectListing, STRectangle, UpdateProject,
@@ -561,7 +856,7 @@ mod tests {
     use geoengine_datatypes::util::test::TestDefault;
     use geoengine_datatypes::util::Identifier;
     use geoengine_operators::engine::{
-        MetaData, MultipleRasterSources, PlotOperator, StaticMetaData, TypedOperator,
+        MetaData, MultipleRasterSources, PlotOperator, ResultExtent, StaticMetaData, TypedOperator,
         TypedResultDescriptor, VectorOperator, VectorResultDescriptor,
     };
     use geoengine_operators::mock::{MockPointSource, MockPointSourceParams};
@@ -571,6 +866,7 @@ mod tests {
         OgrSourceDatasetTimeType, OgrSourceDurationSpec, OgrSourceErrorSpec, OgrSourceTimeFormat,
     };
     use rand::RngCore;
+    use serde_json::json;
     use tokio::runtime::Handle;
 
     /// Setup database schema and return its name.
@@ -826,7 +1122,7 @@ mod tests {
             .await
             .register(Workflow {
                 operator: Statistics {
-                    params: StatisticsParams {},
+                    params: StatisticsParams::default(),
                     sources: MultipleRasterSources { rasters: vec![] },
                 }
                 .boxed()
@@ -1004,6 +1300,112 @@ mod tests {
         .await;
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn it_persists_workflow_macros() {
+        with_temp_context(|ctx, _| async move {
+            let mut registry = ctx.workflow_registry_ref_mut().await;
+
+            for i in 1..=2 {
+                let version = registry
+                    .register_macro("identity".to_string(), json!({ "$input": i.to_string() }))
+                    .await
+                    .unwrap();
+                assert_eq!(version, i);
+            }
+
+            let latest = registry.load_macro("identity", None).await.unwrap();
+            assert_eq!(latest.version, 2);
+            assert_eq!(latest.operator, json!({ "$input": "2" }));
+
+            let first = registry.load_macro("identity", Some(1)).await.unwrap();
+            assert_eq!(first.operator, json!({ "$input": "1" }));
+
+            assert!(registry.load_macro("identity", Some(3)).await.is_err());
+            assert!(registry.load_macro("unknown", None).await.is_err());
+        })
+        .await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn it_persists_user_states() {
+        with_temp_context(|ctx, _| async move {
+            let mut db = ctx.user_db_ref_mut().await;
+
+            let session = db.anonymous().await.unwrap();
+
+            assert_eq!(db.user_state(&session).await.unwrap(), UserState::default());
+
+            for project in [ProjectId::new(), ProjectId::new()] {
+                let state = UserState {
+                    project: Some(project),
+                    view: None,
+                    preferences: json!({ "hiddenLayers": [2] }).as_object().unwrap().clone(),
+                };
+
+                db.set_user_state(&session, state.clone()).await.unwrap();
+
+                assert_eq!(db.user_state(&session).await.unwrap(), state);
+            }
+        })
+        .await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn it_resets_passwords() {
+        with_temp_context(|ctx, _| async move {
+            user_reg_login(&ctx).await;
+
+            let mut db = ctx.user_db_ref_mut().await;
+
+            let session = db
+                .login(UserCredentials {
+                    email: "foo@bar.de".into(),
+                    password: "secret123".into(),
+                })
+                .await
+                .unwrap();
+
+            db.set_notification_settings(
+                &session,
+                NotificationSettings {
+                    task_status: false,
+                    shares: true,
+                },
+            )
+            .await
+            .unwrap();
+
+            let (recipient, token) = db
+                .create_password_reset("foo@bar.de")
+                .await
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(recipient.email, "foo@bar.de");
+            assert!(!recipient.settings.task_status);
+
+            let reset = PasswordReset {
+                token,
+                password: "secret456".into(),
+            };
+
+            db.reset_password(reset.clone().validated().unwrap())
+                .await
+                .unwrap();
+            assert!(db.reset_password(reset.validated().unwrap()).await.is_err());
+
+            assert!(db.session(session.id).await.is_err());
+            assert!(db
+                .login(UserCredentials {
+                    email: "foo@bar.de".into(),
+                    password: "secret456".into(),
+                })
+                .await
+                .is_ok());
+        })
+        .await;
+    }
+
     #[allow(clippy::too_many_lines)]
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn it_persists_datasets() {
@@ -1037,6 +1439,7 @@ mod tests {
                     text: vec![],
                     bool: vec![],
                     datetime: vec![],
+                    category: vec![],
                     rename: None,
                 }),
                 force_ogr_time_filter: false,
@@ -1044,6 +1447,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                generalizations: vec![],
             };
 
             let meta_data = MetaDataDefinition::OgrMetaData(StaticMetaData::<
@@ -1058,6 +1462,7 @@ mod tests {
                     columns: [("foo".to_owned(), FeatureDataType::Float)]
                         .into_iter()
                         .collect(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             });
@@ -1077,6 +1482,7 @@ mod tests {
                         citation: "citation".to_owned(),
                         license: "license".to_owned(),
                         uri: "uri".to_owned(),
+                        version: None,
                     }),
                 }
                 .validated()
@@ -1094,6 +1500,8 @@ mod tests {
                         order: crate::datasets::listing::OrderBy::NameAsc,
                         offset: 0,
                         limit: 10,
+                        bbox: None,
+                        time: None,
                     }
                     .validated()
                     .unwrap(),
@@ -1111,6 +1519,7 @@ mod tests {
                     description: "desc".to_owned(),
                     source_operator: "OgrSource".to_owned(),
                     symbology: None,
+                    extent: ResultExtent::default(),
                     tags: vec![],
                     result_descriptor: TypedResultDescriptor::Vector(VectorResultDescriptor {
                         data_type: VectorDataType::MultiPoint,
@@ -1118,6 +1527,7 @@ mod tests {
                         columns: [("foo".to_owned(), FeatureDataType::Float)]
                             .into_iter()
                             .collect(),
+                        column_metadata: Default::default(),
                     }),
                 },
             );
@@ -1132,6 +1542,7 @@ mod tests {
                         citation: "citation".to_owned(),
                         license: "license".to_owned(),
                         uri: "uri".to_owned(),
+                        version: None,
                     })
                 }
             );
@@ -1148,6 +1559,7 @@ mod tests {
                         ),
                         time_interval: TimeInterval::default(),
                         spatial_resolution: SpatialResolution::zero_point_one(),
+                        spatial_filter: None,
                     })
                     .await
                     .unwrap(),
@@ -1216,6 +1628,7 @@ mod tests {
                     text: vec![],
                     bool: vec![],
                     datetime: vec![],
+                    category: vec![],
                     rename: None,
                 }),
                 force_ogr_time_filter: false,
@@ -1223,6 +1636,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                generalizations: vec![],
             };
 
             let meta_data = MetaDataDefinition::OgrMetaData(StaticMetaData::<
@@ -1237,6 +1651,7 @@ mod tests {
                     columns: [("foo".to_owned(), FeatureDataType::Float)]
                         .into_iter()
                         .collect(),
+                    column_metadata: Default::default(),
                 },
                 phantom: Default::default(),
             });
@@ -1296,6 +1711,8 @@ mod tests {
                         order: crate::datasets::listing::OrderBy::NameAsc,
                         offset: 0,
                         limit: 10,
+                        bbox: None,
+                        time: None,
                     }
                     .validated()
                     .unwrap(),
@@ -1318,6 +1735,7 @@ mod tests {
                 data_type: VectorDataType::Data,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
                 columns: Default::default(),
+                column_metadata: Default::default(),
             };
 
             let ds = AddDataset {
@@ -1342,6 +1760,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: descriptor.clone(),
                 phantom: Default::default(),
@@ -1369,6 +1788,8 @@ mod tests {
                         order: crate::datasets::listing::OrderBy::NameAsc,
                         offset: 0,
                         limit: 1,
+                        bbox: None,
+                        time: None,
                     }
                     .validated()
                     .unwrap(),
@@ -1388,6 +1809,8 @@ mod tests {
                         order: crate::datasets::listing::OrderBy::NameAsc,
                         offset: 0,
                         limit: 1,
+                        bbox: None,
+                        time: None,
                     }
                     .validated()
                     .unwrap(),
@@ -1410,6 +1833,7 @@ mod tests {
                 data_type: VectorDataType::Data,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
                 columns: Default::default(),
+                column_metadata: Default::default(),
             };
 
             let ds = AddDataset {
@@ -1434,6 +1858,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: descriptor.clone(),
                 phantom: Default::default(),
@@ -1478,6 +1903,7 @@ mod tests {
                 data_type: VectorDataType::Data,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
                 columns: Default::default(),
+                column_metadata: Default::default(),
             };
 
             let ds = AddDataset {
@@ -1502,6 +1928,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: descriptor.clone(),
                 phantom: Default::default(),
@@ -1566,6 +1993,7 @@ mod tests {
                 data_type: VectorDataType::Data,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
                 columns: Default::default(),
+                column_metadata: Default::default(),
             };
 
             let ds = AddDataset {
@@ -1590,6 +2018,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: descriptor.clone(),
                 phantom: Default::default(),
@@ -1654,6 +2083,7 @@ mod tests {
                 data_type: VectorDataType::Data,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
                 columns: Default::default(),
+                column_metadata: Default::default(),
             };
 
             let ds = AddDataset {
@@ -1678,6 +2108,7 @@ mod tests {
                     on_error: OgrSourceErrorSpec::Ignore,
                     sql_query: None,
                     attribute_query: None,
+                    generalizations: vec![],
                 },
                 result_descriptor: descriptor.clone(),
                 phantom: Default::default(),
@@ -1741,6 +2172,168 @@ mod tests {
         .await;
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn it_requires_accepted_dataset_terms() {
+        with_temp_context(|ctx, _| async move {
+            let session1 = ctx.user_db_ref_mut().await.anonymous().await.unwrap();
+            let session2 = ctx.user_db_ref_mut().await.anonymous().await.unwrap();
+
+            let ds = AddDataset {
+                id: None,
+                name: "OgrDataset".to_string(),
+                description: "My Ogr dataset".to_string(),
+                source_operator: "OgrSource".to_string(),
+                symbology: None,
+                provenance: None,
+            };
+
+            let meta = StaticMetaData {
+                loading_info: OgrSourceDataset {
+                    file_name: Default::default(),
+                    layer_name: "".to_string(),
+                    data_type: None,
+                    time: Default::default(),
+                    default_geometry: None,
+                    columns: None,
+                    force_ogr_time_filter: false,
+                    force_ogr_spatial_filter: false,
+                    on_error: OgrSourceErrorSpec::Ignore,
+                    sql_query: None,
+                    attribute_query: None,
+                    generalizations: vec![],
+                },
+                result_descriptor: VectorResultDescriptor {
+                    data_type: VectorDataType::Data,
+                    spatial_reference: SpatialReferenceOption::Unreferenced,
+                    columns: Default::default(),
+                    column_metadata: Default::default(),
+                },
+                phantom: Default::default(),
+            };
+
+            let meta = ctx
+                .dataset_db_ref_mut()
+                .await
+                .wrap_meta_data(MetaDataDefinition::OgrMetaData(meta));
+
+            let id = ctx
+                .dataset_db_ref_mut()
+                .await
+                .add_dataset(&session1, ds.validated().unwrap(), meta)
+                .await
+                .unwrap();
+
+            ctx.dataset_db_ref_mut()
+                .await
+                .add_dataset_permission(
+                    &session1,
+                    DatasetPermission {
+                        role: session2.user.id.into(),
+                        dataset: id.clone(),
+                        permission: Permission::Read,
+                    },
+                )
+                .await
+                .unwrap();
+
+            let license = |terms: &str| {
+                DatasetLicense {
+                    dataset: id.clone(),
+                    license: "CC-BY-NC-4.0".to_string(),
+                    terms: Some(terms.to_string()),
+                }
+                .validated()
+                .unwrap()
+            };
+
+            ctx.dataset_db_ref_mut()
+                .await
+                .set_dataset_license(&session1, license("Non-commercial use only."))
+                .await
+                .unwrap();
+
+            let meta: Result<
+                Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+            > = ctx
+                .dataset_db_ref()
+                .await
+                .session_meta_data(&session2, &id)
+                .await;
+
+            assert!(matches!(
+                meta,
+                Err(error::Error::DatasetTermsNotAccepted { .. })
+            ));
+
+            let status = ctx
+                .dataset_db_ref()
+                .await
+                .dataset_license(&session2, &id)
+                .await
+                .unwrap();
+
+            assert_eq!(status.terms_version, 1);
+            assert!(!status.terms_accepted);
+
+            ctx.dataset_db_ref_mut()
+                .await
+                .accept_dataset_terms(&session2, &id, 1)
+                .await
+                .unwrap();
+
+            let meta: Result<
+                Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+            > = ctx
+                .dataset_db_ref()
+                .await
+                .session_meta_data(&session2, &id)
+                .await;
+
+            assert!(meta.is_ok());
+
+            // keeping the terms does not require a new acceptance
+            ctx.dataset_db_ref_mut()
+                .await
+                .set_dataset_license(&session1, license("Non-commercial use only."))
+                .await
+                .unwrap();
+
+            assert!(
+                ctx.dataset_db_ref()
+                    .await
+                    .dataset_license(&session2, &id)
+                    .await
+                    .unwrap()
+                    .terms_accepted
+            );
+
+            ctx.dataset_db_ref_mut()
+                .await
+                .set_dataset_license(&session1, license("No use at all."))
+                .await
+                .unwrap();
+
+            let status = ctx
+                .dataset_db_ref()
+                .await
+                .dataset_license(&session2, &id)
+                .await
+                .unwrap();
+
+            assert_eq!(status.terms_version, 2);
+            assert!(!status.terms_accepted);
+
+            assert!(matches!(
+                ctx.dataset_db_ref_mut()
+                    .await
+                    .accept_dataset_terms(&session2, &id, 1)
+                    .await,
+                Err(error::Error::OutdatedDatasetTerms { current: 2 })
+            ));
+        })
+        .await;
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn it_secures_uploads() {
         with_temp_context(|ctx, _| async move {
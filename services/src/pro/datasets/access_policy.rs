@@ -0,0 +1,453 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr};
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::primitives::VectorQueryRectangle;
+use geoengine_operators::engine::{Cacheability, MetaData, ResultExtent, VectorResultDescriptor};
+use geoengine_operators::source::OgrSourceDataset;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use super::DatasetAccessPolicy;
+use crate::error::{self, Error, Result};
+use crate::pro::users::UserSession;
+
+/// A range of IP addresses in CIDR notation, e.g., `192.168.0.0/16`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct IpRange {
+    network: IpAddr,
+    prefix_length: u8,
+}
+
+impl IpRange {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ipv4_mapped(ip)) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_length))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_length))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Clients that connect to a dual-stack socket via IPv4 have IPv4-mapped IPv6 addresses
+fn ipv4_mapped(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => match v6.octets() {
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, a, b, c, d] => {
+                IpAddr::V4(Ipv4Addr::new(a, b, c, d))
+            }
+            _ => ip,
+        },
+        IpAddr::V4(_) => ip,
+    }
+}
+
+impl FromStr for IpRange {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidIpRange {
+            range: s.to_string(),
+        };
+
+        let (network, prefix_length) = match s.split_once('/') {
+            Some((network, prefix_length)) => (
+                network,
+                Some(prefix_length.parse::<u8>().map_err(|_| invalid())?),
+            ),
+            None => (s, None),
+        };
+
+        let network = IpAddr::from_str(network).map_err(|_| invalid())?;
+        let max_prefix_length = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_length = prefix_length.unwrap_or(max_prefix_length);
+
+        ensure!(
+            prefix_length <= max_prefix_length,
+            error::InvalidIpRange {
+                range: s.to_string()
+            }
+        );
+
+        Ok(Self {
+            network,
+            prefix_length,
+        })
+    }
+}
+
+impl fmt::Display for IpRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_length)
+    }
+}
+
+impl TryFrom<String> for IpRange {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<IpRange> for String {
+    fn from(range: IpRange) -> Self {
+        range.to_string()
+    }
+}
+
+/// The combination of all access policies that apply to a session for a single dataset
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessRestriction {
+    hidden_columns: HashSet<String>,
+    row_filters: Vec<String>,
+    /// the IP ranges of each policy, the client must be in one range of every policy
+    ip_ranges: Vec<Vec<IpRange>>,
+    /// the institutions of each policy, the user must be part of one institution of every policy
+    institutions: Vec<Vec<String>>,
+}
+
+impl AccessRestriction {
+    /// Combines the policies s.t. a column is hidden if any policy hides it and a feature is only
+    /// visible if it satisfies the row filters of all policies
+    pub fn from_policies<'p>(policies: impl IntoIterator<Item = &'p DatasetAccessPolicy>) -> Self {
+        let mut restriction = Self::default();
+
+        for policy in policies {
+            restriction
+                .hidden_columns
+                .extend(policy.hidden_columns.iter().cloned());
+            restriction
+                .row_filters
+                .extend(policy.row_filter.iter().cloned());
+
+            if !policy.ip_ranges.is_empty() {
+                restriction.ip_ranges.push(policy.ip_ranges.clone());
+            }
+            if !policy.institutions.is_empty() {
+                restriction.institutions.push(policy.institutions.clone());
+            }
+        }
+
+        restriction
+    }
+
+    pub fn is_unrestricted(&self) -> bool {
+        self.hidden_columns.is_empty()
+            && self.row_filters.is_empty()
+            && self.ip_ranges.is_empty()
+            && self.institutions.is_empty()
+    }
+
+    /// Fails unless the client of the session is in the IP ranges and the user is part of the
+    /// institutions of all policies. Sessions without a known client address are rejected by
+    /// IP ranges.
+    pub fn ensure_permitted(&self, session: &UserSession, dataset: &DatasetId) -> Result<()> {
+        ensure!(
+            self.ip_ranges.iter().all(|ranges| session
+                .client_ip
+                .map_or(false, |ip| ranges.iter().any(|range| range.contains(ip)))),
+            error::DatasetAccessDeniedForIp {
+                dataset: dataset.clone(),
+            }
+        );
+        ensure!(
+            self.institutions.iter().all(|institutions| session
+                .user
+                .institution
+                .as_ref()
+                .map_or(false, |institution| institutions.contains(institution))),
+            error::DatasetAccessDeniedForInstitution {
+                dataset: dataset.clone(),
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Removes the hidden columns
+    pub fn restrict_result_descriptor(&self, result_descriptor: &mut VectorResultDescriptor) {
+        result_descriptor
+            .columns
+            .retain(|column, _| !self.hidden_columns.contains(column));
+    }
+
+    /// Removes the hidden columns from the column specification, taking their renaming into
+    /// account, and adds the row filters to the attribute query of the dataset
+    pub fn restrict_loading_info(&self, loading_info: &mut OgrSourceDataset) {
+        if let Some(columns) = loading_info.columns.as_mut() {
+            let renamed = columns.rename.clone().unwrap_or_default();
+            let is_visible = |column: &String| {
+                !self
+                    .hidden_columns
+                    .contains(renamed.get(column).unwrap_or(column))
+            };
+
+            columns.int.retain(is_visible);
+            columns.float.retain(is_visible);
+            columns.text.retain(is_visible);
+            columns.bool.retain(is_visible);
+            columns.datetime.retain(is_visible);
+            columns.category.retain(is_visible);
+        }
+
+        if self.row_filters.is_empty() {
+            return;
+        }
+
+        let filters = loading_info
+            .attribute_query
+            .iter()
+            .chain(&self.row_filters)
+            .map(|filter| format!("({})", filter))
+            .collect::<Vec<_>>();
+
+        loading_info.attribute_query = Some(filters.join(" AND "));
+    }
+}
+
+/// Enforces an [`AccessRestriction`] on the meta data of an OGR dataset
+#[derive(Debug, Clone)]
+pub struct RestrictedMetaData {
+    meta_data: Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>,
+    restriction: AccessRestriction,
+}
+
+impl RestrictedMetaData {
+    /// Wraps the `meta_data` unless the `restriction` does not restrict anything
+    pub fn wrap(
+        meta_data: Box<
+            dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
+        >,
+        restriction: AccessRestriction,
+    ) -> Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>> {
+        if restriction.is_unrestricted() {
+            return meta_data;
+        }
+
+        Box::new(Self {
+            meta_data,
+            restriction,
+        })
+    }
+}
+
+#[async_trait]
+impl MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>
+    for RestrictedMetaData
+{
+    async fn loading_info(
+        &self,
+        query: VectorQueryRectangle,
+    ) -> geoengine_operators::util::Result<OgrSourceDataset> {
+        let mut loading_info = self.meta_data.loading_info(query).await?;
+        self.restriction.restrict_loading_info(&mut loading_info);
+        Ok(loading_info)
+    }
+
+    async fn result_descriptor(&self) -> geoengine_operators::util::Result<VectorResultDescriptor> {
+        let mut result_descriptor = self.meta_data.result_descriptor().await?;
+        self.restriction
+            .restrict_result_descriptor(&mut result_descriptor);
+        Ok(result_descriptor)
+    }
+
+    async fn extent(&self) -> geoengine_operators::util::Result<ResultExtent> {
+        self.meta_data.extent().await
+    }
+
+    /// Results must not be shared with sessions that have other restrictions
+    fn cacheability(&self) -> Cacheability {
+        Cacheability::SessionDependent
+    }
+
+    fn box_clone(
+        &self,
+    ) -> Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::MockableSession;
+    use crate::pro::datasets::RoleId;
+    use geoengine_datatypes::collections::VectorDataType;
+    use geoengine_datatypes::dataset::InternalDatasetId;
+    use geoengine_datatypes::primitives::{BoundingBox2D, FeatureDataType, SpatialResolution};
+    use geoengine_datatypes::spatial_reference::SpatialReferenceOption;
+    use geoengine_datatypes::util::Identifier;
+    use geoengine_operators::engine::StaticMetaData;
+    use geoengine_operators::source::{OgrSourceColumnSpec, OgrSourceErrorSpec};
+    use std::collections::HashMap;
+
+    fn policy(hidden_columns: &[&str], row_filter: Option<&str>) -> DatasetAccessPolicy {
+        DatasetAccessPolicy {
+            role: RoleId::new(),
+            dataset: InternalDatasetId::new().into(),
+            hidden_columns: hidden_columns.iter().map(ToString::to_string).collect(),
+            row_filter: row_filter.map(ToString::to_string),
+            ip_ranges: vec![],
+            institutions: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn it_restricts_meta_data() {
+        let meta_data = StaticMetaData {
+            loading_info: OgrSourceDataset {
+                file_name: "species.csv".into(),
+                layer_name: "species".to_string(),
+                data_type: Some(VectorDataType::MultiPoint),
+                time: Default::default(),
+                default_geometry: None,
+                columns: Some(OgrSourceColumnSpec {
+                    format_specifics: None,
+                    x: "x".to_string(),
+                    y: Some("y".to_string()),
+                    int: vec!["count".to_string()],
+                    float: vec![],
+                    text: vec!["name".to_string(), "site".to_string()],
+                    bool: vec![],
+                    datetime: vec![],
+                    category: vec![],
+                    rename: Some([("site".to_string(), "location".to_string())].into()),
+                }),
+                force_ogr_time_filter: false,
+                force_ogr_spatial_filter: false,
+                on_error: OgrSourceErrorSpec::Ignore,
+                sql_query: None,
+                attribute_query: Some("count > 0".to_string()),
+                generalizations: vec![],
+            },
+            result_descriptor: VectorResultDescriptor {
+                data_type: VectorDataType::MultiPoint,
+                spatial_reference: SpatialReferenceOption::Unreferenced,
+                columns: [
+                    ("count".to_string(), FeatureDataType::Int),
+                    ("name".to_string(), FeatureDataType::Text),
+                    ("location".to_string(), FeatureDataType::Text),
+                ]
+                .into_iter()
+                .collect(),
+                column_metadata: Default::default(),
+            },
+            phantom: Default::default(),
+        };
+
+        let restriction = AccessRestriction::from_policies(&[
+            policy(&["location"], Some("endangered = 0")),
+            policy(&[], Some("count < 100")),
+        ]);
+        let restricted = RestrictedMetaData::wrap(Box::new(meta_data), restriction);
+
+        let loading_info = restricted
+            .loading_info(VectorQueryRectangle {
+                spatial_bounds: BoundingBox2D::new_unchecked((0., 0.).into(), (1., 1.).into()),
+                time_interval: Default::default(),
+                spatial_resolution: SpatialResolution::one(),
+                spatial_filter: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(loading_info.columns.unwrap().text, vec!["name".to_string()]);
+        assert_eq!(
+            loading_info.attribute_query.as_deref(),
+            Some("(count > 0) AND (endangered = 0) AND (count < 100)")
+        );
+
+        assert_eq!(
+            restricted.result_descriptor().await.unwrap().columns,
+            [
+                ("count".to_string(), FeatureDataType::Int),
+                ("name".to_string(), FeatureDataType::Text),
+            ]
+            .into_iter()
+            .collect::<HashMap<_, _>>()
+        );
+
+        // restricted results must not be served to other sessions from the result cache
+        assert_eq!(restricted.cacheability(), Cacheability::SessionDependent);
+    }
+
+    #[test]
+    fn it_skips_empty_restrictions() {
+        assert!(AccessRestriction::from_policies(&[policy(&[], None)]).is_unrestricted());
+        assert!(!AccessRestriction::from_policies(&[policy(&["a"], None)]).is_unrestricted());
+    }
+
+    #[test]
+    fn it_parses_ip_ranges() {
+        let range: IpRange = "192.168.0.0/16".parse().unwrap();
+        assert!(range.contains("192.168.10.1".parse().unwrap()));
+        assert!(range.contains("::ffff:192.168.10.1".parse().unwrap()));
+        assert!(!range.contains("192.169.0.1".parse().unwrap()));
+        assert!(!range.contains("2001:db8::1".parse().unwrap()));
+
+        let range: IpRange = "2001:db8::/32".parse().unwrap();
+        assert!(range.contains("2001:db8:1::1".parse().unwrap()));
+        assert!(!range.contains("2001:db9::1".parse().unwrap()));
+
+        assert_eq!(
+            "10.0.0.1".parse::<IpRange>().unwrap().to_string(),
+            "10.0.0.1/32"
+        );
+        assert!("0.0.0.0/0"
+            .parse::<IpRange>()
+            .unwrap()
+            .contains("8.8.8.8".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<IpRange>().is_err());
+        assert!("10.0.0/8".parse::<IpRange>().is_err());
+    }
+
+    #[test]
+    fn it_checks_ip_ranges_and_institutions() {
+        let dataset: DatasetId = InternalDatasetId::new().into();
+
+        let restriction = AccessRestriction::from_policies(&[
+            DatasetAccessPolicy {
+                ip_ranges: vec!["10.0.0.0/8".parse().unwrap()],
+                ..policy(&[], None)
+            },
+            DatasetAccessPolicy {
+                institutions: vec!["University of Marburg".to_string()],
+                ..policy(&[], None)
+            },
+        ]);
+
+        let mut session = UserSession::mock();
+        assert!(matches!(
+            restriction.ensure_permitted(&session, &dataset),
+            Err(Error::DatasetAccessDeniedForIp { .. })
+        ));
+
+        session.client_ip = Some("10.1.2.3".parse().unwrap());
+        assert!(matches!(
+            restriction.ensure_permitted(&session, &dataset),
+            Err(Error::DatasetAccessDeniedForInstitution { .. })
+        ));
+
+        session.user.institution = Some("University of Marburg".to_string());
+        assert!(restriction.ensure_permitted(&session, &dataset).is_ok());
+
+        session.client_ip = Some("192.168.0.1".parse().unwrap());
+        assert!(restriction.ensure_permitted(&session, &dataset).is_err());
+    }
+}
@@ -21,7 +21,7 @@ use geoengine_datatypes::primitives::{
 use geoengine_datatypes::raster::RasterDataType;
 use geoengine_datatypes::spatial_reference::{SpatialReference, SpatialReferenceAuthority};
 use geoengine_operators::engine::{
-    MetaData, MetaDataProvider, RasterResultDescriptor, VectorResultDescriptor,
+    MetaData, MetaDataProvider, RasterResultDescriptor, ResultExtent, VectorResultDescriptor,
 };
 use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
 use geoengine_operators::source::{
@@ -170,6 +170,7 @@ impl SentinelS2L2aCogsDataProvider {
                             .into(),
                             measurement: Measurement::Unitless, // TODO: add measurement
                             no_data_value: band.no_data_value,
+                            dimensions: vec![],
                         }
                         .into(),
                         symbology: Some(Symbology::Raster(RasterSymbology {
@@ -188,6 +189,7 @@ impl SentinelS2L2aCogsDataProvider {
                             )
                             .expect("valid colorizer"),
                         })), // TODO: individual colorizer per band
+                        extent: ResultExtent::default(),
                     };
 
                     let dataset = SentinelDataset {
@@ -358,6 +360,7 @@ impl SentinelS2L2aCogsMetaData {
                 properties_mapping: None,
                 gdal_open_options: None,
                 gdal_config_options: None,
+                apply_scale_offset: false,
             }),
         })
     }
@@ -509,6 +512,7 @@ impl MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>
             .into(),
             measurement: Measurement::Unitless,
             no_data_value: self.band.no_data_value,
+            dimensions: vec![],
         })
     }
 
@@ -641,6 +645,7 @@ mod tests {
                         .timestamp_millis(),
                 )?,
                 spatial_resolution: SpatialResolution::one(),
+                spatial_filter: (),
             })
             .await
             .unwrap();
@@ -662,6 +667,7 @@ mod tests {
                 properties_mapping: None,
                 gdal_open_options: None,
                 gdal_config_options: None,
+                apply_scale_offset: false,
             }),
         }];
 
@@ -745,6 +751,7 @@ mod tests {
                 166_021.44 / 256.,
                 (9_329_005.18 - 534_994.66) / 256.,
             ),
+            spatial_filter: (),
         };
 
         let ctx = MockQueryContext::new(ChunkByteSize::MAX);
@@ -846,6 +853,7 @@ mod tests {
             )
             .unwrap(),
             spatial_resolution: SpatialResolution::new_unchecked(10., 10.),
+            spatial_filter: (),
         };
 
         let loading_info = meta.loading_info(query).await.unwrap();
@@ -875,6 +883,7 @@ mod tests {
                     properties_mapping: None,
                     gdal_open_options: None,
                     gdal_config_options: None,
+                    apply_scale_offset: false,
                 }),
             }]
         );
@@ -1,19 +1,19 @@
-use crate::contexts::MockableSession;
+use crate::contexts::{BackupDb, MockableSession};
 use crate::datasets::listing::SessionMetaDataProvider;
 use crate::datasets::listing::{
     DatasetListOptions, DatasetListing, DatasetProvider, ExternalDatasetProvider, OrderBy,
     ProvenanceOutput,
 };
 use crate::datasets::storage::{
-    AddDataset, Dataset, DatasetDb, DatasetProviderDb, DatasetProviderListOptions,
-    DatasetProviderListing, DatasetStore, DatasetStorer, ExternalDatasetProviderDefinition,
-    MetaDataDefinition,
+    AddDataset, Dataset, DatasetDb, DatasetDefinition, DatasetProviderDb,
+    DatasetProviderListOptions, DatasetProviderListing, DatasetStore, DatasetStorer,
+    ExternalDatasetProviderDefinition, MetaDataDefinition, UpdateDataset,
 };
 use crate::datasets::upload::{Upload, UploadDb, UploadId};
 use crate::error;
 use crate::error::Result;
 use crate::pro::datasets::Permission;
-use crate::pro::users::{UserId, UserSession};
+use crate::pro::users::{Quota, UserId, UserSession};
 use crate::util::user_input::Validated;
 use async_trait::async_trait;
 use geoengine_datatypes::primitives::{RasterQueryRectangle, VectorQueryRectangle};
@@ -30,15 +30,23 @@ use geoengine_operators::source::{
 use geoengine_operators::{mock::MockDatasetDataSourceLoadingInfo, source::GdalMetaDataStatic};
 use log::{info, warn};
 use snafu::ensure;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use super::storage::UpdateDatasetPermissions;
-use super::DatasetPermission;
+use super::storage::{DatasetAccessPolicies, DatasetLicenses, UpdateDatasetPermissions};
+use super::{
+    AccessRestriction, DatasetAccessPolicy, DatasetLicense, DatasetLicenseStatus,
+    DatasetPermission, RestrictedMetaData, Role, RoleId,
+};
 
 #[derive(Default)]
 pub struct ProHashMapDatasetDb {
     datasets: HashMap<DatasetId, Dataset>,
     dataset_permissions: Vec<DatasetPermission>,
+    access_policies: Vec<DatasetAccessPolicy>,
+    /// the licenses of the datasets with the current version of their terms
+    licenses: HashMap<DatasetId, (DatasetLicense, u32)>,
+    /// the versions of the terms the users have accepted
+    terms_acceptances: HashMap<(DatasetId, UserId), u32>,
     ogr_datasets: HashMap<
         InternalDatasetId,
         StaticMetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>,
@@ -55,12 +63,122 @@ pub struct ProHashMapDatasetDb {
         InternalDatasetId,
         Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>,
     >,
+    /// the definitions of the meta data above for exporting them
+    meta_data_definitions: HashMap<InternalDatasetId, MetaDataDefinition>,
     uploads: HashMap<UserId, HashMap<UploadId, Upload>>,
     external_providers: HashMap<DatasetProviderId, Box<dyn ExternalDatasetProviderDefinition>>,
 }
 
 impl DatasetDb<UserSession> for ProHashMapDatasetDb {}
 
+impl ProHashMapDatasetDb {
+    /// Stores the dataset without granting permissions on it
+    async fn insert_dataset(
+        &mut self,
+        dataset: AddDataset,
+        meta_data: &dyn ProHashMapStorable,
+    ) -> DatasetId {
+        let id = dataset
+            .id
+            .unwrap_or_else(|| InternalDatasetId::new().into());
+        let internal_id = id.internal().expect("from AddDataset");
+        let result_descriptor = meta_data.store(internal_id, self);
+        let extent = self.meta_data_definitions[&internal_id].extent().await;
+
+        let d: Dataset = Dataset {
+            id: id.clone(),
+            name: dataset.name,
+            description: dataset.description,
+            result_descriptor,
+            source_operator: dataset.source_operator,
+            symbology: dataset.symbology,
+            provenance: dataset.provenance,
+            extent,
+        };
+        self.datasets.insert(id.clone(), d);
+
+        id
+    }
+
+    /// Owners and the system are not subject to the access policies of a dataset
+    fn is_owner(&self, session: &UserSession, dataset: &DatasetId) -> bool {
+        session.roles.contains(&Role::system_role_id())
+            || self.dataset_permissions.iter().any(|p| {
+                p.dataset == *dataset
+                    && p.permission == Permission::Owner
+                    && session.roles.contains(&p.role)
+            })
+    }
+
+    fn access_restriction(&self, session: &UserSession, dataset: &DatasetId) -> AccessRestriction {
+        if self.is_owner(session, dataset) {
+            return AccessRestriction::default();
+        }
+
+        AccessRestriction::from_policies(
+            self.access_policies
+                .iter()
+                .filter(|p| p.dataset == *dataset && session.roles.contains(&p.role)),
+        )
+    }
+
+    /// Owners, writers and the system can change the properties of a dataset
+    fn is_writer(&self, session: &UserSession, dataset: &DatasetId) -> bool {
+        session.roles.contains(&Role::system_role_id())
+            || self.dataset_permissions.iter().any(|p| {
+                p.dataset == *dataset
+                    && matches!(p.permission, Permission::Write | Permission::Owner)
+                    && session.roles.contains(&p.role)
+            })
+    }
+
+    fn is_permitted(&self, session: &UserSession, dataset: &DatasetId) -> bool {
+        self.dataset_permissions
+            .iter()
+            .any(|p| p.dataset == *dataset && session.roles.contains(&p.role))
+    }
+
+    /// Owners and the system do not need to accept the terms of a dataset
+    fn has_accepted_terms(&self, session: &UserSession, dataset: &DatasetId) -> bool {
+        match self.licenses.get(dataset) {
+            Some((DatasetLicense { terms: Some(_), .. }, version)) => {
+                self.is_owner(session, dataset)
+                    || self
+                        .terms_acceptances
+                        .get(&(dataset.clone(), session.user.id))
+                        == Some(version)
+            }
+            _ => true,
+        }
+    }
+
+    /// Fails if the session is not permitted to query the dataset, did not accept its terms or
+    /// does not satisfy its access policies. Returns the restriction of the session otherwise.
+    fn ensure_queryable(
+        &self,
+        session: &UserSession,
+        dataset: &DatasetId,
+    ) -> Result<AccessRestriction> {
+        ensure!(
+            self.is_permitted(session, dataset),
+            error::DatasetPermissionDenied {
+                dataset: dataset.clone(),
+            }
+        );
+        ensure!(
+            self.has_accepted_terms(session, dataset),
+            error::DatasetTermsNotAccepted {
+                dataset: dataset.clone(),
+            }
+        );
+
+        let restriction = self.access_restriction(session, dataset);
+        restriction.ensure_permitted(session, dataset)?;
+
+        Ok(restriction)
+    }
+}
+
 #[async_trait]
 impl DatasetProviderDb<UserSession> for ProHashMapDatasetDb {
     async fn add_dataset_provider(
@@ -132,6 +250,8 @@ impl ProHashMapStorable
 {
     fn store(&self, id: InternalDatasetId, db: &mut ProHashMapDatasetDb) -> TypedResultDescriptor {
         db.ogr_datasets.insert(id, self.clone());
+        db.meta_data_definitions
+            .insert(id, MetaDataDefinition::OgrMetaData(self.clone()));
         self.result_descriptor.clone().into()
     }
 }
@@ -145,6 +265,8 @@ impl ProHashMapStorable
 {
     fn store(&self, id: InternalDatasetId, db: &mut ProHashMapDatasetDb) -> TypedResultDescriptor {
         db.mock_datasets.insert(id, self.clone());
+        db.meta_data_definitions
+            .insert(id, MetaDataDefinition::MockMetaData(self.clone()));
         self.result_descriptor.clone().into()
     }
 }
@@ -152,6 +274,8 @@ impl ProHashMapStorable
 impl ProHashMapStorable for GdalMetaDataRegular {
     fn store(&self, id: InternalDatasetId, db: &mut ProHashMapDatasetDb) -> TypedResultDescriptor {
         db.gdal_datasets.insert(id, Box::new(self.clone()));
+        db.meta_data_definitions
+            .insert(id, MetaDataDefinition::GdalMetaDataRegular(self.clone()));
         self.result_descriptor.clone().into()
     }
 }
@@ -159,6 +283,8 @@ impl ProHashMapStorable for GdalMetaDataRegular {
 impl ProHashMapStorable for GdalMetaDataStatic {
     fn store(&self, id: InternalDatasetId, db: &mut ProHashMapDatasetDb) -> TypedResultDescriptor {
         db.gdal_datasets.insert(id, Box::new(self.clone()));
+        db.meta_data_definitions
+            .insert(id, MetaDataDefinition::GdalStatic(self.clone()));
         self.result_descriptor.clone().into()
     }
 }
@@ -166,6 +292,8 @@ impl ProHashMapStorable for GdalMetaDataStatic {
 impl ProHashMapStorable for GdalMetadataNetCdfCf {
     fn store(&self, id: InternalDatasetId, db: &mut ProHashMapDatasetDb) -> TypedResultDescriptor {
         db.gdal_datasets.insert(id, Box::new(self.clone()));
+        db.meta_data_definitions
+            .insert(id, MetaDataDefinition::GdalMetadataNetCdfCf(self.clone()));
         self.result_descriptor.clone().into()
     }
 }
@@ -180,22 +308,19 @@ impl DatasetStore<UserSession> for ProHashMapDatasetDb {
     ) -> Result<DatasetId> {
         info!("Add dataset {:?}", dataset.user_input.name);
 
-        let dataset = dataset.user_input;
-        let id = dataset
-            .id
-            .unwrap_or_else(|| InternalDatasetId::new().into());
-        let result_descriptor = meta_data.store(id.internal().expect("from AddDataset"), self);
+        if let Some(organization) = &session.organization {
+            let role = organization.id.into();
+            let datasets = self
+                .dataset_permissions
+                .iter()
+                .filter(|p| p.role == role)
+                .count();
+            Quota::check(organization.quota.max_datasets, datasets, "datasets")?;
+        }
 
-        let d: Dataset = Dataset {
-            id: id.clone(),
-            name: dataset.name,
-            description: dataset.description,
-            result_descriptor,
-            source_operator: dataset.source_operator,
-            symbology: dataset.symbology,
-            provenance: dataset.provenance,
-        };
-        self.datasets.insert(id.clone(), d);
+        let id = self
+            .insert_dataset(dataset.user_input, meta_data.as_ref())
+            .await;
 
         self.dataset_permissions.push(DatasetPermission {
             role: session.user.id.into(),
@@ -203,9 +328,83 @@ impl DatasetStore<UserSession> for ProHashMapDatasetDb {
             permission: Permission::Owner,
         });
 
+        // datasets that are added in an organization are shared with its members
+        if let Some(organization) = &session.organization {
+            self.dataset_permissions.push(DatasetPermission {
+                role: organization.id.into(),
+                dataset: id.clone(),
+                permission: Permission::Read,
+            });
+        }
+
         Ok(id)
     }
 
+    async fn update_dataset(
+        &mut self,
+        session: &UserSession,
+        dataset: &DatasetId,
+        update: Validated<UpdateDataset>,
+    ) -> Result<()> {
+        ensure!(
+            self.is_writer(session, dataset),
+            error::DatasetUpdatePermissionDenied {
+                dataset: dataset.clone(),
+            }
+        );
+
+        info!("Update dataset {:?}", dataset);
+
+        let dataset = self
+            .datasets
+            .get_mut(dataset)
+            .ok_or(error::Error::UnknownDatasetId)?;
+
+        update.user_input.apply(dataset);
+
+        Ok(())
+    }
+
+    async fn ensure_writable(&self, session: &UserSession, dataset: &DatasetId) -> Result<()> {
+        ensure!(
+            self.is_writer(session, dataset),
+            error::DatasetUpdatePermissionDenied {
+                dataset: dataset.clone(),
+            }
+        );
+
+        Ok(())
+    }
+
+    async fn delete_dataset(&mut self, session: &UserSession, dataset: &DatasetId) -> Result<()> {
+        ensure!(
+            self.is_owner(session, dataset),
+            error::DatasetDeletionPermissionDenied {
+                dataset: dataset.clone(),
+            }
+        );
+
+        info!("Delete dataset {:?}", dataset);
+
+        self.datasets
+            .remove(dataset)
+            .ok_or(error::Error::UnknownDatasetId)?;
+
+        self.dataset_permissions.retain(|p| p.dataset != *dataset);
+        self.access_policies.retain(|p| p.dataset != *dataset);
+        self.licenses.remove(dataset);
+        self.terms_acceptances.retain(|(d, _), _| d != dataset);
+
+        if let Some(id) = dataset.internal() {
+            self.ogr_datasets.remove(&id);
+            self.mock_datasets.remove(&id);
+            self.gdal_datasets.remove(&id);
+            self.meta_data_definitions.remove(&id);
+        }
+
+        Ok(())
+    }
+
     fn wrap_meta_data(&self, meta: MetaDataDefinition) -> Self::StorageType {
         Box::new(meta)
     }
@@ -220,10 +419,13 @@ impl DatasetProvider<UserSession> for ProHashMapDatasetDb {
     ) -> Result<Vec<DatasetListing>> {
         let options = options.user_input;
 
+        // a dataset may be permitted by multiple roles, e.g., the user's and its organization's
+        let mut seen = HashSet::new();
+
         let iter = self
             .dataset_permissions
             .iter()
-            .filter(|p| session.roles.contains(&p.role))
+            .filter(|p| session.roles.contains(&p.role) && seen.insert(p.dataset.clone()))
             .filter_map(|p| {
                 let matching_dataset = self.datasets.get(&p.dataset);
 
@@ -240,6 +442,7 @@ impl DatasetProvider<UserSession> for ProHashMapDatasetDb {
         } else {
             iter.collect()
         };
+        list.retain(|d| options.matches_extent(&d.extent));
 
         match options.order {
             OrderBy::NameAsc => list.sort_by(|a, b| a.name.cmp(&b.name)),
@@ -260,16 +463,26 @@ impl DatasetProvider<UserSession> for ProHashMapDatasetDb {
         ensure!(
             self.dataset_permissions
                 .iter()
-                .any(|p| session.roles.contains(&p.role)),
+                .any(|p| p.dataset == *dataset && session.roles.contains(&p.role)),
             error::DatasetPermissionDenied {
                 dataset: dataset.clone(),
             }
         );
 
-        self.datasets
+        let mut dataset_definition = self
+            .datasets
             .get(dataset)
             .map(Clone::clone)
-            .ok_or(error::Error::UnknownDatasetId)
+            .ok_or(error::Error::UnknownDatasetId)?;
+
+        if let TypedResultDescriptor::Vector(result_descriptor) =
+            &mut dataset_definition.result_descriptor
+        {
+            self.access_restriction(session, dataset)
+                .restrict_result_descriptor(result_descriptor);
+        }
+
+        Ok(dataset_definition)
     }
 
     async fn provenance(
@@ -282,7 +495,7 @@ impl DatasetProvider<UserSession> for ProHashMapDatasetDb {
                 ensure!(
                     self.dataset_permissions
                         .iter()
-                        .any(|p| session.roles.contains(&p.role)),
+                        .any(|p| p.dataset == *dataset && session.roles.contains(&p.role)),
                     error::DatasetPermissionDenied {
                         dataset: dataset.clone(),
                     }
@@ -341,6 +554,155 @@ impl UpdateDatasetPermissions for ProHashMapDatasetDb {
     }
 }
 
+#[async_trait]
+impl DatasetAccessPolicies for ProHashMapDatasetDb {
+    async fn set_dataset_access_policy(
+        &mut self,
+        session: &UserSession,
+        policy: Validated<DatasetAccessPolicy>,
+    ) -> Result<()> {
+        let policy = policy.user_input;
+
+        ensure!(
+            self.is_owner(session, &policy.dataset),
+            error::DatasetAccessPolicyPermissionDenied {
+                dataset: policy.dataset,
+            }
+        );
+
+        self.access_policies
+            .retain(|p| p.dataset != policy.dataset || p.role != policy.role);
+        self.access_policies.push(policy);
+
+        Ok(())
+    }
+
+    async fn dataset_access_policies(
+        &self,
+        session: &UserSession,
+        dataset: &DatasetId,
+    ) -> Result<Vec<DatasetAccessPolicy>> {
+        ensure!(
+            self.is_owner(session, dataset),
+            error::DatasetAccessPolicyPermissionDenied {
+                dataset: dataset.clone(),
+            }
+        );
+
+        Ok(self
+            .access_policies
+            .iter()
+            .filter(|p| p.dataset == *dataset)
+            .cloned()
+            .collect())
+    }
+
+    async fn remove_dataset_access_policy(
+        &mut self,
+        session: &UserSession,
+        dataset: &DatasetId,
+        role: RoleId,
+    ) -> Result<()> {
+        ensure!(
+            self.is_owner(session, dataset),
+            error::DatasetAccessPolicyPermissionDenied {
+                dataset: dataset.clone(),
+            }
+        );
+
+        let index = self
+            .access_policies
+            .iter()
+            .position(|p| p.dataset == *dataset && p.role == role)
+            .ok_or(error::Error::UnknownDatasetAccessPolicy)?;
+        self.access_policies.remove(index);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DatasetLicenses for ProHashMapDatasetDb {
+    async fn set_dataset_license(
+        &mut self,
+        session: &UserSession,
+        license: Validated<DatasetLicense>,
+    ) -> Result<()> {
+        let license = license.user_input;
+
+        ensure!(
+            self.is_owner(session, &license.dataset),
+            error::DatasetLicensePermissionDenied {
+                dataset: license.dataset,
+            }
+        );
+
+        let version = match self.licenses.get(&license.dataset) {
+            Some((previous, version)) if previous.terms == license.terms => *version,
+            Some((_, version)) => version + 1,
+            None => u32::from(license.terms.is_some()),
+        };
+
+        self.licenses
+            .insert(license.dataset.clone(), (license, version));
+
+        Ok(())
+    }
+
+    async fn dataset_license(
+        &self,
+        session: &UserSession,
+        dataset: &DatasetId,
+    ) -> Result<DatasetLicenseStatus> {
+        ensure!(
+            self.is_permitted(session, dataset),
+            error::DatasetPermissionDenied {
+                dataset: dataset.clone(),
+            }
+        );
+
+        let (license, version) = self
+            .licenses
+            .get(dataset)
+            .ok_or(error::Error::UnknownDatasetLicense)?;
+
+        Ok(DatasetLicenseStatus {
+            license: license.clone(),
+            terms_version: *version,
+            terms_accepted: self.has_accepted_terms(session, dataset),
+        })
+    }
+
+    async fn accept_dataset_terms(
+        &mut self,
+        session: &UserSession,
+        dataset: &DatasetId,
+        terms_version: u32,
+    ) -> Result<()> {
+        ensure!(
+            self.is_permitted(session, dataset),
+            error::DatasetPermissionDenied {
+                dataset: dataset.clone(),
+            }
+        );
+
+        let (_, current) = self
+            .licenses
+            .get(dataset)
+            .ok_or(error::Error::UnknownDatasetLicense)?;
+
+        ensure!(
+            *current == terms_version,
+            error::OutdatedDatasetTerms { current: *current }
+        );
+
+        self.terms_acceptances
+            .insert((dataset.clone(), session.user.id), terms_version);
+
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl
     SessionMetaDataProvider<
@@ -363,14 +725,7 @@ impl
             >,
         >,
     > {
-        ensure!(
-            self.dataset_permissions
-                .iter()
-                .any(|p| p.dataset == *dataset && session.roles.contains(&p.role)),
-            error::DatasetPermissionDenied {
-                dataset: dataset.clone(),
-            }
-        );
+        self.ensure_queryable(session, dataset)?;
 
         Ok(Box::new(
             self.mock_datasets
@@ -400,25 +755,19 @@ impl
         dataset: &DatasetId,
     ) -> Result<Box<dyn MetaData<OgrSourceDataset, VectorResultDescriptor, VectorQueryRectangle>>>
     {
-        ensure!(
-            self.dataset_permissions
-                .iter()
-                .any(|p| p.dataset == *dataset && session.roles.contains(&p.role)),
-            error::DatasetPermissionDenied {
-                dataset: dataset.clone(),
-            }
-        );
+        let restriction = self.ensure_queryable(session, dataset)?;
+
+        let meta_data = self
+            .ogr_datasets
+            .get(
+                &dataset
+                    .internal()
+                    .ok_or(error::Error::DatasetIdTypeMissMatch)?,
+            )
+            .ok_or(error::Error::UnknownDatasetId)?
+            .clone();
 
-        Ok(Box::new(
-            self.ogr_datasets
-                .get(
-                    &dataset
-                        .internal()
-                        .ok_or(error::Error::DatasetIdTypeMissMatch)?,
-                )
-                .ok_or(error::Error::UnknownDatasetId)?
-                .clone(),
-        ))
+        Ok(RestrictedMetaData::wrap(Box::new(meta_data), restriction))
     }
 }
 
@@ -437,14 +786,7 @@ impl
         dataset: &DatasetId,
     ) -> Result<Box<dyn MetaData<GdalLoadingInfo, RasterResultDescriptor, RasterQueryRectangle>>>
     {
-        ensure!(
-            self.dataset_permissions
-                .iter()
-                .any(|p| p.dataset == *dataset && session.roles.contains(&p.role)),
-            error::DatasetPermissionDenied {
-                dataset: dataset.clone(),
-            }
-        );
+        self.ensure_queryable(session, dataset)?;
 
         let id = dataset
             .internal()
@@ -476,6 +818,61 @@ impl UploadDb<UserSession> for ProHashMapDatasetDb {
     }
 }
 
+#[async_trait]
+impl BackupDb<DatasetDefinition> for ProHashMapDatasetDb {
+    async fn export(&self) -> Result<Vec<DatasetDefinition>> {
+        self.datasets
+            .values()
+            .map(|dataset| {
+                let id = dataset
+                    .id
+                    .internal()
+                    .ok_or(error::Error::InvalidDatasetId)?;
+                let meta_data = self
+                    .meta_data_definitions
+                    .get(&id)
+                    .ok_or(error::Error::UnknownDatasetId)?;
+
+                Ok(dataset.definition(meta_data.clone()))
+            })
+            .collect()
+    }
+
+    async fn import(&mut self, dataset: DatasetDefinition) -> Result<()> {
+        if let Some(id) = &dataset.properties.id {
+            ensure!(
+                !self.datasets.contains_key(id),
+                error::Duplicate {
+                    reason: format!("Dataset {:?} already exists", id),
+                }
+            );
+        }
+
+        self.insert_dataset(dataset.properties, &dataset.meta_data)
+            .await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BackupDb<DatasetPermission> for ProHashMapDatasetDb {
+    async fn export(&self) -> Result<Vec<DatasetPermission>> {
+        Ok(self.dataset_permissions.clone())
+    }
+
+    async fn import(&mut self, permission: DatasetPermission) -> Result<()> {
+        ensure!(
+            !self.dataset_permissions.contains(&permission),
+            error::Duplicate {
+                reason: format!("Dataset permission {:?} already exists", permission),
+            }
+        );
+
+        self.dataset_permissions.push(permission);
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -486,9 +883,10 @@ mod tests {
     use crate::pro::datasets::Role;
     use crate::util::user_input::UserInput;
     use geoengine_datatypes::collections::VectorDataType;
+    use geoengine_datatypes::primitives::TimeInterval;
     use geoengine_datatypes::spatial_reference::SpatialReferenceOption;
     use geoengine_datatypes::util::test::TestDefault;
-    use geoengine_operators::engine::MetaDataProvider;
+    use geoengine_operators::engine::{MetaDataProvider, ResultExtent};
     use geoengine_operators::source::OgrSourceErrorSpec;
 
     #[tokio::test]
@@ -501,6 +899,7 @@ mod tests {
             data_type: VectorDataType::Data,
             spatial_reference: SpatialReferenceOption::Unreferenced,
             columns: Default::default(),
+            column_metadata: Default::default(),
         };
 
         let ds = AddDataset {
@@ -525,6 +924,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                generalizations: vec![],
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -547,7 +947,8 @@ mod tests {
             VectorResultDescriptor {
                 data_type: VectorDataType::Data,
                 spatial_reference: SpatialReferenceOption::Unreferenced,
-                columns: Default::default()
+                columns: Default::default(),
+                column_metadata: Default::default(),
             }
         );
 
@@ -561,6 +962,8 @@ mod tests {
                     order: OrderBy::NameAsc,
                     offset: 0,
                     limit: 1,
+                    bbox: None,
+                    time: None,
                 }
                 .validated()?,
             )
@@ -578,6 +981,8 @@ mod tests {
                 source_operator: "OgrSource".to_string(),
                 result_descriptor: descriptor.into(),
                 symbology: None,
+                // the file cannot be scanned, but features without time are valid forever
+                extent: ResultExtent::new(None, Some(TimeInterval::default())),
             }
         );
 
@@ -595,6 +1000,7 @@ mod tests {
             data_type: VectorDataType::Data,
             spatial_reference: SpatialReferenceOption::Unreferenced,
             columns: Default::default(),
+            column_metadata: Default::default(),
         };
 
         let ds = AddDataset {
@@ -619,6 +1025,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                generalizations: vec![],
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -640,6 +1047,8 @@ mod tests {
                     order: OrderBy::NameAsc,
                     offset: 0,
                     limit: 1,
+                    bbox: None,
+                    time: None,
                 }
                 .validated()?,
             )
@@ -657,6 +1066,8 @@ mod tests {
                     order: OrderBy::NameAsc,
                     offset: 0,
                     limit: 1,
+                    bbox: None,
+                    time: None,
                 }
                 .validated()?,
             )
@@ -678,6 +1089,7 @@ mod tests {
             data_type: VectorDataType::Data,
             spatial_reference: SpatialReferenceOption::Unreferenced,
             columns: Default::default(),
+            column_metadata: Default::default(),
         };
 
         let ds = AddDataset {
@@ -702,6 +1114,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                generalizations: vec![],
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -741,6 +1154,7 @@ mod tests {
             data_type: VectorDataType::Data,
             spatial_reference: SpatialReferenceOption::Unreferenced,
             columns: Default::default(),
+            column_metadata: Default::default(),
         };
 
         let ds = AddDataset {
@@ -765,6 +1179,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                generalizations: vec![],
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -812,6 +1227,95 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn it_checks_permissions_for_updates_and_deletions() -> Result<()> {
+        let ctx = ProInMemoryContext::test_default();
+
+        let owner = UserSession::mock();
+        let writer = UserSession::mock();
+        let reader = UserSession::mock();
+
+        let ds = AddDataset {
+            id: None,
+            name: "OgrDataset".to_string(),
+            description: "My Ogr dataset".to_string(),
+            source_operator: "OgrSource".to_string(),
+            symbology: None,
+            provenance: None,
+        };
+
+        let meta = StaticMetaData {
+            loading_info: OgrSourceDataset {
+                file_name: Default::default(),
+                layer_name: "".to_string(),
+                data_type: None,
+                time: Default::default(),
+                default_geometry: None,
+                columns: None,
+                force_ogr_time_filter: false,
+                force_ogr_spatial_filter: false,
+                on_error: OgrSourceErrorSpec::Ignore,
+                sql_query: None,
+                attribute_query: None,
+                generalizations: vec![],
+            },
+            result_descriptor: VectorResultDescriptor {
+                data_type: VectorDataType::Data,
+                spatial_reference: SpatialReferenceOption::Unreferenced,
+                columns: Default::default(),
+                column_metadata: Default::default(),
+            },
+            phantom: Default::default(),
+        };
+
+        let mut db = ctx.dataset_db_ref_mut().await;
+
+        let id = db
+            .add_dataset(&owner, ds.validated()?, Box::new(meta))
+            .await?;
+
+        for (session, permission) in [(&writer, Permission::Write), (&reader, Permission::Read)] {
+            db.add_dataset_permission(
+                &owner,
+                DatasetPermission {
+                    role: session.user.id.into(),
+                    dataset: id.clone(),
+                    permission,
+                },
+            )
+            .await?;
+        }
+
+        let rename = |name: &str| {
+            UpdateDataset {
+                name: Some(name.to_string()),
+                ..Default::default()
+            }
+            .validated()
+        };
+
+        assert!(matches!(
+            db.update_dataset(&reader, &id, rename("Reader")?).await,
+            Err(error::Error::DatasetUpdatePermissionDenied { .. })
+        ));
+
+        db.update_dataset(&writer, &id, rename("Writer")?).await?;
+        assert_eq!(db.load(&reader, &id).await?.name, "Writer");
+
+        assert!(matches!(
+            db.delete_dataset(&writer, &id).await,
+            Err(error::Error::DatasetDeletionPermissionDenied { .. })
+        ));
+
+        db.delete_dataset(&owner, &id).await?;
+
+        assert!(db.load(&owner, &id).await.is_err());
+        assert!(db.dataset_permissions.iter().all(|p| p.dataset != id));
+        assert!(db.meta_data_definitions.is_empty());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn it_uses_roles_for_permissions() -> Result<()> {
         let ctx = ProInMemoryContext::test_default();
@@ -823,6 +1327,7 @@ mod tests {
             data_type: VectorDataType::Data,
             spatial_reference: SpatialReferenceOption::Unreferenced,
             columns: Default::default(),
+            column_metadata: Default::default(),
         };
 
         let ds = AddDataset {
@@ -847,6 +1352,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                generalizations: vec![],
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
@@ -905,6 +1411,7 @@ mod tests {
             data_type: VectorDataType::Data,
             spatial_reference: SpatialReferenceOption::Unreferenced,
             columns: Default::default(),
+            column_metadata: Default::default(),
         };
 
         let ds = AddDataset {
@@ -929,6 +1436,7 @@ mod tests {
                 on_error: OgrSourceErrorSpec::Ignore,
                 sql_query: None,
                 attribute_query: None,
+                generalizations: vec![],
             },
             result_descriptor: descriptor.clone(),
             phantom: Default::default(),
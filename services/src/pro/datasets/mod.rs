@@ -1,3 +1,4 @@
+mod access_policy;
 mod add_from_directory;
 mod external;
 mod in_memory;
@@ -5,10 +6,12 @@ mod in_memory;
 mod postgres;
 mod storage;
 
+pub use access_policy::{AccessRestriction, IpRange, RestrictedMetaData};
 pub use add_from_directory::add_datasets_from_directory;
 pub use in_memory::{ProHashMapDatasetDb, ProHashMapStorable};
 pub use postgres::PostgresDatasetDb;
 pub use storage::{
-    DatasetPermission, DatasetProviderPermission, Permission, Role, RoleId,
+    DatasetAccessPolicies, DatasetAccessPolicy, DatasetLicense, DatasetLicenseStatus,
+    DatasetLicenses, DatasetPermission, DatasetProviderPermission, Permission, Role, RoleId,
     UpdateDatasetPermissions,
 };
@@ -1,33 +1,40 @@
+use crate::contexts::BackupDb;
 use crate::datasets::listing::ProvenanceOutput;
 use crate::datasets::listing::SessionMetaDataProvider;
 use crate::datasets::storage::{
-    AddDataset, Dataset, DatasetDb, DatasetProviderDb, DatasetProviderListOptions,
-    DatasetProviderListing, DatasetStore, DatasetStorer, ExternalDatasetProviderDefinition,
-    MetaDataDefinition,
+    AddDataset, Dataset, DatasetDb, DatasetDefinition, DatasetProviderDb,
+    DatasetProviderListOptions, DatasetProviderListing, DatasetStore, DatasetStorer,
+    ExternalDatasetProviderDefinition, MetaDataDefinition, UpdateDataset,
 };
 use crate::datasets::upload::FileId;
 use crate::datasets::upload::{Upload, UploadDb, UploadId};
 use crate::error::{self, Error, Result};
-use crate::pro::datasets::storage::UpdateDatasetPermissions;
-use crate::pro::datasets::RoleId;
+use crate::pro::datasets::storage::{
+    DatasetAccessPolicies, DatasetLicenses, UpdateDatasetPermissions,
+};
+use crate::pro::datasets::{
+    AccessRestriction, DatasetAccessPolicy, DatasetLicense, DatasetLicenseStatus,
+    RestrictedMetaData, Role, RoleId,
+};
 use crate::util::user_input::Validated;
 use crate::{
     datasets::listing::{
         DatasetListOptions, DatasetListing, DatasetProvider, ExternalDatasetProvider,
     },
-    pro::users::UserSession,
+    pro::users::{Quota, UserSession},
 };
 use async_trait::async_trait;
 use bb8_postgres::bb8::Pool;
 use bb8_postgres::tokio_postgres::tls::{MakeTlsConnect, TlsConnect};
-use bb8_postgres::tokio_postgres::Socket;
+use bb8_postgres::tokio_postgres::{Client, Row, Socket, Transaction};
 use bb8_postgres::PostgresConnectionManager;
 use geoengine_datatypes::dataset::{DatasetId, DatasetProviderId, InternalDatasetId};
 use geoengine_datatypes::primitives::RasterQueryRectangle;
 use geoengine_datatypes::primitives::VectorQueryRectangle;
 use geoengine_datatypes::util::Identifier;
 use geoengine_operators::engine::{
-    MetaData, RasterResultDescriptor, StaticMetaData, TypedResultDescriptor, VectorResultDescriptor,
+    MetaData, RasterResultDescriptor, ResultExtent, StaticMetaData, TypedResultDescriptor,
+    VectorResultDescriptor,
 };
 use geoengine_operators::mock::MockDatasetDataSourceLoadingInfo;
 use geoengine_operators::source::{GdalLoadingInfo, OgrSourceDataset};
@@ -57,6 +64,57 @@ where
     pub fn new(conn_pool: Pool<PostgresConnectionManager<Tls>>) -> Self {
         Self { conn_pool }
     }
+
+    /// Inserts the dataset without granting permissions on it
+    async fn insert_dataset(
+        tx: &Transaction<'_>,
+        dataset: AddDataset,
+        meta_data: &dyn PostgresStorable<Tls>,
+    ) -> Result<DatasetId> {
+        let id = dataset
+            .id
+            .unwrap_or_else(|| InternalDatasetId::new().into());
+        let internal_id = id.internal().ok_or(Error::InvalidDatasetId)?;
+
+        let meta_data_json = meta_data.to_json()?;
+        let extent = meta_data.extent().await;
+
+        let stmt = tx
+            .prepare(
+                "
+                INSERT INTO datasets (
+                    id,
+                    name,
+                    description,
+                    source_operator,
+                    result_descriptor,
+                    meta_data,
+                    symbology,
+                    provenance,
+                    extent
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            )
+            .await?;
+
+        tx.execute(
+            &stmt,
+            &[
+                &internal_id,
+                &dataset.name,
+                &dataset.description,
+                &dataset.source_operator,
+                &meta_data_json.result_descriptor,
+                &meta_data_json.meta_data,
+                &serde_json::to_value(&dataset.symbology)?,
+                &serde_json::to_value(&dataset.provenance)?,
+                &serde_json::to_value(&extent)?,
+            ],
+        )
+        .await?;
+
+        Ok(id)
+    }
 }
 
 impl<Tls> DatasetDb<UserSession> for PostgresDatasetDb<Tls>
@@ -184,9 +242,10 @@ where
     async fn list(
         &self,
         session: &UserSession,
-        _options: Validated<DatasetListOptions>,
+        options: Validated<DatasetListOptions>,
     ) -> Result<Vec<DatasetListing>> {
         // TODO: use options
+        let options = options.user_input;
 
         let conn = self.conn_pool.get().await?;
         let stmt = conn
@@ -199,12 +258,12 @@ where
                 d.tags,
                 d.source_operator,
                 d.result_descriptor,
-                d.symbology
+                d.symbology,
+                d.extent
             FROM 
-                user_permitted_datasets p JOIN datasets d 
-                    ON (p.dataset_id = d.id)
+                datasets d
             WHERE 
-                p.user_id = $1",
+                d.id IN (SELECT dataset_id FROM user_permitted_datasets WHERE user_id = $1)",
             )
             .await?;
 
@@ -223,9 +282,11 @@ where
                     source_operator: row.get(4),
                     result_descriptor: serde_json::from_value(row.get(5))?,
                     symbology: serde_json::from_value(row.get(6))?,
+                    extent: extent_from_row(row, 7)?,
                 })
             })
             .filter_map(Result::ok)
+            .filter(|listing| options.matches_extent(&listing.extent))
             .collect())
     }
 
@@ -243,7 +304,8 @@ where
                 d.result_descriptor,
                 d.source_operator,
                 d.symbology,
-                d.provenance
+                d.provenance,
+                d.extent
             FROM 
                 user_permitted_datasets p JOIN datasets d 
                     ON (p.dataset_id = d.id)
@@ -257,16 +319,24 @@ where
         // TODO: throw proper dataset does not exist/no permission error
         let row = conn.query_one(&stmt, &[&session.user.id, &id]).await?;
 
+        let mut result_descriptor: TypedResultDescriptor = serde_json::from_value(row.get(3))?;
+        if let TypedResultDescriptor::Vector(result_descriptor) = &mut result_descriptor {
+            access_restriction(&conn, session, id)
+                .await?
+                .restrict_result_descriptor(result_descriptor);
+        }
+
         Ok(Dataset {
             id: DatasetId::Internal {
                 dataset_id: row.get(0),
             },
             name: row.get(1),
             description: row.get(2),
-            result_descriptor: serde_json::from_value(row.get(3))?,
+            result_descriptor,
             source_operator: row.get(4),
             symbology: serde_json::from_value(row.get(5))?,
             provenance: serde_json::from_value(row.get(6))?,
+            extent: extent_from_row(&row, 7)?,
         })
     }
 
@@ -288,7 +358,9 @@ where
                 user_permitted_datasets p JOIN datasets d
                     ON(p.dataset_id = d.id)
             WHERE 
-                p.user_id = $1 AND d.id = $2",
+                p.user_id = $1 AND d.id = $2
+            LIMIT 
+                1",
             )
             .await?;
 
@@ -364,19 +436,26 @@ where
             user_permitted_datasets p JOIN datasets d 
                 ON (p.dataset_id = d.id)
         WHERE 
-            d.id = $1 AND p.user_id = $2",
+            d.id = $1 AND p.user_id = $2
+        LIMIT 
+            1",
             )
             .await?;
 
         let row = conn.query_one(&stmt, &[&id, &session.user.id]).await?;
 
+        ensure_terms_accepted(&conn, session, id).await?;
+
         let meta_data: StaticMetaData<
             OgrSourceDataset,
             VectorResultDescriptor,
             VectorQueryRectangle,
         > = serde_json::from_value(row.get(0))?;
 
-        Ok(Box::new(meta_data))
+        let restriction = access_restriction(&conn, session, id).await?;
+        restriction.ensure_permitted(session, dataset)?;
+
+        Ok(RestrictedMetaData::wrap(Box::new(meta_data), restriction))
     }
 }
 
@@ -412,12 +491,20 @@ where
             user_permitted_datasets p JOIN datasets d 
                 ON (p.dataset_id = d.id)
         WHERE 
-            d.id = $1 AND p.user_id = $2",
+            d.id = $1 AND p.user_id = $2
+        LIMIT 
+            1",
             )
             .await?;
 
         let row = conn.query_one(&stmt, &[&id, &session.user.id]).await?;
 
+        ensure_terms_accepted(&conn, session, id).await?;
+
+        access_restriction(&conn, session, id)
+            .await?
+            .ensure_permitted(session, dataset)?;
+
         let meta_data: MetaDataDefinition = serde_json::from_value(row.get(0))?;
 
         Ok(match meta_data {
@@ -437,6 +524,8 @@ where
     <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
 {
     fn to_json(&self) -> Result<DatasetMetaDataJson>;
+
+    async fn extent(&self) -> ResultExtent;
 }
 
 pub struct DatasetMetaDataJson {
@@ -454,6 +543,7 @@ where
     type StorageType = Box<dyn PostgresStorable<Tls>>;
 }
 
+#[async_trait]
 impl<Tls> PostgresStorable<Tls> for MetaDataDefinition
 where
     Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
@@ -495,6 +585,10 @@ where
             }),
         }
     }
+
+    async fn extent(&self) -> ResultExtent {
+        MetaDataDefinition::extent(self).await
+    }
 }
 
 #[async_trait]
@@ -511,49 +605,28 @@ where
         dataset: Validated<AddDataset>,
         meta_data: Box<dyn PostgresStorable<Tls>>,
     ) -> Result<DatasetId> {
-        let dataset = dataset.user_input;
-        let id = dataset
-            .id
-            .unwrap_or_else(|| InternalDatasetId::new().into());
-        let internal_id = id.internal().ok_or(Error::InvalidDatasetId)?;
-
-        let meta_data_json = meta_data.to_json()?;
-
         let mut conn = self.conn_pool.get().await?;
 
         let tx = conn.build_transaction().start().await?;
 
-        let stmt = tx
-            .prepare(
-                "
-                INSERT INTO datasets (
-                    id,
-                    name,
-                    description,
-                    source_operator,
-                    result_descriptor,
-                    meta_data,
-                    symbology,
-                    provenance
-                )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
-            )
-            .await?;
+        if let Some(organization) = &session.organization {
+            let stmt = tx
+                .prepare("SELECT COUNT(*) FROM dataset_permissions WHERE role_id = $1;")
+                .await?;
+            let datasets: i64 = tx
+                .query_one(&stmt, &[&RoleId::from(organization.id)])
+                .await?
+                .get(0);
+
+            Quota::check(
+                organization.quota.max_datasets,
+                datasets as usize,
+                "datasets",
+            )?;
+        }
 
-        tx.execute(
-            &stmt,
-            &[
-                &internal_id,
-                &dataset.name,
-                &dataset.description,
-                &dataset.source_operator,
-                &meta_data_json.result_descriptor,
-                &meta_data_json.meta_data,
-                &serde_json::to_value(&dataset.symbology)?,
-                &serde_json::to_value(&dataset.provenance)?,
-            ],
-        )
-        .await?;
+        let id = Self::insert_dataset(&tx, dataset.user_input, meta_data.as_ref()).await?;
+        let internal_id = id.internal().ok_or(Error::InvalidDatasetId)?;
 
         let stmt = tx
             .prepare(
@@ -577,11 +650,134 @@ where
         )
         .await?;
 
+        // datasets that are added in an organization are shared with its members
+        if let Some(organization) = &session.organization {
+            tx.execute(
+                &stmt,
+                &[
+                    &RoleId::from(organization.id),
+                    &internal_id,
+                    &Permission::Read,
+                ],
+            )
+            .await?;
+        }
+
         tx.commit().await?;
 
         Ok(id)
     }
 
+    async fn update_dataset(
+        &mut self,
+        session: &UserSession,
+        dataset: &DatasetId,
+        update: Validated<UpdateDataset>,
+    ) -> Result<()> {
+        let update = update.user_input;
+
+        let conn = self.conn_pool.get().await?;
+
+        ensure!(
+            has_permission(
+                &conn,
+                session,
+                dataset,
+                &[Permission::Write, Permission::Owner]
+            )
+            .await?,
+            error::DatasetUpdatePermissionDenied {
+                dataset: dataset.clone(),
+            }
+        );
+
+        info!("Update dataset {:?}", dataset);
+
+        let stmt = conn
+            .prepare(
+                "
+            UPDATE datasets
+            SET 
+                name = COALESCE($2, name),
+                description = COALESCE($3, description),
+                symbology = COALESCE($4, symbology),
+                provenance = COALESCE($5, provenance)
+            WHERE id = $1",
+            )
+            .await?;
+
+        let updated = conn
+            .execute(
+                &stmt,
+                &[
+                    &dataset.internal().ok_or(Error::InvalidDatasetId)?,
+                    &update.name,
+                    &update.description,
+                    &update
+                        .symbology
+                        .as_ref()
+                        .map(serde_json::to_value)
+                        .transpose()?,
+                    &update
+                        .provenance
+                        .as_ref()
+                        .map(serde_json::to_value)
+                        .transpose()?,
+                ],
+            )
+            .await?;
+
+        ensure!(updated > 0, error::UnknownDatasetId);
+
+        Ok(())
+    }
+
+    async fn ensure_writable(&self, session: &UserSession, dataset: &DatasetId) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+
+        ensure!(
+            has_permission(
+                &conn,
+                session,
+                dataset,
+                &[Permission::Write, Permission::Owner]
+            )
+            .await?,
+            error::DatasetUpdatePermissionDenied {
+                dataset: dataset.clone(),
+            }
+        );
+
+        Ok(())
+    }
+
+    async fn delete_dataset(&mut self, session: &UserSession, dataset: &DatasetId) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+
+        ensure!(
+            has_permission(&conn, session, dataset, &[Permission::Owner]).await?,
+            error::DatasetDeletionPermissionDenied {
+                dataset: dataset.clone(),
+            }
+        );
+
+        info!("Delete dataset {:?}", dataset);
+
+        // permissions, access policies and licenses are removed by cascading
+        let stmt = conn.prepare("DELETE FROM datasets WHERE id = $1").await?;
+
+        let deleted = conn
+            .execute(
+                &stmt,
+                &[&dataset.internal().ok_or(Error::InvalidDatasetId)?],
+            )
+            .await?;
+
+        ensure!(deleted > 0, error::UnknownDatasetId);
+
+        Ok(())
+    }
+
     fn wrap_meta_data(&self, meta: MetaDataDefinition) -> Self::StorageType {
         Box::new(meta)
     }
@@ -699,6 +895,571 @@ where
     }
 }
 
+/// Combines the access policies of the session's roles unless the session owns the dataset
+async fn access_restriction(
+    conn: &Client,
+    session: &UserSession,
+    dataset: InternalDatasetId,
+) -> Result<AccessRestriction> {
+    if session.roles.contains(&Role::system_role_id()) {
+        return Ok(AccessRestriction::default());
+    }
+
+    let stmt = conn
+        .prepare(
+            "
+        SELECT 
+            a.role_id, a.hidden_columns, a.row_filter, a.ip_ranges, a.institutions
+        FROM 
+            dataset_access_policies a
+        WHERE 
+            a.dataset_id = $1 AND a.role_id = ANY ($2) AND NOT EXISTS (
+                SELECT 1 FROM dataset_permissions p 
+                WHERE p.dataset_id = $1 AND p.role_id = ANY ($2) AND p.permission = $3
+            )",
+        )
+        .await?;
+
+    let policies = conn
+        .query(&stmt, &[&dataset, &session.roles, &Permission::Owner])
+        .await?
+        .iter()
+        .map(|row| access_policy_from_row(row, dataset.into()))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(AccessRestriction::from_policies(&policies))
+}
+
+/// Reads a policy from a row of the columns `role_id`, `hidden_columns`, `row_filter`, `ip_ranges`
+/// and `institutions`
+/// The extent of a dataset is unknown if it was added before extents were computed
+fn extent_from_row(row: &Row, index: usize) -> Result<ResultExtent> {
+    Ok(row
+        .get::<_, Option<serde_json::Value>>(index)
+        .map(serde_json::from_value)
+        .transpose()?
+        .unwrap_or_default())
+}
+
+fn access_policy_from_row(row: &Row, dataset: DatasetId) -> Result<DatasetAccessPolicy> {
+    Ok(DatasetAccessPolicy {
+        role: row.get(0),
+        dataset,
+        hidden_columns: row.get(1),
+        row_filter: row.get(2),
+        ip_ranges: row
+            .get::<_, Vec<String>>(3)
+            .iter()
+            .map(|range| range.parse())
+            .collect::<Result<_>>()?,
+        institutions: row.get(4),
+    })
+}
+
+/// Fails unless the session owns the dataset or is the system
+async fn ensure_owner(conn: &Client, session: &UserSession, dataset: &DatasetId) -> Result<()> {
+    ensure!(
+        has_permission(conn, session, dataset, &[Permission::Owner]).await?,
+        error::DatasetAccessPolicyPermissionDenied {
+            dataset: dataset.clone(),
+        }
+    );
+
+    Ok(())
+}
+
+/// Whether the session is the system or has one of the `permissions` on the dataset
+async fn has_permission(
+    conn: &Client,
+    session: &UserSession,
+    dataset: &DatasetId,
+    permissions: &[Permission],
+) -> Result<bool> {
+    let id = dataset.internal().ok_or(Error::InvalidDatasetId)?;
+
+    if session.roles.contains(&Role::system_role_id()) {
+        return Ok(true);
+    }
+
+    let stmt = conn
+        .prepare(
+            "
+        SELECT TRUE 
+        FROM dataset_permissions 
+        WHERE dataset_id = $1 AND role_id = ANY ($2) AND permission = ANY ($3)",
+        )
+        .await?;
+
+    let rows = conn
+        .query(&stmt, &[&id, &session.roles, &permissions])
+        .await?;
+
+    Ok(!rows.is_empty())
+}
+
+#[async_trait]
+impl<Tls> DatasetAccessPolicies for PostgresDatasetDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn set_dataset_access_policy(
+        &mut self,
+        session: &UserSession,
+        policy: Validated<DatasetAccessPolicy>,
+    ) -> Result<()> {
+        let policy = policy.user_input;
+
+        let conn = self.conn_pool.get().await?;
+        ensure_owner(&conn, session, &policy.dataset).await?;
+
+        let stmt = conn
+            .prepare(
+                "
+            INSERT INTO dataset_access_policies 
+                (dataset_id, role_id, hidden_columns, row_filter, ip_ranges, institutions)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (dataset_id, role_id) DO UPDATE 
+                SET hidden_columns = EXCLUDED.hidden_columns, row_filter = EXCLUDED.row_filter,
+                    ip_ranges = EXCLUDED.ip_ranges, institutions = EXCLUDED.institutions",
+            )
+            .await?;
+
+        conn.execute(
+            &stmt,
+            &[
+                &policy.dataset.internal().ok_or(Error::InvalidDatasetId)?,
+                &policy.role,
+                &policy.hidden_columns,
+                &policy.row_filter,
+                &policy
+                    .ip_ranges
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+                &policy.institutions,
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn dataset_access_policies(
+        &self,
+        session: &UserSession,
+        dataset: &DatasetId,
+    ) -> Result<Vec<DatasetAccessPolicy>> {
+        let conn = self.conn_pool.get().await?;
+        ensure_owner(&conn, session, dataset).await?;
+
+        let stmt = conn
+            .prepare(
+                "
+            SELECT role_id, hidden_columns, row_filter, ip_ranges, institutions 
+            FROM dataset_access_policies 
+            WHERE dataset_id = $1",
+            )
+            .await?;
+
+        let rows = conn
+            .query(
+                &stmt,
+                &[&dataset.internal().ok_or(Error::InvalidDatasetId)?],
+            )
+            .await?;
+
+        rows.iter()
+            .map(|row| access_policy_from_row(row, dataset.clone()))
+            .collect()
+    }
+
+    async fn remove_dataset_access_policy(
+        &mut self,
+        session: &UserSession,
+        dataset: &DatasetId,
+        role: RoleId,
+    ) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+        ensure_owner(&conn, session, dataset).await?;
+
+        let stmt = conn
+            .prepare("DELETE FROM dataset_access_policies WHERE dataset_id = $1 AND role_id = $2")
+            .await?;
+
+        let deleted = conn
+            .execute(
+                &stmt,
+                &[&dataset.internal().ok_or(Error::InvalidDatasetId)?, &role],
+            )
+            .await?;
+
+        ensure!(deleted > 0, error::UnknownDatasetAccessPolicy);
+
+        Ok(())
+    }
+}
+
+/// Fails unless the session's user accepted the current terms of the dataset, owns it or is the system
+async fn ensure_terms_accepted(
+    conn: &Client,
+    session: &UserSession,
+    dataset: InternalDatasetId,
+) -> Result<()> {
+    if session.roles.contains(&Role::system_role_id()) {
+        return Ok(());
+    }
+
+    let stmt = conn
+        .prepare(
+            "
+        SELECT TRUE
+        FROM 
+            dataset_licenses l
+        WHERE 
+            l.dataset_id = $1 AND l.terms IS NOT NULL AND NOT EXISTS (
+                SELECT 1 FROM dataset_terms_acceptances a 
+                WHERE a.dataset_id = $1 AND a.user_id = $2 AND a.terms_version = l.terms_version
+            ) AND NOT EXISTS (
+                SELECT 1 FROM dataset_permissions p 
+                WHERE p.dataset_id = $1 AND p.role_id = ANY ($3) AND p.permission = $4
+            )",
+        )
+        .await?;
+
+    let pending = conn
+        .query(
+            &stmt,
+            &[
+                &dataset,
+                &session.user.id,
+                &session.roles,
+                &Permission::Owner,
+            ],
+        )
+        .await?;
+
+    ensure!(
+        pending.is_empty(),
+        error::DatasetTermsNotAccepted {
+            dataset: DatasetId::from(dataset),
+        }
+    );
+
+    Ok(())
+}
+
+/// Fails unless the session's user has a permission on the dataset
+async fn ensure_permitted(conn: &Client, session: &UserSession, dataset: &DatasetId) -> Result<()> {
+    let id = dataset.internal().ok_or(Error::InvalidDatasetId)?;
+
+    let stmt = conn
+        .prepare(
+            "
+        SELECT TRUE 
+        FROM user_permitted_datasets 
+        WHERE dataset_id = $1 AND user_id = $2",
+        )
+        .await?;
+
+    let permissions = conn.query(&stmt, &[&id, &session.user.id]).await?;
+
+    ensure!(
+        !permissions.is_empty(),
+        error::DatasetPermissionDenied {
+            dataset: dataset.clone(),
+        }
+    );
+
+    Ok(())
+}
+
+#[async_trait]
+impl<Tls> DatasetLicenses for PostgresDatasetDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn set_dataset_license(
+        &mut self,
+        session: &UserSession,
+        license: Validated<DatasetLicense>,
+    ) -> Result<()> {
+        let license = license.user_input;
+
+        let conn = self.conn_pool.get().await?;
+        ensure_owner(&conn, session, &license.dataset)
+            .await
+            .map_err(|e| match e {
+                Error::DatasetAccessPolicyPermissionDenied { dataset } => {
+                    Error::DatasetLicensePermissionDenied { dataset }
+                }
+                e => e,
+            })?;
+
+        // the terms version only increases if the terms change
+        let stmt = conn
+            .prepare(
+                "
+            INSERT INTO dataset_licenses (dataset_id, license, terms, terms_version)
+            VALUES ($1, $2, $3, CASE WHEN $3 IS NULL THEN 0 ELSE 1 END)
+            ON CONFLICT (dataset_id) DO UPDATE 
+                SET license = EXCLUDED.license, terms = EXCLUDED.terms, terms_version = 
+                    dataset_licenses.terms_version + 
+                    CASE WHEN dataset_licenses.terms IS DISTINCT FROM EXCLUDED.terms THEN 1 ELSE 0 END",
+            )
+            .await?;
+
+        conn.execute(
+            &stmt,
+            &[
+                &license.dataset.internal().ok_or(Error::InvalidDatasetId)?,
+                &license.license,
+                &license.terms,
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn dataset_license(
+        &self,
+        session: &UserSession,
+        dataset: &DatasetId,
+    ) -> Result<DatasetLicenseStatus> {
+        let id = dataset.internal().ok_or(Error::InvalidDatasetId)?;
+
+        let conn = self.conn_pool.get().await?;
+        ensure_permitted(&conn, session, dataset).await?;
+
+        let stmt = conn
+            .prepare(
+                "
+            SELECT license, terms, terms_version 
+            FROM dataset_licenses 
+            WHERE dataset_id = $1",
+            )
+            .await?;
+
+        let row = conn
+            .query_opt(&stmt, &[&id])
+            .await?
+            .ok_or(Error::UnknownDatasetLicense)?;
+
+        let terms_accepted = match ensure_terms_accepted(&conn, session, id).await {
+            Ok(()) => true,
+            Err(Error::DatasetTermsNotAccepted { .. }) => false,
+            Err(e) => return Err(e),
+        };
+
+        Ok(DatasetLicenseStatus {
+            license: DatasetLicense {
+                dataset: dataset.clone(),
+                license: row.get(0),
+                terms: row.get(1),
+            },
+            terms_version: row.get::<_, i32>(2) as u32,
+            terms_accepted,
+        })
+    }
+
+    async fn accept_dataset_terms(
+        &mut self,
+        session: &UserSession,
+        dataset: &DatasetId,
+        terms_version: u32,
+    ) -> Result<()> {
+        let id = dataset.internal().ok_or(Error::InvalidDatasetId)?;
+
+        let mut conn = self.conn_pool.get().await?;
+        ensure_permitted(&conn, session, dataset).await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let stmt = tx
+            .prepare("SELECT terms_version FROM dataset_licenses WHERE dataset_id = $1 FOR UPDATE")
+            .await?;
+
+        let current = tx
+            .query_opt(&stmt, &[&id])
+            .await?
+            .ok_or(Error::UnknownDatasetLicense)?
+            .get::<_, i32>(0) as u32;
+
+        ensure!(
+            current == terms_version,
+            error::OutdatedDatasetTerms { current }
+        );
+
+        let stmt = tx
+            .prepare(
+                "
+            INSERT INTO dataset_terms_acceptances (dataset_id, user_id, terms_version, accepted)
+            VALUES ($1, $2, $3, CURRENT_TIMESTAMP)
+            ON CONFLICT (dataset_id, user_id) DO UPDATE 
+                SET terms_version = EXCLUDED.terms_version, accepted = EXCLUDED.accepted",
+            )
+            .await?;
+
+        tx.execute(&stmt, &[&id, &session.user.id, &(terms_version as i32)])
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Tls> BackupDb<DatasetDefinition> for PostgresDatasetDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn export(&self) -> Result<Vec<DatasetDefinition>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "
+            SELECT
+                id,
+                name,
+                description,
+                source_operator,
+                symbology,
+                provenance,
+                meta_data
+            FROM
+                datasets",
+            )
+            .await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(DatasetDefinition {
+                    properties: AddDataset {
+                        id: Some(DatasetId::Internal {
+                            dataset_id: row.get(0),
+                        }),
+                        name: row.get(1),
+                        description: row.get(2),
+                        source_operator: row.get(3),
+                        symbology: serde_json::from_value(row.get(4))?,
+                        provenance: serde_json::from_value(row.get(5))?,
+                    },
+                    meta_data: serde_json::from_value(row.get(6))?,
+                })
+            })
+            .collect()
+    }
+
+    async fn import(&mut self, dataset: DatasetDefinition) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        if let Some(id) = &dataset.properties.id {
+            let internal_id = id.internal().ok_or(Error::InvalidDatasetId)?;
+
+            let stmt = tx
+                .prepare("SELECT EXISTS(SELECT 1 FROM datasets WHERE id = $1);")
+                .await?;
+            let exists: bool = tx.query_one(&stmt, &[&internal_id]).await?.get(0);
+
+            ensure!(
+                !exists,
+                error::Duplicate {
+                    reason: format!("Dataset {:?} already exists", id),
+                }
+            );
+        }
+
+        Self::insert_dataset(&tx, dataset.properties, &dataset.meta_data).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Tls> BackupDb<DatasetPermission> for PostgresDatasetDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn export(&self) -> Result<Vec<DatasetPermission>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT role_id, dataset_id, permission FROM dataset_permissions")
+            .await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DatasetPermission {
+                role: row.get(0),
+                dataset: DatasetId::Internal {
+                    dataset_id: row.get(1),
+                },
+                permission: row.get(2),
+            })
+            .collect())
+    }
+
+    async fn import(&mut self, permission: DatasetPermission) -> Result<()> {
+        let internal_id = permission
+            .dataset
+            .internal()
+            .ok_or(Error::DatasetIdTypeMissMatch)?;
+
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "
+            INSERT INTO dataset_permissions (
+                role_id,
+                dataset_id,
+                permission
+            )
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING",
+            )
+            .await?;
+
+        let inserted = conn
+            .execute(
+                &stmt,
+                &[&permission.role, &internal_id, &permission.permission],
+            )
+            .await?;
+
+        ensure!(
+            inserted > 0,
+            error::Duplicate {
+                reason: format!("Dataset permission {:?} already exists", permission),
+            }
+        );
+
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl<Tls> UploadDb<UserSession> for PostgresDatasetDb<Tls>
 where
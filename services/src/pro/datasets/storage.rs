@@ -1,7 +1,9 @@
 use std::str::FromStr;
 
-use crate::error::Result;
+use crate::error::{self, Result};
+use crate::pro::datasets::IpRange;
 use crate::pro::users::{UserId, UserSession};
+use crate::util::user_input::{UserInput, Validated};
 use async_trait::async_trait;
 use geoengine_datatypes::{
     dataset::{DatasetId, DatasetProviderId},
@@ -10,6 +12,7 @@ use geoengine_datatypes::{
 #[cfg(feature = "postgres")]
 use postgres_types::{FromSql, ToSql};
 use serde::{Deserialize, Serialize};
+use snafu::ensure;
 
 identifier!(RoleId);
 
@@ -37,6 +40,11 @@ impl Role {
     pub fn anonymous_role_id() -> RoleId {
         RoleId::from_str("fd8e87bf-515c-4f36-8da6-1a53702ff102").expect("valid")
     }
+
+    /// Marks read-only guest sessions. It is only part of the sessions and cannot be granted permissions.
+    pub fn guest_role_id() -> RoleId {
+        RoleId::from_str("0a2b9d5c-7e1f-4c3a-9b86-51d4e2f7a3c8").expect("valid")
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash)]
@@ -69,3 +77,149 @@ pub trait UpdateDatasetPermissions {
         permission: DatasetPermission,
     ) -> Result<()>;
 }
+
+/// Restricts what the members of a role can see of a vector dataset and from where and by whom
+/// they can query it
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetAccessPolicy {
+    pub role: RoleId,
+    pub dataset: DatasetId,
+    /// columns, named as in the dataset's result descriptor, that are removed from its features
+    #[serde(default)]
+    pub hidden_columns: Vec<String>,
+    /// an OGR SQL `WHERE` clause that features must satisfy
+    pub row_filter: Option<String>,
+    /// the IP ranges from which the dataset can be queried, any if empty
+    #[serde(default)]
+    pub ip_ranges: Vec<IpRange>,
+    /// the institutions whose members can query the dataset, any if empty
+    #[serde(default)]
+    pub institutions: Vec<String>,
+}
+
+impl UserInput for DatasetAccessPolicy {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.hidden_columns.iter().all(|c| !c.trim().is_empty()),
+            error::InvalidDatasetAccessPolicy {
+                reason: "Column names must not be empty"
+            }
+        );
+        ensure!(
+            self.row_filter
+                .as_ref()
+                .map_or(true, |f| !f.trim().is_empty() && !f.contains(';')),
+            error::InvalidDatasetAccessPolicy {
+                reason: "The row filter must be a single non-empty expression"
+            }
+        );
+        ensure!(
+            self.institutions.iter().all(|i| !i.trim().is_empty()),
+            error::InvalidDatasetAccessPolicy {
+                reason: "Institutions must not be empty"
+            }
+        );
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+pub trait DatasetAccessPolicies {
+    /// Attaches a policy to a dataset, replacing the previous policy of the role.
+    /// Requires the ownership of the dataset.
+    async fn set_dataset_access_policy(
+        &mut self,
+        session: &UserSession,
+        policy: Validated<DatasetAccessPolicy>,
+    ) -> Result<()>;
+
+    /// Lists the policies of a dataset. Requires the ownership of the dataset.
+    async fn dataset_access_policies(
+        &self,
+        session: &UserSession,
+        dataset: &DatasetId,
+    ) -> Result<Vec<DatasetAccessPolicy>>;
+
+    /// Removes the policy of `role` from a dataset. Requires the ownership of the dataset.
+    async fn remove_dataset_access_policy(
+        &mut self,
+        session: &UserSession,
+        dataset: &DatasetId,
+        role: RoleId,
+    ) -> Result<()>;
+}
+
+/// The license of a dataset and the terms of use that users must accept before querying it
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetLicense {
+    pub dataset: DatasetId,
+    /// an SPDX license identifier or expression, e.g., `CC-BY-4.0`
+    pub license: String,
+    pub terms: Option<String>,
+}
+
+impl UserInput for DatasetLicense {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.license.trim().is_empty()
+                && self
+                    .license
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || " .-+:()".contains(c)),
+            error::InvalidDatasetLicense {
+                reason: "The license must be an SPDX license identifier or expression"
+            }
+        );
+        ensure!(
+            self.terms.as_ref().map_or(true, |t| !t.trim().is_empty()),
+            error::InvalidDatasetLicense {
+                reason: "The terms of use must not be empty"
+            }
+        );
+
+        Ok(())
+    }
+}
+
+/// The license of a dataset as seen by a user
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetLicenseStatus {
+    #[serde(flatten)]
+    pub license: DatasetLicense,
+    /// increases whenever the terms change, which requires users to accept them again
+    pub terms_version: u32,
+    /// whether the user accepted the current terms or does not need to
+    pub terms_accepted: bool,
+}
+
+#[async_trait]
+pub trait DatasetLicenses {
+    /// Sets the license and terms of use of a dataset. Changing the terms requires all users
+    /// to accept them again. Requires the ownership of the dataset.
+    async fn set_dataset_license(
+        &mut self,
+        session: &UserSession,
+        license: Validated<DatasetLicense>,
+    ) -> Result<()>;
+
+    /// Loads the license of a dataset and whether the session's user has accepted its terms.
+    /// Requires a permission on the dataset.
+    async fn dataset_license(
+        &self,
+        session: &UserSession,
+        dataset: &DatasetId,
+    ) -> Result<DatasetLicenseStatus>;
+
+    /// Records that the session's user accepted the given version of the terms of a dataset.
+    /// Requires a permission on the dataset.
+    async fn accept_dataset_terms(
+        &mut self,
+        session: &UserSession,
+        dataset: &DatasetId,
+        terms_version: u32,
+    ) -> Result<()>;
+}
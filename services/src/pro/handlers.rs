@@ -1,4 +1,8 @@
+pub mod backup;
+pub mod datasets;
 #[cfg(feature = "odm")]
 pub mod drone_mapping;
+pub mod groups;
+pub mod organizations;
 pub mod projects;
 pub mod users;
@@ -0,0 +1,113 @@
+use actix_web::{web, HttpRequest, Responder};
+
+use crate::contexts::backup::RestoreOptions;
+use crate::contexts::BackupDb;
+use crate::error::Result;
+use crate::handlers::backup::authorize_backup;
+use crate::pro::contexts::backup::{self, ProBackup};
+use crate::pro::contexts::ProContext;
+use crate::pro::datasets::DatasetPermission;
+use crate::pro::projects::ProProjectDb;
+use crate::util::streaming_json::{BodyLimit, StreamingJson};
+
+pub(crate) fn init_backup_routes<C>(cfg: &mut web::ServiceConfig)
+where
+    C: ProContext,
+    C::ProjectDB: ProProjectDb,
+    C::DatasetDB: BackupDb<DatasetPermission>,
+{
+    cfg.service(web::resource("/backup").route(web::get().to(backup_handler::<C>)))
+        .service(
+            web::resource("/restore")
+                .app_data(BodyLimit::Restore)
+                .route(web::post().to(restore_handler::<C>)),
+        );
+}
+
+/// Exports all users, datasets, workflows, projects and permissions of the instance.
+///
+/// The request must be authorized with the admin token of the `backup` settings.
+/// The exported users contain their password hashes, so the backup must be stored securely.
+///
+/// # Example
+///
+/// ```text
+/// GET /backup
+/// Authorization: Bearer my-admin-token
+/// ```
+/// Response:
+/// ```text
+/// {
+///   "version": 1,
+///   "created": "2022-03-01T12:00:00Z",
+///   "datasets": [...],
+///   "workflows": [...],
+///   "projects": [...],
+///   "users": [...],
+///   "datasetPermissions": [...],
+///   "projectPermissions": [...]
+/// }
+/// ```
+async fn backup_handler<C>(req: HttpRequest, ctx: web::Data<C>) -> Result<impl Responder>
+where
+    C: ProContext,
+    C::ProjectDB: ProProjectDb,
+    C::DatasetDB: BackupDb<DatasetPermission>,
+{
+    authorize_backup(&req)?;
+
+    Ok(web::Json(backup::backup(ctx.get_ref()).await?))
+}
+
+/// Restores a backup that was created with `GET /backup`.
+///
+/// Conflicts are handled like in the default instance.
+/// Users are never restored with new ids, so permissions of skipped users are dropped.
+///
+/// # Example
+///
+/// ```text
+/// POST /restore?onConflict=newId
+/// Authorization: Bearer my-admin-token
+///
+/// {
+///   "version": 1,
+///   ...
+/// }
+/// ```
+/// Response:
+/// ```text
+/// {
+///   "restored": 12,
+///   "skipped": 0,
+///   "remappedDatasets": [],
+///   "remappedWorkflows": [],
+///   "remappedProjects": [
+///     {
+///       "original": "df4ad02e-0d61-4e29-90eb-dc1259c1f5b9",
+///       "new": "3b04e9f9-4d1b-4e47-9b9c-8f4b4f5a3e38"
+///     }
+///   ]
+/// }
+/// ```
+async fn restore_handler<C>(
+    req: HttpRequest,
+    ctx: web::Data<C>,
+    options: web::Query<RestoreOptions>,
+    payload: web::Payload,
+) -> actix_web::Result<impl Responder>
+where
+    C: ProContext,
+    C::ProjectDB: ProProjectDb,
+    C::DatasetDB: BackupDb<DatasetPermission>,
+{
+    authorize_backup(&req)?;
+
+    let backup = StreamingJson::<ProBackup>::from_payload(&req, payload)
+        .await?
+        .into_inner();
+
+    Ok(web::Json(
+        backup::restore(ctx.get_ref(), backup, options.into_inner()).await?,
+    ))
+}
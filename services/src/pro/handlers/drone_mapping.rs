@@ -1,6 +1,7 @@
 use std::convert::TryInto;
 use std::path::Path;
 
+use crate::contexts::Session;
 use crate::datasets::storage::{AddDataset, DatasetDefinition, DatasetStore, MetaDataDefinition};
 use crate::datasets::upload::{UploadId, UploadRootPath};
 use crate::error;
@@ -91,13 +92,15 @@ pub struct CreateDatasetResponse {
 /// },
 /// ```
 async fn start_task_handler<C: ProContext>(
-    _session: C::Session,
+    session: C::Session,
     _ctx: web::Data<C>,
     task_start: web::Json<TaskStart>,
 ) -> Result<impl Responder>
 where
     C::ProjectDB: ProProjectDb,
 {
+    session.ensure_not_guest()?;
+
     let base_url = get_config_element::<Odm>()?.endpoint;
 
     // TODO: auth
@@ -206,6 +209,8 @@ async fn dataset_from_drone_mapping_handler<C: ProContext>(
 where
     C::ProjectDB: ProProjectDb,
 {
+    session.ensure_not_guest()?;
+
     let base_url = get_config_element::<Odm>()?.endpoint;
 
     // TODO: auth
@@ -312,6 +317,7 @@ async fn dataset_definition_from_geotiff(
                     spatial_reference: spatial_reference.into(),
                     measurement: Measurement::Unitless,
                     no_data_value: None, // TODO
+                    dimensions: vec![],
                 },
             }),
         })
@@ -511,6 +517,7 @@ mod tests {
                     .into(),
                 measurement: Measurement::Unitless,
                 no_data_value: None,
+                dimensions: vec![],
             }
         );
 
@@ -521,6 +528,7 @@ mod tests {
             ),
             time_interval: TimeInterval::default(),
             spatial_resolution: SpatialResolution::new_unchecked(1., 1.),
+            spatial_filter: (),
         };
 
         let mut loading_info = meta.loading_info(query).await.unwrap();
@@ -559,6 +567,7 @@ mod tests {
                     properties_mapping: None,
                     gdal_open_options: None,
                     gdal_config_options: None,
+                    apply_scale_offset: false,
                 }),
             }
         );
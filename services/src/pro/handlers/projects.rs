@@ -1,9 +1,11 @@
+use crate::contexts::Session;
 use crate::error::Result;
 use crate::handlers;
 use crate::pro::contexts::ProContext;
+use crate::pro::notifications::{notify, Notification};
 use crate::pro::projects::LoadVersion;
 use crate::pro::projects::{ProProjectDb, UserProjectPermission};
-use crate::projects::{ProjectId, ProjectVersionId};
+use crate::projects::{ProjectDb, ProjectId, ProjectVersionId};
 
 use actix_web::{web, HttpResponse, Responder};
 
@@ -40,6 +42,10 @@ where
                 web::resource("/{project}/permissions")
                     .route(web::get().to(list_permissions_handler::<C>)),
             )
+            .service(
+                web::resource("/{project}/citation")
+                    .route(web::get().to(handlers::projects::project_citation_handler::<C>)),
+            )
             .service(
                 web::resource("/{project}/{version}")
                     .route(web::get().to(load_project_version_handler::<C>)),
@@ -189,10 +195,39 @@ pub(crate) async fn add_permission_handler<C: ProContext>(
 where
     C::ProjectDB: ProProjectDb,
 {
+    session.ensure_not_guest()?;
+
+    let permission = permission.into_inner();
+
     ctx.project_db_ref_mut()
         .await
-        .add_permission(&session, permission.into_inner())
+        .add_permission(&session, permission.clone())
         .await?;
+
+    if permission.user != session.user.id && ctx.mailer().is_some() {
+        let project = ctx
+            .project_db_ref()
+            .await
+            .load(&session, permission.project)
+            .await?;
+
+        notify(
+            ctx.get_ref(),
+            permission.user,
+            Notification::ProjectShared {
+                project: permission.project,
+                name: project.name,
+                permission: permission.permission,
+                shared_by: session
+                    .user
+                    .real_name
+                    .or(session.user.email)
+                    .unwrap_or_default(),
+            },
+        )
+        .await;
+    }
+
     Ok(HttpResponse::Ok())
 }
 
@@ -219,6 +254,8 @@ pub(crate) async fn remove_permission_handler<C: ProContext>(
 where
     C::ProjectDB: ProProjectDb,
 {
+    session.ensure_not_guest()?;
+
     ctx.project_db_ref_mut()
         .await
         .remove_permission(&session, permission.into_inner())
@@ -268,12 +305,14 @@ mod tests {
     use actix_web::{http::header, http::Method, test};
     use actix_web_httpauth::headers::authorization::Bearer;
     use geoengine_datatypes::util::test::TestDefault;
+    use std::sync::Arc;
 
     use crate::{
         contexts::Context,
         handlers::ErrorResponse,
         pro::{
             contexts::ProInMemoryContext,
+            notifications::InMemoryMailer,
             projects::ProjectPermission,
             users::{UserCredentials, UserDb, UserRegistration},
             util::tests::{create_project_helper, send_pro_test_request},
@@ -398,6 +437,48 @@ mod tests {
             .is_ok());
     }
 
+    #[tokio::test]
+    async fn it_notifies_about_shared_projects() {
+        let mailer = Arc::new(InMemoryMailer::default());
+        let ctx = ProInMemoryContext::test_default().with_mailer(mailer.clone());
+
+        let (session, project) = create_project_helper(&ctx).await;
+
+        let target_user = ctx
+            .user_db()
+            .write()
+            .await
+            .register(
+                UserRegistration {
+                    email: "foo2@bar.de".to_string(),
+                    password: "secret1234".to_string(),
+                    real_name: "Foo2 Bar".to_string(),
+                }
+                .validated()
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let req = test::TestRequest::post()
+            .uri("/project/permission/add")
+            .append_header((header::CONTENT_LENGTH, 0))
+            .append_header((header::AUTHORIZATION, Bearer::new(session.id.to_string())))
+            .set_json(&UserProjectPermission {
+                user: target_user,
+                project,
+                permission: ProjectPermission::Write,
+            });
+        let res = send_pro_test_request(req, ctx).await;
+
+        assert_eq!(res.status(), 200);
+
+        let mails = mailer.mails().await;
+        assert_eq!(mails.len(), 1);
+        assert_eq!(mails[0].to, "foo2@bar.de");
+        assert_eq!(mails[0].subject, "Foo Bar shared the project Test with you");
+    }
+
     #[tokio::test]
     async fn add_permission_missing_header() {
         let ctx = ProInMemoryContext::test_default();
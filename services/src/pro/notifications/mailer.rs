@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use tokio::sync::Mutex;
+
+use crate::error::{self, Result};
+use crate::pro::util::config::Notifications;
+use crate::util::config::get_config_element;
+
+/// A plain text e-mail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, mail: Mail) -> Result<()>;
+}
+
+/// Sends e-mails via an SMTP server using STARTTLS
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    sender: String,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &Notifications) -> Result<Self> {
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+            .map_err(|e| error::Error::Mail {
+                reason: e.to_string(),
+            })?
+            .port(config.smtp_port);
+
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            sender: config.sender.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, mail: Mail) -> Result<()> {
+        let mail_error = |e: &dyn std::fmt::Display| error::Error::Mail {
+            reason: e.to_string(),
+        };
+
+        let message = Message::builder()
+            .from(self.sender.parse::<Mailbox>().map_err(|e| mail_error(&e))?)
+            .to(mail.to.parse::<Mailbox>().map_err(|e| mail_error(&e))?)
+            .subject(mail.subject)
+            .body(mail.body)
+            .map_err(|e| mail_error(&e))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| mail_error(&e))?;
+
+        Ok(())
+    }
+}
+
+/// Keeps all e-mails instead of sending them, e.g., for testing
+#[derive(Default)]
+pub struct InMemoryMailer {
+    mails: Mutex<Vec<Mail>>,
+}
+
+impl InMemoryMailer {
+    pub async fn mails(&self) -> Vec<Mail> {
+        self.mails.lock().await.clone()
+    }
+}
+
+#[async_trait]
+impl Mailer for InMemoryMailer {
+    async fn send(&self, mail: Mail) -> Result<()> {
+        self.mails.lock().await.push(mail);
+        Ok(())
+    }
+}
+
+/// Creates an SMTP mailer if notifications are enabled
+pub fn mailer_from_config() -> Option<Arc<dyn Mailer>> {
+    let config = get_config_element::<Notifications>().expect("config error");
+
+    if !config.enabled {
+        return None;
+    }
+
+    Some(Arc::new(
+        SmtpMailer::new(&config).expect("the SMTP configuration must be valid"),
+    ))
+}
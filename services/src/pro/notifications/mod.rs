@@ -0,0 +1,67 @@
+//! E-mail notifications of users, e.g., when a project is shared with them.
+//!
+//! Notifications are only sent if they are enabled in the config and only to registered users
+//! that did not opt out of their kind.
+
+mod mailer;
+mod notification;
+
+use log::warn;
+
+use crate::error::{self, Result};
+use crate::pro::contexts::ProContext;
+use crate::pro::users::{UserDb, UserId};
+use crate::pro::util::config::Notifications;
+use crate::util::config::get_config_element;
+
+pub use mailer::{mailer_from_config, InMemoryMailer, Mail, Mailer, SmtpMailer};
+pub use notification::{
+    Notification, NotificationKind, NotificationRecipient, NotificationSettings,
+};
+
+/// Sends the `notification` to the `recipient` if it did not opt out of its kind
+///
+/// # Errors
+///
+/// This call fails if notifications are disabled or the e-mail cannot be sent.
+///
+pub async fn send_notification<C: ProContext>(
+    ctx: &C,
+    recipient: &NotificationRecipient,
+    notification: &Notification,
+) -> Result<()> {
+    let mailer = ctx.mailer().ok_or(error::Error::NotificationsDisabled)?;
+
+    if !recipient.settings.allows(notification.kind()) {
+        return Ok(());
+    }
+
+    let frontend_url = get_config_element::<Notifications>()?.frontend_url;
+
+    mailer
+        .send(notification.mail(recipient, &frontend_url))
+        .await
+}
+
+/// Notifies the `user` if notifications are enabled.
+///
+/// Failures are only logged because they must not let the action that caused the notification fail.
+pub async fn notify<C: ProContext>(ctx: &C, user: UserId, notification: Notification) {
+    if ctx.mailer().is_none() {
+        return;
+    }
+
+    let recipient = match ctx.user_db_ref().await.notification_recipient(user).await {
+        Ok(Some(recipient)) => recipient,
+        // anonymous users have no e-mail address
+        Ok(None) => return,
+        Err(e) => {
+            warn!("Could not load the recipient of a notification: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = send_notification(ctx, &recipient, &notification).await {
+        warn!("Could not notify user {}: {}", user, e);
+    }
+}
@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::pro::notifications::Mail;
+use crate::pro::projects::ProjectPermission;
+use crate::pro::users::PasswordResetToken;
+use crate::projects::ProjectId;
+
+/// An event a user is informed about by e-mail
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notification {
+    /// A long-running task of the user has finished
+    TaskFinished { task: String },
+    /// A long-running task of the user has failed
+    TaskFailed { task: String, error: String },
+    /// Another user has shared a project with the user
+    ProjectShared {
+        project: ProjectId,
+        name: String,
+        permission: ProjectPermission,
+        shared_by: String,
+    },
+    /// The user has requested to reset its password
+    PasswordReset { token: PasswordResetToken },
+}
+
+/// The kinds of notifications users can opt out of, except for password resets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    TaskStatus,
+    Share,
+    PasswordReset,
+}
+
+/// The notifications a user wants to receive, all by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+    pub task_status: bool,
+    pub shares: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            task_status: true,
+            shares: true,
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn allows(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::TaskStatus => self.task_status,
+            NotificationKind::Share => self.shares,
+            NotificationKind::PasswordReset => true,
+        }
+    }
+}
+
+/// A registered user that can be notified
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationRecipient {
+    pub email: String,
+    pub real_name: String,
+    pub settings: NotificationSettings,
+}
+
+/// The subject and body of a notification with `{placeholders}`
+struct Template {
+    subject: &'static str,
+    body: &'static str,
+}
+
+const TASK_FINISHED: Template = Template {
+    subject: "Your task {task} has finished",
+    body: "Hello {name},\n\nyour task {task} has finished successfully.\n\n{link}\n",
+};
+
+const TASK_FAILED: Template = Template {
+    subject: "Your task {task} has failed",
+    body: "Hello {name},\n\nyour task {task} has failed with the error:\n\n{error}\n\n{link}\n",
+};
+
+const PROJECT_SHARED: Template = Template {
+    subject: "{shared_by} shared the project {project} with you",
+    body: "Hello {name},\n\n{shared_by} granted you {permission} access to the project {project}.\n\n{link}\n",
+};
+
+const PASSWORD_RESET: Template = Template {
+    subject: "Reset your password",
+    body: "Hello {name},\n\nyou can choose a new password within the next hour at:\n\n{link}\n\n\
+           If you did not request a password reset, you can ignore this e-mail.\n",
+};
+
+impl Notification {
+    pub fn kind(&self) -> NotificationKind {
+        match self {
+            Notification::TaskFinished { .. } | Notification::TaskFailed { .. } => {
+                NotificationKind::TaskStatus
+            }
+            Notification::ProjectShared { .. } => NotificationKind::Share,
+            Notification::PasswordReset { .. } => NotificationKind::PasswordReset,
+        }
+    }
+
+    /// Renders the e-mail to the `recipient` with links to the `frontend_url`
+    pub fn mail(&self, recipient: &NotificationRecipient, frontend_url: &Url) -> Mail {
+        let name = recipient.real_name.as_str();
+
+        let (template, values) = match self {
+            Notification::TaskFinished { task } => (
+                TASK_FINISHED,
+                vec![
+                    ("name", name.to_string()),
+                    ("task", task.clone()),
+                    ("link", frontend_url.to_string()),
+                ],
+            ),
+            Notification::TaskFailed { task, error } => (
+                TASK_FAILED,
+                vec![
+                    ("name", name.to_string()),
+                    ("task", task.clone()),
+                    ("error", error.clone()),
+                    ("link", frontend_url.to_string()),
+                ],
+            ),
+            Notification::ProjectShared {
+                project,
+                name: project_name,
+                permission,
+                shared_by,
+            } => (
+                PROJECT_SHARED,
+                vec![
+                    ("name", name.to_string()),
+                    ("project", project_name.clone()),
+                    ("permission", format!("{:?}", permission).to_lowercase()),
+                    ("shared_by", shared_by.clone()),
+                    ("link", link(frontend_url, &format!("project/{}", project))),
+                ],
+            ),
+            Notification::PasswordReset { token } => (
+                PASSWORD_RESET,
+                vec![
+                    ("name", name.to_string()),
+                    (
+                        "link",
+                        link(frontend_url, &format!("passwordReset/{}", token)),
+                    ),
+                ],
+            ),
+        };
+
+        Mail {
+            to: recipient.email.clone(),
+            subject: render(template.subject, &values),
+            body: render(template.body, &values),
+        }
+    }
+}
+
+fn link(frontend_url: &Url, path: &str) -> String {
+    frontend_url
+        .join(path)
+        .map_or_else(|_| frontend_url.to_string(), |url| url.to_string())
+}
+
+/// Replaces the `{placeholders}` of the `template` with their values
+fn render(template: &str, values: &[(&str, String)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |text, (placeholder, value)| {
+            text.replace(&format!("{{{}}}", placeholder), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::util::Identifier;
+
+    #[test]
+    fn it_renders_mails() {
+        let project = ProjectId::new();
+
+        let mail = Notification::ProjectShared {
+            project,
+            name: "Drill Holes".to_string(),
+            permission: ProjectPermission::Read,
+            shared_by: "Foo Bar".to_string(),
+        }
+        .mail(
+            &NotificationRecipient {
+                email: "baz@bar.de".to_string(),
+                real_name: "Baz".to_string(),
+                settings: NotificationSettings::default(),
+            },
+            &Url::parse("https://geoengine.example.com/app/").unwrap(),
+        );
+
+        assert_eq!(mail.to, "baz@bar.de");
+        assert_eq!(
+            mail.subject,
+            "Foo Bar shared the project Drill Holes with you"
+        );
+        assert_eq!(
+            mail.body,
+            format!(
+                "Hello Baz,\n\nFoo Bar granted you read access to the project Drill Holes.\n\nhttps://geoengine.example.com/app/project/{}\n",
+                project
+            )
+        );
+    }
+
+    #[test]
+    fn it_respects_opt_outs() {
+        let settings = NotificationSettings {
+            task_status: false,
+            shares: false,
+        };
+
+        assert!(!settings.allows(NotificationKind::TaskStatus));
+        assert!(!settings.allows(NotificationKind::Share));
+        assert!(settings.allows(NotificationKind::PasswordReset));
+    }
+}
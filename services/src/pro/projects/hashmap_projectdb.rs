@@ -1,7 +1,10 @@
+use crate::contexts::BackupDb;
 use crate::error;
 use crate::error::Result;
-use crate::pro::projects::{ProProjectDb, ProjectPermission, UserProjectPermission};
-use crate::pro::users::UserSession;
+use crate::pro::projects::{
+    ProProjectDb, ProjectOrganization, ProjectPermission, UserProjectPermission,
+};
+use crate::pro::users::{OrganizationId, Quota, UserSession};
 use crate::projects::{
     CreateProject, OrderBy, Project, ProjectDb, ProjectFilter, ProjectId, ProjectListOptions,
     ProjectListing, ProjectVersion, UpdateProject,
@@ -17,6 +20,24 @@ use super::LoadVersion;
 pub struct ProHashMapProjectDb {
     projects: HashMap<ProjectId, Vec<Project>>,
     permissions: Vec<UserProjectPermission>,
+    /// the organizations in which the projects were created
+    organizations: HashMap<ProjectId, OrganizationId>,
+}
+
+impl ProHashMapProjectDb {
+    /// Whether the user has a permission on the project or its organization owns the project
+    fn can_read(&self, session: &UserSession, project: ProjectId) -> bool {
+        let organization_project = session
+            .organization
+            .as_ref()
+            .map_or(false, |o| self.organizations.get(&project) == Some(&o.id));
+
+        organization_project
+            || self
+                .permissions
+                .iter()
+                .any(|p| p.project == project && p.user == session.user.id)
+    }
 }
 
 #[async_trait]
@@ -33,12 +54,11 @@ impl ProjectDb<UserSession> for ProHashMapProjectDb {
             offset,
             limit,
         } = options.user_input;
-        #[allow(clippy::flat_map_option)]
         let mut projects = self
-            .permissions
+            .projects
             .iter()
-            .filter(|p| p.user == session.user.id) // && permissions.contains(&p.permission))
-            .flat_map(|p| self.projects.get(&p.project).and_then(|p| p.last()))
+            .filter(|(id, _)| self.can_read(session, **id))
+            .filter_map(|(_, versions)| versions.last())
             .map(ProjectListing::from)
             .filter(|p| match &filter {
                 ProjectFilter::Name { term } => p.name == *term,
@@ -67,9 +87,21 @@ impl ProjectDb<UserSession> for ProHashMapProjectDb {
         session: &UserSession,
         create: Validated<CreateProject>,
     ) -> Result<ProjectId> {
+        if let Some(organization) = &session.organization {
+            let projects = self
+                .organizations
+                .values()
+                .filter(|o| **o == organization.id)
+                .count();
+            Quota::check(organization.quota.max_projects, projects, "projects")?;
+        }
+
         let project: Project = Project::from_create_project(create.user_input);
         let id = project.id;
         self.projects.insert(id, vec![project]);
+        if let Some(organization) = &session.organization {
+            self.organizations.insert(id, organization.id);
+        }
         self.permissions.push(UserProjectPermission {
             project: id,
             permission: ProjectPermission::Owner,
@@ -118,6 +150,7 @@ impl ProjectDb<UserSession> for ProHashMapProjectDb {
             error::ProjectUpdateFailed
         );
 
+        self.organizations.remove(&project);
         self.projects
             .remove(&project)
             .map(|_| ())
@@ -139,12 +172,7 @@ impl ProProjectDb for ProHashMapProjectDb {
         project: ProjectId,
         version: LoadVersion,
     ) -> Result<Project> {
-        ensure!(
-            self.permissions
-                .iter()
-                .any(|p| p.project == project && p.user == session.user.id),
-            error::ProjectLoadFailed
-        );
+        ensure!(self.can_read(session, project), error::ProjectLoadFailed);
         let project_versions = self
             .projects
             .get(&project)
@@ -170,12 +198,7 @@ impl ProProjectDb for ProHashMapProjectDb {
         project: ProjectId,
     ) -> Result<Vec<ProjectVersion>> {
         // TODO: pagination?
-        ensure!(
-            self.permissions
-                .iter()
-                .any(|p| p.project == project && p.user == session.user.id),
-            error::ProjectLoadFailed
-        );
+        ensure!(self.can_read(session, project), error::ProjectLoadFailed);
 
         Ok(self
             .projects
@@ -253,6 +276,85 @@ impl ProProjectDb for ProHashMapProjectDb {
     }
 }
 
+#[async_trait]
+impl BackupDb<Project> for ProHashMapProjectDb {
+    async fn export(&self) -> Result<Vec<Project>> {
+        Ok(self
+            .projects
+            .values()
+            .filter_map(|versions| versions.last().cloned())
+            .collect())
+    }
+
+    async fn import(&mut self, project: Project) -> Result<()> {
+        ensure!(
+            !self.projects.contains_key(&project.id),
+            error::Duplicate {
+                reason: format!("Project {} already exists", project.id),
+            }
+        );
+
+        self.projects.insert(project.id, vec![project]);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BackupDb<UserProjectPermission> for ProHashMapProjectDb {
+    async fn export(&self) -> Result<Vec<UserProjectPermission>> {
+        Ok(self.permissions.clone())
+    }
+
+    async fn import(&mut self, permission: UserProjectPermission) -> Result<()> {
+        ensure!(
+            !self
+                .permissions
+                .iter()
+                .any(|p| p.project == permission.project && p.user == permission.user),
+            error::Duplicate {
+                reason: format!("Project permission {:?} already exists", permission),
+            }
+        );
+
+        self.permissions.push(permission);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BackupDb<ProjectOrganization> for ProHashMapProjectDb {
+    async fn export(&self) -> Result<Vec<ProjectOrganization>> {
+        Ok(self
+            .organizations
+            .iter()
+            .map(|(&project, &organization)| ProjectOrganization {
+                project,
+                organization,
+            })
+            .collect())
+    }
+
+    async fn import(&mut self, project_organization: ProjectOrganization) -> Result<()> {
+        ensure!(
+            !self
+                .organizations
+                .contains_key(&project_organization.project),
+            error::Duplicate {
+                reason: format!(
+                    "Project {} already belongs to an organization",
+                    project_organization.project
+                ),
+            }
+        );
+
+        self.organizations.insert(
+            project_organization.project,
+            project_organization.organization,
+        );
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -6,7 +6,9 @@ mod projectdb;
 pub use hashmap_projectdb::ProHashMapProjectDb;
 #[cfg(feature = "postgres")]
 pub use postgres_projectdb::PostgresProjectDb;
-pub use projectdb::{ProProjectDb, ProjectListOptions, ProjectPermission, UserProjectPermission};
+pub use projectdb::{
+    ProProjectDb, ProjectListOptions, ProjectOrganization, ProjectPermission, UserProjectPermission,
+};
 
 use crate::projects::ProjectVersionId;
 use uuid::Uuid;
@@ -1,4 +1,6 @@
+use crate::contexts::BackupDb;
 use crate::pro::contexts::PostgresContext;
+use crate::pro::users::Quota;
 use crate::pro::users::UserId;
 use crate::pro::users::UserSession;
 use crate::projects::Layer;
@@ -20,13 +22,14 @@ use bb8_postgres::{
     bb8::Pool, tokio_postgres::tls::MakeTlsConnect, tokio_postgres::tls::TlsConnect,
     tokio_postgres::Socket,
 };
-use snafu::ResultExt;
+use snafu::{ensure, ResultExt};
 
 use bb8_postgres::bb8::PooledConnection;
 use bb8_postgres::tokio_postgres::Transaction;
 
 use super::LoadVersion;
 use super::ProProjectDb;
+use super::ProjectOrganization;
 use super::UserProjectPermission;
 
 pub struct PostgresProjectDb<Tls>
@@ -93,14 +96,89 @@ where
         let plots = rows
             .into_iter()
             .map(|row| Plot {
-                workflow: WorkflowId(row.get(2)),
-                name: row.get(1),
+                workflow: WorkflowId(row.get(1)),
+                name: row.get(0),
             })
             .collect();
 
         Ok(plots)
     }
 
+    async fn load_layers(
+        &self,
+        conn: &PooledConnection<'_, PostgresConnectionManager<Tls>>,
+        project_version_id: &ProjectVersionId,
+    ) -> Result<Vec<Layer>> {
+        let stmt = conn
+            .prepare(
+                "
+        SELECT  
+            name, workflow_id, symbology, visibility
+        FROM project_version_layers
+        WHERE project_version_id = $1
+        ORDER BY layer_index ASC",
+            )
+            .await?;
+
+        let rows = conn.query(&stmt, &[project_version_id]).await?;
+
+        let mut layers = vec![];
+        for row in rows {
+            layers.push(Layer {
+                workflow: WorkflowId(row.get(1)),
+                name: row.get(0),
+                symbology: serde_json::from_value(row.get(2)).context(error::SerdeJson)?,
+                visibility: row.get(3),
+            });
+        }
+
+        Ok(layers)
+    }
+
+    async fn update_layers(
+        &self,
+        trans: &Transaction<'_>,
+        project_id: &ProjectId,
+        project_version_id: &ProjectVersionId,
+        layers: &[Layer],
+    ) -> Result<()> {
+        for (idx, layer) in layers.iter().enumerate() {
+            let stmt = trans
+                .prepare(
+                    "
+                INSERT INTO project_version_layers (
+                    project_id,
+                    project_version_id,
+                    layer_index,
+                    name,
+                    workflow_id,
+                    symbology,
+                    visibility)
+                VALUES ($1, $2, $3, $4, $5, $6, $7);",
+                )
+                .await?;
+
+            let symbology = serde_json::to_value(&layer.symbology).context(error::SerdeJson)?;
+
+            trans
+                .execute(
+                    &stmt,
+                    &[
+                        project_id,
+                        project_version_id,
+                        &(idx as i32),
+                        &layer.name,
+                        &layer.workflow,
+                        &symbology,
+                        &layer.visibility,
+                    ],
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
     async fn update_plots(
         &self,
         trans: &Transaction<'_>,
@@ -163,7 +241,7 @@ where
             .prepare(&format!(
                 "
         SELECT p.id, p.project_id, p.name, p.description, p.changed 
-        FROM user_project_permissions u JOIN project_versions p ON (u.project_id = p.project_id)
+        FROM user_project_access u JOIN project_versions p ON (u.project_id = p.project_id)
         WHERE
             u.user_id = $1
             AND latest IS TRUE
@@ -228,11 +306,26 @@ where
 
         let trans = conn.build_transaction().start().await?;
 
+        let organization = session.organization.as_ref().map(|o| o.id);
+
+        if let Some(organization) = &session.organization {
+            let stmt = trans
+                .prepare("SELECT COUNT(*) FROM projects WHERE organization_id = $1;")
+                .await?;
+            let projects: i64 = trans.query_one(&stmt, &[&organization.id]).await?.get(0);
+
+            Quota::check(
+                organization.quota.max_projects,
+                projects as usize,
+                "projects",
+            )?;
+        }
+
         let stmt = trans
-            .prepare("INSERT INTO projects (id) VALUES ($1);")
+            .prepare("INSERT INTO projects (id, organization_id) VALUES ($1, $2);")
             .await?;
 
-        trans.execute(&stmt, &[&project.id]).await?;
+        trans.execute(&stmt, &[&project.id, &organization]).await?;
 
         let stmt = trans
             .prepare(
@@ -349,39 +442,8 @@ where
             )
             .await?;
 
-        for (idx, layer) in project.layers.iter().enumerate() {
-            let stmt = trans
-                .prepare(
-                    "
-                INSERT INTO project_version_layers (
-                    project_id,
-                    project_version_id,
-                    layer_index,
-                    name,
-                    workflow_id,
-                    symbology,
-                    visibility)
-                VALUES ($1, $2, $3, $4, $5, $6, $7);",
-                )
-                .await?;
-
-            let symbology = serde_json::to_value(&layer.symbology).context(error::SerdeJson)?;
-
-            trans
-                .execute(
-                    &stmt,
-                    &[
-                        &project.id,
-                        &project.version.id,
-                        &(idx as i32),
-                        &layer.name,
-                        &layer.workflow,
-                        &symbology,
-                        &layer.visibility,
-                    ],
-                )
-                .await?;
-        }
+        self.update_layers(&trans, &project.id, &project.version.id, &project.layers)
+            .await?;
 
         self.update_plots(&trans, &project.id, &project.version.id, &project.plots)
             .await?;
@@ -452,7 +514,7 @@ where
             p.time_step,
             p.changed,
             p.author_user_id
-        FROM user_project_permissions u JOIN project_versions p ON (u.project_id = p.project_id)
+        FROM user_project_access u JOIN project_versions p ON (u.project_id = p.project_id)
         WHERE u.user_id = $1 AND u.project_id = $2 AND p.project_version = $3",
                 )
                 .await?;
@@ -472,7 +534,7 @@ where
             p.time_step,
             p.changed,
             p.author_user_id
-        FROM user_project_permissions u JOIN project_versions p ON (u.project_id = p.project_id)
+        FROM user_project_access u JOIN project_versions p ON (u.project_id = p.project_id)
         WHERE u.user_id = $1 AND u.project_id = $2 AND latest IS TRUE",
                 )
                 .await?;
@@ -489,28 +551,7 @@ where
         let changed = row.get(6);
         let _author_id = UserId(row.get(7));
 
-        let stmt = conn
-            .prepare(
-                "
-        SELECT  
-            name, workflow_id, symbology, visibility
-        FROM project_version_layers
-        WHERE project_version_id = $1
-        ORDER BY layer_index ASC",
-            )
-            .await?;
-
-        let rows = conn.query(&stmt, &[&version_id]).await?;
-
-        let mut layers = vec![];
-        for row in rows {
-            layers.push(Layer {
-                workflow: WorkflowId(row.get(1)),
-                name: row.get(0),
-                symbology: serde_json::from_value(row.get(2)).context(error::SerdeJson)?,
-                visibility: row.get(3),
-            });
-        }
+        let layers = self.load_layers(&conn, &version_id).await?;
 
         Ok(Project {
             id: project_id,
@@ -676,3 +717,242 @@ where
         Ok(())
     }
 }
+
+#[async_trait]
+impl<Tls> BackupDb<Project> for PostgresProjectDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    /// Exports the latest versions of all projects
+    async fn export(&self) -> Result<Vec<Project>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "
+        SELECT
+            project_id,
+            id,
+            name,
+            description,
+            bounds,
+            time_step,
+            changed
+        FROM project_versions
+        WHERE latest IS TRUE",
+            )
+            .await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        let mut projects = Vec::with_capacity(rows.len());
+        for row in rows {
+            let version_id = ProjectVersionId(row.get(1));
+
+            projects.push(Project {
+                id: ProjectId(row.get(0)),
+                version: ProjectVersion {
+                    id: version_id,
+                    changed: row.get(6),
+                },
+                name: row.get(2),
+                description: row.get(3),
+                layers: self.load_layers(&conn, &version_id).await?,
+                plots: self.load_plots(&conn, &version_id).await?,
+                bounds: row.get(4),
+                time_step: row.get(5),
+            });
+        }
+
+        Ok(projects)
+    }
+
+    /// Imports the project as its latest version authored by the system user
+    async fn import(&mut self, project: Project) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let trans = conn.build_transaction().start().await?;
+
+        let stmt = trans
+            .prepare("SELECT EXISTS(SELECT 1 FROM projects WHERE id = $1);")
+            .await?;
+        let exists: bool = trans.query_one(&stmt, &[&project.id]).await?.get(0);
+
+        ensure!(
+            !exists,
+            error::Duplicate {
+                reason: format!("Project {} already exists", project.id),
+            }
+        );
+
+        let stmt = trans
+            .prepare("INSERT INTO projects (id) VALUES ($1);")
+            .await?;
+
+        trans.execute(&stmt, &[&project.id]).await?;
+
+        let stmt = trans
+            .prepare(
+                "INSERT INTO project_versions (
+                    id,
+                    project_id,
+                    name,
+                    description,
+                    bounds,
+                    time_step,
+                    author_user_id,
+                    changed,
+                    latest)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, TRUE);",
+            )
+            .await?;
+
+        trans
+            .execute(
+                &stmt,
+                &[
+                    &project.version.id,
+                    &project.id,
+                    &project.name,
+                    &project.description,
+                    &project.bounds,
+                    &project.time_step,
+                    &UserSession::system_session().user.id,
+                    &project.version.changed,
+                ],
+            )
+            .await?;
+
+        self.update_layers(&trans, &project.id, &project.version.id, &project.layers)
+            .await?;
+        self.update_plots(&trans, &project.id, &project.version.id, &project.plots)
+            .await?;
+
+        trans.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Tls> BackupDb<UserProjectPermission> for PostgresProjectDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn export(&self) -> Result<Vec<UserProjectPermission>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT user_id, project_id, permission FROM user_project_permissions;")
+            .await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| UserProjectPermission {
+                user: UserId(row.get(0)),
+                project: ProjectId(row.get(1)),
+                permission: row.get(2),
+            })
+            .collect())
+    }
+
+    async fn import(&mut self, permission: UserProjectPermission) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "
+        INSERT INTO user_project_permissions (user_id, project_id, permission)
+        VALUES ($1, $2, $3)
+        ON CONFLICT DO NOTHING;",
+            )
+            .await?;
+
+        let inserted = conn
+            .execute(
+                &stmt,
+                &[
+                    &permission.user,
+                    &permission.project,
+                    &permission.permission,
+                ],
+            )
+            .await?;
+
+        ensure!(
+            inserted > 0,
+            error::Duplicate {
+                reason: format!("Project permission {:?} already exists", permission),
+            }
+        );
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Tls> BackupDb<ProjectOrganization> for PostgresProjectDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn export(&self) -> Result<Vec<ProjectOrganization>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT id, organization_id FROM projects WHERE organization_id IS NOT NULL;")
+            .await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ProjectOrganization {
+                project: ProjectId(row.get(0)),
+                organization: row.get(1),
+            })
+            .collect())
+    }
+
+    async fn import(&mut self, project_organization: ProjectOrganization) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "UPDATE projects SET organization_id = $2 WHERE id = $1 AND organization_id IS NULL;",
+            )
+            .await?;
+
+        let updated = conn
+            .execute(
+                &stmt,
+                &[
+                    &project_organization.project,
+                    &project_organization.organization,
+                ],
+            )
+            .await?;
+
+        ensure!(
+            updated > 0,
+            error::Duplicate {
+                reason: format!(
+                    "Project {} already belongs to an organization",
+                    project_organization.project
+                ),
+            }
+        );
+
+        Ok(())
+    }
+}
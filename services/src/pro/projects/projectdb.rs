@@ -1,7 +1,8 @@
+use crate::contexts::BackupDb;
 use crate::error::Result;
 use crate::projects::{Project, ProjectDb, ProjectId, ProjectVersion};
 use crate::{
-    pro::users::{UserId, UserSession},
+    pro::users::{OrganizationId, UserId, UserSession},
     projects::{OrderBy, ProjectFilter},
 };
 use async_trait::async_trait;
@@ -13,7 +14,9 @@ use super::LoadVersion;
 
 /// Storage of user projects
 #[async_trait]
-pub trait ProProjectDb: ProjectDb<UserSession> {
+pub trait ProProjectDb:
+    ProjectDb<UserSession> + BackupDb<UserProjectPermission> + BackupDb<ProjectOrganization>
+{
     /// Load the the `version` of the `project` for the `user`
     async fn load_version(
         &self,
@@ -66,6 +69,13 @@ pub struct UserProjectPermission {
     pub user: UserId,
 }
 
+/// The organization in which a project was created and whose members can read it
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash)]
+pub struct ProjectOrganization {
+    pub project: ProjectId,
+    pub organization: OrganizationId,
+}
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash)]
 pub struct ProjectListOptions {
     #[serde(default)]
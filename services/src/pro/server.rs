@@ -1,3 +1,4 @@
+use crate::contexts::BackupDb;
 use crate::error::{Error, Result};
 use crate::handlers;
 use crate::pro;
@@ -5,10 +6,17 @@ use crate::pro;
 use crate::pro::contexts::PostgresContext;
 use crate::pro::contexts::{ProContext, ProInMemoryContext};
 use crate::util::config::{self, get_config_element, Backend};
+use crate::util::rate_limiting::RateLimiting;
 
+use super::datasets::{
+    DatasetAccessPolicies, DatasetLicenses, DatasetPermission, UpdateDatasetPermissions,
+};
 use super::projects::ProProjectDb;
 use crate::server::{
-    calculate_max_blocking_threads_per_worker, configure_extractors, render_404, render_405,
+    api_address, calculate_max_blocking_threads_per_worker, configure_extractors,
+    configure_loading_info_cache, configure_remote_reads, cors, render_404, render_405,
+    schedule_dataset_validation, schedule_export_cleanup, security_headers,
+    watch_dataset_directories,
 };
 use actix_files::Files;
 use actix_web::{http, middleware, web, App, HttpServer};
@@ -17,7 +25,6 @@ use bb8_postgres::tokio_postgres::NoTls;
 use log::{info, warn};
 use std::net::SocketAddr;
 use std::path::PathBuf;
-use url::Url;
 
 async fn start<C>(
     static_files_dir: Option<PathBuf>,
@@ -28,7 +35,29 @@ async fn start<C>(
 where
     C: ProContext,
     C::ProjectDB: ProProjectDb,
+    C::DatasetDB: BackupDb<DatasetPermission>
+        + DatasetAccessPolicies
+        + DatasetLicenses
+        + UpdateDatasetPermissions,
 {
+    let worker_mode = get_config_element::<config::Distributed>()?.worker;
+    if worker_mode {
+        info!("Worker mode is enabled");
+    }
+
+    configure_loading_info_cache()?;
+    configure_remote_reads()?;
+
+    let rate_limiting = RateLimiting::<C>::from_config()?;
+    let cors_config = get_config_element::<config::Cors>()?;
+    let api_address = api_address(&get_config_element::<config::Web>()?)?;
+    let compression = get_config_element::<config::Web>()?.compression;
+    let security_headers_config = get_config_element::<config::SecurityHeaders>()?;
+
+    schedule_dataset_validation(ctx.clone())?;
+    schedule_export_cleanup(&ctx)?;
+    watch_dataset_directories(ctx.clone())?;
+
     let wrapped_ctx = web::Data::new(ctx);
 
     HttpServer::new(move || {
@@ -39,19 +68,38 @@ where
                     .handler(http::StatusCode::NOT_FOUND, render_404)
                     .handler(http::StatusCode::METHOD_NOT_ALLOWED, render_405),
             )
+            .wrap(rate_limiting.clone())
             .wrap(middleware::Logger::default())
             .wrap(middleware::NormalizePath::trim())
+            .wrap(security_headers(&security_headers_config))
+            .wrap(cors(&cors_config, &api_address))
+            .wrap(middleware::Condition::new(
+                compression,
+                middleware::Compress::default(),
+            ))
             .configure(configure_extractors)
+            .configure(pro::handlers::backup::init_backup_routes::<C>)
+            .configure(pro::handlers::datasets::init_dataset_access_policy_routes::<C>)
+            .configure(pro::handlers::datasets::init_dataset_license_routes::<C>)
             .configure(handlers::datasets::init_dataset_routes::<C>)
+            .configure(pro::handlers::groups::init_group_routes::<C>)
+            .configure(handlers::info::init_info_routes::<C>)
             .configure(handlers::plots::init_plot_routes::<C>)
+            .configure(pro::handlers::organizations::init_organization_routes::<C>)
             .configure(pro::handlers::projects::init_project_routes::<C>)
             .configure(pro::handlers::users::init_user_routes::<C>)
+            .configure(handlers::scalars::init_scalar_routes::<C>)
             .configure(handlers::spatial_references::init_spatial_reference_routes::<C>)
+            .configure(handlers::tables::init_table_routes::<C>)
             .configure(handlers::upload::init_upload_routes::<C>)
             .configure(handlers::wcs::init_wcs_routes::<C>)
             .configure(handlers::wfs::init_wfs_routes::<C>)
             .configure(handlers::wms::init_wms_routes::<C>)
             .configure(handlers::workflows::init_workflow_routes::<C>);
+
+        if worker_mode {
+            app = app.configure(handlers::workers::init_worker_routes::<C>);
+        }
         #[cfg(feature = "odm")]
         {
             app = app.configure(pro::handlers::drone_mapping::init_drone_mapping_routes::<C>);
@@ -92,12 +140,7 @@ pub async fn start_pro_server(static_files_dir: Option<PathBuf>) -> Result<()> {
 
     let web_config: config::Web = get_config_element()?;
 
-    info!(
-        "Starting server… {}",
-        web_config
-            .external_address
-            .unwrap_or(Url::parse(&format!("http://{}/", web_config.bind_address))?)
-    );
+    info!("Starting server… {}", api_address(&web_config)?);
 
     let session_config: crate::util::config::Session = get_config_element()?;
     let user_config: crate::pro::util::config::User = get_config_element()?;
@@ -108,6 +151,13 @@ pub async fn start_pro_server(static_files_dir: Option<PathBuf>) -> Result<()> {
         info!("Anonymous access is disabled");
     }
 
+    if session_config.guest_access {
+        info!(
+            "Guest access is enabled with at most {} sessions and {} workflow executions per session",
+            session_config.guest_max_sessions, session_config.guest_max_queries
+        );
+    }
+
     if session_config.fixed_session_token.is_some() {
         warn!("Fixed session token is set, but it will be ignored in Geo Engine Pro");
     }
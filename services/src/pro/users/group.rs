@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::error::{self, Error, Result};
+use crate::pro::datasets::RoleId;
+use crate::pro::users::UserId;
+use crate::util::user_input::UserInput;
+use geoengine_datatypes::identifier;
+
+identifier!(GroupId);
+
+/// Each group has a role that is granted to all its members
+impl From<GroupId> for RoleId {
+    fn from(group_id: GroupId) -> Self {
+        RoleId(group_id.0)
+    }
+}
+
+/// A set of users that datasets can be shared with, a user can be a member of many groups
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Group {
+    pub id: GroupId,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGroup {
+    pub name: String,
+}
+
+impl UserInput for CreateGroup {
+    fn validate(&self) -> Result<(), Error> {
+        ensure!(
+            !self.name.trim().is_empty(),
+            error::InvalidGroup {
+                reason: "Name must not be empty"
+            }
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "postgres",
+    derive(postgres_types::ToSql, postgres_types::FromSql)
+)]
+pub enum GroupRole {
+    /// Manages the members of the group
+    Admin,
+    Member,
+}
+
+/// The membership of a user in a group
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupMembership {
+    pub group: GroupId,
+    pub user: UserId,
+    pub role: GroupRole,
+}
@@ -1,14 +1,19 @@
 use std::collections::HashMap;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use pwhash::bcrypt;
 use snafu::ensure;
 
-use crate::contexts::SessionId;
+use crate::contexts::{BackupDb, SessionId};
 use crate::error::{self, Result};
 use crate::pro::datasets::Role;
+use crate::pro::notifications::{NotificationRecipient, NotificationSettings};
 use crate::pro::users::{
-    User, UserCredentials, UserDb, UserId, UserInfo, UserRegistration, UserSession,
+    session_valid_until, CreateGroup, CreateOrganization, Group, GroupId, GroupMembership,
+    GroupRole, Organization, OrganizationId, OrganizationMembership, OrganizationRole,
+    PasswordReset, PasswordResetToken, Quota, User, UserCredentials, UserDb, UserId, UserInfo,
+    UserRegistration, UserSession, UserState, PASSWORD_RESET_VALIDITY_MINUTES,
 };
 use crate::projects::{ProjectId, STRectangle};
 use crate::util::user_input::Validated;
@@ -18,6 +23,81 @@ use geoengine_datatypes::util::Identifier;
 pub struct HashMapUserDb {
     users: HashMap<String, User>,
     sessions: HashMap<SessionId, UserSession>,
+    organizations: HashMap<OrganizationId, Organization>,
+    memberships: Vec<OrganizationMembership>,
+    groups: HashMap<GroupId, Group>,
+    group_memberships: Vec<GroupMembership>,
+    user_states: HashMap<UserId, UserState>,
+    notification_settings: HashMap<UserId, NotificationSettings>,
+    password_resets: HashMap<PasswordResetToken, (UserId, DateTime<Utc>)>,
+    institutions: HashMap<UserId, String>,
+}
+
+impl HashMapUserDb {
+    /// Adds the current organization of the user to a stored session
+    fn with_organization(&self, mut session: UserSession) -> UserSession {
+        let organization = self
+            .memberships
+            .iter()
+            .find(|m| m.user == session.user.id)
+            .and_then(|m| self.organizations.get(&m.organization));
+
+        if let Some(organization) = organization {
+            session.roles.push(organization.id.into());
+            session.organization = Some(organization.clone());
+        }
+
+        session
+    }
+
+    /// Adds the roles of the user's current groups to a stored session
+    fn with_groups(&self, mut session: UserSession) -> UserSession {
+        session.roles.extend(
+            self.group_memberships
+                .iter()
+                .filter(|m| m.user == session.user.id)
+                .map(|m| m.group.into()),
+        );
+        session
+    }
+
+    /// Adds the current institution of the user to a stored session
+    fn with_institution(&self, mut session: UserSession) -> UserSession {
+        session.user.institution = self.institutions.get(&session.user.id).cloned();
+        session
+    }
+
+    /// The membership of the session's user if it is an admin of its organization
+    fn admin_membership(&self, session: &UserSession) -> Result<&OrganizationMembership> {
+        let membership = self
+            .memberships
+            .iter()
+            .find(|m| m.user == session.user.id)
+            .ok_or(error::Error::NoOrganization)?;
+
+        ensure!(
+            membership.role == OrganizationRole::Admin,
+            error::OrganizationPermissionDenied
+        );
+
+        Ok(membership)
+    }
+
+    /// Fails if the session's user is not an admin of the `group`
+    fn ensure_group_admin(&self, session: &UserSession, group: GroupId) -> Result<()> {
+        let membership = self
+            .group_memberships
+            .iter()
+            .find(|m| m.group == group && m.user == session.user.id)
+            .ok_or(error::Error::UnknownGroup)?;
+
+        ensure!(
+            membership.role == GroupRole::Admin,
+            error::GroupPermissionDenied
+        );
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -50,18 +130,22 @@ impl UserDb for HashMapUserDb {
 
         self.users.insert(id.to_string(), user);
 
+        let created = chrono::Utc::now();
         let session = UserSession {
             id: SessionId::new(),
             user: UserInfo {
                 id,
                 email: None,
                 real_name: None,
+                institution: None,
             },
-            created: chrono::Utc::now(),
-            valid_until: chrono::Utc::now() + chrono::Duration::minutes(60),
+            created,
+            valid_until: session_valid_until(created)?,
             project: None,
             view: None,
             roles: vec![id.into(), Role::anonymous_role_id()],
+            organization: None,
+            client_ip: None,
         };
 
         self.sessions.insert(session.id, session.clone());
@@ -72,23 +156,26 @@ impl UserDb for HashMapUserDb {
     async fn login(&mut self, user_credentials: UserCredentials) -> Result<UserSession> {
         match self.users.get(&user_credentials.email) {
             Some(user) if bcrypt::verify(user_credentials.password, &user.password_hash) => {
+                let created = chrono::Utc::now();
                 let session = UserSession {
                     id: SessionId::new(),
                     user: UserInfo {
                         id: user.id,
                         email: Some(user.email.clone()),
                         real_name: Some(user.real_name.clone()),
+                        institution: None,
                     },
-                    created: chrono::Utc::now(),
-                    // TODO: make session length configurable
-                    valid_until: chrono::Utc::now() + chrono::Duration::minutes(60),
+                    created,
+                    valid_until: session_valid_until(created)?,
                     project: None,
                     view: None,
                     roles: vec![user.id.into(), Role::user_role_id()],
+                    organization: None,
+                    client_ip: None,
                 };
 
                 self.sessions.insert(session.id, session.clone());
-                Ok(session)
+                Ok(self.with_institution(self.with_groups(self.with_organization(session))))
             }
             _ => Err(error::Error::LoginFailed),
         }
@@ -102,10 +189,37 @@ impl UserDb for HashMapUserDb {
         }
     }
 
+    async fn refresh_session(&mut self, session: SessionId) -> Result<UserSession> {
+        let mut session = match self.sessions.remove(&session) {
+            Some(session) if Utc::now() < session.valid_until => session,
+            _ => return Err(error::Error::InvalidSession),
+        };
+
+        session.id = SessionId::new();
+        session.valid_until = session_valid_until(session.created)?;
+
+        self.sessions.insert(session.id, session.clone());
+        Ok(self.with_institution(self.with_groups(self.with_organization(session))))
+    }
+
+    async fn renew_session(&mut self, session: SessionId) -> Result<UserSession> {
+        let session = match self.sessions.get_mut(&session) {
+            Some(session) if Utc::now() < session.valid_until => session,
+            _ => return Err(error::Error::InvalidSession),
+        };
+
+        session.valid_until = session_valid_until(session.created)?;
+
+        let session = session.clone();
+        Ok(self.with_institution(self.with_groups(self.with_organization(session))))
+    }
+
     async fn session(&self, session: SessionId) -> Result<UserSession> {
         match self.sessions.get(&session) {
-            Some(session) => Ok(session.clone()),
-            None => Err(error::Error::InvalidSession),
+            Some(session) if Utc::now() < session.valid_until => Ok(
+                self.with_institution(self.with_groups(self.with_organization(session.clone())))
+            ),
+            _ => Err(error::Error::InvalidSession),
         }
     }
 
@@ -133,6 +247,473 @@ impl UserDb for HashMapUserDb {
             None => Err(error::Error::InvalidSession),
         }
     }
+
+    async fn user_state(&self, session: &UserSession) -> Result<UserState> {
+        ensure!(
+            self.sessions.contains_key(&session.id),
+            error::InvalidSession
+        );
+
+        Ok(self
+            .user_states
+            .get(&session.user.id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn set_user_state(&mut self, session: &UserSession, state: UserState) -> Result<()> {
+        ensure!(
+            self.sessions.contains_key(&session.id),
+            error::InvalidSession
+        );
+
+        self.user_states.insert(session.user.id, state);
+
+        Ok(())
+    }
+
+    async fn notification_settings(&self, session: &UserSession) -> Result<NotificationSettings> {
+        ensure!(
+            self.sessions.contains_key(&session.id),
+            error::InvalidSession
+        );
+
+        Ok(self
+            .notification_settings
+            .get(&session.user.id)
+            .copied()
+            .unwrap_or_default())
+    }
+
+    async fn set_notification_settings(
+        &mut self,
+        session: &UserSession,
+        settings: NotificationSettings,
+    ) -> Result<()> {
+        ensure!(
+            self.sessions.contains_key(&session.id),
+            error::InvalidSession
+        );
+
+        self.notification_settings.insert(session.user.id, settings);
+
+        Ok(())
+    }
+
+    async fn notification_recipient(&self, user: UserId) -> Result<Option<NotificationRecipient>> {
+        Ok(self
+            .users
+            .values()
+            .find(|u| u.id == user && !u.password_hash.is_empty())
+            .map(|u| NotificationRecipient {
+                email: u.email.clone(),
+                real_name: u.real_name.clone(),
+                settings: self
+                    .notification_settings
+                    .get(&u.id)
+                    .copied()
+                    .unwrap_or_default(),
+            }))
+    }
+
+    async fn create_password_reset(
+        &mut self,
+        email: &str,
+    ) -> Result<Option<(NotificationRecipient, PasswordResetToken)>> {
+        let user = match self.users.get(email) {
+            Some(user) if !user.password_hash.is_empty() => user.id,
+            _ => return Ok(None),
+        };
+
+        let token = PasswordResetToken::new();
+        self.password_resets.insert(
+            token,
+            (
+                user,
+                Utc::now() + chrono::Duration::minutes(PASSWORD_RESET_VALIDITY_MINUTES),
+            ),
+        );
+
+        Ok(self
+            .notification_recipient(user)
+            .await?
+            .map(|recipient| (recipient, token)))
+    }
+
+    async fn reset_password(&mut self, reset: Validated<PasswordReset>) -> Result<()> {
+        let reset = reset.user_input;
+
+        let user_id = match self.password_resets.remove(&reset.token) {
+            Some((user, valid_until)) if Utc::now() < valid_until => user,
+            _ => return Err(error::Error::InvalidPasswordResetToken),
+        };
+
+        let user = self
+            .users
+            .values_mut()
+            .find(|u| u.id == user_id)
+            .ok_or(error::Error::InvalidPasswordResetToken)?;
+        user.password_hash =
+            bcrypt::hash(&reset.password).map_err(|_| error::Error::InvalidPassword {
+                reason: "The password cannot be hashed".to_string(),
+            })?;
+
+        self.sessions
+            .retain(|_, session| session.user.id != user_id);
+
+        Ok(())
+    }
+
+    async fn create_organization(
+        &mut self,
+        session: &UserSession,
+        organization: Validated<CreateOrganization>,
+        quota: Quota,
+    ) -> Result<OrganizationId> {
+        let organization = organization.user_input;
+
+        ensure!(
+            session.user.email.is_some(),
+            error::InvalidOrganization {
+                reason: "Anonymous users can not create organizations",
+            }
+        );
+        ensure!(
+            self.memberships.iter().all(|m| m.user != session.user.id),
+            error::AlreadyOrganizationMember
+        );
+        ensure!(
+            self.organizations
+                .values()
+                .all(|o| o.name != organization.name),
+            error::Duplicate {
+                reason: "Organization name already exists"
+            }
+        );
+
+        let id = OrganizationId::new();
+        self.organizations.insert(
+            id,
+            Organization {
+                id,
+                name: organization.name,
+                quota,
+            },
+        );
+        self.memberships.push(OrganizationMembership {
+            organization: id,
+            user: session.user.id,
+            role: OrganizationRole::Admin,
+        });
+
+        Ok(id)
+    }
+
+    async fn organization_members(
+        &self,
+        session: &UserSession,
+    ) -> Result<Vec<OrganizationMembership>> {
+        let organization = session
+            .organization
+            .as_ref()
+            .ok_or(error::Error::NoOrganization)?;
+
+        Ok(self
+            .memberships
+            .iter()
+            .filter(|m| m.organization == organization.id)
+            .cloned()
+            .collect())
+    }
+
+    async fn set_user_institution(
+        &mut self,
+        user: UserId,
+        institution: Option<String>,
+    ) -> Result<()> {
+        ensure!(
+            self.users
+                .values()
+                .any(|u| u.id == user && !u.password_hash.is_empty()),
+            error::UnknownUser
+        );
+
+        match institution {
+            Some(institution) => self.institutions.insert(user, institution),
+            None => self.institutions.remove(&user),
+        };
+
+        Ok(())
+    }
+
+    async fn add_organization_member(
+        &mut self,
+        session: &UserSession,
+        user: UserId,
+        role: OrganizationRole,
+    ) -> Result<()> {
+        let organization = self.admin_membership(session)?.organization;
+
+        ensure!(
+            self.users
+                .values()
+                .any(|u| u.id == user && !u.password_hash.is_empty()),
+            error::UnknownUser
+        );
+        ensure!(
+            self.memberships.iter().all(|m| m.user != user),
+            error::AlreadyOrganizationMember
+        );
+
+        self.memberships.push(OrganizationMembership {
+            organization,
+            user,
+            role,
+        });
+
+        Ok(())
+    }
+
+    async fn remove_organization_member(
+        &mut self,
+        session: &UserSession,
+        user: UserId,
+    ) -> Result<()> {
+        let organization = self.admin_membership(session)?.organization;
+
+        let index = self
+            .memberships
+            .iter()
+            .position(|m| m.organization == organization && m.user == user)
+            .ok_or(error::Error::UnknownUser)?;
+        self.memberships.remove(index);
+
+        Ok(())
+    }
+
+    async fn create_group(
+        &mut self,
+        session: &UserSession,
+        group: Validated<CreateGroup>,
+    ) -> Result<GroupId> {
+        let group = group.user_input;
+
+        ensure!(
+            session.user.email.is_some(),
+            error::InvalidGroup {
+                reason: "Anonymous users can not create groups",
+            }
+        );
+
+        let id = GroupId::new();
+        self.groups.insert(
+            id,
+            Group {
+                id,
+                name: group.name,
+            },
+        );
+        self.group_memberships.push(GroupMembership {
+            group: id,
+            user: session.user.id,
+            role: GroupRole::Admin,
+        });
+
+        Ok(id)
+    }
+
+    async fn groups(&self, session: &UserSession) -> Result<Vec<Group>> {
+        Ok(self
+            .group_memberships
+            .iter()
+            .filter(|m| m.user == session.user.id)
+            .filter_map(|m| self.groups.get(&m.group))
+            .cloned()
+            .collect())
+    }
+
+    async fn group_members(
+        &self,
+        session: &UserSession,
+        group: GroupId,
+    ) -> Result<Vec<GroupMembership>> {
+        ensure!(
+            self.group_memberships
+                .iter()
+                .any(|m| m.group == group && m.user == session.user.id),
+            error::UnknownGroup
+        );
+
+        Ok(self
+            .group_memberships
+            .iter()
+            .filter(|m| m.group == group)
+            .cloned()
+            .collect())
+    }
+
+    async fn add_group_member(
+        &mut self,
+        session: &UserSession,
+        group: GroupId,
+        user: UserId,
+        role: GroupRole,
+    ) -> Result<()> {
+        self.ensure_group_admin(session, group)?;
+
+        ensure!(
+            self.users
+                .values()
+                .any(|u| u.id == user && !u.password_hash.is_empty()),
+            error::UnknownUser
+        );
+        ensure!(
+            self.group_memberships
+                .iter()
+                .all(|m| m.group != group || m.user != user),
+            error::AlreadyGroupMember
+        );
+
+        self.group_memberships
+            .push(GroupMembership { group, user, role });
+
+        Ok(())
+    }
+
+    async fn remove_group_member(
+        &mut self,
+        session: &UserSession,
+        group: GroupId,
+        user: UserId,
+    ) -> Result<()> {
+        self.ensure_group_admin(session, group)?;
+
+        let index = self
+            .group_memberships
+            .iter()
+            .position(|m| m.group == group && m.user == user)
+            .ok_or(error::Error::UnknownUser)?;
+        self.group_memberships.remove(index);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BackupDb<User> for HashMapUserDb {
+    /// Exports all users that can log in, i.e., no anonymous users
+    async fn export(&self) -> Result<Vec<User>> {
+        Ok(self
+            .users
+            .values()
+            .filter(|user| !user.password_hash.is_empty())
+            .cloned()
+            .collect())
+    }
+
+    async fn import(&mut self, user: User) -> Result<()> {
+        ensure!(
+            !self.users.contains_key(&user.email) && self.users.values().all(|u| u.id != user.id),
+            error::Duplicate {
+                reason: format!("User {} already exists", user.email),
+            }
+        );
+
+        self.users.insert(user.email.clone(), user);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BackupDb<Organization> for HashMapUserDb {
+    async fn export(&self) -> Result<Vec<Organization>> {
+        Ok(self.organizations.values().cloned().collect())
+    }
+
+    async fn import(&mut self, organization: Organization) -> Result<()> {
+        ensure!(
+            !self.organizations.contains_key(&organization.id)
+                && self
+                    .organizations
+                    .values()
+                    .all(|o| o.name != organization.name),
+            error::Duplicate {
+                reason: format!("Organization {} already exists", organization.name),
+            }
+        );
+
+        self.organizations.insert(organization.id, organization);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BackupDb<OrganizationMembership> for HashMapUserDb {
+    async fn export(&self) -> Result<Vec<OrganizationMembership>> {
+        Ok(self.memberships.clone())
+    }
+
+    async fn import(&mut self, membership: OrganizationMembership) -> Result<()> {
+        ensure!(
+            self.memberships.iter().all(|m| m.user != membership.user),
+            error::Duplicate {
+                reason: format!(
+                    "User {} is already a member of an organization",
+                    membership.user
+                ),
+            }
+        );
+
+        self.memberships.push(membership);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BackupDb<Group> for HashMapUserDb {
+    async fn export(&self) -> Result<Vec<Group>> {
+        Ok(self.groups.values().cloned().collect())
+    }
+
+    async fn import(&mut self, group: Group) -> Result<()> {
+        ensure!(
+            !self.groups.contains_key(&group.id),
+            error::Duplicate {
+                reason: format!("Group {} already exists", group.id),
+            }
+        );
+
+        self.groups.insert(group.id, group);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl BackupDb<GroupMembership> for HashMapUserDb {
+    async fn export(&self) -> Result<Vec<GroupMembership>> {
+        Ok(self.group_memberships.clone())
+    }
+
+    async fn import(&mut self, membership: GroupMembership) -> Result<()> {
+        ensure!(
+            self.group_memberships
+                .iter()
+                .all(|m| m.group != membership.group || m.user != membership.user),
+            error::Duplicate {
+                reason: format!(
+                    "User {} is already a member of group {}",
+                    membership.user, membership.group
+                ),
+            }
+        );
+        ensure!(
+            self.groups.contains_key(&membership.group),
+            error::UnknownGroup
+        );
+
+        self.group_memberships.push(membership);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -224,4 +805,279 @@ mod tests {
 
         assert!(user_db.session(session.id).await.is_ok());
     }
+
+    #[tokio::test]
+    async fn renew_and_refresh_session() {
+        let mut user_db = HashMapUserDb::default();
+
+        user_db
+            .register(
+                UserRegistration {
+                    email: "foo@bar.de".into(),
+                    password: "secret123".into(),
+                    real_name: "Foo Bar".into(),
+                }
+                .validated()
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let session = user_db
+            .login(UserCredentials {
+                email: "foo@bar.de".into(),
+                password: "secret123".into(),
+            })
+            .await
+            .unwrap();
+
+        let renewed = user_db.renew_session(session.id).await.unwrap();
+        assert_eq!(renewed.id, session.id);
+        assert!(renewed.valid_until >= session.valid_until);
+        assert_eq!(
+            user_db.session(session.id).await.unwrap().valid_until,
+            renewed.valid_until
+        );
+
+        let refreshed = user_db.refresh_session(session.id).await.unwrap();
+        assert_ne!(refreshed.id, session.id);
+        assert_eq!(refreshed.created, session.created);
+        assert!(user_db.session(session.id).await.is_err());
+        assert!(user_db.session(refreshed.id).await.is_ok());
+
+        assert!(user_db.refresh_session(session.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn password_reset() {
+        let mut user_db = HashMapUserDb::default();
+
+        let user_registration = UserRegistration {
+            email: "foo@bar.de".into(),
+            password: "secret123".into(),
+            real_name: "Foo Bar".into(),
+        }
+        .validated()
+        .unwrap();
+
+        user_db.register(user_registration).await.unwrap();
+
+        let session = user_db
+            .login(UserCredentials {
+                email: "foo@bar.de".into(),
+                password: "secret123".into(),
+            })
+            .await
+            .unwrap();
+
+        assert!(user_db
+            .create_password_reset("unknown@bar.de")
+            .await
+            .unwrap()
+            .is_none());
+
+        let (recipient, token) = user_db
+            .create_password_reset("foo@bar.de")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(recipient.email, "foo@bar.de");
+
+        let reset = PasswordReset {
+            token,
+            password: "secret456".into(),
+        };
+
+        user_db
+            .reset_password(reset.clone().validated().unwrap())
+            .await
+            .unwrap();
+
+        // the token can only be used once
+        assert!(user_db
+            .reset_password(reset.validated().unwrap())
+            .await
+            .is_err());
+
+        assert!(user_db.session(session.id).await.is_err());
+
+        assert!(user_db
+            .login(UserCredentials {
+                email: "foo@bar.de".into(),
+                password: "secret456".into(),
+            })
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn user_state() {
+        let mut user_db = HashMapUserDb::default();
+
+        let session = user_db.anonymous().await.unwrap();
+
+        assert_eq!(
+            user_db.user_state(&session).await.unwrap(),
+            UserState::default()
+        );
+
+        let mut preferences = serde_json::Map::new();
+        preferences.insert("showLegend".to_string(), true.into());
+
+        let state = UserState {
+            project: Some(ProjectId::new()),
+            view: None,
+            preferences,
+        };
+
+        user_db
+            .set_user_state(&session, state.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(user_db.user_state(&session).await.unwrap(), state);
+
+        user_db.logout(session.id).await.unwrap();
+
+        assert!(user_db.user_state(&session).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn institution() {
+        let mut user_db = HashMapUserDb::default();
+
+        let user_registration = UserRegistration {
+            email: "foo@bar.de".into(),
+            password: "secret123".into(),
+            real_name: "Foo Bar".into(),
+        }
+        .validated()
+        .unwrap();
+
+        let user = user_db.register(user_registration).await.unwrap();
+
+        let session = user_db
+            .login(UserCredentials {
+                email: "foo@bar.de".into(),
+                password: "secret123".into(),
+            })
+            .await
+            .unwrap();
+
+        assert!(session.user.institution.is_none());
+
+        user_db
+            .set_user_institution(user, Some("University of Marburg".to_string()))
+            .await
+            .unwrap();
+
+        // existing sessions see the institution as well
+        assert_eq!(
+            user_db
+                .session(session.id)
+                .await
+                .unwrap()
+                .user
+                .institution
+                .as_deref(),
+            Some("University of Marburg")
+        );
+
+        user_db.set_user_institution(user, None).await.unwrap();
+
+        assert!(user_db
+            .session(session.id)
+            .await
+            .unwrap()
+            .user
+            .institution
+            .is_none());
+
+        let anonymous = user_db.anonymous().await.unwrap();
+        assert!(user_db
+            .set_user_institution(anonymous.user.id, Some("University of Marburg".to_string()))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn groups() {
+        let mut user_db = HashMapUserDb::default();
+
+        for email in ["admin@bar.de", "member@bar.de"] {
+            user_db
+                .register(
+                    UserRegistration {
+                        email: email.into(),
+                        password: "secret123".into(),
+                        real_name: "Foo Bar".into(),
+                    }
+                    .validated()
+                    .unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let admin = user_db
+            .login(UserCredentials {
+                email: "admin@bar.de".into(),
+                password: "secret123".into(),
+            })
+            .await
+            .unwrap();
+        let member = user_db
+            .login(UserCredentials {
+                email: "member@bar.de".into(),
+                password: "secret123".into(),
+            })
+            .await
+            .unwrap();
+
+        let group = user_db
+            .create_group(
+                &admin,
+                CreateGroup {
+                    name: "Field Team".into(),
+                }
+                .validated()
+                .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        user_db
+            .add_group_member(&admin, group, member.user.id, GroupRole::Member)
+            .await
+            .unwrap();
+        assert!(user_db
+            .add_group_member(&admin, group, member.user.id, GroupRole::Member)
+            .await
+            .is_err());
+
+        // existing sessions get the role of the group
+        let member = user_db.session(member.id).await.unwrap();
+        assert!(member.roles.contains(&group.into()));
+        assert_eq!(user_db.groups(&member).await.unwrap().len(), 1);
+        assert_eq!(
+            user_db.group_members(&member, group).await.unwrap().len(),
+            2
+        );
+
+        // members can not manage the group
+        assert!(user_db
+            .remove_group_member(&member, group, admin.user.id)
+            .await
+            .is_err());
+
+        user_db
+            .remove_group_member(&admin, group, member.user.id)
+            .await
+            .unwrap();
+
+        let member = user_db.session(member.id).await.unwrap();
+        assert!(!member.roles.contains(&group.into()));
+        assert!(user_db.groups(&member).await.unwrap().is_empty());
+        assert!(user_db.group_members(&member, group).await.is_err());
+    }
 }
@@ -1,13 +1,24 @@
+mod group;
 mod hashmap_userdb;
+mod organization;
 #[cfg(feature = "postgres")]
 mod postgres_userdb;
 mod session;
 mod user;
 mod userdb;
 
+pub use group::{CreateGroup, Group, GroupId, GroupMembership, GroupRole};
 pub use hashmap_userdb::HashMapUserDb;
+pub use organization::{
+    CreateOrganization, Organization, OrganizationId, OrganizationMembership, OrganizationRole,
+    Quota,
+};
 #[cfg(feature = "postgres")]
 pub use postgres_userdb::PostgresUserDb;
+pub(crate) use session::session_valid_until;
 pub use session::{UserInfo, UserSession};
-pub use user::{User, UserCredentials, UserId, UserRegistration};
+pub use user::{
+    PasswordReset, PasswordResetToken, User, UserCredentials, UserId, UserRegistration, UserState,
+    PASSWORD_RESET_VALIDITY_MINUTES,
+};
 pub use userdb::UserDb;
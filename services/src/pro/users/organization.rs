@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+
+use crate::error::{self, Error, Result};
+use crate::pro::datasets::RoleId;
+use crate::pro::users::UserId;
+use crate::util::user_input::UserInput;
+use geoengine_datatypes::identifier;
+
+identifier!(OrganizationId);
+
+/// Each organization has a role that is granted to all its members
+impl From<OrganizationId> for RoleId {
+    fn from(organization_id: OrganizationId) -> Self {
+        RoleId(organization_id.0)
+    }
+}
+
+/// A tenant of the instance whose members share datasets and projects
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Organization {
+    pub id: OrganizationId,
+    pub name: String,
+    pub quota: Quota,
+}
+
+/// Limits the number of datasets and projects an organization can create, `None` means unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Quota {
+    pub max_datasets: Option<u32>,
+    pub max_projects: Option<u32>,
+}
+
+impl Quota {
+    /// Fails if another item can not be added to the `used` ones
+    pub fn check(limit: Option<u32>, used: usize, resource: &'static str) -> Result<()> {
+        if let Some(limit) = limit {
+            ensure!(
+                used < limit as usize,
+                error::QuotaExceeded {
+                    resource,
+                    limit: limit as usize,
+                }
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrganization {
+    pub name: String,
+}
+
+impl UserInput for CreateOrganization {
+    fn validate(&self) -> Result<(), Error> {
+        ensure!(
+            !self.name.trim().is_empty(),
+            error::InvalidOrganization {
+                reason: "Name must not be empty"
+            }
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(
+    feature = "postgres",
+    derive(postgres_types::ToSql, postgres_types::FromSql)
+)]
+pub enum OrganizationRole {
+    /// Manages the members of the organization
+    Admin,
+    Member,
+}
+
+/// The membership of a user in an organization, a user belongs to at most one organization
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationMembership {
+    pub organization: OrganizationId,
+    pub user: UserId,
+    pub role: OrganizationRole,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_checks_quotas() {
+        assert!(Quota::check(None, 1000, "datasets").is_ok());
+        assert!(Quota::check(Some(2), 1, "datasets").is_ok());
+        assert!(matches!(
+            Quota::check(Some(2), 2, "datasets"),
+            Err(Error::QuotaExceeded {
+                resource: "datasets",
+                limit: 2
+            })
+        ));
+    }
+}
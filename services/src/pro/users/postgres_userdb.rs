@@ -1,9 +1,13 @@
-use crate::contexts::SessionId;
+use crate::contexts::{BackupDb, SessionId};
 use crate::error::Result;
 use crate::pro::datasets::Role;
+use crate::pro::notifications::{NotificationRecipient, NotificationSettings};
 use crate::pro::projects::ProjectPermission;
 use crate::pro::users::{
-    User, UserCredentials, UserDb, UserId, UserInfo, UserRegistration, UserSession,
+    session_valid_until, CreateGroup, CreateOrganization, Group, GroupId, GroupMembership,
+    GroupRole, Organization, OrganizationId, OrganizationMembership, OrganizationRole,
+    PasswordReset, PasswordResetToken, Quota, User, UserCredentials, UserDb, UserId, UserInfo,
+    UserRegistration, UserSession, UserState, PASSWORD_RESET_VALIDITY_MINUTES,
 };
 use crate::projects::{ProjectId, STRectangle};
 use crate::util::user_input::Validated;
@@ -13,9 +17,11 @@ use async_trait::async_trait;
 use bb8_postgres::PostgresConnectionManager;
 use bb8_postgres::{
     bb8::Pool, tokio_postgres::tls::MakeTlsConnect, tokio_postgres::tls::TlsConnect,
-    tokio_postgres::Socket,
+    tokio_postgres::Client, tokio_postgres::Row, tokio_postgres::Socket,
+    tokio_postgres::Transaction,
 };
 use pwhash::bcrypt;
+use snafu::{ensure, ResultExt};
 use uuid::Uuid;
 
 pub struct PostgresUserDb<Tls>
@@ -57,39 +63,7 @@ where
 
         let user = User::from(user.user_input);
 
-        let stmt = tx
-            .prepare("INSERT INTO roles (id, name) VALUES ($1, $2);")
-            .await?;
-        tx.execute(&stmt, &[&user.id, &user.email]).await?;
-
-        let stmt = tx
-            .prepare(
-                "INSERT INTO users (id, email, password_hash, real_name, active) VALUES ($1, $2, $3, $4, $5);",
-            )
-            .await?;
-
-        tx.execute(
-            &stmt,
-            &[
-                &user.id,
-                &user.email,
-                &user.password_hash,
-                &user.real_name,
-                &user.active,
-            ],
-        )
-        .await?;
-
-        let stmt = tx
-            .prepare("INSERT INTO user_roles (user_id, role_id) VALUES ($1, $2);")
-            .await?;
-        tx.execute(&stmt, &[&user.id, &user.id]).await?;
-
-        let stmt = tx
-            .prepare("INSERT INTO user_roles (user_id, role_id) VALUES ($1, $2);")
-            .await?;
-        tx.execute(&stmt, &[&user.id, &Role::user_role_id()])
-            .await?;
+        insert_user(&tx, &user).await?;
 
         tx.commit().await?;
 
@@ -126,26 +100,19 @@ where
             .await?;
 
         let session_id = SessionId::new();
+        let created = chrono::Utc::now();
+        let valid_until = session_valid_until(created)?;
         let stmt = tx
             .prepare(
                 "
                 INSERT INTO sessions (id, user_id, created, valid_until)
-                VALUES ($1, $2, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP + make_interval(secs:=$3)) 
+                VALUES ($1, $2, $3, $4)
                 RETURNING created, valid_until;",
             )
             .await?;
 
-        // TODO: load from config
-        let session_duration = chrono::Duration::days(30);
         let row = tx
-            .query_one(
-                &stmt,
-                &[
-                    &session_id,
-                    &user_id,
-                    &(session_duration.num_seconds() as f64),
-                ],
-            )
+            .query_one(&stmt, &[&session_id, &user_id, &created, &valid_until])
             .await?;
 
         tx.commit().await?;
@@ -156,19 +123,24 @@ where
                 id: user_id,
                 email: None,
                 real_name: None,
+                institution: None,
             },
             created: row.get(0),
             valid_until: row.get(1),
             project: None,
             view: None,
             roles: vec![user_id.into(), Role::anonymous_role_id()],
+            organization: None,
+            client_ip: None,
         })
     }
 
     async fn login(&mut self, user_credentials: UserCredentials) -> Result<UserSession> {
         let conn = self.conn_pool.get().await?;
         let stmt = conn
-            .prepare("SELECT id, password_hash, email, real_name FROM users WHERE email = $1;")
+            .prepare(
+                "SELECT id, password_hash, email, real_name, institution FROM users WHERE email = $1;",
+            )
             .await?;
 
         let row = conn
@@ -180,29 +152,23 @@ where
         let password_hash = row.get(1);
         let email = row.get(2);
         let real_name = row.get(3);
+        let institution = row.get(4);
 
         if bcrypt::verify(user_credentials.password, password_hash) {
             let session_id = SessionId::new();
+            let created = chrono::Utc::now();
+            let valid_until = session_valid_until(created)?;
             let stmt = conn
                 .prepare(
                     "
                 INSERT INTO sessions (id, user_id, created, valid_until)
-                VALUES ($1, $2, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP + make_interval(secs:=$3)) 
+                VALUES ($1, $2, $3, $4)
                 RETURNING created, valid_until;",
                 )
                 .await?;
 
-            // TODO: load from config
-            let session_duration = chrono::Duration::days(30);
             let row = conn
-                .query_one(
-                    &stmt,
-                    &[
-                        &session_id,
-                        &user_id,
-                        &(session_duration.num_seconds() as f64),
-                    ],
-                )
+                .query_one(&stmt, &[&session_id, &user_id, &created, &valid_until])
                 .await?;
 
             let stmt = conn
@@ -216,18 +182,23 @@ where
 
             let roles = rows.into_iter().map(|row| row.get(0)).collect();
 
+            let organization = user_organization(&conn, user_id).await?;
+
             Ok(UserSession {
                 id: session_id,
                 user: UserInfo {
                     id: user_id,
                     email,
                     real_name,
+                    institution,
                 },
                 created: row.get(0),
                 valid_until: row.get(1),
                 project: None,
                 view: None,
                 roles,
+                organization,
+                client_ip: None,
             })
         } else {
             Err(error::Error::LoginFailed)
@@ -246,6 +217,58 @@ where
         Ok(())
     }
 
+    async fn refresh_session(&mut self, session: SessionId) -> Result<UserSession> {
+        let mut session = self.session(session).await?;
+        let old_session_id = session.id;
+
+        session.id = SessionId::new();
+        session.valid_until = session_valid_until(session.created)?;
+
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        let stmt = tx
+            .prepare(
+                "
+                INSERT INTO sessions (id, user_id, created, valid_until, project_id, view)
+                SELECT $1, user_id, created, $2, project_id, view
+                FROM sessions
+                WHERE id = $3
+                RETURNING valid_until;",
+            )
+            .await?;
+        let row = tx
+            .query_one(&stmt, &[&session.id, &session.valid_until, &old_session_id])
+            .await
+            .map_err(|_error| error::Error::InvalidSession)?;
+        session.valid_until = row.get(0);
+
+        let stmt = tx.prepare("DELETE FROM sessions WHERE id = $1;").await?;
+        tx.execute(&stmt, &[&old_session_id]).await?;
+
+        tx.commit().await?;
+
+        Ok(session)
+    }
+
+    async fn renew_session(&mut self, session: SessionId) -> Result<UserSession> {
+        let mut session = self.session(session).await?;
+
+        session.valid_until = session_valid_until(session.created)?;
+
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare("UPDATE sessions SET valid_until = $1 WHERE id = $2 RETURNING valid_until;")
+            .await?;
+        let row = conn
+            .query_one(&stmt, &[&session.valid_until, &session.id])
+            .await
+            .map_err(|_error| error::Error::InvalidSession)?;
+        session.valid_until = row.get(0);
+
+        Ok(session)
+    }
+
     async fn session(&self, session: SessionId) -> Result<UserSession> {
         let conn = self.conn_pool.get().await?;
         let stmt = conn
@@ -258,7 +281,8 @@ where
                 s.created, 
                 s.valid_until, 
                 s.project_id,
-                s.view           
+                s.view,
+                u.institution
             FROM sessions s JOIN users u ON (s.user_id = u.id)
             WHERE s.id = $1 AND CURRENT_TIMESTAMP < s.valid_until;",
             )
@@ -269,18 +293,33 @@ where
             .await
             .map_err(|_error| error::Error::InvalidSession)?;
 
+        let user_id = UserId(row.get(0));
+
+        let stmt = conn
+            .prepare("SELECT role_id FROM user_roles WHERE user_id = $1;")
+            .await?;
+        let roles = conn
+            .query(&stmt, &[&user_id])
+            .await?
+            .into_iter()
+            .map(|row| row.get(0))
+            .collect();
+
         Ok(UserSession {
             id: session,
             user: UserInfo {
-                id: row.get(0),
+                id: user_id,
                 email: row.get(1),
                 real_name: row.get(2),
+                institution: row.get(7),
             },
             created: row.get(3),
             valid_until: row.get(4),
             project: row.get::<usize, Option<Uuid>>(5).map(ProjectId),
             view: row.get(6),
-            roles: vec![], // TODO
+            roles,
+            organization: user_organization(&conn, user_id).await?,
+            client_ip: None,
         })
     }
 
@@ -322,4 +361,985 @@ where
 
         Ok(())
     }
+
+    async fn user_state(&self, session: &UserSession) -> Result<UserState> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare("SELECT state FROM user_states WHERE user_id = $1;")
+            .await?;
+
+        match conn.query_opt(&stmt, &[&session.user.id]).await? {
+            Some(row) => Ok(serde_json::from_value(row.get(0)).context(error::SerdeJson)?),
+            None => Ok(UserState::default()),
+        }
+    }
+
+    async fn set_user_state(&mut self, session: &UserSession, state: UserState) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare(
+                "
+                INSERT INTO user_states (user_id, state) VALUES ($1, $2)
+                ON CONFLICT (user_id) DO UPDATE SET state = EXCLUDED.state;",
+            )
+            .await?;
+
+        conn.execute(
+            &stmt,
+            &[
+                &session.user.id,
+                &serde_json::to_value(&state).context(error::SerdeJson)?,
+            ],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn notification_settings(&self, session: &UserSession) -> Result<NotificationSettings> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare("SELECT task_status, shares FROM notification_settings WHERE user_id = $1;")
+            .await?;
+
+        Ok(conn
+            .query_opt(&stmt, &[&session.user.id])
+            .await?
+            .map_or_else(NotificationSettings::default, |row| NotificationSettings {
+                task_status: row.get(0),
+                shares: row.get(1),
+            }))
+    }
+
+    async fn set_notification_settings(
+        &mut self,
+        session: &UserSession,
+        settings: NotificationSettings,
+    ) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare(
+                "
+                INSERT INTO notification_settings (user_id, task_status, shares) VALUES ($1, $2, $3)
+                ON CONFLICT (user_id) DO UPDATE
+                SET task_status = EXCLUDED.task_status, shares = EXCLUDED.shares;",
+            )
+            .await?;
+
+        conn.execute(
+            &stmt,
+            &[&session.user.id, &settings.task_status, &settings.shares],
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn notification_recipient(&self, user: UserId) -> Result<Option<NotificationRecipient>> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare(
+                "
+                SELECT u.email, u.real_name, n.task_status, n.shares
+                FROM users u LEFT JOIN notification_settings n ON (u.id = n.user_id)
+                WHERE u.id = $1 AND u.email IS NOT NULL;",
+            )
+            .await?;
+
+        Ok(conn.query_opt(&stmt, &[&user]).await?.map(|row| {
+            let default = NotificationSettings::default();
+
+            NotificationRecipient {
+                email: row.get(0),
+                real_name: row.get(1),
+                settings: NotificationSettings {
+                    task_status: row
+                        .get::<usize, Option<bool>>(2)
+                        .unwrap_or(default.task_status),
+                    shares: row.get::<usize, Option<bool>>(3).unwrap_or(default.shares),
+                },
+            }
+        }))
+    }
+
+    async fn create_password_reset(
+        &mut self,
+        email: &str,
+    ) -> Result<Option<(NotificationRecipient, PasswordResetToken)>> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare("SELECT id FROM users WHERE email = $1 AND id <> $2;")
+            .await?;
+
+        let user = match conn
+            .query_opt(&stmt, &[&email, &Role::system_role_id()])
+            .await?
+        {
+            Some(row) => UserId(row.get(0)),
+            None => return Ok(None),
+        };
+
+        let token = PasswordResetToken::new();
+        let stmt = conn
+            .prepare(
+                "
+                INSERT INTO password_resets (token, user_id, valid_until)
+                VALUES ($1, $2, CURRENT_TIMESTAMP + make_interval(mins:=$3));",
+            )
+            .await?;
+        conn.execute(
+            &stmt,
+            &[&token, &user, &(PASSWORD_RESET_VALIDITY_MINUTES as i32)],
+        )
+        .await?;
+
+        Ok(self
+            .notification_recipient(user)
+            .await?
+            .map(|recipient| (recipient, token)))
+    }
+
+    async fn reset_password(&mut self, reset: Validated<PasswordReset>) -> Result<()> {
+        let reset = reset.user_input;
+
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        let stmt = tx
+            .prepare(
+                "
+                DELETE FROM password_resets
+                WHERE token = $1 AND CURRENT_TIMESTAMP < valid_until
+                RETURNING user_id;",
+            )
+            .await?;
+        let user: UserId = tx
+            .query_opt(&stmt, &[&reset.token])
+            .await?
+            .ok_or(error::Error::InvalidPasswordResetToken)?
+            .get(0);
+
+        let password_hash =
+            bcrypt::hash(&reset.password).map_err(|_| error::Error::InvalidPassword {
+                reason: "The password cannot be hashed".to_string(),
+            })?;
+
+        let stmt = tx
+            .prepare("UPDATE users SET password_hash = $1 WHERE id = $2;")
+            .await?;
+        tx.execute(&stmt, &[&password_hash, &user]).await?;
+
+        let stmt = tx
+            .prepare("DELETE FROM sessions WHERE user_id = $1;")
+            .await?;
+        tx.execute(&stmt, &[&user]).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn create_organization(
+        &mut self,
+        session: &UserSession,
+        organization: Validated<CreateOrganization>,
+        quota: Quota,
+    ) -> Result<OrganizationId> {
+        let organization = organization.user_input;
+
+        ensure!(
+            session.user.email.is_some(),
+            error::InvalidOrganization {
+                reason: "Anonymous users can not create organizations",
+            }
+        );
+
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        let stmt = tx
+            .prepare("SELECT EXISTS(SELECT 1 FROM organization_members WHERE user_id = $1);")
+            .await?;
+        let is_member: bool = tx.query_one(&stmt, &[&session.user.id]).await?.get(0);
+        ensure!(!is_member, error::AlreadyOrganizationMember);
+
+        let stmt = tx
+            .prepare("SELECT EXISTS(SELECT 1 FROM organizations WHERE name = $1);")
+            .await?;
+        let exists: bool = tx.query_one(&stmt, &[&organization.name]).await?.get(0);
+        ensure!(
+            !exists,
+            error::Duplicate {
+                reason: "Organization name already exists"
+            }
+        );
+
+        let id = OrganizationId::new();
+
+        insert_organization(
+            &tx,
+            &Organization {
+                id,
+                name: organization.name,
+                quota,
+            },
+        )
+        .await?;
+
+        insert_membership(
+            &tx,
+            &OrganizationMembership {
+                organization: id,
+                user: session.user.id,
+                role: OrganizationRole::Admin,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    async fn organization_members(
+        &self,
+        session: &UserSession,
+    ) -> Result<Vec<OrganizationMembership>> {
+        let organization = session
+            .organization
+            .as_ref()
+            .ok_or(error::Error::NoOrganization)?;
+
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "SELECT organization_id, user_id, role FROM organization_members WHERE organization_id = $1;",
+            )
+            .await?;
+
+        let rows = conn.query(&stmt, &[&organization.id]).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| OrganizationMembership {
+                organization: row.get(0),
+                user: row.get(1),
+                role: row.get(2),
+            })
+            .collect())
+    }
+
+    async fn set_user_institution(
+        &mut self,
+        user: UserId,
+        institution: Option<String>,
+    ) -> Result<()> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare("UPDATE users SET institution = $2 WHERE id = $1 AND email IS NOT NULL;")
+            .await?;
+
+        let updated = conn.execute(&stmt, &[&user, &institution]).await?;
+        ensure!(updated > 0, error::UnknownUser);
+
+        Ok(())
+    }
+
+    async fn add_organization_member(
+        &mut self,
+        session: &UserSession,
+        user: UserId,
+        role: OrganizationRole,
+    ) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        let organization = admin_organization(&tx, session.user.id).await?;
+
+        let stmt = tx
+            .prepare("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 AND email IS NOT NULL);")
+            .await?;
+        let registered: bool = tx.query_one(&stmt, &[&user]).await?.get(0);
+        ensure!(registered, error::UnknownUser);
+
+        let stmt = tx
+            .prepare("SELECT EXISTS(SELECT 1 FROM organization_members WHERE user_id = $1);")
+            .await?;
+        let is_member: bool = tx.query_one(&stmt, &[&user]).await?.get(0);
+        ensure!(!is_member, error::AlreadyOrganizationMember);
+
+        insert_membership(
+            &tx,
+            &OrganizationMembership {
+                organization,
+                user,
+                role,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn remove_organization_member(
+        &mut self,
+        session: &UserSession,
+        user: UserId,
+    ) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        let organization = admin_organization(&tx, session.user.id).await?;
+
+        let stmt = tx
+            .prepare(
+                "DELETE FROM organization_members WHERE organization_id = $1 AND user_id = $2;",
+            )
+            .await?;
+        let removed = tx.execute(&stmt, &[&organization, &user]).await?;
+        ensure!(removed > 0, error::UnknownUser);
+
+        let stmt = tx
+            .prepare("DELETE FROM user_roles WHERE user_id = $1 AND role_id = $2;")
+            .await?;
+        tx.execute(&stmt, &[&user, &organization]).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn create_group(
+        &mut self,
+        session: &UserSession,
+        group: Validated<CreateGroup>,
+    ) -> Result<GroupId> {
+        let group = group.user_input;
+
+        ensure!(
+            session.user.email.is_some(),
+            error::InvalidGroup {
+                reason: "Anonymous users can not create groups",
+            }
+        );
+
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        let id = GroupId::new();
+
+        insert_group(
+            &tx,
+            &Group {
+                id,
+                name: group.name,
+            },
+        )
+        .await?;
+
+        insert_group_membership(
+            &tx,
+            &GroupMembership {
+                group: id,
+                user: session.user.id,
+                role: GroupRole::Admin,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(id)
+    }
+
+    async fn groups(&self, session: &UserSession) -> Result<Vec<Group>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "
+                SELECT g.id, g.name
+                FROM group_members m JOIN groups g ON (m.group_id = g.id)
+                WHERE m.user_id = $1
+                ORDER BY g.name;",
+            )
+            .await?;
+
+        let rows = conn.query(&stmt, &[&session.user.id]).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Group {
+                id: row.get(0),
+                name: row.get(1),
+            })
+            .collect())
+    }
+
+    async fn group_members(
+        &self,
+        session: &UserSession,
+        group: GroupId,
+    ) -> Result<Vec<GroupMembership>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "SELECT EXISTS(SELECT 1 FROM group_members WHERE group_id = $1 AND user_id = $2);",
+            )
+            .await?;
+        let is_member: bool = conn
+            .query_one(&stmt, &[&group, &session.user.id])
+            .await?
+            .get(0);
+        ensure!(is_member, error::UnknownGroup);
+
+        let stmt = conn
+            .prepare("SELECT group_id, user_id, role FROM group_members WHERE group_id = $1;")
+            .await?;
+
+        let rows = conn.query(&stmt, &[&group]).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| GroupMembership {
+                group: row.get(0),
+                user: row.get(1),
+                role: row.get(2),
+            })
+            .collect())
+    }
+
+    async fn add_group_member(
+        &mut self,
+        session: &UserSession,
+        group: GroupId,
+        user: UserId,
+        role: GroupRole,
+    ) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        ensure_group_admin(&tx, group, session.user.id).await?;
+
+        let stmt = tx
+            .prepare("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 AND email IS NOT NULL);")
+            .await?;
+        let registered: bool = tx.query_one(&stmt, &[&user]).await?.get(0);
+        ensure!(registered, error::UnknownUser);
+
+        let stmt = tx
+            .prepare(
+                "SELECT EXISTS(SELECT 1 FROM group_members WHERE group_id = $1 AND user_id = $2);",
+            )
+            .await?;
+        let is_member: bool = tx.query_one(&stmt, &[&group, &user]).await?.get(0);
+        ensure!(!is_member, error::AlreadyGroupMember);
+
+        insert_group_membership(&tx, &GroupMembership { group, user, role }).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn remove_group_member(
+        &mut self,
+        session: &UserSession,
+        group: GroupId,
+        user: UserId,
+    ) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+        let tx = conn.build_transaction().start().await?;
+
+        ensure_group_admin(&tx, group, session.user.id).await?;
+
+        let stmt = tx
+            .prepare("DELETE FROM group_members WHERE group_id = $1 AND user_id = $2;")
+            .await?;
+        let removed = tx.execute(&stmt, &[&group, &user]).await?;
+        ensure!(removed > 0, error::UnknownUser);
+
+        let stmt = tx
+            .prepare("DELETE FROM user_roles WHERE user_id = $1 AND role_id = $2;")
+            .await?;
+        tx.execute(&stmt, &[&user, &group]).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+/// Loads the organization the user is a member of
+async fn user_organization(conn: &Client, user: UserId) -> Result<Option<Organization>> {
+    let stmt = conn
+        .prepare(
+            "
+            SELECT o.id, o.name, o.max_datasets, o.max_projects
+            FROM organization_members m JOIN organizations o ON (m.organization_id = o.id)
+            WHERE m.user_id = $1;",
+        )
+        .await?;
+
+    let row = conn.query_opt(&stmt, &[&user]).await?;
+
+    Ok(row.as_ref().map(organization_from_row))
+}
+
+/// Reads an organization from a row of `id, name, max_datasets, max_projects`
+fn organization_from_row(row: &Row) -> Organization {
+    Organization {
+        id: row.get(0),
+        name: row.get(1),
+        quota: Quota {
+            max_datasets: row
+                .get::<usize, Option<i64>>(2)
+                .map(|max| u32::try_from(max).unwrap_or(u32::MAX)),
+            max_projects: row
+                .get::<usize, Option<i64>>(3)
+                .map(|max| u32::try_from(max).unwrap_or(u32::MAX)),
+        },
+    }
+}
+
+/// The organization of the user if it is an admin of it
+async fn admin_organization(tx: &Transaction<'_>, user: UserId) -> Result<OrganizationId> {
+    let stmt = tx
+        .prepare("SELECT organization_id, role FROM organization_members WHERE user_id = $1;")
+        .await?;
+
+    let row = tx
+        .query_opt(&stmt, &[&user])
+        .await?
+        .ok_or(error::Error::NoOrganization)?;
+
+    ensure!(
+        row.get::<usize, OrganizationRole>(1) == OrganizationRole::Admin,
+        error::OrganizationPermissionDenied
+    );
+
+    Ok(row.get(0))
+}
+
+/// Adds the user to the organization and grants it the organization's role
+/// Inserts an organization together with its role
+async fn insert_organization(tx: &Transaction<'_>, organization: &Organization) -> Result<()> {
+    let stmt = tx
+        .prepare("INSERT INTO roles (id, name) VALUES ($1, $2);")
+        .await?;
+    tx.execute(&stmt, &[&organization.id, &organization.name])
+        .await?;
+
+    let stmt = tx
+        .prepare(
+            "INSERT INTO organizations (id, name, max_datasets, max_projects) VALUES ($1, $2, $3, $4);",
+        )
+        .await?;
+    tx.execute(
+        &stmt,
+        &[
+            &organization.id,
+            &organization.name,
+            &organization.quota.max_datasets.map(i64::from),
+            &organization.quota.max_projects.map(i64::from),
+        ],
+    )
+    .await?;
+
+    Ok(())
+}
+
+async fn insert_membership(
+    tx: &Transaction<'_>,
+    membership: &OrganizationMembership,
+) -> Result<()> {
+    let stmt = tx
+        .prepare(
+            "INSERT INTO organization_members (organization_id, user_id, role) VALUES ($1, $2, $3);",
+        )
+        .await?;
+    tx.execute(
+        &stmt,
+        &[&membership.organization, &membership.user, &membership.role],
+    )
+    .await?;
+
+    let stmt = tx
+        .prepare("INSERT INTO user_roles (user_id, role_id) VALUES ($1, $2);")
+        .await?;
+    tx.execute(&stmt, &[&membership.user, &membership.organization])
+        .await?;
+
+    Ok(())
+}
+/// Fails if the user is not an admin of the group
+async fn ensure_group_admin(tx: &Transaction<'_>, group: GroupId, user: UserId) -> Result<()> {
+    let stmt = tx
+        .prepare("SELECT role FROM group_members WHERE group_id = $1 AND user_id = $2;")
+        .await?;
+
+    let row = tx
+        .query_opt(&stmt, &[&group, &user])
+        .await?
+        .ok_or(error::Error::UnknownGroup)?;
+
+    ensure!(
+        row.get::<usize, GroupRole>(0) == GroupRole::Admin,
+        error::GroupPermissionDenied
+    );
+
+    Ok(())
+}
+
+/// Adds the user to the group and grants it the group's role
+/// Inserts a group together with its role
+async fn insert_group(tx: &Transaction<'_>, group: &Group) -> Result<()> {
+    let stmt = tx
+        .prepare("INSERT INTO roles (id, name) VALUES ($1, $2);")
+        .await?;
+    tx.execute(&stmt, &[&group.id, &group.name]).await?;
+
+    let stmt = tx
+        .prepare("INSERT INTO groups (id, name) VALUES ($1, $2);")
+        .await?;
+    tx.execute(&stmt, &[&group.id, &group.name]).await?;
+
+    Ok(())
+}
+
+async fn insert_group_membership(tx: &Transaction<'_>, membership: &GroupMembership) -> Result<()> {
+    let stmt = tx
+        .prepare("INSERT INTO group_members (group_id, user_id, role) VALUES ($1, $2, $3);")
+        .await?;
+    tx.execute(
+        &stmt,
+        &[&membership.group, &membership.user, &membership.role],
+    )
+    .await?;
+
+    let stmt = tx
+        .prepare("INSERT INTO user_roles (user_id, role_id) VALUES ($1, $2);")
+        .await?;
+    tx.execute(&stmt, &[&membership.user, &membership.group])
+        .await?;
+
+    Ok(())
+}
+
+/// Inserts a registered user together with its roles
+async fn insert_user(tx: &Transaction<'_>, user: &User) -> Result<()> {
+    let stmt = tx
+        .prepare("INSERT INTO roles (id, name) VALUES ($1, $2);")
+        .await?;
+    tx.execute(&stmt, &[&user.id, &user.email]).await?;
+
+    let stmt = tx
+        .prepare(
+            "INSERT INTO users (id, email, password_hash, real_name, active) VALUES ($1, $2, $3, $4, $5);",
+        )
+        .await?;
+
+    tx.execute(
+        &stmt,
+        &[
+            &user.id,
+            &user.email,
+            &user.password_hash,
+            &user.real_name,
+            &user.active,
+        ],
+    )
+    .await?;
+
+    let stmt = tx
+        .prepare("INSERT INTO user_roles (user_id, role_id) VALUES ($1, $2);")
+        .await?;
+    tx.execute(&stmt, &[&user.id, &user.id]).await?;
+
+    let stmt = tx
+        .prepare("INSERT INTO user_roles (user_id, role_id) VALUES ($1, $2);")
+        .await?;
+    tx.execute(&stmt, &[&user.id, &Role::user_role_id()])
+        .await?;
+
+    Ok(())
+}
+#[async_trait]
+impl<Tls> BackupDb<User> for PostgresUserDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    /// Exports all users that can log in, i.e., neither anonymous users nor the system user
+    async fn export(&self) -> Result<Vec<User>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare(
+                "
+                SELECT id, email, password_hash, real_name, active
+                FROM users
+                WHERE email IS NOT NULL AND id <> $1;",
+            )
+            .await?;
+
+        let rows = conn.query(&stmt, &[&Role::system_role_id()]).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| User {
+                id: UserId(row.get(0)),
+                email: row.get(1),
+                password_hash: row.get(2),
+                real_name: row.get(3),
+                active: row.get(4),
+            })
+            .collect())
+    }
+
+    async fn import(&mut self, user: User) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let stmt = tx
+            .prepare("SELECT EXISTS(SELECT 1 FROM users WHERE id = $1 OR email = $2);")
+            .await?;
+        let exists: bool = tx.query_one(&stmt, &[&user.id, &user.email]).await?.get(0);
+
+        ensure!(
+            !exists,
+            error::Duplicate {
+                reason: format!("User {} already exists", user.email),
+            }
+        );
+
+        insert_user(&tx, &user).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Tls> BackupDb<Organization> for PostgresUserDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn export(&self) -> Result<Vec<Organization>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT id, name, max_datasets, max_projects FROM organizations;")
+            .await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        Ok(rows.iter().map(organization_from_row).collect())
+    }
+
+    async fn import(&mut self, organization: Organization) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let stmt = tx
+            .prepare(
+                "
+                SELECT EXISTS(SELECT 1 FROM roles WHERE id = $1)
+                    OR EXISTS(SELECT 1 FROM organizations WHERE name = $2);",
+            )
+            .await?;
+        let exists: bool = tx
+            .query_one(&stmt, &[&organization.id, &organization.name])
+            .await?
+            .get(0);
+
+        ensure!(
+            !exists,
+            error::Duplicate {
+                reason: format!("Organization {} already exists", organization.name),
+            }
+        );
+
+        insert_organization(&tx, &organization).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Tls> BackupDb<OrganizationMembership> for PostgresUserDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn export(&self) -> Result<Vec<OrganizationMembership>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT organization_id, user_id, role FROM organization_members;")
+            .await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| OrganizationMembership {
+                organization: row.get(0),
+                user: row.get(1),
+                role: row.get(2),
+            })
+            .collect())
+    }
+
+    async fn import(&mut self, membership: OrganizationMembership) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let stmt = tx
+            .prepare("SELECT EXISTS(SELECT 1 FROM organization_members WHERE user_id = $1);")
+            .await?;
+        let exists: bool = tx.query_one(&stmt, &[&membership.user]).await?.get(0);
+
+        ensure!(
+            !exists,
+            error::Duplicate {
+                reason: format!(
+                    "User {} is already a member of an organization",
+                    membership.user
+                ),
+            }
+        );
+
+        insert_membership(&tx, &membership).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Tls> BackupDb<Group> for PostgresUserDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn export(&self) -> Result<Vec<Group>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn.prepare("SELECT id, name FROM groups;").await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Group {
+                id: row.get(0),
+                name: row.get(1),
+            })
+            .collect())
+    }
+
+    async fn import(&mut self, group: Group) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let stmt = tx
+            .prepare("SELECT EXISTS(SELECT 1 FROM roles WHERE id = $1);")
+            .await?;
+        let exists: bool = tx.query_one(&stmt, &[&group.id]).await?.get(0);
+
+        ensure!(
+            !exists,
+            error::Duplicate {
+                reason: format!("Group {} already exists", group.id),
+            }
+        );
+
+        insert_group(&tx, &group).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Tls> BackupDb<GroupMembership> for PostgresUserDb<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn export(&self) -> Result<Vec<GroupMembership>> {
+        let conn = self.conn_pool.get().await?;
+
+        let stmt = conn
+            .prepare("SELECT group_id, user_id, role FROM group_members;")
+            .await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| GroupMembership {
+                group: row.get(0),
+                user: row.get(1),
+                role: row.get(2),
+            })
+            .collect())
+    }
+
+    async fn import(&mut self, membership: GroupMembership) -> Result<()> {
+        let mut conn = self.conn_pool.get().await?;
+
+        let tx = conn.build_transaction().start().await?;
+
+        let stmt = tx
+            .prepare(
+                "SELECT EXISTS(SELECT 1 FROM group_members WHERE group_id = $1 AND user_id = $2);",
+            )
+            .await?;
+        let exists: bool = tx
+            .query_one(&stmt, &[&membership.group, &membership.user])
+            .await?
+            .get(0);
+
+        ensure!(
+            !exists,
+            error::Duplicate {
+                reason: format!(
+                    "User {} is already a member of group {}",
+                    membership.user, membership.group
+                ),
+            }
+        );
+
+        insert_group_membership(&tx, &membership).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
 }
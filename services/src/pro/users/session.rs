@@ -5,8 +5,9 @@ use crate::error;
 use crate::handlers::get_token;
 use crate::pro::contexts::{PostgresContext, ProInMemoryContext};
 use crate::pro::datasets::{Role, RoleId};
-use crate::pro::users::UserId;
+use crate::pro::users::{Organization, UserId};
 use crate::projects::{ProjectId, STRectangle};
+use crate::util::config;
 use crate::util::Identifier;
 use actix_http::Payload;
 use actix_web::{web, FromRequest, HttpRequest};
@@ -15,6 +16,7 @@ use chrono::{DateTime, Utc};
 use futures::future::err;
 use futures_util::future::LocalBoxFuture;
 use futures_util::FutureExt;
+use std::net::IpAddr;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -22,6 +24,8 @@ pub struct UserInfo {
     pub id: UserId,
     pub email: Option<String>,
     pub real_name: Option<String>,
+    /// the institution the user is part of, which is set by the admin
+    pub institution: Option<String>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -34,6 +38,10 @@ pub struct UserSession {
     pub project: Option<ProjectId>,
     pub view: Option<STRectangle>,
     pub roles: Vec<RoleId>, // a user has a default role (= its user id) and other additonal roles
+    pub organization: Option<Organization>,
+    /// the address of the client of the current request, which is not stored with the session
+    #[serde(skip)]
+    pub client_ip: Option<IpAddr>,
 }
 
 impl UserSession {
@@ -46,16 +54,91 @@ impl UserSession {
                 id: user_id,
                 email: None,
                 real_name: None,
+                institution: None,
             },
             created: chrono::Utc::now(),
             valid_until: chrono::Utc::now(),
             project: None,
             view: None,
             roles: vec![role],
+            organization: None,
+            client_ip: None,
         }
     }
 }
 
+impl UserSession {
+    /// Creates a read-only guest session of the built-in guest user that expires after the configured duration.
+    ///
+    /// Guest sessions are only kept in memory, cf. [`crate::contexts::GuestSessions`].
+    pub fn guest() -> error::Result<UserSession> {
+        let config = config::get_config_element::<config::Session>()?;
+        let created = chrono::Utc::now();
+        let user_id = UserId::guest_user_id();
+
+        Ok(Self {
+            id: SessionId::new(),
+            user: UserInfo {
+                id: user_id,
+                email: None,
+                real_name: None,
+                institution: None,
+            },
+            created,
+            valid_until: created
+                + chrono::Duration::minutes(config.guest_session_duration_minutes.into()),
+            project: None,
+            view: None,
+            roles: vec![
+                user_id.into(),
+                Role::anonymous_role_id(),
+                Role::guest_role_id(),
+            ],
+            organization: None,
+            client_ip: None,
+        })
+    }
+}
+
+impl UserSession {
+    /// Whether the session should be renewed when it is used because of sliding expiration,
+    /// which is the case once half of its duration has passed
+    pub(crate) fn needs_renewal(&self) -> error::Result<bool> {
+        let config = config::get_config_element::<crate::pro::util::config::User>()?;
+
+        if !config.sliding_expiration || self.is_guest() {
+            return Ok(false);
+        }
+
+        let half_duration = chrono::Duration::minutes(config.session_duration_minutes.into()) / 2;
+
+        Ok(self.valid_until - chrono::Utc::now() < half_duration
+            && self.valid_until < max_valid_until(&config, self.created))
+    }
+}
+
+/// The expiration of a session that is created or renewed now.
+/// It does not exceed the maximum lifetime of a session that was `created` before.
+pub(crate) fn session_valid_until(created: DateTime<Utc>) -> error::Result<DateTime<Utc>> {
+    let config = config::get_config_element::<crate::pro::util::config::User>()?;
+
+    let valid_until =
+        chrono::Utc::now() + chrono::Duration::minutes(config.session_duration_minutes.into());
+
+    Ok(valid_until.min(max_valid_until(&config, created)))
+}
+
+fn max_valid_until(
+    config: &crate::pro::util::config::User,
+    created: DateTime<Utc>,
+) -> DateTime<Utc> {
+    config
+        .max_session_lifetime_minutes
+        .map_or(chrono::MAX_DATETIME, |lifetime| {
+            created + chrono::Duration::minutes(lifetime.into())
+        })
+}
+
 impl MockableSession for UserSession {
     fn mock() -> Self {
         let user_id = UserId::new();
@@ -65,12 +148,15 @@ impl MockableSession for UserSession {
                 id: user_id,
                 email: None,
                 real_name: None,
+                institution: None,
             },
             created: chrono::Utc::now(),
             valid_until: chrono::Utc::now(),
             project: None,
             view: None,
             roles: vec![user_id.into(), Role::user_role_id()],
+            organization: None,
+            client_ip: None,
         }
     }
 }
@@ -95,6 +181,10 @@ impl Session for UserSession {
     fn view(&self) -> Option<&STRectangle> {
         self.view.as_ref()
     }
+
+    fn is_guest(&self) -> bool {
+        self.roles.contains(&Role::guest_role_id())
+    }
 }
 
 impl FromRequest for UserSession {
@@ -107,18 +197,38 @@ impl FromRequest for UserSession {
             Err(error) => return Box::pin(err(error)),
         };
 
+        // forwarding headers are ignored because they can be forged by clients
+        let client_ip = req.peer_addr().map(|addr| addr.ip());
+        let with_client_ip = move |mut session: UserSession| {
+            session.client_ip = client_ip;
+            session
+        };
+
         #[cfg(feature = "postgres")]
         {
             if let Some(pg_ctx) = req.app_data::<web::Data<PostgresContext<NoTls>>>() {
                 let pg_ctx = pg_ctx.get_ref().clone();
-                return async move { pg_ctx.session_by_id(token).await.map_err(Into::into) }
-                    .boxed_local();
+                return async move {
+                    pg_ctx
+                        .session_by_id(token)
+                        .await
+                        .map(with_client_ip)
+                        .map_err(Into::into)
+                }
+                .boxed_local();
             }
         }
         let mem_ctx = req
             .app_data::<web::Data<ProInMemoryContext>>()
             .expect("ProInMemoryContext will be registered because Postgres was not activated");
         let mem_ctx = mem_ctx.get_ref().clone();
-        async move { mem_ctx.session_by_id(token).await.map_err(Into::into) }.boxed_local()
+        async move {
+            mem_ctx
+                .session_by_id(token)
+                .await
+                .map(with_client_ip)
+                .map_err(Into::into)
+        }
+        .boxed_local()
     }
 }
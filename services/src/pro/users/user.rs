@@ -1,9 +1,12 @@
+use std::str::FromStr;
+
 use pwhash::bcrypt;
 use serde::{Deserialize, Serialize};
 use snafu::ensure;
 
 use crate::error;
 use crate::error::{Error, Result};
+use crate::projects::{ProjectId, STRectangle};
 use crate::util::user_input::UserInput;
 use geoengine_datatypes::identifier;
 use geoengine_datatypes::util::Identifier;
@@ -52,7 +55,41 @@ pub struct UserCredentials {
 
 identifier!(UserId);
 
-#[derive(Clone)]
+impl UserId {
+    /// The built-in user of all read-only guest sessions, s.t. no user is stored per guest
+    pub fn guest_user_id() -> UserId {
+        UserId::from_str("7c5e3a9d-2b4f-4e1a-8d6c-9f0b1e2a3c4d").expect("valid")
+    }
+}
+
+identifier!(PasswordResetToken);
+
+/// How long a password reset token can be used
+pub const PASSWORD_RESET_VALIDITY_MINUTES: i64 = 60;
+
+/// Sets a new password using a token that was sent to the user by e-mail
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct PasswordReset {
+    pub token: PasswordResetToken,
+    pub password: String,
+}
+
+impl UserInput for PasswordReset {
+    fn validate(&self) -> Result<(), Error> {
+        ensure!(
+            self.password.len() >= 8,
+            error::InvalidPassword {
+                reason: "Password must have at least 8 characters"
+            }
+        );
+
+        Ok(())
+    }
+}
+
+/// A registered user, which is serializable for backups and thus contains the password hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct User {
     pub id: UserId,
     pub email: String,
@@ -72,3 +109,18 @@ impl From<UserRegistration> for User {
         }
     }
 }
+
+/// Application state of a user that is kept across sessions and devices, e.g., the last opened project
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserState {
+    /// The last opened project, which might not be accessible anymore
+    #[serde(default)]
+    pub project: Option<ProjectId>,
+    /// The last map extent
+    #[serde(default)]
+    pub view: Option<STRectangle>,
+    /// Preferences of the frontend, e.g., the visibility of layers
+    #[serde(default)]
+    pub preferences: serde_json::Map<String, serde_json::Value>,
+}
@@ -1,6 +1,11 @@
 use crate::contexts::SessionId;
 use crate::error::Result;
-use crate::pro::users::{UserCredentials, UserId, UserRegistration, UserSession};
+use crate::pro::notifications::{NotificationRecipient, NotificationSettings};
+use crate::pro::users::{
+    CreateGroup, CreateOrganization, Group, GroupId, GroupMembership, GroupRole, OrganizationId,
+    OrganizationMembership, OrganizationRole, PasswordReset, PasswordResetToken, Quota,
+    UserCredentials, UserId, UserRegistration, UserSession, UserState,
+};
 use crate::projects::{ProjectId, STRectangle};
 use crate::util::user_input::Validated;
 use async_trait::async_trait;
@@ -39,6 +44,23 @@ pub trait UserDb: Send + Sync {
     ///
     async fn logout(&mut self, session: SessionId) -> Result<()>;
 
+    /// Replaces the token of a valid session with a new one and renews its expiration.
+    /// The old token becomes invalid.
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session is invalid.
+    ///
+    async fn refresh_session(&mut self, session: SessionId) -> Result<UserSession>;
+
+    /// Renews the expiration of a valid session, e.g., for sliding expiration
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session is invalid.
+    ///
+    async fn renew_session(&mut self, session: SessionId) -> Result<UserSession>;
+
     /// Get session by id
     ///
     /// # Errors
@@ -66,4 +88,192 @@ pub trait UserDb: Send + Sync {
     /// This call fails if the session is invalid
     ///
     async fn set_session_view(&mut self, session: &UserSession, view: STRectangle) -> Result<()>;
+
+    /// Loads the application state of the session's user, the default state if none was stored yet
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session is invalid
+    ///
+    async fn user_state(&self, session: &UserSession) -> Result<UserState>;
+
+    /// Stores the application state of the session's user, replacing the previous one
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session is invalid
+    ///
+    async fn set_user_state(&mut self, session: &UserSession, state: UserState) -> Result<()>;
+
+    /// Loads the notifications the session's user wants to receive
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session is invalid
+    ///
+    async fn notification_settings(&self, session: &UserSession) -> Result<NotificationSettings>;
+
+    /// Sets the notifications the session's user wants to receive
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session is invalid
+    ///
+    async fn set_notification_settings(
+        &mut self,
+        session: &UserSession,
+        settings: NotificationSettings,
+    ) -> Result<()>;
+
+    /// Loads the e-mail address and notification settings of a registered `user`,
+    /// `None` for anonymous or unknown users
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the `UserDb` cannot be accessed
+    ///
+    async fn notification_recipient(&self, user: UserId) -> Result<Option<NotificationRecipient>>;
+
+    /// Creates a token that allows the user with the given `email` to reset its password,
+    /// `None` if there is no such user
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the `UserDb` cannot be accessed
+    ///
+    async fn create_password_reset(
+        &mut self,
+        email: &str,
+    ) -> Result<Option<(NotificationRecipient, PasswordResetToken)>>;
+
+    /// Sets the new password of the user of the reset token, consumes the token and ends all sessions of the user
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the token is invalid or expired.
+    ///
+    async fn reset_password(&mut self, reset: Validated<PasswordReset>) -> Result<()>;
+
+    /// Sets the institution of a registered `user`, which dataset access policies can require.
+    /// Must only be called on behalf of the admin because users must not choose their institution.
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the `user` is unknown or anonymous.
+    ///
+    async fn set_user_institution(
+        &mut self,
+        user: UserId,
+        institution: Option<String>,
+    ) -> Result<()>;
+
+    /// Creates an organization with the given `quota` and the session's user as its admin
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the user is anonymous or already a member of an organization.
+    ///
+    async fn create_organization(
+        &mut self,
+        session: &UserSession,
+        organization: Validated<CreateOrganization>,
+        quota: Quota,
+    ) -> Result<OrganizationId>;
+
+    /// Lists the members of the session's organization
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the user is not a member of an organization.
+    ///
+    async fn organization_members(
+        &self,
+        session: &UserSession,
+    ) -> Result<Vec<OrganizationMembership>>;
+
+    /// Adds a registered `user` to the session's organization
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session's user is not an admin of its organization or if the
+    /// `user` is already a member of an organization.
+    ///
+    async fn add_organization_member(
+        &mut self,
+        session: &UserSession,
+        user: UserId,
+        role: OrganizationRole,
+    ) -> Result<()>;
+
+    /// Removes a `user` from the session's organization
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session's user is not an admin of its organization.
+    ///
+    async fn remove_organization_member(
+        &mut self,
+        session: &UserSession,
+        user: UserId,
+    ) -> Result<()>;
+
+    /// Creates a group with the session's user as its admin
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the user is anonymous.
+    ///
+    async fn create_group(
+        &mut self,
+        session: &UserSession,
+        group: Validated<CreateGroup>,
+    ) -> Result<GroupId>;
+
+    /// Lists the groups the session's user is a member of
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the `UserDb` cannot be accessed
+    ///
+    async fn groups(&self, session: &UserSession) -> Result<Vec<Group>>;
+
+    /// Lists the members of a `group` the session's user is a member of
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the group is unknown or the session's user is not a member of it.
+    ///
+    async fn group_members(
+        &self,
+        session: &UserSession,
+        group: GroupId,
+    ) -> Result<Vec<GroupMembership>>;
+
+    /// Adds a registered `user` to a `group` and grants it the group's role
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session's user is not an admin of the group or if the
+    /// `user` is already a member of it.
+    ///
+    async fn add_group_member(
+        &mut self,
+        session: &UserSession,
+        group: GroupId,
+        user: UserId,
+        role: GroupRole,
+    ) -> Result<()>;
+
+    /// Removes a `user` from a `group`
+    ///
+    /// # Errors
+    ///
+    /// This call fails if the session's user is not an admin of the group.
+    ///
+    async fn remove_group_member(
+        &mut self,
+        session: &UserSession,
+        group: GroupId,
+        user: UserId,
+    ) -> Result<()>;
 }
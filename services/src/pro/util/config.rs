@@ -6,12 +6,46 @@ use crate::util::parsing::deserialize_base_url;
 #[derive(Debug, Deserialize)]
 pub struct User {
     pub user_registration: bool,
+    pub session_duration_minutes: u32,
+    /// Renew sessions when they are used instead of letting them expire after a fixed duration
+    pub sliding_expiration: bool,
+    /// The maximum lifetime of a session including all renewals, unlimited if not set
+    pub max_session_lifetime_minutes: Option<u32>,
 }
 
 impl ConfigElement for User {
     const KEY: &'static str = "user";
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Organization {
+    pub organization_creation: bool,
+    /// The default quotas of new organizations, unlimited if not set
+    pub max_datasets: Option<u32>,
+    pub max_projects: Option<u32>,
+}
+
+impl ConfigElement for Organization {
+    const KEY: &'static str = "organization";
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Notifications {
+    pub enabled: bool,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    /// The mailbox e-mails are sent from, e.g., `Geo Engine <noreply@example.com>`
+    pub sender: String,
+    #[serde(deserialize_with = "deserialize_base_url")]
+    pub frontend_url: url::Url,
+}
+
+impl ConfigElement for Notifications {
+    const KEY: &'static str = "notifications";
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Odm {
     #[serde(deserialize_with = "deserialize_base_url")]
@@ -2,11 +2,14 @@ use chrono::{MAX_DATETIME, MIN_DATETIME};
 use geoengine_datatypes::{spatial_reference::SpatialReferenceOption, util::Identifier};
 
 use crate::{
-    contexts::SessionId,
+    contexts::{BackupDb, SessionId},
     handlers, pro,
     pro::{
         contexts::ProContext,
-        datasets::Role,
+        datasets::{
+            DatasetAccessPolicies, DatasetLicenses, DatasetPermission, Role,
+            UpdateDatasetPermissions,
+        },
         projects::ProProjectDb,
         users::{UserCredentials, UserDb, UserId, UserInfo, UserRegistration, UserSession},
     },
@@ -54,12 +57,15 @@ pub fn create_random_user_session_helper() -> UserSession {
             id: user_id,
             email: Some(user_id.to_string()),
             real_name: Some(user_id.to_string()),
+            institution: None,
         },
         created: MIN_DATETIME,
         valid_until: MAX_DATETIME,
         project: None,
         view: None,
         roles: vec![user_id.into(), Role::user_role_id()],
+        organization: None,
+        client_ip: None,
     }
 }
 
@@ -101,6 +107,10 @@ pub async fn send_pro_test_request<C>(req: test::TestRequest, ctx: C) -> Service
 where
     C: ProContext,
     C::ProjectDB: ProProjectDb,
+    C::DatasetDB: BackupDb<DatasetPermission>
+        + DatasetAccessPolicies
+        + DatasetLicenses
+        + UpdateDatasetPermissions,
 {
     #[allow(unused_mut)]
     let mut app = App::new()
@@ -112,8 +122,14 @@ where
         )
         .wrap(middleware::NormalizePath::trim())
         .configure(configure_extractors)
+        .configure(pro::handlers::backup::init_backup_routes::<C>)
+        .configure(pro::handlers::datasets::init_dataset_access_policy_routes::<C>)
+        .configure(pro::handlers::datasets::init_dataset_license_routes::<C>)
         .configure(handlers::datasets::init_dataset_routes::<C>)
+        .configure(pro::handlers::groups::init_group_routes::<C>)
+        .configure(handlers::info::init_info_routes::<C>)
         .configure(handlers::plots::init_plot_routes::<C>)
+        .configure(pro::handlers::organizations::init_organization_routes::<C>)
         .configure(pro::handlers::projects::init_project_routes::<C>)
         .configure(pro::handlers::users::init_user_routes::<C>)
         .configure(handlers::spatial_references::init_spatial_reference_routes::<C>)
@@ -121,6 +137,7 @@ where
         .configure(handlers::wcs::init_wcs_routes::<C>)
         .configure(handlers::wfs::init_wfs_routes::<C>)
         .configure(handlers::wms::init_wms_routes::<C>)
+        .configure(handlers::workers::init_worker_routes::<C>)
         .configure(handlers::workflows::init_workflow_routes::<C>);
     #[cfg(feature = "odm")]
     {
@@ -1,5 +1,8 @@
+use crate::contexts::BackupDb;
 use crate::error::Result;
+use crate::workflows::template::{WorkflowTemplate, WorkflowTemplateId};
 use crate::workflows::workflow::{Workflow, WorkflowId};
+use crate::workflows::workflow_macro::WorkflowMacro;
 use crate::{error, workflows::registry::WorkflowRegistry};
 use async_trait::async_trait;
 use bb8_postgres::{
@@ -72,4 +75,110 @@ where
 
         Ok(serde_json::from_value(row.get(0)).context(error::SerdeJson)?)
     }
+
+    async fn register_template(
+        &mut self,
+        template: WorkflowTemplate,
+    ) -> Result<WorkflowTemplateId> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare(
+                "INSERT INTO workflow_templates (id, template) VALUES ($1, $2) 
+            ON CONFLICT DO NOTHING;",
+            )
+            .await?;
+
+        let template_id = WorkflowTemplateId::from_hash(&template);
+
+        conn.execute(
+            &stmt,
+            &[
+                &template_id,
+                &serde_json::to_value(&template).context(error::SerdeJson)?,
+            ],
+        )
+        .await?;
+
+        Ok(template_id)
+    }
+
+    async fn load_template(&self, id: &WorkflowTemplateId) -> Result<WorkflowTemplate> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare("SELECT template FROM workflow_templates WHERE id = $1")
+            .await?;
+
+        let row = conn
+            .query_opt(&stmt, &[&id])
+            .await?
+            .ok_or(error::Error::NoWorkflowTemplateForGivenId)?;
+
+        Ok(serde_json::from_value(row.get(0)).context(error::SerdeJson)?)
+    }
+
+    async fn register_macro(&mut self, name: String, operator: serde_json::Value) -> Result<u32> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare(
+                "INSERT INTO workflow_macros (name, version, operator)
+            SELECT $1::text, COALESCE(MAX(version), 0) + 1, $2::json FROM workflow_macros WHERE name = $1
+            RETURNING version;",
+            )
+            .await?;
+
+        let row = conn.query_one(&stmt, &[&name, &operator]).await?;
+
+        Ok(row.get::<_, i32>(0) as u32)
+    }
+
+    async fn load_macro(&self, name: &str, version: Option<u32>) -> Result<WorkflowMacro> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn
+            .prepare(
+                "SELECT version, operator FROM workflow_macros
+            WHERE name = $1 AND ($2::int IS NULL OR version = $2)
+            ORDER BY version DESC
+            LIMIT 1",
+            )
+            .await?;
+
+        let row = conn
+            .query_opt(&stmt, &[&name, &version.map(|version| version as i32)])
+            .await?
+            .ok_or_else(|| error::Error::UnknownWorkflowMacro {
+                name: name.to_string(),
+                version,
+            })?;
+
+        Ok(WorkflowMacro {
+            name: name.to_string(),
+            version: row.get::<_, i32>(0) as u32,
+            operator: row.get(1),
+        })
+    }
+}
+
+#[async_trait]
+impl<Tls> BackupDb<Workflow> for PostgresWorkflowRegistry<Tls>
+where
+    Tls: MakeTlsConnect<Socket> + Clone + Send + Sync + 'static,
+    <Tls as MakeTlsConnect<Socket>>::Stream: Send + Sync,
+    <Tls as MakeTlsConnect<Socket>>::TlsConnect: Send,
+    <<Tls as MakeTlsConnect<Socket>>::TlsConnect as TlsConnect<Socket>>::Future: Send,
+{
+    async fn export(&self) -> Result<Vec<Workflow>> {
+        let conn = self.conn_pool.get().await?;
+        let stmt = conn.prepare("SELECT workflow FROM workflows").await?;
+
+        let rows = conn.query(&stmt, &[]).await?;
+
+        rows.into_iter()
+            .map(|row| serde_json::from_value(row.get(0)).context(error::SerdeJson))
+            .collect()
+    }
+
+    async fn import(&mut self, workflow: Workflow) -> Result<()> {
+        // registering is idempotent because the id is derived from the workflow
+        self.register(workflow).await.map(drop)
+    }
 }
@@ -1,3 +1,4 @@
+use crate::contexts::BackupDb;
 use crate::error::Result;
 use crate::projects::{
     CreateProject, OrderBy, Project, ProjectDb, ProjectFilter, ProjectId, ProjectListOptions,
@@ -6,6 +7,7 @@ use crate::projects::{
 use crate::util::user_input::Validated;
 use crate::{contexts::SimpleSession, error};
 use async_trait::async_trait;
+use snafu::ensure;
 use std::collections::HashMap;
 
 #[derive(Default)]
@@ -102,6 +104,25 @@ impl ProjectDb<SimpleSession> for HashMapProjectDb {
     }
 }
 
+#[async_trait]
+impl BackupDb<Project> for HashMapProjectDb {
+    async fn export(&self) -> Result<Vec<Project>> {
+        Ok(self.projects.values().cloned().collect())
+    }
+
+    async fn import(&mut self, project: Project) -> Result<()> {
+        ensure!(
+            !self.projects.contains_key(&project.id),
+            error::Duplicate {
+                reason: format!("Project {} already exists", project.id),
+            }
+        );
+
+        self.projects.insert(project.id, project);
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
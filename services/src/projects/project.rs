@@ -234,6 +234,18 @@ pub enum Symbology {
     Polygon(PolygonSymbology),
 }
 
+impl Symbology {
+    /// The legend entries of the classified colors of vector symbologies
+    pub fn legend(&self) -> Vec<LegendEntry> {
+        match self {
+            Symbology::Raster(_) => Vec::new(),
+            Symbology::Point(point) => point.fill_color.legend(),
+            Symbology::Line(line) => line.stroke.color.legend(),
+            Symbology::Polygon(polygon) => polygon.fill_color.legend(),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct RasterSymbology {
     pub opacity: f64,
@@ -305,6 +317,8 @@ pub struct PolygonSymbology {
 pub enum NumberParam {
     Static { value: usize },
     Derived(DerivedNumber),
+    Expression(ExpressionNumber),
+    Classified(ClassifiedNumber),
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -324,11 +338,50 @@ pub struct StrokeParam {
 
 impl Eq for DerivedNumber {}
 
+/// A number that is computed from the attributes of each feature, e.g., `sqrt(area) * 2`
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpressionNumber {
+    pub expression: String,
+    pub default_value: f64,
+}
+
+impl Eq for ExpressionNumber {}
+
+/// Assigns one of the `values` to each feature by the class of its expression value.
+///
+/// The `breaks` are the lower bounds of the classes except for the first class.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassifiedNumber {
+    pub expression: String,
+    pub breaks: Vec<f64>,
+    pub values: Vec<f64>,
+    pub default_value: f64,
+}
+
+impl Eq for ClassifiedNumber {}
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase", tag = "type")]
 pub enum ColorParam {
     Static { color: RgbaColor },
     Derived(DerivedColor),
+    Expression(ExpressionColor),
+    Classified(ClassifiedColor),
+}
+
+impl ColorParam {
+    /// The legend entries of the color, i.e., one per class for classified colors
+    /// and none for other colors
+    pub fn legend(&self) -> Vec<LegendEntry> {
+        match self {
+            ColorParam::Classified(classified) => classified.legend(),
+            ColorParam::Static { .. } | ColorParam::Derived(_) | ColorParam::Expression(_) => {
+                Vec::new()
+            }
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
@@ -337,6 +390,71 @@ pub struct DerivedColor {
     pub colorizer: Colorizer,
 }
 
+/// A color that is computed from the attributes of each feature by mapping the
+/// expression's value with the `colorizer`
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct ExpressionColor {
+    pub expression: String,
+    pub colorizer: Colorizer,
+}
+
+/// Assigns one of the `colors` to each feature by the class of its expression value.
+///
+/// The `breaks` are the lower bounds of the classes except for the first class.
+/// Thus, there is one color more than there are breaks.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClassifiedColor {
+    pub expression: String,
+    pub breaks: Vec<f64>,
+    pub colors: Vec<RgbaColor>,
+    pub no_data_color: RgbaColor,
+}
+
+impl Eq for ClassifiedColor {}
+
+impl ClassifiedColor {
+    pub fn legend(&self) -> Vec<LegendEntry> {
+        let mut entries = Vec::with_capacity(self.colors.len() + 1);
+
+        for (i, color) in self.colors.iter().enumerate() {
+            let lower = if i > 0 { self.breaks.get(i - 1) } else { None };
+            // the last color is also used for all remaining classes
+            let upper = if i + 1 < self.colors.len() {
+                self.breaks.get(i)
+            } else {
+                None
+            };
+
+            let label = match (lower, upper) {
+                (None, None) if i > 0 => break, // surplus colors are never used
+                (None, None) => self.expression.clone(),
+                (None, Some(upper)) => format!("< {}", upper),
+                (Some(lower), Some(upper)) => format!("{} – {}", lower, upper),
+                (Some(lower), None) => format!("≥ {}", lower),
+            };
+
+            entries.push(LegendEntry {
+                label,
+                color: *color,
+            });
+        }
+
+        entries.push(LegendEntry {
+            label: "no data".to_string(),
+            color: self.no_data_color,
+        });
+
+        entries
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct LegendEntry {
+    pub label: String,
+    pub color: RgbaColor,
+}
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Hash)]
 #[cfg_attr(feature = "postgres", derive(ToSql, FromSql))]
 pub struct LayerVisibility {
@@ -711,6 +829,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn serialize_classified_color_param() {
+        let color = ColorParam::Classified(ClassifiedColor {
+            expression: "population / area".to_owned(),
+            breaks: vec![10., 100.],
+            colors: vec![RgbaColor::black(), RgbaColor::pink(), RgbaColor::white()],
+            no_data_color: RgbaColor::transparent(),
+        });
+
+        let serialized = serde_json::to_value(&color).unwrap();
+
+        assert_eq!(
+            serialized,
+            json!({
+                "type": "classified",
+                "expression": "population / area",
+                "breaks": [10.0, 100.0],
+                "colors": [[0, 0, 0, 255], [255, 0, 255, 255], [255, 255, 255, 255]],
+                "noDataColor": [0, 0, 0, 0]
+            })
+        );
+
+        assert_eq!(
+            serde_json::from_value::<ColorParam>(serialized).unwrap(),
+            color
+        );
+    }
+
+    #[test]
+    fn classified_legend() {
+        let symbology = Symbology::Polygon(PolygonSymbology {
+            fill_color: ColorParam::Classified(ClassifiedColor {
+                expression: "density".to_owned(),
+                breaks: vec![10., 100.5],
+                colors: vec![RgbaColor::black(), RgbaColor::pink(), RgbaColor::white()],
+                no_data_color: RgbaColor::transparent(),
+            }),
+            stroke: StrokeParam {
+                width: NumberParam::Expression(ExpressionNumber {
+                    expression: "density / 10".to_owned(),
+                    default_value: 1.,
+                }),
+                color: ColorParam::Static {
+                    color: RgbaColor::black(),
+                },
+            },
+            text: None,
+        });
+
+        assert_eq!(
+            symbology.legend(),
+            vec![
+                LegendEntry {
+                    label: "< 10".to_owned(),
+                    color: RgbaColor::black()
+                },
+                LegendEntry {
+                    label: "10 – 100.5".to_owned(),
+                    color: RgbaColor::pink()
+                },
+                LegendEntry {
+                    label: "≥ 100.5".to_owned(),
+                    color: RgbaColor::white()
+                },
+                LegendEntry {
+                    label: "no data".to_owned(),
+                    color: RgbaColor::transparent()
+                },
+            ]
+        );
+    }
+
     #[test]
     fn serialize_derived_number_param() {
         assert_eq!(
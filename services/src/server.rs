@@ -1,21 +1,30 @@
-use crate::contexts::{InMemoryContext, SimpleContext};
+use crate::contexts::{Context, InMemoryContext, SimpleContext};
+use crate::datasets::{validation, watcher};
 use crate::error::{Error, Result};
 use crate::handlers;
-use crate::handlers::ErrorResponse;
+use crate::handlers::{ErrorResponse, QUERY_SAMPLED_HEADER};
 use crate::util::config;
 use crate::util::config::get_config_element;
+use crate::util::rate_limiting::RateLimiting;
 
+use crate::workflows::cache::CACHE_STATUS_HEADER;
+use crate::workflows::export;
+
+use actix_cors::Cors;
 use actix_files::Files;
 use actix_http::body::{BoxBody, EitherBody, MessageBody};
+use actix_http::header::{self, HeaderValue};
 use actix_http::uri::PathAndQuery;
 use actix_http::HttpMessage;
 use actix_web::dev::{ServiceRequest, ServiceResponse};
 use actix_web::error::{InternalError, JsonPayloadError, QueryPayloadError};
 use actix_web::{http, middleware, web, App, HttpResponse, HttpServer};
+use geoengine_operators::source::{LoadingInfoCache, RemoteReadPolicy};
 use log::{debug, info};
 use std::net::SocketAddr;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
+use std::time::Duration;
 use tracing::Span;
 use tracing_actix_web::{RequestId, RootSpanBuilder, TracingLogger};
 use url::Url;
@@ -29,12 +38,7 @@ use url::Url;
 pub async fn start_server(static_files_dir: Option<PathBuf>) -> Result<()> {
     let web_config: config::Web = get_config_element()?;
 
-    info!(
-        "Starting server… {}",
-        web_config
-            .external_address
-            .unwrap_or(Url::parse(&format!("http://{}/", web_config.bind_address))?)
-    );
+    info!("Starting server… {}", api_address(&web_config)?);
 
     let session_config: crate::util::config::Session = get_config_element()?;
 
@@ -44,6 +48,13 @@ pub async fn start_server(static_files_dir: Option<PathBuf>) -> Result<()> {
         info!("Anonymous access is disabled");
     }
 
+    if session_config.guest_access {
+        info!(
+            "Guest access is enabled with at most {} sessions and {} workflow executions per session",
+            session_config.guest_max_sessions, session_config.guest_max_queries
+        );
+    }
+
     if let Some(session_token) = session_config.fixed_session_token {
         info!("Fixed session token is set, it is {session_token}");
     }
@@ -84,6 +95,24 @@ async fn start<C>(
 where
     C: SimpleContext,
 {
+    let worker_mode = get_config_element::<config::Distributed>()?.worker;
+    if worker_mode {
+        info!("Worker mode is enabled");
+    }
+
+    configure_loading_info_cache()?;
+    configure_remote_reads()?;
+
+    let rate_limiting = RateLimiting::<C>::from_config()?;
+    let cors_config = get_config_element::<config::Cors>()?;
+    let api_address = api_address(&get_config_element::<config::Web>()?)?;
+    let compression = get_config_element::<config::Web>()?.compression;
+    let security_headers_config = get_config_element::<config::SecurityHeaders>()?;
+
+    schedule_dataset_validation(ctx.clone())?;
+    schedule_export_cleanup(&ctx)?;
+    watch_dataset_directories(ctx.clone())?;
+
     let wrapped_ctx = web::Data::new(ctx);
 
     HttpServer::new(move || {
@@ -95,20 +124,35 @@ where
                     .handler(http::StatusCode::NOT_FOUND, render_404)
                     .handler(http::StatusCode::METHOD_NOT_ALLOWED, render_405),
             )
+            .wrap(rate_limiting.clone())
             .wrap(TracingLogger::<CustomRootSpanBuilder>::new())
             .wrap(middleware::NormalizePath::trim())
+            .wrap(security_headers(&security_headers_config))
+            .wrap(cors(&cors_config, &api_address))
+            .wrap(middleware::Condition::new(
+                compression,
+                middleware::Compress::default(),
+            ))
             .configure(configure_extractors)
+            .configure(handlers::backup::init_backup_routes::<C>)
             .configure(handlers::datasets::init_dataset_routes::<C>)
+            .configure(handlers::info::init_info_routes::<C>)
             .configure(handlers::plots::init_plot_routes::<C>)
             .configure(handlers::projects::init_project_routes::<C>)
+            .configure(handlers::scalars::init_scalar_routes::<C>)
             .configure(handlers::session::init_session_routes::<C>)
             .configure(handlers::spatial_references::init_spatial_reference_routes::<C>)
+            .configure(handlers::tables::init_table_routes::<C>)
             .configure(handlers::upload::init_upload_routes::<C>)
             .configure(handlers::wcs::init_wcs_routes::<C>)
             .configure(handlers::wfs::init_wfs_routes::<C>)
             .configure(handlers::wms::init_wms_routes::<C>)
             .configure(handlers::workflows::init_workflow_routes::<C>);
 
+        if worker_mode {
+            app = app.configure(handlers::workers::init_worker_routes::<C>);
+        }
+
         #[cfg(feature = "ebv")]
         {
             app = app
@@ -195,63 +239,88 @@ pub(crate) fn calculate_max_blocking_threads_per_worker() -> usize {
     std::cmp::max(max_blocking_threads, MIN_BLOCKING_THREADS_PER_WORKER)
 }
 
+/// Applies the configured expiration time and size to the process-wide loading info cache
+pub(crate) fn configure_loading_info_cache() -> Result<()> {
+    let gdal_config = get_config_element::<config::Gdal>()?;
+
+    LoadingInfoCache::global().configure(
+        Duration::from_secs(gdal_config.loading_info_cache_ttl_seconds),
+        gdal_config.loading_info_cache_size,
+    );
+
+    Ok(())
+}
+
+/// Starts the scheduled validation of all datasets if an interval is configured
+pub(crate) fn schedule_dataset_validation<C: Context>(ctx: C) -> Result<()> {
+    let validation_config = get_config_element::<config::DatasetValidation>()?;
+
+    if validation_config.schedule_interval_hours > 0 {
+        info!(
+            "Validating datasets every {} hours",
+            validation_config.schedule_interval_hours
+        );
+
+        validation::schedule_dataset_validation(
+            ctx,
+            Duration::from_secs(validation_config.schedule_interval_hours * 60 * 60),
+            validation_config.max_time_steps,
+        );
+    }
+
+    Ok(())
+}
+
+/// Starts the periodic deletion of expired exports if an interval is configured
+pub(crate) fn schedule_export_cleanup<C: Context>(ctx: &C) -> Result<()> {
+    let export_config = get_config_element::<config::Export>()?;
+
+    if export_config.cleanup_interval_seconds > 0 {
+        export::schedule_export_cleanup(
+            ctx.object_storage(),
+            Duration::from_secs(export_config.cleanup_interval_seconds),
+        );
+    }
+
+    Ok(())
+}
+
+/// Starts scanning the configured directories for new time slices of datasets if there are any
+pub(crate) fn watch_dataset_directories<C: Context>(ctx: C) -> Result<()> {
+    let watcher_config = get_config_element::<config::DatasetWatcher>()?;
+
+    if !watcher_config.paths.is_empty() {
+        info!(
+            "Watching {:?} for new time slices of datasets",
+            watcher_config.paths
+        );
+
+        watcher::watch_directories(
+            ctx,
+            watcher_config.paths,
+            Duration::from_secs(watcher_config.poll_interval_seconds),
+        );
+    }
+
+    Ok(())
+}
+
+/// Applies the configured retries, timeouts and circuit breaking to the reads of remote GDAL files
+pub(crate) fn configure_remote_reads() -> Result<()> {
+    let gdal_config = get_config_element::<config::Gdal>()?;
+
+    RemoteReadPolicy::global().configure((&gdal_config).into());
+
+    Ok(())
+}
+
 pub(crate) fn configure_extractors(cfg: &mut web::ServiceConfig) {
-    cfg.app_data(web::JsonConfig::default().error_handler(|err, _req| {
-        match err {
-            JsonPayloadError::ContentType => InternalError::from_response(
-                err,
-                HttpResponse::UnsupportedMediaType().json(ErrorResponse {
-                    error: "UnsupportedMediaType".to_string(),
-                    message: "Unsupported content type header.".to_string(),
-                }),
-            )
-            .into(),
-            JsonPayloadError::Overflow { limit } => InternalError::from_response(
-                err,
-                HttpResponse::PayloadTooLarge().json(ErrorResponse {
-                    error: "Overflow".to_string(),
-                    message: format!("JSON payload has exceeded limit ({} bytes).", limit),
-                }),
-            )
-            .into(),
-            JsonPayloadError::OverflowKnownLength { length, limit } => {
-                InternalError::from_response(
-                    err,
-                    HttpResponse::PayloadTooLarge().json(ErrorResponse {
-                        error: "Overflow".to_string(),
-                        message: format!(
-                            "JSON payload ({} bytes) is larger than allowed (limit: {} bytes).",
-                            length, limit
-                        ),
-                    }),
-                )
-                .into()
-            }
-            JsonPayloadError::Payload(err) => ErrorResponse {
-                error: "Payload".to_string(),
-                message: err.to_string(),
-            }
-            .into(),
-            JsonPayloadError::Deserialize(err) => ErrorResponse {
-                error: "BodyDeserializeError".to_string(),
-                message: err.to_string(),
-            }
-            .into(),
-            JsonPayloadError::Serialize(err) => ErrorResponse {
-                error: "BodySerializeError".to_string(),
-                message: err.to_string(),
-            }
-            .into(),
-            _ => {
-                debug!("Unknown JsonPayloadError variant");
-                ErrorResponse {
-                    error: "UnknownError".to_string(),
-                    message: "Unknown Error".to_string(),
-                }
-                .into()
-            }
-        }
-    }));
+    let mut json_config = web::JsonConfig::default().error_handler(|err, _req| json_error(err));
+    if let Ok(body_limits) = get_config_element::<config::BodyLimits>() {
+        json_config = json_config.limit(body_limits.json);
+    }
+
+    cfg.app_data(json_config);
     cfg.app_data(web::QueryConfig::default().error_handler(|err, _req| {
         match err {
             QueryPayloadError::Deserialize(err) => ErrorResponse {
@@ -271,6 +340,141 @@ pub(crate) fn configure_extractors(cfg: &mut web::ServiceConfig) {
     }));
 }
 
+/// Renders errors of JSON request bodies, e.g., of `web::Json` or [`crate::util::streaming_json::StreamingJson`]
+pub(crate) fn json_error(err: JsonPayloadError) -> actix_web::Error {
+    match err {
+        JsonPayloadError::ContentType => InternalError::from_response(
+            err,
+            HttpResponse::UnsupportedMediaType().json(ErrorResponse {
+                error: "UnsupportedMediaType".to_string(),
+                message: "Unsupported content type header.".to_string(),
+            }),
+        )
+        .into(),
+        JsonPayloadError::Overflow { limit } => InternalError::from_response(
+            err,
+            HttpResponse::PayloadTooLarge().json(ErrorResponse {
+                error: "Overflow".to_string(),
+                message: format!("JSON payload has exceeded limit ({} bytes).", limit),
+            }),
+        )
+        .into(),
+        JsonPayloadError::OverflowKnownLength { length, limit } => InternalError::from_response(
+            err,
+            HttpResponse::PayloadTooLarge().json(ErrorResponse {
+                error: "Overflow".to_string(),
+                message: format!(
+                    "JSON payload ({} bytes) is larger than allowed (limit: {} bytes).",
+                    length, limit
+                ),
+            }),
+        )
+        .into(),
+        JsonPayloadError::Payload(err) => ErrorResponse {
+            error: "Payload".to_string(),
+            message: err.to_string(),
+        }
+        .into(),
+        JsonPayloadError::Deserialize(err) => ErrorResponse {
+            error: "BodyDeserializeError".to_string(),
+            message: err.to_string(),
+        }
+        .into(),
+        JsonPayloadError::Serialize(err) => ErrorResponse {
+            error: "BodySerializeError".to_string(),
+            message: err.to_string(),
+        }
+        .into(),
+        _ => {
+            debug!("Unknown JsonPayloadError variant");
+            ErrorResponse {
+                error: "UnknownError".to_string(),
+                message: "Unknown Error".to_string(),
+            }
+            .into()
+        }
+    }
+}
+
+/// The address under which the API is reachable, which defaults to the bind address
+pub(crate) fn api_address(web_config: &config::Web) -> Result<Url> {
+    match &web_config.external_address {
+        Some(external_address) => Ok(external_address.clone()),
+        None => Ok(Url::parse(&format!("http://{}/", web_config.bind_address))?),
+    }
+}
+
+/// Creates the CORS middleware that allows requests from the configured origins and from the
+/// origin of the API itself, which is derived from its `api_address`.
+pub(crate) fn cors(config: &config::Cors, api_address: &Url) -> Cors {
+    let api_origin = api_address.origin().ascii_serialization();
+
+    let mut cors = Cors::default()
+        .allow_any_method()
+        .allow_any_header()
+        .expose_headers([
+            header::RETRY_AFTER.as_str(),
+            header::CONTENT_DISPOSITION.as_str(),
+            header::CONTENT_RANGE.as_str(),
+            header::ACCEPT_RANGES.as_str(),
+            header::ETAG.as_str(),
+            CACHE_STATUS_HEADER,
+            QUERY_SAMPLED_HEADER,
+        ])
+        .allowed_origin_fn(move |origin, _request| is_same_origin(origin, &api_origin))
+        .max_age(config.max_age_seconds);
+
+    for origin in &config.allowed_origins {
+        cors = if origin == "*" {
+            cors.allow_any_origin()
+        } else {
+            cors.allowed_origin(origin)
+        };
+    }
+
+    if config.allow_credentials {
+        cors = cors.supports_credentials();
+    }
+
+    cors
+}
+
+/// Compares the scheme, host and port of the `Origin` header with the origin of the API.
+/// The `Host` header is not trusted since clients can set it arbitrarily.
+fn is_same_origin(origin: &HeaderValue, api_origin: &str) -> bool {
+    origin
+        .to_str()
+        .map_or(false, |origin| origin.eq_ignore_ascii_case(api_origin))
+}
+
+/// Creates a middleware that adds the configured security headers to all responses
+pub(crate) fn security_headers(config: &config::SecurityHeaders) -> middleware::DefaultHeaders {
+    let mut headers = middleware::DefaultHeaders::new();
+
+    if config.enabled {
+        headers = headers
+            .add((header::X_CONTENT_TYPE_OPTIONS, "nosniff"))
+            .add((header::X_FRAME_OPTIONS, "DENY"))
+            .add((header::REFERRER_POLICY, "no-referrer"));
+    }
+
+    if config.hsts_max_age_seconds > 0 {
+        headers = headers.add((
+            header::STRICT_TRANSPORT_SECURITY,
+            format!("max-age={}", config.hsts_max_age_seconds),
+        ));
+    }
+
+    if !config.content_security_policy.is_empty() {
+        headers = headers.add((
+            header::CONTENT_SECURITY_POLICY,
+            config.content_security_policy.as_str(),
+        ));
+    }
+
+    headers
+}
+
 /// Shows information about the server software version.
 ///
 /// # Example
@@ -399,6 +603,108 @@ mod tests {
         );
     }
 
+    async fn cors_test_app(
+        config: &config::Cors,
+        api_address: &Url,
+    ) -> impl actix_web::dev::Service<
+        actix_http::Request,
+        Response = ServiceResponse<impl MessageBody>,
+        Error = actix_web::Error,
+    > {
+        actix_web::test::init_service(
+            App::new()
+                .wrap(security_headers(&config::SecurityHeaders {
+                    enabled: true,
+                    hsts_max_age_seconds: 3600,
+                    content_security_policy: String::new(),
+                }))
+                .wrap(cors(config, api_address))
+                .route("/datasets", web::get().to(HttpResponse::Ok)),
+        )
+        .await
+    }
+
+    #[actix_rt::test]
+    async fn it_allows_configured_origins() {
+        let app = cors_test_app(
+            &config::Cors {
+                allowed_origins: vec!["https://app.example.com".to_string()],
+                allow_credentials: false,
+                max_age_seconds: 3600,
+            },
+            &Url::parse("https://api.example.com/api/").unwrap(),
+        )
+        .await;
+
+        let req = actix_web::test::TestRequest::default()
+            .method(http::Method::OPTIONS)
+            .uri("/datasets")
+            .insert_header((header::HOST, "api.example.com"))
+            .insert_header((header::ORIGIN, "https://app.example.com"))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_METHOD, "GET"))
+            .insert_header((header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization"))
+            .to_request();
+        let res = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), 200);
+        assert_eq!(
+            res.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(&HeaderValue::from_static("https://app.example.com"))
+        );
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/datasets")
+            .insert_header((header::HOST, "api.example.com"))
+            .insert_header((header::ORIGIN, "https://app.example.com"))
+            .to_request();
+        let res = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), 200);
+        assert_eq!(
+            res.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(&HeaderValue::from_static("https://app.example.com"))
+        );
+        assert_eq!(
+            res.headers().get(header::X_CONTENT_TYPE_OPTIONS),
+            Some(&HeaderValue::from_static("nosniff"))
+        );
+        assert_eq!(
+            res.headers().get(header::STRICT_TRANSPORT_SECURITY),
+            Some(&HeaderValue::from_static("max-age=3600"))
+        );
+
+        // same origin
+        let req = actix_web::test::TestRequest::get()
+            .uri("/datasets")
+            .insert_header((header::HOST, "api.example.com"))
+            .insert_header((header::ORIGIN, "https://api.example.com"))
+            .to_request();
+        let res = actix_web::test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), 200);
+
+        for (host, origin) in [
+            ("api.example.com", "https://evil.example.com"),
+            // the scheme must match as well
+            ("api.example.com", "http://api.example.com"),
+            // the host header must not be trusted
+            ("evil.example.com", "https://evil.example.com"),
+        ] {
+            let req = actix_web::test::TestRequest::get()
+                .uri("/datasets")
+                .insert_header((header::HOST, host))
+                .insert_header((header::ORIGIN, origin))
+                .to_request();
+            let res = actix_web::test::call_service(&app, req).await;
+
+            assert_eq!(res.status(), 400);
+            assert!(res
+                .headers()
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .is_none());
+        }
+    }
+
     const WAIT_SERVER_RETRIES: i32 = 5;
     const WAIT_SERVER_RETRY_INTERVAL: u64 = 1;
 
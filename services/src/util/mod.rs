@@ -8,9 +8,13 @@ pub use geoengine_operators::util::{spawn_blocking, spawn_blocking_with_thread_p
 
 pub mod config;
 pub mod parsing;
+pub mod query_preview;
+pub mod rate_limiting;
 pub mod retry;
+pub mod streaming_json;
 pub mod tests;
 pub mod user_input;
+pub mod vega;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct IdResponse<T> {
@@ -89,6 +89,23 @@ where
         .map_err(D::Error::custom)
 }
 
+/// Deserialize a list of base URLs by enforcing a trailing slash and then parsing them as URLs.
+pub fn deserialize_base_url_list<'de, D>(deserializer: D) -> Result<Vec<Url>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|mut url_string| {
+            if !url_string.ends_with('/') {
+                url_string.push('/');
+            }
+
+            Url::parse(&url_string).map_err(D::Error::custom)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::fmt::Display;
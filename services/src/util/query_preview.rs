@@ -0,0 +1,116 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use actix_web::HttpResponseBuilder;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use geoengine_datatypes::collections::FeatureCollection;
+use geoengine_datatypes::primitives::{Geometry, SpatialResolution};
+use geoengine_datatypes::util::arrow::ArrowTyped;
+use geoengine_operators::adapters::FeatureCollectionStreamExt;
+
+use crate::error::Result;
+use crate::handlers::QUERY_SAMPLED_HEADER;
+use crate::util::config::{self, get_config_element};
+
+/// A fast preview of a query that samples the result to bound the work, see the `query_preview` settings.
+///
+/// Vector results keep every nth feature up to a maximum number of features, raster results are
+/// computed at a coarser resolution.
+/// Responses of sampled results are marked with the `X-Query-Sampled` header.
+#[derive(Debug, Clone)]
+pub struct QueryPreview {
+    feature_step: usize,
+    max_features: usize,
+    max_pixels: u32,
+    sampled: Arc<AtomicBool>,
+}
+
+impl QueryPreview {
+    /// Creates a preview with the configured work budget if the request asks for a `preview`
+    pub fn from_request(preview: Option<bool>) -> Result<Option<Self>> {
+        if !preview.unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let config = get_config_element::<config::QueryPreview>()?;
+
+        Ok(Some(Self {
+            feature_step: config.feature_step,
+            max_features: config.max_features,
+            max_pixels: config.max_pixels,
+            sampled: Arc::new(AtomicBool::new(false)),
+        }))
+    }
+
+    pub fn sample_features<'a, G>(
+        &self,
+        stream: BoxStream<'a, geoengine_operators::util::Result<FeatureCollection<G>>>,
+    ) -> BoxStream<'a, geoengine_operators::util::Result<FeatureCollection<G>>>
+    where
+        G: Geometry + ArrowTyped + 'static,
+    {
+        stream
+            .sample(self.feature_step, self.max_features, self.sampled.clone())
+            .boxed()
+    }
+
+    /// Coarsens the `resolution` of a raster query of `width` x `height` pixels s.t. it
+    /// produces at most the configured number of pixels
+    pub fn raster_resolution(
+        &self,
+        resolution: SpatialResolution,
+        width: u32,
+        height: u32,
+    ) -> SpatialResolution {
+        let pixels = f64::from(width) * f64::from(height);
+        let max_pixels = f64::from(self.max_pixels.max(1));
+
+        if pixels <= max_pixels {
+            return resolution;
+        }
+
+        self.sampled.store(true, Ordering::Relaxed);
+
+        let factor = (pixels / max_pixels).sqrt();
+        SpatialResolution::new_unchecked(resolution.x * factor, resolution.y * factor)
+    }
+
+    pub fn is_sampled(&self) -> bool {
+        self.sampled.load(Ordering::Relaxed)
+    }
+
+    /// Marks the `response` if the result was sampled
+    pub fn insert_header(&self, response: &mut HttpResponseBuilder) {
+        if self.is_sampled() {
+            response.insert_header((QUERY_SAMPLED_HEADER, "true"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_coarsens_raster_resolutions() {
+        let preview = QueryPreview {
+            feature_step: 1,
+            max_features: 1,
+            max_pixels: 100,
+            sampled: Arc::new(AtomicBool::new(false)),
+        };
+
+        assert_eq!(
+            preview.raster_resolution(SpatialResolution::new_unchecked(1., 2.), 10, 10),
+            SpatialResolution::new_unchecked(1., 2.)
+        );
+        assert!(!preview.is_sampled());
+
+        assert_eq!(
+            preview.raster_resolution(SpatialResolution::new_unchecked(1., 2.), 40, 10),
+            SpatialResolution::new_unchecked(2., 4.)
+        );
+        assert!(preview.is_sampled());
+    }
+}
@@ -0,0 +1,648 @@
+use std::collections::HashMap;
+use std::future::{ready, Ready};
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use actix_http::body::EitherBody;
+use actix_http::header::{self, HeaderValue};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{web, HttpMessage, ResponseError};
+use futures::future::LocalBoxFuture;
+
+use crate::contexts::{Context, SessionId};
+use crate::error::Error;
+use crate::handlers::get_token;
+use crate::util::config::{self, RateLimit};
+
+/// The number of buckets after which full, i.e., inactive, buckets are removed
+const MIN_PRUNING_THRESHOLD: usize = 1024;
+
+/// The number of buckets after which the buckets of the least recently active clients are removed
+const MAX_BUCKETS: usize = 65_536;
+
+/// Requests are limited with separate budgets for cheap and expensive endpoints.
+///
+/// All requests are charged to the [`RequestClass::Metadata`] budget. Requests of resources or
+/// scopes that compute query results, and are wrapped with [`QueryRateLimiting`], are charged to
+/// the [`RequestClass::Query`] budget, too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestClass {
+    Metadata,
+    Query,
+}
+
+/// Identifies the client whose requests are limited
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClientKey {
+    Session(SessionId),
+    Ip(String),
+}
+
+impl ClientKey {
+    /// The peer address of the request.
+    ///
+    /// Forwarding headers like `X-Forwarded-For` are ignored because they can be forged by clients.
+    pub fn of_peer(req: &ServiceRequest) -> Self {
+        ClientKey::Ip(
+            req.peer_addr()
+                .map(|addr| addr.ip().to_string())
+                .unwrap_or_default(),
+        )
+    }
+
+    /// The session of the request if it carries the token of an existing session.
+    ///
+    /// Unvalidated tokens must not be used since clients could send a new one with each request.
+    pub async fn of_session<C: Context>(req: &ServiceRequest) -> Option<Self> {
+        let session_id = get_token(req.request()).ok()?;
+        let ctx = req.app_data::<web::Data<C>>()?;
+
+        ctx.session_by_id(session_id)
+            .await
+            .ok()
+            .map(|_| ClientKey::Session(session_id))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn full(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(limit.burst),
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, limit: RateLimit, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * limit.requests_per_second).min(f64::from(limit.burst));
+        self.last_refill = now;
+    }
+
+    /// Takes a token or returns the time until the next token is available
+    fn try_acquire(&mut self, limit: RateLimit, now: Instant) -> Result<(), Duration> {
+        self.refill(limit, now);
+
+        if self.tokens >= 1. {
+            self.tokens -= 1.;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1. - self.tokens) / limit.requests_per_second,
+            ))
+        }
+    }
+
+    fn is_full(&self, limit: RateLimit, now: Instant) -> bool {
+        let mut bucket = *self;
+        bucket.refill(limit, now);
+        bucket.tokens >= f64::from(limit.burst)
+    }
+}
+
+struct RateLimiterState {
+    buckets: HashMap<(ClientKey, RequestClass), TokenBucket>,
+    pruning_threshold: usize,
+}
+
+/// Limits the requests of each client with token buckets.
+///
+/// The buckets are shared by all workers of the server.
+pub struct RateLimiter {
+    metadata: RateLimit,
+    query: RateLimit,
+    state: Mutex<RateLimiterState>,
+}
+
+impl RateLimiter {
+    pub fn new(metadata: RateLimit, query: RateLimit) -> Self {
+        Self {
+            metadata,
+            query,
+            state: Mutex::new(RateLimiterState {
+                buckets: HashMap::new(),
+                pruning_threshold: MIN_PRUNING_THRESHOLD,
+            }),
+        }
+    }
+
+    fn limit(&self, class: RequestClass) -> RateLimit {
+        match class {
+            RequestClass::Metadata => self.metadata,
+            RequestClass::Query => self.query,
+        }
+    }
+
+    /// Takes a token from the client's bucket or returns the time until the next request is allowed
+    pub fn check(
+        &self,
+        client: ClientKey,
+        class: RequestClass,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let limit = self.limit(class);
+
+        let mut state = self
+            .state
+            .lock()
+            .expect("rate limiter lock must not be poisoned");
+
+        if state.buckets.len() >= state.pruning_threshold {
+            self.prune(&mut state, now);
+        }
+
+        state
+            .buckets
+            .entry((client, class))
+            .or_insert_with(|| TokenBucket::full(limit, now))
+            .try_acquire(limit, now)
+    }
+
+    /// Removes buckets of clients that were inactive long enough to have a full bucket again.
+    /// If there are still too many buckets, the ones of the least recently active clients are
+    /// removed, too.
+    fn prune(&self, state: &mut RateLimiterState, now: Instant) {
+        state
+            .buckets
+            .retain(|(_, class), bucket| !bucket.is_full(self.limit(*class), now));
+
+        if state.buckets.len() >= MAX_BUCKETS {
+            let mut last_refills = state
+                .buckets
+                .values()
+                .map(|bucket| bucket.last_refill)
+                .collect::<Vec<_>>();
+            let (_, cutoff, _) = last_refills.select_nth_unstable(MAX_BUCKETS / 2);
+            let cutoff = *cutoff;
+
+            state
+                .buckets
+                .retain(|_, bucket| bucket.last_refill > cutoff);
+        }
+
+        state.pruning_threshold =
+            (state.buckets.len() * 2).clamp(MIN_PRUNING_THRESHOLD, MAX_BUCKETS);
+    }
+
+    /// Takes a token from the bucket of each client
+    fn check_clients(
+        &self,
+        clients: &[ClientKey],
+        class: RequestClass,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        for client in clients {
+            self.check(client.clone(), class, now)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The rate limiter of the app and the clients of a request.
+///
+/// It is stored in the request extensions s.t. resources can charge further budgets.
+#[derive(Clone)]
+struct RateLimitedRequest {
+    limiter: Arc<RateLimiter>,
+    clients: Vec<ClientKey>,
+}
+
+impl RateLimitedRequest {
+    /// The client's IP address and, if the request belongs to an existing session, the session
+    async fn new<C: Context>(limiter: Arc<RateLimiter>, req: &ServiceRequest) -> Self {
+        let mut clients = vec![ClientKey::of_peer(req)];
+
+        if let Some(session) = ClientKey::of_session::<C>(req).await {
+            clients.push(session);
+        }
+
+        Self { limiter, clients }
+    }
+
+    fn check(&self, class: RequestClass, now: Instant) -> Result<(), Duration> {
+        self.limiter.check_clients(&self.clients, class, now)
+    }
+}
+
+/// Middleware that rejects requests that exceed the metadata budget with `429 Too Many Requests`
+/// and a `Retry-After` header.
+///
+/// It wraps the app. Sessions are validated with the context `C` of the app.
+pub struct RateLimiting<C> {
+    limiter: Option<Arc<RateLimiter>>,
+    context: PhantomData<fn() -> C>,
+}
+
+impl<C> Clone for RateLimiting<C> {
+    fn clone(&self) -> Self {
+        Self {
+            limiter: self.limiter.clone(),
+            context: PhantomData,
+        }
+    }
+}
+
+impl<C> RateLimiting<C> {
+    pub fn new(limiter: Arc<RateLimiter>) -> Self {
+        Self {
+            limiter: Some(limiter),
+            context: PhantomData,
+        }
+    }
+
+    /// A middleware that lets all requests pass
+    pub fn disabled() -> Self {
+        Self {
+            limiter: None,
+            context: PhantomData,
+        }
+    }
+
+    pub fn from_config() -> crate::error::Result<Self> {
+        let config = config::get_config_element::<config::RateLimiting>()?;
+
+        if !config.enabled {
+            return Ok(Self::disabled());
+        }
+
+        Ok(Self::new(Arc::new(RateLimiter::new(
+            config.metadata,
+            config.query,
+        ))))
+    }
+}
+
+impl<S, B, C> Transform<S, ServiceRequest> for RateLimiting<C>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+    C: Context,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = RateLimitingMiddleware<S, C>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitingMiddleware {
+            service: Rc::new(service),
+            limiter: self.limiter.clone(),
+            context: PhantomData,
+        }))
+    }
+}
+
+pub struct RateLimitingMiddleware<S, C> {
+    service: Rc<S>,
+    limiter: Option<Arc<RateLimiter>>,
+    context: PhantomData<fn() -> C>,
+}
+
+impl<S, B, C> Service<ServiceRequest> for RateLimitingMiddleware<S, C>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+    C: Context,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            if let Some(limiter) = limiter {
+                let request = RateLimitedRequest::new::<C>(limiter, &req).await;
+
+                if let Err(retry_after) = request.check(RequestClass::Metadata, Instant::now()) {
+                    let response = too_many_requests(retry_after);
+                    return Ok(req.into_response(response).map_into_right_body());
+                }
+
+                req.extensions_mut().insert(request);
+            }
+
+            service
+                .call(req)
+                .await
+                .map(ServiceResponse::map_into_left_body)
+        })
+    }
+}
+
+/// Middleware that rejects requests that exceed the query budget with `429 Too Many Requests`
+/// and a `Retry-After` header.
+///
+/// It wraps the resources or scopes that compute query results, e.g., WMS or plots.
+/// Requests are only limited if the app is wrapped with an enabled [`RateLimiting`] middleware.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryRateLimiting;
+
+impl<S, B> Transform<S, ServiceRequest> for QueryRateLimiting
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = QueryRateLimitingMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(QueryRateLimitingMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct QueryRateLimitingMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for QueryRateLimitingMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            let request = req.extensions().get::<RateLimitedRequest>().cloned();
+
+            if let Some(request) = request {
+                if let Err(retry_after) = request.check(RequestClass::Query, Instant::now()) {
+                    let response = too_many_requests(retry_after);
+                    return Ok(req.into_response(response).map_into_right_body());
+                }
+            }
+
+            service
+                .call(req)
+                .await
+                .map(ServiceResponse::map_into_left_body)
+        })
+    }
+}
+
+fn too_many_requests(retry_after: Duration) -> actix_web::HttpResponse {
+    // clients must not retry before the next token is available
+    let retry_after_seconds = (retry_after.as_secs_f64().ceil() as u64).max(1);
+
+    let mut response = Error::TooManyRequests {
+        retry_after_seconds,
+    }
+    .error_response();
+
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_seconds));
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contexts::{InMemoryContext, Session, SimpleContext};
+    use crate::handlers::ErrorResponse;
+    use actix_web::{test, App, HttpResponse};
+    use geoengine_datatypes::util::test::TestDefault;
+    use geoengine_datatypes::util::Identifier;
+
+    const METADATA: RateLimit = RateLimit {
+        requests_per_second: 1.,
+        burst: 2,
+    };
+
+    const QUERY: RateLimit = RateLimit {
+        requests_per_second: 0.5,
+        burst: 1,
+    };
+
+    #[test]
+    fn it_limits_per_client_and_class() {
+        let limiter = RateLimiter::new(METADATA, QUERY);
+        let start = Instant::now();
+
+        let alice_session = SessionId::new();
+        let alice = || ClientKey::Session(alice_session);
+        let bob = || ClientKey::Ip("127.0.0.1".to_string());
+
+        assert!(limiter
+            .check(alice(), RequestClass::Metadata, start)
+            .is_ok());
+        assert!(limiter
+            .check(alice(), RequestClass::Metadata, start)
+            .is_ok());
+        assert_eq!(
+            limiter.check(alice(), RequestClass::Metadata, start),
+            Err(Duration::from_secs(1))
+        );
+
+        // separate budgets
+        assert!(limiter.check(alice(), RequestClass::Query, start).is_ok());
+        assert_eq!(
+            limiter.check(alice(), RequestClass::Query, start),
+            Err(Duration::from_secs(2))
+        );
+        assert!(limiter.check(bob(), RequestClass::Metadata, start).is_ok());
+
+        // refill
+        let later = start + Duration::from_millis(1500);
+        assert!(limiter
+            .check(alice(), RequestClass::Metadata, later)
+            .is_ok());
+        assert_eq!(
+            limiter.check(alice(), RequestClass::Query, later),
+            Err(Duration::from_millis(500))
+        );
+    }
+
+    #[test]
+    fn it_prunes_inactive_clients() {
+        let limiter = RateLimiter::new(METADATA, QUERY);
+        let start = Instant::now();
+
+        for i in 0..MIN_PRUNING_THRESHOLD {
+            limiter
+                .check(ClientKey::Ip(i.to_string()), RequestClass::Metadata, start)
+                .unwrap();
+        }
+
+        limiter
+            .check(
+                ClientKey::Ip("new".to_string()),
+                RequestClass::Metadata,
+                start + Duration::from_secs(10),
+            )
+            .unwrap();
+
+        assert_eq!(limiter.state.lock().unwrap().buckets.len(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn it_rejects_requests_with_retry_after() {
+        let limiter = Arc::new(RateLimiter::new(METADATA, QUERY));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(InMemoryContext::test_default()))
+                .wrap(RateLimiting::<InMemoryContext>::new(limiter))
+                .service(
+                    web::resource("/wms/{id}")
+                        .wrap(QueryRateLimiting)
+                        .route(web::get().to(HttpResponse::Ok)),
+                ),
+        )
+        .await;
+
+        let request = || {
+            test::TestRequest::get()
+                .uri("/wms/123")
+                .append_header((header::AUTHORIZATION, "Bearer token"))
+                .to_request()
+        };
+
+        let res = test::call_service(&app, request()).await;
+        assert_eq!(res.status(), 200);
+
+        let res = test::call_service(&app, request()).await;
+        assert_eq!(
+            res.headers().get(header::RETRY_AFTER),
+            Some(&HeaderValue::from_static("2"))
+        );
+        ErrorResponse::assert(
+            res.map_into_boxed_body(),
+            429,
+            "TooManyRequests",
+            "Too many requests, retry after 2 seconds",
+        )
+        .await;
+    }
+
+    #[actix_rt::test]
+    async fn it_charges_the_ip_for_unknown_sessions() {
+        let limiter = Arc::new(RateLimiter::new(METADATA, QUERY));
+        let ctx = InMemoryContext::test_default();
+        let session_id = ctx.default_session_ref().await.id();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(ctx))
+                .wrap(RateLimiting::<InMemoryContext>::new(limiter.clone()))
+                .service(
+                    web::resource("/wms/{id}")
+                        .wrap(QueryRateLimiting)
+                        .route(web::get().to(HttpResponse::Ok)),
+                ),
+        )
+        .await;
+
+        let request = |token: String| {
+            test::TestRequest::get()
+                .uri("/wms/123")
+                .append_header((header::AUTHORIZATION, format!("Bearer {}", token)))
+                .to_request()
+        };
+
+        let res = test::call_service(&app, request(session_id.to_string())).await;
+        assert_eq!(res.status(), 200);
+
+        // a new random token does not get a new budget
+        let res = test::call_service(&app, request(SessionId::new().to_string())).await;
+        assert_eq!(res.status(), 429);
+
+        let buckets = limiter.state.lock().unwrap().buckets.clone();
+        assert_eq!(buckets.len(), 4);
+        assert!(buckets.contains_key(&(ClientKey::Session(session_id), RequestClass::Query)));
+        assert!(buckets.contains_key(&(ClientKey::Ip(String::new()), RequestClass::Query)));
+    }
+
+    #[actix_rt::test]
+    async fn it_charges_only_query_resources_to_the_query_budget() {
+        let limiter = Arc::new(RateLimiter::new(METADATA, QUERY));
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(InMemoryContext::test_default()))
+                .wrap(RateLimiting::<InMemoryContext>::new(limiter.clone()))
+                .service(
+                    web::scope("/workflow")
+                        .service(web::resource("/{id}").route(web::get().to(HttpResponse::Ok)))
+                        .service(
+                            web::resource("/{id}/symbology")
+                                .wrap(QueryRateLimiting)
+                                .route(web::get().to(HttpResponse::Ok)),
+                        ),
+                ),
+        )
+        .await;
+
+        let request = |uri: &str| test::TestRequest::get().uri(uri).to_request();
+
+        let res = test::call_service(&app, request("/workflow/123")).await;
+        assert_eq!(res.status(), 200);
+        assert!(!limiter
+            .state
+            .lock()
+            .unwrap()
+            .buckets
+            .contains_key(&(ClientKey::Ip(String::new()), RequestClass::Query)));
+
+        let res = test::call_service(&app, request("/workflow/123/symbology")).await;
+        assert_eq!(res.status(), 200);
+        assert!(limiter
+            .state
+            .lock()
+            .unwrap()
+            .buckets
+            .contains_key(&(ClientKey::Ip(String::new()), RequestClass::Query)));
+
+        // the metadata budget is exhausted
+        let res = test::call_service(&app, request("/workflow/123")).await;
+        assert_eq!(res.status(), 429);
+    }
+
+    #[test]
+    fn it_bounds_the_number_of_buckets() {
+        let limiter = RateLimiter::new(METADATA, QUERY);
+        let start = Instant::now();
+
+        for i in 0..=MAX_BUCKETS {
+            // the buckets are drained, so they are never full
+            for _ in 0..3 {
+                let _ = limiter.check(
+                    ClientKey::Ip(i.to_string()),
+                    RequestClass::Metadata,
+                    start + Duration::from_nanos(i as u64),
+                );
+            }
+        }
+
+        assert!(limiter.state.lock().unwrap().buckets.len() <= MAX_BUCKETS);
+    }
+}
@@ -0,0 +1,271 @@
+use std::io::{self, BufReader, Read};
+
+use actix_http::error::PayloadError;
+use actix_http::header;
+use actix_http::Payload;
+use actix_web::error::JsonPayloadError;
+use actix_web::{web, FromRequest, HttpMessage, HttpRequest};
+use bytes::Bytes;
+use futures::future::LocalBoxFuture;
+use futures::{FutureExt, StreamExt};
+use serde::de::DeserializeOwned;
+use tokio::sync::mpsc;
+
+use crate::error::Result;
+use crate::server::json_error;
+use crate::util::config::{self, get_config_element};
+
+/// The number of chunks of the payload that are buffered until the parser consumed them
+const CHANNEL_CAPACITY: usize = 16;
+
+/// Selects the limit of the `body_limits` settings that applies to the request bodies of an endpoint.
+///
+/// It is registered as app data of a resource or scope and defaults to [`BodyLimit::Json`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyLimit {
+    Json,
+    Workflow,
+    Restore,
+}
+
+impl BodyLimit {
+    /// The maximum size of a request body in bytes
+    pub fn bytes(self) -> Result<usize> {
+        let limits = get_config_element::<config::BodyLimits>()?;
+
+        Ok(match self {
+            BodyLimit::Json => limits.json,
+            BodyLimit::Workflow => limits.workflow,
+            BodyLimit::Restore => limits.restore,
+        })
+    }
+}
+
+/// A JSON request body like `web::Json` that is deserialized while its chunks arrive.
+///
+/// The body is never buffered as a whole, which avoids memory spikes for large payloads like
+/// workflows with inline GeoJSON or backups. Errors are rendered like the ones of `web::Json`.
+#[derive(Debug)]
+pub struct StreamingJson<T>(pub T);
+
+impl<T> StreamingJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::ops::Deref for StreamingJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> StreamingJson<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    /// Deserializes the payload of a request inside of a handler, e.g., after it was authorized.
+    pub async fn from_payload(req: &HttpRequest, payload: web::Payload) -> actix_web::Result<Self> {
+        Self::deserialize(req, payload.into_inner()).await
+    }
+
+    async fn deserialize(req: &HttpRequest, payload: Payload) -> actix_web::Result<Self> {
+        let is_json = req.mime_type().ok().flatten().map_or(false, |mime| {
+            mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON)
+        });
+
+        if !is_json {
+            return Err(json_error(JsonPayloadError::ContentType));
+        }
+
+        let limit = req
+            .app_data::<BodyLimit>()
+            .copied()
+            .unwrap_or(BodyLimit::Json)
+            .bytes()?;
+
+        let length = req
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|length| length.to_str().ok())
+            .and_then(|length| length.parse::<usize>().ok());
+
+        match length {
+            Some(length) if length > limit => {
+                Err(json_error(JsonPayloadError::OverflowKnownLength {
+                    length,
+                    limit,
+                }))
+            }
+            _ => deserialize_payload(payload, limit)
+                .await
+                .map(StreamingJson)
+                .map_err(json_error),
+        }
+    }
+}
+
+impl<T> FromRequest for StreamingJson<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        let payload = payload.take();
+
+        async move { Self::deserialize(&req, payload).await }.boxed_local()
+    }
+}
+
+/// Passes the chunks of the payload to a parser on a blocking thread, s.t. only the chunks that
+/// were not parsed yet are kept in memory
+async fn deserialize_payload<T>(mut payload: Payload, limit: usize) -> Result<T, JsonPayloadError>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+
+    let parser = crate::util::spawn_blocking(move || {
+        serde_json::from_reader::<_, T>(BufReader::new(ChunkReader {
+            receiver,
+            chunk: Bytes::new(),
+        }))
+    });
+
+    let mut size = 0;
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(JsonPayloadError::Payload)?;
+
+        size += chunk.len();
+        if size > limit {
+            return Err(JsonPayloadError::Overflow { limit });
+        }
+
+        if sender.send(chunk).await.is_err() {
+            // the parser stopped early, e.g., because of a syntax error
+            break;
+        }
+    }
+
+    // signals the end of the payload to the parser
+    drop(sender);
+
+    parser
+        .await
+        .map_err(|error| {
+            JsonPayloadError::Payload(PayloadError::Io(io::Error::new(
+                io::ErrorKind::Other,
+                error,
+            )))
+        })?
+        .map_err(JsonPayloadError::Deserialize)
+}
+
+/// Reads the chunks of a payload that are received from a channel
+struct ChunkReader {
+    receiver: mpsc::Receiver<Bytes>,
+    chunk: Bytes,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.chunk.is_empty() {
+            match self.receiver.blocking_recv() {
+                Some(chunk) => self.chunk = chunk,
+                None => return Ok(0),
+            }
+        }
+
+        let length = buf.len().min(self.chunk.len());
+        buf[..length].copy_from_slice(&self.chunk[..length]);
+        self.chunk = self.chunk.slice(length..);
+
+        Ok(length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Foo {
+        bar: Vec<u32>,
+    }
+
+    #[test]
+    fn it_reads_chunks() {
+        let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+        sender
+            .try_send(Bytes::from_static(b"{\"bar\": [1,"))
+            .unwrap();
+        sender.try_send(Bytes::new()).unwrap();
+        sender.try_send(Bytes::from_static(b" 2, 3]}")).unwrap();
+        drop(sender);
+
+        let foo: Foo = serde_json::from_reader(ChunkReader {
+            receiver,
+            chunk: Bytes::new(),
+        })
+        .unwrap();
+
+        assert_eq!(foo, Foo { bar: vec![1, 2, 3] });
+    }
+
+    #[tokio::test]
+    async fn it_deserializes_payloads() {
+        let (req, mut payload) = TestRequest::post()
+            .insert_header((header::CONTENT_TYPE, mime::APPLICATION_JSON))
+            .set_payload("{\"bar\": [1, 2, 3]}")
+            .to_http_parts();
+
+        let foo = StreamingJson::<Foo>::from_request(&req, &mut payload)
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(foo, Foo { bar: vec![1, 2, 3] });
+    }
+
+    #[tokio::test]
+    async fn it_rejects_payloads_that_exceed_the_limit() {
+        let limit = BodyLimit::Json.bytes().unwrap();
+
+        let (req, mut payload) = TestRequest::post()
+            .insert_header((header::CONTENT_TYPE, mime::APPLICATION_JSON))
+            .set_payload(vec![b' '; limit + 1])
+            .to_http_parts();
+
+        let error = StreamingJson::<Foo>::from_request(&req, &mut payload)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error.as_response_error().status_code(),
+            actix_web::http::StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+
+    #[tokio::test]
+    async fn it_requires_json() {
+        let (req, mut payload) = TestRequest::post()
+            .set_payload("{\"bar\": []}")
+            .to_http_parts();
+
+        let error = StreamingJson::<Foo>::from_request(&req, &mut payload)
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error.as_response_error().status_code(),
+            actix_web::http::StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+    }
+}
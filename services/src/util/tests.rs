@@ -21,7 +21,7 @@ use crate::{
     handlers,
 };
 use actix_web::dev::ServiceResponse;
-use actix_web::{http, http::header, http::Method, middleware, test, web, App};
+use actix_web::{http, http::header, http::Method, middleware, test, web, App, HttpServer};
 use flexi_logger::Logger;
 use geoengine_datatypes::dataset::DatasetId;
 use geoengine_datatypes::operations::image::Colorizer;
@@ -31,6 +31,7 @@ use geoengine_operators::source::{GdalSource, GdalSourceParameters};
 use geoengine_operators::util::gdal::create_ndvi_meta_data;
 use std::io::Write;
 use std::path::PathBuf;
+use url::Url;
 
 #[allow(clippy::missing_panics_doc)]
 pub async fn create_project_helper<C: SimpleContext>(ctx: &C) -> (SimpleSession, ProjectId) {
@@ -122,6 +123,7 @@ pub async fn add_ndvi_to_datasets(ctx: &InMemoryContext) -> DatasetId {
                 citation: "Sample Citation".to_owned(),
                 license: "Sample License".to_owned(),
                 uri: "http://example.org/".to_owned(),
+                version: None,
             }),
         },
         meta_data: MetaDataDefinition::GdalMetaDataRegular(create_ndvi_meta_data()),
@@ -182,6 +184,24 @@ where
     check_allowed_http_methods2(test_helper, allowed_methods, |res| res)
 }
 
+/// Configures all routes of the API like the server does
+pub fn configure_test_routes<C: SimpleContext>(cfg: &mut web::ServiceConfig) {
+    configure_extractors(cfg);
+    handlers::backup::init_backup_routes::<C>(cfg);
+    handlers::datasets::init_dataset_routes::<C>(cfg);
+    handlers::info::init_info_routes::<C>(cfg);
+    handlers::plots::init_plot_routes::<C>(cfg);
+    handlers::projects::init_project_routes::<C>(cfg);
+    handlers::session::init_session_routes::<C>(cfg);
+    handlers::spatial_references::init_spatial_reference_routes::<C>(cfg);
+    handlers::upload::init_upload_routes::<C>(cfg);
+    handlers::wcs::init_wcs_routes::<C>(cfg);
+    handlers::wfs::init_wfs_routes::<C>(cfg);
+    handlers::wms::init_wms_routes::<C>(cfg);
+    handlers::workers::init_worker_routes::<C>(cfg);
+    handlers::workflows::init_workflow_routes::<C>(cfg);
+}
+
 pub async fn send_test_request<C: SimpleContext>(
     req: test::TestRequest,
     ctx: C,
@@ -195,17 +215,7 @@ pub async fn send_test_request<C: SimpleContext>(
                     .handler(http::StatusCode::METHOD_NOT_ALLOWED, render_405),
             )
             .wrap(middleware::NormalizePath::trim())
-            .configure(configure_extractors)
-            .configure(handlers::datasets::init_dataset_routes::<C>)
-            .configure(handlers::plots::init_plot_routes::<C>)
-            .configure(handlers::projects::init_project_routes::<C>)
-            .configure(handlers::session::init_session_routes::<C>)
-            .configure(handlers::spatial_references::init_spatial_reference_routes::<C>)
-            .configure(handlers::upload::init_upload_routes::<C>)
-            .configure(handlers::wcs::init_wcs_routes::<C>)
-            .configure(handlers::wfs::init_wfs_routes::<C>)
-            .configure(handlers::wms::init_wms_routes::<C>)
-            .configure(handlers::workflows::init_workflow_routes::<C>),
+            .configure(configure_test_routes::<C>),
     )
     .await;
     test::call_service(&app, req.to_request())
@@ -213,6 +223,35 @@ pub async fn send_test_request<C: SimpleContext>(
         .map_into_boxed_body()
 }
 
+/// Starts a server with all routes on a random local port and returns its base url.
+///
+/// The server runs until the (actix) runtime of the test is shut down.
+#[allow(clippy::missing_panics_doc)]
+pub fn start_test_server<C: SimpleContext>(ctx: C) -> Url {
+    let ctx = web::Data::new(ctx);
+
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(ctx.clone())
+            .wrap(
+                middleware::ErrorHandlers::default()
+                    .handler(http::StatusCode::NOT_FOUND, render_404)
+                    .handler(http::StatusCode::METHOD_NOT_ALLOWED, render_405),
+            )
+            .wrap(middleware::NormalizePath::trim())
+            .configure(configure_test_routes::<C>)
+    })
+    .workers(1)
+    .bind(("127.0.0.1", 0))
+    .expect("binding to a local port should succeed");
+
+    let address = server.addrs()[0];
+
+    actix_rt::spawn(server.run());
+
+    Url::parse(&format!("http://{}/", address)).expect("address should be a valid url")
+}
+
 pub async fn read_body_string(res: ServiceResponse) -> String {
     let body = test::read_body(res).await;
     String::from_utf8(body.to_vec()).expect("Body is utf 8 string")
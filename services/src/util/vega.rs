@@ -0,0 +1,186 @@
+use std::process::Stdio;
+use std::time::Duration;
+
+use snafu::ResultExt;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+
+use crate::error::{self, Error, Result};
+use crate::util::config::{get_config_element, Plots};
+
+/// The size of rendered plots whose spec has no fixed width or height, e.g., `"container"`
+const DEFAULT_WIDTH: u32 = 640;
+const DEFAULT_HEIGHT: u32 = 480;
+
+/// Extracts the data values of a Vega-Lite `spec`
+pub fn vega_lite_data(spec: &serde_json::Value) -> Option<serde_json::Value> {
+    let data = spec.get("data")?;
+
+    Some(data.get("values").unwrap_or(data).clone())
+}
+
+/// Sets the size of a Vega-Lite `spec` for rendering it to an image.
+///
+/// The size is only changed if it is requested or the spec has no fixed size.
+pub fn set_vega_lite_size(spec: &mut serde_json::Value, width: Option<u32>, height: Option<u32>) {
+    for (key, requested, default) in [
+        ("width", width, DEFAULT_WIDTH),
+        ("height", height, DEFAULT_HEIGHT),
+    ] {
+        let fixed = spec.get(key).map_or(false, serde_json::Value::is_number);
+
+        match requested {
+            Some(size) => spec[key] = size.into(),
+            None if !fixed => spec[key] = default.into(),
+            None => {}
+        }
+    }
+}
+
+/// Renders a Vega-Lite `spec` as PNG using the renderer of the [`Plots`] config
+pub async fn render_vega_lite_png(spec: &serde_json::Value) -> Result<Vec<u8>> {
+    let config = get_config_element::<Plots>()?;
+
+    render_png(
+        &config.vega_lite_renderer,
+        spec,
+        Duration::from_secs(config.vega_lite_renderer_timeout_seconds),
+    )
+    .await
+}
+
+/// Runs the `renderer` command and kills it if it does not finish within the `timeout`
+async fn render_png(
+    renderer: &[String],
+    spec: &serde_json::Value,
+    timeout: Duration,
+) -> Result<Vec<u8>> {
+    let (program, args) = renderer
+        .split_first()
+        .ok_or(Error::NoVegaLiteRendererConfigured)?;
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context(error::Io)?;
+
+    let spec = serde_json::to_vec(spec).context(error::SerdeJson)?;
+    let stdin = child.stdin.take();
+    let mut stdout = child.stdout.take();
+    let mut stderr = child.stderr.take();
+
+    let write_spec = async {
+        if let Some(mut stdin) = stdin {
+            stdin.write_all(&spec).await?;
+            // closing stdin signals the end of the spec
+        }
+        Ok::<_, std::io::Error>(())
+    };
+
+    // the spec is written while reading the output, s.t. large outputs cannot block the renderer
+    let render = async {
+        let mut image = Vec::new();
+        let mut messages = Vec::new();
+        let (_, status, _, _) = tokio::try_join!(
+            write_spec,
+            child.wait(),
+            read_to_end(stdout.as_mut(), &mut image),
+            read_to_end(stderr.as_mut(), &mut messages),
+        )?;
+
+        Ok::<_, std::io::Error>((status, image, messages))
+    };
+
+    let output = tokio::time::timeout(timeout, render).await;
+
+    let (status, image, messages) = match output {
+        Ok(output) => output.context(error::Io)?,
+        Err(_) => {
+            child.kill().await.context(error::Io)?;
+            return Err(Error::VegaLiteRendererTimeout {
+                seconds: timeout.as_secs(),
+            });
+        }
+    };
+
+    if !status.success() || image.is_empty() {
+        return Err(Error::VegaLiteRenderer {
+            details: String::from_utf8_lossy(&messages).trim().to_string(),
+        });
+    }
+
+    Ok(image)
+}
+
+async fn read_to_end<R: AsyncRead + Unpin>(
+    reader: Option<&mut R>,
+    buffer: &mut Vec<u8>,
+) -> std::io::Result<usize> {
+    match reader {
+        Some(reader) => reader.read_to_end(buffer).await,
+        None => Ok(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_extracts_data() {
+        assert_eq!(
+            vega_lite_data(&json!({
+                "data": { "values": [{ "a": 1 }] },
+                "mark": "bar"
+            })),
+            Some(json!([{ "a": 1 }]))
+        );
+        assert_eq!(
+            vega_lite_data(&json!({ "data": [{ "a": 1 }] })),
+            Some(json!([{ "a": 1 }]))
+        );
+        assert_eq!(vega_lite_data(&json!({ "mark": "bar" })), None);
+    }
+
+    #[test]
+    fn it_sets_sizes() {
+        let mut spec = json!({ "width": "container", "height": 200 });
+        set_vega_lite_size(&mut spec, None, None);
+        assert_eq!(spec, json!({ "width": 640, "height": 200 }));
+
+        set_vega_lite_size(&mut spec, Some(100), Some(50));
+        assert_eq!(spec, json!({ "width": 100, "height": 50 }));
+    }
+
+    #[tokio::test]
+    async fn it_kills_slow_renderers() {
+        let renderer = vec!["sleep".to_string(), "10".to_string()];
+
+        let result = render_png(&renderer, &json!({}), Duration::from_millis(100)).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::VegaLiteRendererTimeout { seconds: 0 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_returns_the_output_of_renderers() {
+        let renderer = vec!["cat".to_string()];
+
+        let result = render_png(
+            &renderer,
+            &json!({ "mark": "bar" }),
+            Duration::from_secs(10),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(result, br#"{"mark":"bar"}"#.to_vec());
+    }
+}
@@ -0,0 +1,507 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::{ready, Ready};
+
+use std::time::SystemTime;
+
+use actix_http::header::{HeaderValue, CACHE_CONTROL};
+use actix_http::Payload;
+use actix_web::http::header::{
+    ETag, EntityTag, Header, HttpDate, IfModifiedSince, IfNoneMatch, LastModified,
+};
+use actix_web::{FromRequest, HttpRequest, HttpResponse, HttpResponseBuilder};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use geoengine_datatypes::dataset::DatasetId;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::error;
+use crate::util::config::{self, get_config_element};
+use crate::workflows::workflow::WorkflowId;
+
+/// Response header that tells whether a result was served from the cache (`HIT`) or computed (`MISS`)
+pub const CACHE_STATUS_HEADER: &str = "X-Cache";
+
+/// Identifies a cached result by its workflow and a serialized form of the query
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    pub workflow: WorkflowId,
+    pub query: String,
+}
+
+/// An encoded query result, e.g., a PNG image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResult {
+    pub content_type: String,
+    pub bytes: Bytes,
+}
+
+/// Information about a cache entry for the cache inspection API
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntryInfo {
+    pub query: String,
+    pub datasets: Vec<DatasetId>,
+    pub size_in_bytes: usize,
+    pub created: DateTime<Utc>,
+}
+
+/// Response of the cache invalidation API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheInvalidation {
+    pub invalidated_entries: usize,
+}
+
+struct CacheEntry {
+    result: CachedResult,
+    datasets: Vec<DatasetId>,
+    created: DateTime<Utc>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, CacheEntry>,
+    insertion_order: VecDeque<CacheKey>,
+    size_in_bytes: usize,
+}
+
+impl CacheState {
+    fn remove(&mut self, key: &CacheKey) -> Option<CacheEntry> {
+        let entry = self.entries.remove(key)?;
+        self.size_in_bytes -= entry.result.bytes.len();
+        Some(entry)
+    }
+
+    fn remove_where<F>(&mut self, predicate: F) -> usize
+    where
+        F: Fn(&CacheKey, &CacheEntry) -> bool,
+    {
+        let keys: Vec<CacheKey> = self
+            .entries
+            .iter()
+            .filter(|(key, entry)| predicate(key, entry))
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        for key in &keys {
+            self.remove(key);
+        }
+
+        let entries = &self.entries;
+        self.insertion_order.retain(|key| entries.contains_key(key));
+
+        keys.len()
+    }
+}
+
+/// A size bounded cache for the results of deterministic workflows.
+///
+/// If the cache is full, the oldest entries are evicted first.
+/// Entries are tagged with the datasets they were computed from, s.t. they can be invalidated
+/// if one of these datasets changes.
+pub struct ResultCache {
+    capacity_in_bytes: usize,
+    state: Mutex<CacheState>,
+}
+
+impl ResultCache {
+    /// Creates a cache that holds at most `capacity_in_bytes` bytes of results.
+    /// A capacity of zero disables the cache.
+    pub fn new(capacity_in_bytes: usize) -> Self {
+        Self {
+            capacity_in_bytes,
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    pub fn from_config() -> Self {
+        let config = get_config_element::<config::Cache>().expect("config error");
+
+        if config.enabled {
+            Self::new(config.size_in_mb * 1024 * 1024)
+        } else {
+            Self::new(0)
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity_in_bytes > 0
+    }
+
+    pub async fn get(&self, key: &CacheKey) -> Option<CachedResult> {
+        self.state
+            .lock()
+            .await
+            .entries
+            .get(key)
+            .map(|entry| entry.result.clone())
+    }
+
+    /// Inserts a result into the cache and evicts old entries if necessary.
+    /// Results that are larger than the cache are not stored.
+    pub async fn insert(&self, key: CacheKey, result: CachedResult, datasets: Vec<DatasetId>) {
+        let size = result.bytes.len();
+        if size > self.capacity_in_bytes {
+            return;
+        }
+
+        let mut state = self.state.lock().await;
+
+        if state.remove(&key).is_some() {
+            state.insertion_order.retain(|k| k != &key);
+        }
+
+        while state.size_in_bytes + size > self.capacity_in_bytes {
+            let oldest = match state.insertion_order.pop_front() {
+                Some(oldest) => oldest,
+                None => break,
+            };
+            state.remove(&oldest);
+        }
+
+        state.size_in_bytes += size;
+        state.insertion_order.push_back(key.clone());
+        state.entries.insert(
+            key,
+            CacheEntry {
+                result,
+                datasets,
+                created: Utc::now(),
+            },
+        );
+    }
+
+    /// Lists the entries of a workflow, oldest first
+    pub async fn entries(&self, workflow: &WorkflowId) -> Vec<CacheEntryInfo> {
+        let state = self.state.lock().await;
+
+        state
+            .insertion_order
+            .iter()
+            .filter(|key| &key.workflow == workflow)
+            .filter_map(|key| {
+                state.entries.get(key).map(|entry| CacheEntryInfo {
+                    query: key.query.clone(),
+                    datasets: entry.datasets.clone(),
+                    size_in_bytes: entry.result.bytes.len(),
+                    created: entry.created,
+                })
+            })
+            .collect()
+    }
+
+    /// Removes all entries of a workflow and returns their number
+    pub async fn invalidate_workflow(&self, workflow: &WorkflowId) -> usize {
+        self.state
+            .lock()
+            .await
+            .remove_where(|key, _| &key.workflow == workflow)
+    }
+
+    /// Removes all entries that were computed from a dataset and returns their number
+    pub async fn invalidate_dataset(&self, dataset: &DatasetId) -> usize {
+        self.state
+            .lock()
+            .await
+            .remove_where(|_, entry| entry.datasets.contains(dataset))
+    }
+}
+
+/// Caching hints of a client, given by the `Cache-Control` request header.
+///
+/// `no-cache` forces a recomputation of the result, `no-store` prevents the result from being cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheHint {
+    pub read: bool,
+    pub store: bool,
+}
+
+impl Default for CacheHint {
+    fn default() -> Self {
+        Self {
+            read: true,
+            store: true,
+        }
+    }
+}
+
+impl CacheHint {
+    fn from_header_values<'h, I>(values: I) -> Self
+    where
+        I: IntoIterator<Item = &'h HeaderValue>,
+    {
+        let mut hint = Self::default();
+
+        let directives = values
+            .into_iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|directive| directive.trim().to_ascii_lowercase());
+
+        for directive in directives {
+            match directive.as_str() {
+                "no-cache" | "max-age=0" => hint.read = false,
+                "no-store" => hint.store = false,
+                _ => (),
+            }
+        }
+
+        hint
+    }
+}
+
+impl FromRequest for CacheHint {
+    type Error = error::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(Ok(Self::from_header_values(
+            req.headers().get_all(CACHE_CONTROL),
+        )))
+    }
+}
+
+/// Validators of a deterministic result, s.t. clients can revalidate their copy of it with
+/// conditional requests instead of downloading it again.
+///
+/// The entity tag is derived from the workflow, the query and the time of the last change of the
+/// datasets that the result is computed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultValidators {
+    pub etag: EntityTag,
+    pub last_modified: DateTime<Utc>,
+}
+
+impl ResultValidators {
+    pub fn new(key: &CacheKey, last_modified: DateTime<Utc>) -> Self {
+        let tag = Uuid::new_v5(
+            &Uuid::NAMESPACE_OID,
+            format!(
+                "{}|{}|{}",
+                key.workflow,
+                key.query,
+                last_modified.timestamp_millis()
+            )
+            .as_bytes(),
+        );
+
+        Self {
+            // the representation differs by content encoding, so the tag is weak
+            etag: EntityTag::new_weak(tag.to_simple().to_string()),
+            last_modified,
+        }
+    }
+
+    /// Whether the client's copy of the result is still up to date.
+    /// `If-None-Match` takes precedence over `If-Modified-Since`.
+    pub fn is_fresh(&self, conditions: &ConditionalRequest) -> bool {
+        match (&conditions.if_none_match, conditions.if_modified_since) {
+            (Some(IfNoneMatch::Any), _) => true,
+            (Some(IfNoneMatch::Items(etags)), _) => {
+                etags.iter().any(|etag| etag.weak_eq(&self.etag))
+            }
+            // HTTP dates have a precision of seconds
+            (None, Some(since)) => self.last_modified.timestamp() <= since.timestamp(),
+            (None, None) => false,
+        }
+    }
+
+    pub fn insert_headers(&self, response: &mut HttpResponseBuilder) {
+        response
+            .insert_header(ETag(self.etag.clone()))
+            .insert_header(LastModified(HttpDate::from(SystemTime::from(
+                self.last_modified,
+            ))));
+    }
+
+    pub fn not_modified(&self) -> HttpResponse {
+        let mut response = HttpResponse::NotModified();
+        self.insert_headers(&mut response);
+        response.finish()
+    }
+}
+
+/// The `If-None-Match` and `If-Modified-Since` headers of a conditional request.
+/// Malformed headers are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct ConditionalRequest {
+    pub if_none_match: Option<IfNoneMatch>,
+    pub if_modified_since: Option<DateTime<Utc>>,
+}
+
+impl FromRequest for ConditionalRequest {
+    type Error = error::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let if_none_match = IfNoneMatch::parse(req).ok();
+
+        let if_modified_since = IfModifiedSince::parse(req)
+            .ok()
+            .map(|IfModifiedSince(date)| SystemTime::from(date).into());
+
+        ready(Ok(Self {
+            if_none_match,
+            if_modified_since,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geoengine_datatypes::dataset::InternalDatasetId;
+    use geoengine_datatypes::util::Identifier;
+
+    fn result(size: usize) -> CachedResult {
+        CachedResult {
+            content_type: "image/png".to_string(),
+            bytes: Bytes::from(vec![0; size]),
+        }
+    }
+
+    fn key(workflow: WorkflowId, query: &str) -> CacheKey {
+        CacheKey {
+            workflow,
+            query: query.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_evicts_oldest_entries() {
+        let cache = ResultCache::new(10);
+        let workflow = WorkflowId::new();
+
+        cache.insert(key(workflow, "a"), result(4), vec![]).await;
+        cache.insert(key(workflow, "b"), result(4), vec![]).await;
+        cache.insert(key(workflow, "c"), result(4), vec![]).await;
+
+        assert!(cache.get(&key(workflow, "a")).await.is_none());
+        assert_eq!(cache.get(&key(workflow, "b")).await, Some(result(4)));
+        assert_eq!(cache.get(&key(workflow, "c")).await, Some(result(4)));
+
+        // too large
+        cache.insert(key(workflow, "d"), result(11), vec![]).await;
+        assert!(cache.get(&key(workflow, "d")).await.is_none());
+
+        let queries: Vec<String> = cache
+            .entries(&workflow)
+            .await
+            .into_iter()
+            .map(|entry| entry.query)
+            .collect();
+        assert_eq!(queries, vec!["b".to_string(), "c".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn it_invalidates() {
+        let cache = ResultCache::new(100);
+        let workflow_a = WorkflowId::new();
+        let workflow_b = WorkflowId::new();
+        let dataset: DatasetId = InternalDatasetId::new().into();
+
+        cache
+            .insert(key(workflow_a, "1"), result(1), vec![dataset.clone()])
+            .await;
+        cache.insert(key(workflow_a, "2"), result(1), vec![]).await;
+        cache
+            .insert(key(workflow_b, "1"), result(1), vec![dataset.clone()])
+            .await;
+
+        assert_eq!(cache.invalidate_dataset(&dataset).await, 2);
+        assert_eq!(cache.entries(&workflow_a).await.len(), 1);
+        assert!(cache.entries(&workflow_b).await.is_empty());
+
+        assert_eq!(cache.invalidate_workflow(&workflow_a).await, 1);
+        assert!(cache.entries(&workflow_a).await.is_empty());
+
+        // the freed space can be used again
+        cache
+            .insert(key(workflow_a, "3"), result(100), vec![])
+            .await;
+        assert!(cache.get(&key(workflow_a, "3")).await.is_some());
+    }
+
+    #[test]
+    fn it_validates_conditional_requests() {
+        let workflow = WorkflowId::new();
+        let last_modified = DateTime::parse_from_rfc3339("2014-01-01T12:00:00.5Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let validators = ResultValidators::new(&key(workflow, "a"), last_modified);
+
+        assert_eq!(
+            validators,
+            ResultValidators::new(&key(workflow, "a"), last_modified)
+        );
+        assert_ne!(
+            validators.etag,
+            ResultValidators::new(&key(workflow, "b"), last_modified).etag
+        );
+        assert_ne!(
+            validators.etag,
+            ResultValidators::new(&key(workflow, "a"), Utc::now()).etag
+        );
+
+        let matching_etag = ConditionalRequest {
+            if_none_match: Some(IfNoneMatch::Items(vec![
+                EntityTag::new_strong("foo".to_string()),
+                EntityTag::new_strong(validators.etag.tag().to_string()),
+            ])),
+            // `If-None-Match` takes precedence
+            if_modified_since: Some(last_modified - chrono::Duration::days(1)),
+        };
+        assert!(validators.is_fresh(&matching_etag));
+
+        let other_etag = ConditionalRequest {
+            if_none_match: Some(IfNoneMatch::Items(vec![EntityTag::new_weak(
+                "foo".to_string(),
+            )])),
+            if_modified_since: None,
+        };
+        assert!(!validators.is_fresh(&other_etag));
+
+        let same_second = ConditionalRequest {
+            if_none_match: None,
+            if_modified_since: Some(last_modified - chrono::Duration::milliseconds(500)),
+        };
+        assert!(validators.is_fresh(&same_second));
+
+        let earlier = ConditionalRequest {
+            if_none_match: None,
+            if_modified_since: Some(last_modified - chrono::Duration::seconds(1)),
+        };
+        assert!(!validators.is_fresh(&earlier));
+
+        assert!(!validators.is_fresh(&ConditionalRequest::default()));
+
+        let response = validators.not_modified();
+        assert_eq!(response.status(), actix_web::http::StatusCode::NOT_MODIFIED);
+        assert_eq!(
+            response.headers().get(ETag::name()).unwrap(),
+            &validators.etag.to_string()
+        );
+    }
+
+    #[test]
+    fn it_parses_cache_control() {
+        assert_eq!(CacheHint::from_header_values(&[]), CacheHint::default());
+        assert_eq!(
+            CacheHint::from_header_values(&[HeaderValue::from_static("No-Cache")]),
+            CacheHint {
+                read: false,
+                store: true
+            }
+        );
+        assert_eq!(
+            CacheHint::from_header_values(&[HeaderValue::from_static("no-cache, no-store")]),
+            CacheHint {
+                read: false,
+                store: false
+            }
+        );
+    }
+}
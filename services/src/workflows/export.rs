@@ -0,0 +1,412 @@
+//! Stored results of workflow exports, e.g., `GeoTiff`s, that are downloaded via expiring URLs.
+//!
+//! Artifacts are stored in the object storage below `exports/`.
+//! Download URLs carry their expiration time and a signature of the artifact and the expiration
+//! time, so they can be validated without storing them.
+//! Artifacts are deleted by a periodic cleanup once their URLs have expired.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use geoengine_datatypes::identifier;
+use geoengine_datatypes::primitives::{
+    AxisAlignedRectangle, Coordinate2D, RasterQueryRectangle, SpatialPartition2D,
+};
+use hmac::{Hmac, Mac};
+use lazy_static::lazy_static;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use snafu::ensure;
+use url::Url;
+
+use crate::error::{self, Error, Result};
+use crate::object_storage::ObjectStorage;
+use crate::util::config::{self, get_config_element};
+
+identifier!(ExportId);
+
+/// The directory of the exports in the object storage
+const EXPORTS_PREFIX: &str = "exports/";
+
+lazy_static! {
+    /// Signs download URLs if no secret is configured, s.t. URLs become invalid on restart
+    static ref RANDOM_URL_SECRET: String = uuid::Uuid::new_v4().to_string();
+}
+
+impl ExportId {
+    /// The key of a file of this export in the object storage
+    pub fn file_key(&self, file_name: &str) -> String {
+        format!("{}{}/{}", EXPORTS_PREFIX, self, file_name)
+    }
+}
+
+/// The file formats of exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    GeoTiff,
+}
+
+impl Default for ExportFormat {
+    fn default() -> Self {
+        Self::GeoTiff
+    }
+}
+
+impl ExportFormat {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            ExportFormat::GeoTiff => "export.tiff",
+        }
+    }
+
+    /// The name of the zip that contains the tiles and the index of a tiled export
+    pub fn tiled_file_name(self) -> &'static str {
+        match self {
+            ExportFormat::GeoTiff => "export.zip",
+        }
+    }
+
+    /// The name of the file inside the zip of a tiled export that mosaics all tiles
+    pub fn index_file_name(self) -> &'static str {
+        match self {
+            ExportFormat::GeoTiff => "export.vrt",
+        }
+    }
+
+    pub fn tile_file_name(self, tile: &ExportTile) -> String {
+        match self {
+            ExportFormat::GeoTiff => tile.file_name("export", "tiff"),
+        }
+    }
+}
+
+/// A part of an export that is too large for a single file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportTile {
+    pub row: usize,
+    pub column: usize,
+    pub query: RasterQueryRectangle,
+}
+
+impl ExportTile {
+    /// The name of the file of this tile, e.g., `raster_0_1.tiff` for the stem `raster`
+    pub fn file_name(&self, stem: &str, extension: &str) -> String {
+        format!("{}_{}_{}.{}", stem, self.row, self.column, extension)
+    }
+}
+
+/// Splits the `query` of an export into tiles with at most `max_file_pixels` pixels.
+/// Returns `None` if the export fits into a single file.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+pub fn export_tiles(query: RasterQueryRectangle, max_file_pixels: u64) -> Option<Vec<ExportTile>> {
+    let bounds = query.spatial_bounds;
+    let resolution = query.spatial_resolution;
+
+    let width = (bounds.size_x() / resolution.x).ceil() as u64;
+    let height = (bounds.size_y() / resolution.y).ceil() as u64;
+
+    if width * height <= max_file_pixels {
+        return None;
+    }
+
+    // use full rows of the export if possible, s.t. the tiles are strips
+    let tile_width = width.min(max_file_pixels).max(1);
+    let tile_height = (max_file_pixels / tile_width).clamp(1, height);
+
+    let columns = (width + tile_width - 1) / tile_width;
+    let rows = (height + tile_height - 1) / tile_height;
+
+    let upper_left = bounds.upper_left();
+    let lower_right = bounds.lower_right();
+
+    let tile_size_x = tile_width as f64 * resolution.x;
+    let tile_size_y = tile_height as f64 * resolution.y;
+
+    let mut tiles = Vec::with_capacity((rows * columns) as usize);
+    for row in 0..rows {
+        for column in 0..columns {
+            let tile_upper_left = Coordinate2D::new(
+                upper_left.x + column as f64 * tile_size_x,
+                upper_left.y - row as f64 * tile_size_y,
+            );
+            let tile_lower_right = Coordinate2D::new(
+                (tile_upper_left.x + tile_size_x).min(lower_right.x),
+                (tile_upper_left.y - tile_size_y).max(lower_right.y),
+            );
+
+            tiles.push(ExportTile {
+                row: row as usize,
+                column: column as usize,
+                query: RasterQueryRectangle {
+                    spatial_bounds: SpatialPartition2D::new_unchecked(
+                        tile_upper_left,
+                        tile_lower_right,
+                    ),
+                    time_interval: query.time_interval,
+                    spatial_resolution: resolution,
+                    spatial_filter: (),
+                },
+            });
+        }
+    }
+
+    Some(tiles)
+}
+
+/// A stored export and the URL to download it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportArtifact {
+    pub id: ExportId,
+    pub file_name: String,
+    pub size_in_bytes: u64,
+    /// Downloads the artifact without further authentication. Supports range requests.
+    pub url: Url,
+    /// The point in time when the URL becomes invalid
+    pub expires: DateTime<Utc>,
+}
+
+/// The query parameters of a download URL of an export
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadSignature {
+    /// Unix timestamp in seconds
+    pub expires: i64,
+    /// Hex-encoded HMAC-SHA256 of the artifact and the expiration time
+    pub signature: String,
+}
+
+impl DownloadSignature {
+    pub fn new(id: ExportId, file_name: &str, expires: DateTime<Utc>) -> Result<Self> {
+        let expires = expires.timestamp();
+        let signature = hex::encode(
+            download_mac(id, file_name, expires)?
+                .finalize()
+                .into_bytes(),
+        );
+
+        Ok(Self { expires, signature })
+    }
+
+    /// Checks that the signature belongs to the file and that it has not expired at `now`
+    pub fn verify(&self, id: ExportId, file_name: &str, now: DateTime<Utc>) -> Result<()> {
+        let signature =
+            hex::decode(&self.signature).map_err(|_| Error::InvalidDownloadSignature)?;
+
+        download_mac(id, file_name, self.expires)?
+            .verify_slice(&signature)
+            .map_err(|_| Error::InvalidDownloadSignature)?;
+
+        ensure!(self.expires > now.timestamp(), error::DownloadUrlExpired);
+
+        Ok(())
+    }
+}
+
+/// Creates a download URL for a file of an export that is valid for the configured duration
+#[allow(clippy::cast_possible_wrap)]
+pub fn download_url(id: ExportId, file_name: &str) -> Result<(Url, DateTime<Utc>)> {
+    let url_expiration_seconds = get_config_element::<config::Export>()?.url_expiration_seconds;
+    let expires = Utc::now() + Duration::seconds(url_expiration_seconds as i64);
+
+    let signature = DownloadSignature::new(id, file_name, expires)?;
+
+    let base = get_config_element::<config::Web>()?
+        .external_address
+        .ok_or(Error::ExternalAddressNotConfigured)?;
+
+    let mut url = base.join(&format!("export/{}/{}", id, file_name))?;
+    url.query_pairs_mut()
+        .append_pair("expires", &signature.expires.to_string())
+        .append_pair("signature", &signature.signature);
+
+    Ok((url, Utc.timestamp(signature.expires, 0)))
+}
+
+/// Deletes the files of exports whose download URLs have expired at `now`.
+/// Returns the number of deleted files.
+///
+/// URLs are created right after the files are stored, so they expire the configured duration
+/// after the last modification of the files.
+#[allow(clippy::cast_possible_wrap)]
+pub async fn delete_expired_exports(
+    storage: &dyn ObjectStorage,
+    now: DateTime<Utc>,
+) -> Result<usize> {
+    let url_expiration_seconds = get_config_element::<config::Export>()?.url_expiration_seconds;
+    let expired_before = now - Duration::seconds(url_expiration_seconds as i64);
+
+    let mut deleted = 0;
+    for object in storage.list(EXPORTS_PREFIX).await? {
+        if object.last_modified < expired_before {
+            storage.delete(&object.key).await?;
+            deleted += 1;
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// Deletes expired exports every `interval` in the background
+pub fn schedule_export_cleanup(storage: Arc<dyn ObjectStorage>, interval: std::time::Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+
+        loop {
+            interval.tick().await;
+
+            match delete_expired_exports(storage.as_ref(), Utc::now()).await {
+                Ok(0) => {}
+                Ok(deleted) => info!("Deleted {} files of expired exports", deleted),
+                Err(e) => warn!("Deletion of expired exports failed: {}", e),
+            }
+        }
+    });
+}
+
+fn download_mac(id: ExportId, file_name: &str, expires: i64) -> Result<Hmac<Sha256>> {
+    let secret = get_config_element::<config::Export>()?
+        .url_secret
+        .unwrap_or_else(|| RANDOM_URL_SECRET.clone());
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(format!("{}/{}|{}", id, file_name, expires).as_bytes());
+
+    Ok(mac)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object_storage::LocalObjectStorage;
+    use geoengine_datatypes::primitives::SpatialResolution;
+    use geoengine_datatypes::util::Identifier;
+
+    #[test]
+    fn it_verifies_download_signatures() {
+        let id = ExportId::new();
+        let now = Utc.timestamp(1_600_000_000, 0);
+
+        let signature =
+            DownloadSignature::new(id, "export.tiff", now + Duration::hours(1)).unwrap();
+
+        assert!(signature.verify(id, "export.tiff", now).is_ok());
+
+        assert!(matches!(
+            signature.verify(id, "other.tiff", now),
+            Err(Error::InvalidDownloadSignature)
+        ));
+        assert!(matches!(
+            signature.verify(ExportId::new(), "export.tiff", now),
+            Err(Error::InvalidDownloadSignature)
+        ));
+        assert!(matches!(
+            signature.verify(id, "export.tiff", now + Duration::hours(2)),
+            Err(Error::DownloadUrlExpired)
+        ));
+
+        let extended = DownloadSignature {
+            expires: signature.expires + 3600,
+            ..signature
+        };
+        assert!(matches!(
+            extended.verify(id, "export.tiff", now + Duration::hours(2)),
+            Err(Error::InvalidDownloadSignature)
+        ));
+    }
+
+    #[test]
+    fn it_splits_large_exports_into_tiles() {
+        let query = RasterQueryRectangle {
+            spatial_bounds: SpatialPartition2D::new((0., 10.).into(), (10., 0.).into()).unwrap(),
+            time_interval: Default::default(),
+            spatial_resolution: SpatialResolution::one(),
+            spatial_filter: (),
+        };
+
+        assert!(export_tiles(query, 100).is_none());
+
+        let tiles = export_tiles(query, 40).unwrap();
+
+        assert_eq!(tiles.len(), 3);
+        assert_eq!(
+            tiles
+                .iter()
+                .map(|tile| (tile.row, tile.column, tile.query.spatial_bounds))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    0,
+                    0,
+                    SpatialPartition2D::new((0., 10.).into(), (10., 6.).into()).unwrap()
+                ),
+                (
+                    1,
+                    0,
+                    SpatialPartition2D::new((0., 6.).into(), (10., 2.).into()).unwrap()
+                ),
+                (
+                    2,
+                    0,
+                    SpatialPartition2D::new((0., 2.).into(), (10., 0.).into()).unwrap()
+                ),
+            ]
+        );
+        assert_eq!(
+            ExportFormat::GeoTiff.tile_file_name(&tiles[2]),
+            "export_2_0.tiff"
+        );
+
+        let tiles = export_tiles(query, 4).unwrap();
+
+        assert_eq!(tiles.len(), 10 * 3);
+        assert_eq!(
+            tiles[7].query.spatial_bounds,
+            SpatialPartition2D::new((4., 8.).into(), (8., 7.).into()).unwrap()
+        );
+        assert_eq!(
+            tiles.last().unwrap().query.spatial_bounds,
+            SpatialPartition2D::new((8., 1.).into(), (10., 0.).into()).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn it_deletes_expired_exports() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalObjectStorage::new(dir.path().to_path_buf());
+
+        let id = ExportId::new();
+        let writer = storage.writer(&id.file_key("export.tiff")).await.unwrap();
+        writer.finish().await.unwrap();
+        let writer = storage.writer("upload/file.txt").await.unwrap();
+        writer.finish().await.unwrap();
+
+        assert_eq!(
+            delete_expired_exports(&storage, Utc::now()).await.unwrap(),
+            0
+        );
+        assert!(storage.read(&id.file_key("export.tiff")).await.is_ok());
+
+        let url_expiration_seconds = get_config_element::<config::Export>()
+            .unwrap()
+            .url_expiration_seconds;
+        let after_expiration = Utc::now() + Duration::seconds(url_expiration_seconds as i64 + 1);
+
+        assert_eq!(
+            delete_expired_exports(&storage, after_expiration)
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(storage.read(&id.file_key("export.tiff")).await.is_err());
+
+        // only exports are deleted
+        assert!(storage.read("upload/file.txt").await.is_ok());
+    }
+}
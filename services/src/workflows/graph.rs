@@ -0,0 +1,420 @@
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_operators::call_on_typed_operator;
+use geoengine_operators::engine::{
+    ExecutionContext, MultiOutputOperator, OperatorDatasets, OperatorOutput, PlotOperator,
+    RasterOperator, ResultDescriptor, ScalarOperator, TableOperator, TypedOperator,
+    TypedResultDescriptor, VectorOperator,
+};
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+use crate::contexts::Context;
+use crate::datasets::listing::DatasetProvider;
+use crate::error::{self, Result};
+use crate::workflows::workflow::Workflow;
+
+/// Strings in the parameter summary are shortened to this number of characters
+const MAX_SUMMARY_STRING_LENGTH: usize = 40;
+
+/// The formats in which the operator graph of a workflow can be exported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GraphFormat {
+    /// A list of nodes and edges in JSON
+    Json,
+    /// A Graphviz DOT digraph
+    Dot,
+}
+
+impl Default for GraphFormat {
+    fn default() -> Self {
+        GraphFormat::Json
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphOptions {
+    #[serde(default)]
+    pub format: GraphFormat,
+}
+
+/// The operator tree of a workflow where edges point from sources to their consumers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowGraph {
+    pub nodes: Vec<WorkflowGraphNode>,
+    pub edges: Vec<WorkflowGraphEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowGraphNode {
+    pub id: usize,
+    pub operator_type: String,
+    /// The parameters of the operator where nested values are abbreviated
+    pub params: serde_json::Map<String, serde_json::Value>,
+    /// The result descriptor of operators with a single output
+    pub result_descriptor: Option<TypedResultDescriptor>,
+    /// The outputs of operators with multiple outputs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<OperatorOutput>,
+    /// The datasets that are loaded by source operators
+    pub datasets: Vec<WorkflowGraphDataset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowGraphDataset {
+    pub id: DatasetId,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowGraphEdge {
+    pub source: usize,
+    pub target: usize,
+    /// The name of the source slot of the target, e.g., `raster` or `rasters[1]`
+    pub slot: String,
+}
+
+/// Builds the operator graph of the `workflow`.
+/// The root operator is the node with id `0`.
+pub async fn workflow_graph<C: Context>(
+    ctx: &C,
+    session: C::Session,
+    workflow: &Workflow,
+) -> Result<WorkflowGraph> {
+    let operator = serde_json::to_value(&workflow.operator).context(error::SerdeJson)?;
+    let execution_context = ctx.execution_context(session.clone())?;
+
+    let mut graph = WorkflowGraph {
+        nodes: Vec::new(),
+        edges: Vec::new(),
+    };
+
+    let mut stack = vec![(operator["operator"].clone(), None)];
+
+    while let Some((node, target)) = stack.pop() {
+        let id = graph.nodes.len();
+
+        if let Some((target, slot)) = target {
+            graph.edges.push(WorkflowGraphEdge {
+                source: id,
+                target,
+                slot,
+            });
+        }
+
+        let node_operator = node_operator(&node)?;
+
+        let sources = source_operators(&node);
+        let datasets = if sources.is_empty() {
+            let dataset_ids = match &node_operator {
+                NodeOperator::Typed(operators) => operators[0].datasets(),
+                NodeOperator::MultiOutput(operator) => operator.datasets(),
+            };
+
+            let mut datasets = Vec::new();
+            for dataset_id in dataset_ids {
+                let name = ctx
+                    .dataset_db_ref()
+                    .await
+                    .load(&session, &dataset_id)
+                    .await
+                    .ok()
+                    .map(|dataset| dataset.name);
+
+                datasets.push(WorkflowGraphDataset {
+                    id: dataset_id,
+                    name,
+                });
+            }
+            datasets
+        } else {
+            Vec::new()
+        };
+
+        let (result_descriptor, outputs) = match node_operator {
+            NodeOperator::Typed(operators) => (
+                Some(initialize(operators, &execution_context).await?),
+                Vec::new(),
+            ),
+            NodeOperator::MultiOutput(operator) => {
+                let operator = operator
+                    .initialize(&execution_context)
+                    .await
+                    .context(error::Operator)?;
+
+                (None, operator.outputs().to_vec())
+            }
+        };
+
+        graph.nodes.push(WorkflowGraphNode {
+            id,
+            operator_type: node["type"].as_str().unwrap_or_default().to_string(),
+            params: summarize_params(&node["params"]),
+            result_descriptor,
+            outputs,
+            datasets,
+        });
+
+        // reverse the sources to visit them in order
+        for (slot, source) in sources.into_iter().rev() {
+            stack.push((source.clone(), Some((id, slot))));
+        }
+    }
+
+    Ok(graph)
+}
+
+enum NodeOperator {
+    /// All output types that the operator supports, e.g., a `Reprojection` is a raster and a vector operator
+    Typed(Vec<TypedOperator>),
+    MultiOutput(Box<dyn MultiOutputOperator>),
+}
+
+/// Deserializes an operator of any output type
+fn node_operator(node: &serde_json::Value) -> Result<NodeOperator> {
+    let mut operators = Vec::new();
+
+    if let Ok(operator) = serde_json::from_value::<Box<dyn RasterOperator>>(node.clone()) {
+        operators.push(TypedOperator::Raster(operator));
+    }
+    if let Ok(operator) = serde_json::from_value::<Box<dyn VectorOperator>>(node.clone()) {
+        operators.push(TypedOperator::Vector(operator));
+    }
+
+    if let Ok(operator) = serde_json::from_value::<Box<dyn PlotOperator>>(node.clone()) {
+        operators.push(TypedOperator::Plot(operator));
+    }
+    if let Ok(operator) = serde_json::from_value::<Box<dyn TableOperator>>(node.clone()) {
+        operators.push(TypedOperator::Table(operator));
+    }
+    if let Ok(operator) = serde_json::from_value::<Box<dyn ScalarOperator>>(node.clone()) {
+        operators.push(TypedOperator::Scalar(operator));
+    }
+
+    if !operators.is_empty() {
+        return Ok(NodeOperator::Typed(operators));
+    }
+
+    serde_json::from_value::<Box<dyn MultiOutputOperator>>(node.clone())
+        .map(NodeOperator::MultiOutput)
+        .context(error::SerdeJson)
+}
+
+/// Initializes the first of the `operators` that is valid and returns its result descriptor
+async fn initialize<E: ExecutionContext>(
+    operators: Vec<TypedOperator>,
+    execution_context: &E,
+) -> Result<TypedResultDescriptor> {
+    let mut last_error = None;
+
+    for typed_operator in operators {
+        let initialized = call_on_typed_operator!(
+            typed_operator,
+            operator => operator
+                .initialize(execution_context).await
+                .map(|operator| {
+                    #[allow(clippy::clone_on_copy)]
+                    let result_descriptor: TypedResultDescriptor = operator.result_descriptor().clone().into();
+                    result_descriptor
+                })
+        );
+
+        match initialized {
+            Ok(result_descriptor) => return Ok(result_descriptor),
+            Err(error) => last_error = Some(error),
+        }
+    }
+
+    Err(last_error.expect("a node deserializes to at least one operator")).context(error::Operator)
+}
+
+/// The operators in the `sources` of a node together with the name of their slot
+fn source_operators(node: &serde_json::Value) -> Vec<(String, &serde_json::Value)> {
+    fn is_operator(value: &serde_json::Value) -> bool {
+        value
+            .get("type")
+            .map_or(false, serde_json::Value::is_string)
+    }
+
+    let mut sources = Vec::new();
+
+    let map = match node.get("sources").and_then(serde_json::Value::as_object) {
+        Some(map) => map,
+        None => return sources,
+    };
+
+    for (slot, value) in map {
+        match value {
+            serde_json::Value::Array(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    if is_operator(value) {
+                        sources.push((format!("{}[{}]", slot, i), value));
+                    }
+                }
+            }
+            value if is_operator(value) => sources.push((slot.clone(), value)),
+            // named sources, e.g., the inputs of a macro
+            serde_json::Value::Object(values) => {
+                for (name, value) in values {
+                    if is_operator(value) {
+                        sources.push((format!("{}.{}", slot, name), value));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    sources
+}
+
+/// Keeps scalar parameters and abbreviates long strings, arrays and objects
+fn summarize_params(params: &serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+    let params = match params.as_object() {
+        Some(params) => params,
+        None => return serde_json::Map::new(),
+    };
+
+    params
+        .iter()
+        .map(|(key, value)| {
+            let summary = match value {
+                serde_json::Value::String(s) if s.chars().count() > MAX_SUMMARY_STRING_LENGTH => {
+                    let prefix: String = s.chars().take(MAX_SUMMARY_STRING_LENGTH).collect();
+                    serde_json::Value::String(format!("{}…", prefix))
+                }
+                serde_json::Value::Array(values) => {
+                    serde_json::Value::String(format!("[{} entries]", values.len()))
+                }
+                serde_json::Value::Object(_) => serde_json::Value::String("{…}".to_string()),
+                value => value.clone(),
+            };
+
+            (key.clone(), summary)
+        })
+        .collect()
+}
+
+impl WorkflowGraph {
+    /// Renders the graph as a Graphviz DOT digraph
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph workflow {\n  node [shape=box];\n");
+
+        for node in &self.nodes {
+            let output = match &node.result_descriptor {
+                Some(TypedResultDescriptor::Plot(_)) => "plot".to_string(),
+                Some(TypedResultDescriptor::Raster(_)) => "raster".to_string(),
+                Some(TypedResultDescriptor::Vector(_)) => "vector".to_string(),
+                Some(TypedResultDescriptor::Table(_)) => "table".to_string(),
+                Some(TypedResultDescriptor::Scalar(_)) => "scalar".to_string(),
+                None => node
+                    .outputs
+                    .iter()
+                    .map(|output| output.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+
+            let mut label = format!("{}\n({})", node.operator_type, output);
+            for dataset in &node.datasets {
+                label.push('\n');
+                match (&dataset.name, &dataset.id) {
+                    (Some(name), _) => label.push_str(name),
+                    (None, DatasetId::Internal { dataset_id }) => {
+                        label.push_str(&dataset_id.to_string());
+                    }
+                    (None, DatasetId::External(id)) => {
+                        label.push_str(&format!("{}:{}", id.provider_id, id.dataset_id));
+                    }
+                }
+            }
+
+            dot.push_str(&format!(
+                "  n{} [label=\"{}\"];\n",
+                node.id,
+                escape_dot(&label)
+            ));
+        }
+
+        for edge in &self.edges {
+            dot.push_str(&format!(
+                "  n{} -> n{} [label=\"{}\"];\n",
+                edge.source,
+                edge.target,
+                escape_dot(&edge.slot)
+            ));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_finds_sources() {
+        let node = json!({
+            "type": "Expression",
+            "params": {},
+            "sources": {
+                "a": { "type": "GdalSource", "params": {} },
+                "rasters": [
+                    { "type": "GdalSource", "params": {} },
+                    { "type": "GdalSource", "params": {} }
+                ],
+                "vectors": {
+                    "b": { "type": "OgrSource", "params": {} }
+                }
+            }
+        });
+
+        let slots: Vec<String> = source_operators(&node)
+            .into_iter()
+            .map(|(slot, _)| slot)
+            .collect();
+
+        assert_eq!(slots, vec!["a", "rasters[0]", "rasters[1]", "vectors.b"]);
+    }
+
+    #[test]
+    fn it_summarizes_params() {
+        let params = json!({
+            "expression": "A + B",
+            "outputType": "F32",
+            "points": [1, 2, 3],
+            "dataset": { "type": "internal" },
+            "long": "a".repeat(50),
+        });
+
+        assert_eq!(
+            serde_json::Value::Object(summarize_params(&params)),
+            json!({
+                "expression": "A + B",
+                "outputType": "F32",
+                "points": "[3 entries]",
+                "dataset": "{…}",
+                "long": format!("{}…", "a".repeat(40)),
+            })
+        );
+    }
+
+    #[test]
+    fn it_escapes_dot() {
+        assert_eq!(escape_dot("a \"b\"\nc"), "a \\\"b\\\"\\nc");
+    }
+}
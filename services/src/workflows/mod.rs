@@ -1,2 +1,8 @@
+pub mod cache;
+pub mod export;
+pub mod graph;
 pub mod registry;
+pub mod symbology;
+pub mod template;
 pub mod workflow;
+pub mod workflow_macro;
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
+use super::template::{WorkflowTemplate, WorkflowTemplateId};
 use super::workflow::{Workflow, WorkflowId};
+use super::workflow_macro::WorkflowMacro;
+use crate::contexts::BackupDb;
 use crate::error;
 use crate::error::Result;
 use async_trait::async_trait;
@@ -9,11 +12,22 @@ use async_trait::async_trait;
 pub trait WorkflowRegistry: Send + Sync {
     async fn register(&mut self, workflow: Workflow) -> Result<WorkflowId>;
     async fn load(&self, id: &WorkflowId) -> Result<Workflow>;
+
+    async fn register_template(&mut self, template: WorkflowTemplate)
+        -> Result<WorkflowTemplateId>;
+    async fn load_template(&self, id: &WorkflowTemplateId) -> Result<WorkflowTemplate>;
+
+    /// Adds a new version of the macro `name` and returns the version number, starting at `1`
+    async fn register_macro(&mut self, name: String, operator: serde_json::Value) -> Result<u32>;
+    /// Loads a `version` of the macro `name` or its latest version if no version is given
+    async fn load_macro(&self, name: &str, version: Option<u32>) -> Result<WorkflowMacro>;
 }
 
 #[derive(Default)]
 pub struct HashMapRegistry {
     map: HashMap<WorkflowId, Workflow>,
+    templates: HashMap<WorkflowTemplateId, WorkflowTemplate>,
+    macros: HashMap<String, Vec<serde_json::Value>>,
 }
 
 #[async_trait]
@@ -30,4 +44,62 @@ impl WorkflowRegistry for HashMapRegistry {
             .cloned()
             .ok_or(error::Error::NoWorkflowForGivenId)
     }
+
+    async fn register_template(
+        &mut self,
+        template: WorkflowTemplate,
+    ) -> Result<WorkflowTemplateId> {
+        let id = WorkflowTemplateId::from_hash(&template);
+        self.templates.insert(id, template);
+        Ok(id)
+    }
+
+    async fn load_template(&self, id: &WorkflowTemplateId) -> Result<WorkflowTemplate> {
+        self.templates
+            .get(id)
+            .cloned()
+            .ok_or(error::Error::NoWorkflowTemplateForGivenId)
+    }
+
+    async fn register_macro(&mut self, name: String, operator: serde_json::Value) -> Result<u32> {
+        let versions = self.macros.entry(name).or_default();
+        versions.push(operator);
+        Ok(versions.len() as u32)
+    }
+
+    async fn load_macro(&self, name: &str, version: Option<u32>) -> Result<WorkflowMacro> {
+        let versions = self.macros.get(name).map_or(&[][..], Vec::as_slice);
+
+        let (version, operator) = match version {
+            Some(version) => (
+                version,
+                version
+                    .checked_sub(1)
+                    .and_then(|index| versions.get(index as usize)),
+            ),
+            None => (versions.len() as u32, versions.last()),
+        };
+
+        operator
+            .map(|operator| WorkflowMacro {
+                name: name.to_string(),
+                version,
+                operator: operator.clone(),
+            })
+            .ok_or_else(|| error::Error::UnknownWorkflowMacro {
+                name: name.to_string(),
+                version: Some(version).filter(|&version| version > 0),
+            })
+    }
+}
+
+#[async_trait]
+impl BackupDb<Workflow> for HashMapRegistry {
+    async fn export(&self) -> Result<Vec<Workflow>> {
+        Ok(self.map.values().cloned().collect())
+    }
+
+    async fn import(&mut self, workflow: Workflow) -> Result<()> {
+        self.register(workflow).await.map(drop)
+    }
 }
@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+
+use futures::{StreamExt, TryStreamExt};
+use geoengine_datatypes::collections::VectorDataType;
+use geoengine_datatypes::operations::image::{Breakpoint, Colorizer, RgbaColor};
+use geoengine_datatypes::operations::reproject::reproject_query;
+use geoengine_datatypes::primitives::{
+    BoundingBox2D, Measurement, SpatialResolution, TimeInterval, VectorQueryRectangle,
+};
+use geoengine_datatypes::raster::GridOrEmpty;
+use geoengine_datatypes::spatial_reference::SpatialReference;
+use geoengine_operators::engine::{
+    OperatorDatasets, RasterOperator, RasterResultDescriptor, ResultDescriptor, TypedOperator,
+    VectorOperator,
+};
+use geoengine_operators::util::classification::{class_breaks, ClassificationMethod};
+use geoengine_operators::util::feature_expression::FeatureExpression;
+use geoengine_operators::{call_on_generic_raster_processor, call_on_generic_vector_processor};
+use num_traits::AsPrimitive;
+use serde::Deserialize;
+use snafu::ResultExt;
+
+use crate::contexts::Context;
+use crate::datasets::listing::DatasetProvider;
+use crate::error::{self, Result};
+use crate::ogc::util::parse_spatial_resolution_option;
+use crate::ogc::util::{parse_bbox_option, parse_time_option};
+use crate::projects::{
+    ClassifiedColor, ColorParam, ExpressionColor, LineSymbology, NumberParam, PointSymbology,
+    PolygonSymbology, RasterSymbology, StrokeParam, Symbology,
+};
+use crate::workflows::workflow::Workflow;
+
+/// The query rectangle over which the value range of a raster workflow is computed.
+/// It is only required for rasters that are neither backed by a dataset with a symbology nor classified.
+///
+/// Vector workflows can be styled by an `expression` over the features' attributes.
+/// Then, the expression's values inside the query rectangle are either mapped to a gradient or,
+/// if a `classification` is given, divided into `classes`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbologyOptions {
+    #[serde(default, deserialize_with = "parse_bbox_option")]
+    pub bbox: Option<BoundingBox2D>,
+    pub crs: Option<SpatialReference>,
+    #[serde(default, deserialize_with = "parse_time_option")]
+    pub time: Option<TimeInterval>,
+    #[serde(default, deserialize_with = "parse_spatial_resolution_option")]
+    pub spatial_resolution: Option<SpatialResolution>,
+    pub expression: Option<String>,
+    pub classification: Option<ClassificationMethod>,
+    pub classes: Option<usize>,
+}
+
+/// The number of classes if the request does not specify it
+const DEFAULT_CLASSES: usize = 5;
+
+/// Colors of a perceptually uniform gradient (viridis) for continuous rasters
+const GRADIENT_COLORS: [[u8; 3]; 3] = [[68, 1, 84], [33, 145, 140], [253, 231, 37]];
+
+/// Qualitative colors for the classes of classified rasters
+const CLASS_COLORS: [[u8; 3]; 12] = [
+    [166, 206, 227],
+    [31, 120, 180],
+    [178, 223, 138],
+    [51, 160, 44],
+    [251, 154, 153],
+    [227, 26, 28],
+    [253, 191, 111],
+    [255, 127, 0],
+    [202, 178, 214],
+    [106, 61, 154],
+    [255, 255, 153],
+    [177, 89, 40],
+];
+
+/// Determines the symbology that is recommended for displaying the result of a `workflow`.
+///
+/// A workflow that consists of only a dataset source uses the dataset's symbology.
+/// Otherwise, a default symbology is derived from the workflow's result descriptor.
+/// For continuous rasters, this is a gradient over the value range inside the query rectangle of the `options`.
+/// Vector workflows with an expression in the `options` are styled by the expression's values.
+pub async fn workflow_symbology<C: Context>(
+    ctx: &C,
+    session: C::Session,
+    workflow: Workflow,
+    options: SymbologyOptions,
+) -> Result<Symbology> {
+    if options.expression.is_none() {
+        if let Some(symbology) = dataset_symbology(ctx, &session, &workflow.operator).await? {
+            return Ok(symbology);
+        }
+    }
+
+    let execution_context = ctx.execution_context(session)?;
+
+    match workflow.operator {
+        TypedOperator::Vector(operator) if options.expression.is_some() => {
+            expression_symbology(ctx, operator, &execution_context, options).await
+        }
+        TypedOperator::Vector(operator) => {
+            let initialized = operator
+                .initialize(&execution_context)
+                .await
+                .context(error::Operator)?;
+
+            vector_symbology(initialized.result_descriptor().data_type)
+        }
+        TypedOperator::Raster(operator) => {
+            raster_symbology(ctx, operator, &execution_context, options).await
+        }
+        TypedOperator::Plot(_) => Err(error::Error::NoSymbologyForDataType {
+            data_type: "Plot".to_string(),
+        }),
+        TypedOperator::Table(_) => Err(error::Error::NoSymbologyForDataType {
+            data_type: "Table".to_string(),
+        }),
+        TypedOperator::Scalar(_) => Err(error::Error::NoSymbologyForDataType {
+            data_type: "Scalar".to_string(),
+        }),
+    }
+}
+
+/// The symbology of the dataset if the `operator` is the source operator of a single dataset
+async fn dataset_symbology<C: Context>(
+    ctx: &C,
+    session: &C::Session,
+    operator: &TypedOperator,
+) -> Result<Option<Symbology>> {
+    let datasets = operator.datasets();
+    let dataset_id = match datasets.as_slice() {
+        [dataset_id] => dataset_id,
+        _ => return Ok(None),
+    };
+
+    let operator_json = serde_json::to_value(operator).context(error::SerdeJson)?;
+    let operator_type = operator_json["operator"]["type"].as_str();
+
+    let dataset = ctx.dataset_db_ref().await.load(session, dataset_id).await?;
+
+    if operator_type != Some(dataset.source_operator.as_str()) {
+        return Ok(None);
+    }
+
+    Ok(dataset.symbology)
+}
+
+fn vector_symbology(data_type: VectorDataType) -> Result<Symbology> {
+    let stroke = StrokeParam {
+        width: NumberParam::Static { value: 1 },
+        color: ColorParam::Static {
+            color: RgbaColor::black(),
+        },
+    };
+
+    Ok(match data_type {
+        VectorDataType::MultiPoint => Symbology::Point(PointSymbology::default()),
+        VectorDataType::MultiLineString => Symbology::Line(LineSymbology { stroke, text: None }),
+        VectorDataType::MultiPolygon => Symbology::Polygon(PolygonSymbology {
+            fill_color: ColorParam::Static {
+                color: RgbaColor::white().with_alpha(127),
+            },
+            stroke,
+            text: None,
+        }),
+        VectorDataType::Data => {
+            return Err(error::Error::NoSymbologyForDataType {
+                data_type: format!("{:?}", data_type),
+            })
+        }
+    })
+}
+
+async fn raster_symbology<C: Context>(
+    ctx: &C,
+    operator: Box<dyn RasterOperator>,
+    execution_context: &C::ExecutionContext,
+    options: SymbologyOptions,
+) -> Result<Symbology> {
+    let initialized = operator
+        .initialize(execution_context)
+        .await
+        .context(error::Operator)?;
+
+    let result_descriptor: RasterResultDescriptor = initialized.result_descriptor().clone();
+
+    if let Measurement::Classification(measurement) = &result_descriptor.measurement {
+        let mut classes: Vec<u8> = measurement.classes.keys().copied().collect();
+        classes.sort_unstable();
+
+        let colors: HashMap<_, _> = classes
+            .into_iter()
+            .zip(CLASS_COLORS.iter().cycle())
+            .map(|(class, &[r, g, b])| (class.into(), RgbaColor::new(r, g, b, u8::MAX)))
+            .collect();
+
+        let colorizer =
+            Colorizer::palette(colors, RgbaColor::transparent(), RgbaColor::transparent())
+                .context(error::DataType)?;
+
+        return Ok(Symbology::Raster(RasterSymbology {
+            opacity: 1.0,
+            colorizer,
+        }));
+    }
+
+    let query_rect = query_rectangle(&options, result_descriptor.spatial_reference().into())?;
+
+    let processor = initialized.query_processor().context(error::Operator)?;
+    let query_ctx = ctx.query_context()?;
+
+    let (min, max) = call_on_generic_raster_processor!(processor, processor => {
+        processor
+            .raster_query(query_rect.into(), &query_ctx)
+            .await
+            .context(error::Operator)?
+            .try_fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), tile| async move {
+                Ok(match tile.grid_array {
+                    GridOrEmpty::Grid(grid) => grid
+                        .data
+                        .iter()
+                        .filter(|&&value| Some(value) != grid.no_data_value)
+                        .map(|&value| -> f64 { value.as_() })
+                        .filter(|value| !value.is_nan())
+                        .fold((min, max), |(min, max), value| (min.min(value), max.max(value))),
+                    GridOrEmpty::Empty(_) => (min, max),
+                })
+            })
+            .await
+            .context(error::Operator)?
+    });
+
+    Ok(Symbology::Raster(RasterSymbology {
+        opacity: 1.0,
+        colorizer: gradient(min, max)?,
+    }))
+}
+
+/// Styles the features of a vector workflow by the values of the `options`' expression
+/// inside the `options`' query rectangle
+async fn expression_symbology<C: Context>(
+    ctx: &C,
+    operator: Box<dyn VectorOperator>,
+    execution_context: &C::ExecutionContext,
+    options: SymbologyOptions,
+) -> Result<Symbology> {
+    let expression = options.expression.as_deref().unwrap_or_default();
+    let feature_expression = FeatureExpression::parse(expression).context(error::Operator)?;
+
+    let initialized = operator
+        .initialize(execution_context)
+        .await
+        .context(error::Operator)?;
+
+    let result_descriptor = initialized.result_descriptor().clone();
+    let query_rect = query_rectangle(&options, result_descriptor.spatial_reference().into())?;
+
+    let processor = initialized.query_processor().context(error::Operator)?;
+    let query_ctx = ctx.query_context()?;
+
+    let values = call_on_generic_vector_processor!(processor, processor => {
+        let feature_expression = &feature_expression;
+        processor
+            .vector_query(query_rect, &query_ctx)
+            .await
+            .context(error::Operator)?
+            .map(|collection| feature_expression.evaluate(&collection?))
+            .try_fold(Vec::new(), |mut values, collection_values| async move {
+                values.extend(collection_values.into_iter().flatten());
+                Ok(values)
+            })
+            .await
+            .context(error::Operator)?
+    });
+
+    let color = match options.classification {
+        Some(method) => {
+            let breaks = class_breaks(method, &values, options.classes.unwrap_or(DEFAULT_CLASSES));
+            let colors = gradient_colors(breaks.len() + 1);
+
+            ColorParam::Classified(ClassifiedColor {
+                expression: expression.to_string(),
+                breaks,
+                colors,
+                no_data_color: RgbaColor::transparent(),
+            })
+        }
+        None => {
+            let (min, max) = values
+                .iter()
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+                    (min.min(value), max.max(value))
+                });
+
+            ColorParam::Expression(ExpressionColor {
+                expression: expression.to_string(),
+                colorizer: gradient(min, max)?,
+            })
+        }
+    };
+
+    Ok(match vector_symbology(result_descriptor.data_type)? {
+        Symbology::Point(point) => Symbology::Point(PointSymbology {
+            fill_color: color,
+            ..point
+        }),
+        Symbology::Line(mut line) => {
+            line.stroke.color = color;
+            Symbology::Line(line)
+        }
+        Symbology::Polygon(polygon) => Symbology::Polygon(PolygonSymbology {
+            fill_color: color,
+            ..polygon
+        }),
+        symbology @ Symbology::Raster(_) => symbology,
+    })
+}
+
+/// The query rectangle of the `options` in the workflow's spatial reference
+fn query_rectangle(
+    options: &SymbologyOptions,
+    workflow_spatial_ref: Option<SpatialReference>,
+) -> Result<VectorQueryRectangle> {
+    let (bbox, time, spatial_resolution) =
+        match (options.bbox, options.time, options.spatial_resolution) {
+            (Some(bbox), Some(time), Some(spatial_resolution)) => (bbox, time, spatial_resolution),
+            _ => return Err(error::Error::SymbologyRequiresQueryRectangle),
+        };
+
+    let query_rect = VectorQueryRectangle {
+        spatial_bounds: bbox,
+        time_interval: time,
+        spatial_resolution,
+        spatial_filter: None,
+    };
+
+    Ok(match (options.crs, workflow_spatial_ref) {
+        (Some(request_spatial_ref), Some(workflow_spatial_ref))
+            if request_spatial_ref != workflow_spatial_ref =>
+        {
+            reproject_query(query_rect, workflow_spatial_ref, request_spatial_ref)
+                .context(error::DataType)?
+        }
+        _ => query_rect,
+    })
+}
+
+/// `n` colors that are evenly distributed over the gradient colors
+fn gradient_colors(n: usize) -> Vec<RgbaColor> {
+    let steps = (GRADIENT_COLORS.len() - 1) as f64;
+
+    (0..n)
+        .map(|i| {
+            let position = if n > 1 {
+                i as f64 / (n - 1) as f64 * steps
+            } else {
+                0.
+            };
+
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let lower = (position.floor() as usize).min(GRADIENT_COLORS.len() - 2);
+            let fraction = position - lower as f64;
+
+            let [r, g, b] =
+                interpolate(GRADIENT_COLORS[lower], GRADIENT_COLORS[lower + 1], fraction);
+            RgbaColor::new(r, g, b, u8::MAX)
+        })
+        .collect()
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn interpolate(a: [u8; 3], b: [u8; 3], fraction: f64) -> [u8; 3] {
+    let mut result = [0; 3];
+    for (channel, (a, b)) in result.iter_mut().zip(a.into_iter().zip(b)) {
+        *channel = (f64::from(a) * (1. - fraction) + f64::from(b) * fraction).round() as u8;
+    }
+    result
+}
+
+/// A gradient over `[min, max]` that falls back to a unit range if the bounds are empty
+fn gradient(min: f64, max: f64) -> Result<Colorizer> {
+    let (min, max) = match (min.is_finite(), max.is_finite()) {
+        (true, true) if min < max => (min, max),
+        (true, true) => (min, min + 1.),
+        _ => (0., 1.),
+    };
+
+    let steps = (GRADIENT_COLORS.len() - 1) as f64;
+    let breakpoints = GRADIENT_COLORS
+        .iter()
+        .enumerate()
+        .map(|(i, &[r, g, b])| {
+            // interpolate without computing `max - min` so that the value cannot overflow
+            let fraction = i as f64 / steps;
+            let value = min * (1. - fraction) + max * fraction;
+
+            Breakpoint::try_from((value, RgbaColor::new(r, g, b, u8::MAX)))
+                .expect("breakpoints between finite bounds are not NaN")
+        })
+        .collect();
+
+    Colorizer::linear_gradient(
+        breakpoints,
+        RgbaColor::transparent(),
+        RgbaColor::transparent(),
+    )
+    .context(error::DataType)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn it_derives_gradients() {
+        let colorizer = gradient(2., 10.).unwrap();
+        assert_eq!(colorizer.min_value(), 2.);
+        assert_eq!(colorizer.max_value(), 10.);
+
+        let colorizer = gradient(5., 5.).unwrap();
+        assert_eq!(colorizer.min_value(), 5.);
+        assert_eq!(colorizer.max_value(), 6.);
+
+        let colorizer = gradient(f64::INFINITY, f64::NEG_INFINITY).unwrap();
+        assert_eq!(colorizer.min_value(), 0.);
+        assert_eq!(colorizer.max_value(), 1.);
+    }
+
+    #[test]
+    fn it_derives_gradient_colors() {
+        assert_eq!(
+            gradient_colors(5),
+            vec![
+                RgbaColor::new(68, 1, 84, 255),
+                RgbaColor::new(51, 73, 112, 255),
+                RgbaColor::new(33, 145, 140, 255),
+                RgbaColor::new(143, 188, 89, 255),
+                RgbaColor::new(253, 231, 37, 255),
+            ]
+        );
+        assert_eq!(gradient_colors(1), vec![RgbaColor::new(68, 1, 84, 255)]);
+        assert!(gradient_colors(0).is_empty());
+    }
+
+    #[test]
+    fn it_derives_vector_symbologies() {
+        assert_eq!(
+            vector_symbology(VectorDataType::MultiPoint).unwrap(),
+            Symbology::Point(PointSymbology::default())
+        );
+        assert!(matches!(
+            vector_symbology(VectorDataType::MultiPolygon).unwrap(),
+            Symbology::Polygon(_)
+        ));
+        assert!(vector_symbology(VectorDataType::Data).is_err());
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::{HashMap, HashSet};
+
+use geoengine_datatypes::dataset::DatasetId;
+use geoengine_datatypes::identifier;
+use geoengine_datatypes::primitives::{TimeInterval, TimeStep};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, ResultExt};
+use uuid::Uuid;
+
+use crate::error::{self, Error, Result};
+use crate::workflows::workflow::Workflow;
+
+identifier!(WorkflowTemplateId);
+
+impl WorkflowTemplateId {
+    pub fn from_hash(template: &WorkflowTemplate) -> Self {
+        Self(Uuid::new_v5(
+            &Uuid::NAMESPACE_OID,
+            serde_json::to_string(template)
+                .expect("It is always possible to create a template id from a template.")
+                .as_bytes(),
+        ))
+    }
+}
+
+/// The key of the objects that are placeholders for parameters, e.g., `{ "$parameter": "threshold" }`
+pub const PARAMETER_PLACEHOLDER_KEY: &str = "$parameter";
+
+/// A workflow with named parameters that are substituted when instantiating it.
+///
+/// The `workflow` is the JSON of a [`Workflow`] where parameter values are replaced by placeholders,
+/// i.e., objects with the single key [`PARAMETER_PLACEHOLDER_KEY`] and the name of the parameter as value.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowTemplate {
+    pub parameters: Vec<WorkflowParameter>,
+    pub workflow: serde_json::Value,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowParameter {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub parameter_type: WorkflowParameterType,
+    /// The value that is used if the parameter is not given on instantiation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WorkflowParameterType {
+    Number,
+    Integer,
+    String,
+    Boolean,
+    Dataset,
+    TimeInterval,
+    TimeStep,
+}
+
+impl WorkflowParameterType {
+    fn is_valid(self, value: &serde_json::Value) -> bool {
+        fn deserializes_to<T: DeserializeOwned>(value: &serde_json::Value) -> bool {
+            T::deserialize(value).is_ok()
+        }
+
+        match self {
+            WorkflowParameterType::Number => value.is_number(),
+            WorkflowParameterType::Integer => value.is_i64() || value.is_u64(),
+            WorkflowParameterType::String => value.is_string(),
+            WorkflowParameterType::Boolean => value.is_boolean(),
+            WorkflowParameterType::Dataset => deserializes_to::<DatasetId>(value),
+            WorkflowParameterType::TimeInterval => deserializes_to::<TimeInterval>(value),
+            WorkflowParameterType::TimeStep => deserializes_to::<TimeStep>(value),
+        }
+    }
+}
+
+impl WorkflowParameter {
+    fn validate_value(&self, value: &serde_json::Value) -> Result<()> {
+        ensure!(
+            self.parameter_type.is_valid(value),
+            error::InvalidWorkflowParameterValue {
+                parameter: self.name.clone(),
+                expected: self.parameter_type,
+            }
+        );
+
+        Ok(())
+    }
+}
+
+impl WorkflowTemplate {
+    /// Checks that the parameters are unique, their defaults are valid and that every placeholder refers to a parameter
+    pub fn validate(&self) -> Result<()> {
+        let mut names = HashSet::with_capacity(self.parameters.len());
+
+        for parameter in &self.parameters {
+            ensure!(
+                names.insert(parameter.name.as_str()),
+                error::DuplicateWorkflowParameter {
+                    parameter: parameter.name.clone(),
+                }
+            );
+
+            if let Some(default) = &parameter.default {
+                parameter.validate_value(default)?;
+            }
+        }
+
+        let mut placeholders = Vec::new();
+        collect_placeholders(&self.workflow, &mut placeholders);
+
+        for placeholder in placeholders {
+            ensure!(
+                names.contains(placeholder),
+                error::UnknownWorkflowParameter {
+                    parameter: placeholder.to_string(),
+                }
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Substitutes the placeholders with the `values` or the parameters' defaults
+    pub fn instantiate(&self, mut values: HashMap<String, serde_json::Value>) -> Result<Workflow> {
+        let mut substitutions = HashMap::with_capacity(self.parameters.len());
+
+        for parameter in &self.parameters {
+            let value =
+                match values.remove(&parameter.name) {
+                    Some(value) => {
+                        parameter.validate_value(&value)?;
+                        value
+                    }
+                    None => parameter.default.clone().ok_or_else(|| {
+                        Error::MissingWorkflowParameter {
+                            parameter: parameter.name.clone(),
+                        }
+                    })?,
+                };
+
+            substitutions.insert(parameter.name.as_str(), value);
+        }
+
+        if let Some(parameter) = values.into_keys().next() {
+            return Err(Error::UnknownWorkflowParameter { parameter });
+        }
+
+        let mut workflow = self.workflow.clone();
+        substitute_placeholders(&mut workflow, &substitutions)?;
+
+        serde_json::from_value(workflow).context(error::SerdeJson)
+    }
+}
+
+fn placeholder(value: &serde_json::Value) -> Option<&str> {
+    match value.as_object()?.get(PARAMETER_PLACEHOLDER_KEY) {
+        Some(serde_json::Value::String(name)) if value.as_object()?.len() == 1 => {
+            Some(name.as_str())
+        }
+        _ => None,
+    }
+}
+
+fn collect_placeholders<'a>(value: &'a serde_json::Value, placeholders: &mut Vec<&'a str>) {
+    if let Some(name) = placeholder(value) {
+        placeholders.push(name);
+        return;
+    }
+
+    match value {
+        serde_json::Value::Array(values) => {
+            for value in values {
+                collect_placeholders(value, placeholders);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for value in map.values() {
+                collect_placeholders(value, placeholders);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn substitute_placeholders(
+    value: &mut serde_json::Value,
+    substitutions: &HashMap<&str, serde_json::Value>,
+) -> Result<()> {
+    if let Some(name) = placeholder(value) {
+        let substitution =
+            substitutions
+                .get(name)
+                .cloned()
+                .ok_or_else(|| Error::UnknownWorkflowParameter {
+                    parameter: name.to_string(),
+                })?;
+        *value = substitution;
+        return Ok(());
+    }
+
+    match value {
+        serde_json::Value::Array(values) => {
+            for value in values {
+                substitute_placeholders(value, substitutions)?;
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for value in map.values_mut() {
+                substitute_placeholders(value, substitutions)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn template() -> WorkflowTemplate {
+        serde_json::from_value(json!({
+            "parameters": [
+                { "name": "x", "type": "number" },
+                { "name": "y", "type": "number", "default": 2.0 }
+            ],
+            "workflow": {
+                "type": "Vector",
+                "operator": {
+                    "type": "MockPointSource",
+                    "params": {
+                        "points": [{ "x": { "$parameter": "x" }, "y": { "$parameter": "y" } }]
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn it_instantiates() {
+        let template = template();
+        template.validate().unwrap();
+
+        let workflow = template
+            .instantiate([("x".to_string(), json!(1.0))].into_iter().collect())
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&workflow).unwrap(),
+            json!({
+                "type": "Vector",
+                "operator": {
+                    "type": "MockPointSource",
+                    "params": {
+                        "points": [{ "x": 1.0, "y": 2.0 }]
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn it_validates_values() {
+        let template = template();
+
+        assert!(matches!(
+            template.instantiate(HashMap::new()),
+            Err(Error::MissingWorkflowParameter { parameter }) if parameter == "x"
+        ));
+        assert!(matches!(
+            template.instantiate([("x".to_string(), json!("1"))].into_iter().collect()),
+            Err(Error::InvalidWorkflowParameterValue { parameter, .. }) if parameter == "x"
+        ));
+        assert!(matches!(
+            template.instantiate(
+                [("x".to_string(), json!(1)), ("z".to_string(), json!(1))]
+                    .into_iter()
+                    .collect()
+            ),
+            Err(Error::UnknownWorkflowParameter { parameter }) if parameter == "z"
+        ));
+    }
+
+    #[test]
+    fn it_validates_templates() {
+        let mut template = template();
+        template.parameters.pop();
+
+        assert!(matches!(
+            template.validate(),
+            Err(Error::UnknownWorkflowParameter { parameter }) if parameter == "y"
+        ));
+
+        template.parameters.push(template.parameters[0].clone());
+
+        assert!(matches!(
+            template.validate(),
+            Err(Error::DuplicateWorkflowParameter { parameter }) if parameter == "x"
+        ));
+    }
+
+    #[test]
+    fn it_checks_parameter_types() {
+        assert!(WorkflowParameterType::Integer.is_valid(&json!(1)));
+        assert!(!WorkflowParameterType::Integer.is_valid(&json!(1.5)));
+        assert!(WorkflowParameterType::TimeStep.is_valid(&json!({
+            "granularity": "Days",
+            "step": 1
+        })));
+        assert!(WorkflowParameterType::Dataset.is_valid(&json!({
+            "type": "internal",
+            "datasetId": "9c874b9e-cea0-4553-b727-a13cb26ae4bb"
+        })));
+        assert!(!WorkflowParameterType::Dataset.is_valid(&json!("foo")));
+    }
+}
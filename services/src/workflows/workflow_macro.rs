@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::workflows::registry::WorkflowRegistry;
+
+/// The operator type of macro references inside of workflows
+const MACRO_OPERATOR_TYPE: &str = "Macro";
+
+/// A versioned, reusable sub-workflow that is referenced by its name inside of other workflows.
+///
+/// The `operator` is the JSON of an operator where inputs of the macro are replaced by placeholders,
+/// i.e., objects of the form `{ "$input": "<name>" }`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowMacro {
+    pub name: String,
+    pub version: u32,
+    pub operator: serde_json::Value,
+}
+
+/// Fixes the versions of all macros that are referenced by the `operator`.
+///
+/// References without a version are set to the latest version of the macro.
+/// Since only existing versions can be referenced, macros cannot (indirectly) reference themselves.
+pub async fn pin_macro_versions<W: WorkflowRegistry + ?Sized>(
+    registry: &W,
+    operator: &mut serde_json::Value,
+) -> Result<()> {
+    let mut references = Vec::new();
+    collect_macro_params(operator, &mut references);
+
+    let mut latest_versions = HashMap::new();
+
+    for params in references {
+        let name = params["name"].as_str().unwrap_or_default().to_string();
+        let version = params["version"].as_u64().map(|version| version as u32);
+
+        let version = match (version, latest_versions.get(&name)) {
+            (None, Some(&latest)) => latest,
+            (version, _) => {
+                let workflow_macro = registry.load_macro(&name, version).await?;
+
+                if version.is_none() {
+                    latest_versions.insert(name, workflow_macro.version);
+                }
+
+                workflow_macro.version
+            }
+        };
+
+        params["version"] = version.into();
+    }
+
+    Ok(())
+}
+
+/// Collects the `params` of all macro references
+fn collect_macro_params<'a>(
+    value: &'a mut serde_json::Value,
+    params: &mut Vec<&'a mut serde_json::Value>,
+) {
+    match value {
+        serde_json::Value::Array(values) => {
+            for value in values {
+                collect_macro_params(value, params);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            let is_macro =
+                map.get("type").and_then(serde_json::Value::as_str) == Some(MACRO_OPERATOR_TYPE);
+
+            for (key, value) in map.iter_mut() {
+                if is_macro && key == "params" && value.is_object() {
+                    params.push(value);
+                } else {
+                    collect_macro_params(value, params);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflows::registry::HashMapRegistry;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn it_pins_versions() {
+        let mut registry = HashMapRegistry::default();
+
+        registry
+            .register_macro("a".to_string(), json!({ "$input": "x" }))
+            .await
+            .unwrap();
+        registry
+            .register_macro("a".to_string(), json!({ "$input": "x" }))
+            .await
+            .unwrap();
+
+        let mut operator = json!({
+            "type": "Macro",
+            "params": { "name": "a" },
+            "sources": {
+                "vectors": {
+                    "x": {
+                        "type": "Macro",
+                        "params": { "name": "a", "version": 1 },
+                        "sources": {}
+                    }
+                }
+            }
+        });
+
+        pin_macro_versions(&registry, &mut operator).await.unwrap();
+
+        assert_eq!(operator["params"]["version"], 2);
+        assert_eq!(operator["sources"]["vectors"]["x"]["params"]["version"], 1);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_unknown_macros() {
+        let registry = HashMapRegistry::default();
+
+        let mut operator = json!({
+            "type": "Macro",
+            "params": { "name": "a" },
+            "sources": {}
+        });
+
+        assert!(pin_macro_versions(&registry, &mut operator).await.is_err());
+    }
+}